@@ -0,0 +1,303 @@
+//! Client-side archive extraction for `ov add-resource`.
+//!
+//! A `.zip`, `.tar`, `.tar.gz`, or `.tgz` passed to `add-resource` is unpacked
+//! into a temporary directory and uploaded as that directory, so the server
+//! ingests the files it contains instead of one opaque blob. Archives come from
+//! anywhere, so extraction refuses entries that would land outside the target
+//! (absolute paths, `..`), skips links and special files, and stops at
+//! [`MAX_ENTRIES`] entries or [`MAX_EXTRACTED_BYTES`] of content, counting the
+//! bytes actually written rather than the sizes the archive claims.
+
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+use tempfile::TempDir;
+
+use crate::error::{Error, Result};
+
+/// Most files and directories one archive may unpack to.
+pub const MAX_ENTRIES: usize = 50_000;
+
+/// Most content one archive may unpack to, 4 GiB.
+pub const MAX_EXTRACTED_BYTES: u64 = 4 << 30;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveKind {
+    /// The kind of archive `path` names by its extension, if any.
+    pub fn of(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+        if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(Self::Tar)
+        } else {
+            None
+        }
+    }
+
+    fn extension(self, name: &str) -> usize {
+        let lower = name.to_ascii_lowercase();
+        match self {
+            Self::Zip => ".zip".len(),
+            Self::Tar => ".tar".len(),
+            Self::TarGz if lower.ends_with(".tgz") => ".tgz".len(),
+            Self::TarGz => ".tar.gz".len(),
+        }
+    }
+}
+
+/// An archive unpacked into a temporary directory, removed on drop.
+pub struct Extracted {
+    _dir: TempDir,
+    /// Directory to upload: named after the archive, or the archive's single
+    /// top-level directory when it has nothing else.
+    pub root: PathBuf,
+    pub files: usize,
+}
+
+/// Unpack `path` with the safety limits described in the module docs.
+pub fn extract(path: &Path, kind: ArchiveKind) -> Result<Extracted> {
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("archive");
+    let stem = &file_name[..file_name.len() - kind.extension(file_name)];
+    let stem = if stem.is_empty() { "archive" } else { stem };
+
+    let dir = tempfile::tempdir()?;
+    let root = dir.path().join(stem);
+    std::fs::create_dir(&root)?;
+    let mut limits = Limits::default();
+    let file = File::open(path)?;
+    match kind {
+        ArchiveKind::Zip => extract_zip(file, &root, &mut limits),
+        ArchiveKind::Tar => extract_tar(file, &root, &mut limits),
+        ArchiveKind::TarGz => extract_tar(flate2::read::GzDecoder::new(file), &root, &mut limits),
+    }
+    .map_err(|e| match e {
+        Error::Client(message) => Error::Client(format!("{}: {message}", path.display())),
+        Error::Io(e) => Error::Client(format!("Failed to extract {}: {e}", path.display())),
+        e => e,
+    })?;
+    if limits.files == 0 {
+        return Err(Error::Client(format!(
+            "{} contains no files to add",
+            path.display()
+        )));
+    }
+
+    Ok(Extracted {
+        root: single_directory(&root)?.unwrap_or(root),
+        _dir: dir,
+        files: limits.files,
+    })
+}
+
+#[derive(Default)]
+struct Limits {
+    entries: usize,
+    files: usize,
+    bytes: u64,
+}
+
+impl Limits {
+    fn entry(&mut self) -> Result<()> {
+        self.entries += 1;
+        if self.entries > MAX_ENTRIES {
+            return Err(Error::Client(format!(
+                "archive has more than {MAX_ENTRIES} entries"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Copy one file's content to `dest`, failing once the archive as a whole
+    /// has produced more than [`MAX_EXTRACTED_BYTES`].
+    fn write(&mut self, reader: &mut impl Read, dest: &Path) -> Result<()> {
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut out = File::create(dest)?;
+        let budget = MAX_EXTRACTED_BYTES - self.bytes;
+        let written = std::io::copy(&mut reader.take(budget + 1), &mut out)?;
+        out.flush()?;
+        if written > budget {
+            return Err(Error::Client(format!(
+                "archive unpacks to more than {} GiB",
+                MAX_EXTRACTED_BYTES >> 30
+            )));
+        }
+        self.bytes += written;
+        self.files += 1;
+        Ok(())
+    }
+}
+
+/// `name` as a relative path under the extraction root (empty for the root
+/// itself), or `None` when it could escape it.
+fn enclosed(name: &Path) -> Option<PathBuf> {
+    let mut path = PathBuf::new();
+    for component in name.components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(path)
+}
+
+fn unsafe_entry(name: impl std::fmt::Display) -> Error {
+    Error::Client(format!("archive entry '{name}' points outside the archive"))
+}
+
+fn extract_zip(file: File, root: &Path, limits: &mut Limits) -> Result<()> {
+    let mut archive = zip::ZipArchive::new(file)?;
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        limits.entry()?;
+        let relative =
+            enclosed(Path::new(entry.name())).ok_or_else(|| unsafe_entry(entry.name()))?;
+        if relative.as_os_str().is_empty() {
+            continue;
+        }
+        if entry.is_dir() {
+            std::fs::create_dir_all(root.join(relative))?;
+        } else if entry.is_file() && !entry.is_symlink() {
+            limits.write(&mut entry, &root.join(relative))?;
+        }
+    }
+    Ok(())
+}
+
+fn extract_tar(reader: impl Read, root: &Path, limits: &mut Limits) -> Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        limits.entry()?;
+        let name = entry.path()?.into_owned();
+        let relative = enclosed(&name).ok_or_else(|| unsafe_entry(name.display()))?;
+        if relative.as_os_str().is_empty() {
+            continue;
+        }
+        let kind = entry.header().entry_type();
+        if kind.is_dir() {
+            std::fs::create_dir_all(root.join(relative))?;
+        } else if kind.is_file() {
+            limits.write(&mut entry, &root.join(relative))?;
+        }
+    }
+    Ok(())
+}
+
+/// The one directory under `root` when it holds nothing else, as archives of
+/// a project folder (`project-1.0.tar.gz` → `project-1.0/...`) usually do.
+fn single_directory(root: &Path) -> Result<Option<PathBuf>> {
+    let mut entries = std::fs::read_dir(root)?;
+    let (Some(only), None) = (entries.next().transpose()?, entries.next()) else {
+        return Ok(None);
+    };
+    Ok(only.file_type()?.is_dir().then(|| only.path()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zip::write::SimpleFileOptions;
+
+    fn zip_with(entries: &[(&str, &str)]) -> (TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Handbook.ZIP");
+        let mut writer = zip::ZipWriter::new(File::create(&path).unwrap());
+        for (name, content) in entries {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+        (dir, path)
+    }
+
+    #[test]
+    fn archives_are_recognized_by_extension() {
+        assert_eq!(
+            ArchiveKind::of(Path::new("a/docs.zip")),
+            Some(ArchiveKind::Zip)
+        );
+        assert_eq!(
+            ArchiveKind::of(Path::new("docs.tgz")),
+            Some(ArchiveKind::TarGz)
+        );
+        assert_eq!(
+            ArchiveKind::of(Path::new("docs.TAR.GZ")),
+            Some(ArchiveKind::TarGz)
+        );
+        assert_eq!(
+            ArchiveKind::of(Path::new("docs.tar")),
+            Some(ArchiveKind::Tar)
+        );
+        assert_eq!(ArchiveKind::of(Path::new("docs.gz")), None);
+        assert_eq!(ArchiveKind::of(Path::new("notes.md")), None);
+    }
+
+    #[test]
+    fn zip_is_extracted_under_a_directory_named_after_it() {
+        let (_dir, path) = zip_with(&[("intro.md", "# Intro"), ("guide/setup.md", "# Setup")]);
+        let extracted = extract(&path, ArchiveKind::Zip).unwrap();
+        assert!(extracted.root.ends_with("Handbook"));
+        assert_eq!(extracted.files, 2);
+        assert_eq!(
+            std::fs::read_to_string(extracted.root.join("guide/setup.md")).unwrap(),
+            "# Setup"
+        );
+    }
+
+    #[test]
+    fn single_top_level_directory_becomes_the_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("project-1.0.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(
+            File::create(&path).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        let content = b"# Project";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "project-1.0/README.md", &content[..])
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let extracted = extract(&path, ArchiveKind::TarGz).unwrap();
+        assert!(extracted.root.ends_with("project-1.0"));
+        assert!(extracted.root.join("README.md").is_file());
+    }
+
+    #[test]
+    fn entries_escaping_the_root_are_refused() {
+        assert_eq!(
+            enclosed(Path::new("./a/b.md")),
+            Some(PathBuf::from("a/b.md"))
+        );
+        assert_eq!(enclosed(Path::new("a/../../b.md")), None);
+        assert_eq!(enclosed(Path::new("/etc/passwd")), None);
+        assert_eq!(enclosed(Path::new("./")), Some(PathBuf::new()));
+
+        let (_dir, path) = zip_with(&[("../evil.md", "x")]);
+        let error = extract(&path, ArchiveKind::Zip).err().unwrap();
+        assert!(error.to_string().contains("points outside the archive"));
+    }
+}
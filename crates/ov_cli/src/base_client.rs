@@ -3,15 +3,26 @@ use serde::de::DeserializeOwned;
 use serde_json::Value;
 use std::any::TypeId;
 use std::fs::File;
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tempfile::{Builder, NamedTempFile};
 use zip::CompressionMethod;
 use zip::write::FileOptions;
 
 use indicatif::{ProgressBar, ProgressStyle};
 
+use crate::cassette::{Cassette, RecordedRequest};
+use crate::chunked_upload;
 use crate::error::{Error, Result};
+use crate::http_debug;
+use crate::ingest;
+use crate::notebook;
+use crate::output::NdjsonStream;
+use crate::timing;
 
 const GATEWAY_MARKER_HEADER: &str = "X-VikingBot-Gateway";
 const GATEWAY_TOKEN_HEADER: &str = "X-Gateway-Token";
@@ -69,6 +80,20 @@ fn zip_entry_name(relative_path: &Path) -> Result<String> {
     Ok(normalize_zip_entry_name(name))
 }
 
+/// Add `bytes` to the upload as `name`, as the ingest rewrites leave them.
+fn add_filtered(
+    zip: &mut zip::ZipWriter<File>,
+    name: &str,
+    bytes: Vec<u8>,
+    options: FileOptions<'_, ()>,
+) -> Result<()> {
+    if let Some((name, bytes)) = ingest::filter(name, bytes)? {
+        zip.start_file(name, options)?;
+        zip.write_all(&bytes)?;
+    }
+    Ok(())
+}
+
 pub fn api_error_from_envelope(json: &Value, status: StatusCode) -> Error {
     let error_code = json
         .get("error")
@@ -101,7 +126,7 @@ pub fn api_error_from_envelope(json: &Value, status: StatusCode) -> Error {
     )
 }
 
-pub(crate) fn api_error_from_body(body: &[u8], status: StatusCode) -> Error {
+pub fn api_error_from_body(body: &[u8], status: StatusCode) -> Error {
     match serde_json::from_slice(body) {
         Ok(json) => api_error_from_envelope(&json, status),
         Err(_) => Error::api_with_status(
@@ -124,8 +149,18 @@ pub fn unwrap_success_envelope(json: Value, preserve_profile: bool) -> Value {
         return result.clone();
     }
 
+    // Telemetry the request asked for travels with an object result.
+    let mut result = result.clone();
+    if let (Some(result_obj), Some(telemetry)) = (
+        result.as_object_mut(),
+        json.get("telemetry")
+            .filter(|telemetry| !telemetry.is_null()),
+    ) {
+        result_obj.insert("telemetry".to_string(), telemetry.clone());
+    }
+
     let Some(profile) = json.get("profile").filter(|profile| !profile.is_null()) else {
-        return result.clone();
+        return result;
     };
 
     if let Some(result_obj) = result.as_object() {
@@ -140,6 +175,137 @@ pub fn unwrap_success_envelope(json: Value, preserve_profile: bool) -> Value {
     Value::Object(wrapped)
 }
 
+// ============ Proxy ============
+
+/// `proxy` value that bypasses every proxy, including `HTTP(S)_PROXY`.
+pub const PROXY_NONE: &str = "none";
+
+/// Apply a `--proxy` / config `proxy` setting to a client builder.
+///
+/// Without a setting the standard `HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY` and
+/// `NO_PROXY` variables apply. An explicit URL (`http://`, `https://`,
+/// `socks5://` or `socks5h://`) replaces them but still honors `NO_PROXY`;
+/// `none` connects directly.
+pub fn configure_proxy(
+    builder: reqwest::ClientBuilder,
+    proxy: Option<&str>,
+) -> Result<reqwest::ClientBuilder> {
+    let Some(proxy) = proxy.map(str::trim).filter(|proxy| !proxy.is_empty()) else {
+        return Ok(builder);
+    };
+    if proxy.eq_ignore_ascii_case(PROXY_NONE) {
+        return Ok(builder.no_proxy());
+    }
+    let scheme = proxy.split_once("://").map(|(scheme, _)| scheme);
+    if !matches!(scheme, Some("http" | "https" | "socks5" | "socks5h")) {
+        return Err(Error::Config(format!(
+            "Invalid proxy URL '{proxy}': expected http://, https://, socks5:// or socks5h://, or 'none'"
+        )));
+    }
+    let parsed = reqwest::Proxy::all(proxy)
+        .map_err(|e| Error::Config(format!("Invalid proxy URL '{proxy}': {e}")))?
+        .no_proxy(reqwest::NoProxy::from_env());
+    Ok(builder.proxy(parsed))
+}
+
+// ============ Unix socket ============
+
+const UNIX_SOCKET_SCHEME: &str = "unix://";
+
+/// Requests over a Unix socket still need an HTTP URL; only the path is used.
+pub const UNIX_SOCKET_BASE_URL: &str = "http://localhost";
+
+/// Socket path of a `unix:///var/run/openviking.sock` server URL.
+pub fn unix_socket_path(url: &str) -> Option<PathBuf> {
+    url.trim()
+        .strip_prefix(UNIX_SOCKET_SCHEME)
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Base URL for requests: the server URL itself, or a placeholder HTTP URL
+/// when the server is reached over a Unix socket.
+pub fn request_base_url(url: &str) -> String {
+    if unix_socket_path(url).is_some() {
+        UNIX_SOCKET_BASE_URL.to_string()
+    } else {
+        url.trim_end_matches('/').to_string()
+    }
+}
+
+pub fn configure_unix_socket(
+    builder: reqwest::ClientBuilder,
+    unix_socket: Option<&Path>,
+) -> reqwest::ClientBuilder {
+    #[cfg(unix)]
+    if let Some(path) = unix_socket {
+        return builder.unix_socket(path);
+    }
+    #[cfg(not(unix))]
+    let _ = unix_socket;
+    builder
+}
+
+// ============ TLS ============
+
+/// PEM files for a private CA and an optional mTLS client identity.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TlsFiles {
+    pub ca_cert: Option<PathBuf>,
+    pub client_cert: Option<PathBuf>,
+    /// Omit when `client_cert` holds both the certificate and its key.
+    pub client_key: Option<PathBuf>,
+}
+
+/// Trust `ca_cert` in addition to the built-in roots and present the client
+/// identity when the server asks for one.
+pub fn configure_tls(
+    mut builder: reqwest::ClientBuilder,
+    tls: &TlsFiles,
+) -> Result<reqwest::ClientBuilder> {
+    if let Some(path) = &tls.ca_cert {
+        let pem = read_pem(path, "CA certificate")?;
+        let certs = reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| {
+            Error::Config(format!("Invalid CA certificate '{}': {e}", path.display()))
+        })?;
+        if certs.is_empty() {
+            return Err(Error::Config(format!(
+                "Invalid CA certificate '{}': no PEM certificates found",
+                path.display()
+            )));
+        }
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+    match (&tls.client_cert, &tls.client_key) {
+        (Some(cert_path), key_path) => {
+            let mut pem = read_pem(cert_path, "client certificate")?;
+            if let Some(key_path) = key_path {
+                pem.push(b'\n');
+                pem.extend(read_pem(key_path, "client key")?);
+            }
+            let identity = reqwest::Identity::from_pem(&pem).map_err(|e| {
+                Error::Config(format!(
+                    "Invalid client certificate '{}': {e}",
+                    cert_path.display()
+                ))
+            })?;
+            builder = builder.identity(identity);
+        }
+        (None, Some(_)) => {
+            return Err(Error::Config("client_key requires client_cert".to_string()));
+        }
+        (None, None) => {}
+    }
+    Ok(builder)
+}
+
+fn read_pem(path: &Path, what: &str) -> Result<Vec<u8>> {
+    std::fs::read(path)
+        .map_err(|e| Error::Config(format!("Failed to read {what} '{}': {e}", path.display())))
+}
+
 // ============ TimeoutConfig ============
 
 /// Dynamic timeout calculator based on file size
@@ -167,28 +333,83 @@ impl TimeoutConfig {
 
     pub fn calculate(&self, file_path: &Path) -> Result<std::time::Duration> {
         let file_size = std::fs::metadata(file_path)?.len();
+        Ok(self.calculate_for_size(file_size))
+    }
+
+    pub fn calculate_for_size(&self, file_size: u64) -> std::time::Duration {
         let file_size_mb = file_size as f64 / (1024.0 * 1024.0);
         let calculated_timeout = (file_size_mb * self.seconds_per_mb).ceil() as u64;
         let timeout_secs = std::cmp::max(self.min_timeout_secs, calculated_timeout);
 
-        Ok(std::time::Duration::from_secs(timeout_secs))
+        std::time::Duration::from_secs(timeout_secs)
     }
 }
 
+// ============ Shared connections ============
+
+/// Everything a reqwest client is built from, so clients built alike can be
+/// shared.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ConnectionSettings {
+    proxy: Option<String>,
+    tls: TlsFiles,
+    connect_timeout: Option<std::time::Duration>,
+    unix_socket: Option<PathBuf>,
+    timeout: std::time::Duration,
+}
+
+static SHARE_CONNECTIONS: AtomicBool = AtomicBool::new(false);
+static SHARED_CLIENTS: Mutex<Vec<(ConnectionSettings, ReqwestClient)>> = Mutex::new(Vec::new());
+
+/// Reuse one reqwest client, and with it its open connections, across every
+/// `BaseClient` built with the same settings for the rest of the process.
+/// `ov run` turns this on so a script's commands share connections and TLS
+/// sessions instead of each setting up their own.
+pub fn share_connections() {
+    SHARE_CONNECTIONS.store(true, Ordering::Relaxed);
+}
+
+fn shared_http(
+    settings: ConnectionSettings,
+    build: impl FnOnce() -> reqwest::Result<ReqwestClient>,
+) -> reqwest::Result<ReqwestClient> {
+    if !SHARE_CONNECTIONS.load(Ordering::Relaxed) {
+        return build();
+    }
+    let mut clients = SHARED_CLIENTS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some((_, http)) = clients.iter().find(|(shared, _)| *shared == settings) {
+        return Ok(http.clone());
+    }
+    let http = build()?;
+    clients.push((settings, http.clone()));
+    Ok(http)
+}
+
 // ============ BaseClient ============
 
 /// Low-level HTTP client with timeout control and header management
 #[derive(Clone)]
 pub struct BaseClient {
-    pub(crate) http: ReqwestClient,
-    pub(crate) base_url: String,
-    pub(crate) api_key: Option<String>,
-    pub(crate) account: Option<String>,
-    pub(crate) user: Option<String>,
-    pub(crate) actor_peer_id: Option<String>,
-    pub(crate) profile_enabled: bool,
-    pub(crate) extra_headers: Option<std::collections::HashMap<String, String>>,
+    pub http: ReqwestClient,
+    pub base_url: String,
+    /// The configured server URL, which `base_url` may stand in for.
+    pub server_url: String,
+    pub api_key: Option<String>,
+    pub account: Option<String>,
+    pub user: Option<String>,
+    pub actor_peer_id: Option<String>,
+    pub profile_enabled: bool,
+    pub extra_headers: Option<std::collections::HashMap<String, String>>,
     gateway_token: Option<String>,
+    timeout: std::time::Duration,
+    proxy: Option<String>,
+    tls: TlsFiles,
+    connect_timeout: Option<std::time::Duration>,
+    unix_socket: Option<PathBuf>,
+    cassette: Option<Arc<Cassette>>,
+    ndjson_stream: Option<Arc<NdjsonStream>>,
 }
 
 impl BaseClient {
@@ -202,14 +423,28 @@ impl BaseClient {
         profile_enabled: bool,
         extra_headers: Option<std::collections::HashMap<String, String>>,
     ) -> Self {
-        let http = ReqwestClient::builder()
-            .timeout(std::time::Duration::from_secs_f64(timeout_secs))
-            .build()
-            .expect("Failed to build HTTP client");
+        let server_url = base_url.into();
+        let unix_socket = unix_socket_path(&server_url);
+        let base_url = request_base_url(&server_url);
+        let timeout = std::time::Duration::from_secs_f64(timeout_secs);
+        let settings = ConnectionSettings {
+            proxy: None,
+            tls: TlsFiles::default(),
+            connect_timeout: None,
+            unix_socket: unix_socket.clone(),
+            timeout,
+        };
+        let http = shared_http(settings, || {
+            configure_unix_socket(ReqwestClient::builder(), unix_socket.as_deref())
+                .timeout(timeout)
+                .build()
+        })
+        .expect("Failed to build HTTP client");
 
         Self {
             http,
-            base_url: base_url.into().trim_end_matches('/').to_string(),
+            base_url,
+            server_url,
             api_key,
             account,
             user,
@@ -217,6 +452,13 @@ impl BaseClient {
             profile_enabled,
             extra_headers,
             gateway_token: None,
+            timeout,
+            proxy: None,
+            tls: TlsFiles::default(),
+            connect_timeout: None,
+            unix_socket,
+            cassette: None,
+            ndjson_stream: None,
         }
     }
 
@@ -227,6 +469,82 @@ impl BaseClient {
         self
     }
 
+    /// Route requests through a proxy; see [`configure_proxy`]. The setting is
+    /// checked by `Config::validate_proxy` before any client is built.
+    pub fn with_proxy(mut self, proxy: Option<String>) -> Self {
+        self.proxy = proxy;
+        self.rebuild_http();
+        self
+    }
+
+    /// Use a private CA and client certificate; see [`configure_tls`]. The
+    /// files are checked by `Config::validate_tls` before any client is built.
+    pub fn with_tls(mut self, tls: TlsFiles) -> Self {
+        self.tls = tls;
+        self.rebuild_http();
+        self
+    }
+
+    /// Limit connection setup separately from the whole-request timeout;
+    /// `None` keeps reqwest's default of no separate limit.
+    pub fn with_connect_timeout(mut self, connect_timeout: Option<std::time::Duration>) -> Self {
+        self.connect_timeout = connect_timeout;
+        self.rebuild_http();
+        self
+    }
+
+    /// Send requests through the `ov daemon` listening on `socket`, which holds
+    /// the server connection along with its proxy and TLS settings.
+    pub fn with_daemon(mut self, socket: Option<PathBuf>) -> Self {
+        if let Some(socket) = socket {
+            self.base_url = UNIX_SOCKET_BASE_URL.to_string();
+            self.unix_socket = Some(socket);
+            self.proxy = None;
+            self.tls = TlsFiles::default();
+            self.rebuild_http();
+        }
+        self
+    }
+
+    /// Record every exchange to `cassette`, or answer every request from it;
+    /// see [`crate::cassette`].
+    pub fn with_cassette(mut self, cassette: Option<Arc<Cassette>>) -> Self {
+        self.cassette = cassette;
+        self
+    }
+
+    /// Print the records of JSON responses through `stream` as they arrive.
+    pub fn with_ndjson_stream(mut self, stream: Option<Arc<NdjsonStream>>) -> Self {
+        self.ndjson_stream = stream;
+        self
+    }
+
+    fn rebuild_http(&mut self) {
+        let settings = ConnectionSettings {
+            proxy: self.proxy.clone(),
+            tls: self.tls.clone(),
+            connect_timeout: self.connect_timeout,
+            unix_socket: self.unix_socket.clone(),
+            timeout: self.timeout,
+        };
+        if let Ok(http) = shared_http(settings, || {
+            self.client_builder().timeout(self.timeout).build()
+        }) {
+            self.http = http;
+        }
+    }
+
+    fn client_builder(&self) -> reqwest::ClientBuilder {
+        let builder = configure_proxy(ReqwestClient::builder(), self.proxy.as_deref())
+            .and_then(|builder| configure_tls(builder, &self.tls))
+            .unwrap_or_else(|_| ReqwestClient::builder());
+        let builder = match self.connect_timeout {
+            Some(connect_timeout) => builder.connect_timeout(connect_timeout),
+            None => builder,
+        };
+        configure_unix_socket(builder, self.unix_socket.as_deref())
+    }
+
     fn append_profile_query<'a>(&self, params: &'a [(String, String)]) -> Vec<(String, String)> {
         let mut merged = params.to_vec();
         if self.profile_enabled && !merged.iter().any(|(k, _)| k == "profile") {
@@ -294,16 +612,13 @@ impl BaseClient {
                 .is_some_and(|value| value.eq_ignore_ascii_case("true"))
     }
 
-    pub(crate) async fn send_request(
+    pub async fn send_request(
         &self,
         request: reqwest::RequestBuilder,
         error_context: &str,
     ) -> Result<reqwest::Response> {
         let retry = request.try_clone();
-        let response = request
-            .send()
-            .await
-            .map_err(|e| Error::from_reqwest(error_context, e))?;
+        let response = self.send_traced(request, 1, error_context).await?;
         if !Self::is_gateway_token_challenge(&response) {
             return Ok(response);
         }
@@ -314,11 +629,62 @@ impl BaseClient {
         let Some(retry) = retry else {
             return Ok(response);
         };
-        retry
-            .header(GATEWAY_TOKEN_HEADER, gateway_token)
-            .send()
-            .await
-            .map_err(|e| Error::from_reqwest(error_context, e))
+        self.send_traced(
+            retry.header(GATEWAY_TOKEN_HEADER, gateway_token),
+            2,
+            error_context,
+        )
+        .await
+    }
+
+    async fn send_traced(
+        &self,
+        request: reqwest::RequestBuilder,
+        attempt: u32,
+        error_context: &str,
+    ) -> Result<reqwest::Response> {
+        let (client, request) = request.build_split();
+        let request = request.map_err(|e| Error::from_reqwest(error_context, e))?;
+        http_debug::log_request(&request, attempt);
+        let recording = match &self.cassette {
+            Some(cassette) if cassette.is_replaying() => {
+                let response = cassette.replay_response(&request)?;
+                http_debug::log_response(&response, std::time::Duration::ZERO);
+                return Ok(response);
+            }
+            Some(cassette) => Some((cassette, RecordedRequest::of(&request))),
+            None => None,
+        };
+        let method = request.method().clone();
+        let path = request.url().path().to_string();
+        let sent = request
+            .body()
+            .and_then(reqwest::Body::as_bytes)
+            .map_or(0, |body| body.len() as u64);
+        let started = Instant::now();
+        match client.execute(request).await {
+            Ok(response) => {
+                let elapsed = started.elapsed();
+                http_debug::log_response(&response, elapsed);
+                timing::record(
+                    method.as_str(),
+                    &path,
+                    Some(response.status().as_u16()),
+                    elapsed,
+                    sent,
+                );
+                match recording {
+                    Some((cassette, request)) => cassette.record_response(request, response).await,
+                    None => Ok(response),
+                }
+            }
+            Err(e) => {
+                let elapsed = started.elapsed();
+                http_debug::log_failure(&e, elapsed);
+                timing::record(method.as_str(), &path, None, elapsed, sent);
+                Err(Error::from_reqwest(error_context, e))
+            }
+        }
     }
 
     async fn headers_for_uncloneable_request(&self) -> Result<reqwest::header::HeaderMap> {
@@ -327,13 +693,10 @@ impl BaseClient {
             return Ok(headers);
         };
         let health_url = format!("{}/health", self.base_url);
+        let request = self.http.get(health_url).headers(headers.clone());
         let response = self
-            .http
-            .get(health_url)
-            .headers(headers.clone())
-            .send()
-            .await
-            .map_err(|e| Error::from_reqwest("Gateway detection request failed", e))?;
+            .send_traced(request, 1, "Gateway detection request failed")
+            .await?;
         if Self::is_gateway_token_challenge(&response) {
             if let Ok(value) = reqwest::header::HeaderValue::from_str(gateway_token) {
                 headers.insert(GATEWAY_TOKEN_HEADER, value);
@@ -342,24 +705,34 @@ impl BaseClient {
         Ok(headers)
     }
 
-    pub(crate) async fn handle_response<T: DeserializeOwned + 'static>(
+    pub async fn handle_response<T: DeserializeOwned + 'static>(
         &self,
         response: reqwest::Response,
     ) -> Result<T> {
         let status = response.status();
+        let path = response.url().path().to_string();
 
         if status == StatusCode::NO_CONTENT || status == StatusCode::ACCEPTED {
             return serde_json::from_value(Value::Null)
                 .map_err(|e| Error::Parse(format!("Failed to parse empty response: {}", e)));
         }
 
-        let bytes = response
-            .bytes()
-            .await
-            .map_err(|e| Error::from_reqwest("Failed to read response body", e))?;
+        let preserve_profile = TypeId::of::<T>() == TypeId::of::<Value>();
+        let bytes: bytes::Bytes = match &self.ndjson_stream {
+            Some(stream) if preserve_profile && status.is_success() => {
+                stream.read(response).await?.into()
+            }
+            _ => response
+                .bytes()
+                .await
+                .map_err(|e| Error::from_reqwest("Failed to read response body", e))?,
+        };
+
+        http_debug::log_response_body(&bytes);
+        timing::record_received(bytes.len() as u64);
 
         if !status.is_success() {
-            return Err(api_error_from_body(&bytes, status));
+            return Err(api_error_from_body(&bytes, status).with_endpoint(&path));
         }
 
         let json: Value = match serde_json::from_slice(&bytes) {
@@ -379,7 +752,6 @@ impl BaseClient {
             }
         }
 
-        let preserve_profile = TypeId::of::<T>() == TypeId::of::<Value>();
         let result = unwrap_success_envelope(json.clone(), preserve_profile);
 
         serde_json::from_value(result).map_err(|e| {
@@ -390,23 +762,23 @@ impl BaseClient {
         })
     }
 
-    pub(crate) fn create_client_with_timeout(
+    pub fn create_client_with_timeout(
         &self,
         timeout: std::time::Duration,
     ) -> Result<ReqwestClient> {
-        ReqwestClient::builder()
+        self.client_builder()
             .timeout(timeout)
             .build()
             .map_err(|e| Error::from_reqwest("Failed to build HTTP client", e))
     }
 
-    pub(crate) fn create_client_with_connect_timeout(
+    pub fn create_client_with_connect_timeout(
         &self,
         connect_timeout: std::time::Duration,
         timeout: std::time::Duration,
     ) -> Result<ReqwestClient> {
-        ReqwestClient::builder()
-            .connect_timeout(connect_timeout)
+        self.client_builder()
+            .connect_timeout(self.connect_timeout.unwrap_or(connect_timeout))
             .timeout(timeout)
             .build()
             .map_err(|e| Error::from_reqwest("Failed to build HTTP client", e))
@@ -429,6 +801,23 @@ impl BaseClient {
         self.handle_response(response).await
     }
 
+    /// GET a probe such as `/ready`, whose 503 answer still carries the JSON
+    /// report of what is not ready.
+    pub async fn get_probe(&self, path: &str) -> Result<Value> {
+        let url = format!("{}{}", self.base_url, path);
+        let request = self.http.get(&url).headers(self.build_headers());
+        let response = self.send_request(request, "HTTP request failed").await?;
+        if response.status() != StatusCode::SERVICE_UNAVAILABLE {
+            return self.handle_response(response).await;
+        }
+        let status = response.status();
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| Error::from_reqwest("Failed to read response body", e))?;
+        serde_json::from_slice(&bytes).map_err(|_| api_error_from_body(&bytes, status))
+    }
+
     pub async fn post<B: serde::Serialize, T: DeserializeOwned + 'static>(
         &self,
         path: &str,
@@ -562,6 +951,30 @@ impl BaseClient {
 
         self.handle_response(response).await
     }
+
+    /// Send an arbitrary method to `path`, with an optional JSON body.
+    pub async fn request<T: DeserializeOwned + 'static>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        params: &[(String, String)],
+        body: Option<&Value>,
+    ) -> Result<T> {
+        let url = format!("{}{}", self.base_url, path);
+        let params = self.append_profile_query(params);
+        let request = self
+            .http
+            .request(method, &url)
+            .headers(self.build_headers())
+            .query(&params);
+        let request = match body {
+            Some(body) => request.json(body),
+            None => request,
+        };
+        let response = self.send_request(request, "HTTP request failed").await?;
+
+        self.handle_response(response).await
+    }
 }
 
 #[cfg(test)]
@@ -572,6 +985,58 @@ mod tests {
     use tokio::io::AsyncWriteExt;
     use tokio::net::TcpListener;
 
+    #[test]
+    fn configure_tls_reports_unusable_certificate_files() {
+        let dir = tempfile::tempdir().expect("tempdir should be created");
+        let empty = dir.path().join("empty.pem");
+        std::fs::write(&empty, "not a certificate\n").unwrap();
+        let error_for = |tls: TlsFiles| match configure_tls(ReqwestClient::builder(), &tls) {
+            Err(Error::Config(message)) => message,
+            Err(other) => panic!("expected config error, got {other:?}"),
+            Ok(_) => panic!("expected config error"),
+        };
+
+        let message = error_for(TlsFiles {
+            ca_cert: Some(dir.path().join("missing.pem")),
+            ..TlsFiles::default()
+        });
+        assert!(message.starts_with("Failed to read CA certificate"));
+
+        let message = error_for(TlsFiles {
+            ca_cert: Some(empty.clone()),
+            ..TlsFiles::default()
+        });
+        assert!(message.contains("no PEM certificates found"));
+
+        let message = error_for(TlsFiles {
+            client_key: Some(empty.clone()),
+            ..TlsFiles::default()
+        });
+        assert_eq!(message, "client_key requires client_cert");
+
+        let message = error_for(TlsFiles {
+            client_cert: Some(empty),
+            ..TlsFiles::default()
+        });
+        assert!(message.starts_with("Invalid client certificate"));
+
+        assert!(configure_tls(ReqwestClient::builder(), &TlsFiles::default()).is_ok());
+    }
+
+    #[test]
+    fn unwrap_success_envelope_keeps_requested_telemetry_with_object_results() {
+        let body = json!({
+            "status": "ok",
+            "result": {"resources": []},
+            "telemetry": {"id": "t-1", "summary": {"vector": {"scored": 12}}}
+        });
+
+        let result = unwrap_success_envelope(body, true);
+
+        assert_eq!(result["telemetry"]["summary"]["vector"]["scored"], 12);
+        assert_eq!(result["resources"], json!([]));
+    }
+
     #[test]
     fn unwrap_success_envelope_preserves_profile_for_value_results() {
         let body = json!({
@@ -665,6 +1130,53 @@ mod tests {
         ));
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_socket_url_sends_requests_over_the_socket() {
+        use tokio::io::AsyncReadExt;
+
+        let dir = tempfile::tempdir().expect("tempdir should be created");
+        let socket = dir.path().join("openviking.sock");
+        let listener = tokio::net::UnixListener::bind(&socket).unwrap();
+        let server = tokio::spawn(async move {
+            let (mut connection, _) = listener.accept().await.unwrap();
+            let mut request = vec![0; 1024];
+            let read = connection.read(&mut request).await.unwrap();
+            let body = r#"{"status":"ok","result":{"healthy":true}}"#;
+            connection
+                .write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+                        body.len()
+                    )
+                    .as_bytes(),
+                )
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&request[..read]).into_owned()
+        });
+
+        let client = BaseClient::new(
+            format!("unix://{}", socket.display()),
+            None,
+            None,
+            None,
+            None,
+            5.0,
+            false,
+            None,
+        );
+        let result: Value = client.get("/health", &[]).await.unwrap();
+
+        assert_eq!(result, json!({"healthy": true}));
+        assert!(server.await.unwrap().starts_with("GET /health HTTP/1.1"));
+        assert_eq!(
+            unix_socket_path("unix:///var/run/openviking.sock"),
+            Some(PathBuf::from("/var/run/openviking.sock"))
+        );
+        assert_eq!(unix_socket_path("http://localhost:1933"), None);
+    }
+
     #[test]
     fn unwrap_success_envelope_drops_null_profile_for_value_results() {
         let body = json!({
@@ -789,6 +1301,9 @@ mod tests {
 pub struct FileUploader<'a> {
     client: &'a BaseClient,
     upload_mode: Option<String>,
+    resume: bool,
+    convert_notebooks: bool,
+    content_type: Option<String>,
 }
 
 impl<'a> FileUploader<'a> {
@@ -796,6 +1311,9 @@ impl<'a> FileUploader<'a> {
         Self {
             client,
             upload_mode: None,
+            resume: false,
+            convert_notebooks: false,
+            content_type: None,
         }
     }
 
@@ -804,6 +1322,77 @@ impl<'a> FileUploader<'a> {
         self
     }
 
+    /// Continue an earlier chunked upload of the same file; see [`chunked_upload`].
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    /// Content type of uploaded files; `None` sends them as octet streams.
+    pub fn with_content_type(mut self, content_type: Option<String>) -> Self {
+        self.content_type = content_type;
+        self
+    }
+
+    /// Zip `.ipynb` files as Markdown; see [`crate::notebook`].
+    pub fn with_notebook_conversion(mut self, convert: bool) -> Self {
+        self.convert_notebooks = convert;
+        self
+    }
+
+    /// Add the file at `path` to `zip` as `name` and return its size on disk.
+    /// Notebooks that fail to convert are added as they are.
+    fn add_to_zip(
+        &self,
+        zip: &mut zip::ZipWriter<File>,
+        path: &Path,
+        name: String,
+        options: FileOptions<'_, ()>,
+    ) -> Result<u64> {
+        if self.convert_notebooks && notebook::is_notebook(path) {
+            let json = std::fs::read_to_string(path)?;
+            if let Ok(markdown) = notebook::to_markdown(&json) {
+                add_filtered(
+                    zip,
+                    &notebook::markdown_name(&name),
+                    markdown.into_bytes(),
+                    options,
+                )?;
+                return Ok(json.len() as u64);
+            }
+        }
+        if ingest::active() {
+            let bytes = std::fs::read(path)?;
+            let size = bytes.len() as u64;
+            add_filtered(zip, &name, bytes, options)?;
+            return Ok(size);
+        }
+        zip.start_file(name, options)?;
+        let mut file = File::open(path)?;
+        Ok(std::io::copy(&mut file, zip)?)
+    }
+
+    /// Send large files, and every file when resuming, in chunks. `None`
+    /// means the file should go out as a single multipart request.
+    async fn upload_chunked(
+        &self,
+        file_path: &Path,
+        progress: Option<&ProgressBar>,
+    ) -> Result<Option<String>> {
+        let size = std::fs::metadata(file_path)?.len();
+        if !chunked_upload::should_chunk(size, self.resume) {
+            return Ok(None);
+        }
+        chunked_upload::upload(
+            self.client,
+            file_path,
+            self.upload_mode.as_deref(),
+            self.resume,
+            progress,
+        )
+        .await
+    }
+
     pub fn zip_directory(
         &self,
         dir_path: &Path,
@@ -833,9 +1422,7 @@ impl<'a> FileUploader<'a> {
             if path.is_file() {
                 let name = path.strip_prefix(dir_path).unwrap_or(path);
                 let name_str = zip_entry_name(name)?;
-                zip.start_file(name_str, options)?;
-                let mut file = File::open(path)?;
-                std::io::copy(&mut file, &mut zip)?;
+                self.add_to_zip(&mut zip, path, name_str, options)?;
             }
         }
 
@@ -909,9 +1496,7 @@ impl<'a> FileUploader<'a> {
                 if verbose {
                     eprintln!("  Adding: {}", name_str);
                 }
-                zip.start_file(name_str, options)?;
-                let mut file = File::open(path)?;
-                let file_size = std::io::copy(&mut file, &mut zip)?;
+                let file_size = self.add_to_zip(&mut zip, path, name_str, options)?;
 
                 if let Some(pb) = &pb {
                     if pb.length().is_some() {
@@ -946,6 +1531,9 @@ impl<'a> FileUploader<'a> {
     }
 
     pub async fn upload_temp_file(&self, file_path: &Path) -> Result<String> {
+        if let Some(temp_file_id) = self.upload_chunked(file_path, None).await? {
+            return Ok(temp_file_id);
+        }
         let url = format!("{}/api/v1/resources/temp_upload", self.client.base_url);
         let file_name = file_path
             .file_name()
@@ -957,7 +1545,11 @@ impl<'a> FileUploader<'a> {
         let part = reqwest::multipart::Part::bytes(file_content).file_name(file_name.to_string());
 
         let part = part
-            .mime_str("application/octet-stream")
+            .mime_str(
+                self.content_type
+                    .as_deref()
+                    .unwrap_or("application/octet-stream"),
+            )
             .map_err(|e| Error::from_reqwest("Failed to set mime type", e))?;
 
         let mut form = reqwest::multipart::Form::new().part("file", part);
@@ -974,13 +1566,14 @@ impl<'a> FileUploader<'a> {
             upload_timeout,
         )?;
 
-        let response = long_timeout_client
+        let request = long_timeout_client
             .post(&url)
             .headers(headers)
-            .multipart(form)
-            .send()
-            .await
-            .map_err(|e| Error::from_reqwest("File upload failed", e))?;
+            .multipart(form);
+        let response = self
+            .client
+            .send_traced(request, 1, "File upload failed")
+            .await?;
 
         let result: Value = self.client.handle_response(response).await?;
         result
@@ -1003,8 +1596,7 @@ impl<'a> FileUploader<'a> {
             .and_then(|n| n.to_str())
             .unwrap_or("temp_upload.zip");
 
-        let file_content = tokio::fs::read(file_path).await?;
-        let file_size = file_content.len() as u64;
+        let file_size = std::fs::metadata(file_path)?.len();
 
         if verbose {
             eprintln!(
@@ -1022,10 +1614,20 @@ impl<'a> FileUploader<'a> {
         ));
         pb.enable_steady_tick(std::time::Duration::from_millis(100));
 
+        if let Some(temp_file_id) = self.upload_chunked(file_path, Some(&pb)).await? {
+            pb.finish_with_message("Upload complete");
+            return Ok(temp_file_id);
+        }
+
+        let file_content = tokio::fs::read(file_path).await?;
         let part = reqwest::multipart::Part::bytes(file_content).file_name(file_name.to_string());
 
         let part = part
-            .mime_str("application/octet-stream")
+            .mime_str(
+                self.content_type
+                    .as_deref()
+                    .unwrap_or("application/octet-stream"),
+            )
             .map_err(|e| Error::from_reqwest("Failed to set mime type", e))?;
 
         let mut form = reqwest::multipart::Form::new().part("file", part);
@@ -1042,13 +1644,14 @@ impl<'a> FileUploader<'a> {
             upload_timeout,
         )?;
 
-        let response = long_timeout_client
+        let request = long_timeout_client
             .post(&url)
             .headers(headers)
-            .multipart(form)
-            .send()
-            .await
-            .map_err(|e| Error::from_reqwest("File upload failed", e))?;
+            .multipart(form);
+        let response = self
+            .client
+            .send_traced(request, 1, "File upload failed")
+            .await?;
 
         pb.finish_with_message("Upload complete");
 
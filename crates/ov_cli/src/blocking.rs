@@ -0,0 +1,380 @@
+//! A blocking [`HttpClient`], for tools and build scripts that are not async.
+//!
+//! Built with the `blocking` feature. The client owns a current-thread tokio
+//! runtime and runs each call of the async client to completion on it, so
+//! callers need no runtime of their own. Like `reqwest::blocking`, it cannot
+//! be used from inside an async runtime: there, creating it or calling it
+//! returns an error, and async code should use [`client::HttpClient`].
+//!
+//! ```no_run
+//! use ov_cli::{blocking, client};
+//!
+//! let client = blocking::HttpClient::new(
+//!     client::HttpClient::new("http://127.0.0.1:1933", None, None, None, None, 60.0, false, None),
+//! )?;
+//! println!("{}", client.read("viking://resources/guide.md")?);
+//! # Ok::<(), ov_cli::error::Error>(())
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use tokio::runtime::{Handle, Runtime};
+
+use crate::client::{
+    self, PackFile, SearchScoring, SnapshotCommitReq, SnapshotRestoreReq, SnapshotShowResult,
+};
+use crate::error::{Error, Result};
+
+/// Blocking versions of async client methods with the same name and
+/// arguments.
+macro_rules! blocking_methods {
+    ($(fn $name:ident(&self $(, $arg:ident: $ty:ty)* $(,)?) -> $ret:ty;)*) => {
+        $(
+            #[allow(clippy::too_many_arguments)]
+            pub fn $name(&self $(, $arg: $ty)*) -> Result<$ret> {
+                self.block_on(self.inner.$name($($arg),*))
+            }
+        )*
+    };
+}
+
+/// The async [`client::HttpClient`] with every request method made blocking.
+pub struct HttpClient {
+    inner: client::HttpClient,
+    runtime: Runtime,
+}
+
+impl HttpClient {
+    /// Wrap a client configured with the async client's builders.
+    pub fn new(inner: client::HttpClient) -> Result<Self> {
+        outside_runtime()?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        Ok(Self { inner, runtime })
+    }
+
+    /// Run `call` to completion on the client's runtime.
+    fn block_on<T>(&self, call: impl Future<Output = Result<T>>) -> Result<T> {
+        outside_runtime()?;
+        self.runtime.block_on(call)
+    }
+
+    /// The wrapped async client.
+    pub fn inner(&self) -> &client::HttpClient {
+        &self.inner
+    }
+
+    pub fn server_url(&self) -> &str {
+        self.inner.server_url()
+    }
+
+    pub fn get<T: DeserializeOwned + 'static>(
+        &self,
+        path: &str,
+        params: &[(String, String)],
+    ) -> Result<T> {
+        self.block_on(self.inner.get(path, params))
+    }
+
+    pub fn post<B: serde::Serialize, T: DeserializeOwned + 'static>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T> {
+        self.block_on(self.inner.post(path, body))
+    }
+
+    pub fn put<B: serde::Serialize, T: DeserializeOwned + 'static>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T> {
+        self.block_on(self.inner.put(path, body))
+    }
+
+    pub fn delete<T: DeserializeOwned + 'static>(
+        &self,
+        path: &str,
+        params: &[(String, String)],
+    ) -> Result<T> {
+        self.block_on(self.inner.delete(path, params))
+    }
+
+    pub fn delete_with_body<B: serde::Serialize, T: DeserializeOwned + 'static>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T> {
+        self.runtime
+            .block_on(self.inner.delete_with_body(path, body))
+    }
+
+    pub fn patch<B: serde::Serialize, T: DeserializeOwned + 'static>(
+        &self,
+        path: &str,
+        body: &B,
+        params: &[(String, String)],
+    ) -> Result<T> {
+        self.block_on(self.inner.patch(path, body, params))
+    }
+
+    pub fn post_with_query<B: serde::Serialize, T: DeserializeOwned + 'static>(
+        &self,
+        path: &str,
+        body: &B,
+        params: &[(String, String)],
+    ) -> Result<T> {
+        self.runtime
+            .block_on(self.inner.post_with_query(path, body, params))
+    }
+
+    pub fn request<T: DeserializeOwned + 'static>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        params: &[(String, String)],
+        body: Option<&Value>,
+    ) -> Result<T> {
+        self.runtime
+            .block_on(self.inner.request(method, path, params, body))
+    }
+
+    blocking_methods! {
+        fn read(&self, uri: &str) -> String;
+        fn read_profiled(&self, uri: &str) -> Value;
+        fn abstract_content(&self, uri: &str) -> String;
+        fn abstract_content_profiled(&self, uri: &str) -> Value;
+        fn overview(&self, uri: &str) -> String;
+        fn overview_profiled(&self, uri: &str) -> Value;
+        fn write(&self, uri: &str, content: &str, mode: &str, wait: bool, timeout: Option<f64>) -> Value;
+        fn set_tags(&self, uri: &str, tags: Vec<String>, mode: &str, recursive: bool) -> Value;
+        fn set_metadata(&self, uri: &str, values: Map<String, Value>, remove: Vec<String>) -> Value;
+        fn reindex(&self, uri: &str, mode: &str, wait: bool, dry_run: bool) -> Value;
+        fn consistency(&self, uri: &str) -> Value;
+        fn backend_sync_status(&self, uri: &str) -> Value;
+        fn backend_sync_retry(&self, uri: &str) -> Value;
+        fn get_bytes(&self, uri: &str) -> Vec<u8>;
+        fn ls(
+            &self,
+            uri: &str,
+            simple: bool,
+            recursive: bool,
+            output: &str,
+            abs_limit: i32,
+            show_all_hidden: bool,
+            node_limit: i32,
+        ) -> Value;
+        fn tree(
+            &self,
+            uri: &str,
+            output: &str,
+            abs_limit: i32,
+            show_all_hidden: bool,
+            node_limit: i32,
+            level_limit: i32,
+        ) -> Value;
+        fn mkdir(&self, uri: &str, description: Option<&str>) -> Value;
+        fn rm(&self, uri: &str, recursive: bool, wait: bool, timeout: Option<f64>) -> Value;
+        fn mv(&self, from_uri: &str, to_uri: &str) -> Value;
+        fn stat(&self, uri: &str) -> Value;
+        fn attrs(&self, uri: &str) -> Value;
+        fn find(
+            &self,
+            query: String,
+            uri: String,
+            image: Option<String>,
+            node_limit: i32,
+            threshold: Option<f64>,
+            since: Option<String>,
+            until: Option<String>,
+            time_field: Option<String>,
+            level: Option<Vec<i32>>,
+            context_type: Option<Vec<String>>,
+            tags: Option<Vec<String>>,
+            scoring: &SearchScoring,
+        ) -> Value;
+        fn search(
+            &self,
+            query: String,
+            uri: String,
+            image: Option<String>,
+            session_id: Option<String>,
+            node_limit: i32,
+            threshold: Option<f64>,
+            since: Option<String>,
+            until: Option<String>,
+            time_field: Option<String>,
+            level: Option<Vec<i32>>,
+            context_type: Option<Vec<String>>,
+            tags: Option<Vec<String>>,
+            scoring: &SearchScoring,
+        ) -> Value;
+        fn grep(
+            &self,
+            uri: &str,
+            exclude_uri: Option<String>,
+            pattern: &str,
+            ignore_case: bool,
+            node_limit: i32,
+            level_limit: i32,
+        ) -> Value;
+        fn glob(&self, pattern: &str, uri: &str, node_limit: i32) -> Value;
+        fn add_resource(
+            &self,
+            path: &str,
+            to: Option<String>,
+            parent: Option<String>,
+            parent_auto_create: Option<String>,
+            reason: &str,
+            instruction: &str,
+            wait: bool,
+            timeout: Option<f64>,
+            strict: bool,
+            ignore_dirs: Option<String>,
+            include: Option<String>,
+            exclude: Option<String>,
+            directly_upload_media: bool,
+            watch_interval: f64,
+            resource_args: Option<Map<String, Value>>,
+            show_progress: bool,
+            verbose: bool,
+        ) -> Value;
+        fn add_skill(
+            &self,
+            data: &str,
+            wait: bool,
+            timeout: Option<f64>,
+            show_progress: bool,
+            verbose: bool,
+            source_metadata: Option<Value>,
+            target_uri: Option<&str>,
+        ) -> Value;
+        fn skills_list(&self, node_limit: i32, target_uri: Option<&str>) -> Value;
+        fn skill_show(
+            &self,
+            name: &str,
+            include_content: bool,
+            include_files: bool,
+            include_source: bool,
+            level: Option<i32>,
+            target_uri: Option<&str>,
+        ) -> Value;
+        fn skill_find(
+            &self,
+            query: &str,
+            node_limit: i32,
+            threshold: Option<f64>,
+            level: Option<Vec<i32>>,
+            target_uri: Option<&str>,
+        ) -> Value;
+        fn skill_validate(&self, path: &str, strict: bool) -> Value;
+        fn skill_update(
+            &self,
+            name: &str,
+            data: &str,
+            wait: bool,
+            timeout: Option<f64>,
+            show_progress: bool,
+            verbose: bool,
+            source_metadata: Option<Value>,
+            target_uri: Option<&str>,
+        ) -> Value;
+        fn skill_remove(&self, name: &str, target_uri: Option<&str>) -> Value;
+        fn get_task(&self, task_id: &str) -> Value;
+        fn list_tasks(&self, task_type: Option<&str>, status: Option<&str>, limit: Option<u32>) -> Value;
+        fn readiness(&self) -> Value;
+        fn queue_status(&self, uri: Option<&str>) -> Value;
+        fn relations(&self, uri: &str) -> Value;
+        fn link(&self, from_uri: &str, to_uris: &[String], reason: &str) -> Value;
+        fn unlink(&self, from_uri: &str, to_uri: &str) -> Value;
+        fn export_ovpack(&self, uri: &str, to: &str, include_vectors: bool) -> PackFile;
+        fn backup_ovpack(&self, to: &str, include_vectors: bool, show_progress: bool) -> PackFile;
+        fn import_ovpack(
+            &self,
+            file_path: &str,
+            parent: &str,
+            on_conflict: Option<&str>,
+            vector_mode: Option<&str>,
+        ) -> Value;
+        fn restore_ovpack(
+            &self,
+            file_path: &str,
+            on_conflict: Option<&str>,
+            vector_mode: Option<&str>,
+            show_progress: bool,
+        ) -> Value;
+        fn admin_create_account(
+            &self,
+            account_id: &str,
+            admin_user_id: &str,
+            seed: Option<&str>,
+            user_config: Option<&Value>,
+        ) -> Value;
+        fn admin_list_accounts(&self) -> Value;
+        fn admin_delete_account(&self, account_id: &str) -> Value;
+        fn admin_register_user(
+            &self,
+            account_id: &str,
+            user_id: &str,
+            role: &str,
+            seed: Option<&str>,
+            user_config: Option<&Value>,
+        ) -> Value;
+        fn admin_list_users(
+            &self,
+            account_id: &str,
+            limit: u32,
+            name: Option<String>,
+            role: Option<String>,
+        ) -> Value;
+        fn admin_remove_user(&self, account_id: &str, user_id: &str) -> Value;
+        fn admin_set_role(&self, account_id: &str, user_id: &str, role: &str) -> Value;
+        fn admin_regenerate_key(&self, account_id: &str, user_id: &str, seed: Option<&str>) -> Value;
+        fn admin_migrate(&self, cleanup: bool) -> Value;
+        fn audit_logs(&self, page: u32, page_size: u32, api_types: &[&str], statuses: &[&str]) -> Value;
+        fn usage_tokens(&self, start_date: &str, end_date: &str) -> Value;
+        fn debug_vector_scroll(
+            &self,
+            limit: Option<u32>,
+            cursor: Option<String>,
+            uri_prefix: Option<String>,
+        ) -> (Vec<Value>, Option<String>);
+        fn debug_vector_count(&self, filter: Option<&Value>, uri_prefix: Option<String>) -> u64;
+        fn privacy_list_categories(&self) -> Value;
+        fn privacy_list_targets(&self, category: &str) -> Value;
+        fn privacy_get_current(&self, category: &str, target_key: &str) -> Value;
+        fn privacy_upsert(&self, category: &str, target_key: &str, body: &Value) -> Value;
+        fn privacy_list_versions(&self, category: &str, target_key: &str) -> Value;
+        fn privacy_get_version(&self, category: &str, target_key: &str, version: i32) -> Value;
+        fn privacy_activate(&self, category: &str, target_key: &str, version: i32) -> Value;
+        fn list_watches(&self, active_only: bool) -> Value;
+        fn get_watch_by_id(&self, task_id: &str) -> Value;
+        fn get_watch_by_uri(&self, to_uri: &str) -> Value;
+        fn patch_watch_by_id(&self, task_id: &str, body: &Value) -> Value;
+        fn patch_watch_by_uri(&self, to_uri: &str, body: &Value) -> Value;
+        fn delete_watch_by_id(&self, task_id: &str) -> Value;
+        fn delete_watch_by_uri(&self, to_uri: &str) -> Value;
+        fn trigger_watch_by_id(&self, task_id: &str) -> Value;
+        fn trigger_watch_by_uri(&self, to_uri: &str) -> Value;
+        fn snapshot_commit(&self, req: &SnapshotCommitReq) -> Value;
+        fn snapshot_restore(&self, req: &SnapshotRestoreReq) -> Value;
+        fn snapshot_log(&self, branch: &str, limit: u32, paths: Option<&[String]>) -> Value;
+        fn snapshot_diff(&self, path: &str, from_ref: Option<&str>, to_ref: &str) -> Value;
+        fn snapshot_ignore_get(&self) -> Value;
+        fn snapshot_ignore_set(&self, content: &str) -> Value;
+        fn snapshot_ignore_delete(&self) -> Value;
+        fn snapshot_show(&self, target_ref: &str, path: Option<&str>) -> SnapshotShowResult;
+    }
+}
+
+/// Blocking inside an async runtime would panic, so refuse instead.
+fn outside_runtime() -> Result<()> {
+    match Handle::try_current() {
+        Ok(_) => Err(Error::Client(
+            "the blocking client cannot be used inside an async runtime; use client::HttpClient"
+                .to_string(),
+        )),
+        Err(_) => Ok(()),
+    }
+}
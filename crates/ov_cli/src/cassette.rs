@@ -0,0 +1,440 @@
+//! `OV_CASSETTE` / `OV_RECORD`: record the client's requests and responses to
+//! a fixture file, and answer requests from it without a server.
+//!
+//! With `OV_CASSETTE=<file>` set, every request the client sends is answered
+//! from the cassette instead of the network, so a command can be run and
+//! tested hermetically. Adding `OV_RECORD=1` sends requests to the server as
+//! usual and writes each exchange to the cassette, replacing what it held.
+//!
+//! A cassette is JSON: `{"interactions": [{"request": ..., "response": ...}]}`.
+//! Requests keep their method, path, query, and body; responses their status,
+//! headers, and body. Bodies are stored as `{"json": ...}`, `{"text": ...}`, or
+//! `{"base64": ...}`, whichever fits, so fixtures can be read and edited by
+//! hand. Request headers, and with them credentials, are not recorded, nor is
+//! the server's address, so a cassette replays against any configured URL.
+//!
+//! Each recorded response is replayed once. A request is answered by the first
+//! unused interaction with the same method, path, query, and body, or failing
+//! that the first with the same method, path, and query; one with none left is
+//! an error.
+//!
+//! Programs using the crate as a library get the same with
+//! [`Cassette::replay`] or [`Cassette::record`] and `HttpClient::with_cassette`.
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use reqwest::{ResponseBuilderExt, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+
+pub const CASSETTE_ENV: &str = "OV_CASSETTE";
+pub const RECORD_ENV: &str = "OV_RECORD";
+
+/// Response headers that describe the original connection and transfer
+/// rather than the response, so are not replayed.
+const SKIPPED_HEADERS: &[&str] = &[
+    "connection",
+    "content-encoding",
+    "content-length",
+    "date",
+    "keep-alive",
+    "transfer-encoding",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Body {
+    Json(Value),
+    Text(String),
+    Base64(String),
+}
+
+impl Body {
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.is_empty() {
+            return None;
+        }
+        if let Ok(json) = serde_json::from_slice(bytes) {
+            return Some(Self::Json(json));
+        }
+        Some(match std::str::from_utf8(bytes) {
+            Ok(text) => Self::Text(text.to_string()),
+            Err(_) => Self::Base64(BASE64.encode(bytes)),
+        })
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(match self {
+            Self::Json(json) => serde_json::to_vec(json)?,
+            Self::Text(text) => text.clone().into_bytes(),
+            Self::Base64(encoded) => BASE64
+                .decode(encoded)
+                .map_err(|e| Error::Config(format!("Invalid base64 body in cassette: {e}")))?,
+        })
+    }
+}
+
+/// What a cassette keeps of a request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct RecordedRequest {
+    method: String,
+    path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    query: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<Body>,
+}
+
+impl RecordedRequest {
+    pub(crate) fn of(request: &reqwest::Request) -> Self {
+        Self {
+            method: request.method().to_string(),
+            path: request.url().path().to_string(),
+            query: request.url().query().map(str::to_string),
+            body: request
+                .body()
+                .and_then(reqwest::Body::as_bytes)
+                .and_then(Body::from_bytes),
+        }
+    }
+
+    fn same_endpoint(&self, other: &Self) -> bool {
+        self.method == other.method && self.path == other.path && self.query == other.query
+    }
+
+    fn describe(&self) -> String {
+        match &self.query {
+            Some(query) => format!("{} {}?{query}", self.method, self.path),
+            None => format!("{} {}", self.method, self.path),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    headers: Vec<(String, String)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<Body>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Recording {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Record,
+    Replay,
+}
+
+/// A fixture file the client records its exchanges to or replays them from.
+#[derive(Debug)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: Mode,
+    /// What has been recorded so far, or what is left to replay.
+    recording: Mutex<Recording>,
+}
+
+impl Cassette {
+    /// Record every exchange to `path`, starting it over now.
+    pub fn record(path: impl Into<PathBuf>) -> Result<Self> {
+        let cassette = Self {
+            path: path.into(),
+            mode: Mode::Record,
+            recording: Mutex::new(Recording::default()),
+        };
+        cassette.save(&Recording::default())?;
+        Ok(cassette)
+    }
+
+    /// Answer every request from the cassette at `path`.
+    pub fn replay(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            Error::Config(format!("Failed to read cassette {}: {e}", path.display()))
+        })?;
+        let recording = serde_json::from_str(&content)
+            .map_err(|e| Error::Config(format!("Invalid cassette {}: {e}", path.display())))?;
+        Ok(Self {
+            path,
+            mode: Mode::Replay,
+            recording: Mutex::new(recording),
+        })
+    }
+
+    pub(crate) fn is_replaying(&self) -> bool {
+        self.mode == Mode::Replay
+    }
+
+    /// The recorded response to `request`, used up.
+    pub(crate) fn replay_response(&self, request: &reqwest::Request) -> Result<reqwest::Response> {
+        let wanted = RecordedRequest::of(request);
+        let mut recording = self.recording.lock().unwrap_or_else(|e| e.into_inner());
+        let interactions = &mut recording.interactions;
+        let index = interactions
+            .iter()
+            .position(|interaction| interaction.request == wanted)
+            .or_else(|| {
+                interactions
+                    .iter()
+                    .position(|interaction| interaction.request.same_endpoint(&wanted))
+            })
+            .ok_or_else(|| {
+                Error::Client(format!(
+                    "Cassette {} has no response left for {}",
+                    self.path.display(),
+                    wanted.describe()
+                ))
+            })?;
+        let response = interactions.remove(index).response;
+        let body = match &response.body {
+            Some(body) => body.to_bytes()?,
+            None => Vec::new(),
+        };
+        build_response(
+            response.status,
+            &response.headers,
+            body,
+            request.url().clone(),
+        )
+    }
+
+    /// Add `request` and the server's `response` to the cassette, and hand the
+    /// response on with its body read.
+    pub(crate) async fn record_response(
+        &self,
+        request: RecordedRequest,
+        response: reqwest::Response,
+    ) -> Result<reqwest::Response> {
+        let status = response.status().as_u16();
+        let url = response.url().clone();
+        let headers: Vec<(String, String)> = response
+            .headers()
+            .iter()
+            .filter(|(name, _)| !SKIPPED_HEADERS.contains(&name.as_str()))
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| Error::from_reqwest("Failed to read response body", e))?;
+
+        let mut recording = self.recording.lock().unwrap_or_else(|e| e.into_inner());
+        recording.interactions.push(Interaction {
+            request,
+            response: RecordedResponse {
+                status,
+                headers: headers.clone(),
+                body: Body::from_bytes(&bytes),
+            },
+        });
+        self.save(&recording)?;
+        build_response(status, &headers, bytes.to_vec(), url)
+    }
+
+    fn save(&self, recording: &Recording) -> Result<()> {
+        let content = serde_json::to_string_pretty(recording)?;
+        std::fs::write(&self.path, content + "\n").map_err(|e| {
+            Error::Config(format!(
+                "Failed to write cassette {}: {e}",
+                self.path.display()
+            ))
+        })
+    }
+}
+
+fn build_response(
+    status: u16,
+    headers: &[(String, String)],
+    body: Vec<u8>,
+    url: Url,
+) -> Result<reqwest::Response> {
+    let mut builder = hyper::Response::builder().status(status).url(url);
+    for (name, value) in headers {
+        builder = builder.header(name, value);
+    }
+    let response = builder
+        .body(body)
+        .map_err(|e| Error::Config(format!("Invalid response in cassette: {e}")))?;
+    Ok(reqwest::Response::from(response))
+}
+
+static INSTALLED: OnceLock<Option<Arc<Cassette>>> = OnceLock::new();
+
+/// Record to or replay from the cassette `OV_CASSETTE` names, as `OV_RECORD`
+/// says, in every client the process builds.
+pub fn install_from_env() -> Result<()> {
+    let path = std::env::var_os(CASSETTE_ENV).filter(|path| !path.is_empty());
+    let record =
+        std::env::var_os(RECORD_ENV).is_some_and(|value| !value.is_empty() && value != "0");
+    let cassette = match (path, record) {
+        (None, false) => None,
+        (None, true) => {
+            return Err(Error::Config(format!(
+                "{RECORD_ENV}=1 needs {CASSETTE_ENV} set to the file to record to"
+            )));
+        }
+        (Some(path), true) => Some(Cassette::record(path)?),
+        (Some(path), false) => Some(Cassette::replay(path)?),
+    };
+    let _ = INSTALLED.set(cassette.map(Arc::new));
+    Ok(())
+}
+
+/// The cassette installed for this process, if any.
+pub fn installed() -> Option<Arc<Cassette>> {
+    INSTALLED.get().cloned().flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base_client::BaseClient;
+    use serde_json::json;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// A server that answers each of `bodies` on its own connection, in order.
+    async fn serve(bodies: Vec<&'static str>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            for body in bodies {
+                let (mut connection, _) = listener.accept().await.unwrap();
+                let mut request = vec![0; 4096];
+                let _ = connection.read(&mut request).await.unwrap();
+                connection
+                    .write_all(
+                        format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{body}",
+                            body.len()
+                        )
+                        .as_bytes(),
+                    )
+                    .await
+                    .unwrap();
+            }
+        });
+        format!("http://{address}")
+    }
+
+    fn client(url: &str, cassette: Cassette) -> BaseClient {
+        BaseClient::new(url, None, None, None, None, 5.0, false, None)
+            .with_cassette(Some(Arc::new(cassette)))
+    }
+
+    #[tokio::test]
+    async fn recorded_exchanges_replay_without_a_server() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cassette.json");
+        let url = serve(vec![
+            r#"{"status":"ok","result":{"healthy":true}}"#,
+            r#"{"status":"ok","result":{"hits":["a"]}}"#,
+            r#"{"status":"ok","result":{"hits":["b"]}}"#,
+        ])
+        .await;
+
+        let recorder = client(&url, Cassette::record(&path).unwrap());
+        let health: Value = recorder.get("/health", &[]).await.unwrap();
+        let first: Value = recorder.post("/search", &json!({"q": "a"})).await.unwrap();
+        let second: Value = recorder.post("/search", &json!({"q": "b"})).await.unwrap();
+        assert_eq!(health, json!({"healthy": true}));
+        assert_eq!(
+            (&first["hits"][0], &second["hits"][0]),
+            (&json!("a"), &json!("b"))
+        );
+
+        let recorded: Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let interaction = &recorded["interactions"][1];
+        assert_eq!(interaction["request"]["method"], "POST");
+        assert_eq!(interaction["request"]["body"], json!({"json": {"q": "a"}}));
+        assert_eq!(interaction["response"]["status"], 200);
+        assert_eq!(
+            interaction["response"]["headers"],
+            json!([["content-type", "application/json"]])
+        );
+
+        // Nothing listens here; matching bodies pick the recordings out of
+        // order, and each is used once.
+        let replayer = client("http://127.0.0.1:9", Cassette::replay(&path).unwrap());
+        let second: Value = replayer.post("/search", &json!({"q": "b"})).await.unwrap();
+        let first: Value = replayer.post("/search", &json!({"q": "a"})).await.unwrap();
+        let health: Value = replayer.get("/health", &[]).await.unwrap();
+        assert_eq!(
+            (&first["hits"][0], &second["hits"][0]),
+            (&json!("a"), &json!("b"))
+        );
+        assert_eq!(health, json!({"healthy": true}));
+
+        let error = replayer.get::<Value>("/health", &[]).await.unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("no response left for GET /health")
+        );
+    }
+
+    #[test]
+    fn replayed_bodies_fall_back_to_the_endpoint_and_keep_their_form() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cassette.json");
+        std::fs::write(
+            &path,
+            json!({"interactions": [
+                {
+                    "request": {"method": "POST", "path": "/upload", "body": {"text": "old"}},
+                    "response": {"status": 503, "body": {"text": "busy"}}
+                },
+                {
+                    "request": {"method": "GET", "path": "/blob", "query": "id=1"},
+                    "response": {"status": 200, "body": {"base64": "AP8="}}
+                }
+            ]})
+            .to_string(),
+        )
+        .unwrap();
+        let cassette = Cassette::replay(&path).unwrap();
+        let http = reqwest::Client::new();
+
+        let upload = http
+            .post("http://example.invalid/upload")
+            .body("new")
+            .build()
+            .unwrap();
+        let response = cassette.replay_response(&upload).unwrap();
+        assert_eq!(response.status(), 503);
+        assert_eq!(response.url().path(), "/upload");
+
+        let blob = http
+            .get("http://example.invalid/blob?id=2")
+            .build()
+            .unwrap();
+        assert!(cassette.replay_response(&blob).is_err());
+        let blob = http
+            .get("http://example.invalid/blob?id=1")
+            .build()
+            .unwrap();
+        let response = cassette.replay_response(&blob).unwrap();
+        let bytes = futures::executor::block_on(response.bytes()).unwrap();
+        assert_eq!(&bytes[..], &[0x00, 0xff]);
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(matches!(Cassette::replay(&path), Err(Error::Config(_))));
+    }
+}
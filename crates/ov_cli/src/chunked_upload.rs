@@ -0,0 +1,375 @@
+//! Resumable chunked uploads for large local files.
+//!
+//! Files of at least [`CHUNKED_THRESHOLD`] bytes, and every upload made with
+//! `--resume`, go through `/api/v1/resources/uploads`: the file's SHA-256 is
+//! declared up front, chunks are appended at the offset the server reports, and
+//! the server checks the assembled file before issuing a `temp_file_id`. A
+//! manifest under `~/.openviking/uploads/` remembers the server-side upload so
+//! `--resume` continues where a failed or interrupted transfer stopped.
+
+use std::io::{Read, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use indicatif::ProgressBar;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use crate::base_client::{BaseClient, TimeoutConfig};
+use crate::config::default_config_path;
+use crate::error::{ApiErrorKind, Error, Result};
+use crate::theme;
+
+/// Files at least this large are uploaded in chunks even without `--resume`.
+pub const CHUNKED_THRESHOLD: u64 = 64 * 1024 * 1024;
+const CHUNK_SIZE: usize = 8 * 1024 * 1024;
+const CHUNK_ATTEMPTS: u32 = 3;
+/// Matches how long the server keeps a partial upload that stopped growing.
+const MANIFEST_MAX_AGE: Duration = Duration::from_secs(24 * 3600);
+
+/// What `--resume` needs to find the server-side upload of a local file again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Manifest {
+    server: String,
+    path: PathBuf,
+    size: u64,
+    modified: u64,
+    sha256: String,
+    upload_id: String,
+}
+
+impl Manifest {
+    /// The file is unchanged since the manifest was written.
+    fn matches(&self, server: &str, path: &Path, size: u64, modified: u64) -> bool {
+        self.server == server && self.path == path && self.size == size && self.modified == modified
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct UploadStatus {
+    upload_id: String,
+    received: u64,
+}
+
+pub fn should_chunk(size: u64, resume: bool) -> bool {
+    resume || size >= CHUNKED_THRESHOLD
+}
+
+/// Upload `file_path` in chunks and return its `temp_file_id`, or `None` when
+/// the server predates chunked uploads and the caller should send it whole.
+pub async fn upload(
+    client: &BaseClient,
+    file_path: &Path,
+    upload_mode: Option<&str>,
+    resume: bool,
+    progress: Option<&ProgressBar>,
+) -> Result<Option<String>> {
+    let path = file_path.canonicalize()?;
+    let metadata = std::fs::metadata(&path)?;
+    let size = metadata.len();
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |age| age.as_secs());
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("upload.tmp")
+        .to_string();
+    let manifest_dir = manifest_dir()?;
+    let manifest_path = manifest_path(&manifest_dir, &client.base_url, &path);
+
+    let mut resumed = None;
+    if resume {
+        match read_manifest(&manifest_path)
+            .filter(|manifest| manifest.matches(&client.base_url, &path, size, modified))
+        {
+            Some(manifest) => match upload_status(client, &manifest.upload_id).await {
+                Ok(status) if status.received <= size => resumed = Some((manifest, status)),
+                Ok(_) => {}
+                Err(e) if e.api_kind() == Some(ApiErrorKind::NotFound) => {}
+                Err(e) => return Err(e),
+            },
+            None => eprintln!(
+                "{}",
+                theme::muted(format!(
+                    "No interrupted upload of {file_name} to resume; starting from the beginning."
+                ))
+            ),
+        }
+    }
+
+    let (manifest, mut offset) = match resumed {
+        Some((manifest, status)) => {
+            eprintln!(
+                "{}",
+                theme::muted(format!(
+                    "Resuming {file_name} at {} of {}.",
+                    format_mib(status.received),
+                    format_mib(size)
+                ))
+            );
+            (manifest, status.received)
+        }
+        None => {
+            let hash_path = path.clone();
+            let sha256 = tokio::task::spawn_blocking(move || sha256_file(&hash_path))
+                .await
+                .map_err(|e| Error::Client(format!("Failed to hash {file_name}: {e}")))??;
+            let started: Result<UploadStatus> = client
+                .post(
+                    "/api/v1/resources/uploads",
+                    &json!({ "filename": file_name, "size": size, "sha256": sha256 }),
+                )
+                .await;
+            let status = match started {
+                Ok(status) => status,
+                Err(Error::Api {
+                    status: Some(404 | 405),
+                    ..
+                }) if !resume => return Ok(None),
+                Err(Error::Api {
+                    status: Some(404 | 405),
+                    ..
+                }) => {
+                    return Err(Error::Client(
+                        "The server does not support resumable uploads; retry without --resume"
+                            .to_string(),
+                    ));
+                }
+                Err(e) => return Err(e),
+            };
+            let manifest = Manifest {
+                server: client.base_url.clone(),
+                path: path.clone(),
+                size,
+                modified,
+                sha256,
+                upload_id: status.upload_id,
+            };
+            prune_manifests(&manifest_dir);
+            write_manifest(&manifest_dir, &manifest_path, &manifest)?;
+            (manifest, 0)
+        }
+    };
+
+    let chunk_timeout = TimeoutConfig::for_upload().calculate_for_size(CHUNK_SIZE as u64);
+    let http = client.create_client_with_connect_timeout(Duration::from_secs(30), chunk_timeout)?;
+    let chunk_url = format!(
+        "{}/api/v1/resources/uploads/{}",
+        client.base_url, manifest.upload_id
+    );
+    let mut headers = client.build_headers();
+    headers.insert(
+        reqwest::header::CONTENT_TYPE,
+        reqwest::header::HeaderValue::from_static("application/octet-stream"),
+    );
+    let mut file = tokio::fs::File::open(&path).await?;
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut failures = 0;
+    while offset < size {
+        if let Some(progress) = progress {
+            progress.set_message(format!(
+                "Uploading {file_name} ({} of {})...",
+                format_mib(offset),
+                format_mib(size)
+            ));
+        }
+        file.seek(SeekFrom::Start(offset)).await?;
+        let want = CHUNK_SIZE.min((size - offset) as usize);
+        file.read_exact(&mut buffer[..want]).await?;
+        let request = http
+            .put(&chunk_url)
+            .headers(headers.clone())
+            .query(&[("offset", offset)])
+            .body(buffer[..want].to_vec());
+        let sent = match client.send_request(request, "Chunk upload failed").await {
+            Ok(response) => client.handle_response::<UploadStatus>(response).await,
+            Err(e) => Err(e),
+        };
+        match sent {
+            Ok(status) => {
+                offset = status.received;
+                failures = 0;
+            }
+            // A lost response or a concurrent writer leaves the server ahead
+            // of or behind us; ask where it is and carry on from there.
+            Err(e) if is_retryable(&e) && failures + 1 < CHUNK_ATTEMPTS => {
+                failures += 1;
+                tracing::warn!(error = %e, offset, "chunk upload failed; resyncing");
+                if let Ok(status) = upload_status(client, &manifest.upload_id).await {
+                    offset = status.received;
+                }
+            }
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    theme::muted(format!(
+                        "Upload stopped at {} of {}; rerun with --resume to continue.",
+                        format_mib(offset),
+                        format_mib(size)
+                    ))
+                );
+                return Err(e);
+            }
+        }
+    }
+
+    let completed: Result<Value> = client
+        .post(
+            &format!("/api/v1/resources/uploads/{}/complete", manifest.upload_id),
+            &json!({ "upload_mode": upload_mode.unwrap_or("local") }),
+        )
+        .await;
+    match completed {
+        Ok(result) => {
+            let _ = std::fs::remove_file(&manifest_path);
+            result
+                .get("temp_file_id")
+                .and_then(Value::as_str)
+                .map(|id| Some(id.to_string()))
+                .ok_or_else(|| Error::Parse("Missing temp_file_id in response".to_string()))
+        }
+        // The server discards an upload whose checksum does not match.
+        Err(e) if e.api_kind() == Some(ApiErrorKind::InvalidArgument) => {
+            let _ = std::fs::remove_file(&manifest_path);
+            Err(e)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+async fn upload_status(client: &BaseClient, upload_id: &str) -> Result<UploadStatus> {
+    client
+        .get(&format!("/api/v1/resources/uploads/{upload_id}"), &[])
+        .await
+}
+
+fn is_retryable(error: &Error) -> bool {
+    match error {
+        Error::Network(_) | Error::Timeout(_) => true,
+        Error::Api { .. } => matches!(
+            error.api_kind(),
+            Some(ApiErrorKind::Conflict | ApiErrorKind::Unavailable)
+        ),
+        _ => false,
+    }
+}
+
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn manifest_dir() -> Result<PathBuf> {
+    let config_path = default_config_path()?;
+    let home = config_path.parent().unwrap_or(Path::new("."));
+    Ok(home.join("uploads"))
+}
+
+/// One manifest per server and local file.
+fn manifest_path(dir: &Path, server: &str, path: &Path) -> PathBuf {
+    let key = Sha256::digest(format!("{server}\n{}", path.display()).as_bytes());
+    dir.join(format!("{}.json", &hex::encode(key)[..32]))
+}
+
+fn read_manifest(path: &Path) -> Option<Manifest> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn write_manifest(dir: &Path, path: &Path, manifest: &Manifest) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(path, serde_json::to_string_pretty(manifest)?)?;
+    Ok(())
+}
+
+/// Forget uploads the server has already expired.
+fn prune_manifests(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let now = SystemTime::now();
+    for entry in entries.flatten() {
+        let expired = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > MANIFEST_MAX_AGE);
+        if expired {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+}
+
+fn format_mib(bytes: u64) -> String {
+    format!("{:.1} MiB", bytes as f64 / 1024.0 / 1024.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_files_are_sent_whole_unless_resuming() {
+        assert!(!should_chunk(1024, false));
+        assert!(should_chunk(1024, true));
+        assert!(should_chunk(CHUNKED_THRESHOLD, false));
+    }
+
+    #[test]
+    fn manifest_is_keyed_by_server_and_file() {
+        let dir = Path::new("/tmp/uploads");
+        let file = Path::new("/data/video.mp4");
+        let local = manifest_path(dir, "http://localhost:1933", file);
+
+        assert_eq!(local, manifest_path(dir, "http://localhost:1933", file));
+        assert_ne!(local, manifest_path(dir, "https://ov.example.com", file));
+        assert_ne!(
+            local,
+            manifest_path(dir, "http://localhost:1933", Path::new("/data/other.mp4"))
+        );
+    }
+
+    #[test]
+    fn manifest_only_matches_an_unchanged_file() {
+        let manifest = Manifest {
+            server: "http://localhost:1933".to_string(),
+            path: PathBuf::from("/data/video.mp4"),
+            size: 10,
+            modified: 100,
+            sha256: "ab".repeat(32),
+            upload_id: "0".repeat(32),
+        };
+        let path = Path::new("/data/video.mp4");
+
+        assert!(manifest.matches("http://localhost:1933", path, 10, 100));
+        assert!(!manifest.matches("http://localhost:1933", path, 11, 100));
+        assert!(!manifest.matches("http://localhost:1933", path, 10, 101));
+    }
+
+    #[test]
+    fn sha256_file_hashes_contents() {
+        let dir = tempfile::tempdir().expect("tempdir should be created");
+        let path = dir.path().join("a.txt");
+        std::fs::write(&path, b"abc").unwrap();
+
+        assert_eq!(
+            sha256_file(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -2,12 +2,16 @@ use serde::de::DeserializeOwned;
 use serde_json::{Map, Value};
 use std::env;
 use std::path::Path;
+use std::sync::Arc;
 
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64_STANDARD};
 
 pub use crate::base_client::{BaseClient, FileUploader, TimeoutConfig};
 
 use crate::error::{Error, Result};
+use crate::output::NdjsonStream;
+use crate::pack_compression::PackCompression;
+use crate::pack_crypto::PackEncryption;
 
 /// Drop null-valued keys (and an empty `args` object) from a request body before
 /// sending it. Older, stricter servers use `extra="forbid"` and reject any field
@@ -97,12 +101,119 @@ pub enum SnapshotShowResult {
     },
 }
 
+/// What `add_resource` does with a file the server already holds at `existing`.
+#[derive(Debug, PartialEq)]
+enum Duplicate {
+    /// No target was given, or the copy is already under it.
+    Reuse,
+    /// Link the existing resource from the requested parent.
+    LinkFrom(String),
+    /// `--to` names a new location, so import another copy there.
+    Upload,
+}
+
+fn plan_duplicate(existing: &str, to: Option<&str>, parent: Option<&str>) -> Duplicate {
+    let within = |target: &str| {
+        let target = target.trim_end_matches('/');
+        existing == target
+            || existing
+                .strip_prefix(target)
+                .is_some_and(|rest| rest.starts_with('/'))
+    };
+    match (to, parent) {
+        (Some(to), _) if within(to) => Duplicate::Reuse,
+        (Some(_), _) => Duplicate::Upload,
+        (None, Some(parent)) if within(parent) => Duplicate::Reuse,
+        (None, Some(parent)) => Duplicate::LinkFrom(parent.to_string()),
+        (None, None) => Duplicate::Reuse,
+    }
+}
+
+/// An .ovpack written by `export_ovpack` or `backup_ovpack`.
+#[derive(Debug, Clone)]
+pub struct PackFile {
+    pub path: String,
+    /// Bytes on disk, after compression and encryption.
+    pub size: u64,
+    /// Uncompressed size of the pack's members, if the pack could be read.
+    pub content_size: Option<u64>,
+    pub compression: &'static str,
+}
+
+/// Ranking controls for `find` and `search`. Unset fields stay out of the
+/// request, so the server keeps its defaults.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchScoring {
+    /// `vector`, `keyword` or `hybrid`
+    pub mode: Option<String>,
+    /// Force the server's rerank model on or off
+    pub rerank: Option<bool>,
+    pub vector_weight: Option<f64>,
+    pub keyword_weight: Option<f64>,
+    /// Return each strategy's score with every result
+    pub include_scores: bool,
+    /// Also return provenance and telemetry for `ov ... --explain`
+    pub explain: bool,
+}
+
+impl SearchScoring {
+    fn add_to(&self, body: &mut Value) {
+        body["mode"] = serde_json::json!(self.mode);
+        body["rerank"] = serde_json::json!(self.rerank);
+        body["vector_weight"] = serde_json::json!(self.vector_weight);
+        body["keyword_weight"] = serde_json::json!(self.keyword_weight);
+        let include_scores = self.include_scores || self.explain;
+        body["include_scores"] = serde_json::json!(include_scores.then_some(true));
+        if self.explain {
+            body["include_provenance"] = serde_json::json!(true);
+            body["telemetry"] = serde_json::json!(true);
+        }
+    }
+}
+
+/// Read a pack response body, showing bytes received on `pb`.
+async fn download_with_progress(
+    mut response: reqwest::Response,
+    pb: &indicatif::ProgressBar,
+) -> Result<bytes::Bytes> {
+    let template = match response.content_length() {
+        Some(total) => {
+            pb.set_length(total);
+            "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta}) {msg}"
+        }
+        None => "{spinner:.green} [{elapsed_precise}] {bytes} {msg}",
+    };
+    pb.set_style(
+        indicatif::ProgressStyle::default_bar()
+            .template(template)
+            .unwrap_or_else(|_| indicatif::ProgressStyle::default_bar())
+            .progress_chars("#>-"),
+    );
+    pb.set_message("Downloading");
+    let mut body = Vec::with_capacity(response.content_length().unwrap_or(0) as usize);
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| Error::from_reqwest("Failed to read response bytes", e))?
+    {
+        body.extend_from_slice(&chunk);
+        pb.inc(chunk.len() as u64);
+    }
+    Ok(body.into())
+}
+
 // ============ HttpClient ============
 
 /// High-level HTTP client for OpenViking API
 #[derive(Clone)]
 pub struct HttpClient {
     base: BaseClient,
+    resume_uploads: bool,
+    dedup_uploads: bool,
+    convert_notebooks: bool,
+    content_type: Option<String>,
+    pack_encryption: Option<Arc<PackEncryption>>,
+    pack_compression: Option<PackCompression>,
 }
 
 impl HttpClient {
@@ -127,6 +238,12 @@ impl HttpClient {
                 profile_enabled,
                 extra_headers,
             ),
+            resume_uploads: false,
+            dedup_uploads: false,
+            convert_notebooks: false,
+            content_type: None,
+            pack_encryption: None,
+            pack_compression: None,
         }
     }
 
@@ -135,6 +252,83 @@ impl HttpClient {
         self
     }
 
+    pub fn with_proxy(mut self, proxy: Option<String>) -> Self {
+        self.base = self.base.with_proxy(proxy);
+        self
+    }
+
+    pub fn with_tls(mut self, tls: crate::base_client::TlsFiles) -> Self {
+        self.base = self.base.with_tls(tls);
+        self
+    }
+
+    pub fn with_connect_timeout(mut self, connect_timeout: Option<std::time::Duration>) -> Self {
+        self.base = self.base.with_connect_timeout(connect_timeout);
+        self
+    }
+
+    pub fn with_daemon(mut self, socket: Option<std::path::PathBuf>) -> Self {
+        self.base = self.base.with_daemon(socket);
+        self
+    }
+
+    /// Record to or replay from `cassette`; see [`crate::cassette`].
+    pub fn with_cassette(
+        mut self,
+        cassette: Option<std::sync::Arc<crate::cassette::Cassette>>,
+    ) -> Self {
+        self.base = self.base.with_cassette(cassette);
+        self
+    }
+
+    /// Print the records of JSON responses through `stream` as they arrive;
+    /// see [`NdjsonStream`].
+    pub fn with_ndjson_stream(mut self, stream: Option<Arc<NdjsonStream>>) -> Self {
+        self.base = self.base.with_ndjson_stream(stream);
+        self
+    }
+
+    /// Continue interrupted chunked uploads instead of starting them over.
+    pub fn with_resumable_uploads(mut self, resume: bool) -> Self {
+        self.resume_uploads = resume;
+        self
+    }
+
+    /// Ask the server for an identical file before uploading one in `add_resource`.
+    pub fn with_upload_dedup(mut self, dedup: bool) -> Self {
+        self.dedup_uploads = dedup;
+        self
+    }
+
+    /// Content type of the single file `add_resource` uploads, instead of the
+    /// one detected from its bytes; see [`crate::file_type`].
+    pub fn with_content_type(mut self, content_type: Option<String>) -> Self {
+        self.content_type = content_type;
+        self
+    }
+
+    /// Upload Jupyter notebooks as Markdown in `add_resource`; see [`crate::notebook`].
+    pub fn with_notebook_conversion(mut self, convert: bool) -> Self {
+        self.convert_notebooks = convert;
+        self
+    }
+
+    /// Encrypt exported and backed-up packs before they are written to disk.
+    pub fn with_pack_encryption(mut self, encryption: Option<PackEncryption>) -> Self {
+        self.pack_encryption = encryption.map(Arc::new);
+        self
+    }
+
+    /// Rewrite exported and backed-up packs with another member compression.
+    pub fn with_pack_compression(mut self, compression: Option<PackCompression>) -> Self {
+        self.pack_compression = compression;
+        self
+    }
+
+    pub fn server_url(&self) -> &str {
+        &self.base.server_url
+    }
+
     pub fn user_id(&self) -> Option<&str> {
         self.base.user_id()
     }
@@ -221,10 +415,23 @@ impl HttpClient {
         self.base.post_with_query(path, body, params).await
     }
 
+    pub async fn request<T: DeserializeOwned + 'static>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        params: &[(String, String)],
+        body: Option<&Value>,
+    ) -> Result<T> {
+        self.base.request(method, path, params, body).await
+    }
+
     // ============ File Helper Methods ============
 
     fn create_uploader(&self) -> FileUploader<'_> {
-        FileUploader::new(&self.base).with_upload_mode(self.upload_mode())
+        FileUploader::new(&self.base)
+            .with_upload_mode(self.upload_mode())
+            .with_resume(self.resume_uploads)
+            .with_notebook_conversion(self.convert_notebooks)
     }
 
     fn zip_directory(
@@ -299,7 +506,8 @@ impl HttpClient {
         wait: bool,
         timeout: Option<f64>,
     ) -> Result<serde_json::Value> {
-        let body = Self::build_write_body(uri, content, mode, wait, timeout);
+        let content = crate::redact::text(uri, content);
+        let body = Self::build_write_body(uri, &content, mode, wait, timeout);
         self.post("/api/v1/content/write", &body).await
     }
 
@@ -319,6 +527,22 @@ impl HttpClient {
         self.post("/api/v1/fs/attrs/set_tags", &body).await
     }
 
+    pub async fn set_metadata(
+        &self,
+        uri: &str,
+        values: Map<String, Value>,
+        remove: Vec<String>,
+    ) -> Result<serde_json::Value> {
+        let mut values = Value::Object(values);
+        crate::redact::value(uri, &mut values);
+        let body = serde_json::json!({
+            "uri": uri,
+            "values": values,
+            "remove": remove,
+        });
+        self.post("/api/v1/fs/attrs/set_metadata", &body).await
+    }
+
     fn build_write_body(
         uri: &str,
         content: &str,
@@ -511,6 +735,7 @@ impl HttpClient {
         level: Option<Vec<i32>>,
         context_type: Option<Vec<String>>,
         tags: Option<Vec<String>>,
+        scoring: &SearchScoring,
     ) -> Result<serde_json::Value> {
         let image_url = normalize_image_input(image)?;
         let mut body = serde_json::json!({
@@ -526,6 +751,7 @@ impl HttpClient {
             "context_type": context_type,
             "tags": tags,
         });
+        scoring.add_to(&mut body);
         compact_request_body(&mut body);
         self.post("/api/v1/search/find", &body).await
     }
@@ -544,6 +770,7 @@ impl HttpClient {
         level: Option<Vec<i32>>,
         context_type: Option<Vec<String>>,
         tags: Option<Vec<String>>,
+        scoring: &SearchScoring,
     ) -> Result<serde_json::Value> {
         let image_url = normalize_image_input(image)?;
         let mut body = serde_json::json!({
@@ -560,6 +787,7 @@ impl HttpClient {
             "context_type": context_type,
             "tags": tags,
         });
+        scoring.add_to(&mut body);
         compact_request_body(&mut body);
         self.post("/api/v1/search/search", &body).await
     }
@@ -621,6 +849,27 @@ impl HttpClient {
         verbose: bool,
     ) -> Result<serde_json::Value> {
         let path_obj = Path::new(path);
+        // Held until the upload finishes; the Markdown is deleted on drop.
+        let notebook = if self.convert_notebooks
+            && path_obj.is_file()
+            && crate::notebook::is_notebook(path_obj)
+        {
+            Some(crate::notebook::convert_file(path_obj)?)
+        } else {
+            None
+        };
+        let path_obj = notebook
+            .as_ref()
+            .map_or(path_obj, |converted| converted.path.as_path());
+        // Likewise the file as the ingest rewrites left it.
+        let filtered = if path_obj.is_file() {
+            crate::ingest::filter_file(path_obj)?
+        } else {
+            None
+        };
+        let path_obj = filtered
+            .as_ref()
+            .map_or(path_obj, |filtered| filtered.path.as_path());
         let args = Value::Object(resource_args.unwrap_or_default());
 
         // Determine effective parent and create_parent flag.
@@ -691,15 +940,41 @@ impl HttpClient {
                     .file_name()
                     .and_then(|n| n.to_str())
                     .map(|s| s.to_string());
+                let mut sha256 = None;
+                if self.dedup_uploads && watch_interval <= 0.0 {
+                    let digest = crate::chunked_upload::sha256_file(path_obj)?;
+                    // `None` means the server predates content lookup: upload
+                    // without declaring the hash, which it would reject.
+                    if let Some(found) = self.find_by_content(&digest).await {
+                        if let Some(existing) = found["root_uri"].as_str() {
+                            let plan = plan_duplicate(
+                                existing,
+                                to.as_deref(),
+                                effective_parent.as_deref(),
+                            );
+                            if plan != Duplicate::Upload {
+                                return self.reuse_duplicate(existing, plan, reason).await;
+                            }
+                        }
+                        sha256 = Some(digest);
+                    }
+                }
+                let content_type = match &self.content_type {
+                    Some(content_type) => content_type.clone(),
+                    None => crate::file_type::detect(path_obj)?.mime,
+                };
+                let uploader = self.create_uploader().with_content_type(Some(content_type));
                 let temp_file_id = if show_progress {
-                    self.upload_temp_file_with_progress(path_obj, verbose)
+                    uploader
+                        .upload_temp_file_with_progress(path_obj, verbose)
                         .await?
                 } else {
-                    self.upload_temp_file(path_obj).await?
+                    uploader.upload_temp_file(path_obj).await?
                 };
 
                 let body = build_body(serde_json::json!({
                     "temp_file_id": temp_file_id,
+                    "sha256": sha256,
                     "source_name": source_name,
                     "to": to,
                     "parent": effective_parent,
@@ -742,6 +1017,11 @@ impl HttpClient {
                 self.post("/api/v1/resources", &body).await
             }
         } else {
+            if crate::redact::active() {
+                return Err(Error::Client(format!(
+                    "--redact applies to local files; the server fetches {path} itself"
+                )));
+            }
             let body = build_body(serde_json::json!({
                 "path": path,
                 "to": to,
@@ -763,6 +1043,30 @@ impl HttpClient {
         }
     }
 
+    /// Look up a resource imported from a file with this SHA-256. Returns
+    /// `None` when the server can't answer, so callers fall back to uploading.
+    async fn find_by_content(&self, sha256: &str) -> Option<Value> {
+        let path = format!("/api/v1/resources/by-hash/{sha256}");
+        self.get::<Value>(&path, &[]).await.ok()
+    }
+
+    async fn reuse_duplicate(
+        &self,
+        existing: &str,
+        plan: Duplicate,
+        reason: &str,
+    ) -> Result<serde_json::Value> {
+        let mut result = serde_json::json!({
+            "root_uri": existing,
+            "deduplicated": true,
+        });
+        if let Duplicate::LinkFrom(parent) = plan {
+            self.link(&parent, &[existing.to_string()], reason).await?;
+            result["linked_from"] = Value::String(parent);
+        }
+        Ok(result)
+    }
+
     pub async fn add_skill(
         &self,
         data: &str,
@@ -1079,6 +1383,7 @@ impl HttpClient {
         &self,
         task_type: Option<&str>,
         status: Option<&str>,
+        limit: Option<u32>,
     ) -> Result<serde_json::Value> {
         let mut params: Vec<(String, String)> = Vec::new();
         if let Some(t) = task_type {
@@ -1087,9 +1392,25 @@ impl HttpClient {
         if let Some(s) = status {
             params.push(("status".to_string(), s.to_string()));
         }
+        if let Some(limit) = limit {
+            params.push(("limit".to_string(), limit.to_string()));
+        }
         self.get("/api/v1/tasks", &params).await
     }
 
+    /// The server's readiness report, including the checks that failed.
+    pub async fn readiness(&self) -> Result<serde_json::Value> {
+        self.base.get_probe("/ready").await
+    }
+
+    /// Queue counts and per-URI progress, optionally for one URI subtree.
+    pub async fn queue_status(&self, uri: Option<&str>) -> Result<serde_json::Value> {
+        let params: Vec<(String, String)> = uri
+            .map(|uri| vec![("uri".to_string(), uri.to_string())])
+            .unwrap_or_default();
+        self.get("/api/v1/observer/queue/items", &params).await
+    }
+
     // ============ Relation Methods ============
 
     pub async fn relations(&self, uri: &str) -> Result<serde_json::Value> {
@@ -1127,7 +1448,14 @@ impl HttpClient {
         body: serde_json::Value,
         to: &str,
         default_name: &str,
-    ) -> Result<String> {
+        show_progress: bool,
+    ) -> Result<PackFile> {
+        let progress = show_progress.then(|| {
+            let pb = indicatif::ProgressBar::new_spinner();
+            pb.set_message(format!("Building {default_name}.ovpack on the server..."));
+            pb.enable_steady_tick(std::time::Duration::from_millis(100));
+            pb
+        });
         let url = format!("{}{}", self.base.base_url, endpoint);
         let request = self
             .base
@@ -1151,10 +1479,13 @@ impl HttpClient {
             return Err(crate::base_client::api_error_from_body(&bytes, status));
         }
 
-        let bytes = response
-            .bytes()
-            .await
-            .map_err(|e| Error::from_reqwest("Failed to read response bytes", e))?;
+        let bytes = match &progress {
+            Some(pb) => download_with_progress(response, pb).await?,
+            None => response
+                .bytes()
+                .await
+                .map_err(|e| Error::from_reqwest("Failed to read response bytes", e))?,
+        };
 
         let to_path = Path::new(to);
         let final_path = if to_path.is_dir() {
@@ -1169,9 +1500,31 @@ impl HttpClient {
             std::fs::create_dir_all(parent)?;
         }
 
-        std::fs::write(&final_path, bytes)?;
+        let content_size = crate::pack_compression::content_size(&bytes).ok();
+        if let Some(pb) = &progress {
+            pb.set_message(format!("Writing {}...", final_path.display()));
+        }
+        let bytes = match &self.pack_compression {
+            Some(compression) => compression.recompress(&bytes)?.into(),
+            None => bytes,
+        };
+        let bytes = match &self.pack_encryption {
+            Some(encryption) => encryption.encrypt(&bytes)?.into(),
+            None => bytes,
+        };
+        std::fs::write(&final_path, &bytes)?;
+        if let Some(pb) = progress {
+            pb.finish_and_clear();
+        }
 
-        Ok(final_path.to_string_lossy().to_string())
+        Ok(PackFile {
+            path: final_path.to_string_lossy().to_string(),
+            size: bytes.len() as u64,
+            content_size,
+            compression: self
+                .pack_compression
+                .map_or("deflate", |compression| compression.name()),
+        })
     }
 
     pub async fn export_ovpack(
@@ -1179,7 +1532,7 @@ impl HttpClient {
         uri: &str,
         to: &str,
         include_vectors: bool,
-    ) -> Result<String> {
+    ) -> Result<PackFile> {
         let body = serde_json::json!({
             "uri": uri,
             "include_vectors": include_vectors,
@@ -1189,16 +1542,22 @@ impl HttpClient {
             .split('/')
             .last()
             .unwrap_or("export");
-        self.download_pack("/api/v1/pack/export", body, to, base_name)
+        self.download_pack("/api/v1/pack/export", body, to, base_name, false)
             .await
     }
 
-    pub async fn backup_ovpack(&self, to: &str, include_vectors: bool) -> Result<String> {
+    pub async fn backup_ovpack(
+        &self,
+        to: &str,
+        include_vectors: bool,
+        show_progress: bool,
+    ) -> Result<PackFile> {
         self.download_pack(
             "/api/v1/pack/backup",
             serde_json::json!({"include_vectors": include_vectors}),
             to,
             "openviking-backup",
+            show_progress,
         )
         .await
     }
@@ -1238,6 +1597,7 @@ impl HttpClient {
         file_path: &str,
         on_conflict: Option<&str>,
         vector_mode: Option<&str>,
+        show_progress: bool,
     ) -> Result<serde_json::Value> {
         let file_path_obj = Path::new(file_path);
 
@@ -1251,14 +1611,29 @@ impl HttpClient {
             return Err(Error::Client(format!("Path is not a file: {}", file_path)));
         }
 
-        let temp_file_id = self.upload_temp_file(file_path_obj).await?;
+        let temp_file_id = if show_progress {
+            self.upload_temp_file_with_progress(file_path_obj, false)
+                .await?
+        } else {
+            self.upload_temp_file(file_path_obj).await?
+        };
         let conflict_policy = on_conflict.unwrap_or("fail");
         let body = serde_json::json!({
             "temp_file_id": temp_file_id,
             "on_conflict": conflict_policy,
             "vector_mode": vector_mode.unwrap_or("auto"),
         });
-        self.post("/api/v1/pack/restore", &body).await
+        let progress = show_progress.then(|| {
+            let pb = indicatif::ProgressBar::new_spinner();
+            pb.set_message("Restoring backup on the server...");
+            pb.enable_steady_tick(std::time::Duration::from_millis(100));
+            pb
+        });
+        let result = self.post("/api/v1/pack/restore", &body).await;
+        if let Some(pb) = progress {
+            pb.finish_and_clear();
+        }
+        result
     }
 
     // ============ Admin Methods ============
@@ -1382,6 +1757,39 @@ impl HttpClient {
         .await
     }
 
+    /// One page of the request audit log, newest first. `api_types` and
+    /// `statuses` (`success`, `error`, `4xx`, ...) narrow it on the server; an
+    /// empty slice matches everything.
+    pub async fn audit_logs(
+        &self,
+        page: u32,
+        page_size: u32,
+        api_types: &[&str],
+        statuses: &[&str],
+    ) -> Result<Value> {
+        let mut params = vec![
+            ("page".to_string(), page.to_string()),
+            ("page_size".to_string(), page_size.to_string()),
+        ];
+        if !api_types.is_empty() {
+            params.push(("api_type".to_string(), api_types.join(",")));
+        }
+        if !statuses.is_empty() {
+            params.push(("status".to_string(), statuses.join(",")));
+        }
+        self.get("/api/v1/console/audit", &params).await
+    }
+
+    /// Daily token usage (`vlm_input`, `vlm_output`, `embedding_input`) for
+    /// the dates `start_date..=end_date`, in `YYYY-MM-DD` form.
+    pub async fn usage_tokens(&self, start_date: &str, end_date: &str) -> Result<Value> {
+        let params = vec![
+            ("start_date".to_string(), start_date.to_string()),
+            ("end_date".to_string(), end_date.to_string()),
+        ];
+        self.get("/api/v1/console/tokens", &params).await
+    }
+
     // ============ Debug Vector Methods ============
 
     /// Get paginated vector records
@@ -1745,6 +2153,30 @@ mod tests {
         assert!(!obj.contains_key("args"));
     }
 
+    #[test]
+    fn plan_duplicate_reuses_links_or_uploads_by_target() {
+        use super::{Duplicate, plan_duplicate};
+        let existing = "viking://resources/docs/guide";
+
+        assert_eq!(plan_duplicate(existing, None, None), Duplicate::Reuse);
+        assert_eq!(
+            plan_duplicate(existing, None, Some("viking://resources/docs/")),
+            Duplicate::Reuse
+        );
+        assert_eq!(
+            plan_duplicate(existing, Some("viking://resources/docs/guide"), None),
+            Duplicate::Reuse
+        );
+        assert_eq!(
+            plan_duplicate(existing, None, Some("viking://resources/doc")),
+            Duplicate::LinkFrom("viking://resources/doc".to_string())
+        );
+        assert_eq!(
+            plan_duplicate(existing, Some("viking://resources/copy"), None),
+            Duplicate::Upload
+        );
+    }
+
     #[test]
     fn compact_request_body_keeps_non_empty_args() {
         let mut body = json!({"path": "x", "args": {"feishu_access_token": "u-x"}});
@@ -1858,6 +2290,30 @@ mod tests {
         assert!(!request.contains("include_mod_time_iso="));
     }
 
+    #[tokio::test]
+    async fn proxy_receives_absolute_form_requests() {
+        let (proxy_url, request_rx) = spawn_request_capture_server().await;
+        let client = HttpClient::new(
+            "http://openviking.internal:1933",
+            None,
+            None,
+            None,
+            None,
+            5.0,
+            false,
+            None,
+        )
+        .with_proxy(Some(proxy_url));
+
+        client
+            .ls("viking://resources", false, false, "agent", 256, false, 1)
+            .await
+            .expect("ls request should succeed through the proxy");
+
+        let request = request_rx.await.expect("request should be captured");
+        assert!(request.starts_with("GET http://openviking.internal:1933/api/v1/fs/ls?"));
+    }
+
     #[tokio::test]
     async fn gateway_token_is_not_sent_without_a_gateway_challenge() {
         let (base_url, request_rx) = spawn_request_capture_server().await;
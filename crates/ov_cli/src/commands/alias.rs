@@ -0,0 +1,323 @@
+//! URI aliases and bookmarks: short names for long `viking://` paths.
+//!
+//! `ov alias set contracts viking://resources/contract/2024` stores the name
+//! in `~/.openviking/aliases.json`; `ov bookmark add <uri>` stores a document
+//! under its file name in `~/.openviking/bookmarks.json`. Before the command
+//! line is parsed, an argument `@name` (or `@name/rest/of/path`, or the value
+//! of `--flag=@name`) is replaced with the URI saved under that name, so
+//! `ov ls @contracts` and `ov read @contracts/q3.md` work with any command.
+//! Aliases and bookmarks share one namespace; an `@word` that names neither is
+//! left alone.
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::error::{Error, Result};
+use crate::json_store;
+use crate::output::{OutputFormat, output_success};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Entry {
+    uri: String,
+    saved_at: String,
+}
+
+impl Entry {
+    fn new(uri: &str) -> Self {
+        Self {
+            uri: uri.trim_end_matches('/').to_string(),
+            saved_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        }
+    }
+
+    fn row(&self, name: &str) -> Value {
+        json!({
+            "name": name,
+            "uri": self.uri,
+            "saved_at": self.saved_at,
+        })
+    }
+}
+
+type Entries = BTreeMap<String, Entry>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Alias,
+    Bookmark,
+}
+
+impl Kind {
+    fn noun(self) -> &'static str {
+        match self {
+            Self::Alias => "alias",
+            Self::Bookmark => "bookmark",
+        }
+    }
+
+    fn file_name(self) -> &'static str {
+        match self {
+            Self::Alias => "aliases.json",
+            Self::Bookmark => "bookmarks.json",
+        }
+    }
+
+    fn other(self) -> Self {
+        match self {
+            Self::Alias => Self::Bookmark,
+            Self::Bookmark => Self::Alias,
+        }
+    }
+}
+
+fn store_path(kind: Kind) -> Result<PathBuf> {
+    json_store::path(kind.file_name())
+}
+
+fn validate_name(kind: Kind, name: &str) -> Result<()> {
+    json_store::validate_name(kind.noun(), name)
+}
+
+fn validate_uri(uri: &str) -> Result<()> {
+    if !uri.starts_with("viking://") {
+        return Err(Error::Client(format!("'{uri}' is not a viking:// URI")));
+    }
+    Ok(())
+}
+
+/// The name a bookmark gets without `--name`: the URI's last segment, minus
+/// its extension.
+fn default_bookmark_name(uri: &str) -> Option<&str> {
+    let segment = uri
+        .trim_start_matches("viking://")
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|segment| !segment.is_empty())?;
+    Some(match segment.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem,
+        _ => segment,
+    })
+}
+
+fn save(kind: Kind, name: &str, uri: &str, format: OutputFormat, compact: bool) -> Result<()> {
+    validate_name(kind, name)?;
+    validate_uri(uri)?;
+    let others: Entries = json_store::read(&store_path(kind.other())?)?;
+    if others.contains_key(name) {
+        return Err(Error::Client(format!(
+            "'{name}' is already saved with `ov {other}`; remove it with `ov {other} rm {name}` or pick another name",
+            other = kind.other().noun()
+        )));
+    }
+    let path = store_path(kind)?;
+    let mut entries: Entries = json_store::read(&path)?;
+    let entry = Entry::new(uri);
+    let mut row = entry.row(name);
+    row["replaced"] = json!(entries.insert(name.to_string(), entry).is_some());
+    json_store::write(&path, &entries)?;
+    output_success(row, format, compact);
+    Ok(())
+}
+
+fn list(kind: Kind, format: OutputFormat, compact: bool) -> Result<()> {
+    let entries: Entries = json_store::read(&store_path(kind)?)?;
+    let rows: Vec<Value> = entries
+        .iter()
+        .map(|(name, entry)| entry.row(name))
+        .collect();
+    output_success(rows, format, compact);
+    Ok(())
+}
+
+fn remove(kind: Kind, name: &str, format: OutputFormat, compact: bool) -> Result<()> {
+    let path = store_path(kind)?;
+    let mut entries: Entries = json_store::read(&path)?;
+    if entries.remove(name).is_none() {
+        return Err(Error::Client(format!(
+            "No {} named '{name}'; see `ov {} ls`",
+            kind.noun(),
+            kind.noun()
+        )));
+    }
+    json_store::write(&path, &entries)?;
+    output_success(json!({ "removed": name }), format, compact);
+    Ok(())
+}
+
+pub fn set_alias(name: &str, uri: &str, format: OutputFormat, compact: bool) -> Result<()> {
+    save(Kind::Alias, name, uri, format, compact)
+}
+
+pub fn list_aliases(format: OutputFormat, compact: bool) -> Result<()> {
+    list(Kind::Alias, format, compact)
+}
+
+pub fn remove_alias(name: &str, format: OutputFormat, compact: bool) -> Result<()> {
+    remove(Kind::Alias, name, format, compact)
+}
+
+pub fn add_bookmark(
+    uri: &str,
+    name: Option<&str>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let name = match name {
+        Some(name) => name,
+        None => default_bookmark_name(uri).ok_or_else(|| {
+            Error::Client(format!("Cannot name a bookmark for '{uri}'; pass --name"))
+        })?,
+    };
+    save(Kind::Bookmark, name, uri, format, compact)
+}
+
+pub fn list_bookmarks(format: OutputFormat, compact: bool) -> Result<()> {
+    list(Kind::Bookmark, format, compact)
+}
+
+pub fn remove_bookmark(name: &str, format: OutputFormat, compact: bool) -> Result<()> {
+    remove(Kind::Bookmark, name, format, compact)
+}
+
+/// The expansion of one argument, or `None` when it names no alias or bookmark.
+fn expand_arg(arg: &str, lookup: &dyn Fn(&str) -> Option<String>) -> Option<String> {
+    let (flag, value) = match arg.split_once("=@") {
+        Some((flag, value)) if flag.starts_with("--") => (Some(flag), value),
+        _ => (None, arg.strip_prefix('@')?),
+    };
+    let (name, rest) = match value.find('/') {
+        Some(slash) => value.split_at(slash),
+        None => (value, ""),
+    };
+    let uri = lookup(name)?;
+    let expanded = format!("{uri}{rest}");
+    Some(match flag {
+        Some(flag) => format!("{flag}={expanded}"),
+        None => expanded,
+    })
+}
+
+fn expand_with(args: Vec<OsString>, lookup: &dyn Fn(&str) -> Option<String>) -> Vec<OsString> {
+    let mut past_separator = false;
+    args.into_iter()
+        .enumerate()
+        .map(|(index, arg)| {
+            if index == 0 || past_separator {
+                return arg;
+            }
+            if arg == "--" {
+                past_separator = true;
+                return arg;
+            }
+            match arg.to_str().and_then(|text| expand_arg(text, lookup)) {
+                Some(expanded) => OsString::from(expanded),
+                None => arg,
+            }
+        })
+        .collect()
+}
+
+/// Replace `@name` arguments with the URIs saved under those names.
+///
+/// The stores are only read when some argument starts with `@`, so a corrupt
+/// store does not break commands that never use it.
+pub fn expand_args(args: Vec<OsString>) -> Result<Vec<OsString>> {
+    let mentions_name = args.iter().skip(1).any(|arg| {
+        arg.to_str()
+            .is_some_and(|text| text.starts_with('@') || text.contains("=@"))
+    });
+    if !mentions_name {
+        return Ok(args);
+    }
+    let aliases: Entries = json_store::read(&store_path(Kind::Alias)?)?;
+    let bookmarks: Entries = json_store::read(&store_path(Kind::Bookmark)?)?;
+    let lookup = |name: &str| {
+        aliases
+            .get(name)
+            .or_else(|| bookmarks.get(name))
+            .map(|entry| entry.uri.clone())
+    };
+    Ok(expand_with(args, &lookup))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(args: &[&str]) -> Vec<String> {
+        let lookup = |name: &str| {
+            (name == "contracts").then(|| "viking://resources/contract/2024".to_string())
+        };
+        expand_with(args.iter().map(OsString::from).collect(), &lookup)
+            .into_iter()
+            .map(|arg| arg.into_string().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn at_names_expand_to_saved_uris() {
+        assert_eq!(
+            expand(&["ov", "read", "@contracts/q3.md"]),
+            vec!["ov", "read", "viking://resources/contract/2024/q3.md"]
+        );
+        assert_eq!(
+            expand(&[
+                "ov",
+                "find",
+                "terms",
+                "--uri=@contracts",
+                "-u",
+                "@contracts"
+            ]),
+            vec![
+                "ov",
+                "find",
+                "terms",
+                "--uri=viking://resources/contract/2024",
+                "-u",
+                "viking://resources/contract/2024"
+            ]
+        );
+        assert_eq!(
+            expand(&["ov", "search", "@alice", "--", "@contracts"]),
+            vec!["ov", "search", "@alice", "--", "@contracts"]
+        );
+    }
+
+    #[test]
+    fn bookmark_names_default_to_the_file_stem() {
+        assert_eq!(
+            default_bookmark_name("viking://resources/contract/2024/q3-report.md"),
+            Some("q3-report")
+        );
+        assert_eq!(
+            default_bookmark_name("viking://resources/handbook/"),
+            Some("handbook")
+        );
+        assert_eq!(default_bookmark_name("viking://"), None);
+        assert!(validate_name(Kind::Bookmark, "合同.2024").is_ok());
+        assert!(validate_name(Kind::Alias, "a/b").is_err());
+    }
+
+    #[test]
+    fn store_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("aliases.json");
+        assert!(json_store::read::<Entries>(&path).unwrap().is_empty());
+
+        let mut entries = Entries::new();
+        entries.insert(
+            "contracts".to_string(),
+            Entry::new("viking://resources/contract/2024/"),
+        );
+        json_store::write(&path, &entries).unwrap();
+        let read: Entries = json_store::read(&path).unwrap();
+        assert_eq!(read, entries);
+        assert_eq!(read["contracts"].uri, "viking://resources/contract/2024");
+    }
+}
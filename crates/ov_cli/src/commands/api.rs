@@ -0,0 +1,181 @@
+use crate::client::HttpClient;
+use crate::error::{Error, Result};
+use crate::output::{OutputFormat, output_success};
+use serde_json::{Map, Value};
+use std::io::Read;
+
+const API_PREFIX: &str = "/api/v1/";
+
+pub async fn request(
+    client: &HttpClient,
+    method: &str,
+    path: &str,
+    fields: &[String],
+    raw_body: Option<&str>,
+    output_format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let method = reqwest::Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+        .map_err(|_| Error::Client(format!("Invalid HTTP method: {method}")))?;
+    let fields = fields
+        .iter()
+        .map(|field| parse_field(field))
+        .collect::<Result<Vec<_>>>()?;
+    let raw_body = raw_body.map(read_raw_body).transpose()?;
+
+    let (params, body) = request_parts(&method, fields, raw_body);
+    let result: Value = client
+        .request(method, &api_path(path), &params, body.as_ref())
+        .await?;
+    output_success(&result, output_format, compact);
+    Ok(())
+}
+
+/// Paths without a leading `/` are relative to `/api/v1/`, so `ov api GET sessions`
+/// and `ov api GET /api/v1/sessions` hit the same endpoint.
+pub fn api_path(path: &str) -> String {
+    let path = path.trim();
+    if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("{API_PREFIX}{path}")
+    }
+}
+
+/// Parse a `--field key=value` pair. `true`, `false`, `null` and numbers keep
+/// their JSON type, `@path` reads the value from a file, and anything else is
+/// sent as a string.
+pub fn parse_field(field: &str) -> Result<(String, Value)> {
+    let Some((key, value)) = field.split_once('=') else {
+        return Err(Error::Client(format!(
+            "Invalid --field '{field}': expected key=value"
+        )));
+    };
+    if key.is_empty() {
+        return Err(Error::Client(format!(
+            "Invalid --field '{field}': key is empty"
+        )));
+    }
+    let value = if let Some(file) = value.strip_prefix('@') {
+        Value::String(read_input(file)?)
+    } else {
+        match value {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            "null" => Value::Null,
+            _ => value
+                .parse::<i64>()
+                .map(Value::from)
+                .or_else(|_| value.parse::<f64>().map(Value::from))
+                .unwrap_or_else(|_| Value::String(value.to_string())),
+        }
+    };
+    Ok((key.to_string(), value))
+}
+
+/// `--raw-body` takes inline JSON, `@file`, or `@-` for stdin.
+fn read_raw_body(spec: &str) -> Result<Value> {
+    let text = match spec.strip_prefix('@') {
+        Some(file) => read_input(file)?,
+        None => spec.to_string(),
+    };
+    serde_json::from_str(&text)
+        .map_err(|e| Error::Client(format!("Invalid JSON for --raw-body: {e}")))
+}
+
+fn read_input(file: &str) -> Result<String> {
+    if file == "-" {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text)?;
+        Ok(text)
+    } else {
+        Ok(std::fs::read_to_string(file)?)
+    }
+}
+
+/// Like `gh api`: fields become query parameters for GET/DELETE or when a raw
+/// body is given, and a JSON object body otherwise.
+pub fn request_parts(
+    method: &reqwest::Method,
+    fields: Vec<(String, Value)>,
+    raw_body: Option<Value>,
+) -> (Vec<(String, String)>, Option<Value>) {
+    let fields_in_query =
+        raw_body.is_some() || matches!(*method, reqwest::Method::GET | reqwest::Method::DELETE);
+    if fields_in_query {
+        let params = fields
+            .into_iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    Value::String(value) => value,
+                    value => value.to_string(),
+                };
+                (key, value)
+            })
+            .collect();
+        return (params, raw_body);
+    }
+    let body = fields.into_iter().collect::<Map<_, _>>();
+    (Vec::new(), Some(Value::Object(body)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn api_path_prefixes_relative_paths() {
+        assert_eq!(api_path("sessions"), "/api/v1/sessions");
+        assert_eq!(api_path("/api/v1/sessions"), "/api/v1/sessions");
+        assert_eq!(api_path("/health"), "/health");
+    }
+
+    #[test]
+    fn parse_field_keeps_json_scalar_types() {
+        assert_eq!(
+            parse_field("limit=10").unwrap(),
+            ("limit".to_string(), json!(10))
+        );
+        assert_eq!(
+            parse_field("recursive=true").unwrap(),
+            ("recursive".to_string(), json!(true))
+        );
+        assert_eq!(
+            parse_field("uri=viking://resources/a=b").unwrap(),
+            ("uri".to_string(), json!("viking://resources/a=b"))
+        );
+        assert!(parse_field("missing-separator").is_err());
+        assert!(parse_field("=value").is_err());
+    }
+
+    #[test]
+    fn request_parts_route_fields_by_method() {
+        let fields = vec![
+            ("uri".to_string(), json!("viking://resources")),
+            ("limit".to_string(), json!(5)),
+        ];
+
+        let (params, body) = request_parts(&reqwest::Method::GET, fields.clone(), None);
+        assert_eq!(
+            params,
+            vec![
+                ("uri".to_string(), "viking://resources".to_string()),
+                ("limit".to_string(), "5".to_string()),
+            ]
+        );
+        assert_eq!(body, None);
+
+        let (params, body) = request_parts(&reqwest::Method::POST, fields.clone(), None);
+        assert!(params.is_empty());
+        assert_eq!(body, Some(json!({"uri": "viking://resources", "limit": 5})));
+
+        let (params, body) = request_parts(
+            &reqwest::Method::POST,
+            fields,
+            Some(json!({"query": "auth"})),
+        );
+        assert_eq!(params.len(), 2);
+        assert_eq!(body, Some(json!({"query": "auth"})));
+    }
+}
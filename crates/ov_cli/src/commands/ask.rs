@@ -0,0 +1,305 @@
+//! `ov ask`: answer a question from OpenViking context.
+//!
+//! The question is answered in one pass: retrieve and pack context as
+//! `ov context assemble` does, send it to the OpenAI-compatible endpoint in
+//! the config's `"llm"` section, stream the answer, then list the viking://
+//! URIs it cites. With a session, retrieval is context-aware and the
+//! exchange is added to the session.
+
+use std::io::Write;
+use std::time::Duration;
+
+use colored::Colorize;
+use reqwest::Client;
+use serde_json::{Value, json};
+
+use super::context::{AssembleOptions, Assembled, Layer, gather};
+use super::session::url_encode;
+use crate::base_client::configure_proxy;
+use crate::client::HttpClient;
+use crate::config::{Config, LlmConfig};
+use crate::error::{Error, Result};
+use crate::output::{OutputFormat, output_success};
+use crate::theme;
+
+/// Answers can take a while to generate, well past a server request's timeout.
+const LLM_TIMEOUT: Duration = Duration::from_secs(300);
+
+pub struct AskOptions<'a> {
+    pub question: &'a str,
+    /// Only retrieve under this URI.
+    pub target: &'a str,
+    pub session_id: Option<&'a str>,
+    /// Estimated token budget for the context.
+    pub budget: usize,
+    pub node_limit: i32,
+    /// Model to use instead of the configured one.
+    pub model: Option<&'a str>,
+}
+
+pub async fn ask(
+    client: &HttpClient,
+    config: &Config,
+    options: AskOptions<'_>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let llm = config.llm.as_ref().ok_or_else(|| {
+        Error::Config(
+            "No LLM configured. Add an \"llm\" section with \"api_base\" and \"model\" to ovcli.conf"
+                .to_string(),
+        )
+    })?;
+    let model = options.model.unwrap_or(&llm.model);
+    let assembled = gather(
+        client,
+        &AssembleOptions {
+            query: options.question,
+            uri: options.target,
+            budget: options.budget,
+            layers: vec![Layer::Abstract, Layer::Overview, Layer::Content],
+            node_limit: options.node_limit,
+            threshold: None,
+            session_id: options.session_id,
+            messages: false,
+        },
+    )
+    .await?;
+    eprintln!(
+        "{}",
+        theme::muted(format!(
+            "Answering with {model} from {} source(s), ~{} tokens of context",
+            assembled.sources.len(),
+            assembled.used_tokens
+        ))
+    );
+
+    let stream = !format.is_json();
+    let answer = complete(
+        config,
+        llm,
+        model,
+        &assembled.messages(options.question),
+        stream,
+    )
+    .await?;
+    let cited = cited_uris(&assembled, &answer);
+
+    if let Some(session_id) = options.session_id {
+        let path = format!("/api/v1/sessions/{}/messages/batch", url_encode(session_id));
+        let messages = json!({"messages": [
+            {"role": "user", "content": options.question},
+            {"role": "assistant", "content": answer},
+        ]});
+        let _: Value = client.post(&path, &messages).await?;
+    }
+
+    if format.is_json() {
+        output_success(
+            json!({
+                "question": options.question,
+                "answer": answer,
+                "model": model,
+                "cited": cited,
+                "sources": assembled.sources,
+            }),
+            format,
+            compact,
+        );
+        return Ok(());
+    }
+    // The answer is already on screen; list what it was based on.
+    println!();
+    let (heading, uris) = if cited.is_empty() {
+        ("Context used", source_uris(&assembled))
+    } else {
+        ("Sources", cited)
+    };
+    if !uris.is_empty() {
+        println!();
+        println!("{}", theme::heading(heading).bold());
+        for uri in uris {
+            println!("  {}", theme::sky_value(uri));
+        }
+    }
+    Ok(())
+}
+
+fn source_uris(assembled: &Assembled) -> Vec<&str> {
+    assembled
+        .sources
+        .iter()
+        .filter_map(|source| source["uri"].as_str())
+        .collect()
+}
+
+/// The packed sources the answer names, in rank order.
+fn cited_uris<'a>(assembled: &'a Assembled, answer: &str) -> Vec<&'a str> {
+    source_uris(assembled)
+        .into_iter()
+        .filter(|uri| mentions(answer, uri))
+        .collect()
+}
+
+/// Whether `uri` appears in `text` on its own, not as the start of a longer
+/// URI such as a file in that directory. A trailing period ends a sentence.
+fn mentions(text: &str, uri: &str) -> bool {
+    text.match_indices(uri).any(|(start, _)| {
+        let mut rest = text[start + uri.len()..].chars();
+        match rest.next() {
+            None => true,
+            Some('.') => !rest.next().is_some_and(char::is_alphanumeric),
+            Some(next) => !(next.is_alphanumeric() || "/_-%".contains(next)),
+        }
+    })
+}
+
+/// Send `messages` to the chat completions endpoint and return the answer,
+/// printing it as it arrives when `stream` is set.
+async fn complete(
+    config: &Config,
+    llm: &LlmConfig,
+    model: &str,
+    messages: &Value,
+    stream: bool,
+) -> Result<String> {
+    let http = configure_proxy(Client::builder(), config.proxy.as_deref())?
+        .timeout(LLM_TIMEOUT)
+        .build()
+        .map_err(|e| Error::from_reqwest("Failed to create HTTP client", e))?;
+    let mut body = json!({"model": model, "messages": messages, "stream": stream});
+    if let Some(temperature) = llm.temperature {
+        body["temperature"] = json!(temperature);
+    }
+    let url = format!("{}/chat/completions", llm.api_base.trim_end_matches('/'));
+    let mut request = http.post(&url).json(&body);
+    if let Some(api_key) = llm.api_key.as_deref().filter(|key| !key.is_empty()) {
+        request = request.bearer_auth(api_key);
+    }
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| Error::from_reqwest("Failed to reach the LLM endpoint", e))?;
+    if !response.status().is_success() {
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+        return Err(llm_error(status, &body));
+    }
+    if !stream {
+        let reply: Value = response
+            .json()
+            .await
+            .map_err(|e| Error::from_reqwest("Failed to read the LLM response", e))?;
+        return Ok(reply["choices"][0]["message"]["content"]
+            .as_str()
+            .unwrap_or("")
+            .to_string());
+    }
+
+    let mut answer = String::new();
+    // Bytes, not text: a chunk may end inside a multi-byte character.
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut stdout = std::io::stdout();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| Error::from_reqwest("LLM stream error", e))?
+    {
+        buffer.extend_from_slice(&chunk);
+        while let Some(newline) = buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=newline).collect();
+            match stream_event(String::from_utf8_lossy(&line).trim_end()) {
+                Some(StreamEvent::Delta(delta)) => {
+                    print!("{delta}");
+                    let _ = stdout.flush();
+                    answer.push_str(&delta);
+                }
+                Some(StreamEvent::Done) => return Ok(answer),
+                None => {}
+            }
+        }
+    }
+    Ok(answer)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum StreamEvent {
+    Delta(String),
+    Done,
+}
+
+/// One server-sent line of a streamed chat completion.
+fn stream_event(line: &str) -> Option<StreamEvent> {
+    let data = line.strip_prefix("data:")?.trim_start();
+    if data == "[DONE]" {
+        return Some(StreamEvent::Done);
+    }
+    let chunk: Value = serde_json::from_str(data).ok()?;
+    chunk["choices"][0]["delta"]["content"]
+        .as_str()
+        .filter(|delta| !delta.is_empty())
+        .map(|delta| StreamEvent::Delta(delta.to_string()))
+}
+
+/// The provider's own message from an OpenAI-style error body.
+fn llm_error(status: u16, body: &str) -> Error {
+    let message = serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|body| body["error"]["message"].as_str().map(str::to_string))
+        .unwrap_or_else(|| body.trim().to_string());
+    Error::api_with_status(format!("LLM request failed ({status}): {message}"), status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_lines_yield_deltas_until_done() {
+        assert_eq!(
+            stream_event(r#"data: {"choices":[{"delta":{"content":"Hel"}}]}"#),
+            Some(StreamEvent::Delta("Hel".to_string()))
+        );
+        assert_eq!(
+            stream_event(r#"data:{"choices":[{"delta":{"role":"assistant"}}]}"#),
+            None
+        );
+        assert_eq!(stream_event(": keep-alive"), None);
+        assert_eq!(stream_event("data: [DONE]"), Some(StreamEvent::Done));
+    }
+
+    #[test]
+    fn answers_cite_packed_sources_in_rank_order() {
+        let assembled = Assembled {
+            block: String::new(),
+            used_tokens: 0,
+            retrieved: 3,
+            sources: vec![
+                json!({"uri": "viking://resources/docs/auth.md"}),
+                json!({"uri": "viking://resources/docs"}),
+            ],
+            omitted: vec![json!("viking://resources/old.md")],
+        };
+        let answer = "Tokens are issued by the gateway (viking://resources/docs/auth.md).";
+        assert_eq!(
+            cited_uris(&assembled, answer),
+            vec!["viking://resources/docs/auth.md"]
+        );
+        assert!(cited_uris(&assembled, "No idea.").is_empty());
+        assert_eq!(
+            cited_uris(&assembled, "See viking://resources/docs."),
+            vec!["viking://resources/docs"]
+        );
+    }
+
+    #[test]
+    fn provider_errors_keep_their_message() {
+        let error = llm_error(401, r#"{"error":{"message":"Incorrect API key provided"}}"#);
+        assert!(error.to_string().contains("Incorrect API key provided"));
+        assert!(
+            llm_error(502, "bad gateway")
+                .to_string()
+                .contains("bad gateway")
+        );
+    }
+}
@@ -0,0 +1,323 @@
+//! `ov audit tail`: read the server's request audit log.
+//!
+//! The server records each API request (user, method, route, status) and
+//! serves them newest first from `/api/v1/console/audit`, where it can only
+//! filter by API type. `--user`, `--since` and the method half of `--action`
+//! are applied here while paging back, so a narrow filter over a long log reads
+//! more pages. ADMIN and ROOT keys see the whole workspace; a USER key sees
+//! only its own requests.
+
+use std::collections::HashSet;
+use std::io::Write;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde_json::{Value, json};
+
+use super::index::parse_since;
+use crate::client::HttpClient;
+use crate::error::{Error, Result};
+use crate::output::{OutputFormat, output_success};
+use crate::theme;
+
+/// Rows requested per page; the server's maximum.
+const PAGE_SIZE: u32 = 100;
+
+/// Which requests `--action` keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    /// Resources and skills added.
+    Add,
+    /// Any `DELETE` request.
+    Delete,
+    /// Searches of every kind.
+    Search,
+}
+
+impl Action {
+    fn parse(value: &str) -> Result<Self> {
+        match value {
+            "add" => Ok(Self::Add),
+            "delete" => Ok(Self::Delete),
+            "search" => Ok(Self::Search),
+            _ => Err(Error::Client(format!(
+                "Invalid --action '{value}'; use add, delete, or search"
+            ))),
+        }
+    }
+
+    /// The API types the server can narrow the log to.
+    fn api_types(self) -> &'static [&'static str] {
+        match self {
+            Self::Add => &["resources", "skills"],
+            Self::Delete => &[],
+            Self::Search => &["search.find", "search.search", "search"],
+        }
+    }
+
+    fn matches(self, event: &Value) -> bool {
+        let method = event["method"].as_str().unwrap_or_default();
+        let route = event["route"].as_str().unwrap_or_default();
+        match self {
+            Self::Add => {
+                method == "POST" && matches!(route, "/api/v1/resources" | "/api/v1/skills")
+            }
+            Self::Delete => method == "DELETE",
+            Self::Search => true,
+        }
+    }
+}
+
+pub struct AuditTailOptions<'a> {
+    pub user: Option<&'a str>,
+    pub action: Option<&'a str>,
+    pub since: Option<&'a str>,
+    pub lines: usize,
+    pub follow: bool,
+    pub interval: f64,
+}
+
+struct Filter<'a> {
+    user: Option<&'a str>,
+    action: Option<Action>,
+}
+
+impl Filter<'_> {
+    fn api_types(&self) -> &'static [&'static str] {
+        self.action.map_or(&[], Action::api_types)
+    }
+
+    fn matches(&self, event: &Value) -> bool {
+        self.user
+            .is_none_or(|user| event["user_id"].as_str() == Some(user))
+            && self.action.is_none_or(|action| action.matches(event))
+    }
+}
+
+fn event_time(event: &Value) -> Option<DateTime<Utc>> {
+    event["created_at"]
+        .as_str()
+        .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+        .map(|time| time.with_timezone(&Utc))
+}
+
+fn event_key(event: &Value) -> String {
+    match event["request_id"].as_str() {
+        Some(request_id) => format!("{request_id}@{}", event["created_at"]),
+        None => event.to_string(),
+    }
+}
+
+/// One page of events, newest first, and whether older pages follow.
+async fn fetch_page(
+    client: &HttpClient,
+    filter: &Filter<'_>,
+    page: u32,
+) -> Result<(Vec<Value>, bool)> {
+    let result = client
+        .audit_logs(page, PAGE_SIZE, filter.api_types(), &[])
+        .await?;
+    if result["enabled"] == Value::Bool(false) {
+        return Err(Error::Client(
+            "The server's audit log is disabled; enable usage_audit in the server config"
+                .to_string(),
+        ));
+    }
+    let items = result["items"].as_array().cloned().unwrap_or_default();
+    let total = result["total"].as_u64().unwrap_or(0);
+    let more = items.len() == PAGE_SIZE as usize && u64::from(page * PAGE_SIZE) < total;
+    Ok((items, more))
+}
+
+/// The events one walk back through the log collected, and the time of the
+/// newest event it saw.
+struct Walk {
+    events: Vec<Value>,
+    newest: Option<DateTime<Utc>>,
+}
+
+/// Walk the log back from the newest event until `stop` says to or `limit`
+/// events are collected, keeping the unseen events that match `filter`,
+/// newest first. Every event walked past is added to `seen`.
+async fn walk(
+    client: &HttpClient,
+    filter: &Filter<'_>,
+    seen: &mut HashSet<String>,
+    limit: usize,
+    stop: &dyn Fn(&Value) -> bool,
+) -> Result<Walk> {
+    let mut walked = Walk {
+        events: Vec::new(),
+        newest: None,
+    };
+    let mut page = 1;
+    loop {
+        let (items, more) = fetch_page(client, filter, page).await?;
+        if page == 1 {
+            walked.newest = items.first().and_then(event_time);
+        }
+        for event in items {
+            if stop(&event) || walked.events.len() == limit {
+                return Ok(walked);
+            }
+            if seen.insert(event_key(&event)) && filter.matches(&event) {
+                walked.events.push(event);
+            }
+        }
+        if !more {
+            return Ok(walked);
+        }
+        page += 1;
+    }
+}
+
+/// The fields `ov audit tail` shows, in table column order.
+fn row(event: &Value) -> Value {
+    json!({
+        "created_at": event["created_at"],
+        "user_id": event["user_id"],
+        "method": event["method"],
+        "route": event["route"],
+        "status_code": event["status_code"],
+        "duration_ms": event["duration_ms"],
+        "request_id": event["request_id"],
+    })
+}
+
+fn render_line(event: &Value) -> String {
+    let time = event["created_at"]
+        .as_str()
+        .and_then(|time| time.get(..19))
+        .map(|time| time.replace('T', " "))
+        .unwrap_or_else(|| "-".to_string());
+    let status = event["status_code"].as_u64().unwrap_or(0);
+    let status = if (200..400).contains(&status) {
+        status.to_string()
+    } else {
+        theme::error(status.to_string()).to_string()
+    };
+    format!(
+        "{time}  {:<12} {:<6} {}  {status}  {:.0}ms",
+        event["user_id"].as_str().unwrap_or("-"),
+        event["method"].as_str().unwrap_or("?"),
+        event["route"].as_str().unwrap_or("?"),
+        event["duration_ms"].as_f64().unwrap_or(0.0),
+    )
+}
+
+fn print_event(event: &Value, format: OutputFormat) {
+    if format.is_json() {
+        println!("{}", row(event));
+    } else {
+        println!("{}", render_line(event));
+    }
+    let _ = std::io::stdout().flush();
+}
+
+pub async fn tail(
+    client: &HttpClient,
+    options: AuditTailOptions<'_>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    if options.follow && (!options.interval.is_finite() || options.interval <= 0.0) {
+        return Err(Error::Client(
+            "--interval must be a positive number of seconds".to_string(),
+        ));
+    }
+    let filter = Filter {
+        user: options.user,
+        action: options.action.map(Action::parse).transpose()?,
+    };
+    let since = options
+        .since
+        .map(|value| parse_since("--since", value, Utc::now()))
+        .transpose()?;
+    let before_since = |event: &Value| {
+        since.is_some_and(|since| event_time(event).is_some_and(|time| time < since))
+    };
+
+    let mut seen = HashSet::new();
+    let first = walk(client, &filter, &mut seen, options.lines, &before_since).await?;
+    let mut events = first.events;
+    events.reverse();
+    if !options.follow {
+        let rows: Vec<Value> = events.iter().map(row).collect();
+        output_success(rows, format, compact);
+        return Ok(());
+    }
+
+    for event in &events {
+        print_event(event, format);
+    }
+    // Later polls stop at the newest event already seen, so they only walk
+    // what arrived in between.
+    let mut newest = first.newest;
+    loop {
+        tokio::time::sleep(Duration::from_secs_f64(options.interval)).await;
+        let older = |event: &Value| {
+            let time = event_time(event);
+            newest.is_some_and(|newest| time.is_some_and(|time| time < newest))
+                || before_since(event)
+        };
+        let fresh = match walk(client, &filter, &mut seen, usize::MAX, &older).await {
+            Ok(fresh) => fresh,
+            Err(error) => {
+                eprintln!("{} {error}; retrying", theme::warning("warning:"));
+                continue;
+            }
+        };
+        for event in fresh.events.iter().rev() {
+            print_event(event, format);
+        }
+        newest = newest.max(fresh.newest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(method: &str, route: &str, user: &str) -> Value {
+        json!({
+            "request_id": "r1",
+            "user_id": user,
+            "method": method,
+            "route": route,
+            "status_code": 200,
+            "duration_ms": 3.5,
+            "created_at": "2026-10-17T09:00:00+00:00",
+        })
+    }
+
+    #[test]
+    fn actions_match_adds_deletes_and_searches() {
+        let add = Action::parse("add").unwrap();
+        assert!(add.matches(&event("POST", "/api/v1/resources", "alice")));
+        assert!(!add.matches(&event("GET", "/api/v1/resources", "alice")));
+        assert!(!add.matches(&event("POST", "/api/v1/resources/uploads", "alice")));
+        let delete = Action::parse("delete").unwrap();
+        assert!(delete.matches(&event("DELETE", "/api/v1/fs", "alice")));
+        assert!(!delete.matches(&event("POST", "/api/v1/fs/mkdir", "alice")));
+        assert!(Action::parse("rename").is_err());
+
+        let filter = Filter {
+            user: Some("bob"),
+            action: Some(delete),
+        };
+        assert_eq!(filter.api_types(), &[] as &[&str]);
+        assert!(filter.matches(&event("DELETE", "/api/v1/fs", "bob")));
+        assert!(!filter.matches(&event("DELETE", "/api/v1/fs", "alice")));
+    }
+
+    #[test]
+    fn rows_keep_the_audit_fields() {
+        let event = event("DELETE", "/api/v1/fs", "bob");
+        assert_eq!(row(&event)["route"], "/api/v1/fs");
+        assert_eq!(
+            event_time(&event).unwrap().to_rfc3339(),
+            "2026-10-17T09:00:00+00:00"
+        );
+        assert!(render_line(&event).starts_with("2026-10-17 09:00:00  bob"));
+    }
+}
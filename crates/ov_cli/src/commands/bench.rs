@@ -0,0 +1,259 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use serde_json::{Value, json};
+
+use crate::client::{HttpClient, SearchScoring};
+use crate::error::{Error, Result};
+use crate::output::{OutputFormat, output_success};
+use crate::timing::percentile;
+
+use super::session::url_encode;
+
+/// One benchmarked operation, resolved from `ov bench <op>`.
+#[derive(Debug, Clone)]
+pub enum BenchOp {
+    Find {
+        query: String,
+        uri: String,
+    },
+    Read {
+        uri: String,
+    },
+    /// Append a message to a scratch session that is deleted afterwards.
+    Add,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BenchLimits {
+    pub concurrency: usize,
+    pub duration: Option<Duration>,
+    pub requests: Option<usize>,
+}
+
+#[derive(Debug, Default)]
+struct WorkerResult {
+    latencies: Vec<Duration>,
+    errors: usize,
+    first_error: Option<String>,
+}
+
+pub async fn run(
+    client: &HttpClient,
+    op: BenchOp,
+    limits: BenchLimits,
+    output_format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    if limits.concurrency == 0 {
+        return Err(Error::Client(
+            "--concurrency must be at least 1".to_string(),
+        ));
+    }
+    let scratch_session = match op {
+        BenchOp::Add => Some(create_scratch_session(client).await?),
+        _ => None,
+    };
+
+    let started = Instant::now();
+    let deadline = limits.duration.map(|duration| started + duration);
+    let issued = Arc::new(AtomicUsize::new(0));
+    let mut workers = Vec::with_capacity(limits.concurrency);
+    for _ in 0..limits.concurrency {
+        let client = client.clone();
+        let op = op.clone();
+        let issued = Arc::clone(&issued);
+        let session = scratch_session.clone();
+        workers.push(tokio::spawn(async move {
+            let mut result = WorkerResult::default();
+            loop {
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    break;
+                }
+                if let Some(requests) = limits.requests
+                    && issued.fetch_add(1, Ordering::Relaxed) >= requests
+                {
+                    break;
+                }
+                let call_started = Instant::now();
+                let outcome = run_once(&client, &op, session.as_deref()).await;
+                result.latencies.push(call_started.elapsed());
+                if let Err(error) = outcome {
+                    result.errors += 1;
+                    result.first_error.get_or_insert_with(|| {
+                        let message = error.to_string();
+                        message.lines().next().unwrap_or_default().to_string()
+                    });
+                }
+            }
+            result
+        }));
+    }
+
+    let mut latencies = Vec::new();
+    let mut errors = 0;
+    let mut first_error = None;
+    for worker in workers {
+        let result = worker
+            .await
+            .map_err(|e| Error::Client(format!("Benchmark worker failed: {e}")))?;
+        latencies.extend(result.latencies);
+        errors += result.errors;
+        first_error = first_error.or(result.first_error);
+    }
+    let elapsed = started.elapsed();
+
+    if let Some(session_id) = scratch_session {
+        let path = format!("/api/v1/sessions/{}", url_encode(&session_id));
+        let _: Result<Value> = client.delete(&path, &[]).await;
+    }
+
+    let mut report = bench_report(&op, limits.concurrency, &mut latencies, errors, elapsed);
+    if let Some(error) = first_error {
+        report["first_error"] = Value::String(error);
+    }
+    output_success(&report, output_format, compact);
+    Ok(())
+}
+
+async fn run_once(client: &HttpClient, op: &BenchOp, session: Option<&str>) -> Result<()> {
+    match op {
+        BenchOp::Find { query, uri } => {
+            client
+                .find(
+                    query.clone(),
+                    uri.clone(),
+                    None,
+                    10,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    &SearchScoring::default(),
+                )
+                .await?;
+        }
+        BenchOp::Read { uri } => {
+            client.read(uri).await?;
+        }
+        BenchOp::Add => {
+            let path = format!(
+                "/api/v1/sessions/{}/messages",
+                url_encode(session.unwrap_or_default())
+            );
+            let _: Value = client
+                .post(
+                    &path,
+                    &json!({"role": "user", "content": "ov bench probe message"}),
+                )
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+async fn create_scratch_session(client: &HttpClient) -> Result<String> {
+    let response: Value = client.post("/api/v1/sessions", &json!({})).await?;
+    response["session_id"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| Error::api("Failed to get session_id from new session response"))
+}
+
+fn op_name(op: &BenchOp) -> &'static str {
+    match op {
+        BenchOp::Find { .. } => "find",
+        BenchOp::Read { .. } => "read",
+        BenchOp::Add => "add",
+    }
+}
+
+fn bench_report(
+    op: &BenchOp,
+    concurrency: usize,
+    latencies: &mut [Duration],
+    errors: usize,
+    elapsed: Duration,
+) -> Value {
+    latencies.sort();
+    let requests = latencies.len();
+    let seconds = elapsed.as_secs_f64();
+    let throughput = if seconds > 0.0 {
+        requests as f64 / seconds
+    } else {
+        0.0
+    };
+    let millis = |duration: Duration| (duration.as_secs_f64() * 10_000.0).round() / 10.0;
+    let at = |percent: usize| {
+        if latencies.is_empty() {
+            Value::Null
+        } else {
+            json!(millis(percentile(latencies, percent)))
+        }
+    };
+    json!({
+        "operation": op_name(op),
+        "concurrency": concurrency,
+        "requests": requests,
+        "errors": errors,
+        "duration_secs": (seconds * 100.0).round() / 100.0,
+        "throughput_per_sec": (throughput * 10.0).round() / 10.0,
+        "p50_ms": at(50),
+        "p90_ms": at(90),
+        "p95_ms": at(95),
+        "p99_ms": at(99),
+        "max_ms": latencies.last().map_or(Value::Null, |max| json!(millis(*max))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bench_report_summarizes_latency_and_throughput() {
+        let mut latencies: Vec<Duration> = [40, 10, 30, 20]
+            .into_iter()
+            .map(Duration::from_millis)
+            .collect();
+        let report = bench_report(
+            &BenchOp::Read {
+                uri: "viking://resources/a.md".to_string(),
+            },
+            2,
+            &mut latencies,
+            1,
+            Duration::from_secs(2),
+        );
+
+        assert_eq!(
+            report,
+            json!({
+                "operation": "read",
+                "concurrency": 2,
+                "requests": 4,
+                "errors": 1,
+                "duration_secs": 2.0,
+                "throughput_per_sec": 2.0,
+                "p50_ms": 20.0,
+                "p90_ms": 40.0,
+                "p95_ms": 40.0,
+                "p99_ms": 40.0,
+                "max_ms": 40.0,
+            })
+        );
+    }
+
+    #[test]
+    fn bench_report_handles_no_requests() {
+        let report = bench_report(&BenchOp::Add, 1, &mut [], 0, Duration::ZERO);
+
+        assert_eq!(report["requests"], 0);
+        assert_eq!(report["p50_ms"], Value::Null);
+        assert_eq!(report["throughput_per_sec"], 0.0);
+    }
+}
@@ -6,6 +6,7 @@
 //! - Command history support
 //! - Streaming response support
 
+use std::io::Write;
 use std::{collections::HashMap, time::Duration};
 
 use clap::Parser;
@@ -18,7 +19,10 @@ use termimad::MadSkin;
 use unicode_width::UnicodeWidthStr;
 use uuid::Uuid;
 
-use crate::base_client::api_error_from_body;
+use crate::base_client::{
+    api_error_from_body, configure_proxy, configure_tls, configure_unix_socket, request_base_url,
+    unix_socket_path,
+};
 use crate::config::Config;
 use crate::i18n::{Language, copy};
 use crate::theme;
@@ -76,6 +80,16 @@ pub struct ChatCommand {
     /// Disable command history
     #[arg(long)]
     pub no_history: bool,
+
+    /// Print `content_delta` events as they arrive instead of rendering the
+    /// final reply once (`ov session chat`)
+    #[arg(skip)]
+    pub stream_tokens: bool,
+
+    /// Append every completed exchange to the OpenViking session
+    /// (`ov session chat`)
+    #[arg(skip)]
+    pub record_session: bool,
 }
 
 /// Chat message for API
@@ -162,12 +176,22 @@ impl ChatCommand {
     /// Execute the chat command
     pub async fn execute(&self) -> Result<()> {
         let language = Language::current();
-        let client = Client::builder()
-            .timeout(Duration::from_secs(300))
-            .build()
-            .map_err(|e| Error::from_reqwest("Failed to create HTTP client", e))?;
-
         let config = Config::load()?;
+        let unix_socket = non_empty_string(self.endpoint.clone())
+            .is_none()
+            .then(|| unix_socket_path(&config.url))
+            .flatten();
+        let client = configure_proxy(Client::builder(), config.proxy.as_deref())
+            .and_then(|builder| configure_tls(builder, &config.tls_files()))
+            .map(|builder| configure_unix_socket(builder, unix_socket.as_deref()))?;
+        let client = match config.connect_timeout() {
+            Some(connect_timeout) => client.connect_timeout(connect_timeout),
+            None => client,
+        }
+        .timeout(Duration::from_secs(300))
+        .build()
+        .map_err(|e| Error::from_reqwest("Failed to create HTTP client", e))?;
+
         let endpoint = self.resolve_endpoint_from_config(&config);
         let probe_auth = self.health_probe_auth_from_config(&config);
         let health = self
@@ -210,7 +234,7 @@ impl ChatCommand {
         if let Some(endpoint) = non_empty_string(self.endpoint.clone()) {
             return endpoint.trim_end_matches('/').to_string();
         }
-        chat_endpoint_from_base_url(&config.url)
+        chat_endpoint_from_base_url(&request_base_url(&config.url))
     }
 
     fn resolve_auth_from_config(
@@ -311,6 +335,17 @@ impl ChatCommand {
             .map(|server_url| format!("{}/health", server_url.trim_end_matches('/')))
     }
 
+    fn openviking_session_messages_url(&self, endpoint: &str, session_id: &str) -> Option<String> {
+        let endpoint = endpoint.trim_end_matches('/');
+        endpoint.strip_suffix("/bot/v1").map(|server_url| {
+            format!(
+                "{}/api/v1/sessions/{}/messages/batch",
+                server_url.trim_end_matches('/'),
+                crate::commands::session::url_encode(session_id)
+            )
+        })
+    }
+
     async fn fetch_openviking_health(
         &self,
         client: &Client,
@@ -394,13 +429,18 @@ impl ChatCommand {
         message: &str,
         auth: &ChatAuth,
     ) -> Result<()> {
-        if self.stream {
+        let reply = if self.stream {
             self.send_message_stream(client, endpoint, message, auth)
-                .await
+                .await?
         } else {
             self.send_message_non_stream(client, endpoint, message, auth)
-                .await
+                .await?
+        };
+        if let Some(session_id) = &self.session {
+            self.record_exchange(client, endpoint, session_id, message, &reply, auth)
+                .await?;
         }
+        Ok(())
     }
 
     /// Send a single message with non-streaming response
@@ -410,7 +450,7 @@ impl ChatCommand {
         endpoint: &str,
         message: &str,
         auth: &ChatAuth,
-    ) -> Result<()> {
+    ) -> Result<String> {
         let url = format!("{}/chat", endpoint);
 
         let request = ChatRequest {
@@ -448,7 +488,7 @@ impl ChatCommand {
         // Print final response
         self.print_response(&chat_response.message);
 
-        Ok(())
+        Ok(chat_response.message)
     }
 
     /// Send a single message with streaming response
@@ -458,7 +498,7 @@ impl ChatCommand {
         endpoint: &str,
         message: &str,
         auth: &ChatAuth,
-    ) -> Result<()> {
+    ) -> Result<String> {
         let url = format!("{}/chat/stream", endpoint);
 
         let request = ChatRequest {
@@ -485,10 +525,15 @@ impl ChatCommand {
             return Err(api_error_from_body(&body, status));
         }
 
-        // Process the SSE stream
-        let mut response = response;
-        let mut buffer = String::new();
+        self.read_reply_stream(response).await
+    }
+
+    /// Consume the SSE stream of one reply, printing events as they arrive,
+    /// and return the final assistant message.
+    async fn read_reply_stream(&self, mut response: reqwest::Response) -> Result<String> {
+        let mut buffer = Vec::new();
         let mut final_message = String::new();
+        let mut streamed_message = String::new();
         let mut response_id: Option<String> = None;
 
         while let Some(chunk) = response
@@ -496,14 +541,7 @@ impl ChatCommand {
             .await
             .map_err(|e| Error::from_reqwest("Stream error", e))?
         {
-            let chunk_str = String::from_utf8_lossy(&chunk);
-            buffer.push_str(&chunk_str);
-
-            // Process complete lines from buffer
-            while let Some(newline_pos) = buffer.find('\n') {
-                let line = buffer[..newline_pos].trim_end().to_string();
-                buffer = buffer[newline_pos + 1..].to_string();
-
+            for line in take_stream_lines(&mut buffer, &chunk) {
                 if line.is_empty() {
                     continue;
                 }
@@ -511,6 +549,13 @@ impl ChatCommand {
                 // Parse SSE line: "data: {json}"
                 if let Some(data_str) = line.strip_prefix("data: ") {
                     if let Ok(event) = serde_json::from_str::<ChatStreamEvent>(data_str) {
+                        if self.stream_tokens && event.event == "content_delta" {
+                            if let Some(delta) = event.data.as_str() {
+                                self.print_content_delta(delta, streamed_message.is_empty());
+                                streamed_message.push_str(delta);
+                            }
+                            continue;
+                        }
                         self.print_stream_event(&event);
                         if event.event == "response" {
                             if let Some(msg) = event.data.as_str() {
@@ -532,6 +577,11 @@ impl ChatCommand {
             }
         }
 
+        if !streamed_message.is_empty() {
+            // The reply is already on screen; only finish its last line.
+            println!();
+        }
+
         if let Some(response_id) = response_id {
             eprintln!(
                 "{}",
@@ -542,12 +592,76 @@ impl ChatCommand {
             );
         }
 
-        // Print final response with markdown if we have it
-        if !final_message.is_empty() {
+        if !streamed_message.is_empty() {
+            if final_message.is_empty() {
+                final_message = streamed_message;
+            }
+        } else if !final_message.is_empty() {
+            // Print final response with markdown if we have it
             println!();
             self.print_response(&final_message);
         }
 
+        Ok(final_message)
+    }
+
+    /// Print one `content_delta` chunk without waiting for the full reply.
+    fn print_content_delta(&self, delta: &str, first: bool) {
+        if first {
+            println!();
+            if !self.no_format {
+                println!(
+                    "{}",
+                    theme::heading(copy(Language::current(), "VikingBot", "VikingBot")).bold()
+                );
+            }
+        }
+        print!("{delta}");
+        let _ = std::io::stdout().flush();
+    }
+
+    /// Append a completed exchange to the OpenViking session when running as
+    /// `ov session chat`.
+    async fn record_exchange(
+        &self,
+        client: &Client,
+        endpoint: &str,
+        session_id: &str,
+        message: &str,
+        reply: &str,
+        auth: &ChatAuth,
+    ) -> Result<()> {
+        if !self.record_session || reply.is_empty() {
+            return Ok(());
+        }
+        let url = self
+            .openviking_session_messages_url(endpoint, session_id)
+            .ok_or_else(|| {
+                Error::Client(format!(
+                    "Cannot derive the OpenViking server URL from chat endpoint {endpoint}"
+                ))
+            })?;
+        let body = serde_json::json!({
+            "messages": [
+                {"role": "user", "content": message},
+                {"role": "assistant", "content": reply},
+            ]
+        });
+
+        let response = self
+            .apply_auth_headers(client.post(&url).json(&body), auth)
+            .send()
+            .await
+            .map_err(|e| Error::from_reqwest("Failed to append messages to session", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response
+                .bytes()
+                .await
+                .map_err(|e| Error::from_reqwest("Failed to read error response", e))?;
+            return Err(api_error_from_body(&body, status));
+        }
         Ok(())
     }
 
@@ -616,7 +730,17 @@ impl ChatCommand {
                         .send_interactive_message(client, endpoint, input, &mut session_id, auth)
                         .await
                     {
-                        Ok(_) => {}
+                        Ok(reply) => {
+                            if let Some(session_id) = session_id.as_deref()
+                                && let Err(e) = self
+                                    .record_exchange(
+                                        client, endpoint, session_id, input, &reply, auth,
+                                    )
+                                    .await
+                            {
+                                print_chat_error(language, &e.to_string());
+                            }
+                        }
                         Err(e) => {
                             print_chat_error(language, &e.to_string());
                         }
@@ -661,7 +785,7 @@ impl ChatCommand {
         input: &str,
         session_id: &mut Option<String>,
         auth: &ChatAuth,
-    ) -> Result<()> {
+    ) -> Result<String> {
         if self.stream {
             self.send_interactive_message_stream(client, endpoint, input, session_id, auth)
                 .await
@@ -679,7 +803,7 @@ impl ChatCommand {
         input: &str,
         session_id: &mut Option<String>,
         auth: &ChatAuth,
-    ) -> Result<()> {
+    ) -> Result<String> {
         let url = format!("{}/chat", endpoint);
 
         let request = ChatRequest {
@@ -724,7 +848,7 @@ impl ChatCommand {
         self.print_response(&chat_response.message);
         println!();
 
-        Ok(())
+        Ok(chat_response.message)
     }
 
     /// Send a message in interactive mode (streaming)
@@ -735,7 +859,7 @@ impl ChatCommand {
         input: &str,
         session_id: &mut Option<String>,
         auth: &ChatAuth,
-    ) -> Result<()> {
+    ) -> Result<String> {
         let url = format!("{}/chat/stream", endpoint);
         let request_session_id = session_id
             .clone()
@@ -772,74 +896,14 @@ impl ChatCommand {
             .and_then(|value| value.to_str().ok())
             .and_then(non_empty_str)
             .map(ToString::to_string);
-        let mut response = response;
-        let mut buffer = String::new();
-        let mut final_message = String::new();
-        let mut response_id: Option<String> = None;
-
         if session_id.is_none() {
             *session_id = response_session_id.or(request_session_id);
         }
 
-        while let Some(chunk) = response
-            .chunk()
-            .await
-            .map_err(|e| Error::from_reqwest("Stream error", e))?
-        {
-            let chunk_str = String::from_utf8_lossy(&chunk);
-            buffer.push_str(&chunk_str);
-
-            // Process complete lines from buffer
-            while let Some(newline_pos) = buffer.find('\n') {
-                let line = buffer[..newline_pos].trim_end().to_string();
-                buffer = buffer[newline_pos + 1..].to_string();
-
-                if line.is_empty() {
-                    continue;
-                }
-
-                // Parse SSE line: "data: {json}"
-                if let Some(data_str) = line.strip_prefix("data: ") {
-                    if let Ok(event) = serde_json::from_str::<ChatStreamEvent>(data_str) {
-                        self.print_stream_event(&event);
-                        if event.event == "response" {
-                            if let Some(msg) = event.data.as_str() {
-                                final_message = msg.to_string();
-                            } else if let Some(obj) = event.data.as_object() {
-                                if let Some(msg) = obj.get("content").and_then(|m| m.as_str()) {
-                                    final_message = msg.to_string();
-                                }
-                                if let Some(rid) = obj.get("response_id").and_then(|r| r.as_str()) {
-                                    response_id = Some(rid.to_string());
-                                }
-                                if let Some(err) = obj.get("error").and_then(|e| e.as_str()) {
-                                    print_chat_error(Language::current(), err);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        if let Some(response_id) = response_id {
-            eprintln!(
-                "{}",
-                chat_detail_line(
-                    copy(Language::current(), "Response ID", "响应 ID"),
-                    muted_value(&response_id),
-                )
-            );
-        }
-
-        // Print final response with markdown
-        if !final_message.is_empty() {
-            println!();
-            self.print_response(&final_message);
-        }
+        let reply = self.read_reply_stream(response).await?;
         println!();
 
-        Ok(())
+        Ok(reply)
     }
 
     /// Print a single stream event as it arrives
@@ -946,6 +1010,18 @@ impl ChatCommand {
     }
 }
 
+/// Bot endpoint override from `VIKINGBOT_ENDPOINT`, or `OPENVIKING_URL` plus
+/// `/bot/v1`. `None` falls back to the configured server URL.
+pub fn endpoint_from_env() -> Option<String> {
+    if let Ok(env_endpoint) = std::env::var("VIKINGBOT_ENDPOINT") {
+        Some(env_endpoint)
+    } else if let Ok(config_url) = std::env::var("OPENVIKING_URL") {
+        Some(format!("{}/bot/v1", config_url))
+    } else {
+        None
+    }
+}
+
 fn render_chat_banner(
     endpoint: &str,
     session: Option<&str>,
@@ -1178,6 +1254,21 @@ fn render_markdown(text: &str) {
     skin.print_text(text);
 }
 
+/// Append `chunk` to `buffer` and take the complete lines out of it, without
+/// their line endings. Only whole lines are decoded, so a character split
+/// across chunks stays in `buffer` until its last bytes arrive.
+fn take_stream_lines(buffer: &mut Vec<u8>, chunk: &[u8]) -> Vec<String> {
+    buffer.extend_from_slice(chunk);
+    let Some(end) = buffer.iter().rposition(|&byte| byte == b'\n') else {
+        return Vec::new();
+    };
+    let complete: Vec<u8> = buffer.drain(..=end).collect();
+    String::from_utf8_lossy(&complete)
+        .lines()
+        .map(|line| line.trim_end().to_string())
+        .collect()
+}
+
 fn health_auth_mode(health: &OpenVikingHealth) -> &str {
     health.auth_mode.as_deref().unwrap_or_default().trim()
 }
@@ -1278,6 +1369,8 @@ mod tests {
             stream: true,
             no_format: false,
             no_history: false,
+            stream_tokens: false,
+            record_session: false,
         }
     }
 
@@ -1383,6 +1476,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn session_messages_url_is_derived_from_bot_proxy_endpoint() {
+        let command = command_with_api_key(None);
+
+        assert_eq!(
+            command
+                .openviking_session_messages_url("http://localhost:1933/bot/v1/", "team/a b")
+                .as_deref(),
+            Some("http://localhost:1933/api/v1/sessions/team%2Fa%20b/messages/batch")
+        );
+        assert_eq!(
+            command.openviking_session_messages_url("http://localhost:18790", "s"),
+            None
+        );
+    }
+
     #[test]
     fn chat_endpoint_is_derived_from_config_url_when_endpoint_is_not_explicit() {
         let command = command_with_api_key(None);
@@ -1593,4 +1702,24 @@ mod tests {
 
         output
     }
+
+    #[test]
+    fn stream_lines_keep_characters_split_across_chunks() {
+        let line = "data: {\"event\":\"content_delta\",\"data\":\"你好\"}\n".as_bytes();
+        let split = line.iter().position(|&byte| byte >= 0x80).unwrap() + 1;
+        let mut buffer = Vec::new();
+
+        assert!(take_stream_lines(&mut buffer, &line[..split]).is_empty());
+        assert_eq!(
+            take_stream_lines(&mut buffer, &line[split..]),
+            ["data: {\"event\":\"content_delta\",\"data\":\"你好\"}"]
+        );
+        assert!(buffer.is_empty());
+
+        assert_eq!(
+            take_stream_lines(&mut buffer, b"a\r\n\nb"),
+            ["a".to_string(), String::new()]
+        );
+        assert_eq!(buffer, b"b");
+    }
 }
@@ -0,0 +1,175 @@
+//! `ov clip`: capture the system clipboard as a resource.
+//!
+//! Text on the clipboard is added as a Markdown file and an image as a PNG,
+//! named after the local time of the capture (`clip-20260412-093015.md`) and
+//! placed under an inbox URI: `--inbox`, else `"clip_inbox"` in the config,
+//! else [`DEFAULT_INBOX`]. The inbox is created when missing, and the new URI
+//! is printed on its own so it can be pasted or piped straight on.
+
+use std::io::Cursor;
+
+use chrono::{DateTime, TimeZone};
+use serde_json::json;
+
+use crate::client::HttpClient;
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::output::{OutputFormat, output_success};
+
+/// Where captures go unless `--inbox` or the config says otherwise.
+pub const DEFAULT_INBOX: &str = "viking://resources/inbox";
+
+/// What the clipboard held.
+enum Capture {
+    Text(String),
+    Png(Vec<u8>),
+}
+
+impl Capture {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Text(_) => "text",
+            Self::Png(_) => "image",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Text(_) => "md",
+            Self::Png(_) => "png",
+        }
+    }
+
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Self::Text(text) => text.as_bytes(),
+            Self::Png(png) => png,
+        }
+    }
+}
+
+/// The inbox captures go to: `--inbox`, the config's `"clip_inbox"`, or
+/// [`DEFAULT_INBOX`].
+pub fn inbox(flag: Option<&str>, config: &Config) -> String {
+    flag.or(config.clip_inbox.as_deref())
+        .unwrap_or(DEFAULT_INBOX)
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// The file name a capture made at `time` is added as.
+fn clip_name<Tz: TimeZone>(time: &DateTime<Tz>, extension: &str) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    format!("clip-{}.{extension}", time.format("%Y%m%d-%H%M%S"))
+}
+
+fn unavailable(e: arboard::Error) -> Error {
+    Error::Client(format!("Could not read the clipboard: {e}"))
+}
+
+/// Read the clipboard, preferring text over an image.
+fn read_clipboard() -> Result<Capture> {
+    let mut clipboard = arboard::Clipboard::new().map_err(unavailable)?;
+    match clipboard.get_text() {
+        Ok(text) if !text.trim().is_empty() => return Ok(Capture::Text(text)),
+        Ok(_) | Err(arboard::Error::ContentNotAvailable) => {}
+        Err(e) => return Err(unavailable(e)),
+    }
+    let image = match clipboard.get_image() {
+        Ok(image) => image,
+        Err(arboard::Error::ContentNotAvailable) => {
+            return Err(Error::Client(
+                "The clipboard holds no text or image to capture".to_string(),
+            ));
+        }
+        Err(e) => return Err(unavailable(e)),
+    };
+    let rgba = image::RgbaImage::from_raw(
+        image.width as u32,
+        image.height as u32,
+        image.bytes.into_owned(),
+    )
+    .ok_or_else(|| Error::Client("The clipboard image has an unexpected size".to_string()))?;
+    let mut png = Vec::new();
+    rgba.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| Error::Client(format!("Failed to encode the clipboard image: {e}")))?;
+    Ok(Capture::Png(png))
+}
+
+pub async fn clip(
+    client: &HttpClient,
+    inbox: &str,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let capture = read_clipboard()?;
+    let name = clip_name(&chrono::Local::now(), capture.extension());
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join(&name);
+    std::fs::write(&path, capture.bytes())?;
+
+    let result = client
+        .add_resource(
+            &path.to_string_lossy(),
+            None,
+            None,
+            Some(inbox.to_string()),
+            "",
+            "",
+            false,
+            None,
+            false,
+            None,
+            None,
+            None,
+            true,
+            0.0,
+            None,
+            false,
+            false,
+        )
+        .await?;
+    let uri = result["root_uri"]
+        .as_str()
+        .map_or_else(|| format!("{inbox}/{name}"), str::to_string);
+
+    if matches!(format, OutputFormat::Table) {
+        println!("{uri}");
+    } else {
+        output_success(
+            json!({
+                "uri": uri,
+                "kind": capture.kind(),
+                "bytes": capture.bytes().len(),
+            }),
+            format,
+            compact,
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_are_named_by_time_under_the_inbox() {
+        let time = chrono::FixedOffset::east_opt(8 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2026, 4, 12, 9, 30, 15)
+            .unwrap();
+        assert_eq!(clip_name(&time, "md"), "clip-20260412-093015.md");
+
+        let mut config = Config::default();
+        assert_eq!(inbox(None, &config), DEFAULT_INBOX);
+        config.clip_inbox = Some("viking://resources/capture/".to_string());
+        assert_eq!(inbox(None, &config), "viking://resources/capture");
+        assert_eq!(
+            inbox(Some("viking://resources/desk"), &config),
+            "viking://resources/desk"
+        );
+    }
+}
@@ -0,0 +1,191 @@
+//! `ov completions <shell>`: a shell script that completes `ov` by calling back
+//! into it, so completions always match the installed CLI.
+//!
+//! Besides commands and options, saved config names complete wherever a
+//! config is named, and viking:// URIs complete from the ones the `ov daemon`
+//! for the active server has recently read, when one is running.
+
+use std::ffi::OsStr;
+use std::io::Write;
+
+use clap::{Arg, Command};
+use clap_complete::env::{Bash, EnvCompleter, Fish, Powershell, Zsh};
+use clap_complete::{ArgValueCandidates, ArgValueCompleter, CompletionCandidate};
+
+use crate::config::Config;
+use crate::config_wizard::ConfigStore;
+use crate::daemon;
+use crate::error::{Error, Result};
+
+/// Environment variable the generated scripts set when asking `ov` for candidates.
+pub const COMPLETE_ENV: &str = "OV_COMPLETE";
+
+/// Print the completion script for `shell`.
+pub fn print(shell: &str) -> Result<()> {
+    let completer: &dyn EnvCompleter = match shell {
+        "bash" => &Bash,
+        "zsh" => &Zsh,
+        "fish" => &Fish,
+        "powershell" => &Powershell,
+        other => return Err(Error::Client(format!("Unsupported shell '{other}'"))),
+    };
+    let mut script = Vec::new();
+    completer.write_registration(COMPLETE_ENV, "ov", "ov", &completer_path(), &mut script)?;
+    std::io::stdout().write_all(&script)?;
+    Ok(())
+}
+
+/// How the script should invoke this binary: as typed when it was found on
+/// `PATH`, so upgrades keep working, otherwise as an absolute path.
+fn completer_path() -> String {
+    let invoked = std::env::args_os().next().unwrap_or_else(|| "ov".into());
+    let invoked = std::path::PathBuf::from(invoked);
+    let invoked = match std::env::current_dir() {
+        Ok(dir) if invoked.components().count() > 1 => dir.join(invoked),
+        _ => invoked,
+    };
+    invoked.to_string_lossy().into_owned()
+}
+
+/// What an argument's values complete to, beyond clap's own possible values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dynamic {
+    Profile,
+    Uri,
+}
+
+/// Saved config arguments are `--from`/`--to` of `ov sync` and the name given
+/// to `ov config switch|edit|delete`; URI arguments are named `uri`, alone or
+/// as in `from-uri` or `task-or-uri`, except ones that also take local paths.
+fn dynamic_values(path: &[String], arg: &Arg) -> Option<Dynamic> {
+    let value_names: Vec<&str> = arg
+        .get_value_names()
+        .into_iter()
+        .flatten()
+        .map(|name| name.as_str())
+        .collect();
+    if value_names.contains(&"profile")
+        || (matches!(path, [config, action]
+            if config == "config" && matches!(action.as_str(), "switch" | "edit" | "delete"))
+            && arg.get_id() == "name")
+    {
+        return Some(Dynamic::Profile);
+    }
+    let words = || value_names.iter().flat_map(|name| name.split(['|', '-']));
+    (words().any(|word| word == "uri") && !words().any(|word| word == "path"))
+        .then_some(Dynamic::Uri)
+}
+
+/// `command` with completers attached to its config and URI arguments, for
+/// the completion engine only; parsing never sees them.
+pub fn with_dynamic_values(command: Command) -> Command {
+    attach(command, &[])
+}
+
+fn attach(command: Command, path: &[String]) -> Command {
+    command
+        .mut_args(|arg| match dynamic_values(path, &arg) {
+            Some(Dynamic::Profile) => arg.add(ArgValueCandidates::new(saved_configs)),
+            Some(Dynamic::Uri) => arg.add(ArgValueCompleter::new(recent_uris)),
+            None => arg,
+        })
+        .mut_subcommands(|subcommand| {
+            let mut subpath = path.to_vec();
+            subpath.push(subcommand.get_name().to_string());
+            attach(subcommand, &subpath)
+        })
+}
+
+fn saved_configs() -> Vec<CompletionCandidate> {
+    let Ok(report) = ConfigStore::new().and_then(|store| store.list_configs_report()) else {
+        return Vec::new();
+    };
+    let mut configs = report.configs;
+    configs.sort_by(|a, b| a.name.cmp(&b.name));
+    configs
+        .into_iter()
+        .map(|entry| CompletionCandidate::new(entry.name).help(Some(entry.config.url.into())))
+        .collect()
+}
+
+fn recent_uris(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(current) = current.to_str() else {
+        return Vec::new();
+    };
+    let recent = Config::load_default()
+        .ok()
+        .and_then(|config| daemon::running_socket(&config.url))
+        .and_then(|socket| daemon::recent_uris(&socket).ok())
+        .unwrap_or_default();
+    matching_uris(current, recent)
+        .into_iter()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Recent URIs that extend what has been typed, keeping their recency order.
+fn matching_uris(current: &str, recent: Vec<String>) -> Vec<String> {
+    recent
+        .into_iter()
+        .filter(|uri| uri.starts_with(current))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind(path: &[&str], arg: Arg) -> Option<Dynamic> {
+        let path: Vec<String> = path.iter().map(|part| part.to_string()).collect();
+        dynamic_values(&path, &arg)
+    }
+
+    #[test]
+    fn config_names_and_uri_arguments_get_dynamic_values() {
+        let named =
+            |id: &'static str, value_name: &'static str| Arg::new(id).value_name(value_name);
+
+        assert_eq!(
+            kind(&["sync"], named("from", "profile")),
+            Some(Dynamic::Profile)
+        );
+        assert_eq!(
+            kind(&["config", "switch"], named("name", "name")),
+            Some(Dynamic::Profile)
+        );
+        assert_eq!(kind(&["skills", "show"], named("name", "name")), None);
+        assert_eq!(kind(&["read"], named("uri", "uri")), Some(Dynamic::Uri));
+        assert_eq!(
+            kind(&["task", "wait"], named("target", "task-or-uri")),
+            Some(Dynamic::Uri)
+        );
+        assert_eq!(kind(&["get"], named("source", "path|uri")), None);
+        assert_eq!(kind(&["find"], named("query", "query")), None);
+    }
+
+    #[test]
+    fn completers_keep_the_command_tree_valid() {
+        use clap::CommandFactory;
+
+        // Attaching must not reorder arguments, which would shift positionals.
+        with_dynamic_values(crate::Cli::command()).debug_assert();
+    }
+
+    #[test]
+    fn uri_completion_keeps_recent_uris_under_the_typed_prefix() {
+        let recent = vec![
+            "viking://resources/docs/b.md".to_string(),
+            "viking://user/memories".to_string(),
+            "viking://resources/docs/a.md".to_string(),
+        ];
+        assert_eq!(
+            matching_uris("viking://res", recent.clone()),
+            vec![
+                "viking://resources/docs/b.md",
+                "viking://resources/docs/a.md"
+            ]
+        );
+        assert_eq!(matching_uris("", recent.clone()).len(), 3);
+        assert!(matching_uris("viking://agent", recent).is_empty());
+    }
+}
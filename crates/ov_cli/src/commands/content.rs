@@ -1,19 +1,31 @@
 use crate::client::HttpClient;
-use crate::error::Result;
+use crate::error::{ApiErrorKind, Error, Result};
 use crate::output::OutputFormat;
+use futures::StreamExt;
 use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
 use std::collections::BTreeSet;
 use std::fs::File;
-use std::io::Write;
+use std::io::{IsTerminal, Write};
 use std::path::Path;
+use termimad::{FmtText, MadSkin};
 
 pub async fn read(
     client: &HttpClient,
     uri: &str,
+    render: bool,
     output_format: OutputFormat,
     compact: bool,
 ) -> Result<()> {
     let content = client.read_profiled(uri).await?;
+    if render
+        && matches!(output_format, OutputFormat::Table)
+        && std::io::stdout().is_terminal()
+        && let Some(rendered) = render_markdown_result(&content)
+    {
+        crate::pager::page_or_print(&rendered);
+        return Ok(());
+    }
     output_content_result(content, output_format, compact)
 }
 
@@ -37,6 +49,131 @@ pub async fn overview(
     output_content_result(content, output_format, compact)
 }
 
+/// How many URIs `read_many` fetches at once.
+const READ_MANY_CONCURRENCY: usize = 8;
+
+/// The content level `ov read`, `ov abstract` and `ov overview` fetch.
+#[derive(Debug, Clone, Copy)]
+pub enum ContentLevel {
+    Read,
+    Abstract,
+    Overview,
+}
+
+impl ContentLevel {
+    fn command(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Abstract => "abstract",
+            Self::Overview => "overview",
+        }
+    }
+
+    async fn fetch(self, client: &HttpClient, uri: &str) -> Result<String> {
+        match self {
+            Self::Read => client.read(uri).await,
+            Self::Abstract => client.abstract_content(uri).await,
+            Self::Overview => client.overview(uri).await,
+        }
+    }
+}
+
+/// Merge positional URIs with those listed in `--from-file` (one per line,
+/// `-` for stdin), skipping blank lines and `#` comments.
+pub fn collect_uris(mut uris: Vec<String>, from_file: Option<&str>) -> Result<Vec<String>> {
+    if let Some(file) = from_file {
+        let text = if file == "-" {
+            std::io::read_to_string(std::io::stdin())?
+        } else {
+            std::fs::read_to_string(file)
+                .map_err(|e| Error::Client(format!("Failed to read --from-file: {e}")))?
+        };
+        uris.extend(
+            text.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_string),
+        );
+    }
+    if uris.is_empty() {
+        return Err(Error::Client("No URIs to read".to_string()));
+    }
+    Ok(uris)
+}
+
+/// Fetch several URIs concurrently and print them in the given order as they
+/// arrive: under `==> uri <==` headers, one NDJSON record each, or one JSON
+/// array. A failed URI is reported in place and the rest are still printed.
+pub async fn read_many(
+    client: &HttpClient,
+    level: ContentLevel,
+    uris: &[String],
+    render: bool,
+    output_format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let render = render && std::io::stdout().is_terminal();
+    let mut reads = futures::stream::iter(uris)
+        .map(|uri| async move { (uri, level.fetch(client, uri).await) })
+        .buffered(READ_MANY_CONCURRENCY);
+    let mut records = Vec::new();
+    let mut failed = 0;
+    let mut first = true;
+    while let Some((uri, content)) = reads.next().await {
+        failed += usize::from(content.is_err());
+        match output_format {
+            OutputFormat::Table => {
+                if !first {
+                    println!();
+                }
+                println!("==> {uri} <==");
+                match content {
+                    Ok(content) if render => {
+                        let rendered = render_markdown_result(&Value::String(content));
+                        println!("{}", rendered.unwrap_or_default());
+                    }
+                    Ok(content) => println!("{}", content.trim_end()),
+                    Err(error) => {
+                        eprintln!("ov {}: {uri}: {}", level.command(), error_message(&error))
+                    }
+                }
+            }
+            OutputFormat::Ndjson => {
+                crate::output::output_success(content_record(uri, content), output_format, compact)
+            }
+            OutputFormat::Json => records.push(content_record(uri, content)),
+        }
+        first = false;
+    }
+    if matches!(output_format, OutputFormat::Json) {
+        crate::output::output_success(records, output_format, compact);
+    }
+    if failed > 0 {
+        // Each failure was already printed next to its URI.
+        return Err(Error::AlreadyReported);
+    }
+    Ok(())
+}
+
+fn content_record(uri: &str, content: Result<String>) -> Value {
+    match content {
+        Ok(content) => json!({"uri": uri, "content": content}),
+        Err(error) => json!({
+            "uri": uri,
+            "error": {"code": error.code(), "message": error_message(&error)},
+        }),
+    }
+}
+
+fn error_message(error: &Error) -> String {
+    match error {
+        Error::Api { message, .. } => message.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Write `content` to `uri` in `mode`. A file that does not exist yet is
+/// created, along with the directories above it.
 pub async fn write(
     client: &HttpClient,
     uri: &str,
@@ -47,7 +184,92 @@ pub async fn write(
     output_format: OutputFormat,
     compact: bool,
 ) -> Result<()> {
-    let result = client.write(uri, content, mode, wait, timeout).await?;
+    let result = match client.write(uri, content, mode, wait, timeout).await {
+        Err(e) if mode != "create" && e.api_kind() == Some(ApiErrorKind::NotFound) => {
+            create_parents(client, uri).await?;
+            client.write(uri, content, "create", wait, timeout).await?
+        }
+        result => result?,
+    };
+    crate::output::output_success(result, output_format, compact);
+    Ok(())
+}
+
+/// The directories above `uri` below its scope root, outermost first:
+/// `viking://resources/logs` and `viking://resources/logs/2026` for
+/// `viking://resources/logs/2026/run.md`.
+fn parent_dirs(uri: &str) -> Vec<String> {
+    let Some(path) = uri.strip_prefix("viking://") else {
+        return Vec::new();
+    };
+    let scope_end = path.find('/').unwrap_or(path.len());
+    path.match_indices('/')
+        .filter(|&(end, _)| end > scope_end)
+        .map(|(end, _)| format!("viking://{}", &path[..end]))
+        .collect()
+}
+
+async fn create_parents(client: &HttpClient, uri: &str) -> Result<()> {
+    for dir in parent_dirs(uri) {
+        match client.mkdir(&dir, None).await {
+            Err(e) if e.api_kind() != Some(ApiErrorKind::Conflict) => return Err(e),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Open `uri` in `$EDITOR` and write the saved content back in its place.
+///
+/// Unless `force` is set, the content is read again before writing and the
+/// write is refused when its SHA-256 no longer matches what was opened; the
+/// edits are then left in the temporary file instead of overwriting someone
+/// else's change.
+pub async fn edit(
+    client: &HttpClient,
+    uri: &str,
+    force: bool,
+    wait: bool,
+    timeout: Option<f64>,
+    output_format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let original = client.read(uri).await?;
+    let name = uri
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|name| !name.is_empty() && !name.contains(':'))
+        .unwrap_or("content.md");
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join(name);
+    std::fs::write(&path, &original)?;
+    crate::editor::edit(&path)?;
+    let edited = std::fs::read_to_string(&path)?;
+    if edited == original {
+        if matches!(output_format, OutputFormat::Table) {
+            eprintln!("No changes to {uri}; nothing written.");
+        } else {
+            crate::output::output_success(
+                json!({"uri": uri, "changed": false}),
+                output_format,
+                compact,
+            );
+        }
+        return Ok(());
+    }
+
+    if !force {
+        let current = client.read(uri).await?;
+        if Sha256::digest(&current) != Sha256::digest(&original) {
+            let kept = dir.keep().join(name);
+            return Err(Error::Client(format!(
+                "{uri} changed on the server while it was open; your edits are kept in {}. Merge them and run ov write {uri} --from-file <file>, or rerun ov edit with --force to overwrite",
+                kept.display()
+            )));
+        }
+    }
+    let result = client.write(uri, &edited, "replace", wait, timeout).await?;
     crate::output::output_success(result, output_format, compact);
     Ok(())
 }
@@ -110,12 +332,14 @@ pub async fn get(client: &HttpClient, uri: &str, local_path: &str) -> Result<()>
 
 fn output_content_result(result: Value, output_format: OutputFormat, compact: bool) -> Result<()> {
     match output_format {
-        OutputFormat::Json => crate::output::output_success(result, output_format, compact),
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            crate::output::output_success(result, output_format, compact)
+        }
         OutputFormat::Table => {
             if let Some(rendered) = crate::output::render_profiled_scalar_result(&result) {
-                println!("{}", rendered);
+                crate::pager::page_or_print(&rendered);
             } else if let Some(content) = result.as_str() {
-                println!("{}", content);
+                crate::pager::page_or_print(content);
             } else {
                 crate::output::output_success(result, output_format, compact);
             }
@@ -124,9 +348,32 @@ fn output_content_result(result: Value, output_format: OutputFormat, compact: bo
     Ok(())
 }
 
+/// Style markdown content for the terminal, keeping any `--profile` section
+/// below it. Returns `None` when the payload is not text.
+fn render_markdown_result(result: &Value) -> Option<String> {
+    let text = result
+        .as_str()
+        .or_else(|| result.get("result").and_then(Value::as_str))?;
+    let skin = if colored::control::SHOULD_COLORIZE.should_colorize() {
+        MadSkin::default()
+    } else {
+        MadSkin::no_style()
+    };
+    let width = crossterm::terminal::size()
+        .map(|(columns, _)| usize::from(columns))
+        .unwrap_or(80);
+    let rendered = FmtText::from(&skin, text, Some(width)).to_string();
+    Some(crate::output::append_profile_to_rendered(
+        rendered.trim_end().to_string(),
+        result,
+    ))
+}
+
 fn output_set_tags_result(result: Value, output_format: OutputFormat, compact: bool) {
     match output_format {
-        OutputFormat::Json => crate::output::output_success(result, output_format, compact),
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            crate::output::output_success(result, output_format, compact)
+        }
         OutputFormat::Table => {
             if let Some(rendered) = render_set_tags_result_for_table(&result) {
                 println!("{rendered}");
@@ -232,6 +479,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn render_markdown_styles_content_and_keeps_profile() {
+        let result = json!({
+            "result": "# Title\n\n- first\n- second\n\n```\nlet x = 1;\n```",
+            "profile": ["line one"]
+        });
+
+        let rendered = super::render_markdown_result(&result)
+            .map(|value| strip_ansi(&value))
+            .expect("markdown text");
+
+        assert!(!rendered.contains("# Title"));
+        assert!(rendered.contains("Title"));
+        assert!(rendered.contains("first\n- second"));
+        assert!(rendered.contains("let x = 1;"));
+        assert!(rendered.contains("profile\nline one"));
+        assert!(super::render_markdown_result(&json!({"result": {"a": 1}})).is_none());
+    }
+
+    #[test]
+    fn collect_uris_appends_from_file_skipping_blanks_and_comments() {
+        let dir = tempfile::tempdir().expect("tempdir should be created");
+        let path = dir.path().join("uris.txt");
+        std::fs::write(&path, "viking://b\n\n# skipped\n  viking://c  \n").unwrap();
+
+        let uris =
+            super::collect_uris(vec!["viking://a".to_string()], path.to_str()).expect("uris");
+
+        assert_eq!(uris, ["viking://a", "viking://b", "viking://c"]);
+        assert!(super::collect_uris(Vec::new(), None).is_err());
+    }
+
+    #[test]
+    fn content_record_reports_content_or_error_per_uri() {
+        let ok = super::content_record("viking://a", Ok("text".to_string()));
+        assert_eq!(ok, json!({"uri": "viking://a", "content": "text"}));
+
+        let missing = crate::error::Error::api_with_status("Resource not found", 404);
+        let failed = super::content_record("viking://b", Err(missing));
+        assert_eq!(
+            failed,
+            json!({
+                "uri": "viking://b",
+                "error": {"code": "NOT_FOUND", "message": "Resource not found"},
+            })
+        );
+    }
+
     #[test]
     fn table_output_renders_set_tags_uri_and_updated_uris() {
         let result = json!({
@@ -272,4 +567,14 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn missing_files_get_their_parents_below_the_scope_root() {
+        assert_eq!(
+            super::parent_dirs("viking://resources/logs/2026/run.md"),
+            vec!["viking://resources/logs", "viking://resources/logs/2026"]
+        );
+        assert!(super::parent_dirs("viking://resources/run.md").is_empty());
+        assert!(super::parent_dirs("resources/run.md").is_empty());
+    }
 }
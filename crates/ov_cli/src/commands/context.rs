@@ -0,0 +1,432 @@
+//! `ov context assemble`: one call from a question to a context block an agent
+//! can inject as is.
+//!
+//! Results of a `find` are packed greedily in rank order, one layer at a time:
+//! first every abstract that fits (L0), then directory overviews (L1) and file
+//! contents (L2) replace those abstracts, best result first, while the budget
+//! allows. Breadth comes before depth, so a long top document cannot crowd
+//! out every other source. Token counts are estimates, made the way the
+//! server estimates them.
+
+use futures::StreamExt;
+use serde_json::{Value, json};
+
+use super::search::search_result_items;
+use super::tokens::estimate_tokens;
+use crate::client::{HttpClient, SearchScoring};
+use crate::error::{Error, Result};
+use crate::output::{OutputFormat, output_success};
+
+/// How many overviews and contents are fetched at once.
+const FETCH_CONCURRENCY: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Layer {
+    Abstract,
+    Overview,
+    Content,
+}
+
+impl Layer {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "l0" => Some(Self::Abstract),
+            "l1" => Some(Self::Overview),
+            "l2" => Some(Self::Content),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Abstract => "l0",
+            Self::Overview => "l1",
+            Self::Content => "l2",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Abstract => "abstract",
+            Self::Overview => "overview",
+            Self::Content => "content",
+        }
+    }
+}
+
+pub struct AssembleOptions<'a> {
+    pub query: &'a str,
+    pub uri: &'a str,
+    pub budget: usize,
+    pub layers: Vec<Layer>,
+    pub node_limit: i32,
+    pub threshold: Option<f64>,
+    /// Retrieve with context-aware search in this session.
+    pub session_id: Option<&'a str>,
+    /// Print an OpenAI-style messages array instead of the bare block.
+    pub messages: bool,
+}
+
+/// One search hit: a file, or a directory reported through its abstract or
+/// overview record.
+#[derive(Debug, Clone, PartialEq)]
+struct Source {
+    uri: String,
+    is_file: bool,
+    score: f64,
+    context_type: String,
+    abstract_text: String,
+    /// The directory's overview or the file's content, when fetched.
+    detail: Option<String>,
+}
+
+impl Source {
+    fn text(&self, layer: Layer) -> Option<&str> {
+        let text = match layer {
+            Layer::Abstract => Some(self.abstract_text.as_str()),
+            Layer::Overview if !self.is_file => self.detail.as_deref(),
+            Layer::Content if self.is_file => self.detail.as_deref(),
+            _ => None,
+        }?;
+        Some(text.trim()).filter(|text| !text.is_empty())
+    }
+}
+
+fn section(source: &Source, layer: Layer, text: &str) -> String {
+    format!("### {} ({})\n{text}\n", source.uri, layer.label())
+}
+
+/// Search hits as sources, best first, one per file or directory. Directory
+/// hits name their `.abstract.md` or `.overview.md` record; the directory
+/// itself is what gets its overview read.
+fn sources(result: &Value) -> Vec<Source> {
+    let mut sources: Vec<Source> = Vec::new();
+    let items = search_result_items(result)
+        .map(|(items, _)| items)
+        .unwrap_or_default();
+    for item in items {
+        let Some(uri) = item["uri"].as_str() else {
+            continue;
+        };
+        let directory = uri
+            .strip_suffix("/.abstract.md")
+            .or_else(|| uri.strip_suffix("/.overview.md"));
+        let uri = directory.unwrap_or(uri).to_string();
+        if sources.iter().any(|known| known.uri == uri) {
+            continue;
+        }
+        let is_file = directory.is_none() && item["level"].as_i64().unwrap_or(2) == 2;
+        sources.push(Source {
+            uri,
+            is_file,
+            score: item["score"].as_f64().unwrap_or(0.0),
+            context_type: item["context_type"].as_str().unwrap_or("").to_string(),
+            abstract_text: item["abstract"].as_str().unwrap_or("").to_string(),
+            detail: (!is_file)
+                .then(|| item["overview"].as_str().map(str::to_string))
+                .flatten(),
+        });
+    }
+    sources.sort_by(|a, b| b.score.total_cmp(&a.score));
+    sources
+}
+
+/// The layer and token cost each source is packed at, `None` for sources
+/// left out. Each pass over `layers` goes in rank order and upgrades a
+/// source only when the difference still fits.
+fn pack(sources: &[Source], layers: &[Layer], budget: usize) -> Vec<Option<(Layer, usize)>> {
+    let mut layers = layers.to_vec();
+    layers.sort();
+    layers.dedup();
+    let mut packed: Vec<Option<(Layer, usize)>> = vec![None; sources.len()];
+    let mut used = 0;
+    for layer in layers {
+        for (source, slot) in sources.iter().zip(packed.iter_mut()) {
+            let Some(text) = source.text(layer) else {
+                continue;
+            };
+            let cost = estimate_tokens(&section(source, layer, text));
+            let current = slot.map_or(0, |(_, tokens)| tokens);
+            if used - current + cost <= budget {
+                used = used - current + cost;
+                *slot = Some((layer, cost));
+            }
+        }
+    }
+    packed
+}
+
+/// Fetch the overview or content each source could be upgraded to.
+async fn fetch_details(
+    client: &HttpClient,
+    sources: &mut [Source],
+    layers: &[Layer],
+) -> Result<()> {
+    let wanted: Vec<(usize, String, bool)> = sources
+        .iter()
+        .enumerate()
+        .filter(|(_, source)| source.detail.is_none())
+        .filter(|(_, source)| {
+            layers.contains(if source.is_file {
+                &Layer::Content
+            } else {
+                &Layer::Overview
+            })
+        })
+        .map(|(index, source)| (index, source.uri.clone(), source.is_file))
+        .collect();
+    let fetched: Vec<(usize, Result<String>)> = futures::stream::iter(wanted)
+        .map(|(index, uri, is_file)| async move {
+            let detail = if is_file {
+                client.read(&uri).await
+            } else {
+                client.overview(&uri).await
+            };
+            (index, detail)
+        })
+        .buffer_unordered(FETCH_CONCURRENCY)
+        .collect()
+        .await;
+    for (index, detail) in fetched {
+        match detail {
+            Ok(detail) => sources[index].detail = Some(detail),
+            // The source still counts at its abstract; only a lost server is fatal.
+            Err(e @ (Error::Network(_) | Error::Interrupted)) => return Err(e),
+            Err(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// A packed context block and what went into it.
+pub struct Assembled {
+    pub block: String,
+    pub used_tokens: usize,
+    /// Retrieved sources, packed or not.
+    pub retrieved: usize,
+    /// `{uri, level, tokens, score, context_type}` of each packed source, best first.
+    pub sources: Vec<Value>,
+    /// URIs of the sources left out for lack of budget.
+    pub omitted: Vec<Value>,
+}
+
+impl Assembled {
+    /// A system message carrying the block, then `query` from the user.
+    pub fn messages(&self, query: &str) -> Value {
+        json!([
+            {
+                "role": "system",
+                "content": format!(
+                    "Use the following context to answer. Cite the viking:// URIs you rely on.\n\n{}",
+                    self.block
+                ),
+            },
+            { "role": "user", "content": query },
+        ])
+    }
+}
+
+/// Retrieve sources for `options.query` and pack them into a block. With a
+/// session, retrieval is context-aware `search` rather than `find`.
+pub async fn gather(client: &HttpClient, options: &AssembleOptions<'_>) -> Result<Assembled> {
+    if options.layers.is_empty() {
+        return Err(Error::Client(
+            "--levels needs at least one of l0, l1, l2".to_string(),
+        ));
+    }
+    let result = match options.session_id {
+        Some(session_id) => {
+            client
+                .search(
+                    options.query.to_string(),
+                    options.uri.to_string(),
+                    None,
+                    Some(session_id.to_string()),
+                    options.node_limit,
+                    options.threshold,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    &SearchScoring::default(),
+                )
+                .await?
+        }
+        None => {
+            client
+                .find(
+                    options.query.to_string(),
+                    options.uri.to_string(),
+                    None,
+                    options.node_limit,
+                    options.threshold,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    &SearchScoring::default(),
+                )
+                .await?
+        }
+    };
+    let mut sources = sources(&result);
+    fetch_details(client, &mut sources, &options.layers).await?;
+    let packed = pack(&sources, &options.layers, options.budget);
+
+    let mut assembled = Assembled {
+        block: format!("Context from OpenViking for: {}\n", options.query),
+        used_tokens: 0,
+        retrieved: sources.len(),
+        sources: Vec::new(),
+        omitted: Vec::new(),
+    };
+    for (source, slot) in sources.iter().zip(&packed) {
+        let Some((layer, tokens)) = *slot else {
+            assembled.omitted.push(json!(source.uri));
+            continue;
+        };
+        assembled.block.push('\n');
+        assembled
+            .block
+            .push_str(&section(source, layer, source.text(layer).unwrap_or("")));
+        assembled.used_tokens += tokens;
+        assembled.sources.push(json!({
+            "uri": source.uri,
+            "level": layer.name(),
+            "tokens": tokens,
+            "score": source.score,
+            "context_type": source.context_type,
+        }));
+    }
+    Ok(assembled)
+}
+
+pub async fn assemble(
+    client: &HttpClient,
+    options: AssembleOptions<'_>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let assembled = gather(client, &options).await?;
+    eprintln!(
+        "Packed {} of {} source(s) into ~{} of {} tokens",
+        assembled.sources.len(),
+        assembled.retrieved,
+        assembled.used_tokens,
+        options.budget
+    );
+
+    if options.messages {
+        let messages = assembled.messages(options.query);
+        let rendered = if compact {
+            serde_json::to_string(&messages)?
+        } else {
+            serde_json::to_string_pretty(&messages)?
+        };
+        println!("{rendered}");
+    } else if format.is_json() {
+        output_success(
+            json!({
+                "query": options.query,
+                "budget": options.budget,
+                "used_tokens": assembled.used_tokens,
+                "sources": assembled.sources,
+                "omitted": assembled.omitted,
+                "context": assembled.block,
+            }),
+            format,
+            compact,
+        );
+    } else {
+        print!("{}", assembled.block);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(uri: &str, is_file: bool, abstract_text: &str, detail: &str) -> Source {
+        Source {
+            uri: uri.to_string(),
+            is_file,
+            score: 0.0,
+            context_type: "resource".to_string(),
+            abstract_text: abstract_text.to_string(),
+            detail: Some(detail.to_string()),
+        }
+    }
+
+    #[test]
+    fn sources_merge_directory_records_and_rank_by_score() {
+        let result = json!({
+            "memories": [
+                {"uri": "viking://user/memories/.overview.md", "level": 1, "score": 0.4,
+                 "abstract": "mem", "overview": "memory overview", "context_type": "memory"}
+            ],
+            "resources": [
+                {"uri": "viking://resources/docs/.abstract.md", "level": 0, "score": 0.5,
+                 "abstract": "docs"},
+                {"uri": "viking://resources/docs/a.md", "level": 2, "score": 0.9,
+                 "abstract": "a"},
+                {"uri": "viking://resources/docs/.overview.md", "level": 1, "score": 0.3}
+            ]
+        });
+        let sources = sources(&result);
+        let uris: Vec<&str> = sources.iter().map(|source| source.uri.as_str()).collect();
+        assert_eq!(
+            uris,
+            vec![
+                "viking://resources/docs/a.md",
+                "viking://resources/docs",
+                "viking://user/memories"
+            ]
+        );
+        assert!(sources[0].is_file);
+        assert!(!sources[1].is_file);
+        assert_eq!(sources[2].detail.as_deref(), Some("memory overview"));
+    }
+
+    #[test]
+    fn packing_covers_every_abstract_before_going_deeper() {
+        let long = "x".repeat(400);
+        let sources = vec![
+            source("viking://r/a.md", true, "first abstract", &long),
+            source("viking://r/dir", false, "second abstract", "short overview"),
+            source("viking://r/b.md", true, "c", "tiny"),
+        ];
+        let abstracts = estimate_tokens(&section(&sources[0], Layer::Abstract, "first abstract"))
+            + estimate_tokens(&section(&sources[1], Layer::Abstract, "second abstract"))
+            + estimate_tokens(&section(&sources[2], Layer::Abstract, "c"));
+        let all = [Layer::Abstract, Layer::Overview, Layer::Content];
+
+        // Room for the abstracts and small upgrades, but not the long file.
+        let packed = pack(&sources, &all, abstracts + 10);
+        let layers: Vec<Option<Layer>> = packed.iter().map(|slot| slot.map(|(l, _)| l)).collect();
+        assert_eq!(
+            layers,
+            vec![
+                Some(Layer::Abstract),
+                Some(Layer::Overview),
+                Some(Layer::Content)
+            ]
+        );
+        let used: usize = packed.iter().flatten().map(|(_, tokens)| tokens).sum();
+        assert!(used <= abstracts + 10);
+
+        // A source that does not fit is skipped, not the end of packing.
+        let third = estimate_tokens(&section(&sources[2], Layer::Abstract, "c"));
+        let packed = pack(&sources, &[Layer::Abstract], third);
+        assert_eq!(packed, vec![None, None, Some((Layer::Abstract, third))]);
+
+        // Without L0, sources go straight to their overview or content.
+        let packed = pack(&sources, &[Layer::Content], 1_000);
+        assert_eq!(packed[0].map(|(layer, _)| layer), Some(Layer::Content));
+        assert_eq!(packed[1], None);
+    }
+}
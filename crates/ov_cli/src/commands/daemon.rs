@@ -0,0 +1,162 @@
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde_json::{Value, json};
+
+use crate::config::Config;
+use crate::daemon;
+use crate::error::{Error, Result};
+use crate::output::{OutputFormat, output_success};
+
+/// How long `start` and `stop` wait for the socket to appear or go away.
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Run the daemon in the foreground.
+pub async fn run(
+    config: &Config,
+    ttl: u64,
+    max_entries: usize,
+    metrics: Option<&str>,
+) -> Result<()> {
+    let metrics = metrics.map(daemon::parse_metrics_addr).transpose()?;
+    let socket = daemon::socket_path(&config.url)?;
+    daemon::serve(
+        config,
+        &socket,
+        Duration::from_secs(ttl),
+        max_entries,
+        metrics,
+    )
+    .await
+}
+
+/// Start `ov daemon run` in the background with the same arguments and wait
+/// until it accepts connections.
+pub async fn start(
+    config: &Config,
+    metrics: Option<&str>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    if let Some(metrics) = metrics {
+        daemon::parse_metrics_addr(metrics)?;
+    }
+    let socket = daemon::socket_path(&config.url)?;
+    if daemon::is_listening(&socket) {
+        let mut status = daemon::status(&socket).await?;
+        status["message"] = json!("ov daemon is already running");
+        output_success(status, format, compact);
+        return Ok(());
+    }
+    if let Some(dir) = socket.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let log_path = socket.with_extension("log");
+    let log = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)?;
+    let mut command = std::process::Command::new(std::env::current_exe()?);
+    command
+        .args(run_args(std::env::args_os().skip(1).collect()))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(log);
+    // Keep Ctrl-C in this terminal from reaching the daemon.
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    let mut child = command.spawn()?;
+
+    let deadline = tokio::time::Instant::now() + SETTLE_TIMEOUT;
+    while !daemon::is_listening(&socket) {
+        if child.try_wait()?.is_some() || tokio::time::Instant::now() >= deadline {
+            return Err(Error::Client(format!(
+                "ov daemon did not start; see {}",
+                log_path.display()
+            )));
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    let mut status = daemon::status(&socket).await?;
+    status["log"] = json!(log_path.display().to_string());
+    output_success(status, format, compact);
+    Ok(())
+}
+
+pub async fn stop(config: &Config, format: OutputFormat, compact: bool) -> Result<()> {
+    let socket = daemon::socket_path(&config.url)?;
+    if !daemon::is_listening(&socket) {
+        output_success(not_running(config, &socket), format, compact);
+        return Ok(());
+    }
+    daemon::stop(&socket).await?;
+    let deadline = tokio::time::Instant::now() + SETTLE_TIMEOUT;
+    while daemon::is_listening(&socket) {
+        if tokio::time::Instant::now() >= deadline {
+            return Err(Error::Client(format!(
+                "ov daemon on {} did not stop",
+                socket.display()
+            )));
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    output_success(
+        json!({ "server": config.url, "message": "ov daemon stopped" }),
+        format,
+        compact,
+    );
+    Ok(())
+}
+
+pub async fn status(config: &Config, format: OutputFormat, compact: bool) -> Result<()> {
+    let socket = daemon::socket_path(&config.url)?;
+    let status = if daemon::is_listening(&socket) {
+        let mut status = json!({ "running": true });
+        if let (Some(status), Value::Object(fields)) =
+            (status.as_object_mut(), daemon::status(&socket).await?)
+        {
+            status.extend(fields);
+        }
+        status
+    } else {
+        not_running(config, &socket)
+    };
+    output_success(status, format, compact);
+    Ok(())
+}
+
+fn not_running(config: &Config, socket: &Path) -> Value {
+    json!({
+        "running": false,
+        "server": config.url,
+        "socket": socket.display().to_string(),
+        "message": "ov daemon is not running; start it with `ov daemon start`",
+    })
+}
+
+/// The arguments of this `ov ... daemon start ...` with `start` swapped for
+/// `run`, so global options such as `--proxy` apply to the daemon too.
+fn run_args(mut args: Vec<OsString>) -> Vec<OsString> {
+    if let Some(daemon) = args.iter().position(|arg| arg == "daemon")
+        && let Some(start) = args[daemon..].iter().position(|arg| arg == "start")
+    {
+        args[daemon + start] = OsString::from("run");
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_args_keep_global_options() {
+        let args = |list: &[&str]| list.iter().map(OsString::from).collect::<Vec<_>>();
+        assert_eq!(
+            run_args(args(&["--proxy", "none", "daemon", "start", "--ttl", "30"])),
+            args(&["--proxy", "none", "daemon", "run", "--ttl", "30"])
+        );
+    }
+}
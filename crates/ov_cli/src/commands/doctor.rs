@@ -0,0 +1,479 @@
+//! `ov doctor`: the usual suspects between this CLI and its server, checked in
+//! order, each with a PASS/WARN/FAIL verdict and what to do about it.
+
+use std::time::Instant;
+
+use serde_json::{Value, json};
+
+use crate::client::HttpClient;
+use crate::error::{ApiErrorKind, EXIT_UNREACHABLE, Error, Result};
+use crate::output::{OutputFormat, output_success};
+use crate::theme;
+
+/// A pending item older than this suggests the queue is stuck, not just busy.
+const STALLED_QUEUE_SECS: f64 = 600.0;
+const LABEL_WIDTH: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verdict {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl Verdict {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Pass => "PASS",
+            Self::Warn => "WARN",
+            Self::Fail => "FAIL",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Check {
+    name: &'static str,
+    verdict: Verdict,
+    detail: String,
+    hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            verdict: Verdict::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            verdict: Verdict::Warn,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            verdict: Verdict::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "check": self.name,
+            "status": self.verdict.label().to_lowercase(),
+            "detail": self.detail,
+            "hint": self.hint,
+        })
+    }
+
+    fn render(&self) -> String {
+        let label = match self.verdict {
+            Verdict::Pass => theme::success(self.verdict.label()),
+            Verdict::Warn => theme::warning(self.verdict.label()),
+            Verdict::Fail => theme::error(self.verdict.label()),
+        };
+        let mut line = format!(
+            "{label}  {}  {}",
+            theme::strong(format!("{:<LABEL_WIDTH$}", self.name)),
+            self.detail
+        );
+        if let Some(hint) = &self.hint {
+            line.push_str(&format!(
+                "\n{:width$}{}",
+                "",
+                theme::muted(format!("→ {hint}")),
+                width = LABEL_WIDTH + 8
+            ));
+        }
+        line
+    }
+}
+
+/// Run every check against the current server and report them in order.
+pub async fn run(
+    client: &HttpClient,
+    sample: usize,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let mut checks = Vec::new();
+    let health = reachability(client, &mut checks).await;
+    if let Some(health) = health {
+        checks.push(version_check(
+            env!("OPENVIKING_CLI_VERSION"),
+            health["version"].as_str(),
+        ));
+        checks.push(auth_check(client, &health).await);
+        checks.push(embedding_check(client).await);
+        let (queue, busy) = queue_check(client).await;
+        if sample > 0 {
+            checks.push(index_check(client, sample, busy).await);
+        }
+        checks.push(queue);
+    }
+
+    let count = |verdict| checks.iter().filter(|c| c.verdict == verdict).count();
+    let (passed, warned, failed) = (
+        count(Verdict::Pass),
+        count(Verdict::Warn),
+        count(Verdict::Fail),
+    );
+    if matches!(format, OutputFormat::Table) {
+        for check in &checks {
+            println!("{}", check.render());
+        }
+        println!();
+        println!(
+            "{}",
+            theme::muted(format!(
+                "{passed} passed, {warned} warning(s), {failed} failed"
+            ))
+        );
+    } else {
+        output_success(
+            json!({
+                "server": client.server_url(),
+                "checks": checks.iter().map(Check::to_json).collect::<Vec<_>>(),
+                "passed": passed,
+                "warnings": warned,
+                "failed": failed,
+            }),
+            format,
+            compact,
+        );
+    }
+    if failed > 0 {
+        return Err(Error::AlreadyReported);
+    }
+    Ok(())
+}
+
+/// `/health` answers without credentials; the other checks need it to pass.
+async fn reachability(client: &HttpClient, checks: &mut Vec<Check>) -> Option<Value> {
+    let started = Instant::now();
+    let server = client.server_url().to_string();
+    match client.get::<Value>("/health", &[]).await {
+        Ok(health) => {
+            let elapsed = started.elapsed().as_millis();
+            checks.push(if health["healthy"].as_bool() == Some(false) {
+                Check::warn(
+                    "Server",
+                    format!("{server} answered in {elapsed} ms but reports itself unhealthy"),
+                    "Run `ov status --verbose` to see which component is failing",
+                )
+            } else {
+                Check::pass("Server", format!("{server} answered in {elapsed} ms"))
+            });
+            Some(health)
+        }
+        Err(e) => {
+            let hint = if e.exit_code() == EXIT_UNREACHABLE {
+                "Start the server, or fix `url` and proxy settings with `ov config show`"
+            } else {
+                "Something other than OpenViking answered; check `url` with `ov config show`"
+            };
+            checks.push(Check::fail("Server", format!("{server}: {e}"), hint));
+            None
+        }
+    }
+}
+
+/// Compare `major.minor`; patch releases of the same line interoperate.
+fn version_check(cli: &str, server: Option<&str>) -> Check {
+    let Some(server) = server else {
+        return Check::warn(
+            "Version",
+            format!("server did not report a version (CLI {cli})"),
+            "Upgrade the server so `/health` reports its version",
+        );
+    };
+    match (release_line(cli), release_line(server)) {
+        (Some((0, 0)), _) => Check::pass(
+            "Version",
+            format!("server {server}; CLI is a development build"),
+        ),
+        (Some(ours), Some(theirs)) if ours == theirs => {
+            Check::pass("Version", format!("server {server}, CLI {cli}"))
+        }
+        (Some(ours), Some(theirs)) => Check::warn(
+            "Version",
+            format!("server {server}, CLI {cli}"),
+            if ours < theirs {
+                "Upgrade the CLI to match the server's release line"
+            } else {
+                "The server is older than this CLI; newer commands may be rejected"
+            },
+        ),
+        _ => Check::warn(
+            "Version",
+            format!("cannot compare server {server} with CLI {cli}"),
+            "Use matching OpenViking releases for the server and the CLI",
+        ),
+    }
+}
+
+/// `(major, minor)` of versions such as `0.4.1`, `v0.4`, or `0.4.1.dev3+g1a2b`.
+pub(crate) fn release_line(version: &str) -> Option<(u64, u64)> {
+    let mut parts = version.trim().trim_start_matches('v').split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor: String = parts
+        .next()?
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    Some((major, minor.parse().ok()?))
+}
+
+async fn auth_check(client: &HttpClient, health: &Value) -> Check {
+    match client.get::<Value>("/api/v1/system/status", &[]).await {
+        Ok(status) => {
+            let user = status["user"].as_str().unwrap_or("unknown user");
+            let detail = match (health["account_id"].as_str(), health["role"].as_str()) {
+                (Some(account), Some(role)) => format!("{user} in {account} ({role})"),
+                _ => user.to_string(),
+            };
+            Check::pass("Auth", format!("authenticated as {detail}"))
+        }
+        Err(e)
+            if matches!(
+                e.api_kind(),
+                Some(ApiErrorKind::Unauthenticated | ApiErrorKind::PermissionDenied)
+            ) =>
+        {
+            Check::fail(
+                "Auth",
+                e.to_string(),
+                "Check `api_key`, `account`, and `user` with `ov config show`",
+            )
+        }
+        Err(e) => Check::warn(
+            "Auth",
+            format!("could not verify credentials: {e}"),
+            "Retry once the server finishes starting",
+        ),
+    }
+}
+
+async fn embedding_check(client: &HttpClient) -> Check {
+    let ready = match client.readiness().await {
+        Ok(ready) => ready,
+        Err(e) => {
+            return Check::warn(
+                "Embedding",
+                format!("readiness probe failed: {e}"),
+                "Upgrade the server so it serves `/ready`",
+            );
+        }
+    };
+    match ready["checks"]["embedding"].as_str() {
+        Some("ok") => Check::pass("Embedding", "provider answered a test embedding"),
+        Some("not_configured") => Check::warn(
+            "Embedding",
+            "no embedding model is configured",
+            "Configure `embedding` in the server's ov.conf; without it nothing is searchable",
+        ),
+        Some(error) => Check::fail(
+            "Embedding",
+            error.to_string(),
+            "Check the provider's endpoint, model name, and API key in the server's ov.conf",
+        ),
+        None => Check::warn(
+            "Embedding",
+            "the server's readiness report has no embedding check",
+            "Upgrade the server to report embedding health",
+        ),
+    }
+}
+
+/// The queue verdict, and whether work is still pending (which explains
+/// missing index records).
+async fn queue_check(client: &HttpClient) -> (Check, bool) {
+    let report = match client.queue_status(None).await {
+        Ok(report) => report,
+        Err(e) => {
+            return (
+                Check::warn(
+                    "Queue",
+                    format!("could not read queue status: {e}"),
+                    "Run `ov observer queue` to see the server's queue table",
+                ),
+                false,
+            );
+        }
+    };
+    let queues: Vec<&Value> = report["queues"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|queue| queue["name"] != "Semantic-Nodes")
+        .collect();
+    let sum = |key: &str| {
+        queues
+            .iter()
+            .map(|queue| queue[key].as_u64().unwrap_or(0))
+            .sum::<u64>()
+    };
+    let (pending, processing, errors) = (sum("pending"), sum("in_progress"), sum("errors"));
+    let oldest = queues
+        .iter()
+        .filter_map(|queue| queue["oldest_pending_seconds"].as_f64())
+        .fold(None, |oldest: Option<f64>, age| {
+            Some(oldest.map_or(age, |oldest| oldest.max(age)))
+        });
+    let busy = pending + processing > 0;
+    let detail = format!("{pending} pending, {processing} processing, {errors} failed");
+    let check = if let Some(age) = oldest.filter(|age| *age >= STALLED_QUEUE_SECS) {
+        Check::warn(
+            "Queue",
+            format!("{detail}; oldest has waited {}m", (age / 60.0).round()),
+            "The embedding or VLM provider may be throttled; see `ov queue status` and server logs",
+        )
+    } else if errors > 0 {
+        Check::warn(
+            "Queue",
+            detail,
+            "`ov queue status` lists the URIs that failed and why",
+        )
+    } else {
+        Check::pass("Queue", detail)
+    };
+    (check, busy)
+}
+
+/// Compare the filesystem with the vector index for a random sample of
+/// top-level resource directories.
+async fn index_check(client: &HttpClient, sample: usize, busy: bool) -> Check {
+    let listing = match client
+        .ls(
+            "viking://resources",
+            false,
+            false,
+            "original",
+            0,
+            false,
+            1000,
+        )
+        .await
+    {
+        Ok(listing) => listing,
+        Err(e) => {
+            return Check::warn(
+                "Index",
+                format!("could not list viking://resources: {e}"),
+                "Run `ov ls viking://resources` to see why",
+            );
+        }
+    };
+    let dirs: Vec<String> = listing
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry["isDir"].as_bool() == Some(true))
+        .filter_map(|entry| entry["uri"].as_str().map(str::to_string))
+        .collect();
+    if dirs.is_empty() {
+        return Check::pass("Index", "no resource directories to sample");
+    }
+
+    let total_dirs = dirs.len();
+    let sampled = sample_of(dirs, sample);
+    let (mut expected, mut missing) = (0, Vec::new());
+    for uri in &sampled {
+        match client.consistency(uri).await {
+            Ok(report) => {
+                expected += report["expected_count"].as_u64().unwrap_or(0);
+                let count = report["missing_record_count"].as_u64().unwrap_or(0);
+                if count > 0 {
+                    missing.push((uri.as_str(), count));
+                }
+            }
+            Err(e) => {
+                return Check::warn(
+                    "Index",
+                    format!("consistency check failed for {uri}: {e}"),
+                    format!("Run `ov system consistency {uri}` to see why"),
+                );
+            }
+        }
+    }
+    let scope = format!(
+        "{} of {total_dirs} resource directories sampled, {expected} records expected",
+        sampled.len()
+    );
+    let Some((worst, _)) = missing.iter().max_by_key(|(_, count)| *count) else {
+        return Check::pass("Index", format!("{scope}, none missing"));
+    };
+    let total: u64 = missing.iter().map(|(_, count)| count).sum();
+    let detail = format!("{scope}, {total} missing from the vector index");
+    if busy {
+        Check::warn(
+            "Index",
+            detail,
+            format!("Work is still queued; `ov queue wait {worst}` then run doctor again"),
+        )
+    } else {
+        Check::fail(
+            "Index",
+            detail,
+            format!("Rebuild the missing vectors with `ov reindex {worst}`"),
+        )
+    }
+}
+
+/// Up to `n` items picked at random, keeping their original order.
+fn sample_of<T>(items: Vec<T>, n: usize) -> Vec<T> {
+    if items.len() <= n {
+        return items;
+    }
+    let mut indices: Vec<usize> = (0..items.len()).collect();
+    for i in 0..n {
+        let mut bytes = [0u8; 8];
+        let random = match getrandom::getrandom(&mut bytes) {
+            Ok(()) => u64::from_le_bytes(bytes) as usize,
+            Err(_) => i,
+        };
+        let j = i + random % (indices.len() - i);
+        indices.swap(i, j);
+    }
+    let mut picked = indices[..n].to_vec();
+    picked.sort_unstable();
+    items
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| picked.binary_search(index).is_ok())
+        .map(|(_, item)| item)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_check_compares_release_lines() {
+        assert_eq!(release_line("v0.4.1.dev3+g1a2b"), Some((0, 4)));
+        assert_eq!(release_line("unknown"), None);
+        assert_eq!(version_check("0.4.2", Some("0.4.0")).verdict, Verdict::Pass);
+        assert_eq!(version_check("0.3.9", Some("0.4.0")).verdict, Verdict::Warn);
+        assert_eq!(version_check("0.0.0", Some("0.4.0")).verdict, Verdict::Pass);
+        assert_eq!(version_check("0.4.0", None).verdict, Verdict::Warn);
+    }
+
+    #[test]
+    fn sample_of_keeps_order_and_size() {
+        let picked = sample_of((0..20).collect(), 3);
+        assert_eq!(picked.len(), 3);
+        assert!(picked.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(sample_of(vec!["a", "b"], 3), vec!["a", "b"]);
+    }
+}
@@ -0,0 +1,330 @@
+//! `ov events subscribe`: follow what the server does in the background.
+//!
+//! The server has no event stream, so events are derived from its task list:
+//! each poll of `/api/v1/tasks` is compared with the previous one, and a task
+//! that appears, completes, or fails becomes an event. Tasks the server
+//! already had when the subscription started only produce events once they
+//! finish. Each event is printed as one JSON line, or passed to `--exec`.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde_json::{Value, json};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::client::HttpClient;
+use crate::error::{Error, Result};
+use crate::theme;
+
+/// Tasks requested per poll; the server's maximum.
+const TASK_LIMIT: u32 = 200;
+
+/// Every event type, in the order `--filter` errors list them.
+const EVENT_TYPES: [&str; 10] = [
+    "resource.created",
+    "skill.created",
+    "reindex.started",
+    "index.completed",
+    "index.failed",
+    "session.commit_started",
+    "session.committed",
+    "session.commit_failed",
+    "task.completed",
+    "task.failed",
+];
+
+/// The events a task of `task_type` produces when it appears, completes, and
+/// fails. Task types without events of their own only report how they ended.
+fn event_types(task_type: &str) -> (Option<&'static str>, &'static str, &'static str) {
+    match task_type {
+        "add_resource" | "connector_import" => {
+            (Some("resource.created"), "index.completed", "index.failed")
+        }
+        "add_skill" => (Some("skill.created"), "index.completed", "index.failed"),
+        "admin_reindex" | "snapshot_restore_reindex" => {
+            (Some("reindex.started"), "index.completed", "index.failed")
+        }
+        "session_commit" => (
+            Some("session.commit_started"),
+            "session.committed",
+            "session.commit_failed",
+        ),
+        _ => (None, "task.completed", "task.failed"),
+    }
+}
+
+pub struct SubscribeOptions<'a> {
+    pub filter: &'a [String],
+    pub uri: Option<&'a str>,
+    pub exec: Option<&'a str>,
+    pub interval: f64,
+}
+
+/// Which events `--filter` and `--uri` keep.
+struct Filter<'a> {
+    patterns: &'a [String],
+    uri: Option<&'a str>,
+}
+
+impl<'a> Filter<'a> {
+    /// Check every pattern names a known event, or ends in `.*` after a
+    /// known prefix.
+    fn new(patterns: &'a [String], uri: Option<&'a str>) -> Result<Self> {
+        for pattern in patterns {
+            if !EVENT_TYPES
+                .iter()
+                .any(|event| pattern_matches(pattern, event))
+            {
+                return Err(Error::Client(format!(
+                    "Unknown event '{pattern}' in --filter; use {}",
+                    EVENT_TYPES.join(", ")
+                )));
+            }
+        }
+        Ok(Self { patterns, uri })
+    }
+
+    fn matches(&self, event: &Value) -> bool {
+        let kind = event["type"].as_str().unwrap_or_default();
+        (self.patterns.is_empty()
+            || self
+                .patterns
+                .iter()
+                .any(|pattern| pattern_matches(pattern, kind)))
+            && self.uri.is_none_or(|prefix| {
+                event["resource"]
+                    .as_str()
+                    .is_some_and(|uri| under_prefix(uri, prefix))
+            })
+    }
+}
+
+fn pattern_matches(pattern: &str, kind: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some("") => true,
+        Some(prefix) => prefix.ends_with('.') && kind.starts_with(prefix),
+        None => pattern == kind,
+    }
+}
+
+fn under_prefix(uri: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    uri == prefix
+        || uri
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+fn event(kind: &str, task: &Value) -> Value {
+    json!({
+        "type": kind,
+        "at": task["updated_at_iso"],
+        "task_id": task["task_id"],
+        "task_type": task["task_type"],
+        "resource": task["resource_id"],
+        "status": task["status"],
+        "error": task["error"],
+    })
+}
+
+/// The status of every task seen so far, so each poll only reports changes.
+#[derive(Default)]
+struct Tracker {
+    statuses: Option<HashMap<String, String>>,
+}
+
+impl Tracker {
+    /// The events between the last poll and `tasks`, oldest first. The first
+    /// poll only records where things stand.
+    fn update(&mut self, tasks: &[Value]) -> Vec<Value> {
+        let mut tasks: Vec<&Value> = tasks.iter().collect();
+        tasks.sort_by(|a, b| {
+            let created = |task: &Value| task["created_at"].as_f64().unwrap_or(0.0);
+            created(a).total_cmp(&created(b))
+        });
+        let baseline = self.statuses.is_none();
+        let statuses = self.statuses.get_or_insert_default();
+        let mut events = Vec::new();
+        for task in tasks {
+            let (Some(id), Some(status)) = (task["task_id"].as_str(), task["status"].as_str())
+            else {
+                continue;
+            };
+            let previous = statuses.insert(id.to_string(), status.to_string());
+            if baseline || previous.as_deref() == Some(status) {
+                continue;
+            }
+            let (created, completed, failed) =
+                event_types(task["task_type"].as_str().unwrap_or_default());
+            if previous.is_none()
+                && let Some(created) = created
+            {
+                events.push(event(created, task));
+            }
+            match status {
+                "completed" => events.push(event(completed, task)),
+                "failed" => events.push(event(failed, task)),
+                _ => {}
+            }
+        }
+        events
+    }
+}
+
+/// Run `command` through the shell with `event` on stdin and in the
+/// environment, and wait for it.
+async fn run_hook(command_line: &str, event: &Value) -> Result<()> {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C");
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c");
+        command
+    };
+    let text = event.to_string();
+    let mut child = command
+        .arg(command_line)
+        .env("OV_EVENT", &text)
+        .env("OV_EVENT_TYPE", event["type"].as_str().unwrap_or_default())
+        .env(
+            "OV_EVENT_TASK_ID",
+            event["task_id"].as_str().unwrap_or_default(),
+        )
+        .env(
+            "OV_EVENT_RESOURCE",
+            event["resource"].as_str().unwrap_or_default(),
+        )
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| Error::Client(format!("Cannot run `{command_line}`: {e}")))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that ignores stdin may exit before reading it.
+        let _ = stdin.write_all(format!("{text}\n").as_bytes()).await;
+    }
+    let status = child.wait().await?;
+    if !status.success() {
+        return Err(Error::Client(format!(
+            "`{command_line}` exited with {status} for {}",
+            event["type"].as_str().unwrap_or("event")
+        )));
+    }
+    Ok(())
+}
+
+pub async fn subscribe(client: &HttpClient, options: SubscribeOptions<'_>) -> Result<()> {
+    if !options.interval.is_finite() || options.interval <= 0.0 {
+        return Err(Error::Client(
+            "--interval must be a positive number of seconds".to_string(),
+        ));
+    }
+    let filter = Filter::new(options.filter, options.uri)?;
+    let mut tracker = Tracker::default();
+    let tasks = client.list_tasks(None, None, Some(TASK_LIMIT)).await?;
+    tracker.update(tasks.as_array().map_or(&[], Vec::as_slice));
+    loop {
+        tokio::time::sleep(Duration::from_secs_f64(options.interval)).await;
+        let tasks = match client.list_tasks(None, None, Some(TASK_LIMIT)).await {
+            Ok(tasks) => tasks,
+            Err(error) => {
+                eprintln!("{} {error}; retrying", theme::warning("warning:"));
+                continue;
+            }
+        };
+        for event in tracker.update(tasks.as_array().map_or(&[], Vec::as_slice)) {
+            if !filter.matches(&event) {
+                continue;
+            }
+            match options.exec {
+                Some(command) => {
+                    if let Err(error) = run_hook(command, &event).await {
+                        eprintln!("{} {error}", theme::warning("warning:"));
+                    }
+                }
+                None => {
+                    println!("{event}");
+                    let _ = std::io::stdout().flush();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str, task_type: &str, status: &str, created_at: f64) -> Value {
+        json!({
+            "task_id": id,
+            "task_type": task_type,
+            "status": status,
+            "created_at": created_at,
+            "updated_at_iso": "2026-10-17T09:00:00+00:00",
+            "resource_id": format!("viking://resources/{id}"),
+            "error": null,
+        })
+    }
+
+    fn types(events: &[Value]) -> Vec<&str> {
+        events
+            .iter()
+            .map(|event| event["type"].as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn tasks_that_appear_and_finish_become_events() {
+        let mut tracker = Tracker::default();
+        assert!(
+            tracker
+                .update(&[task("old", "add_resource", "running", 1.0)])
+                .is_empty()
+        );
+        let events = tracker.update(&[
+            task("commit", "session_commit", "failed", 3.0),
+            task("old", "add_resource", "completed", 1.0),
+            task("new", "add_resource", "pending", 2.0),
+        ]);
+        assert_eq!(
+            types(&events),
+            vec![
+                "index.completed",
+                "resource.created",
+                "session.commit_started",
+                "session.commit_failed",
+            ]
+        );
+        assert_eq!(events[0]["resource"], "viking://resources/old");
+        let events = tracker.update(&[
+            task("new", "add_resource", "running", 2.0),
+            task("gc", "legacy_cleanup", "completed", 4.0),
+        ]);
+        assert_eq!(types(&events), vec!["task.completed"]);
+    }
+
+    #[test]
+    fn filters_match_event_types_prefixes_and_uris() {
+        let patterns = vec!["index.*".to_string(), "resource.created".to_string()];
+        let filter = Filter::new(&patterns, Some("viking://resources/docs/")).unwrap();
+        let completed = |uri: &str| {
+            let mut task = task("t", "add_resource", "completed", 1.0);
+            task["resource_id"] = json!(uri);
+            event("index.completed", &task)
+        };
+        assert!(filter.matches(&completed("viking://resources/docs/a.md")));
+        assert!(!filter.matches(&completed("viking://resources/docsets/a.md")));
+        let mut failed = completed("viking://resources/docs");
+        failed["type"] = json!("session.committed");
+        assert!(!filter.matches(&failed));
+
+        assert!(Filter::new(&["*".to_string()], None).is_ok());
+        assert!(Filter::new(&["index.done".to_string()], None).is_err());
+        assert!(Filter::new(&["ind*".to_string()], None).is_err());
+        assert!(under_prefix("viking://resources/a", "viking://"));
+    }
+}
@@ -0,0 +1,374 @@
+//! Tree view for `ov find --explain` and `ov search --explain`.
+
+use super::search::search_result_strategy_scores;
+use crate::theme;
+use colored::Colorize;
+use serde_json::{Map, Value};
+
+const COLLECTION_KEYS: &[&str] = &["memories", "resources", "skills"];
+
+/// Candidate counts in the order the retriever narrows them.
+const CANDIDATE_STAGES: &[(&str, &str)] = &[
+    ("scanned", "scanned"),
+    ("scored", "scored"),
+    ("passed", "passed threshold"),
+    ("returned", "returned"),
+];
+
+const TIMING_STAGES: &[(&str, &str)] = &[
+    ("target_abstract", "target abstract"),
+    ("intent_analysis", "intent analysis"),
+    ("embed_query", "embed query"),
+    ("vector_retrieval", "vector retrieval"),
+];
+
+struct Node {
+    label: String,
+    children: Vec<Node>,
+}
+
+impl Node {
+    fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            children: Vec::new(),
+        }
+    }
+
+    fn with_children(label: impl Into<String>, children: Vec<Node>) -> Self {
+        Self {
+            label: label.into(),
+            children,
+        }
+    }
+
+    fn render_children(&self, prefix: &str, lines: &mut Vec<String>) {
+        for (index, child) in self.children.iter().enumerate() {
+            let last = index + 1 == self.children.len();
+            let (branch, indent) = if last {
+                ("└─", "   ")
+            } else {
+                ("├─", "│  ")
+            };
+            lines.push(format!("{prefix}{} {}", theme::muted(branch), child.label));
+            child.render_children(&format!("{prefix}{}", theme::muted(indent)), lines);
+        }
+    }
+}
+
+/// Render why a find/search result ranked the way it did, or `None` when
+/// the response carries nothing to explain.
+pub(super) fn render_explanation(query: &str, result: &Value) -> Option<String> {
+    let object = result.as_object()?;
+    let summary = object
+        .get("telemetry")
+        .and_then(|telemetry| telemetry.get("summary"));
+    let sections: Vec<Node> = [
+        intent_section(object),
+        retrieval_section(object),
+        candidate_section(summary),
+        timing_section(summary),
+        ranking_section(object),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if sections.is_empty() {
+        return None;
+    }
+
+    let root = Node::with_children(
+        format!(
+            "{} {}",
+            theme::heading("Explain").bold(),
+            theme::body(format!("\"{query}\""))
+        ),
+        sections,
+    );
+    let mut lines = vec![root.label.clone()];
+    root.render_children("", &mut lines);
+    Some(lines.join("\n"))
+}
+
+fn intent_section(object: &Map<String, Value>) -> Option<Node> {
+    let plan = object.get("query_plan")?.as_object()?;
+    let mut children = Vec::new();
+    if let Some(reasoning) = non_empty_str(plan.get("reasoning")) {
+        children.push(Node::new(theme::body(reasoning).to_string()));
+    }
+    let rewrites: Vec<Node> = plan
+        .get("queries")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(rewrite_node)
+        .collect();
+    if !rewrites.is_empty() {
+        children.push(Node::with_children(
+            theme::muted(count(rewrites.len(), "query rewrite", "query rewrites")).to_string(),
+            rewrites,
+        ));
+    }
+    (!children.is_empty()).then(|| Node::with_children(section("Intent analysis"), children))
+}
+
+fn rewrite_node(query: &Value) -> Option<Node> {
+    let text = non_empty_str(query.get("query"))?;
+    let mut tags = Vec::new();
+    if let Some(context_type) = non_empty_str(query.get("context_type")) {
+        tags.push(context_type.to_string());
+    }
+    if let Some(priority) = query.get("priority").and_then(Value::as_i64) {
+        tags.push(format!("priority {priority}"));
+    }
+    let mut label = theme::body(format!("\"{text}\"")).to_string();
+    if !tags.is_empty() {
+        label.push_str(&format!(
+            " {}",
+            theme::muted(format!("[{}]", tags.join(", ")))
+        ));
+    }
+    if let Some(intent) = non_empty_str(query.get("intent")) {
+        label.push_str(&format!(" {}", theme::muted(format!("— {intent}"))));
+    }
+    Some(Node::new(label))
+}
+
+fn retrieval_section(object: &Map<String, Value>) -> Option<Node> {
+    let entries = object.get("provenance")?.as_array()?;
+    let children: Vec<Node> = entries
+        .iter()
+        .map(|entry| {
+            let query = non_empty_str(entry.get("query")).unwrap_or("(image)");
+            let directories: Vec<&str> = entry
+                .get("searched_directories")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .collect();
+            let matches = entry
+                .get("matched_contexts")
+                .and_then(Value::as_array)
+                .map_or(0, Vec::len);
+            let label = format!(
+                "{} {}",
+                theme::body(format!("\"{query}\"")),
+                theme::muted(format!(
+                    "→ {} searched, {} matched",
+                    count(directories.len(), "directory", "directories"),
+                    matches
+                ))
+            );
+            let directories = directories
+                .into_iter()
+                .map(|directory| Node::new(theme::muted(directory).to_string()))
+                .collect();
+            Node::with_children(label, directories)
+        })
+        .collect();
+    (!children.is_empty()).then(|| Node::with_children(section("Retrieval"), children))
+}
+
+fn candidate_section(summary: Option<&Value>) -> Option<Node> {
+    let vector = summary?.get("vector")?;
+    let stages: Vec<String> = CANDIDATE_STAGES
+        .iter()
+        .filter_map(|(key, label)| {
+            let value = vector.get(key)?.as_u64()?;
+            Some(format!(
+                "{} {}",
+                theme::value(value.to_string()).bold(),
+                label
+            ))
+        })
+        .collect();
+    if stages.is_empty() {
+        return None;
+    }
+    let mut children = vec![Node::new(stages.join(&theme::muted(" → ").to_string()))];
+    if let Some(searches) = vector.get("searches").and_then(Value::as_u64) {
+        children.push(Node::new(
+            theme::muted(count(searches as usize, "vector search", "vector searches")).to_string(),
+        ));
+    }
+    if let Some(reason) = non_empty_str(vector.get("scan_reason")) {
+        children.push(Node::new(
+            theme::muted(format!("scan: {reason}")).to_string(),
+        ));
+    }
+    Some(Node::with_children(section("Candidates"), children))
+}
+
+fn timing_section(summary: Option<&Value>) -> Option<Node> {
+    let summary = summary?;
+    let search = summary.get("search");
+    let mut children: Vec<Node> = TIMING_STAGES
+        .iter()
+        .filter_map(|(key, label)| {
+            let duration = search?.get(key)?.get("duration_ms")?.as_f64()?;
+            Some(timing_node(label, duration))
+        })
+        .collect();
+    if let Some(total) = summary.get("duration_ms").and_then(Value::as_f64) {
+        children.push(timing_node("total", total));
+    }
+    (!children.is_empty()).then(|| Node::with_children(section("Timing"), children))
+}
+
+fn timing_node(label: &str, duration_ms: f64) -> Node {
+    Node::new(format!(
+        "{} {}",
+        theme::muted(format!("{label}:")),
+        theme::value(format!("{duration_ms:.1} ms"))
+    ))
+}
+
+fn ranking_section(object: &Map<String, Value>) -> Option<Node> {
+    let mut hits: Vec<&Map<String, Value>> = COLLECTION_KEYS
+        .iter()
+        .filter_map(|key| object.get(*key)?.as_array())
+        .flatten()
+        .filter_map(Value::as_object)
+        .collect();
+    if hits.is_empty() {
+        return None;
+    }
+    hits.sort_by(|left, right| score(right).total_cmp(&score(left)));
+    let children = hits
+        .into_iter()
+        .enumerate()
+        .map(|(index, hit)| {
+            let uri = non_empty_str(hit.get("uri")).unwrap_or("-");
+            let mut label = format!(
+                "{} {} {}",
+                theme::muted(format!("{}.", index + 1)),
+                theme::value(format!("{:.3}", score(hit))).bold(),
+                theme::body(uri)
+            );
+            if let Some(context_type) = non_empty_str(hit.get("context_type")) {
+                label.push_str(&format!(" {}", theme::muted(format!("[{context_type}]"))));
+            }
+            let breakdown = search_result_strategy_scores(Some(hit))
+                .map(|scores| vec![Node::new(theme::muted(scores).to_string())])
+                .unwrap_or_default();
+            Node::with_children(label, breakdown)
+        })
+        .collect();
+    Some(Node::with_children(section("Final ranking"), children))
+}
+
+fn section(title: &str) -> String {
+    theme::heading(title).bold().to_string()
+}
+
+fn score(hit: &Map<String, Value>) -> f64 {
+    hit.get("score").and_then(Value::as_f64).unwrap_or(0.0)
+}
+
+fn count(value: usize, singular: &str, plural: &str) -> String {
+    format!("{value} {}", if value == 1 { singular } else { plural })
+}
+
+fn non_empty_str(value: Option<&Value>) -> Option<&str> {
+    value?
+        .as_str()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn strip_ansi(input: &str) -> String {
+        let mut output = String::with_capacity(input.len());
+        let mut chars = input.chars().peekable();
+        while let Some(ch) = chars.next() {
+            if ch == '\u{1b}' && chars.peek() == Some(&'[') {
+                chars.next();
+                for next in chars.by_ref() {
+                    if next == 'm' {
+                        break;
+                    }
+                }
+            } else {
+                output.push(ch);
+            }
+        }
+        output
+    }
+
+    #[test]
+    fn explanation_walks_intent_retrieval_candidates_and_ranking() {
+        let result = json!({
+            "memories": [{
+                "uri": "viking://user/memories/prefs.md",
+                "context_type": "memory",
+                "score": 0.41,
+                "scores": {"vector": 0.41}
+            }],
+            "resources": [{
+                "uri": "viking://resources/junk.md",
+                "context_type": "resource",
+                "score": 0.87,
+                "scores": {"vector": 0.62, "rerank": 0.87}
+            }],
+            "query_plan": {
+                "reasoning": "User asks about their own preferences.",
+                "queries": [{
+                    "query": "editor preferences",
+                    "context_type": "memory",
+                    "intent": "recall stated preference",
+                    "priority": 1
+                }]
+            },
+            "provenance": [{
+                "query": "editor preferences",
+                "searched_directories": ["viking://user/memories"],
+                "matched_contexts": [{"uri": "viking://user/memories/prefs.md"}]
+            }],
+            "telemetry": {"summary": {
+                "duration_ms": 812.4,
+                "vector": {"searches": 2, "scored": 40, "passed": 6, "returned": 2},
+                "search": {"intent_analysis": {"duration_ms": 530.0}}
+            }}
+        });
+
+        let rendered =
+            strip_ansi(&render_explanation("which editor do I like", &result).expect("tree"));
+
+        assert_eq!(
+            rendered,
+            [
+                "Explain \"which editor do I like\"",
+                "├─ Intent analysis",
+                "│  ├─ User asks about their own preferences.",
+                "│  └─ 1 query rewrite",
+                "│     └─ \"editor preferences\" [memory, priority 1] — recall stated preference",
+                "├─ Retrieval",
+                "│  └─ \"editor preferences\" → 1 directory searched, 1 matched",
+                "│     └─ viking://user/memories",
+                "├─ Candidates",
+                "│  ├─ 40 scored → 6 passed threshold → 2 returned",
+                "│  └─ 2 vector searches",
+                "├─ Timing",
+                "│  ├─ intent analysis: 530.0 ms",
+                "│  └─ total: 812.4 ms",
+                "└─ Final ranking",
+                "   ├─ 1. 0.870 viking://resources/junk.md [resource]",
+                "   │  └─ vector 0.620 / rerank 0.870",
+                "   └─ 2. 0.410 viking://user/memories/prefs.md [memory]",
+                "      └─ vector 0.410",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn explanation_is_absent_without_anything_to_explain() {
+        assert!(render_explanation("q", &json!({"resources": [], "total": 0})).is_none());
+        assert!(render_explanation("q", &json!([])).is_none());
+    }
+}
@@ -0,0 +1,570 @@
+//! `ov add feed`: add the new entries of a sitemap or an RSS/Atom feed as
+//! Markdown resources, for cron-driven ingestion of blogs and changelogs.
+//!
+//! Entries are keyed by their guid, id, or URL. The keys already added are
+//! kept per feed in `~/.openviking/feeds.json` (or `--state`), so a repeated
+//! run only adds entries it has not seen; a failed entry is retried on the
+//! next run. An entry's embedded content (`content:encoded`, Atom `content`)
+//! is used when present; otherwise its page is fetched and its main content
+//! extracted as `ov add url` does. A sitemap index is read one level deep.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+use roxmltree::{Document, Node, ParsingOptions};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use url::Url;
+
+use super::mcp_import::{markdown_file_name, upload_file};
+use super::web_import::{
+    Page, fetch, fetch_text, http_client, normalize, page_document, page_name, parse_page,
+};
+use crate::client::HttpClient;
+use crate::error::{Error, Result};
+use crate::html_markdown::fragment_markdown;
+use crate::interrupt;
+use crate::json_store;
+use crate::output::{OutputFormat, output_success};
+use crate::theme;
+
+pub struct AddFeedOptions<'a> {
+    pub url: &'a str,
+    pub parent: &'a str,
+    pub limit: Option<usize>,
+    pub mark_seen: bool,
+    pub dry_run: bool,
+    pub state: Option<&'a Path>,
+    pub timeout: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FeedKind {
+    Sitemap,
+    SitemapIndex,
+    Rss,
+    Atom,
+}
+
+impl FeedKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Sitemap => "sitemap",
+            Self::SitemapIndex => "sitemap-index",
+            Self::Rss => "rss",
+            Self::Atom => "atom",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+    id: String,
+    url: Option<Url>,
+    title: Option<String>,
+    /// Embedded HTML content, when the feed carries the full text.
+    content: Option<String>,
+    /// A summary, used only when the entry has neither content nor a URL.
+    summary: Option<String>,
+}
+
+#[derive(Debug)]
+struct Feed {
+    kind: FeedKind,
+    title: Option<String>,
+    entries: Vec<Entry>,
+    /// The sitemaps a sitemap index lists.
+    sitemaps: Vec<Url>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+struct FeedState {
+    parent: String,
+    #[serde(default)]
+    last_run: Option<String>,
+    #[serde(default)]
+    seen: BTreeSet<String>,
+}
+
+/// Feed URL to what has been added from it.
+type FeedStates = BTreeMap<String, FeedState>;
+
+fn state_path() -> Result<PathBuf> {
+    json_store::path("feeds.json")
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children()
+        .find(|child| child.is_element() && child.tag_name().name() == name)
+}
+
+/// The trimmed text under the first `name` child, when not empty.
+fn child_text(node: Node, name: &str) -> Option<String> {
+    let text: String = child(node, name)?
+        .descendants()
+        .filter(|node| node.is_text())
+        .filter_map(|node| node.text())
+        .collect();
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+fn locations(root: Node, item: &str, base: &Url) -> Vec<Url> {
+    root.children()
+        .filter(|node| node.tag_name().name() == item)
+        .filter_map(|node| base.join(&child_text(node, "loc")?).ok())
+        .collect()
+}
+
+fn rss_entry(item: Node, base: &Url) -> Option<Entry> {
+    let url = child_text(item, "link").and_then(|link| base.join(&link).ok());
+    let title = child_text(item, "title");
+    let id = child_text(item, "guid")
+        .or_else(|| url.as_ref().map(Url::to_string))
+        .or_else(|| title.clone())?;
+    Some(Entry {
+        id,
+        url,
+        title,
+        content: child_text(item, "encoded"),
+        summary: child_text(item, "description"),
+    })
+}
+
+fn atom_entry(entry: Node, xml: &str, base: &Url) -> Option<Entry> {
+    let url = entry
+        .children()
+        .filter(|node| node.tag_name().name() == "link")
+        .find(|link| matches!(link.attribute("rel"), None | Some("alternate")))
+        .and_then(|link| base.join(link.attribute("href")?).ok());
+    let title = child_text(entry, "title");
+    let content = child(entry, "content").and_then(|content| {
+        if content.attribute("src").is_some() {
+            None
+        } else if content.attribute("type") == Some("xhtml") {
+            Some(xml[content.range()].to_string())
+        } else {
+            child_text(entry, "content")
+        }
+    });
+    let id = child_text(entry, "id")
+        .or_else(|| url.as_ref().map(Url::to_string))
+        .or_else(|| title.clone())?;
+    Some(Entry {
+        id,
+        url,
+        title,
+        content,
+        summary: child_text(entry, "summary"),
+    })
+}
+
+fn parse_feed(xml: &str, base: &Url) -> Result<Feed> {
+    let xml = xml.trim_start_matches('\u{feff}');
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    let document = Document::parse_with_options(xml, options)
+        .map_err(|e| Error::Parse(format!("{base} is not a valid XML feed: {e}")))?;
+    let root = document.root_element();
+    let mut feed = Feed {
+        kind: FeedKind::Sitemap,
+        title: None,
+        entries: Vec::new(),
+        sitemaps: Vec::new(),
+    };
+    match root.tag_name().name() {
+        "urlset" => {
+            feed.entries = locations(root, "url", base)
+                .into_iter()
+                .map(|url| Entry {
+                    id: url.to_string(),
+                    url: Some(url),
+                    title: None,
+                    content: None,
+                    summary: None,
+                })
+                .collect();
+        }
+        "sitemapindex" => {
+            feed.kind = FeedKind::SitemapIndex;
+            feed.sitemaps = locations(root, "sitemap", base);
+        }
+        // RSS 0.9x/2.0 keep items in the channel; RSS 1.0 (RDF) beside it.
+        "rss" | "RDF" => {
+            feed.kind = FeedKind::Rss;
+            feed.title = child(root, "channel").and_then(|channel| child_text(channel, "title"));
+            feed.entries = root
+                .descendants()
+                .filter(|node| node.tag_name().name() == "item")
+                .filter_map(|item| rss_entry(item, base))
+                .collect();
+        }
+        "feed" => {
+            feed.kind = FeedKind::Atom;
+            feed.title = child_text(root, "title");
+            feed.entries = root
+                .children()
+                .filter(|node| node.tag_name().name() == "entry")
+                .filter_map(|entry| atom_entry(entry, xml, base))
+                .collect();
+        }
+        other => {
+            return Err(Error::Parse(format!(
+                "{base} is not a sitemap or an RSS/Atom feed (root element <{other}>)"
+            )));
+        }
+    }
+    Ok(feed)
+}
+
+/// Entries not in `seen`, each once, in feed order.
+fn new_entries(entries: Vec<Entry>, seen: &BTreeSet<String>) -> Vec<Entry> {
+    let mut taken = HashSet::new();
+    entries
+        .into_iter()
+        .filter(|entry| !seen.contains(&entry.id) && taken.insert(entry.id.clone()))
+        .collect()
+}
+
+/// The entry as a page: its embedded content, else its fetched page, else its
+/// summary.
+async fn entry_page(http: &reqwest::Client, entry: &Entry, feed_url: &Url) -> Result<Page> {
+    let base = entry.url.as_ref().unwrap_or(feed_url);
+    let mut page = match (&entry.content, &entry.url) {
+        (Some(content), _) => Page {
+            canonical: normalize(base.clone()),
+            title: None,
+            markdown: fragment_markdown(content, base),
+            links: Vec::new(),
+        },
+        (None, Some(url)) => match fetch(http, url).await? {
+            Some(response) if response.html => parse_page(&response.body, &response.url),
+            Some(response) => Page {
+                canonical: normalize(response.url),
+                title: None,
+                markdown: response.body,
+                links: Vec::new(),
+            },
+            None => {
+                return Err(Error::Client(format!("{url} is not an HTML or text page")));
+            }
+        },
+        (None, None) => Page {
+            canonical: normalize(base.clone()),
+            title: None,
+            markdown: fragment_markdown(entry.summary.as_deref().unwrap_or_default(), base),
+            links: Vec::new(),
+        },
+    };
+    if entry.title.is_some() {
+        page.title = entry.title.clone();
+    }
+    Ok(page)
+}
+
+fn row(entry: &Entry, status: &str, uri: Option<&str>, error: Option<String>) -> Value {
+    json!({
+        "id": entry.id,
+        "title": entry.title,
+        "url": entry.url.as_ref().map(Url::as_str),
+        "status": status,
+        "uri": uri,
+        "error": error,
+    })
+}
+
+pub async fn add_feed(
+    client: &HttpClient,
+    options: AddFeedOptions<'_>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let feed_url = Url::parse(options.url)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .ok_or_else(|| {
+            Error::Client(format!(
+                "Invalid URL '{}'; expected an http:// or https:// feed URL",
+                options.url
+            ))
+        })?;
+    let state_path = match options.state {
+        Some(path) => path.to_path_buf(),
+        None => state_path()?,
+    };
+    let mut states = json_store::read::<FeedStates>(&state_path)?;
+    let http = http_client(options.timeout)?;
+
+    interrupt::enter_batch();
+    let (url, xml) = interrupt::cancellable(fetch_text(&http, &feed_url)).await?;
+    let mut feed = parse_feed(&xml, &url)?;
+    let mut rows = Vec::new();
+    let mut failed = 0;
+    for sitemap in std::mem::take(&mut feed.sitemaps) {
+        let result = match interrupt::cancellable(fetch_text(&http, &sitemap)).await {
+            Ok((url, xml)) => parse_feed(&xml, &url),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(child) => feed.entries.extend(child.entries),
+            Err(Error::Interrupted) => return Err(Error::Interrupted),
+            Err(e) => {
+                failed += 1;
+                let entry = Entry {
+                    id: sitemap.to_string(),
+                    url: Some(sitemap),
+                    title: None,
+                    content: None,
+                    summary: None,
+                };
+                rows.push(row(&entry, "failed", None, Some(e.to_string())));
+            }
+        }
+    }
+
+    let state = states.entry(options.url.to_string()).or_default();
+    let total = feed.entries.len();
+    let mut entries = new_entries(feed.entries, &state.seen);
+    let new = entries.len();
+    if let Some(limit) = options.limit {
+        entries.truncate(limit);
+    }
+
+    let dir = tempfile::tempdir()?;
+    let mut taken = HashSet::new();
+    let mut imported = 0;
+    let mut interrupted = false;
+    for (index, entry) in entries.iter().enumerate() {
+        if options.dry_run {
+            rows.push(row(entry, "planned", None, None));
+            continue;
+        }
+        if options.mark_seen {
+            state.seen.insert(entry.id.clone());
+            rows.push(row(entry, "marked", None, None));
+            continue;
+        }
+        eprintln!(
+            "{}",
+            theme::muted(format!(
+                "[{}/{}] {}",
+                index + 1,
+                entries.len(),
+                entry.title.as_deref().unwrap_or(&entry.id)
+            ))
+        );
+        let result = interrupt::cancellable(async {
+            let page = entry_page(&http, entry, &url).await?;
+            let path = dir
+                .path()
+                .join(markdown_file_name(page_name(&page), &mut taken));
+            std::fs::write(&path, page_document(&page))?;
+            upload_file(client, &path, options.parent).await
+        })
+        .await;
+        match result {
+            Ok(uri) => {
+                imported += 1;
+                state.seen.insert(entry.id.clone());
+                rows.push(row(entry, "imported", Some(&uri), None));
+            }
+            Err(Error::Interrupted) => {
+                interrupted = true;
+                break;
+            }
+            Err(e) => {
+                failed += 1;
+                rows.push(row(entry, "failed", None, Some(e.to_string())));
+            }
+        }
+    }
+    if !options.dry_run {
+        state.parent = options.parent.to_string();
+        state.last_run = Some(chrono::Utc::now().to_rfc3339());
+        json_store::write(&state_path, &states)?;
+    }
+
+    if matches!(format, OutputFormat::Table) {
+        output_success(Value::Array(rows), format, compact);
+        let summary = if options.dry_run {
+            format!(
+                "{new} new of {total} entries; {} would be imported into {}",
+                entries.len(),
+                options.parent
+            )
+        } else if options.mark_seen {
+            format!(
+                "{} of {total} entries marked as seen in {}",
+                entries.len(),
+                state_path.display()
+            )
+        } else {
+            format!(
+                "{imported} of {new} new entries imported into {}: {failed} failed",
+                options.parent
+            )
+        };
+        println!("{}", theme::muted(summary));
+    } else {
+        output_success(
+            json!({
+                "feed": options.url,
+                "kind": feed.kind.as_str(),
+                "title": feed.title,
+                "parent": options.parent,
+                "state": state_path.display().to_string(),
+                "dry_run": options.dry_run,
+                "entries": total,
+                "new": new,
+                "imported": imported,
+                "failed": failed,
+                "results": rows,
+            }),
+            format,
+            compact,
+        );
+    }
+    if interrupted {
+        return Err(Error::Interrupted);
+    }
+    if failed > 0 {
+        return Err(Error::AlreadyReported);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Url {
+        Url::parse("https://blog.example.com/feed.xml").unwrap()
+    }
+
+    #[test]
+    fn rss_and_atom_entries_are_parsed() {
+        let rss = r#"<?xml version="1.0"?>
+            <rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/">
+              <channel><title>Changelog</title>
+                <item>
+                  <title>v1.2</title><link>/posts/v1-2</link>
+                  <guid isPermaLink="false">release-1.2</guid>
+                  <content:encoded><![CDATA[<p>New <b>feed</b> support</p>]]></content:encoded>
+                </item>
+                <item><title>v1.1</title><link>https://blog.example.com/posts/v1-1</link>
+                  <description>Short summary</description></item>
+              </channel>
+            </rss>"#;
+        let feed = parse_feed(rss, &base()).unwrap();
+        assert_eq!(feed.kind, FeedKind::Rss);
+        assert_eq!(feed.title.as_deref(), Some("Changelog"));
+        assert_eq!(
+            feed.entries,
+            vec![
+                Entry {
+                    id: "release-1.2".into(),
+                    url: Some(Url::parse("https://blog.example.com/posts/v1-2").unwrap()),
+                    title: Some("v1.2".into()),
+                    content: Some("<p>New <b>feed</b> support</p>".into()),
+                    summary: None,
+                },
+                Entry {
+                    id: "https://blog.example.com/posts/v1-1".into(),
+                    url: Some(Url::parse("https://blog.example.com/posts/v1-1").unwrap()),
+                    title: Some("v1.1".into()),
+                    content: None,
+                    summary: Some("Short summary".into()),
+                },
+            ]
+        );
+
+        let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>Blog</title>
+              <entry><id>urn:post:1</id><title>Hello</title>
+                <link rel="edit" href="/edit/1"/><link href="/posts/hello"/>
+                <content type="html">&lt;p&gt;Hi&lt;/p&gt;</content>
+              </entry>
+              <entry><id>urn:post:2</id><link rel="alternate" href="/posts/two"/>
+                <summary>Two</summary></entry>
+            </feed>"#;
+        let feed = parse_feed(atom, &base()).unwrap();
+        assert_eq!(feed.kind, FeedKind::Atom);
+        assert_eq!(feed.entries[0].id, "urn:post:1");
+        assert_eq!(
+            feed.entries[0].url.as_ref().map(Url::as_str),
+            Some("https://blog.example.com/posts/hello")
+        );
+        assert_eq!(feed.entries[0].content.as_deref(), Some("<p>Hi</p>"));
+        assert_eq!(feed.entries[1].content, None);
+        assert_eq!(feed.entries[1].summary.as_deref(), Some("Two"));
+
+        assert!(parse_feed("<html><body/></html>", &base()).is_err());
+        assert!(parse_feed("not xml", &base()).is_err());
+    }
+
+    #[test]
+    fn sitemaps_list_pages_and_child_sitemaps() {
+        let sitemap = r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+              <url><loc>https://docs.example.com/a</loc><lastmod>2026-01-01</lastmod></url>
+              <url><loc> https://docs.example.com/b </loc></url>
+            </urlset>"#;
+        let feed = parse_feed(sitemap, &base()).unwrap();
+        assert_eq!(feed.kind, FeedKind::Sitemap);
+        assert_eq!(
+            feed.entries
+                .iter()
+                .map(|entry| entry.id.as_str())
+                .collect::<Vec<_>>(),
+            ["https://docs.example.com/a", "https://docs.example.com/b"]
+        );
+
+        let index = r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+              <sitemap><loc>https://docs.example.com/sitemap-1.xml</loc></sitemap>
+            </sitemapindex>"#;
+        let feed = parse_feed(index, &base()).unwrap();
+        assert_eq!(feed.kind, FeedKind::SitemapIndex);
+        assert!(feed.entries.is_empty());
+        assert_eq!(
+            feed.sitemaps[0].as_str(),
+            "https://docs.example.com/sitemap-1.xml"
+        );
+    }
+
+    #[test]
+    fn state_keeps_seen_entries_between_runs() {
+        let entry = |id: &str| Entry {
+            id: id.into(),
+            url: None,
+            title: None,
+            content: None,
+            summary: None,
+        };
+        let seen = BTreeSet::from(["a".to_string()]);
+        let fresh = new_entries(vec![entry("a"), entry("b"), entry("c"), entry("b")], &seen);
+        assert_eq!(
+            fresh
+                .iter()
+                .map(|entry| entry.id.as_str())
+                .collect::<Vec<_>>(),
+            ["b", "c"]
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("feeds.json");
+        assert!(json_store::read::<FeedStates>(&path).unwrap().is_empty());
+        let mut states = FeedStates::new();
+        states.insert(
+            "https://blog.example.com/feed.xml".into(),
+            FeedState {
+                parent: "viking://resources/feeds".into(),
+                last_run: None,
+                seen,
+            },
+        );
+        json_store::write(&path, &states).unwrap();
+        assert_eq!(json_store::read::<FeedStates>(&path).unwrap(), states);
+        std::fs::write(&path, "{").unwrap();
+        assert!(json_store::read::<FeedStates>(&path).is_err());
+    }
+}
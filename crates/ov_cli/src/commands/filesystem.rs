@@ -1,12 +1,15 @@
 use super::render_utils::{append_profile_lines, with_ascii_ellipsis, wrap_display_text};
+use super::search::result_tokens;
+use super::tokens::{Listing, Tokenizer, annotate};
 use crate::client::HttpClient;
 use crate::error::{Error, Result};
-use crate::output::{OutputFormat, output_success};
+use crate::interrupt;
+use crate::output::{DecorateRecord, NdjsonStream, OutputFormat, output_success};
 use crate::theme;
 use chrono::{DateTime, Local};
 use colored::Colorize;
 use serde_json::Value;
-use std::io::IsTerminal;
+use std::io::{IsTerminal, Write};
 use unicode_width::UnicodeWidthStr;
 
 const ENTRY_TEXT_WIDTH: usize = 96;
@@ -16,34 +19,87 @@ const ENTRY_INDENT: &str = "   ";
 const TREE_INDENT: &str = "  ";
 const TREE_NAME_COLUMN_WIDTH: usize = 38;
 const TREE_MIN_NAME_COLUMN_WIDTH: usize = 18;
+const STAT_CHILD_LIMIT: i32 = 1000;
+const COPY_NODE_LIMIT: i32 = 100_000;
+
+/// What `ls` lists, and how much of each entry it shows.
+pub struct LsOptions<'a> {
+    pub uri: &'a str,
+    pub simple: bool,
+    pub recursive: bool,
+    pub abs_limit: i32,
+    pub show_all_hidden: bool,
+    pub node_limit: i32,
+    /// Keep only entries carrying every one of these tags.
+    pub tags: &'a [String],
+}
 
 pub async fn ls(
     client: &HttpClient,
-    uri: &str,
-    simple: bool,
-    recursive: bool,
+    options: LsOptions<'_>,
     output: &str,
-    abs_limit: i32,
-    show_all_hidden: bool,
-    node_limit: i32,
+    tokens: Option<Tokenizer>,
     output_format: OutputFormat,
     compact: bool,
 ) -> Result<()> {
-    let result = client
+    let stream = NdjsonStream::decorated(
+        output_format,
+        decorate_entries(client, options.tags, tokens),
+    );
+    let mut result = client
+        .clone()
+        .with_ndjson_stream(stream.clone())
         .ls(
-            uri,
-            simple,
-            recursive,
+            options.uri,
+            options.simple,
+            options.recursive,
             output,
-            abs_limit,
-            show_all_hidden,
-            node_limit,
+            options.abs_limit,
+            options.show_all_hidden,
+            options.node_limit,
         )
         .await?;
+    if stream.is_some_and(|stream| stream.printed()) {
+        return Ok(());
+    }
+    if !options.tags.is_empty() {
+        super::tags::retain_tagged(client, &mut result, options.tags).await?;
+    }
+    if let Some(tokenizer) = tokens {
+        annotate(client, &mut result, Listing::Entries, tokenizer).await?;
+    }
     output_filesystem_entries(&result, output_format, compact, false);
     Ok(())
 }
 
+/// `--tag` and `--tokens` for one entry of a streamed listing: entries
+/// without the tags are dropped.
+fn decorate_entries(
+    client: &HttpClient,
+    tags: &[String],
+    tokens: Option<Tokenizer>,
+) -> Option<DecorateRecord> {
+    if tags.is_empty() && tokens.is_none() {
+        return None;
+    }
+    let client = client.clone();
+    let tags = tags.to_vec();
+    Some(Box::new(move |entry| {
+        let client = client.clone();
+        let tags = tags.clone();
+        Box::pin(async move {
+            let mut entries = Value::Array(vec![entry]);
+            if !tags.is_empty() {
+                super::tags::retain_tagged(&client, &mut entries, &tags).await?;
+            }
+            if let Some(tokenizer) = tokens {
+                annotate(&client, &mut entries, Listing::Entries, tokenizer).await?;
+            }
+            Ok(entries.as_array_mut().and_then(Vec::pop))
+        })
+    }))
+}
+
 pub async fn tree(
     client: &HttpClient,
     uri: &str,
@@ -55,7 +111,10 @@ pub async fn tree(
     output_format: OutputFormat,
     compact: bool,
 ) -> Result<()> {
+    let stream = NdjsonStream::for_output(output_format);
     let result = client
+        .clone()
+        .with_ndjson_stream(stream.clone())
         .tree(
             uri,
             output,
@@ -65,6 +124,9 @@ pub async fn tree(
             level_limit,
         )
         .await?;
+    if stream.is_some_and(|stream| stream.printed()) {
+        return Ok(());
+    }
     output_filesystem_entries(&result, output_format, compact, true);
     Ok(())
 }
@@ -76,7 +138,7 @@ fn output_filesystem_entries(
     is_tree: bool,
 ) {
     if let Some(rendered) = render_filesystem_entries_for_table(result, output_format, is_tree) {
-        println!("{rendered}");
+        crate::pager::page_or_print(&rendered);
     } else {
         output_success(result, output_format, compact);
     }
@@ -87,7 +149,7 @@ fn render_filesystem_entries_for_table(
     output_format: OutputFormat,
     is_tree: bool,
 ) -> Option<String> {
-    if matches!(output_format, OutputFormat::Json) {
+    if output_format.is_json() || crate::output::table_layout_requested() {
         return None;
     }
     if is_tree {
@@ -223,6 +285,10 @@ fn entry_metadata(object: Option<&serde_json::Map<String, Value>>) -> Vec<String
         metadata.push(theme::value(format_size(size)).bold().to_string());
     }
 
+    if let Some(tokens) = result_tokens(object) {
+        metadata.push(theme::value(tokens).bold().to_string());
+    }
+
     if let Some(mod_time) = entry_mod_time(object) {
         metadata.push(theme::muted(mod_time).to_string());
     }
@@ -381,16 +447,45 @@ pub async fn mkdir(
     Ok(())
 }
 
+/// Flags shared by single-URI and `--glob` removal.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RmOptions {
+    pub recursive: bool,
+    pub wait: bool,
+    pub timeout: Option<f64>,
+    pub dry_run: bool,
+    pub yes: bool,
+}
+
 pub async fn rm(
     client: &HttpClient,
     uri: &str,
-    recursive: bool,
-    wait: bool,
-    timeout: Option<f64>,
+    options: RmOptions,
     output_format: OutputFormat,
     compact: bool,
 ) -> Result<()> {
-    let result = client.rm(uri, recursive, wait, timeout).await?;
+    if options.dry_run {
+        // Fail the preview the same way the real delete would on a missing URI.
+        let stat = client.stat(uri).await?;
+        let kind = if entry_is_dir(stat.as_object()) {
+            "dir"
+        } else {
+            "file"
+        };
+        let uris = vec![uri.to_string()];
+        output_rm_preview(&uris, Some(kind), options, output_format, compact);
+        return Ok(());
+    }
+    // Scripts have always been able to run `ov rm -r` unattended, so only ask
+    // when someone is at the terminal.
+    if options.recursive && !confirm_removal(&[uri.to_string()], options, false)? {
+        println!("{}", theme::muted("Aborted."));
+        return Ok(());
+    }
+
+    let result = client
+        .rm(uri, options.recursive, options.wait, options.timeout)
+        .await?;
 
     let message = if let Some(count) = result
         .get("estimated_deleted_count")
@@ -406,6 +501,175 @@ pub async fn rm(
     Ok(())
 }
 
+/// Remove every URI matching `pattern`, e.g. `viking://resources/contract/2023-*`.
+pub async fn rm_glob(
+    client: &HttpClient,
+    pattern: &str,
+    options: RmOptions,
+    output_format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let (base, relative) = split_glob_pattern(pattern);
+    let result = client.glob(relative, &base, 0).await?;
+    let uris: Vec<String> = result
+        .get("matches")
+        .and_then(Value::as_array)
+        .map(|matches| {
+            matches
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if uris.is_empty() {
+        return Err(Error::Client(format!("No URIs match {pattern}")));
+    }
+    if options.dry_run {
+        output_rm_preview(&uris, None, options, output_format, compact);
+        return Ok(());
+    }
+    if !confirm_removal(&uris, options, true)? {
+        println!("{}", theme::muted("Aborted."));
+        return Ok(());
+    }
+
+    let mut removed = Vec::with_capacity(uris.len());
+    let mut interrupted = false;
+    interrupt::enter_batch();
+    for uri in &uris {
+        let step = client.rm(uri, options.recursive, options.wait, options.timeout);
+        match interrupt::cancellable(step).await {
+            Err(Error::Interrupted) => {
+                interrupted = true;
+                break;
+            }
+            result => result?,
+        };
+        if matches!(output_format, OutputFormat::Table) {
+            println!("Removed: {uri}");
+        }
+        removed.push(uri.clone());
+    }
+
+    if output_format.is_json() {
+        let count = removed.len();
+        let mut summary = serde_json::json!({ "removed": removed, "count": count });
+        if interrupted {
+            summary["interrupted"] = Value::Bool(true);
+        }
+        output_success(summary, output_format, compact);
+    } else if interrupted {
+        println!(
+            "{}",
+            theme::muted(format!(
+                "Stopped after {} of {} URI(s).",
+                removed.len(),
+                uris.len()
+            ))
+        );
+    }
+    if interrupted {
+        return Err(Error::Interrupted);
+    }
+    Ok(())
+}
+
+/// Split a glob into the literal URI prefix to search under and the pattern
+/// relative to it, which is what the glob endpoint expects.
+fn split_glob_pattern(pattern: &str) -> (String, &str) {
+    let wildcard = pattern.find(['*', '?', '[', '{']).unwrap_or(pattern.len());
+    let Some(slash) = pattern[..wildcard].rfind('/') else {
+        return ("viking://".to_string(), pattern);
+    };
+    let base = &pattern[..=slash];
+    let base = if base.ends_with("://") {
+        base
+    } else {
+        base.trim_end_matches('/')
+    };
+    (base.to_string(), &pattern[slash + 1..])
+}
+
+fn output_rm_preview(
+    uris: &[String],
+    kind: Option<&str>,
+    options: RmOptions,
+    output_format: OutputFormat,
+    compact: bool,
+) {
+    if output_format.is_json() {
+        output_success(
+            serde_json::json!({
+                "dry_run": true,
+                "recursive": options.recursive,
+                "uris": uris,
+                "count": uris.len(),
+            }),
+            output_format,
+            compact,
+        );
+        return;
+    }
+    println!(
+        "{}",
+        theme::heading(format!("Would remove {} URI(s):", uris.len())).bold()
+    );
+    for uri in uris {
+        match kind {
+            Some(kind) => println!("  {}  {}", theme::sky_value(uri), theme::muted(kind)),
+            None => println!("  {}", theme::sky_value(uri)),
+        }
+    }
+    if options.recursive {
+        println!(
+            "{}",
+            theme::muted("Directories would be removed with their contents.")
+        );
+    }
+}
+
+/// Ask before deleting. Without a terminal, `required` decides between
+/// refusing (pattern deletes) and proceeding (plain recursive deletes).
+fn confirm_removal(uris: &[String], options: RmOptions, required: bool) -> Result<bool> {
+    if options.yes {
+        return Ok(true);
+    }
+    if !(std::io::stdin().is_terminal() && std::io::stdout().is_terminal()) {
+        if !required {
+            return Ok(true);
+        }
+        return Err(Error::Client(
+            "Pattern deletion requires confirmation. Pass --yes to skip the prompt or --dry-run to preview."
+                .to_string(),
+        ));
+    }
+
+    const MAX_LISTED: usize = 10;
+    for uri in uris.iter().take(MAX_LISTED) {
+        println!("  {}", theme::sky_value(uri));
+    }
+    if uris.len() > MAX_LISTED {
+        println!(
+            "  {}",
+            theme::muted(format!("... and {} more", uris.len() - MAX_LISTED))
+        );
+    }
+    let scope = if options.recursive {
+        " and everything under them"
+    } else {
+        ""
+    };
+    print!("Remove {} URI(s){scope}? [y/N]: ", uris.len());
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    let answer = answer.trim().to_ascii_lowercase();
+    Ok(answer == "y" || answer == "yes")
+}
+
 pub async fn mv(
     client: &HttpClient,
     from_uri: &str,
@@ -423,17 +687,359 @@ pub async fn mv(
     Ok(())
 }
 
+/// Flags for `ov cp`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CpOptions {
+    pub recursive: bool,
+    pub preserve_relations: bool,
+    pub wait: bool,
+}
+
+/// Copy a resource or subtree. Files are copied through the content API so
+/// the copy is indexed like any new file; directories travel as an `.ovpack`
+/// so binary content and structure survive unchanged.
+pub async fn cp(
+    client: &HttpClient,
+    from_uri: &str,
+    to_uri: &str,
+    options: CpOptions,
+    output_format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let from_uri = from_uri.trim_end_matches('/');
+    let to_uri = to_uri.trim_end_matches('/');
+    if to_uri == from_uri || is_under_uri(to_uri, from_uri) {
+        return Err(Error::Client(format!(
+            "Cannot copy {from_uri} into itself ({to_uri})"
+        )));
+    }
+
+    let stat = client.stat(from_uri).await?;
+    let is_dir = entry_is_dir(stat.as_object());
+    if is_dir && !options.recursive {
+        return Err(Error::Client(format!(
+            "{from_uri} is a directory; pass --recursive to copy it"
+        )));
+    }
+
+    let relations = if options.preserve_relations {
+        collect_subtree_relations(client, from_uri, is_dir).await?
+    } else {
+        Vec::new()
+    };
+
+    if is_dir {
+        copy_directory(client, from_uri, to_uri, options.wait).await?;
+    } else {
+        let content = client.read(from_uri).await?;
+        client
+            .write(to_uri, &content, "create", options.wait, None)
+            .await?;
+    }
+
+    let mut linked = 0;
+    for (source, targets) in &relations {
+        let copy = rebase_uri(source, from_uri, to_uri);
+        for (reason, uris) in targets {
+            let uris: Vec<String> = uris
+                .iter()
+                .map(|uri| rebase_uri(uri, from_uri, to_uri))
+                .collect();
+            client.link(&copy, &uris, reason).await?;
+            linked += uris.len();
+        }
+    }
+
+    let mut message = format!("Copied: {} -> {}", from_uri, to_uri);
+    if options.preserve_relations {
+        message.push_str(&format!(" ({linked} relations)"));
+    }
+    output_message_result(
+        serde_json::json!({
+            "from": from_uri,
+            "to": to_uri,
+            "recursive": is_dir,
+            "relations": linked,
+        }),
+        message,
+        output_format,
+        compact,
+    );
+    Ok(())
+}
+
+async fn copy_directory(
+    client: &HttpClient,
+    from_uri: &str,
+    to_uri: &str,
+    wait: bool,
+) -> Result<()> {
+    let staging = tempfile::tempdir()?;
+    let pack_dir = staging.path().to_string_lossy().to_string();
+    let pack = client.export_ovpack(from_uri, &pack_dir, false).await?.path;
+
+    // An import always lands at `<parent>/<source name>`, so a copy under a new
+    // name (or next to its source) is imported into a scratch directory first
+    // and then moved into place.
+    let (to_parent, to_name) = split_parent_uri(to_uri);
+    let (_, from_name) = split_parent_uri(from_uri);
+    if to_name == from_name {
+        client
+            .import_ovpack(&pack, to_parent, Some("fail"), None)
+            .await?;
+    } else {
+        let scratch = format!(
+            "{}/ov-cp-{}",
+            to_parent.trim_end_matches('/'),
+            &uuid::Uuid::new_v4().simple().to_string()[..8]
+        );
+        client.mkdir(&scratch, None).await?;
+        let moved = async {
+            client
+                .import_ovpack(&pack, &scratch, Some("fail"), None)
+                .await?;
+            client.mv(&format!("{scratch}/{from_name}"), to_uri).await
+        }
+        .await;
+        client.rm(&scratch, true, false, None).await?;
+        moved?;
+    }
+
+    if wait {
+        let _: Value = client
+            .post(
+                "/api/v1/system/wait",
+                &serde_json::json!({ "timeout": null }),
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+type RelationTargets = Vec<(String, Vec<String>)>;
+
+/// Outgoing relations of `uri` and, for a directory, every node below it,
+/// grouped by reason so each group can be re-linked in one call.
+async fn collect_subtree_relations(
+    client: &HttpClient,
+    uri: &str,
+    is_dir: bool,
+) -> Result<Vec<(String, RelationTargets)>> {
+    let mut nodes = vec![uri.to_string()];
+    if is_dir {
+        let tree = client
+            .tree(uri, "original", 0, true, COPY_NODE_LIMIT, COPY_NODE_LIMIT)
+            .await?;
+        if let Some((entries, _)) = filesystem_entries(&tree) {
+            nodes.extend(
+                entries
+                    .into_iter()
+                    .filter_map(|entry| entry_string(entry.as_object(), "uri"))
+                    .map(str::to_string),
+            );
+        }
+    }
+
+    let mut relations = Vec::new();
+    for node in nodes {
+        let groups = group_relations_by_reason(&client.relations(&node).await?);
+        if !groups.is_empty() {
+            relations.push((node, groups));
+        }
+    }
+    Ok(relations)
+}
+
+fn group_relations_by_reason(relations: &Value) -> RelationTargets {
+    let mut groups: RelationTargets = Vec::new();
+    for relation in relations.as_array().into_iter().flatten() {
+        let Some(uri) = relation.get("uri").and_then(Value::as_str) else {
+            continue;
+        };
+        let reason = relation
+            .get("reason")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        match groups.iter_mut().find(|(existing, _)| existing == reason) {
+            Some((_, uris)) => uris.push(uri.to_string()),
+            None => groups.push((reason.to_string(), vec![uri.to_string()])),
+        }
+    }
+    groups
+}
+
+fn is_under_uri(uri: &str, root: &str) -> bool {
+    uri.strip_prefix(root)
+        .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Map a URI inside the copied subtree onto the copy; anything outside it is
+/// left alone so links to unrelated resources stay as they were.
+fn rebase_uri(uri: &str, from_root: &str, to_root: &str) -> String {
+    let uri = uri.trim_end_matches('/');
+    if uri == from_root || is_under_uri(uri, from_root) {
+        format!("{to_root}{}", &uri[from_root.len()..])
+    } else {
+        uri.to_string()
+    }
+}
+
+fn split_parent_uri(uri: &str) -> (&str, &str) {
+    match uri.rfind('/') {
+        Some(index) if uri[..=index].ends_with("://") => (&uri[..=index], &uri[index + 1..]),
+        Some(index) => (&uri[..index], &uri[index + 1..]),
+        None => ("viking://", uri),
+    }
+}
+
 pub async fn stat(
     client: &HttpClient,
     uri: &str,
     output_format: OutputFormat,
     compact: bool,
 ) -> Result<()> {
-    let result = client.stat(uri).await?;
-    output_success(&result, output_format, compact);
+    let mut result = client.stat(uri).await?;
+
+    // The extra lookups are best effort: the stat itself succeeded, so a
+    // missing endpoint or permission only leaves the field unknown.
+    let children = if entry_is_dir(result.as_object()) {
+        client
+            .ls(uri, true, false, "original", 0, true, STAT_CHILD_LIMIT)
+            .await
+            .ok()
+            .and_then(|entries| entries.as_array().map(Vec::len))
+    } else {
+        None
+    };
+    let relations = client
+        .relations(uri)
+        .await
+        .ok()
+        .and_then(|relations| relations.as_array().map(Vec::len));
+    let vectors = match result.get("count").and_then(Value::as_u64) {
+        Some(count) => Some(count),
+        None => client
+            .debug_vector_count(None, Some(uri.to_string()))
+            .await
+            .ok(),
+    };
+    enrich_stat_result(&mut result, uri, children, relations, vectors);
+    if let Some(metadata) = crate::commands::meta::metadata_of(client, uri)
+        .await
+        .ok()
+        .filter(|metadata| !metadata.is_empty())
+    {
+        result["metadata"] = Value::Object(metadata);
+    }
+
+    if output_format.is_json() || crate::output::table_layout_requested() {
+        output_success(&result, output_format, compact);
+    } else {
+        output_success(stat_display(&result), output_format, compact);
+    }
     Ok(())
 }
 
+/// Add the derived fields `ov stat` reports on top of the raw server stat.
+fn enrich_stat_result(
+    result: &mut Value,
+    uri: &str,
+    children: Option<usize>,
+    relations: Option<usize>,
+    vectors: Option<u64>,
+) {
+    let Some(object) = result.as_object_mut() else {
+        return;
+    };
+    object
+        .entry("uri")
+        .or_insert_with(|| Value::String(uri.to_string()));
+    if let Some(vectors) = vectors {
+        object.insert("vectors".to_string(), Value::from(vectors));
+        object.insert("vectorized".to_string(), Value::Bool(vectors > 0));
+    }
+    if let Some(children) = children {
+        object.insert("children".to_string(), Value::from(children));
+    }
+    if let Some(relations) = relations {
+        object.insert("relations".to_string(), Value::from(relations));
+    }
+}
+
+fn stat_display(result: &Value) -> Value {
+    let object = result.as_object();
+    let is_dir = entry_is_dir(object);
+    let mut display = serde_json::Map::new();
+    let mut field = |key: &str, value: Value| {
+        display.insert(key.to_string(), value);
+    };
+
+    field(
+        "uri",
+        entry_string(object, "uri").map_or(Value::Null, Value::from),
+    );
+    field("type", Value::from(if is_dir { "dir" } else { "file" }));
+    if let Some(size) = object
+        .and_then(|object| object.get("size"))
+        .and_then(Value::as_u64)
+    {
+        field("size", Value::from(format_size(size)));
+    }
+    if let Some(mod_time) = entry_mod_time(object) {
+        field("modified", Value::from(mod_time));
+    }
+    field("vectorization", Value::from(vectorization_label(object)));
+    if is_dir {
+        field(
+            "children",
+            stat_count(object, "children", Some(STAT_CHILD_LIMIT)),
+        );
+    }
+    field("relations", stat_count(object, "relations", None));
+    if let Some(metadata) = object.and_then(|object| object.get("metadata")) {
+        field("metadata", metadata.clone());
+    }
+    if object
+        .and_then(|object| object.get("isLocked"))
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+    {
+        field("locked", Value::Bool(true));
+    }
+    Value::Object(display)
+}
+
+fn vectorization_label(object: Option<&serde_json::Map<String, Value>>) -> String {
+    match object
+        .and_then(|object| object.get("vectors"))
+        .and_then(Value::as_u64)
+    {
+        Some(0) => "not indexed (not searchable)".to_string(),
+        Some(1) => "indexed (1 vector)".to_string(),
+        Some(count) => format!("indexed ({count} vectors)"),
+        None => "unknown".to_string(),
+    }
+}
+
+/// Counts that hit the listing limit are shown as a lower bound, e.g. `1000+`.
+fn stat_count(
+    object: Option<&serde_json::Map<String, Value>>,
+    key: &str,
+    limit: Option<i32>,
+) -> Value {
+    let Some(count) = object
+        .and_then(|object| object.get(key))
+        .and_then(Value::as_i64)
+    else {
+        return Value::from("unknown");
+    };
+    if limit.is_some_and(|limit| count >= i64::from(limit)) {
+        Value::from(format!("{count}+"))
+    } else {
+        Value::from(count)
+    }
+}
+
 pub async fn attrs(
     client: &HttpClient,
     uri: &str,
@@ -466,7 +1072,7 @@ fn output_message_result(
     compact: bool,
 ) {
     match output_format {
-        OutputFormat::Json => output_success(result, output_format, compact),
+        OutputFormat::Json | OutputFormat::Ndjson => output_success(result, output_format, compact),
         OutputFormat::Table => {
             println!(
                 "{}",
@@ -479,12 +1085,149 @@ fn output_message_result(
 #[cfg(test)]
 mod tests {
     use super::{
+        enrich_stat_result, group_relations_by_reason, rebase_uri,
         render_filesystem_entries_for_table, render_ls_entries_for_table,
-        render_tree_entries_for_table,
+        render_tree_entries_for_table, split_glob_pattern, split_parent_uri, stat_display,
     };
     use crate::output::render_profiled_scalar_result;
     use serde_json::json;
 
+    #[test]
+    fn copy_rebases_only_uris_inside_the_copied_subtree() {
+        let from = "viking://resources/contract";
+        let to = "viking://archive/contract-2023";
+
+        assert_eq!(rebase_uri(from, from, to), to);
+        assert_eq!(
+            rebase_uri("viking://resources/contract/a.md", from, to),
+            "viking://archive/contract-2023/a.md"
+        );
+        assert_eq!(
+            rebase_uri("viking://resources/contract-old/a.md", from, to),
+            "viking://resources/contract-old/a.md"
+        );
+        assert_eq!(
+            split_parent_uri("viking://resources/contract"),
+            ("viking://resources", "contract")
+        );
+        assert_eq!(
+            split_parent_uri("viking://resources"),
+            ("viking://", "resources")
+        );
+    }
+
+    #[test]
+    fn relations_are_grouped_by_reason_for_relinking() {
+        let relations = json!([
+            {"uri": "viking://a", "reason": "cites"},
+            {"uri": "viking://b", "reason": ""},
+            {"uri": "viking://c", "reason": "cites"},
+            {"reason": "missing uri"}
+        ]);
+
+        assert_eq!(
+            group_relations_by_reason(&relations),
+            vec![
+                (
+                    "cites".to_string(),
+                    vec!["viking://a".to_string(), "viking://c".to_string()]
+                ),
+                (String::new(), vec!["viking://b".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn glob_pattern_splits_into_literal_base_and_relative_pattern() {
+        assert_eq!(
+            split_glob_pattern("viking://resources/contract/2023-*"),
+            ("viking://resources/contract".to_string(), "2023-*")
+        );
+        assert_eq!(
+            split_glob_pattern("viking://resources/**/draft-?.md"),
+            ("viking://resources".to_string(), "**/draft-?.md")
+        );
+        assert_eq!(
+            split_glob_pattern("viking://*"),
+            ("viking://".to_string(), "*")
+        );
+        assert_eq!(
+            split_glob_pattern("*.md"),
+            ("viking://".to_string(), "*.md")
+        );
+    }
+
+    #[test]
+    fn stat_reports_vectorization_children_and_relations() {
+        let mut result = json!({
+            "name": "contract",
+            "size": 2048,
+            "modTime": "not-a-date",
+            "isDir": true,
+            "isLocked": false,
+            "count": 0
+        });
+
+        enrich_stat_result(
+            &mut result,
+            "viking://resources/contract",
+            Some(1000),
+            Some(2),
+            Some(0),
+        );
+
+        assert_eq!(result["vectorized"], json!(false));
+        assert_eq!(result["children"], json!(1000));
+        assert_eq!(
+            stat_display(&result),
+            json!({
+                "uri": "viking://resources/contract",
+                "type": "dir",
+                "size": "2.0 KB",
+                "modified": "not-a-date",
+                "vectorization": "not indexed (not searchable)",
+                "children": "1000+",
+                "relations": 2
+            })
+        );
+    }
+
+    #[test]
+    fn stat_marks_unavailable_lookups_as_unknown() {
+        let mut result = json!({"name": "a.md", "size": 12, "isDir": false});
+
+        enrich_stat_result(&mut result, "viking://resources/a.md", None, None, Some(3));
+
+        assert_eq!(
+            stat_display(&result),
+            json!({
+                "uri": "viking://resources/a.md",
+                "type": "file",
+                "size": "12 B",
+                "vectorization": "indexed (3 vectors)",
+                "relations": "unknown"
+            })
+        );
+    }
+
+    #[test]
+    fn stat_display_includes_user_metadata() {
+        let mut result = json!({"name": "a.md", "size": 12, "isDir": false});
+        enrich_stat_result(
+            &mut result,
+            "viking://resources/a.md",
+            None,
+            Some(0),
+            Some(1),
+        );
+        result["metadata"] = json!({"source": "crm", "batch": 42});
+
+        assert_eq!(
+            stat_display(&result)["metadata"],
+            json!({"source": "crm", "batch": 42})
+        );
+    }
+
     #[test]
     fn profiled_filesystem_message_includes_profile_section() {
         let result = json!({
@@ -0,0 +1,254 @@
+//! `ov gen-man`: roff man pages and a JSON description of the whole command
+//! tree, generated from the clap definitions so wrappers and documentation
+//! cannot drift from the CLI.
+//!
+//! The JSON keeps hidden commands and options, marked as such, since wrappers
+//! need the global flags the help screens leave out.
+
+use std::any::TypeId;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use clap::{Arg, ArgAction, Command};
+use serde_json::{Value, json};
+
+use crate::error::Result;
+use crate::help_ui::{command_about_tags, strip_command_tags};
+use crate::output::{OutputFormat, output_success};
+
+/// Print the `ov` man page, or with `-o json` the command tree; with an
+/// output directory, write a page per command plus `ov.json` there instead.
+pub fn run(
+    command: Command,
+    out_dir: Option<&Path>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let command = without_tags(command.name("ov").bin_name("ov"));
+    let Some(out_dir) = out_dir else {
+        if format.is_json() {
+            output_success(describe(&command, &[]), format, compact);
+        } else {
+            let mut page = Vec::new();
+            man_page(command).render(&mut page)?;
+            std::io::stdout().write_all(&page)?;
+        }
+        return Ok(());
+    };
+
+    std::fs::create_dir_all(out_dir)?;
+    let description = serde_json::to_string_pretty(&describe(&command, &[]))?;
+    let json_path = out_dir.join("ov.json");
+    std::fs::write(&json_path, description + "\n")?;
+    let mut command = command.disable_help_subcommand(true);
+    command.build();
+    let man_pages = write_man_pages(&command, out_dir)?;
+    if format.is_json() {
+        output_success(
+            json!({
+                "out_dir": out_dir.display().to_string(),
+                "man_pages": man_pages,
+                "json": json_path.display().to_string(),
+            }),
+            format,
+            compact,
+        );
+    } else {
+        println!(
+            "Wrote {} man page(s) and {} to {}",
+            man_pages,
+            json_path.display(),
+            out_dir.display()
+        );
+    }
+    Ok(())
+}
+
+/// Every subcommand carries the CLI version in its page footer, which
+/// clap would otherwise leave to the top-level page only.
+fn man_page(command: Command) -> clap_mangen::Man {
+    clap_mangen::Man::new(command)
+        .source(format!("ov {}", env!("OPENVIKING_CLI_VERSION")))
+        .manual("OpenViking Manual")
+}
+
+/// One page for `command` and each visible command below it, named after the
+/// command path, e.g. `ov-index-rebuild.1`. Returns how many were written.
+fn write_man_pages(command: &Command, out_dir: &Path) -> Result<usize> {
+    let mut written = 0;
+    for subcommand in command.get_subcommands().filter(|sub| !sub.is_hide_set()) {
+        written += write_man_pages(subcommand, out_dir)?;
+    }
+    man_page(command.clone()).generate_to(out_dir)?;
+    Ok(written + 1)
+}
+
+/// Help groups such as `[Data]` sort the command list on screen; in a man
+/// page they would only clutter the one-line description.
+fn without_tags(command: Command) -> Command {
+    let about = command
+        .get_about()
+        .map(|about| strip_command_tags(&about.to_string()).to_string());
+    let command = match about {
+        Some(about) => command.about(about),
+        None => command,
+    };
+    command.mut_subcommands(without_tags)
+}
+
+/// `command` and everything below it. `parents` are the names leading to it.
+fn describe(command: &Command, parents: &[&str]) -> Value {
+    let mut path = parents.to_vec();
+    path.push(command.get_name());
+    let about = command.get_about().map(ToString::to_string);
+    json!({
+        "name": command.get_name(),
+        "command": path.join(" "),
+        "about": about.as_deref().map(strip_command_tags),
+        "tags": about.as_deref().map(command_about_tags).unwrap_or_default(),
+        "aliases": command.get_visible_aliases().collect::<Vec<_>>(),
+        "hidden": command.is_hide_set(),
+        "arguments": command
+            .get_arguments()
+            .map(describe_arg)
+            .collect::<Vec<_>>(),
+        "subcommands": command
+            .get_subcommands()
+            .map(|subcommand| describe(subcommand, &path))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn describe_arg(arg: &Arg) -> Value {
+    json!({
+        "name": arg.get_id().as_str(),
+        "long": arg.get_long().map(|long| format!("--{long}")),
+        "short": arg.get_short().map(|short| format!("-{short}")),
+        "aliases": arg.get_visible_aliases().unwrap_or_default(),
+        "positional": arg.is_positional(),
+        "type": value_type(arg),
+        "value_names": arg
+            .get_value_names()
+            .map(|names| names.iter().map(|name| name.as_str()).collect::<Vec<_>>()),
+        "required": arg.is_required_set(),
+        "multiple": matches!(arg.get_action(), ArgAction::Append)
+            || arg.get_num_args().is_some_and(|range| range.max_values() > 1),
+        "default": arg
+            .get_default_values()
+            .iter()
+            .map(|value| value.to_string_lossy().into_owned())
+            .collect::<Vec<_>>(),
+        "possible_values": arg
+            .get_possible_values()
+            .iter()
+            .filter(|value| !value.is_hide_set())
+            .map(|value| value.get_name().to_string())
+            .collect::<Vec<_>>(),
+        "env": arg.get_env().map(|env| env.to_string_lossy().into_owned()),
+        "global": arg.is_global_set(),
+        "hidden": arg.is_hide_set(),
+        "help_heading": arg.get_help_heading(),
+        "help": arg.get_help().map(ToString::to_string),
+    })
+}
+
+/// The kind of value an argument takes, from its action and value parser.
+fn value_type(arg: &Arg) -> &'static str {
+    match arg.get_action() {
+        ArgAction::SetTrue | ArgAction::SetFalse => return "bool",
+        ArgAction::Count => return "count",
+        ArgAction::Help | ArgAction::HelpShort | ArgAction::HelpLong | ArgAction::Version => {
+            return "flag";
+        }
+        _ => {}
+    }
+    if !arg.get_possible_values().is_empty() {
+        return "enum";
+    }
+    let parsed = arg.get_value_parser().type_id();
+    let is = |type_id: TypeId| parsed == type_id;
+    if is(TypeId::of::<bool>()) {
+        "bool"
+    } else if [
+        TypeId::of::<i8>(),
+        TypeId::of::<i16>(),
+        TypeId::of::<i32>(),
+        TypeId::of::<i64>(),
+        TypeId::of::<u8>(),
+        TypeId::of::<u16>(),
+        TypeId::of::<u32>(),
+        TypeId::of::<u64>(),
+        TypeId::of::<usize>(),
+    ]
+    .into_iter()
+    .any(is)
+    {
+        "integer"
+    } else if is(TypeId::of::<f32>()) || is(TypeId::of::<f64>()) {
+        "number"
+    } else if is(TypeId::of::<PathBuf>()) {
+        "path"
+    } else {
+        "string"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn description_covers_arguments_types_and_subcommands() {
+        let command = Command::new("ov").subcommand(
+            Command::new("find")
+                .about("[Data] Semantic search")
+                .arg(Arg::new("query").value_name("query").required(true))
+                .arg(
+                    Arg::new("limit")
+                        .long("limit")
+                        .short('n')
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("10"),
+                )
+                .arg(Arg::new("mode").long("mode").value_parser(["fast", "deep"]))
+                .arg(Arg::new("tag").long("tag").action(ArgAction::Append))
+                .arg(
+                    Arg::new("wait")
+                        .long("wait")
+                        .action(ArgAction::SetTrue)
+                        .hide(true),
+                ),
+        );
+        let description = describe(&command, &[]);
+        let find = &description["subcommands"][0];
+
+        assert_eq!(find["command"], "ov find");
+        assert_eq!(find["about"], "Semantic search");
+        assert_eq!(find["tags"], json!(["Data"]));
+        let arguments = find["arguments"].as_array().unwrap();
+        assert_eq!(arguments[0]["positional"], true);
+        assert_eq!(arguments[0]["required"], true);
+        assert_eq!(arguments[0]["type"], "string");
+        assert_eq!(arguments[1]["short"], "-n");
+        assert_eq!(arguments[1]["type"], "integer");
+        assert_eq!(arguments[1]["default"], json!(["10"]));
+        assert_eq!(arguments[2]["type"], "enum");
+        assert_eq!(arguments[2]["possible_values"], json!(["fast", "deep"]));
+        assert_eq!(arguments[3]["multiple"], true);
+        assert_eq!(arguments[4]["type"], "bool");
+        assert_eq!(arguments[4]["hidden"], true);
+    }
+
+    #[test]
+    fn man_pages_drop_help_group_tags() {
+        let command = without_tags(
+            Command::new("ov")
+                .subcommand(Command::new("stat").about("[Data] Get resource metadata")),
+        );
+        let stat = command.find_subcommand("stat").unwrap();
+        assert_eq!(
+            stat.get_about().unwrap().to_string(),
+            "Get resource metadata"
+        );
+    }
+}
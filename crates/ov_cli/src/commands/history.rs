@@ -0,0 +1,269 @@
+//! Local command history and `ov recent`.
+//!
+//! Every command that succeeds is appended to `~/.openviking/history.jsonl`:
+//! its arguments as typed and the `viking://` URIs it named, after `@name`
+//! aliases were expanded. Only the newest [`MAX_ENTRIES`] are kept. Setting
+//! `"history": false` in the config turns recording off; `ov recent --clear`
+//! deletes what was recorded.
+
+use std::ffi::OsString;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::config::default_config_path;
+use crate::error::{Error, Result};
+use crate::output::{OutputFormat, output_success};
+
+/// How many commands the history keeps.
+const MAX_ENTRIES: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Entry {
+    at: String,
+    args: Vec<String>,
+    #[serde(default)]
+    uris: Vec<String>,
+}
+
+/// Which part of the history `ov recent` shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecentKind {
+    /// Commands with the URIs they named.
+    All,
+    /// URIs, most recently used first, each once.
+    Uris,
+    /// Command lines only.
+    Commands,
+}
+
+fn history_path() -> Result<PathBuf> {
+    let config_path = default_config_path()?;
+    let home = config_path.parent().unwrap_or(Path::new("."));
+    Ok(home.join("history.jsonl"))
+}
+
+fn read_history(path: &Path) -> Result<Vec<Entry>> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line).map_err(|e| {
+                Error::Parse(format!("Corrupt history entry in {}: {e}", path.display()))
+            })
+        })
+        .collect()
+}
+
+fn write_history(path: &Path, entries: &[Entry]) -> Result<()> {
+    let temp = path.with_extension("jsonl.tmp");
+    let mut text = String::new();
+    for entry in entries {
+        text.push_str(&serde_json::to_string(entry)?);
+        text.push('\n');
+    }
+    std::fs::write(&temp, text)?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
+}
+
+/// Append `entry`, dropping the oldest entries once the file holds twice the
+/// limit, so most commands only append a line.
+fn append(path: &Path, entry: &Entry) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    drop(file);
+
+    let lines = std::fs::read(path)?
+        .iter()
+        .filter(|byte| **byte == b'\n')
+        .count();
+    if lines >= MAX_ENTRIES * 2 {
+        let entries = read_history(path)?;
+        write_history(path, &entries[entries.len().saturating_sub(MAX_ENTRIES)..])?;
+    }
+    Ok(())
+}
+
+/// The `viking://` URIs among `args`, including `--flag=viking://...` values,
+/// each once and in order.
+fn touched_uris(args: &[OsString]) -> Vec<String> {
+    let mut uris: Vec<String> = Vec::new();
+    for arg in args.iter().skip(1).filter_map(|arg| arg.to_str()) {
+        let value = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => value,
+            _ => arg,
+        };
+        if value.starts_with("viking://") && !uris.iter().any(|uri| uri == value) {
+            uris.push(value.to_string());
+        }
+    }
+    uris
+}
+
+/// Record a command that succeeded: `typed` as the user wrote it, and `run`
+/// after `@name` aliases were expanded.
+pub fn record(typed: &[OsString], run: &[OsString]) -> Result<()> {
+    let entry = Entry {
+        at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        args: typed
+            .iter()
+            .skip(1)
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect(),
+        uris: touched_uris(run),
+    };
+    append(&history_path()?, &entry)
+}
+
+/// The rows `ov recent` prints for `entries`, newest first.
+fn recent_rows(entries: &[Entry], kind: RecentKind, limit: usize) -> Vec<Value> {
+    let newest_first = entries.iter().rev();
+    match kind {
+        RecentKind::All => newest_first
+            .take(limit)
+            .map(|entry| {
+                json!({
+                    "at": entry.at,
+                    "command": super::render_utils::command_line(&entry.args),
+                    "uris": entry.uris.join(" "),
+                })
+            })
+            .collect(),
+        RecentKind::Commands => newest_first
+            .take(limit)
+            .map(|entry| {
+                json!({
+                    "at": entry.at,
+                    "command": super::render_utils::command_line(&entry.args),
+                })
+            })
+            .collect(),
+        RecentKind::Uris => {
+            let mut seen: Vec<&str> = Vec::new();
+            let mut rows = Vec::new();
+            for entry in newest_first {
+                for uri in &entry.uris {
+                    if rows.len() == limit {
+                        return rows;
+                    }
+                    if !seen.contains(&uri.as_str()) {
+                        seen.push(uri);
+                        rows.push(json!({
+                            "uri": uri,
+                            "at": entry.at,
+                            "command": entry.args.first().cloned().unwrap_or_default(),
+                        }));
+                    }
+                }
+            }
+            rows
+        }
+    }
+}
+
+pub fn recent(kind: RecentKind, limit: usize, format: OutputFormat, compact: bool) -> Result<()> {
+    let entries = read_history(&history_path()?)?;
+    output_success(recent_rows(&entries, kind, limit), format, compact);
+    Ok(())
+}
+
+pub fn clear(format: OutputFormat, compact: bool) -> Result<()> {
+    let path = history_path()?;
+    let removed = read_history(&path).map_or(0, |entries| entries.len());
+    match std::fs::remove_file(&path) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    output_success(json!({ "cleared": removed }), format, compact);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(items: &[&str]) -> Vec<OsString> {
+        items.iter().map(OsString::from).collect()
+    }
+
+    fn entry(at: &str, items: &[&str]) -> Entry {
+        let args = args(items);
+        Entry {
+            at: at.to_string(),
+            args: items[1..].iter().map(|item| item.to_string()).collect(),
+            uris: touched_uris(&args),
+        }
+    }
+
+    #[test]
+    fn uris_are_taken_from_arguments_and_flag_values() {
+        assert_eq!(
+            touched_uris(&args(&[
+                "ov",
+                "find",
+                "terms",
+                "--uri=viking://resources/a",
+                "viking://resources/b.md",
+                "viking://resources/a",
+            ])),
+            vec!["viking://resources/a", "viking://resources/b.md"]
+        );
+        assert!(touched_uris(&args(&["ov", "status"])).is_empty());
+    }
+
+    #[test]
+    fn recent_uris_are_newest_first_and_unique() {
+        let entries = vec![
+            entry(
+                "2026-10-17T09:00:00Z",
+                &["ov", "read", "viking://resources/a.md"],
+            ),
+            entry(
+                "2026-10-17T09:05:00Z",
+                &["ov", "read", "viking://resources/b.md"],
+            ),
+            entry(
+                "2026-10-17T09:10:00Z",
+                &["ov", "stat", "viking://resources/a.md"],
+            ),
+            entry("2026-10-17T09:12:00Z", &["ov", "status"]),
+        ];
+        let rows = recent_rows(&entries, RecentKind::Uris, 10);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["uri"], "viking://resources/a.md");
+        assert_eq!(rows[0]["command"], "stat");
+        assert_eq!(rows[1]["uri"], "viking://resources/b.md");
+
+        let commands = recent_rows(&entries, RecentKind::Commands, 2);
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0]["command"], "ov status");
+        assert_eq!(recent_rows(&entries, RecentKind::Uris, 1).len(), 1);
+    }
+
+    #[test]
+    fn history_is_trimmed_to_the_newest_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        assert!(read_history(&path).unwrap().is_empty());
+        for n in 0..MAX_ENTRIES * 2 {
+            append(&path, &entry(&n.to_string(), &["ov", "status"])).unwrap();
+        }
+        let entries = read_history(&path).unwrap();
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries[0].at, MAX_ENTRIES.to_string());
+    }
+}
@@ -0,0 +1,565 @@
+//! `ov index verify` and `ov index repair`: cross-check stored content against
+//! the vector index under a prefix, then re-enqueue whatever never made it in.
+//!
+//! Missing records come from the server's consistency check, which lists at
+//! most 20 of them. Orphans (vectors whose content is gone) come from a
+//! `prune_orphans` reindex dry run, which only reports a count.
+//!
+//! `ov index rebuild` re-embeds a subtree from scratch, e.g. after switching
+//! embedding models. It splits the prefix into its immediate children and
+//! reindexes each one as its own server job, a few at a time, so progress
+//! can be reported and one bad child does not abort the rest; the jobs show up
+//! in `ov jobs ls` while they run. The prefix's
+//! own abstract and overview records are not part of any child; `ov reindex
+//! <prefix>` covers those.
+
+use std::time::Instant;
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use futures::StreamExt;
+use serde_json::{Value, json};
+
+use crate::client::HttpClient;
+use crate::error::{ApiErrorKind, Error, Result};
+use crate::interrupt;
+use crate::output::{OutputFormat, output_success};
+use crate::theme;
+
+/// Upper bound on the subtree listing `ov index rebuild` plans from.
+const REBUILD_NODE_LIMIT: i32 = 100_000;
+/// Seconds between polls of each rebuild job.
+const REBUILD_POLL_INTERVAL: f64 = 1.0;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Verification {
+    expected: u64,
+    missing: u64,
+    missing_records: Vec<Value>,
+    truncated: bool,
+    /// `None` when the orphan scan could not run, with the reason in `note`.
+    orphans: Option<u64>,
+    note: Option<String>,
+}
+
+impl Verification {
+    fn consistent(&self) -> bool {
+        self.missing == 0 && self.orphans.unwrap_or(0) == 0
+    }
+}
+
+async fn verification(client: &HttpClient, prefix: &str) -> Result<Verification> {
+    let report = client.consistency(prefix).await?;
+    let (orphans, note) = match client.reindex(prefix, "prune_orphans", true, true).await {
+        Ok(scan) => (scan["would_delete_records"].as_u64(), None),
+        Err(e) if e.api_kind() == Some(ApiErrorKind::PermissionDenied) => (
+            None,
+            Some(format!(
+                "orphan scan needs admin rights for {prefix}; rerun with --sudo"
+            )),
+        ),
+        Err(e @ (Error::Network(_) | Error::Interrupted)) => return Err(e),
+        Err(e) => (None, Some(format!("orphan scan failed: {e}"))),
+    };
+    Ok(Verification {
+        expected: report["expected_count"].as_u64().unwrap_or(0),
+        missing: report["missing_record_count"].as_u64().unwrap_or(0),
+        missing_records: report["missing_records"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default(),
+        truncated: report["missing_records_truncated"].as_bool() == Some(true),
+        orphans,
+        note,
+    })
+}
+
+pub async fn verify(
+    client: &HttpClient,
+    prefix: &str,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let found = verification(client, prefix).await?;
+    if matches!(format, OutputFormat::Table) {
+        let orphans = found
+            .orphans
+            .map_or_else(|| "unknown".to_string(), |count| count.to_string());
+        println!(
+            "{prefix}: {} records expected, {} missing, {orphans} orphaned",
+            found.expected, found.missing
+        );
+        if !found.missing_records.is_empty() {
+            output_success(Value::Array(found.missing_records.clone()), format, compact);
+        }
+        if found.truncated {
+            println!(
+                "{}",
+                theme::muted(format!(
+                    "Showing {} of {} missing records.",
+                    found.missing_records.len(),
+                    found.missing
+                ))
+            );
+        }
+        if let Some(note) = &found.note {
+            eprintln!("{}", theme::warning(note));
+        }
+        if found.consistent() {
+            println!("{}", theme::success("Index is consistent."));
+        } else {
+            let prune = if found.orphans.unwrap_or(0) > 0 {
+                " --prune-orphans"
+            } else {
+                ""
+            };
+            eprintln!(
+                "{}",
+                theme::muted(format!(
+                    "Fix with: ov index repair --prefix {prefix}{prune}"
+                ))
+            );
+        }
+    } else {
+        output_success(
+            json!({
+                "prefix": prefix,
+                "consistent": found.consistent(),
+                "expected_count": found.expected,
+                "missing_record_count": found.missing,
+                "missing_records": found.missing_records,
+                "missing_records_truncated": found.truncated,
+                "orphan_record_count": found.orphans,
+                "note": found.note,
+            }),
+            format,
+            compact,
+        );
+    }
+    if !found.consistent() {
+        return Err(Error::AlreadyReported);
+    }
+    Ok(())
+}
+
+/// The URIs to reindex for a set of missing records: each distinct URI once,
+/// skipping any that sit under another URI already in the list.
+fn repair_targets(missing_records: &[Value]) -> Vec<String> {
+    let mut uris: Vec<&str> = missing_records
+        .iter()
+        .filter_map(|record| record["uri"].as_str())
+        .collect();
+    uris.sort_unstable();
+    uris.dedup();
+    let mut targets: Vec<String> = Vec::new();
+    for uri in uris {
+        let covered = targets.iter().any(|parent| {
+            uri.strip_prefix(parent.trim_end_matches('/'))
+                .is_some_and(|rest| rest.starts_with('/'))
+        });
+        if !covered {
+            targets.push(uri.to_string());
+        }
+    }
+    targets
+}
+
+pub struct RepairOptions {
+    pub prune_orphans: bool,
+    pub wait: bool,
+    pub dry_run: bool,
+}
+
+pub async fn repair(
+    client: &HttpClient,
+    prefix: &str,
+    options: RepairOptions,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let report = client.consistency(prefix).await?;
+    let missing_records = report["missing_records"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    // The server lists only the first few missing records; past that, rebuild
+    // the whole prefix rather than guess at the rest.
+    let targets = if report["missing_records_truncated"].as_bool() == Some(true) {
+        vec![prefix.to_string()]
+    } else {
+        repair_targets(&missing_records)
+    };
+
+    let mut plan: Vec<(String, &str)> = targets
+        .into_iter()
+        .map(|uri| (uri, "vectors_only"))
+        .collect();
+    if options.prune_orphans {
+        plan.push((prefix.to_string(), "prune_orphans"));
+    }
+    if plan.is_empty() {
+        if matches!(format, OutputFormat::Table) {
+            println!(
+                "{}",
+                theme::success(format!("Nothing to repair under {prefix}."))
+            );
+        } else {
+            output_success(json!({ "prefix": prefix, "repairs": [] }), format, compact);
+        }
+        return Ok(());
+    }
+
+    let mut rows = Vec::with_capacity(plan.len());
+    for (uri, mode) in plan {
+        let row = if options.dry_run {
+            json!({ "uri": uri, "mode": mode, "status": "planned", "task_id": null })
+        } else {
+            let result = client.reindex(&uri, mode, options.wait, false).await?;
+            json!({
+                "uri": uri,
+                "mode": mode,
+                "status": result["status"],
+                "task_id": result["task_id"],
+            })
+        };
+        rows.push(row);
+    }
+
+    if matches!(format, OutputFormat::Table) {
+        output_success(Value::Array(rows), format, compact);
+        if !options.dry_run && !options.wait {
+            eprintln!(
+                "{}",
+                theme::muted(format!(
+                    "Re-enqueued. Follow with: ov queue wait {prefix} && ov index verify --prefix {prefix}"
+                ))
+            );
+        }
+    } else {
+        output_success(
+            json!({ "prefix": prefix, "dry_run": options.dry_run, "repairs": rows }),
+            format,
+            compact,
+        );
+    }
+    Ok(())
+}
+
+/// Parse a `--since`-style `flag`: a relative age (`30m`, `48h`, `7d`, `2w`),
+/// a date (midnight UTC), or an ISO-8601 timestamp (UTC unless it has an
+/// offset).
+pub(crate) fn parse_since(flag: &str, value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let value = value.trim();
+    if let Some(unit) = value.chars().last()
+        && let Ok(amount) = value[..value.len() - unit.len_utf8()].parse::<i64>()
+    {
+        let age = match unit {
+            's' => Some(Duration::seconds(amount)),
+            'm' => Some(Duration::minutes(amount)),
+            'h' => Some(Duration::hours(amount)),
+            'd' => Some(Duration::days(amount)),
+            'w' => Some(Duration::weeks(amount)),
+            _ => None,
+        };
+        if let Some(age) = age {
+            return Ok(now - age);
+        }
+    }
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    if let Ok(time) = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f") {
+        return Ok(time.and_utc());
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    Err(Error::Client(format!(
+        "Invalid {flag} '{value}'; use e.g. 48h, 7d, 2026-03-10, or an ISO-8601 time"
+    )))
+}
+
+fn entry_changed_since(entry: &Value, since: DateTime<Utc>) -> bool {
+    entry["modTime"]
+        .as_str()
+        .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+        // An entry without a readable time is rebuilt rather than skipped.
+        .is_none_or(|time| time.with_timezone(&Utc) >= since)
+}
+
+/// The immediate children of `prefix` to rebuild, given every entry below it.
+/// With `since`, a child is kept only if it or anything under it changed.
+fn rebuild_units(prefix: &str, entries: &[Value], since: Option<DateTime<Utc>>) -> Vec<String> {
+    let base = format!("{}/", prefix.trim_end_matches('/'));
+    let mut units: Vec<String> = entries
+        .iter()
+        .filter_map(|entry| entry["uri"].as_str())
+        .filter_map(|uri| uri.strip_prefix(&base).map(|name| (uri, name)))
+        .filter(|(_, name)| !name.is_empty() && !name.contains('/') && !name.starts_with('.'))
+        .map(|(uri, _)| uri.to_string())
+        .collect();
+    units.sort_unstable();
+    units.dedup();
+    if let Some(since) = since {
+        units.retain(|unit| {
+            let below = format!("{unit}/");
+            entries.iter().any(|entry| {
+                entry["uri"]
+                    .as_str()
+                    .is_some_and(|uri| uri == unit || uri.starts_with(&below))
+                    && entry_changed_since(entry, since)
+            })
+        });
+    }
+    units
+}
+
+/// Rebuild the vectors of `uri` as a server job and return its report.
+async fn rebuild_job(client: &HttpClient, uri: &str) -> Result<Value> {
+    let submitted = client.reindex(uri, "vectors_only", false, false).await?;
+    match submitted["task_id"].as_str() {
+        Some(id) => {
+            let job = super::jobs::wait_for(client, id, None, REBUILD_POLL_INTERVAL, false).await?;
+            Ok(job["result"].clone())
+        }
+        None => Ok(submitted),
+    }
+}
+
+pub struct RebuildOptions<'a> {
+    pub changed_since: Option<&'a str>,
+    pub concurrency: usize,
+    pub dry_run: bool,
+}
+
+pub async fn rebuild(
+    client: &HttpClient,
+    prefix: &str,
+    options: RebuildOptions<'_>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    if options.concurrency == 0 {
+        return Err(Error::Client(
+            "--concurrency must be at least 1".to_string(),
+        ));
+    }
+    let since = options
+        .changed_since
+        .map(|value| parse_since("--changed-since", value, Utc::now()))
+        .transpose()?;
+
+    let stat = client.stat(prefix).await?;
+    let units = if stat["isDir"].as_bool() == Some(true) {
+        let tree = client
+            .tree(
+                prefix,
+                "original",
+                0,
+                true,
+                REBUILD_NODE_LIMIT,
+                REBUILD_NODE_LIMIT,
+            )
+            .await?;
+        let entries = tree.as_array().cloned().unwrap_or_default();
+        rebuild_units(prefix, &entries, since)
+    } else if since.is_none_or(|since| entry_changed_since(&stat, since)) {
+        vec![prefix.to_string()]
+    } else {
+        Vec::new()
+    };
+    let since_label = since.map(|since| since.to_rfc3339());
+
+    if units.is_empty() || options.dry_run {
+        if matches!(format, OutputFormat::Table) {
+            if units.is_empty() {
+                println!(
+                    "{}",
+                    theme::success(format!("Nothing to rebuild under {prefix}."))
+                );
+            } else {
+                let rows: Vec<Value> = units.iter().map(|uri| json!({ "uri": uri })).collect();
+                output_success(Value::Array(rows), format, compact);
+                println!(
+                    "{}",
+                    theme::muted(format!("{} item(s) would be rebuilt.", units.len()))
+                );
+            }
+        } else {
+            output_success(
+                json!({
+                    "prefix": prefix,
+                    "changed_since": since_label,
+                    "dry_run": options.dry_run,
+                    "planned": units,
+                }),
+                format,
+                compact,
+            );
+        }
+        return Ok(());
+    }
+
+    interrupt::enter_batch();
+    let total = units.len();
+    let mut rebuilds = futures::stream::iter(units)
+        .map(|uri| async move {
+            let started = Instant::now();
+            let result = rebuild_job(client, &uri).await;
+            (uri, result, started.elapsed().as_secs_f64())
+        })
+        .buffer_unordered(options.concurrency);
+    let mut rows = Vec::with_capacity(total);
+    let (mut rebuilt, mut failed, mut failed_items) = (0, 0, 0);
+    let mut interrupted = false;
+    while rows.len() < total {
+        let Ok(Some((uri, result, seconds))) =
+            interrupt::cancellable(async { Ok(rebuilds.next().await) }).await
+        else {
+            interrupted = true;
+            break;
+        };
+        let row = match result {
+            Ok(report) => {
+                let done = report["rebuilt_records"].as_u64().unwrap_or(0);
+                let errors = report["failed_records"].as_u64().unwrap_or(0);
+                rebuilt += done;
+                failed += errors;
+                json!({
+                    "uri": uri,
+                    "status": if errors == 0 { "rebuilt" } else { "partial" },
+                    "rebuilt_records": done,
+                    "failed_records": errors,
+                    "seconds": (seconds * 10.0).round() / 10.0,
+                    "error": (errors > 0)
+                        .then(|| report["warnings"].as_array().and_then(|w| w.first()).cloned())
+                        .flatten(),
+                })
+            }
+            Err(e @ Error::Network(_)) => return Err(e),
+            Err(e) => {
+                failed_items += 1;
+                json!({
+                    "uri": uri,
+                    "status": "failed",
+                    "rebuilt_records": 0,
+                    "failed_records": null,
+                    "seconds": (seconds * 10.0).round() / 10.0,
+                    "error": e.to_string(),
+                })
+            }
+        };
+        eprintln!(
+            "{}",
+            theme::muted(format!(
+                "[{}/{total}] {uri}: {}",
+                rows.len() + 1,
+                row["status"].as_str().unwrap_or_default()
+            ))
+        );
+        rows.push(row);
+    }
+    drop(rebuilds);
+
+    let done = rows.len();
+    if matches!(format, OutputFormat::Table) {
+        output_success(Value::Array(rows), format, compact);
+        println!(
+            "{}",
+            theme::muted(format!(
+                "{done} of {total} item(s) processed under {prefix}: {rebuilt} records rebuilt, {failed} record(s) and {failed_items} item(s) failed"
+            ))
+        );
+    } else {
+        output_success(
+            json!({
+                "prefix": prefix,
+                "changed_since": since_label,
+                "planned": total,
+                "completed": done,
+                "rebuilt_records": rebuilt,
+                "failed_records": failed,
+                "failed_items": failed_items,
+                "results": rows,
+            }),
+            format,
+            compact,
+        );
+    }
+    if interrupted {
+        return Err(Error::Interrupted);
+    }
+    if failed > 0 || failed_items > 0 {
+        return Err(Error::AlreadyReported);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repair_targets_dedupes_and_skips_covered_uris() {
+        let records = vec![
+            json!({"uri": "viking://resources/docs/a.md", "level": 2}),
+            json!({"uri": "viking://resources/docs", "level": 0}),
+            json!({"uri": "viking://resources/docs", "level": 1}),
+            json!({"uri": "viking://resources/docs-old/b.md", "level": 2}),
+            json!({"uri": "viking://user/alice/memories/m1.md", "level": 2}),
+        ];
+        assert_eq!(
+            repair_targets(&records),
+            vec![
+                "viking://resources/docs",
+                "viking://resources/docs-old/b.md",
+                "viking://user/alice/memories/m1.md",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_since_accepts_ages_dates_and_timestamps() {
+        let now = DateTime::parse_from_rfc3339("2026-03-10T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let at = |value: &str| {
+            parse_since("--changed-since", value, now)
+                .unwrap()
+                .to_rfc3339()
+        };
+        assert_eq!(at("48h"), "2026-03-08T12:00:00+00:00");
+        assert_eq!(at("1w"), "2026-03-03T12:00:00+00:00");
+        assert_eq!(at("2026-03-01"), "2026-03-01T00:00:00+00:00");
+        assert_eq!(at("2026-03-01T08:00:00+08:00"), "2026-03-01T00:00:00+00:00");
+        assert_eq!(at("2026-03-01T08:00:00"), "2026-03-01T08:00:00+00:00");
+        assert!(parse_since("--changed-since", "yesterday", now).is_err());
+        assert!(parse_since("--changed-since", "5y", now).is_err());
+    }
+
+    #[test]
+    fn rebuild_units_are_children_with_changes_below_them() {
+        let entries = vec![
+            json!({"uri": "viking://memories/.abstract.md", "modTime": "2026-03-09T00:00:00Z"}),
+            json!({"uri": "viking://memories/old", "modTime": "2026-01-01T00:00:00Z"}),
+            json!({"uri": "viking://memories/old/a.md", "modTime": "2026-01-01T00:00:00Z"}),
+            json!({"uri": "viking://memories/prefs", "modTime": "2026-01-01T00:00:00Z"}),
+            json!({"uri": "viking://memories/prefs/b.md", "modTime": "2026-03-09T00:00:00Z"}),
+            json!({"uri": "viking://memories/c.md", "modTime": "2026-03-09T00:00:00Z"}),
+        ];
+        assert_eq!(
+            rebuild_units("viking://memories/", &entries, None),
+            vec![
+                "viking://memories/c.md",
+                "viking://memories/old",
+                "viking://memories/prefs",
+            ]
+        );
+        let since = DateTime::parse_from_rfc3339("2026-03-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            rebuild_units("viking://memories", &entries, Some(since)),
+            vec!["viking://memories/c.md", "viking://memories/prefs"]
+        );
+    }
+}
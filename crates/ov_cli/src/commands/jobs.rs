@@ -0,0 +1,439 @@
+//! `ov jobs`: follow the server's long-running jobs.
+//!
+//! A job is a task in the server's task tracker, under the task's ID: an
+//! import, a skill, a reindex, a session commit. The server reports a job's
+//! status and stage; the progress shown here is estimated from the stage, and
+//! its errors are collected from the job's own error and the errors and
+//! warnings in its result. `add-resource --wait`, `add-skill --wait`, and
+//! `index rebuild` submit their work as jobs and wait for them here.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde_json::{Value, json};
+
+use crate::client::HttpClient;
+use crate::error::{Error, Result};
+use crate::interrupt;
+use crate::output::{OutputFormat, output_success};
+use crate::theme;
+
+/// Seconds between polls when a command waits for its own job.
+const POLL_INTERVAL: f64 = 1.0;
+
+/// Estimated percent done for the stages a job reports while it runs.
+fn stage_progress(stage: &str) -> Option<u8> {
+    match stage {
+        "queued" => Some(0),
+        "fetching" | "parsing" => Some(10),
+        "finalizing" => Some(40),
+        "processing_queue" => Some(60),
+        _ => None,
+    }
+}
+
+fn is_finished(job: &Value) -> bool {
+    matches!(job["status"].as_str(), Some("completed" | "failed"))
+}
+
+/// Percent done: 100 once finished, the job's own figure if it reports one,
+/// and otherwise an estimate from its stage.
+fn progress(job: &Value) -> Option<u8> {
+    if is_finished(job) {
+        return Some(100);
+    }
+    if let Some(percent) = job["result"]["progress"].as_f64() {
+        return Some(percent.clamp(0.0, 100.0) as u8);
+    }
+    match job["status"].as_str() {
+        Some("pending") => Some(0),
+        _ => job["stage"].as_str().and_then(stage_progress),
+    }
+}
+
+/// The job's error, then the errors and warnings in its result.
+fn errors(job: &Value) -> Vec<String> {
+    let mut errors: Vec<String> = job["error"]
+        .as_str()
+        .map(str::to_string)
+        .into_iter()
+        .collect();
+    for key in ["errors", "warnings"] {
+        for item in job["result"][key].as_array().into_iter().flatten() {
+            let message = match item {
+                Value::String(message) => message.clone(),
+                item => item["message"]
+                    .as_str()
+                    .or_else(|| item["error"].as_str())
+                    .map_or_else(|| item.to_string(), str::to_string),
+            };
+            if !errors.contains(&message) {
+                errors.push(message);
+            }
+        }
+    }
+    errors
+}
+
+/// Seconds from the job's creation to its last update, or to now while it
+/// runs.
+fn elapsed(job: &Value, now: f64) -> Option<f64> {
+    let created = job["created_at"].as_f64()?;
+    let end = if is_finished(job) {
+        job["updated_at"].as_f64()?
+    } else {
+        now
+    };
+    Some(((end - created).max(0.0) * 10.0).round() / 10.0)
+}
+
+fn now_secs() -> f64 {
+    chrono::Utc::now().timestamp_millis() as f64 / 1000.0
+}
+
+/// The fields `ov jobs ls` shows, in table column order.
+fn row(job: &Value, now: f64) -> Value {
+    json!({
+        "id": job["task_id"],
+        "type": job["task_type"],
+        "target": job["resource_id"],
+        "status": job["status"],
+        "stage": job["stage"],
+        "progress": progress(job).map(|percent| format!("{percent}%")),
+        "seconds": elapsed(job, now),
+        "errors": errors(job).len(),
+    })
+}
+
+/// "job <id> running (parsing, 10%)" for progress lines.
+fn describe(job: &Value) -> String {
+    let id = job["task_id"].as_str().unwrap_or("?");
+    let status = job["status"].as_str().unwrap_or("unknown");
+    let mut detail = Vec::new();
+    if let Some(stage) = job["stage"].as_str().filter(|_| !is_finished(job)) {
+        detail.push(stage.to_string());
+    }
+    if let Some(percent) = progress(job).filter(|_| !is_finished(job)) {
+        detail.push(format!("{percent}%"));
+    }
+    if detail.is_empty() {
+        format!("job {id} {status}")
+    } else {
+        format!("job {id} {status} ({})", detail.join(", "))
+    }
+}
+
+fn failure(job: &Value) -> Error {
+    let id = job["task_id"].as_str().unwrap_or("?");
+    let reason = errors(job)
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| "no error reported".to_string());
+    Error::Api {
+        code: Some("PROCESSING_ERROR".to_string()),
+        message: format!("Job {id} failed: {reason}"),
+        details: Some(job.clone()),
+        status: None,
+    }
+}
+
+/// Poll the job `id` until it finishes and return it, printing progress to
+/// stderr when `report` is set. A failed job is an error, and so is one still
+/// running after `timeout` seconds.
+pub(crate) async fn wait_for(
+    client: &HttpClient,
+    id: &str,
+    timeout: Option<f64>,
+    interval: f64,
+    report: bool,
+) -> Result<Value> {
+    let started = tokio::time::Instant::now();
+    let interval = Duration::from_secs_f64(interval.max(0.1));
+    let mut last = String::new();
+    loop {
+        let job = client.get_task(id).await?;
+        if job["status"] == "failed" {
+            return Err(failure(&job));
+        }
+        if is_finished(&job) {
+            return Ok(job);
+        }
+        let line = describe(&job);
+        if report && line != last {
+            eprintln!("{}", theme::muted(format!("… {line}")));
+        }
+        last = line;
+        if let Some(timeout) = timeout
+            && started.elapsed().as_secs_f64() >= timeout
+        {
+            return Err(Error::Timeout(format!(
+                "{last} after {timeout}s; follow it with `ov jobs wait {id}`"
+            )));
+        }
+        interrupt::cancellable(async {
+            tokio::time::sleep(interval).await;
+            Ok(())
+        })
+        .await?;
+    }
+}
+
+/// Finish a submission made without the server waiting: wait for the job it
+/// started and return the job's result, which is what the server would have
+/// answered had it waited. A submission without a job waits for the
+/// processing queue under its `root_uri` instead.
+pub(crate) async fn finish_submitted(
+    client: &HttpClient,
+    submitted: Value,
+    timeout: Option<f64>,
+    report: bool,
+) -> Result<Value> {
+    if submitted["deduplicated"].as_bool() == Some(true) {
+        return Ok(submitted);
+    }
+    if let Some(id) = submitted["task_id"].as_str() {
+        let job = wait_for(client, id, timeout, POLL_INTERVAL, report).await?;
+        let mut result = match job["result"].clone() {
+            Value::Object(result) => result,
+            _ => serde_json::Map::new(),
+        };
+        for key in ["task_id", "root_uri"] {
+            if !result.contains_key(key)
+                && let Some(value) = submitted.get(key)
+            {
+                result.insert(key.to_string(), value.clone());
+            }
+        }
+        return Ok(Value::Object(result));
+    }
+    if let Some(uri) = submitted["root_uri"].as_str() {
+        super::queue::wait_until_processed(client, uri, timeout, POLL_INTERVAL).await?;
+    }
+    Ok(submitted)
+}
+
+pub async fn ls(
+    client: &HttpClient,
+    job_type: Option<&str>,
+    status: Option<&str>,
+    limit: u32,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let jobs = client.list_tasks(job_type, status, Some(limit)).await?;
+    let now = now_secs();
+    let rows: Vec<Value> = jobs
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|job| row(job, now))
+        .collect();
+    output_success(rows, format, compact);
+    Ok(())
+}
+
+fn details(job: &Value, now: f64) -> Value {
+    let mut details = row(job, now);
+    details["created_at"] = job["created_at_iso"].clone();
+    details["updated_at"] = job["updated_at_iso"].clone();
+    details["errors"] = json!(errors(job));
+    details["result"] = job["result"].clone();
+    details
+}
+
+pub async fn show(
+    client: &HttpClient,
+    id: &str,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let job = client.get_task(id).await?;
+    let details = details(&job, now_secs());
+    if matches!(format, OutputFormat::Table) && !crate::output::table_layout_requested() {
+        let mut summary = details.clone();
+        if let Some(fields) = summary.as_object_mut() {
+            fields.remove("errors");
+            fields.remove("result");
+        }
+        let mut sections = vec![
+            crate::output::render_table_with_optional_profile(&summary, compact)
+                .unwrap_or_default()
+                .trim_end()
+                .to_string(),
+        ];
+        let errors = errors(&job);
+        if !errors.is_empty() {
+            let rows: Vec<Value> = errors
+                .iter()
+                .map(|error| json!({ "error": error }))
+                .collect();
+            sections.push(format!(
+                "errors\n{}",
+                crate::output::render_table_with_optional_profile(&Value::Array(rows), compact)
+                    .unwrap_or_default()
+                    .trim_end()
+            ));
+        }
+        println!("{}", sections.join("\n\n"));
+    } else {
+        output_success(details, format, compact);
+    }
+    Ok(())
+}
+
+/// Wait for every job in `ids`, all at once, and fail if any of them failed.
+pub async fn wait(
+    client: &HttpClient,
+    ids: &[String],
+    timeout: Option<f64>,
+    interval: f64,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    if !interval.is_finite() || interval <= 0.0 {
+        return Err(Error::Client(
+            "--interval must be a positive number of seconds".to_string(),
+        ));
+    }
+    let started = tokio::time::Instant::now();
+    let interval = Duration::from_secs_f64(interval);
+    let mut finished: BTreeMap<&str, Value> = BTreeMap::new();
+    let mut last: BTreeMap<&str, String> = BTreeMap::new();
+    loop {
+        for id in ids {
+            if finished.contains_key(id.as_str()) {
+                continue;
+            }
+            let job = client.get_task(id).await?;
+            if is_finished(&job) {
+                eprintln!("{}", theme::muted(format!("… {}", describe(&job))));
+                finished.insert(id.as_str(), job);
+                continue;
+            }
+            let line = describe(&job);
+            if last.get(id.as_str()) != Some(&line) {
+                eprintln!("{}", theme::muted(format!("… {line}")));
+                last.insert(id.as_str(), line);
+            }
+        }
+        if finished.len() == ids.len() {
+            break;
+        }
+        if let Some(timeout) = timeout
+            && started.elapsed().as_secs_f64() >= timeout
+        {
+            let running: Vec<&str> = ids
+                .iter()
+                .map(String::as_str)
+                .filter(|id| !finished.contains_key(id))
+                .collect();
+            return Err(Error::Timeout(format!(
+                "{} job(s) still running after {timeout}s: {}",
+                running.len(),
+                running.join(", ")
+            )));
+        }
+        interrupt::cancellable(async {
+            tokio::time::sleep(interval).await;
+            Ok(())
+        })
+        .await?;
+    }
+
+    let now = now_secs();
+    let rows: Vec<Value> = ids
+        .iter()
+        .filter_map(|id| finished.get(id.as_str()))
+        .map(|job| row(job, now))
+        .collect();
+    let failed: Vec<&Value> = ids
+        .iter()
+        .filter_map(|id| finished.get(id.as_str()))
+        .filter(|job| job["status"] == "failed")
+        .collect();
+    match failed.as_slice() {
+        [] => {
+            output_success(rows, format, compact);
+            Ok(())
+        }
+        [job] => Err(failure(job)),
+        jobs => Err(Error::Api {
+            code: Some("PROCESSING_ERROR".to_string()),
+            message: format!(
+                "{} of {} jobs failed; see `ov jobs show <id>`",
+                jobs.len(),
+                ids.len()
+            ),
+            details: Some(json!({ "jobs": rows })),
+            status: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(status: &str, stage: Option<&str>) -> Value {
+        json!({
+            "task_id": "t1",
+            "task_type": "add_resource",
+            "status": status,
+            "stage": stage,
+            "created_at": 100.0,
+            "updated_at": 130.5,
+            "resource_id": "viking://resources/docs",
+            "error": null,
+            "result": null,
+        })
+    }
+
+    #[test]
+    fn progress_follows_the_stage_until_the_job_finishes() {
+        assert_eq!(progress(&job("pending", None)), Some(0));
+        assert_eq!(progress(&job("running", Some("parsing"))), Some(10));
+        assert_eq!(
+            progress(&job("running", Some("processing_queue"))),
+            Some(60)
+        );
+        assert_eq!(progress(&job("running", Some("reindexing"))), None);
+        assert_eq!(
+            progress(&job("completed", Some("processing_queue"))),
+            Some(100)
+        );
+        let mut reported = job("running", Some("reindexing"));
+        reported["result"] = json!({ "progress": 42.5 });
+        assert_eq!(progress(&reported), Some(42));
+
+        assert_eq!(
+            describe(&job("running", Some("parsing"))),
+            "job t1 running (parsing, 10%)"
+        );
+        assert_eq!(
+            describe(&job("completed", Some("parsing"))),
+            "job t1 completed"
+        );
+        assert_eq!(elapsed(&job("completed", None), 500.0), Some(30.5));
+        assert_eq!(elapsed(&job("running", None), 112.0), Some(12.0));
+    }
+
+    #[test]
+    fn errors_are_collected_from_the_job_and_its_result() {
+        let mut failed = job("failed", None);
+        failed["error"] = json!("parse failed");
+        failed["result"] = json!({
+            "errors": ["parse failed", {"message": "bad page 3"}],
+            "warnings": [{"uri": "viking://resources/docs/a.md", "error": "embedding timed out"}],
+        });
+        assert_eq!(
+            errors(&failed),
+            vec!["parse failed", "bad page 3", "embedding timed out"]
+        );
+        assert_eq!(row(&failed, 0.0)["errors"], 3);
+        assert!(
+            failure(&failed)
+                .to_string()
+                .contains("Job t1 failed: parse failed")
+        );
+    }
+}
@@ -0,0 +1,870 @@
+//! `ov import mbox <file>`: import a mailing list or support mailbox as one
+//! resource per thread.
+//!
+//! The input is an mbox file, a single `.eml` message, or a directory of
+//! `.eml` files. Messages are threaded by `References` / `In-Reply-To`, and a
+//! `Re:` without those headers joins the thread with the same subject. A reply
+//! that changes the subject starts a thread of its own, linked to the thread
+//! it answers by a relation. Quoted text, attribution lines ("On ... wrote:"),
+//! forwarded Outlook history, and signatures are dropped from each message, so
+//! a thread reads as the conversation once. Each thread's participants, dates,
+//! and message count are set as metadata on its resource.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64_STANDARD};
+use chrono::{DateTime, FixedOffset};
+use serde_json::{Map, Value, json};
+use url::Url;
+
+use super::mcp_import::markdown_file_name;
+use super::notion_import::write_page;
+use crate::client::HttpClient;
+use crate::error::{Error, Result};
+use crate::html_markdown::fragment_markdown;
+use crate::interrupt;
+use crate::output::{OutputFormat, output_success};
+use crate::theme;
+
+const LINK_REASON: &str = "Reply to this thread";
+
+pub struct MboxImportOptions<'a> {
+    pub path: &'a Path,
+    pub parent: &'a str,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Message {
+    id: String,
+    in_reply_to: Option<String>,
+    references: Vec<String>,
+    subject: String,
+    from: String,
+    to: Vec<String>,
+    cc: Vec<String>,
+    date: Option<DateTime<FixedOffset>>,
+    body: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Thread {
+    subject: String,
+    /// Indexes into the message list, oldest first.
+    messages: Vec<usize>,
+    /// The thread this one's first message replies to.
+    replies_to: Option<usize>,
+}
+
+/// Header names and values, folded lines joined, and the body after them.
+fn split_headers(raw: &[u8]) -> (Vec<(String, String)>, &[u8]) {
+    let (head, body) = [&b"\r\n\r\n"[..], b"\n\n"]
+        .iter()
+        .filter_map(|separator| {
+            raw.windows(separator.len())
+                .position(|window| window == *separator)
+                .map(|at| (at, separator.len()))
+        })
+        .min()
+        .map_or((raw, &raw[raw.len()..]), |(at, len)| {
+            (&raw[..at], &raw[at + len..])
+        });
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in String::from_utf8_lossy(head).lines() {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+    (headers, body)
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// The lowercased MIME type of a `Content-Type` value and its parameters.
+fn content_type(value: &str) -> (String, HashMap<String, String>) {
+    let mut parts = value.split(';');
+    let mime = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+    let params = parts
+        .filter_map(|part| part.split_once('='))
+        .map(|(name, value)| {
+            (
+                name.trim().to_ascii_lowercase(),
+                value.trim().trim_matches('"').to_string(),
+            )
+        })
+        .collect();
+    (mime, params)
+}
+
+fn decode_charset(bytes: &[u8], charset: Option<&str>) -> String {
+    match charset.map(str::to_ascii_lowercase).as_deref() {
+        Some("iso-8859-1" | "latin1" | "windows-1252" | "us-ascii") => {
+            bytes.iter().map(|&byte| byte as char).collect()
+        }
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+fn decode_quoted_printable(text: &[u8], header: bool) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len());
+    let mut index = 0;
+    while index < text.len() {
+        match text[index] {
+            b'=' if text[index + 1..].starts_with(b"\r\n") => index += 3,
+            b'=' if text[index + 1..].starts_with(b"\n") => index += 2,
+            b'=' => match text
+                .get(index + 1..index + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                Some(byte) => {
+                    out.push(byte);
+                    index += 3;
+                }
+                None => {
+                    out.push(b'=');
+                    index += 1;
+                }
+            },
+            b'_' if header => {
+                out.push(b' ');
+                index += 1;
+            }
+            byte => {
+                out.push(byte);
+                index += 1;
+            }
+        }
+    }
+    out
+}
+
+fn decode_transfer(body: &[u8], encoding: Option<&str>) -> Vec<u8> {
+    match encoding.map(str::to_ascii_lowercase).as_deref() {
+        Some("base64") => {
+            let compact: Vec<u8> = body
+                .iter()
+                .copied()
+                .filter(|byte| !byte.is_ascii_whitespace())
+                .collect();
+            BASE64_STANDARD
+                .decode(compact)
+                .unwrap_or_else(|_| body.to_vec())
+        }
+        Some("quoted-printable") => decode_quoted_printable(body, false),
+        _ => body.to_vec(),
+    }
+}
+
+/// A header value with its RFC 2047 encoded words (`=?UTF-8?B?...?=`) decoded.
+fn decode_words(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    let mut after_word = false;
+    while let Some(start) = rest.find("=?") {
+        let decoded = rest[start + 2..].find("?=").and_then(|length| {
+            let word = &rest[start + 2..start + 2 + length];
+            let mut fields = word.splitn(3, '?');
+            let (charset, encoding, text) = (fields.next()?, fields.next()?, fields.next()?);
+            let bytes = match encoding.to_ascii_lowercase().as_str() {
+                "b" => BASE64_STANDARD.decode(text).ok()?,
+                "q" => decode_quoted_printable(text.as_bytes(), true),
+                _ => return None,
+            };
+            Some((
+                decode_charset(&bytes, Some(charset)),
+                start + 2 + length + 2,
+            ))
+        });
+        let Some((text, end)) = decoded else {
+            out.push_str(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            after_word = false;
+            continue;
+        };
+        // Whitespace between two encoded words is not part of the text.
+        let between = &rest[..start];
+        if !(after_word && between.trim().is_empty()) {
+            out.push_str(between);
+        }
+        out.push_str(&text);
+        rest = &rest[end..];
+        after_word = true;
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The first `text/plain` and `text/html` bodies of a (possibly multipart)
+/// entity, skipping attachments.
+fn text_bodies(
+    headers: &[(String, String)],
+    body: &[u8],
+    plain: &mut Option<String>,
+    html: &mut Option<String>,
+) {
+    if header(headers, "content-disposition")
+        .is_some_and(|value| value.to_ascii_lowercase().starts_with("attachment"))
+    {
+        return;
+    }
+    let (mime, params) = content_type(header(headers, "content-type").unwrap_or("text/plain"));
+    if mime.starts_with("multipart/") {
+        let Some(boundary) = params.get("boundary") else {
+            return;
+        };
+        let delimiter = format!("--{boundary}");
+        let text = String::from_utf8_lossy(body);
+        for part in text.split(delimiter.as_str()).skip(1) {
+            if part.starts_with("--") {
+                break;
+            }
+            let part = part.trim_start_matches(['\r', '\n']).as_bytes();
+            let (part_headers, part_body) = split_headers(part);
+            text_bodies(&part_headers, part_body, plain, html);
+        }
+        return;
+    }
+    let slot = match mime.as_str() {
+        "text/plain" => plain,
+        "text/html" => html,
+        _ => return,
+    };
+    if slot.is_none() {
+        let bytes = decode_transfer(body, header(headers, "content-transfer-encoding"));
+        *slot = Some(decode_charset(
+            &bytes,
+            params.get("charset").map(String::as_str),
+        ));
+    }
+}
+
+/// The `<...>` message ids in a header value.
+fn message_ids(value: &str) -> Vec<String> {
+    value
+        .split('<')
+        .skip(1)
+        .filter_map(|part| part.split_once('>'))
+        .map(|(id, _)| format!("<{}>", id.trim()))
+        .collect()
+}
+
+/// Addresses in a `To`/`Cc` value, split on commas outside quotes.
+fn addresses(value: &str) -> Vec<String> {
+    let mut list = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in value.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            ',' if !quoted => list.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    list.push(current);
+    list.into_iter()
+        .map(|address| address.trim().to_string())
+        .filter(|address| !address.is_empty())
+        .collect()
+}
+
+/// The lowercased email in `Name <email>` or a bare address.
+fn email_of(address: &str) -> String {
+    match address.rsplit_once('<') {
+        Some((_, rest)) => rest.trim_end_matches('>').trim().to_ascii_lowercase(),
+        None => address.trim().to_ascii_lowercase(),
+    }
+}
+
+fn parse_message(raw: &[u8], position: usize) -> Message {
+    let (headers, body) = split_headers(raw);
+    let decoded = |name: &str| header(&headers, name).map(decode_words);
+    let (mut plain, mut html) = (None, None);
+    text_bodies(&headers, body, &mut plain, &mut html);
+    let body = match (plain, html) {
+        (Some(plain), _) => plain,
+        (None, Some(html)) => {
+            let base = Url::parse("about:blank").expect("valid base URL");
+            fragment_markdown(&html, &base)
+        }
+        (None, None) => String::new(),
+    };
+    Message {
+        id: header(&headers, "message-id")
+            .and_then(|value| message_ids(value).into_iter().next())
+            .unwrap_or_else(|| format!("<message-{position}@ov-import>")),
+        in_reply_to: header(&headers, "in-reply-to")
+            .and_then(|value| message_ids(value).into_iter().next()),
+        references: header(&headers, "references")
+            .map(message_ids)
+            .unwrap_or_default(),
+        subject: decoded("subject").unwrap_or_default(),
+        from: decoded("from").unwrap_or_default(),
+        to: decoded("to").map(|to| addresses(&to)).unwrap_or_default(),
+        cc: decoded("cc").map(|cc| addresses(&cc)).unwrap_or_default(),
+        date: header(&headers, "date").and_then(|date| {
+            // Drop a trailing comment such as "(UTC)", which chrono rejects.
+            let date = date.split(" (").next().unwrap_or(date);
+            DateTime::parse_from_rfc2822(date.trim()).ok()
+        }),
+        body: strip_quoted(&body),
+    }
+}
+
+/// The raw messages of an mbox, `>From ` escapes undone.
+fn split_mbox(data: &[u8]) -> Vec<Vec<u8>> {
+    let mut messages = Vec::new();
+    let mut current: Option<Vec<u8>> = None;
+    for line in data.split_inclusive(|&byte| byte == b'\n') {
+        if line.starts_with(b"From ") {
+            messages.extend(current.replace(Vec::new()));
+            continue;
+        }
+        let Some(message) = current.as_mut() else {
+            continue;
+        };
+        let unquoted = line.iter().position(|&byte| byte != b'>');
+        if unquoted.is_some_and(|at| at > 0 && line[at..].starts_with(b"From ")) {
+            message.extend_from_slice(&line[1..]);
+        } else {
+            message.extend_from_slice(line);
+        }
+    }
+    messages.extend(current);
+    messages
+}
+
+/// Every message in an mbox file, a `.eml` file, or a directory of `.eml`s.
+fn read_messages(path: &Path) -> Result<Vec<Message>> {
+    let raws = if path.is_dir() {
+        let mut raws = Vec::new();
+        let files = walkdir::WalkDir::new(path)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry.file_type().is_file()
+                    && entry
+                        .path()
+                        .extension()
+                        .is_some_and(|extension| extension.eq_ignore_ascii_case("eml"))
+            });
+        for entry in files {
+            raws.push(std::fs::read(entry.path())?);
+        }
+        raws
+    } else {
+        let data = std::fs::read(path)?;
+        if data.starts_with(b"From ") {
+            split_mbox(&data)
+        } else {
+            vec![data]
+        }
+    };
+    Ok(raws
+        .iter()
+        .enumerate()
+        .map(|(position, raw)| parse_message(raw, position))
+        .collect())
+}
+
+/// `subject` without `Re:`/`Fwd:`/`[list]` prefixes, for comparing threads.
+fn normalized_subject(subject: &str) -> String {
+    let mut subject = subject.trim();
+    loop {
+        let lower = subject.to_ascii_lowercase();
+        let stripped = ["re:", "fw:", "fwd:", "aw:", "回复：", "回复:", "转发："]
+            .iter()
+            .find(|prefix| lower.starts_with(*prefix))
+            .map(|prefix| &subject[prefix.len()..])
+            .or_else(|| {
+                subject
+                    .starts_with('[')
+                    .then(|| subject.split_once(']').map(|(_, rest)| rest))
+                    .flatten()
+            });
+        match stripped {
+            Some(rest) => subject = rest.trim(),
+            None => return subject.to_lowercase(),
+        }
+    }
+}
+
+fn is_reply(subject: &str) -> bool {
+    let lower = subject.trim().to_ascii_lowercase();
+    lower.starts_with("re:") || subject.trim().starts_with("回复")
+}
+
+/// Group `messages`, oldest first, into threads.
+fn thread(messages: &mut [Message]) -> Vec<Thread> {
+    messages.sort_by_key(|message| message.date);
+    let mut threads: Vec<Thread> = Vec::new();
+    let mut thread_of: HashMap<&str, usize> = HashMap::new();
+    let mut by_subject: HashMap<String, usize> = HashMap::new();
+    for (index, message) in messages.iter().enumerate() {
+        let subject = normalized_subject(&message.subject);
+        let parent = message
+            .references
+            .iter()
+            .rev()
+            .chain(message.in_reply_to.iter())
+            .find_map(|id| thread_of.get(id.as_str()).copied());
+        let joined = match parent {
+            Some(parent) if normalized_subject(&threads[parent].subject) == subject => Some(parent),
+            Some(_) => None,
+            None if is_reply(&message.subject) => by_subject.get(&subject).copied(),
+            None => None,
+        };
+        let thread = match joined {
+            Some(thread) => {
+                threads[thread].messages.push(index);
+                thread
+            }
+            None => {
+                threads.push(Thread {
+                    subject: message.subject.clone(),
+                    messages: vec![index],
+                    replies_to: parent,
+                });
+                by_subject.entry(subject).or_insert(threads.len() - 1);
+                threads.len() - 1
+            }
+        };
+        thread_of.insert(&message.id, thread);
+    }
+    threads
+}
+
+/// `body` without quoted text, attribution lines, forwarded Outlook history,
+/// or a signature.
+fn strip_quoted(body: &str) -> String {
+    let lines: Vec<&str> = body.lines().map(str::trim_end).collect();
+    let next_is_quote = |from: usize| {
+        lines[from..]
+            .iter()
+            .find(|line| !line.is_empty())
+            .is_some_and(|line| line.starts_with('>'))
+    };
+    let attribution = |line: &str| line.ends_with("wrote:") || line.ends_with("写道：");
+    let mut kept: Vec<&str> = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        let outlook = line.starts_with("-----Original Message-----")
+            || (line.len() >= 20 && line.chars().all(|c| c == '_'))
+            || (line.starts_with("From: ")
+                && lines
+                    .get(index + 1)
+                    .is_some_and(|next| next.starts_with("Sent: ")));
+        if line == "--" || outlook {
+            break;
+        }
+        if attribution(line) && next_is_quote(index + 1) {
+            index += 1;
+            continue;
+        }
+        if line.starts_with("On ")
+            && lines
+                .get(index + 1)
+                .is_some_and(|next| attribution(next) && !next.starts_with("On "))
+            && next_is_quote(index + 2)
+        {
+            index += 2;
+            continue;
+        }
+        let repeated_blank = line.is_empty() && kept.last() == Some(&"");
+        if !line.starts_with('>') && !repeated_blank {
+            kept.push(line);
+        }
+        index += 1;
+    }
+    kept.join("\n").trim().to_string()
+}
+
+/// Everyone who wrote or was addressed in `thread`, one per email address.
+fn participants(thread: &Thread, messages: &[Message]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    thread
+        .messages
+        .iter()
+        .flat_map(|&index| {
+            let message = &messages[index];
+            std::iter::once(&message.from)
+                .chain(&message.to)
+                .chain(&message.cc)
+        })
+        .filter(|address| !address.is_empty() && seen.insert(email_of(address)))
+        .cloned()
+        .collect()
+}
+
+fn format_date(date: Option<DateTime<FixedOffset>>) -> String {
+    date.map_or_else(
+        || "unknown date".to_string(),
+        |date| date.format("%Y-%m-%d %H:%M %:z").to_string(),
+    )
+}
+
+fn thread_markdown(thread: &Thread, messages: &[Message]) -> String {
+    let first = messages[thread.messages[0]].date;
+    let last = messages[*thread.messages.last().expect("threads are not empty")].date;
+    let mut markdown = format!(
+        "# {}\n\n- Participants: {}\n- Messages: {}\n- Dates: {} to {}\n",
+        thread.subject.trim(),
+        participants(thread, messages).join(", "),
+        thread.messages.len(),
+        format_date(first),
+        format_date(last),
+    );
+    for &index in &thread.messages {
+        let message = &messages[index];
+        markdown.push_str(&format!(
+            "\n## {} ({})\n\n{}\n",
+            message.from,
+            format_date(message.date),
+            message.body
+        ));
+    }
+    markdown
+}
+
+fn thread_metadata(thread: &Thread, messages: &[Message]) -> Map<String, Value> {
+    let dates: Vec<_> = thread
+        .messages
+        .iter()
+        .filter_map(|&index| messages[index].date)
+        .collect();
+    let mut metadata = Map::new();
+    metadata.insert(
+        "participants".to_string(),
+        json!(participants(thread, messages)),
+    );
+    metadata.insert("messages".to_string(), json!(thread.messages.len()));
+    if let (Some(first), Some(last)) = (dates.iter().min(), dates.iter().max()) {
+        metadata.insert("first_date".to_string(), json!(first.to_rfc3339()));
+        metadata.insert("last_date".to_string(), json!(last.to_rfc3339()));
+    }
+    metadata.insert(
+        "message_id".to_string(),
+        json!(messages[thread.messages[0]].id),
+    );
+    metadata
+}
+
+fn row(thread: &Thread, status: &str, uri: Option<&str>, error: Option<String>) -> Value {
+    json!({
+        "subject": thread.subject,
+        "messages": thread.messages.len(),
+        "status": status,
+        "uri": uri,
+        "error": error,
+    })
+}
+
+pub async fn import(
+    client: &HttpClient,
+    options: MboxImportOptions<'_>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let mut messages = read_messages(options.path)?;
+    if messages.is_empty() {
+        return Err(Error::Client(format!(
+            "No messages found in {}",
+            options.path.display()
+        )));
+    }
+    let threads = thread(&mut messages);
+    let mut taken = HashSet::new();
+    let paths: Vec<String> = threads
+        .iter()
+        .map(|thread| markdown_file_name(&normalized_subject_title(&thread.subject), &mut taken))
+        .collect();
+    let parent = options.parent.trim_end_matches('/');
+
+    interrupt::enter_batch();
+    let mut rows = Vec::with_capacity(threads.len());
+    let mut uris: BTreeMap<usize, String> = BTreeMap::new();
+    let (mut imported, mut failed) = (0, 0);
+    let mut interrupted = false;
+    let mut dirs = HashSet::new();
+    for (index, thread) in threads.iter().enumerate() {
+        let uri = format!("{parent}/{}", paths[index]);
+        if options.dry_run {
+            rows.push(row(thread, "planned", Some(&uri), None));
+            continue;
+        }
+        eprintln!(
+            "{}",
+            theme::muted(format!(
+                "[{}/{}] {}",
+                index + 1,
+                threads.len(),
+                thread.subject
+            ))
+        );
+        let result = interrupt::cancellable(async {
+            let uri = write_page(
+                client,
+                parent,
+                &paths[index],
+                &thread_markdown(thread, &messages),
+                &mut dirs,
+            )
+            .await?;
+            client
+                .set_metadata(&uri, thread_metadata(thread, &messages), Vec::new())
+                .await?;
+            Ok(uri)
+        })
+        .await;
+        match result {
+            Ok(uri) => {
+                imported += 1;
+                rows.push(row(thread, "imported", Some(&uri), None));
+                uris.insert(index, uri);
+            }
+            Err(Error::Interrupted) => {
+                interrupted = true;
+                break;
+            }
+            Err(e) => {
+                failed += 1;
+                rows.push(row(thread, "failed", None, Some(e.to_string())));
+            }
+        }
+    }
+
+    let replies: Vec<(usize, usize)> = threads
+        .iter()
+        .enumerate()
+        .filter_map(|(index, thread)| Some((index, thread.replies_to?)))
+        .collect();
+    let mut links = 0;
+    for &(from, to) in &replies {
+        if options.dry_run {
+            links += 1;
+            continue;
+        }
+        if interrupted {
+            break;
+        }
+        let (Some(from_uri), Some(to_uri)) = (uris.get(&from), uris.get(&to)) else {
+            continue;
+        };
+        match interrupt::cancellable(client.link(
+            from_uri,
+            std::slice::from_ref(to_uri),
+            LINK_REASON,
+        ))
+        .await
+        {
+            Ok(_) => links += 1,
+            Err(Error::Interrupted) => interrupted = true,
+            Err(e) => {
+                failed += 1;
+                rows.push(row(
+                    &threads[from],
+                    "failed",
+                    Some(from_uri),
+                    Some(format!("Failed to link to {to_uri}: {e}")),
+                ));
+            }
+        }
+    }
+
+    if matches!(format, OutputFormat::Table) {
+        output_success(Value::Array(rows), format, compact);
+        let summary = if options.dry_run {
+            format!(
+                "{} messages in {} threads would be imported into {parent}, with {links} reply links",
+                messages.len(),
+                threads.len()
+            )
+        } else {
+            format!(
+                "{imported} of {} threads imported into {parent}: {links} reply links, {failed} failed",
+                threads.len()
+            )
+        };
+        println!("{}", theme::muted(summary));
+    } else {
+        output_success(
+            json!({
+                "source": options.path.display().to_string(),
+                "parent": parent,
+                "dry_run": options.dry_run,
+                "messages": messages.len(),
+                "threads": threads.len(),
+                "imported": imported,
+                "links": links,
+                "failed": failed,
+                "results": rows,
+            }),
+            format,
+            compact,
+        );
+    }
+    if interrupted {
+        return Err(Error::Interrupted);
+    }
+    if failed > 0 {
+        return Err(Error::AlreadyReported);
+    }
+    Ok(())
+}
+
+/// The subject a thread's file is named after, reply prefixes removed.
+fn normalized_subject_title(subject: &str) -> String {
+    let title = subject.trim();
+    let lower = normalized_subject(title);
+    // Keep the original casing: cut the prefixes off the original.
+    let start = title.len().saturating_sub(lower.len());
+    let title = title.get(start..).unwrap_or(title).trim();
+    if title.is_empty() {
+        "no subject".to_string()
+    } else {
+        title.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MBOX: &str = "From alice@example.com Mon May  6 09:00:00 2024
+Message-ID: <1@example.com>
+From: Alice <alice@example.com>
+To: dev@lists.example.com
+Subject: [dev] Release 2.0 plan
+Date: Mon, 6 May 2024 09:00:00 +0000
+
+Shall we ship on Friday?
+>From the changelog, everything is merged.
+
+--
+Alice
+
+From bob@example.com Mon May  6 10:00:00 2024
+Message-ID: <2@example.com>
+In-Reply-To: <1@example.com>
+References: <1@example.com>
+From: =?UTF-8?B?Qm9iIE3DvGxsZXI=?= <bob@example.com>
+To: dev@lists.example.com
+Cc: \"Carol, QA\" <carol@example.com>
+Subject: Re: [dev] Release 2.0 plan
+Date: Mon, 6 May 2024 10:00:00 +0000 (UTC)
+Content-Type: text/plain; charset=utf-8
+Content-Transfer-Encoding: quoted-printable
+
+Friday works. Caf=C3=A9 at noon?
+
+On Mon, 6 May 2024, Alice <alice@example.com> wrote:
+> Shall we ship on Friday?
+
+From carol@example.com Mon May  6 11:00:00 2024
+Message-ID: <3@example.com>
+In-Reply-To: <2@example.com>
+References: <1@example.com> <2@example.com>
+From: Carol <carol@example.com>
+Subject: QA sign-off for 2.0
+Date: Mon, 6 May 2024 11:00:00 +0000
+Content-Type: multipart/alternative; boundary=\"b1\"
+
+--b1
+Content-Type: text/html
+
+<p>QA needs <b>one more day</b>.</p>
+--b1--
+";
+
+    #[test]
+    fn mbox_messages_are_parsed_and_threaded() {
+        let messages = split_mbox(MBOX.as_bytes());
+        assert_eq!(messages.len(), 3);
+        let mut messages: Vec<Message> = messages
+            .iter()
+            .enumerate()
+            .map(|(position, raw)| parse_message(raw, position))
+            .collect();
+
+        assert_eq!(
+            messages[0].body,
+            "Shall we ship on Friday?\nFrom the changelog, everything is merged."
+        );
+        assert_eq!(messages[1].from, "Bob Müller <bob@example.com>");
+        assert_eq!(
+            messages[1].cc,
+            ["\"Carol, QA\" <carol@example.com>".to_string()]
+        );
+        assert_eq!(messages[1].body, "Friday works. Café at noon?");
+        assert!(messages[1].date.is_some());
+        assert_eq!(messages[2].body.trim(), "QA needs **one more day**.");
+
+        let threads = thread(&mut messages);
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0].messages, [0, 1]);
+        assert_eq!(threads[1].replies_to, Some(0));
+        assert_eq!(
+            normalized_subject_title(&threads[0].subject),
+            "Release 2.0 plan"
+        );
+        assert_eq!(
+            participants(&threads[0], &messages),
+            [
+                "Alice <alice@example.com>",
+                "dev@lists.example.com",
+                "Bob Müller <bob@example.com>",
+                "\"Carol, QA\" <carol@example.com>",
+            ]
+        );
+        let metadata = thread_metadata(&threads[0], &messages);
+        assert_eq!(metadata["messages"], 2);
+        assert_eq!(metadata["first_date"], "2024-05-06T09:00:00+00:00");
+        assert_eq!(metadata["last_date"], "2024-05-06T10:00:00+00:00");
+    }
+
+    #[test]
+    fn replies_without_references_join_by_subject() {
+        let message = |id: &str, subject: &str, hour: u32| Message {
+            id: id.into(),
+            subject: subject.into(),
+            date: DateTime::parse_from_rfc3339(&format!("2024-05-06T{hour:02}:00:00Z")).ok(),
+            ..Message::default()
+        };
+        let mut messages = vec![
+            message("<b>", "RE: Outage", 2),
+            message("<a>", "Outage", 1),
+            message("<c>", "Outage report", 3),
+        ];
+        let threads = thread(&mut messages);
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0].messages, [0, 1]);
+    }
+
+    #[test]
+    fn quoted_history_and_signatures_are_stripped() {
+        let body = "Thanks, merged.\n\n\nOn Tue, May 7, 2024 at 9:00 AM Bob\n<bob@example.com> wrote:\n\n> Can you merge?\n> Thanks\n\n--\nAlice";
+        assert_eq!(strip_quoted(body), "Thanks, merged.");
+        let outlook = "Approved.\n\nFrom: Bob\nSent: Tuesday\nTo: Alice\nSubject: Budget";
+        assert_eq!(strip_quoted(outlook), "Approved.");
+        assert_eq!(
+            decode_words("=?utf-8?q?Caf=C3=A9_menu?= =?utf-8?q?_today?="),
+            "Café menu today"
+        );
+    }
+}
@@ -0,0 +1,23 @@
+use std::net::SocketAddr;
+
+use crate::client::HttpClient;
+use crate::error::{Error, Result};
+use crate::mcp::{self, McpServer};
+
+/// Serve the configured OpenViking server to MCP hosts until stdin closes
+/// (stdio) or Ctrl-C (SSE).
+pub async fn serve(client: HttpClient, transport: &str, host: &str, port: u16) -> Result<()> {
+    let server = McpServer::new(client);
+    match transport {
+        "sse" => {
+            let addr: SocketAddr = format!("{host}:{port}")
+                .parse()
+                .or_else(|_| format!("[{host}]:{port}").parse())
+                .map_err(|_| {
+                    Error::Client(format!("Invalid --host '{host}'; use an IP address"))
+                })?;
+            mcp::serve_sse(server, addr).await
+        }
+        _ => mcp::serve_stdio(server).await,
+    }
+}
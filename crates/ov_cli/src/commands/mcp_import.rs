@@ -0,0 +1,466 @@
+//! `ov import mcp`: pull documents out of another MCP server (a Feishu/Lark
+//! docs server, say) and add them as resources.
+//!
+//! The first `--tool` lists documents; its output is read as JSON, either a
+//! bare array or an object holding one under a field such as `items` or
+//! `documents`. Each item needs an id, and may carry a title and its content.
+//! When a second `--tool` is given it is called once per item with the id to
+//! fetch the content. Every document is uploaded as a Markdown file under
+//! `--parent`, and reported on its own row, so one failed fetch does not stop
+//! the rest.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use serde_json::{Map, Value, json};
+
+use crate::client::HttpClient;
+use crate::error::{Error, Result};
+use crate::interrupt;
+use crate::mcp::{McpClient, tool_text};
+use crate::output::{OutputFormat, output_success};
+use crate::theme;
+
+/// Fields tried, in order, for an item's id when `--id-field` is not given.
+const ID_FIELDS: &[&str] = &["id", "document_id", "doc_id", "token", "uri", "url", "path"];
+const TITLE_FIELDS: &[&str] = &["title", "name", "file_name"];
+const CONTENT_FIELDS: &[&str] = &["content", "markdown", "text", "body"];
+/// Fields an object-shaped listing may keep its items under.
+const LIST_FIELDS: &[&str] = &["items", "documents", "docs", "files", "results", "data"];
+
+pub struct McpImportOptions<'a> {
+    pub server: &'a str,
+    /// The list tool, then optionally the fetch tool.
+    pub tools: &'a [String],
+    pub args: Option<&'a str>,
+    pub id_field: Option<&'a str>,
+    pub fetch_arg: Option<&'a str>,
+    pub parent: &'a str,
+    pub limit: Option<usize>,
+    pub dry_run: bool,
+    pub timeout: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Document {
+    id: String,
+    title: Option<String>,
+    content: Option<String>,
+}
+
+/// What a tool returned: its structured content when present, otherwise its
+/// text parsed as JSON, otherwise the text itself.
+fn tool_payload(result: &Value) -> Value {
+    if let Some(structured) = result.get("structuredContent").filter(|v| !v.is_null()) {
+        return structured.clone();
+    }
+    let text = tool_text(result);
+    serde_json::from_str(&text).unwrap_or(Value::String(text))
+}
+
+fn find_list(payload: &Value) -> Option<&Vec<Value>> {
+    match payload {
+        Value::Array(items) => Some(items),
+        Value::Object(fields) => LIST_FIELDS
+            .iter()
+            .filter_map(|name| fields.get(*name))
+            .find_map(find_list),
+        _ => None,
+    }
+}
+
+fn first_string(item: &Map<String, Value>, fields: &[&str]) -> Option<String> {
+    fields.iter().find_map(|field| match item.get(*field)? {
+        Value::String(text) if !text.trim().is_empty() => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    })
+}
+
+/// The documents a list tool returned.
+fn documents(payload: &Value, id_field: Option<&str>) -> Result<Vec<Document>> {
+    let items = match find_list(payload) {
+        Some(items) => items.as_slice(),
+        None if payload.is_object() => std::slice::from_ref(payload),
+        None => {
+            return Err(Error::Parse(
+                "List tool did not return a JSON array of documents".to_string(),
+            ));
+        }
+    };
+    let id_fields = id_field.map(|field| vec![field]);
+    let id_fields = id_fields.as_deref().unwrap_or(ID_FIELDS);
+    items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| match item {
+            Value::String(id) => Ok(Document {
+                id: id.clone(),
+                title: None,
+                content: None,
+            }),
+            Value::Object(fields) => Ok(Document {
+                id: first_string(fields, id_fields).ok_or_else(|| {
+                    Error::Parse(format!(
+                        "Item {} has no {} field; pass --id-field",
+                        index + 1,
+                        id_fields.join("/")
+                    ))
+                })?,
+                title: first_string(fields, TITLE_FIELDS),
+                content: first_string(fields, CONTENT_FIELDS),
+            }),
+            other => Err(Error::Parse(format!(
+                "Item {} is not an object or a string: {other}",
+                index + 1
+            ))),
+        })
+        .collect()
+}
+
+/// The text of a fetched document, and its title when the fetch result has one.
+fn document_content(payload: Value) -> (String, Option<String>) {
+    match payload {
+        Value::String(text) => (text, None),
+        Value::Object(fields) => match first_string(&fields, CONTENT_FIELDS) {
+            Some(content) => (content, first_string(&fields, TITLE_FIELDS)),
+            None => (
+                serde_json::to_string_pretty(&fields).unwrap_or_default(),
+                None,
+            ),
+        },
+        other => (
+            serde_json::to_string_pretty(&other).unwrap_or_default(),
+            None,
+        ),
+    }
+}
+
+/// A `.md` file name for the document, unique among `taken`.
+fn file_name(document: &Document, taken: &mut HashSet<String>) -> String {
+    markdown_file_name(document.title.as_deref().unwrap_or(&document.id), taken)
+}
+
+/// `base` with characters unsafe in a URI segment replaced, trimmed, and cut
+/// to 100 characters; empty when nothing usable is left.
+pub(crate) fn safe_name(base: &str) -> String {
+    base.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ' ') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>()
+        .trim()
+        .trim_matches('.')
+        .chars()
+        .take(100)
+        .collect()
+}
+
+/// A `.md` file name made safe from `base`, unique among `taken`.
+pub(crate) fn markdown_file_name(base: &str, taken: &mut HashSet<String>) -> String {
+    let mut stem = safe_name(base);
+    if stem.is_empty() {
+        stem = "document".to_string();
+    }
+    let mut name = format!("{stem}.md");
+    let mut suffix = 2;
+    while !taken.insert(name.clone()) {
+        name = format!("{stem}-{suffix}.md");
+        suffix += 1;
+    }
+    name
+}
+
+fn parse_args(args: Option<&str>) -> Result<Map<String, Value>> {
+    match args {
+        None => Ok(Map::new()),
+        Some(raw) => match serde_json::from_str(raw) {
+            Ok(Value::Object(args)) => Ok(args),
+            _ => Err(Error::Client(format!(
+                "Invalid --args '{raw}'; expected a JSON object, e.g. '{{\"folder\":\"wiki\"}}'"
+            ))),
+        },
+    }
+}
+
+fn row(document: &Document, status: &str, uri: Option<&str>, error: Option<String>) -> Value {
+    json!({
+        "id": document.id,
+        "title": document.title,
+        "status": status,
+        "uri": uri,
+        "error": error,
+    })
+}
+
+/// Fetch (when needed) and upload one document, returning its URI.
+async fn import_one(
+    client: &HttpClient,
+    mcp: &mut McpClient,
+    options: &McpImportOptions<'_>,
+    document: &mut Document,
+    path: &std::path::Path,
+) -> Result<String> {
+    let content = match (options.tools.get(1), document.content.take()) {
+        (Some(fetch_tool), _) => {
+            let argument = options
+                .fetch_arg
+                .or(options.id_field)
+                .unwrap_or("id")
+                .to_string();
+            let result = mcp
+                .call_tool(fetch_tool, json!({ argument: document.id }))
+                .await?;
+            let (content, title) = document_content(tool_payload(&result));
+            if document.title.is_none() {
+                document.title = title;
+            }
+            content
+        }
+        (None, Some(content)) => content,
+        (None, None) => {
+            return Err(Error::Client(
+                "Listing has no content for this document; pass a fetch tool as the second --tool"
+                    .to_string(),
+            ));
+        }
+    };
+    std::fs::write(path, content)?;
+    upload_file(client, path, options.parent).await
+}
+
+/// Add one local file as a resource under `parent`, creating the parent when
+/// missing, and return its URI.
+pub(crate) async fn upload_file(
+    client: &HttpClient,
+    path: &std::path::Path,
+    parent: &str,
+) -> Result<String> {
+    let result = client
+        .add_resource(
+            &path.to_string_lossy(),
+            None,
+            None,
+            Some(parent.to_string()),
+            "",
+            "",
+            false,
+            None,
+            false,
+            None,
+            None,
+            None,
+            true,
+            0.0,
+            None,
+            false,
+            false,
+        )
+        .await?;
+    Ok(result["root_uri"].as_str().unwrap_or_default().to_string())
+}
+
+pub async fn import(
+    client: &HttpClient,
+    options: McpImportOptions<'_>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    interrupt::enter_batch();
+    let mut mcp = interrupt::cancellable(McpClient::connect(
+        options.server,
+        Duration::from_secs(options.timeout),
+    ))
+    .await?;
+    let tools = interrupt::cancellable(mcp.list_tools()).await?;
+    let Some(list_tool) = options.tools.first() else {
+        let rows: Vec<Value> = tools
+            .iter()
+            .map(|tool| json!({ "name": tool["name"], "description": tool["description"] }))
+            .collect();
+        output_success(Value::Array(rows), format, compact);
+        return Ok(());
+    };
+    if options.tools.len() > 2 {
+        return Err(Error::Client(
+            "Pass at most two --tool values: a list tool and a fetch tool".to_string(),
+        ));
+    }
+    for name in options.tools {
+        if !tools.iter().any(|tool| tool["name"] == name.as_str()) {
+            return Err(Error::Client(format!(
+                "MCP server has no tool '{name}'; run `ov import mcp --server ...` without --tool to list them"
+            )));
+        }
+    }
+
+    let listing =
+        interrupt::cancellable(mcp.call_tool(list_tool, Value::Object(parse_args(options.args)?)))
+            .await?;
+    let mut documents = documents(&tool_payload(&listing), options.id_field)?;
+    if let Some(limit) = options.limit {
+        documents.truncate(limit);
+    }
+
+    let dir = tempfile::tempdir()?;
+    let mut taken = HashSet::new();
+    let total = documents.len();
+    let mut rows = Vec::new();
+    let mut imported = 0;
+    let mut failed = 0;
+    let mut interrupted = false;
+    for mut document in documents {
+        if options.dry_run {
+            rows.push(row(&document, "planned", None, None));
+            continue;
+        }
+        let path = dir.path().join(file_name(&document, &mut taken));
+        let result =
+            interrupt::cancellable(import_one(client, &mut mcp, &options, &mut document, &path))
+                .await;
+        match result {
+            Ok(uri) => {
+                imported += 1;
+                rows.push(row(&document, "imported", Some(&uri), None));
+            }
+            Err(Error::Interrupted) => {
+                interrupted = true;
+                break;
+            }
+            Err(e) => {
+                failed += 1;
+                rows.push(row(&document, "failed", None, Some(e.to_string())));
+            }
+        }
+        eprintln!(
+            "{}",
+            theme::muted(format!(
+                "[{}/{total}] {}",
+                imported + failed,
+                document.title.as_deref().unwrap_or(&document.id)
+            ))
+        );
+    }
+
+    if matches!(format, OutputFormat::Table) {
+        output_success(Value::Array(rows), format, compact);
+        let summary = if options.dry_run {
+            format!(
+                "{total} document(s) would be imported into {}",
+                options.parent
+            )
+        } else {
+            format!(
+                "{imported} of {total} document(s) imported into {}: {failed} failed",
+                options.parent
+            )
+        };
+        println!("{}", theme::muted(summary));
+    } else {
+        output_success(
+            json!({
+                "server": options.server,
+                "parent": options.parent,
+                "dry_run": options.dry_run,
+                "documents": total,
+                "imported": imported,
+                "failed": failed,
+                "results": rows,
+            }),
+            format,
+            compact,
+        );
+    }
+    if interrupted {
+        return Err(Error::Interrupted);
+    }
+    if failed > 0 {
+        return Err(Error::AlreadyReported);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_result(text: &str) -> Value {
+        json!({ "content": [{ "type": "text", "text": text }] })
+    }
+
+    #[test]
+    fn payload_prefers_structured_content_then_json_text() {
+        let structured = json!({
+            "content": [{ "type": "text", "text": "ignored" }],
+            "structuredContent": { "items": [] },
+        });
+        assert_eq!(tool_payload(&structured), json!({ "items": [] }));
+        assert_eq!(tool_payload(&text_result("[1, 2]")), json!([1, 2]));
+        assert_eq!(tool_payload(&text_result("# Notes")), json!("# Notes"));
+    }
+
+    #[test]
+    fn documents_are_found_in_nested_listings() {
+        let payload = json!({
+            "data": { "items": [
+                { "token": "doxcn1", "title": "Roadmap" },
+                { "document_id": 42, "name": "Retro", "markdown": "# Retro" },
+                "doxcn3",
+            ] },
+        });
+        assert_eq!(
+            documents(&payload, None).unwrap(),
+            vec![
+                Document {
+                    id: "doxcn1".into(),
+                    title: Some("Roadmap".into()),
+                    content: None,
+                },
+                Document {
+                    id: "42".into(),
+                    title: Some("Retro".into()),
+                    content: Some("# Retro".into()),
+                },
+                Document {
+                    id: "doxcn3".into(),
+                    title: None,
+                    content: None,
+                },
+            ]
+        );
+        let err = documents(&json!([{ "title": "x" }]), Some("obj_token")).unwrap_err();
+        assert!(err.to_string().contains("obj_token"));
+        assert!(documents(&json!("plain text"), None).is_err());
+    }
+
+    #[test]
+    fn fetched_content_and_file_names() {
+        assert_eq!(
+            document_content(json!({ "title": "Plan", "content": "body" })),
+            ("body".to_string(), Some("Plan".to_string()))
+        );
+        assert_eq!(document_content(json!("raw")).0, "raw");
+
+        let mut taken = HashSet::new();
+        let doc = |id: &str, title: Option<&str>| Document {
+            id: id.into(),
+            title: title.map(str::to_string),
+            content: None,
+        };
+        assert_eq!(
+            file_name(&doc("a", Some("Q3 / plan")), &mut taken),
+            "Q3 _ plan.md"
+        );
+        assert_eq!(
+            file_name(&doc("b", Some("Q3 / plan")), &mut taken),
+            "Q3 _ plan-2.md"
+        );
+        assert_eq!(file_name(&doc("..", None), &mut taken), "document.md");
+        assert!(parse_args(Some("[1]")).is_err());
+        assert_eq!(parse_args(Some(r#"{"q":"x"}"#)).unwrap()["q"], "x");
+    }
+}
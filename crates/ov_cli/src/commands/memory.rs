@@ -0,0 +1,126 @@
+use crate::client::HttpClient;
+use crate::error::Result;
+use crate::output::{OutputFormat, output_success};
+use serde_json::{Value, json};
+
+/// Current-user memory root; the server canonicalizes it to the caller's space.
+pub const MEMORY_ROOT: &str = "viking://user/memories";
+
+const MEMORY_ABSTRACT_LIMIT: i32 = 256;
+
+pub async fn ls(
+    client: &HttpClient,
+    category: Option<&str>,
+    node_limit: i32,
+    output_format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let uri = resolve_memory_uri(category.unwrap_or_default());
+    let entries = client
+        .ls(
+            &uri,
+            false,
+            true,
+            "original",
+            MEMORY_ABSTRACT_LIMIT,
+            false,
+            node_limit,
+        )
+        .await?;
+    if output_format.is_json() {
+        output_success(&entries, output_format, compact);
+    } else {
+        output_success(memory_rows(&entries), output_format, compact);
+    }
+    Ok(())
+}
+
+pub async fn show(
+    client: &HttpClient,
+    uri: &str,
+    output_format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    super::content::read(
+        client,
+        &resolve_memory_uri(uri),
+        true,
+        output_format,
+        compact,
+    )
+    .await
+}
+
+/// Accept full `viking://` URIs as-is and treat anything else as a path below
+/// the memory root, so `ov memory show preferences/editor.md` works.
+pub fn resolve_memory_uri(input: &str) -> String {
+    let input = input.trim();
+    if input.starts_with("viking://") {
+        return input.to_string();
+    }
+    let relative = input.trim_matches('/');
+    if relative.is_empty() {
+        MEMORY_ROOT.to_string()
+    } else {
+        format!("{MEMORY_ROOT}/{relative}")
+    }
+}
+
+/// Keep memory files only (directories are categories) as `uri`/`abstract`
+/// rows for the table view.
+pub fn memory_rows(entries: &Value) -> Value {
+    let rows = entries
+        .as_array()
+        .map(|entries| {
+            entries
+                .iter()
+                .filter(|entry| !entry.get("isDir").and_then(Value::as_bool).unwrap_or(false))
+                .map(|entry| {
+                    json!({
+                        "uri": entry.get("uri").cloned().unwrap_or_default(),
+                        "abstract": entry.get("abstract").cloned().unwrap_or_default(),
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    Value::Array(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_memory_uri_expands_relative_paths() {
+        assert_eq!(resolve_memory_uri(""), "viking://user/memories");
+        assert_eq!(
+            resolve_memory_uri("/preferences/"),
+            "viking://user/memories/preferences"
+        );
+        assert_eq!(
+            resolve_memory_uri("viking://user/alice/memories/events/a.md"),
+            "viking://user/alice/memories/events/a.md"
+        );
+    }
+
+    #[test]
+    fn memory_rows_skip_category_directories() {
+        let rows = memory_rows(&json!([
+            {"uri": "viking://user/memories/events", "isDir": true, "abstract": ""},
+            {
+                "uri": "viking://user/memories/events/launch.md",
+                "isDir": false,
+                "abstract": "Shipped v2 on Friday"
+            }
+        ]));
+
+        assert_eq!(
+            rows,
+            json!([{
+                "uri": "viking://user/memories/events/launch.md",
+                "abstract": "Shipped v2 on Friday"
+            }])
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! `ov meta`: attach free-form `key=value` metadata to a URI, read it back,
+//! and remove keys.
+//!
+//! Metadata lives next to the content on the server and is returned by
+//! `/api/v1/fs/attrs` under `attrs.metadata`. Values are JSON: `batch=42`
+//! stores a number and `labels=["a","b"]` an array, while anything that does
+//! not parse as JSON is kept as a string. Quote a value (`id='"42"'`) to store
+//! a JSON-looking value as a string.
+
+use serde_json::{Map, Value, json};
+
+use crate::client::HttpClient;
+use crate::error::{Error, Result};
+use crate::output::{OutputFormat, output_success};
+
+fn parse_value(raw: &str) -> Value {
+    serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+}
+
+fn validate_key(key: &str) -> Result<&str> {
+    let key = key.trim();
+    if key.is_empty() || key.contains('=') {
+        return Err(Error::Client(format!(
+            "Invalid metadata key '{key}'; keys must be non-empty and contain no '='"
+        )));
+    }
+    Ok(key)
+}
+
+fn parse_pairs(pairs: &[String]) -> Result<Map<String, Value>> {
+    pairs
+        .iter()
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => Ok((validate_key(key)?.to_string(), parse_value(value))),
+            None => Err(Error::Client(format!(
+                "Invalid metadata '{pair}'; expected key=value, e.g. source=crm"
+            ))),
+        })
+        .collect()
+}
+
+/// The metadata currently attached to `uri`.
+pub async fn metadata_of(client: &HttpClient, uri: &str) -> Result<Map<String, Value>> {
+    let attrs = client.attrs(uri).await?;
+    Ok(attrs["attrs"]["metadata"]
+        .as_object()
+        .cloned()
+        .unwrap_or_default())
+}
+
+fn metadata_rows(uri: &str, metadata: &Map<String, Value>) -> Value {
+    let rows: Vec<Value> = metadata
+        .iter()
+        .map(|(key, value)| json!({ "uri": uri, "key": key, "value": value }))
+        .collect();
+    Value::Array(rows)
+}
+
+pub async fn set(
+    client: &HttpClient,
+    uri: &str,
+    pairs: &[String],
+    output_format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let values = parse_pairs(pairs)?;
+    let result = client.set_metadata(uri, values, Vec::new()).await?;
+    let metadata = result["metadata"].as_object().cloned().unwrap_or_default();
+    output_success(metadata_rows(uri, &metadata), output_format, compact);
+    Ok(())
+}
+
+/// Print all metadata of `uri`, or just the value of `key`.
+pub async fn get(
+    client: &HttpClient,
+    uri: &str,
+    key: Option<&str>,
+    output_format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let metadata = metadata_of(client, uri).await?;
+    match key {
+        Some(key) => {
+            let value = metadata.get(key).ok_or_else(|| {
+                Error::Client(format!(
+                    "{uri} has no metadata key '{key}'; see `ov meta get {uri}`"
+                ))
+            })?;
+            output_success(value.clone(), output_format, compact);
+        }
+        None => output_success(metadata_rows(uri, &metadata), output_format, compact),
+    }
+    Ok(())
+}
+
+pub async fn remove(
+    client: &HttpClient,
+    uri: &str,
+    keys: &[String],
+    output_format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let keys = keys
+        .iter()
+        .map(|key| validate_key(key).map(str::to_string))
+        .collect::<Result<Vec<_>>>()?;
+    let current = metadata_of(client, uri).await?;
+    let (removed, missing): (Vec<String>, Vec<String>) =
+        keys.into_iter().partition(|key| current.contains_key(key));
+    if removed.is_empty() {
+        return Err(Error::Client(format!(
+            "{uri} has no metadata key {}; see `ov meta get {uri}`",
+            missing.join(", ")
+        )));
+    }
+    let result = client
+        .set_metadata(uri, Map::new(), removed.clone())
+        .await?;
+    output_success(
+        json!({ "uri": uri, "removed": removed, "metadata": result["metadata"] }),
+        output_format,
+        compact,
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(items: &[&str]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    #[test]
+    fn values_parse_as_json_or_fall_back_to_strings() {
+        let values = parse_pairs(&pairs(&[
+            "source=crm",
+            "batch=42",
+            "reviewed=true",
+            r#"labels=["a","b"]"#,
+            r#"id="42""#,
+            "note=a=b",
+            " owner =",
+        ]))
+        .unwrap();
+        assert_eq!(
+            Value::Object(values),
+            json!({
+                "source": "crm",
+                "batch": 42,
+                "reviewed": true,
+                "labels": ["a", "b"],
+                "id": "42",
+                "note": "a=b",
+                "owner": "",
+            })
+        );
+    }
+
+    #[test]
+    fn pairs_need_a_key_and_an_equals_sign() {
+        for bad in ["source", "=crm", " =crm"] {
+            assert!(
+                parse_pairs(&pairs(&[bad])).is_err(),
+                "{bad} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn metadata_rows_keep_json_values() {
+        let mut metadata = Map::new();
+        metadata.insert("batch".to_string(), json!(42));
+        assert_eq!(
+            metadata_rows("viking://resources/a.md", &metadata),
+            json!([{ "uri": "viking://resources/a.md", "key": "batch", "value": 42 }])
+        );
+    }
+}
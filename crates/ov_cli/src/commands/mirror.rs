@@ -0,0 +1,570 @@
+//! `ov watch`: keep a resources subtree mirroring a local directory.
+//!
+//! Filesystem events only trigger a rescan. What changed is decided by
+//! comparing the directory with a state file under `~/.openviking/watch/` that
+//! records the size, mtime, and SHA-256 of every file last mirrored, so a
+//! restarted watch catches up on edits made while it was stopped. Text files
+//! the content write API accepts are written in place and reindexed; other
+//! files are skipped, and hidden entries such as `.obsidian/` are ignored.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use crate::chunked_upload::sha256_file;
+use crate::client::HttpClient;
+use crate::config::default_config_path;
+use crate::error::{ApiErrorKind, Error, Result};
+use crate::interrupt;
+use crate::output::OutputFormat;
+use crate::theme;
+
+/// Extensions the content write API creates files for.
+const TEXT_EXTENSIONS: [&str; 9] = ["md", "txt", "json", "yaml", "yml", "toml", "py", "js", "ts"];
+/// How soon a failed sync is retried when no new event arrives.
+const RETRY_AFTER: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct WatchState {
+    dir: PathBuf,
+    target: String,
+    files: BTreeMap<String, FileState>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct FileState {
+    size: u64,
+    /// Milliseconds since the epoch.
+    modified: u64,
+    sha256: String,
+    /// Not a text file the write API accepts, so it was never uploaded.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    skipped: bool,
+}
+
+/// One step that brings the target in line with the directory.
+#[derive(Debug, PartialEq, Eq)]
+enum Change {
+    /// Upload a new or edited file.
+    Write(String),
+    /// Remove a deleted file, or the top-most deleted directory above it.
+    Remove { path: String, directory: bool },
+}
+
+struct Mirror<'a> {
+    client: &'a HttpClient,
+    dir: PathBuf,
+    target: String,
+    state_path: PathBuf,
+    state: WatchState,
+    /// Directories below the target known to exist on the server.
+    remote_dirs: HashSet<String>,
+    format: OutputFormat,
+}
+
+/// Mirror `dir` to `target`, then keep mirroring until Ctrl-C; with `once`,
+/// stop after the first pass.
+pub async fn watch(
+    client: &HttpClient,
+    dir: &str,
+    target: &str,
+    debounce: Duration,
+    state_path: Option<&str>,
+    once: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let dir = Path::new(dir)
+        .canonicalize()
+        .map_err(|e| Error::InvalidPath(format!("Cannot read {dir}: {e}")))?;
+    if !dir.is_dir() {
+        return Err(Error::InvalidPath(format!(
+            "Not a directory: {}",
+            dir.display()
+        )));
+    }
+    let target = target.trim_end_matches('/');
+    if !target.starts_with("viking://") || target.len() == "viking://".len() {
+        return Err(Error::Client(format!(
+            "--target must be a viking:// directory URI: {target}"
+        )));
+    }
+    let state_path = match state_path {
+        Some(path) => PathBuf::from(path),
+        None => default_state_path(client.server_url(), &dir, target)?,
+    };
+    let mut mirror = Mirror::open(client, dir, target, state_path, format)?;
+    mirror.ensure_target().await?;
+    if once {
+        mirror.sync().await?;
+        return Ok(());
+    }
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event| {
+        let _ = tx.send(event);
+    })
+    .map_err(|e| Error::Client(format!("Cannot watch for file changes: {e}")))?;
+    watcher
+        .watch(&mirror.dir, RecursiveMode::Recursive)
+        .map_err(|e| {
+            Error::Client(format!(
+                "Cannot watch {} for changes: {e}",
+                mirror.dir.display()
+            ))
+        })?;
+    eprintln!(
+        "{}",
+        theme::muted(format!(
+            "Watching {} and mirroring it to {}; press Ctrl-C to stop.",
+            mirror.dir.display(),
+            mirror.target
+        ))
+    );
+    interrupt::enter_batch();
+    match interrupt::cancellable(mirror.sync()).await {
+        Err(Error::Interrupted) => return Ok(()),
+        result => result?,
+    }
+
+    let mut retry = false;
+    loop {
+        let next = async {
+            if retry {
+                let _ = tokio::time::timeout(RETRY_AFTER, next_change(&mut rx, &mirror.dir)).await;
+            } else {
+                next_change(&mut rx, &mirror.dir).await;
+            }
+            // Let a burst of events (an editor's save, a git checkout) settle.
+            while let Ok(Some(_)) = tokio::time::timeout(debounce, rx.recv()).await {}
+            Ok(())
+        };
+        if let Err(Error::Interrupted) = interrupt::cancellable(next).await {
+            break;
+        }
+        match interrupt::cancellable(mirror.sync()).await {
+            Err(Error::Interrupted) => break,
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    theme::warning(format!(
+                        "Sync failed, retrying on the next change or in {}s: {e}",
+                        RETRY_AFTER.as_secs()
+                    ))
+                );
+                retry = true;
+            }
+            Ok(()) => retry = false,
+        }
+    }
+    drop(watcher);
+    Ok(())
+}
+
+/// Wait for an event that may change what gets mirrored: anything but reads,
+/// touching at least one path outside hidden entries.
+async fn next_change(rx: &mut UnboundedReceiver<notify::Result<notify::Event>>, dir: &Path) {
+    while let Some(event) = rx.recv().await {
+        let Ok(event) = event else {
+            return;
+        };
+        if matches!(event.kind, EventKind::Access(_)) {
+            continue;
+        }
+        let visible = event.paths.iter().any(|path| {
+            path.strip_prefix(dir)
+                .is_ok_and(|rel| !rel.components().any(|part| is_hidden(part.as_os_str())))
+        });
+        if visible {
+            return;
+        }
+    }
+    std::future::pending::<()>().await;
+}
+
+impl<'a> Mirror<'a> {
+    fn open(
+        client: &'a HttpClient,
+        dir: PathBuf,
+        target: &str,
+        state_path: PathBuf,
+        format: OutputFormat,
+    ) -> Result<Self> {
+        let state = match std::fs::read_to_string(&state_path) {
+            Ok(text) => {
+                let state: WatchState = serde_json::from_str(&text).map_err(|e| {
+                    Error::Parse(format!(
+                        "Invalid watch state file {}: {e}",
+                        state_path.display()
+                    ))
+                })?;
+                if state.dir != dir || state.target != target {
+                    return Err(Error::Client(format!(
+                        "State file belongs to {} -> {}: {}",
+                        state.dir.display(),
+                        state.target,
+                        state_path.display()
+                    )));
+                }
+                state
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => WatchState {
+                dir: dir.clone(),
+                target: target.to_string(),
+                files: BTreeMap::new(),
+            },
+            Err(e) => return Err(e.into()),
+        };
+        let remote_dirs = state
+            .files
+            .iter()
+            .filter(|(_, file)| !file.skipped)
+            .flat_map(|(path, _)| ancestors(path))
+            .map(str::to_string)
+            .collect();
+        Ok(Self {
+            client,
+            dir,
+            target: target.to_string(),
+            state_path,
+            state,
+            remote_dirs,
+            format,
+        })
+    }
+
+    async fn ensure_target(&self) -> Result<()> {
+        match self.client.stat(&self.target).await {
+            Ok(_) => Ok(()),
+            Err(e) if e.api_kind() == Some(ApiErrorKind::NotFound) => {
+                self.client.mkdir(&self.target, None).await.map(|_| ())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Apply every change since the last pass, saving the state after each.
+    async fn sync(&mut self) -> Result<()> {
+        let local = scan(&self.dir)?;
+        let dir = self.dir.clone();
+        let changes = plan_changes(&self.state.files, &local, |path| dir.join(path).is_dir());
+        for change in changes {
+            match change {
+                Change::Write(path) => self.write(&path).await?,
+                Change::Remove { path, directory } => self.remove(&path, directory).await?,
+            }
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    async fn write(&mut self, path: &str) -> Result<()> {
+        let file = self.dir.join(path);
+        let Ok((size, modified)) = stamp(&file) else {
+            // Gone again since the scan; the next pass removes it.
+            return Ok(());
+        };
+        let sha256 = sha256_file(&file)?;
+        let previous = self.state.files.get(path).cloned();
+        if previous
+            .as_ref()
+            .is_some_and(|previous| previous.sha256 == sha256)
+        {
+            if let Some(entry) = self.state.files.get_mut(path) {
+                (entry.size, entry.modified) = (size, modified);
+            }
+            return Ok(());
+        }
+        let mut entry = FileState {
+            size,
+            modified,
+            sha256,
+            skipped: false,
+        };
+        let content = std::fs::read(&file)?;
+        let Some(content) = is_text_path(path)
+            .then(|| String::from_utf8(content).ok())
+            .flatten()
+        else {
+            if !previous.is_some_and(|previous| previous.skipped) {
+                self.report("skip", path, "only UTF-8 text files can be mirrored");
+            }
+            entry.skipped = true;
+            self.state.files.insert(path.to_string(), entry);
+            return Ok(());
+        };
+
+        let uri = self.uri(path);
+        let exists = previous.is_some_and(|previous| !previous.skipped);
+        if !exists {
+            for parent in ancestors(path) {
+                if self.remote_dirs.contains(parent) {
+                    continue;
+                }
+                match self.client.mkdir(&self.uri(parent), None).await {
+                    Err(e) if e.api_kind() != Some(ApiErrorKind::Conflict) => return Err(e),
+                    _ => {}
+                }
+                self.remote_dirs.insert(parent.to_string());
+            }
+        }
+        // The server may disagree with the state file, e.g. after another
+        // client touched the target; fall back to the other mode.
+        let (mode, fallback, mismatch) = if exists {
+            ("replace", "create", ApiErrorKind::NotFound)
+        } else {
+            ("create", "replace", ApiErrorKind::Conflict)
+        };
+        match self.client.write(&uri, &content, mode, false, None).await {
+            Err(e) if e.api_kind() == Some(mismatch) => {
+                self.client
+                    .write(&uri, &content, fallback, false, None)
+                    .await?;
+            }
+            result => {
+                result?;
+            }
+        }
+        self.state.files.insert(path.to_string(), entry);
+        self.report("write", path, &uri);
+        Ok(())
+    }
+
+    async fn remove(&mut self, path: &str, directory: bool) -> Result<()> {
+        let below = format!("{path}/");
+        let mirrored = self
+            .state
+            .files
+            .iter()
+            .any(|(file, state)| (file == path || file.starts_with(&below)) && !state.skipped);
+        let uri = self.uri(path);
+        if mirrored {
+            match self.client.rm(&uri, directory, false, None).await {
+                Err(e) if e.api_kind() != Some(ApiErrorKind::NotFound) => return Err(e),
+                _ => {}
+            }
+        }
+        self.state
+            .files
+            .retain(|file, _| file != path && !file.starts_with(&below));
+        if directory {
+            self.remote_dirs
+                .retain(|dir| dir != path && !dir.starts_with(&below));
+        }
+        if mirrored {
+            self.report("remove", path, &uri);
+        }
+        Ok(())
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.state_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.state_path, serde_json::to_string_pretty(&self.state)?)?;
+        Ok(())
+    }
+
+    fn uri(&self, path: &str) -> String {
+        format!("{}/{path}", self.target)
+    }
+
+    /// One line per change: `uri` is the written or removed URI, or why a file
+    /// was skipped.
+    fn report(&self, action: &str, path: &str, detail: &str) {
+        if self.format.is_json() {
+            let key = if action == "skip" { "reason" } else { "uri" };
+            println!("{}", json!({"action": action, "path": path, key: detail}));
+            return;
+        }
+        match action {
+            "write" => println!("{} {detail}", theme::success("Wrote")),
+            "remove" => println!("{} {detail}", theme::warning("Removed")),
+            _ => println!("{} {path} ({detail})", theme::muted("Skipped")),
+        }
+    }
+}
+
+/// Size and mtime of every visible file below `dir`, by `/`-separated path.
+fn scan(dir: &Path) -> Result<BTreeMap<String, (u64, u64)>> {
+    let mut files = BTreeMap::new();
+    let walker = walkdir::WalkDir::new(dir)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|entry| !is_hidden(entry.file_name()));
+    for entry in walker {
+        let entry = entry.map_err(|e| Error::Io(e.into()))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Some(path) = entry
+            .path()
+            .strip_prefix(dir)
+            .ok()
+            .and_then(Path::to_str)
+            .map(|path| path.replace(std::path::MAIN_SEPARATOR, "/"))
+        else {
+            continue;
+        };
+        if let Ok(stamp) = stamp(entry.path()) {
+            files.insert(path, stamp);
+        }
+    }
+    Ok(files)
+}
+
+/// Writes for new or touched files, then removals for deleted ones; a deleted
+/// directory is removed once rather than file by file.
+fn plan_changes(
+    state: &BTreeMap<String, FileState>,
+    local: &BTreeMap<String, (u64, u64)>,
+    is_dir: impl Fn(&str) -> bool,
+) -> Vec<Change> {
+    let mut changes: Vec<Change> = local
+        .iter()
+        .filter(|(path, (size, modified))| {
+            state
+                .get(*path)
+                .is_none_or(|file| (file.size, file.modified) != (*size, *modified))
+        })
+        .map(|(path, _)| Change::Write(path.clone()))
+        .collect();
+    let mut removed_dirs: Vec<&str> = Vec::new();
+    for path in state.keys().filter(|path| !local.contains_key(*path)) {
+        if removed_dirs
+            .iter()
+            .any(|dir| path.starts_with(&format!("{dir}/")))
+        {
+            continue;
+        }
+        // `ancestors` runs from the top, so the first missing one is top-most.
+        match ancestors(path).find(|dir| !is_dir(dir)) {
+            Some(dir) => {
+                removed_dirs.push(dir);
+                changes.push(Change::Remove {
+                    path: dir.to_string(),
+                    directory: true,
+                });
+            }
+            None => changes.push(Change::Remove {
+                path: path.clone(),
+                directory: false,
+            }),
+        }
+    }
+    changes
+}
+
+/// Directories above `path`, top-most first: `a/b/c.md` gives `a`, `a/b`.
+fn ancestors(path: &str) -> impl Iterator<Item = &str> {
+    path.match_indices('/')
+        .map(move |(index, _)| &path[..index])
+}
+
+fn stamp(path: &Path) -> std::io::Result<(u64, u64)> {
+    let metadata = std::fs::metadata(path)?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |age| age.as_millis() as u64);
+    Ok((metadata.len(), modified))
+}
+
+fn is_hidden(name: &std::ffi::OsStr) -> bool {
+    name.to_str().is_some_and(|name| name.starts_with('.'))
+}
+
+fn is_text_path(path: &str) -> bool {
+    path.rsplit_once('.')
+        .is_some_and(|(_, ext)| TEXT_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// One state file per server, directory, and target.
+fn default_state_path(server: &str, dir: &Path, target: &str) -> Result<PathBuf> {
+    let config_path = default_config_path()?;
+    let home = config_path.parent().unwrap_or(Path::new("."));
+    let key = Sha256::digest(format!("{server}\n{}\n{target}", dir.display()).as_bytes());
+    Ok(home
+        .join("watch")
+        .join(format!("{}.json", &hex::encode(key)[..32])))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(size: u64, modified: u64) -> FileState {
+        FileState {
+            size,
+            modified,
+            sha256: String::new(),
+            skipped: false,
+        }
+    }
+
+    #[test]
+    fn plan_writes_touched_files_and_removes_deleted_directories_once() {
+        let state = BTreeMap::from([
+            ("daily/2026-10-01.md".to_string(), file(10, 1)),
+            ("daily/2026-10-02.md".to_string(), file(10, 1)),
+            ("archive/old/a.md".to_string(), file(3, 1)),
+            ("archive/old/b.md".to_string(), file(3, 1)),
+            ("archive/keep.md".to_string(), file(3, 1)),
+            ("inbox.md".to_string(), file(5, 1)),
+        ]);
+        let local = BTreeMap::from([
+            ("daily/2026-10-01.md".to_string(), (10, 1)),
+            ("daily/2026-10-02.md".to_string(), (12, 2)),
+            ("archive/keep.md".to_string(), (3, 1)),
+            ("projects/plan.md".to_string(), (7, 3)),
+        ]);
+        let changes = plan_changes(&state, &local, |dir| dir != "archive/old");
+        assert_eq!(
+            changes,
+            [
+                Change::Write("daily/2026-10-02.md".to_string()),
+                Change::Write("projects/plan.md".to_string()),
+                Change::Remove {
+                    path: "archive/old".to_string(),
+                    directory: true,
+                },
+                Change::Remove {
+                    path: "inbox.md".to_string(),
+                    directory: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn scan_skips_hidden_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".obsidian")).unwrap();
+        std::fs::create_dir_all(dir.path().join("notes")).unwrap();
+        std::fs::write(dir.path().join(".obsidian/workspace.json"), "{}").unwrap();
+        std::fs::write(dir.path().join("notes/.draft.md"), "x").unwrap();
+        std::fs::write(dir.path().join("notes/idea.md"), "idea").unwrap();
+        std::fs::write(dir.path().join("photo.png"), [0u8, 1]).unwrap();
+
+        let files = scan(dir.path()).unwrap();
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            ["notes/idea.md", "photo.png"]
+        );
+        assert_eq!(files["notes/idea.md"].0, 4);
+        assert!(is_text_path("notes/idea.md") && is_text_path("README.MD"));
+        assert!(!is_text_path("photo.png") && !is_text_path("Makefile"));
+    }
+
+    #[test]
+    fn ancestors_run_from_the_top() {
+        assert_eq!(ancestors("a/b/c.md").collect::<Vec<_>>(), ["a", "a/b"]);
+        assert_eq!(ancestors("c.md").count(), 0);
+    }
+}
@@ -0,0 +1,24 @@
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::error::{Error, Result};
+use crate::mock_server::{self, MockServer};
+
+/// Serve an in-memory OpenViking API, loaded from `fixtures` when given,
+/// until Ctrl-C.
+pub async fn run(fixtures: Option<&Path>, host: &str, port: u16) -> Result<()> {
+    let server = Arc::new(MockServer::new(fixtures)?);
+    let addr: SocketAddr = format!("{host}:{port}")
+        .parse()
+        .or_else(|_| format!("[{host}]:{port}").parse())
+        .map_err(|_| Error::Client(format!("Invalid --host '{host}'; use an IP address")))?;
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| Error::Client(format!("Cannot listen on {addr}: {e}")))?;
+    eprintln!(
+        "ov mock-server listening on http://{}",
+        listener.local_addr()?
+    );
+    mock_server::serve(listener, server).await
+}
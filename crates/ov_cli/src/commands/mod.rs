@@ -1,18 +1,62 @@
 pub mod admin;
+pub mod alias;
+pub mod api;
+pub mod ask;
+pub mod audit;
+pub mod bench;
 pub mod chat;
+pub mod clip;
+pub mod completions;
 pub mod content;
+pub mod context;
 pub mod crypto;
+pub mod daemon;
+pub mod doctor;
+pub mod events;
+pub mod explain;
+pub mod feed_import;
 pub mod filesystem;
+pub mod gen_man;
+pub mod history;
+pub mod index;
+pub mod jobs;
+pub mod mbox_import;
+pub mod mcp;
+pub mod mcp_import;
+pub mod memory;
+pub mod meta;
+pub mod mirror;
+pub mod mock_server;
+pub mod notion_import;
+pub mod object_import;
 pub mod observer;
 pub mod pack;
+pub mod pick;
+pub mod plugin;
+pub mod preflight;
 pub mod privacy;
+pub mod queue;
+pub mod relation_import;
 pub mod relations;
 pub(crate) mod render_utils;
 pub mod resources;
+pub mod saved_search;
+pub mod schedule;
+pub mod script;
 pub mod search;
 pub mod session;
 pub mod skills;
 pub mod snapshot;
+pub mod snippets;
+pub mod stats;
+pub mod stdin_import;
 pub mod system;
+pub mod table_import;
+pub mod tags;
 pub mod task;
+pub mod tokens;
+pub mod top;
+pub mod version;
 pub mod watch;
+pub mod web_import;
+pub mod workspace;
@@ -0,0 +1,719 @@
+//! `ov import notion <export.zip>`: import a Notion "Markdown & CSV" export.
+//!
+//! Notion names every page `Title <32-hex id>.md` and keeps its subpages in a
+//! sibling folder of the same name; a database is `Title <id>.csv` (plus an
+//! `_all.csv` with every row) next to a folder of row pages. The import keeps
+//! that hierarchy as the URI tree under `--parent`, with the ids dropped from
+//! the names, turns each database into a Markdown table whose first column
+//! links to its row pages, and uploads images and other attachments beside the
+//! pages that embed them. Links between pages, relative or `notion.so` URLs,
+//! are rewritten to the pages' viking:// URIs and recorded as relations.
+//!
+//! Large exports arrive as a zip of `Part-N.zip` files; the parts are merged.
+//! Pages are written in place, so rerunning an import updates it.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use regex::{Captures, Regex};
+use serde_json::{Value, json};
+
+use super::mcp_import::{safe_name, upload_file};
+use crate::archive::{self, ArchiveKind, Extracted};
+use crate::client::HttpClient;
+use crate::error::{ApiErrorKind, Error, Result};
+use crate::interrupt;
+use crate::output::{OutputFormat, output_success};
+use crate::theme;
+use crate::utils::percent_decode;
+
+const LINK_REASON: &str = "Linked from the Notion page";
+
+pub struct NotionImportOptions<'a> {
+    pub export: &'a Path,
+    pub parent: &'a str,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Page,
+    Database,
+    Attachment,
+}
+
+impl Kind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Page => "page",
+            Self::Database => "database",
+            Self::Attachment => "attachment",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Item {
+    source: PathBuf,
+    /// Path in the export, `/`-separated, ids included.
+    original: String,
+    /// Path under `--parent`, ids dropped.
+    path: String,
+    kind: Kind,
+}
+
+/// The title and Notion id of a file stem such as `Roadmap 0123...cdef`.
+fn split_id(stem: &str) -> (&str, Option<&str>) {
+    match stem.rsplit_once(' ') {
+        Some((title, id)) if id.len() == 32 && id.bytes().all(|b| b.is_ascii_hexdigit()) => {
+            (title, Some(id))
+        }
+        _ => (stem, None),
+    }
+}
+
+/// Clean, per-directory unique names for export entries. A page's `.md` and
+/// its subpage folder share a stem, so they get the same name.
+#[derive(Default)]
+struct Names {
+    assigned: HashMap<(String, String), String>,
+    taken: HashMap<String, HashSet<String>>,
+}
+
+impl Names {
+    fn name(&mut self, dir: &str, original: &str) -> String {
+        let key = (dir.to_string(), original.to_string());
+        if let Some(name) = self.assigned.get(&key) {
+            return name.clone();
+        }
+        let mut base = safe_name(split_id(original).0);
+        if base.is_empty() {
+            base = "Untitled".to_string();
+        }
+        let taken = self.taken.entry(dir.to_string()).or_default();
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while !taken.insert(name.clone()) {
+            name = format!("{base}-{suffix}");
+            suffix += 1;
+        }
+        self.assigned.insert(key, name.clone());
+        name
+    }
+}
+
+/// Every page, database, and attachment under `roots`, which are merged as
+/// one tree.
+fn plan(roots: &[PathBuf]) -> Vec<Item> {
+    let mut names = Names::default();
+    let mut items = Vec::new();
+    for root in roots {
+        let files = walkdir::WalkDir::new(root)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file());
+        for entry in files {
+            let Ok(relative) = entry.path().strip_prefix(root) else {
+                continue;
+            };
+            let parts: Vec<String> = relative
+                .components()
+                .map(|part| part.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            let Some((file, dirs)) = parts.split_last() else {
+                continue;
+            };
+            let lower = file.to_ascii_lowercase();
+            if lower.ends_with(".zip") {
+                continue;
+            }
+            let (kind, stem) = if lower.ends_with(".md") {
+                (Kind::Page, &file[..file.len() - 3])
+            } else if lower.ends_with("_all.csv") {
+                (Kind::Database, &file[..file.len() - 8])
+            } else if lower.ends_with(".csv") {
+                let stem = &file[..file.len() - 4];
+                if entry
+                    .path()
+                    .with_file_name(format!("{stem}_all.csv"))
+                    .exists()
+                {
+                    continue;
+                }
+                (Kind::Database, stem)
+            } else {
+                (Kind::Attachment, file.as_str())
+            };
+
+            let mut dir = String::new();
+            for part in dirs {
+                let name = names.name(&dir, part);
+                dir = if dir.is_empty() {
+                    name
+                } else {
+                    format!("{dir}/{name}")
+                };
+            }
+            let mut name = names.name(&dir, stem);
+            if kind != Kind::Attachment {
+                name.push_str(".md");
+            }
+            items.push(Item {
+                source: entry.path().to_path_buf(),
+                original: parts.join("/"),
+                path: if dir.is_empty() {
+                    name
+                } else {
+                    format!("{dir}/{name}")
+                },
+                kind,
+            });
+        }
+    }
+    items
+}
+
+/// `path` with `.` and `..` segments resolved; `None` if it climbs out.
+fn normalize(path: &str) -> Option<String> {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            part => parts.push(part),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+/// Where each export entry lands, for rewriting links between them.
+struct Index {
+    uris: Vec<String>,
+    by_path: HashMap<String, usize>,
+    by_id: HashMap<String, usize>,
+}
+
+impl Index {
+    fn new(items: &[Item], parent: &str) -> Self {
+        let mut by_path = HashMap::new();
+        let mut by_id = HashMap::new();
+        for (index, item) in items.iter().enumerate() {
+            by_path.insert(item.original.clone(), index);
+            if item.kind != Kind::Attachment {
+                let file = item.original.rsplit('/').next().unwrap_or_default();
+                let stem = file.rsplit_once('.').map_or(file, |(stem, _)| stem);
+                if let (_, Some(id)) = split_id(stem.trim_end_matches("_all")) {
+                    by_id.entry(id.to_ascii_lowercase()).or_insert(index);
+                }
+            }
+        }
+        Self {
+            uris: items
+                .iter()
+                .map(|item| format!("{}/{}", parent.trim_end_matches('/'), item.path))
+                .collect(),
+            by_path,
+            by_id,
+        }
+    }
+
+    /// The entry a link in the page at `from` points to.
+    fn resolve(&self, from: &str, target: &str) -> Option<usize> {
+        if target.starts_with('#') || target.starts_with("mailto:") {
+            return None;
+        }
+        let target = target.split(['#', '?']).next()?;
+        if let Some((scheme, rest)) = target.split_once("://") {
+            if !matches!(scheme, "http" | "https") || !rest.contains("notion.") {
+                return None;
+            }
+            let last = rest.rsplit('/').next()?;
+            let id = last.rsplit('-').next()?.to_ascii_lowercase();
+            return self.by_id.get(&id).copied();
+        }
+        let decoded = percent_decode(target);
+        normalize(&format!("{}/{decoded}", parent_dir(from)))
+            .and_then(|path| self.by_path.get(&path).copied())
+            .or_else(|| {
+                let file = decoded.rsplit('/').next()?;
+                let stem = file.rsplit_once('.').map_or(file, |(stem, _)| stem);
+                let id = split_id(stem.trim_end_matches("_all")).1?;
+                self.by_id.get(&id.to_ascii_lowercase()).copied()
+            })
+    }
+
+    /// A Markdown link destination for entry `index`.
+    fn destination(&self, index: usize) -> String {
+        let uri = &self.uris[index];
+        if uri.contains([' ', '(', ')']) {
+            format!("<{uri}>")
+        } else {
+            uri.clone()
+        }
+    }
+}
+
+/// `markdown` with its links to other export entries pointing at their
+/// URIs, and the pages and databases it links to.
+fn rewrite_links(
+    markdown: &str,
+    from: &str,
+    index: &Index,
+    items: &[Item],
+) -> (String, BTreeSet<usize>) {
+    static LINK: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+    let link = LINK.get_or_init(|| Regex::new(r"\]\(([^()\s]+)\)").expect("valid link regex"));
+    let mut linked = BTreeSet::new();
+    let rewritten = link.replace_all(markdown, |captures: &Captures| {
+        match index.resolve(from, &captures[1]) {
+            Some(target) => {
+                if items[target].kind != Kind::Attachment {
+                    linked.insert(target);
+                }
+                format!("]({})", index.destination(target))
+            }
+            None => captures[0].to_string(),
+        }
+    });
+    (rewritten.into_owned(), linked)
+}
+
+/// Rows of a CSV file, with quoted fields and a leading BOM handled.
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') => quoted = true,
+            (false, ',') => row.push(std::mem::take(&mut field)),
+            (false, '\r') => {}
+            (false, '\n') => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+fn table_cell(value: &str) -> String {
+    value.trim().replace('|', "\\|").replace('\n', "<br>")
+}
+
+/// A database as a Markdown table whose first column links to the row pages
+/// in its folder, and the rows it links to.
+fn database_markdown(
+    csv: &str,
+    item: &Item,
+    index: &Index,
+    items: &[Item],
+) -> (String, BTreeSet<usize>) {
+    let file = item.original.rsplit('/').next().unwrap_or_default();
+    let stem = &file[..file.len() - 4];
+    let stem = stem.strip_suffix("_all").unwrap_or(stem);
+    let folder = match parent_dir(&item.original) {
+        "" => format!("{stem}/"),
+        dir => format!("{dir}/{stem}/"),
+    };
+    // Row pages are `<title> <id>.md` directly in the database's folder.
+    let row_pages: HashMap<&str, usize> = items
+        .iter()
+        .enumerate()
+        .filter(|(_, row)| row.kind == Kind::Page)
+        .filter_map(|(position, row)| {
+            let name = row.original.strip_prefix(&folder)?;
+            (!name.contains('/')).then_some(())?;
+            Some((split_id(name.strip_suffix(".md")?).0, position))
+        })
+        .collect();
+
+    let mut linked = BTreeSet::new();
+    let mut markdown = format!("# {}\n", split_id(stem).0);
+    let rows = parse_csv(csv);
+    if let Some((header, rows)) = rows.split_first() {
+        let cell = |row: &[String], column: usize| {
+            table_cell(row.get(column).map(String::as_str).unwrap_or_default())
+        };
+        markdown.push_str(&format!(
+            "\n| {} |\n|{}\n",
+            (0..header.len())
+                .map(|column| cell(header, column))
+                .collect::<Vec<_>>()
+                .join(" | "),
+            " --- |".repeat(header.len())
+        ));
+        for row in rows {
+            let cells: Vec<String> = (0..header.len())
+                .map(|column| match (column, row_pages.get(row[0].trim())) {
+                    (0, Some(&page)) => {
+                        linked.insert(page);
+                        format!("[{}]({})", cell(row, 0), index.destination(page))
+                    }
+                    _ => cell(row, column),
+                })
+                .collect();
+            markdown.push_str(&format!("| {} |\n", cells.join(" | ")));
+        }
+    }
+    (markdown, linked)
+}
+
+/// The export's top-level folders: the zip itself, or each `Part-N.zip`
+/// inside it.
+fn open_export(path: &Path) -> Result<(Vec<Extracted>, Vec<PathBuf>)> {
+    if path.is_dir() {
+        return Ok((Vec::new(), vec![path.to_path_buf()]));
+    }
+    if ArchiveKind::of(path) != Some(ArchiveKind::Zip) {
+        return Err(Error::Client(format!(
+            "{} is not a Notion export; expected the .zip from Export > Markdown & CSV, or its extracted folder",
+            path.display()
+        )));
+    }
+    let outer = archive::extract(path, ArchiveKind::Zip)?;
+    let parts: Vec<PathBuf> = walkdir::WalkDir::new(&outer.root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| ArchiveKind::of(entry.path()) == Some(ArchiveKind::Zip))
+        .map(|entry| entry.into_path())
+        .collect();
+    let mut roots = vec![outer.root.clone()];
+    let mut extracted = vec![outer];
+    for part in parts {
+        let part = archive::extract(&part, ArchiveKind::Zip)?;
+        roots.push(part.root.clone());
+        extracted.push(part);
+    }
+    Ok((extracted, roots))
+}
+
+fn row(item: &Item, status: &str, uri: Option<&str>, error: Option<String>) -> Value {
+    json!({
+        "path": item.original,
+        "kind": item.kind.as_str(),
+        "status": status,
+        "uri": uri,
+        "error": error,
+    })
+}
+
+/// Write a page at `path` under `parent`, creating the directories above it
+/// that `dirs` has not seen yet.
+pub(crate) async fn write_page(
+    client: &HttpClient,
+    parent: &str,
+    path: &str,
+    content: &str,
+    dirs: &mut HashSet<String>,
+) -> Result<String> {
+    let parent = parent.trim_end_matches('/');
+    let ancestors = path
+        .match_indices('/')
+        .map(|(position, _)| format!("{parent}/{}", &path[..position]));
+    for dir in std::iter::once(parent.to_string()).chain(ancestors) {
+        if dirs.insert(dir.clone()) {
+            match client.mkdir(&dir, None).await {
+                Err(e) if e.api_kind() != Some(ApiErrorKind::Conflict) => return Err(e),
+                _ => {}
+            }
+        }
+    }
+    let uri = format!("{parent}/{path}");
+    match client.write(&uri, content, "create", false, None).await {
+        Err(e) if e.api_kind() == Some(ApiErrorKind::Conflict) => {
+            client.write(&uri, content, "replace", false, None).await?;
+        }
+        result => {
+            result?;
+        }
+    }
+    Ok(uri)
+}
+
+pub async fn import(
+    client: &HttpClient,
+    options: NotionImportOptions<'_>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let (_extracted, roots) = open_export(options.export)?;
+    let items = plan(&roots);
+    if !items.iter().any(|item| item.kind != Kind::Attachment) {
+        return Err(Error::Client(format!(
+            "{} has no Notion pages or databases",
+            options.export.display()
+        )));
+    }
+    let mut index = Index::new(&items, options.parent);
+
+    interrupt::enter_batch();
+    let mut rows = Vec::with_capacity(items.len());
+    let (mut imported, mut failed) = (0, 0);
+    let mut interrupted = false;
+    let mut relations: BTreeMap<usize, BTreeSet<usize>> = BTreeMap::new();
+    let mut dirs = HashSet::new();
+    // Attachments first: the server names them, and pages link to those URIs.
+    let order = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.kind == Kind::Attachment)
+        .chain(
+            items
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| item.kind != Kind::Attachment),
+        );
+    for (position, (item_index, item)) in order.enumerate() {
+        let content = match item.kind {
+            Kind::Attachment => None,
+            Kind::Page | Kind::Database => match std::fs::read_to_string(&item.source) {
+                Ok(text) if item.kind == Kind::Page => {
+                    Some(rewrite_links(&text, &item.original, &index, &items))
+                }
+                Ok(text) => Some(database_markdown(&text, item, &index, &items)),
+                Err(e) => {
+                    failed += 1;
+                    rows.push(row(item, "failed", None, Some(e.to_string())));
+                    continue;
+                }
+            },
+        };
+        if let Some((_, linked)) = &content
+            && !linked.is_empty()
+        {
+            relations.insert(item_index, linked.clone());
+        }
+        if options.dry_run {
+            rows.push(row(item, "planned", Some(&index.uris[item_index]), None));
+            continue;
+        }
+        eprintln!(
+            "{}",
+            theme::muted(format!(
+                "[{}/{}] {}",
+                position + 1,
+                items.len(),
+                item.original
+            ))
+        );
+        let uri = index.uris[item_index].clone();
+        let result = interrupt::cancellable(async {
+            match &content {
+                Some((markdown, _)) => {
+                    write_page(client, options.parent, &item.path, markdown, &mut dirs).await
+                }
+                None => upload_file(client, &item.source, parent_dir(&uri)).await,
+            }
+        })
+        .await;
+        match result {
+            Ok(uri) => {
+                imported += 1;
+                rows.push(row(item, "imported", Some(&uri), None));
+                index.uris[item_index] = uri;
+            }
+            Err(Error::Interrupted) => {
+                interrupted = true;
+                break;
+            }
+            Err(e) => {
+                failed += 1;
+                rows.push(row(item, "failed", None, Some(e.to_string())));
+            }
+        }
+    }
+
+    let mut links = 0;
+    if !options.dry_run && !interrupted {
+        for (from, targets) in &relations {
+            let targets: Vec<String> = targets
+                .iter()
+                .map(|target| index.uris[*target].clone())
+                .collect();
+            match interrupt::cancellable(client.link(&index.uris[*from], &targets, LINK_REASON))
+                .await
+            {
+                Ok(_) => links += targets.len(),
+                Err(Error::Interrupted) => {
+                    interrupted = true;
+                    break;
+                }
+                Err(e) => {
+                    failed += 1;
+                    rows.push(json!({
+                        "path": items[*from].original,
+                        "kind": "relations",
+                        "status": "failed",
+                        "uri": index.uris[*from],
+                        "error": e.to_string(),
+                    }));
+                }
+            }
+        }
+    } else if options.dry_run {
+        links = relations.values().map(BTreeSet::len).sum();
+    }
+
+    if matches!(format, OutputFormat::Table) {
+        output_success(Value::Array(rows), format, compact);
+        let summary = if options.dry_run {
+            format!(
+                "{} entries and {links} links would be imported into {}",
+                items.len(),
+                options.parent
+            )
+        } else {
+            format!(
+                "{imported} of {} entries imported into {}: {links} links, {failed} failed",
+                items.len(),
+                options.parent
+            )
+        };
+        println!("{}", theme::muted(summary));
+    } else {
+        output_success(
+            json!({
+                "export": options.export.display().to_string(),
+                "parent": options.parent,
+                "dry_run": options.dry_run,
+                "entries": items.len(),
+                "imported": imported,
+                "links": links,
+                "failed": failed,
+                "results": rows,
+            }),
+            format,
+            compact,
+        );
+    }
+    if interrupted {
+        return Err(Error::Interrupted);
+    }
+    if failed > 0 {
+        return Err(Error::AlreadyReported);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROADMAP: &str = "0123456789abcdef0123456789abcdef";
+    const TASKS: &str = "fedcba9876543210fedcba9876543210";
+    const SHIP: &str = "00000000000000000000000000000001";
+
+    fn export() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            (
+                format!("Roadmap {ROADMAP}.md"),
+                format!(
+                    "# Roadmap\n\nSee [Tasks](Roadmap%20{ROADMAP}/Tasks%20{TASKS}.csv), \
+                     [Ship](https://www.notion.so/acme/Ship-it-{SHIP}?pvs=4) and \
+                     ![chart](Roadmap%20{ROADMAP}/chart.png).\n"
+                ),
+            ),
+            (format!("Roadmap {ROADMAP}/chart.png"), "png".to_string()),
+            (
+                format!("Roadmap {ROADMAP}/Tasks {TASKS}.csv"),
+                "Name,Status\nShip it,Done\n".to_string(),
+            ),
+            (
+                format!("Roadmap {ROADMAP}/Tasks {TASKS}_all.csv"),
+                "\u{feff}Name,Status\nShip it,Done\n\"Plan, then build\",\"Doing\"\n".to_string(),
+            ),
+            (
+                format!("Roadmap {ROADMAP}/Tasks {TASKS}/Ship it {SHIP}.md"),
+                format!("# Ship it\n\nBack to [Roadmap](../../Roadmap%20{ROADMAP}.md)\n"),
+            ),
+        ];
+        for (path, content) in files {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn hierarchy_is_kept_without_notion_ids() {
+        let dir = export();
+        let items = plan(&[dir.path().to_path_buf()]);
+        let paths: Vec<(&str, Kind)> = items
+            .iter()
+            .map(|item| (item.path.as_str(), item.kind))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                ("Roadmap/Tasks/Ship it.md", Kind::Page),
+                ("Roadmap/Tasks.md", Kind::Database),
+                ("Roadmap/chart.png", Kind::Attachment),
+                ("Roadmap.md", Kind::Page),
+            ]
+        );
+
+        let mut names = Names::default();
+        assert_eq!(names.name("", &format!("Notes {ROADMAP}")), "Notes");
+        assert_eq!(names.name("", &format!("Notes {TASKS}")), "Notes-2");
+        assert_eq!(names.name("", &format!("Notes {ROADMAP}")), "Notes");
+    }
+
+    #[test]
+    fn links_and_databases_point_at_imported_uris() {
+        let dir = export();
+        let items = plan(&[dir.path().to_path_buf()]);
+        let index = Index::new(&items, "viking://resources/notion/");
+        let find = |path: &str| items.iter().position(|item| item.path == path).unwrap();
+        let roadmap = &items[find("Roadmap.md")];
+
+        let text = std::fs::read_to_string(&roadmap.source).unwrap();
+        let (markdown, linked) = rewrite_links(&text, &roadmap.original, &index, &items);
+        assert!(markdown.contains("[Tasks](viking://resources/notion/Roadmap/Tasks.md)"));
+        assert!(markdown.contains("[Ship](<viking://resources/notion/Roadmap/Tasks/Ship it.md>)"));
+        assert!(markdown.contains("![chart](viking://resources/notion/Roadmap/chart.png)"));
+        assert_eq!(
+            linked,
+            BTreeSet::from([find("Roadmap/Tasks.md"), find("Roadmap/Tasks/Ship it.md")])
+        );
+
+        let ship = &items[find("Roadmap/Tasks/Ship it.md")];
+        let text = std::fs::read_to_string(&ship.source).unwrap();
+        let (markdown, _) = rewrite_links(&text, &ship.original, &index, &items);
+        assert!(markdown.contains("[Roadmap](viking://resources/notion/Roadmap.md)"));
+
+        let tasks = &items[find("Roadmap/Tasks.md")];
+        let csv = std::fs::read_to_string(&tasks.source).unwrap();
+        let (markdown, linked) = database_markdown(&csv, tasks, &index, &items);
+        assert_eq!(
+            markdown,
+            "# Tasks\n\n| Name | Status |\n| --- | --- |\n\
+             | [Ship it](<viking://resources/notion/Roadmap/Tasks/Ship it.md>) | Done |\n\
+             | Plan, then build | Doing |\n"
+        );
+        assert_eq!(linked, BTreeSet::from([find("Roadmap/Tasks/Ship it.md")]));
+    }
+}
@@ -0,0 +1,312 @@
+//! `ov add s3://bucket/prefix` / `ov add tos://bucket/prefix`: add the objects
+//! under a prefix in S3-compatible storage as resources, without a local
+//! staging copy of the whole tree.
+//!
+//! Objects are streamed one at a time into a temporary file, uploaded under
+//! `--parent` at their key's path relative to the prefix, and deleted; up to
+//! `--concurrency` objects are in flight at once. The ETag of every object
+//! added is kept per source in `~/.openviking/objects.json` (or `--state`), so
+//! a rerun, or a run resumed after Ctrl-C, only adds objects that are new or
+//! have changed since.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use super::mcp_import::upload_file;
+use crate::client::HttpClient;
+use crate::error::{Error, Result};
+use crate::interrupt;
+use crate::json_store;
+use crate::object_store::{Location, Object, ObjectStore};
+use crate::output::{OutputFormat, output_success};
+use crate::theme;
+
+pub struct AddObjectsOptions<'a> {
+    pub source: &'a str,
+    pub parent: Option<&'a str>,
+    pub concurrency: usize,
+    pub region: Option<&'a str>,
+    pub endpoint: Option<&'a str>,
+    pub limit: Option<usize>,
+    pub dry_run: bool,
+    pub state: Option<&'a Path>,
+    pub timeout: u64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+struct SourceState {
+    parent: String,
+    #[serde(default)]
+    last_run: Option<String>,
+    /// Key to the ETag it had when added.
+    #[serde(default)]
+    objects: BTreeMap<String, String>,
+}
+
+/// Source URI to what has been added from it.
+type SourceStates = BTreeMap<String, SourceState>;
+
+fn state_path() -> Result<PathBuf> {
+    json_store::path("objects.json")
+}
+
+/// The objects to add: files only (not `dir/` placeholders), minus those
+/// already added with the same ETag.
+fn pending(objects: Vec<Object>, added: &BTreeMap<String, String>) -> Vec<Object> {
+    objects
+        .into_iter()
+        .filter(|object| !object.key.ends_with('/'))
+        .filter(|object| added.get(&object.key) != Some(&object.etag))
+        .collect()
+}
+
+/// Where `key` goes: the parent URI for its directories below the prefix's
+/// own directory, and its file name.
+fn destination<'a>(parent: &str, prefix: &str, key: &'a str) -> (String, &'a str) {
+    let base = &prefix[..prefix.rfind('/').map_or(0, |index| index + 1)];
+    let relative = key.strip_prefix(base).unwrap_or(key);
+    match relative.rsplit_once('/') {
+        Some((dirs, name)) => (format!("{}/{dirs}", parent.trim_end_matches('/')), name),
+        None => (parent.to_string(), relative),
+    }
+}
+
+fn row(object: &Object, status: &str, uri: Option<&str>, error: Option<String>) -> Value {
+    json!({
+        "key": object.key,
+        "size": object.size,
+        "status": status,
+        "uri": uri,
+        "error": error,
+    })
+}
+
+async fn add_object(
+    client: &HttpClient,
+    store: &ObjectStore,
+    dir: &Path,
+    index: usize,
+    object: &Object,
+    parent: &str,
+    prefix: &str,
+) -> Result<String> {
+    let (parent, name) = destination(parent, prefix, &object.key);
+    let dir = dir.join(index.to_string());
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(name);
+    let result = async {
+        store.download(&object.key, &path).await?;
+        upload_file(client, &path, &parent).await
+    }
+    .await;
+    let _ = std::fs::remove_dir_all(&dir);
+    result
+}
+
+pub async fn add_objects(
+    client: &HttpClient,
+    options: AddObjectsOptions<'_>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let location = Location::parse(options.source).unwrap_or_else(|| {
+        Err(Error::Client(format!(
+            "Invalid source '{}'; expected s3://bucket/prefix or tos://bucket/prefix",
+            options.source
+        )))
+    })?;
+    if options.concurrency == 0 {
+        return Err(Error::Client(
+            "--concurrency must be at least 1".to_string(),
+        ));
+    }
+    let parent = match options.parent {
+        Some(parent) => parent.to_string(),
+        None => format!("viking://resources/{}", location.bucket),
+    };
+    let state_path = match options.state {
+        Some(path) => path.to_path_buf(),
+        None => state_path()?,
+    };
+    let mut states = json_store::read::<SourceStates>(&state_path)?;
+    let store = ObjectStore::new(&location, options.region, options.endpoint, options.timeout)?;
+
+    interrupt::enter_batch();
+    let objects = interrupt::cancellable(store.list(&location.prefix)).await?;
+    let source = location.to_string();
+    let mut state = states.remove(&source).unwrap_or_default();
+    let total = objects.len();
+    let mut objects = pending(objects, &state.objects);
+    let changed = objects.len();
+    if let Some(limit) = options.limit {
+        objects.truncate(limit);
+    }
+
+    let mut rows = Vec::with_capacity(objects.len());
+    let (mut imported, mut failed) = (0, 0);
+    let mut interrupted = false;
+    if options.dry_run {
+        rows.extend(
+            objects
+                .iter()
+                .map(|object| row(object, "planned", None, None)),
+        );
+    } else {
+        state.parent = parent.clone();
+        let dir = tempfile::tempdir()?;
+        let count = objects.len();
+        let mut uploads = futures::stream::iter(objects.iter().enumerate())
+            .map(|(index, object)| {
+                let (store, dir, parent) = (&store, dir.path(), parent.as_str());
+                let prefix = location.prefix.as_str();
+                async move {
+                    eprintln!(
+                        "{}",
+                        theme::muted(format!("[{}/{count}] {}", index + 1, object.key))
+                    );
+                    let result =
+                        add_object(client, store, dir, index, object, parent, prefix).await;
+                    (object, result)
+                }
+            })
+            .buffer_unordered(options.concurrency);
+        while rows.len() < count {
+            let Ok(Some((object, result))) =
+                interrupt::cancellable(async { Ok(uploads.next().await) }).await
+            else {
+                interrupted = true;
+                break;
+            };
+            match result {
+                Ok(uri) => {
+                    imported += 1;
+                    state
+                        .objects
+                        .insert(object.key.clone(), object.etag.clone());
+                    state.last_run = Some(chrono::Utc::now().to_rfc3339());
+                    states.insert(source.clone(), state.clone());
+                    json_store::write(&state_path, &states)?;
+                    rows.push(row(object, "imported", Some(&uri), None));
+                }
+                Err(e) => {
+                    failed += 1;
+                    rows.push(row(object, "failed", None, Some(e.to_string())));
+                }
+            }
+        }
+        state.last_run = Some(chrono::Utc::now().to_rfc3339());
+        states.insert(source.clone(), state);
+        json_store::write(&state_path, &states)?;
+    }
+
+    let unchanged = total - changed;
+    let planned = rows.len();
+    if matches!(format, OutputFormat::Table) {
+        output_success(Value::Array(rows), format, compact);
+        let summary = if options.dry_run {
+            format!(
+                "{changed} new or changed of {total} objects; {planned} would be imported into {parent}"
+            )
+        } else {
+            format!(
+                "{imported} of {changed} new or changed objects imported into {parent}: {unchanged} unchanged, {failed} failed"
+            )
+        };
+        println!("{}", theme::muted(summary));
+    } else {
+        output_success(
+            json!({
+                "source": source,
+                "parent": parent,
+                "state": state_path.display().to_string(),
+                "dry_run": options.dry_run,
+                "objects": total,
+                "unchanged": unchanged,
+                "imported": imported,
+                "failed": failed,
+                "results": rows,
+            }),
+            format,
+            compact,
+        );
+    }
+    if interrupted {
+        return Err(Error::Interrupted);
+    }
+    if failed > 0 {
+        return Err(Error::AlreadyReported);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(key: &str, etag: &str) -> Object {
+        Object {
+            key: key.into(),
+            size: 1,
+            etag: etag.into(),
+        }
+    }
+
+    #[test]
+    fn only_new_or_changed_files_are_pending() {
+        let added = BTreeMap::from([
+            ("docs/a.md".to_string(), "1".to_string()),
+            ("docs/b.md".to_string(), "1".to_string()),
+        ]);
+        let fresh = pending(
+            vec![
+                object("docs/", "0"),
+                object("docs/a.md", "1"),
+                object("docs/b.md", "2"),
+                object("docs/c/d.md", "1"),
+            ],
+            &added,
+        );
+        assert_eq!(
+            fresh
+                .iter()
+                .map(|object| object.key.as_str())
+                .collect::<Vec<_>>(),
+            ["docs/b.md", "docs/c/d.md"]
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("objects.json");
+        assert!(json_store::read::<SourceStates>(&path).unwrap().is_empty());
+        let states = SourceStates::from([(
+            "s3://bucket/docs/".to_string(),
+            SourceState {
+                parent: "viking://resources/bucket".into(),
+                last_run: None,
+                objects: added,
+            },
+        )]);
+        json_store::write(&path, &states).unwrap();
+        assert_eq!(json_store::read::<SourceStates>(&path).unwrap(), states);
+    }
+
+    #[test]
+    fn keys_keep_their_directories_below_the_prefix() {
+        let parent = "viking://resources/bucket";
+        assert_eq!(
+            destination(parent, "docs/", "docs/guide/intro.md"),
+            ("viking://resources/bucket/guide".to_string(), "intro.md")
+        );
+        assert_eq!(
+            destination(parent, "docs/gu", "docs/guide/intro.md"),
+            ("viking://resources/bucket/guide".to_string(), "intro.md")
+        );
+        assert_eq!(
+            destination(parent, "", "readme.md"),
+            (parent.to_string(), "readme.md")
+        );
+    }
+}
@@ -1,6 +1,38 @@
-use crate::client::HttpClient;
-use crate::error::Result;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64_STANDARD};
+use ring::signature::{ED25519, Ed25519KeyPair, KeyPair, UnparsedPublicKey};
+use serde_json::{Map, Value, json};
+use sha2::{Digest, Sha256};
+use tempfile::NamedTempFile;
+use zip::CompressionMethod;
+use zip::write::SimpleFileOptions;
+
+use crate::client::{HttpClient, PackFile};
+use crate::error::{ApiErrorKind, Error, Result};
 use crate::output::{OutputFormat, output_success};
+use crate::pack_compression;
+use crate::pack_crypto::{self, PackDecryption};
+
+const FORMAT_VERSION: u64 = 3;
+const PACK_KIND: &str = "openviking.ovpack";
+const MANIFEST_LEAF: &str = "_ovpack/manifest.json";
+const INDEX_RECORDS_LEAF: &str = "_ovpack/index_records.jsonl";
+const DENSE_LEAF: &str = "_ovpack/dense.f32";
+const SIGNATURE_LEAF: &str = "_ovpack/signature.json";
+const SIGNATURE_ALGORITHM: &str = "ed25519";
+/// DER prefix of an Ed25519 SubjectPublicKeyInfo, as in `openssl pkey -pubout`.
+const ED25519_SPKI_PREFIX: [u8; 12] = [
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
+/// Directory abstracts and overviews travel as files; `inspect` shows them as
+/// their directory's abstract instead of listing them.
+const SEMANTIC_SIDECARS: [&str; 2] = [".abstract.md", ".overview.md"];
+/// Server-maintained files that export never writes and import refuses.
+const EXCLUDED_FILENAMES: [&str; 1] = [".relations.json"];
+const IMPORTABLE_SCOPES: [&str; 2] = ["resources", "user"];
 
 pub async fn export(
     client: &HttpClient,
@@ -10,15 +42,9 @@ pub async fn export(
     format: OutputFormat,
     compact: bool,
 ) -> Result<()> {
-    let file_path = client.export_ovpack(uri, to, include_vectors).await?;
-
-    // Output success message with the file path
-    let result = serde_json::json!({
-        "file": file_path,
-        "message": format!("Successfully exported to {}", file_path)
-    });
-
-    output_success(&result, format, compact);
+    let pack = client.export_ovpack(uri, to, include_vectors).await?;
+    let message = format!("Successfully exported to {}", pack.path);
+    output_success(pack_file_summary(&pack, message), format, compact);
     Ok(())
 }
 
@@ -26,20 +52,63 @@ pub async fn backup(
     client: &HttpClient,
     to: &str,
     include_vectors: bool,
+    progress: bool,
     format: OutputFormat,
     compact: bool,
 ) -> Result<()> {
-    let file_path = client.backup_ovpack(to, include_vectors).await?;
+    let pack = client.backup_ovpack(to, include_vectors, progress).await?;
+    let message = format!("Successfully backed up to {}", pack.path);
+    output_success(pack_file_summary(&pack, message), format, compact);
+    Ok(())
+}
 
-    let result = serde_json::json!({
-        "file": file_path,
-        "message": format!("Successfully backed up to {}", file_path)
-    });
+/// Where an exported pack went and how well it compressed; `ratio` is content
+/// size over file size.
+fn pack_file_summary(pack: &PackFile, message: String) -> Value {
+    let ratio = pack
+        .content_size
+        .filter(|_| pack.size > 0)
+        .map(|content_size| (content_size as f64 / pack.size as f64 * 100.0).round() / 100.0);
+    json!({
+        "file": pack.path,
+        "size": pack.size,
+        "content_size": pack.content_size,
+        "compression": pack.compression,
+        "ratio": ratio,
+        "message": message,
+    })
+}
 
-    output_success(&result, format, compact);
+/// Check an .ovpack's embedded signature against `trust` before it is uploaded.
+pub async fn verify(file_path: &str, trust: PackTrust) -> Result<()> {
+    let path = PathBuf::from(file_path);
+    let signer = tokio::task::spawn_blocking(move || verify_archive(&path, &trust))
+        .await
+        .map_err(|e| Error::Client(format!("Failed to verify {file_path}: {e}")))??;
+    if let Some(key_id) = signer {
+        tracing::info!(file = file_path, key_id, "ovpack signature verified");
+    }
     Ok(())
 }
 
+/// Turn an encrypted or zstd pack into a temporary plain deflate pack the
+/// server can read; `None` when `file_path` can be uploaded as it is.
+pub async fn prepare_upload(
+    file_path: &str,
+    keys: PackDecryption,
+) -> Result<Option<NamedTempFile>> {
+    let path = PathBuf::from(file_path);
+    tokio::task::spawn_blocking(move || {
+        let decrypted = pack_crypto::decrypt_to_temp(&path, &keys)?;
+        let plain = decrypted
+            .as_ref()
+            .map_or(path.as_path(), |temp| temp.path());
+        Ok(pack_compression::deflate_to_temp(plain)?.or(decrypted))
+    })
+    .await
+    .map_err(|e| Error::Client(format!("Failed to read {file_path}: {e}")))?
+}
+
 pub async fn import(
     client: &HttpClient,
     file_path: &str,
@@ -49,9 +118,11 @@ pub async fn import(
     format: OutputFormat,
     compact: bool,
 ) -> Result<()> {
+    tracing::info!(file = file_path, target, "importing ovpack");
     let result = client
         .import_ovpack(file_path, target, on_conflict, vector_mode)
         .await?;
+    tracing::info!(file = file_path, target, "ovpack imported");
     output_success(&result, format, compact);
     Ok(())
 }
@@ -61,12 +132,1344 @@ pub async fn restore(
     file_path: &str,
     on_conflict: Option<&str>,
     vector_mode: Option<&str>,
+    progress: bool,
     format: OutputFormat,
     compact: bool,
 ) -> Result<()> {
     let result = client
-        .restore_ovpack(file_path, on_conflict, vector_mode)
+        .restore_ovpack(file_path, on_conflict, vector_mode, progress)
         .await?;
     output_success(&result, format, compact);
     Ok(())
 }
+
+/// Show what restoring a backup would do to each scope without uploading it.
+/// `pack_path` is the plain copy of `file_path` that `prepare_upload` made, if
+/// it made one.
+///
+/// The server restores all scopes or none: with `fail` any existing scope
+/// aborts the restore, and with `skip` it leaves every scope alone.
+pub async fn restore_plan(
+    client: &HttpClient,
+    file_path: &str,
+    pack_path: &Path,
+    on_conflict: Option<&str>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let scopes = backup_scopes(pack_path)?;
+    let mut existing = Vec::new();
+    for scope in &scopes {
+        let uri = format!("viking://{}", scope.name);
+        let exists = match client.stat(&uri).await {
+            Ok(_) => true,
+            Err(e) if e.api_kind() == Some(ApiErrorKind::NotFound) => false,
+            Err(e) => return Err(e),
+        };
+        existing.push(exists);
+    }
+
+    let policy = on_conflict.unwrap_or("fail");
+    let conflict = existing.iter().any(|exists| *exists);
+    let outcome = match policy {
+        "overwrite" => "restore",
+        _ if !conflict => "restore",
+        "skip" => "skip",
+        _ => "fail",
+    };
+    let rows: Vec<Value> = scopes
+        .iter()
+        .zip(&existing)
+        .map(|(scope, exists)| {
+            let action = match (outcome, exists) {
+                ("skip", _) => "skip",
+                ("fail", true) => "conflict",
+                (_, true) => "replace",
+                (_, false) => "create",
+            };
+            json!({
+                "scope": format!("viking://{}", scope.name),
+                "files": scope.files,
+                "size": scope.size,
+                "exists": exists,
+                "action": action,
+            })
+        })
+        .collect();
+    let message = match outcome {
+        "restore" => "Dry run: restore would proceed; nothing was changed",
+        "skip" => "Dry run: existing scopes would be kept and nothing restored",
+        _ => "Dry run: restore would fail on existing scopes; pass --overwrite to replace them",
+    };
+    let report = json!({
+        "file": file_path,
+        "on_conflict": policy,
+        "outcome": outcome,
+        "message": message,
+        "scopes": rows,
+    });
+    output_with_rows(report, "scopes", format, compact);
+    Ok(())
+}
+
+/// List an .ovpack archive's manifest and entries without contacting a server.
+pub fn inspect(file_path: &str, format: OutputFormat, compact: bool) -> Result<()> {
+    let report = inspect_archive(Path::new(file_path))?;
+    output_with_rows(report, "entries", format, compact);
+    Ok(())
+}
+
+/// Compare two packs, or a pack and a live subtree, by manifest content hash.
+///
+/// A `viking://` side is exported to a temporary pack first, so it costs a
+/// full export of that subtree.
+pub async fn diff(
+    client: &HttpClient,
+    old: &str,
+    new: &str,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let staging = tempfile::tempdir()?;
+    let mut paths = Vec::new();
+    for (side, label) in [(old, "old"), (new, "new")] {
+        if !side.starts_with("viking://") {
+            paths.push(PathBuf::from(side));
+            continue;
+        }
+        let dir = staging.path().join(label);
+        std::fs::create_dir(&dir)?;
+        let pack = client
+            .export_ovpack(side, &dir.to_string_lossy(), false)
+            .await?;
+        paths.push(PathBuf::from(pack.path));
+    }
+    let mut report = json!({"old": old, "new": new});
+    if let (Some(report), Value::Object(diff)) =
+        (report.as_object_mut(), diff_archives(&paths[0], &paths[1])?)
+    {
+        report.extend(diff);
+    }
+    output_with_rows(report, "changes", format, compact);
+    Ok(())
+}
+
+/// Copy entries below `prefix` that are new or changed on `source` over to
+/// `target`, comparing both subtrees by manifest content hash.
+///
+/// Both sides are exported to temporary packs. If `prefix` is missing on the
+/// target, the whole source pack is imported. Otherwise the changed files are
+/// rebuilt into a partial pack, imported into a scratch directory next to
+/// `prefix`, and moved into place. Entries only the target holds are kept.
+/// `profiles` names the source and target for the report.
+pub async fn sync(
+    source: &HttpClient,
+    target: &HttpClient,
+    profiles: (&str, &str),
+    prefix: &str,
+    dry_run: bool,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let (from, to) = profiles;
+    let prefix = prefix.trim_end_matches('/');
+    let (_, name) = pack_root(prefix)?;
+    let staging = tempfile::tempdir()?;
+    let mut packs = Vec::new();
+    for (client, label) in [(source, "source"), (target, "target")] {
+        let dir = staging.path().join(label);
+        std::fs::create_dir(&dir)?;
+        match client
+            .export_ovpack(prefix, &dir.to_string_lossy(), false)
+            .await
+        {
+            Ok(pack) => packs.push(Some(PathBuf::from(pack.path))),
+            Err(e) if label == "target" && e.api_kind() == Some(ApiErrorKind::NotFound) => {
+                packs.push(None)
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    let source_pack = packs[0].take().unwrap_or_default();
+    let target_pack = packs[1].take();
+    let (_, source_entries) = diff_entries(&source_pack)?;
+    let target_entries = match &target_pack {
+        Some(path) => diff_entries(path)?.1,
+        None => HashMap::new(),
+    };
+    let plan = sync_plan(&source_entries, &target_entries);
+    let count = |kind: &str| {
+        plan.changes
+            .iter()
+            .filter(|change| change.change == kind)
+            .count()
+    };
+    let copied = plan.changes.len() - count("conflict");
+
+    let message = if dry_run {
+        format!("Dry run: nothing was changed on {to}")
+    } else if copied == 0 && count("conflict") > 0 {
+        format!("Nothing copied; the conflicting entries on {to} were left alone")
+    } else if copied == 0 {
+        format!("{prefix} is already in sync on {to}")
+    } else if target_pack.is_none() {
+        let (parent, _) = prefix.rsplit_once('/').unwrap_or((prefix, ""));
+        target
+            .import_ovpack(&source_pack.to_string_lossy(), parent, Some("fail"), None)
+            .await?;
+        format!("Copied {prefix} to {to}")
+    } else {
+        let partial_dir = staging.path().join("partial");
+        std::fs::create_dir(&partial_dir)?;
+        let copies: Vec<&SyncChange> = plan
+            .changes
+            .iter()
+            .filter(|change| change.change != "conflict")
+            .collect();
+        extract_entries(&source_pack, &copies, &partial_dir)?;
+        let partial = staging.path().join(format!("{name}.ovpack"));
+        create_archive(&partial_dir, &partial, Some(prefix))?;
+
+        let (parent, _) = prefix.rsplit_once('/').unwrap_or((prefix, ""));
+        let scratch = format!(
+            "{parent}/ov-sync-{}",
+            &uuid::Uuid::new_v4().simple().to_string()[..8]
+        );
+        target.mkdir(&scratch, None).await?;
+        let moved = async {
+            target
+                .import_ovpack(&partial.to_string_lossy(), &scratch, Some("fail"), None)
+                .await?;
+            for path in plan.moves() {
+                target
+                    .mv(&format!("{scratch}/{name}/{path}"), &join_uri(prefix, path))
+                    .await?;
+            }
+            Ok::<_, Error>(())
+        }
+        .await;
+        target.rm(&scratch, true, false, None).await?;
+        moved?;
+        format!("Copied new and changed entries to {to}")
+    };
+
+    let rows: Vec<Value> = plan
+        .changes
+        .iter()
+        .map(|change| {
+            json!({
+                "change": change.change,
+                "uri": join_uri(prefix, &change.path),
+                "kind": change.kind,
+                "size": change.size,
+            })
+        })
+        .collect();
+    let report = json!({
+        "from": from,
+        "to": to,
+        "prefix": prefix,
+        "added": count("added"),
+        "modified": count("modified"),
+        "unchanged": plan.unchanged,
+        "conflicts": count("conflict"),
+        "target_only": plan.target_only,
+        "message": message,
+        "changes": rows,
+    });
+    output_with_rows(report, "changes", format, compact);
+    Ok(())
+}
+
+/// Print `report`; tables show its `rows_key` list as a second table.
+fn output_with_rows(report: Value, rows_key: &str, format: OutputFormat, compact: bool) {
+    if !matches!(format, OutputFormat::Table) {
+        output_success(&report, format, compact);
+        return;
+    }
+    let mut summary = report;
+    let rows = summary
+        .as_object_mut()
+        .and_then(|summary| summary.remove(rows_key))
+        .unwrap_or_default();
+    output_success(&summary, format, compact);
+    if rows.as_array().is_some_and(|rows| !rows.is_empty()) {
+        output_success(&rows, format, compact);
+    }
+}
+
+/// Build an .ovpack from a local directory without contacting a server.
+///
+/// The pack carries no index records, so the server computes abstracts and
+/// vectors on import just as it does for a freshly added resource.
+pub fn create(
+    dir: &str,
+    to: &str,
+    root_uri: Option<&str>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let report = create_archive(Path::new(dir), Path::new(to), root_uri)?;
+    output_success(&report, format, compact);
+    Ok(())
+}
+
+fn create_archive(dir: &Path, to: &Path, root_uri: Option<&str>) -> Result<Value> {
+    let source = dir
+        .canonicalize()
+        .map_err(|e| Error::InvalidPath(format!("Cannot read {}: {e}", dir.display())))?;
+    if !source.is_dir() {
+        return Err(Error::InvalidPath(format!(
+            "Not a directory: {}",
+            dir.display()
+        )));
+    }
+    let root_uri = match root_uri {
+        Some(uri) => uri.trim_end_matches('/').to_string(),
+        None => {
+            let dir_name = source
+                .file_name()
+                .and_then(|name| name.to_str())
+                .ok_or_else(|| {
+                    Error::InvalidPath(format!("Cannot name a pack after {}", dir.display()))
+                })?;
+            format!("viking://resources/{dir_name}")
+        }
+    };
+    let (scope, name) = pack_root(&root_uri)?;
+
+    let to = if to.is_dir() {
+        to.join(format!("{name}.ovpack"))
+    } else if to.extension().is_some_and(|ext| ext == "ovpack") {
+        to.to_path_buf()
+    } else {
+        PathBuf::from(format!("{}.ovpack", to.display()))
+    };
+    let skip = to.canonicalize().ok();
+    let mut members = Vec::new();
+    for entry in walkdir::WalkDir::new(&source)
+        .min_depth(1)
+        .sort_by_file_name()
+    {
+        let entry = entry.map_err(|e| Error::Io(e.into()))?;
+        let file_type = entry.file_type();
+        if !(file_type.is_dir() || file_type.is_file()) || skip.as_deref() == Some(entry.path()) {
+            continue;
+        }
+        let rel_path = pack_rel_path(entry.path().strip_prefix(&source).unwrap_or(entry.path()))?;
+        let leaf = rel_path.rsplit('/').next().unwrap_or(&rel_path);
+        if EXCLUDED_FILENAMES.contains(&leaf) {
+            continue;
+        }
+        members.push((rel_path, entry.into_path(), file_type.is_dir()));
+    }
+
+    let parent = to
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    std::fs::create_dir_all(parent)?;
+    let temp = tempfile::Builder::new()
+        .prefix(".ovpack-")
+        .tempfile_in(parent)?;
+    let mut zip = zip::ZipWriter::new(temp.as_file());
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.add_directory(format!("{name}/"), options)?;
+    zip.add_directory(format!("{name}/files/"), options)?;
+
+    let mut entries = vec![json!({"path": "", "kind": "directory"})];
+    let mut content = Vec::new();
+    let (mut files, mut directories, mut total_size) = (0u64, 1u64, 0u64);
+    let mut buffer = vec![0u8; 1024 * 1024];
+    for (rel_path, path, is_dir) in members {
+        if is_dir {
+            zip.add_directory(format!("{name}/files/{rel_path}/"), options)?;
+            entries.push(json!({"path": rel_path, "kind": "directory", "size": 0}));
+            directories += 1;
+            continue;
+        }
+        let mut file = std::fs::File::open(&path)?;
+        let size = file.metadata()?.len();
+        zip.start_file(
+            format!("{name}/files/{rel_path}"),
+            options.large_file(size >= u32::MAX as u64),
+        )?;
+        let mut hasher = Sha256::new();
+        loop {
+            let read = file.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+            zip.write_all(&buffer[..read])?;
+        }
+        let sha256 = hex::encode(hasher.finalize());
+        entries.push(json!({"path": rel_path, "kind": "file", "size": size, "sha256": sha256}));
+        content.push(json!({"path": rel_path, "sha256": sha256, "size": size}));
+        files += 1;
+        total_size += size;
+    }
+
+    // Must match the server's manifest_content_sha256: file entries sorted by
+    // path, serialized with sorted keys and no whitespace.
+    content.sort_by(|a, b| a["path"].as_str().cmp(&b["path"].as_str()));
+    let content_sha256 = hex::encode(Sha256::digest(serde_json::to_vec(&content)?));
+    let manifest = json!({
+        "content_sha256": content_sha256,
+        "entries": entries,
+        "format_version": FORMAT_VERSION,
+        "index": {
+            "records": {
+                "count": 0,
+                "path": INDEX_RECORDS_LEAF,
+                "sha256": hex::encode(Sha256::digest(b"")),
+            },
+        },
+        "kind": PACK_KIND,
+        "root": {"name": name, "scope": scope, "uri": root_uri},
+    });
+    zip.add_directory(format!("{name}/_ovpack/"), options)?;
+    zip.start_file(format!("{name}/{INDEX_RECORDS_LEAF}"), options)?;
+    zip.start_file(format!("{name}/{MANIFEST_LEAF}"), options)?;
+    zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+    zip.finish()?;
+    temp.persist(&to).map_err(|e| Error::Io(e.error))?;
+
+    Ok(json!({
+        "file": to.display().to_string(),
+        "root_uri": root_uri,
+        "files": files,
+        "directories": directories,
+        "total_size": total_size,
+    }))
+}
+
+/// Signature policy for `ov import`; a signature that is present is always checked.
+#[derive(Debug, Clone, Default)]
+pub struct PackTrust {
+    pub require_signed: bool,
+    /// Raw Ed25519 public keys; when non-empty the signer must be one of them.
+    pub trusted_keys: Vec<Vec<u8>>,
+}
+
+impl PackTrust {
+    pub fn new(require_signed: bool, trusted_keys: &[String]) -> Result<Self> {
+        let trusted_keys = trusted_keys
+            .iter()
+            .map(|key| load_public_key(key))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            require_signed: require_signed || !trusted_keys.is_empty(),
+            trusted_keys,
+        })
+    }
+}
+
+/// Sign an .ovpack in place with an Ed25519 PKCS#8 private key.
+pub fn sign(file_path: &str, key_path: &str, format: OutputFormat, compact: bool) -> Result<()> {
+    let report = sign_archive(Path::new(file_path), Path::new(key_path))?;
+    output_success(&report, format, compact);
+    Ok(())
+}
+
+fn sign_archive(path: &Path, key_path: &Path) -> Result<Value> {
+    let key_pair = load_key_pair(key_path)?;
+    let mut archive = open_archive(path)?;
+    let (base, manifest) = read_manifest(&mut archive, path)?;
+    let public_key = key_pair.public_key().as_ref().to_vec();
+    let signature = json!({
+        "algorithm": SIGNATURE_ALGORITHM,
+        "key_id": key_id(&public_key),
+        "public_key": BASE64_STANDARD.encode(&public_key),
+        "signature": BASE64_STANDARD.encode(key_pair.sign(manifest.as_bytes())),
+    });
+
+    // Rewrite the archive next to the original so a failure leaves it intact.
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let temp = tempfile::Builder::new()
+        .prefix(".ovpack-")
+        .tempfile_in(parent)?;
+    let mut zip = zip::ZipWriter::new(temp.as_file());
+    let signature_path = format!("{base}{SIGNATURE_LEAF}");
+    for index in 0..archive.len() {
+        let member = archive.by_index_raw(index)?;
+        if member.name() != signature_path {
+            zip.raw_copy_file(member)?;
+        }
+    }
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.start_file(signature_path, options)?;
+    zip.write_all(&serde_json::to_vec_pretty(&signature)?)?;
+    zip.finish()?;
+    temp.persist(path).map_err(|e| Error::Io(e.error))?;
+
+    Ok(json!({
+        "file": path.display().to_string(),
+        "key_id": signature["key_id"],
+        "public_key": signature["public_key"],
+    }))
+}
+
+/// Check an archive's signature and the checksums it covers.
+///
+/// The signature covers the manifest bytes, and the manifest carries the
+/// checksum of every file and index member, so each of those is re-hashed
+/// too. Returns the signer's key id, or `None` for an accepted unsigned pack.
+fn verify_archive(path: &Path, trust: &PackTrust) -> Result<Option<String>> {
+    let mut archive = open_archive(path)?;
+    let (base, raw_manifest) = read_manifest(&mut archive, path)?;
+    let Ok(raw_signature) = read_member(&mut archive, &format!("{base}{SIGNATURE_LEAF}")) else {
+        if trust.require_signed {
+            return Err(Error::Client(format!(
+                "Refusing unsigned pack {}; sign it with `ov pack sign` or drop --require-signed",
+                path.display()
+            )));
+        }
+        return Ok(None);
+    };
+
+    let invalid =
+        |reason: &str| Error::Client(format!("Invalid signature on {}: {reason}", path.display()));
+    let signature: Value =
+        serde_json::from_str(&raw_signature).map_err(|_| invalid("unreadable signature.json"))?;
+    if signature["algorithm"] != SIGNATURE_ALGORITHM {
+        return Err(invalid("unsupported algorithm"));
+    }
+    let decode = |field: &str| {
+        signature[field]
+            .as_str()
+            .and_then(|value| BASE64_STANDARD.decode(value).ok())
+            .ok_or_else(|| invalid(&format!("missing {field}")))
+    };
+    let public_key = decode("public_key")?;
+    UnparsedPublicKey::new(&ED25519, &public_key)
+        .verify(raw_manifest.as_bytes(), &decode("signature")?)
+        .map_err(|_| invalid("the manifest does not match its signature"))?;
+    let key_id = key_id(&public_key);
+    if !trust.trusted_keys.is_empty() && !trust.trusted_keys.contains(&public_key) {
+        return Err(Error::Client(format!(
+            "Untrusted signer {key_id} on {}",
+            path.display()
+        )));
+    }
+
+    let manifest: Value = serde_json::from_str(&raw_manifest)
+        .map_err(|e| Error::Parse(format!("Invalid ovpack manifest: {e}")))?;
+    let mut expected = vec![(
+        format!("{base}{INDEX_RECORDS_LEAF}"),
+        &manifest["index"]["records"]["sha256"],
+    )];
+    if manifest["index"].get("dense").is_some() {
+        expected.push((
+            format!("{base}{DENSE_LEAF}"),
+            &manifest["index"]["dense"]["sha256"],
+        ));
+    }
+    for entry in manifest["entries"].as_array().into_iter().flatten() {
+        if entry["kind"] == "file" {
+            let rel_path = entry["path"].as_str().unwrap_or_default();
+            expected.push((format!("{base}files/{rel_path}"), &entry["sha256"]));
+        }
+    }
+    for (member, sha256) in expected {
+        let actual = {
+            let mut hasher = Sha256::new();
+            std::io::copy(&mut archive.by_name(&member)?, &mut hasher)?;
+            hex::encode(hasher.finalize())
+        };
+        if sha256.as_str() != Some(actual.as_str()) {
+            return Err(invalid(&format!(
+                "{} does not match the signed manifest",
+                member.trim_start_matches(&base)
+            )));
+        }
+    }
+    Ok(Some(key_id))
+}
+
+fn key_id(public_key: &[u8]) -> String {
+    hex::encode(&Sha256::digest(public_key)[..8])
+}
+
+/// Read a PEM or DER PKCS#8 Ed25519 private key, e.g. from
+/// `openssl genpkey -algorithm ed25519`.
+fn load_key_pair(key_path: &Path) -> Result<Ed25519KeyPair> {
+    let bytes = std::fs::read(key_path)
+        .map_err(|e| Error::Client(format!("Cannot read {}: {e}", key_path.display())))?;
+    let der = pem_body(&bytes).unwrap_or(bytes);
+    Ed25519KeyPair::from_pkcs8_maybe_unchecked(&der).map_err(|_| {
+        Error::Client(format!(
+            "Not an Ed25519 PKCS#8 private key: {}",
+            key_path.display()
+        ))
+    })
+}
+
+/// Accept a public key file (PEM or DER SubjectPublicKeyInfo) or the base64
+/// `public_key` that `ov pack sign` prints.
+fn load_public_key(key: &str) -> Result<Vec<u8>> {
+    let bytes = match std::fs::read(key) {
+        Ok(bytes) => pem_body(&bytes).unwrap_or(bytes),
+        Err(_) => BASE64_STANDARD.decode(key.trim()).unwrap_or_default(),
+    };
+    let raw = match bytes.strip_prefix(ED25519_SPKI_PREFIX.as_slice()) {
+        Some(raw) => raw.to_vec(),
+        None => bytes,
+    };
+    if raw.len() != 32 {
+        return Err(Error::Client(format!(
+            "Not an Ed25519 public key file or base64 public key: {key}"
+        )));
+    }
+    Ok(raw)
+}
+
+fn pem_body(bytes: &[u8]) -> Option<Vec<u8>> {
+    let text = std::str::from_utf8(bytes).ok()?;
+    if !text.trim_start().starts_with("-----BEGIN") {
+        return None;
+    }
+    let body: String = text
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .map(str::trim)
+        .collect();
+    BASE64_STANDARD.decode(body).ok()
+}
+
+/// Split a pack root URI into its scope and the name import will give it.
+fn pack_root(root_uri: &str) -> Result<(&str, &str)> {
+    let path = root_uri
+        .strip_prefix("viking://")
+        .ok_or_else(|| Error::Client(format!("Root URI must start with viking://: {root_uri}")))?;
+    let (scope, rest) = path.split_once('/').unwrap_or((path, ""));
+    if !IMPORTABLE_SCOPES.contains(&scope) {
+        return Err(Error::Client(format!(
+            "Packs can only be created for viking://resources or viking://user, not {root_uri}"
+        )));
+    }
+    let name = rest.rsplit('/').next().unwrap_or_default();
+    if name.is_empty() || matches!(name, "." | "..") || name.contains('\\') {
+        return Err(Error::Client(format!(
+            "Root URI needs a name below its scope: {root_uri}"
+        )));
+    }
+    Ok((scope, name))
+}
+
+/// The `/`-separated pack path the server accepts for a file below the source.
+fn pack_rel_path(path: &Path) -> Result<String> {
+    let mut parts = Vec::new();
+    for part in path.components() {
+        let part = part
+            .as_os_str()
+            .to_str()
+            .filter(|part| !part.contains('\\'))
+            .ok_or_else(|| {
+                Error::InvalidPath(format!("Cannot store {} in an .ovpack", path.display()))
+            })?;
+        parts.push(part);
+    }
+    Ok(parts.join("/"))
+}
+
+fn inspect_archive(path: &Path) -> Result<Value> {
+    let mut archive = open_archive(path)?;
+    let (base, raw_manifest) = read_manifest(&mut archive, path)?;
+    let manifest: Value = serde_json::from_str(&raw_manifest)
+        .map_err(|e| Error::Parse(format!("Invalid ovpack manifest: {e}")))?;
+
+    let mut abstracts = HashMap::new();
+    if let Ok(records) = read_member(&mut archive, &format!("{base}{INDEX_RECORDS_LEAF}")) {
+        for record in records
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        {
+            if record["level"].as_i64() == Some(0)
+                && let (Some(path), Some(text)) = (record["path"].as_str(), record["text"].as_str())
+            {
+                abstracts.insert(path.to_string(), text.trim().to_string());
+            }
+        }
+    }
+
+    let root_uri = manifest["root"]["uri"].as_str().unwrap_or("viking://");
+    let mut entries = Vec::new();
+    let (mut files, mut directories, mut total_size) = (0u64, 0u64, 0u64);
+    for entry in manifest["entries"].as_array().into_iter().flatten() {
+        let rel_path = entry["path"].as_str().unwrap_or_default();
+        let kind = entry["kind"].as_str().unwrap_or("file");
+        let size = entry["size"].as_u64().unwrap_or(0);
+        if kind == "directory" {
+            directories += 1;
+        } else {
+            files += 1;
+            total_size += size;
+        }
+        let leaf = rel_path.rsplit('/').next().unwrap_or(rel_path);
+        if kind != "directory" && SEMANTIC_SIDECARS.contains(&leaf) {
+            continue;
+        }
+        let abstract_text = abstracts.remove(rel_path).or_else(|| {
+            let dir = if rel_path.is_empty() {
+                String::new()
+            } else {
+                format!("{rel_path}/")
+            };
+            (kind == "directory")
+                .then(|| read_member(&mut archive, &format!("{base}files/{dir}.abstract.md")).ok())
+                .flatten()
+                .map(|text| text.trim().to_string())
+        });
+        let mut row = Map::new();
+        row.insert("uri".into(), json!(join_uri(root_uri, rel_path)));
+        row.insert("kind".into(), json!(kind));
+        row.insert("size".into(), json!(size));
+        row.insert("abstract".into(), json!(abstract_text.unwrap_or_default()));
+        entries.push(Value::Object(row));
+    }
+
+    let mut report = json!({
+        "file": path.display().to_string(),
+        "name": manifest["root"]["name"],
+        "root_uri": root_uri,
+        "format_version": manifest["format_version"],
+        "files": files,
+        "directories": directories,
+        "total_size": total_size,
+        "index_records": manifest["index"]["records"]["count"].as_u64().unwrap_or(0),
+        "vectors": manifest["index"].get("dense").is_some(),
+    });
+    if let Some(package_type) = manifest["root"]["package_type"].as_str() {
+        report["package_type"] = json!(package_type);
+    }
+    if let Some(scopes) = manifest.get("scopes") {
+        report["scopes"] = scopes.clone();
+    }
+    if let Some(content_sha256) = manifest.get("content_sha256") {
+        report["content_sha256"] = content_sha256.clone();
+    }
+    if let Ok(signature) = read_member(&mut archive, &format!("{base}{SIGNATURE_LEAF}"))
+        && let Ok(signature) = serde_json::from_str::<Value>(&signature)
+    {
+        report["signed_by"] = signature["key_id"].clone();
+    }
+    report["entries"] = Value::Array(entries);
+    Ok(report)
+}
+
+/// Files and bytes a backup holds for one public scope.
+struct BackupScope {
+    name: String,
+    files: u64,
+    size: u64,
+}
+
+fn backup_scopes(path: &Path) -> Result<Vec<BackupScope>> {
+    let mut archive = open_archive(path)?;
+    let (_, raw_manifest) = read_manifest(&mut archive, path)?;
+    let manifest: Value = serde_json::from_str(&raw_manifest)
+        .map_err(|e| Error::Parse(format!("Invalid ovpack manifest: {e}")))?;
+    if manifest["root"]["package_type"].as_str() != Some("backup") {
+        return Err(Error::Client(format!(
+            "Not a backup package; use `ov import` for: {}",
+            path.display()
+        )));
+    }
+    let mut scopes: Vec<BackupScope> = manifest["scopes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(|name| BackupScope {
+            name: name.to_string(),
+            files: 0,
+            size: 0,
+        })
+        .collect();
+    for entry in manifest["entries"].as_array().into_iter().flatten() {
+        let rel_path = entry["path"].as_str().unwrap_or_default();
+        if entry["kind"].as_str() == Some("directory") {
+            continue;
+        }
+        let name = rel_path.split('/').next().unwrap_or_default();
+        if let Some(scope) = scopes.iter_mut().find(|scope| scope.name == name) {
+            scope.files += 1;
+            scope.size += entry["size"].as_u64().unwrap_or(0);
+        }
+    }
+    Ok(scopes)
+}
+
+/// One manifest entry as `diff` compares it.
+#[derive(PartialEq)]
+struct DiffEntry {
+    kind: String,
+    size: u64,
+    sha256: Option<String>,
+}
+
+fn diff_archives(old: &Path, new: &Path) -> Result<Value> {
+    let (old_root, old_entries) = diff_entries(old)?;
+    let (new_root, new_entries) = diff_entries(new)?;
+    let paths: std::collections::BTreeSet<&String> =
+        old_entries.keys().chain(new_entries.keys()).collect();
+
+    let mut changes = Vec::new();
+    let (mut added, mut removed, mut modified, mut unchanged) = (0u64, 0u64, 0u64, 0u64);
+    for path in paths {
+        let (before, after) = (old_entries.get(path), new_entries.get(path));
+        let (change, root) = match (before, after) {
+            (Some(before), Some(after)) if before == after => {
+                unchanged += 1;
+                continue;
+            }
+            (Some(_), Some(_)) => {
+                modified += 1;
+                ("modified", &new_root)
+            }
+            (None, Some(_)) => {
+                added += 1;
+                ("added", &new_root)
+            }
+            _ => {
+                removed += 1;
+                ("removed", &old_root)
+            }
+        };
+        let kind = after.or(before).map_or("file", |entry| entry.kind.as_str());
+        changes.push(json!({
+            "change": change,
+            "uri": join_uri(root, path),
+            "kind": kind,
+            "old_size": before.map(|entry| entry.size),
+            "new_size": after.map(|entry| entry.size),
+        }));
+    }
+    Ok(json!({
+        "added": added,
+        "removed": removed,
+        "modified": modified,
+        "unchanged": unchanged,
+        "changes": changes,
+    }))
+}
+
+/// Root URI and entries by relative path, without semantic sidecars. Packs
+/// whose manifest lacks file hashes are hashed from their members.
+fn diff_entries(path: &Path) -> Result<(String, HashMap<String, DiffEntry>)> {
+    let mut archive = open_archive(path)?;
+    let (base, raw_manifest) = read_manifest(&mut archive, path)?;
+    let manifest: Value = serde_json::from_str(&raw_manifest)
+        .map_err(|e| Error::Parse(format!("Invalid ovpack manifest: {e}")))?;
+    let mut entries = HashMap::new();
+    for entry in manifest["entries"].as_array().into_iter().flatten() {
+        let rel_path = entry["path"].as_str().unwrap_or_default();
+        let kind = entry["kind"].as_str().unwrap_or("file");
+        let leaf = rel_path.rsplit('/').next().unwrap_or(rel_path);
+        if rel_path.is_empty() || (kind != "directory" && SEMANTIC_SIDECARS.contains(&leaf)) {
+            continue;
+        }
+        let mut sha256 = entry["sha256"].as_str().map(str::to_string);
+        if sha256.is_none() && kind != "directory" {
+            let mut hasher = Sha256::new();
+            std::io::copy(
+                &mut archive.by_name(&format!("{base}files/{rel_path}"))?,
+                &mut hasher,
+            )?;
+            sha256 = Some(hex::encode(hasher.finalize()));
+        }
+        entries.insert(
+            rel_path.to_string(),
+            DiffEntry {
+                kind: kind.to_string(),
+                size: entry["size"].as_u64().unwrap_or(0),
+                sha256,
+            },
+        );
+    }
+    let root_uri = manifest["root"]["uri"].as_str().unwrap_or("viking://");
+    Ok((root_uri.to_string(), entries))
+}
+
+/// One entry `sync` copies, or skips as a `conflict` when it is a file on one
+/// side and a directory on the other (or lies below such a directory).
+struct SyncChange {
+    path: String,
+    change: &'static str,
+    kind: String,
+    size: u64,
+}
+
+struct SyncPlan {
+    changes: Vec<SyncChange>,
+    unchanged: u64,
+    target_only: u64,
+}
+
+impl SyncPlan {
+    /// Paths to move out of the scratch import: changed files, and added
+    /// entries whose parent already exists on the target.
+    fn moves(&self) -> Vec<&str> {
+        let added: std::collections::HashSet<&str> = self
+            .changes
+            .iter()
+            .filter(|change| change.change == "added")
+            .map(|change| change.path.as_str())
+            .collect();
+        self.changes
+            .iter()
+            .filter(|change| match change.change {
+                "modified" => true,
+                "added" => change
+                    .path
+                    .rsplit_once('/')
+                    .is_none_or(|(parent, _)| !added.contains(parent)),
+                _ => false,
+            })
+            .map(|change| change.path.as_str())
+            .collect()
+    }
+}
+
+fn sync_plan(source: &HashMap<String, DiffEntry>, target: &HashMap<String, DiffEntry>) -> SyncPlan {
+    let mut paths: Vec<&String> = source.keys().collect();
+    paths.sort();
+    let mut plan = SyncPlan {
+        changes: Vec::new(),
+        unchanged: 0,
+        target_only: target
+            .keys()
+            .filter(|path| !source.contains_key(*path))
+            .count() as u64,
+    };
+    let mut conflicts = std::collections::HashSet::new();
+    for path in paths {
+        let entry = &source[path];
+        // Paths sort before their children, so a conflicting directory is
+        // already recorded when its entries come up.
+        let below_conflict =
+            std::iter::successors(path.rsplit_once('/'), |(parent, _)| parent.rsplit_once('/'))
+                .any(|(parent, _)| conflicts.contains(parent));
+        let change = match target.get(path) {
+            _ if below_conflict => "conflict",
+            Some(existing) if existing == entry => {
+                plan.unchanged += 1;
+                continue;
+            }
+            Some(existing) if existing.kind != entry.kind => "conflict",
+            Some(_) => "modified",
+            None => "added",
+        };
+        if change == "conflict" {
+            conflicts.insert(path.as_str());
+        }
+        plan.changes.push(SyncChange {
+            path: path.clone(),
+            change,
+            kind: entry.kind.clone(),
+            size: entry.size,
+        });
+    }
+    plan
+}
+
+/// Copy the listed entries of `pack` into `dir` under their relative paths.
+fn extract_entries(pack: &Path, entries: &[&SyncChange], dir: &Path) -> Result<()> {
+    let mut archive = open_archive(pack)?;
+    let (base, _) = read_manifest(&mut archive, pack)?;
+    for entry in entries {
+        if entry
+            .path
+            .split('/')
+            .any(|part| matches!(part, "" | "." | ".."))
+        {
+            return Err(Error::Client(format!(
+                "Unsafe path in pack {}: {}",
+                pack.display(),
+                entry.path
+            )));
+        }
+        let path = dir.join(&entry.path);
+        if entry.kind == "directory" {
+            std::fs::create_dir_all(&path)?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut member = archive.by_name(&format!("{base}files/{}", entry.path))?;
+        std::io::copy(&mut member, &mut std::fs::File::create(&path)?)?;
+    }
+    Ok(())
+}
+
+fn open_archive(path: &Path) -> Result<zip::ZipArchive<std::fs::File>> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| Error::Client(format!("Cannot open {}: {e}", path.display())))?;
+    if pack_crypto::is_encrypted(path)? {
+        return Err(Error::Client(format!(
+            "Pack is age-encrypted; decrypt it with `age -d` first: {}",
+            path.display()
+        )));
+    }
+    Ok(zip::ZipArchive::new(file)?)
+}
+
+/// Find the pack's `<root>/` prefix and return it with the raw manifest text.
+fn read_manifest(
+    archive: &mut zip::ZipArchive<std::fs::File>,
+    path: &Path,
+) -> Result<(String, String)> {
+    let manifest_path = archive
+        .file_names()
+        .find(|name| {
+            name.strip_suffix(MANIFEST_LEAF)
+                .is_some_and(|base| base.matches('/').count() == 1 && base.ends_with('/'))
+        })
+        .map(str::to_string)
+        .ok_or_else(|| {
+            Error::Client(format!(
+                "Not an .ovpack archive (no {MANIFEST_LEAF}): {}",
+                path.display()
+            ))
+        })?;
+    let base = manifest_path.trim_end_matches(MANIFEST_LEAF).to_string();
+    let manifest = read_member(archive, &manifest_path)?;
+    Ok((base, manifest))
+}
+
+fn read_member(archive: &mut zip::ZipArchive<std::fs::File>, name: &str) -> Result<String> {
+    let mut text = String::new();
+    archive.by_name(name)?.read_to_string(&mut text)?;
+    Ok(text)
+}
+
+fn join_uri(root_uri: &str, rel_path: &str) -> String {
+    if rel_path.is_empty() {
+        root_uri.to_string()
+    } else if root_uri.ends_with('/') {
+        format!("{root_uri}{rel_path}")
+    } else {
+        format!("{root_uri}/{rel_path}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use zip::write::SimpleFileOptions;
+
+    use super::*;
+
+    #[test]
+    fn inspect_archive_lists_entries_with_abstracts() {
+        let pack = tempfile::Builder::new()
+            .suffix(".ovpack")
+            .tempfile()
+            .unwrap();
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(pack.path()).unwrap());
+        let manifest = json!({
+            "format_version": 2,
+            "root": {"name": "acme", "uri": "viking://resources/acme"},
+            "entries": [
+                {"path": "", "kind": "directory"},
+                {"path": ".abstract.md", "kind": "file", "size": 9},
+                {"path": "guide.md", "kind": "file", "size": 120},
+            ],
+            "index": {"records": {"count": 1}},
+        });
+        for (name, body) in [
+            ("acme/_ovpack/manifest.json", manifest.to_string()),
+            (
+                "acme/_ovpack/index_records.jsonl",
+                r#"{"path":"guide.md","level":0,"text":"How to deploy"}"#.to_string(),
+            ),
+            ("acme/files/.abstract.md", "Acme docs\n".to_string()),
+            ("acme/files/guide.md", "# Guide".to_string()),
+        ] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(body.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let report = inspect_archive(pack.path()).unwrap();
+        assert_eq!(report["name"], "acme");
+        assert_eq!(report["files"], 2);
+        assert_eq!(report["directories"], 1);
+        assert_eq!(report["total_size"], 129);
+        assert_eq!(report["vectors"], false);
+        assert_eq!(
+            report["entries"],
+            json!([
+                {"uri": "viking://resources/acme", "kind": "directory", "size": 0, "abstract": "Acme docs"},
+                {"uri": "viking://resources/acme/guide.md", "kind": "file", "size": 120, "abstract": "How to deploy"},
+            ])
+        );
+    }
+
+    #[test]
+    fn create_archive_writes_a_pack_the_server_can_verify() {
+        let source = tempfile::tempdir().unwrap();
+        std::fs::create_dir(source.path().join("docs")).unwrap();
+        std::fs::write(source.path().join("docs/guide.md"), "# Guide").unwrap();
+        std::fs::write(source.path().join(".relations.json"), "{}").unwrap();
+        let out = tempfile::tempdir().unwrap();
+
+        let report = create_archive(
+            source.path(),
+            &out.path().join("acme"),
+            Some("viking://resources/acme/"),
+        )
+        .unwrap();
+        assert_eq!(report["root_uri"], "viking://resources/acme");
+        assert_eq!(report["files"], 1);
+
+        let pack = out.path().join("acme.ovpack");
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&pack).unwrap()).unwrap();
+        let manifest: Value =
+            serde_json::from_str(&read_member(&mut archive, "acme/_ovpack/manifest.json").unwrap())
+                .unwrap();
+        let guide_sha = hex::encode(Sha256::digest(b"# Guide"));
+        let content = format!(r#"[{{"path":"docs/guide.md","sha256":"{guide_sha}","size":7}}]"#);
+        assert_eq!(
+            manifest["content_sha256"],
+            hex::encode(Sha256::digest(content.as_bytes()))
+        );
+        assert_eq!(manifest["root"]["name"], "acme");
+        assert_eq!(manifest["index"]["records"]["count"], 0);
+        assert_eq!(
+            read_member(&mut archive, "acme/files/docs/guide.md").unwrap(),
+            "# Guide"
+        );
+        assert!(archive.by_name("acme/files/.relations.json").is_err());
+
+        let inspected = inspect_archive(&pack).unwrap();
+        assert_eq!(inspected["directories"], 2);
+        assert_eq!(
+            inspected["entries"][2]["uri"],
+            "viking://resources/acme/docs/guide.md"
+        );
+    }
+
+    #[test]
+    fn signed_archive_verifies_only_for_trusted_keys_and_intact_content() {
+        let source = tempfile::tempdir().unwrap();
+        std::fs::write(source.path().join("notes.md"), "hello").unwrap();
+        let out = tempfile::tempdir().unwrap();
+        let pack = out.path().join("notes.ovpack");
+        create_archive(source.path(), &pack, Some("viking://resources/notes")).unwrap();
+
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let key_path = out.path().join("signing.der");
+        std::fs::write(&key_path, pkcs8.as_ref()).unwrap();
+        let public_key = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref())
+            .unwrap()
+            .public_key()
+            .as_ref()
+            .to_vec();
+
+        let require = PackTrust::new(true, &[]).unwrap();
+        assert!(verify_archive(&pack, &require).is_err());
+        assert_eq!(verify_archive(&pack, &PackTrust::default()).unwrap(), None);
+
+        let signed = sign_archive(&pack, &key_path).unwrap();
+        let trusted = PackTrust::new(false, &[BASE64_STANDARD.encode(&public_key)]).unwrap();
+        assert_eq!(
+            verify_archive(&pack, &trusted).unwrap().as_deref(),
+            signed["key_id"].as_str()
+        );
+        let stranger = PackTrust::new(false, &[BASE64_STANDARD.encode([7u8; 32])]).unwrap();
+        assert!(verify_archive(&pack, &stranger).is_err());
+
+        // Re-signing replaces the signature instead of adding a second one.
+        sign_archive(&pack, &key_path).unwrap();
+        let mut archive = open_archive(&pack).unwrap();
+        let signatures = archive
+            .file_names()
+            .filter(|name| name.ends_with(SIGNATURE_LEAF))
+            .count();
+        assert_eq!(signatures, 1);
+
+        let tampered = out.path().join("tampered.ovpack");
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&tampered).unwrap());
+        for index in 0..archive.len() {
+            let mut member = archive.by_index(index).unwrap();
+            let name = member.name().to_string();
+            let mut body = Vec::new();
+            member.read_to_end(&mut body).unwrap();
+            if name.ends_with("notes.md") {
+                body = b"jello".to_vec();
+            }
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(&body).unwrap();
+        }
+        zip.finish().unwrap();
+        let err = verify_archive(&tampered, &trusted).unwrap_err();
+        assert!(err.to_string().contains("files/notes.md"));
+    }
+
+    #[test]
+    fn pack_root_requires_an_importable_scope_and_a_name() {
+        assert_eq!(pack_root("viking://user/notes").unwrap(), ("user", "notes"));
+        assert!(pack_root("viking://agent/skills").is_err());
+        assert!(pack_root("viking://resources").is_err());
+        assert!(pack_root("resources/acme").is_err());
+    }
+
+    #[test]
+    fn diff_archives_reports_added_removed_and_modified_uris() {
+        let source = tempfile::tempdir().unwrap();
+        std::fs::create_dir(source.path().join("docs")).unwrap();
+        std::fs::write(source.path().join("docs/guide.md"), "# Guide").unwrap();
+        std::fs::write(source.path().join("docs/old.md"), "retired").unwrap();
+        std::fs::write(source.path().join("faq.md"), "same").unwrap();
+        let out = tempfile::tempdir().unwrap();
+        let old = out.path().join("old.ovpack");
+        create_archive(source.path(), &old, Some("viking://resources/kb")).unwrap();
+
+        std::fs::write(source.path().join("docs/guide.md"), "# Guide v2").unwrap();
+        std::fs::remove_file(source.path().join("docs/old.md")).unwrap();
+        std::fs::write(source.path().join("docs/new.md"), "fresh").unwrap();
+        let new = out.path().join("new.ovpack");
+        create_archive(source.path(), &new, Some("viking://resources/kb")).unwrap();
+
+        let report = diff_archives(&old, &new).unwrap();
+        assert_eq!(
+            (&report["added"], &report["removed"], &report["modified"]),
+            (&json!(1), &json!(1), &json!(1))
+        );
+        assert_eq!(report["unchanged"], 2);
+        assert_eq!(
+            report["changes"],
+            json!([
+                {"change": "modified", "uri": "viking://resources/kb/docs/guide.md", "kind": "file", "old_size": 7, "new_size": 10},
+                {"change": "added", "uri": "viking://resources/kb/docs/new.md", "kind": "file", "old_size": null, "new_size": 5},
+                {"change": "removed", "uri": "viking://resources/kb/docs/old.md", "kind": "file", "old_size": 7, "new_size": null},
+            ])
+        );
+    }
+
+    #[test]
+    fn sync_plan_copies_new_and_changed_entries_and_skips_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("target");
+        std::fs::create_dir_all(target.join("docs")).unwrap();
+        std::fs::write(target.join("docs/guide.md"), "# Guide").unwrap();
+        std::fs::write(target.join("faq.md"), "same").unwrap();
+        std::fs::write(target.join("notes"), "a file here").unwrap();
+        std::fs::write(target.join("prod-only.md"), "kept").unwrap();
+        let source = dir.path().join("source");
+        std::fs::create_dir_all(source.join("docs/more/deep")).unwrap();
+        std::fs::create_dir_all(source.join("notes")).unwrap();
+        std::fs::write(source.join("docs/guide.md"), "# Guide v2").unwrap();
+        std::fs::write(source.join("docs/more/deep/z.md"), "deep").unwrap();
+        std::fs::write(source.join("faq.md"), "same").unwrap();
+        std::fs::write(source.join("notes/x.md"), "x").unwrap();
+        std::fs::write(source.join("top.md"), "new").unwrap();
+        for side in ["source", "target"] {
+            create_archive(
+                &dir.path().join(side),
+                &dir.path().join(format!("{side}.ovpack")),
+                Some("viking://resources/team"),
+            )
+            .unwrap();
+        }
+
+        let (_, source_entries) = diff_entries(&dir.path().join("source.ovpack")).unwrap();
+        let (_, target_entries) = diff_entries(&dir.path().join("target.ovpack")).unwrap();
+        let plan = sync_plan(&source_entries, &target_entries);
+        let changes: Vec<(&str, &str)> = plan
+            .changes
+            .iter()
+            .map(|change| (change.change, change.path.as_str()))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                ("modified", "docs/guide.md"),
+                ("added", "docs/more"),
+                ("added", "docs/more/deep"),
+                ("added", "docs/more/deep/z.md"),
+                ("conflict", "notes"),
+                ("conflict", "notes/x.md"),
+                ("added", "top.md"),
+            ]
+        );
+        assert_eq!((plan.unchanged, plan.target_only), (2, 1));
+        assert_eq!(plan.moves(), ["docs/guide.md", "docs/more", "top.md"]);
+
+        let out = dir.path().join("partial");
+        let copies: Vec<&SyncChange> = plan
+            .changes
+            .iter()
+            .filter(|change| change.change != "conflict")
+            .collect();
+        extract_entries(&dir.path().join("source.ovpack"), &copies, &out).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(out.join("docs/more/deep/z.md")).unwrap(),
+            "deep"
+        );
+        assert!(!out.join("faq.md").exists() && !out.join("notes").exists());
+    }
+
+    #[test]
+    fn backup_scopes_count_files_per_scope() {
+        let pack = tempfile::Builder::new()
+            .suffix(".ovpack")
+            .tempfile()
+            .unwrap();
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(pack.path()).unwrap());
+        let manifest = json!({
+            "format_version": 3,
+            "root": {"name": "openviking-backup", "uri": "viking://", "package_type": "backup"},
+            "scopes": ["resources", "user"],
+            "entries": [
+                {"path": "", "kind": "directory"},
+                {"path": "resources", "kind": "directory"},
+                {"path": "resources/.relations.json", "kind": "file", "size": 13},
+                {"path": "resources/guide.md", "kind": "file", "size": 120},
+                {"path": "user", "kind": "directory"},
+                {"path": "user/memories/editor.md", "kind": "file", "size": 30},
+            ],
+        });
+        zip.start_file(
+            "openviking-backup/_ovpack/manifest.json",
+            SimpleFileOptions::default(),
+        )
+        .unwrap();
+        zip.write_all(manifest.to_string().as_bytes()).unwrap();
+        zip.finish().unwrap();
+
+        let scopes = backup_scopes(pack.path()).unwrap();
+        let counts: Vec<_> = scopes
+            .iter()
+            .map(|scope| (scope.name.as_str(), scope.files, scope.size))
+            .collect();
+        assert_eq!(counts, [("resources", 2, 133), ("user", 1, 30)]);
+
+        let source = tempfile::tempdir().unwrap();
+        std::fs::write(source.path().join("guide.md"), "# Guide").unwrap();
+        let export = source.path().join("kb.ovpack");
+        create_archive(source.path(), &export, Some("viking://resources/kb")).unwrap();
+        let err = backup_scopes(&export).err().unwrap();
+        assert!(err.to_string().contains("Not a backup package"));
+    }
+
+    #[test]
+    fn inspect_archive_rejects_plain_zip() {
+        let pack = tempfile::Builder::new().suffix(".zip").tempfile().unwrap();
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(pack.path()).unwrap());
+        zip.start_file("notes.txt", SimpleFileOptions::default())
+            .unwrap();
+        zip.finish().unwrap();
+
+        let err = inspect_archive(pack.path()).unwrap_err();
+        assert!(err.to_string().contains("Not an .ovpack archive"));
+    }
+}
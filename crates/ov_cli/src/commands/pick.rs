@@ -0,0 +1,343 @@
+//! `ov pick [prefix]`: choose a URI with a fuzzy finder.
+//!
+//! The tree under the prefix is listed with one recursive `ls`, served from
+//! the daemon's cache when one is running, and narrowed as the user types: a
+//! URI matches when it contains the query's characters in order, and matches
+//! at word starts and in runs rank first. The finder draws on stderr and the
+//! chosen URI is printed on stdout, so `ov read $(ov pick)` works.
+
+use std::io::{self, IsTerminal};
+
+use crossterm::ExecutableCommand;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{
+    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+};
+use ratatui::prelude::*;
+use ratatui::widgets::{List, ListItem, ListState, Paragraph};
+use serde_json::Value;
+
+use crate::client::HttpClient;
+use crate::error::{Error, Result};
+
+pub struct PickOptions<'a> {
+    pub prefix: &'a str,
+    pub query: Option<&'a str>,
+    pub node_limit: i32,
+}
+
+struct Candidate {
+    uri: String,
+    dir: bool,
+}
+
+/// A candidate that matches the query, with the positions of the matched
+/// characters.
+struct Match {
+    index: usize,
+    score: i64,
+    positions: Vec<usize>,
+}
+
+const MATCH: i64 = 16;
+const WORD_START: i64 = 8;
+const RUN: i64 = 4;
+
+fn is_separator(c: char) -> bool {
+    matches!(c, '/' | '-' | '_' | '.' | ' ' | ':')
+}
+
+/// Score `candidate` for `query`, matched case-insensitively as a
+/// subsequence, or `None` when it does not match. The match is the shortest
+/// window ending at the first place the whole query fits.
+fn fuzzy_match(query: &[char], candidate: &str) -> Option<(i64, Vec<usize>)> {
+    let chars: Vec<char> = candidate.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+    let mut next = 0;
+    let mut end = None;
+    for (at, &c) in chars.iter().enumerate() {
+        if c == query[next] {
+            next += 1;
+            if next == query.len() {
+                end = Some(at);
+                break;
+            }
+        }
+    }
+    // Walking back from the end finds the tightest window for that end.
+    let mut positions = Vec::with_capacity(query.len());
+    let mut remaining = query.len();
+    for at in (0..=end?).rev() {
+        if chars[at] == query[remaining - 1] {
+            positions.push(at);
+            remaining -= 1;
+            if remaining == 0 {
+                break;
+            }
+        }
+    }
+    positions.reverse();
+
+    let mut score = 0;
+    for (n, &at) in positions.iter().enumerate() {
+        score += MATCH;
+        if at == 0 || is_separator(chars[at - 1]) {
+            score += WORD_START;
+        }
+        if n > 0 {
+            let gap = at - positions[n - 1] - 1;
+            score += if gap == 0 { RUN } else { -(gap.min(16) as i64) };
+        }
+    }
+    // Matches in the last path segment name the thing itself.
+    let last_segment = chars
+        .iter()
+        .rposition(|&c| c == '/')
+        .map_or(0, |slash| slash + 1);
+    if positions[0] >= last_segment {
+        score += WORD_START;
+    }
+    Some((score, positions))
+}
+
+/// The candidates matching `query`, best first; ties go to shorter URIs.
+fn filter(candidates: &[Candidate], query: &str) -> Vec<Match> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    let mut matches: Vec<Match> = candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(index, candidate)| {
+            fuzzy_match(&query, &candidate.uri).map(|(score, positions)| Match {
+                index,
+                score,
+                positions,
+            })
+        })
+        .collect();
+    if !query.is_empty() {
+        matches.sort_by(|a, b| {
+            b.score.cmp(&a.score).then_with(|| {
+                candidates[a.index]
+                    .uri
+                    .len()
+                    .cmp(&candidates[b.index].uri.len())
+            })
+        });
+    }
+    matches
+}
+
+/// Restores the terminal when a full-screen view exits, however it exits.
+pub(crate) struct Screen;
+
+impl Screen {
+    pub(crate) fn enter() -> Result<Self> {
+        enable_raw_mode()?;
+        if let Err(e) = io::stderr().execute(EnterAlternateScreen) {
+            let _ = disable_raw_mode();
+            return Err(Error::Io(e));
+        }
+        Ok(Self)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = io::stderr().execute(LeaveAlternateScreen);
+        let _ = disable_raw_mode();
+    }
+}
+
+fn item<'a>(candidate: &'a Candidate, found: &Match) -> ListItem<'a> {
+    let highlight = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let mut spans: Vec<Span> = candidate
+        .uri
+        .chars()
+        .enumerate()
+        .map(|(at, c)| {
+            if found.positions.binary_search(&at).is_ok() {
+                Span::styled(c.to_string(), highlight)
+            } else {
+                Span::raw(c.to_string())
+            }
+        })
+        .collect();
+    if candidate.dir && !candidate.uri.ends_with('/') {
+        spans.push(Span::raw("/"));
+    }
+    ListItem::new(Line::from(spans))
+}
+
+/// Run the finder until a URI is chosen (`Some`) or it is cancelled (`None`).
+fn run_finder(candidates: &[Candidate], query: &str) -> Result<Option<String>> {
+    let _screen = Screen::enter()?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stderr()))?;
+    let mut query = query.to_string();
+    let mut matches = filter(candidates, &query);
+    let mut state = ListState::default().with_selected(Some(0));
+
+    loop {
+        terminal.draw(|frame| {
+            let [prompt, count, list] = Layout::vertical([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(0),
+            ])
+            .areas(frame.area());
+            frame.render_widget(Paragraph::new(format!("> {query}")), prompt);
+            frame.set_cursor_position((prompt.x + 2 + query.chars().count() as u16, prompt.y));
+            frame.render_widget(
+                Paragraph::new(format!("  {}/{}", matches.len(), candidates.len()))
+                    .style(Style::default().fg(Color::DarkGray)),
+                count,
+            );
+            let items: Vec<ListItem> = matches
+                .iter()
+                .map(|found| item(&candidates[found.index], found))
+                .collect();
+            let list_widget = List::new(items)
+                .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+                .highlight_symbol("> ");
+            frame.render_stateful_widget(list_widget, list, &mut state);
+        })?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        let selected = state.selected().unwrap_or(0);
+        let page = terminal.size()?.height.saturating_sub(2).max(1) as usize;
+        match key.code {
+            KeyCode::Enter => {
+                return Ok(matches
+                    .get(selected)
+                    .map(|found| candidates[found.index].uri.clone()));
+            }
+            KeyCode::Esc => return Ok(None),
+            KeyCode::Char('c') if control => return Ok(None),
+            KeyCode::Up => state.select(Some(selected.saturating_sub(1))),
+            KeyCode::Char('p' | 'k') if control => {
+                state.select(Some(selected.saturating_sub(1)));
+            }
+            KeyCode::Down => state.select(Some(selected + 1)),
+            KeyCode::Char('n' | 'j') if control => state.select(Some(selected + 1)),
+            KeyCode::PageUp => state.select(Some(selected.saturating_sub(page))),
+            KeyCode::PageDown => state.select(Some(selected + page)),
+            KeyCode::Char('u') if control => {
+                query.clear();
+                matches = filter(candidates, &query);
+                state.select(Some(0));
+            }
+            KeyCode::Backspace => {
+                query.pop();
+                matches = filter(candidates, &query);
+                state.select(Some(0));
+            }
+            KeyCode::Char(c) if !control => {
+                query.push(c);
+                matches = filter(candidates, &query);
+                state.select(Some(0));
+            }
+            _ => {}
+        }
+        if let Some(selected) = state.selected() {
+            state.select(Some(selected.min(matches.len().saturating_sub(1))));
+        }
+    }
+}
+
+pub async fn pick(client: &HttpClient, options: PickOptions<'_>) -> Result<()> {
+    if !io::stdin().is_terminal() || !io::stderr().is_terminal() {
+        return Err(Error::Client(
+            "ov pick needs a terminal to show the finder; use ov ls --recursive to list URIs in scripts"
+                .to_string(),
+        ));
+    }
+    let listing = client
+        .ls(
+            options.prefix,
+            false,
+            true,
+            "original",
+            0,
+            false,
+            options.node_limit,
+        )
+        .await?;
+    let candidates: Vec<Candidate> = listing
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            Some(Candidate {
+                uri: entry.get("uri")?.as_str()?.to_string(),
+                dir: entry.get("isDir").and_then(Value::as_bool).unwrap_or(false),
+            })
+        })
+        .collect();
+    if candidates.is_empty() {
+        return Err(Error::Client(format!("No URIs under {}", options.prefix)));
+    }
+
+    match run_finder(&candidates, options.query.unwrap_or_default())? {
+        Some(uri) => {
+            println!("{uri}");
+            Ok(())
+        }
+        None => Err(Error::Interrupted),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates(uris: &[&str]) -> Vec<Candidate> {
+        uris.iter()
+            .map(|uri| Candidate {
+                uri: uri.to_string(),
+                dir: false,
+            })
+            .collect()
+    }
+
+    fn ranked(candidates: &[Candidate], query: &str) -> Vec<String> {
+        filter(candidates, query)
+            .iter()
+            .map(|found| candidates[found.index].uri.clone())
+            .collect()
+    }
+
+    #[test]
+    fn word_starts_and_runs_rank_first() {
+        let uris = candidates(&[
+            "viking://resources/docs/api/authentication.md",
+            "viking://resources/archive/auth-notes.md",
+            "viking://resources/docs/guide.md",
+        ]);
+        assert_eq!(
+            ranked(&uris, "auth"),
+            vec![
+                "viking://resources/archive/auth-notes.md",
+                "viking://resources/docs/api/authentication.md",
+            ]
+        );
+        assert_eq!(
+            ranked(&uris, "DGUI"),
+            vec!["viking://resources/docs/guide.md"]
+        );
+        assert!(ranked(&uris, "zz").is_empty());
+        assert_eq!(ranked(&uris, "").len(), 3);
+
+        let query: Vec<char> = "gd".chars().collect();
+        let (_, positions) = fuzzy_match(&query, "guide.md").unwrap();
+        assert_eq!(positions, vec![0, 3]);
+    }
+}
@@ -0,0 +1,186 @@
+//! External commands: `ov <name>` runs `ov-<name>` from PATH when `<name>` is
+//! not a built-in command, the way git and cargo find theirs.
+//!
+//! The plugin gets the arguments after its name unchanged and inherits the
+//! terminal. Everything it needs to reach the server as `ov` would, and to
+//! print in the format the user asked for, is passed in the environment:
+//!
+//! - `OV_BIN`: the `ov` executable, for calling back into the CLI.
+//! - `OV_CONFIG_NAME`: the saved config that is active, when there is one.
+//! - `OV_CONFIG_FILE`: the config file in use.
+//! - `OV_URL`, `OV_API_KEY`, `OV_GATEWAY_TOKEN`, `OV_ACCOUNT`, `OV_USER`: the
+//!   server and the credentials and identity to send it.
+//! - `OV_OUTPUT` (`table`, `json`, or `ndjson`) and `OV_COMPACT` (`true` or
+//!   `false`): the `-o` and `-c` in effect. `--no-color` sets `NO_COLOR=1`.
+//!
+//! Variables without a value are removed rather than inherited, so a plugin
+//! started by another plugin never sees its parent's settings.
+
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::{Error, Result};
+use crate::output::OutputFormat;
+
+pub const PREFIX: &str = "ov-";
+
+/// The executable `ov-<name>` on PATH, if there is one.
+pub fn find(name: &str) -> Option<PathBuf> {
+    find_in(name, &std::env::var_os("PATH")?)
+}
+
+fn find_in(name: &str, path: &OsStr) -> Option<PathBuf> {
+    if name.is_empty() || name.starts_with('-') || name.contains(['/', '\\']) {
+        return None;
+    }
+    let file = format!("{PREFIX}{name}{}", std::env::consts::EXE_SUFFIX);
+    std::env::split_paths(path)
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .map(|dir| dir.join(&file))
+        .find(|candidate| is_executable(candidate))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// What a plugin is told about the `ov` that runs it.
+#[derive(Debug, Clone)]
+pub struct PluginEnv {
+    pub config_name: Option<String>,
+    pub config_file: Option<PathBuf>,
+    pub url: String,
+    pub api_key: Option<String>,
+    pub gateway_token: Option<String>,
+    pub account: Option<String>,
+    pub user: Option<String>,
+    pub output_format: OutputFormat,
+    pub compact: bool,
+    /// `--no-color`, passed on as `NO_COLOR=1`.
+    pub no_color: bool,
+}
+
+impl PluginEnv {
+    fn vars(&self) -> Vec<(&'static str, Option<OsString>)> {
+        let text = |value: &Option<String>| value.as_ref().map(OsString::from);
+        let output = match self.output_format {
+            OutputFormat::Table => "table",
+            OutputFormat::Json => "json",
+            OutputFormat::Ndjson => "ndjson",
+        };
+        vec![
+            (
+                "OV_BIN",
+                std::env::current_exe().ok().map(PathBuf::into_os_string),
+            ),
+            ("OV_CONFIG_NAME", text(&self.config_name)),
+            (
+                "OV_CONFIG_FILE",
+                self.config_file.clone().map(PathBuf::into_os_string),
+            ),
+            ("OV_URL", Some(OsString::from(&self.url))),
+            ("OV_API_KEY", text(&self.api_key)),
+            ("OV_GATEWAY_TOKEN", text(&self.gateway_token)),
+            ("OV_ACCOUNT", text(&self.account)),
+            ("OV_USER", text(&self.user)),
+            ("OV_OUTPUT", Some(OsString::from(output))),
+            ("OV_COMPACT", Some(OsString::from(self.compact.to_string()))),
+        ]
+    }
+}
+
+/// Run the plugin at `path` with `args`. On Unix it replaces this process;
+/// elsewhere it runs to completion and its exit code is returned.
+pub fn run(path: &Path, args: &[OsString], env: &PluginEnv) -> Result<i32> {
+    let mut command = Command::new(path);
+    command.args(args);
+    for (name, value) in env.vars() {
+        match value {
+            Some(value) => command.env(name, value),
+            None => command.env_remove(name),
+        };
+    }
+    if env.no_color {
+        command.env("NO_COLOR", "1");
+    }
+    let failed = |e: std::io::Error| Error::Client(format!("Cannot run {}: {e}", path.display()));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        Err(failed(command.exec()))
+    }
+    #[cfg(not(unix))]
+    {
+        let status = command.status().map_err(failed)?;
+        Ok(status.code().unwrap_or(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn plugins_are_executables_named_after_the_command() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        let install = |dir: &Path, name: &str, mode: u32| {
+            let path = dir.join(name);
+            std::fs::write(&path, "#!/bin/sh\n").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+            path
+        };
+        install(first.path(), "ov-lint", 0o644);
+        let lint = install(second.path(), "ov-lint", 0o755);
+        let deploy = install(first.path(), "ov-deploy", 0o755);
+        install(second.path(), "ov-deploy", 0o755);
+        let path = std::env::join_paths([first.path(), second.path()]).unwrap();
+
+        assert_eq!(find_in("lint", &path), Some(lint));
+        assert_eq!(find_in("deploy", &path), Some(deploy));
+        assert_eq!(find_in("missing", &path), None);
+        assert_eq!(find_in("../ov-lint", &path), None);
+        assert_eq!(find_in("", &path), None);
+    }
+
+    #[test]
+    fn unset_settings_are_removed_from_the_plugin_environment() {
+        let env = PluginEnv {
+            config_name: Some("staging".to_string()),
+            config_file: None,
+            url: "http://127.0.0.1:1933".to_string(),
+            api_key: None,
+            gateway_token: None,
+            account: Some("acme".to_string()),
+            user: None,
+            output_format: OutputFormat::Ndjson,
+            compact: false,
+            no_color: false,
+        };
+        let vars = env.vars();
+        let get = |name: &str| {
+            vars.iter()
+                .find(|(var, _)| *var == name)
+                .and_then(|(_, value)| value.clone())
+        };
+        assert_eq!(get("OV_CONFIG_NAME"), Some("staging".into()));
+        assert_eq!(get("OV_ACCOUNT"), Some("acme".into()));
+        assert_eq!(get("OV_OUTPUT"), Some("ndjson".into()));
+        assert_eq!(get("OV_COMPACT"), Some("false".into()));
+        assert_eq!(get("OV_API_KEY"), None);
+        assert!(vars.iter().any(|(name, _)| *name == "OV_API_KEY"));
+    }
+}
@@ -0,0 +1,458 @@
+//! `ov add --check-only <path>`: catch files the server would fail to ingest
+//! before any of them is uploaded.
+//!
+//! Each file, including the files inside a directory or an archive that
+//! `add-resource` would extract, is checked locally for what commonly makes
+//! server-side parsing fail or go quietly wrong: content that disagrees with
+//! the extension or that no parser accepts, text that is not UTF-8, Markdown
+//! front matter the server's line-by-line reader will not see, notebooks that
+//! do not convert, and empty or oversized files. Nothing is sent to the server.
+
+use std::path::Path;
+
+use serde_json::{Value, json};
+
+use crate::archive::{self, ArchiveKind};
+use crate::chunked_upload::CHUNKED_THRESHOLD;
+use crate::error::{Error, Result};
+use crate::file_type::{self, FileType};
+use crate::notebook;
+use crate::output::{OutputFormat, output_success};
+use crate::theme;
+
+/// Images larger than this are tiled before the server describes them.
+const LARGE_IMAGE_BYTES: u64 = 10 << 20;
+
+/// Most bytes of a text file read to check its encoding and front matter.
+const TEXT_CHECK_BYTES: u64 = 16 << 20;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Level {
+    Ok,
+    Warning,
+    Error,
+}
+
+impl Level {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// What checking one file found.
+#[derive(Debug, Default)]
+struct Report {
+    parser: String,
+    encoding: Option<&'static str>,
+    warnings: Vec<String>,
+    errors: Vec<String>,
+    notes: Vec<String>,
+}
+
+impl Report {
+    fn level(&self) -> Level {
+        if !self.errors.is_empty() {
+            Level::Error
+        } else if !self.warnings.is_empty() {
+            Level::Warning
+        } else {
+            Level::Ok
+        }
+    }
+}
+
+/// The document parser the server picks for `path` by its extension.
+fn parser_by_extension(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    Some(match extension.as_str() {
+        "md" | "markdown" | "mdown" | "mkd" => "Markdown",
+        "html" | "htm" => "HTML",
+        "txt" | "text" => "text",
+        "pdf" => "PDF",
+        "docx" => "Word",
+        "doc" => "Word (legacy)",
+        "pptx" => "PowerPoint",
+        "xlsx" | "xls" | "xlsm" => "Excel",
+        "epub" => "EPUB",
+        "zip" => "zip",
+        _ => return None,
+    })
+}
+
+/// The parser the server would use for `path`, detected as `found`.
+fn parser(path: &Path, found: &FileType) -> &'static str {
+    parser_by_extension(path).unwrap_or_else(|| {
+        let mime = found.mime.as_str();
+        if mime.starts_with("image/") {
+            "image"
+        } else if mime.starts_with("audio/") {
+            "audio"
+        } else if mime.starts_with("video/") {
+            "video"
+        } else if found.text {
+            "text"
+        } else if file_type::is_supported(found) {
+            "document"
+        } else {
+            "none"
+        }
+    })
+}
+
+/// The encoding of `bytes`, and a warning when the server would have to
+/// guess it.
+fn encoding(bytes: &[u8]) -> (&'static str, Option<String>) {
+    if bytes.starts_with(b"\xef\xbb\xbf") {
+        return ("UTF-8 with BOM", None);
+    }
+    if bytes.starts_with(b"\xff\xfe") || bytes.starts_with(b"\xfe\xff") {
+        return ("UTF-16", None);
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) if text.is_ascii() => ("ASCII", None),
+        Ok(_) => ("UTF-8", None),
+        // A character cut off where the check stopped reading.
+        Err(e) if e.error_len().is_none() => ("UTF-8", None),
+        Err(_) => {
+            let (name, from) = if looks_like_gb18030(bytes) {
+                ("GBK/GB18030", "GB18030")
+            } else {
+                ("Latin-1/Windows-1252", "WINDOWS-1252")
+            };
+            (
+                name,
+                Some(format!(
+                    "not UTF-8 (looks like {name}); the server guesses legacy encodings, which can misread short files, so convert it first, e.g. iconv -f {from} -t UTF-8"
+                )),
+            )
+        }
+    }
+}
+
+/// Whether every non-ASCII byte of `bytes` pairs up as a GBK/GB18030 double-
+/// or four-byte sequence.
+fn looks_like_gb18030(bytes: &[u8]) -> bool {
+    let mut index = 0;
+    let mut sequences = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        if byte < 0x80 {
+            index += 1;
+            continue;
+        }
+        if !(0x81..=0xfe).contains(&byte) {
+            return false;
+        }
+        match bytes.get(index + 1) {
+            Some(0x40..=0x7e | 0x80..=0xfe) => index += 2,
+            Some(0x30..=0x39) => index += 4,
+            // Cut off where the check stopped reading.
+            None => break,
+            Some(_) => return false,
+        }
+        sequences += 1;
+    }
+    sequences > 0
+}
+
+/// Problems with the YAML front matter of a Markdown file. The server only
+/// recognizes a block opened and closed by `---` lines with LF endings, and
+/// reads it line by line as `key: value` pairs.
+fn front_matter(text: &str, report: &mut Report) {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    if text.starts_with("---\r\n") {
+        report.warnings.push(
+            "front matter has CRLF line endings, which the server does not recognize; it will be indexed as body text"
+                .to_string(),
+        );
+        return;
+    }
+    let Some(rest) = text.strip_prefix("---\n") else {
+        return;
+    };
+    let Some(end) = rest
+        .match_indices("\n---")
+        .map(|(at, _)| at)
+        .find(|&at| matches!(rest[at + 4..].chars().next(), None | Some('\n')))
+    else {
+        report
+            .warnings
+            .push("front matter is never closed by a --- line".to_string());
+        return;
+    };
+    let block = &rest[..end];
+    if let Err(e) = serde_yaml::from_str::<serde_yaml::Value>(block) {
+        report
+            .warnings
+            .push(format!("front matter is not valid YAML: {e}"));
+    }
+    let mut keys = 0;
+    for (number, line) in block.lines().enumerate() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        match line.split_once(':') {
+            Some((key, _)) if !line.starts_with([' ', '\t', '-']) && !key.trim().is_empty() => {
+                keys += 1;
+            }
+            _ => report.warnings.push(format!(
+                "front matter line {} ('{}') is not a key: value pair; the server reads front matter line by line and will drop it",
+                number + 2,
+                line.trim()
+            )),
+        }
+    }
+    report.notes.push(format!("{keys} front matter keys"));
+}
+
+fn check_file(path: &Path, report: &mut Report) -> Result<FileType> {
+    let size = std::fs::metadata(path)?.len();
+    let found = file_type::detect(path)?;
+    report.parser = parser(path, &found).to_string();
+    if size == 0 {
+        report
+            .warnings
+            .push("empty; there is nothing to index".to_string());
+        return Ok(found);
+    }
+    // A document parser handed the wrong content fails; media parsers cope.
+    let fails = !file_type::is_supported(&found) || parser_by_extension(path).is_some();
+    for warning in file_type::warnings(path, &found) {
+        if fails {
+            report.errors.push(warning);
+        } else {
+            report.warnings.push(warning);
+        }
+    }
+    if size >= CHUNKED_THRESHOLD {
+        report.notes.push("uploaded in chunks".to_string());
+    }
+    if found.mime.starts_with("image/") && size > LARGE_IMAGE_BYTES {
+        report
+            .notes
+            .push("over 10 MB; the server tiles it before describing it".to_string());
+    }
+
+    if notebook::is_notebook(path) {
+        match std::fs::read_to_string(path)
+            .map_err(Error::from)
+            .and_then(|json| notebook::to_markdown(&json))
+        {
+            Ok(_) => {
+                report.parser = "Markdown".to_string();
+                report.notes.push("converted to Markdown".to_string());
+            }
+            Err(e) => report.errors.push(format!(
+                "notebook does not convert to Markdown: {e}; add it with --no-convert to upload the JSON"
+            )),
+        }
+        return Ok(found);
+    }
+    if found.text {
+        let mut bytes = Vec::new();
+        std::io::Read::read_to_end(
+            &mut std::io::Read::take(std::fs::File::open(path)?, TEXT_CHECK_BYTES),
+            &mut bytes,
+        )?;
+        let (name, warning) = encoding(&bytes);
+        report.encoding = Some(name);
+        report.warnings.extend(warning);
+        if report.parser == "Markdown" {
+            front_matter(&String::from_utf8_lossy(&bytes), report);
+        }
+    }
+    Ok(found)
+}
+
+fn result_row(path: &str, size: u64, found: Option<&FileType>, report: &Report) -> Value {
+    let mut messages = report.errors.clone();
+    messages.extend(report.warnings.iter().cloned());
+    messages.extend(report.notes.iter().cloned());
+    json!({
+        "path": path,
+        "size": size,
+        "type": found.map(|found| found.mime.as_str()),
+        "encoding": report.encoding,
+        "parser": report.parser,
+        "status": report.level().name(),
+        "notes": messages.join("; "),
+    })
+}
+
+/// Check `path`, shown as `label`, and everything in it, appending a row per
+/// file to `rows`.
+fn check_path(path: &Path, label: &str, rows: &mut Vec<Value>) -> Result<()> {
+    if path.is_dir() {
+        let mut files: Vec<_> = walkdir::WalkDir::new(path)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| entry.into_path())
+            .collect();
+        files.sort();
+        for file in files {
+            let relative = file.strip_prefix(path).unwrap_or(&file);
+            let label = format!("{label}/{}", relative.to_string_lossy());
+            check_path(&file, &label, rows)?;
+        }
+        return Ok(());
+    }
+
+    let size = std::fs::metadata(path)?.len();
+    if let Some(kind) = ArchiveKind::of(path) {
+        match archive::extract(path, kind) {
+            Ok(extracted) => {
+                let mut report = Report {
+                    parser: "archive".to_string(),
+                    ..Report::default()
+                };
+                report.notes.push(format!(
+                    "extracted to {} files, checked below",
+                    extracted.files
+                ));
+                rows.push(result_row(label, size, None, &report));
+                return check_path(&extracted.root, label, rows);
+            }
+            Err(e) => {
+                let report = Report {
+                    parser: "archive".to_string(),
+                    errors: vec![e.to_string()],
+                    ..Report::default()
+                };
+                rows.push(result_row(label, size, None, &report));
+                return Ok(());
+            }
+        }
+    }
+    let mut report = Report::default();
+    let found = check_file(path, &mut report)?;
+    rows.push(result_row(label, size, Some(&found), &report));
+    Ok(())
+}
+
+pub fn check(path: &str, format: OutputFormat, compact: bool) -> Result<()> {
+    let local = Path::new(path);
+    if !local.exists() {
+        return Err(Error::Client(format!(
+            "Local path does not exist: {path}. --check-only checks local files and directories."
+        )));
+    }
+    let mut rows = Vec::new();
+    check_path(local, path.trim_end_matches('/'), &mut rows)?;
+
+    let count = |level: Level| {
+        rows.iter()
+            .filter(|row| row["status"] == level.name())
+            .count()
+    };
+    let (ok, warnings, errors) = (count(Level::Ok), count(Level::Warning), count(Level::Error));
+    if matches!(format, OutputFormat::Table) {
+        let checked = rows.len();
+        output_success(Value::Array(rows), format, compact);
+        println!(
+            "{}",
+            theme::muted(format!(
+                "{checked} checked, nothing uploaded: {ok} ok, {warnings} with warnings, {errors} would fail"
+            ))
+        );
+    } else {
+        output_success(
+            json!({
+                "path": path,
+                "ok": ok,
+                "warnings": warnings,
+                "errors": errors,
+                "results": rows,
+            }),
+            format,
+            compact,
+        );
+    }
+    if errors > 0 {
+        return Err(Error::AlreadyReported);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodings_are_reported() {
+        assert_eq!(encoding("合同".as_bytes()), ("UTF-8", None));
+        assert_eq!(encoding(b"plain").0, "ASCII");
+        // "合同条款" in GBK.
+        let (name, warning) = encoding(b"\xba\xcf\xcd\xac\xcc\xf5\xbf\xee\n");
+        assert_eq!(name, "GBK/GB18030");
+        assert!(warning.unwrap().contains("iconv -f GB18030 -t UTF-8"));
+        assert_eq!(encoding(b"caf\xe9 cr\xe8me").0, "Latin-1/Windows-1252");
+    }
+
+    #[test]
+    fn front_matter_the_server_would_miss_is_flagged() {
+        let mut report = Report::default();
+        front_matter(
+            "---\ntitle: 合同\ntags:\n  - legal\n---\n# Body\n",
+            &mut report,
+        );
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].contains("line 4 ('- legal')"));
+        assert_eq!(report.notes, ["2 front matter keys"]);
+
+        let mut report = Report::default();
+        front_matter("---\r\ntitle: x\r\n---\r\n", &mut report);
+        assert!(report.warnings[0].contains("CRLF"));
+
+        let mut report = Report::default();
+        front_matter("---\ntitle: x\n# Body\n", &mut report);
+        assert!(report.warnings[0].contains("never closed"));
+
+        let mut report = Report::default();
+        front_matter("# No front matter\n---\n", &mut report);
+        assert!(report.warnings.is_empty() && report.notes.is_empty());
+    }
+
+    #[test]
+    fn directories_and_archives_are_checked_file_by_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("ok.md"), "# Title\n").unwrap();
+        std::fs::write(dir.path().join("contract.md"), b"%PDF-1.7\n\x00\x01").unwrap();
+        std::fs::write(dir.path().join("empty.txt"), "").unwrap();
+        let mut zip =
+            zip::ZipWriter::new(std::fs::File::create(dir.path().join("docs.zip")).unwrap());
+        zip.start_file("guide.txt", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        std::io::Write::write_all(&mut zip, b"hello").unwrap();
+        zip.finish().unwrap();
+
+        let mut rows = Vec::new();
+        check_path(dir.path(), "data", &mut rows).unwrap();
+        let status: Vec<(&str, &str)> = rows
+            .iter()
+            .map(|row| {
+                (
+                    row["path"].as_str().unwrap(),
+                    row["status"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            status,
+            [
+                ("data/contract.md", "error"),
+                ("data/docs.zip", "ok"),
+                ("data/docs.zip/guide.txt", "ok"),
+                ("data/empty.txt", "warning"),
+                ("data/ok.md", "ok"),
+            ]
+        );
+        assert_eq!(rows[0]["parser"], "Markdown");
+        assert_eq!(rows[0]["type"], "application/pdf");
+    }
+}
@@ -0,0 +1,420 @@
+//! Offline command queue and server processing queues.
+//!
+//! With `--queue-offline`, a write command that cannot reach the server is
+//! appended to `~/.openviking/queue.jsonl` instead of failing: its arguments,
+//! working directory, and server URL. `ov queue flush` replays the entries for
+//! the current server in order by running `ov` again with the same arguments,
+//! and stops at the first one that fails, so later writes never overtake it.
+//!
+//! `ov queue status` and `ov queue wait` look at the other queue: the parse,
+//! semantic, and embedding work the server still has to do before new content
+//! is searchable.
+
+use std::ffi::OsString;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::client::HttpClient;
+use crate::config::default_config_path;
+use crate::error::{EXIT_UNREACHABLE, Error, Result};
+use crate::interrupt;
+use crate::output::{OutputFormat, output_success};
+use crate::theme;
+
+use super::render_utils;
+
+pub const QUEUE_OFFLINE_FLAG: &str = "--queue-offline";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct QueuedCommand {
+    id: String,
+    queued_at: String,
+    server: String,
+    cwd: PathBuf,
+    args: Vec<String>,
+}
+
+impl QueuedCommand {
+    fn command_line(&self) -> String {
+        render_utils::command_line(&self.args)
+    }
+
+    fn row(&self) -> Value {
+        json!({
+            "id": self.id,
+            "queued_at": self.queued_at,
+            "server": self.server,
+            "command": self.command_line(),
+        })
+    }
+}
+
+fn journal_path() -> Result<PathBuf> {
+    let config_path = default_config_path()?;
+    let home = config_path.parent().unwrap_or(Path::new("."));
+    Ok(home.join("queue.jsonl"))
+}
+
+fn read_journal(path: &Path) -> Result<Vec<QueuedCommand>> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line).map_err(|e| {
+                Error::Parse(format!("Corrupt queue entry in {}: {e}", path.display()))
+            })
+        })
+        .collect()
+}
+
+fn write_journal(path: &Path, entries: &[QueuedCommand]) -> Result<()> {
+    let temp = path.with_extension("jsonl.tmp");
+    let mut text = String::new();
+    for entry in entries {
+        text.push_str(&serde_json::to_string(entry)?);
+        text.push('\n');
+    }
+    std::fs::write(&temp, text)?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
+}
+
+/// How many commands are queued for `server`.
+pub(crate) fn queued_count(server: &str) -> Result<usize> {
+    Ok(read_journal(&journal_path()?)?
+        .iter()
+        .filter(|entry| entry.server == server)
+        .count())
+}
+
+/// Drop one entry, re-reading the journal so commands queued meanwhile stay.
+fn remove_entry(path: &Path, id: &str) -> Result<bool> {
+    let mut entries = read_journal(path)?;
+    let before = entries.len();
+    entries.retain(|entry| entry.id != id);
+    if entries.len() == before {
+        return Ok(false);
+    }
+    write_journal(path, &entries)?;
+    Ok(true)
+}
+
+/// The arguments to replay: this invocation's, without `--queue-offline`.
+fn replay_args(args: impl IntoIterator<Item = OsString>) -> Vec<String> {
+    args.into_iter()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .filter(|arg| arg != QUEUE_OFFLINE_FLAG)
+        .collect()
+}
+
+/// Append the running command to the journal after it found `server`
+/// unreachable.
+pub fn enqueue(server: &str, format: OutputFormat, compact: bool) -> Result<()> {
+    let path = journal_path()?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let entry = QueuedCommand {
+        id: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+        queued_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        server: server.to_string(),
+        cwd: std::env::current_dir()?,
+        args: replay_args(std::env::args_os().skip(1)),
+    };
+    let mut journal = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?;
+    writeln!(journal, "{}", serde_json::to_string(&entry)?)?;
+    let pending = read_journal(&path)?.len();
+    output_success(
+        json!({
+            "queued": entry.id,
+            "command": entry.command_line(),
+            "pending": pending,
+            "message": "Server unreachable; run `ov queue flush` when it is back",
+        }),
+        format,
+        compact,
+    );
+    Ok(())
+}
+
+pub fn list(format: OutputFormat, compact: bool) -> Result<()> {
+    let entries = read_journal(&journal_path()?)?;
+    let rows: Vec<Value> = entries.iter().map(QueuedCommand::row).collect();
+    output_success(rows, format, compact);
+    Ok(())
+}
+
+pub fn drop_command(id: &str, format: OutputFormat, compact: bool) -> Result<()> {
+    if !remove_entry(&journal_path()?, id)? {
+        return Err(Error::Client(format!(
+            "No queued command with id '{id}'; see `ov queue list`"
+        )));
+    }
+    output_success(json!({ "dropped": id }), format, compact);
+    Ok(())
+}
+
+/// Replay the commands queued for `server`, oldest first.
+pub async fn flush(client: &HttpClient, format: OutputFormat, compact: bool) -> Result<()> {
+    interrupt::enter_batch();
+    let server = client.server_url();
+    let path = journal_path()?;
+    let pending: Vec<QueuedCommand> = read_journal(&path)?
+        .into_iter()
+        .filter(|entry| entry.server == server)
+        .collect();
+    if !pending.is_empty()
+        && let Err(e) = client.get::<Value>("/health", &[]).await
+        && e.exit_code() == EXIT_UNREACHABLE
+    {
+        return Err(Error::Network(format!(
+            "{server} is still unreachable; {} queued command(s) kept",
+            pending.len()
+        )));
+    }
+    let exe = std::env::current_exe()?;
+    let mut replayed = 0;
+    let mut failed = None;
+    for entry in &pending {
+        if interrupt::requested() {
+            break;
+        }
+        eprintln!("{}", theme::muted(format!("→ {}", entry.command_line())));
+        let mut command = tokio::process::Command::new(&exe);
+        command.args(&entry.args);
+        if entry.cwd.is_dir() {
+            command.current_dir(&entry.cwd);
+        }
+        // Keep stdout to this command's own report when it is machine-readable.
+        if !matches!(format, OutputFormat::Table) {
+            command.stdout(Stdio::from(std::io::stderr()));
+        }
+        let status = interrupt::cancellable(async { Ok(command.status().await?) }).await?;
+        if status.success() {
+            remove_entry(&path, &entry.id)?;
+            replayed += 1;
+        } else {
+            failed = Some((entry, status.code()));
+            break;
+        }
+    }
+
+    let remaining = read_journal(&path)?
+        .iter()
+        .filter(|entry| entry.server == server)
+        .count();
+    let message = match failed {
+        None if interrupt::requested() => {
+            format!("Interrupted; {remaining} command(s) still queued")
+        }
+        None if pending.is_empty() => "No commands queued for this server".to_string(),
+        None => "All queued commands were replayed".to_string(),
+        Some((entry, Some(EXIT_UNREACHABLE))) => format!(
+            "Server became unreachable at {}; {remaining} command(s) still queued",
+            entry.id
+        ),
+        Some((entry, _)) => format!(
+            "{} failed and was kept with {} later command(s); fix it and flush again, or remove it with `ov queue drop {}`",
+            entry.id,
+            remaining.saturating_sub(1),
+            entry.id
+        ),
+    };
+    let report = json!({
+        "server": server,
+        "replayed": replayed,
+        "remaining": remaining,
+        "failed": failed.map(|(entry, _)| entry.id.clone()),
+        "message": message,
+    });
+    output_success(report, format, compact);
+    match failed {
+        Some(_) => Err(Error::AlreadyReported),
+        None if interrupt::requested() => Err(Error::Interrupted),
+        None => Ok(()),
+    }
+}
+
+/// Show pending parse and vectorization work, optionally for one URI subtree.
+pub async fn status(
+    client: &HttpClient,
+    uri: Option<&str>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let report = client.queue_status(uri).await?;
+    output_processing_report(report, format, compact);
+    Ok(())
+}
+
+/// Poll until the server has no pending or running work for `uri`.
+pub async fn wait(
+    client: &HttpClient,
+    uri: &str,
+    timeout: Option<f64>,
+    interval: f64,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let started = tokio::time::Instant::now();
+    wait_until_processed(client, uri, timeout, interval).await?;
+    output_success(
+        json!({
+            "uri": uri,
+            "complete": true,
+            "waited_seconds": (started.elapsed().as_secs_f64() * 10.0).round() / 10.0,
+        }),
+        format,
+        compact,
+    );
+    Ok(())
+}
+
+/// Poll until the server has no pending or running work for `uri`, reporting
+/// progress on stderr, and fail if any of it failed.
+pub(crate) async fn wait_until_processed(
+    client: &HttpClient,
+    uri: &str,
+    timeout: Option<f64>,
+    interval: f64,
+) -> Result<()> {
+    let started = tokio::time::Instant::now();
+    let interval = Duration::from_secs_f64(interval.max(0.1));
+    let mut last_progress = String::new();
+    let report = loop {
+        let report = client.queue_status(Some(uri)).await?;
+        if report["complete"].as_bool().unwrap_or(true) {
+            break report;
+        }
+        let progress = processing_progress(&report);
+        if progress != last_progress {
+            eprintln!("{}", theme::muted(format!("… {uri}: {progress}")));
+            last_progress = progress;
+        }
+        if let Some(timeout) = timeout
+            && started.elapsed().as_secs_f64() >= timeout
+        {
+            return Err(Error::Timeout(format!(
+                "{uri} is still processing after {timeout}s ({last_progress})"
+            )));
+        }
+        interrupt::cancellable(async {
+            tokio::time::sleep(interval).await;
+            Ok(())
+        })
+        .await?;
+    };
+
+    let failed: Vec<Value> = report["items"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|item| item["state"] == "failed")
+        .cloned()
+        .collect();
+    if !failed.is_empty() {
+        return Err(Error::Api {
+            code: Some("PROCESSING_ERROR".to_string()),
+            message: format!(
+                "Processing failed for {} item(s) under {uri}; see `ov queue status {uri}`",
+                failed.len()
+            ),
+            details: Some(json!({ "items": failed })),
+            status: None,
+        });
+    }
+    Ok(())
+}
+
+/// "2 pending, 1 processing" over the tracked items of a status report.
+fn processing_progress(report: &Value) -> String {
+    let (mut pending, mut processing) = (0, 0);
+    for item in report["items"].as_array().into_iter().flatten() {
+        pending += item["pending"].as_u64().unwrap_or(0);
+        processing += item["in_progress"].as_u64().unwrap_or(0);
+    }
+    format!("{pending} pending, {processing} processing")
+}
+
+/// Tables show the per-queue counts and then the per-URI items.
+fn output_processing_report(report: Value, format: OutputFormat, compact: bool) {
+    if !matches!(format, OutputFormat::Table) {
+        output_success(&report, format, compact);
+        return;
+    }
+    output_success(&report["queues"], format, compact);
+    if report["items"]
+        .as_array()
+        .is_some_and(|items| !items.is_empty())
+    {
+        output_success(&report["items"], format, compact);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, args: &[&str]) -> QueuedCommand {
+        QueuedCommand {
+            id: id.to_string(),
+            queued_at: "2026-10-16T09:00:00Z".to_string(),
+            server: "http://localhost:1933".to_string(),
+            cwd: PathBuf::from("/tmp"),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn journal_keeps_order_and_removes_replayed_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("queue.jsonl");
+        assert!(read_journal(&path).unwrap().is_empty());
+
+        let first = entry("a1", &["rm", "viking://resources/old.md"]);
+        let second = entry(
+            "b2",
+            &["write", "viking://resources/n.md", "--content", "it's"],
+        );
+        write_journal(&path, &[first.clone(), second.clone()]).unwrap();
+        assert_eq!(read_journal(&path).unwrap(), vec![first, second.clone()]);
+
+        assert!(remove_entry(&path, "a1").unwrap());
+        assert!(!remove_entry(&path, "a1").unwrap());
+        assert_eq!(read_journal(&path).unwrap(), vec![second.clone()]);
+        assert_eq!(
+            second.command_line(),
+            "ov write viking://resources/n.md --content 'it'\\''s'"
+        );
+    }
+
+    #[test]
+    fn processing_progress_sums_tracked_items() {
+        let report = json!({
+            "items": [
+                { "uri": "viking://resources/a", "pending": 2, "in_progress": 0 },
+                { "uri": "viking://resources/a/b.md", "pending": 0, "in_progress": 1 },
+            ],
+        });
+        assert_eq!(processing_progress(&report), "2 pending, 1 processing");
+        assert_eq!(processing_progress(&json!({})), "0 pending, 0 processing");
+    }
+
+    #[test]
+    fn replay_args_drop_the_queue_flag() {
+        let args = ["--queue-offline", "rm", "viking://resources/a.md"].map(OsString::from);
+        assert_eq!(replay_args(args), vec!["rm", "viking://resources/a.md"]);
+    }
+}
@@ -0,0 +1,396 @@
+//! `ov relations import`: create many relation links from a CSV or JSONL file.
+//!
+//! CSV files need a header row with `from` and `to` columns and an optional
+//! `reason` column; JSONL files hold one `{"from", "to", "reason"}` object per
+//! line. Rows sharing a source URI and reason are sent together as one link
+//! request, so a failed request is reported against every row it carried.
+//! Rows that cannot be parsed are reported by line number and never sent.
+
+use serde_json::{Value, json};
+
+use crate::client::HttpClient;
+use crate::error::{Error, Result};
+use crate::interrupt;
+use crate::output::{OutputFormat, output_success};
+use crate::theme;
+
+/// Most target URIs sent in one link request.
+const BATCH_SIZE: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct EdgeRow {
+    line: usize,
+    from: String,
+    to: String,
+    reason: String,
+}
+
+/// A row that could not be turned into an edge, with the line it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BadRow {
+    line: usize,
+    error: String,
+}
+
+fn is_jsonl(path: &str, format: Option<&str>) -> bool {
+    match format {
+        Some(format) => format == "jsonl",
+        None => {
+            let path = path.to_ascii_lowercase();
+            path.ends_with(".jsonl") || path.ends_with(".ndjson")
+        }
+    }
+}
+
+/// Split CSV text into records of fields, each tagged with the line it
+/// starts on. Quoted fields may contain commas, newlines, and `""` escapes.
+fn csv_records(text: &str) -> Result<Vec<(usize, Vec<String>)>> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match (in_quotes, ch) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => in_quotes = false,
+            (true, c) => {
+                if c == '\n' {
+                    line += 1;
+                }
+                field.push(c);
+            }
+            (false, '"') if field.is_empty() => in_quotes = true,
+            (false, ',') => fields.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                fields.push(std::mem::take(&mut field));
+                records.push((record_line, std::mem::take(&mut fields)));
+                line += 1;
+                record_line = line;
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(Error::Parse(format!(
+            "Unterminated quoted field starting on line {record_line}"
+        )));
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push((record_line, fields));
+    }
+    records.retain(|(_, fields)| !(fields.len() == 1 && fields[0].trim().is_empty()));
+    Ok(records)
+}
+
+fn edge_from_fields(
+    line: usize,
+    from: Option<&str>,
+    to: Option<&str>,
+    reason: Option<&str>,
+) -> std::result::Result<EdgeRow, BadRow> {
+    let required = |value: Option<&str>, column: &str| {
+        value
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+            .ok_or_else(|| BadRow {
+                line,
+                error: format!("missing '{column}'"),
+            })
+    };
+    Ok(EdgeRow {
+        line,
+        from: required(from, "from")?,
+        to: required(to, "to")?,
+        reason: reason.unwrap_or_default().trim().to_string(),
+    })
+}
+
+fn parse_csv(text: &str) -> Result<(Vec<EdgeRow>, Vec<BadRow>)> {
+    let mut records = csv_records(text)?.into_iter();
+    let Some((_, header)) = records.next() else {
+        return Ok((Vec::new(), Vec::new()));
+    };
+    let column = |name: &str| {
+        header.iter().position(|field| {
+            field
+                .trim()
+                .trim_start_matches('\u{feff}')
+                .eq_ignore_ascii_case(name)
+        })
+    };
+    let (Some(from), Some(to)) = (column("from"), column("to")) else {
+        return Err(Error::Parse(
+            "CSV header must name 'from' and 'to' columns (and optionally 'reason')".to_string(),
+        ));
+    };
+    let reason = column("reason");
+
+    let mut edges = Vec::new();
+    let mut bad = Vec::new();
+    for (line, fields) in records {
+        let field = |index: usize| fields.get(index).map(String::as_str);
+        match edge_from_fields(line, field(from), field(to), reason.and_then(field)) {
+            Ok(edge) => edges.push(edge),
+            Err(row) => bad.push(row),
+        }
+    }
+    Ok((edges, bad))
+}
+
+fn parse_jsonl(text: &str) -> (Vec<EdgeRow>, Vec<BadRow>) {
+    let mut edges = Vec::new();
+    let mut bad = Vec::new();
+    for (index, raw) in text.lines().enumerate() {
+        let line = index + 1;
+        if raw.trim().is_empty() {
+            continue;
+        }
+        let value: Value = match serde_json::from_str(raw) {
+            Ok(value) => value,
+            Err(e) => {
+                bad.push(BadRow {
+                    line,
+                    error: format!("invalid JSON: {e}"),
+                });
+                continue;
+            }
+        };
+        let field = |name: &str| value.get(name).and_then(Value::as_str);
+        match edge_from_fields(line, field("from"), field("to"), field("reason")) {
+            Ok(edge) => edges.push(edge),
+            Err(row) => bad.push(row),
+        }
+    }
+    (edges, bad)
+}
+
+/// Group edges by source URI and reason, in first-seen order, and split each
+/// group into link requests of at most `BATCH_SIZE` targets.
+fn batches(edges: Vec<EdgeRow>) -> Vec<Vec<EdgeRow>> {
+    let mut groups: Vec<Vec<EdgeRow>> = Vec::new();
+    for edge in edges {
+        match groups.iter_mut().find(|group| {
+            group[0].from == edge.from && group[0].reason == edge.reason && group.len() < BATCH_SIZE
+        }) {
+            Some(group) => group.push(edge),
+            None => groups.push(vec![edge]),
+        }
+    }
+    groups
+}
+
+fn row(edge: &EdgeRow, status: &str, error: Option<String>) -> Value {
+    json!({
+        "line": edge.line,
+        "from": edge.from,
+        "to": edge.to,
+        "reason": edge.reason,
+        "status": status,
+        "error": error,
+    })
+}
+
+pub async fn import(
+    client: &HttpClient,
+    path: &str,
+    input_format: Option<&str>,
+    dry_run: bool,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| Error::Client(format!("Cannot read {path}: {e}")))?;
+    let (edges, bad) = if is_jsonl(path, input_format) {
+        parse_jsonl(&text)
+    } else {
+        parse_csv(&text)?
+    };
+
+    let mut rows: Vec<Value> = bad
+        .iter()
+        .map(|bad| {
+            json!({
+                "line": bad.line,
+                "from": null,
+                "to": null,
+                "reason": null,
+                "status": "invalid",
+                "error": bad.error,
+            })
+        })
+        .collect();
+    let total = edges.len();
+    let mut linked = 0;
+    let mut failed = 0;
+    let mut interrupted = false;
+    for batch in batches(edges) {
+        if dry_run {
+            rows.extend(batch.iter().map(|edge| row(edge, "planned", None)));
+            continue;
+        }
+        let targets: Vec<String> = batch.iter().map(|edge| edge.to.clone()).collect();
+        let result =
+            interrupt::cancellable(client.link(&batch[0].from, &targets, &batch[0].reason)).await;
+        match result {
+            Ok(_) => {
+                linked += batch.len();
+                rows.extend(batch.iter().map(|edge| row(edge, "linked", None)));
+            }
+            Err(Error::Interrupted) => {
+                interrupted = true;
+                break;
+            }
+            Err(e) => {
+                failed += batch.len();
+                let error = e.to_string();
+                rows.extend(
+                    batch
+                        .iter()
+                        .map(|edge| row(edge, "failed", Some(error.clone()))),
+                );
+            }
+        }
+        eprintln!(
+            "{}",
+            theme::muted(format!(
+                "[{}/{total}] {} -> {} target(s)",
+                linked + failed,
+                batch[0].from,
+                batch.len()
+            ))
+        );
+    }
+    rows.sort_by_key(|row| row["line"].as_u64().unwrap_or_default());
+
+    if matches!(format, OutputFormat::Table) {
+        output_success(Value::Array(rows), format, compact);
+        let summary = if dry_run {
+            format!(
+                "{total} link(s) would be created from {path}: {} invalid row(s)",
+                bad.len()
+            )
+        } else {
+            format!(
+                "{linked} of {total} link(s) created from {path}: {failed} failed, {} invalid row(s)",
+                bad.len()
+            )
+        };
+        println!("{}", theme::muted(summary));
+    } else {
+        output_success(
+            json!({
+                "file": path,
+                "dry_run": dry_run,
+                "rows": total + bad.len(),
+                "linked": linked,
+                "failed": failed,
+                "invalid": bad.len(),
+                "results": rows,
+            }),
+            format,
+            compact,
+        );
+    }
+    if interrupted {
+        return Err(Error::Interrupted);
+    }
+    if failed > 0 || !bad.is_empty() {
+        return Err(Error::AlreadyReported);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_records_handle_quotes_and_embedded_newlines() {
+        let records = csv_records(
+            "from,to,reason\r\na,b,\"cites, \"\"twice\"\"\"\nc,d,\"multi\nline\"\n\ne,f\n",
+        )
+        .unwrap();
+        assert_eq!(
+            records,
+            vec![
+                (1, vec!["from".into(), "to".into(), "reason".into()]),
+                (2, vec!["a".into(), "b".into(), "cites, \"twice\"".into()]),
+                (3, vec!["c".into(), "d".into(), "multi\nline".into()]),
+                (6, vec!["e".into(), "f".into()]),
+            ]
+        );
+        assert!(csv_records("a,\"open\n").is_err());
+    }
+
+    #[test]
+    fn csv_rows_map_columns_by_header_and_report_bad_lines() {
+        let (edges, bad) =
+            parse_csv("Reason,From,To\ncites,viking://a.md,viking://b.md\n,viking://a.md,\n")
+                .unwrap();
+        assert_eq!(
+            edges,
+            vec![EdgeRow {
+                line: 2,
+                from: "viking://a.md".into(),
+                to: "viking://b.md".into(),
+                reason: "cites".into(),
+            }]
+        );
+        assert_eq!(
+            bad,
+            vec![BadRow {
+                line: 3,
+                error: "missing 'to'".into(),
+            }]
+        );
+        assert!(parse_csv("source,target\na,b\n").is_err());
+    }
+
+    #[test]
+    fn jsonl_rows_report_invalid_json_by_line() {
+        let (edges, bad) = parse_jsonl(
+            "{\"from\":\"viking://a.md\",\"to\":\"viking://b.md\"}\n\nnot json\n{\"to\":\"x\"}\n",
+        );
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].reason, "");
+        assert_eq!(
+            bad.iter().map(|row| row.line).collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+    }
+
+    #[test]
+    fn batches_group_by_source_and_reason() {
+        let edge = |line, from: &str, to: &str, reason: &str| EdgeRow {
+            line,
+            from: from.into(),
+            to: to.into(),
+            reason: reason.into(),
+        };
+        let grouped = batches(vec![
+            edge(2, "a", "b", "cites"),
+            edge(3, "a", "c", "mentions"),
+            edge(4, "a", "d", "cites"),
+        ]);
+        assert_eq!(
+            grouped
+                .iter()
+                .map(|batch| batch.iter().map(|edge| edge.line).collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            vec![vec![2, 4], vec![3]]
+        );
+        assert!(is_jsonl("edges.JSONL", None));
+        assert!(!is_jsonl("edges.csv", None));
+        assert!(is_jsonl("edges.txt", Some("jsonl")));
+    }
+}
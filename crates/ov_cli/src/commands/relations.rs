@@ -1,7 +1,13 @@
+use futures::{StreamExt, TryStreamExt};
+use regex::Regex;
+use serde_json::{Value, json};
+
 use crate::client::HttpClient;
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::output::{OutputFormat, output_success};
 
+const FETCH_CONCURRENCY: usize = 8;
+
 pub async fn list_relations(
     client: &HttpClient,
     uri: &str,
@@ -55,3 +61,446 @@ pub async fn unlink(
     }
     Ok(())
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Edge {
+    from: String,
+    to: String,
+    reason: String,
+}
+
+/// A URI reached by a walk, with the number of hops from the root and the
+/// index of the edge that first reached it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Node {
+    uri: String,
+    hops: u32,
+    via: Option<usize>,
+}
+
+/// The part of the relation graph reachable from a root URI. Nodes are in
+/// breadth-first order, so the root comes first and every node's `via` edge
+/// lies on a shortest path from the root.
+#[derive(Debug, PartialEq, Eq)]
+struct RelationGraph {
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+}
+
+impl RelationGraph {
+    fn new(root: &str) -> Self {
+        Self {
+            nodes: vec![Node {
+                uri: root.to_string(),
+                hops: 0,
+                via: None,
+            }],
+            edges: Vec::new(),
+        }
+    }
+
+    fn node(&self, uri: &str) -> Option<&Node> {
+        self.nodes.iter().find(|node| node.uri == uri)
+    }
+
+    /// Record the edges found at `hop` that pass `reason`, returning the URIs
+    /// they reach for the first time.
+    fn absorb(&mut self, hop: u32, edges: Vec<Edge>, reason: Option<&Regex>) -> Vec<String> {
+        let mut reached = Vec::new();
+        for edge in edges {
+            if reason.is_some_and(|reason| !reason.is_match(&edge.reason)) {
+                continue;
+            }
+            if self.node(&edge.to).is_none() {
+                self.nodes.push(Node {
+                    uri: edge.to.clone(),
+                    hops: hop,
+                    via: Some(self.edges.len()),
+                });
+                reached.push(edge.to.clone());
+            }
+            self.edges.push(edge);
+        }
+        reached
+    }
+
+    /// The edges of the shortest path from the root to `uri`, in order.
+    fn path_to(&self, uri: &str) -> Option<Vec<&Edge>> {
+        let mut path = Vec::new();
+        let mut node = self.node(uri)?;
+        while let Some(via) = node.via {
+            let edge = &self.edges[via];
+            path.push(edge);
+            node = self.node(&edge.from)?;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+fn relation_edges(from: &str, relations: &Value) -> Vec<Edge> {
+    relations
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|relation| {
+            let to = relation.get("uri").and_then(Value::as_str)?;
+            let reason = relation
+                .get("reason")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            Some(Edge {
+                from: from.to_string(),
+                to: to.trim_end_matches('/').to_string(),
+                reason: reason.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Follow outgoing links breadth-first up to `depth` hops from `root`, one
+/// batch of concurrent lookups per hop. Only links whose reason matches
+/// `reason` are followed, and the walk stops early once `target` is reached.
+///
+/// A linked URI whose relations cannot be read (e.g. it was deleted) stays in
+/// the graph as a leaf; only a failure on the root itself is an error.
+async fn walk(
+    client: &HttpClient,
+    root: &str,
+    depth: u32,
+    reason: Option<&Regex>,
+    target: Option<&str>,
+) -> Result<RelationGraph> {
+    let root = root.trim_end_matches('/');
+    let mut graph = RelationGraph::new(root);
+    let root_edges = relation_edges(root, &client.relations(root).await?);
+    let mut frontier = graph.absorb(1, root_edges, reason);
+    for hop in 2..=depth {
+        if frontier.is_empty() || target.is_some_and(|target| graph.node(target).is_some()) {
+            break;
+        }
+        let batches: Vec<Vec<Edge>> = futures::stream::iter(frontier)
+            .map(|uri| async move {
+                match client.relations(&uri).await {
+                    Ok(relations) => Ok(relation_edges(&uri, &relations)),
+                    Err(e @ (Error::Network(_) | Error::Interrupted)) => Err(e),
+                    Err(_) => Ok(Vec::new()),
+                }
+            })
+            .buffered(FETCH_CONCURRENCY)
+            .try_collect()
+            .await?;
+        frontier = graph.absorb(hop, batches.into_iter().flatten().collect(), reason);
+    }
+    Ok(graph)
+}
+
+fn node_label(uri: &str) -> &str {
+    uri.strip_prefix("viking://").unwrap_or(uri)
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn render_dot(graph: &RelationGraph) -> String {
+    let mut out = String::from("digraph relations {\n  rankdir=LR;\n  node [shape=box];\n");
+    for node in &graph.nodes {
+        out.push_str(&format!(
+            "  \"{}\" [label=\"{}\"];\n",
+            dot_escape(&node.uri),
+            dot_escape(node_label(&node.uri))
+        ));
+    }
+    for edge in &graph.edges {
+        out.push_str(&format!(
+            "  \"{}\" -> \"{}\"",
+            dot_escape(&edge.from),
+            dot_escape(&edge.to)
+        ));
+        if !edge.reason.is_empty() {
+            out.push_str(&format!(" [label=\"{}\"]", dot_escape(&edge.reason)));
+        }
+        out.push_str(";\n");
+    }
+    out.push('}');
+    out
+}
+
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+fn render_mermaid(graph: &RelationGraph) -> String {
+    let id = |uri: &str| {
+        graph
+            .nodes
+            .iter()
+            .position(|node| node.uri == uri)
+            .map(|index| format!("n{index}"))
+            .unwrap_or_default()
+    };
+    let mut out = String::from("graph LR\n");
+    for (index, node) in graph.nodes.iter().enumerate() {
+        out.push_str(&format!(
+            "  n{index}[\"{}\"]\n",
+            mermaid_escape(node_label(&node.uri))
+        ));
+    }
+    for edge in &graph.edges {
+        if edge.reason.is_empty() {
+            out.push_str(&format!("  {} --> {}\n", id(&edge.from), id(&edge.to)));
+        } else {
+            out.push_str(&format!(
+                "  {} -->|\"{}\"| {}\n",
+                id(&edge.from),
+                mermaid_escape(&edge.reason),
+                id(&edge.to)
+            ));
+        }
+    }
+    out.trim_end().to_string()
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_graphml(graph: &RelationGraph) -> String {
+    let mut out = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+        "  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n",
+        "  <key id=\"reason\" for=\"edge\" attr.name=\"reason\" attr.type=\"string\"/>\n",
+        "  <graph id=\"relations\" edgedefault=\"directed\">\n",
+    ));
+    for node in &graph.nodes {
+        out.push_str(&format!(
+            "    <node id=\"{}\"><data key=\"label\">{}</data></node>\n",
+            xml_escape(&node.uri),
+            xml_escape(node_label(&node.uri))
+        ));
+    }
+    for edge in &graph.edges {
+        out.push_str(&format!(
+            "    <edge source=\"{}\" target=\"{}\"><data key=\"reason\">{}</data></edge>\n",
+            xml_escape(&edge.from),
+            xml_escape(&edge.to),
+            xml_escape(&edge.reason)
+        ));
+    }
+    out.push_str("  </graph>\n</graphml>");
+    out
+}
+
+/// Print the relation graph around `uri` as DOT, Mermaid, or GraphML.
+pub async fn graph(client: &HttpClient, uri: &str, depth: u32, format: &str) -> Result<()> {
+    let graph = walk(client, uri, depth, None, None).await?;
+    let rendered = match format {
+        "mermaid" => render_mermaid(&graph),
+        "graphml" => render_graphml(&graph),
+        _ => render_dot(&graph),
+    };
+    println!("{rendered}");
+    Ok(())
+}
+
+fn reason_regex(pattern: Option<&str>) -> Result<Option<Regex>> {
+    pattern
+        .map(|pattern| {
+            Regex::new(pattern)
+                .map_err(|e| Error::Client(format!("Invalid --reason-filter '{pattern}': {e}")))
+        })
+        .transpose()
+}
+
+fn neighbor_rows(graph: &RelationGraph) -> Value {
+    let rows: Vec<Value> = graph
+        .nodes
+        .iter()
+        .filter_map(|node| {
+            let edge = &graph.edges[node.via?];
+            Some(json!({
+                "uri": node.uri,
+                "hops": node.hops,
+                "from": edge.from,
+                "reason": edge.reason,
+            }))
+        })
+        .collect();
+    Value::Array(rows)
+}
+
+fn path_rows(from: &str, path: &[&Edge]) -> Value {
+    let rows: Vec<Value> = std::iter::once(json!({ "step": 0, "uri": from, "reason": "" }))
+        .chain(path.iter().enumerate().map(
+            |(index, edge)| json!({ "step": index + 1, "uri": edge.to, "reason": edge.reason }),
+        ))
+        .collect();
+    Value::Array(rows)
+}
+
+/// Everything linked from `uri` within `depth` hops, nearest first.
+pub async fn neighbors(
+    client: &HttpClient,
+    uri: &str,
+    depth: u32,
+    reason_filter: Option<&str>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let reason = reason_regex(reason_filter)?;
+    let graph = walk(client, uri, depth, reason.as_ref(), None).await?;
+    output_success(neighbor_rows(&graph), format, compact);
+    Ok(())
+}
+
+/// The shortest chain of links from `from` to `to`, one row per hop.
+pub async fn path(
+    client: &HttpClient,
+    from: &str,
+    to: &str,
+    depth: u32,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let from = from.trim_end_matches('/');
+    let to = to.trim_end_matches('/');
+    let graph = walk(client, from, depth, None, Some(to)).await?;
+    let path = graph.path_to(to).ok_or_else(|| {
+        Error::Client(format!(
+            "No relation path from {from} to {to} within {depth} hops; try a larger --depth"
+        ))
+    })?;
+    output_success(path_rows(from, &path), format, compact);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(from: &str, to: &str, reason: &str) -> Edge {
+        Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    fn sample() -> RelationGraph {
+        let mut graph = RelationGraph::new("viking://resources/contract.md");
+        graph.absorb(
+            1,
+            vec![edge(
+                "viking://resources/contract.md",
+                "viking://user/memories/acme.md",
+                "signed \"as is\"",
+            )],
+            None,
+        );
+        graph
+    }
+
+    #[test]
+    fn absorb_tracks_hops_and_skips_filtered_reasons() {
+        let mut graph = RelationGraph::new("a");
+        let reason = Regex::new("^cites").unwrap();
+        let reached = graph.absorb(
+            1,
+            vec![edge("a", "b", "cites"), edge("a", "c", "mentions")],
+            Some(&reason),
+        );
+        assert_eq!(reached, vec!["b"]);
+        let reached = graph.absorb(
+            2,
+            vec![edge("b", "d", "cites again"), edge("b", "a", "cites back")],
+            Some(&reason),
+        );
+        assert_eq!(reached, vec!["d"]);
+        assert_eq!(
+            neighbor_rows(&graph),
+            json!([
+                {"uri": "b", "hops": 1, "from": "a", "reason": "cites"},
+                {"uri": "d", "hops": 2, "from": "b", "reason": "cites again"},
+            ])
+        );
+    }
+
+    #[test]
+    fn path_follows_first_discovery_edges_back_to_the_root() {
+        let mut graph = RelationGraph::new("a");
+        graph.absorb(1, vec![edge("a", "b", "x"), edge("a", "c", "y")], None);
+        graph.absorb(2, vec![edge("b", "d", "z"), edge("c", "d", "w")], None);
+        let path = graph.path_to("d").unwrap();
+        assert_eq!(
+            path_rows("a", &path),
+            json!([
+                {"step": 0, "uri": "a", "reason": ""},
+                {"step": 1, "uri": "b", "reason": "x"},
+                {"step": 2, "uri": "d", "reason": "z"},
+            ])
+        );
+        assert!(graph.path_to("e").is_none());
+        assert!(graph.path_to("a").unwrap().is_empty());
+    }
+
+    #[test]
+    fn relation_edges_skip_entries_without_uri() {
+        let edges = relation_edges(
+            "viking://a.md",
+            &json!([{"uri": "viking://b.md", "reason": "cites"}, {"reason": "orphan"}]),
+        );
+        assert_eq!(
+            edges,
+            vec![Edge {
+                from: "viking://a.md".to_string(),
+                to: "viking://b.md".to_string(),
+                reason: "cites".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn dot_quotes_ids_and_labels() {
+        assert_eq!(
+            render_dot(&sample()),
+            concat!(
+                "digraph relations {\n",
+                "  rankdir=LR;\n",
+                "  node [shape=box];\n",
+                "  \"viking://resources/contract.md\" [label=\"resources/contract.md\"];\n",
+                "  \"viking://user/memories/acme.md\" [label=\"user/memories/acme.md\"];\n",
+                "  \"viking://resources/contract.md\" -> \"viking://user/memories/acme.md\" ",
+                "[label=\"signed \\\"as is\\\"\"];\n",
+                "}"
+            )
+        );
+    }
+
+    #[test]
+    fn mermaid_uses_indexed_node_ids() {
+        assert_eq!(
+            render_mermaid(&sample()),
+            concat!(
+                "graph LR\n",
+                "  n0[\"resources/contract.md\"]\n",
+                "  n1[\"user/memories/acme.md\"]\n",
+                "  n0 -->|\"signed #quot;as is#quot;\"| n1"
+            )
+        );
+    }
+
+    #[test]
+    fn graphml_escapes_xml() {
+        let rendered = render_graphml(&sample());
+        assert!(rendered.contains(
+            "<node id=\"viking://resources/contract.md\"><data key=\"label\">resources/contract.md</data></node>"
+        ));
+        assert!(rendered.contains("<data key=\"reason\">signed &quot;as is&quot;</data>"));
+        assert!(rendered.ends_with("</graphml>"));
+    }
+}
@@ -1,6 +1,6 @@
+pub(crate) use crate::output::{with_ascii_ellipsis, wrap_display_text};
 use crate::theme;
 use serde_json::Value;
-use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 pub(crate) fn append_profile_lines(profile: Option<&Value>, lines: &mut Vec<String>) {
     let Some(profile) = profile else {
@@ -28,118 +28,46 @@ pub(crate) fn append_profile_lines(profile: Option<&Value>, lines: &mut Vec<Stri
     }
 }
 
-pub(crate) fn wrap_display_text(input: &str, width: usize, max_lines: usize) -> Vec<String> {
-    if width == 0 || max_lines == 0 {
-        return Vec::new();
-    }
-
-    let normalized = input.split_whitespace().collect::<Vec<_>>().join(" ");
-    if normalized.is_empty() {
-        return Vec::new();
-    }
-
-    let mut lines = Vec::new();
-    let mut current = String::new();
-    let mut truncated = false;
-
-    for word in normalized.split(' ') {
-        if word.is_empty() {
-            continue;
-        }
-
-        if append_word_to_line(&mut current, word, width) {
-            continue;
-        }
-
-        if !current.is_empty() {
-            lines.push(std::mem::take(&mut current));
-
-            if lines.len() == max_lines {
-                truncated = true;
-                break;
-            }
-
-            if append_word_to_line(&mut current, word, width) {
-                continue;
-            }
-        }
-
-        let mut segment = String::new();
-        let mut segment_width = 0;
-        for ch in word.chars() {
-            let char_width = UnicodeWidthChar::width(ch).unwrap_or(0);
-            if !segment.is_empty() && segment_width + char_width > width {
-                lines.push(segment);
-                segment = String::new();
-                segment_width = 0;
-
-                if lines.len() == max_lines {
-                    truncated = true;
-                    break;
-                }
-            }
-
-            segment.push(ch);
-            segment_width += char_width;
-        }
-
-        if truncated {
-            break;
-        }
-
-        current = segment;
-    }
-
-    if !truncated && !current.is_empty() {
-        lines.push(current);
-    }
-
-    if lines.len() > max_lines {
-        lines.truncate(max_lines);
-        truncated = true;
-    }
-
-    if truncated {
-        if let Some(last) = lines.last_mut() {
-            *last = with_ascii_ellipsis(last, width);
+/// `ov` and `args` as one line a POSIX shell would split back into the same
+/// words. Anything outside a small set of characters that no shell treats
+/// specially gets single-quoted.
+pub(crate) fn command_line(args: &[String]) -> String {
+    let mut line = String::from("ov");
+    for arg in args {
+        line.push(' ');
+        let safe = !arg.is_empty()
+            && arg
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_./:@=,+%".contains(c));
+        if safe {
+            line.push_str(arg);
+        } else {
+            line.push_str(&format!("'{}'", arg.replace('\'', r"'\''")));
         }
     }
-
-    lines
+    line
 }
 
-fn append_word_to_line(line: &mut String, word: &str, width: usize) -> bool {
-    let separator_width = usize::from(!line.is_empty());
-    if line.width() + separator_width + word.width() > width {
-        return false;
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    if !line.is_empty() {
-        line.push(' ');
+    fn line(args: &[&str]) -> String {
+        command_line(&args.iter().map(ToString::to_string).collect::<Vec<_>>())
     }
-    line.push_str(word);
-    true
-}
 
-pub(crate) fn with_ascii_ellipsis(line: &str, width: usize) -> String {
-    const ELLIPSIS: &str = "...";
-    let ellipsis_width = ELLIPSIS.width();
-    if line.width() + ellipsis_width <= width {
-        return format!("{line}{ELLIPSIS}");
+    #[test]
+    fn command_lines_quote_everything_a_shell_would_expand() {
+        assert_eq!(
+            line(&["ls", "viking://resources/a-b_c.md", "--node-limit=5"]),
+            "ov ls viking://resources/a-b_c.md --node-limit=5"
+        );
+        assert_eq!(
+            line(&[
+                "write", "$HOME", "*", "a;b", "a|b", "{a,b}", "~", "", "it's"
+            ]),
+            r"ov write '$HOME' '*' 'a;b' 'a|b' '{a,b}' '~' '' 'it'\''s'"
+        );
+        assert_eq!(line(&["find", "合同"]), "ov find '合同'");
     }
-
-    let target_width = width.saturating_sub(ellipsis_width);
-    let mut output = String::new();
-    let mut output_width = 0;
-
-    for ch in line.chars() {
-        let char_width = UnicodeWidthChar::width(ch).unwrap_or(0);
-        if output_width + char_width > target_width {
-            break;
-        }
-        output.push(ch);
-        output_width += char_width;
-    }
-
-    format!("{}{ELLIPSIS}", output.trim_end())
 }
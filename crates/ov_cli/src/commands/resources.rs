@@ -25,7 +25,11 @@ pub async fn add_resource(
     show_progress: bool,
     verbose: bool,
 ) -> Result<()> {
-    let result = client
+    tracing::info!(path, wait, "adding resource");
+    // With --wait the server still answers at once; the job it starts is
+    // awaited here, so progress shows and a slow import cannot time out the
+    // request.
+    let submitted = client
         .add_resource(
             path,
             to,
@@ -33,7 +37,7 @@ pub async fn add_resource(
             parent_auto_create,
             &reason,
             &instruction,
-            wait,
+            false,
             timeout,
             strict,
             ignore_dirs,
@@ -46,11 +50,33 @@ pub async fn add_resource(
             verbose,
         )
         .await?;
+    let result = if wait {
+        super::jobs::finish_submitted(client, submitted, timeout, show_progress).await?
+    } else {
+        submitted
+    };
+    tracing::info!(
+        path,
+        root_uri = result.get("root_uri").and_then(|uri| uri.as_str()),
+        task_id = result.get("task_id").and_then(|id| id.as_str()),
+        "resource added"
+    );
 
-    if !wait && matches!(format, OutputFormat::Table) {
+    let deduplicated = result["deduplicated"].as_bool() == Some(true);
+    if deduplicated && matches!(format, OutputFormat::Table) {
+        let root_uri = result["root_uri"].as_str().unwrap_or_default();
+        match result["linked_from"].as_str() {
+            Some(parent) => eprintln!(
+                "Note: Identical content is already at {root_uri}; linked it from {parent} instead of uploading."
+            ),
+            None => eprintln!(
+                "Note: Identical content is already at {root_uri}; skipped the upload (use --no-dedup to import a copy)."
+            ),
+        }
+    } else if !wait && matches!(format, OutputFormat::Table) {
         eprintln!("Note: Resource is being processed in the background.");
         eprintln!(
-            "Use 'ov task status <task_id>' to check progress, or 'ov task list' to see all tasks."
+            "Use 'ov jobs show <task_id>' to check progress, or 'ov jobs wait <task_id>' to wait for it."
         );
     }
 
@@ -69,14 +95,19 @@ pub async fn add_skill(
     format: OutputFormat,
     compact: bool,
 ) -> Result<()> {
-    let result = client
-        .add_skill(data, wait, timeout, show_progress, verbose, None, parent)
+    let submitted = client
+        .add_skill(data, false, timeout, show_progress, verbose, None, parent)
         .await?;
+    let result = if wait {
+        super::jobs::finish_submitted(client, submitted, timeout, show_progress).await?
+    } else {
+        submitted
+    };
 
     if !wait && matches!(format, OutputFormat::Table) {
         eprintln!("Note: Skill is being processed in the background.");
         eprintln!(
-            "Use 'ov task status <task_id>' to check progress, or 'ov task list' to see all tasks."
+            "Use 'ov jobs show <task_id>' to check progress, or 'ov jobs wait <task_id>' to wait for it."
         );
     }
 
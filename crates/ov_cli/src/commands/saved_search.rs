@@ -0,0 +1,326 @@
+//! Saved searches: named `ov search` invocations kept in the config directory.
+//!
+//! `ov search save <name> "<query>" --filters "<flags>"` stores the query and
+//! the filter flags in `~/.openviking/searches.json`. `ov search run <name>`
+//! turns them back into the arguments of an `ov search` command, which is then
+//! parsed and run as if it had been typed. The query and the flags may contain
+//! `{{name}}` placeholders, filled from `name=value` arguments to `run`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::error::{Error, Result};
+use crate::json_store;
+use crate::output::{OutputFormat, output_success};
+
+static PLACEHOLDER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_-]*)\s*\}\}").unwrap());
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SavedSearch {
+    query: String,
+    #[serde(default)]
+    filters: Vec<String>,
+    saved_at: String,
+}
+
+impl SavedSearch {
+    /// Placeholder names used anywhere in the query or the filters.
+    fn placeholders(&self) -> BTreeSet<String> {
+        std::iter::once(&self.query)
+            .chain(&self.filters)
+            .flat_map(|text| PLACEHOLDER.captures_iter(text))
+            .map(|captures| captures[1].to_string())
+            .collect()
+    }
+
+    fn row(&self, name: &str) -> Value {
+        json!({
+            "name": name,
+            "query": self.query,
+            "filters": self.filters.join(" "),
+            "placeholders": self.placeholders().into_iter().collect::<Vec<_>>().join(","),
+            "saved_at": self.saved_at,
+        })
+    }
+}
+
+type SavedSearches = BTreeMap<String, SavedSearch>;
+
+fn store_path() -> Result<PathBuf> {
+    json_store::path("searches.json")
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    json_store::validate_name("saved search", name)
+}
+
+/// Split `--filters` the way a POSIX shell would split words: on whitespace,
+/// keeping quoted runs together and honouring backslash escapes.
+fn split_filters(raw: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = raw.chars();
+
+    while let Some(ch) = chars.next() {
+        match (quote, ch) {
+            (Some('\''), '\'') | (Some('"'), '"') => quote = None,
+            (Some('"'), '\\') | (None, '\\') => {
+                let Some(next) = chars.next() else {
+                    return Err(Error::Client(
+                        "Invalid --filters: trailing backslash".to_string(),
+                    ));
+                };
+                current.push(next);
+                in_word = true;
+            }
+            (Some(_), _) => current.push(ch),
+            (None, '\'' | '"') => {
+                quote = Some(ch);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, _) => {
+                current.push(ch);
+                in_word = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        return Err(Error::Client(
+            "Invalid --filters: unterminated quote".to_string(),
+        ));
+    }
+    if in_word {
+        words.push(current);
+    }
+    Ok(words)
+}
+
+/// Parse `name=value` arguments to `ov search run`.
+fn parse_values(values: &[String]) -> Result<BTreeMap<String, String>> {
+    values
+        .iter()
+        .map(|item| match item.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                Ok((key.trim().to_string(), value.to_string()))
+            }
+            _ => Err(Error::Client(format!(
+                "Invalid placeholder value '{item}'; expected name=value"
+            ))),
+        })
+        .collect()
+}
+
+/// The `ov search` arguments for `search`, with every placeholder filled.
+///
+/// A placeholder without a value and a value without a placeholder are both
+/// errors, so a typo cannot silently run a broader search than intended.
+fn expand_search(
+    name: &str,
+    search: &SavedSearch,
+    values: &BTreeMap<String, String>,
+) -> Result<Vec<String>> {
+    let placeholders = search.placeholders();
+    let missing: Vec<&str> = placeholders
+        .iter()
+        .filter(|key| !values.contains_key(*key))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        return Err(Error::Client(format!(
+            "Saved search '{name}' needs a value for {}; pass {}",
+            missing.join(", "),
+            missing
+                .iter()
+                .map(|key| format!("{key}=<value>"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        )));
+    }
+    let unused: Vec<&str> = values
+        .keys()
+        .filter(|key| !placeholders.contains(*key))
+        .map(String::as_str)
+        .collect();
+    if !unused.is_empty() {
+        return Err(Error::Client(format!(
+            "Saved search '{name}' has no placeholder named {}",
+            unused.join(", ")
+        )));
+    }
+    let fill = |text: &String| {
+        PLACEHOLDER
+            .replace_all(text, |captures: &regex::Captures| {
+                values[&captures[1]].clone()
+            })
+            .into_owned()
+    };
+    Ok(std::iter::once(&search.query)
+        .chain(&search.filters)
+        .map(fill)
+        .collect())
+}
+
+pub fn save(
+    name: &str,
+    query: &str,
+    filters: Option<&str>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    validate_name(name)?;
+    if query.trim().is_empty() {
+        return Err(Error::Client(
+            "Saved search query must not be empty.".to_string(),
+        ));
+    }
+    let search = SavedSearch {
+        query: query.to_string(),
+        filters: split_filters(filters.unwrap_or_default())?,
+        saved_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    };
+    let path = store_path()?;
+    let mut searches = json_store::read::<SavedSearches>(&path)?;
+    let mut row = search.row(name);
+    row["replaced"] = json!(searches.insert(name.to_string(), search).is_some());
+    json_store::write(&path, &searches)?;
+    output_success(row, format, compact);
+    Ok(())
+}
+
+pub fn list(format: OutputFormat, compact: bool) -> Result<()> {
+    let searches = json_store::read::<SavedSearches>(&store_path()?)?;
+    let rows: Vec<Value> = searches
+        .iter()
+        .map(|(name, search)| search.row(name))
+        .collect();
+    output_success(rows, format, compact);
+    Ok(())
+}
+
+pub fn remove(name: &str, format: OutputFormat, compact: bool) -> Result<()> {
+    let path = store_path()?;
+    let mut searches = json_store::read::<SavedSearches>(&path)?;
+    if searches.remove(name).is_none() {
+        return Err(Error::Client(format!(
+            "No saved search named '{name}'; see `ov search list`"
+        )));
+    }
+    json_store::write(&path, &searches)?;
+    output_success(json!({ "removed": name }), format, compact);
+    Ok(())
+}
+
+/// The arguments that follow `ov search` when running the saved search `name`.
+pub fn expand(name: &str, values: &[String]) -> Result<Vec<String>> {
+    let searches = json_store::read::<SavedSearches>(&store_path()?)?;
+    let search = searches.get(name).ok_or_else(|| {
+        Error::Client(format!(
+            "No saved search named '{name}'; see `ov search list`"
+        ))
+    })?;
+    expand_search(name, search, &parse_values(values)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saved(query: &str, filters: &str) -> SavedSearch {
+        SavedSearch {
+            query: query.to_string(),
+            filters: split_filters(filters).unwrap(),
+            saved_at: "2026-10-16T09:00:00Z".to_string(),
+        }
+    }
+
+    fn values(items: &[&str]) -> BTreeMap<String, String> {
+        parse_values(
+            &items
+                .iter()
+                .map(|item| item.to_string())
+                .collect::<Vec<_>>(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn filters_split_like_shell_words() {
+        assert_eq!(
+            split_filters(r#"--uri viking://resources/notes --tags "a b",c -n\ 5 '' x"#).unwrap(),
+            vec![
+                "--uri",
+                "viking://resources/notes",
+                "--tags",
+                "a b,c",
+                "-n 5",
+                "",
+                "x"
+            ]
+        );
+        assert!(split_filters("--tags 'open").is_err());
+        assert!(split_filters("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn placeholders_fill_query_and_filters() {
+        let search = saved(
+            "unresolved action items for {{ project }}",
+            "--after {{since}} --uri viking://resources/{{project}}",
+        );
+        assert_eq!(
+            search.placeholders().into_iter().collect::<Vec<_>>(),
+            vec!["project", "since"]
+        );
+        assert_eq!(
+            expand_search("todo", &search, &values(&["project=acme web", "since=7d"])).unwrap(),
+            vec![
+                "unresolved action items for acme web",
+                "--after",
+                "7d",
+                "--uri",
+                "viking://resources/acme web",
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_and_unknown_placeholder_values_are_rejected() {
+        let search = saved("items since {{since}}", "");
+        let missing = expand_search("todo", &search, &values(&[])).unwrap_err();
+        assert!(missing.to_string().contains("since=<value>"));
+        let unknown =
+            expand_search("todo", &search, &values(&["since=7d", "sinse=1d"])).unwrap_err();
+        assert!(unknown.to_string().contains("sinse"));
+        assert!(parse_values(&["since".to_string()]).is_err());
+    }
+
+    #[test]
+    fn store_round_trips_and_rejects_bad_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("searches.json");
+        assert!(json_store::read::<SavedSearches>(&path).unwrap().is_empty());
+
+        let mut searches = SavedSearches::new();
+        searches.insert("todo".to_string(), saved("open items", "-n 20"));
+        json_store::write(&path, &searches).unwrap();
+        assert_eq!(json_store::read::<SavedSearches>(&path).unwrap(), searches);
+
+        assert!(validate_name("weekly-review_2.0").is_ok());
+        assert!(validate_name("a/b").is_err());
+        assert!(validate_name("").is_err());
+    }
+}
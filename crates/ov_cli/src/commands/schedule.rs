@@ -0,0 +1,531 @@
+//! `ov schedule`: ov commands the daemon runs on a cron schedule.
+//!
+//! `ov schedule add nightly --cron "0 2 * * *" -- sync --from prod --to
+//! staging --prefix viking://resources` stores the command in
+//! `~/.openviking/schedules.json`, tied to the current server. A daemon running
+//! for that server checks its schedules every minute and runs each due command
+//! as `ov <command>` with the daemon's own environment, one run per schedule at
+//! a time. How the last run went is written back to the file for
+//! `ov schedule ls`. Cron fields are read in local time; runs that fall due
+//! while no daemon is up are skipped, not made up later.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::daemon;
+use crate::error::{Error, Result};
+use crate::json_store;
+use crate::output::{OutputFormat, output_success};
+use crate::theme;
+
+use super::render_utils;
+
+/// How far ahead to look for a cron's next run; far enough for `0 0 29 2 1`.
+const SEARCH_DAYS: u32 = 366 * 28;
+
+/// Longest error kept from a failed run.
+const ERROR_CHARS: usize = 300;
+
+/// A parsed five-field cron expression: minute, hour, day of month, month,
+/// and day of week, each as a bit set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cron {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl Cron {
+    fn parse(expr: &str) -> Result<Self> {
+        let expanded = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(Error::Client(format!(
+                "Invalid cron '{expr}'; use five fields (minute hour day month weekday) or @hourly, @daily, @weekly, @monthly"
+            )));
+        };
+        let weekdays = field(weekday, 0, 7, "weekday")?;
+        Ok(Self {
+            minutes: field(minute, 0, 59, "minute")?,
+            hours: field(hour, 0, 23, "hour")?,
+            days: field(day, 1, 31, "day")?,
+            months: field(month, 1, 12, "month")?,
+            // Sunday is both 0 and 7.
+            weekdays: (weekdays | (weekdays >> 7)) & 0x7f,
+            any_day: day == "*",
+            any_weekday: weekday == "*",
+        })
+    }
+
+    /// Whether the cron runs on `date`. As in cron, a day of month and a day
+    /// of week that are both restricted match either one.
+    fn runs_on(&self, date: NaiveDate) -> bool {
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        self.months & (1 << date.month()) != 0
+            && match (self.any_day, self.any_weekday) {
+                (true, _) => weekday,
+                (false, true) => day,
+                (false, false) => day || weekday,
+            }
+    }
+
+    /// The first minute after `after` the cron runs at.
+    fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + chrono::Duration::minutes(1);
+        let mut date = start.date();
+        for _ in 0..SEARCH_DAYS {
+            if self.runs_on(date) {
+                for hour in (0..24).filter(|hour| self.hours & (1 << hour) != 0) {
+                    for minute in (0..60).filter(|minute| self.minutes & (1 << minute) != 0) {
+                        let time = date.and_hms_opt(hour, minute, 0)?;
+                        if time >= start {
+                            return Some(time);
+                        }
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+}
+
+/// One comma-separated cron field as a bit set: `*`, `n`, `a-b`, each
+/// optionally stepped with `/s`.
+fn field(text: &str, min: u32, max: u32, name: &str) -> Result<u64> {
+    let invalid = || {
+        Error::Client(format!(
+            "Invalid cron {name} '{text}'; use *, a number from {min} to {max}, a range a-b, or a step */n"
+        ))
+    };
+    let number = |value: &str| {
+        value
+            .parse::<u32>()
+            .ok()
+            .filter(|value| (min..=max).contains(value))
+            .ok_or_else(invalid)
+    };
+    let mut bits = 0u64;
+    for part in text.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(invalid)?,
+            ),
+            None => (part, 1),
+        };
+        let (first, last) = match range {
+            "*" => (min, max),
+            range => match range.split_once('-') {
+                Some((first, last)) => (number(first)?, number(last)?),
+                None if step > 1 => (number(range)?, max),
+                None => (number(range)?, number(range)?),
+            },
+        };
+        if first > last {
+            return Err(invalid());
+        }
+        for value in (first..=last).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+/// How a schedule's last run went.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct LastRun {
+    started_at: String,
+    seconds: f64,
+    exit_code: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl LastRun {
+    fn status(&self) -> String {
+        match self.exit_code {
+            Some(0) => "ok".to_string(),
+            Some(code) => format!("failed (exit {code})"),
+            None => "failed".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Schedule {
+    cron: String,
+    command: Vec<String>,
+    server: String,
+    saved_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_run: Option<LastRun>,
+}
+
+impl Schedule {
+    fn row(&self, name: &str, now: NaiveDateTime) -> Value {
+        let next_run = Cron::parse(&self.cron)
+            .ok()
+            .and_then(|cron| cron.next_after(now))
+            .map(|time| time.format("%Y-%m-%d %H:%M").to_string());
+        json!({
+            "name": name,
+            "cron": self.cron,
+            "command": render_utils::command_line(&self.command),
+            "server": self.server,
+            "next_run": next_run,
+            "last_run": self.last_run.as_ref().map(|run| run.started_at.clone()),
+            "last_status": self.last_run.as_ref().map(LastRun::status),
+            "last_error": self.last_run.as_ref().and_then(|run| run.error.clone()),
+        })
+    }
+}
+
+type Schedules = BTreeMap<String, Schedule>;
+
+fn store_path() -> Result<PathBuf> {
+    json_store::path("schedules.json")
+}
+
+fn same_server(a: &str, b: &str) -> bool {
+    a.trim().trim_end_matches('/') == b.trim().trim_end_matches('/')
+}
+
+/// The command as it would be typed, quoting arguments the shell would split.
+fn validate_name(name: &str) -> Result<()> {
+    json_store::validate_name("schedule", name)
+}
+
+fn now_local() -> NaiveDateTime {
+    Local::now().naive_local()
+}
+
+/// Warn when no daemon is up to run the current server's schedules.
+fn warn_without_daemon(server: &str, schedules: &Schedules) {
+    let waiting = schedules
+        .values()
+        .any(|schedule| same_server(&schedule.server, server));
+    let running = daemon::socket_path(server).is_ok_and(|socket| daemon::is_listening(&socket));
+    if waiting && !running {
+        eprintln!(
+            "{} no ov daemon is running for {server}; schedules only run while `ov daemon start` is up",
+            theme::warning("warning:")
+        );
+    }
+}
+
+pub fn add(
+    server: &str,
+    name: &str,
+    cron: &str,
+    command: &[String],
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    validate_name(name)?;
+    let parsed = Cron::parse(cron)?;
+    if parsed.next_after(now_local()).is_none() {
+        return Err(Error::Client(format!(
+            "Cron '{cron}' never runs; check its day and month"
+        )));
+    }
+    let path = store_path()?;
+    let mut schedules = json_store::read::<Schedules>(&path)?;
+    let schedule = Schedule {
+        cron: cron.trim().to_string(),
+        command: command.to_vec(),
+        server: server.to_string(),
+        saved_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        last_run: None,
+    };
+    let mut row = schedule.row(name, now_local());
+    row["replaced"] = json!(schedules.insert(name.to_string(), schedule).is_some());
+    json_store::write(&path, &schedules)?;
+    warn_without_daemon(server, &schedules);
+    output_success(row, format, compact);
+    Ok(())
+}
+
+pub fn list(server: &str, format: OutputFormat, compact: bool) -> Result<()> {
+    let schedules = json_store::read::<Schedules>(&store_path()?)?;
+    let now = now_local();
+    let rows: Vec<Value> = schedules
+        .iter()
+        .map(|(name, schedule)| schedule.row(name, now))
+        .collect();
+    warn_without_daemon(server, &schedules);
+    output_success(rows, format, compact);
+    Ok(())
+}
+
+pub fn remove(name: &str, format: OutputFormat, compact: bool) -> Result<()> {
+    let path = store_path()?;
+    let mut schedules = json_store::read::<Schedules>(&path)?;
+    if schedules.remove(name).is_none() {
+        return Err(Error::Client(format!(
+            "No schedule named '{name}'; see `ov schedule ls`"
+        )));
+    }
+    json_store::write(&path, &schedules)?;
+    output_success(json!({ "removed": name }), format, compact);
+    Ok(())
+}
+
+/// The number of schedules the daemon for `server` runs.
+pub(crate) fn count(server: &str) -> Result<usize> {
+    let schedules = json_store::read::<Schedules>(&store_path()?)?;
+    Ok(schedules
+        .values()
+        .filter(|schedule| same_server(&schedule.server, server))
+        .count())
+}
+
+/// The schedules for `server` due at some minute after `since` and up to
+/// `now`, as name and command.
+fn due(
+    schedules: &Schedules,
+    server: &str,
+    since: NaiveDateTime,
+    now: NaiveDateTime,
+) -> Vec<(String, Vec<String>)> {
+    schedules
+        .iter()
+        .filter(|(_, schedule)| same_server(&schedule.server, server))
+        .filter(|(_, schedule)| {
+            Cron::parse(&schedule.cron)
+                .ok()
+                .and_then(|cron| cron.next_after(since))
+                .is_some_and(|next| next <= now)
+        })
+        .map(|(name, schedule)| (name.clone(), schedule.command.clone()))
+        .collect()
+}
+
+/// Write `run` back as the last run of `name`, unless the schedule was
+/// removed or given another command while it ran.
+fn record(name: &str, command: &[String], run: LastRun) -> Result<()> {
+    let path = store_path()?;
+    let mut schedules = json_store::read::<Schedules>(&path)?;
+    match schedules.get_mut(name) {
+        Some(schedule) if schedule.command == command => schedule.last_run = Some(run),
+        _ => return Ok(()),
+    }
+    json_store::write(&path, &schedules)
+}
+
+/// The last line the command wrote to stderr, for the run's error.
+fn last_line(stderr: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(stderr);
+    let line = text.lines().rev().find(|line| !line.trim().is_empty())?;
+    Some(line.trim().chars().take(ERROR_CHARS).collect())
+}
+
+async fn run_once(name: &str, command: &[String]) -> LastRun {
+    let started_at = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let started = Instant::now();
+    eprintln!(
+        "ov daemon: schedule {name} running {}",
+        render_utils::command_line(command)
+    );
+    let output = match std::env::current_exe() {
+        Ok(exe) => {
+            tokio::process::Command::new(exe)
+                .args(command)
+                .stdin(Stdio::null())
+                .output()
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    let seconds = (started.elapsed().as_secs_f64() * 10.0).round() / 10.0;
+    let run = match output {
+        Ok(output) => LastRun {
+            started_at,
+            seconds,
+            exit_code: output.status.code(),
+            error: (!output.status.success())
+                .then(|| last_line(&output.stderr))
+                .flatten(),
+        },
+        Err(e) => LastRun {
+            started_at,
+            seconds,
+            exit_code: None,
+            error: Some(format!("Cannot run ov: {e}")),
+        },
+    };
+    match &run.error {
+        Some(error) => eprintln!(
+            "ov daemon: schedule {name} {} after {seconds}s: {error}",
+            run.status()
+        ),
+        None => eprintln!(
+            "ov daemon: schedule {name} {} after {seconds}s",
+            run.status()
+        ),
+    }
+    run
+}
+
+/// Run the schedules for `server` as they fall due, until the daemon exits.
+/// A schedule still running when it falls due again is skipped that time.
+pub(crate) async fn run_due(server: String) {
+    let running: Arc<Mutex<HashSet<String>>> = Arc::default();
+    let mut since = now_local();
+    loop {
+        let now = Local::now();
+        let to_next_minute = 60 - u64::from(now.second());
+        tokio::time::sleep(Duration::from_secs(to_next_minute)).await;
+        let now = now_local();
+        let schedules = match store_path().and_then(|path| json_store::read::<Schedules>(&path)) {
+            Ok(schedules) => schedules,
+            Err(e) => {
+                eprintln!("ov daemon: cannot read schedules: {e}");
+                since = now;
+                continue;
+            }
+        };
+        for (name, command) in due(&schedules, &server, since, now) {
+            if !running.lock().unwrap().insert(name.clone()) {
+                eprintln!("ov daemon: schedule {name} is still running; skipping this run");
+                continue;
+            }
+            let running = running.clone();
+            tokio::spawn(async move {
+                let run = run_once(&name, &command).await;
+                if let Err(e) = record(&name, &command, run) {
+                    eprintln!("ov daemon: cannot record the run of schedule {name}: {e}");
+                }
+                running.lock().unwrap().remove(&name);
+            });
+        }
+        since = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn cron_finds_the_next_run() {
+        let nightly = Cron::parse("0 2 * * *").unwrap();
+        assert_eq!(
+            nightly.next_after(at("2026-10-17 01:59")),
+            Some(at("2026-10-17 02:00"))
+        );
+        assert_eq!(
+            nightly.next_after(at("2026-10-17 02:00")),
+            Some(at("2026-10-18 02:00"))
+        );
+        // 2026-10-17 is a Saturday.
+        let weekly = Cron::parse("@weekly").unwrap();
+        assert_eq!(
+            weekly.next_after(at("2026-10-17 12:00")),
+            Some(at("2026-10-18 00:00"))
+        );
+        let steps = Cron::parse("*/15 9-17 * * 1-5").unwrap();
+        assert_eq!(
+            steps.next_after(at("2026-10-17 12:00")),
+            Some(at("2026-10-19 09:00"))
+        );
+        assert_eq!(
+            steps.next_after(at("2026-10-19 09:14")),
+            Some(at("2026-10-19 09:15"))
+        );
+        // Day 13 or any Friday, as in cron.
+        let either = Cron::parse("0 0 13 * 5").unwrap();
+        assert_eq!(
+            either.next_after(at("2026-10-17 00:00")),
+            Some(at("2026-10-23 00:00"))
+        );
+        assert_eq!(Cron::parse("0 0 * * 7").unwrap(), weekly);
+        assert_eq!(
+            Cron::parse("0 0 31 2 *")
+                .unwrap()
+                .next_after(at("2026-10-17 00:00")),
+            None
+        );
+    }
+
+    #[test]
+    fn cron_rejects_bad_fields() {
+        for expr in [
+            "0 2 * *",
+            "60 * * * *",
+            "0 5-2 * * *",
+            "*/0 * * * *",
+            "@yearly",
+        ] {
+            assert!(Cron::parse(expr).is_err(), "{expr}");
+        }
+    }
+
+    #[test]
+    fn due_schedules_fall_between_ticks_for_their_server() {
+        let schedule = |cron: &str, server: &str| Schedule {
+            cron: cron.to_string(),
+            command: vec!["backup".to_string(), "create".to_string()],
+            server: server.to_string(),
+            saved_at: "2026-10-17T00:00:00Z".to_string(),
+            last_run: None,
+        };
+        let schedules = Schedules::from([
+            (
+                "nightly".to_string(),
+                schedule("0 2 * * *", "http://a:1933/"),
+            ),
+            ("hourly".to_string(), schedule("@hourly", "http://a:1933")),
+            ("other".to_string(), schedule("0 2 * * *", "http://b:1933")),
+        ]);
+        let names = |since: &str, now: &str| {
+            due(&schedules, "http://a:1933", at(since), at(now))
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+        };
+        assert!(names("2026-10-17 01:58", "2026-10-17 01:59").is_empty());
+        assert_eq!(
+            names("2026-10-17 01:59", "2026-10-17 02:00"),
+            vec!["hourly", "nightly"]
+        );
+        assert_eq!(
+            names("2026-10-17 02:00", "2026-10-17 03:01"),
+            vec!["hourly"]
+        );
+    }
+
+    #[test]
+    fn commands_display_as_typed() {
+        let command = ["sync", "--prefix", "viking://resources/a b", "it's"].map(String::from);
+        assert_eq!(
+            render_utils::command_line(&command),
+            r"ov sync --prefix 'viking://resources/a b' 'it'\''s'"
+        );
+    }
+}
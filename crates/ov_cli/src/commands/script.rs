@@ -0,0 +1,315 @@
+//! `ov run <script>`: batch scripts of ov commands.
+//!
+//! A script holds one command per line, written as it would be typed, with
+//! or without the leading `ov`; a line ending in `\` continues on the next,
+//! and `#` starts a comment. Words split as in a POSIX shell, and `$name` or
+//! `${name}` is replaced outside single quotes. Two lines are directives
+//! rather than commands:
+//!
+//! - `set <name> <value>` defines a variable for the lines after it. A
+//!   variable given with `--var name=value` wins over `set`, so a script can
+//!   give defaults. Names not set either way are read from the environment.
+//! - `onerror continue` keeps going after a command fails, and
+//!   `onerror abort`, the default, stops at it.
+//!
+//! The whole script is read and checked before the first command runs; the
+//! commands then run in order in this process, see `run_script` in lib.rs.
+
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::error::{Error, Result};
+
+use super::render_utils;
+
+/// What a failed command does to the rest of the script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnError {
+    Continue,
+    Abort,
+}
+
+/// One command of a script, with its variables filled in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// Line the command starts on, counting from 1.
+    pub line: usize,
+    /// The arguments after `ov`.
+    pub args: Vec<String>,
+    pub on_error: OnError,
+}
+
+impl Step {
+    /// The command as it would be typed.
+    pub fn display(&self) -> String {
+        render_utils::command_line(&self.args)
+    }
+}
+
+/// Read the script at `path` and fill in its variables, with `vars` given as
+/// `name=value`.
+pub fn load(path: &str, vars: &[String]) -> Result<Vec<Step>> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| Error::Client(format!("Cannot read script {path}: {e}")))?;
+    let mut given = HashMap::new();
+    for var in vars {
+        let (name, value) = var
+            .split_once('=')
+            .filter(|(name, _)| valid_name(name))
+            .ok_or_else(|| {
+                Error::Client(format!(
+                    "Invalid --var '{var}'; use name=value, with a name of letters, digits, and '_'"
+                ))
+            })?;
+        given.insert(name.to_string(), value.to_string());
+    }
+    parse(&text, given, &|name| std::env::var(name).ok())
+        .map_err(|(line, message)| Error::Client(format!("{path}:{line}: {message}")))
+}
+
+fn valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// The script's lines with continuations joined, each with the number of the
+/// line it starts on.
+fn logical_lines(text: &str) -> Vec<(usize, String)> {
+    let mut lines = Vec::new();
+    let mut pending: Option<(usize, String)> = None;
+    for (index, line) in text.lines().enumerate() {
+        let (start, mut joined) = pending.take().unwrap_or((index + 1, String::new()));
+        match line.trim_end().strip_suffix('\\') {
+            Some(head) => {
+                joined.push_str(head);
+                joined.push(' ');
+                pending = Some((start, joined));
+            }
+            None => {
+                joined.push_str(line);
+                lines.push((start, joined));
+            }
+        }
+    }
+    lines.extend(pending);
+    lines
+}
+
+fn parse(
+    text: &str,
+    given: HashMap<String, String>,
+    env: &dyn Fn(&str) -> Option<String>,
+) -> std::result::Result<Vec<Step>, (usize, String)> {
+    let mut vars = given.clone();
+    let mut on_error = OnError::Abort;
+    let mut steps = Vec::new();
+    for (line, text) in logical_lines(text) {
+        let lookup = |name: &str| vars.get(name).cloned().or_else(|| env(name));
+        let mut words = split_words(text.trim(), &lookup).map_err(|message| (line, message))?;
+        if words.first().is_some_and(|word| word == "ov") {
+            words.remove(0);
+        }
+        match words.first().map(String::as_str) {
+            None => {}
+            Some("set") => {
+                let [_, name, value] = &words[..] else {
+                    return Err((
+                        line,
+                        "use `set <name> <value>`; quote a value with spaces".to_string(),
+                    ));
+                };
+                if !valid_name(name) {
+                    return Err((
+                        line,
+                        format!("invalid variable name '{name}'; use letters, digits, and '_'"),
+                    ));
+                }
+                if !given.contains_key(name) {
+                    vars.insert(name.clone(), value.clone());
+                }
+            }
+            Some("onerror") => {
+                on_error = match words.get(1).map(String::as_str) {
+                    Some("continue") if words.len() == 2 => OnError::Continue,
+                    Some("abort") if words.len() == 2 => OnError::Abort,
+                    _ => {
+                        return Err((
+                            line,
+                            "use `onerror continue` or `onerror abort`".to_string(),
+                        ));
+                    }
+                };
+            }
+            Some(_) => steps.push(Step {
+                line,
+                args: words,
+                on_error,
+            }),
+        }
+    }
+    Ok(steps)
+}
+
+/// Split `text` into words the way a POSIX shell would, replacing variables
+/// outside single quotes and stopping at a `#` that starts a word.
+fn split_words(
+    text: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> std::result::Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match (quote, ch) {
+            (Some('\''), '\'') | (Some('"'), '"') => quote = None,
+            (Some('\''), _) => current.push(ch),
+            (_, '\\') => {
+                let Some(next) = chars.next() else {
+                    return Err("trailing backslash".to_string());
+                };
+                current.push(next);
+                in_word = true;
+            }
+            (_, '$') => {
+                match variable_name(&mut chars)? {
+                    Some(name) => current.push_str(
+                        &lookup(&name).ok_or_else(|| format!("undefined variable '{name}'"))?,
+                    ),
+                    None => current.push('$'),
+                }
+                in_word = true;
+            }
+            (Some(_), _) => current.push(ch),
+            (None, '\'' | '"') => {
+                quote = Some(ch);
+                in_word = true;
+            }
+            (None, '#') if !in_word => break,
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, _) => {
+                current.push(ch);
+                in_word = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        return Err("unterminated quote".to_string());
+    }
+    if in_word {
+        words.push(current);
+    }
+    Ok(words)
+}
+
+/// The variable named after a `$`, or `None` when the `$` starts no name.
+fn variable_name(chars: &mut Peekable<Chars>) -> std::result::Result<Option<String>, String> {
+    if chars.next_if_eq(&'{').is_some() {
+        let mut name = String::new();
+        loop {
+            match chars.next() {
+                Some('}') => break,
+                Some(c) => name.push(c),
+                None => return Err("unterminated ${".to_string()),
+            }
+        }
+        if !valid_name(&name) {
+            return Err(format!("invalid variable name '{name}'"));
+        }
+        return Ok(Some(name));
+    }
+    let mut name = String::new();
+    while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_') {
+        name.push(c);
+    }
+    Ok(valid_name(&name).then_some(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn steps(
+        text: &str,
+        given: &[(&str, &str)],
+    ) -> std::result::Result<Vec<Step>, (usize, String)> {
+        let given = given
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        parse(text, given, &|name| {
+            (name == "HOME").then(|| "/home/me".to_string())
+        })
+    }
+
+    #[test]
+    fn scripts_set_variables_and_error_modes() {
+        let script = r#"
+# Provision the docs tree
+set root viking://resources/docs
+set env staging
+ov mkdir $root/${env}
+onerror continue
+add-resource "$HOME/My Docs/a.md" --to $root/a.md \
+    --reason 'costs $5'   # trailing comment
+onerror abort
+rm $root/tmp
+"#;
+        let steps = steps(script, &[("env", "prod")]).unwrap();
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[0].args, vec!["mkdir", "viking://resources/docs/prod"]);
+        assert_eq!(steps[0].line, 5);
+        assert_eq!(steps[0].on_error, OnError::Abort);
+        assert_eq!(
+            steps[1].args,
+            vec![
+                "add-resource",
+                "/home/me/My Docs/a.md",
+                "--to",
+                "viking://resources/docs/a.md",
+                "--reason",
+                "costs $5",
+            ]
+        );
+        assert_eq!(steps[1].line, 7);
+        assert_eq!(steps[1].on_error, OnError::Continue);
+        assert_eq!(steps[2].on_error, OnError::Abort);
+        assert_eq!(
+            steps[1].display(),
+            "ov add-resource '/home/me/My Docs/a.md' --to viking://resources/docs/a.md --reason 'costs $5'"
+        );
+    }
+
+    #[test]
+    fn script_errors_name_their_line() {
+        assert_eq!(
+            steps("ls\nread $missing/a.md", &[]).unwrap_err(),
+            (2, "undefined variable 'missing'".to_string())
+        );
+        assert_eq!(steps("onerror retry", &[]).unwrap_err().0, 1);
+        assert_eq!(steps("set a b c", &[]).unwrap_err().0, 1);
+        assert_eq!(
+            steps("\nls 'open", &[]).unwrap_err().1,
+            "unterminated quote"
+        );
+        assert_eq!(
+            steps("echo ${a-b}", &[]).unwrap_err().1,
+            "invalid variable name 'a-b'"
+        );
+        assert_eq!(
+            steps("find '100$'", &[]).unwrap()[0].args,
+            vec!["find", "100$"]
+        );
+    }
+}
@@ -1,10 +1,13 @@
 use super::render_utils::{append_profile_lines, wrap_display_text};
-use crate::client::HttpClient;
-use crate::error::Result;
-use crate::output::{OutputFormat, output_success};
+use super::snippets::{MATCH_MARKER, highlight_line};
+use super::tokens::{Listing, Tokenizer, annotate};
+use crate::client::{HttpClient, SearchScoring};
+use crate::error::{Error, Result};
+use crate::output::{DecorateRecord, NdjsonStream, OutputFormat, output_success};
 use crate::theme;
 use colored::Colorize;
-use serde_json::Value;
+use futures::StreamExt;
+use serde_json::{Value, json};
 use std::io::IsTerminal;
 use unicode_width::UnicodeWidthStr;
 
@@ -12,7 +15,10 @@ const SEARCH_TEXT_WIDTH: usize = 96;
 const SEARCH_MIN_TEXT_WIDTH: usize = 32;
 const SEARCH_MAX_ABSTRACT_LINES: usize = 2;
 const SEARCH_MAX_URI_LINES: usize = 2;
+const SEARCH_MAX_SNIPPET_LINES: usize = 2;
 const SEARCH_INDENT: &str = "   ";
+const GREP_FALLBACK_FILE_LIMIT: i32 = 10_000;
+const GREP_FALLBACK_CONCURRENCY: usize = 8;
 const SEARCH_RESULT_COLLECTION_KEYS: &[&str] =
     &["memories", "resources", "skills", "results", "items"];
 
@@ -20,7 +26,6 @@ const SEARCH_RESULT_COLLECTION_KEYS: &[&str] =
 enum SearchRenderMode {
     Find,
     Search,
-    SkillsList,
     SkillsFind,
 }
 
@@ -45,13 +50,6 @@ impl SearchRenderContext {
         }
     }
 
-    fn skills_list() -> Self {
-        Self {
-            mode: SearchRenderMode::SkillsList,
-            node_limit: 0,
-        }
-    }
-
     fn skills_find(node_limit: i32) -> Self {
         Self {
             mode: SearchRenderMode::SkillsFind,
@@ -60,44 +58,19 @@ impl SearchRenderContext {
     }
 
     fn hides_level_and_score(self) -> bool {
-        matches!(
-            self.mode,
-            SearchRenderMode::SkillsList | SearchRenderMode::SkillsFind
-        )
+        matches!(self.mode, SearchRenderMode::SkillsFind)
     }
 
     fn splits_name_and_description(self) -> bool {
-        matches!(
-            self.mode,
-            SearchRenderMode::SkillsList | SearchRenderMode::SkillsFind
-        )
+        matches!(self.mode, SearchRenderMode::SkillsFind)
     }
 
     fn item_noun(self) -> (&'static str, &'static str) {
         match self.mode {
-            SearchRenderMode::SkillsList | SearchRenderMode::SkillsFind => ("skill", "skills"),
+            SearchRenderMode::SkillsFind => ("skill", "skills"),
             _ => ("result", "results"),
         }
     }
-
-    fn shows_ranking_line(self) -> bool {
-        !matches!(self.mode, SearchRenderMode::SkillsList)
-    }
-}
-
-pub(super) fn output_skills_list_results(
-    result: &Value,
-    output_format: OutputFormat,
-    compact: bool,
-) {
-    let context = SearchRenderContext::skills_list();
-    if let Some(rendered) =
-        render_search_output_for_table_with_context(result, output_format, Some(context))
-    {
-        println!("{rendered}");
-    } else {
-        output_success(result, output_format, compact);
-    }
 }
 
 pub(super) fn output_skills_find_results(
@@ -116,88 +89,169 @@ pub(super) fn output_skills_find_results(
     }
 }
 
+/// What `find` and `search` ask the server for.
+pub struct SearchOptions<'a> {
+    pub query: &'a str,
+    pub uri: &'a str,
+    pub image: Option<String>,
+    /// The session whose context informs `search`; `find` ignores it.
+    pub session_id: Option<String>,
+    pub node_limit: i32,
+    pub threshold: Option<f64>,
+    pub since: Option<&'a str>,
+    pub until: Option<&'a str>,
+    pub time_field: Option<&'a str>,
+    pub level: Option<Vec<i32>>,
+    pub context_type: Option<Vec<String>>,
+    pub tags: Option<Vec<String>>,
+}
+
 pub async fn find(
     client: &HttpClient,
-    query: &str,
-    uri: &str,
-    image: Option<String>,
-    node_limit: i32,
-    threshold: Option<f64>,
-    since: Option<&str>,
-    until: Option<&str>,
-    time_field: Option<&str>,
-    level: Option<Vec<i32>>,
-    context_type: Option<Vec<String>>,
-    tags: Option<Vec<String>>,
+    options: SearchOptions<'_>,
+    scoring: &SearchScoring,
+    tokens: Option<Tokenizer>,
+    snippets: bool,
     output_format: OutputFormat,
     compact: bool,
 ) -> Result<()> {
-    let result = client
+    let query = options.query;
+    let node_limit = options.node_limit;
+    let stream = NdjsonStream::decorated(
+        output_format,
+        decorate_hits(client, tokens, snippets.then_some(query)),
+    );
+    let mut result = client
+        .clone()
+        .with_ndjson_stream(stream.clone())
         .find(
             query.to_string(),
-            uri.to_string(),
-            image,
+            options.uri.to_string(),
+            options.image,
             node_limit,
-            threshold,
-            since.map(|s| s.to_string()),
-            until.map(|s| s.to_string()),
-            time_field.map(|s| s.to_string()),
-            level,
-            context_type,
-            tags,
+            options.threshold,
+            options.since.map(|s| s.to_string()),
+            options.until.map(|s| s.to_string()),
+            options.time_field.map(|s| s.to_string()),
+            options.level,
+            options.context_type,
+            options.tags,
+            scoring,
         )
         .await?;
+    if stream.is_some_and(|stream| stream.printed()) {
+        return Ok(());
+    }
+    if let Some(tokenizer) = tokens {
+        annotate(client, &mut result, Listing::Hits, tokenizer).await?;
+    }
+    if snippets {
+        super::snippets::annotate(client, &mut result, query).await?;
+    }
     output_search_results(
         &result,
         output_format,
         compact,
         SearchRenderContext::find(node_limit),
     );
+    if scoring.explain {
+        output_explanation(query, &result, output_format);
+    }
     Ok(())
 }
 
 pub async fn search(
     client: &HttpClient,
-    query: &str,
-    uri: &str,
-    image: Option<String>,
-    session_id: Option<String>,
-    node_limit: i32,
-    threshold: Option<f64>,
-    since: Option<&str>,
-    until: Option<&str>,
-    time_field: Option<&str>,
-    level: Option<Vec<i32>>,
-    context_type: Option<Vec<String>>,
-    tags: Option<Vec<String>>,
+    options: SearchOptions<'_>,
+    scoring: &SearchScoring,
+    tokens: Option<Tokenizer>,
+    snippets: bool,
     output_format: OutputFormat,
     compact: bool,
 ) -> Result<()> {
-    let result = client
+    let query = options.query;
+    let node_limit = options.node_limit;
+    let stream = NdjsonStream::decorated(
+        output_format,
+        decorate_hits(client, tokens, snippets.then_some(query)),
+    );
+    let mut result = client
+        .clone()
+        .with_ndjson_stream(stream.clone())
         .search(
             query.to_string(),
-            uri.to_string(),
-            image,
-            session_id,
+            options.uri.to_string(),
+            options.image,
+            options.session_id,
             node_limit,
-            threshold,
-            since.map(|s| s.to_string()),
-            until.map(|s| s.to_string()),
-            time_field.map(|s| s.to_string()),
-            level,
-            context_type,
-            tags,
+            options.threshold,
+            options.since.map(|s| s.to_string()),
+            options.until.map(|s| s.to_string()),
+            options.time_field.map(|s| s.to_string()),
+            options.level,
+            options.context_type,
+            options.tags,
+            scoring,
         )
         .await?;
+    if stream.is_some_and(|stream| stream.printed()) {
+        return Ok(());
+    }
+    if let Some(tokenizer) = tokens {
+        annotate(client, &mut result, Listing::Hits, tokenizer).await?;
+    }
+    if snippets {
+        super::snippets::annotate(client, &mut result, query).await?;
+    }
     output_search_results(
         &result,
         output_format,
         compact,
         SearchRenderContext::search(node_limit),
     );
+    if scoring.explain {
+        output_explanation(query, &result, output_format);
+    }
     Ok(())
 }
 
+/// `--tokens` and `--snippets` for one hit of a streamed result.
+fn decorate_hits(
+    client: &HttpClient,
+    tokens: Option<Tokenizer>,
+    snippets_query: Option<&str>,
+) -> Option<DecorateRecord> {
+    if tokens.is_none() && snippets_query.is_none() {
+        return None;
+    }
+    let client = client.clone();
+    let snippets_query = snippets_query.map(str::to_string);
+    Some(Box::new(move |mut hit| {
+        let client = client.clone();
+        let snippets_query = snippets_query.clone();
+        Box::pin(async move {
+            if let Some(tokenizer) = tokens {
+                annotate(&client, &mut hit, Listing::Hits, tokenizer).await?;
+            }
+            if let Some(query) = snippets_query {
+                super::snippets::annotate(&client, &mut hit, &query).await?;
+            }
+            Ok(Some(hit))
+        })
+    }))
+}
+
+/// Print the `--explain` tree after the cards; JSON output already carries
+/// the provenance and telemetry it is built from.
+fn output_explanation(query: &str, result: &Value, output_format: OutputFormat) {
+    if output_format.is_json() || crate::output::table_layout_requested() {
+        return;
+    }
+    if let Some(rendered) = super::explain::render_explanation(query, result) {
+        println!("\n{rendered}");
+    }
+}
+
 fn output_search_results(
     result: &Value,
     output_format: OutputFormat,
@@ -223,7 +277,7 @@ fn render_search_output_for_table_with_context(
     output_format: OutputFormat,
     context: Option<SearchRenderContext>,
 ) -> Option<String> {
-    if matches!(output_format, OutputFormat::Json) {
+    if output_format.is_json() || crate::output::table_layout_requested() {
         return None;
     }
     render_search_results_for_table_with_context(value, context)
@@ -257,9 +311,7 @@ fn render_search_results_for_table_with_context(
             .bold()
             .to_string(),
     );
-    if context.is_none_or(|context| context.shows_ranking_line()) {
-        lines.push(theme::body(search_ranking_line(context, pass_count)).to_string());
-    }
+    lines.push(theme::body(search_ranking_line(context, pass_count)).to_string());
     lines.push(String::new());
 
     let hide_level_and_score = context.is_some_and(|context| context.hides_level_and_score());
@@ -331,7 +383,6 @@ fn search_ranking_line(context: Option<SearchRenderContext>, pass_count: Option<
             format!("limit {} per pass", context.node_limit)
         }
         SearchRenderMode::Search => format!("limit {} per search pass", context.node_limit),
-        SearchRenderMode::SkillsList => return "Ranked by relevance".to_string(),
     };
 
     format!("Ranked by relevance · {suffix}")
@@ -355,7 +406,7 @@ fn search_pass_count(value: &Value) -> Option<usize> {
     })
 }
 
-fn search_result_items(value: &Value) -> Option<(Vec<&Value>, Option<&Value>)> {
+pub(super) fn search_result_items(value: &Value) -> Option<(Vec<&Value>, Option<&Value>)> {
     if let Some(items) = value.as_array() {
         return Some((items.iter().collect(), None));
     }
@@ -422,6 +473,14 @@ fn render_search_result_card(
         if let Some(score) = search_result_score(object) {
             metadata.push(theme::warning(score).bold().to_string());
         }
+
+        if let Some(scores) = search_result_strategy_scores(object) {
+            metadata.push(theme::muted(scores).to_string());
+        }
+    }
+
+    if let Some(tokens) = result_tokens(object) {
+        metadata.push(theme::value(tokens).bold().to_string());
     }
 
     lines.push(format!(
@@ -441,19 +500,63 @@ fn render_search_result_card(
     }
 
     let abstract_text = search_result_text(item, object);
-    let wrapped = wrap_display_text(&abstract_text, text_width, SEARCH_MAX_ABSTRACT_LINES);
+    let mut snippets = search_result_snippets(object);
+    // A snippet of the whole abstract highlights it rather than repeating it.
+    let marked_abstract = snippets
+        .iter()
+        .position(|snippet| snippet.replace(MATCH_MARKER, "") == abstract_text)
+        .map(|index| snippets.remove(index));
+    let wrapped = wrap_display_text(
+        marked_abstract.unwrap_or(&abstract_text),
+        text_width,
+        SEARCH_MAX_ABSTRACT_LINES,
+    );
     if wrapped.is_empty() {
         lines.push(format!(
             "{SEARCH_INDENT}{}",
             theme::muted("No abstract available.")
         ));
     } else {
+        let mut inside_match = false;
         for line in wrapped {
-            lines.push(format!("{SEARCH_INDENT}{}", theme::body(line)));
+            let line = if marked_abstract.is_some() {
+                highlight_line(&line, &mut inside_match)
+            } else {
+                theme::body(line).to_string()
+            };
+            lines.push(format!("{SEARCH_INDENT}{line}"));
+        }
+    }
+
+    for snippet in snippets {
+        let mut inside_match = false;
+        let wrapped = wrap_display_text(
+            snippet,
+            text_width.saturating_sub(2),
+            SEARCH_MAX_SNIPPET_LINES,
+        );
+        for (index, line) in wrapped.iter().enumerate() {
+            let marker = if index == 0 { "›" } else { " " };
+            lines.push(format!(
+                "{SEARCH_INDENT}{} {}",
+                theme::muted(marker),
+                highlight_line(line, &mut inside_match)
+            ));
         }
     }
 }
 
+/// The passages `--snippets` adds, with `**` around each match.
+fn search_result_snippets(object: Option<&serde_json::Map<String, Value>>) -> Vec<&str> {
+    object
+        .and_then(|object| object.get("snippets"))
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect()
+}
+
 fn render_skill_name_and_description(
     object: Option<&serde_json::Map<String, Value>>,
     text_width: usize,
@@ -583,6 +686,25 @@ fn search_result_score(object: Option<&serde_json::Map<String, Value>>) -> Optio
     Some(format!("score {score:.3}"))
 }
 
+/// The per-strategy `scores` a `--scores` search returns, e.g.
+/// "vector 0.412 / keyword 1.000".
+pub(super) fn search_result_strategy_scores(
+    object: Option<&serde_json::Map<String, Value>>,
+) -> Option<String> {
+    let scores = object?.get("scores")?.as_object()?;
+    let parts: Vec<String> = ["vector", "keyword", "rerank"]
+        .into_iter()
+        .filter_map(|name| Some(format!("{name} {:.3}", scores.get(name)?.as_f64()?)))
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(" / "))
+}
+
+/// The `tokens` field `--tokens` adds, as shown on a result card.
+pub(super) fn result_tokens(object: Option<&serde_json::Map<String, Value>>) -> Option<String> {
+    let tokens = object?.get("tokens")?.as_u64()?;
+    Some(format!("{tokens} {}", pluralize(tokens, "token", "tokens")))
+}
+
 fn search_result_uri(object: Option<&serde_json::Map<String, Value>>) -> Option<&str> {
     object?
         .get("uri")
@@ -624,21 +746,136 @@ pub async fn grep(
     output_format: OutputFormat,
     compact: bool,
 ) -> Result<()> {
-    let result = client
+    let stream = NdjsonStream::for_output(output_format);
+    let result = match client
+        .clone()
+        .with_ndjson_stream(stream.clone())
         .grep(
             uri,
-            exclude_uri,
+            exclude_uri.clone(),
             pattern,
             ignore_case,
             node_limit,
             level_limit,
         )
-        .await?;
+        .await
+    {
+        Err(error) if error.is_missing_endpoint() => {
+            grep_client_side(
+                client,
+                uri,
+                exclude_uri.as_deref(),
+                pattern,
+                ignore_case,
+                node_limit,
+                level_limit,
+            )
+            .await?
+        }
+        result => result?,
+    };
+    if stream.is_some_and(|stream| stream.printed()) {
+        return Ok(());
+    }
     output_grep_results(&result, output_format, compact);
     Ok(())
 }
 
+/// Fallback for servers without `/api/v1/search/grep`: list the subtree, read
+/// files in parallel and match them locally. Returns the same shape as the
+/// server endpoint.
+async fn grep_client_side(
+    client: &HttpClient,
+    uri: &str,
+    exclude_uri: Option<&str>,
+    pattern: &str,
+    ignore_case: bool,
+    node_limit: i32,
+    level_limit: i32,
+) -> Result<Value> {
+    let regex = regex::RegexBuilder::new(pattern)
+        .case_insensitive(ignore_case)
+        .build()
+        .map_err(|error| Error::Client(format!("Invalid grep pattern: {error}")))?;
+    let tree = client
+        .tree(
+            uri,
+            "original",
+            0,
+            false,
+            GREP_FALLBACK_FILE_LIMIT,
+            level_limit,
+        )
+        .await?;
+    let files = grep_candidate_files(&tree, exclude_uri);
+    let files_scanned = files.len();
+
+    let mut reads = futures::stream::iter(files)
+        .map(|file| async move {
+            let content = client.read(&file).await;
+            (file, content)
+        })
+        .buffer_unordered(GREP_FALLBACK_CONCURRENCY);
+    let mut matches = Vec::new();
+    while let Some((file, content)) = reads.next().await {
+        // Binary or unreadable files are skipped, like grep does.
+        if let Ok(content) = content {
+            matches.extend(grep_lines(&regex, &file, &content));
+        }
+    }
+    matches.sort_by(|a, b| (&a.0, a.1).cmp(&(&b.0, b.1)));
+    let match_count = matches.len();
+    if let Ok(limit) = usize::try_from(node_limit)
+        && limit > 0
+    {
+        matches.truncate(limit);
+    }
+
+    let matches: Vec<Value> = matches
+        .into_iter()
+        .map(|(uri, line, content)| json!({ "uri": uri, "line": line, "content": content }))
+        .collect();
+    Ok(json!({
+        "matches": matches,
+        "count": matches.len(),
+        "match_count": match_count,
+        "files_scanned": files_scanned,
+    }))
+}
+
+fn grep_candidate_files(tree: &Value, exclude_uri: Option<&str>) -> Vec<String> {
+    let entries = tree
+        .as_array()
+        .or_else(|| tree.get("result").and_then(Value::as_array));
+    entries
+        .into_iter()
+        .flatten()
+        .filter(|entry| !entry.get("isDir").and_then(Value::as_bool).unwrap_or(false))
+        .filter_map(|entry| entry.get("uri").and_then(Value::as_str))
+        .filter(|uri| exclude_uri.is_none_or(|excluded| !uri.starts_with(excluded)))
+        .map(str::to_string)
+        .collect()
+}
+
+fn grep_lines(regex: &regex::Regex, uri: &str, content: &str) -> Vec<(String, usize, String)> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| regex.is_match(line))
+        .map(|(index, line)| (uri.to_string(), index + 1, line.to_string()))
+        .collect()
+}
+
 fn output_grep_results(result: &Value, output_format: OutputFormat, compact: bool) {
+    if !output_format.is_json()
+        && !std::io::stdout().is_terminal()
+        && let Some(rendered) = render_grep_output_plain(result)
+    {
+        if !rendered.is_empty() {
+            println!("{rendered}");
+        }
+        return;
+    }
     if let Some(rendered) = render_grep_output_for_table(result, output_format) {
         println!("{rendered}");
     } else {
@@ -646,8 +883,26 @@ fn output_grep_results(result: &Value, output_format: OutputFormat, compact: boo
     }
 }
 
+/// `uri:line:match`, one per line, for pipes and scripts.
+fn render_grep_output_plain(value: &Value) -> Option<String> {
+    let matches = value.get("matches")?.as_array()?;
+    let lines: Vec<String> = matches
+        .iter()
+        .map(|item| {
+            let field = |key: &str| item.get(key).and_then(Value::as_str).unwrap_or_default();
+            let line = item
+                .get("line")
+                .and_then(Value::as_i64)
+                .map(|line| line.to_string())
+                .unwrap_or_default();
+            format!("{}:{}:{}", field("uri"), line, field("content"))
+        })
+        .collect();
+    Some(lines.join("\n"))
+}
+
 fn render_grep_output_for_table(value: &Value, output_format: OutputFormat) -> Option<String> {
-    if matches!(output_format, OutputFormat::Json) {
+    if output_format.is_json() {
         return None;
     }
 
@@ -741,7 +996,15 @@ pub async fn glob(
     output_format: OutputFormat,
     compact: bool,
 ) -> Result<()> {
-    let result = client.glob(pattern, uri, node_limit).await?;
+    let stream = NdjsonStream::for_output(output_format);
+    let result = client
+        .clone()
+        .with_ndjson_stream(stream.clone())
+        .glob(pattern, uri, node_limit)
+        .await?;
+    if stream.is_some_and(|stream| stream.printed()) {
+        return Ok(());
+    }
     output_success(&result, output_format, compact);
     Ok(())
 }
@@ -751,6 +1014,113 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn grep_fallback_matches_lines_and_skips_dirs_and_excluded_uris() {
+        let tree = json!([
+            {"uri": "viking://resources/docs", "isDir": true},
+            {"uri": "viking://resources/docs/a.md", "isDir": false},
+            {"uri": "viking://resources/docs/drafts/b.md", "isDir": false}
+        ]);
+        assert_eq!(
+            grep_candidate_files(&tree, Some("viking://resources/docs/drafts")),
+            vec!["viking://resources/docs/a.md".to_string()]
+        );
+
+        let regex = regex::RegexBuilder::new("auth(entication)?")
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+        assert_eq!(
+            grep_lines(
+                &regex,
+                "viking://a.md",
+                "intro\nAuth flow\nnothing\nauthentication"
+            ),
+            vec![
+                ("viking://a.md".to_string(), 2, "Auth flow".to_string()),
+                ("viking://a.md".to_string(), 4, "authentication".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn grep_plain_output_prints_uri_line_match() {
+        let result = json!({
+            "matches": [
+                {"uri": "viking://resources/a.md", "line": 3, "content": "token: abc"},
+                {"uri": "viking://resources/b.md", "line": 10, "content": "token = 1"}
+            ],
+            "count": 2
+        });
+
+        assert_eq!(
+            render_grep_output_plain(&result).as_deref(),
+            Some("viking://resources/a.md:3:token: abc\nviking://resources/b.md:10:token = 1")
+        );
+    }
+
+    #[test]
+    fn only_router_level_not_found_triggers_grep_fallback() {
+        assert!(Error::api_with_status("Not Found", 404).is_missing_endpoint());
+        assert!(
+            !Error::api_response(Some("NOT_FOUND".to_string()), "missing uri", None, 404)
+                .is_missing_endpoint()
+        );
+        assert!(!Error::api_with_status("boom", 500).is_missing_endpoint());
+    }
+
+    #[test]
+    fn search_result_cards_show_strategy_scores() {
+        let results = json!([
+            {
+                "context_type": "resource",
+                "uri": "viking://resources/contracts/msa.md",
+                "level": 2,
+                "score": 0.706,
+                "scores": {"vector": 0.412, "keyword": 1.0},
+                "abstract": "Termination for convenience under clause 4.2."
+            }
+        ]);
+
+        let rendered = strip_ansi(&render_search_results_for_table(&results).expect("cards"));
+
+        assert!(
+            rendered.contains("1. resource · Level 2 · score 0.706 · vector 0.412 / keyword 1.000")
+        );
+    }
+
+    #[test]
+    fn search_result_cards_show_snippets_and_fold_a_marked_abstract() {
+        let results = json!([
+            {
+                "context_type": "resource",
+                "uri": "viking://resources/contracts/msa.md",
+                "level": 2,
+                "score": 0.9,
+                "abstract": "Master services agreement.",
+                "snippets": ["…end it under the **termination** clause…"]
+            },
+            {
+                "context_type": "resource",
+                "uri": "viking://resources/contracts/.abstract.md",
+                "level": 0,
+                "score": 0.6,
+                "abstract": "Contract termination rules",
+                "snippets": ["Contract **termination** rules"]
+            }
+        ]);
+
+        let rendered = strip_ansi(&render_search_results_for_table(&results).expect("cards"));
+
+        assert!(
+            rendered.contains(
+                "   Master services agreement.\n   › …end it under the termination clause…"
+            )
+        );
+        assert!(rendered.ends_with("   Contract termination rules"));
+        assert!(!rendered.contains("**"));
+    }
+
     #[test]
     fn search_result_cards_render_ranked_scannable_rows() {
         let results = json!([
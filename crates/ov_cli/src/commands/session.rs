@@ -1,11 +1,15 @@
 use super::render_utils::append_profile_lines;
 use crate::client::HttpClient;
 use crate::error::{Error, Result};
+use crate::interrupt;
 use crate::output::{OutputFormat, output_success};
 use crate::theme;
 use colored::Colorize;
 use serde_json::Value;
 use serde_json::json;
+use std::collections::HashSet;
+use std::io::Write;
+use std::time::Duration;
 
 pub async fn new_session(
     client: &HttpClient,
@@ -337,6 +341,149 @@ pub async fn add_messages(
     Ok(())
 }
 
+/// Outcome of `ov session add-messages --file`, keyed by input line.
+#[derive(Debug, Default)]
+struct AddMessagesReport {
+    added: usize,
+    errors: Vec<Value>,
+}
+
+impl AddMessagesReport {
+    fn fail(&mut self, line: usize, error: impl std::fmt::Display) {
+        tracing::warn!(line, error = %error, "message rejected");
+        eprintln!("{} line {line}: {error}", theme::error("error:"));
+        self.errors
+            .push(json!({"line": line, "error": error.to_string()}));
+    }
+}
+
+pub async fn add_messages_from_file(
+    client: &HttpClient,
+    session_id: &str,
+    file: &str,
+    output_format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let reader = std::fs::File::open(file)
+        .map(std::io::BufReader::new)
+        .map_err(|e| Error::Client(format!("Failed to open {file}: {e}")))?;
+    let session_path = format!("/api/v1/sessions/{}", url_encode(session_id));
+    let mut report = AddMessagesReport::default();
+    let mut batch: Vec<(usize, Value)> = Vec::with_capacity(IMPORT_BATCH_SIZE);
+    let mut interrupted = false;
+    interrupt::enter_batch();
+
+    for (index, line) in std::io::BufRead::lines(reader).enumerate() {
+        let line = line.map_err(|e| Error::Client(format!("Failed to read {file}: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_message_line(&line) {
+            Ok(message) => batch.push((index + 1, message)),
+            Err(error) => report.fail(index + 1, error),
+        }
+        if batch.len() == IMPORT_BATCH_SIZE {
+            interrupted = !send_message_batch(client, &session_path, &mut batch, &mut report).await;
+            if interrupted {
+                break;
+            }
+        }
+    }
+    if !interrupted && !batch.is_empty() {
+        interrupted = !send_message_batch(client, &session_path, &mut batch, &mut report).await;
+    }
+
+    let failed = report.errors.len();
+    tracing::info!(
+        session_id,
+        file,
+        added = report.added,
+        failed,
+        "messages file processed"
+    );
+    let mut summary = json!({
+        "session_id": session_id,
+        "added": report.added,
+        "failed": failed,
+    });
+    if interrupted {
+        summary["interrupted"] = Value::Bool(true);
+    }
+    if output_format.is_json() {
+        summary["errors"] = Value::Array(report.errors);
+    }
+    output_success(&summary, output_format, compact);
+    if interrupted {
+        return Err(Error::Interrupted);
+    }
+    if failed > 0 {
+        return Err(Error::Client(format!(
+            "{failed} message(s) from {file} could not be added"
+        )));
+    }
+    Ok(())
+}
+
+fn parse_message_line(line: &str) -> std::result::Result<Value, String> {
+    let item: Value =
+        serde_json::from_str(line).map_err(|error| format!("invalid JSON: {error}"))?;
+    import_message(&item)
+        .ok_or_else(|| "expected a user or assistant message with text content".to_string())
+}
+
+/// Send one batch, returning `false` when Ctrl-C abandoned it part-way.
+async fn send_message_batch(
+    client: &HttpClient,
+    session_path: &str,
+    batch: &mut Vec<(usize, Value)>,
+    report: &mut AddMessagesReport,
+) -> bool {
+    let sent = interrupt::cancellable(async {
+        send_message_batch_inner(client, session_path, batch, report).await;
+        Ok(())
+    })
+    .await;
+    batch.clear();
+    sent.is_ok()
+}
+
+/// The batch endpoint rejects the whole request when any message is invalid,
+/// so a failed batch is retried message by message to pin the error to its
+/// line.
+async fn send_message_batch_inner(
+    client: &HttpClient,
+    session_path: &str,
+    batch: &[(usize, Value)],
+    report: &mut AddMessagesReport,
+) {
+    let messages: Vec<&Value> = batch.iter().map(|(_, message)| message).collect();
+    let batch_path = format!("{session_path}/messages/batch");
+    let sent: Result<Value> = client
+        .post(&batch_path, &json!({ "messages": messages }))
+        .await;
+    if sent.is_ok() {
+        report.added += batch.len();
+        tracing::info!(
+            messages = batch.len(),
+            added = report.added,
+            "message batch added"
+        );
+    } else {
+        tracing::warn!(
+            messages = batch.len(),
+            "message batch rejected; retrying one message at a time"
+        );
+        let message_path = format!("{session_path}/messages");
+        for (line, message) in batch.iter() {
+            let sent: Result<Value> = client.post(&message_path, message).await;
+            match sent {
+                Ok(_) => report.added += 1,
+                Err(error) => report.fail(*line, error),
+            }
+        }
+    }
+}
+
 pub async fn commit_session(
     client: &HttpClient,
     session_id: &str,
@@ -349,6 +496,394 @@ pub async fn commit_session(
     Ok(())
 }
 
+pub async fn extract_session(
+    client: &HttpClient,
+    session_id: &str,
+    output_format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let path = format!("/api/v1/sessions/{}/extract", url_encode(session_id));
+    let response: Value = client.post(&path, &json!({})).await?;
+    if output_format.is_json() {
+        output_success(&response, output_format, compact);
+    } else {
+        output_success(extracted_memory_rows(&response), output_format, compact);
+    }
+    Ok(())
+}
+
+/// Reduce extracted memory contexts to what a reader needs to find them again.
+fn extracted_memory_rows(response: &Value) -> Value {
+    let rows = response
+        .as_array()
+        .map(|memories| {
+            memories
+                .iter()
+                .map(|memory| {
+                    json!({
+                        "uri": memory.get("uri").cloned().unwrap_or_default(),
+                        "category": memory.get("category").cloned().unwrap_or_default(),
+                        "abstract": memory.get("abstract").cloned().unwrap_or_default(),
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    Value::Array(rows)
+}
+
+/// The batch endpoint accepts at most this many messages per request.
+const IMPORT_BATCH_SIZE: usize = 100;
+
+/// Message fields that survive a JSONL export/import round trip.
+const TRANSCRIPT_RECORD_FIELDS: &[&str] = &[
+    "role",
+    "parts",
+    "peer_id",
+    "created_at",
+    "turn_id",
+    "message_kind",
+    "source_message_ids",
+];
+
+pub async fn export_session(client: &HttpClient, session_id: &str, format: &str) -> Result<()> {
+    let session_uri = session_uri(client, session_id).await?;
+    let records = session_message_records(client, &session_uri).await?;
+    println!("{}", render_transcript(&records, format));
+    Ok(())
+}
+
+async fn session_uri(client: &HttpClient, session_id: &str) -> Result<String> {
+    let path = format!("/api/v1/sessions/{}", url_encode(session_id));
+    let session: Value = client.get(&path, &[]).await?;
+    session
+        .get("uri")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| Error::api(format!("Session {session_id} has no uri")))
+}
+
+/// Follow a session's live messages. The server has no push channel for
+/// session messages, so this polls `messages.jsonl` and prints records whose
+/// id has not been seen yet; a commit that archives the file is harmless.
+pub async fn tail_session(
+    client: &HttpClient,
+    session_id: &str,
+    lines: usize,
+    interval: f64,
+    output_format: OutputFormat,
+) -> Result<()> {
+    if !interval.is_finite() || interval <= 0.0 {
+        return Err(Error::Client(
+            "--interval must be a positive number of seconds".to_string(),
+        ));
+    }
+    let messages_uri = format!("{}/messages.jsonl", session_uri(client, session_id).await?);
+    let mut seen = HashSet::new();
+
+    let initial = read_optional(client, &messages_uri)
+        .await?
+        .map(|content| parse_jsonl_records(&content))
+        .unwrap_or_default();
+    let first_shown = initial.len().saturating_sub(lines);
+    for (index, record) in initial.iter().enumerate() {
+        seen.insert(tail_record_key(record));
+        if index >= first_shown {
+            print_tail_record(record, output_format);
+        }
+    }
+
+    loop {
+        tokio::time::sleep(Duration::from_secs_f64(interval)).await;
+        let records = match read_optional(client, &messages_uri).await {
+            Ok(content) => content
+                .map(|content| parse_jsonl_records(&content))
+                .unwrap_or_default(),
+            Err(error) => {
+                eprintln!("{} {error}; retrying", theme::warning("warning:"));
+                continue;
+            }
+        };
+        for record in &records {
+            if seen.insert(tail_record_key(record)) {
+                print_tail_record(record, output_format);
+            }
+        }
+    }
+}
+
+fn tail_record_key(record: &Value) -> String {
+    record
+        .get("id")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| record.to_string())
+}
+
+fn print_tail_record(record: &Value, output_format: OutputFormat) {
+    if output_format.is_json() {
+        println!("{record}");
+    } else {
+        println!("{}", render_tail_record(record));
+    }
+    let _ = std::io::stdout().flush();
+}
+
+fn render_tail_record(record: &Value) -> String {
+    let time = record
+        .get("created_at")
+        .and_then(Value::as_str)
+        .and_then(|created_at| created_at.split('T').nth(1))
+        .map(|time| time.get(..8).unwrap_or(time))
+        .unwrap_or("--:--:--");
+    let role = record.get("role").and_then(Value::as_str).unwrap_or("?");
+    let text = record.get("parts").map(parts_text).unwrap_or_default();
+    format!(
+        "{} {} {}",
+        theme::muted(time),
+        theme::heading(format!("{role:<9}")).bold(),
+        theme::body(text)
+    )
+}
+
+/// Stored messages of a session in chronological order: archived history
+/// first, then the live `messages.jsonl`.
+async fn session_message_records(client: &HttpClient, session_uri: &str) -> Result<Vec<Value>> {
+    let history = client
+        .ls(
+            &format!("{session_uri}/history"),
+            false,
+            false,
+            "original",
+            0,
+            false,
+            100_000,
+        )
+        .await
+        .unwrap_or_default();
+    let mut archives: Vec<String> = history
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.get("isDir").and_then(Value::as_bool).unwrap_or(false))
+        .filter_map(|entry| entry.get("uri").and_then(Value::as_str))
+        .filter(|uri| {
+            uri.rsplit('/')
+                .next()
+                .is_some_and(|name| name.starts_with("archive_"))
+        })
+        .map(str::to_string)
+        .collect();
+    archives.sort();
+
+    let mut records = Vec::new();
+    for archive in archives {
+        // Archives still being written may not have their messages yet.
+        if let Some(content) = read_optional(client, &format!("{archive}/messages.jsonl")).await? {
+            records.extend(parse_jsonl_records(&content));
+        }
+    }
+    if let Some(content) = read_optional(client, &format!("{session_uri}/messages.jsonl")).await? {
+        records.extend(parse_jsonl_records(&content));
+    }
+    Ok(records)
+}
+
+async fn read_optional(client: &HttpClient, uri: &str) -> Result<Option<String>> {
+    match client.read(uri).await {
+        Ok(content) => Ok(Some(content)),
+        Err(Error::Api { code, status, .. })
+            if code.as_deref() == Some("NOT_FOUND") || status == Some(404) =>
+        {
+            Ok(None)
+        }
+        Err(error) => Err(error),
+    }
+}
+
+fn parse_jsonl_records(content: &str) -> Vec<Value> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(Value::is_object)
+        .collect()
+}
+
+fn render_transcript(records: &[Value], format: &str) -> String {
+    match format {
+        "openai" => {
+            let messages: Vec<Value> = records
+                .iter()
+                .filter_map(|record| {
+                    let role = record.get("role").and_then(Value::as_str)?;
+                    let content = parts_text(record.get("parts")?);
+                    (!content.is_empty()).then(|| json!({"role": role, "content": content}))
+                })
+                .collect();
+            serde_json::to_string_pretty(&json!({ "messages": messages })).unwrap_or_default()
+        }
+        _ => records
+            .iter()
+            .map(|record| transcript_record(record).to_string())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+fn transcript_record(record: &Value) -> Value {
+    let mut kept = serde_json::Map::new();
+    for field in TRANSCRIPT_RECORD_FIELDS {
+        if let Some(value) = record.get(*field).filter(|value| !value.is_null()) {
+            kept.insert((*field).to_string(), value.clone());
+        }
+    }
+    Value::Object(kept)
+}
+
+/// Join the text of OpenViking parts or OpenAI content parts.
+fn parts_text(parts: &Value) -> String {
+    parts
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|part| part.get("type").and_then(Value::as_str) == Some("text"))
+        .filter_map(|part| part.get("text").and_then(Value::as_str))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct ImportedTranscript {
+    messages: Vec<Value>,
+    skipped: usize,
+}
+
+/// Parse a transcript file. Accepts OpenViking JSONL exports, OpenAI chat
+/// payloads (`{"messages": [...]}` or a bare array, as JSON or JSONL) and
+/// LangChain message dumps (`type: human|ai`).
+fn parse_transcript(input: &str) -> Result<ImportedTranscript> {
+    let items: Vec<Value> = match serde_json::from_str::<Value>(input.trim()) {
+        Ok(Value::Array(items)) => items,
+        Ok(Value::Object(object)) => match object.get("messages") {
+            Some(Value::Array(items)) => items.clone(),
+            _ => vec![Value::Object(object)],
+        },
+        _ => input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str::<Value>(line).map_err(|error| {
+                    Error::Client(format!("line {}: invalid JSON: {error}", index + 1))
+                })
+            })
+            .collect::<Result<_>>()?,
+    };
+
+    let mut transcript = ImportedTranscript::default();
+    for item in &items {
+        match import_message(item) {
+            Some(message) => transcript.messages.push(message),
+            None => transcript.skipped += 1,
+        }
+    }
+    Ok(transcript)
+}
+
+/// Convert one transcript item into a batch-add request body. System and tool
+/// messages, and messages without text, have no session equivalent and are
+/// skipped.
+fn import_message(item: &Value) -> Option<Value> {
+    let data = item
+        .get("data")
+        .filter(|data| data.is_object())
+        .unwrap_or(item);
+    let role = item
+        .get("role")
+        .or_else(|| item.get("type"))
+        .and_then(Value::as_str)
+        .and_then(|role| match role {
+            "user" | "human" => Some("user"),
+            "assistant" | "ai" => Some("assistant"),
+            _ => None,
+        })?;
+
+    if item.get("parts").is_some_and(Value::is_array) {
+        let mut record = transcript_record(item);
+        record["role"] = json!(role);
+        return Some(record);
+    }
+
+    let content = match data.get("content")? {
+        Value::String(text) => text.clone(),
+        parts @ Value::Array(_) => parts_text(parts),
+        _ => return None,
+    };
+    (!content.trim().is_empty()).then(|| message_body(role, &content))
+}
+
+pub async fn import_session(
+    client: &HttpClient,
+    file: &str,
+    session_id: Option<&str>,
+    output_format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let input = std::fs::read_to_string(file)
+        .map_err(|e| Error::Client(format!("Failed to read {file}: {e}")))?;
+    let mut transcript = parse_transcript(&input)?;
+    if transcript.messages.is_empty() {
+        return Err(Error::Client(format!(
+            "No user or assistant messages found in {file}"
+        )));
+    }
+
+    let session_id = match session_id {
+        Some(session_id) => session_id.to_string(),
+        None => {
+            let response: Value = client.post("/api/v1/sessions", &json!({})).await?;
+            response["session_id"]
+                .as_str()
+                .ok_or_else(|| Error::api("Failed to get session_id from new session response"))?
+                .to_string()
+        }
+    };
+
+    for (index, message) in transcript.messages.iter_mut().enumerate() {
+        crate::redact::value(&format!("{file}: message {}", index + 1), message);
+    }
+
+    let path = format!(
+        "/api/v1/sessions/{}/messages/batch",
+        url_encode(&session_id)
+    );
+    tracing::info!(
+        session_id = %session_id,
+        file,
+        messages = transcript.messages.len(),
+        skipped = transcript.skipped,
+        "importing transcript"
+    );
+    let mut imported = 0;
+    for batch in transcript.messages.chunks(IMPORT_BATCH_SIZE) {
+        let _: Value = client.post(&path, &json!({ "messages": batch })).await?;
+        imported += batch.len();
+        tracing::info!(session_id = %session_id, imported, "message batch imported");
+    }
+
+    output_success(
+        json!({
+            "session_id": session_id,
+            "imported": transcript.messages.len(),
+            "skipped": transcript.skipped,
+        }),
+        output_format,
+        compact,
+    );
+    Ok(())
+}
+
 /// Add memory in one shot: creates a session, adds messages, and commits.
 ///
 /// Input can be:
@@ -405,7 +940,7 @@ fn extract_profile_lines(value: &serde_json::Value) -> Vec<serde_json::Value> {
         .unwrap_or_default()
 }
 
-fn url_encode(s: &str) -> String {
+pub(crate) fn url_encode(s: &str) -> String {
     // Simple URL encoding for session IDs
     s.replace('/', "%2F")
         .replace(':', "%3A")
@@ -414,7 +949,10 @@ fn url_encode(s: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_messages, render_session_get_for_table};
+    use super::{
+        extracted_memory_rows, parse_message_line, parse_messages, parse_transcript,
+        render_session_get_for_table, render_tail_record, render_transcript,
+    };
     use crate::error::Error;
     use serde_json::json;
 
@@ -444,6 +982,151 @@ mod tests {
         }
     }
 
+    #[test]
+    fn extracted_memory_rows_keep_uri_category_and_abstract() {
+        let rows = extracted_memory_rows(&json!([{
+            "id": "m1",
+            "uri": "viking://user/alice/memories/preferences/editor.md",
+            "category": "preferences",
+            "abstract": "Prefers vim keybindings",
+            "vector": [0.1, 0.2]
+        }]));
+
+        assert_eq!(
+            rows,
+            json!([{
+                "uri": "viking://user/alice/memories/preferences/editor.md",
+                "category": "preferences",
+                "abstract": "Prefers vim keybindings"
+            }])
+        );
+    }
+
+    #[test]
+    fn transcript_jsonl_export_round_trips_through_import() {
+        let records = vec![
+            json!({
+                "id": "msg_1",
+                "role": "user",
+                "parts": [{"type": "text", "text": "hi"}],
+                "created_at": "2026-05-26T09:53:03.661Z",
+                "peer_id": null
+            }),
+            json!({
+                "id": "msg_2",
+                "role": "assistant",
+                "parts": [{"type": "text", "text": "hello"}],
+                "created_at": "2026-05-26T09:53:04.000Z"
+            }),
+        ];
+
+        let exported = render_transcript(&records, "jsonl");
+        let imported = parse_transcript(&exported).expect("jsonl should parse");
+
+        assert_eq!(imported.skipped, 0);
+        assert_eq!(
+            imported.messages[0],
+            json!({
+                "role": "user",
+                "parts": [{"type": "text", "text": "hi"}],
+                "created_at": "2026-05-26T09:53:03.661Z"
+            })
+        );
+        assert_eq!(imported.messages.len(), 2);
+    }
+
+    #[test]
+    fn transcript_openai_export_flattens_text_parts() {
+        let records = vec![json!({
+            "role": "assistant",
+            "parts": [
+                {"type": "text", "text": "line one"},
+                {"type": "context", "uri": "viking://resources/a"},
+                {"type": "text", "text": "line two"}
+            ]
+        })];
+
+        let exported: serde_json::Value =
+            serde_json::from_str(&render_transcript(&records, "openai")).expect("json");
+
+        assert_eq!(
+            exported,
+            json!({"messages": [{"role": "assistant", "content": "line one\nline two"}]})
+        );
+    }
+
+    #[test]
+    fn transcript_import_accepts_openai_and_langchain_messages() {
+        let openai = parse_transcript(
+            r#"{"messages": [
+                {"role": "system", "content": "be brief"},
+                {"role": "user", "content": [{"type": "text", "text": "hi"}]},
+                {"role": "assistant", "content": "hello"}
+            ]}"#,
+        )
+        .expect("openai payload should parse");
+        assert_eq!(openai.skipped, 1);
+        assert_eq!(
+            openai.messages,
+            vec![
+                json!({"role": "user", "content": "hi"}),
+                json!({"role": "assistant", "content": "hello"}),
+            ]
+        );
+
+        let langchain = parse_transcript(
+            "{\"type\": \"human\", \"data\": {\"content\": \"q\"}}\n{\"type\": \"ai\", \"content\": \"a\"}\n",
+        )
+        .expect("langchain jsonl should parse");
+        assert_eq!(
+            langchain.messages,
+            vec![
+                json!({"role": "user", "content": "q"}),
+                json!({"role": "assistant", "content": "a"}),
+            ]
+        );
+    }
+
+    #[test]
+    fn transcript_import_reports_invalid_jsonl_line() {
+        let error = parse_transcript("{\"role\": \"user\", \"content\": \"ok\"}\nnot json\n")
+            .expect_err("broken line should fail");
+
+        match error {
+            Error::Client(message) => assert!(message.starts_with("line 2: invalid JSON")),
+            other => panic!("expected client error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn tail_record_shows_time_role_and_text() {
+        let rendered = strip_ansi(&render_tail_record(&json!({
+            "id": "msg_1",
+            "role": "assistant",
+            "parts": [{"type": "text", "text": "done"}],
+            "created_at": "2026-05-26T09:53:03.661Z"
+        })));
+
+        assert_eq!(rendered, "09:53:03 assistant done");
+    }
+
+    #[test]
+    fn message_lines_report_what_is_wrong() {
+        assert_eq!(
+            parse_message_line(r#"{"role": "user", "content": "hi"}"#),
+            Ok(json!({"role": "user", "content": "hi"}))
+        );
+        assert!(
+            parse_message_line("{role: user}")
+                .expect_err("bad json")
+                .starts_with("invalid JSON")
+        );
+        assert_eq!(
+            parse_message_line(r#"{"role": "system", "content": "x"}"#),
+            Err("expected a user or assistant message with text content".to_string())
+        );
+    }
+
     #[test]
     fn add_memory_ok_output_can_include_profile_section() {
         let result = json!({
@@ -86,6 +86,7 @@ pub async fn add(
     list_only: bool,
     wait: bool,
     yes: bool,
+    validate: bool,
     show_progress: bool,
     verbose: bool,
     output_format: OutputFormat,
@@ -101,6 +102,11 @@ pub async fn add(
     if targets.is_empty() {
         return Err(Error::Client("No skills to install.".to_string()));
     }
+    if validate {
+        for target in &targets {
+            validate_add_target(target)?;
+        }
+    }
     if targets.len() > 1 && !yes {
         let names = targets.iter().map(skill_target_label).collect::<Vec<_>>();
         if !confirm_action("Install", &names)? {
@@ -163,10 +169,327 @@ pub async fn list(
     parent: Option<&str>,
 ) -> Result<()> {
     let result = client.skills_list(node_limit, parent).await?;
-    super::search::output_skills_list_results(&result, output_format, compact);
+    if output_format.is_json() {
+        output_success(result, output_format, compact);
+    } else {
+        output_success(installed_skill_rows(&result), output_format, compact);
+    }
     Ok(())
 }
 
+/// One table row per installed skill: what it is called, where it lives, and
+/// the description and tags an agent matches against when picking it.
+fn installed_skill_rows(result: &Value) -> Value {
+    let rows: Vec<Value> = result
+        .get("skills")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|skill| {
+            let uri = skill
+                .get("root_uri")
+                .or_else(|| skill.get("uri"))
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let tags = match skill.get("tags") {
+                Some(Value::Array(items)) => items
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                Some(Value::String(tags)) => tags.clone(),
+                _ => String::new(),
+            };
+            json!({
+                "name": skill.get("name").and_then(Value::as_str).unwrap_or_default(),
+                "scope": if uri.starts_with("viking://agent/") { "agent" } else { "user" },
+                "tags": tags,
+                "description": skill.get("description").and_then(Value::as_str).unwrap_or_default(),
+            })
+        })
+        .collect();
+    Value::Array(rows)
+}
+
+/// Download an installed skill's files into `<dest>/<name>/`, so it can be
+/// edited, versioned, or installed elsewhere with `ov skills add`.
+pub async fn export(
+    client: &HttpClient,
+    name: &str,
+    dest: &str,
+    force: bool,
+    output_format: OutputFormat,
+    compact: bool,
+    parent: Option<&str>,
+) -> Result<()> {
+    let result = client
+        .skill_show(name, false, true, false, Some(0), parent)
+        .await?;
+    let skill_dir = Path::new(dest).join(name);
+    if skill_dir.exists() && !force {
+        return Err(Error::Client(format!(
+            "'{}' already exists; pass --force to overwrite it.",
+            skill_dir.display()
+        )));
+    }
+
+    let mut written = Vec::new();
+    for file in result
+        .get("files")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        if file.get("is_dir").and_then(Value::as_bool).unwrap_or(false) {
+            continue;
+        }
+        let (Some(uri), Some(relative)) = (
+            file.get("uri").and_then(Value::as_str),
+            file.get("path").and_then(Value::as_str),
+        ) else {
+            continue;
+        };
+        let Some(relative) = export_relative_path(relative) else {
+            continue;
+        };
+        let target = skill_dir.join(&relative);
+        if let Some(dir) = target.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&target, client.get_bytes(uri).await?)?;
+        written.push(path_to_string(&relative));
+    }
+    if !written.iter().any(|path| path == "SKILL.md") {
+        return Err(Error::Client(format!(
+            "Skill '{name}' has no SKILL.md to export."
+        )));
+    }
+
+    output_success(
+        json!({
+            "name": name,
+            "path": path_to_string(&skill_dir),
+            "files": written,
+        }),
+        output_format,
+        compact,
+    );
+    Ok(())
+}
+
+/// Where an exported file goes under the skill directory. Derived files the
+/// server regenerates on install, and paths that would escape the directory,
+/// are skipped.
+fn export_relative_path(path: &str) -> Option<PathBuf> {
+    const DERIVED: &[&str] = &[
+        ".abstract.md",
+        ".overview.md",
+        ".relations.json",
+        ".meta.json",
+    ];
+    let path = Path::new(path);
+    let file_name = path.file_name()?.to_str()?;
+    if DERIVED.contains(&file_name) {
+        return None;
+    }
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_)))
+        .then(|| path.to_path_buf())
+}
+
+/// The skill `init` scaffolds, and where.
+pub struct InitOptions<'a> {
+    pub name: &'a str,
+    pub dest: &'a str,
+    pub description: Option<&'a str>,
+    pub tags: &'a [String],
+    /// A resource subtree whose files become the skill's references.
+    pub from_uri: Option<&'a str>,
+    /// Overwrite an existing skill directory.
+    pub force: bool,
+}
+
+/// Generate a skeleton skill directory at `<dest>/<name>/`: a SKILL.md with
+/// the name, description and tags filled in, and a `references/` directory.
+/// With `from_uri`, the files under that resource subtree are copied into
+/// `references/` and listed in SKILL.md; otherwise an example file is written.
+pub async fn init(
+    client: &HttpClient,
+    options: InitOptions<'_>,
+    output_format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let InitOptions {
+        name,
+        dest,
+        description,
+        tags,
+        from_uri,
+        force,
+    } = options;
+    if name.is_empty() || name.chars().count() > 64 || !is_valid_skill_name(name) {
+        return Err(Error::Client(format!(
+            "Invalid skill name '{name}'; use at most 64 letters, numbers, underscores, and hyphens."
+        )));
+    }
+    let skill_dir = Path::new(dest).join(name);
+    if skill_dir.exists() && !force {
+        return Err(Error::Client(format!(
+            "'{}' already exists; pass --force to overwrite it.",
+            skill_dir.display()
+        )));
+    }
+
+    let mut files: Vec<(PathBuf, Vec<u8>)> = Vec::new();
+    let mut references = Vec::new();
+    let mut description = description.map(str::to_string);
+    match from_uri {
+        Some(uri) => {
+            let listing = client
+                .ls(uri, false, true, "agent", 256, false, 1000)
+                .await?;
+            for entry in listing.as_array().into_iter().flatten() {
+                if entry.get("isDir").and_then(Value::as_bool).unwrap_or(false) {
+                    continue;
+                }
+                let (Some(file_uri), Some(relative)) = (
+                    entry.get("uri").and_then(Value::as_str),
+                    entry.get("rel_path").and_then(Value::as_str),
+                ) else {
+                    continue;
+                };
+                let Some(relative) = export_relative_path(relative) else {
+                    continue;
+                };
+                let path = Path::new("references").join(relative);
+                let summary = entry
+                    .get("abstract")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                references.push((path_to_string(&path), first_line(summary)));
+                files.push((path, client.get_bytes(file_uri).await?));
+            }
+            if files.is_empty() {
+                return Err(Error::Client(format!(
+                    "No files under {uri} to copy into the skill."
+                )));
+            }
+            if description.is_none() {
+                let summary = first_line(&client.abstract_content(uri).await.unwrap_or_default());
+                description = (!summary.is_empty()).then_some(summary);
+            }
+        }
+        None => {
+            let path = Path::new("references").join("example.md");
+            references.push((
+                path_to_string(&path),
+                "TODO: replace with the material this skill relies on".to_string(),
+            ));
+            files.push((path, EXAMPLE_REFERENCE.as_bytes().to_vec()));
+        }
+    }
+
+    let skill_md = scaffold_skill_md(name, description.as_deref(), tags, &references)?;
+    files.insert(0, (PathBuf::from("SKILL.md"), skill_md.into_bytes()));
+    let mut written = Vec::new();
+    for (relative, content) in files {
+        let target = skill_dir.join(&relative);
+        if let Some(dir) = target.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&target, content)?;
+        written.push(path_to_string(&relative));
+    }
+
+    output_success(
+        json!({
+            "name": name,
+            "path": path_to_string(&skill_dir),
+            "from_uri": from_uri,
+            "files": written,
+        }),
+        output_format,
+        compact,
+    );
+    Ok(())
+}
+
+const EXAMPLE_REFERENCE: &str = "# Example\n\n\
+Replace this file with reference material the skill needs: templates, \
+checklists, sample inputs and outputs. Files under `references/` are installed \
+with the skill and can be linked from SKILL.md.\n";
+
+/// The SKILL.md of a new skill. `references` pairs each file path under the
+/// skill directory with a one-line summary.
+fn scaffold_skill_md(
+    name: &str,
+    description: Option<&str>,
+    tags: &[String],
+    references: &[(String, String)],
+) -> Result<String> {
+    let mut meta = serde_yaml::Mapping::new();
+    meta.insert("name".into(), name.into());
+    meta.insert(
+        "description".into(),
+        description
+            .unwrap_or("TODO: what this skill does and when an agent should use it")
+            .into(),
+    );
+    let tags: Vec<serde_yaml::Value> = tags
+        .iter()
+        .map(|tag| tag.trim())
+        .filter(|tag| !tag.is_empty())
+        .map(Into::into)
+        .collect();
+    if !tags.is_empty() {
+        meta.insert("tags".into(), serde_yaml::Value::Sequence(tags));
+    }
+    let frontmatter = serde_yaml::to_string(&meta)
+        .map_err(|e| Error::Client(format!("Failed to render SKILL.md frontmatter: {e}")))?;
+
+    let title = name
+        .split(['-', '_'])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let resources = references
+        .iter()
+        .map(|(path, summary)| {
+            if summary.is_empty() {
+                format!("- [{path}]({path})")
+            } else {
+                format!("- [{path}]({path}): {summary}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(format!(
+        "---\n{frontmatter}---\n\n# {title}\n\n\
+         ## When to use\n\n\
+         - TODO: the requests or situations this skill is meant for.\n\n\
+         ## Instructions\n\n\
+         1. TODO: the steps an agent should follow.\n\n\
+         ## Resources\n\n\
+         {resources}\n"
+    ))
+}
+
+fn first_line(text: &str) -> String {
+    let line = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    line.chars().take(1024).collect()
+}
+
 pub async fn show(
     client: &HttpClient,
     name: &str,
@@ -374,27 +697,8 @@ pub async fn validate(
     compact: bool,
 ) -> Result<()> {
     let result = validate_skill_path(path, strict)?;
-    if result
-        .get("valid")
-        .and_then(Value::as_bool)
-        .is_some_and(|valid| !valid)
-    {
-        let errors = result
-            .get("errors")
-            .and_then(Value::as_array)
-            .map(|items| {
-                items
-                    .iter()
-                    .filter_map(|item| {
-                        item.as_str()
-                            .or_else(|| item.get("message").and_then(Value::as_str))
-                    })
-                    .collect::<Vec<_>>()
-                    .join("; ")
-            })
-            .filter(|text| !text.is_empty())
-            .unwrap_or_else(|| "Skill validation failed".to_string());
-        if matches!(output_format, OutputFormat::Json) {
+    if let Some(errors) = validation_failure(&result) {
+        if output_format.is_json() {
             output_success(result, output_format, compact);
         }
         return Err(Error::Client(errors));
@@ -403,6 +707,63 @@ pub async fn validate(
     Ok(())
 }
 
+/// The joined error messages of a failed validation result.
+fn validation_failure(result: &Value) -> Option<String> {
+    if result.get("valid").and_then(Value::as_bool) != Some(false) {
+        return None;
+    }
+    Some(
+        issue_messages(result, "errors")
+            .filter(|text| !text.is_empty())
+            .unwrap_or_else(|| "Skill validation failed".to_string()),
+    )
+}
+
+fn issue_messages(result: &Value, key: &str) -> Option<String> {
+    result.get(key).and_then(Value::as_array).map(|items| {
+        items
+            .iter()
+            .filter_map(|item| {
+                item.as_str()
+                    .or_else(|| item.get("message").and_then(Value::as_str))
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    })
+}
+
+/// Check a skill against the same rules as `ov skills validate` before it is
+/// uploaded, so a malformed SKILL.md fails fast instead of on the server.
+/// Archives and other files the server unpacks itself are left to it.
+fn validate_add_target(target: &AddTarget) -> Result<()> {
+    let path = Path::new(&target.data);
+    let result = if path.is_dir() || path.file_name().is_some_and(|name| name == "SKILL.md") {
+        validate_skill_path(&target.data, false)?
+    } else if path.is_file() {
+        if path.extension().is_none_or(|ext| ext != "md") {
+            return Ok(());
+        }
+        let content = std::fs::read_to_string(path)?;
+        validate_skill_content(&content, false, &target.data, "")
+    } else {
+        validate_skill_content(&target.data, false, "<inline>", "")
+    };
+    let label = if path.exists() {
+        skill_target_label(target)
+    } else {
+        "<inline>".to_string()
+    };
+    if let Some(errors) = validation_failure(&result) {
+        return Err(Error::Client(format!(
+            "Skill '{label}' is invalid: {errors}. Fix it, or pass --no-validate to let the server decide."
+        )));
+    }
+    if let Some(warnings) = issue_messages(&result, "warnings").filter(|text| !text.is_empty()) {
+        eprintln!("{}", theme::muted(format!("Skill '{label}': {warnings}")));
+    }
+    Ok(())
+}
+
 fn validate_skill_path(path: &str, strict: bool) -> Result<Value> {
     let path_obj = Path::new(path);
     if !path_obj.exists() {
@@ -688,7 +1049,7 @@ fn push_mode_issue(
 }
 
 fn output_skill_validate_success(result: &Value, output_format: OutputFormat, compact: bool) {
-    if matches!(output_format, OutputFormat::Json) {
+    if output_format.is_json() {
         output_success(result, output_format, compact);
         return;
     }
@@ -1762,7 +2123,7 @@ fn output_message_result(
     compact: bool,
 ) {
     match output_format {
-        OutputFormat::Json => output_success(result, output_format, compact),
+        OutputFormat::Json | OutputFormat::Ndjson => output_success(result, output_format, compact),
         OutputFormat::Table => {
             println!(
                 "{}",
@@ -1775,14 +2136,43 @@ fn output_message_result(
 #[cfg(test)]
 mod tests {
     use super::{
-        PreparedSource, RenderedSkillSelectRegion, SkillSourceRecord, SourceOrigin,
-        filter_skill_show_level, parse_github_tree_source, parse_skill_md,
-        prepare_source_from_git_record, render_skill_show_for_table, rendered_skill_select_rows,
-        resolve_add_targets, update_target_from_record,
+        AddTarget, PreparedSource, RenderedSkillSelectRegion, SkillSourceRecord, SourceOrigin,
+        export_relative_path, filter_skill_show_level, installed_skill_rows,
+        parse_github_tree_source, parse_skill_md, prepare_source_from_git_record,
+        render_skill_show_for_table, rendered_skill_select_rows, resolve_add_targets,
+        scaffold_skill_md, update_target_from_record, validate_add_target, validate_skill_content,
     };
     use serde_json::json;
     use std::path::Path;
 
+    #[test]
+    fn scaffolded_skill_md_passes_strict_validation() {
+        let content = scaffold_skill_md(
+            "code-review",
+            Some("Review a diff: check tests first"),
+            &["review".to_string(), " ".to_string()],
+            &[(
+                "references/checklist.md".to_string(),
+                "What to look for".to_string(),
+            )],
+        )
+        .unwrap();
+        let result = validate_skill_content(&content, true, "SKILL.md", "code-review");
+        assert_eq!(result["valid"], true, "{result}");
+        assert_eq!(result["description"], "Review a diff: check tests first");
+        assert_eq!(result["tags"], json!(["review"]));
+        assert!(content.contains("# Code Review\n"));
+        assert!(
+            content
+                .contains("- [references/checklist.md](references/checklist.md): What to look for")
+        );
+
+        let placeholder = scaffold_skill_md("notes", None, &[], &[]).unwrap();
+        let result = validate_skill_content(&placeholder, true, "SKILL.md", "notes");
+        assert_eq!(result["valid"], true, "{result}");
+        assert!(!placeholder.contains("tags:"));
+    }
+
     #[test]
     fn skill_show_table_renders_complete_skill_information() {
         let result = json!({
@@ -2038,4 +2428,77 @@ mod tests {
         assert_eq!(rendered_skill_select_rows(&lines, 30), 3);
         assert_eq!(region.rows_to_clear(30), 3);
     }
+
+    #[test]
+    fn installed_skill_rows_show_scope_tags_and_description() {
+        let result = json!({
+            "skills": [
+                {
+                    "name": "code-review",
+                    "root_uri": "viking://agent/skills/code-review",
+                    "description": "Use when reviewing a pull request.",
+                    "tags": ["review", "git"],
+                },
+                {
+                    "name": "notes",
+                    "root_uri": "viking://user/default/skills/notes",
+                    "description": "",
+                },
+            ]
+        });
+
+        assert_eq!(
+            installed_skill_rows(&result),
+            json!([
+                {
+                    "name": "code-review",
+                    "scope": "agent",
+                    "tags": "review, git",
+                    "description": "Use when reviewing a pull request.",
+                },
+                {"name": "notes", "scope": "user", "tags": "", "description": ""},
+            ])
+        );
+    }
+
+    #[test]
+    fn export_skips_derived_files_and_escaping_paths() {
+        assert_eq!(
+            export_relative_path("scripts/run.sh"),
+            Some(Path::new("scripts/run.sh").to_path_buf())
+        );
+        assert_eq!(export_relative_path(".abstract.md"), None);
+        assert_eq!(export_relative_path("docs/.overview.md"), None);
+        assert_eq!(export_relative_path("../outside.md"), None);
+        assert_eq!(export_relative_path("/etc/passwd"), None);
+    }
+
+    #[test]
+    fn add_validation_rejects_skills_without_a_description() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let skill_dir = temp.path().join("code-review");
+        std::fs::create_dir(&skill_dir).expect("create skill dir");
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: code-review\n---\nReview code.\n",
+        )
+        .expect("write SKILL.md");
+        let target = |data: String| AddTarget {
+            data,
+            source: None,
+            _temp_dir: None,
+        };
+
+        let err = validate_add_target(&target(skill_dir.to_string_lossy().to_string()))
+            .expect_err("missing description should fail");
+        assert!(err.to_string().contains("description is required"));
+        assert!(err.to_string().contains("--no-validate"));
+
+        assert!(
+            validate_add_target(&target(
+                "---\nname: notes\ndescription: Take notes.\n---\nBody\n".to_string()
+            ))
+            .is_ok()
+        );
+    }
 }
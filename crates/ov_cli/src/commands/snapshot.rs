@@ -105,7 +105,7 @@ pub async fn dispatch(
 }
 
 fn print_diff(value: &Value, output_format: OutputFormat, compact: bool) {
-    if matches!(output_format, OutputFormat::Json) {
+    if output_format.is_json() {
         output_success(value, output_format, compact);
         return;
     }
@@ -128,7 +128,7 @@ fn resolve_ignore_content(content: Option<&str>, file: Option<&std::path::Path>)
 }
 
 fn print_ignore_get(value: &Value, output_format: OutputFormat, compact: bool) {
-    if matches!(output_format, OutputFormat::Json) {
+    if output_format.is_json() {
         output_success(value, output_format, compact);
         return;
     }
@@ -140,7 +140,7 @@ fn print_ignore_get(value: &Value, output_format: OutputFormat, compact: bool) {
 }
 
 fn print_commit(value: &Value, output_format: OutputFormat, compact: bool) {
-    if matches!(output_format, OutputFormat::Json) {
+    if output_format.is_json() {
         output_success(value, output_format, compact);
         return;
     }
@@ -150,7 +150,7 @@ fn print_commit(value: &Value, output_format: OutputFormat, compact: bool) {
 }
 
 fn print_restore(value: &Value, output_format: OutputFormat, compact: bool) {
-    if matches!(output_format, OutputFormat::Json) {
+    if output_format.is_json() {
         output_success(value, output_format, compact);
         return;
     }
@@ -184,7 +184,7 @@ fn handle_show(
 ) -> Result<()> {
     match result {
         SnapshotShowResult::Metadata(meta) => {
-            if matches!(output_format, OutputFormat::Json) {
+            if output_format.is_json() {
                 output_success(&meta, output_format, compact);
                 return Ok(());
             }
@@ -211,7 +211,7 @@ fn handle_show(
             Ok(())
         }
         SnapshotShowResult::Blob { oid, bytes, size } => {
-            if matches!(output_format, OutputFormat::Json) {
+            if output_format.is_json() {
                 let envelope = serde_json::json!({"oid": oid, "size": size});
                 output_success(&envelope, output_format, compact);
                 if let Some(path) = out_path {
@@ -243,7 +243,7 @@ fn handle_show(
 }
 
 fn print_log(value: &Value, output_format: OutputFormat, compact: bool) {
-    if matches!(output_format, OutputFormat::Json) {
+    if output_format.is_json() {
         output_success(value, output_format, compact);
         return;
     }
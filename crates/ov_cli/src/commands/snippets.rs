@@ -0,0 +1,272 @@
+//! Contextual snippets for `ov find --snippets` and `ov search --snippets`.
+//!
+//! Each hit gets the passages of its matched layer that contain the query's
+//! terms, with every match wrapped in `**` so JSON output stays plain text.
+//! Table output turns the markers into a highlight.
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+use colored::Colorize;
+use serde_json::{Value, json};
+
+use super::tokens::{Listing, row_texts, rows_mut};
+use crate::client::HttpClient;
+use crate::error::Result;
+use crate::theme;
+
+/// Wraps each matched term in a snippet.
+pub const MATCH_MARKER: &str = "**";
+/// Characters of context kept on each side of a match.
+const CONTEXT_CHARS: usize = 60;
+/// Longest a snippet grows when nearby matches merge into it.
+const MAX_SNIPPET_CHARS: usize = 240;
+const MAX_SNIPPETS: usize = 2;
+
+/// Add a `snippets` field to each hit of `value` whose text contains a term
+/// of `query`.
+pub async fn annotate(client: &HttpClient, value: &mut Value, query: &str) -> Result<()> {
+    let terms = query_terms(query);
+    if terms.is_empty() {
+        return Ok(());
+    }
+    let mut rows = Vec::new();
+    rows_mut(value, &mut rows);
+    let texts = row_texts(client, &rows, Listing::Hits).await?;
+    for (row, text) in rows.into_iter().zip(texts) {
+        let snippets = text.map(|text| snippets(&text, &terms)).unwrap_or_default();
+        if !snippets.is_empty() {
+            row.insert("snippets".to_string(), json!(snippets));
+        }
+    }
+    Ok(())
+}
+
+/// Lowercased terms of `query`, split as the server's keyword ranking splits
+/// them: one term per CJK character, otherwise runs of letters and digits.
+/// Single Latin letters match too much to be worth marking.
+fn query_terms(query: &str) -> HashSet<String> {
+    let chars: Vec<char> = query.chars().collect();
+    term_spans(&chars)
+        .into_iter()
+        .filter(|(start, end)| end - start > 1 || is_cjk(chars[*start]))
+        .map(|(start, end)| chars[start..end].iter().collect::<String>().to_lowercase())
+        .collect()
+}
+
+/// Char ranges of the terms in `chars`.
+fn term_spans(chars: &[char]) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let start = index;
+        if is_cjk(chars[index]) {
+            index += 1;
+        } else if chars[index].is_alphanumeric() {
+            while index < chars.len() && chars[index].is_alphanumeric() && !is_cjk(chars[index]) {
+                index += 1;
+            }
+        } else {
+            index += 1;
+            continue;
+        }
+        spans.push((start, index));
+    }
+    spans
+}
+
+fn is_cjk(ch: char) -> bool {
+    ('\u{4e00}'..='\u{9fff}').contains(&ch)
+}
+
+struct Window {
+    start: usize,
+    end: usize,
+    matches: Vec<(usize, usize)>,
+    terms: HashSet<String>,
+}
+
+/// The passages of `text` richest in distinct query terms, best first.
+fn snippets(text: &str, terms: &HashSet<String>) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut windows: Vec<Window> = Vec::new();
+    for (start, end) in term_spans(&chars) {
+        let term = chars[start..end].iter().collect::<String>().to_lowercase();
+        if !terms.contains(&term) {
+            continue;
+        }
+        let from = start.saturating_sub(CONTEXT_CHARS);
+        let to = (end + CONTEXT_CHARS).min(chars.len());
+        if let Some(window) = windows.last_mut()
+            && from <= window.end
+            && to - window.start <= MAX_SNIPPET_CHARS
+        {
+            window.end = to;
+            window.matches.push((start, end));
+            window.terms.insert(term);
+            continue;
+        }
+        let from = windows.last().map_or(from, |window| from.max(window.end));
+        windows.push(Window {
+            start: from,
+            end: to,
+            matches: vec![(start, end)],
+            terms: HashSet::from([term]),
+        });
+    }
+    windows.sort_by_key(|window| Reverse(window.terms.len()));
+    windows
+        .iter()
+        .take(MAX_SNIPPETS)
+        .map(|window| render_window(&chars, window))
+        .collect()
+}
+
+/// `window` as one line: whitespace collapsed, cut at word boundaries, with
+/// `…` where the text goes on and each match marked.
+fn render_window(chars: &[char], window: &Window) -> String {
+    let first = window.matches[0].0;
+    let last = window.matches[window.matches.len() - 1].1;
+    let mut start = window.start;
+    if start > 0
+        && let Some(space) = chars[start..first].iter().position(|ch| ch.is_whitespace())
+    {
+        start += space + 1;
+    }
+    let mut end = window.end;
+    if end < chars.len()
+        && let Some(space) = chars[last..end].iter().rposition(|ch| ch.is_whitespace())
+    {
+        end = last + space;
+    }
+
+    let mut out = String::new();
+    if start > 0 {
+        out.push('…');
+    }
+    let mut matches = window.matches.iter().peekable();
+    let mut index = start;
+    while index < end {
+        if let Some(&&(match_start, match_end)) = matches.peek()
+            && match_start == index
+        {
+            out.push_str(MATCH_MARKER);
+            out.extend(&chars[match_start..match_end]);
+            out.push_str(MATCH_MARKER);
+            matches.next();
+            index = match_end;
+            continue;
+        }
+        let ch = chars[index];
+        if ch == '*' && chars.get(index + 1) == Some(&'*') {
+            // The source's own bold markers would read as matches.
+            index += 2;
+        } else if ch.is_whitespace() {
+            if !out.ends_with(' ') && !out.is_empty() {
+                out.push(' ');
+            }
+            index += 1;
+        } else {
+            out.push(ch);
+            index += 1;
+        }
+    }
+    let mut out = out.trim_end().to_string();
+    if end < chars.len() {
+        out.push('…');
+    }
+    out
+}
+
+/// One wrapped line of a snippet with its marked matches highlighted.
+/// `inside` carries a match that wrapping split across lines.
+pub(super) fn highlight_line(line: &str, inside: &mut bool) -> String {
+    let mut out = String::new();
+    for (index, part) in line.split(MATCH_MARKER).enumerate() {
+        if index > 0 {
+            *inside = !*inside;
+        }
+        if part.is_empty() {
+            continue;
+        }
+        let styled = if *inside {
+            theme::warning(part).bold()
+        } else {
+            theme::body(part)
+        };
+        out.push_str(&styled.to_string());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snippets_mark_query_terms_in_context() {
+        let text = "Intro.\n\nThe   agreement may be ended under the **termination** clause \
+                    with thirty days notice. Nothing else.";
+
+        assert_eq!(
+            snippets(text, &query_terms("Termination notice")),
+            vec![
+                "Intro. The agreement may be ended under the **termination** clause with \
+                 thirty days **notice**. Nothing else."
+                    .to_string()
+            ]
+        );
+        assert!(snippets(text, &query_terms("indemnity")).is_empty());
+        assert!(query_terms("a").is_empty());
+        assert_eq!(
+            query_terms("上下文 v2"),
+            HashSet::from(["上", "下", "文", "v2"].map(String::from))
+        );
+    }
+
+    #[test]
+    fn snippets_cut_long_text_at_words_and_prefer_more_terms() {
+        let filler = "lorem ipsum dolor ".repeat(20);
+        let text = format!("{filler}cache only {filler}cache eviction policy {filler}");
+
+        let found = snippets(&text, &query_terms("cache eviction"));
+
+        assert_eq!(found.len(), 2);
+        assert!(found[0].contains("**cache** **eviction** policy"));
+        assert!(found[0].starts_with("…") && found[0].ends_with("…"));
+        assert!(found[1].contains("**cache** only"));
+    }
+
+    #[test]
+    fn highlight_line_carries_a_match_across_wrapped_lines() {
+        let mut inside = false;
+        assert_eq!(
+            strip_ansi(&highlight_line("the **two word", &mut inside)),
+            "the two word"
+        );
+        assert!(inside);
+        assert_eq!(
+            strip_ansi(&highlight_line("match** after", &mut inside)),
+            "match after"
+        );
+        assert!(!inside);
+    }
+
+    fn strip_ansi(input: &str) -> String {
+        let mut output = String::with_capacity(input.len());
+        let mut chars = input.chars().peekable();
+        while let Some(ch) = chars.next() {
+            if ch == '\u{1b}' && chars.peek() == Some(&'[') {
+                chars.next();
+                for next in chars.by_ref() {
+                    if next == 'm' {
+                        break;
+                    }
+                }
+            } else {
+                output.push(ch);
+            }
+        }
+        output
+    }
+}
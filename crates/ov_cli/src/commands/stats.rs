@@ -0,0 +1,277 @@
+//! `ov stats [uri]`: how much is stored and what it costs.
+//!
+//! Files, directories, and bytes come from one recursive `ls`, grouped by file
+//! extension and, with `--by-prefix`, by the child of `uri` they sit under.
+//! Vector counts come from the debug vector API, and token usage from the
+//! server's usage report for the last `--days` days; token usage covers the
+//! whole workspace, not just `uri`. A figure the server does not report is
+//! `null`.
+
+use std::collections::BTreeMap;
+
+use chrono::{Duration, Local};
+use serde_json::{Value, json};
+
+use crate::client::HttpClient;
+use crate::error::Result;
+use crate::output::{OutputFormat, output_success};
+use crate::theme;
+
+pub struct StatsOptions<'a> {
+    pub uri: &'a str,
+    pub by_prefix: bool,
+    pub days: u32,
+    pub node_limit: i32,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Tally {
+    files: u64,
+    bytes: u64,
+}
+
+impl Tally {
+    fn add(&mut self, bytes: u64) {
+        self.files += 1;
+        self.bytes += bytes;
+    }
+}
+
+#[derive(Debug, Default)]
+struct Inventory {
+    directories: u64,
+    total: Tally,
+    types: BTreeMap<String, Tally>,
+    prefixes: BTreeMap<String, Tally>,
+}
+
+/// A file's extension, lowercased, or `(none)`.
+fn file_type(uri: &str) -> String {
+    let name = uri.rsplit('/').next().unwrap_or(uri);
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() && !ext.is_empty() => ext.to_lowercase(),
+        _ => "(none)".to_string(),
+    }
+}
+
+/// The child of `base` that `uri` sits under, or `base` for its own files.
+fn prefix_of(base: &str, uri: &str) -> String {
+    let base = if base.ends_with("://") {
+        base
+    } else {
+        base.trim_end_matches('/')
+    };
+    let rest = uri
+        .strip_prefix(base)
+        .map(|rest| rest.trim_start_matches('/'))
+        .unwrap_or_default();
+    match rest.split_once('/') {
+        Some((child, _)) if base.ends_with('/') => format!("{base}{child}"),
+        Some((child, _)) => format!("{base}/{child}"),
+        None => base.to_string(),
+    }
+}
+
+fn take_inventory(base: &str, entries: &[Value]) -> Inventory {
+    let mut inventory = Inventory::default();
+    for entry in entries {
+        let Some(uri) = entry["uri"].as_str() else {
+            continue;
+        };
+        if entry["isDir"].as_bool().unwrap_or(false) {
+            inventory.directories += 1;
+            continue;
+        }
+        let bytes = entry["size"].as_u64().unwrap_or(0);
+        inventory.total.add(bytes);
+        inventory
+            .types
+            .entry(file_type(uri))
+            .or_default()
+            .add(bytes);
+        inventory
+            .prefixes
+            .entry(prefix_of(base, uri))
+            .or_default()
+            .add(bytes);
+    }
+    inventory
+}
+
+/// Summed token usage for the report, or `None` when usage reporting is off
+/// or missing on the server.
+fn sum_tokens(report: &Value) -> Option<(u64, u64)> {
+    if report["enabled"] == Value::Bool(false) {
+        return None;
+    }
+    let items = report["items"].as_array()?;
+    let total = |key: &str| items.iter().filter_map(|item| item[key].as_u64()).sum();
+    Some((
+        total("embedding_input"),
+        total("vlm_input") + total("vlm_output"),
+    ))
+}
+
+async fn token_usage(client: &HttpClient, days: u32) -> Option<(u64, u64)> {
+    let today = Local::now().date_naive();
+    let start = today - Duration::days(i64::from(days.max(1)) - 1);
+    let report = client
+        .usage_tokens(
+            &start.format("%Y-%m-%d").to_string(),
+            &today.format("%Y-%m-%d").to_string(),
+        )
+        .await
+        .ok()?;
+    sum_tokens(&report)
+}
+
+fn print_table(report: &Value, compact: bool) {
+    let mut summary = report.clone();
+    let mut sections = Vec::new();
+    let mut lists = Vec::new();
+    for key in ["types", "prefixes"] {
+        if let Some(Value::Array(rows)) = summary.as_object_mut().and_then(|obj| obj.remove(key))
+            && !rows.is_empty()
+        {
+            lists.push((key, rows));
+        }
+    }
+    sections.push(
+        crate::output::render_table_with_optional_profile(&summary, compact)
+            .unwrap_or_default()
+            .trim_end()
+            .to_string(),
+    );
+    for (key, rows) in lists {
+        sections.push(format!(
+            "{key}\n{}",
+            crate::output::render_table_with_optional_profile(&Value::Array(rows), compact)
+                .unwrap_or_default()
+                .trim_end()
+        ));
+    }
+    println!("{}", sections.join("\n\n"));
+}
+
+pub async fn stats(
+    client: &HttpClient,
+    options: StatsOptions<'_>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let listing = client
+        .ls(
+            options.uri,
+            false,
+            true,
+            "original",
+            0,
+            false,
+            options.node_limit,
+        )
+        .await?;
+    let entries = listing.as_array().cloned().unwrap_or_default();
+    if entries.len() >= options.node_limit.max(0) as usize {
+        eprintln!(
+            "{} counted the first {} entries under {}; raise --node-limit for the rest",
+            theme::warning("warning:"),
+            entries.len(),
+            options.uri
+        );
+    }
+    let inventory = take_inventory(options.uri, &entries);
+    let vectors = client
+        .debug_vector_count(None, Some(options.uri.to_string()))
+        .await
+        .ok();
+    let tokens = token_usage(client, options.days).await;
+
+    let mut report = json!({
+        "uri": options.uri,
+        "files": inventory.total.files,
+        "directories": inventory.directories,
+        "bytes": inventory.total.bytes,
+        "vectors": vectors,
+        "embedding_tokens": tokens.map(|(embedding, _)| embedding),
+        "vlm_tokens": tokens.map(|(_, vlm)| vlm),
+        "token_days": options.days,
+        "types": inventory
+            .types
+            .iter()
+            .map(|(name, tally)| json!({ "type": name, "files": tally.files, "bytes": tally.bytes }))
+            .collect::<Vec<_>>(),
+    });
+    if options.by_prefix {
+        let mut prefixes = Vec::new();
+        for (prefix, tally) in &inventory.prefixes {
+            let vectors = client
+                .debug_vector_count(None, Some(prefix.clone()))
+                .await
+                .ok();
+            prefixes.push(json!({
+                "prefix": prefix,
+                "files": tally.files,
+                "bytes": tally.bytes,
+                "vectors": vectors,
+            }));
+        }
+        report["prefixes"] = Value::Array(prefixes);
+    }
+
+    if matches!(format, OutputFormat::Table) && !crate::output::table_layout_requested() {
+        print_table(&report, compact);
+    } else {
+        output_success(&report, format, compact);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inventory_groups_files_by_type_and_prefix() {
+        let entries = vec![
+            json!({"uri": "viking://resources/docs", "isDir": true}),
+            json!({"uri": "viking://resources/docs/guide.md", "isDir": false, "size": 100}),
+            json!({"uri": "viking://resources/docs/api/spec.PDF", "isDir": false, "size": 900}),
+            json!({"uri": "viking://resources/notes.md", "isDir": false, "size": 20}),
+            json!({"uri": "viking://resources/Makefile", "isDir": false, "size": 5}),
+        ];
+        let inventory = take_inventory("viking://resources/", &entries);
+        assert_eq!(inventory.directories, 1);
+        assert_eq!(
+            inventory.total,
+            Tally {
+                files: 4,
+                bytes: 1025
+            }
+        );
+        assert_eq!(
+            inventory.types["md"],
+            Tally {
+                files: 2,
+                bytes: 120
+            }
+        );
+        assert_eq!(inventory.types["pdf"].files, 1);
+        assert_eq!(inventory.types["(none)"].files, 1);
+        assert_eq!(inventory.prefixes["viking://resources/docs"].bytes, 1000);
+        assert_eq!(inventory.prefixes["viking://resources"].files, 2);
+        assert_eq!(
+            prefix_of("viking://", "viking://resources/docs/guide.md"),
+            "viking://resources"
+        );
+    }
+
+    #[test]
+    fn token_usage_sums_the_daily_report() {
+        let report = json!({"items": [
+            {"date": "2026-10-16", "vlm_input": 10, "vlm_output": 5, "embedding_input": 300},
+            {"date": "2026-10-17", "vlm_input": 1, "vlm_output": 2, "embedding_input": 40},
+        ]});
+        assert_eq!(sum_tokens(&report), Some((340, 18)));
+        assert_eq!(sum_tokens(&json!({"enabled": false})), None);
+    }
+}
@@ -0,0 +1,131 @@
+//! `ov add -`: add content piped on stdin as a resource.
+//!
+//! Tools that generate content (`some-tool | ov add - --name notes.md`) should
+//! not need a temporary file of their own. Stdin is read to the end, written
+//! under `--name` into a temporary directory, and added like a local file, so
+//! the server picks its parser from that name. Without `--name`, text is added
+//! as `stdin.md` and other content is named after the type its bytes show.
+
+use std::io::{IsTerminal, Read};
+use std::path::Path;
+
+use crate::client::HttpClient;
+use crate::error::{Error, Result};
+use crate::file_type::{self, FileType};
+use crate::output::OutputFormat;
+use crate::theme;
+
+pub struct AddStdinOptions<'a> {
+    pub name: Option<&'a str>,
+    pub target: &'a str,
+    pub show_progress: bool,
+    pub verbose: bool,
+}
+
+/// Check a `--name` value: one file name, with no directories.
+fn validate_name(name: &str) -> Result<()> {
+    let valid =
+        !name.trim().is_empty() && !name.contains(['/', '\\']) && name != "." && name != "..";
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::Client(format!(
+            "Invalid --name '{name}'; expected a file name such as notes.md"
+        )))
+    }
+}
+
+/// The name stdin content is added as when `--name` is not given.
+fn default_name(found: &FileType) -> Result<String> {
+    if found.text {
+        return Ok("stdin.md".to_string());
+    }
+    mime_guess::get_mime_extensions_str(&found.mime)
+        .and_then(|extensions| extensions.first())
+        .filter(|_| found.sniffed)
+        .map(|extension| format!("stdin.{extension}"))
+        .ok_or_else(|| {
+            Error::Client(format!(
+                "Could not tell what kind of file stdin holds ({}); pass --name with an extension, e.g. --name report.pdf",
+                found.mime
+            ))
+        })
+}
+
+pub async fn add_stdin(
+    client: &HttpClient,
+    options: AddStdinOptions<'_>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    if let Some(name) = options.name {
+        validate_name(name)?;
+    }
+    let mut stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        return Err(Error::Client(
+            "ov add - reads the content to add from stdin; pipe it in, e.g. some-tool | ov add - --name notes.md"
+                .to_string(),
+        ));
+    }
+    let mut content = Vec::new();
+    stdin.read_to_end(&mut content)?;
+    if content.is_empty() {
+        return Err(Error::Client("Nothing to add: stdin was empty".to_string()));
+    }
+
+    let dir = tempfile::tempdir()?;
+    let name = match options.name {
+        Some(name) => name.to_string(),
+        None => default_name(&file_type::detect_bytes(Path::new("stdin"), &content))?,
+    };
+    let path = dir.path().join(&name);
+    std::fs::write(&path, &content)?;
+    for warning in file_type::warnings(&path, &file_type::detect(&path)?) {
+        eprintln!("{}", theme::warning(format!("Warning: {warning}")));
+    }
+
+    super::resources::add_resource(
+        client,
+        &path.to_string_lossy(),
+        None,
+        None,
+        Some(options.target.to_string()),
+        String::new(),
+        String::new(),
+        false,
+        None,
+        false,
+        None,
+        None,
+        None,
+        true,
+        0.0,
+        None,
+        format,
+        compact,
+        options.show_progress,
+        options.verbose,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_come_from_the_flag_or_the_content() {
+        assert!(validate_name("notes.md").is_ok());
+        assert!(validate_name("reports/q3.md").is_err());
+        assert!(validate_name("..").is_err());
+        assert!(validate_name(" ").is_err());
+
+        let text = file_type::detect_bytes(Path::new("stdin"), "# 周报\n".as_bytes());
+        assert_eq!(default_name(&text).unwrap(), "stdin.md");
+        let pdf = file_type::detect_bytes(Path::new("stdin"), b"%PDF-1.7\n");
+        assert_eq!(default_name(&pdf).unwrap(), "stdin.pdf");
+        let blob = file_type::detect_bytes(Path::new("stdin"), b"\x01\x02\0\x03");
+        assert!(default_name(&blob).is_err());
+    }
+}
@@ -34,7 +34,7 @@ pub async fn diagnostic_status(
 ) -> Result<()> {
     let meta = status_ui::current_config_meta();
 
-    if matches!(output_format, OutputFormat::Json) || verbose {
+    if output_format.is_json() || verbose {
         let response: serde_json::Value = client.get("/api/v1/observer/system", &[]).await?;
         output_success(&response, output_format, compact);
         return Ok(());
@@ -168,7 +168,7 @@ pub async fn health(
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
 
-    if matches!(output_format, OutputFormat::Json) {
+    if output_format.is_json() {
         output_success(&response, output_format, compact);
     } else {
         print!("{}", health_ui::render_health(&response, config));
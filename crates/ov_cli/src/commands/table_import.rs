@@ -0,0 +1,584 @@
+//! `ov add table <file>`: add a CSV, TSV, or Parquet file as searchable
+//! Markdown.
+//!
+//! The server treats a tabular file as one opaque blob. Here the file is read
+//! client-side and streamed in chunks of `--chunk-rows` rows: each chunk is
+//! written under the target as a Markdown table (`rows-000001-000200.md`), and
+//! once every row has been seen a `schema.md` summarizes the columns with their
+//! types, null counts, numeric ranges, and example values. Column types come
+//! from the Parquet schema, or are inferred from the values of a CSV.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::path::Path;
+
+use arrow::array::Array;
+use arrow::record_batch::RecordBatch;
+use arrow::util::display::{ArrayFormatter, FormatOptions};
+use parquet::arrow::arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder};
+use serde_json::{Value, json};
+
+use super::notion_import::write_page;
+use crate::client::HttpClient;
+use crate::error::{Error, Result};
+use crate::interrupt;
+use crate::output::{OutputFormat, output_success};
+use crate::theme;
+
+/// Longest cell value kept in a chunk, in characters.
+const MAX_CELL_CHARS: usize = 300;
+
+/// Distinct example values listed per column in the schema summary.
+const EXAMPLES: usize = 3;
+
+pub struct AddTableOptions<'a> {
+    pub path: &'a Path,
+    pub target: Option<&'a str>,
+    pub chunk_rows: usize,
+    pub delimiter: Option<char>,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Boolean,
+    Integer,
+    Float,
+    Date,
+    Text,
+}
+
+impl Kind {
+    fn of(value: &str) -> Self {
+        if value.parse::<i64>().is_ok() {
+            Self::Integer
+        } else if value.parse::<f64>().is_ok() {
+            Self::Float
+        } else if value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false") {
+            Self::Boolean
+        } else if chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+            || chrono::DateTime::parse_from_rfc3339(value).is_ok()
+        {
+            Self::Date
+        } else {
+            Self::Text
+        }
+    }
+
+    /// The narrowest kind that holds values of both kinds.
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Self::Integer, Self::Float) | (Self::Float, Self::Integer) => Self::Float,
+            _ => Self::Text,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Boolean => "boolean",
+            Self::Integer => "integer",
+            Self::Float => "float",
+            Self::Date => "date",
+            Self::Text => "text",
+        }
+    }
+}
+
+/// What the schema summary says about one column, gathered row by row.
+#[derive(Debug, Clone, Default)]
+struct Column {
+    name: String,
+    /// The type the file declares, for Parquet.
+    declared: Option<String>,
+    kind: Option<Kind>,
+    nulls: u64,
+    min: Option<f64>,
+    max: Option<f64>,
+    examples: Vec<String>,
+}
+
+impl Column {
+    fn new(name: impl Into<String>, declared: Option<String>) -> Self {
+        Self {
+            name: name.into(),
+            declared,
+            ..Self::default()
+        }
+    }
+
+    fn observe(&mut self, value: Option<&str>) {
+        let Some(value) = value.map(str::trim).filter(|value| !value.is_empty()) else {
+            self.nulls += 1;
+            return;
+        };
+        let kind = Kind::of(value);
+        self.kind = Some(self.kind.map_or(kind, |seen| seen.merge(kind)));
+        if let Ok(number) = value.parse::<f64>() {
+            self.min = Some(self.min.map_or(number, |min| min.min(number)));
+            self.max = Some(self.max.map_or(number, |max| max.max(number)));
+        }
+        if self.examples.len() < EXAMPLES && !self.examples.iter().any(|seen| seen == value) {
+            self.examples.push(value.to_string());
+        }
+    }
+
+    fn type_name(&self) -> &str {
+        self.declared
+            .as_deref()
+            .unwrap_or_else(|| self.kind.map_or("empty", Kind::name))
+    }
+
+    fn range(&self) -> String {
+        let numeric = matches!(self.kind, Some(Kind::Integer | Kind::Float));
+        match (self.min, self.max) {
+            (Some(min), Some(max)) if numeric => {
+                format!("{} to {}", format_number(min), format_number(max))
+            }
+            _ => String::new(),
+        }
+    }
+}
+
+fn format_number(number: f64) -> String {
+    if number.fract() == 0.0 && number.abs() < 1e15 {
+        format!("{}", number as i64)
+    } else {
+        format!("{number}")
+    }
+}
+
+type Row = Vec<Option<String>>;
+
+enum Rows {
+    Csv(csv::StringRecordsIntoIter<File>),
+    Parquet(ParquetRecordBatchReader),
+}
+
+/// A tabular file being read a chunk at a time.
+struct Table {
+    format: &'static str,
+    columns: Vec<Column>,
+    /// Row count from the file's metadata, when it has one.
+    total: Option<u64>,
+    rows: Rows,
+}
+
+fn read_error(path: &Path, e: impl std::fmt::Display) -> Error {
+    Error::Parse(format!("Failed to read {}: {e}", path.display()))
+}
+
+impl Table {
+    fn open(path: &Path, delimiter: Option<char>, chunk_rows: usize) -> Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase)
+            .unwrap_or_default();
+        match extension.as_str() {
+            "parquet" | "pq" => {
+                let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)
+                    .map_err(|e| read_error(path, e))?;
+                let total = builder.metadata().file_metadata().num_rows();
+                let columns = builder
+                    .schema()
+                    .fields()
+                    .iter()
+                    .map(|field| Column::new(field.name(), Some(field.data_type().to_string())))
+                    .collect();
+                let reader = builder
+                    .with_batch_size(chunk_rows)
+                    .build()
+                    .map_err(|e| read_error(path, e))?;
+                Ok(Self {
+                    format: "Parquet",
+                    columns,
+                    total: u64::try_from(total).ok(),
+                    rows: Rows::Parquet(reader),
+                })
+            }
+            "csv" | "tsv" | "tab" | "txt" => {
+                let default = if extension == "csv" { ',' } else { '\t' };
+                let delimiter = u8::try_from(delimiter.unwrap_or(default)).map_err(|_| {
+                    Error::Client("--delimiter must be a single ASCII character".to_string())
+                })?;
+                let mut reader = csv::ReaderBuilder::new()
+                    .delimiter(delimiter)
+                    .flexible(true)
+                    .from_path(path)
+                    .map_err(|e| read_error(path, e))?;
+                let columns = reader
+                    .headers()
+                    .map_err(|e| read_error(path, e))?
+                    .iter()
+                    .enumerate()
+                    .map(|(index, name)| match name.trim() {
+                        "" => Column::new(format!("column_{}", index + 1), None),
+                        name => Column::new(name, None),
+                    })
+                    .collect();
+                Ok(Self {
+                    format: match delimiter {
+                        b',' => "CSV",
+                        b'\t' => "TSV",
+                        _ => "Delimited text",
+                    },
+                    columns,
+                    total: None,
+                    rows: Rows::Csv(reader.into_records()),
+                })
+            }
+            _ => Err(Error::Client(format!(
+                "Unsupported table file {}; expected .csv, .tsv, or .parquet",
+                path.display()
+            ))),
+        }
+    }
+
+    /// The next `size` rows, recorded in the column statistics, or `None`
+    /// once the file is exhausted.
+    fn next_chunk(&mut self, path: &Path, size: usize) -> Result<Option<Vec<Row>>> {
+        let rows = match &mut self.rows {
+            Rows::Csv(records) => {
+                let width = self.columns.len();
+                let mut rows = Vec::with_capacity(size);
+                for record in records.by_ref().take(size) {
+                    let record = record.map_err(|e| read_error(path, e))?;
+                    rows.push(
+                        (0..width)
+                            .map(|index| record.get(index).map(str::to_string))
+                            .map(|value| value.filter(|value| !value.is_empty()))
+                            .collect(),
+                    );
+                }
+                rows
+            }
+            Rows::Parquet(reader) => match reader.next() {
+                Some(batch) => batch_rows(&batch.map_err(|e| read_error(path, e))?)
+                    .map_err(|e| read_error(path, e))?,
+                None => Vec::new(),
+            },
+        };
+        if rows.is_empty() {
+            return Ok(None);
+        }
+        for row in &rows {
+            for (column, value) in self.columns.iter_mut().zip(row) {
+                column.observe(value.as_deref());
+            }
+        }
+        Ok(Some(rows))
+    }
+}
+
+fn batch_rows(batch: &RecordBatch) -> std::result::Result<Vec<Row>, arrow::error::ArrowError> {
+    let options = FormatOptions::default();
+    let formatters = batch
+        .columns()
+        .iter()
+        .map(|column| ArrayFormatter::try_new(column.as_ref(), &options))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok((0..batch.num_rows())
+        .map(|row| {
+            batch
+                .columns()
+                .iter()
+                .zip(&formatters)
+                .map(|(column, formatter)| {
+                    (!column.is_null(row)).then(|| formatter.value(row).to_string())
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// `value` made safe for one Markdown table cell.
+fn cell(value: &str) -> String {
+    let mut text: String = value.chars().take(MAX_CELL_CHARS).collect();
+    if text.len() < value.len() {
+        text.push('…');
+    }
+    text.replace('\r', "")
+        .replace('|', "\\|")
+        .replace('\n', "<br>")
+}
+
+fn table_header(names: impl Iterator<Item = String>) -> String {
+    let names: Vec<String> = names.collect();
+    format!(
+        "| {} |\n|{}\n",
+        names.join(" | "),
+        " --- |".repeat(names.len())
+    )
+}
+
+fn chunk_markdown(title: &str, columns: &[Column], first: u64, rows: &[Row]) -> String {
+    let mut markdown = format!(
+        "# {title}, rows {first} to {}\n\n",
+        first + rows.len() as u64 - 1
+    );
+    markdown.push_str(&table_header(
+        columns.iter().map(|column| cell(&column.name)),
+    ));
+    for row in rows {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|value| value.as_deref().map(cell).unwrap_or_default())
+            .collect();
+        markdown.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    markdown
+}
+
+fn schema_markdown(title: &str, table: &Table, rows: u64, chunks: &[String]) -> String {
+    let mut markdown = format!(
+        "# {title}\n\n- Format: {}\n- Rows: {rows}\n- Columns: {}\n- Chunks: {}\n\n## Columns\n\n",
+        table.format,
+        table.columns.len(),
+        chunks.len()
+    );
+    markdown.push_str(&table_header(
+        ["Column", "Type", "Nulls", "Range", "Examples"]
+            .into_iter()
+            .map(str::to_string),
+    ));
+    for column in &table.columns {
+        let examples: Vec<String> = column
+            .examples
+            .iter()
+            .map(|example| format!("`{}`", cell(example).replace('`', "'")))
+            .collect();
+        markdown.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            cell(&column.name),
+            column.type_name(),
+            column.nulls,
+            column.range(),
+            examples.join(", ")
+        ));
+    }
+    if !chunks.is_empty() {
+        markdown.push_str("\n## Chunks\n\n");
+        for chunk in chunks {
+            markdown.push_str(&format!("- [{chunk}]({chunk})\n"));
+        }
+    }
+    markdown
+}
+
+fn row(file: &str, rows: &str, status: &str, uri: Option<&str>, error: Option<String>) -> Value {
+    json!({
+        "file": file,
+        "rows": rows,
+        "status": status,
+        "uri": uri,
+        "error": error,
+    })
+}
+
+pub async fn add_table(
+    client: &HttpClient,
+    options: AddTableOptions<'_>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    if options.chunk_rows == 0 {
+        return Err(Error::Client("--chunk-rows must be at least 1".to_string()));
+    }
+    let path = options.path;
+    let title = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "table".to_string());
+    let stem = path
+        .file_stem()
+        .map(|stem| super::mcp_import::safe_name(&stem.to_string_lossy()))
+        .unwrap_or_else(|| "table".to_string());
+    let target = options.target.map_or_else(
+        || format!("viking://resources/tables/{stem}"),
+        |target| target.trim_end_matches('/').to_string(),
+    );
+    let mut table = Table::open(path, options.delimiter, options.chunk_rows)?;
+    let expected = table
+        .total
+        .map(|total| total.div_ceil(options.chunk_rows as u64));
+
+    interrupt::enter_batch();
+    let mut results = Vec::new();
+    let mut chunks = Vec::new();
+    let mut dirs = HashSet::new();
+    let (mut total, mut written, mut failed) = (0u64, 0, 0);
+    let mut interrupted = false;
+    while let Some(rows) = table.next_chunk(path, options.chunk_rows)? {
+        let first = total + 1;
+        total += rows.len() as u64;
+        let name = format!("rows-{first:06}-{total:06}.md");
+        let range = format!("{first}-{total}");
+        chunks.push(name.clone());
+        if options.dry_run {
+            results.push(row(&name, &range, "planned", None, None));
+            continue;
+        }
+        let position = match expected {
+            Some(expected) => format!("{}/{expected}", chunks.len()),
+            None => chunks.len().to_string(),
+        };
+        eprintln!(
+            "{}",
+            theme::muted(format!("[{position}] rows {first} to {total}"))
+        );
+        let markdown = chunk_markdown(&title, &table.columns, first, &rows);
+        match interrupt::cancellable(write_page(client, &target, &name, &markdown, &mut dirs)).await
+        {
+            Ok(uri) => {
+                written += 1;
+                results.push(row(&name, &range, "added", Some(&uri), None));
+            }
+            Err(Error::Interrupted) => {
+                interrupted = true;
+                break;
+            }
+            Err(e) => {
+                failed += 1;
+                results.push(row(&name, &range, "failed", None, Some(e.to_string())));
+            }
+        }
+    }
+
+    // The summary lists every chunk, so it goes last, once all rows are seen.
+    let schema = schema_markdown(&title, &table, total, &chunks);
+    let columns = table.columns.len();
+    if options.dry_run {
+        results.insert(0, row("schema.md", "", "planned", None, None));
+    } else if !interrupted {
+        match interrupt::cancellable(write_page(client, &target, "schema.md", &schema, &mut dirs))
+            .await
+        {
+            Ok(uri) => results.insert(0, row("schema.md", "", "added", Some(&uri), None)),
+            Err(Error::Interrupted) => interrupted = true,
+            Err(e) => {
+                failed += 1;
+                results.insert(0, row("schema.md", "", "failed", None, Some(e.to_string())));
+            }
+        }
+    }
+
+    if matches!(format, OutputFormat::Table) {
+        output_success(Value::Array(results), format, compact);
+        let summary = if options.dry_run {
+            format!(
+                "{total} rows and {columns} columns would be added to {target} in {} chunks",
+                chunks.len()
+            )
+        } else {
+            format!(
+                "{total} rows and {columns} columns added to {target}: {written} of {} chunks written, {failed} failed",
+                chunks.len()
+            )
+        };
+        println!("{}", theme::muted(summary));
+    } else {
+        output_success(
+            json!({
+                "source": path.display().to_string(),
+                "target": target,
+                "format": table.format,
+                "dry_run": options.dry_run,
+                "rows": total,
+                "columns": columns,
+                "chunks": chunks.len(),
+                "written": written,
+                "failed": failed,
+                "results": results,
+            }),
+            format,
+            compact,
+        );
+    }
+    if interrupted {
+        return Err(Error::Interrupted);
+    }
+    if failed > 0 {
+        return Err(Error::AlreadyReported);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::{Float64Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use parquet::arrow::ArrowWriter;
+
+    fn chunks(table: &mut Table, path: &Path, size: usize) -> Vec<Vec<Row>> {
+        std::iter::from_fn(|| table.next_chunk(path, size).unwrap()).collect()
+    }
+
+    #[test]
+    fn csv_is_chunked_and_summarized() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sales.csv");
+        std::fs::write(
+            &path,
+            "region,amount,closed,note\nEMEA,12,2024-01-05,\"big | deal\"\nAPAC,7.5,2024-02-01,\nEMEA,,2024-03-09,\"two\nlines\"\n",
+        )
+        .unwrap();
+
+        let mut table = Table::open(&path, None, 2).unwrap();
+        let chunks = chunks(&mut table, &path, 2);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(
+            chunk_markdown("sales.csv", &table.columns, 3, &chunks[1]),
+            "# sales.csv, rows 3 to 3\n\n\
+             | region | amount | closed | note |\n| --- | --- | --- | --- |\n\
+             | EMEA |  | 2024-03-09 | two<br>lines |\n"
+        );
+
+        let amount = &table.columns[1];
+        assert_eq!(amount.type_name(), "float");
+        assert_eq!(amount.nulls, 1);
+        assert_eq!(amount.range(), "7.5 to 12");
+        assert_eq!(table.columns[0].examples, ["EMEA", "APAC"]);
+        assert_eq!(table.columns[2].type_name(), "date");
+
+        let schema = schema_markdown("sales.csv", &table, 3, &["rows-000001-000002.md".into()]);
+        assert!(schema.contains("- Format: CSV\n- Rows: 3\n- Columns: 4\n- Chunks: 1"));
+        assert!(schema.contains("| note | text | 1 |  | `big \\| deal`, `two<br>lines` |"));
+        assert!(schema.contains("- [rows-000001-000002.md](rows-000001-000002.md)"));
+    }
+
+    #[test]
+    fn parquet_columns_keep_their_declared_types() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metrics.parquet");
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("host", DataType::Utf8, false),
+            Field::new("latency", DataType::Float64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["a", "b", "c"])),
+                Arc::new(Float64Array::from(vec![Some(1.5), None, Some(3.0)])),
+            ],
+        )
+        .unwrap();
+        let mut writer = ArrowWriter::try_new(File::create(&path).unwrap(), schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let mut table = Table::open(&path, None, 2).unwrap();
+        assert_eq!(table.total, Some(3));
+        let chunks = chunks(&mut table, &path, 2);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0][1], [Some("b".to_string()), None]);
+        assert_eq!(table.columns[1].type_name(), "Float64");
+        assert_eq!(table.columns[1].nulls, 1);
+        assert_eq!(table.columns[1].range(), "1.5 to 3");
+
+        assert!(Table::open(Path::new("data.xlsx"), None, 2).is_err());
+    }
+}
@@ -0,0 +1,215 @@
+//! `ov tag`: add, remove, and list the explicit `k=v` retrieval tags a URI
+//! carries, and filter `ov ls` entries by them.
+//!
+//! Tags live in the server's attribute metadata (`/api/v1/fs/attrs`). The
+//! server merges appended tags by key, so `ov tag add` appends; removal reads
+//! the current tags and writes back the rest.
+
+use futures::{StreamExt, TryStreamExt};
+use serde_json::{Value, json};
+
+use crate::client::HttpClient;
+use crate::error::{Error, Result};
+use crate::output::{OutputFormat, output_success};
+
+const FETCH_CONCURRENCY: usize = 8;
+
+/// Tags are compared the way the server stores them: trimmed and lowercase.
+fn normalize(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+fn validate(tags: &[String]) -> Result<Vec<String>> {
+    tags.iter()
+        .map(|tag| {
+            let tag = normalize(tag);
+            match tag.split_once('=') {
+                Some((key, value))
+                    if !key.is_empty() && !value.is_empty() && !value.contains('=') =>
+                {
+                    Ok(tag)
+                }
+                _ => Err(Error::Client(format!(
+                    "Invalid tag '{tag}'; expected key=value, e.g. team=search"
+                ))),
+            }
+        })
+        .collect()
+}
+
+/// A tag pattern matches a tag exactly, or by key when it has no `=`.
+fn matches(pattern: &str, tag: &str) -> bool {
+    if pattern.contains('=') {
+        pattern == tag
+    } else {
+        tag.split_once('=').is_some_and(|(key, _)| key == pattern)
+    }
+}
+
+fn has_all(tags: &[String], patterns: &[String]) -> bool {
+    patterns
+        .iter()
+        .all(|pattern| tags.iter().any(|tag| matches(pattern, tag)))
+}
+
+async fn tags_of(client: &HttpClient, uri: &str) -> Result<Vec<String>> {
+    let attrs = client.attrs(uri).await?;
+    Ok(attrs["attrs"]["tags"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(normalize)
+        .collect())
+}
+
+fn tag_rows(uri: &str, tags: &[String]) -> Value {
+    let rows: Vec<Value> = tags
+        .iter()
+        .map(|tag| {
+            let (key, value) = tag.split_once('=').unwrap_or((tag, ""));
+            json!({ "uri": uri, "key": key, "value": value })
+        })
+        .collect();
+    Value::Array(rows)
+}
+
+pub async fn add(
+    client: &HttpClient,
+    uri: &str,
+    tags: &[String],
+    recursive: bool,
+    output_format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let tags = validate(tags)?;
+    client.set_tags(uri, tags, "append", recursive).await?;
+    output_success(
+        tag_rows(uri, &tags_of(client, uri).await?),
+        output_format,
+        compact,
+    );
+    Ok(())
+}
+
+/// Remove tags given as `k=v`, or every value of a key given as `k`.
+pub async fn remove(
+    client: &HttpClient,
+    uri: &str,
+    patterns: &[String],
+    output_format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let patterns: Vec<String> = patterns.iter().map(|tag| normalize(tag)).collect();
+    let current = tags_of(client, uri).await?;
+    let (removed, kept): (Vec<String>, Vec<String>) = current
+        .into_iter()
+        .partition(|tag| patterns.iter().any(|pattern| matches(pattern, tag)));
+    if removed.is_empty() {
+        return Err(Error::Client(format!(
+            "{uri} has no tag matching {}; see `ov tag ls {uri}`",
+            patterns.join(", ")
+        )));
+    }
+    client.set_tags(uri, kept.clone(), "replace", false).await?;
+    output_success(
+        json!({ "uri": uri, "removed": removed, "tags": kept }),
+        output_format,
+        compact,
+    );
+    Ok(())
+}
+
+pub async fn ls(
+    client: &HttpClient,
+    uri: &str,
+    output_format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    output_success(
+        tag_rows(uri, &tags_of(client, uri).await?),
+        output_format,
+        compact,
+    );
+    Ok(())
+}
+
+/// Keep the `ov ls` entries that carry every tag in `patterns`. Entries the
+/// server cannot report attributes for are dropped.
+pub async fn retain_tagged(
+    client: &HttpClient,
+    value: &mut Value,
+    patterns: &[String],
+) -> Result<()> {
+    let Value::Array(entries) = value else {
+        return Ok(());
+    };
+    let patterns: Vec<String> = patterns.iter().map(|tag| normalize(tag)).collect();
+    let uris: Vec<Option<String>> = entries
+        .iter()
+        .map(|entry| {
+            entry
+                .as_str()
+                .or_else(|| entry.get("uri").and_then(Value::as_str))
+                .map(str::to_string)
+        })
+        .collect();
+    let keep: Vec<bool> = futures::stream::iter(uris)
+        .map(|uri| {
+            let patterns = &patterns;
+            async move {
+                let Some(uri) = uri else {
+                    return Ok(false);
+                };
+                match tags_of(client, &uri).await {
+                    Ok(tags) => Ok(has_all(&tags, patterns)),
+                    Err(e @ (Error::Network(_) | Error::Interrupted)) => Err(e),
+                    Err(_) => Ok(false),
+                }
+            }
+        })
+        .buffered(FETCH_CONCURRENCY)
+        .try_collect()
+        .await?;
+    let mut keep = keep.into_iter();
+    entries.retain(|_| keep.next().unwrap_or(false));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(items: &[&str]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    #[test]
+    fn tags_must_be_key_value_pairs() {
+        assert_eq!(
+            validate(&tags(&[" Team=Search ", "env=prod"])).unwrap(),
+            tags(&["team=search", "env=prod"])
+        );
+        for bad in ["team", "=search", "team=", "a=b=c"] {
+            assert!(validate(&tags(&[bad])).is_err(), "{bad} should be rejected");
+        }
+    }
+
+    #[test]
+    fn patterns_match_exact_tags_or_keys() {
+        let current = tags(&["team=search", "env=prod"]);
+        assert!(has_all(&current, &tags(&["team=search"])));
+        assert!(has_all(&current, &tags(&["env", "team=search"])));
+        assert!(!has_all(&current, &tags(&["team=infra"])));
+        assert!(!has_all(&current, &tags(&["owner"])));
+        assert!(has_all(&current, &[]));
+    }
+
+    #[test]
+    fn tag_rows_split_key_and_value() {
+        assert_eq!(
+            tag_rows("viking://resources/a.md", &tags(&["team=search"])),
+            json!([{ "uri": "viking://resources/a.md", "key": "team", "value": "search" }])
+        );
+    }
+}
@@ -20,7 +20,7 @@ pub async fn list(
     output_format: OutputFormat,
     compact: bool,
 ) -> Result<()> {
-    let result = client.list_tasks(task_type, status).await?;
+    let result = client.list_tasks(task_type, status, None).await?;
     output_success(&result, output_format, compact);
     Ok(())
 }
@@ -0,0 +1,308 @@
+//! Token counts for budgeting context windows: `ov tokens count` and the
+//! `--tokens` column of `ov ls`, `ov find` and `ov search`.
+//!
+//! `cl100k` and `o200k` are the OpenAI encodings and count exactly for models
+//! that use them. `estimate` is the server's own rule of thumb, the one it
+//! applies to every embedding and rerank provider, and needs no vocabulary.
+
+use futures::StreamExt;
+use serde_json::{Value, json};
+
+use crate::client::HttpClient;
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::output::{OutputFormat, output_success};
+
+/// How many contents `--tokens` fetches at once.
+const FETCH_CONCURRENCY: usize = 8;
+
+pub const TOKENIZERS: [&str; 3] = ["cl100k", "o200k", "estimate"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tokenizer {
+    Cl100k,
+    O200k,
+    Estimate,
+}
+
+impl Tokenizer {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "cl100k" | "cl100k_base" => Some(Self::Cl100k),
+            "o200k" | "o200k_base" => Some(Self::O200k),
+            "estimate" => Some(Self::Estimate),
+            _ => None,
+        }
+    }
+
+    /// The tokenizer named by `"tokenizer"` in the config, `cl100k` if unset.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let Some(name) = config.tokenizer.as_deref() else {
+            return Ok(Self::Cl100k);
+        };
+        Self::parse(name).ok_or_else(|| {
+            Error::Config(format!(
+                "Unknown tokenizer '{name}' in config; use one of {}",
+                TOKENIZERS.join(", ")
+            ))
+        })
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Cl100k => "cl100k",
+            Self::O200k => "o200k",
+            Self::Estimate => "estimate",
+        }
+    }
+
+    pub fn count(self, text: &str) -> usize {
+        match self {
+            Self::Cl100k => tiktoken_rs::cl100k_base_singleton()
+                .encode_ordinary(text)
+                .len(),
+            Self::O200k => tiktoken_rs::o200k_base_singleton()
+                .encode_ordinary(text)
+                .len(),
+            Self::Estimate => estimate_tokens(text),
+        }
+    }
+}
+
+/// Tokens `text` is likely to cost: one per CJK character and one per four
+/// other characters, as the server's own estimator counts.
+pub fn estimate_tokens(text: &str) -> usize {
+    if text.is_empty() {
+        return 0;
+    }
+    let cjk = text
+        .chars()
+        .filter(|c| ('\u{4e00}'..='\u{9fff}').contains(c))
+        .count();
+    let other = text.chars().count() - cjk;
+    (cjk + other / 4).max(1)
+}
+
+/// Count the tokens of each source: a viking:// URI, a local file, or `-`
+/// for standard input.
+pub async fn count(
+    client: &HttpClient,
+    sources: &[String],
+    tokenizer: Tokenizer,
+    output_format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let mut rows = Vec::with_capacity(sources.len());
+    let mut total = 0;
+    for source in sources {
+        let text = if source == "-" {
+            std::io::read_to_string(std::io::stdin())?
+        } else if source.starts_with("viking://") {
+            client.read(source).await?
+        } else {
+            std::fs::read_to_string(source)
+                .map_err(|e| Error::Client(format!("Cannot read {source}: {e}")))?
+        };
+        let tokens = tokenizer.count(&text);
+        total += tokens;
+        rows.push(json!({
+            "source": source,
+            "tokenizer": tokenizer.name(),
+            "tokens": tokens,
+            "chars": text.chars().count(),
+        }));
+    }
+
+    if sources.len() == 1 && matches!(output_format, OutputFormat::Table) {
+        println!("{}", rows[0]["tokens"]);
+    } else if output_format.is_json() {
+        output_success(
+            json!({"tokenizer": tokenizer.name(), "total": total, "sources": rows}),
+            output_format,
+            compact,
+        );
+    } else {
+        output_success(Value::Array(rows), output_format, compact);
+        println!("Total: {total} tokens ({})", tokenizer.name());
+    }
+    Ok(())
+}
+
+/// The shape of a response `--tokens` annotates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Listing {
+    /// `ls` entries: files count their content, directories their overview.
+    Entries,
+    /// `find`/`search` hits: each counts the layer it matched, an abstract
+    /// (L0), a directory overview (L1), or a file's content (L2).
+    Hits,
+}
+
+/// Where the text of one row comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Text {
+    Inline(String),
+    Read(String),
+    Overview(String),
+}
+
+fn row_text(row: &serde_json::Map<String, Value>, listing: Listing) -> Option<Text> {
+    let uri = row.get("uri")?.as_str()?.trim();
+    let field = |key: &str| row.get(key).and_then(Value::as_str).map(str::to_string);
+    match listing {
+        Listing::Entries if row.get("isDir").and_then(Value::as_bool) == Some(true) => {
+            Some(Text::Overview(uri.to_string()))
+        }
+        Listing::Entries => Some(Text::Read(uri.to_string())),
+        Listing::Hits => {
+            let level = row.get("level").and_then(Value::as_i64).unwrap_or(2);
+            if uri.ends_with("/.abstract.md") {
+                return field("abstract").map(Text::Inline);
+            }
+            if let Some(directory) = uri.strip_suffix("/.overview.md") {
+                return Some(
+                    field("overview").map_or(Text::Overview(directory.to_string()), Text::Inline),
+                );
+            }
+            match level {
+                0 => field("abstract").map(Text::Inline),
+                1 => Some(field("overview").map_or(Text::Overview(uri.to_string()), Text::Inline)),
+                _ => Some(Text::Read(uri.to_string())),
+            }
+        }
+    }
+}
+
+/// Every object with a `uri` in `value`, wherever the response nests them.
+/// The hits `--explain` lists under `provenance` repeat the results and are
+/// skipped.
+pub(super) fn rows_mut<'a>(
+    value: &'a mut Value,
+    rows: &mut Vec<&'a mut serde_json::Map<String, Value>>,
+) {
+    match value {
+        Value::Array(items) => items.iter_mut().for_each(|item| rows_mut(item, rows)),
+        Value::Object(object) => {
+            if object.get("uri").is_some_and(Value::is_string) {
+                rows.push(object);
+            } else {
+                object
+                    .iter_mut()
+                    .filter(|(key, _)| key.as_str() != "provenance")
+                    .for_each(|(_, item)| rows_mut(item, rows));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The text each row shows, fetching contents and overviews the response
+/// does not carry. Rows whose text cannot be read get `None`.
+pub(super) async fn row_texts(
+    client: &HttpClient,
+    rows: &[&mut serde_json::Map<String, Value>],
+    listing: Listing,
+) -> Result<Vec<Option<String>>> {
+    let texts: Vec<Option<Text>> = rows.iter().map(|row| row_text(row, listing)).collect();
+    futures::stream::iter(texts)
+        .map(|text| async move {
+            let text = match text? {
+                Text::Inline(text) => Ok(text),
+                Text::Read(uri) => client.read(&uri).await,
+                Text::Overview(uri) => client.overview(&uri).await,
+            };
+            match text {
+                Ok(text) => Some(Ok(text)),
+                Err(e @ (Error::Network(_) | Error::Interrupted)) => Some(Err(e)),
+                Err(_) => None,
+            }
+        })
+        .buffered(FETCH_CONCURRENCY)
+        .map(Option::transpose)
+        .collect::<Vec<Result<Option<String>>>>()
+        .await
+        .into_iter()
+        .collect()
+}
+
+/// Add a `tokens` field to each row of `value`, fetching contents and
+/// overviews the response does not carry. Rows whose text cannot be read
+/// are left without one.
+pub async fn annotate(
+    client: &HttpClient,
+    value: &mut Value,
+    listing: Listing,
+    tokenizer: Tokenizer,
+) -> Result<()> {
+    let mut rows = Vec::new();
+    rows_mut(value, &mut rows);
+    let texts = row_texts(client, &rows, listing).await?;
+    for (row, text) in rows.into_iter().zip(texts) {
+        if let Some(text) = text {
+            row.insert("tokens".to_string(), json!(tokenizer.count(&text)));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenizers_count_known_strings() {
+        assert_eq!(Tokenizer::Cl100k.count("hello world"), 2);
+        assert_eq!(Tokenizer::O200k.count("hello world"), 2);
+        assert_eq!(Tokenizer::Cl100k.count(""), 0);
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("ab"), 1);
+        assert_eq!(estimate_tokens("abcdefgh"), 2);
+        assert_eq!(estimate_tokens("上下文abcd"), 4);
+        assert_eq!(Tokenizer::parse("O200K_BASE"), Some(Tokenizer::O200k));
+        assert_eq!(Tokenizer::parse("p50k"), None);
+    }
+
+    #[test]
+    fn rows_count_the_layer_they_show() {
+        let row = |value: Value| value.as_object().unwrap().clone();
+        let entry = row(json!({"uri": "viking://resources/docs", "isDir": true}));
+        assert_eq!(
+            row_text(&entry, Listing::Entries),
+            Some(Text::Overview("viking://resources/docs".to_string()))
+        );
+        let hit = row(
+            json!({"uri": "viking://resources/docs/.abstract.md", "level": 0,
+                             "abstract": "Project docs"}),
+        );
+        assert_eq!(
+            row_text(&hit, Listing::Hits),
+            Some(Text::Inline("Project docs".to_string()))
+        );
+        let hit = row(json!({"uri": "viking://resources/docs/.overview.md", "level": 1}));
+        assert_eq!(
+            row_text(&hit, Listing::Hits),
+            Some(Text::Overview("viking://resources/docs".to_string()))
+        );
+        let hit = row(json!({"uri": "viking://resources/docs/a.md", "level": 2}));
+        assert_eq!(
+            row_text(&hit, Listing::Hits),
+            Some(Text::Read("viking://resources/docs/a.md".to_string()))
+        );
+    }
+
+    #[test]
+    fn rows_are_found_wherever_the_response_nests_them() {
+        let mut value = json!({
+            "result": {
+                "memories": [{"uri": "viking://user/memories/a.md"}],
+                "resources": [{"uri": "viking://resources/b.md"}],
+                "query_plan": {"queries": ["q"]},
+                "provenance": [{"matched_contexts": [{"uri": "viking://resources/b.md"}]}]
+            },
+            "profile": {"total_ms": 3}
+        });
+        let mut rows = Vec::new();
+        rows_mut(&mut value, &mut rows);
+        assert_eq!(rows.len(), 2);
+    }
+}
@@ -0,0 +1,495 @@
+//! `ov top`: a live dashboard for watching the server work through a load.
+//!
+//! Every `--interval` seconds it samples the queue observer, the retrieval
+//! observer, and (when the server keeps one) the audit log's failed requests,
+//! then redraws: queue depth per queue, ingestion throughput from the change in
+//! processed counts, index lag as the age of the oldest pending embedding,
+//! search latency, and the most recent errors. `q` quits and `r` refreshes now.
+
+use std::collections::VecDeque;
+use std::io::{self, IsTerminal};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Sparkline, Table};
+use serde_json::Value;
+
+use super::pick::Screen;
+use crate::client::HttpClient;
+use crate::error::{Error, Result};
+
+/// Throughput samples kept for the sparkline.
+const HISTORY: usize = 120;
+/// Errors kept in the errors panel.
+const MAX_ERRORS: usize = 50;
+/// The queue whose oldest pending item is the index lag.
+const EMBEDDING_QUEUE: &str = "Embedding";
+/// Node counts inside semantic processing; not items ingested.
+const SEMANTIC_NODES_QUEUE: &str = "Semantic-Nodes";
+
+pub struct TopOptions<'a> {
+    pub server: &'a str,
+    pub uri: Option<&'a str>,
+    pub interval: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct QueueRow {
+    name: String,
+    pending: u64,
+    in_progress: u64,
+    processed: u64,
+    errors: u64,
+    oldest_pending_seconds: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ErrorRow {
+    when: String,
+    source: String,
+    detail: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SearchLatency {
+    avg_ms: f64,
+    max_ms: f64,
+}
+
+fn queue_rows(status: &Value) -> Vec<QueueRow> {
+    let count = |queue: &Value, key: &str| queue[key].as_u64().unwrap_or(0);
+    status["queues"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|queue| QueueRow {
+            name: queue["name"].as_str().unwrap_or("?").to_string(),
+            pending: count(queue, "pending"),
+            in_progress: count(queue, "in_progress"),
+            processed: count(queue, "processed"),
+            errors: count(queue, "errors"),
+            oldest_pending_seconds: queue["oldest_pending_seconds"].as_f64(),
+        })
+        .collect()
+}
+
+/// Items the queues have finished, not counting semantic sub-nodes.
+fn processed_total(queues: &[QueueRow]) -> u64 {
+    queues
+        .iter()
+        .filter(|queue| queue.name != SEMANTIC_NODES_QUEUE)
+        .map(|queue| queue.processed)
+        .sum()
+}
+
+fn queue_errors(status: &Value) -> Vec<ErrorRow> {
+    status["items"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|item| item["state"].as_str() == Some("failed"))
+        .map(|item| ErrorRow {
+            when: "-".to_string(),
+            source: item["queue"].as_str().unwrap_or("queue").to_string(),
+            detail: format!(
+                "{}: {}",
+                item["uri"].as_str().unwrap_or("?"),
+                item["error"].as_str().unwrap_or("failed")
+            ),
+        })
+        .collect()
+}
+
+fn api_errors(audit: &Value) -> Vec<ErrorRow> {
+    audit["items"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|event| ErrorRow {
+            when: event["created_at"]
+                .as_str()
+                .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+                .map(|time| time.with_timezone(&Local).format("%H:%M:%S").to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            source: format!("HTTP {}", event["status_code"].as_u64().unwrap_or(0)),
+            detail: format!(
+                "{} {} ({})",
+                event["method"].as_str().unwrap_or("?"),
+                event["route"].as_str().unwrap_or("?"),
+                event["user_id"].as_str().unwrap_or("-")
+            ),
+        })
+        .collect()
+}
+
+/// A numeric value from the retrieval observer's text table, e.g. the
+/// `Avg Latency (ms)` row of `| Avg Latency (ms) | 12.5 |`.
+fn table_metric(table: &str, metric: &str) -> Option<f64> {
+    table
+        .lines()
+        .find(|line| line.contains(metric))?
+        .split('|')
+        .map(str::trim)
+        .rfind(|cell| !cell.is_empty())?
+        .parse()
+        .ok()
+}
+
+fn search_latency(retrieval: &Value) -> Option<SearchLatency> {
+    let table = retrieval["status"].as_str()?;
+    Some(SearchLatency {
+        avg_ms: table_metric(table, "Avg Latency (ms)")?,
+        max_ms: table_metric(table, "Max Latency (ms)")?,
+    })
+}
+
+#[derive(Default)]
+struct Dashboard {
+    queues: Vec<QueueRow>,
+    previous: Option<(Instant, u64)>,
+    throughput: Option<f64>,
+    history: VecDeque<u64>,
+    search: Option<SearchLatency>,
+    errors: Vec<ErrorRow>,
+    fetch_error: Option<String>,
+    updated: Option<DateTime<Local>>,
+}
+
+impl Dashboard {
+    fn index_lag(&self) -> Option<f64> {
+        self.queues
+            .iter()
+            .find(|queue| queue.name == EMBEDDING_QUEUE)
+            .and_then(|queue| queue.oldest_pending_seconds)
+    }
+
+    fn record_queues(&mut self, queues: Vec<QueueRow>, at: Instant) {
+        let processed = processed_total(&queues);
+        // A counter that went backwards means the server restarted.
+        self.throughput = match self.previous {
+            Some((then, before)) if processed >= before && at > then => {
+                Some((processed - before) as f64 / (at - then).as_secs_f64())
+            }
+            _ => None,
+        };
+        if let Some(rate) = self.throughput {
+            if self.history.len() == HISTORY {
+                self.history.pop_front();
+            }
+            self.history.push_back(rate.round() as u64);
+        }
+        self.previous = Some((at, processed));
+        self.queues = queues;
+    }
+
+    async fn refresh(&mut self, client: &HttpClient, uri: Option<&str>) {
+        let (queue, retrieval, audit) = tokio::join!(
+            client.queue_status(uri),
+            client.get::<Value>("/api/v1/observer/retrieval", &[]),
+            client.audit_logs(1, MAX_ERRORS as u32, &[], &["error"]),
+        );
+        let status = match queue {
+            Ok(status) => status,
+            Err(error) => {
+                self.fetch_error = Some(error.to_string());
+                return;
+            }
+        };
+        self.fetch_error = None;
+        self.record_queues(queue_rows(&status), Instant::now());
+        self.search = retrieval.ok().as_ref().and_then(search_latency);
+        let mut errors = queue_errors(&status);
+        if let Ok(audit) = audit {
+            errors.extend(api_errors(&audit));
+        }
+        errors.truncate(MAX_ERRORS);
+        self.errors = errors;
+        self.updated = Some(Local::now());
+    }
+}
+
+fn seconds(value: Option<f64>) -> String {
+    match value {
+        Some(secs) if secs >= 3600.0 => format!("{:.1}h", secs / 3600.0),
+        Some(secs) if secs >= 60.0 => format!("{:.1}m", secs / 60.0),
+        Some(secs) => format!("{secs:.0}s"),
+        None => "-".to_string(),
+    }
+}
+
+fn draw(frame: &mut Frame, dashboard: &Dashboard, options: &TopOptions) {
+    let [header, top, errors, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(9),
+        Constraint::Min(3),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [queues_area, ingest_area] =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(top);
+
+    let updated = dashboard
+        .updated
+        .map(|time| time.format("%H:%M:%S").to_string())
+        .unwrap_or_else(|| "never".to_string());
+    let mut title = vec![
+        Span::styled("ov top", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(format!(
+            "  {}{}  every {}s  updated {updated}",
+            options.server,
+            options
+                .uri
+                .map(|uri| format!("  {uri}"))
+                .unwrap_or_default(),
+            options.interval
+        )),
+    ];
+    if let Some(error) = &dashboard.fetch_error {
+        title.push(Span::styled(
+            format!("  {error}"),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    frame.render_widget(Paragraph::new(Line::from(title)), header);
+
+    let rows = dashboard.queues.iter().map(|queue| {
+        let errors = Cell::from(queue.errors.to_string()).style(if queue.errors > 0 {
+            Style::default().fg(Color::Red)
+        } else {
+            Style::default()
+        });
+        Row::new(vec![
+            Cell::from(queue.name.clone()),
+            Cell::from((queue.pending + queue.in_progress).to_string()),
+            Cell::from(queue.in_progress.to_string()),
+            Cell::from(queue.processed.to_string()),
+            errors,
+            Cell::from(seconds(queue.oldest_pending_seconds)),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(14),
+            Constraint::Length(7),
+            Constraint::Length(7),
+            Constraint::Length(9),
+            Constraint::Length(7),
+            Constraint::Length(8),
+        ],
+    )
+    .header(
+        Row::new(["queue", "depth", "active", "done", "errors", "oldest"])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::default().borders(Borders::ALL).title(" Queues "));
+    frame.render_widget(table, queues_area);
+
+    let ingest_block = Block::default().borders(Borders::ALL).title(" Ingestion ");
+    let inner = ingest_block.inner(ingest_area);
+    frame.render_widget(ingest_block, ingest_area);
+    let [stats, spark] = Layout::vertical([Constraint::Length(4), Constraint::Min(1)]).areas(inner);
+    let ms = |value: Option<f64>| value.map_or_else(|| "-".to_string(), |ms| format!("{ms:.0}ms"));
+    let lines = vec![
+        Line::from(format!(
+            "ingest  {}",
+            dashboard
+                .throughput
+                .map_or_else(|| "-".to_string(), |rate| format!("{rate:.1}/s"))
+        )),
+        Line::from(format!("lag     {}", seconds(dashboard.index_lag()))),
+        Line::from(format!(
+            "search  {} avg",
+            ms(dashboard.search.map(|search| search.avg_ms))
+        )),
+        Line::from(format!(
+            "        {} max",
+            ms(dashboard.search.map(|search| search.max_ms))
+        )),
+    ];
+    frame.render_widget(Paragraph::new(lines), stats);
+    let history: Vec<u64> = dashboard.history.iter().copied().collect();
+    let visible = &history[history.len().saturating_sub(spark.width as usize)..];
+    frame.render_widget(
+        Sparkline::default()
+            .data(visible)
+            .style(Style::default().fg(Color::Green)),
+        spark,
+    );
+
+    let error_rows = dashboard.errors.iter().map(|error| {
+        Row::new(vec![
+            Cell::from(error.when.clone()),
+            Cell::from(error.source.clone()).style(Style::default().fg(Color::Red)),
+            Cell::from(error.detail.clone()),
+        ])
+    });
+    let errors_table = Table::new(
+        error_rows,
+        [
+            Constraint::Length(8),
+            Constraint::Length(12),
+            Constraint::Min(20),
+        ],
+    )
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Recent errors "),
+    );
+    frame.render_widget(errors_table, errors);
+
+    frame.render_widget(
+        Paragraph::new("q quit  r refresh").style(Style::default().fg(Color::DarkGray)),
+        footer,
+    );
+}
+
+pub async fn top(client: &HttpClient, options: TopOptions<'_>) -> Result<()> {
+    if !options.interval.is_finite() || options.interval <= 0.0 {
+        return Err(Error::Client(
+            "--interval must be a positive number of seconds".to_string(),
+        ));
+    }
+    if !io::stdin().is_terminal() || !io::stderr().is_terminal() {
+        return Err(Error::Client(
+            "ov top needs a terminal; use ov observer queue or ov status in scripts".to_string(),
+        ));
+    }
+    let _screen = Screen::enter()?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stderr()))?;
+    let mut dashboard = Dashboard::default();
+    let interval = Duration::from_secs_f64(options.interval);
+    let mut next_refresh = Instant::now();
+
+    loop {
+        if Instant::now() >= next_refresh {
+            dashboard.refresh(client, options.uri).await;
+            next_refresh = Instant::now() + interval;
+        }
+        terminal.draw(|frame| draw(frame, &dashboard, &options))?;
+
+        if !event::poll(Duration::from_millis(200))? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            KeyCode::Char('r') => next_refresh = Instant::now(),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn status(embedding_processed: u64, embedding_oldest: Option<f64>) -> Value {
+        json!({
+            "queues": [
+                {"name": "Embedding", "pending": 40, "in_progress": 4, "processed": embedding_processed,
+                 "requeued": 0, "errors": 1, "oldest_pending_seconds": embedding_oldest},
+                {"name": "Semantic", "pending": 2, "in_progress": 1, "processed": 10,
+                 "requeued": 0, "errors": 0, "oldest_pending_seconds": null},
+                {"name": "Semantic-Nodes", "pending": 0, "in_progress": 0, "processed": 500,
+                 "requeued": 0, "errors": 0, "oldest_pending_seconds": null},
+            ],
+            "items": [
+                {"uri": "viking://resources/a.pdf", "queue": "Embedding", "state": "failed",
+                 "error": "rate limited"},
+                {"uri": "viking://resources/b.md", "queue": "Semantic", "state": "pending"},
+            ],
+        })
+    }
+
+    #[test]
+    fn throughput_and_lag_come_from_queue_samples() {
+        let mut dashboard = Dashboard::default();
+        let start = Instant::now();
+        dashboard.record_queues(queue_rows(&status(100, Some(12.0))), start);
+        assert_eq!(dashboard.throughput, None);
+        assert_eq!(dashboard.index_lag(), Some(12.0));
+
+        dashboard.record_queues(
+            queue_rows(&status(120, None)),
+            start + Duration::from_secs(2),
+        );
+        assert_eq!(dashboard.throughput, Some(10.0));
+        assert_eq!(dashboard.history, VecDeque::from([10]));
+        assert_eq!(dashboard.index_lag(), None);
+
+        // A restart resets the counters instead of reporting a negative rate.
+        dashboard.record_queues(queue_rows(&status(5, None)), start + Duration::from_secs(4));
+        assert_eq!(dashboard.throughput, None);
+    }
+
+    #[test]
+    fn dashboard_renders_in_a_small_terminal() {
+        let mut dashboard = Dashboard::default();
+        let start = Instant::now();
+        for (n, processed) in [100, 130, 170].into_iter().enumerate() {
+            dashboard.record_queues(
+                queue_rows(&status(processed, Some(90.0))),
+                start + Duration::from_secs(n as u64 * 2),
+            );
+        }
+        dashboard.errors = queue_errors(&status(0, None));
+        let options = TopOptions {
+            server: "http://localhost:1933",
+            uri: None,
+            interval: 2.0,
+        };
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(60, 16)).unwrap();
+        terminal
+            .draw(|frame| draw(frame, &dashboard, &options))
+            .unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("Embedding"));
+        assert!(screen.contains("ingest  20.0/s"));
+        assert!(screen.contains("1.5m"));
+    }
+
+    #[test]
+    fn errors_and_latency_are_read_from_observers() {
+        let errors = queue_errors(&status(0, None));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].detail, "viking://resources/a.pdf: rate limited");
+
+        let audit = json!({"items": [{"created_at": "2026-10-17T09:00:00+00:00",
+            "method": "DELETE", "route": "/api/v1/fs", "status_code": 403, "user_id": "bob"}]});
+        assert_eq!(api_errors(&audit)[0].source, "HTTP 403");
+
+        let table = "+------------------+-------+\n\
+                     | Metric           | Value |\n\
+                     | Total Queries    | 42    |\n\
+                     | Avg Latency (ms) | 120.5 |\n\
+                     | Max Latency (ms) | 980.0 |\n";
+        assert_eq!(
+            search_latency(&json!({ "status": table })),
+            Some(SearchLatency {
+                avg_ms: 120.5,
+                max_ms: 980.0
+            })
+        );
+        assert_eq!(
+            search_latency(&json!({ "status": "No retrieval queries recorded." })),
+            None
+        );
+    }
+}
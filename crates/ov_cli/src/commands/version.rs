@@ -0,0 +1,252 @@
+//! `ov version --remote`: the CLI and server versions side by side, and which
+//! of the endpoints this CLI's commands rely on the server actually serves.
+//!
+//! Support is read from the server's own OpenAPI schema rather than from its
+//! version number, so builds between releases are reported correctly.
+
+use colored::Colorize;
+use serde_json::{Value, json};
+
+use crate::client::HttpClient;
+use crate::error::Result;
+use crate::output::{OutputFormat, output_success};
+use crate::theme;
+
+use super::doctor::release_line;
+
+/// An API feature the CLI uses: its name, the endpoint that provides it, and
+/// the commands that need it.
+struct Feature {
+    name: &'static str,
+    method: &'static str,
+    path: &'static str,
+    commands: &'static str,
+}
+
+const FEATURES: &[Feature] = &[
+    Feature {
+        name: "search",
+        method: "post",
+        path: "/api/v1/search/find",
+        commands: "ov find",
+    },
+    Feature {
+        name: "grep",
+        method: "post",
+        path: "/api/v1/search/grep",
+        commands: "ov grep (falls back to reading files)",
+    },
+    Feature {
+        name: "glob",
+        method: "post",
+        path: "/api/v1/search/glob",
+        commands: "ov glob",
+    },
+    Feature {
+        name: "chunked-upload",
+        method: "post",
+        path: "/api/v1/resources/uploads",
+        commands: "ov add-resource --resume",
+    },
+    Feature {
+        name: "reindex",
+        method: "post",
+        path: "/api/v1/content/reindex",
+        commands: "ov reindex, ov index",
+    },
+    Feature {
+        name: "consistency",
+        method: "post",
+        path: "/api/v1/system/consistency",
+        commands: "ov index, ov system consistency, ov doctor",
+    },
+    Feature {
+        name: "queue-items",
+        method: "get",
+        path: "/api/v1/observer/queue/items",
+        commands: "ov queue status, ov queue wait",
+    },
+    Feature {
+        name: "readiness",
+        method: "get",
+        path: "/ready",
+        commands: "ov doctor",
+    },
+    Feature {
+        name: "pack",
+        method: "post",
+        path: "/api/v1/pack/export",
+        commands: "ov export, ov import",
+    },
+    Feature {
+        name: "backup",
+        method: "post",
+        path: "/api/v1/pack/backup",
+        commands: "ov backup",
+    },
+    Feature {
+        name: "snapshots",
+        method: "post",
+        path: "/api/v1/snapshot/commit",
+        commands: "ov snapshot",
+    },
+    Feature {
+        name: "watches",
+        method: "get",
+        path: "/api/v1/watches",
+        commands: "ov task watch",
+    },
+    Feature {
+        name: "privacy",
+        method: "get",
+        path: "/api/v1/privacy-configs",
+        commands: "ov privacy",
+    },
+    Feature {
+        name: "backend-sync",
+        method: "post",
+        path: "/api/v1/system/backend/sync-status",
+        commands: "ov system backend",
+    },
+    Feature {
+        name: "audit",
+        method: "get",
+        path: "/api/v1/console/audit",
+        commands: "ov audit tail",
+    },
+    Feature {
+        name: "usage",
+        method: "get",
+        path: "/api/v1/console/tokens",
+        commands: "ov stats",
+    },
+];
+
+/// Whether `schema` (an OpenAPI document) serves each feature; `None` when
+/// the schema has no `paths` to check against.
+fn feature_support(schema: &Value) -> Option<Vec<bool>> {
+    let paths = schema.get("paths")?.as_object()?;
+    Some(
+        FEATURES
+            .iter()
+            .map(|feature| {
+                paths
+                    .get(feature.path)
+                    .is_some_and(|operations| operations.get(feature.method).is_some())
+            })
+            .collect(),
+    )
+}
+
+pub async fn remote(client: &HttpClient, format: OutputFormat, compact: bool) -> Result<()> {
+    let cli_version = env!("OPENVIKING_CLI_VERSION");
+    let health: Value = client.get("/health", &[]).await?;
+    let server_version = health["version"].as_str();
+    // Servers can turn the schema off; that leaves support unknown, not absent.
+    let support = client
+        .get::<Value>("/openapi.json", &[])
+        .await
+        .ok()
+        .and_then(|schema| feature_support(&schema));
+
+    let rows: Vec<Value> = FEATURES
+        .iter()
+        .enumerate()
+        .map(|(i, feature)| {
+            json!({
+                "feature": feature.name,
+                "endpoint": format!("{} {}", feature.method.to_uppercase(), feature.path),
+                "supported": support.as_ref().map(|support| support[i]),
+                "commands": feature.commands,
+            })
+        })
+        .collect();
+
+    if !matches!(format, OutputFormat::Table) {
+        output_success(
+            json!({
+                "cli_version": cli_version,
+                "server": client.server_url(),
+                "server_version": server_version,
+                "features": rows,
+            }),
+            format,
+            compact,
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{}     {}",
+        theme::muted("CLI:"),
+        theme::version(cli_version).bold()
+    );
+    println!(
+        "{}  {} {}",
+        theme::muted("Server:"),
+        theme::sky_value(server_version.unwrap_or("unknown")).bold(),
+        theme::muted(format!("({})", client.server_url()))
+    );
+    if let (Some(ours), Some(theirs)) = (
+        release_line(cli_version),
+        server_version.and_then(release_line),
+    ) && ours != (0, 0)
+        && theirs < ours
+    {
+        println!(
+            "{}",
+            theme::warning(
+                "The server is older than this CLI; some commands may not be supported."
+            )
+        );
+    }
+    println!();
+    output_success(Value::Array(rows), format, compact);
+    match &support {
+        Some(support) => {
+            let missing = support.iter().filter(|supported| !**supported).count();
+            if missing > 0 {
+                println!(
+                    "{}",
+                    theme::muted(format!(
+                        "{missing} of {} features are not available on this server.",
+                        FEATURES.len()
+                    ))
+                );
+            }
+        }
+        None => println!(
+            "{}",
+            theme::warning(
+                "The server does not publish /openapi.json, so feature support is unknown."
+            )
+        ),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn feature_support_checks_path_and_method() {
+        let schema = json!({
+            "paths": {
+                "/api/v1/search/find": {"post": {}},
+                "/api/v1/search/grep": {"get": {}},
+                "/ready": {"get": {}},
+            }
+        });
+        let support = feature_support(&schema).unwrap();
+        let supported: Vec<&str> = FEATURES
+            .iter()
+            .zip(support)
+            .filter(|(_, supported)| *supported)
+            .map(|(feature, _)| feature.name)
+            .collect();
+
+        assert_eq!(supported, vec!["search", "readiness"]);
+        assert!(feature_support(&json!({"detail": "Not Found"})).is_none());
+    }
+}
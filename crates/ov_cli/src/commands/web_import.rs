@@ -0,0 +1,482 @@
+//! `ov add url`: crawl web pages, such as a documentation site, and add each
+//! one as a Markdown resource.
+//!
+//! Pages are fetched here rather than by the server. The main content of an
+//! HTML page (its `<article>` or `<main>`, else the body without navigation,
+//! headers and footers) is converted to Markdown. Links are followed breadth
+//! first up to `--depth`, only on the start URL's host with `--same-domain`,
+//! and every page is keyed by its canonical URL, so that aliases, redirects
+//! and `#fragment` links are uploaded once.
+
+use std::collections::{HashSet, VecDeque};
+use std::time::Duration;
+
+use reqwest::header::CONTENT_TYPE;
+use scraper::{ElementRef, Html};
+use serde_json::{Value, json};
+use url::Url;
+
+use super::mcp_import::{markdown_file_name, upload_file};
+use crate::client::HttpClient;
+use crate::error::{Error, Result};
+use crate::html_markdown::{element_markdown, is_chrome, selector};
+use crate::interrupt;
+use crate::output::{OutputFormat, output_success};
+use crate::theme;
+
+/// Selectors tried, in order, for a page's main content.
+const CONTENT_SELECTORS: &[&str] = &[
+    "article",
+    "main",
+    "[role=main]",
+    "#content",
+    ".markdown-body",
+    ".content",
+];
+/// A content candidate with less text than this is passed over.
+const MIN_CONTENT_CHARS: usize = 200;
+/// Links to files with these extensions are never crawled.
+const ASSET_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "svg", "webp", "ico", "css", "js", "json", "xml", "pdf", "zip",
+    "gz", "tgz", "tar", "mp3", "mp4", "webm", "woff", "woff2", "ttf",
+];
+
+pub struct AddUrlOptions<'a> {
+    pub url: &'a str,
+    pub depth: usize,
+    pub same_domain: bool,
+    pub parent: &'a str,
+    pub limit: usize,
+    pub dry_run: bool,
+    pub timeout: u64,
+}
+
+/// A fetched page as Markdown, with the links it points to.
+#[derive(Debug)]
+pub(crate) struct Page {
+    pub(crate) canonical: Url,
+    pub(crate) title: Option<String>,
+    pub(crate) markdown: String,
+    pub(crate) links: Vec<Url>,
+}
+
+/// A page body `ov` can turn into a resource, and where it was served from
+/// after redirects.
+pub(crate) struct Response {
+    pub(crate) url: Url,
+    pub(crate) html: bool,
+    pub(crate) body: String,
+}
+
+/// `url` without its fragment: the key pages are deduplicated by.
+pub(crate) fn normalize(mut url: Url) -> Url {
+    url.set_fragment(None);
+    url
+}
+
+/// Whether a link may be a page: http(s), and not an image, stylesheet or
+/// archive.
+fn crawlable(url: &Url) -> bool {
+    let extension = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, extension)| extension.to_ascii_lowercase());
+    matches!(url.scheme(), "http" | "https")
+        && !extension.is_some_and(|extension| ASSET_EXTENSIONS.contains(&extension.as_str()))
+}
+
+fn text_len(element: &ElementRef) -> usize {
+    element.text().map(|text| text.trim().chars().count()).sum()
+}
+
+/// The element holding a page's main content.
+fn content_root(document: &Html) -> ElementRef<'_> {
+    CONTENT_SELECTORS
+        .iter()
+        .find_map(|css| {
+            document
+                .select(&selector(css))
+                .find(|element| !is_chrome(element) && text_len(element) >= MIN_CONTENT_CHARS)
+        })
+        .or_else(|| document.select(&selector("body")).next())
+        .unwrap_or_else(|| document.root_element())
+}
+
+pub(crate) fn parse_page(html: &str, url: &Url) -> Page {
+    let document = Html::parse_document(html);
+    let base = document
+        .select(&selector("base[href]"))
+        .next()
+        .and_then(|base| url.join(base.attr("href")?).ok())
+        .unwrap_or_else(|| url.clone());
+    let canonical = document
+        .select(&selector("link[rel~=canonical][href]"))
+        .next()
+        .and_then(|link| base.join(link.attr("href")?).ok())
+        .filter(crawlable)
+        .unwrap_or_else(|| url.clone());
+    let title = document
+        .select(&selector("title"))
+        .chain(document.select(&selector("h1")))
+        .map(|element| element.text().collect::<Vec<_>>().join(" "))
+        .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+        .find(|text| !text.is_empty());
+    let links = document
+        .select(&selector("a[href]"))
+        .filter_map(|link| base.join(link.attr("href")?).ok())
+        .filter(crawlable)
+        .map(normalize)
+        .collect();
+    Page {
+        canonical: normalize(canonical),
+        title,
+        markdown: element_markdown(content_root(&document), &base),
+        links,
+    }
+}
+
+pub(crate) fn http_client(timeout: u64) -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .user_agent(format!("ov/{}", env!("OPENVIKING_CLI_VERSION")))
+        .timeout(Duration::from_secs(timeout))
+        .build()
+        .map_err(|e| Error::Network(format!("Failed to build HTTP client: {e}")))
+}
+
+async fn get(http: &reqwest::Client, url: &Url) -> Result<reqwest::Response> {
+    let response = http
+        .get(url.clone())
+        .send()
+        .await
+        .map_err(|e| Error::Network(format!("Failed to fetch {url}: {e}")))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(Error::Client(format!("{url} answered HTTP {status}")));
+    }
+    Ok(response)
+}
+
+/// The body of `url` whatever its type, and where it was served from.
+pub(crate) async fn fetch_text(http: &reqwest::Client, url: &Url) -> Result<(Url, String)> {
+    let response = get(http, url).await?;
+    let url = response.url().clone();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| Error::Network(format!("Failed to read {url}: {e}")))?;
+    Ok((url, body))
+}
+
+/// Fetch `url`; `None` when it is neither HTML nor text.
+pub(crate) async fn fetch(http: &reqwest::Client, url: &Url) -> Result<Option<Response>> {
+    let response = get(http, url).await?;
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("text/html")
+        .to_ascii_lowercase();
+    let html = content_type.contains("html");
+    if !html && !content_type.starts_with("text/") {
+        return Ok(None);
+    }
+    let url = response.url().clone();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| Error::Network(format!("Failed to read {url}: {e}")))?;
+    Ok(Some(Response { url, html, body }))
+}
+
+/// The uploaded file: the page's Markdown under its title and source URL.
+pub(crate) fn page_document(page: &Page) -> String {
+    let mut document = String::new();
+    if let Some(title) = page
+        .title
+        .as_deref()
+        .filter(|_| !page.markdown.starts_with("# "))
+    {
+        document.push_str(&format!("# {title}\n\n"));
+    }
+    document.push_str(&format!(
+        "Source: <{}>\n\n{}\n",
+        page.canonical, page.markdown
+    ));
+    document
+}
+
+/// The page's title, else the last segment of its path, else its host.
+pub(crate) fn page_name(page: &Page) -> &str {
+    page.title
+        .as_deref()
+        .or_else(|| {
+            page.canonical
+                .path_segments()
+                .and_then(|mut segments| segments.rfind(|segment| !segment.is_empty()))
+        })
+        .or_else(|| page.canonical.host_str())
+        .unwrap_or("page")
+}
+
+fn row(
+    url: &Url,
+    depth: usize,
+    status: &str,
+    title: Option<&str>,
+    uri: Option<&str>,
+    error: Option<String>,
+) -> Value {
+    json!({
+        "url": url.as_str(),
+        "depth": depth,
+        "status": status,
+        "title": title,
+        "uri": uri,
+        "error": error,
+    })
+}
+
+pub async fn add_url(
+    client: &HttpClient,
+    options: AddUrlOptions<'_>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let start = Url::parse(options.url)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .ok_or_else(|| {
+            Error::Client(format!(
+                "Invalid URL '{}'; expected an http:// or https:// URL",
+                options.url
+            ))
+        })?;
+    let http = http_client(options.timeout)?;
+    let host = start.host_str().map(str::to_string);
+    let start = normalize(start);
+
+    let dir = tempfile::tempdir()?;
+    let mut taken = HashSet::new();
+    // URLs queued or fetched, and the canonical URLs of pages kept.
+    let mut seen = HashSet::from([start.clone()]);
+    let mut kept = HashSet::new();
+    let mut queue = VecDeque::from([(start, 0)]);
+    let mut rows = Vec::new();
+    let mut pages = 0;
+    let mut imported = 0;
+    let mut failed = 0;
+    let mut duplicates = 0;
+    let mut interrupted = false;
+    interrupt::enter_batch();
+    while let Some((url, depth)) = queue.pop_front() {
+        if pages >= options.limit {
+            queue.push_front((url, depth));
+            break;
+        }
+        eprintln!("{}", theme::muted(format!("[{}] {url}", rows.len() + 1)));
+        let response = match interrupt::cancellable(fetch(&http, &url)).await {
+            Ok(Some(response)) => response,
+            Ok(None) => {
+                rows.push(row(
+                    &url,
+                    depth,
+                    "skipped",
+                    None,
+                    None,
+                    Some("not an HTML or text page".to_string()),
+                ));
+                continue;
+            }
+            Err(Error::Interrupted) => {
+                interrupted = true;
+                break;
+            }
+            Err(e) => {
+                failed += 1;
+                rows.push(row(&url, depth, "failed", None, None, Some(e.to_string())));
+                continue;
+            }
+        };
+        let page = if response.html {
+            parse_page(&response.body, &response.url)
+        } else {
+            Page {
+                canonical: normalize(response.url.clone()),
+                title: None,
+                markdown: response.body,
+                links: Vec::new(),
+            }
+        };
+        seen.insert(normalize(response.url));
+        seen.insert(page.canonical.clone());
+        if !kept.insert(page.canonical.clone()) {
+            duplicates += 1;
+            rows.push(row(
+                &page.canonical,
+                depth,
+                "duplicate",
+                page.title.as_deref(),
+                None,
+                None,
+            ));
+            continue;
+        }
+        if depth < options.depth {
+            for link in &page.links {
+                if options.same_domain && link.host_str() != host.as_deref() {
+                    continue;
+                }
+                if seen.insert(link.clone()) {
+                    queue.push_back((link.clone(), depth + 1));
+                }
+            }
+        }
+
+        pages += 1;
+        let title = page.title.as_deref();
+        if options.dry_run {
+            rows.push(row(&page.canonical, depth, "planned", title, None, None));
+            continue;
+        }
+        let path = dir
+            .path()
+            .join(markdown_file_name(page_name(&page), &mut taken));
+        std::fs::write(&path, page_document(&page))?;
+        match interrupt::cancellable(upload_file(client, &path, options.parent)).await {
+            Ok(uri) => {
+                imported += 1;
+                rows.push(row(
+                    &page.canonical,
+                    depth,
+                    "imported",
+                    title,
+                    Some(&uri),
+                    None,
+                ));
+            }
+            Err(Error::Interrupted) => {
+                interrupted = true;
+                break;
+            }
+            Err(e) => {
+                failed += 1;
+                rows.push(row(
+                    &page.canonical,
+                    depth,
+                    "failed",
+                    title,
+                    None,
+                    Some(e.to_string()),
+                ));
+            }
+        }
+    }
+    let remaining = queue.len();
+
+    if matches!(format, OutputFormat::Table) {
+        output_success(Value::Array(rows), format, compact);
+        let mut summary = if options.dry_run {
+            format!("{pages} page(s) would be imported into {}", options.parent)
+        } else {
+            format!(
+                "{imported} of {pages} page(s) imported into {}: {failed} failed, {duplicates} duplicate(s)",
+                options.parent
+            )
+        };
+        if remaining > 0 && !interrupted {
+            summary.push_str(&format!(
+                "; stopped at --limit {} with {remaining} link(s) left",
+                options.limit
+            ));
+        }
+        println!("{}", theme::muted(summary));
+    } else {
+        output_success(
+            json!({
+                "url": options.url,
+                "parent": options.parent,
+                "depth": options.depth,
+                "dry_run": options.dry_run,
+                "pages": pages,
+                "imported": imported,
+                "failed": failed,
+                "duplicates": duplicates,
+                "remaining": remaining,
+                "results": rows,
+            }),
+            format,
+            compact,
+        );
+    }
+    if interrupted {
+        return Err(Error::Interrupted);
+    }
+    if failed > 0 {
+        return Err(Error::AlreadyReported);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Url {
+        Url::parse("https://docs.example.com/guide/intro.html").unwrap()
+    }
+
+    #[test]
+    fn pages_keep_main_content_canonical_url_and_crawlable_links() {
+        let filler = "Words about the page. ".repeat(20);
+        let html = format!(
+            r#"<html><head>
+                <title>Intro | Docs</title>
+                <link rel="canonical" href="/guide/intro">
+            </head><body>
+                <header>Site header</header>
+                <div class="content"><p>Sidebar-ish teaser</p></div>
+                <main><h2>Intro</h2><p>{filler}</p>
+                  <a href="install.html#top">Install</a>
+                  <a href="https://other.example.org/">Other</a>
+                  <a href="logo.png">Logo</a>
+                  <a href="mailto:docs@example.com">Mail</a>
+                </main>
+            </body></html>"#
+        );
+        let page = parse_page(&html, &base());
+        assert_eq!(
+            page.canonical.as_str(),
+            "https://docs.example.com/guide/intro"
+        );
+        assert_eq!(page.title.as_deref(), Some("Intro | Docs"));
+        assert!(
+            page.markdown
+                .starts_with("## Intro\n\nWords about the page.")
+        );
+        assert!(!page.markdown.contains("Site header"));
+        assert!(!page.markdown.contains("teaser"));
+        assert_eq!(
+            page.links.iter().map(Url::as_str).collect::<Vec<_>>(),
+            [
+                "https://docs.example.com/guide/install.html",
+                "https://other.example.org/",
+            ]
+        );
+        assert_eq!(
+            page_document(&page),
+            format!(
+                "# Intro | Docs\n\nSource: <https://docs.example.com/guide/intro>\n\n{}\n",
+                page.markdown
+            )
+        );
+
+        let untitled = Page {
+            canonical: Url::parse("https://docs.example.com/guide/install/").unwrap(),
+            title: None,
+            markdown: String::new(),
+            links: Vec::new(),
+        };
+        assert_eq!(page_name(&untitled), "install");
+    }
+}
@@ -0,0 +1,149 @@
+//! `ov workspace`: list workspaces and choose the default one.
+//!
+//! A workspace is a server account: every request carries it as
+//! `X-OpenViking-Account`, which scopes the `viking://` tree and the API
+//! calls. `ov workspace use <name>` saves it as the `account` of the active
+//! config and of the saved config it was switched to from, so `ov config
+//! switch` switches workspaces too. `--workspace` overrides it for one command.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Value, json};
+
+use crate::client::HttpClient;
+use crate::config::Config;
+use crate::config_wizard::{ConfigStore, validate_account_id_value};
+use crate::error::{Error, Result};
+use crate::output::{OutputFormat, output_success};
+
+/// The workspaces named by the active config and the saved configs, with the
+/// saved configs that use each.
+fn local_rows(current: Option<&str>, configs: &[(String, Option<String>)]) -> Vec<Value> {
+    let mut workspaces: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    if let Some(current) = current {
+        workspaces.entry(current).or_default();
+    }
+    for (name, account) in configs {
+        if let Some(account) = account {
+            workspaces.entry(account).or_default().push(name);
+        }
+    }
+    workspaces
+        .into_iter()
+        .map(|(workspace, configs)| {
+            json!({
+                "workspace": workspace,
+                "current": Some(workspace) == current,
+                "configs": configs.join(","),
+            })
+        })
+        .collect()
+}
+
+/// Rows for the server's account list.
+fn server_rows(accounts: &Value, current: Option<&str>) -> Vec<Value> {
+    accounts
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|account| {
+            let workspace = account.get("account_id")?.as_str()?;
+            Some(json!({
+                "workspace": workspace,
+                "current": Some(workspace) == current,
+                "users": account.get("user_count").cloned().unwrap_or(Value::Null),
+                "created_at": account.get("created_at").cloned().unwrap_or(Value::Null),
+            }))
+        })
+        .collect()
+}
+
+/// List workspaces. With a root key (`root_client`) the server's accounts are
+/// listed; otherwise those named by the saved configs.
+pub async fn list(
+    config: &Config,
+    root_client: Option<&HttpClient>,
+    format: OutputFormat,
+    compact: bool,
+) -> Result<()> {
+    let current = config.account.as_deref();
+    let rows = match root_client {
+        Some(client) => server_rows(&client.admin_list_accounts().await?, current),
+        None => {
+            let report = ConfigStore::new()?.list_configs_report()?;
+            let configs: Vec<(String, Option<String>)> = report
+                .configs
+                .into_iter()
+                .map(|entry| (entry.name, entry.config.account))
+                .collect();
+            local_rows(current, &configs)
+        }
+    };
+    output_success(rows, format, compact);
+    Ok(())
+}
+
+/// Make `name` the default workspace of the active config and its saved copy.
+pub fn use_workspace(name: &str, format: OutputFormat, compact: bool) -> Result<()> {
+    validate_account_id_value(name)?;
+    let store = ConfigStore::new()?;
+    let mut config = store.load_active()?.ok_or(Error::MissingConfig)?;
+    let saved = store
+        .list_configs_report()?
+        .configs
+        .into_iter()
+        .find(|entry| entry.is_active)
+        .map(|entry| entry.name);
+    let previous = config.account.replace(name.trim().to_string());
+    if let Some(saved) = &saved {
+        store.save_named_config(saved, &config)?;
+    }
+    store.save_active_config(&config)?;
+    output_success(
+        json!({
+            "workspace": name.trim(),
+            "previous": previous,
+            "config": saved,
+        }),
+        format,
+        compact,
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_workspaces_come_from_saved_configs_and_the_active_one() {
+        let configs = vec![
+            ("prod".to_string(), Some("acme".to_string())),
+            ("staging".to_string(), Some("acme".to_string())),
+            ("local".to_string(), None),
+            ("partner".to_string(), Some("globex".to_string())),
+        ];
+        let rows = local_rows(Some("scratch"), &configs);
+        assert_eq!(
+            rows,
+            vec![
+                json!({"workspace": "acme", "current": false, "configs": "prod,staging"}),
+                json!({"workspace": "globex", "current": false, "configs": "partner"}),
+                json!({"workspace": "scratch", "current": true, "configs": ""}),
+            ]
+        );
+    }
+
+    #[test]
+    fn server_accounts_mark_the_current_workspace() {
+        let accounts = json!([
+            {"account_id": "acme", "created_at": "2026-01-01T00:00:00Z", "user_count": 3},
+            {"account_id": "globex", "created_at": "2026-02-01T00:00:00Z", "user_count": 1},
+        ]);
+        let rows = server_rows(&accounts, Some("globex"));
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["current"], false);
+        assert_eq!(rows[1]["current"], true);
+        assert_eq!(rows[1]["users"], 1);
+    }
+}
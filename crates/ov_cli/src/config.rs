@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+use crate::base_client::TlsFiles;
 use crate::error::{Error, Result};
 
 const OPENVIKING_CLI_CONFIG_ENV: &str = "OPENVIKING_CLI_CONFIG_FILE";
@@ -35,6 +37,19 @@ impl Default for UploadConfig {
     }
 }
 
+/// An OpenAI-compatible chat completions endpoint, used by `ov ask`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmConfig {
+    /// Base URL the `/chat/completions` path is appended to, e.g.
+    /// `https://api.openai.com/v1`.
+    pub api_base: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    pub model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     #[serde(default = "default_url", skip_serializing_if = "is_default_url")]
@@ -56,6 +71,9 @@ pub struct Config {
         skip_serializing_if = "is_default_timeout"
     )]
     pub timeout: f64,
+    /// Seconds allowed to establish a connection, separate from `timeout`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connect_timeout: Option<f64>,
     #[serde(
         default = "default_output_format",
         skip_serializing_if = "is_default_output"
@@ -88,10 +106,46 @@ pub struct Config {
     pub extra_headers: Option<HashMap<String, String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub gateway_token: Option<String>,
+    /// Proxy URL (`http://`, `https://`, `socks5://`), or `none` to ignore
+    /// `HTTP(S)_PROXY`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// PEM bundle of extra CA certificates to trust, e.g. a private CA.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_cert: Option<String>,
+    /// PEM client certificate for mutual TLS.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_cert: Option<String>,
+    /// PEM private key for `client_cert`, unless that file already holds it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<String>,
+    /// Tokenizer for `ov tokens count` and `--tokens`: `cl100k` (default),
+    /// `o200k`, or `estimate`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokenizer: Option<String>,
+    /// Directory URI `ov clip` adds captures under.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clip_inbox: Option<String>,
+    /// Record commands and the URIs they touch for `ov recent`.
+    #[serde(
+        default = "default_history",
+        skip_serializing_if = "is_default_history"
+    )]
+    pub history: bool,
+    /// WebAssembly plugins that transform output and filter uploaded files,
+    /// run in this order; see `crate::wasm_plugin`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub wasm_plugins: Vec<String>,
+    /// Regexes `--redact` masks besides emails, phone numbers, and ID
+    /// numbers, by the name matches are masked under.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub redact_patterns: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub llm: Option<LlmConfig>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub(crate) struct EffectiveAuth {
+pub struct EffectiveAuth {
     pub api_key: Option<String>,
     pub account: Option<String>,
     pub user: Option<String>,
@@ -121,6 +175,10 @@ fn default_verbose() -> bool {
     false
 }
 
+fn default_history() -> bool {
+    true
+}
+
 fn is_default_url(value: &str) -> bool {
     value == default_url()
 }
@@ -145,6 +203,10 @@ fn is_default_verbose(value: &bool) -> bool {
     *value == default_verbose()
 }
 
+fn is_default_history(value: &bool) -> bool {
+    *value == default_history()
+}
+
 fn is_default_profile(value: &bool) -> bool {
     !*value
 }
@@ -160,6 +222,7 @@ impl Default for Config {
             actor_peer_id: None,
             agent_id: None,
             timeout: 60.0,
+            connect_timeout: None,
             output: "table".to_string(),
             echo_command: true,
             show_progress: false,
@@ -168,6 +231,16 @@ impl Default for Config {
             upload: UploadConfig::default(),
             extra_headers: None,
             gateway_token: None,
+            proxy: None,
+            ca_cert: None,
+            client_cert: None,
+            client_key: None,
+            tokenizer: None,
+            clip_inbox: None,
+            history: true,
+            wasm_plugins: Vec::new(),
+            redact_patterns: BTreeMap::new(),
+            llm: None,
         }
     }
 }
@@ -247,6 +320,9 @@ impl Config {
         let config: Config = serde_json::from_str(&content)
             .map_err(|e| Error::Config(format!("Failed to parse config file: {}", e)))?;
         config.validate_identity_mode()?;
+        config.validate_server_url()?;
+        config.validate_proxy()?;
+        config.validate_timeouts()?;
         Ok(config)
     }
 
@@ -263,15 +339,15 @@ impl Config {
         Ok(())
     }
 
-    pub(crate) fn effective_auth(&self, sudo: bool) -> EffectiveAuth {
+    pub fn effective_auth(&self, sudo: bool) -> EffectiveAuth {
         self.effective_auth_with_overrides(None, None, None, sudo)
     }
 
-    pub(crate) fn effective_actor_peer_id(&self) -> Option<String> {
+    pub fn effective_actor_peer_id(&self) -> Option<String> {
         self.actor_peer_id.clone().or_else(|| self.agent_id.clone())
     }
 
-    pub(crate) fn effective_gateway_token(&self) -> Option<String> {
+    pub fn effective_gateway_token(&self) -> Option<String> {
         self.gateway_token
             .as_deref()
             .map(str::trim)
@@ -289,7 +365,7 @@ impl Config {
             })
     }
 
-    pub(crate) fn effective_extra_headers(&self) -> Option<HashMap<String, String>> {
+    pub fn effective_extra_headers(&self) -> Option<HashMap<String, String>> {
         let mut headers = self.extra_headers.clone().unwrap_or_default();
         headers.retain(|key, _| !key.eq_ignore_ascii_case(GATEWAY_TOKEN_HEADER));
         if headers.is_empty() {
@@ -308,7 +384,73 @@ impl Config {
         Ok(())
     }
 
-    pub(crate) fn effective_auth_with_overrides(
+    fn validate_server_url(&self) -> Result<()> {
+        if !cfg!(unix) && crate::base_client::unix_socket_path(&self.url).is_some() {
+            return Err(Error::Config(
+                "unix:// server URLs are only supported on Unix".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn wasm_plugin_paths(&self) -> Vec<PathBuf> {
+        self.wasm_plugins
+            .iter()
+            .map(|path| path.trim())
+            .filter(|path| !path.is_empty())
+            .map(expand_home)
+            .collect()
+    }
+
+    pub fn tls_files(&self) -> TlsFiles {
+        let path = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(expand_home)
+        };
+        TlsFiles {
+            ca_cert: path(&self.ca_cert),
+            client_cert: path(&self.client_cert),
+            client_key: path(&self.client_key),
+        }
+    }
+
+    /// Load the certificate files so a bad path fails before any request.
+    pub fn validate_tls(&self) -> Result<()> {
+        crate::base_client::configure_tls(reqwest::Client::builder(), &self.tls_files()).map(|_| ())
+    }
+
+    pub fn connect_timeout(&self) -> Option<Duration> {
+        self.connect_timeout.map(Duration::from_secs_f64)
+    }
+
+    /// Reject zero, negative or non-finite timeouts, which reqwest would
+    /// otherwise panic on or treat as "fail immediately".
+    pub fn validate_timeouts(&self) -> Result<()> {
+        let settings = [
+            ("timeout", Some(self.timeout)),
+            ("connect_timeout", self.connect_timeout),
+        ];
+        for (name, value) in settings {
+            if let Some(secs) = value
+                && !(secs.is_finite() && secs > 0.0)
+            {
+                return Err(Error::Config(format!(
+                    "{name} must be a positive number of seconds, got {secs}"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn validate_proxy(&self) -> Result<()> {
+        crate::base_client::configure_proxy(reqwest::Client::builder(), self.proxy.as_deref())
+            .map(|_| ())
+    }
+
+    pub fn effective_auth_with_overrides(
         &self,
         api_key_override: Option<String>,
         account_override: Option<String>,
@@ -333,12 +475,29 @@ impl Config {
     }
 }
 
+/// Resolve a leading `~/` in a config file path against the home directory.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
 pub fn default_config_path() -> Result<PathBuf> {
     let home = dirs::home_dir()
         .ok_or_else(|| Error::Config("Could not determine home directory".to_string()))?;
     Ok(home.join(".openviking").join("ovcli.conf"))
 }
 
+/// The config file commands read: `OPENVIKING_CLI_CONFIG_FILE` when set,
+/// otherwise the default path.
+pub fn active_config_path() -> Result<PathBuf> {
+    match std::env::var(OPENVIKING_CLI_CONFIG_ENV) {
+        Ok(path) => Ok(PathBuf::from(path)),
+        Err(_) => default_config_path(),
+    }
+}
+
 pub fn display_config_home() -> String {
     let path = default_config_path()
         .ok()
@@ -420,6 +579,42 @@ mod tests {
         assert!(config.upload.exclude.is_none());
     }
 
+    #[test]
+    fn from_file_validates_proxy_url() {
+        let dir = tempfile::tempdir().expect("tempdir should be created");
+        let path = dir.path().join("ovcli.conf");
+
+        for proxy in ["socks5h://127.0.0.1:1080", "http://proxy.corp:3128", "none"] {
+            std::fs::write(&path, format!(r#"{{"proxy": "{proxy}"}}"#)).unwrap();
+            let config = Config::from_file(&path.to_string_lossy())
+                .expect("supported proxy settings should load");
+            assert_eq!(config.proxy.as_deref(), Some(proxy));
+        }
+
+        std::fs::write(&path, r#"{"proxy": "ftp://proxy.corp"}"#).unwrap();
+        let error = Config::from_file(&path.to_string_lossy())
+            .expect_err("unsupported proxy scheme should be rejected");
+        assert!(matches!(error, Error::Config(message) if message.contains("ftp://proxy.corp")));
+    }
+
+    #[test]
+    fn from_file_rejects_non_positive_timeouts() {
+        let dir = tempfile::tempdir().expect("tempdir should be created");
+        let path = dir.path().join("ovcli.conf");
+
+        std::fs::write(&path, r#"{"timeout": 30, "connect_timeout": 2.5}"#).unwrap();
+        let config = Config::from_file(&path.to_string_lossy()).expect("timeouts should load");
+        assert_eq!(
+            config.connect_timeout(),
+            Some(std::time::Duration::from_millis(2500))
+        );
+
+        std::fs::write(&path, r#"{"connect_timeout": 0}"#).unwrap();
+        let error = Config::from_file(&path.to_string_lossy())
+            .expect_err("a zero connect timeout should be rejected");
+        assert!(matches!(error, Error::Config(message) if message.contains("connect_timeout")));
+    }
+
     #[test]
     fn config_deserializes_agent_id_as_effective_actor_peer() {
         let config: Config = serde_json::from_str(
@@ -242,7 +242,7 @@ fn delete_saved_config(store: &ConfigStore, args: ConfigDeleteArgs) -> AgentResu
 
 pub(crate) fn print_success(output: AgentOutput, ctx: &CliContext) {
     match ctx.output_format {
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Ndjson => {
             let result = match output {
                 AgentOutput::AddEdit(result) => json!(result),
                 AgentOutput::Switch(result) => json!(result),
@@ -287,7 +287,7 @@ pub(crate) fn print_success(output: AgentOutput, ctx: &CliContext) {
 
 pub(crate) fn print_error(error: &AgentError, ctx: &CliContext) {
     match ctx.output_format {
-        OutputFormat::Json => eprintln!(
+        OutputFormat::Json | OutputFormat::Ndjson => eprintln!(
             "{}",
             json!({
                 "status": "error",
@@ -926,7 +926,10 @@ async fn status_box_runtime(active: Option<&Config>) -> StatusBoxRuntime {
         config.profile,
         config.effective_extra_headers(),
     )
-    .with_gateway_token(config.effective_gateway_token());
+    .with_gateway_token(config.effective_gateway_token())
+    .with_proxy(config.proxy.clone())
+    .with_tls(config.tls_files())
+    .with_connect_timeout(config.connect_timeout());
 
     match client.get::<Value>("/api/v1/system/status", &[]).await {
         Ok(status) => {
@@ -4607,6 +4610,9 @@ fn root_admin_client(config: &Config, root_api_key: &str) -> BaseClient {
         config.effective_extra_headers(),
     )
     .with_gateway_token(config.effective_gateway_token())
+    .with_proxy(config.proxy.clone())
+    .with_tls(config.tls_files())
+    .with_connect_timeout(config.connect_timeout())
 }
 
 async fn list_root_accounts(client: &BaseClient) -> Result<Vec<RootAccountSummary>> {
@@ -0,0 +1,1083 @@
+//! `ov daemon`: a local proxy that keeps server connections warm and caches
+//! read results.
+//!
+//! The daemon listens on a Unix socket under `~/.openviking/daemon/`, one per
+//! server URL, and forwards each request with the caller's own headers, so it
+//! never holds credentials. Abstracts, overviews, reads, listings, and search
+//! results are cached for a short TTL, keyed by the request and its identity
+//! headers. Any other request that is not a GET clears the cache, since it may
+//! have changed what those reads return. Commands route through a running
+//! daemon for their server on their own; `OPENVIKING_NO_DAEMON=1` bypasses it.
+//! The URIs of recent reads are also kept, unkeyed, for shell completion.
+//!
+//! With `--metrics`, the daemon also serves Prometheus metrics over plain HTTP:
+//! forwarded requests by method, route, and status, how long the server took
+//! to answer them, cache hits and misses, and the commands waiting in the
+//! offline queue for its server.
+//!
+//! The daemon also runs the schedules saved with `ov schedule add` for its
+//! server; see `commands::schedule`.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt::Write as _;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use crate::base_client::{UNIX_SOCKET_BASE_URL, configure_unix_socket};
+use crate::config::default_config_path;
+use crate::error::{Error, Result};
+
+pub const NO_DAEMON_ENV: &str = "OPENVIKING_NO_DAEMON";
+/// Response header telling whether a cacheable request was a `hit` or `miss`.
+pub const CACHE_HEADER: &str = "x-openviking-cache";
+
+const STATUS_PATH: &str = "/_daemon/status";
+const STOP_PATH: &str = "/_daemon/stop";
+const RECENT_PATH: &str = "/_daemon/recent";
+/// How many recently read URIs are kept for shell completion.
+const RECENT_URIS: usize = 200;
+const METRICS_PATH: &str = "/metrics";
+/// Upper bounds, in seconds, of the request latency histogram buckets.
+const LATENCY_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// GET endpoints whose responses are cached.
+const CACHED_READS: [&str; 6] = [
+    "/api/v1/content/abstract",
+    "/api/v1/content/overview",
+    "/api/v1/content/read",
+    "/api/v1/fs/ls",
+    "/api/v1/fs/tree",
+    "/api/v1/fs/stat",
+];
+/// POST endpoints that only read, so they are cached rather than invalidating.
+const CACHED_SEARCHES: [&str; 4] = [
+    "/api/v1/search/find",
+    "/api/v1/search/search",
+    "/api/v1/search/grep",
+    "/api/v1/search/glob",
+];
+/// Request headers that do not change the response, left out of cache keys.
+const UNKEYED_HEADERS: [&str; 5] = [
+    "host",
+    "content-length",
+    "user-agent",
+    "accept",
+    "accept-encoding",
+];
+/// Headers that are not passed through in either direction: hop-by-hop ones,
+/// plus `host` and `accept-encoding`, which the upstream client sets itself.
+const DROPPED_HEADERS: [&str; 10] = [
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+    "host",
+    "accept-encoding",
+];
+
+/// The socket a daemon for `server` listens on.
+pub fn socket_path(server: &str) -> Result<PathBuf> {
+    let config_path = default_config_path()?;
+    let home = config_path.parent().unwrap_or(Path::new("."));
+    let key = Sha256::digest(server.trim().trim_end_matches('/').as_bytes());
+    Ok(home
+        .join("daemon")
+        .join(format!("{}.sock", &hex::encode(key)[..16])))
+}
+
+/// Socket of a daemon running for `server`, unless `OPENVIKING_NO_DAEMON` is set.
+pub fn running_socket(server: &str) -> Option<PathBuf> {
+    if std::env::var_os(NO_DAEMON_ENV).is_some_and(|value| !value.is_empty() && value != "0") {
+        return None;
+    }
+    let path = socket_path(server).ok()?;
+    is_listening(&path).then_some(path)
+}
+
+/// Whether something accepts connections on `path`; a socket file left by a
+/// daemon that died does not.
+pub fn is_listening(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        std::os::unix::net::UnixStream::connect(path).is_ok()
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+/// The address `--metrics` listens on: `host:port`, or a bare port on
+/// localhost.
+pub fn parse_metrics_addr(value: &str) -> Result<SocketAddr> {
+    let value = value.trim();
+    if let Ok(port) = value.parse::<u16>() {
+        return Ok(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port));
+    }
+    value.parse().map_err(|_| {
+        Error::Client(format!(
+            "Invalid --metrics '{value}'; use a port or host:port, e.g. 9464 or 0.0.0.0:9464"
+        ))
+    })
+}
+
+/// Ask the daemon on `socket` for its status.
+pub async fn status(socket: &Path) -> Result<Value> {
+    control(socket, Method::GET, STATUS_PATH).await
+}
+
+/// Ask the daemon on `socket` to shut down.
+pub async fn stop(socket: &Path) -> Result<Value> {
+    control(socket, Method::POST, STOP_PATH).await
+}
+
+/// URIs the daemon on `socket` recently served reads for, most recent first.
+/// Shell completion calls this outside any async runtime, so it speaks HTTP
+/// over a plain blocking socket with a short timeout.
+pub fn recent_uris(socket: &Path) -> Result<Vec<String>> {
+    #[cfg(unix)]
+    {
+        use std::io::{Read, Write};
+
+        let mut stream = std::os::unix::net::UnixStream::connect(socket)?;
+        stream.set_read_timeout(Some(Duration::from_millis(500)))?;
+        stream.set_write_timeout(Some(Duration::from_millis(500)))?;
+        write!(
+            stream,
+            "GET {RECENT_PATH} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n"
+        )?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response)?;
+        let body = response
+            .windows(4)
+            .position(|window| window == b"\r\n\r\n")
+            .map_or(&response[..0], |end| &response[end + 4..]);
+        let recent: Value = serde_json::from_slice(body)?;
+        Ok(recent["uris"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|uri| uri.as_str().map(str::to_string))
+            .collect())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = socket;
+        Ok(Vec::new())
+    }
+}
+
+async fn control(socket: &Path, method: Method, path: &str) -> Result<Value> {
+    let client = configure_unix_socket(reqwest::Client::builder(), Some(socket))
+        .timeout(Duration::from_secs(5))
+        .build()
+        .map_err(|e| Error::from_reqwest("Failed to build daemon client", e))?;
+    let response = client
+        .request(method, format!("{UNIX_SOCKET_BASE_URL}{path}"))
+        .send()
+        .await
+        .map_err(|e| Error::from_reqwest("Failed to reach ov daemon", e))?;
+    response
+        .json()
+        .await
+        .map_err(|e| Error::from_reqwest("Failed to read ov daemon response", e))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Route {
+    Cached,
+    Invalidates,
+    Passthrough,
+}
+
+fn route(method: &Method, path: &str) -> Route {
+    if (*method == Method::GET && CACHED_READS.contains(&path))
+        || (*method == Method::POST && CACHED_SEARCHES.contains(&path))
+    {
+        Route::Cached
+    } else if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        Route::Passthrough
+    } else {
+        Route::Invalidates
+    }
+}
+
+impl Route {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Cached => "cached",
+            Self::Invalidates => "write",
+            Self::Passthrough => "passthrough",
+        }
+    }
+}
+
+/// Requests that only differ in headers such as the user agent share an entry;
+/// ones sent with another API key, account, or user do not.
+fn cache_key(method: &Method, path_and_query: &str, headers: &HeaderMap, body: &[u8]) -> String {
+    let mut keyed: Vec<_> = headers
+        .iter()
+        .filter(|(name, _)| !UNKEYED_HEADERS.contains(&name.as_str()))
+        .collect();
+    keyed.sort_by(|a, b| (a.0.as_str(), a.1.as_bytes()).cmp(&(b.0.as_str(), b.1.as_bytes())));
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{method} {path_and_query}\n"));
+    for (name, value) in keyed {
+        hasher.update(name.as_str());
+        hasher.update(b": ");
+        hasher.update(value.as_bytes());
+        hasher.update(b"\n");
+    }
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+fn forwarded_headers(headers: &HeaderMap) -> HeaderMap {
+    let mut forwarded = headers.clone();
+    for name in DROPPED_HEADERS {
+        forwarded.remove(name);
+    }
+    forwarded
+}
+
+#[derive(Debug, Clone)]
+struct CachedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+struct CacheEntry {
+    response: CachedResponse,
+    stored: Instant,
+    used: u64,
+}
+
+/// Responses kept for `ttl`, evicting the least recently used past `capacity`.
+struct ResponseCache {
+    entries: HashMap<String, CacheEntry>,
+    ttl: Duration,
+    capacity: usize,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+    invalidations: u64,
+}
+
+impl ResponseCache {
+    fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            ttl,
+            capacity,
+            clock: 0,
+            hits: 0,
+            misses: 0,
+            invalidations: 0,
+        }
+    }
+
+    fn get(&mut self, key: &str, now: Instant) -> Option<CachedResponse> {
+        self.clock += 1;
+        let fresh = self
+            .entries
+            .get(key)
+            .is_some_and(|entry| now.duration_since(entry.stored) < self.ttl);
+        if !fresh {
+            self.entries.remove(key);
+            self.misses += 1;
+            return None;
+        }
+        self.hits += 1;
+        let entry = self.entries.get_mut(key)?;
+        entry.used = self.clock;
+        Some(entry.response.clone())
+    }
+
+    fn insert(&mut self, key: String, response: CachedResponse, now: Instant) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.clock += 1;
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+            let ttl = self.ttl;
+            self.entries
+                .retain(|_, entry| now.duration_since(entry.stored) < ttl);
+        }
+        if self.entries.len() >= self.capacity
+            && !self.entries.contains_key(&key)
+            && let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.used)
+                .map(|(key, _)| key.clone())
+        {
+            self.entries.remove(&oldest);
+        }
+        self.entries.insert(
+            key,
+            CacheEntry {
+                response,
+                stored: now,
+                used: self.clock,
+            },
+        );
+    }
+
+    /// Drop every entry because of a write.
+    fn invalidate(&mut self) {
+        self.entries.clear();
+        self.invalidations += 1;
+    }
+
+    fn stats(&self) -> Value {
+        json!({
+            "entries": self.entries.len(),
+            "max_entries": self.capacity,
+            "ttl_secs": self.ttl.as_secs(),
+            "hits": self.hits,
+            "misses": self.misses,
+            "invalidations": self.invalidations,
+        })
+    }
+}
+
+/// The `uri` query parameter of a request, which every cached read takes.
+fn request_uri(path_and_query: &str) -> Option<String> {
+    let (_, query) = path_and_query.split_once('?')?;
+    url::form_urlencoded::parse(query.as_bytes())
+        .find(|(name, _)| name == "uri")
+        .map(|(_, uri)| uri.into_owned())
+        .filter(|uri| uri.starts_with("viking://"))
+}
+
+/// Recently read URIs, most recent first, without duplicates.
+struct RecentUris {
+    uris: VecDeque<String>,
+    capacity: usize,
+}
+
+impl RecentUris {
+    fn new(capacity: usize) -> Self {
+        Self {
+            uris: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    fn record(&mut self, uri: String) {
+        self.uris.retain(|known| *known != uri);
+        self.uris.push_front(uri);
+        self.uris.truncate(self.capacity);
+    }
+
+    fn list(&self) -> Vec<&str> {
+        self.uris.iter().map(String::as_str).collect()
+    }
+}
+
+#[derive(Debug, Default)]
+struct Histogram {
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+}
+
+/// Forwarded requests counted by method, route, and status, with how long
+/// each route took to answer. A request the server could not be reached for
+/// has status `error`.
+#[derive(Debug, Default)]
+struct RequestMetrics {
+    requests: BTreeMap<(String, &'static str, String), u64>,
+    latency: BTreeMap<&'static str, Histogram>,
+}
+
+impl RequestMetrics {
+    fn observe(
+        &mut self,
+        method: &Method,
+        route: Route,
+        status: Option<StatusCode>,
+        elapsed: Duration,
+    ) {
+        let status =
+            status.map_or_else(|| "error".to_string(), |status| status.as_u16().to_string());
+        *self
+            .requests
+            .entry((method.to_string(), route.label(), status))
+            .or_default() += 1;
+        self.latency
+            .entry(route.label())
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+    }
+}
+
+/// Append one metric family: its help and type, then its samples, each a
+/// name with optional labels followed by the value.
+fn family(text: &mut String, name: &str, kind: &str, help: &str, samples: &[String]) {
+    let _ = writeln!(text, "# HELP {name} {help}");
+    let _ = writeln!(text, "# TYPE {name} {kind}");
+    for sample in samples {
+        let _ = writeln!(text, "{sample}");
+    }
+}
+
+/// The daemon's metrics in the Prometheus text format. `queued` is left out
+/// when the offline queue could not be read.
+fn render_metrics(
+    requests: &RequestMetrics,
+    cache: &ResponseCache,
+    queued: Option<usize>,
+    uptime: Duration,
+) -> String {
+    let mut text = String::new();
+    family(
+        &mut text,
+        "ov_daemon_uptime_seconds",
+        "gauge",
+        "Seconds since the daemon started.",
+        &[format!("ov_daemon_uptime_seconds {}", uptime.as_secs())],
+    );
+
+    let name = "ov_daemon_requests_total";
+    let samples: Vec<String> = requests
+        .requests
+        .iter()
+        .map(|((method, route, status), count)| {
+            format!("{name}{{method=\"{method}\",route=\"{route}\",status=\"{status}\"}} {count}")
+        })
+        .collect();
+    family(
+        &mut text,
+        name,
+        "counter",
+        "Requests forwarded to the server.",
+        &samples,
+    );
+
+    let name = "ov_daemon_request_duration_seconds";
+    let mut samples = Vec::new();
+    for (route, histogram) in &requests.latency {
+        for (count, bound) in histogram.buckets.iter().zip(LATENCY_BUCKETS) {
+            samples.push(format!(
+                "{name}_bucket{{route=\"{route}\",le=\"{bound}\"}} {count}"
+            ));
+        }
+        samples.push(format!(
+            "{name}_bucket{{route=\"{route}\",le=\"+Inf\"}} {}",
+            histogram.count
+        ));
+        samples.push(format!("{name}_sum{{route=\"{route}\"}} {}", histogram.sum));
+        samples.push(format!(
+            "{name}_count{{route=\"{route}\"}} {}",
+            histogram.count
+        ));
+    }
+    family(
+        &mut text,
+        name,
+        "histogram",
+        "Seconds until the response from the server or the cache started.",
+        &samples,
+    );
+
+    let lookups = cache.hits + cache.misses;
+    let ratio = if lookups == 0 {
+        0.0
+    } else {
+        cache.hits as f64 / lookups as f64
+    };
+    for (name, kind, help, value) in [
+        (
+            "ov_daemon_cache_hits_total",
+            "counter",
+            "Cacheable requests answered from the cache.",
+            cache.hits.to_string(),
+        ),
+        (
+            "ov_daemon_cache_misses_total",
+            "counter",
+            "Cacheable requests sent to the server.",
+            cache.misses.to_string(),
+        ),
+        (
+            "ov_daemon_cache_invalidations_total",
+            "counter",
+            "Times a write cleared the cache.",
+            cache.invalidations.to_string(),
+        ),
+        (
+            "ov_daemon_cache_entries",
+            "gauge",
+            "Responses in the cache.",
+            cache.entries.len().to_string(),
+        ),
+        (
+            "ov_daemon_cache_hit_ratio",
+            "gauge",
+            "Share of cacheable requests answered from the cache.",
+            ratio.to_string(),
+        ),
+    ] {
+        family(&mut text, name, kind, help, &[format!("{name} {value}")]);
+    }
+    if let Some(queued) = queued {
+        family(
+            &mut text,
+            "ov_offline_queue_length",
+            "gauge",
+            "Commands queued with --queue-offline for this server.",
+            &[format!("ov_offline_queue_length {queued}")],
+        );
+    }
+    text
+}
+
+#[cfg(unix)]
+pub use server::serve;
+
+#[cfg(not(unix))]
+pub async fn serve(
+    _config: &crate::config::Config,
+    _socket: &Path,
+    _ttl: Duration,
+    _max_entries: usize,
+    _metrics: Option<SocketAddr>,
+) -> Result<()> {
+    Err(Error::Client(
+        "ov daemon needs Unix sockets, which this platform does not support".to_string(),
+    ))
+}
+
+#[cfg(unix)]
+mod server {
+    use std::net::SocketAddr;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    use bytes::Bytes;
+    use http_body_util::combinators::UnsyncBoxBody;
+    use http_body_util::{BodyExt, Full};
+    use hyper::body::Incoming;
+    use hyper::header::{CONTENT_LENGTH, CONTENT_TYPE, HeaderValue, TRANSFER_ENCODING};
+    use hyper::{Method, Request, Response, StatusCode};
+    use hyper_util::rt::TokioIo;
+    use serde_json::{Value, json};
+    use tokio::net::{TcpListener, UnixListener};
+    use tokio::signal::unix::{SignalKind, signal};
+    use tokio::sync::Notify;
+
+    use super::{
+        CACHE_HEADER, CachedResponse, METRICS_PATH, RECENT_PATH, RECENT_URIS, RecentUris,
+        RequestMetrics, ResponseCache, Route, STATUS_PATH, STOP_PATH, cache_key, forwarded_headers,
+        is_listening, render_metrics, request_uri, route,
+    };
+    use crate::base_client::{
+        configure_proxy, configure_tls, configure_unix_socket, request_base_url, unix_socket_path,
+    };
+    use crate::commands::queue::queued_count;
+    use crate::commands::schedule;
+    use crate::config::Config;
+    use crate::error::{Error, Result};
+
+    type ProxyBody = UnsyncBoxBody<Bytes, std::io::Error>;
+
+    struct Daemon {
+        upstream: reqwest::Client,
+        base_url: String,
+        server: String,
+        socket: PathBuf,
+        started: Instant,
+        requests: AtomicU64,
+        metrics: Mutex<RequestMetrics>,
+        metrics_addr: Option<SocketAddr>,
+        cache: Mutex<ResponseCache>,
+        recent: Mutex<RecentUris>,
+        shutdown: Notify,
+    }
+
+    /// Removes the socket file however the daemon exits.
+    struct SocketFile(PathBuf);
+
+    impl Drop for SocketFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    /// Serve on `socket` until `ov daemon stop`, Ctrl-C, or SIGTERM, and
+    /// Prometheus metrics on `metrics` if given.
+    pub async fn serve(
+        config: &Config,
+        socket: &Path,
+        ttl: Duration,
+        max_entries: usize,
+        metrics: Option<SocketAddr>,
+    ) -> Result<()> {
+        if is_listening(socket) {
+            return Err(Error::Client(format!(
+                "An ov daemon is already listening on {}",
+                socket.display()
+            )));
+        }
+        let daemon = Arc::new(Daemon {
+            upstream: upstream_client(config)?,
+            base_url: request_base_url(&config.url),
+            server: config.url.clone(),
+            socket: socket.to_path_buf(),
+            started: Instant::now(),
+            requests: AtomicU64::new(0),
+            metrics: Mutex::new(RequestMetrics::default()),
+            metrics_addr: metrics,
+            cache: Mutex::new(ResponseCache::new(ttl, max_entries)),
+            recent: Mutex::new(RecentUris::new(RECENT_URIS)),
+            shutdown: Notify::new(),
+        });
+        if let Some(dir) = socket.parent() {
+            std::fs::create_dir_all(dir)?;
+            std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+        }
+        let metrics_listener = match metrics {
+            Some(addr) => Some(TcpListener::bind(addr).await.map_err(|e| {
+                Error::Client(format!("Failed to listen for metrics on {addr}: {e}"))
+            })?),
+            None => None,
+        };
+        let _ = std::fs::remove_file(socket);
+        let listener = UnixListener::bind(socket)?;
+        let _socket_file = SocketFile(socket.to_path_buf());
+        std::fs::set_permissions(socket, std::fs::Permissions::from_mode(0o600))?;
+        eprintln!(
+            "ov daemon {} forwarding to {}",
+            std::process::id(),
+            config.url
+        );
+        if let (Some(listener), Some(addr)) = (metrics_listener, metrics) {
+            eprintln!("ov daemon metrics on http://{addr}{METRICS_PATH}");
+            tokio::spawn(serve_metrics(listener, daemon.clone()));
+        }
+        tokio::spawn(schedule::run_due(config.url.clone()));
+
+        let mut terminate = signal(SignalKind::terminate())?;
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => {
+                    let (stream, _) = accepted?;
+                    let daemon = daemon.clone();
+                    tokio::spawn(async move {
+                        let service = hyper::service::service_fn(move |request| {
+                            let daemon = daemon.clone();
+                            async move { daemon.handle(request).await }
+                        });
+                        if let Err(e) = hyper::server::conn::http1::Builder::new()
+                            .serve_connection(TokioIo::new(stream), service)
+                            .await
+                            && !e.is_user()
+                        {
+                            eprintln!("ov daemon: connection error: {e}");
+                        }
+                    });
+                }
+                _ = daemon.shutdown.notified() => break,
+                _ = tokio::signal::ctrl_c() => break,
+                _ = terminate.recv() => break,
+            }
+        }
+        eprintln!("ov daemon {} stopped", std::process::id());
+        Ok(())
+    }
+
+    /// Answer scrapes on `listener` until the daemon exits.
+    async fn serve_metrics(listener: TcpListener, daemon: Arc<Daemon>) {
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    eprintln!("ov daemon: metrics connection error: {e}");
+                    continue;
+                }
+            };
+            let daemon = daemon.clone();
+            tokio::spawn(async move {
+                let service = hyper::service::service_fn(move |request: Request<Incoming>| {
+                    let daemon = daemon.clone();
+                    async move { Ok::<_, Error>(daemon.scrape(&request)) }
+                });
+                let _ = hyper::server::conn::http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await;
+            });
+        }
+    }
+
+    /// One client for the daemon's lifetime, so idle connections, TLS sessions
+    /// included, are reused instead of set up per command. Each command keeps
+    /// its own timeout, so the daemon sets none on the whole request.
+    fn upstream_client(config: &Config) -> Result<reqwest::Client> {
+        let builder = configure_proxy(reqwest::Client::builder(), config.proxy.as_deref())?;
+        let builder = configure_tls(builder, &config.tls_files())?;
+        let builder = match config.connect_timeout() {
+            Some(connect_timeout) => builder.connect_timeout(connect_timeout),
+            None => builder,
+        };
+        configure_unix_socket(builder, unix_socket_path(&config.url).as_deref())
+            .pool_idle_timeout(None)
+            .tcp_keepalive(Duration::from_secs(60))
+            .build()
+            .map_err(|e| Error::from_reqwest("Failed to build upstream client", e))
+    }
+
+    impl Daemon {
+        /// A request the server could not be reached for gets no response at
+        /// all, so the command reports the same connection error it would
+        /// without the daemon; the reason goes to the daemon's log.
+        async fn handle(&self, request: Request<Incoming>) -> Result<Response<ProxyBody>> {
+            match (request.method(), request.uri().path()) {
+                (&Method::GET, STATUS_PATH) => {
+                    return Ok(json_response(StatusCode::OK, self.status()));
+                }
+                (&Method::GET, RECENT_PATH) => {
+                    let uris = json!({ "uris": self.recent.lock().unwrap().list() });
+                    return Ok(json_response(StatusCode::OK, uris));
+                }
+                (&Method::POST, STOP_PATH) => {
+                    self.shutdown.notify_one();
+                    return Ok(json_response(StatusCode::OK, json!({ "stopping": true })));
+                }
+                _ => {}
+            }
+            self.requests.fetch_add(1, Ordering::Relaxed);
+            let method = request.method().clone();
+            let route = route(&method, request.uri().path());
+            let started = Instant::now();
+            let result = self.forward(request).await;
+            self.metrics.lock().unwrap().observe(
+                &method,
+                route,
+                result.as_ref().ok().map(Response::status),
+                started.elapsed(),
+            );
+            result.inspect_err(|e| eprintln!("ov daemon: {e}"))
+        }
+
+        fn scrape(&self, request: &Request<Incoming>) -> Response<ProxyBody> {
+            if request.method() != Method::GET || request.uri().path() != METRICS_PATH {
+                let mut response = Response::new(full_body(Bytes::from_static(b"not found\n")));
+                *response.status_mut() = StatusCode::NOT_FOUND;
+                return response;
+            }
+            let text = render_metrics(
+                &self.metrics.lock().unwrap(),
+                &self.cache.lock().unwrap(),
+                queued_count(&self.server).ok(),
+                self.started.elapsed(),
+            );
+            let mut response = Response::new(full_body(Bytes::from(text)));
+            response.headers_mut().insert(
+                CONTENT_TYPE,
+                HeaderValue::from_static("text/plain; version=0.0.4; charset=utf-8"),
+            );
+            response
+        }
+
+        fn status(&self) -> Value {
+            let mut status = json!({
+                "pid": std::process::id(),
+                "server": self.server,
+                "socket": self.socket.display().to_string(),
+                "uptime_secs": self.started.elapsed().as_secs(),
+                "requests": self.requests.load(Ordering::Relaxed),
+                "cache": self.cache.lock().unwrap().stats(),
+            });
+            if let Some(addr) = self.metrics_addr {
+                status["metrics"] = json!(format!("http://{addr}{METRICS_PATH}"));
+            }
+            if let Ok(schedules) = schedule::count(&self.server) {
+                status["schedules"] = json!(schedules);
+            }
+            status
+        }
+
+        async fn forward(&self, request: Request<Incoming>) -> Result<Response<ProxyBody>> {
+            let (parts, body) = request.into_parts();
+            let path_and_query = parts.uri.path_and_query().map_or("/", |path| path.as_str());
+            let route = route(&parts.method, parts.uri.path());
+            let upstream = self
+                .upstream
+                .request(
+                    parts.method.clone(),
+                    format!("{}{path_and_query}", self.base_url),
+                )
+                .headers(forwarded_headers(&parts.headers));
+
+            if route == Route::Cached {
+                let body = body
+                    .collect()
+                    .await
+                    .map_err(|e| Error::Network(format!("Failed to read request: {e}")))?
+                    .to_bytes();
+                let key = cache_key(&parts.method, path_and_query, &parts.headers, &body);
+                let hit = self.cache.lock().unwrap().get(&key, Instant::now());
+                if let Some(hit) = hit {
+                    self.remember(path_and_query);
+                    return Ok(cached_response(hit, "hit"));
+                }
+                let response = upstream
+                    .body(body)
+                    .send()
+                    .await
+                    .map_err(|e| Error::from_reqwest("Upstream request failed", e))?;
+                let fetched = CachedResponse {
+                    status: response.status(),
+                    headers: forwarded_headers(response.headers()),
+                    body: response
+                        .bytes()
+                        .await
+                        .map_err(|e| Error::from_reqwest("Upstream response failed", e))?,
+                };
+                if fetched.status == StatusCode::OK {
+                    self.remember(path_and_query);
+                    self.cache
+                        .lock()
+                        .unwrap()
+                        .insert(key, fetched.clone(), Instant::now());
+                }
+                return Ok(cached_response(fetched, "miss"));
+            }
+
+            // Cleared before and after, so a read racing the write cannot
+            // leave its stale result behind.
+            if route == Route::Invalidates {
+                self.cache.lock().unwrap().invalidate();
+            }
+            let has_body = parts.headers.contains_key(TRANSFER_ENCODING)
+                || parts
+                    .headers
+                    .get(CONTENT_LENGTH)
+                    .is_some_and(|length| length != "0");
+            let upstream = if has_body {
+                upstream.body(reqwest::Body::wrap(body))
+            } else {
+                upstream
+            };
+            let response = upstream
+                .send()
+                .await
+                .map_err(|e| Error::from_reqwest("Upstream request failed", e))?;
+            if route == Route::Invalidates {
+                self.cache.lock().unwrap().entries.clear();
+            }
+
+            let status = response.status();
+            let headers = forwarded_headers(response.headers());
+            let body = reqwest::Body::from(response).map_err(std::io::Error::other);
+            let mut proxied = Response::new(UnsyncBoxBody::new(body));
+            *proxied.status_mut() = status;
+            *proxied.headers_mut() = headers;
+            Ok(proxied)
+        }
+
+        /// Note the URI a successful read was for.
+        fn remember(&self, path_and_query: &str) {
+            if let Some(uri) = request_uri(path_and_query) {
+                self.recent.lock().unwrap().record(uri);
+            }
+        }
+    }
+
+    fn cached_response(cached: CachedResponse, outcome: &'static str) -> Response<ProxyBody> {
+        let mut response = Response::new(full_body(cached.body));
+        *response.status_mut() = cached.status;
+        *response.headers_mut() = cached.headers;
+        response
+            .headers_mut()
+            .insert(CACHE_HEADER, HeaderValue::from_static(outcome));
+        response
+    }
+
+    fn json_response(status: StatusCode, body: Value) -> Response<ProxyBody> {
+        let mut response = Response::new(full_body(Bytes::from(body.to_string())));
+        *response.status_mut() = status;
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        response
+    }
+
+    fn full_body(bytes: Bytes) -> ProxyBody {
+        UnsyncBoxBody::new(Full::new(bytes).map_err(|never| match never {}))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn response(body: &'static str) -> CachedResponse {
+        CachedResponse {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: Bytes::from_static(body.as_bytes()),
+        }
+    }
+
+    #[test]
+    fn only_reads_and_searches_are_cached_and_writes_invalidate() {
+        assert_eq!(
+            route(&Method::GET, "/api/v1/content/abstract"),
+            Route::Cached
+        );
+        assert_eq!(route(&Method::POST, "/api/v1/search/find"), Route::Cached);
+        assert_eq!(route(&Method::GET, "/api/v1/tasks/t1"), Route::Passthrough);
+        assert_eq!(
+            route(&Method::POST, "/api/v1/content/write"),
+            Route::Invalidates
+        );
+        assert_eq!(route(&Method::DELETE, "/api/v1/fs"), Route::Invalidates);
+        assert_eq!(route(&Method::POST, "/api/v1/fs/ls"), Route::Invalidates);
+    }
+
+    #[test]
+    fn cache_keys_follow_identity_headers_but_not_the_user_agent() {
+        let mut alice = HeaderMap::new();
+        alice.insert("x-api-key", HeaderValue::from_static("alice"));
+        let mut alice_curl = alice.clone();
+        alice_curl.insert("user-agent", HeaderValue::from_static("curl"));
+        let mut bob = HeaderMap::new();
+        bob.insert("x-api-key", HeaderValue::from_static("bob"));
+
+        let key = |headers: &HeaderMap, body: &[u8]| {
+            cache_key(&Method::POST, "/api/v1/search/find", headers, body)
+        };
+        assert_eq!(key(&alice, b"{}"), key(&alice_curl, b"{}"));
+        assert_ne!(key(&alice, b"{}"), key(&bob, b"{}"));
+        assert_ne!(key(&alice, b"{}"), key(&alice, b"{\"q\":1}"));
+    }
+
+    #[test]
+    fn cache_expires_entries_and_evicts_the_least_recently_used() {
+        let start = Instant::now();
+        let mut cache = ResponseCache::new(Duration::from_secs(60), 2);
+        cache.insert("a".to_string(), response("a"), start);
+        cache.insert("b".to_string(), response("b"), start);
+        assert!(cache.get("a", start).is_some());
+        cache.insert("c".to_string(), response("c"), start);
+        assert!(cache.get("b", start).is_none());
+        assert_eq!(cache.get("a", start).unwrap().body, "a");
+        assert!(cache.get("c", start).is_some());
+
+        assert!(cache.get("a", start + Duration::from_secs(61)).is_none());
+        cache.invalidate();
+        assert!(cache.get("c", start).is_none());
+        assert_eq!(cache.stats()["hits"], 3);
+        assert_eq!(cache.stats()["misses"], 3);
+        assert_eq!(cache.stats()["invalidations"], 1);
+    }
+
+    #[test]
+    fn metrics_count_requests_latency_cache_and_the_offline_queue() {
+        let mut requests = RequestMetrics::default();
+        let get = Method::GET;
+        requests.observe(
+            &get,
+            Route::Cached,
+            Some(StatusCode::OK),
+            Duration::from_millis(20),
+        );
+        requests.observe(
+            &get,
+            Route::Cached,
+            Some(StatusCode::OK),
+            Duration::from_secs(3),
+        );
+        requests.observe(
+            &Method::POST,
+            Route::Invalidates,
+            None,
+            Duration::from_millis(1),
+        );
+        let start = Instant::now();
+        let mut cache = ResponseCache::new(Duration::from_secs(60), 10);
+        assert!(cache.get("a", start).is_none());
+        cache.insert("a".to_string(), response("a"), start);
+        assert!(cache.get("a", start).is_some());
+
+        let text = render_metrics(&requests, &cache, Some(2), Duration::from_secs(90));
+        for line in [
+            "ov_daemon_uptime_seconds 90",
+            "ov_daemon_requests_total{method=\"GET\",route=\"cached\",status=\"200\"} 2",
+            "ov_daemon_requests_total{method=\"POST\",route=\"write\",status=\"error\"} 1",
+            "ov_daemon_request_duration_seconds_bucket{route=\"cached\",le=\"0.025\"} 1",
+            "ov_daemon_request_duration_seconds_bucket{route=\"cached\",le=\"5\"} 2",
+            "ov_daemon_request_duration_seconds_bucket{route=\"cached\",le=\"+Inf\"} 2",
+            "ov_daemon_request_duration_seconds_count{route=\"write\"} 1",
+            "ov_daemon_cache_hits_total 1",
+            "ov_daemon_cache_entries 1",
+            "ov_daemon_cache_hit_ratio 0.5",
+            "ov_offline_queue_length 2",
+        ] {
+            assert!(text.lines().any(|sample| sample == line), "missing {line}");
+        }
+        assert!(
+            !render_metrics(&requests, &cache, None, Duration::ZERO).contains("ov_offline_queue")
+        );
+
+        assert_eq!(
+            parse_metrics_addr("9464").unwrap().to_string(),
+            "127.0.0.1:9464"
+        );
+        assert_eq!(
+            parse_metrics_addr("0.0.0.0:9100").unwrap().to_string(),
+            "0.0.0.0:9100"
+        );
+        assert!(parse_metrics_addr("localhost").is_err());
+    }
+
+    #[test]
+    fn recent_uris_come_from_read_queries_newest_first() {
+        assert_eq!(
+            request_uri("/api/v1/fs/ls?uri=viking%3A%2F%2Fresources%2Fdocs&simple=true").as_deref(),
+            Some("viking://resources/docs")
+        );
+        assert_eq!(request_uri("/api/v1/search/find"), None);
+        assert_eq!(request_uri("/api/v1/fs/stat?uri=/tmp/x"), None);
+
+        let mut recent = RecentUris::new(2);
+        recent.record("viking://a".to_string());
+        recent.record("viking://b".to_string());
+        recent.record("viking://a".to_string());
+        recent.record("viking://c".to_string());
+        assert_eq!(recent.list(), vec!["viking://c", "viking://a"]);
+    }
+}
@@ -0,0 +1,67 @@
+//! Open a file in the user's editor, the way git does.
+//!
+//! `$VISUAL` wins over `$EDITOR`; with neither set, `vi` is used (`notepad` on
+//! Windows). The value may carry arguments, e.g. `code --wait`.
+
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+use crate::error::{Error, Result};
+
+#[cfg(windows)]
+const DEFAULT_EDITOR: &str = "notepad";
+#[cfg(not(windows))]
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Resolve the editor program and its arguments.
+fn editor_command(visual: Option<&str>, editor: Option<&str>) -> Vec<String> {
+    let spec = [visual, editor]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .find(|spec| !spec.is_empty())
+        .unwrap_or(DEFAULT_EDITOR);
+    spec.split_whitespace().map(str::to_string).collect()
+}
+
+/// Open `path` in the editor and wait for it to exit.
+pub fn edit(path: &Path) -> Result<()> {
+    let editor = editor_command(
+        env::var("VISUAL").ok().as_deref(),
+        env::var("EDITOR").ok().as_deref(),
+    );
+    let status = Command::new(&editor[0])
+        .args(&editor[1..])
+        .arg(path)
+        .status()
+        .map_err(|e| {
+            Error::Client(format!(
+                "Failed to start editor '{}': {e}; set $EDITOR to the editor to use",
+                editor[0]
+            ))
+        })?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::Client(format!(
+            "Editor '{}' exited with {status}; nothing was saved",
+            editor[0]
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn editor_command_prefers_visual_and_keeps_arguments() {
+        assert_eq!(
+            editor_command(Some("code --wait"), Some("nano")),
+            vec!["code".to_string(), "--wait".to_string()]
+        );
+        assert_eq!(editor_command(Some(" "), Some("nano")), vec!["nano"]);
+        assert_eq!(editor_command(None, None), vec![DEFAULT_EDITOR]);
+    }
+}
@@ -47,6 +47,9 @@ pub enum Error {
     #[error("Zip error: {0}")]
     Zip(#[from] zip::result::ZipError),
 
+    #[error("Interrupted")]
+    Interrupted,
+
     #[error("already reported")]
     AlreadyReported,
 }
@@ -84,7 +87,8 @@ impl Error {
         }
     }
 
-    pub(crate) fn from_reqwest(context: &str, error: reqwest::Error) -> Self {
+    #[doc(hidden)]
+    pub fn from_reqwest(context: &str, error: reqwest::Error) -> Self {
         if error.is_timeout() {
             Self::Timeout(format!("{context}: request timed out: {error}"))
         } else if error.is_decode() {
@@ -96,12 +100,58 @@ impl Error {
         }
     }
 
-    pub(crate) fn code(&self) -> &str {
+    /// Whether the server has no route for the request at all, as opposed to a
+    /// handler reporting a missing resource: the framework's bare "Not Found"
+    /// or "Method Not Allowed" (newer servers wrap it in an envelope), a 404
+    /// whose body is not JSON, or a 501 without an OpenViking error code.
+    pub fn is_missing_endpoint(&self) -> bool {
+        let Self::Api {
+            code,
+            message,
+            status: Some(status),
+            ..
+        } = self
+        else {
+            return false;
+        };
+        match status {
+            404 => {
+                message == "Not Found" || (code.is_none() && message.starts_with("HTTP error 404"))
+            }
+            405 => code.is_none() || message == "Method Not Allowed",
+            501 => code.is_none(),
+            _ => false,
+        }
+    }
+
+    /// Remember which endpoint a missing-endpoint error came from, so the
+    /// report can name it.
+    pub fn with_endpoint(mut self, path: &str) -> Self {
+        if !self.is_missing_endpoint() {
+            return self;
+        }
+        if let Self::Api { details, .. } = &mut self {
+            match details {
+                Some(Value::Object(map)) => {
+                    map.insert("endpoint".to_string(), Value::from(path));
+                }
+                _ => *details = Some(serde_json::json!({ "endpoint": path })),
+            }
+        }
+        self
+    }
+
+    /// The stable error code reported for this error, such as `UNIMPLEMENTED`.
+    pub fn code(&self) -> &str {
+        if self.is_missing_endpoint() {
+            return "UNIMPLEMENTED";
+        }
         match self {
             Self::MissingConfig | Self::Config(_) => "FAILED_PRECONDITION",
             Self::Language(_) | Self::Client(_) | Self::InvalidPath(_) => "INVALID_ARGUMENT",
             Self::Network(_) => "UNAVAILABLE",
             Self::Timeout(_) => "DEADLINE_EXCEEDED",
+            Self::Interrupted => "CANCELLED",
             Self::Api { code, status, .. } => code
                 .as_deref()
                 .unwrap_or_else(|| code_from_http_status(*status)),
@@ -113,6 +163,128 @@ impl Error {
             | Self::AlreadyReported => "INTERNAL",
         }
     }
+
+    /// Typed view of a server error; `None` for failures raised by the CLI.
+    pub fn api_kind(&self) -> Option<ApiErrorKind> {
+        if self.is_missing_endpoint() {
+            return Some(ApiErrorKind::Unimplemented);
+        }
+        match self {
+            Self::Api { code, status, .. } => {
+                Some(ApiErrorKind::from_code(code.as_deref(), *status))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Api { .. } => self.api_kind().map_or(EXIT_SERVER, ApiErrorKind::exit_code),
+            Self::MissingConfig | Self::Config(_) => EXIT_CONFIG,
+            Self::Language(_) | Self::Client(_) | Self::InvalidPath(_) => EXIT_USAGE,
+            Self::Network(_) => EXIT_UNREACHABLE,
+            Self::Timeout(_) => EXIT_TIMEOUT,
+            Self::Interrupted => EXIT_INTERRUPTED,
+            Self::Parse(_)
+            | Self::Output(_)
+            | Self::Io(_)
+            | Self::Serialization(_)
+            | Self::Zip(_)
+            | Self::AlreadyReported => EXIT_FAILURE,
+        }
+    }
+}
+
+/// Process exit codes, one per failure class scripts may want to branch on.
+pub const EXIT_FAILURE: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_NOT_FOUND: i32 = 3;
+pub const EXIT_SERVER: i32 = 4;
+pub const EXIT_TIMEOUT: i32 = 5;
+pub const EXIT_AUTH: i32 = 6;
+pub const EXIT_UNREACHABLE: i32 = 7;
+pub const EXIT_CONFLICT: i32 = 8;
+pub const EXIT_QUOTA: i32 = 9;
+pub const EXIT_NOT_READY: i32 = 10;
+pub const EXIT_PROCESSING: i32 = 11;
+pub const EXIT_CONFIG: i32 = 12;
+/// Ctrl-C, following the shell's 128 + SIGINT convention.
+pub const EXIT_INTERRUPTED: i32 = 130;
+
+/// Server error codes grouped by what the caller can do about them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiErrorKind {
+    InvalidArgument,
+    NotFound,
+    Conflict,
+    Unauthenticated,
+    PermissionDenied,
+    QuotaExceeded,
+    /// The index or a server component is not ready yet; retrying later helps.
+    IndexPending,
+    /// Parsing, embedding or VLM processing of the content failed.
+    ProcessingFailed,
+    FailedPrecondition,
+    Unimplemented,
+    Unavailable,
+    DeadlineExceeded,
+    Internal,
+}
+
+impl ApiErrorKind {
+    /// Map an OpenViking error code, falling back to the HTTP status when the
+    /// code is missing or unknown.
+    pub fn from_code(code: Option<&str>, status: Option<u16>) -> Self {
+        match code {
+            Some(
+                "INVALID_ARGUMENT" | "INVALID_URI" | "INVALID_FILTER" | "UNSUPPORTED_URI"
+                | "UNSUPPORTED_MODE",
+            ) => Self::InvalidArgument,
+            Some("NOT_FOUND") => Self::NotFound,
+            Some("ALREADY_EXISTS" | "CONFLICT" | "ABORTED") => Self::Conflict,
+            Some("UNAUTHENTICATED") => Self::Unauthenticated,
+            Some("PERMISSION_DENIED") => Self::PermissionDenied,
+            Some("RESOURCE_EXHAUSTED") => Self::QuotaExceeded,
+            Some("NOT_INITIALIZED" | "NO_VECTOR_DB") => Self::IndexPending,
+            Some("PROCESSING_ERROR" | "EMBEDDING_FAILED" | "VLM_FAILED") => Self::ProcessingFailed,
+            Some("FAILED_PRECONDITION" | "SESSION_EXPIRED") => Self::FailedPrecondition,
+            Some("UNIMPLEMENTED") => Self::Unimplemented,
+            Some("UNAVAILABLE") => Self::Unavailable,
+            Some("DEADLINE_EXCEEDED") => Self::DeadlineExceeded,
+            _ => Self::from_status(status),
+        }
+    }
+
+    fn from_status(status: Option<u16>) -> Self {
+        match status {
+            Some(400 | 422) => Self::InvalidArgument,
+            Some(401) => Self::Unauthenticated,
+            Some(403) => Self::PermissionDenied,
+            Some(404) => Self::NotFound,
+            Some(409) => Self::Conflict,
+            Some(412) => Self::FailedPrecondition,
+            Some(402 | 429) => Self::QuotaExceeded,
+            Some(501) => Self::Unimplemented,
+            Some(502 | 503) => Self::Unavailable,
+            Some(408 | 504) => Self::DeadlineExceeded,
+            _ => Self::Internal,
+        }
+    }
+
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Self::InvalidArgument => EXIT_USAGE,
+            Self::NotFound => EXIT_NOT_FOUND,
+            Self::Conflict => EXIT_CONFLICT,
+            Self::Unauthenticated | Self::PermissionDenied => EXIT_AUTH,
+            Self::QuotaExceeded => EXIT_QUOTA,
+            Self::IndexPending => EXIT_NOT_READY,
+            Self::ProcessingFailed => EXIT_PROCESSING,
+            Self::FailedPrecondition | Self::Unimplemented | Self::Internal => EXIT_SERVER,
+            Self::Unavailable => EXIT_UNREACHABLE,
+            Self::DeadlineExceeded => EXIT_TIMEOUT,
+        }
+    }
 }
 
 fn code_from_http_status(status: Option<u16>) -> &'static str {
@@ -131,3 +303,68 @@ fn code_from_http_status(status: Option<u16>) -> &'static str {
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_error_kind_prefers_code_over_status() {
+        assert_eq!(
+            ApiErrorKind::from_code(Some("NOT_FOUND"), Some(500)),
+            ApiErrorKind::NotFound
+        );
+        assert_eq!(
+            ApiErrorKind::from_code(Some("EMBEDDING_FAILED"), Some(500)),
+            ApiErrorKind::ProcessingFailed
+        );
+        assert_eq!(
+            ApiErrorKind::from_code(Some("SOMETHING_NEW"), Some(429)),
+            ApiErrorKind::QuotaExceeded
+        );
+        assert_eq!(ApiErrorKind::from_code(None, None), ApiErrorKind::Internal);
+    }
+
+    #[test]
+    fn exit_codes_separate_missing_resources_from_unreachable_servers() {
+        let not_found = Error::api_response(Some("NOT_FOUND".to_string()), "gone", None, 404);
+        let unreachable = Error::Network("connection refused".to_string());
+        let unavailable = Error::api_with_status("bad gateway", 502);
+
+        assert_eq!(not_found.exit_code(), EXIT_NOT_FOUND);
+        assert_eq!(unreachable.exit_code(), EXIT_UNREACHABLE);
+        assert_eq!(unavailable.exit_code(), EXIT_UNREACHABLE);
+        assert_eq!(Error::Timeout("slow".to_string()).exit_code(), EXIT_TIMEOUT);
+        assert_eq!(Error::MissingConfig.exit_code(), EXIT_CONFIG);
+        assert_eq!(
+            Error::Client("bad flag".to_string()).exit_code(),
+            EXIT_USAGE
+        );
+        assert_eq!(Error::Interrupted.exit_code(), EXIT_INTERRUPTED);
+    }
+
+    #[test]
+    fn router_not_found_is_a_missing_endpoint_not_a_missing_resource() {
+        let bare = Error::api_with_status("Not Found", 404).with_endpoint("/api/v1/index/verify");
+        let wrapped = Error::api_response(Some("NOT_FOUND".to_string()), "Not Found", None, 404);
+        let resource = Error::api_response(
+            Some("NOT_FOUND".to_string()),
+            "File not found: viking://resources/a.md",
+            None,
+            404,
+        );
+
+        assert!(bare.is_missing_endpoint());
+        assert!(wrapped.is_missing_endpoint());
+        assert!(!resource.is_missing_endpoint());
+        assert_eq!(bare.api_kind(), Some(ApiErrorKind::Unimplemented));
+        assert_eq!(wrapped.code(), "UNIMPLEMENTED");
+        assert_eq!(resource.exit_code(), EXIT_NOT_FOUND);
+        match bare {
+            Error::Api { details, .. } => {
+                assert_eq!(details.unwrap()["endpoint"], "/api/v1/index/verify")
+            }
+            _ => unreachable!(),
+        }
+    }
+}
@@ -5,7 +5,7 @@ use serde_json::{Map, Value};
 use unicode_width::UnicodeWidthStr;
 
 use crate::{
-    error::Error,
+    error::{ApiErrorKind, Error},
     i18n::{Language, copy},
     output::OutputFormat,
     terminal_ui::{fit_to_display_width, truncate_to_display_width},
@@ -90,7 +90,7 @@ pub(crate) fn print_runtime_error(
     compact: bool,
     verbose: bool,
 ) {
-    if matches!(format, OutputFormat::Json) {
+    if format.is_json() {
         eprintln!("{}", render_json_error(error, compact));
     } else {
         print_report(&report_for_runtime_error(command, error), verbose);
@@ -291,11 +291,41 @@ pub(crate) fn report_for_runtime_error(command: impl Into<String>, error: &Error
             ErrorAction::new("ov config", copy(language, "Edit this config", "编辑这个配置")),
             ErrorAction::new("ov config switch", copy(language, "Use another config", "使用其他配置")),
         ]),
+        Error::Api { details, .. } if error.is_missing_endpoint() => {
+            let endpoint = details
+                .as_ref()
+                .and_then(|details| details.get("endpoint"))
+                .and_then(Value::as_str);
+            let message = match endpoint {
+                Some(endpoint) => copy(
+                    language,
+                    "This server has no {endpoint} endpoint, so it does not support this command. It is probably older than this CLI.",
+                    "该服务器没有 {endpoint} 接口，因此不支持此命令，很可能比当前 CLI 旧。",
+                )
+                .replace("{endpoint}", endpoint),
+                None => copy(
+                    language,
+                    "This server does not support this command. It is probably older than this CLI.",
+                    "该服务器不支持此命令，很可能比当前 CLI 旧。",
+                )
+                .to_string(),
+            };
+            ErrorReport::new(copy(language, "Not Supported by Server", "服务器不支持"), message)
+                .with_command(command)
+                .with_actions(vec![ErrorAction::new(
+                    "ov version --remote",
+                    copy(language, "Compare CLI and server versions and features", "对比 CLI 与服务器的版本和功能"),
+                )])
+        }
         Error::Api { message, details, .. } => {
-            let mut report = ErrorReport::new(
-                copy(language, "OpenViking API Error", "OpenViking API 错误"),
-                api_error_message(error.code(), message),
-            )
+            let message = api_error_message(error.code(), message);
+            let mut report = match error.api_kind() {
+                Some(kind) => api_kind_report(kind, message, &command, language),
+                None => ErrorReport::new(
+                    copy(language, "OpenViking API Error", "OpenViking API 错误"),
+                    message,
+                ),
+            }
             .with_command(command);
             if let Some(details) = details {
                 report = report.with_detail(details.to_string());
@@ -332,11 +362,123 @@ pub(crate) fn report_for_runtime_error(command: impl Into<String>, error: &Error
         Error::Zip(error) => ErrorReport::new(copy(language, "Archive Error", "压缩包错误"), copy(language, "OpenViking could not process the archive.", "OpenViking 无法处理压缩包。"))
             .with_command(command)
             .with_detail(error.to_string()),
+        Error::Interrupted => ErrorReport::new(
+            copy(language, "Interrupted", "已中断"),
+            copy(
+                language,
+                "The command was cancelled with Ctrl-C. Requests still in flight were abandoned; the server may have applied some of them.",
+                "命令已通过 Ctrl-C 取消。尚未完成的请求已放弃，服务器可能已处理其中一部分。",
+            ),
+        )
+        .with_command(command),
         Error::AlreadyReported => ErrorReport::new(copy(language, "Command Error", "命令错误"), copy(language, "The command failed.", "命令执行失败。"))
             .with_command(command),
     }
 }
 
+/// Titles and next steps for the server error classes a user can act on;
+/// everything else keeps the generic API error card.
+fn api_kind_report(
+    kind: ApiErrorKind,
+    message: String,
+    command: &str,
+    language: Language,
+) -> ErrorReport {
+    match kind {
+        ApiErrorKind::NotFound => {
+            let report = ErrorReport::new(copy(language, "Not Found", "未找到"), message);
+            match command_parent_uri(command) {
+                Some(parent) => report.with_actions(vec![ErrorAction::new(
+                    format!("ov ls {parent}"),
+                    copy(language, "List what exists there", "查看该目录下的内容"),
+                )]),
+                None => report,
+            }
+        }
+        ApiErrorKind::PermissionDenied => {
+            ErrorReport::new(copy(language, "Permission Denied", "权限不足"), message).with_actions(
+                vec![ErrorAction::new(
+                    "ov config show",
+                    copy(
+                        language,
+                        "Check which account and user this config uses",
+                        "查看当前配置使用的账户和用户",
+                    ),
+                )],
+            )
+        }
+        ApiErrorKind::QuotaExceeded => {
+            ErrorReport::new(copy(language, "Quota Exceeded", "超出配额"), message).with_suggestion(
+                copy(
+                    language,
+                    "wait and retry, or ask an admin to raise the limit",
+                    "稍后重试，或请管理员提高限额",
+                ),
+            )
+        }
+        ApiErrorKind::IndexPending => {
+            ErrorReport::new(copy(language, "Index Not Ready", "索引尚未就绪"), message)
+                .with_actions(vec![
+                    ErrorAction::new(
+                        "ov wait",
+                        copy(
+                            language,
+                            "Wait for queued processing",
+                            "等待排队中的处理完成",
+                        ),
+                    ),
+                    ErrorAction::new(
+                        "ov observer queue",
+                        copy(language, "Inspect the processing queue", "查看处理队列"),
+                    ),
+                ])
+        }
+        ApiErrorKind::ProcessingFailed => {
+            ErrorReport::new(copy(language, "Processing Failed", "处理失败"), message).with_actions(
+                vec![ErrorAction::new(
+                    "ov task list",
+                    copy(language, "Inspect failed tasks", "查看失败的任务"),
+                )],
+            )
+        }
+        ApiErrorKind::Conflict => ErrorReport::new(copy(language, "Conflict", "冲突"), message),
+        ApiErrorKind::Unavailable => {
+            ErrorReport::new(copy(language, "Server Unavailable", "服务不可用"), message)
+                .with_actions(vec![ErrorAction::new(
+                    "ov health",
+                    copy(
+                        language,
+                        "Run a quick server health check",
+                        "快速检查服务器健康状态",
+                    ),
+                )])
+        }
+        ApiErrorKind::InvalidArgument
+        | ApiErrorKind::Unauthenticated
+        | ApiErrorKind::FailedPrecondition
+        | ApiErrorKind::Unimplemented
+        | ApiErrorKind::DeadlineExceeded
+        | ApiErrorKind::Internal => ErrorReport::new(
+            copy(language, "OpenViking API Error", "OpenViking API 错误"),
+            message,
+        ),
+    }
+}
+
+/// Parent of the first `viking://` URI in the command line, for "look around"
+/// hints on not-found errors.
+fn command_parent_uri(command: &str) -> Option<String> {
+    let uri = command
+        .split_whitespace()
+        .find(|token| token.starts_with("viking://"))?
+        .trim_end_matches('/');
+    let (parent, _) = uri.rsplit_once('/')?;
+    if parent == "viking:/" {
+        return Some("viking://".to_string());
+    }
+    Some(parent.to_string())
+}
+
 fn config_error_actions(message: &str, language: Language) -> Vec<ErrorAction> {
     if is_config_file_load_error(message) {
         return vec![ErrorAction::new(
@@ -419,9 +561,20 @@ fn contextual_help_command(command: &str) -> Option<String> {
                 "delete",
                 "add-message",
                 "add-messages",
+                "chat",
+                "extract",
+                "export",
+                "import",
+                "tail",
                 "commit",
             ],
         )),
+        "memory" => Some(group_help_command(
+            program,
+            root,
+            tokens.get(2).copied(),
+            &["ls", "list", "show"],
+        )),
         "privacy" => Some(group_help_command(
             program,
             root,
@@ -513,6 +666,7 @@ fn is_contextual_top_level_command(command: &str) -> bool {
             | "mkdir"
             | "rm"
             | "mv"
+            | "cp"
             | "stat"
             | "read"
             | "abstract"
@@ -531,12 +685,15 @@ fn is_contextual_top_level_command(command: &str) -> bool {
             | "backup"
             | "import"
             | "restore"
+            | "watch"
             | "tui"
             | "chat"
             | "wait"
             | "status"
             | "health"
             | "reindex"
+            | "api"
+            | "bench"
             | "language"
     )
 }
@@ -943,7 +1100,7 @@ fn help_command_from_usage(usage: &str) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::{
-        CARD_WIDTH, ErrorAction, ErrorReport, render_json_error, render_report,
+        CARD_WIDTH, ErrorAction, ErrorReport, command_parent_uri, render_json_error, render_report,
         render_report_with_width, report_for_clap_error, report_for_runtime_error,
     };
     use crate::error::Error;
@@ -1086,6 +1243,58 @@ Usage: ov config [OPTIONS] [COMMAND]
         assert_eq!(json["error"]["details"]["feishu_code"], 99991672);
     }
 
+    #[test]
+    fn not_found_errors_suggest_listing_the_parent() {
+        let error = Error::api_response(
+            Some("NOT_FOUND".to_string()),
+            "File not found: viking://resources/docs/a.md",
+            None,
+            404,
+        );
+
+        let report = report_for_runtime_error("ov read viking://resources/docs/a.md", &error);
+        let rendered = strip_ansi(&render_report(&report, false));
+
+        assert!(rendered.contains("Not Found"));
+        assert!(rendered.contains("[NOT_FOUND] File not found"));
+        assert!(rendered.contains("ov ls viking://resources/docs"));
+        assert_eq!(
+            command_parent_uri("ov stat viking://resources"),
+            Some("viking://".to_string())
+        );
+    }
+
+    #[test]
+    fn missing_endpoint_errors_name_the_endpoint_and_point_to_version() {
+        let error = Error::api_response(Some("NOT_FOUND".to_string()), "Not Found", None, 404)
+            .with_endpoint("/api/v1/observer/queue/items");
+
+        let report = report_for_runtime_error("ov queue status", &error);
+        let rendered = strip_ansi(&render_report(&report, false));
+
+        assert!(rendered.contains("Not Supported by Server"));
+        assert!(rendered.contains("/api/v1/observer/queue/items"));
+        assert!(rendered.contains("ov version --remote"));
+    }
+
+    #[test]
+    fn index_pending_errors_point_to_wait() {
+        let error = Error::api_response(
+            Some("NOT_INITIALIZED".to_string()),
+            "VikingDB is not initialized",
+            None,
+            500,
+        );
+
+        let rendered = strip_ansi(&render_report(
+            &report_for_runtime_error("ov find cats", &error),
+            false,
+        ));
+
+        assert!(rendered.contains("Index Not Ready"));
+        assert!(rendered.contains("ov wait"));
+    }
+
     #[test]
     fn local_runtime_json_error_always_has_code() {
         let error = Error::Config("Failed to parse config file".to_string());
@@ -0,0 +1,324 @@
+//! Client-side file type detection for `ov add-resource`.
+//!
+//! The server picks a parser by file name, so a PDF saved as `contract.md`, or
+//! a binary with no extension, fails deep in parsing with an error that does
+//! not say why. Before a single file is uploaded its first bytes are checked
+//! against known signatures: the detected type is sent as the upload's content
+//! type, and a warning is printed when the content disagrees with the extension
+//! or looks like binary data no parser accepts. `--force-type` overrides the
+//! detection.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+
+/// Bytes read from the start of a file to detect its type.
+const HEAD_BYTES: usize = 8192;
+
+/// What a file's content says it is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileType {
+    pub mime: String,
+    /// Whether the type came from a signature in the content rather than the
+    /// extension.
+    pub sniffed: bool,
+    pub text: bool,
+}
+
+/// Signatures at the start of a file, most specific first.
+const SIGNATURES: &[(&[u8], &str)] = &[
+    (b"%PDF-", "application/pdf"),
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"BM", "image/bmp"),
+    (b"II*\0", "image/tiff"),
+    (b"MM\0*", "image/tiff"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"PK\x05\x06", "application/zip"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"BZh", "application/x-bzip2"),
+    (b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed"),
+    (b"Rar!\x1a\x07", "application/vnd.rar"),
+    (b"\xfd7zXZ\0", "application/x-xz"),
+    (b"\x28\xb5\x2f\xfd", "application/zstd"),
+    (
+        b"\xd0\xcf\x11\xe0\xa1\xb1\x1a\xe1",
+        "application/x-ole-storage",
+    ),
+    (b"{\\rtf", "application/rtf"),
+    (b"SQLite format 3\0", "application/vnd.sqlite3"),
+    (b"PAR1", "application/vnd.apache.parquet"),
+    (b"\x7fELF", "application/x-executable"),
+    (b"MZ", "application/vnd.microsoft.portable-executable"),
+    (b"\xca\xfe\xba\xbe", "application/x-mach-binary"),
+    (b"\xcf\xfa\xed\xfe", "application/x-mach-binary"),
+    (b"\0asm", "application/wasm"),
+    (b"ID3", "audio/mpeg"),
+    (b"fLaC", "audio/flac"),
+    (b"OggS", "audio/ogg"),
+    (b"\x1aE\xdf\xa3", "video/webm"),
+    (b"\xef\xbb\xbf", "text/plain"),
+    (b"\xff\xfe", "text/plain"),
+    (b"\xfe\xff", "text/plain"),
+];
+
+/// The type `head`, the first bytes of a file, has a signature for.
+fn sniff(head: &[u8]) -> Option<&'static str> {
+    if head.len() >= 12 && head.starts_with(b"RIFF") {
+        return match &head[8..12] {
+            b"WEBP" => Some("image/webp"),
+            b"WAVE" => Some("audio/wav"),
+            b"AVI " => Some("video/x-msvideo"),
+            _ => None,
+        };
+    }
+    if head.len() >= 12 && &head[4..8] == b"ftyp" {
+        return Some(match &head[8..12] {
+            b"heic" | b"heix" | b"mif1" => "image/heic",
+            b"M4A " => "audio/mp4",
+            b"qt  " => "video/quicktime",
+            _ => "video/mp4",
+        });
+    }
+    // Short printable signatures such as `BM` or `MZ` also start ordinary
+    // text, so they only count for content that is not text.
+    SIGNATURES
+        .iter()
+        .find(|(signature, _)| {
+            head.starts_with(signature)
+                && (signature.len() >= 4 || !signature.is_ascii() || !looks_like_text(head))
+        })
+        .map(|(_, mime)| *mime)
+}
+
+/// The type the extension of `path` names, if any.
+fn by_extension(path: &Path) -> Option<String> {
+    mime_guess::from_path(path)
+        .first()
+        .map(|mime| mime.essence_str().to_string())
+}
+
+/// Whether `head` reads as text: no NUL bytes, and valid UTF-8 apart from a
+/// character cut off at the end, or mostly printable in another encoding.
+fn looks_like_text(head: &[u8]) -> bool {
+    if head.contains(&0) {
+        return false;
+    }
+    match std::str::from_utf8(head) {
+        Ok(_) => true,
+        Err(e) if e.error_len().is_none() => true,
+        Err(_) => {
+            let control = head
+                .iter()
+                .filter(|&&byte| byte < 0x20 && !b"\t\n\r\x0c\x1b".contains(&byte))
+                .count();
+            control * 20 < head.len()
+        }
+    }
+}
+
+/// Detect the type of the file at `path` from its first bytes.
+pub fn detect(path: &Path) -> Result<FileType> {
+    let mut head = Vec::with_capacity(HEAD_BYTES);
+    File::open(path)?
+        .take(HEAD_BYTES as u64)
+        .read_to_end(&mut head)?;
+    Ok(classify(path, &head))
+}
+
+/// Detect the type of `content`, which is to be added as `path`.
+pub fn detect_bytes(path: &Path, content: &[u8]) -> FileType {
+    classify(path, &content[..content.len().min(HEAD_BYTES)])
+}
+
+fn classify(path: &Path, head: &[u8]) -> FileType {
+    let extension = by_extension(path);
+    if let Some(mime) = sniff(head) {
+        // Office documents, EPUB, and JARs are all zips, legacy Office files
+        // all OLE storage, and text with a BOM any kind of text; the
+        // extension tells them apart.
+        let mime = match (mime, &extension) {
+            ("application/zip", Some(named)) if is_zip_container(named) => named.clone(),
+            ("text/plain", Some(named)) if is_text(named) => named.clone(),
+            ("application/x-ole-storage", Some(named)) if named.starts_with("application/") => {
+                named.clone()
+            }
+            (mime, _) => mime.to_string(),
+        };
+        return FileType {
+            text: mime.starts_with("text/"),
+            mime,
+            sniffed: true,
+        };
+    }
+    if looks_like_text(head) {
+        let mime = extension
+            .filter(|named| is_text(named))
+            .unwrap_or_else(|| "text/plain".to_string());
+        return FileType {
+            mime,
+            sniffed: false,
+            text: true,
+        };
+    }
+    FileType {
+        mime: extension.unwrap_or_else(|| "application/octet-stream".to_string()),
+        sniffed: false,
+        text: false,
+    }
+}
+
+fn is_zip_container(mime: &str) -> bool {
+    mime.starts_with("application/vnd.openxmlformats-officedocument.")
+        || mime.starts_with("application/vnd.oasis.opendocument.")
+        || matches!(
+            mime,
+            "application/epub+zip" | "application/java-archive" | "application/zip"
+        )
+}
+
+fn is_text(mime: &str) -> bool {
+    mime.starts_with("text/")
+        || mime.ends_with("+json")
+        || mime.ends_with("+xml")
+        || matches!(
+            mime,
+            "application/json"
+                | "application/xml"
+                | "application/javascript"
+                | "application/x-javascript"
+                | "application/x-yaml"
+                | "application/yaml"
+                | "application/toml"
+                | "application/x-sh"
+                | "application/sql"
+        )
+}
+
+/// Whether the server has a parser for content of this type.
+fn parseable(mime: &str) -> bool {
+    is_text(mime)
+        || is_zip_container(mime)
+        || ["image/", "audio/", "video/"]
+            .iter()
+            .any(|prefix| mime.starts_with(prefix))
+        || matches!(
+            mime,
+            "application/pdf"
+                | "application/rtf"
+                | "application/msword"
+                | "application/vnd.ms-excel"
+                | "application/vnd.ms-powerpoint"
+                | "application/gzip"
+                | "application/x-tar"
+        )
+}
+
+/// Whether the server can parse a file detected as `found`.
+pub fn is_supported(found: &FileType) -> bool {
+    found.text || parseable(&found.mime)
+}
+
+/// Problems worth telling the user about before `path`, detected as `found`,
+/// is uploaded.
+pub fn warnings(path: &Path, found: &FileType) -> Vec<String> {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string());
+    let mut warnings = Vec::new();
+    let named = by_extension(path);
+    let disagrees = match &named {
+        Some(named) if found.sniffed => named != &found.mime && !(found.text && is_text(named)),
+        Some(named) => !found.text && is_text(named),
+        None => false,
+    };
+    if disagrees {
+        warnings.push(format!(
+            "{name} contains {} but its extension says {}; the server picks a parser by extension, so rename it or pass --force-type",
+            found.mime,
+            named.as_deref().unwrap_or_default()
+        ));
+    }
+    if !is_supported(found) {
+        warnings.push(format!(
+            "{name} looks like binary data ({}) that the server's parsers will likely reject",
+            found.mime
+        ));
+    }
+    warnings
+}
+
+/// Check a `--force-type` value: a `type/subtype` MIME type.
+pub fn validate(mime: &str) -> Result<()> {
+    let valid = mime.split_once('/').is_some_and(|(kind, subtype)| {
+        let token = |part: &str| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "!#$&^_.+-".contains(c))
+        };
+        token(kind) && token(subtype.split(';').next().unwrap_or_default().trim())
+    });
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::Client(format!(
+            "Invalid --force-type '{mime}'; expected a MIME type such as text/markdown"
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_wins_over_the_extension() {
+        let pdf = classify(Path::new("contract.md"), b"%PDF-1.7\n%\xe2\xe3\xcf\xd3");
+        assert_eq!(pdf.mime, "application/pdf");
+        assert!(pdf.sniffed);
+        let warnings = warnings(Path::new("contract.md"), &pdf);
+        assert_eq!(warnings.len(), 1);
+        assert!(
+            warnings[0].contains("contains application/pdf but its extension says text/markdown")
+        );
+
+        let docx = classify(Path::new("spec.docx"), b"PK\x03\x04\x14\0\x06\0");
+        assert_eq!(
+            docx.mime,
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+        );
+        assert!(super::warnings(Path::new("spec.docx"), &docx).is_empty());
+
+        let markdown = classify(Path::new("notes.md"), "# 合同\n正文".as_bytes());
+        assert_eq!(markdown.mime, "text/markdown");
+        assert!(super::warnings(Path::new("notes.md"), &markdown).is_empty());
+
+        let unnamed = classify(Path::new("README"), b"BMW fleet report");
+        assert_eq!(unnamed.mime, "text/plain");
+    }
+
+    #[test]
+    fn binaries_the_server_cannot_parse_are_flagged() {
+        let elf = classify(Path::new("tool"), b"\x7fELF\x02\x01\x01\0");
+        assert!(!elf.text);
+        let warnings = warnings(Path::new("tool"), &elf);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("likely reject"));
+
+        let blob = classify(Path::new("data.bin"), b"\x01\x02\0\x03");
+        assert_eq!(blob.mime, "application/octet-stream");
+        assert_eq!(super::warnings(Path::new("data.bin"), &blob).len(), 1);
+
+        let png = classify(Path::new("diagram.png"), b"\x89PNG\r\n\x1a\n\0\0");
+        assert!(super::warnings(Path::new("diagram.png"), &png).is_empty());
+
+        assert!(validate("text/markdown; charset=utf-8").is_ok());
+        assert!(validate("markdown").is_err());
+    }
+}
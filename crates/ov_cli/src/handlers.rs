@@ -1,10 +1,14 @@
 use crate::CliContext;
 use crate::PrivacyCommands;
+use crate::archive::{self, ArchiveKind};
 use crate::client;
 use crate::commands;
 use crate::config::merge_csv_options;
 use crate::config_agent;
 use crate::error::{Error, Result};
+use crate::file_type;
+use crate::pack_compression::PackCompression;
+use crate::pack_crypto::{PackDecryption, PackEncryption};
 use crate::terminal_ui::{
     RenderedRegion as RenderedSelectRegion, clear_rendered_lines, live_select_block,
 };
@@ -12,25 +16,51 @@ use crate::theme;
 use crate::tui;
 use colored::Colorize;
 use serde_json::{Map, Value};
-
-pub async fn handle_add_resource(
-    mut path: String,
-    to: Option<String>,
-    parent: Option<String>,
-    parent_auto_create: Option<String>,
-    reason: String,
-    instruction: String,
-    wait: bool,
-    timeout: Option<f64>,
-    strict_mode: bool,
-    ignore_dirs: Option<String>,
-    include: Option<String>,
-    exclude: Option<String>,
-    no_directly_upload_media: bool,
-    watch_interval: f64,
-    resource_args: Option<String>,
-    ctx: CliContext,
-) -> Result<()> {
+use std::io::IsTerminal;
+
+/// What `add-resource` uploads and how the server should process it.
+pub struct AddResourceOptions {
+    pub path: String,
+    pub to: Option<String>,
+    pub parent: Option<String>,
+    pub parent_auto_create: Option<String>,
+    pub reason: String,
+    pub instruction: String,
+    pub wait: bool,
+    pub timeout: Option<f64>,
+    pub strict_mode: bool,
+    pub ignore_dirs: Option<String>,
+    pub include: Option<String>,
+    pub exclude: Option<String>,
+    pub no_directly_upload_media: bool,
+    pub watch_interval: f64,
+    pub resource_args: Option<String>,
+    pub no_extract: bool,
+    pub no_convert: bool,
+    pub force_type: Option<String>,
+}
+
+pub async fn handle_add_resource(options: AddResourceOptions, ctx: CliContext) -> Result<()> {
+    let AddResourceOptions {
+        mut path,
+        to,
+        parent,
+        parent_auto_create,
+        reason,
+        instruction,
+        wait,
+        timeout,
+        strict_mode,
+        ignore_dirs,
+        include,
+        exclude,
+        no_directly_upload_media,
+        watch_interval,
+        resource_args,
+        no_extract,
+        no_convert,
+        force_type,
+    } = options;
     let is_url =
         path.starts_with("http://") || path.starts_with("https://") || path.starts_with("git@");
 
@@ -48,6 +78,37 @@ pub async fn handle_add_resource(
         path = unescaped_path;
     }
 
+    // Held until the upload finishes; the extracted files are deleted on drop.
+    let mut extracted = None;
+    if let Some(kind) = ArchiveKind::of(std::path::Path::new(&path))
+        .filter(|_| !is_url && !no_extract && std::path::Path::new(&path).is_file())
+    {
+        let archive = archive::extract(std::path::Path::new(&path), kind)?;
+        if matches!(ctx.output_format, output::OutputFormat::Table) {
+            eprintln!(
+                "Note: Extracted {} files from {path}; adding them instead of the archive (use --no-extract to upload it as is).",
+                archive.files
+            );
+        }
+        path = archive.root.to_string_lossy().into_owned();
+        extracted = Some(archive);
+    }
+
+    if !is_url && std::path::Path::new(&path).is_file() {
+        if let Some(mime) = &force_type {
+            file_type::validate(mime)?;
+        } else {
+            let local = std::path::Path::new(&path);
+            for warning in file_type::warnings(local, &file_type::detect(local)?) {
+                eprintln!("{}", theme::warning(format!("Warning: {warning}")));
+            }
+        }
+    } else if force_type.is_some() {
+        return Err(Error::Client(
+            "--force-type applies only when adding a single local file; add archives with --no-extract to use it".to_string(),
+        ));
+    }
+
     // Check that only one of --to, --parent, or --parent-auto-create is set
     let mut exclusive_count = 0;
     if to.is_some() {
@@ -91,8 +152,15 @@ pub async fn handle_add_resource(
         ctx.profile.unwrap_or(ctx.config.profile),
         ctx.config.effective_extra_headers(),
     )
-    .with_gateway_token(ctx.config.effective_gateway_token());
-    commands::resources::add_resource(
+    .with_gateway_token(ctx.config.effective_gateway_token())
+    .with_proxy(ctx.config.proxy.clone())
+    .with_tls(ctx.config.tls_files())
+    .with_connect_timeout(ctx.config.connect_timeout())
+    .with_resumable_uploads(ctx.resume_upload)
+    .with_upload_dedup(ctx.dedup_upload)
+    .with_notebook_conversion(!no_convert)
+    .with_content_type(force_type);
+    let result = commands::resources::add_resource(
         &client,
         &path,
         to,
@@ -114,7 +182,9 @@ pub async fn handle_add_resource(
         ctx.should_show_progress(),
         ctx.is_verbose(),
     )
-    .await
+    .await;
+    drop(extracted);
+    result
 }
 
 fn parse_add_resource_args(raw: Option<&str>) -> Result<Option<Map<String, Value>>> {
@@ -288,9 +358,14 @@ pub async fn handle_export(
     uri: String,
     to: String,
     include_vectors: bool,
+    compression: Option<PackCompression>,
+    encryption: Option<PackEncryption>,
     ctx: CliContext,
 ) -> Result<()> {
-    let client = ctx.get_client();
+    let client = ctx
+        .get_client()
+        .with_pack_compression(compression)
+        .with_pack_encryption(encryption);
     commands::pack::export(
         &client,
         &uri,
@@ -302,12 +377,44 @@ pub async fn handle_export(
     .await
 }
 
-pub async fn handle_backup(to: String, include_vectors: bool, ctx: CliContext) -> Result<()> {
-    let client = ctx.get_client();
+pub async fn handle_backup(
+    to: String,
+    include_vectors: bool,
+    compression: Option<PackCompression>,
+    encryption: Option<PackEncryption>,
+    progress: bool,
+    ctx: CliContext,
+) -> Result<()> {
+    let client = ctx
+        .get_client()
+        .with_pack_compression(compression)
+        .with_pack_encryption(encryption);
     commands::pack::backup(
         &client,
         &to,
         include_vectors,
+        progress && std::io::stderr().is_terminal(),
+        ctx.output_format,
+        ctx.compact,
+    )
+    .await
+}
+
+pub async fn handle_sync(
+    from: String,
+    to: String,
+    prefix: String,
+    dry_run: bool,
+    ctx: CliContext,
+) -> Result<()> {
+    let source = ctx.with_saved_config(&from)?.get_client();
+    let target = ctx.with_saved_config(&to)?.get_client();
+    commands::pack::sync(
+        &source,
+        &target,
+        (&from, &to),
+        &prefix,
+        dry_run,
         ctx.output_format,
         ctx.compact,
     )
@@ -319,8 +426,15 @@ pub async fn handle_import(
     target_uri: String,
     on_conflict: Option<String>,
     vector_mode: Option<String>,
+    trust: commands::pack::PackTrust,
+    decryption: PackDecryption,
     ctx: CliContext,
 ) -> Result<()> {
+    let prepared = commands::pack::prepare_upload(&file_path, decryption).await?;
+    let file_path = prepared
+        .as_ref()
+        .map_or(file_path, |temp| temp.path().to_string_lossy().into_owned());
+    commands::pack::verify(&file_path, trust).await?;
     let client = ctx.get_client();
     commands::pack::import(
         &client,
@@ -338,14 +452,36 @@ pub async fn handle_restore(
     file_path: String,
     on_conflict: Option<String>,
     vector_mode: Option<String>,
+    dry_run: bool,
+    progress: bool,
+    decryption: PackDecryption,
     ctx: CliContext,
 ) -> Result<()> {
+    let prepared = commands::pack::prepare_upload(&file_path, decryption).await?;
     let client = ctx.get_client();
+    if dry_run {
+        let pack_path = prepared
+            .as_ref()
+            .map_or(std::path::Path::new(&file_path), |temp| temp.path());
+        return commands::pack::restore_plan(
+            &client,
+            &file_path,
+            pack_path,
+            on_conflict.as_deref(),
+            ctx.output_format,
+            ctx.compact,
+        )
+        .await;
+    }
+    let file_path = prepared
+        .as_ref()
+        .map_or(file_path, |temp| temp.path().to_string_lossy().into_owned());
     commands::pack::restore(
         &client,
         &file_path,
         on_conflict.as_deref(),
         vector_mode.as_deref(),
+        progress && std::io::stderr().is_terminal(),
         ctx.output_format,
         ctx.compact,
     )
@@ -481,16 +617,85 @@ pub async fn handle_session(cmd: SessionCommands, ctx: CliContext) -> Result<()>
         SessionCommands::AddMessages {
             session_id,
             messages,
+            file,
+        } => match (messages, file) {
+            (_, Some(file)) => {
+                commands::session::add_messages_from_file(
+                    &client,
+                    &session_id,
+                    &file,
+                    ctx.output_format,
+                    ctx.compact,
+                )
+                .await
+            }
+            (messages, None) => {
+                commands::session::add_messages(
+                    &client,
+                    &session_id,
+                    &messages.unwrap_or_default(),
+                    ctx.output_format,
+                    ctx.compact,
+                )
+                .await
+            }
+        },
+        SessionCommands::Chat {
+            session_id,
+            message,
+            no_format,
+            no_history,
         } => {
-            commands::session::add_messages(
+            let cmd = commands::chat::ChatCommand {
+                endpoint: commands::chat::endpoint_from_env(),
+                api_key: std::env::var("VIKINGBOT_API_KEY").ok(),
+                account: ctx.config.account.clone(),
+                user: ctx.config.user.clone(),
+                actor_peer_id: ctx.config.effective_actor_peer_id(),
+                session: Some(session_id),
+                sender: "user".to_string(),
+                message,
+                stream: true,
+                no_format,
+                no_history,
+                stream_tokens: true,
+                record_session: true,
+            };
+            cmd.run().await
+        }
+        SessionCommands::Extract { session_id } => {
+            commands::session::extract_session(&client, &session_id, ctx.output_format, ctx.compact)
+                .await
+        }
+        SessionCommands::Export { session_id, format } => {
+            commands::session::export_session(&client, &session_id, &format).await
+        }
+        SessionCommands::Import {
+            file, session_id, ..
+        } => {
+            commands::session::import_session(
                 &client,
-                &session_id,
-                &messages,
+                &file,
+                session_id.as_deref(),
                 ctx.output_format,
                 ctx.compact,
             )
             .await
         }
+        SessionCommands::Tail {
+            session_id,
+            lines,
+            interval,
+        } => {
+            commands::session::tail_session(
+                &client,
+                &session_id,
+                lines,
+                interval,
+                ctx.output_format,
+            )
+            .await
+        }
         SessionCommands::Commit { session_id } => {
             commands::session::commit_session(&client, &session_id, ctx.output_format, ctx.compact)
                 .await
@@ -498,7 +703,41 @@ pub async fn handle_session(cmd: SessionCommands, ctx: CliContext) -> Result<()>
     }
 }
 
-use crate::AdminCommands;
+use crate::MemoryCommands;
+
+pub async fn handle_memory(cmd: MemoryCommands, ctx: CliContext) -> Result<()> {
+    let client = ctx.get_client();
+    match cmd {
+        MemoryCommands::Ls {
+            category,
+            node_limit,
+        } => {
+            commands::memory::ls(
+                &client,
+                category.as_deref(),
+                node_limit,
+                ctx.output_format,
+                ctx.compact,
+            )
+            .await
+        }
+        MemoryCommands::Show { uri } => {
+            commands::memory::show(&client, &uri, ctx.output_format, ctx.compact).await
+        }
+    }
+}
+
+use crate::{AdminCommands, AdminUserCommands};
+
+/// The workspace `ov admin user` acts on.
+fn admin_workspace(ctx: &CliContext) -> Result<String> {
+    ctx.config.account.clone().ok_or_else(|| {
+        Error::Client(
+            "No workspace selected; pass --workspace <name> or run ov workspace use <name>"
+                .to_string(),
+        )
+    })
+}
 
 pub async fn handle_admin(cmd: AdminCommands, ctx: CliContext) -> Result<()> {
     let client = ctx.get_client();
@@ -609,6 +848,51 @@ pub async fn handle_admin(cmd: AdminCommands, ctx: CliContext) -> Result<()> {
             )
             .await
         }
+        AdminCommands::User { action } => {
+            let account_id = admin_workspace(&ctx)?;
+            match action {
+                AdminUserCommands::Add {
+                    user_id,
+                    role,
+                    seed,
+                    user_config_json,
+                } => {
+                    commands::admin::register_user(
+                        &client,
+                        &account_id,
+                        &user_id,
+                        &role,
+                        seed.as_deref(),
+                        user_config_json.as_deref(),
+                        ctx.output_format,
+                        ctx.compact,
+                    )
+                    .await
+                }
+                AdminUserCommands::Ls { limit, name, role } => {
+                    commands::admin::list_users(
+                        &client,
+                        &account_id,
+                        limit,
+                        name,
+                        role,
+                        ctx.output_format,
+                        ctx.compact,
+                    )
+                    .await
+                }
+                AdminUserCommands::Rm { user_id } => {
+                    commands::admin::remove_user(
+                        &client,
+                        &account_id,
+                        &user_id,
+                        ctx.output_format,
+                        ctx.compact,
+                    )
+                    .await
+                }
+            }
+        }
     }
 }
 
@@ -1178,19 +1462,56 @@ fn validating_target_config(language: Language) -> &'static str {
     }
 }
 
-pub async fn handle_read(uri: String, ctx: CliContext) -> Result<()> {
+pub async fn handle_read(
+    uris: Vec<String>,
+    from_file: Option<String>,
+    render: bool,
+    ctx: CliContext,
+) -> Result<()> {
     let client = ctx.get_client();
-    commands::content::read(&client, &uri, ctx.output_format, ctx.compact).await
+    let uris = commands::content::collect_uris(uris, from_file.as_deref())?;
+    if let [uri] = uris.as_slice() {
+        return commands::content::read(&client, uri, render, ctx.output_format, ctx.compact).await;
+    }
+    let level = commands::content::ContentLevel::Read;
+    commands::content::read_many(
+        &client,
+        level,
+        &uris,
+        render,
+        ctx.output_format,
+        ctx.compact,
+    )
+    .await
 }
 
-pub async fn handle_abstract(uri: String, ctx: CliContext) -> Result<()> {
+pub async fn handle_abstract(
+    uris: Vec<String>,
+    from_file: Option<String>,
+    ctx: CliContext,
+) -> Result<()> {
     let client = ctx.get_client();
-    commands::content::abstract_content(&client, &uri, ctx.output_format, ctx.compact).await
+    let uris = commands::content::collect_uris(uris, from_file.as_deref())?;
+    if let [uri] = uris.as_slice() {
+        return commands::content::abstract_content(&client, uri, ctx.output_format, ctx.compact)
+            .await;
+    }
+    let level = commands::content::ContentLevel::Abstract;
+    commands::content::read_many(&client, level, &uris, false, ctx.output_format, ctx.compact).await
 }
 
-pub async fn handle_overview(uri: String, ctx: CliContext) -> Result<()> {
+pub async fn handle_overview(
+    uris: Vec<String>,
+    from_file: Option<String>,
+    ctx: CliContext,
+) -> Result<()> {
     let client = ctx.get_client();
-    commands::content::overview(&client, &uri, ctx.output_format, ctx.compact).await
+    let uris = commands::content::collect_uris(uris, from_file.as_deref())?;
+    if let [uri] = uris.as_slice() {
+        return commands::content::overview(&client, uri, ctx.output_format, ctx.compact).await;
+    }
+    let level = commands::content::ContentLevel::Overview;
+    commands::content::read_many(&client, level, &uris, false, ctx.output_format, ctx.compact).await
 }
 
 pub async fn handle_write(
@@ -1205,11 +1526,22 @@ pub async fn handle_write(
     let client = ctx.get_client();
     let payload = match (content, from_file) {
         (Some(value), None) => value,
-        (None, Some(path)) => std::fs::read_to_string(path)
-            .map_err(|e| Error::Client(format!("Failed to read --from-file: {}", e)))?,
+        (None, Some(path)) if path == "-" => {
+            if std::io::stdin().is_terminal() {
+                return Err(Error::Client(
+                    "Reading content from stdin; pipe it in, e.g. some-tool | ov append <uri> -"
+                        .into(),
+                ));
+            }
+            std::io::read_to_string(std::io::stdin())
+                .map_err(|e| Error::Client(format!("Failed to read stdin: {}", e)))?
+        }
+        (None, Some(path)) => std::fs::read_to_string(&path)
+            .map_err(|e| Error::Client(format!("Failed to read {}: {}", path, e)))?,
         _ => {
             return Err(Error::Client(
-                "Specify exactly one of --content or --from-file".into(),
+                "Specify exactly one of --content, --from-file, or a file argument (- for stdin)"
+                    .into(),
             ));
         }
     };
@@ -1272,63 +1604,19 @@ pub async fn handle_get(uri: String, local_path: String, ctx: CliContext) -> Res
 }
 
 pub async fn handle_find(
-    query: Option<String>,
-    uri: String,
-    image: Option<String>,
-    node_limit: i32,
-    threshold: Option<f64>,
-    after: Option<String>,
-    before: Option<String>,
-    level: Option<Vec<i32>>,
-    context_type: Option<Vec<String>>,
-    tags: Option<Vec<String>>,
+    options: commands::search::SearchOptions<'_>,
     ctx: CliContext,
 ) -> Result<()> {
-    let query = query.unwrap_or_default();
-    if query.trim().is_empty() && image.is_none() {
-        return Err(Error::Client(
-            "Search query or --image must not be empty.".to_string(),
-        ));
-    }
-    let mut params = vec![format!("--uri={}", uri), format!("-n {}", node_limit)];
-    if let Some(ref img) = image {
-        params.push(format!("--image {}", img));
-    }
-    if let Some(t) = threshold {
-        params.push(format!("--threshold {}", t));
-    }
-    append_time_filter_params(&mut params, after.as_deref(), before.as_deref());
-    if let Some(ref l) = level {
-        params.push(format!(
-            "--level {}",
-            l.iter()
-                .map(|v| v.to_string())
-                .collect::<Vec<_>>()
-                .join(",")
-        ));
-    }
-    if let Some(ref context_types) = context_type {
-        params.push(format!("--context-type {}", context_types.join(",")));
-    }
-    if let Some(ref t) = tags {
-        params.push(format!("--tags {}", t.join(",")));
-    }
-    params.push(format!("\"{}\"", query));
-    print_command_echo("ov find", &params.join(" "), ctx.config.echo_command);
+    let scoring = ctx.search_scoring()?;
+    let params = search_echo_params(&options, &scoring)?;
+    print_command_echo("ov find", &params.join(" "), &ctx);
     let client = ctx.get_client();
     commands::search::find(
         &client,
-        &query,
-        &uri,
-        image,
-        node_limit,
-        threshold,
-        after.as_deref(),
-        before.as_deref(),
-        None,
-        level,
-        context_type,
-        tags,
+        options,
+        &scoring,
+        ctx.token_column()?,
+        ctx.snippets,
         ctx.output_format,
         ctx.compact,
     )
@@ -1336,37 +1624,56 @@ pub async fn handle_find(
 }
 
 pub async fn handle_search(
-    query: Option<String>,
-    uri: String,
-    image: Option<String>,
-    session_id: Option<String>,
-    node_limit: i32,
-    threshold: Option<f64>,
-    after: Option<String>,
-    before: Option<String>,
-    level: Option<Vec<i32>>,
-    context_type: Option<Vec<String>>,
-    tags: Option<Vec<String>>,
+    options: commands::search::SearchOptions<'_>,
     ctx: CliContext,
 ) -> Result<()> {
-    let query = query.unwrap_or_default();
-    if query.trim().is_empty() && image.is_none() {
+    let scoring = ctx.search_scoring()?;
+    let params = search_echo_params(&options, &scoring)?;
+    print_command_echo("ov search", &params.join(" "), &ctx);
+    let client = ctx.get_client();
+    commands::search::search(
+        &client,
+        options,
+        &scoring,
+        ctx.token_column()?,
+        ctx.snippets,
+        ctx.output_format,
+        ctx.compact,
+    )
+    .await
+}
+
+/// The flags `find` and `search` echo, after checking there is something to
+/// search for.
+fn search_echo_params(
+    options: &commands::search::SearchOptions<'_>,
+    scoring: &client::SearchScoring,
+) -> Result<Vec<String>> {
+    if options.query.trim().is_empty() && options.image.is_none() {
         return Err(Error::Client(
             "Search query or --image must not be empty.".to_string(),
         ));
     }
-    let mut params = vec![format!("--uri={}", uri), format!("-n {}", node_limit)];
-    if let Some(ref img) = image {
+    let mut params = vec![
+        format!("--uri={}", options.uri),
+        format!("-n {}", options.node_limit),
+    ];
+    if let Some(ref img) = options.image {
         params.push(format!("--image {}", img));
     }
-    if let Some(s) = &session_id {
+    if let Some(s) = &options.session_id {
         params.push(format!("--session-id {}", s));
     }
-    if let Some(t) = threshold {
+    if let Some(t) = options.threshold {
         params.push(format!("--threshold {}", t));
     }
-    append_time_filter_params(&mut params, after.as_deref(), before.as_deref());
-    if let Some(ref l) = level {
+    append_time_filter_params(
+        &mut params,
+        options.since,
+        options.until,
+        options.time_field,
+    );
+    if let Some(ref l) = options.level {
         params.push(format!(
             "--level {}",
             l.iter()
@@ -1375,91 +1682,96 @@ pub async fn handle_search(
                 .join(",")
         ));
     }
-    if let Some(ref context_types) = context_type {
+    if let Some(ref context_types) = options.context_type {
         params.push(format!("--context-type {}", context_types.join(",")));
     }
-    if let Some(ref t) = tags {
+    if let Some(ref t) = options.tags {
         params.push(format!("--tags {}", t.join(",")));
     }
-    params.push(format!("\"{}\"", query));
-    print_command_echo("ov search", &params.join(" "), ctx.config.echo_command);
-    let client = ctx.get_client();
-    commands::search::search(
-        &client,
-        &query,
-        &uri,
-        image,
-        session_id,
-        node_limit,
-        threshold,
-        after.as_deref(),
-        before.as_deref(),
-        None,
-        level,
-        context_type,
-        tags,
-        ctx.output_format,
-        ctx.compact,
-    )
-    .await
+    append_scoring_params(&mut params, scoring);
+    params.push(format!("\"{}\"", options.query));
+    Ok(params)
 }
 
 pub fn append_time_filter_params(
     params: &mut Vec<String>,
     after: Option<&str>,
     before: Option<&str>,
+    time_field: Option<&str>,
 ) {
+    let prefix = match time_field {
+        Some("created_at") => "created-",
+        _ => "",
+    };
     if let Some(value) = after {
-        params.push(format!("--after {}", value));
+        params.push(format!("--{prefix}after {}", value));
     }
     if let Some(value) = before {
-        params.push(format!("--before {}", value));
+        params.push(format!("--{prefix}before {}", value));
     }
 }
 
-/// Print command with specified parameters for debugging
-pub fn print_command_echo(command: &str, params: &str, echo_enabled: bool) {
-    if echo_enabled {
-        println!("cmd: {} {}", command, params);
+fn append_scoring_params(params: &mut Vec<String>, scoring: &client::SearchScoring) {
+    if let Some(mode) = &scoring.mode {
+        params.push(format!("--mode {}", mode));
+    }
+    match scoring.rerank {
+        Some(true) => params.push("--rerank".to_string()),
+        Some(false) => params.push("--no-rerank".to_string()),
+        None => {}
+    }
+    if let Some(weight) = scoring.vector_weight {
+        params.push(format!("--vector-weight {}", weight));
+    }
+    if let Some(weight) = scoring.keyword_weight {
+        params.push(format!("--keyword-weight {}", weight));
+    }
+    if scoring.include_scores {
+        params.push("--scores".to_string());
+    }
+    if scoring.explain {
+        params.push("--explain".to_string());
+    }
+}
+
+/// Print command with specified parameters for debugging. It goes to stderr,
+/// and not at all with machine-readable output, so stdout stays parseable.
+pub fn print_command_echo(command: &str, params: &str, ctx: &CliContext) {
+    if ctx.config.echo_command && !output::is_machine_readable(ctx.output_format) {
+        eprintln!("cmd: {} {}", command, params);
     }
 }
 
 pub async fn handle_ls(
-    uri: String,
-    simple: bool,
-    recursive: bool,
-    abs_limit: i32,
-    show_all_hidden: bool,
-    node_limit: i32,
+    options: commands::filesystem::LsOptions<'_>,
     ctx: CliContext,
 ) -> Result<()> {
     let mut params = vec![
-        uri.clone(),
-        format!("-l {}", abs_limit),
-        format!("-n {}", node_limit),
+        options.uri.to_string(),
+        format!("-l {}", options.abs_limit),
+        format!("-n {}", options.node_limit),
     ];
-    if simple {
+    if options.simple {
         params.push("-s".to_string());
     }
-    if recursive {
+    if options.recursive {
         params.push("-r".to_string());
     }
-    if show_all_hidden {
+    if options.show_all_hidden {
         params.push("-a".to_string());
     }
-    print_command_echo("ov ls", &params.join(" "), ctx.config.echo_command);
+    for tag in options.tags {
+        params.push(format!("--tag {}", tag));
+    }
+    print_command_echo("ov ls", &params.join(" "), &ctx);
 
     let client = ctx.get_client();
     let api_output = if ctx.compact { "agent" } else { "original" };
     commands::filesystem::ls(
         &client,
-        &uri,
-        simple,
-        recursive,
+        options,
         api_output,
-        abs_limit,
-        show_all_hidden,
-        node_limit,
+        ctx.token_column()?,
         ctx.output_format,
         ctx.compact,
     )
@@ -1483,7 +1795,7 @@ pub async fn handle_tree(
     if show_all_hidden {
         params.push("-a".to_string());
     }
-    print_command_echo("ov tree", &params.join(" "), ctx.config.echo_command);
+    print_command_echo("ov tree", &params.join(" "), &ctx);
 
     let client = ctx.get_client();
     let api_output = if ctx.compact { "agent" } else { "original" };
@@ -1514,33 +1826,120 @@ pub async fn handle_mkdir(uri: String, description: Option<String>, ctx: CliCont
 }
 
 pub async fn handle_rm(
-    uri: String,
-    recursive: bool,
-    wait: bool,
-    timeout: Option<f64>,
+    uri: Option<String>,
+    glob: Option<String>,
+    options: commands::filesystem::RmOptions,
     ctx: CliContext,
 ) -> Result<()> {
     let client = ctx.get_client();
-    commands::filesystem::rm(
+    match (uri, glob) {
+        (_, Some(pattern)) => {
+            commands::filesystem::rm_glob(
+                &client,
+                &pattern,
+                options,
+                ctx.output_format,
+                ctx.compact,
+            )
+            .await
+        }
+        (Some(uri), None) => {
+            commands::filesystem::rm(&client, &uri, options, ctx.output_format, ctx.compact).await
+        }
+        (None, None) => Err(Error::Client("ov rm requires a URI or --glob".to_string())),
+    }
+}
+
+pub async fn handle_mv(from_uri: String, to_uri: String, ctx: CliContext) -> Result<()> {
+    let client = ctx.get_client();
+    commands::filesystem::mv(&client, &from_uri, &to_uri, ctx.output_format, ctx.compact).await
+}
+
+pub async fn handle_cp(
+    from_uri: String,
+    to_uri: String,
+    options: commands::filesystem::CpOptions,
+    ctx: CliContext,
+) -> Result<()> {
+    let client = ctx.get_client();
+    commands::filesystem::cp(
         &client,
-        &uri,
-        recursive,
-        wait,
-        timeout,
+        &from_uri,
+        &to_uri,
+        options,
         ctx.output_format,
         ctx.compact,
     )
     .await
 }
 
-pub async fn handle_mv(from_uri: String, to_uri: String, ctx: CliContext) -> Result<()> {
+pub async fn handle_stat(uri: String, ctx: CliContext) -> Result<()> {
     let client = ctx.get_client();
-    commands::filesystem::mv(&client, &from_uri, &to_uri, ctx.output_format, ctx.compact).await
+    commands::filesystem::stat(&client, &uri, ctx.output_format, ctx.compact).await
 }
 
-pub async fn handle_stat(uri: String, ctx: CliContext) -> Result<()> {
+pub async fn handle_bench(
+    op: String,
+    target: Option<String>,
+    uri: String,
+    concurrency: usize,
+    duration: Option<f64>,
+    requests: Option<usize>,
+    ctx: CliContext,
+) -> Result<()> {
+    let op = match (op.as_str(), target) {
+        ("find", Some(query)) => commands::bench::BenchOp::Find { query, uri },
+        ("read", Some(uri)) => commands::bench::BenchOp::Read { uri },
+        ("add", _) => commands::bench::BenchOp::Add,
+        (op, None) => {
+            let what = if op == "find" { "A query" } else { "A URI" };
+            return Err(Error::Client(format!(
+                "{what} is required for ov bench {op}"
+            )));
+        }
+        (op, Some(_)) => return Err(Error::Client(format!("Unknown bench operation: {op}"))),
+    };
+    if duration.is_some_and(|secs| secs.is_nan() || secs <= 0.0) {
+        return Err(Error::Client(
+            "--duration must be greater than 0".to_string(),
+        ));
+    }
+    let duration = duration
+        .or(requests.is_none().then_some(10.0))
+        .map(std::time::Duration::from_secs_f64);
     let client = ctx.get_client();
-    commands::filesystem::stat(&client, &uri, ctx.output_format, ctx.compact).await
+    commands::bench::run(
+        &client,
+        op,
+        commands::bench::BenchLimits {
+            concurrency,
+            duration,
+            requests,
+        },
+        ctx.output_format,
+        ctx.compact,
+    )
+    .await
+}
+
+pub async fn handle_api(
+    method: String,
+    path: String,
+    fields: Vec<String>,
+    raw_body: Option<String>,
+    ctx: CliContext,
+) -> Result<()> {
+    let client = ctx.get_client();
+    commands::api::request(
+        &client,
+        &method,
+        &path,
+        &fields,
+        raw_body.as_deref(),
+        ctx.output_format,
+        ctx.compact,
+    )
+    .await
 }
 
 pub async fn handle_attrs(uri: String, key: Option<String>, ctx: CliContext) -> Result<()> {
@@ -1583,7 +1982,7 @@ pub async fn handle_grep(
         params.push("-i".to_string());
     }
     params.push(format!("\"{}\"", pattern));
-    print_command_echo("ov grep", &params.join(" "), ctx.config.echo_command);
+    print_command_echo("ov grep", &params.join(" "), &ctx);
     let client = ctx.get_client();
     commands::search::grep(
         &client,
@@ -1610,7 +2009,7 @@ pub async fn handle_glob(
         format!("-n {}", node_limit),
         format!("\"{}\"", pattern),
     ];
-    print_command_echo("ov glob", &params.join(" "), ctx.config.echo_command);
+    print_command_echo("ov glob", &params.join(" "), &ctx);
     let client = ctx.get_client();
     commands::search::glob(
         &client,
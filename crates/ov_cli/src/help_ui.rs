@@ -6,6 +6,7 @@ use colored::Colorize;
 use crate::{
     Cli,
     cli_arg_scan::ValueOptions,
+    error,
     i18n::{Language, copy},
     terminal_ui::{
         display_width, fit_to_display_width, pad_to_display_width, truncate_to_display_width,
@@ -63,35 +64,65 @@ struct CommandHelpSpec {
 const CORE_WORKFLOW: &[HelpCommand] = help_commands![
     "add-resource",
     "add-skill",
+    "add",
+    "clip",
     "skills",
     "find",
     "read",
     "write",
+    "append",
+    "edit",
     "add-memory",
     "set-tags",
 ];
 
-const FILESYSTEM: &[HelpCommand] =
-    help_commands!["ls", "tree", "mkdir", "rm", "mv", "stat", "attrs", "get"];
+const FILESYSTEM: &[HelpCommand] = help_commands![
+    "ls", "tree", "mkdir", "rm", "mv", "cp", "stat", "attrs", "tag", "meta", "get", "alias",
+    "bookmark", "recent"
+];
 
 const SEARCH_CONTEXT: &[HelpCommand] = help_commands![
-    "find", "search", "grep", "glob", "abstract", "overview", "read"
+    "find", "search", "grep", "glob", "context", "ask", "tokens", "abstract", "overview", "read"
 ];
 
 const CONFIG_STATUS: &[HelpCommand] = help_commands![
-    "config", "language", "health", "status", "observer", "wait", "task", "version",
+    "config",
+    "workspace",
+    "language",
+    "health",
+    "doctor",
+    "status",
+    "stats",
+    "observer",
+    "wait",
+    "task",
+    "jobs",
+    "events",
+    "daemon",
+    "schedule",
+    "version",
+    "completions",
 ];
 
 const IMPORT_EXPORT_SESSIONS: &[HelpCommand] = help_commands![
-    "import", "export", "backup", "restore", "snapshot", "session", "privacy"
+    "import", "export", "backup", "restore", "sync", "watch", "queue", "run", "pack", "snapshot",
+    "session", "memory", "privacy"
 ];
 
 const INTERACTIVE_ADMIN: &[HelpCommand] = help_commands![
     "tui",
+    "pick",
+    "top",
     "chat",
+    "mcp",
+    "mock-server",
     "admin",
+    "audit",
     "system",
     "reindex",
+    "index",
+    "api",
+    "bench",
     "relations",
     "link",
     "unlink"
@@ -141,6 +172,22 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
                 label: "ov add-resource https://example.com/sitemap.xml --watch-interval 1440",
                 description: "Import a whole site via sitemap/RSS and refresh it daily.",
             },
+            HelpItem {
+                label: "ov add-resource ./handbook.zip --parent viking://resources",
+                description: "Extract an archive locally and import the files it contains.",
+            },
+            HelpItem {
+                label: "ov add-resource ./churn-analysis.ipynb",
+                description: "Add a notebook as Markdown with its code and summarized outputs.",
+            },
+            HelpItem {
+                label: "ov add-resource ./contract --force-type application/pdf",
+                description: "Upload a file as the given type when detection gets it wrong.",
+            },
+            HelpItem {
+                label: "ov add-resource ./tickets --redact",
+                description: "Mask emails, phone numbers, and ID numbers before upload.",
+            },
         ],
         next_steps: &[
             HelpItem {
@@ -181,6 +228,128 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
             },
         ],
     },
+    CommandHelpSpec {
+        path: &["add"],
+        purpose: "Add resources that ov fetches or converts itself: objects under an S3 or TOS prefix, content piped on stdin, a documentation site, a blog feed, or a tabular data file. --check-only checks local files without uploading them.",
+        examples: &[
+            HelpItem {
+                label: "ov add s3://team-docs/handbook/ -j 8",
+                description: "Add every object under a bucket prefix, eight at a time; reruns only add new or changed objects.",
+            },
+            HelpItem {
+                label: "ov add tos://team-docs/handbook/ --region cn-shanghai --dry-run",
+                description: "List the objects a run against a Volcengine TOS bucket would add.",
+            },
+            HelpItem {
+                label: "some-tool | ov add - --name notes.md --target viking://resources/notes",
+                description: "Add generated content straight from a pipe, without a temporary file.",
+            },
+            HelpItem {
+                label: "ov add url https://docs.example.com/guide/ --depth 2 --same-domain",
+                description: "Crawl a documentation site and add each page as Markdown.",
+            },
+            HelpItem {
+                label: "ov add feed https://blog.example.com/feed.xml",
+                description: "Add the feed entries earlier runs have not added.",
+            },
+            HelpItem {
+                label: "ov add table data.parquet --target viking://resources/datasets/sales",
+                description: "Add a dataset as a schema summary and searchable row chunks.",
+            },
+            HelpItem {
+                label: "ov add --check-only ./contracts",
+                description: "Check local files for encoding, type, and front matter problems before adding them.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov add url --help",
+            description: "Show crawl options.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["clip"],
+        purpose: "Capture the clipboard's text or image as a resource with a timestamped name under an inbox URI, and print the new URI.",
+        examples: &[
+            HelpItem {
+                label: "ov clip",
+                description: "Add the clipboard to the inbox, e.g. as clip-20260412-093015.md or .png.",
+            },
+            HelpItem {
+                label: "ov clip --inbox viking://resources/research/inbox",
+                description: "Capture into another inbox; set \"clip_inbox\" in the config to change the default.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov ls viking://resources/inbox",
+            description: "List earlier captures.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["add", "url"],
+        purpose: "Fetch pages from a URL, extract their main content as Markdown, follow links up to a depth, and add each page once by canonical URL.",
+        examples: &[
+            HelpItem {
+                label: "ov add url https://docs.example.com/guide/intro",
+                description: "Add a single page.",
+            },
+            HelpItem {
+                label: "ov add url https://docs.example.com/guide/ --depth 2 --same-domain --dry-run",
+                description: "List the pages a two-hop crawl of the site would add.",
+            },
+            HelpItem {
+                label: "ov add url https://docs.example.com/ --depth 3 --same-domain -n 500 --parent viking://resources/example-docs",
+                description: "Ingest up to 500 pages of a documentation site.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov tree viking://resources/web",
+            description: "Inspect the added pages.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["add", "feed"],
+        purpose: "Add the entries of a sitemap.xml or RSS/Atom feed, keeping a local state file so repeated runs only add new entries.",
+        examples: &[
+            HelpItem {
+                label: "ov add feed https://blog.example.com/feed.xml --dry-run",
+                description: "List the entries the next run would add.",
+            },
+            HelpItem {
+                label: "ov add feed https://github.com/acme/app/releases.atom --mark-seen",
+                description: "Start from now: record current entries without adding them.",
+            },
+            HelpItem {
+                label: "ov add feed https://docs.example.com/sitemap.xml --parent viking://resources/example-docs --state ./feeds.json",
+                description: "Add new sitemap pages, keeping state next to a cron job.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov tree viking://resources/feeds",
+            description: "Inspect the added entries.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["add", "table"],
+        purpose: "Read a CSV, TSV, or Parquet file locally and add it as a schema summary (column types, nulls, ranges, examples) plus Markdown tables of --chunk-rows rows each, so the data is searchable instead of an opaque blob.",
+        examples: &[
+            HelpItem {
+                label: "ov add table ./sales.csv --dry-run",
+                description: "Read the file and list the chunks that would be written.",
+            },
+            HelpItem {
+                label: "ov add table data.parquet --target viking://resources/datasets/sales --chunk-rows 500",
+                description: "Add a Parquet dataset in chunks of 500 rows.",
+            },
+            HelpItem {
+                label: "ov add table export.txt --delimiter ';'",
+                description: "Add semicolon-separated text.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov read viking://resources/tables/<name>/schema.md",
+            description: "Read the column summary.",
+        }],
+    },
     CommandHelpSpec {
         path: &["skills"],
         purpose: "Manage installed agent skills.",
@@ -193,6 +362,10 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
                 label: "ov skills find \"code review\"",
                 description: "Search installed skills semantically.",
             },
+            HelpItem {
+                label: "ov skills export code-review ./skills",
+                description: "Download an installed skill to edit or share it.",
+            },
         ],
         next_steps: &[
             HelpItem {
@@ -205,6 +378,48 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
             },
         ],
     },
+    CommandHelpSpec {
+        path: &["skills", "export"],
+        purpose: "Download an installed skill's SKILL.md and supporting files into a local directory.",
+        examples: &[
+            HelpItem {
+                label: "ov skills export code-review ./skills",
+                description: "Write the skill to ./skills/code-review.",
+            },
+            HelpItem {
+                label: "ov skills export code-review --uri viking://agent/skills --force",
+                description: "Export the shared copy, replacing an earlier export.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov skills add ./skills/code-review",
+            description: "Install the edited skill again.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["skills", "init"],
+        purpose: "Generate a skeleton skill directory with a templated SKILL.md and references/.",
+        examples: &[
+            HelpItem {
+                label: "ov skills init code-review ./skills --tags review,git",
+                description: "Write ./skills/code-review with placeholder instructions.",
+            },
+            HelpItem {
+                label: "ov skills init onboarding --from-uri viking://resources/handbook",
+                description: "Copy a resource subtree into the new skill's references/.",
+            },
+        ],
+        next_steps: &[
+            HelpItem {
+                label: "ov skills validate ./skills/code-review",
+                description: "Check the edited SKILL.md.",
+            },
+            HelpItem {
+                label: "ov skills add ./skills/code-review",
+                description: "Install the skill.",
+            },
+        ],
+    },
     CommandHelpSpec {
         path: &["ls"],
         purpose: "List resources under a Viking URI.",
@@ -233,7 +448,7 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
         path: &["tree"],
         purpose: "Show a hierarchical view of resources under a URI.",
         examples: &[HelpItem {
-            label: "ov tree viking://projects/acme -L 4",
+            label: "ov tree viking://projects/acme --depth 4",
             description: "Show a project tree up to depth 4.",
         }],
         next_steps: &[
@@ -275,6 +490,14 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
                 label: "ov rm viking://resources/images/foo --recursive --wait",
                 description: "Remove a subtree and wait for generated overviews to refresh.",
             },
+            HelpItem {
+                label: "ov rm --glob 'viking://resources/contract/2023-*' --dry-run",
+                description: "Preview every URI a pattern deletion would remove.",
+            },
+            HelpItem {
+                label: "ov rm --glob 'viking://resources/contract/2023-*' -r --yes",
+                description: "Remove matching URIs without a confirmation prompt.",
+            },
         ],
         next_steps: &[
             HelpItem {
@@ -305,12 +528,36 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
             },
         ],
     },
+    CommandHelpSpec {
+        path: &["cp"],
+        purpose: "Copy a resource or subtree to a new URI.",
+        examples: &[
+            HelpItem {
+                label: "ov cp viking://notes/draft.md viking://archive/draft.md",
+                description: "Copy one file resource.",
+            },
+            HelpItem {
+                label: "ov cp viking://resources/contract viking://archive/contract -r --preserve-relations",
+                description: "Copy a subtree and keep its relations pointing at the copies.",
+            },
+        ],
+        next_steps: &[
+            HelpItem {
+                label: "ov tree <to-uri>",
+                description: "Inspect the copied subtree.",
+            },
+            HelpItem {
+                label: "ov relations <to-uri>",
+                description: "Check the copied relations.",
+            },
+        ],
+    },
     CommandHelpSpec {
         path: &["stat"],
-        purpose: "Show metadata for one resource.",
+        purpose: "Show metadata, vectorization status, and child and relation counts for one resource.",
         examples: &[HelpItem {
             label: "ov stat viking://projects/acme/spec.md",
-            description: "Inspect resource metadata.",
+            description: "Check whether a resource is indexed and searchable.",
         }],
         next_steps: &[
             HelpItem {
@@ -347,13 +594,121 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
             },
         ],
     },
+    CommandHelpSpec {
+        path: &["tag"],
+        purpose: "Add, remove, and list explicit k=v retrieval tags, to organize resources across the URI hierarchy.",
+        examples: &[
+            HelpItem {
+                label: "ov tag add viking://projects/acme/spec.md team=search status=draft",
+                description: "Tag a resource; a tag replaces any existing value for its key.",
+            },
+            HelpItem {
+                label: "ov tag rm viking://projects/acme/spec.md status",
+                description: "Remove every value of a key.",
+            },
+            HelpItem {
+                label: "ov ls viking://projects/acme --tag team=search",
+                description: "List only the entries carrying a tag.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov find \"query\" --tag team=search",
+            description: "Search only tagged resources.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["meta"],
+        purpose: "Set, get, and remove free-form key=value metadata, such as the source system, ingest batch, or owner of a resource.",
+        examples: &[
+            HelpItem {
+                label: "ov meta set viking://resources/acme.md source=crm batch=42 owner=ops",
+                description: "Set keys; values that parse as JSON keep their type.",
+            },
+            HelpItem {
+                label: "ov meta get viking://resources/acme.md source",
+                description: "Print the value of one key.",
+            },
+            HelpItem {
+                label: "ov meta rm viking://resources/acme.md batch",
+                description: "Remove a key.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov stat viking://resources/acme.md",
+            description: "See metadata alongside size and index status.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["alias"],
+        purpose: "Save short names for long URIs; any argument written @name or @name/path is replaced with the URI before the command runs.",
+        examples: &[
+            HelpItem {
+                label: "ov alias set contracts viking://resources/contract/2024",
+                description: "Name a directory.",
+            },
+            HelpItem {
+                label: "ov read @contracts/q3.md",
+                description: "Use the name in place of the URI, here with a path under it.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov alias ls",
+            description: "Show saved aliases.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["bookmark"],
+        purpose: "Keep frequently read documents under short names, usable as @name like aliases.",
+        examples: &[
+            HelpItem {
+                label: "ov bookmark add viking://resources/contract/2024/q3-report.md",
+                description: "Bookmark a document as @q3-report.",
+            },
+            HelpItem {
+                label: "ov bookmark add viking://resources/handbook/onboarding.md --name onboard",
+                description: "Choose the name yourself.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov read @q3-report",
+            description: "Read a bookmarked document.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["recent"],
+        purpose: "Show the URIs and commands recorded in the local history, newest first. Set \"history\": false in the config to stop recording.",
+        examples: &[
+            HelpItem {
+                label: "ov recent --uris",
+                description: "URIs used most recently, each once.",
+            },
+            HelpItem {
+                label: "ov recent --commands -n 50",
+                description: "The last 50 command lines.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov recent --clear",
+            description: "Delete the recorded history.",
+        }],
+    },
     CommandHelpSpec {
         path: &["read"],
         purpose: "Read exact Level 2 file content from a Viking URI.",
-        examples: &[HelpItem {
-            label: "ov read viking://projects/acme/spec.md",
-            description: "Print exact file content.",
-        }],
+        examples: &[
+            HelpItem {
+                label: "ov read viking://projects/acme/spec.md",
+                description: "Print exact file content.",
+            },
+            HelpItem {
+                label: "ov read viking://projects/acme/spec.md --render",
+                description: "Render markdown headings, lists, tables, and code blocks.",
+            },
+            HelpItem {
+                label: "ov read <uri> <uri> -o ndjson",
+                description: "Fetch several files concurrently, one record per URI.",
+            },
+        ],
         next_steps: &[
             HelpItem {
                 label: "ov write <uri> --content \"...\"",
@@ -391,12 +746,16 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
     },
     CommandHelpSpec {
         path: &["write"],
-        purpose: "Update text content in an existing resource.",
+        purpose: "Replace text content at a URI, creating the file and its parent directories if missing.",
         examples: &[
             HelpItem {
                 label: "ov write viking://notes/todo.md --content \"Ship config UX\"",
                 description: "Replace a file with inline text.",
             },
+            HelpItem {
+                label: "generate-report | ov write viking://resources/reports/latest.md -",
+                description: "Write content piped on stdin.",
+            },
             HelpItem {
                 label: "ov write viking://notes/todo.md --from-file ./todo.md --wait",
                 description: "Write from disk and wait for processing.",
@@ -413,6 +772,46 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
             },
         ],
     },
+    CommandHelpSpec {
+        path: &["append"],
+        purpose: "Append text content to a file, creating the file and its parent directories if missing, e.g. to keep a run log or decision journal.",
+        examples: &[
+            HelpItem {
+                label: "ov append viking://resources/journal/decisions.md --content \"- Use zstd for packs\"",
+                description: "Add a line to a decision journal.",
+            },
+            HelpItem {
+                label: "./run.sh 2>&1 | ov append viking://resources/logs/nightly.md -",
+                description: "Append a run's output piped on stdin.",
+            },
+            HelpItem {
+                label: "ov append viking://resources/logs/nightly.md ./summary.md",
+                description: "Append the content of a local file.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov read <uri>",
+            description: "Read the document so far.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["edit"],
+        purpose: "Open a file's content in $VISUAL or $EDITOR and write it back on save. The write is refused if the content changed on the server while it was open.",
+        examples: &[
+            HelpItem {
+                label: "ov edit viking://resources/docs/guide.md",
+                description: "Fix a typo without deleting and re-adding the resource.",
+            },
+            HelpItem {
+                label: "EDITOR=\"code --wait\" ov edit viking://notes/todo.md --wait",
+                description: "Edit in VS Code and wait for processing after the write.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov read <uri>",
+            description: "Confirm the updated content.",
+        }],
+    },
     CommandHelpSpec {
         path: &["set-tags"],
         purpose: "Update explicit retrieval tags for a file or directory.",
@@ -459,6 +858,14 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
                 label: "ov find --image ./query.png -u viking://resources/images",
                 description: "Search by image with a local file or image URI.",
             },
+            HelpItem {
+                label: "ov find \"clause 4.2\" --mode hybrid --scores",
+                description: "Blend keyword and vector ranking and show each score.",
+            },
+            HelpItem {
+                label: "ov find \"termination notice\" --snippets",
+                description: "Show the matching passages with query terms highlighted.",
+            },
         ],
         next_steps: &[
             HelpItem {
@@ -474,22 +881,72 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
     CommandHelpSpec {
         path: &["search"],
         purpose: "Run experimental context-aware retrieval, optionally scoped to a session.",
+        examples: &[
+            HelpItem {
+                label: "ov search \"what changed last time?\" --session-id abc123",
+                description: "Search with session context.",
+            },
+            HelpItem {
+                label: "ov search \"my editor preference\" --explain",
+                description: "Show intent analysis, candidate counts and scores per result.",
+            },
+            HelpItem {
+                label: "ov search \"editor preference\" --type memory --created-after 7d",
+                description: "Only search memories extracted in the last week.",
+            },
+            HelpItem {
+                label: "ov search run todo since=7d",
+                description: "Run a saved search, filling its {{since}} placeholder.",
+            },
+        ],
+        next_steps: &[
+            HelpItem {
+                label: "ov session get-session-context <id>",
+                description: "Inspect the session context directly.",
+            },
+            HelpItem {
+                label: "ov search list",
+                description: "Show saved searches and their placeholders.",
+            },
+        ],
+    },
+    CommandHelpSpec {
+        path: &["search", "save"],
+        purpose: "Save a search query and its flags under a name, stored in the config directory.",
         examples: &[HelpItem {
-            label: "ov search \"what changed last time?\" --session-id abc123",
-            description: "Search with session context.",
+            label: "ov search save todo \"unresolved action items\" --filters \"--after {{since}} -n 20\"",
+            description: "Save a query whose time window is filled in when it runs.",
         }],
         next_steps: &[HelpItem {
-            label: "ov session get-session-context <id>",
-            description: "Inspect the session context directly.",
+            label: "ov search run todo since=7d",
+            description: "Run the saved search.",
         }],
     },
     CommandHelpSpec {
-        path: &["grep"],
-        purpose: "Search resource content with a text pattern.",
+        path: &["search", "run"],
+        purpose: "Run a saved search, filling its {{name}} placeholders from name=value arguments.",
         examples: &[HelpItem {
-            label: "ov grep \"TODO\" -u viking://projects/acme -i",
-            description: "Find case-insensitive matches in a subtree.",
+            label: "ov search run todo since=7d -o json",
+            description: "Run a saved search with JSON output.",
+        }],
+        next_steps: &[HelpItem {
+            label: "ov search list",
+            description: "Show saved searches and their placeholders.",
         }],
+    },
+    CommandHelpSpec {
+        path: &["grep"],
+        purpose: "Search resource content with a text pattern.",
+        examples: &[
+            HelpItem {
+                label: "ov grep \"TODO\" -u viking://projects/acme -i",
+                description: "Find case-insensitive matches in a subtree.",
+            },
+            HelpItem {
+                label: "ov grep -F \"a.b(c)\" viking://projects/acme",
+                description: "Match a literal string; piped output is uri:line:match.",
+            },
+        ],
         next_steps: &[HelpItem {
             label: "ov read <uri>",
             description: "Open a matching resource.",
@@ -519,6 +976,26 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
                 label: "ov session add-message <id> --role user --content \"...\"",
                 description: "Append a message.",
             },
+            HelpItem {
+                label: "ov session extract <id>",
+                description: "Extract memories now and list what was written.",
+            },
+            HelpItem {
+                label: "ov session export <id> --format openai > chat.json",
+                description: "Export the full message history.",
+            },
+            HelpItem {
+                label: "ov session import chat.json",
+                description: "Load a transcript into a new session.",
+            },
+            HelpItem {
+                label: "ov session tail <id>",
+                description: "Follow messages as they are appended.",
+            },
+            HelpItem {
+                label: "ov session chat <id>",
+                description: "Chat with the bot and record each turn in the session.",
+            },
         ],
         next_steps: &[HelpItem {
             label: "ov session <subcommand> --help",
@@ -526,7 +1003,25 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
         }],
     },
     CommandHelpSpec {
-        path: &["snapshot"],
+        path: &["memory"],
+        purpose: "Inspect long-term memories extracted from sessions.",
+        examples: &[
+            HelpItem {
+                label: "ov memory ls preferences",
+                description: "List preference memories with their abstracts.",
+            },
+            HelpItem {
+                label: "ov memory show preferences/editor.md",
+                description: "Show one memory, relative to viking://user/memories.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov session extract <id>",
+            description: "Extract new memories from a session.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["snapshot"],
         purpose: "Manage workspace snapshots: commit, restore, show, diff, and walk history.",
         examples: &[
             HelpItem {
@@ -749,6 +1244,76 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
             },
         ],
     },
+    CommandHelpSpec {
+        path: &["relations", "graph"],
+        purpose: "Walk outgoing relation links from a URI and print the graph as DOT, Mermaid, or GraphML. Experimental.",
+        examples: &[
+            HelpItem {
+                label: "ov relations graph viking://projects/acme/spec.md --depth 3 | dot -Tsvg > graph.svg",
+                description: "Render the graph with Graphviz.",
+            },
+            HelpItem {
+                label: "ov relations graph viking://projects/acme/spec.md --format mermaid",
+                description: "Paste into a Markdown Mermaid block.",
+            },
+            HelpItem {
+                label: "ov relations graph viking://projects/acme --format graphml > graph.graphml",
+                description: "Open in Gephi, yEd, or Cytoscape.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov relations <uri>",
+            description: "List the direct links of one node.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["relations", "neighbors"],
+        purpose: "List everything linked from a URI within N hops, nearest first. Experimental.",
+        examples: &[
+            HelpItem {
+                label: "ov relations neighbors viking://resources/contracts/acme.md --depth 2",
+                description: "Show everything connected to a contract.",
+            },
+            HelpItem {
+                label: "ov relations neighbors viking://resources/contracts/acme.md --reason-filter '^amends'",
+                description: "Only follow links whose reason matches.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov relations graph <uri>",
+            description: "Render the same neighborhood as a graph.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["relations", "path"],
+        purpose: "Show the shortest chain of relation links from one URI to another. Experimental.",
+        examples: &[HelpItem {
+            label: "ov relations path viking://resources/contracts/acme.md viking://user/memories/acme.md",
+            description: "Explain how two resources are connected.",
+        }],
+        next_steps: &[HelpItem {
+            label: "ov relations neighbors <uri>",
+            description: "List everything linked from one end.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["relations", "import"],
+        purpose: "Create many relation links from a CSV or JSONL file, in batched requests, reporting every row that fails. Experimental.",
+        examples: &[
+            HelpItem {
+                label: "ov relations import edges.csv --dry-run",
+                description: "Check a CSV with from,to,reason columns without linking.",
+            },
+            HelpItem {
+                label: "ov relations import edges.jsonl",
+                description: "Link one {\"from\",\"to\",\"reason\"} object per line.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov relations graph <uri>",
+            description: "See the imported links around a resource.",
+        }],
+    },
     CommandHelpSpec {
         path: &["link"],
         purpose: "Create one or more relation links between resources. Experimental.",
@@ -776,10 +1341,16 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
     CommandHelpSpec {
         path: &["export"],
         purpose: "Export context from a URI as an .ovpack file.",
-        examples: &[HelpItem {
-            label: "ov export viking://projects/acme ./acme.ovpack",
-            description: "Export a project subtree.",
-        }],
+        examples: &[
+            HelpItem {
+                label: "ov export viking://projects/acme ./acme.ovpack",
+                description: "Export a project subtree.",
+            },
+            HelpItem {
+                label: "ov export viking://projects/acme ./acme.ovpack --recipient age1...",
+                description: "Encrypt the pack for an age public key.",
+            },
+        ],
         next_steps: &[HelpItem {
             label: "ov import ./file.ovpack <target-uri>",
             description: "Import the exported pack elsewhere.",
@@ -787,23 +1358,97 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
     },
     CommandHelpSpec {
         path: &["backup"],
-        purpose: "Create a restore-only backup .ovpack for public OpenViking scopes.",
-        examples: &[HelpItem {
-            label: "ov backup ./openviking-backup.ovpack --include-vectors",
-            description: "Create a backup with vectors when compatible.",
+        purpose: "Back up or restore all resources, memories, sessions, and relations in one file.",
+        examples: &[
+            HelpItem {
+                label: "ov backup create ./openviking-backup.ovpack",
+                description: "Back up every public scope, relations included.",
+            },
+            HelpItem {
+                label: "ov backup restore ./openviking-backup.ovpack --dry-run",
+                description: "Preview which scopes a restore would create or replace.",
+            },
+            HelpItem {
+                label: "ov backup restore ./openviking-backup.ovpack --overwrite",
+                description: "Restore over existing scopes, e.g. after data loss.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov backup <subcommand> --help",
+            description: "Show exact arguments for a backup operation.",
         }],
+    },
+    CommandHelpSpec {
+        path: &["backup", "create"],
+        purpose: "Create a restore-only backup .ovpack of every public OpenViking scope.",
+        examples: &[
+            HelpItem {
+                label: "ov backup create ./openviking-backup.ovpack --include-vectors",
+                description: "Create a backup with vectors when compatible.",
+            },
+            HelpItem {
+                label: "ov backup create ./openviking-backup.ovpack --compression zstd --level 19",
+                description: "Write a smaller backup compressed with zstd.",
+            },
+        ],
         next_steps: &[HelpItem {
-            label: "ov restore ./openviking-backup.ovpack",
-            description: "Restore this backup later.",
+            label: "ov backup restore ./openviking-backup.ovpack --dry-run",
+            description: "Check what restoring this backup would do.",
         }],
     },
+    CommandHelpSpec {
+        path: &["backup", "restore"],
+        purpose: "Restore a backup .ovpack to its original scope roots.",
+        examples: &[
+            HelpItem {
+                label: "ov backup restore ./openviking-backup.ovpack --dry-run",
+                description: "List each scope and whether it would be created or replaced.",
+            },
+            HelpItem {
+                label: "ov backup restore ./openviking-backup.ovpack --overwrite",
+                description: "Replace scopes that already exist.",
+            },
+        ],
+        next_steps: &[
+            HelpItem {
+                label: "ov status",
+                description: "Check service health after restore.",
+            },
+            HelpItem {
+                label: "ov tree viking://",
+                description: "Inspect restored resources.",
+            },
+        ],
+    },
     CommandHelpSpec {
         path: &["import"],
         purpose: "Import an .ovpack into a target URI.",
-        examples: &[HelpItem {
-            label: "ov import ./acme.ovpack viking://imports/acme --on-conflict skip",
-            description: "Import while keeping existing resources.",
-        }],
+        examples: &[
+            HelpItem {
+                label: "ov import ./acme.ovpack viking://imports/acme --on-conflict skip",
+                description: "Import while keeping existing resources.",
+            },
+            HelpItem {
+                label: "ov import ./acme.ovpack viking://resources --trusted-key team.pub",
+                description: "Import only if the pack was signed with the team key.",
+            },
+            HelpItem {
+                label: "ov import ./acme.ovpack viking://resources --identity key.txt",
+                description: "Decrypt an encrypted pack with an age identity file.",
+            },
+            HelpItem {
+                label: "ov import mcp --server \"npx -y lark-docs-mcp\"",
+                description: "List the tools of an MCP server to import documents from.",
+            },
+            HelpItem {
+                label: "ov import notion ./Export-acme.zip",
+                description: "Import a Notion export with its page tree and links.",
+            },
+            HelpItem {
+                label: "ov import mbox ./dev-list.mbox",
+                description: "Import a mailing list archive as one resource per thread.",
+            },
+        ],
         next_steps: &[
             HelpItem {
                 label: "ov tree <target-uri>",
@@ -815,13 +1460,81 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
             },
         ],
     },
+    CommandHelpSpec {
+        path: &["import", "mcp"],
+        purpose: "Import documents from an external MCP server, such as a Feishu/Lark docs server, as resources.",
+        examples: &[
+            HelpItem {
+                label: "ov import mcp --server \"npx -y lark-docs-mcp\"",
+                description: "List the server's tools to find the list and fetch tools.",
+            },
+            HelpItem {
+                label: "ov import mcp --server \"npx -y lark-docs-mcp\" --tool list_docs --tool get_doc --args '{\"folder\":\"wiki\"}' --dry-run",
+                description: "Show which documents a folder listing would import.",
+            },
+            HelpItem {
+                label: "ov import mcp --server https://mcp.example.com/mcp --tool list_docs --tool get_doc --id-field token --parent viking://resources/lark",
+                description: "Fetch each listed document by token over HTTP and import it.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov tree viking://resources/mcp",
+            description: "Inspect the imported documents.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["import", "notion"],
+        purpose: "Import a Notion Markdown & CSV export: pages keep their hierarchy as the URI tree, databases become Markdown tables, and links between pages become viking:// links and relations.",
+        examples: &[
+            HelpItem {
+                label: "ov import notion ./Export-acme.zip --dry-run",
+                description: "List where each page, database, and attachment would go.",
+            },
+            HelpItem {
+                label: "ov import notion ./Export-acme.zip --parent viking://resources/wiki",
+                description: "Import the workspace export under a wiki folder.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov relations viking://resources/notion/<page>.md",
+            description: "See the pages a page links to.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["import", "mbox"],
+        purpose: "Import an mbox file or .eml messages as one Markdown resource per thread, with quoted replies and signatures stripped, participants and dates as metadata, and relations from each split-off thread to the thread it answers.",
+        examples: &[
+            HelpItem {
+                label: "ov import mbox ./dev-list.mbox --dry-run",
+                description: "List the threads and where each would go.",
+            },
+            HelpItem {
+                label: "ov import mbox ./support/ --parent viking://resources/support",
+                description: "Import a directory of .eml messages under a support folder.",
+            },
+            HelpItem {
+                label: "ov import mbox ./support.mbox --redact",
+                description: "Mask contact details and ID numbers in every thread before it is sent.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov meta get viking://resources/mail/<thread>.md participants",
+            description: "See who took part in a thread.",
+        }],
+    },
     CommandHelpSpec {
         path: &["restore"],
         purpose: "Restore a backup .ovpack to its original public scope roots.",
-        examples: &[HelpItem {
-            label: "ov restore ./openviking-backup.ovpack --on-conflict fail",
-            description: "Restore only if there are no conflicts.",
-        }],
+        examples: &[
+            HelpItem {
+                label: "ov restore ./openviking-backup.ovpack --on-conflict fail",
+                description: "Restore only if there are no conflicts.",
+            },
+            HelpItem {
+                label: "ov restore ./openviking-backup.ovpack --dry-run",
+                description: "Preview the restore without changing anything.",
+            },
+        ],
         next_steps: &[
             HelpItem {
                 label: "ov status",
@@ -833,6 +1546,228 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
             },
         ],
     },
+    CommandHelpSpec {
+        path: &["sync"],
+        purpose: "Copy new and changed entries under a prefix from one saved config's server to another's.",
+        examples: &[
+            HelpItem {
+                label: "ov sync --from staging --to prod --prefix viking://resources/team --dry-run",
+                description: "List what would be copied to production.",
+            },
+            HelpItem {
+                label: "ov sync --from staging --to prod --prefix viking://resources/team",
+                description: "Promote the changes; entries only production holds are kept.",
+            },
+        ],
+        next_steps: &[
+            HelpItem {
+                label: "ov config list",
+                description: "See the saved configs --from and --to can name.",
+            },
+            HelpItem {
+                label: "ov pack diff <a.ovpack> <b.ovpack>",
+                description: "Compare two exported packs in detail.",
+            },
+        ],
+    },
+    CommandHelpSpec {
+        path: &["watch"],
+        purpose: "Mirror a local directory to a server directory and keep it in sync as files change.",
+        examples: &[
+            HelpItem {
+                label: "ov watch ~/Obsidian/Vault --target viking://resources/notes",
+                description: "Upload edits and remove deleted notes until Ctrl-C.",
+            },
+            HelpItem {
+                label: "ov watch ~/Obsidian/Vault --target viking://resources/notes --once",
+                description: "Catch up once, e.g. from cron, and exit.",
+            },
+        ],
+        next_steps: &[
+            HelpItem {
+                label: "ov tree viking://resources/notes",
+                description: "Browse the mirrored files.",
+            },
+            HelpItem {
+                label: "ov find \"meeting notes\" --uri viking://resources/notes",
+                description: "Search the mirrored notes.",
+            },
+        ],
+    },
+    CommandHelpSpec {
+        path: &["run"],
+        purpose: "Run a script of ov commands, one per line, in one process that reuses a single connection to the server. `set name value` defines $name for later lines, and `onerror continue` keeps going past failures.",
+        examples: &[
+            HelpItem {
+                label: "ov run seed.ov",
+                description: "Run each command in order, stopping at the first failure.",
+            },
+            HelpItem {
+                label: "ov run seed.ov --var env=staging",
+                description: "Override the script's `set env ...` line.",
+            },
+            HelpItem {
+                label: "ov run seed.ov --dry-run",
+                description: "Print the commands with their variables filled in.",
+            },
+        ],
+        next_steps: &[
+            HelpItem {
+                label: "ov jobs ls",
+                description: "Follow the background work the script started.",
+            },
+            HelpItem {
+                label: "ov schedule add nightly --cron @daily -- run seed.ov",
+                description: "Run the script every day from the daemon.",
+            },
+        ],
+    },
+    CommandHelpSpec {
+        path: &["queue"],
+        purpose: "Replay writes queued with --queue-offline, and check the parse and vectorization work the server still has to do.",
+        examples: &[
+            HelpItem {
+                label: "ov add-resource ./notes.md --queue-offline",
+                description: "Import now, or queue the import if the server cannot be reached.",
+            },
+            HelpItem {
+                label: "ov queue list",
+                description: "Show queued commands, oldest first.",
+            },
+            HelpItem {
+                label: "ov queue flush",
+                description: "Replay them in order once the server is back.",
+            },
+            HelpItem {
+                label: "ov queue status",
+                description: "Show pending parse and vectorization work with per-URI state.",
+            },
+            HelpItem {
+                label: "ov queue wait viking://resources/docs --timeout 300",
+                description: "Block until new content under a URI is searchable.",
+            },
+        ],
+        next_steps: &[
+            HelpItem {
+                label: "ov queue drop <id>",
+                description: "Remove a queued command that keeps failing.",
+            },
+            HelpItem {
+                label: "ov observer queue",
+                description: "See the server's own queue table.",
+            },
+        ],
+    },
+    CommandHelpSpec {
+        path: &["pack"],
+        purpose: "Work with .ovpack archives locally, without a server.",
+        examples: &[
+            HelpItem {
+                label: "ov pack inspect ./acme.ovpack",
+                description: "List the archive's URIs, sizes, and abstracts.",
+            },
+            HelpItem {
+                label: "ov pack create ./docs ./acme.ovpack",
+                description: "Build a pack from a local directory, e.g. in CI.",
+            },
+            HelpItem {
+                label: "ov pack sign ./acme.ovpack --key team.pem",
+                description: "Sign the pack so imports can check who built it.",
+            },
+            HelpItem {
+                label: "ov pack diff ./kb-2026-09.ovpack ./kb-2026-10.ovpack",
+                description: "See which URIs changed between two exports.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov import ./acme.ovpack <target-uri>",
+            description: "Import the pack once it looks right.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["pack", "create"],
+        purpose: "Build an importable .ovpack from a local directory without a server.",
+        examples: &[
+            HelpItem {
+                label: "ov pack create ./docs ./acme.ovpack",
+                description: "Pack ./docs as viking://resources/docs.",
+            },
+            HelpItem {
+                label: "ov pack create ./docs ./dist --root-uri viking://resources/acme",
+                description: "Name the pack acme and write dist/acme.ovpack.",
+            },
+        ],
+        next_steps: &[
+            HelpItem {
+                label: "ov pack inspect ./acme.ovpack",
+                description: "Check what the pack contains.",
+            },
+            HelpItem {
+                label: "ov import ./acme.ovpack viking://resources",
+                description: "Import it; abstracts and vectors are built on import.",
+            },
+        ],
+    },
+    CommandHelpSpec {
+        path: &["pack", "sign"],
+        purpose: "Sign an .ovpack in place with an Ed25519 key so imports can verify it.",
+        examples: &[
+            HelpItem {
+                label: "openssl genpkey -algorithm ed25519 -out team.pem",
+                description: "Create a signing key once.",
+            },
+            HelpItem {
+                label: "ov pack sign ./acme.ovpack --key team.pem",
+                description: "Sign the pack; re-signing replaces the old signature.",
+            },
+        ],
+        next_steps: &[
+            HelpItem {
+                label: "openssl pkey -in team.pem -pubout -out team.pub",
+                description: "Export the public key for the importing side.",
+            },
+            HelpItem {
+                label: "ov import ./acme.ovpack viking://resources --trusted-key team.pub",
+                description: "Import only packs signed with that key.",
+            },
+        ],
+    },
+    CommandHelpSpec {
+        path: &["pack", "inspect"],
+        purpose: "List an .ovpack's manifest, URIs, sizes, and abstracts without a server.",
+        examples: &[
+            HelpItem {
+                label: "ov pack inspect ./acme.ovpack",
+                description: "Summarize the pack and list its entries.",
+            },
+            HelpItem {
+                label: "ov pack inspect ./acme.ovpack -o json --query '.entries[].uri'",
+                description: "Print only the URIs the pack would import.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov import ./acme.ovpack <target-uri>",
+            description: "Import the pack.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["pack", "diff"],
+        purpose: "Compare two .ovpack files, or a pack and a live subtree, by content hash.",
+        examples: &[
+            HelpItem {
+                label: "ov pack diff ./kb-2026-09.ovpack ./kb-2026-10.ovpack",
+                description: "List URIs added, removed, or modified between two exports.",
+            },
+            HelpItem {
+                label: "ov pack diff ./kb-2026-09.ovpack viking://resources/kb",
+                description: "Compare a pack with the live subtree (exports it first).",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov read <uri>",
+            description: "Read a modified resource.",
+        }],
+    },
     CommandHelpSpec {
         path: &["tui"],
         purpose: "Open the interactive file explorer.",
@@ -845,6 +1780,42 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
             description: "Use a non-interactive tree view instead.",
         }],
     },
+    CommandHelpSpec {
+        path: &["pick"],
+        purpose: "Filter the URIs under a prefix as you type and print the one you choose, for use in other commands. Enter picks, Esc cancels.",
+        examples: &[
+            HelpItem {
+                label: "ov read $(ov pick)",
+                description: "Choose a file anywhere and read it.",
+            },
+            HelpItem {
+                label: "ov pick viking://resources/docs guide",
+                description: "Start from a subtree with the filter already typed.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov tui <uri>",
+            description: "Browse the tree with previews instead.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["top"],
+        purpose: "Watch the server work through a load: queue depth, ingestion throughput, index lag, search latency, and recent errors, refreshed every few seconds. q quits, r refreshes now.",
+        examples: &[
+            HelpItem {
+                label: "ov top",
+                description: "Watch every queue, refreshing every 2 seconds.",
+            },
+            HelpItem {
+                label: "ov top --uri viking://resources/wiki --interval 5",
+                description: "Follow one import's subtree.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov queue wait <uri>",
+            description: "Block until a subtree finishes processing.",
+        }],
+    },
     CommandHelpSpec {
         path: &["chat"],
         purpose: "Chat with the vikingbot agent.",
@@ -983,6 +1954,34 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
             },
         ],
     },
+    CommandHelpSpec {
+        path: &["doctor"],
+        purpose: "Check the server end to end and print a PASS/WARN/FAIL line, with a fix, for each check.",
+        examples: &[
+            HelpItem {
+                label: "ov doctor",
+                description: "Check reachability, versions, auth, embedding, index, and queues.",
+            },
+            HelpItem {
+                label: "ov doctor --sample 10",
+                description: "Compare more resource directories against the vector index.",
+            },
+            HelpItem {
+                label: "ov -o json doctor",
+                description: "Get the checks as JSON for scripts and bug reports.",
+            },
+        ],
+        next_steps: &[
+            HelpItem {
+                label: "ov queue status",
+                description: "See which URIs are still pending or failed.",
+            },
+            HelpItem {
+                label: "ov system consistency <uri>",
+                description: "Check one directory against the vector index.",
+            },
+        ],
+    },
     CommandHelpSpec {
         path: &["config"],
         purpose: "Add, edit, delete, show, validate, or switch OpenViking CLI configs.",
@@ -1015,6 +2014,28 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
             },
         ],
     },
+    CommandHelpSpec {
+        path: &["workspace"],
+        purpose: "List workspaces (server accounts) and choose the one the active config scopes URIs and API calls to; --workspace overrides it for one command.",
+        examples: &[
+            HelpItem {
+                label: "ov workspace list",
+                description: "List workspaces; the server's accounts when a root key is configured.",
+            },
+            HelpItem {
+                label: "ov workspace use acme",
+                description: "Make acme the default of the active config.",
+            },
+            HelpItem {
+                label: "ov ls viking://resources --workspace globex",
+                description: "Run one command in another workspace.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov config switch <name>",
+            description: "Switch to another saved config and its workspace.",
+        }],
+    },
     CommandHelpSpec {
         path: &["config", "show"],
         purpose: "Print the active CLI config with secrets redacted.",
@@ -1214,84 +2235,308 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
         ],
         next_steps: &[
             HelpItem {
-                label: "ov config list",
-                description: "Inspect remaining configs.",
+                label: "ov config list",
+                description: "Inspect remaining configs.",
+            },
+            HelpItem {
+                label: "ov config switch <name>",
+                description: "Switch away from an active config before deleting it.",
+            },
+        ],
+    },
+    CommandHelpSpec {
+        path: &["language"],
+        purpose: "Choose the OpenViking CLI display language.",
+        examples: &[
+            HelpItem {
+                label: "ov language",
+                description: "Open the language selector.",
+            },
+            HelpItem {
+                label: "ov language zh-CN",
+                description: "Switch display text to Simplified Chinese.",
+            },
+            HelpItem {
+                label: "ov lang en",
+                description: "Use the short alias to switch display text to English.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov config",
+            description: "Open the config manager.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["mcp"],
+        purpose: "Serve the configured OpenViking server to MCP hosts such as Claude Desktop and Cursor.",
+        examples: &[
+            HelpItem {
+                label: "ov mcp serve",
+                description: "Speak MCP on stdin/stdout; use this as the host's server command.",
+            },
+            HelpItem {
+                label: "ov mcp serve --transport sse --port 8765",
+                description: "Listen on http://127.0.0.1:8765/sse for hosts that connect over HTTP.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov mcp serve --help",
+            description: "Show transport and listen options.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["mcp", "serve"],
+        purpose: "Expose find, search, read, abstract, add_resource, and session tools over the Model Context Protocol.",
+        examples: &[
+            HelpItem {
+                label: "ov mcp serve",
+                description: "Serve over stdio, as MCP hosts that launch ov expect.",
+            },
+            HelpItem {
+                label: "ov mcp serve --transport sse --user alice",
+                description: "Serve over HTTP and SSE, calling the server as user alice.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov doctor",
+            description: "Check the server the tools will call.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["mock-server"],
+        purpose: "Serve an in-memory OpenViking API for developing and testing clients offline: the filesystem, content, add-resource uploads, find/search/grep/glob, and sessions. Other endpoints answer 501 NOT_IMPLEMENTED, and nothing is kept after exit.",
+        examples: &[
+            HelpItem {
+                label: "ov mock-server",
+                description: "Serve empty scopes on http://127.0.0.1:1933.",
+            },
+            HelpItem {
+                label: "ov mock-server --fixtures tests/fixtures --port 0",
+                description: "Serve tests/fixtures/resources/a.md as viking://resources/a.md on a free port, with canned results from tests/fixtures/search.json.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov config",
+            description: "Point a config at the mock server's URL.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["daemon"],
+        purpose: "Run a background process that keeps connections to the server warm and caches abstracts, overviews, listings, and search results; commands use it automatically.",
+        examples: &[
+            HelpItem {
+                label: "ov daemon start",
+                description: "Start the daemon for the current config's server.",
+            },
+            HelpItem {
+                label: "ov daemon start --ttl 300",
+                description: "Reuse cached results for five minutes instead of one.",
+            },
+            HelpItem {
+                label: "ov daemon start --metrics 9464",
+                description: "Also serve Prometheus metrics on localhost:9464/metrics.",
+            },
+            HelpItem {
+                label: "OPENVIKING_NO_DAEMON=1 ov ls viking://resources",
+                description: "Bypass a running daemon for one command.",
+            },
+        ],
+        next_steps: &[
+            HelpItem {
+                label: "ov daemon status",
+                description: "Show cache hits, misses, and entries.",
+            },
+            HelpItem {
+                label: "ov daemon stop",
+                description: "Stop the daemon and drop its cache.",
+            },
+        ],
+    },
+    CommandHelpSpec {
+        path: &["schedule"],
+        purpose: "Run ov commands on a cron schedule in local time, such as a nightly sync or a weekly backup. Schedules are kept in ~/.openviking/schedules.json and run by the current server's daemon; ls shows each one's next run and how its last run went.",
+        examples: &[
+            HelpItem {
+                label: "ov schedule add nightly-sync --cron \"0 2 * * *\" -- sync --from prod --to staging --prefix viking://resources/docs",
+                description: "Copy new and changed docs to staging every night at 02:00.",
+            },
+            HelpItem {
+                label: "ov schedule add weekly-backup --cron @weekly -- backup create /backups/weekly.ovpack",
+                description: "Back up the server every Sunday at midnight.",
+            },
+            HelpItem {
+                label: "ov schedule ls",
+                description: "Show next runs, last runs, and their exit status.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov daemon start",
+            description: "Start the daemon that runs the schedules.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["version"],
+        purpose: "Print the OpenViking CLI version, and with --remote the server's version and supported API features.",
+        examples: &[
+            HelpItem {
+                label: "ov version",
+                description: "Show the installed CLI version.",
+            },
+            HelpItem {
+                label: "ov version --remote",
+                description: "Compare with the server and list which features it supports.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov --help",
+            description: "See all commands.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["completions"],
+        purpose: "Print a shell completion script that also completes saved config names and recently read viking:// URIs.",
+        examples: &[
+            HelpItem {
+                label: "source <(ov completions bash)",
+                description: "Enable completion in the current bash session; add it to ~/.bashrc to keep it.",
+            },
+            HelpItem {
+                label: "ov completions fish | source",
+                description: "Enable completion in fish.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov daemon start",
+            description: "Keep a daemon running so viking:// URIs complete from recent reads.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["admin"],
+        purpose: "Manage accounts, users, roles, and API keys. Admin/root access required.",
+        examples: &[
+            HelpItem {
+                label: "ov admin list-accounts --sudo",
+                description: "List accounts with the root API key.",
+            },
+            HelpItem {
+                label: "ov admin register-user <account> <user>",
+                description: "Register a user in an account.",
+            },
+            HelpItem {
+                label: "ov admin user add alice --workspace acme",
+                description: "Add a user to the selected workspace.",
+            },
+            HelpItem {
+                label: "ov admin regenerate-key <account> <user> --seed <seed>",
+                description: "Regenerate a predictable API key from a seed.",
+            },
+            HelpItem {
+                label: "ov admin migrate --sudo",
+                description: "Start legacy agent/session migration.",
+            },
+            HelpItem {
+                label: "ov admin migrate --cleanup --sudo",
+                description: "Remove legacy agent/session directories after verifying migration.",
+            },
+        ],
+        next_steps: &[
+            HelpItem {
+                label: "ov config show",
+                description: "Check whether root_api_key is configured.",
             },
             HelpItem {
-                label: "ov config switch <name>",
-                description: "Switch away from an active config before deleting it.",
+                label: "ov admin <subcommand> --help",
+                description: "Show exact arguments for an admin operation.",
             },
         ],
     },
     CommandHelpSpec {
-        path: &["language"],
-        purpose: "Choose the OpenViking CLI display language.",
+        path: &["stats"],
+        purpose: "Count stored files, directories, bytes, and vectors under a URI, by file type and optionally by child prefix, with the workspace's recent token usage.",
         examples: &[
             HelpItem {
-                label: "ov language",
-                description: "Open the language selector.",
+                label: "ov stats",
+                description: "Summarize viking://resources.",
             },
             HelpItem {
-                label: "ov language zh-CN",
-                description: "Switch display text to Simplified Chinese.",
+                label: "ov stats viking://resources/docs --by-prefix",
+                description: "Break the totals down by each child of docs.",
             },
             HelpItem {
-                label: "ov lang en",
-                description: "Use the short alias to switch display text to English.",
+                label: "ov stats --days 7 -o json",
+                description: "Emit the report as JSON for a dashboard.",
             },
         ],
         next_steps: &[HelpItem {
-            label: "ov config",
-            description: "Open the config manager.",
+            label: "ov index verify --prefix <uri>",
+            description: "Check that the vector count matches the stored content.",
         }],
     },
     CommandHelpSpec {
-        path: &["version"],
-        purpose: "Print the OpenViking CLI version.",
-        examples: &[HelpItem {
-            label: "ov version",
-            description: "Show the installed CLI version.",
-        }],
+        path: &["jobs"],
+        purpose: "Follow the server's long-running jobs (imports, skills, reindexing, session commits): list them, show progress and collected errors, or wait for them. add-resource --wait, add-skill --wait, and index rebuild wait through jobs too.",
+        examples: &[
+            HelpItem {
+                label: "ov jobs ls --status running",
+                description: "List the jobs still in progress.",
+            },
+            HelpItem {
+                label: "ov jobs show <id>",
+                description: "Show a job's stage, progress, result, and errors.",
+            },
+            HelpItem {
+                label: "ov jobs wait <id> <id> --timeout 600",
+                description: "Block until both jobs finish; exit non-zero if one failed.",
+            },
+        ],
         next_steps: &[HelpItem {
-            label: "ov --help",
-            description: "See all commands.",
+            label: "ov events subscribe --filter index.completed",
+            description: "Get notified as jobs finish instead of waiting.",
         }],
     },
     CommandHelpSpec {
-        path: &["admin"],
-        purpose: "Manage accounts, users, roles, and API keys. Admin/root access required.",
+        path: &["events"],
+        purpose: "Follow background events: resources added, indexing finished or failed, sessions committed. Each event prints as a JSON line or runs --exec; events come from polling the server's task list.",
         examples: &[
             HelpItem {
-                label: "ov admin list-accounts --sudo",
-                description: "List accounts with the root API key.",
-            },
-            HelpItem {
-                label: "ov admin register-user <account> <user>",
-                description: "Register a user in an account.",
+                label: "ov events subscribe",
+                description: "Print every event as a JSON line.",
             },
             HelpItem {
-                label: "ov admin regenerate-key <account> <user> --seed <seed>",
-                description: "Regenerate a predictable API key from a seed.",
+                label: "ov events subscribe --filter index.completed,index.failed --uri viking://resources/batch-7",
+                description: "Watch one batch finish indexing.",
             },
             HelpItem {
-                label: "ov admin migrate --sudo",
-                description: "Start legacy agent/session migration.",
+                label: "ov events subscribe --filter 'index.*' --exec 'notify-send \"$OV_EVENT_TYPE\" \"$OV_EVENT_RESOURCE\"'",
+                description: "Run a command for each event; the event is also on its stdin.",
             },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov task list",
+            description: "List the tasks events come from.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["audit"],
+        purpose: "Page or follow the server's request audit log: who called which route, when, and with what status. ADMIN keys see the whole workspace; USER keys see their own requests.",
+        examples: &[
             HelpItem {
-                label: "ov admin migrate --cleanup --sudo",
-                description: "Remove legacy agent/session directories after verifying migration.",
+                label: "ov audit tail --action delete --since 7d",
+                description: "List deletions from the last week.",
             },
-        ],
-        next_steps: &[
             HelpItem {
-                label: "ov config show",
-                description: "Check whether root_api_key is configured.",
+                label: "ov audit tail --user bob -n 50",
+                description: "Show bob's 50 most recent requests.",
             },
             HelpItem {
-                label: "ov admin <subcommand> --help",
-                description: "Show exact arguments for an admin operation.",
+                label: "ov audit tail -f -o json",
+                description: "Stream new events as JSON lines.",
             },
         ],
+        next_steps: &[HelpItem {
+            label: "ov admin user ls",
+            description: "List the users in the workspace.",
+        }],
     },
     CommandHelpSpec {
         path: &["system"],
@@ -1361,6 +2606,243 @@ const COMMAND_HELP_SPECS: &[CommandHelpSpec] = &[
             },
         ],
     },
+    CommandHelpSpec {
+        path: &["context"],
+        purpose: "Retrieve the best matches for a query and pack their abstracts, overviews, and contents into one block within a token budget.",
+        examples: &[
+            HelpItem {
+                label: "ov context assemble --query \"how does auth work\" --budget 8000",
+                description: "Print a context block ready to paste into a prompt.",
+            },
+            HelpItem {
+                label: "ov context assemble --query \"release steps\" --levels l0,l1 -u viking://resources/docs",
+                description: "Use only abstracts and overviews from one subtree.",
+            },
+            HelpItem {
+                label: "ov context assemble --query \"deploy\" --messages",
+                description: "Print an OpenAI messages array with the context as the system message.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov find \"<query>\"",
+            description: "See the ranked matches the block is built from.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["ask"],
+        purpose: "Answer a question from retrieved context with the LLM in the config's \"llm\" section, then list the sources the answer cites.",
+        examples: &[
+            HelpItem {
+                label: "ov ask \"how are API keys rotated?\"",
+                description: "Stream an answer grounded in everything you can read.",
+            },
+            HelpItem {
+                label: "ov ask \"what changed in v2?\" --target viking://resources/docs",
+                description: "Only retrieve from one subtree.",
+            },
+            HelpItem {
+                label: "ov ask \"and for admins?\" --session <id>",
+                description: "Follow up in a session; the exchange is added to it.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov context assemble \"<question>\"",
+            description: "See the context the answer is built from.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["tokens"],
+        purpose: "Count the tokens content will cost an LLM, with the cl100k or o200k encoding or the server's estimate.",
+        examples: &[
+            HelpItem {
+                label: "ov tokens count viking://resources/docs/guide.md",
+                description: "Count a stored file with the configured tokenizer.",
+            },
+            HelpItem {
+                label: "cat prompt.txt | ov tokens count - --tokenizer o200k",
+                description: "Count standard input with the o200k encoding.",
+            },
+            HelpItem {
+                label: "ov ls viking://resources/docs --tokens",
+                description: "Show what each file and directory overview would cost.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov context assemble \"<query>\" --budget <tokens>",
+            description: "Pack the best matches into a token budget.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["index"],
+        purpose: "Cross-check stored content against the vector index, re-enqueue missing records, or re-embed a subtree.",
+        examples: &[
+            HelpItem {
+                label: "ov index verify --prefix viking://user/memories",
+                description: "Report missing and orphaned vectors under a prefix.",
+            },
+            HelpItem {
+                label: "ov index repair --prefix viking://user/memories",
+                description: "Re-enqueue the missing records for vectorization.",
+            },
+            HelpItem {
+                label: "ov --sudo index repair --prefix viking://resources --prune-orphans",
+                description: "Also delete vectors whose content is gone.",
+            },
+            HelpItem {
+                label: "ov index rebuild --prefix viking://user/memories --changed-since 7d -j 8",
+                description: "Re-embed what changed in the last week, 8 items at a time.",
+            },
+        ],
+        next_steps: &[
+            HelpItem {
+                label: "ov queue wait <uri>",
+                description: "Wait for the re-enqueued work to finish.",
+            },
+            HelpItem {
+                label: "ov index verify --prefix <uri>",
+                description: "Confirm nothing is missing any more.",
+            },
+        ],
+    },
+    CommandHelpSpec {
+        path: &["api"],
+        purpose: "Send a raw request to any server endpoint with the configured URL and credentials.",
+        examples: &[
+            HelpItem {
+                label: "ov api GET /api/v1/sessions",
+                description: "Call an endpoint by its full path.",
+            },
+            HelpItem {
+                label: "ov api GET fs/ls -F uri=viking://resources",
+                description: "Relative paths resolve under /api/v1/.",
+            },
+            HelpItem {
+                label: "ov api POST search/find --raw-body @query.json",
+                description: "Send a JSON body from a file.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov help exit-codes",
+            description: "Branch on the exit code in scripts.",
+        }],
+    },
+    CommandHelpSpec {
+        path: &["bench"],
+        purpose: "Fire concurrent find, read, or add operations and report throughput and latency percentiles.",
+        examples: &[
+            HelpItem {
+                label: "ov bench find \"auth flow\" -j 8 --duration 30",
+                description: "Run 8 concurrent searches for 30 seconds.",
+            },
+            HelpItem {
+                label: "ov bench read viking://resources/docs/README.md -n 500",
+                description: "Read one file 500 times.",
+            },
+            HelpItem {
+                label: "ov bench add -j 4",
+                description: "Append messages to a scratch session, deleted afterwards.",
+            },
+        ],
+        next_steps: &[HelpItem {
+            label: "ov --timing find \"query\"",
+            description: "Time the calls behind a single command.",
+        }],
+    },
+];
+
+#[derive(Debug, Clone, Copy)]
+struct ExitCodeHelp {
+    code: i32,
+    name: &'static str,
+    description: &'static str,
+    description_zh: &'static str,
+}
+
+const EXIT_CODE_HELP: &[ExitCodeHelp] = &[
+    ExitCodeHelp {
+        code: 0,
+        name: "success",
+        description: "The command completed.",
+        description_zh: "命令执行成功。",
+    },
+    ExitCodeHelp {
+        code: error::EXIT_FAILURE,
+        name: "failure",
+        description: "Unclassified local failure, such as an I/O or parse error.",
+        description_zh: "未归类的本地失败，例如 I/O 或解析错误。",
+    },
+    ExitCodeHelp {
+        code: error::EXIT_USAGE,
+        name: "usage",
+        description: "Invalid command, option or argument.",
+        description_zh: "命令、选项或参数无效。",
+    },
+    ExitCodeHelp {
+        code: error::EXIT_NOT_FOUND,
+        name: "not-found",
+        description: "The URI, session or resource does not exist.",
+        description_zh: "URI、会话或资源不存在。",
+    },
+    ExitCodeHelp {
+        code: error::EXIT_SERVER,
+        name: "server",
+        description: "The server failed or rejected the request.",
+        description_zh: "服务端执行失败或拒绝了请求。",
+    },
+    ExitCodeHelp {
+        code: error::EXIT_TIMEOUT,
+        name: "timeout",
+        description: "The request or wait deadline expired.",
+        description_zh: "请求或等待超时。",
+    },
+    ExitCodeHelp {
+        code: error::EXIT_AUTH,
+        name: "auth",
+        description: "Missing, invalid or insufficient API key.",
+        description_zh: "API Key 缺失、无效或权限不足。",
+    },
+    ExitCodeHelp {
+        code: error::EXIT_UNREACHABLE,
+        name: "unreachable",
+        description: "The server could not be reached or is unavailable.",
+        description_zh: "无法连接服务端或服务不可用。",
+    },
+    ExitCodeHelp {
+        code: error::EXIT_CONFLICT,
+        name: "conflict",
+        description: "The target already exists or changed concurrently.",
+        description_zh: "目标已存在或被并发修改。",
+    },
+    ExitCodeHelp {
+        code: error::EXIT_QUOTA,
+        name: "quota",
+        description: "A quota or rate limit was exceeded.",
+        description_zh: "超出配额或速率限制。",
+    },
+    ExitCodeHelp {
+        code: error::EXIT_NOT_READY,
+        name: "not-ready",
+        description: "Indexing has not finished yet; retry after `ov wait`.",
+        description_zh: "索引尚未完成；可在 `ov wait` 后重试。",
+    },
+    ExitCodeHelp {
+        code: error::EXIT_PROCESSING,
+        name: "processing",
+        description: "Server-side processing of the content failed.",
+        description_zh: "服务端处理内容失败。",
+    },
+    ExitCodeHelp {
+        code: error::EXIT_CONFIG,
+        name: "config",
+        description: "The CLI config is missing or invalid.",
+        description_zh: "CLI 配置缺失或无效。",
+    },
+    ExitCodeHelp {
+        code: error::EXIT_INTERRUPTED,
+        name: "interrupted",
+        description: "Ctrl-C cancelled the command; in-flight requests were abandoned.",
+        description_zh: "命令被 Ctrl-C 取消；进行中的请求已被放弃。",
+    },
 ];
 
 pub(crate) fn is_top_level_help_request(args: &[OsString]) -> bool {
@@ -1380,6 +2862,72 @@ pub(crate) fn render_command_help_request(args: &[OsString]) -> Option<String> {
     Some(render_command_help(spec))
 }
 
+/// `ov help exit-codes`, the only `help` topic; plain `ov help` stays a misuse.
+pub(crate) fn is_exit_codes_help_request(args: &[OsString]) -> bool {
+    let tokens: Vec<String> = args
+        .iter()
+        .map(|arg| arg.to_string_lossy().to_string())
+        .collect();
+    let value_options = cli_value_options();
+    let mut words = Vec::new();
+    let mut i = 1;
+    while i < tokens.len() {
+        if let Some(width) = option_token_width(&tokens, i, &value_options) {
+            i += width;
+            continue;
+        }
+        words.push(tokens[i].as_str());
+        i += 1;
+    }
+    words == ["help", "exit-codes"]
+}
+
+pub(crate) fn render_exit_codes_help() -> String {
+    render_exit_codes_help_with_width(Language::current(), help_output_width())
+}
+
+fn render_exit_codes_help_with_width(language: Language, width: usize) -> String {
+    let mut lines = vec![
+        heading_line(copy(language, "Exit codes", "退出码"), width),
+        String::new(),
+        strong_line(copy(language, "Codes:", "退出码："), width),
+    ];
+    for item in EXIT_CODE_HELP {
+        lines.push(two_column_line_with_bold_label(
+            &format!("{:>3}  {}", item.code, item.name),
+            copy(language, item.description, item.description_zh),
+            18,
+            width,
+        ));
+    }
+    lines.push(String::new());
+    lines.push(strong_line(copy(language, "More:", "更多："), width));
+    lines.push(two_column_line_with_bold_label(
+        "ov help exit-codes -o json",
+        copy(language, "Print the table as JSON", "以 JSON 输出此表"),
+        28,
+        width,
+    ));
+    format!("{}\n", lines.join("\n"))
+}
+
+/// `code`/`name`/`description` rows for `ov help exit-codes -o json`.
+pub(crate) fn exit_codes_json() -> serde_json::Value {
+    let language = Language::current();
+    serde_json::Value::Array(
+        EXIT_CODE_HELP
+            .iter()
+            .map(|item| {
+                serde_json::json!({
+                    "code": item.code,
+                    "name": item.name,
+                    "description": copy(language, item.description, item.description_zh),
+                })
+            })
+            .collect(),
+    )
+}
+
 pub(crate) fn render_top_level_help() -> String {
     render_top_level_help_with_language(Language::current())
 }
@@ -1462,6 +3010,20 @@ fn render_top_level_help_with_language_and_width(language: Language, width: usiz
         copy(language, "Configure the CLI", "配置 CLI"),
         width,
     ));
+    lines.push(start_here_line(
+        "ov help exit-codes",
+        copy(language, "Exit codes for scripts", "脚本可用的退出码"),
+        width,
+    ));
+    lines.push(start_here_line(
+        "ov <name>",
+        copy(
+            language,
+            "Run an ov-<name> plugin from PATH",
+            "运行 PATH 中的 ov-<name> 插件",
+        ),
+        width,
+    ));
 
     format!("{}\n", lines.join("\n"))
 }
@@ -1639,19 +3201,34 @@ fn global_options_for(spec: &CommandHelpSpec) -> Vec<RenderedHelpItem> {
     );
     let include_sudo = matches!(
         spec.path,
-        ["admin"] | ["system"] | ["system", "backend"] | ["reindex"]
+        ["admin"] | ["system"] | ["system", "backend"] | ["reindex"] | ["index"] | ["api"]
+    );
+    let include_queue_offline = matches!(
+        spec.path,
+        ["add-resource"]
+            | ["add-skill"]
+            | ["add-memory"]
+            | ["write"]
+            | ["mkdir"]
+            | ["rm"]
+            | ["mv"]
+            | ["session", "add-message"]
+            | ["session", "add-messages"]
     );
 
     let mut root = Cli::command();
     root.build();
 
-    let mut ids = vec!["output", "compact"];
+    let mut ids = vec!["output", "compact", "output_query", "output_template"];
     if include_identity {
-        ids.extend(["account", "user"]);
+        ids.extend(["workspace", "account", "user"]);
     }
     if include_sudo {
         ids.push("sudo");
     }
+    if include_queue_offline {
+        ids.push("queue_offline");
+    }
 
     rendered_global_options(&root, &ids, None)
 }
@@ -1885,8 +3462,126 @@ fn localized_command_purpose(spec: &CommandHelpSpec, language: Language) -> &str
         ["config", "edit"] => "不打开交互式向导，编辑已保存的 CLI 配置。",
         ["config", "delete"] => "不打开交互式向导，删除已保存的 CLI 配置。",
         ["health"] => "快速检查服务器是否可连接。",
+        ["doctor"] => "端到端检查服务器，并为每项检查输出 PASS/WARN/FAIL 结果和修复建议。",
         ["status"] => "查看 OpenViking 服务器诊断状态。",
+        ["mcp"] => "把当前配置的 OpenViking 服务器提供给 Claude Desktop、Cursor 等 MCP 宿主使用。",
+        ["mcp", "serve"] => {
+            "通过 Model Context Protocol 提供 find、search、read、abstract、add_resource 和会话工具。"
+        }
+        ["mock-server"] => {
+            "在内存中提供 OpenViking API，便于离线开发和测试客户端：文件系统、内容读写、add-resource 上传、find/search/grep/glob 和会话。其他接口返回 501 NOT_IMPLEMENTED，退出后不保留任何数据。"
+        }
+        ["daemon"] => {
+            "运行保持服务器连接常驻的后台进程，并缓存摘要、概览、目录列表和搜索结果；命令会自动经由它访问服务器。"
+        }
+        ["schedule"] => {
+            "按 cron 表达式（本地时间）定时运行 ov 命令，例如每晚同步、每周备份。计划保存在 ~/.openviking/schedules.json，由当前服务器的守护进程执行，ls 会显示下次运行时间和上次运行结果。"
+        }
         ["language"] => "选择 OpenViking CLI 显示语言。",
+        ["tag"] => "添加、删除和列出显式 k=v 检索标签，按 URI 层级之外的维度组织资源。",
+        ["relations", "graph"] => {
+            "从 URI 出发沿关系链接遍历，并以 DOT、Mermaid 或 GraphML 格式输出关系图。实验功能。"
+        }
+        ["relations", "neighbors"] => {
+            "列出从 URI 出发 N 跳以内链接到的所有资源，按距离排序。实验功能。"
+        }
+        ["relations", "path"] => "显示从一个 URI 到另一个 URI 的最短关系链接路径。实验功能。",
+        ["add"] => {
+            "添加由 ov 自行获取或转换的资源：S3 或 TOS 前缀下的对象、通过标准输入传入的内容、文档站点、博客订阅源或表格数据文件。"
+        }
+        ["append"] => {
+            "向文件追加文本内容；文件或其父目录不存在时自动创建，适合维护运行日志或决策记录。"
+        }
+        ["top"] => {
+            "实时查看服务端处理负载的情况：队列深度、导入吞吐、索引延迟、检索延迟和最近错误，每隔几秒刷新。q 退出，r 立即刷新。"
+        }
+        ["pick"] => {
+            "输入时实时过滤前缀下的 URI，并输出所选 URI，便于在其他命令中使用。Enter 选择，Esc 取消。"
+        }
+        ["edit"] => {
+            "在 $VISUAL 或 $EDITOR 中打开文件内容，保存后写回。若打开期间服务器上的内容已被修改，则拒绝写入。"
+        }
+        ["alias"] => {
+            "为较长的 URI 保存短名称；任何写成 @name 或 @name/路径 的参数都会在命令执行前替换为对应 URI。"
+        }
+        ["bookmark"] => "以短名称收藏常读文档，可像别名一样以 @name 使用。",
+        ["recent"] => {
+            "按时间倒序显示本地历史中记录的 URI 和命令。在配置中设置 \"history\": false 可停止记录。"
+        }
+        ["workspace"] => {
+            "列出工作区（服务器账户），并选择当前配置默认使用的工作区，用于限定 URI 和 API 调用的范围；--workspace 可为单条命令覆盖。"
+        }
+        ["stats"] => {
+            "统计 URI 下已存储的文件数、目录数、字节数和向量数，按文件类型（可选按子前缀）分组，并汇总工作区近期的 token 用量。"
+        }
+        ["jobs"] => {
+            "跟踪服务器上长时间运行的作业（导入、技能、重建索引、会话提交）：列出作业，查看进度和收集到的错误，或等待其完成。add-resource --wait、add-skill --wait 和 index rebuild 也通过作业等待。"
+        }
+        ["events"] => {
+            "订阅后台事件：资源添加、索引完成或失败、会话提交。每个事件输出为一行 JSON，或交给 --exec 指定的命令处理。事件来自轮询服务器的任务列表。"
+        }
+        ["audit"] => {
+            "分页查看或持续跟踪服务端请求审计日志：谁在何时调用了哪个路由、返回了什么状态。ADMIN key 可查看整个工作区，USER key 只能查看自己的请求。"
+        }
+        ["clip"] => "把剪贴板中的文本或图片以带时间戳的名称添加到收件箱 URI 下，并输出新 URI。",
+        ["add", "url"] => {
+            "抓取 URL 页面，将正文提取为 Markdown，按深度跟随链接，并按规范 URL 去重后逐页添加。"
+        }
+        ["add", "feed"] => {
+            "添加 sitemap.xml 或 RSS/Atom 订阅源中的条目，并用本地状态文件记录，重复运行时只添加新条目。"
+        }
+        ["add", "table"] => {
+            "在本地读取 CSV、TSV 或 Parquet 文件，添加为一份结构摘要（列类型、空值数、数值范围、示例值）和每块 --chunk-rows 行的 Markdown 表格，使数据可被检索，而不是一个不透明文件。"
+        }
+        ["import", "mcp"] => {
+            "从外部 MCP 服务器（例如飞书文档服务器）列出并拉取文档，作为资源导入。"
+        }
+        ["import", "notion"] => {
+            "导入 Notion 的 Markdown & CSV 导出：页面层级保留为 URI 树，数据库转为 Markdown 表格，页面间链接改写为 viking:// 链接并记录为关系。"
+        }
+        ["import", "mbox"] => {
+            "将 mbox 文件或 .eml 邮件按线程导入，每个线程一个 Markdown 资源：去掉引用回复和签名，参与者与日期写入元数据，改了主题的回复线程通过关系链接到原线程。"
+        }
+        ["relations", "import"] => {
+            "从 CSV 或 JSONL 文件批量创建关系链接，并逐行报告失败。实验功能。"
+        }
+        ["meta"] => {
+            "为 URI 设置、读取和删除自由格式的 key=value 元数据，例如来源系统、导入批次或负责人。"
+        }
+        ["skills", "export"] => "将已安装技能的 SKILL.md 及附属文件下载到本地目录。",
+        ["skills", "init"] => "生成技能骨架目录，包含模板化的 SKILL.md 和 references/。",
+        ["search", "save"] => "以名称保存搜索查询及其参数，存放在配置目录中。",
+        ["search", "run"] => "运行已保存的搜索，并用 name=value 参数填充其中的 {{name}} 占位符。",
+        ["backup"] => "在一个文件中备份或恢复全部资源、记忆、会话和关系。",
+        ["backup", "create"] => "为所有公共 OpenViking 作用域创建仅恢复备份 .ovpack。",
+        ["backup", "restore"] => "将备份 .ovpack 恢复到原始作用域根目录。",
+        ["sync"] => "把某个前缀下新增和变更的条目从一个已保存配置的服务器复制到另一个。",
+        ["watch"] => "把本地目录镜像到服务器目录，并在文件变化时持续同步。",
+        ["version"] => {
+            "显示 OpenViking CLI 版本；加 --remote 时同时显示服务器版本及其支持的 API 功能。"
+        }
+        ["completions"] => {
+            "输出 shell 补全脚本，同时补全已保存的配置名和最近读取过的 viking:// URI。"
+        }
+        ["run"] => {
+            "在同一个进程中逐行运行脚本里的 ov 命令，并复用同一个服务器连接；`set name value` 为后续行定义 $name，`onerror continue` 让脚本在命令失败后继续执行。"
+        }
+        ["queue"] => {
+            "重放通过 --queue-offline 排队的写入命令，并查看服务器尚未完成的解析和向量化任务。"
+        }
+        ["context"] => {
+            "检索与问题最相关的结果，在 token 预算内把它们的摘要、概览和正文打包成一个上下文块。"
+        }
+        ["ask"] => "用检索到的上下文和配置中 \"llm\" 部分的模型回答问题，并列出答案引用的来源。",
+        ["tokens"] => "用 cl100k、o200k 编码或服务端估算统计内容会消耗多少 LLM token。",
+        ["index"] => {
+            "比对已存储内容与向量索引，报告缺失和孤立的向量，重新排队缺失的条目，或重新向量化整个子树。"
+        }
+        ["pack"] => "在本地处理 .ovpack 归档，无需连接服务器。",
+        ["pack", "create"] => "无需服务器，从本地目录构建可导入的 .ovpack。",
+        ["pack", "sign"] => "用 Ed25519 密钥就地签名 .ovpack，供导入时校验。",
+        ["pack", "inspect"] => "无需服务器，列出 .ovpack 的清单、URI、大小和摘要。",
+        ["pack", "diff"] => "比较两个 .ovpack，或一个包与在线子树，列出新增、删除和修改的 URI。",
         ["snapshot"] => "管理工作区快照：提交、恢复、查看、对比，以及遍历历史。",
         ["snapshot", "commit"] => "将当前工作区状态提交为新的快照。",
         ["snapshot", "restore"] => "通过一次前向提交，将项目目录恢复到历史快照。",
@@ -1948,11 +3643,17 @@ fn localized_help_item_description<'a>(
         }
         "--activate" => "同时写入当前 ovcli.conf。",
         "--force" => "替换已有的已保存配置。",
-        "-o, --output <table|json>" => "选择表格输出或机器可读 JSON。",
+        "-o, --output <table|json|ndjson>" => "选择表格输出、机器可读 JSON 或逐行 NDJSON。",
         "-c, --compact <bool>" => "使用紧凑的表格或 JSON 输出。",
+        "--query <expr>" => "输出前用 jq 风格表达式过滤响应，例如 '.[].uri'。",
+        "--template <template>" => "用模板逐行渲染结果，例如 '{{.uri}}\\t{{.abstract}}'。",
         "--account <account>" => "覆盖本次命令的 X-OpenViking-Account。",
+        "--workspace <name>" => "本次命令使用其他工作区，而非当前配置的默认工作区。",
         "--user <user>" => "覆盖本次命令的 X-OpenViking-User。",
         "--sudo" => "使用 root API Key 执行支持的管理和任务查询命令。",
+        "--queue-offline" => {
+            "服务器不可达时把本次写入加入队列，等待 `ov queue flush` 重放，而不是直接失败。"
+        }
         _ => description,
     }
 }
@@ -1982,7 +3683,32 @@ fn top_level_start_here_line(
 fn top_level_global_options(root: &Command, language: Language) -> Vec<RenderedHelpItem> {
     let mut items = rendered_global_options(
         root,
-        &["output", "compact", "account", "user", "sudo"],
+        &[
+            "output",
+            "compact",
+            "output_query",
+            "output_template",
+            "table_columns",
+            "table_sort",
+            "table_wide",
+            "table_wrap",
+            "no_color",
+            "no_pager",
+            "timing",
+            "debug_http",
+            "log_level",
+            "log_file",
+            "log_format",
+            "proxy",
+            "ca_cert",
+            "client_cert",
+            "client_key",
+            "timeout",
+            "connect_timeout",
+            "account",
+            "user",
+            "sudo",
+        ],
         Some(language),
     );
 
@@ -2136,7 +3862,7 @@ fn command_about_without_tags(command: &Command) -> String {
     strip_command_tags(&about).to_string()
 }
 
-fn command_about_tags(about: &str) -> Vec<&str> {
+pub(crate) fn command_about_tags(about: &str) -> Vec<&str> {
     let mut tags = Vec::new();
     let mut rest = about.trim_start();
 
@@ -2151,7 +3877,7 @@ fn command_about_tags(about: &str) -> Vec<&str> {
     tags
 }
 
-fn strip_command_tags(about: &str) -> &str {
+pub(crate) fn strip_command_tags(about: &str) -> &str {
     let mut rest = about.trim_start();
 
     while let Some(after_open) = rest.strip_prefix('[') {
@@ -2176,9 +3902,28 @@ fn localized_global_option_description<'a>(
     match id {
         "output" => "输出格式",
         "compact" => "紧凑输出",
+        "output_query" => "jq 风格过滤响应",
+        "output_template" => "按模板逐行渲染结果",
+        "table_columns" => "只显示指定表格列",
+        "table_sort" => "按列排序，'-' 前缀为降序",
+        "table_wide" => "不截断表格单元格",
+        "table_wrap" => "长单元格换行显示",
+        "no_color" => "关闭彩色输出",
+        "no_pager" => "不使用分页器显示长输出",
+        "timing" => "打印每个 API 调用耗时，并在结束时输出汇总",
+        "debug_http" => "打印 HTTP 请求和响应（隐藏凭证）",
+        "log_level" => "记录该级别及以上的日志事件",
+        "log_file" => "将日志追加写入文件而不是 stderr",
+        "log_format" => "日志格式：文本或每行一个 JSON 对象",
+        "proxy" => "服务端请求使用的代理（http、https、socks5），'none' 表示忽略 HTTP(S)_PROXY",
+        "ca_cert" => "额外信任的 CA 证书（PEM）",
+        "client_cert" => "双向 TLS 客户端证书（PEM）",
+        "client_key" => "--client-cert 对应的私钥（PEM），证书文件已包含时可省略",
+        "timeout" => "每个服务端请求的超时秒数；需写在命令之前",
+        "connect_timeout" => "建立服务端连接的超时秒数",
         "account" => "覆盖账户",
         "user" => "覆盖用户",
-        "sudo" => "admin、system、reindex、task status/list 使用 root API Key",
+        "sudo" => "admin、system、reindex、index、task status/list 使用 root API Key",
         _ => description,
     }
 }
@@ -2318,18 +4063,31 @@ fn localized_command_description<'a>(
     match name {
         "add-resource" => "添加文件、文件夹、URL 或仓库",
         "add-skill" => "添加技能到 OpenViking",
+        "add" => "从对象存储、标准输入、网页或表格文件添加资源",
+        "clip" => "捕获剪贴板内容",
         "skills" => "管理已安装技能",
         "find" => "语义检索相关上下文",
+        "context" => "按 token 预算组装上下文",
+        "tokens" => "统计内容的 token 数",
+        "ask" => "基于上下文用 LLM 回答问题",
         "read" => "读取精确资源内容",
-        "write" => "更新已有资源",
+        "write" => "写入资源内容",
+        "append" => "向资源追加内容",
+        "edit" => "在编辑器中修改资源内容",
         "add-memory" => "直接添加记忆",
         "ls" => "列出目录内容",
         "tree" => "查看范围内的资源树",
         "mkdir" => "创建目录",
         "rm" => "删除资源",
         "mv" => "移动或重命名资源",
+        "cp" => "复制资源或子树",
         "stat" => "查看资源元数据",
+        "tag" => "添加、删除或列出检索标签",
+        "meta" => "设置、读取或删除元数据",
         "get" => "下载文件",
+        "alias" => "为 URI 设置 @别名",
+        "bookmark" => "收藏常读文档",
+        "recent" => "查看最近使用的 URI 和命令",
         "search" => "上下文感知检索",
         "grep" => "模式搜索",
         "glob" => "Glob 路径搜索",
@@ -2339,6 +4097,8 @@ fn localized_command_description<'a>(
         "link" => "创建关系链接",
         "unlink" => "删除关系链接",
         "config" => "添加、编辑、删除或切换配置",
+        "workspace" => "列出并切换工作区",
+        "stats" => "统计存储量、向量数和 token 用量",
         "config show" => "显示当前配置",
         "config validate" => "验证当前配置",
         "config switch" => "切换当前配置",
@@ -2346,23 +4106,43 @@ fn localized_command_description<'a>(
         "config list" => "列出已保存配置",
         "config delete" => "删除已保存配置",
         "health" => "快速检查服务器连接",
+        "doctor" => "诊断常见问题并给出修复建议",
         "status" => "查看系统状态",
         "wait" => "等待异步任务完成",
         "task" => "查看异步任务",
+        "jobs" => "跟踪长时间运行的作业",
+        "events" => "订阅后台事件",
         "observer" => "观察服务器组件",
         "session" => "管理会话",
-        "import" => "导入 .ovpack",
+        "memory" => "查看长期记忆",
+        "import" => "导入 .ovpack、MCP 服务器文档、Notion 导出或邮件归档",
         "export" => "导出为 .ovpack",
-        "backup" => "创建仅恢复备份",
+        "backup" => "备份或恢复全部数据",
         "restore" => "恢复备份",
+        "sync" => "在服务器之间同步变更",
+        "watch" => "持续镜像本地目录",
+        "queue" => "离线写入队列与处理队列",
+        "run" => "批量运行 ov 命令脚本",
+        "pack" => "在本地检查、构建、签名和比较 .ovpack 归档",
         "snapshot" => "管理工作区快照",
         "tui" => "打开交互式浏览器",
+        "pick" => "模糊查找并选择 URI",
+        "top" => "实时查看队列、吞吐和错误",
         "chat" => "与 VikingBot 对话",
         "admin" => "管理账户、用户和 API Key",
+        "audit" => "查看请求审计日志",
         "system" => "系统维护命令",
         "privacy" => "管理隐私策略",
         "reindex" => "重建语义和向量索引",
+        "index" => "校验、修复和重建向量索引",
+        "api" => "向任意服务端接口发送原始请求",
+        "bench" => "压测服务端吞吐量和延迟",
+        "daemon" => "运行带缓存的后台守护进程",
+        "schedule" => "由守护进程定时运行命令",
+        "mcp" => "以 MCP 服务器方式提供 OpenViking 工具",
+        "mock-server" => "提供离线的内存版 OpenViking API",
         "version" => "显示版本信息",
+        "completions" => "输出 shell 补全脚本",
         "language" => "选择 CLI 显示语言（别名：lang）",
         _ => description,
     }
@@ -2405,14 +4185,15 @@ fn command_help_path(args: &[OsString]) -> Option<Vec<String>> {
             if is_help_flag(next) {
                 // Explicit help for this top-level command.
             } else if !next.starts_with('-') {
-                if has_help_flag && path.len() == 1 && is_bare_group_help_command(&path[0]) {
+                if has_help_flag && path.len() == 1 {
                     let nested_path =
-                        command_path_until_help_flag(&tokens, i + 1, path, &value_options);
-                    return if command_spec(&nested_path).is_some() {
-                        Some(nested_path)
-                    } else {
-                        None
-                    };
+                        command_path_until_help_flag(&tokens, i + 1, path.clone(), &value_options);
+                    if command_spec(&nested_path).is_some() {
+                        return Some(nested_path);
+                    }
+                    if is_bare_group_help_command(&path[0]) {
+                        return None;
+                    }
                 }
                 return if has_help_flag { Some(path) } else { None };
             } else {
@@ -2555,7 +4336,9 @@ fn canonical_command_token(token: &str) -> String {
         "list" => "ls",
         "del" | "delete" => "rm",
         "rename" => "mv",
+        "copy" => "cp",
         "lang" => "language",
+        "skill" => "skills",
         other => other,
     }
     .to_string()
@@ -2572,7 +4355,25 @@ fn version() -> String {
 fn is_bare_group_help_command(command: &str) -> bool {
     matches!(
         command,
-        "task" | "skills" | "session" | "snapshot" | "privacy" | "admin" | "system" | "observer"
+        "task"
+            | "backup"
+            | "add"
+            | "skills"
+            | "session"
+            | "memory"
+            | "snapshot"
+            | "pack"
+            | "daemon"
+            | "schedule"
+            | "mcp"
+            | "queue"
+            | "index"
+            | "context"
+            | "tokens"
+            | "privacy"
+            | "admin"
+            | "system"
+            | "observer"
     )
 }
 
@@ -2632,6 +4433,7 @@ mod tests {
         render_command_help_request, render_command_help_with_width, render_top_level_help,
         render_top_level_help_with_language_and_width,
     };
+    use super::{EXIT_CODE_HELP, is_exit_codes_help_request, render_exit_codes_help_with_width};
     use super::{command_spec, is_top_level_help_request};
     use crate::Cli;
     use crate::i18n::Language;
@@ -2693,7 +4495,9 @@ mod tests {
         assert!(rendered.contains("ov tui"));
         assert!(rendered.contains("-c, --compact <true|false>"));
         assert!(
-            rendered.contains("Use root API key for admin, system, reindex, and task status/list")
+            rendered.contains(
+                "Use root API key for admin, system, reindex, index, and task status/list"
+            )
         );
     }
 
@@ -3045,7 +4849,7 @@ mod tests {
         assert!(!custom.contains("--use-root-key-for-normal-commands"));
         assert!(custom.contains("--account <account>"));
         assert!(!custom.contains("Override X-OpenViking-Account"));
-        assert!(custom.contains("-o, --output <table|json>"));
+        assert!(custom.contains("-o, --output <table|json|ndjson>"));
         assert!(
             render_command_help_request(&os_args(&["ov", "config", "add", "cloud", "--help"]))
                 .is_none()
@@ -3110,7 +4914,7 @@ mod tests {
         assert!(session.contains("get-session-context <session-id>"));
         assert!(session.contains("get-session-archive <session-id> <archive-id>"));
         assert!(session.contains("add-message <session-id>"));
-        assert!(session.contains("add-messages <session-id> <messages-json>"));
+        assert!(session.contains("add-messages <session-id> [messages-json]"));
 
         let watch = strip_ansi(
             &render_command_help_request(&os_args(&["ov", "task", "watch", "--help"]))
@@ -3147,6 +4951,10 @@ mod tests {
                 "session",
                 "Manage sessions, messages, archives, and committed session context.",
             ),
+            (
+                "memory",
+                "Inspect long-term memories extracted from sessions.",
+            ),
             (
                 "privacy",
                 "Manage privacy config categories, targets, versions, and activation.",
@@ -3245,4 +5053,53 @@ mod tests {
         assert!(rendered.contains("ov ls [OPTIONS] [uri]"));
         assert!(rendered.contains("List resources under a Viking URI."));
     }
+
+    #[test]
+    fn exit_codes_topic_is_recognized_after_global_options() {
+        assert!(is_exit_codes_help_request(&os_args(&[
+            "ov",
+            "help",
+            "exit-codes"
+        ])));
+        assert!(is_exit_codes_help_request(&os_args(&[
+            "ov",
+            "-o",
+            "json",
+            "help",
+            "exit-codes"
+        ])));
+        assert!(!is_exit_codes_help_request(&os_args(&["ov", "help"])));
+        assert!(!is_exit_codes_help_request(&os_args(&[
+            "ov",
+            "help",
+            "exit-codes",
+            "extra"
+        ])));
+    }
+
+    #[test]
+    fn exit_codes_help_lists_every_code_once() {
+        let help = strip_ansi(&render_exit_codes_help_with_width(Language::En, 74));
+
+        for item in EXIT_CODE_HELP {
+            let label = format!("{:>3}  {}", item.code, item.name);
+            assert_eq!(help.matches(&label).count(), 1, "{label}\n{help}");
+        }
+        assert!(help.contains("  3  not-found"));
+        assert!(help.contains("130  interrupted"));
+        let columns: Vec<usize> = EXIT_CODE_HELP
+            .iter()
+            .filter_map(|item| {
+                let label = format!("{:>3}  {}", item.code, item.name);
+                let line = help.lines().find(|line| line.contains(&label))?;
+                line.find(item.description.split_whitespace().next()?)
+            })
+            .collect();
+        assert_eq!(columns.len(), EXIT_CODE_HELP.len());
+        assert!(columns.iter().all(|column| *column == columns[0]), "{help}");
+
+        let mut codes: Vec<i32> = EXIT_CODE_HELP.iter().map(|item| item.code).collect();
+        codes.dedup();
+        assert_eq!(codes, (0..=12).chain([130]).collect::<Vec<_>>());
+    }
 }
@@ -0,0 +1,405 @@
+//! HTML to Markdown conversion, for pages and for HTML fragments such as
+//! feed entries and mail bodies.
+
+use scraper::{ElementRef, Html, Node, Selector};
+use url::Url;
+
+/// Elements whose content is never part of a page's text.
+const SKIPPED_TAGS: &[&str] = &[
+    "head", "script", "style", "noscript", "template", "svg", "canvas", "iframe", "nav", "header",
+    "footer", "aside", "form", "button", "select",
+];
+/// Class or id values that mark page chrome rather than content.
+const CHROME_MARKERS: &[&str] = &[
+    "sidebar",
+    "navbar",
+    "nav",
+    "navigation",
+    "menu",
+    "breadcrumb",
+    "breadcrumbs",
+    "toc",
+    "table-of-contents",
+    "skip-link",
+    "cookie-banner",
+    "site-header",
+    "site-footer",
+];
+/// Elements rendered as paragraphs of their own.
+const BLOCK_TAGS: &[&str] = &[
+    "html",
+    "body",
+    "p",
+    "div",
+    "section",
+    "article",
+    "main",
+    "figure",
+    "figcaption",
+    "dl",
+    "dt",
+    "dd",
+    "address",
+    "details",
+    "summary",
+    "center",
+    "fieldset",
+];
+
+pub fn selector(css: &str) -> Selector {
+    Selector::parse(css).expect("static selector")
+}
+
+/// Whether `element` is page chrome, such as navigation or a hidden block.
+pub fn is_chrome(element: &ElementRef) -> bool {
+    let element = element.value();
+    let marked = |value: &str| CHROME_MARKERS.contains(&value.to_ascii_lowercase().as_str());
+    element.attr("hidden").is_some()
+        || element.attr("aria-hidden") == Some("true")
+        || element.id().is_some_and(marked)
+        || element.classes().any(marked)
+}
+
+/// The HTML subtree rooted at `element` as Markdown.
+pub fn element_markdown(element: ElementRef, base: &Url) -> String {
+    let mut markdown = Markdown::new(base);
+    markdown.element(element);
+    markdown.finish()
+}
+
+/// An HTML fragment, such as the content of a feed entry, as Markdown.
+pub fn fragment_markdown(html: &str, base: &Url) -> String {
+    element_markdown(Html::parse_fragment(html).root_element(), base)
+}
+
+/// Renders an HTML subtree as Markdown.
+struct Markdown<'a> {
+    base: &'a Url,
+    out: String,
+    /// The next item number of each enclosing list; `None` for bullet lists.
+    lists: Vec<Option<usize>>,
+    /// Set right after a list marker, so the item's first paragraph stays on
+    /// the marker's line.
+    item_start: bool,
+}
+
+impl<'a> Markdown<'a> {
+    fn new(base: &'a Url) -> Self {
+        Self {
+            base,
+            out: String::new(),
+            lists: Vec::new(),
+            item_start: false,
+        }
+    }
+
+    fn push(&mut self, text: &str) {
+        self.item_start = false;
+        self.out.push_str(text);
+    }
+
+    fn space(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with(char::is_whitespace) {
+            self.out.push(' ');
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        if text.starts_with(char::is_whitespace) {
+            self.space();
+        }
+        if words.is_empty() {
+            return;
+        }
+        self.push(&words.join(" "));
+        if text.ends_with(char::is_whitespace) {
+            self.space();
+        }
+    }
+
+    fn line(&mut self) {
+        if self.item_start {
+            return;
+        }
+        let len = self.out.trim_end_matches(' ').len();
+        self.out.truncate(len);
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+    }
+
+    /// Start a new paragraph; inside lists, just a new line, so items stay
+    /// together.
+    fn block(&mut self) {
+        self.line();
+        if self.lists.is_empty() && !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+
+    fn children(&mut self, element: ElementRef) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => self.text(text),
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        self.element(child);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn element(&mut self, element: ElementRef) {
+        let name = element.value().name();
+        if SKIPPED_TAGS.contains(&name) || is_chrome(&element) {
+            return;
+        }
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.block();
+                self.push(&"#".repeat(name[1..].parse().unwrap_or(1)));
+                self.push(" ");
+                self.children(element);
+                self.block();
+            }
+            "br" => self.line(),
+            "hr" => {
+                self.block();
+                self.push("---");
+                self.block();
+            }
+            "pre" => self.code_block(element),
+            "code" | "kbd" | "samp" | "tt" => {
+                let code: String = element.text().collect();
+                let code = code.trim();
+                if !code.is_empty() {
+                    let tick = if code.contains('`') { "``" } else { "`" };
+                    self.push(&format!("{tick}{code}{tick}"));
+                }
+            }
+            "strong" | "b" => self.inline(element, |text| format!("**{text}**")),
+            "em" | "i" => self.inline(element, |text| format!("*{text}*")),
+            "del" | "s" | "strike" => self.inline(element, |text| format!("~~{text}~~")),
+            "a" => {
+                let target = element
+                    .attr("href")
+                    .and_then(|href| self.base.join(href).ok())
+                    .filter(|url| matches!(url.scheme(), "http" | "https" | "mailto"));
+                match target {
+                    Some(target) => self.inline(element, |text| format!("[{text}]({target})")),
+                    None => self.children(element),
+                }
+            }
+            "img" => {
+                let source = element
+                    .attr("src")
+                    .and_then(|src| self.base.join(src).ok())
+                    .filter(|url| matches!(url.scheme(), "http" | "https"));
+                if let Some(source) = source {
+                    let alt = element.attr("alt").unwrap_or_default().trim();
+                    self.push(&format!("![{alt}]({source})"));
+                }
+            }
+            "ul" | "ol" => {
+                self.block();
+                let start = element
+                    .attr("start")
+                    .and_then(|start| start.parse().ok())
+                    .unwrap_or(1);
+                self.lists.push((name == "ol").then_some(start));
+                self.children(element);
+                self.lists.pop();
+                self.block();
+            }
+            "li" => {
+                self.line();
+                let indent = "    ".repeat(self.lists.len().saturating_sub(1));
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => "- ".to_string(),
+                };
+                self.push(&format!("{indent}{marker}"));
+                self.item_start = true;
+                self.children(element);
+                self.item_start = false;
+                self.line();
+            }
+            "blockquote" => {
+                self.block();
+                let start = self.out.len();
+                self.children(element);
+                let quoted = self.out.split_off(start);
+                for line in quoted.trim().lines() {
+                    self.push(format!("> {line}").trim_end());
+                    self.out.push('\n');
+                }
+                self.block();
+            }
+            "table" => self.table(element),
+            _ if BLOCK_TAGS.contains(&name) => {
+                self.block();
+                self.children(element);
+                self.block();
+            }
+            _ => self.children(element),
+        }
+    }
+
+    /// Render `element`'s children and wrap the text with `wrap`, keeping the
+    /// whitespace around it. Empty or multi-line text is left unwrapped.
+    fn inline(&mut self, element: ElementRef, wrap: impl FnOnce(&str) -> String) {
+        let start = self.out.len();
+        self.children(element);
+        let inner = self.out.split_off(start);
+        let text = inner.trim();
+        if text.is_empty() || text.contains('\n') {
+            self.out.push_str(&inner);
+            return;
+        }
+        if inner.starts_with(' ') {
+            self.out.push(' ');
+        }
+        self.push(&wrap(text));
+        if inner.ends_with(' ') {
+            self.out.push(' ');
+        }
+    }
+
+    fn code_block(&mut self, pre: ElementRef) {
+        let code: String = pre.text().collect();
+        let language = std::iter::once(pre)
+            .chain(
+                pre.child_elements()
+                    .filter(|child| child.value().name() == "code"),
+            )
+            .flat_map(|element| element.value().classes())
+            .find_map(|class| {
+                class
+                    .strip_prefix("language-")
+                    .or_else(|| class.strip_prefix("lang-"))
+            })
+            .unwrap_or_default();
+        let fence = if code.contains("```") { "~~~" } else { "```" };
+        self.block();
+        self.push(&format!(
+            "{fence}{language}\n{}\n{fence}",
+            code.trim_matches('\n')
+        ));
+        self.block();
+    }
+
+    /// A table as a Markdown table whose first row is the header.
+    fn table(&mut self, table: ElementRef) {
+        let rows: Vec<Vec<String>> = table
+            .select(&selector("tr"))
+            .map(|row| {
+                row.child_elements()
+                    .filter(|cell| matches!(cell.value().name(), "td" | "th"))
+                    .map(|cell| self.cell(cell))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|row| !row.is_empty())
+            .collect();
+        let Some(width) = rows.iter().map(Vec::len).max() else {
+            return;
+        };
+        self.block();
+        for (index, row) in rows.into_iter().enumerate() {
+            let mut cells = row;
+            cells.resize(width, String::new());
+            self.push(&format!("| {} |\n", cells.join(" | ")));
+            if index == 0 {
+                self.push(&format!("|{}|\n", vec![" --- "; width].join("|")));
+            }
+        }
+        self.block();
+    }
+
+    fn cell(&self, cell: ElementRef) -> String {
+        let mut markdown = Markdown::new(self.base);
+        markdown.children(cell);
+        markdown
+            .out
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .replace('|', "\\|")
+    }
+
+    /// The Markdown, with trailing spaces and repeated blank lines removed
+    /// outside code blocks.
+    fn finish(self) -> String {
+        let mut lines: Vec<&str> = Vec::new();
+        let mut fenced = false;
+        for line in self.out.lines() {
+            let line = if fenced { line } else { line.trim_end() };
+            if line.starts_with("```") || line.starts_with("~~~") {
+                fenced = !fenced;
+            }
+            if !fenced && line.is_empty() && lines.last().is_none_or(|last| last.is_empty()) {
+                continue;
+            }
+            lines.push(line);
+        }
+        lines.join("\n").trim().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Url {
+        Url::parse("https://docs.example.com/guide/intro.html").unwrap()
+    }
+
+    fn markdown(html: &str) -> String {
+        let document = Html::parse_document(html);
+        let base = base();
+        element_markdown(document.root_element(), &base)
+    }
+
+    #[test]
+    fn html_is_converted_to_markdown() {
+        let html = r#"<body>
+            <nav><a href="/">Home</a></nav>
+            <h1>Getting  started</h1>
+            <p>Install <code>ov</code> and read the <a href="setup.html#linux">setup <b>guide</b></a>.</p>
+            <ul>
+              <li><p>First</p></li>
+              <li>Second
+                <ol start="3"><li>nested</li><li>again</li></ol>
+              </li>
+            </ul>
+            <pre><code class="language-bash">ov add url https://example.com
+  --depth 2
+</code></pre>
+            <blockquote><p>Note</p><p>Two lines</p></blockquote>
+            <table><tr><th>Flag</th><th>Use</th></tr><tr><td>--depth</td><td>a | b</td></tr></table>
+            <script>alert(1)</script>
+            <div class="sidebar">Related</div>
+        </body>"#;
+        assert_eq!(
+            markdown(html),
+            "# Getting started\n\n\
+             Install `ov` and read the [setup **guide**](https://docs.example.com/guide/setup.html#linux).\n\n\
+             - First\n\
+             - Second\n    \
+             3. nested\n    \
+             4. again\n\n\
+             ```bash\n\
+             ov add url https://example.com\n  --depth 2\n\
+             ```\n\n\
+             > Note\n>\n> Two lines\n\n\
+             | Flag | Use |\n\
+             | --- | --- |\n\
+             | --depth | a \\| b |"
+        );
+    }
+}
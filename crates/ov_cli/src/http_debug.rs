@@ -0,0 +1,180 @@
+//! `--debug-http`: trace every request the client sends.
+//!
+//! Events go to the `ov::http` tracing target: the request line, headers with
+//! credentials redacted, the body, an equivalent curl command, then the
+//! response status, headers, body and timing. `logging` decides where they are
+//! written; nothing is formatted unless the target is enabled, so the hooks
+//! cost nothing on normal runs.
+
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use tracing::Level;
+
+pub const TARGET: &str = "ov::http";
+
+const REDACTED: &str = "<redacted>";
+
+pub fn enabled() -> bool {
+    tracing::enabled!(target: TARGET, Level::DEBUG)
+}
+
+pub fn log_request(request: &reqwest::Request, attempt: u32) {
+    if !enabled() {
+        return;
+    }
+    let body = request
+        .body()
+        .and_then(reqwest::Body::as_bytes)
+        .map(|bytes| String::from_utf8_lossy(bytes).into_owned());
+    let retry = if attempt > 1 {
+        format!(" (attempt {attempt})")
+    } else {
+        String::new()
+    };
+    tracing::debug!(target: TARGET, "> {} {}{retry}", request.method(), request.url());
+    for line in header_lines(request.headers()) {
+        tracing::debug!(target: TARGET, "> {line}");
+    }
+    match &body {
+        Some(body) if !body.is_empty() => tracing::debug!(target: TARGET, "> {body}"),
+        Some(_) => {}
+        None if request.body().is_some() => {
+            tracing::debug!(target: TARGET, "> <streamed body>")
+        }
+        None => {}
+    }
+    tracing::debug!(
+        target: TARGET,
+        "curl: {}",
+        curl_command(
+            request.method().as_str(),
+            request.url().as_str(),
+            request.headers(),
+            body.as_deref(),
+        )
+    );
+}
+
+pub fn log_response(response: &reqwest::Response, elapsed: Duration) {
+    if !enabled() {
+        return;
+    }
+    tracing::debug!(
+        target: TARGET,
+        "< {:?} {} ({} ms)",
+        response.version(),
+        response.status(),
+        elapsed.as_millis()
+    );
+    for line in header_lines(response.headers()) {
+        tracing::debug!(target: TARGET, "< {line}");
+    }
+}
+
+pub fn log_response_body(body: &[u8]) {
+    if enabled() && !body.is_empty() {
+        tracing::debug!(target: TARGET, "< {}", String::from_utf8_lossy(body));
+    }
+}
+
+pub fn log_failure(error: &reqwest::Error, elapsed: Duration) {
+    tracing::debug!(target: TARGET, "! {error} ({} ms)", elapsed.as_millis());
+}
+
+/// Headers that carry credentials: API keys, tokens, cookies and auth.
+fn is_secret_header(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    [
+        "key",
+        "token",
+        "secret",
+        "authorization",
+        "cookie",
+        "password",
+    ]
+    .iter()
+    .any(|marker| name.contains(marker))
+}
+
+fn header_lines(headers: &HeaderMap) -> Vec<String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if is_secret_header(name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            format!("{name}: {value}")
+        })
+        .collect()
+}
+
+/// A shell-ready curl command reproducing the request, with secrets redacted.
+pub fn curl_command(method: &str, url: &str, headers: &HeaderMap, body: Option<&str>) -> String {
+    let mut parts = vec!["curl".to_string()];
+    if method != "GET" || body.is_some() {
+        parts.push(format!("-X {method}"));
+    }
+    parts.push(shell_quote(url));
+    for line in header_lines(headers) {
+        parts.push(format!("-H {}", shell_quote(&line)));
+    }
+    if let Some(body) = body.filter(|body| !body.is_empty()) {
+        parts.push(format!("--data-raw {}", shell_quote(body)));
+    }
+    parts.join(" ")
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        headers.insert("x-api-key", HeaderValue::from_static("sk-live-123"));
+        headers.insert("x-gateway-token", HeaderValue::from_static("gw-456"));
+        headers.insert("x-openviking-user", HeaderValue::from_static("alice"));
+        headers
+    }
+
+    #[test]
+    fn header_lines_redact_credentials() {
+        let lines = header_lines(&headers());
+
+        assert!(lines.contains(&"x-api-key: <redacted>".to_string()));
+        assert!(lines.contains(&"x-gateway-token: <redacted>".to_string()));
+        assert!(lines.contains(&"x-openviking-user: alice".to_string()));
+        assert!(lines.iter().all(|line| !line.contains("sk-live-123")));
+    }
+
+    #[test]
+    fn curl_command_reproduces_request_without_secrets() {
+        let curl = curl_command(
+            "POST",
+            "http://localhost:1933/api/v1/search/find",
+            &headers(),
+            Some(r#"{"query":"it's"}"#),
+        );
+
+        assert!(curl.starts_with("curl -X POST 'http://localhost:1933/api/v1/search/find'"));
+        assert!(curl.contains("-H 'x-api-key: <redacted>'"));
+        assert!(curl.contains(r#"--data-raw '{"query":"it'\''s"}'"#));
+        assert!(!curl.contains("gw-456"));
+
+        let get = curl_command(
+            "GET",
+            "http://localhost:1933/health",
+            &HeaderMap::new(),
+            None,
+        );
+        assert_eq!(get, "curl 'http://localhost:1933/health'");
+    }
+}
@@ -0,0 +1,60 @@
+//! Rewrites applied to local files on their way to the server: the ingest
+//! hooks of WASM plugins (`crate::wasm_plugin`), then `--redact`
+//! (`crate::redact`).
+
+use std::path::{Path, PathBuf};
+
+use tempfile::TempDir;
+
+use crate::error::{Error, Result};
+use crate::{redact, wasm_plugin};
+
+/// Whether files are rewritten before upload, so they must be read into
+/// memory rather than streamed.
+pub fn active() -> bool {
+    wasm_plugin::filters_ingest() || redact::active()
+}
+
+/// The file `name` as it should be uploaded, possibly under a new name, or
+/// `None` when a plugin left it out.
+pub fn filter(name: &str, bytes: Vec<u8>) -> Result<Option<(String, Vec<u8>)>> {
+    let Some((name, bytes)) = wasm_plugin::filter_ingest(name, bytes)? else {
+        return Ok(None);
+    };
+    let bytes = redact::file(&name, bytes);
+    Ok(Some((name, bytes)))
+}
+
+/// A single file filtered into a temporary directory, removed on drop.
+pub struct Filtered {
+    _dir: TempDir,
+    pub path: PathBuf,
+}
+
+/// Filter the file at `path` for upload on its own. `None` when nothing
+/// rewrites files, so it goes out as it is.
+pub fn filter_file(path: &Path) -> Result<Option<Filtered>> {
+    if !active() {
+        return Ok(None);
+    }
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("upload");
+    let (name, bytes) = filter(name, std::fs::read(path)?)?.ok_or_else(|| {
+        Error::Client(format!(
+            "A WASM plugin left out {}; there is nothing to upload",
+            path.display()
+        ))
+    })?;
+    let dir = tempfile::tempdir()?;
+    let filtered = dir.path().join(&name);
+    if let Some(parent) = filtered.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&filtered, bytes)?;
+    Ok(Some(Filtered {
+        _dir: dir,
+        path: filtered,
+    }))
+}
@@ -0,0 +1,92 @@
+//! Ctrl-C handling: cancel the running command instead of dying mid-request.
+//!
+//! The first Ctrl-C drops the command's future, which aborts every request in
+//! flight, and `ov` exits with [`EXIT_INTERRUPTED`]. Batch commands opt in with
+//! [`enter_batch`] so they can stop between items and print what they already
+//! did. A second Ctrl-C, for a command that does not unwind, such as one stuck
+//! in a blocking prompt, exits after a short grace period.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use tokio::sync::Notify;
+
+use crate::error::{EXIT_INTERRUPTED, Error, Result};
+
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+static BATCH: AtomicBool = AtomicBool::new(false);
+static NOTIFY: Notify = Notify::const_new();
+
+/// How long a command gets to unwind after a second Ctrl-C before the process
+/// exits anyway.
+const GRACE_PERIOD: Duration = Duration::from_secs(1);
+
+/// Take over SIGINT for the rest of the process.
+pub fn install() {
+    tokio::spawn(async {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            if REQUESTED.swap(true, Ordering::SeqCst) {
+                tokio::spawn(async {
+                    tokio::time::sleep(GRACE_PERIOD).await;
+                    eprintln!("Interrupted");
+                    std::process::exit(EXIT_INTERRUPTED);
+                });
+            }
+            NOTIFY.notify_waiters();
+        }
+    });
+}
+
+pub fn requested() -> bool {
+    REQUESTED.load(Ordering::SeqCst)
+}
+
+/// Let the current command handle Ctrl-C itself, through [`cancellable`], so
+/// it can report partial results before returning [`Error::Interrupted`].
+pub fn enter_batch() {
+    BATCH.store(true, Ordering::SeqCst);
+}
+
+/// Resolves once Ctrl-C has been pressed.
+async fn interrupted() {
+    loop {
+        let notified = NOTIFY.notified();
+        tokio::pin!(notified);
+        notified.as_mut().enable();
+        if requested() {
+            return;
+        }
+        notified.await;
+    }
+}
+
+/// Run a whole command, dropping it on Ctrl-C unless it [`enter_batch`]ed.
+/// A Ctrl-C pressed before the command started does not count against it.
+pub async fn run<F>(command: F) -> Result<()>
+where
+    F: Future<Output = Result<()>>,
+{
+    REQUESTED.store(false, Ordering::SeqCst);
+    BATCH.store(false, Ordering::SeqCst);
+    tokio::select! {
+        result = command => result,
+        _ = async {
+            interrupted().await;
+            if BATCH.load(Ordering::SeqCst) {
+                std::future::pending::<()>().await;
+            }
+        } => Err(Error::Interrupted),
+    }
+}
+
+/// Run one step of a batch, abandoning it on Ctrl-C.
+pub async fn cancellable<T, F>(step: F) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    tokio::select! {
+        result = step => result,
+        _ = interrupted() => Err(Error::Interrupted),
+    }
+}
@@ -0,0 +1,89 @@
+//! Small JSON files the CLI keeps next to its config, such as `aliases.json`,
+//! `searches.json` and `schedules.json`, each a map of named entries.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::config::default_config_path;
+use crate::error::{Error, Result};
+
+/// Where `file_name` lives: the directory of the default config file.
+pub fn path(file_name: &str) -> Result<PathBuf> {
+    let config_path = default_config_path()?;
+    let home = config_path.parent().unwrap_or(Path::new("."));
+    Ok(home.join(file_name))
+}
+
+/// The file's contents, or the default value when it does not exist yet.
+pub fn read<T: DeserializeOwned + Default>(path: &Path) -> Result<T> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(T::default()),
+        Err(e) => return Err(e.into()),
+    };
+    serde_json::from_str(&text)
+        .map_err(|e| Error::Parse(format!("Corrupt {}: {e}", path.display())))
+}
+
+/// Replace the file through a temporary one, so an interrupted write never
+/// leaves it half written.
+pub fn write<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let temp = path.with_extension("json.tmp");
+    std::fs::write(&temp, serde_json::to_string_pretty(value)?)?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
+}
+
+/// Check the name of an entry: letters (in any script), digits, `-`, `_` and
+/// `.`. `noun` names the kind of entry in the error.
+pub fn validate_name(noun: &str, name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(Error::Client(format!(
+            "Invalid {noun} name '{name}'; use letters, digits, '-', '_' and '.'"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn missing_files_read_as_empty_and_writes_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("entries.json");
+        assert!(read::<BTreeMap<String, u32>>(&path).unwrap().is_empty());
+
+        let entries = BTreeMap::from([("a".to_string(), 1), ("b".to_string(), 2)]);
+        write(&path, &entries).unwrap();
+        assert_eq!(read::<BTreeMap<String, u32>>(&path).unwrap(), entries);
+        assert!(!path.with_extension("json.tmp").exists());
+
+        std::fs::write(&path, "{").unwrap();
+        assert!(matches!(
+            read::<BTreeMap<String, u32>>(&path),
+            Err(Error::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn names_allow_letters_of_any_script_but_no_separators() {
+        assert!(validate_name("alias", "weekly-review_2.0").is_ok());
+        assert!(validate_name("bookmark", "合同.2024").is_ok());
+        assert!(validate_name("alias", "a/b").is_err());
+        assert!(validate_name("alias", "a b").is_err());
+        assert!(validate_name("alias", "").is_err());
+    }
+}
@@ -0,0 +1,51 @@
+//! The OpenViking HTTP client behind the `ov` CLI.
+//!
+//! [`client::HttpClient`] talks to an OpenViking server; [`cassette`] records
+//! and replays its requests, for tests that run without one. The `blocking`
+//! feature adds a synchronous wrapper in `blocking`.
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod cassette;
+pub mod client;
+pub mod error;
+
+// The client's own building blocks. The `ov` binary shares them, so that it
+// configures the same output, theme and timing state the client reads; they
+// are not part of the library's API.
+#[doc(hidden)]
+pub mod base_client;
+#[doc(hidden)]
+pub mod chunked_upload;
+#[doc(hidden)]
+pub mod config;
+#[doc(hidden)]
+pub mod file_type;
+#[doc(hidden)]
+pub mod html_markdown;
+#[doc(hidden)]
+pub mod http_debug;
+#[doc(hidden)]
+pub mod ingest;
+#[doc(hidden)]
+pub mod notebook;
+#[doc(hidden)]
+pub mod output;
+#[doc(hidden)]
+pub mod pack_compression;
+#[doc(hidden)]
+pub mod pack_crypto;
+#[doc(hidden)]
+pub mod pager;
+#[doc(hidden)]
+pub mod query;
+#[doc(hidden)]
+pub mod redact;
+#[doc(hidden)]
+pub mod template;
+#[doc(hidden)]
+pub mod theme;
+#[doc(hidden)]
+pub mod timing;
+#[doc(hidden)]
+pub mod wasm_plugin;
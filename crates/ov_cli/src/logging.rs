@@ -0,0 +1,160 @@
+//! Process-wide `tracing` setup for `--log-level`, `--log-file`,
+//! `--log-format` and `--debug-http`.
+//!
+//! Logging stays off unless one of those flags is given. Only the CLI's own
+//! events are recorded; `--debug-http` additionally turns on the `ov::http`
+//! request traces regardless of the chosen level.
+
+use std::fs::OpenOptions;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::sync::Mutex;
+
+use tracing::Level;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+use crate::error::Result;
+use crate::http_debug;
+
+/// Target prefix shared by every event the `ov` binary emits.
+const CLI_TARGET: &str = "ov";
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogOptions<'a> {
+    pub level: Option<&'a str>,
+    pub file: Option<&'a Path>,
+    pub json: bool,
+    pub debug_http: bool,
+}
+
+pub fn init(options: &LogOptions<'_>) -> Result<()> {
+    let Some(targets) = targets(options) else {
+        return Ok(());
+    };
+    let (writer, ansi) = match options.file {
+        Some(path) => {
+            let file = OpenOptions::new().create(true).append(true).open(path)?;
+            (BoxMakeWriter::new(Mutex::new(file)), false)
+        }
+        None => (
+            BoxMakeWriter::new(std::io::stderr),
+            std::io::stderr().is_terminal(),
+        ),
+    };
+    let registry = tracing_subscriber::registry().with(targets);
+    // A subscriber can only be installed once per process; a second call is a no-op.
+    let _ = if options.json {
+        registry
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .json()
+                    .with_current_span(false)
+                    .with_writer(writer),
+            )
+            .try_init()
+    } else {
+        registry
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .with_ansi(ansi)
+                    .with_writer(writer),
+            )
+            .try_init()
+    };
+    Ok(())
+}
+
+/// Which targets to record at which level, or `None` when logging is off.
+/// A log file or JSON format without an explicit level records `info`.
+fn targets(options: &LogOptions<'_>) -> Option<Targets> {
+    let level = options
+        .level
+        .map(parse_level)
+        .or_else(|| (options.file.is_some() || options.json).then_some(Level::INFO));
+    if level.is_none() && !options.debug_http {
+        return None;
+    }
+
+    let mut targets = Targets::new();
+    if let Some(level) = level {
+        targets = targets.with_target(CLI_TARGET, level);
+    }
+    if options.debug_http {
+        let http_level = level.map_or(Level::DEBUG, |level| level.max(Level::DEBUG));
+        targets = targets.with_target(http_debug::TARGET, http_level);
+    }
+    Some(targets)
+}
+
+fn parse_level(level: &str) -> Level {
+    match level {
+        "error" => Level::ERROR,
+        "warn" => Level::WARN,
+        "debug" => Level::DEBUG,
+        "trace" => Level::TRACE,
+        _ => Level::INFO,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logging_is_off_without_flags() {
+        assert!(targets(&LogOptions::default()).is_none());
+    }
+
+    #[test]
+    fn log_file_and_json_default_to_info() {
+        let options = LogOptions {
+            file: Some(Path::new("import.log")),
+            ..LogOptions::default()
+        };
+        let targets = targets(&options).expect("log file enables logging");
+
+        assert!(targets.would_enable("ov::commands::session", &Level::INFO));
+        assert!(!targets.would_enable("ov::commands::session", &Level::DEBUG));
+        assert!(!targets.would_enable("hyper::client", &Level::INFO));
+
+        let options = LogOptions {
+            json: true,
+            ..LogOptions::default()
+        };
+        assert!(targets_enable(&options, "ov::logging", Level::INFO));
+    }
+
+    #[test]
+    fn debug_http_traces_requests_at_any_level() {
+        let options = LogOptions {
+            level: Some("warn"),
+            debug_http: true,
+            ..LogOptions::default()
+        };
+
+        assert!(targets_enable(&options, http_debug::TARGET, Level::DEBUG));
+        assert!(!targets_enable(
+            &options,
+            "ov::commands::session",
+            Level::INFO
+        ));
+
+        let options = LogOptions {
+            debug_http: true,
+            ..LogOptions::default()
+        };
+        assert!(targets_enable(&options, http_debug::TARGET, Level::DEBUG));
+        assert!(!targets_enable(
+            &options,
+            "ov::commands::session",
+            Level::ERROR
+        ));
+    }
+
+    fn targets_enable(options: &LogOptions<'_>, target: &str, level: Level) -> bool {
+        targets(options).is_some_and(|targets| targets.would_enable(target, &level))
+    }
+}
@@ -1,25 +1,39 @@
-mod base_client;
+// `ov_cli::error::Error` is returned everywhere; clippy only sizes it once it
+// comes from another crate, as it does now that the library holds it.
+#![allow(clippy::result_large_err)]
+
+mod archive;
 mod cli_arg_scan;
-mod client;
 mod commands;
-mod config;
 mod config_agent;
 mod config_command_ui;
 mod config_wizard;
-mod error;
+mod daemon;
+mod editor;
 mod error_ui;
 mod handlers;
 mod health_ui;
 mod help_ui;
 mod i18n;
-mod output;
+mod interrupt;
+mod json_store;
+mod logging;
+mod mcp;
+mod mock_server;
+mod object_store;
 mod status_ui;
 mod terminal_ui;
-mod theme;
 mod tui;
 mod utils;
 
-use clap::{ArgAction, Args, CommandFactory, Parser, Subcommand};
+use ov_cli::{
+    base_client, cassette, chunked_upload, client, config, error, file_type, html_markdown,
+    http_debug, notebook, output, pack_compression, pack_crypto, pager, query, redact, template,
+    theme, timing, wasm_plugin,
+};
+
+use clap::parser::ValueSource;
+use clap::{ArgAction, Args, CommandFactory, FromArgMatches, Parser, Subcommand};
 use colored::Colorize;
 use config::Config;
 use error::{Error, Result};
@@ -41,6 +55,16 @@ pub struct CliContext {
     /// Whether to enable verbose output (override config)
     pub verbose: Option<bool>,
     pub profile: Option<bool>,
+    /// Continue an interrupted chunked upload (`--resume`)
+    pub resume_upload: bool,
+    /// Skip uploading files the server already holds (`add-resource` without `--no-dedup`)
+    pub dedup_upload: bool,
+    /// Add token counts to listed rows (`--tokens`)
+    pub count_tokens: bool,
+    /// Add highlighted passages around query matches to hits (`--snippets`)
+    pub snippets: bool,
+    /// Ranking flags of `find`/`search` (`--mode`, `--rerank`, weights, `--scores`)
+    scoring: ScoringCliOptions,
 }
 
 impl CliContext {
@@ -74,9 +98,23 @@ impl CliContext {
             show_progress,
             verbose,
             profile,
+            resume_upload: false,
+            dedup_upload: false,
+            count_tokens: false,
+            snippets: false,
+            scoring: ScoringCliOptions::default(),
         }
     }
 
+    /// The same flags applied to the saved config `name` instead of the active one.
+    pub fn with_saved_config(&self, name: &str) -> Result<Self> {
+        let config = config_wizard::ConfigStore::new()?.load_saved_config(name)?;
+        Ok(Self {
+            config,
+            ..self.clone()
+        })
+    }
+
     /// Check if progress should be shown
     pub fn should_show_progress(&self) -> bool {
         self.show_progress.unwrap_or(self.config.show_progress)
@@ -87,6 +125,52 @@ impl CliContext {
         self.verbose.unwrap_or(self.config.verbose)
     }
 
+    /// The tokenizer for `--tokens`, when it was given
+    pub fn token_column(&self) -> Result<Option<commands::tokens::Tokenizer>> {
+        self.count_tokens
+            .then(|| commands::tokens::Tokenizer::from_config(&self.config))
+            .transpose()
+    }
+
+    /// The ranking controls of `find`/`search`. Weights without `--mode`
+    /// select hybrid ranking, the only mode they apply to.
+    pub fn search_scoring(&self) -> Result<client::SearchScoring> {
+        let options = &self.scoring;
+        let weights = [options.vector_weight, options.keyword_weight];
+        let weighted = weights.iter().any(Option::is_some);
+        let mode = match options.mode.as_deref() {
+            None if weighted => Some("hybrid"),
+            Some(mode) if weighted && mode != "hybrid" => {
+                return Err(Error::Client(format!(
+                    "--vector-weight and --keyword-weight apply to --mode hybrid, not {mode}"
+                )));
+            }
+            mode => mode,
+        };
+        if weights
+            .into_iter()
+            .flatten()
+            .any(|weight| weight.is_nan() || weight < 0.0)
+        {
+            return Err(Error::Client(
+                "Hybrid weights must be zero or more".to_string(),
+            ));
+        }
+        let rerank = match (options.rerank, options.no_rerank) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        };
+        Ok(client::SearchScoring {
+            mode: mode.map(str::to_string),
+            rerank,
+            vector_weight: options.vector_weight,
+            keyword_weight: options.keyword_weight,
+            include_scores: options.scores,
+            explain: options.explain,
+        })
+    }
+
     pub fn get_client(&self) -> client::HttpClient {
         self.get_client_with_timeout(None)
     }
@@ -104,6 +188,13 @@ impl CliContext {
             self.config.effective_extra_headers(),
         )
         .with_gateway_token(self.config.effective_gateway_token())
+        .with_proxy(self.config.proxy.clone())
+        .with_tls(self.config.tls_files())
+        .with_connect_timeout(self.config.connect_timeout())
+        .with_daemon(daemon::running_socket(&self.config.url))
+        .with_cassette(cassette::installed())
+        .with_resumable_uploads(self.resume_upload)
+        .with_upload_dedup(self.dedup_upload)
     }
 }
 
@@ -113,7 +204,7 @@ impl CliContext {
 #[command(version = env!("OPENVIKING_CLI_VERSION"))]
 #[command(arg_required_else_help = true)]
 struct Cli {
-    /// Choose human table output or machine-readable JSON
+    /// Choose human table output, machine-readable JSON, or line-delimited NDJSON
     #[arg(
         short,
         long,
@@ -121,7 +212,7 @@ struct Cli {
         default_value = "table",
         global = true,
         hide = true,
-        value_name = "table|json"
+        value_name = "table|json|ndjson"
     )]
     output: OutputFormat,
 
@@ -140,10 +231,129 @@ struct Cli {
     )]
     compact: bool,
 
+    /// Filter the response with a jq-style expression, e.g. '.[].uri'
+    #[arg(
+        long = "query",
+        id = "output_query",
+        global = true,
+        hide = true,
+        value_name = "expr"
+    )]
+    query: Option<String>,
+
+    /// Render each result through a template, e.g. '{{.uri}}\t{{.abstract}}'
+    #[arg(
+        long = "template",
+        id = "output_template",
+        global = true,
+        hide = true,
+        value_name = "template"
+    )]
+    template: Option<String>,
+
+    /// Show only these table columns, in this order (comma-separated)
+    #[arg(
+        long = "columns",
+        id = "table_columns",
+        global = true,
+        hide = true,
+        value_delimiter = ',',
+        value_name = "col,..."
+    )]
+    columns: Vec<String>,
+
+    /// Sort table rows by a column; prefix with '-' for descending
+    #[arg(
+        long = "sort",
+        id = "table_sort",
+        global = true,
+        hide = true,
+        allow_hyphen_values = true,
+        value_name = "[-]col"
+    )]
+    sort: Option<String>,
+
+    /// Never truncate or fit table cells to the terminal width
+    #[arg(
+        long = "wide",
+        visible_alias = "no-truncate",
+        id = "table_wide",
+        global = true,
+        hide = true
+    )]
+    wide: bool,
+
+    /// Wrap long table cells onto extra lines instead of truncating them
+    #[arg(
+        long = "wrap",
+        id = "table_wrap",
+        global = true,
+        hide = true,
+        conflicts_with = "table_wide"
+    )]
+    wrap: bool,
+
+    /// Disable colored output (NO_COLOR and non-TTY stdout also disable it)
+    #[arg(long = "no-color", global = true, hide = true)]
+    no_color: bool,
+
+    /// Never pipe long output through $PAGER
+    #[arg(long = "no-pager", global = true, hide = true)]
+    no_pager: bool,
+
+    /// Trace HTTP requests and responses to the log (stderr by default), with credentials redacted
+    #[arg(long = "debug-http", global = true, hide = true)]
+    debug_http: bool,
+
+    /// Print the latency of each API call and a summary when the command finishes
+    #[arg(long = "timing", global = true, hide = true)]
+    timing: bool,
+
+    /// Record CLI log events at this level and above
+    #[arg(
+        long = "log-level",
+        global = true,
+        hide = true,
+        value_parser = ["error", "warn", "info", "debug", "trace"],
+        value_name = "level"
+    )]
+    log_level: Option<String>,
+
+    /// Append log events to this file instead of stderr
+    #[arg(long = "log-file", global = true, hide = true, value_name = "path")]
+    log_file: Option<std::path::PathBuf>,
+
+    /// Log line format: human-readable text or one JSON object per line
+    #[arg(
+        long = "log-format",
+        global = true,
+        hide = true,
+        default_value = "text",
+        value_parser = ["text", "json"],
+        value_name = "text|json"
+    )]
+    log_format: String,
+
+    #[command(flatten)]
+    connection: ConnectionArgs,
+
+    #[command(flatten)]
+    offline: OfflineQueueArgs,
+
     /// Override X-OpenViking-Account for this command
     #[arg(long, global = true, hide = true)]
     account: Option<String>,
 
+    /// Scope this command to another workspace than the active config's
+    #[arg(
+        long,
+        global = true,
+        hide = true,
+        conflicts_with = "account",
+        value_name = "name"
+    )]
+    workspace: Option<String>,
+
     /// Override X-OpenViking-User for this command
     #[arg(long, global = true, hide = true)]
     user: Option<String>,
@@ -152,7 +362,7 @@ struct Cli {
     #[arg(long = "actor-peer-id", global = true, hide = true)]
     actor_peer_id: Option<String>,
 
-    /// Use root API key for admin, system, reindex, and task status/list commands
+    /// Use root API key for admin, system, reindex, index, and task status/list commands
     #[arg(long, global = true, hide = true)]
     sudo: bool,
 
@@ -176,6 +386,323 @@ struct Cli {
     command: Commands,
 }
 
+#[derive(Args, Debug, Clone, Default)]
+struct PackSignatureArgs {
+    /// Refuse packs without a valid `ov pack sign` signature
+    #[arg(long, help_heading = "Common options")]
+    require_signed: bool,
+
+    /// Only accept packs signed by this Ed25519 public key (file or base64; repeatable, implies --require-signed)
+    #[arg(
+        long = "trusted-key",
+        value_name = "key",
+        help_heading = "Common options"
+    )]
+    trusted_keys: Vec<String>,
+}
+
+#[derive(Args, Debug, Clone, Default)]
+struct PackCompressionArgs {
+    /// Member compression for the written pack (default: deflate, as sent by the server)
+    #[arg(
+        long,
+        value_parser = pack_compression::COMPRESSION_METHODS,
+        value_name = "method",
+        help_heading = "Common options"
+    )]
+    compression: Option<String>,
+
+    /// Compression level: 0-9 for deflate, 1-22 for zstd
+    #[arg(long, value_name = "n", help_heading = "Common options")]
+    level: Option<i64>,
+}
+
+impl PackCompressionArgs {
+    fn compression(&self) -> Result<Option<pack_compression::PackCompression>> {
+        pack_compression::PackCompression::new(self.compression.as_deref(), self.level)
+    }
+}
+
+#[derive(Args, Debug, Clone, Default)]
+struct PassphraseArgs {
+    /// Read the pack passphrase from stdin
+    #[arg(
+        long,
+        conflicts_with = "passphrase_env",
+        help_heading = "Common options"
+    )]
+    passphrase_stdin: bool,
+
+    /// Read the pack passphrase from this environment variable
+    #[arg(long, value_name = "env", help_heading = "Common options")]
+    passphrase_env: Option<String>,
+}
+
+impl PassphraseArgs {
+    fn read(&self) -> Result<Option<age::secrecy::SecretString>> {
+        pack_crypto::read_passphrase(self.passphrase_stdin, self.passphrase_env.as_deref())
+    }
+}
+
+#[derive(Args, Debug, Clone, Default)]
+struct PackEncryptArgs {
+    /// Encrypt the pack with age for --recipient keys or a passphrase
+    #[arg(long, help_heading = "Common options")]
+    encrypt: bool,
+
+    /// age recipient (age1...) or a file of recipients (repeatable, implies --encrypt)
+    #[arg(
+        long = "recipient",
+        value_name = "recipient",
+        conflicts_with_all = ["passphrase_stdin", "passphrase_env"],
+        help_heading = "Common options"
+    )]
+    recipients: Vec<String>,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+}
+
+impl PackEncryptArgs {
+    fn encryption(&self) -> Result<Option<pack_crypto::PackEncryption>> {
+        pack_crypto::PackEncryption::new(self.encrypt, &self.recipients, self.passphrase.read()?)
+    }
+}
+
+#[derive(Args, Debug, Clone, Default)]
+struct PackDecryptArgs {
+    /// age identity file for packs encrypted to a recipient (repeatable)
+    #[arg(
+        long = "identity",
+        value_name = "file",
+        help_heading = "Common options"
+    )]
+    identities: Vec<String>,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+}
+
+impl PackDecryptArgs {
+    fn decryption(&self) -> Result<pack_crypto::PackDecryption> {
+        Ok(pack_crypto::PackDecryption {
+            identity_files: self.identities.clone(),
+            passphrase: self.passphrase.read()?,
+        })
+    }
+}
+
+#[derive(Args, Debug, Clone, Default)]
+struct BackupCreateArgs {
+    /// Output .ovpack file path
+    // Optional only so `ov backup restore` can parse without it.
+    #[arg(value_name = "output.ovpack", required = true)]
+    to: Option<String>,
+    /// Include dense vector snapshot when compatible metadata is available
+    #[arg(long, default_value_t = false, help_heading = "Common options")]
+    include_vectors: bool,
+    /// Hide the download progress bar
+    #[arg(long = "no-progress", help_heading = "Advanced options")]
+    no_progress: bool,
+    #[command(flatten)]
+    compression: PackCompressionArgs,
+    #[command(flatten)]
+    encryption: PackEncryptArgs,
+}
+
+#[derive(Args, Debug, Clone, Default)]
+struct BackupRestoreArgs {
+    /// Input backup .ovpack file path
+    #[arg(value_name = "backup.ovpack")]
+    file_path: String,
+    /// Conflict policy: fail, overwrite, or skip
+    #[arg(
+        long,
+        value_parser = ["fail", "overwrite", "skip"],
+        value_name = "policy",
+        help_heading = "Common options"
+    )]
+    on_conflict: Option<String>,
+    /// Replace scopes that already exist (same as --on-conflict overwrite)
+    #[arg(long, conflicts_with = "on_conflict", help_heading = "Common options")]
+    overwrite: bool,
+    /// Show which scopes would be created, replaced, or skipped without restoring
+    #[arg(long, help_heading = "Common options")]
+    dry_run: bool,
+    /// Vector handling: auto restores compatible snapshots, recompute ignores them, require fails if unavailable
+    #[arg(
+        long,
+        value_parser = ["auto", "recompute", "require"],
+        value_name = "mode",
+        help_heading = "Common options"
+    )]
+    vector_mode: Option<String>,
+    /// Hide the upload progress bar
+    #[arg(long = "no-progress", help_heading = "Advanced options")]
+    no_progress: bool,
+    #[command(flatten)]
+    decryption: PackDecryptArgs,
+}
+
+#[derive(Args, Debug, Clone)]
+struct AddResourceArgs {
+    /// Local path or URL to import
+    #[arg(value_name = "path-or-url")]
+    path: String,
+    /// Exact target URI (must not exist yet) (cannot be used with --parent)
+    #[arg(long, value_name = "uri", help_heading = "Common options")]
+    to: Option<String>,
+    /// Target parent URI (must already exist and be a directory) (cannot be used with --to)
+    #[arg(long, value_name = "uri", help_heading = "Common options")]
+    parent: Option<String>,
+    /// Target parent URI (create parent directory if it does not exist) (cannot be used with --to or --parent)
+    #[arg(
+        short = 'p',
+        long = "parent-auto-create",
+        value_name = "uri",
+        help_heading = "Common options"
+    )]
+    parent_auto_create: Option<String>,
+    /// Reason for import
+    #[arg(
+        long,
+        default_value = "",
+        value_name = "text",
+        help_heading = "Advanced options"
+    )]
+    reason: String,
+    /// Additional instruction
+    #[arg(
+        long,
+        default_value = "",
+        value_name = "text",
+        help_heading = "Advanced options"
+    )]
+    instruction: String,
+    /// Wait until processing is complete
+    #[arg(long, help_heading = "Common options")]
+    wait: bool,
+    /// Wait timeout in seconds (only used with --wait)
+    #[arg(long, value_name = "seconds", help_heading = "Common options")]
+    timeout: Option<f64>,
+    /// Enable strict mode for directory scanning (fail if any unsupported files found)
+    #[arg(
+        long = "strict",
+        action = ArgAction::SetTrue,
+        help_heading = "Advanced options"
+    )]
+    strict_mode: bool,
+    /// Ignore directories, e.g. --ignore-dirs "node_modules,dist"
+    #[arg(long, value_name = "dirs", help_heading = "Advanced options")]
+    ignore_dirs: Option<String>,
+    /// Include files extensions, e.g. --include "*.pdf,*.md"
+    #[arg(long, value_name = "pattern", help_heading = "Common options")]
+    include: Option<String>,
+    /// Exclude files extensions, e.g. --exclude "*.tmp,*.log"
+    #[arg(long, value_name = "pattern", help_heading = "Common options")]
+    exclude: Option<String>,
+    /// Do not directly upload media files
+    #[arg(
+        long = "no-directly-upload-media",
+        default_value_t = false,
+        help_heading = "Advanced options"
+    )]
+    no_directly_upload_media: bool,
+    /// Watch interval in minutes for automatic resource monitoring (0 = no monitoring)
+    #[arg(
+        long,
+        default_value = "0",
+        value_name = "minutes",
+        help_heading = "Advanced options"
+    )]
+    watch_interval: f64,
+    /// Parser-specific import options, e.g. --args feishu_access_token:u-xxx
+    #[arg(long = "args")]
+    resource_args: Option<String>,
+    /// Upload a .zip/.tar/.tar.gz/.tgz as one file instead of adding the files it contains
+    #[arg(long, help_heading = "Advanced options")]
+    no_extract: bool,
+    /// Upload .ipynb notebooks as raw JSON instead of converting them to Markdown
+    #[arg(long, help_heading = "Advanced options")]
+    no_convert: bool,
+    /// Content type to upload a single file as, instead of the one detected from its bytes
+    #[arg(long, value_name = "mime", help_heading = "Advanced options")]
+    force_type: Option<String>,
+    #[command(flatten)]
+    upload_options: UploadCliOptions,
+    #[command(flatten)]
+    dedup_options: DedupCliOptions,
+    #[command(flatten)]
+    redact_options: RedactCliOptions,
+}
+
+#[derive(Args, Debug, Clone, Default)]
+struct SyncArgs {
+    /// Saved config to copy from (see `ov config list`)
+    #[arg(long, value_name = "profile", help_heading = "Common options")]
+    from: String,
+    /// Saved config to copy to
+    #[arg(long, value_name = "profile", help_heading = "Common options")]
+    to: String,
+    /// Subtree to sync, e.g. viking://resources/team
+    #[arg(long, value_name = "uri", help_heading = "Common options")]
+    prefix: String,
+    /// Show what would be copied without changing the target
+    #[arg(long, help_heading = "Common options")]
+    dry_run: bool,
+}
+
+#[derive(Args, Debug, Clone, Default)]
+struct WatchArgs {
+    /// Local directory to mirror
+    #[arg(value_name = "dir")]
+    dir: String,
+    /// Directory URI the files are mirrored to, e.g. viking://resources/notes
+    #[arg(long, value_name = "uri", help_heading = "Common options")]
+    target: String,
+    /// Milliseconds to wait for a burst of changes to settle before syncing
+    #[arg(
+        long,
+        default_value_t = 1000,
+        value_name = "ms",
+        help_heading = "Advanced options"
+    )]
+    debounce: u64,
+    /// State file recording what was mirrored (default: under ~/.openviking/watch/)
+    #[arg(long, value_name = "file", help_heading = "Advanced options")]
+    state: Option<String>,
+    /// Sync once and exit instead of watching
+    #[arg(long, help_heading = "Common options")]
+    once: bool,
+}
+
+#[derive(Args, Debug, Clone, Default)]
+struct RunArgs {
+    /// Script of ov commands, one per line
+    #[arg(value_name = "script")]
+    script: String,
+    /// Set a script variable, overriding its `set` line; repeatable
+    #[arg(
+        long = "var",
+        value_name = "name=value",
+        help_heading = "Common options"
+    )]
+    vars: Vec<String>,
+    /// Print the commands with their variables filled in, without running them
+    #[arg(long, help_heading = "Common options")]
+    dry_run: bool,
+}
+
+impl BackupRestoreArgs {
+    fn on_conflict(&self) -> Option<String> {
+        if self.overwrite {
+            Some("overwrite".to_string())
+        } else {
+            self.on_conflict.clone()
+        }
+    }
+}
+
 #[derive(Args, Debug, Clone, Copy, Default)]
 struct UploadCliOptions {
     /// Show local file upload progress (overrides config file)
@@ -197,6 +724,10 @@ struct UploadCliOptions {
     /// Print extra diagnostics during local file upload
     #[arg(short, long, help_heading = "Advanced options")]
     verbose: bool,
+
+    /// Continue an interrupted upload of this file from where it stopped
+    #[arg(long, help_heading = "Advanced options")]
+    resume: bool,
 }
 
 impl UploadCliOptions {
@@ -209,6 +740,7 @@ impl UploadCliOptions {
             progress: self.progress || (!self.no_progress && legacy.progress),
             no_progress: self.no_progress || (!self.progress && legacy.no_progress),
             verbose: self.verbose || legacy.verbose,
+            resume: self.resume,
         }
     }
 
@@ -227,10 +759,216 @@ impl UploadCliOptions {
     }
 }
 
+#[derive(Args, Debug, Clone, Default)]
+struct UriListArgs {
+    /// Also read URIs from a file, one per line ("-" for stdin)
+    #[arg(
+        long = "from-file",
+        value_name = "path",
+        help_heading = "Common options"
+    )]
+    from_file: Option<String>,
+}
+
+#[derive(Args, Debug, Clone, Copy, Default)]
+struct DedupCliOptions {
+    /// Upload even if the server already has a resource with identical content
+    #[arg(long = "no-dedup", help_heading = "Advanced options")]
+    no_dedup: bool,
+}
+
+#[derive(Args, Debug, Clone, Default)]
+struct RedactCliOptions {
+    /// Mask emails, phone numbers, ID numbers, and the config's redact_patterns in text before it is sent
+    #[arg(long, global = true, help_heading = "Common options")]
+    redact: bool,
+    /// Also mask matches of a regex under a name, e.g. ticket=TCK-\d+; repeatable, implies --redact
+    #[arg(
+        long = "redact-pattern",
+        value_name = "name=regex",
+        global = true,
+        help_heading = "Advanced options"
+    )]
+    redact_patterns: Vec<String>,
+}
+
+impl RedactCliOptions {
+    /// The redactor these options ask for, with the config's patterns
+    /// before the ones given here.
+    fn redactor(&self, config: &Config) -> Result<Option<redact::Redactor>> {
+        if !self.redact && self.redact_patterns.is_empty() {
+            return Ok(None);
+        }
+        let patterns: Vec<String> = config
+            .redact_patterns
+            .iter()
+            .map(|(name, regex)| format!("{name}={regex}"))
+            .chain(self.redact_patterns.iter().cloned())
+            .collect();
+        redact::Redactor::new(&patterns).map(Some)
+    }
+}
+
+#[derive(Args, Debug, Clone, Copy, Default)]
+struct TokenCliOptions {
+    /// Add a token count to each row, using the config's tokenizer
+    #[arg(long, help_heading = "Advanced options")]
+    tokens: bool,
+}
+
+#[derive(Args, Debug, Clone, Default)]
+struct FilterCliOptions {
+    /// Only include results created on or after this time (e.g. 7d, 2026-03-10, ISO-8601)
+    #[arg(
+        long,
+        value_name = "time",
+        conflicts_with_all = ["after", "before"],
+        help_heading = "Common options"
+    )]
+    created_after: Option<String>,
+    /// Only include results created on or before this time (e.g. 24h, 2026-03-15, ISO-8601)
+    #[arg(
+        long,
+        value_name = "time",
+        conflicts_with_all = ["after", "before"],
+        help_heading = "Common options"
+    )]
+    created_before: Option<String>,
+    /// Only include results of this kind (repeatable): doc, memory, skill
+    #[arg(
+        long = "type",
+        value_delimiter = ',',
+        value_parser = ["doc", "memory", "skill"],
+        value_name = "type",
+        help_heading = "Common options"
+    )]
+    types: Vec<String>,
+    /// Only include results with this explicit tag (repeatable; all must match)
+    #[arg(long = "tag", value_name = "k=v", help_heading = "Common options")]
+    tag: Vec<String>,
+}
+
+/// The server-side filters of `find`/`search` once `FilterCliOptions` are
+/// folded into the older `--after`/`--before`/`--context-type`/`--tags` flags.
+struct SearchFilters {
+    after: Option<String>,
+    before: Option<String>,
+    time_field: Option<String>,
+    context_type: Option<Vec<String>>,
+    tags: Option<Vec<String>>,
+}
+
+impl FilterCliOptions {
+    fn merge(
+        self,
+        after: Option<String>,
+        before: Option<String>,
+        context_type: Option<Vec<String>>,
+        tags: Option<Vec<String>>,
+    ) -> SearchFilters {
+        let by_creation = self.created_after.is_some() || self.created_before.is_some();
+        let extend = |base: Option<Vec<String>>, extra: Vec<String>| {
+            let mut values = base.unwrap_or_default();
+            for value in extra {
+                if !values.contains(&value) {
+                    values.push(value);
+                }
+            }
+            (!values.is_empty()).then_some(values)
+        };
+        // Resources are "doc" to users and "resource" to the server.
+        let types = self
+            .types
+            .into_iter()
+            .map(|kind| match kind.as_str() {
+                "doc" => "resource".to_string(),
+                _ => kind,
+            })
+            .collect();
+        SearchFilters {
+            after: self.created_after.or(after),
+            before: self.created_before.or(before),
+            time_field: by_creation.then(|| "created_at".to_string()),
+            context_type: extend(context_type, types),
+            tags: extend(tags, self.tag),
+        }
+    }
+}
+
+#[derive(Args, Debug, Clone, Default)]
+struct SnippetCliOptions {
+    /// Show the passages around query matches, with the matched terms highlighted
+    #[arg(long, help_heading = "Advanced options")]
+    snippets: bool,
+}
+
+#[derive(Args, Debug, Clone, Default)]
+struct ScoringCliOptions {
+    /// Rank by embedding similarity, exact query terms, or a blend of both
+    #[arg(
+        long,
+        value_parser = ["vector", "keyword", "hybrid"],
+        value_name = "mode",
+        help_heading = "Advanced options"
+    )]
+    mode: Option<String>,
+    /// Rerank candidates with the server's rerank model
+    #[arg(long, overrides_with = "no_rerank", help_heading = "Advanced options")]
+    rerank: bool,
+    /// Skip the rerank model even when the server would use it
+    #[arg(
+        long = "no-rerank",
+        overrides_with = "rerank",
+        help_heading = "Advanced options"
+    )]
+    no_rerank: bool,
+    /// Weight of the vector score in hybrid ranking (default 0.5)
+    #[arg(
+        long = "vector-weight",
+        value_name = "w",
+        help_heading = "Advanced options"
+    )]
+    vector_weight: Option<f64>,
+    /// Weight of the keyword score in hybrid ranking (default 0.5)
+    #[arg(
+        long = "keyword-weight",
+        value_name = "w",
+        help_heading = "Advanced options"
+    )]
+    keyword_weight: Option<f64>,
+    /// Show each strategy's score (vector, keyword, rerank) for every result
+    #[arg(long, help_heading = "Advanced options")]
+    scores: bool,
+    /// Explain the ranking: intent analysis, query rewrites, candidates per stage and scores
+    #[arg(long, help_heading = "Advanced options")]
+    explain: bool,
+}
+
 impl CliContext {
     fn with_upload_options(mut self, options: UploadCliOptions) -> Self {
         self.show_progress = options.show_progress_override();
         self.verbose = options.verbose_override();
+        self.resume_upload = options.resume;
+        self
+    }
+
+    fn with_dedup_options(mut self, options: DedupCliOptions) -> Self {
+        self.dedup_upload = !options.no_dedup;
+        self
+    }
+
+    fn with_token_options(mut self, options: TokenCliOptions) -> Self {
+        self.count_tokens = options.tokens;
+        self
+    }
+
+    fn with_scoring_options(mut self, options: ScoringCliOptions) -> Self {
+        self.scoring = options;
+        self
+    }
+
+    fn with_snippet_options(mut self, options: SnippetCliOptions) -> Self {
+        self.snippets = options.snippets;
         self
     }
 }
@@ -262,6 +1000,229 @@ enum AttrsCommands {
     },
 }
 
+#[derive(Subcommand)]
+enum TagCommands {
+    /// Add tags to a URI; a tag replaces any existing value for its key
+    Add {
+        /// Viking URI
+        #[arg(value_name = "uri")]
+        uri: String,
+        /// Tags in k=v form, e.g. team=search
+        #[arg(value_name = "k=v", required = true)]
+        tags: Vec<String>,
+        /// Also tag descendant files and semantic nodes when the URI is a directory
+        #[arg(short, long, help_heading = "Common options")]
+        recursive: bool,
+    },
+    /// Remove tags from a URI; a bare key removes every value for it
+    Rm {
+        /// Viking URI
+        #[arg(value_name = "uri")]
+        uri: String,
+        /// Tags in k=v form, or bare keys
+        #[arg(value_name = "k[=v]", required = true)]
+        tags: Vec<String>,
+    },
+    /// List the tags on a URI
+    Ls {
+        /// Viking URI
+        #[arg(value_name = "uri")]
+        uri: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum RelationsCommands {
+    /// Walk outgoing links from a URI and print the graph for rendering
+    Graph {
+        /// Viking URI to start from
+        #[arg(value_name = "uri")]
+        uri: String,
+        /// Number of link hops to follow
+        #[arg(
+            long,
+            default_value = "3",
+            value_parser = clap::value_parser!(u32).range(1..=10),
+            value_name = "n",
+            help_heading = "Common options"
+        )]
+        depth: u32,
+        /// Graph description format
+        #[arg(
+            long = "format",
+            value_parser = ["dot", "mermaid", "graphml"],
+            default_value = "dot",
+            value_name = "dot|mermaid|graphml",
+            help_heading = "Common options"
+        )]
+        format: String,
+    },
+    /// List everything linked from a URI within N hops, nearest first
+    Neighbors {
+        /// Viking URI to start from
+        #[arg(value_name = "uri")]
+        uri: String,
+        /// Number of link hops to follow
+        #[arg(
+            long,
+            default_value = "1",
+            value_parser = clap::value_parser!(u32).range(1..=10),
+            value_name = "n",
+            help_heading = "Common options"
+        )]
+        depth: u32,
+        /// Only follow links whose reason matches this regex
+        #[arg(long, value_name = "regex", help_heading = "Common options")]
+        reason_filter: Option<String>,
+    },
+    /// Show the shortest chain of links from one URI to another
+    Path {
+        /// Viking URI to start from
+        #[arg(value_name = "from-uri")]
+        from_uri: String,
+        /// Viking URI to reach
+        #[arg(value_name = "to-uri")]
+        to_uri: String,
+        /// Maximum number of link hops to search
+        #[arg(
+            long,
+            default_value = "6",
+            value_parser = clap::value_parser!(u32).range(1..=10),
+            value_name = "n",
+            help_heading = "Common options"
+        )]
+        depth: u32,
+    },
+    /// Create links in bulk from a CSV (from,to,reason columns) or JSONL file
+    Import {
+        /// CSV or JSONL file of edges
+        #[arg(value_name = "file")]
+        file: String,
+        /// Input format; inferred from the file extension when omitted
+        #[arg(
+            long = "format",
+            value_parser = ["csv", "jsonl"],
+            value_name = "csv|jsonl",
+            help_heading = "Common options"
+        )]
+        format: Option<String>,
+        /// Validate and show the planned links without creating them
+        #[arg(long, help_heading = "Common options")]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum MetaCommands {
+    /// Set metadata keys on a URI; values are parsed as JSON, else kept as strings
+    Set {
+        /// Viking URI
+        #[arg(value_name = "uri")]
+        uri: String,
+        /// Metadata in key=value form, e.g. source=crm batch=42
+        #[arg(value_name = "key=value", required = true)]
+        pairs: Vec<String>,
+    },
+    /// Show the metadata on a URI, or the value of one key
+    Get {
+        /// Viking URI
+        #[arg(value_name = "uri")]
+        uri: String,
+        /// Only print the value of this key
+        #[arg(value_name = "key")]
+        key: Option<String>,
+    },
+    /// Remove metadata keys from a URI
+    Rm {
+        /// Viking URI
+        #[arg(value_name = "uri")]
+        uri: String,
+        /// Keys to remove
+        #[arg(value_name = "key", required = true)]
+        keys: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum AliasCommands {
+    /// Save a short name for a URI, usable as @name in any command
+    Set {
+        /// Alias name
+        #[arg(value_name = "name")]
+        name: String,
+        /// Viking URI the name stands for
+        #[arg(value_name = "uri")]
+        uri: String,
+    },
+    /// List aliases
+    Ls,
+    /// Remove an alias
+    Rm {
+        /// Alias name
+        #[arg(value_name = "name")]
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum BookmarkCommands {
+    /// Bookmark a document, named after its file unless --name is given
+    Add {
+        /// Viking URI to bookmark
+        #[arg(value_name = "uri")]
+        uri: String,
+        /// Name to use as @name; defaults to the file name without its extension
+        #[arg(long, value_name = "name", help_heading = "Common options")]
+        name: Option<String>,
+    },
+    /// List bookmarks
+    Ls,
+    /// Remove a bookmark
+    Rm {
+        /// Bookmark name
+        #[arg(value_name = "name")]
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum WorkspaceCommands {
+    /// List workspaces: the server's with a root key, else those of saved configs
+    #[command(alias = "ls")]
+    List,
+    /// Make a workspace the default of the active config
+    Use {
+        /// Workspace (account) name
+        #[arg(value_name = "name")]
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuditCommands {
+    /// Show the newest audit events; with --follow, keep printing new ones
+    Tail {
+        /// Only requests made by this user
+        #[arg(long, value_name = "user")]
+        user: Option<String>,
+        /// Only resources and skills added, DELETE requests, or searches
+        #[arg(long, value_parser = ["add", "delete", "search"], value_name = "add|delete|search")]
+        action: Option<String>,
+        /// Only events newer than this: an age (30m, 1h, 7d), a date, or an ISO-8601 time
+        #[arg(long, value_name = "age|time")]
+        since: Option<String>,
+        /// Number of events to show
+        #[arg(short = 'n', long = "lines", default_value = "20", value_name = "n")]
+        lines: usize,
+        /// Keep polling and print new events as they arrive
+        #[arg(short = 'f', long)]
+        follow: bool,
+        /// Seconds between polls with --follow
+        #[arg(long, default_value = "5", value_name = "seconds")]
+        interval: f64,
+    },
+}
+
 // Commands are organized with category tags in their doc comments.
 //
 // # Command Tagging System
@@ -278,182 +1239,32 @@ enum AttrsCommands {
 enum Commands {
     // --- Data Operations ---
     /// [Data] Add resources into OpenViking
-    AddResource {
-        /// Local path or URL to import
-        #[arg(value_name = "path-or-url")]
-        path: String,
-        /// Exact target URI (must not exist yet) (cannot be used with --parent)
-        #[arg(long, value_name = "uri", help_heading = "Common options")]
-        to: Option<String>,
-        /// Target parent URI (must already exist and be a directory) (cannot be used with --to)
-        #[arg(long, value_name = "uri", help_heading = "Common options")]
-        parent: Option<String>,
-        /// Target parent URI (create parent directory if it does not exist) (cannot be used with --to or --parent)
-        #[arg(
-            short = 'p',
-            long = "parent-auto-create",
-            value_name = "uri",
-            help_heading = "Common options"
-        )]
-        parent_auto_create: Option<String>,
-        /// Reason for import
-        #[arg(
-            long,
-            default_value = "",
-            value_name = "text",
-            help_heading = "Advanced options"
-        )]
-        reason: String,
-        /// Additional instruction
-        #[arg(
-            long,
-            default_value = "",
-            value_name = "text",
-            help_heading = "Advanced options"
-        )]
-        instruction: String,
-        /// Wait until processing is complete
-        #[arg(long, help_heading = "Common options")]
-        wait: bool,
-        /// Wait timeout in seconds (only used with --wait)
-        #[arg(long, value_name = "seconds", help_heading = "Common options")]
-        timeout: Option<f64>,
-        /// Enable strict mode for directory scanning (fail if any unsupported files found)
-        #[arg(
-            long = "strict",
-            action = ArgAction::SetTrue,
-            help_heading = "Advanced options"
-        )]
-        strict_mode: bool,
-        /// Ignore directories, e.g. --ignore-dirs "node_modules,dist"
-        #[arg(long, value_name = "dirs", help_heading = "Advanced options")]
-        ignore_dirs: Option<String>,
-        /// Include files extensions, e.g. --include "*.pdf,*.md"
-        #[arg(long, value_name = "pattern", help_heading = "Common options")]
-        include: Option<String>,
-        /// Exclude files extensions, e.g. --exclude "*.tmp,*.log"
-        #[arg(long, value_name = "pattern", help_heading = "Common options")]
-        exclude: Option<String>,
-        /// Do not directly upload media files
-        #[arg(
-            long = "no-directly-upload-media",
-            default_value_t = false,
-            help_heading = "Advanced options"
-        )]
-        no_directly_upload_media: bool,
-        /// Watch interval in minutes for automatic resource monitoring (0 = no monitoring)
-        #[arg(
-            long,
-            default_value = "0",
-            value_name = "minutes",
-            help_heading = "Advanced options"
-        )]
-        watch_interval: f64,
-        /// Parser-specific import options, e.g. --args feishu_access_token:u-xxx
-        #[arg(long = "args")]
-        resource_args: Option<String>,
-        #[command(flatten)]
-        upload_options: UploadCliOptions,
-    },
+    AddResource(Box<AddResourceArgs>),
     /// [Data] Add a skill into OpenViking
-    AddSkill {
-        /// Skill directory, SKILL.md, or raw content
-        #[arg(value_name = "skill-path-or-content")]
-        data: String,
-        /// Wait until processing is complete
-        #[arg(long, help_heading = "Common options")]
-        wait: bool,
-        /// Wait timeout in seconds
-        #[arg(long, value_name = "seconds", help_heading = "Common options")]
-        timeout: Option<f64>,
-        /// Parent skill root URI (e.g. viking://agent/skills); defaults to user-private skills
-        #[arg(
-            short = 'p',
-            long = "parent-auto-create",
-            value_name = "uri",
-            help_heading = "Skill options"
-        )]
-        parent: Option<String>,
+    AddSkill(Box<AddSkillArgs>),
+    /// [Data] Add resources from object storage, stdin, the web, or tabular files
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    Add {
+        #[command(subcommand)]
+        action: Option<AddCommands>,
         #[command(flatten)]
-        upload_options: UploadCliOptions,
+        objects: AddObjectsArgs,
+        #[command(flatten)]
+        redact_options: RedactCliOptions,
     },
+    /// [Data] Add the clipboard's text or image to an inbox and print its URI
+    Clip(ClipArgs),
     /// [Data] Manage installed skills
+    #[command(visible_alias = "skill")]
     Skills {
         #[command(subcommand)]
         action: SkillCommands,
     },
     /// [Data] List directory contents
     #[command(alias = "list")]
-    Ls {
-        /// Viking URI to list (default: viking://)
-        #[arg(default_value = "viking://", value_name = "uri")]
-        uri: String,
-        /// Simple path output (just paths, no table)
-        #[arg(short, long, help_heading = "Common options")]
-        simple: bool,
-        /// List all subdirectories recursively
-        #[arg(short, long, help_heading = "Common options")]
-        recursive: bool,
-        /// Abstract content limit (only for agent output)
-        #[arg(
-            long = "abs-limit",
-            short = 'l',
-            default_value = "256",
-            value_name = "n",
-            help_heading = "Advanced options"
-        )]
-        abs_limit: i32,
-        /// Show all hidden files
-        #[arg(short, long, help_heading = "Common options")]
-        all: bool,
-        /// Maximum number of nodes to list
-        #[arg(
-            long = "node-limit",
-            short = 'n',
-            alias = "limit",
-            default_value = "256",
-            value_name = "n",
-            help_heading = "Common options"
-        )]
-        node_limit: i32,
-    },
+    Ls(Box<LsArgs>),
     /// [Data] Get directory tree
-    Tree {
-        /// Viking URI to get tree for
-        #[arg(value_name = "uri")]
-        uri: String,
-        /// Abstract content limit (only for agent output)
-        #[arg(
-            long = "abs-limit",
-            short = 'l',
-            default_value = "128",
-            value_name = "n",
-            help_heading = "Advanced options"
-        )]
-        abs_limit: i32,
-        /// Show all hidden files
-        #[arg(short, long, help_heading = "Common options")]
-        all: bool,
-        /// Maximum number of nodes to list
-        #[arg(
-            long = "node-limit",
-            short = 'n',
-            alias = "limit",
-            default_value = "256",
-            value_name = "n",
-            help_heading = "Common options"
-        )]
-        node_limit: i32,
-        /// Maximum depth level to traverse (default: 3)
-        #[arg(
-            short = 'L',
-            long = "level-limit",
-            default_value = "3",
-            value_name = "n",
-            help_heading = "Common options"
-        )]
-        level_limit: i32,
-    },
+    Tree(Box<TreeArgs>),
     /// [Data] Create directory
     Mkdir {
         /// Directory URI to create
@@ -465,20 +1276,7 @@ enum Commands {
     },
     /// [Data] Remove resource
     #[command(alias = "del", alias = "delete")]
-    Rm {
-        /// Viking URI to remove
-        #[arg(value_name = "uri")]
-        uri: String,
-        /// Remove recursively
-        #[arg(short, long, help_heading = "Common options")]
-        recursive: bool,
-        /// Wait until semantic refresh is complete
-        #[arg(long, help_heading = "Common options")]
-        wait: bool,
-        /// Wait timeout in seconds (only used with --wait)
-        #[arg(long, value_name = "seconds", help_heading = "Common options")]
-        timeout: Option<f64>,
-    },
+    Rm(Box<RmArgs>),
     /// [Data] Move or rename resource
     #[command(alias = "rename")]
     Mv {
@@ -489,6 +1287,9 @@ enum Commands {
         #[arg(value_name = "to-uri")]
         to_uri: String,
     },
+    /// [Data] Copy a resource or subtree
+    #[command(alias = "copy")]
+    Cp(Box<CpArgs>),
     /// [Data] Get resource metadata
     Stat {
         /// Viking URI to get metadata for
@@ -502,78 +1303,52 @@ enum Commands {
     },
     /// [Data] Read file content (Level 2)
     Read {
-        /// Viking URI
-        #[arg(value_name = "uri")]
-        uri: String,
+        /// Viking URIs; several are fetched concurrently
+        #[arg(value_name = "uri", required_unless_present = "from_file")]
+        uris: Vec<String>,
+        /// Render markdown with terminal styling (raw text when piped)
+        #[arg(long, help_heading = "Common options")]
+        render: bool,
+        #[command(flatten)]
+        uri_list: UriListArgs,
     },
     /// [Data] Read abstract content (Level 0)
     Abstract {
-        /// Directory URI
-        #[arg(value_name = "directory-uri")]
-        uri: String,
+        /// Directory URIs; several are fetched concurrently
+        #[arg(value_name = "directory-uri", required_unless_present = "from_file")]
+        uris: Vec<String>,
+        #[command(flatten)]
+        uri_list: UriListArgs,
     },
     /// [Data] Read overview content (Level 1)
     Overview {
-        /// Directory URI
-        #[arg(value_name = "directory-uri")]
-        uri: String,
+        /// Directory URIs; several are fetched concurrently
+        #[arg(value_name = "directory-uri", required_unless_present = "from_file")]
+        uris: Vec<String>,
+        #[command(flatten)]
+        uri_list: UriListArgs,
+    },
+    /// [Data] Write text content to a file, creating it if missing
+    Write(Box<WriteArgs>),
+    /// [Data] Append text content to a file, creating it if missing
+    Append(Box<AppendArgs>),
+    /// [Data] Edit a file's content in $EDITOR and write it back on save
+    Edit(Box<EditArgs>),
+    /// [Data] Update explicit retrieval tags metadata for a file or directory
+    #[command(hide = true)]
+    SetTags(Box<SetTagsArgs>),
+    /// [Data] Add, remove, and list explicit k=v retrieval tags on a URI
+    Tag {
+        #[command(subcommand)]
+        action: TagCommands,
+    },
+    /// [Data] Set, get, and remove free-form key=value metadata on a URI
+    Meta {
+        #[command(subcommand)]
+        action: MetaCommands,
     },
-    /// [Data] Write text content to an existing file
-    Write {
-        /// Viking URI
-        #[arg(value_name = "uri")]
-        uri: String,
-        /// Content to write
-        #[arg(
-            long,
-            conflicts_with = "from_file",
-            value_name = "text",
-            help_heading = "Common options"
-        )]
-        content: Option<String>,
-        /// Read content from a local file
-        #[arg(
-            long = "from-file",
-            conflicts_with = "content",
-            value_name = "path",
-            help_heading = "Common options"
-        )]
-        from_file: Option<String>,
-        /// Append instead of replacing the file
-        #[arg(long, help_heading = "Common options")]
-        append: bool,
-        /// Write mode: replace, append, or create (default: replace)
-        #[arg(
-            long,
-            value_name = "replace|append|create",
-            conflicts_with = "append",
-            help_heading = "Advanced options"
-        )]
-        mode: Option<String>,
-        /// Wait for async processing to finish
-        #[arg(long, default_value = "false", help_heading = "Common options")]
-        wait: bool,
-        /// Optional wait timeout in seconds
-        #[arg(long, value_name = "seconds", help_heading = "Common options")]
-        timeout: Option<f64>,
-    },
-    /// [Data] Update explicit retrieval tags metadata for a file or directory
-    #[command(hide = true)]
-    SetTags {
-        /// Viking URI
-        uri: String,
-        /// Comma-separated k=v tags, e.g. env=prod,team=search
-        #[arg(long = "tags", value_delimiter = ',')]
-        tags: Vec<String>,
-        /// Tag update mode: replace or append (append replaces existing values by key)
-        #[arg(long, default_value = "replace")]
-        mode: String,
-        /// Recursively update descendant files and semantic nodes when target is a directory
-        #[arg(long, default_value = "false")]
-        recursive: bool,
-    },
-    /// [Data] Download file to local path (supports binaries/images)
-    Get {
+    /// [Data] Download file to local path (supports binaries/images)
+    Get {
         /// Viking URI
         #[arg(value_name = "uri")]
         uri: String,
@@ -581,184 +1356,25 @@ enum Commands {
         #[arg(value_name = "local-path")]
         local_path: String,
     },
-    /// [Data] Run semantic retrieval
-    Find {
-        /// Search query
-        #[arg(value_name = "query")]
-        query: Option<String>,
-        /// Image query: local path, data URI, HTTP URL, or viking:// URI
-        #[arg(
-            long = "image",
-            value_name = "path|uri",
-            help_heading = "Common options"
-        )]
-        image: Option<String>,
-        /// Target URI
-        #[arg(
-            short,
-            long,
-            default_value = "",
-            value_name = "uri",
-            help_heading = "Common options"
-        )]
-        uri: String,
-        /// Maximum final results returned
-        #[arg(
-            short = 'n',
-            long = "node-limit",
-            alias = "limit",
-            default_value = "10",
-            value_name = "n",
-            help_heading = "Common options"
-        )]
-        node_limit: i32,
-        /// Score threshold
-        #[arg(short, long, value_name = "score", help_heading = "Common options")]
-        threshold: Option<f64>,
-        /// Only include results on or after this time (e.g. 48h, 7d, 2026-03-10, ISO-8601)
-        #[arg(long = "after", value_name = "time", help_heading = "Advanced options")]
-        after: Option<String>,
-        /// Only include results on or before this time (e.g. 24h, 2026-03-15, ISO-8601)
-        #[arg(
-            long = "before",
-            value_name = "time",
-            help_heading = "Advanced options"
-        )]
-        before: Option<String>,
-        /// Only include results with specific level(s) (0=abstract, 1=overview, 2=file)
-        #[arg(
-            short = 'L',
-            long = "level",
-            value_delimiter = ',',
-            value_name = "0,1,2",
-            help_heading = "Common options"
-        )]
-        level: Option<Vec<i32>>,
-        /// Only include results with specific context type(s) (memory, resource, skill)
-        #[arg(
-            long = "context-type",
-            value_delimiter = ',',
-            value_name = "type",
-            help_heading = "Common options"
-        )]
-        context_type: Option<Vec<String>>,
-        /// Only include results matching all of these explicit tags
-        #[arg(long = "tags", value_delimiter = ',')]
-        tags: Option<Vec<String>>,
+    /// [Data] Name a URI so that @name can be typed in its place
+    Alias {
+        #[command(subcommand)]
+        action: AliasCommands,
     },
-    /// [Experimental][Data] Run context-aware retrieval
-    Search {
-        /// Search query
-        #[arg(value_name = "query")]
-        query: Option<String>,
-        /// Image query: local path, data URI, HTTP URL, or viking:// URI
-        #[arg(
-            long = "image",
-            value_name = "path|uri",
-            help_heading = "Common options"
-        )]
-        image: Option<String>,
-        /// Target URI
-        #[arg(
-            short,
-            long,
-            default_value = "",
-            value_name = "uri",
-            help_heading = "Common options"
-        )]
-        uri: String,
-        /// Session ID for context-aware search
-        #[arg(long, value_name = "id", help_heading = "Common options")]
-        session_id: Option<String>,
-        /// Maximum results per search pass. Search may merge multiple passes.
-        #[arg(
-            short = 'n',
-            long = "node-limit",
-            alias = "limit",
-            default_value = "10",
-            value_name = "n",
-            help_heading = "Common options"
-        )]
-        node_limit: i32,
-        /// Score threshold
-        #[arg(short, long, value_name = "score", help_heading = "Advanced options")]
-        threshold: Option<f64>,
-        /// Only include results on or after this time (e.g. 48h, 7d, 2026-03-10, ISO-8601)
-        #[arg(long = "after", value_name = "time", help_heading = "Advanced options")]
-        after: Option<String>,
-        /// Only include results on or before this time (e.g. 24h, 2026-03-15, ISO-8601)
-        #[arg(
-            long = "before",
-            value_name = "time",
-            help_heading = "Advanced options"
-        )]
-        before: Option<String>,
-        /// Only include results with specific level(s) (0=abstract, 1=overview, 2=file)
-        #[arg(
-            short = 'L',
-            long = "level",
-            value_delimiter = ',',
-            value_name = "0,1,2",
-            help_heading = "Advanced options"
-        )]
-        level: Option<Vec<i32>>,
-        /// Only include results with specific context type(s) (memory, resource, skill)
-        #[arg(
-            long = "context-type",
-            value_delimiter = ',',
-            value_name = "type",
-            help_heading = "Advanced options"
-        )]
-        context_type: Option<Vec<String>>,
-        /// Only include results matching all of these explicit tags
-        #[arg(long = "tags", value_delimiter = ',')]
-        tags: Option<Vec<String>>,
+    /// [Data] Keep frequently read documents under short @names
+    Bookmark {
+        #[command(subcommand)]
+        action: BookmarkCommands,
     },
+    /// [Data] Show recently used URIs and commands from the local history
+    Recent(Box<RecentArgs>),
+    /// [Data] Run semantic retrieval
+    Find(Box<FindArgs>),
+    /// [Experimental][Data] Run context-aware retrieval
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    Search(Box<SearchArgs>),
     /// [Data] Run content pattern search
-    Grep {
-        /// Target URI
-        #[arg(
-            short,
-            long,
-            default_value = "viking://",
-            value_name = "uri",
-            help_heading = "Common options"
-        )]
-        uri: String,
-        /// Excluded URI range. Any entry whose URI falls under this URI prefix is skipped
-        #[arg(
-            short = 'x',
-            long = "exclude-uri",
-            value_name = "uri",
-            help_heading = "Advanced options"
-        )]
-        exclude_uri: Option<String>,
-        /// Search pattern
-        #[arg(value_name = "pattern")]
-        pattern: String,
-        /// Case insensitive
-        #[arg(short, long, help_heading = "Common options")]
-        ignore_case: bool,
-        /// Maximum number of results
-        #[arg(
-            short = 'n',
-            long = "node-limit",
-            alias = "limit",
-            default_value = "256",
-            value_name = "n",
-            help_heading = "Common options"
-        )]
-        node_limit: i32,
-        /// Maximum depth level to traverse (default: 10)
-        #[arg(
-            short = 'L',
-            long = "level-limit",
-            default_value = "10",
-            value_name = "n",
-            help_heading = "Advanced options"
-        )]
-        level_limit: i32,
-    },
+    Grep(Box<GrepArgs>),
     /// [Data] Run file glob pattern search
     Glob {
         /// Glob pattern
@@ -784,11 +1400,22 @@ enum Commands {
         )]
         node_limit: i32,
     },
+    /// [Data] Retrieve and pack context for a query within a token budget
+    Context(ContextArgs),
+    /// [Data] Count the tokens content will cost an LLM
+    Tokens(TokensArgs),
+    /// [Data] Answer a question from retrieved context with the configured LLM
+    Ask(AskArgs),
     /// [Data] Session management commands
     Session {
         #[command(subcommand)]
         action: SessionCommands,
     },
+    /// [Data] Inspect extracted long-term memories
+    Memory {
+        #[command(subcommand)]
+        action: MemoryCommands,
+    },
     /// [Experimental][Data] Add memory in one shot (creates session, adds messages, commits)
     AddMemory {
         /// Content to memorize. Plain string (treated as user message),
@@ -803,10 +1430,13 @@ enum Commands {
         action: PrivacyCommands,
     },
     /// [Experimental][Data] List relations of a resource
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
     Relations {
+        #[command(subcommand)]
+        action: Option<RelationsCommands>,
         /// Viking URI
-        #[arg(value_name = "uri")]
-        uri: String,
+        #[arg(value_name = "uri", required = true)]
+        uri: Option<String>,
     },
     /// [Experimental][Data] Create relation links from one URI to one or more targets
     Link {
@@ -835,73 +1465,45 @@ enum Commands {
         to_uri: String,
     },
     /// [Data] Export context as .ovpack
-    Export {
-        /// Source URI
-        #[arg(value_name = "uri")]
-        uri: String,
-        /// Output .ovpack file path
-        #[arg(value_name = "output.ovpack")]
-        to: String,
-        /// Include dense vector snapshot when compatible metadata is available
-        #[arg(long, default_value_t = false, help_heading = "Common options")]
-        include_vectors: bool,
-    },
-    /// [Data] Back up public OpenViking scopes as a restore-only .ovpack
+    Export(Box<ExportArgs>),
+    /// [Data] Back up or restore resources, memories, sessions, and relations
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
     Backup {
-        /// Output .ovpack file path
-        #[arg(value_name = "output.ovpack")]
-        to: String,
-        /// Include dense vector snapshot when compatible metadata is available
-        #[arg(long, default_value_t = false, help_heading = "Common options")]
-        include_vectors: bool,
+        #[command(subcommand)]
+        action: Option<BackupCommands>,
+        #[command(flatten)]
+        create: BackupCreateArgs,
     },
-    /// [Data] Import .ovpack into target URI
-    Import {
-        /// Input .ovpack file path
-        #[arg(value_name = "file.ovpack")]
-        file_path: String,
-        /// Target parent URI
-        #[arg(value_name = "target-uri")]
-        target_uri: String,
-        /// Conflict policy: fail, overwrite, or skip
-        #[arg(
-            long,
-            value_parser = ["fail", "overwrite", "skip"],
-            value_name = "policy",
-            help_heading = "Common options"
-        )]
-        on_conflict: Option<String>,
-        /// Vector handling: auto restores compatible snapshots, recompute ignores them, require fails if unavailable
-        #[arg(
-            long,
-            value_parser = ["auto", "recompute", "require"],
-            value_name = "mode",
-            help_heading = "Common options"
-        )]
-        vector_mode: Option<String>,
+    /// [Data] Import .ovpack into target URI, or documents from an MCP server, Notion export, or mailbox
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    Import(Box<ImportArgs>),
+    /// [Data] Inspect, build, sign, and diff .ovpack archives locally
+    Pack {
+        #[command(subcommand)]
+        action: PackCommands,
     },
     /// [Data] Restore a backup .ovpack to original public scope roots
     Restore {
-        /// Input backup .ovpack file path
-        #[arg(value_name = "backup.ovpack")]
-        file_path: String,
-        /// Conflict policy: fail, overwrite, or skip
-        #[arg(
-            long,
-            value_parser = ["fail", "overwrite", "skip"],
-            value_name = "policy",
-            help_heading = "Common options"
-        )]
-        on_conflict: Option<String>,
-        /// Vector handling: auto restores compatible snapshots, recompute ignores them, require fails if unavailable
-        #[arg(
-            long,
-            value_parser = ["auto", "recompute", "require"],
-            value_name = "mode",
-            help_heading = "Common options"
-        )]
-        vector_mode: Option<String>,
+        #[command(flatten)]
+        restore: BackupRestoreArgs,
+    },
+    /// [Data] Copy new and changed entries between two servers
+    Sync {
+        #[command(flatten)]
+        sync: SyncArgs,
     },
+    /// [Data] Mirror a local directory to the server as its files change
+    Watch {
+        #[command(flatten)]
+        watch: WatchArgs,
+    },
+    /// [Data] Replay offline writes and check the server's parse and vectorization queues
+    Queue {
+        #[command(subcommand)]
+        action: QueueCommands,
+    },
+    /// [Data] Run a script of ov commands, one per line, in a single process
+    Run(RunArgs),
     // --- Interactive Tools ---
     /// [Interactive] Interactive TUI file explorer
     Tui {
@@ -909,37 +1511,35 @@ enum Commands {
         #[arg(default_value = "/", value_name = "uri")]
         uri: String,
     },
-    /// [Interactive] Chat with vikingbot agent
-    Chat {
-        /// Message to send to the agent
-        #[arg(short, long, value_name = "text", help_heading = "Common options")]
-        message: Option<String>,
-        /// Session ID (defaults to machine unique ID)
-        #[arg(short, long, value_name = "id", help_heading = "Common options")]
-        session: Option<String>,
-        /// Sender ID
-        #[arg(
-            long,
-            default_value = "user",
-            value_name = "id",
-            help_heading = "Advanced options"
-        )]
-        sender: String,
-        /// Stream the response (default: true)
+    /// [Interactive] Choose a URI with a fuzzy finder and print it
+    Pick {
+        /// Viking URI to list from
+        #[arg(default_value = "viking://", value_name = "prefix")]
+        prefix: String,
+        /// Text to start filtering with (also accepted as --query)
+        #[arg(value_name = "query")]
+        query: Option<String>,
+        /// Maximum number of URIs to load
         #[arg(
-            long,
-            default_value_t = true,
-            value_name = "bool",
+            long = "node-limit",
+            short = 'n',
+            default_value = "10000",
+            value_name = "n",
             help_heading = "Advanced options"
         )]
-        stream: bool,
-        /// Disable rich formatting / markdown rendering
-        #[arg(long, help_heading = "Common options")]
-        no_format: bool,
-        /// Disable command history
-        #[arg(long, help_heading = "Advanced options")]
-        no_history: bool,
+        node_limit: i32,
+    },
+    /// [Interactive] Watch queue depth, throughput, index lag, search latency, and errors live
+    Top {
+        /// Only count queue items under this URI
+        #[arg(long, value_name = "uri")]
+        uri: Option<String>,
+        /// Seconds between refreshes
+        #[arg(long, default_value = "2", value_name = "seconds")]
+        interval: f64,
     },
+    /// [Interactive] Chat with vikingbot agent
+    Chat(Box<ChatArgs>),
 
     // --- Status & Observability ---
     /// [Status] Wait for queued async processing to complete
@@ -953,6 +1553,16 @@ enum Commands {
         #[command(subcommand)]
         action: TaskCommands,
     },
+    /// [Status] Follow long-running server jobs: imports, reindexing, session commits
+    Jobs {
+        #[command(subcommand)]
+        action: JobsCommands,
+    },
+    /// [Status] Follow background events: resources added, indexing finished, sessions committed
+    Events {
+        #[command(subcommand)]
+        action: EventsCommands,
+    },
     /// [Version] Manage workspace snapshots (commit, restore, show, diff, log)
     Snapshot {
         #[command(subcommand)]
@@ -971,11 +1581,20 @@ enum Commands {
     },
     /// [Status] Quick health check
     Health,
+    /// [Status] Check connectivity, versions, auth, embedding, index, and queues, with fixes
+    Doctor(DoctorArgs),
+    /// [Status] Show stored files, bytes, vectors, and token usage by type or prefix
+    Stats(Box<StatsArgs>),
     /// [Status] Configuration management; run without a subcommand to add, edit, or delete configs
     Config {
         #[command(subcommand)]
         action: Option<ConfigCommands>,
     },
+    /// [Status] List workspaces and choose the one commands are scoped to
+    Workspace {
+        #[command(subcommand)]
+        action: WorkspaceCommands,
+    },
     /// [Status] Choose CLI display language
     #[command(alias = "lang")]
     Language {
@@ -983,8 +1602,30 @@ enum Commands {
         #[arg(value_name = "en|zh-CN")]
         language: Option<String>,
     },
-    /// [Status] Show CLI version
-    Version,
+    /// [Status] Show CLI version; with --remote, the server's version and supported features
+    Version(VersionArgs),
+    /// [Status] Print a shell completion script for ov
+    Completions(CompletionsArgs),
+    /// Generate man pages and a JSON description of every command
+    #[command(hide = true)]
+    GenMan(GenManArgs),
+    /// [Status] Run a background daemon that keeps connections warm and caches reads
+    Daemon {
+        #[command(subcommand)]
+        action: DaemonCommands,
+    },
+    /// [Status] Run ov commands on a cron schedule from the daemon, e.g. a nightly sync
+    Schedule {
+        #[command(subcommand)]
+        action: ScheduleCommands,
+    },
+    /// [Interactive] Serve OpenViking tools to MCP hosts such as Claude Desktop and Cursor
+    Mcp {
+        #[command(subcommand)]
+        action: McpCommands,
+    },
+    /// [Interactive] Serve an offline, in-memory OpenViking API for developing and testing clients
+    MockServer(MockServerArgs),
 
     // --- Admin Tools ---
     /// [Admin] Account and user management commands (multi-tenant)
@@ -992,45 +1633,639 @@ enum Commands {
         #[command(subcommand)]
         action: AdminCommands,
     },
+    /// [Admin] Review the server's request audit log: who added, deleted, or searched what
+    Audit {
+        #[command(subcommand)]
+        action: AuditCommands,
+    },
     /// [Admin] System utility commands
     System {
         #[command(subcommand)]
         action: SystemCommands,
     },
     /// [Admin] Reindex semantic/vector artifacts for a URI
-    Reindex {
-        /// Viking URI
-        #[arg(value_name = "uri")]
-        uri: String,
-        /// Reindex mode: vectors_only rebuilds vectors; semantic_and_vectors regenerates semantic artifacts, then vectors; prune_orphans deletes orphan vector records
-        #[arg(
-            long,
-            default_value = "vectors_only",
-            value_parser = ["vectors_only", "semantic_and_vectors", "prune_orphans"],
-            value_name = "mode",
-            help_heading = "Common options"
-        )]
-        mode: String,
-        /// Wait for reindex to complete
-        #[arg(
-            long,
-            default_value_t = true,
-            action = ArgAction::Set,
-            value_name = "bool",
-            help_heading = "Common options"
-        )]
-        wait: bool,
-        /// Preview prune_orphans deletions without mutating vectors
-        #[arg(long, help_heading = "Common options")]
-        dry_run: bool,
-    },
+    Reindex(ReindexArgs),
+    /// [Admin] Verify the vector index against stored content and repair missing records
+    Index(IndexArgs),
+    /// [Admin] Benchmark server throughput and latency percentiles
+    Bench(BenchArgs),
+    /// [Admin] Send a raw request to any server endpoint
+    Api(Box<ApiArgs>),
+}
+
+#[derive(Args)]
+struct AddSkillArgs {
+    /// Skill directory, SKILL.md, or raw content
+    #[arg(value_name = "skill-path-or-content")]
+    data: String,
+    /// Wait until processing is complete
+    #[arg(long, help_heading = "Common options")]
+    wait: bool,
+    /// Wait timeout in seconds
+    #[arg(long, value_name = "seconds", help_heading = "Common options")]
+    timeout: Option<f64>,
+    /// Parent skill root URI (e.g. viking://agent/skills); defaults to user-private skills
+    #[arg(
+        short = 'p',
+        long = "parent-auto-create",
+        value_name = "uri",
+        help_heading = "Skill options"
+    )]
+    parent: Option<String>,
+    #[command(flatten)]
+    upload_options: UploadCliOptions,
+}
+
+#[derive(Args)]
+struct LsArgs {
+    /// Viking URI to list (default: viking://)
+    #[arg(default_value = "viking://", value_name = "uri")]
+    uri: String,
+    /// Simple path output (just paths, no table)
+    #[arg(short, long, help_heading = "Common options")]
+    simple: bool,
+    /// List all subdirectories recursively
+    #[arg(short, long, help_heading = "Common options")]
+    recursive: bool,
+    /// Abstract content limit (only for agent output)
+    #[arg(
+        long = "abs-limit",
+        short = 'l',
+        default_value = "256",
+        value_name = "n",
+        help_heading = "Advanced options"
+    )]
+    abs_limit: i32,
+    /// Show all hidden files
+    #[arg(short, long, help_heading = "Common options")]
+    all: bool,
+    /// Maximum number of nodes to list
+    #[arg(
+        long = "node-limit",
+        short = 'n',
+        alias = "limit",
+        default_value = "256",
+        value_name = "n",
+        help_heading = "Common options"
+    )]
+    node_limit: i32,
+    /// Only list entries carrying this tag, as k=v or a bare key (repeatable)
+    #[arg(long = "tag", value_name = "k[=v]", help_heading = "Common options")]
+    tag: Vec<String>,
+    #[command(flatten)]
+    token_options: TokenCliOptions,
+}
+
+#[derive(Args)]
+struct TreeArgs {
+    /// Viking URI to get tree for
+    #[arg(value_name = "uri")]
+    uri: String,
+    /// Abstract content limit (only for agent output)
+    #[arg(
+        long = "abs-limit",
+        short = 'l',
+        default_value = "128",
+        value_name = "n",
+        help_heading = "Advanced options"
+    )]
+    abs_limit: i32,
+    /// Show all hidden files
+    #[arg(short, long, help_heading = "Common options")]
+    all: bool,
+    /// Maximum number of nodes to list
+    #[arg(
+        long = "node-limit",
+        short = 'n',
+        alias = "limit",
+        default_value = "256",
+        value_name = "n",
+        help_heading = "Common options"
+    )]
+    node_limit: i32,
+    /// Maximum depth level to traverse (default: 3)
+    #[arg(
+        short = 'L',
+        long = "level-limit",
+        visible_alias = "depth",
+        default_value = "3",
+        value_name = "n",
+        help_heading = "Common options"
+    )]
+    level_limit: i32,
+}
+
+#[derive(Args)]
+struct RmArgs {
+    /// Viking URI to remove
+    #[arg(value_name = "uri", required_unless_present = "glob")]
+    uri: Option<String>,
+    /// Remove every URI matching a glob, e.g. 'viking://resources/contract/2023-*'
+    #[arg(
+        long,
+        value_name = "pattern",
+        conflicts_with = "uri",
+        help_heading = "Common options"
+    )]
+    glob: Option<String>,
+    /// Remove recursively
+    #[arg(short, long, help_heading = "Common options")]
+    recursive: bool,
+    /// Show what would be removed without deleting anything
+    #[arg(long = "dry-run", help_heading = "Common options")]
+    dry_run: bool,
+    /// Skip the confirmation prompt
+    #[arg(short = 'y', long = "yes", help_heading = "Common options")]
+    yes: bool,
+    /// Wait until semantic refresh is complete
+    #[arg(long, help_heading = "Common options")]
+    wait: bool,
+    /// Wait timeout in seconds (only used with --wait)
+    #[arg(long, value_name = "seconds", help_heading = "Common options")]
+    timeout: Option<f64>,
+}
+
+#[derive(Args)]
+struct CpArgs {
+    /// Source URI
+    #[arg(value_name = "from-uri")]
+    from_uri: String,
+    /// Target URI
+    #[arg(value_name = "to-uri")]
+    to_uri: String,
+    /// Copy a directory and everything under it
+    #[arg(short, long, help_heading = "Common options")]
+    recursive: bool,
+    /// Re-create the copied resources' relations on the copies
+    #[arg(long = "preserve-relations", help_heading = "Common options")]
+    preserve_relations: bool,
+    /// Wait until the copies are indexed
+    #[arg(long, help_heading = "Common options")]
+    wait: bool,
+}
+
+#[derive(Args)]
+struct WriteArgs {
+    /// Viking URI
+    #[arg(value_name = "uri")]
+    uri: String,
+    /// Local file to read the content from, or - for stdin
+    #[arg(value_name = "file", conflicts_with_all = ["content", "from_file"])]
+    source: Option<String>,
+    /// Content to write
+    #[arg(
+        long,
+        conflicts_with = "from_file",
+        value_name = "text",
+        help_heading = "Common options"
+    )]
+    content: Option<String>,
+    /// Read content from a local file
+    #[arg(
+        long = "from-file",
+        conflicts_with = "content",
+        value_name = "path",
+        help_heading = "Common options"
+    )]
+    from_file: Option<String>,
+    /// Append instead of replacing the file
+    #[arg(long, help_heading = "Common options")]
+    append: bool,
+    /// Write mode: replace, append, or create (default: replace)
+    #[arg(
+        long,
+        value_name = "replace|append|create",
+        conflicts_with = "append",
+        help_heading = "Advanced options"
+    )]
+    mode: Option<String>,
+    /// Wait for async processing to finish
+    #[arg(long, default_value = "false", help_heading = "Common options")]
+    wait: bool,
+    /// Optional wait timeout in seconds
+    #[arg(long, value_name = "seconds", help_heading = "Common options")]
+    timeout: Option<f64>,
+}
+
+#[derive(Args)]
+struct AppendArgs {
+    /// Viking URI
+    #[arg(value_name = "uri")]
+    uri: String,
+    /// Local file to read the content from, or - for stdin
+    #[arg(value_name = "file", conflicts_with = "content")]
+    source: Option<String>,
+    /// Content to append
+    #[arg(long, value_name = "text", help_heading = "Common options")]
+    content: Option<String>,
+    /// Wait for async processing to finish
+    #[arg(long, help_heading = "Common options")]
+    wait: bool,
+    /// Optional wait timeout in seconds
+    #[arg(long, value_name = "seconds", help_heading = "Common options")]
+    timeout: Option<f64>,
+}
+
+#[derive(Args)]
+struct EditArgs {
+    /// Viking URI
+    #[arg(value_name = "uri")]
+    uri: String,
+    /// Write even if the content changed on the server while it was open
+    #[arg(long, help_heading = "Common options")]
+    force: bool,
+    /// Wait for async processing to finish
+    #[arg(long, help_heading = "Common options")]
+    wait: bool,
+    /// Optional wait timeout in seconds
+    #[arg(long, value_name = "seconds", help_heading = "Common options")]
+    timeout: Option<f64>,
+}
+
+#[derive(Args)]
+struct SetTagsArgs {
+    /// Viking URI
+    uri: String,
+    /// Comma-separated k=v tags, e.g. env=prod,team=search
+    #[arg(long = "tags", value_delimiter = ',')]
+    tags: Vec<String>,
+    /// Tag update mode: replace or append (append replaces existing values by key)
+    #[arg(long, default_value = "replace")]
+    mode: String,
+    /// Recursively update descendant files and semantic nodes when target is a directory
+    #[arg(long, default_value = "false")]
+    recursive: bool,
+}
+
+#[derive(Args)]
+struct RecentArgs {
+    /// Only list URIs, most recently used first
+    #[arg(long, conflicts_with = "commands", help_heading = "Common options")]
+    uris: bool,
+    /// Only list command lines
+    #[arg(long, help_heading = "Common options")]
+    commands: bool,
+    /// Number of entries to show
+    #[arg(
+        long,
+        short = 'n',
+        default_value = "20",
+        value_name = "n",
+        help_heading = "Common options"
+    )]
+    limit: usize,
+    /// Delete the recorded history
+    #[arg(long, conflicts_with_all = ["uris", "commands"], help_heading = "Advanced options")]
+    clear: bool,
+}
+
+#[derive(Args)]
+struct FindArgs {
+    /// Search query
+    #[arg(value_name = "query")]
+    query: Option<String>,
+    /// Image query: local path, data URI, HTTP URL, or viking:// URI
+    #[arg(
+        long = "image",
+        value_name = "path|uri",
+        help_heading = "Common options"
+    )]
+    image: Option<String>,
+    /// Target URI
+    #[arg(
+        short,
+        long,
+        default_value = "",
+        value_name = "uri",
+        help_heading = "Common options"
+    )]
+    uri: String,
+    /// Maximum final results returned
+    #[arg(
+        short = 'n',
+        long = "node-limit",
+        alias = "limit",
+        default_value = "10",
+        value_name = "n",
+        help_heading = "Common options"
+    )]
+    node_limit: i32,
+    /// Score threshold
+    #[arg(short, long, value_name = "score", help_heading = "Common options")]
+    threshold: Option<f64>,
+    /// Only include results on or after this time (e.g. 48h, 7d, 2026-03-10, ISO-8601)
+    #[arg(long = "after", value_name = "time", help_heading = "Advanced options")]
+    after: Option<String>,
+    /// Only include results on or before this time (e.g. 24h, 2026-03-15, ISO-8601)
+    #[arg(
+        long = "before",
+        value_name = "time",
+        help_heading = "Advanced options"
+    )]
+    before: Option<String>,
+    /// Only include results with specific level(s) (0=abstract, 1=overview, 2=file)
+    #[arg(
+        short = 'L',
+        long = "level",
+        value_delimiter = ',',
+        value_name = "0,1,2",
+        help_heading = "Common options"
+    )]
+    level: Option<Vec<i32>>,
+    /// Only include results with specific context type(s) (memory, resource, skill)
+    #[arg(
+        long = "context-type",
+        value_delimiter = ',',
+        value_name = "type",
+        help_heading = "Common options"
+    )]
+    context_type: Option<Vec<String>>,
+    /// Only include results matching all of these explicit tags
+    #[arg(long = "tags", value_delimiter = ',')]
+    tags: Option<Vec<String>>,
+    #[command(flatten)]
+    filter_options: FilterCliOptions,
+    #[command(flatten)]
+    scoring_options: ScoringCliOptions,
+    #[command(flatten)]
+    token_options: TokenCliOptions,
+    #[command(flatten)]
+    snippet_options: SnippetCliOptions,
+}
+
+#[derive(Args)]
+struct SearchArgs {
+    #[command(subcommand)]
+    action: Option<SearchCommands>,
+    /// Search query
+    #[arg(value_name = "query")]
+    query: Option<String>,
+    /// Image query: local path, data URI, HTTP URL, or viking:// URI
+    #[arg(
+        long = "image",
+        value_name = "path|uri",
+        help_heading = "Common options"
+    )]
+    image: Option<String>,
+    /// Target URI
+    #[arg(
+        short,
+        long,
+        default_value = "",
+        value_name = "uri",
+        help_heading = "Common options"
+    )]
+    uri: String,
+    /// Session ID for context-aware search
+    #[arg(long, value_name = "id", help_heading = "Common options")]
+    session_id: Option<String>,
+    /// Maximum results per search pass. Search may merge multiple passes.
+    #[arg(
+        short = 'n',
+        long = "node-limit",
+        alias = "limit",
+        default_value = "10",
+        value_name = "n",
+        help_heading = "Common options"
+    )]
+    node_limit: i32,
+    /// Score threshold
+    #[arg(short, long, value_name = "score", help_heading = "Advanced options")]
+    threshold: Option<f64>,
+    /// Only include results on or after this time (e.g. 48h, 7d, 2026-03-10, ISO-8601)
+    #[arg(long = "after", value_name = "time", help_heading = "Advanced options")]
+    after: Option<String>,
+    /// Only include results on or before this time (e.g. 24h, 2026-03-15, ISO-8601)
+    #[arg(
+        long = "before",
+        value_name = "time",
+        help_heading = "Advanced options"
+    )]
+    before: Option<String>,
+    /// Only include results with specific level(s) (0=abstract, 1=overview, 2=file)
+    #[arg(
+        short = 'L',
+        long = "level",
+        value_delimiter = ',',
+        value_name = "0,1,2",
+        help_heading = "Advanced options"
+    )]
+    level: Option<Vec<i32>>,
+    /// Only include results with specific context type(s) (memory, resource, skill)
+    #[arg(
+        long = "context-type",
+        value_delimiter = ',',
+        value_name = "type",
+        help_heading = "Advanced options"
+    )]
+    context_type: Option<Vec<String>>,
+    /// Only include results matching all of these explicit tags
+    #[arg(long = "tags", value_delimiter = ',')]
+    tags: Option<Vec<String>>,
+    #[command(flatten)]
+    filter_options: FilterCliOptions,
+    #[command(flatten)]
+    scoring_options: ScoringCliOptions,
+    #[command(flatten)]
+    token_options: TokenCliOptions,
+    #[command(flatten)]
+    snippet_options: SnippetCliOptions,
+}
+
+#[derive(Args)]
+struct GrepArgs {
+    /// Target URI
+    #[arg(
+        short,
+        long,
+        default_value = "viking://",
+        value_name = "uri",
+        help_heading = "Common options"
+    )]
+    uri: String,
+    /// Excluded URI range. Any entry whose URI falls under this URI prefix is skipped
+    #[arg(
+        short = 'x',
+        long = "exclude-uri",
+        value_name = "uri",
+        help_heading = "Advanced options"
+    )]
+    exclude_uri: Option<String>,
+    /// Search pattern (a regular expression unless --fixed-strings is set)
+    #[arg(value_name = "pattern")]
+    pattern: String,
+    /// URI prefix to search; same as --uri
+    #[arg(value_name = "uri-prefix")]
+    uri_prefix: Option<String>,
+    /// Case insensitive
+    #[arg(short, long, help_heading = "Common options")]
+    ignore_case: bool,
+    /// Treat the pattern as a literal string instead of a regular expression
+    #[arg(short = 'F', long = "fixed-strings", help_heading = "Common options")]
+    fixed_strings: bool,
+    /// Maximum number of results
+    #[arg(
+        short = 'n',
+        long = "node-limit",
+        alias = "limit",
+        default_value = "256",
+        value_name = "n",
+        help_heading = "Common options"
+    )]
+    node_limit: i32,
+    /// Maximum depth level to traverse (default: 10)
+    #[arg(
+        short = 'L',
+        long = "level-limit",
+        default_value = "10",
+        value_name = "n",
+        help_heading = "Advanced options"
+    )]
+    level_limit: i32,
+}
+
+#[derive(Args)]
+struct ExportArgs {
+    /// Source URI
+    #[arg(value_name = "uri")]
+    uri: String,
+    /// Output .ovpack file path
+    #[arg(value_name = "output.ovpack")]
+    to: String,
+    /// Include dense vector snapshot when compatible metadata is available
+    #[arg(long, default_value_t = false, help_heading = "Common options")]
+    include_vectors: bool,
+    #[command(flatten)]
+    compression: PackCompressionArgs,
+    #[command(flatten)]
+    encryption: PackEncryptArgs,
+}
+
+#[derive(Args)]
+struct ImportArgs {
+    #[command(subcommand)]
+    action: Option<ImportCommands>,
+    /// Input .ovpack file path
+    #[arg(value_name = "file.ovpack", required = true)]
+    file_path: Option<String>,
+    /// Target parent URI
+    #[arg(value_name = "target-uri", required = true)]
+    target_uri: Option<String>,
+    /// Conflict policy: fail, overwrite, or skip
+    #[arg(
+        long,
+        value_parser = ["fail", "overwrite", "skip"],
+        value_name = "policy",
+        help_heading = "Common options"
+    )]
+    on_conflict: Option<String>,
+    /// Vector handling: auto restores compatible snapshots, recompute ignores them, require fails if unavailable
+    #[arg(
+        long,
+        value_parser = ["auto", "recompute", "require"],
+        value_name = "mode",
+        help_heading = "Common options"
+    )]
+    vector_mode: Option<String>,
+    #[command(flatten)]
+    signature: PackSignatureArgs,
+    #[command(flatten)]
+    decryption: PackDecryptArgs,
+    #[command(flatten)]
+    redact_options: RedactCliOptions,
+}
+
+#[derive(Args)]
+struct ChatArgs {
+    /// Message to send to the agent
+    #[arg(short, long, value_name = "text", help_heading = "Common options")]
+    message: Option<String>,
+    /// Session ID (defaults to machine unique ID)
+    #[arg(short, long, value_name = "id", help_heading = "Common options")]
+    session: Option<String>,
+    /// Sender ID
+    #[arg(
+        long,
+        default_value = "user",
+        value_name = "id",
+        help_heading = "Advanced options"
+    )]
+    sender: String,
+    /// Stream the response (default: true)
+    #[arg(
+        long,
+        default_value_t = true,
+        value_name = "bool",
+        help_heading = "Advanced options"
+    )]
+    stream: bool,
+    /// Disable rich formatting / markdown rendering
+    #[arg(long, help_heading = "Common options")]
+    no_format: bool,
+    /// Disable command history
+    #[arg(long, help_heading = "Advanced options")]
+    no_history: bool,
+}
+
+#[derive(Args)]
+struct StatsArgs {
+    /// Viking URI to report on
+    #[arg(default_value = "viking://resources", value_name = "uri")]
+    uri: String,
+    /// Break files, bytes, and vectors down by the URI's children
+    #[arg(long = "by-prefix")]
+    by_prefix: bool,
+    /// Days of token usage to sum
+    #[arg(long, default_value = "30", value_name = "days")]
+    days: u32,
+    /// Maximum number of entries to count
+    #[arg(
+        long = "node-limit",
+        default_value = "100000",
+        value_name = "n",
+        help_heading = "Advanced options"
+    )]
+    node_limit: i32,
+}
+
+#[derive(Args)]
+struct ApiArgs {
+    /// HTTP method
+    #[arg(
+        value_parser = ["GET", "POST", "PUT", "PATCH", "DELETE"],
+        ignore_case = true,
+        value_name = "method"
+    )]
+    method: String,
+    /// Endpoint path; relative paths are resolved under /api/v1/
+    #[arg(value_name = "path")]
+    path: String,
+    /// Add a key=value parameter (query string for GET/DELETE, JSON body otherwise; @file reads the value from a file)
+    #[arg(
+        short = 'F',
+        long = "field",
+        value_name = "key=value",
+        help_heading = "Common options"
+    )]
+    fields: Vec<String>,
+    /// JSON request body: inline JSON, @file, or @- for stdin
+    #[arg(
+        long = "raw-body",
+        value_name = "json|@file",
+        help_heading = "Common options"
+    )]
+    raw_body: Option<String>,
 }
 
 impl Commands {
     /// Returns true if this command supports running with the root API key.
     fn supports_sudo(&self) -> bool {
         match self {
-            Self::Admin { .. } | Self::System { .. } | Self::Reindex { .. } => true,
+            Self::Admin { .. }
+            | Self::System { .. }
+            | Self::Reindex(_)
+            | Self::Index(_)
+            | Self::Api { .. } => true,
             Self::Task { action } => matches!(
                 action,
                 TaskCommands::Status { .. } | TaskCommands::List { .. }
@@ -1040,7 +2275,7 @@ impl Commands {
     }
 
     fn supports_upload_options(&self) -> bool {
-        matches!(self, Self::AddResource { .. } | Self::AddSkill { .. })
+        matches!(self, Self::AddResource(_) | Self::AddSkill { .. })
     }
 }
 
@@ -1081,6 +2316,59 @@ enum TaskCommands {
     },
 }
 
+#[derive(Subcommand)]
+enum JobsCommands {
+    /// List recent jobs, newest first, with their progress
+    Ls {
+        /// Only jobs of this type (e.g. add_resource, add_skill, admin_reindex, session_commit)
+        #[arg(long = "type", value_name = "type")]
+        job_type: Option<String>,
+        /// Only jobs in this state
+        #[arg(long, value_parser = ["pending", "running", "completed", "failed"], value_name = "status")]
+        status: Option<String>,
+        /// Most jobs to list
+        #[arg(short = 'n', long, default_value_t = 50, value_parser = clap::value_parser!(u32).range(1..=200), value_name = "n")]
+        limit: u32,
+    },
+    /// Show a job's progress, result, and errors
+    Show {
+        /// Job ID, as printed by add-resource, add-skill, or reindex
+        #[arg(value_name = "id")]
+        id: String,
+    },
+    /// Wait until jobs finish; fails if any of them failed
+    Wait {
+        /// Job IDs
+        #[arg(value_name = "id", required = true)]
+        ids: Vec<String>,
+        /// Give up after this many seconds
+        #[arg(long, value_name = "seconds")]
+        timeout: Option<f64>,
+        /// Seconds between polls
+        #[arg(long, default_value = "2", value_name = "seconds")]
+        interval: f64,
+    },
+}
+
+#[derive(Subcommand)]
+enum EventsCommands {
+    /// Print events as JSON lines as they happen, or run a command for each
+    Subscribe {
+        /// Event types to keep, comma-separated; `index.*` keeps every index event
+        #[arg(long, value_delimiter = ',', value_name = "types")]
+        filter: Vec<String>,
+        /// Only events for resources under this URI
+        #[arg(long, value_name = "uri")]
+        uri: Option<String>,
+        /// Shell command to run for each event, with the event as JSON on stdin and in $OV_EVENT
+        #[arg(long, value_name = "cmd")]
+        exec: Option<String>,
+        /// Seconds between polls of the server's task list
+        #[arg(long, default_value = "2", value_name = "seconds")]
+        interval: f64,
+    },
+}
+
 #[derive(Subcommand)]
 pub(crate) enum SnapshotCmd {
     /// Create a snapshot of current workspace state
@@ -1281,8 +2569,69 @@ enum SessionCommands {
         #[arg(value_name = "session-id")]
         session_id: String,
         /// Messages as JSON array of {role, content} objects
-        #[arg(value_name = "messages-json")]
-        messages: String,
+        #[arg(value_name = "messages-json", required_unless_present = "file")]
+        messages: Option<String>,
+        /// Read messages from a JSONL file, one message per line, sent in batches
+        #[arg(long, value_name = "path", conflicts_with = "messages")]
+        file: Option<String>,
+    },
+    /// Chat with the bot inside a session, streaming replies and recording each turn
+    Chat {
+        /// Session ID
+        #[arg(value_name = "session-id")]
+        session_id: String,
+        /// Send one message and exit instead of starting an interactive loop
+        #[arg(short, long, value_name = "message")]
+        message: Option<String>,
+        /// Print replies as plain text without markdown rendering
+        #[arg(long)]
+        no_format: bool,
+        /// Disable command history
+        #[arg(long)]
+        no_history: bool,
+    },
+    /// Extract long-term memories from a session without archiving it
+    Extract {
+        /// Session ID
+        #[arg(value_name = "session-id")]
+        session_id: String,
+    },
+    /// Export a session's full message history
+    Export {
+        /// Session ID
+        #[arg(value_name = "session-id")]
+        session_id: String,
+        /// Transcript format: lossless OpenViking JSONL or OpenAI chat messages
+        #[arg(
+            long = "format",
+            value_parser = ["jsonl", "openai"],
+            default_value = "jsonl",
+            value_name = "jsonl|openai"
+        )]
+        format: String,
+    },
+    /// Import messages from a JSONL, OpenAI, or LangChain transcript file
+    Import {
+        /// Transcript file
+        #[arg(value_name = "file")]
+        file: String,
+        /// Append to this session instead of creating a new one
+        #[arg(long = "session-id", value_name = "session-id")]
+        session_id: Option<String>,
+        #[command(flatten)]
+        redact_options: RedactCliOptions,
+    },
+    /// Follow new messages appended to a session
+    Tail {
+        /// Session ID
+        #[arg(value_name = "session-id")]
+        session_id: String,
+        /// Number of existing messages to show before following
+        #[arg(short = 'n', long = "lines", default_value = "10", value_name = "n")]
+        lines: usize,
+        /// Seconds between polls
+        #[arg(long, default_value = "2", value_name = "seconds")]
+        interval: f64,
     },
     /// Commit a session (archive messages and extract memories)
     Commit {
@@ -1292,6 +2641,32 @@ enum SessionCommands {
     },
 }
 
+#[derive(Subcommand)]
+enum MemoryCommands {
+    /// List memory files with their abstracts
+    #[command(alias = "list")]
+    Ls {
+        /// Memory category (e.g. preferences, entities) or a viking:// URI; defaults to all memories
+        #[arg(value_name = "category")]
+        category: Option<String>,
+        /// Maximum number of entries to list
+        #[arg(
+            short = 'n',
+            long = "node-limit",
+            alias = "limit",
+            default_value = "256",
+            value_name = "n"
+        )]
+        node_limit: i32,
+    },
+    /// Show one memory's content
+    Show {
+        /// Memory URI, or a path relative to viking://user/memories
+        #[arg(value_name = "uri")]
+        uri: String,
+    },
+}
+
 #[derive(Subcommand)]
 enum SkillCommands {
     /// Add skills from a source
@@ -1311,6 +2686,9 @@ enum SkillCommands {
         /// Skip confirmation prompt
         #[arg(short = 'y', long = "yes")]
         yes: bool,
+        /// Upload without checking SKILL.md locally first
+        #[arg(long = "no-validate")]
+        no_validate: bool,
         /// Parent skill root URI (e.g. viking://agent/skills); defaults to user-private skills
         #[arg(short = 'p', long = "parent-auto-create", value_name = "uri")]
         parent: Option<String>,
@@ -1420,6 +2798,21 @@ enum SkillCommands {
         #[arg(short = 'p', long = "parent-auto-create", value_name = "uri")]
         parent: Option<String>,
     },
+    /// Download an installed skill's files into a local directory
+    Export {
+        /// Skill name
+        #[arg(value_name = "name")]
+        name: String,
+        /// Directory to export into; the skill is written to <dir>/<name>
+        #[arg(value_name = "dir", default_value = ".")]
+        dest: String,
+        /// Overwrite an existing <dir>/<name>
+        #[arg(short = 'f', long = "force")]
+        force: bool,
+        /// Resolve the skill under a specific root URI (e.g. viking://agent/skills)
+        #[arg(short = 'p', long = "uri", value_name = "uri")]
+        parent: Option<String>,
+    },
     /// Validate a local SKILL.md file or skill directory
     Validate {
         /// SKILL.md file or skill directory path
@@ -1429,6 +2822,30 @@ enum SkillCommands {
         #[arg(long = "strict")]
         strict: bool,
     },
+    /// Generate a skeleton skill directory to edit and install
+    Init(SkillInitArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+struct SkillInitArgs {
+    /// Skill name; also the name of the generated directory
+    #[arg(value_name = "name")]
+    name: String,
+    /// Directory to create the skill in; the skill is written to <dir>/<name>
+    #[arg(value_name = "dir", default_value = ".")]
+    dest: String,
+    /// Description for the SKILL.md frontmatter
+    #[arg(short = 'd', long, value_name = "text")]
+    description: Option<String>,
+    /// Comma-separated tags for the SKILL.md frontmatter
+    #[arg(short = 't', long, value_name = "tags", value_delimiter = ',')]
+    tags: Vec<String>,
+    /// Copy the files under this resource URI into the skill's references/
+    #[arg(long = "from-uri", value_name = "uri")]
+    from_uri: Option<String>,
+    /// Overwrite an existing <dir>/<name>
+    #[arg(short = 'f', long = "force")]
+    force: bool,
 }
 
 #[derive(Subcommand)]
@@ -1657,6 +3074,11 @@ enum AdminCommands {
         #[arg(value_name = "role")]
         role: String,
     },
+    /// Add, list, and remove users of the current workspace (--workspace to pick another)
+    User {
+        #[command(subcommand)]
+        action: AdminUserCommands,
+    },
     /// Regenerate a user's API key (old key immediately invalidated)
     RegenerateKey {
         /// Account ID
@@ -1671,2927 +3093,7212 @@ enum AdminCommands {
     },
 }
 
-impl Commands {
-    fn requires_cli_config_file(&self) -> bool {
-        !matches!(
-            self,
-            Commands::Config {
-                action: None
-                    | Some(
-                        ConfigCommands::Switch { .. }
-                            | ConfigCommands::Add { .. }
+#[derive(Subcommand)]
+enum AdminUserCommands {
+    /// Register a user; prints their new API key
+    Add {
+        /// User ID to register
+        #[arg(value_name = "user-id")]
+        user_id: String,
+        /// Role: admin or user
+        #[arg(long, default_value = "user", value_name = "role")]
+        role: String,
+        /// Deterministic API key seed
+        #[arg(long, value_name = "seed")]
+        seed: Option<String>,
+        /// Initial config for the new user as JSON
+        #[arg(long = "user-config-json", value_name = "json")]
+        user_config_json: Option<String>,
+    },
+    /// List users
+    Ls {
+        /// Maximum number of users to list (default: 100)
+        #[arg(long, default_value = "100", value_name = "n")]
+        limit: u32,
+        /// Filter users by name (supports wildcard * and ?)
+        #[arg(long, value_name = "pattern")]
+        name: Option<String>,
+        /// Filter users by role
+        #[arg(long, value_name = "role")]
+        role: Option<String>,
+    },
+    /// Remove a user
+    Rm {
+        /// User ID to remove
+        #[arg(value_name = "user-id")]
+        user_id: String,
+    },
+}
+
+impl Commands {
+    /// `--redact` and `--redact-pattern`, on the commands that ingest content.
+    fn redact_options(&self) -> Option<&RedactCliOptions> {
+        match self {
+            Commands::AddResource(args) => Some(&args.redact_options),
+            Commands::Import(args) => Some(&args.redact_options),
+            Commands::Add { redact_options, .. }
+            | Commands::Session {
+                action: SessionCommands::Import { redact_options, .. },
+            } => Some(redact_options),
+            _ => None,
+        }
+    }
+
+    /// Writes `--queue-offline` can journal and `ov queue flush` can replay.
+    fn can_queue_offline(&self) -> bool {
+        // A replay runs `ov` again, so content piped on stdin would be lost.
+        let source = match self {
+            Commands::Write(args) => args.source.as_deref(),
+            Commands::Append(args) => args.source.as_deref(),
+            _ => None,
+        };
+        if source == Some("-") {
+            return false;
+        }
+        matches!(
+            self,
+            Commands::AddResource(_)
+                | Commands::AddSkill(_)
+                | Commands::AddMemory { .. }
+                | Commands::Write(_)
+                | Commands::Append(_)
+                | Commands::Mkdir { .. }
+                | Commands::Rm(_)
+                | Commands::Mv { .. }
+                | Commands::Session {
+                    action: SessionCommands::AddMessage { .. }
+                        | SessionCommands::AddMessages { .. },
+                }
+        )
+    }
+
+    fn requires_cli_config_file(&self) -> bool {
+        if let Commands::Tokens(TokensArgs {
+            action: TokensCommands::Count(args),
+        }) = self
+        {
+            return args
+                .sources
+                .iter()
+                .any(|source| source.starts_with("viking://"));
+        }
+        if let Commands::Search(args) = self {
+            return !matches!(
+                args.action,
+                Some(
+                    SearchCommands::Save { .. } | SearchCommands::List | SearchCommands::Rm { .. }
+                )
+            );
+        }
+        !matches!(
+            self,
+            Commands::Config {
+                action: None
+                    | Some(
+                        ConfigCommands::Switch { .. }
+                            | ConfigCommands::Add { .. }
                             | ConfigCommands::Edit(_)
                             | ConfigCommands::Delete(_)
                             | ConfigCommands::List,
                     ),
             } | Commands::Skills {
-                action: SkillCommands::Validate { .. },
-            } | Commands::Version
+                action: SkillCommands::Validate { .. }
+                    | SkillCommands::Init(SkillInitArgs { from_uri: None, .. }),
+            } | Commands::Add {
+                action: None,
+                objects: AddObjectsArgs {
+                    check_only: true,
+                    ..
+                },
+                ..
+            } | Commands::Alias { .. }
+                | Commands::Bookmark { .. }
+                | Commands::Recent(_)
+                | Commands::Pack { .. }
+                | Commands::Sync { .. }
+                | Commands::Version(VersionArgs { remote: false })
+                | Commands::Completions(_)
+                | Commands::GenMan(_)
+                | Commands::MockServer(_)
         )
     }
 }
 
+#[derive(Args, Debug, Clone)]
+struct ReindexArgs {
+    /// Viking URI
+    #[arg(value_name = "uri")]
+    uri: String,
+    /// Reindex mode: vectors_only rebuilds vectors; semantic_and_vectors regenerates semantic artifacts, then vectors; prune_orphans deletes orphan vector records
+    #[arg(
+        long,
+        default_value = "vectors_only",
+        value_parser = ["vectors_only", "semantic_and_vectors", "prune_orphans"],
+        value_name = "mode",
+        help_heading = "Common options"
+    )]
+    mode: String,
+    /// Wait for reindex to complete
+    #[arg(
+        long,
+        default_value_t = true,
+        action = ArgAction::Set,
+        value_name = "bool",
+        help_heading = "Common options"
+    )]
+    wait: bool,
+    /// Preview prune_orphans deletions without mutating vectors
+    #[arg(long, help_heading = "Common options")]
+    dry_run: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+struct CompletionsArgs {
+    /// Shell to complete for
+    #[arg(value_parser = ["bash", "zsh", "fish", "powershell"], value_name = "shell")]
+    shell: String,
+}
+
+#[derive(Args, Debug, Clone)]
+struct GenManArgs {
+    /// Write a man page per command and ov.json here instead of printing the top-level page
+    #[arg(long, value_name = "dir")]
+    out_dir: Option<String>,
+}
+
+#[derive(Args, Debug, Clone)]
+struct VersionArgs {
+    /// Also ask the server for its version and which API features it supports
+    #[arg(long)]
+    remote: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+struct DoctorArgs {
+    /// Resource directories to compare against the vector index; 0 skips the check
+    #[arg(long, default_value_t = 3, value_name = "n")]
+    sample: usize,
+}
+
 #[derive(Subcommand)]
-enum ConfigCommands {
-    /// Show current configuration
-    Show,
-    /// Validate configuration file
-    Validate,
-    /// Switch between saved configs
-    Switch {
-        /// Saved config name to activate. Omit to open the interactive selector.
-        #[arg(value_name = "name")]
-        name: Option<String>,
-    },
-    /// List saved configs
+enum QueueCommands {
+    /// List queued commands, oldest first
     List,
-    /// Delete a saved config
-    Delete(ConfigDeleteArgs),
-    /// Add a saved config without opening the interactive wizard
-    Add {
-        #[command(subcommand)]
-        target: ConfigAddTarget,
+    /// Replay the commands queued for the current server, stopping at the first failure
+    Flush,
+    /// Remove a queued command without running it
+    Drop {
+        /// Id shown by `ov queue list`
+        #[arg(value_name = "id")]
+        id: String,
     },
-    /// Edit a saved config without opening the interactive wizard
-    Edit(ConfigEditArgs),
+    /// Show pending parse and vectorization work on the server, with per-URI state
+    Status {
+        /// Only show work for this URI and everything below it
+        #[arg(value_name = "uri")]
+        uri: Option<String>,
+    },
+    /// Wait until the server has finished processing a URI
+    Wait(QueueWaitArgs),
 }
 
-#[derive(Subcommand)]
-enum ConfigAddTarget {
-    /// Add an OpenViking Service config
-    OvService(ConfigAddOvServiceArgs),
-    /// Add a custom config
-    Custom(ConfigAddCustomArgs),
+#[derive(Args, Debug, Clone)]
+struct QueueWaitArgs {
+    /// URI to wait for; covers everything below it
+    #[arg(value_name = "uri")]
+    uri: String,
+    /// Give up after this many seconds
+    #[arg(long, value_name = "seconds")]
+    timeout: Option<f64>,
+    /// Seconds between checks
+    #[arg(long, default_value_t = 1.0, value_name = "seconds")]
+    interval: f64,
 }
 
-#[derive(Args, Debug, Clone)]
-struct ConfigAddOvServiceArgs {
-    /// Saved config name. Agents should pass this for idempotent retries; generated when omitted.
-    #[arg(long, value_name = "name", help_heading = "Common options")]
-    name: Option<String>,
-    /// Read API key from stdin
-    #[arg(long, conflicts_with = "api_key_env", help_heading = "Common options")]
-    api_key_stdin: bool,
-    /// Read API key from an environment variable
-    #[arg(
-        long,
-        conflicts_with = "api_key_stdin",
-        value_name = "env",
-        help_heading = "Common options"
-    )]
-    api_key_env: Option<String>,
-    /// Account identifier to send as X-OpenViking-Account
-    #[arg(long, value_name = "account", help_heading = "Advanced options")]
-    account: Option<String>,
-    /// User identifier to send as X-OpenViking-User
-    #[arg(long, value_name = "user", help_heading = "Advanced options")]
-    user: Option<String>,
-    /// Peer actor scope to send as X-OpenViking-Actor-Peer
-    #[arg(long = "actor-peer-id", hide = true)]
-    actor_peer_id: Option<String>,
-    /// Make the saved config active after validation
-    #[arg(long, help_heading = "Common options")]
-    activate: bool,
-    /// Replace an existing saved config
-    #[arg(long, help_heading = "Common options")]
-    force: bool,
+#[derive(Args)]
+struct ContextArgs {
+    #[command(subcommand)]
+    action: ContextCommands,
+}
+
+#[derive(Subcommand)]
+enum ContextCommands {
+    /// Pack abstracts, overviews, and contents of the best matches into one block
+    Assemble(ContextAssembleArgs),
 }
 
 #[derive(Args, Debug, Clone)]
-struct ConfigAddCustomArgs {
-    /// Saved config name. Agents should pass this for idempotent retries; generated when omitted.
-    #[arg(long, value_name = "name", help_heading = "Common options")]
-    name: Option<String>,
-    /// OpenViking server URL
-    #[arg(long, value_name = "url", help_heading = "Common options")]
-    url: Option<String>,
-    /// Read API key from stdin
+struct ContextAssembleArgs {
+    /// Question or task to gather context for; `--query "<q>"` works too
+    #[arg(value_name = "query", allow_hyphen_values = true)]
+    query: Option<String>,
+    /// Estimated token budget for the whole block
     #[arg(
         long,
-        conflicts_with_all = ["api_key_env", "root_api_key_stdin"],
+        default_value = "8000",
+        value_name = "tokens",
         help_heading = "Common options"
     )]
-    api_key_stdin: bool,
-    /// Read API key from an environment variable
+    budget: usize,
+    /// Layers that may be packed: l0 abstracts, l1 directory overviews, l2 file contents
     #[arg(
         long,
-        conflicts_with = "api_key_stdin",
-        value_name = "env",
+        value_delimiter = ',',
+        default_value = "l0,l1,l2",
+        value_parser = ["l0", "l1", "l2"],
+        value_name = "l0,l1,l2",
         help_heading = "Common options"
     )]
-    api_key_env: Option<String>,
-    /// Read root API key from stdin
+    levels: Vec<String>,
+    /// Only retrieve under this URI
     #[arg(
+        short,
         long,
-        conflicts_with_all = ["root_api_key_env", "api_key_stdin"],
+        default_value = "",
+        value_name = "uri",
         help_heading = "Common options"
     )]
-    root_api_key_stdin: bool,
-    /// Read root API key from an environment variable
+    uri: String,
+    /// Maximum search results to consider
     #[arg(
-        long,
-        conflicts_with = "root_api_key_stdin",
-        value_name = "env",
+        short = 'n',
+        long = "node-limit",
+        alias = "limit",
+        default_value = "10",
+        value_name = "n",
         help_heading = "Common options"
     )]
-    root_api_key_env: Option<String>,
-    /// Account identifier to send as X-OpenViking-Account
-    #[arg(long, value_name = "account", help_heading = "Advanced options")]
-    account: Option<String>,
-    /// User identifier to send as X-OpenViking-User
-    #[arg(long, value_name = "user", help_heading = "Advanced options")]
-    user: Option<String>,
-    /// Peer actor scope to send as X-OpenViking-Actor-Peer
-    #[arg(long = "actor-peer-id", hide = true)]
-    actor_peer_id: Option<String>,
-    /// Make the saved config active after validation
+    node_limit: i32,
+    /// Score threshold
+    #[arg(short, long, value_name = "score", help_heading = "Advanced options")]
+    threshold: Option<f64>,
+    /// Print an OpenAI messages array (system context plus the user query) instead of the block
     #[arg(long, help_heading = "Common options")]
-    activate: bool,
-    /// Replace an existing saved config
-    #[arg(long, help_heading = "Common options")]
-    force: bool,
+    messages: bool,
+}
+
+#[derive(Args)]
+struct TokensArgs {
+    #[command(subcommand)]
+    action: TokensCommands,
+}
+
+#[derive(Subcommand)]
+enum TokensCommands {
+    /// Count the tokens of viking:// URIs, local files, or standard input
+    Count(TokensCountArgs),
 }
 
 #[derive(Args, Debug, Clone)]
-struct ConfigEditArgs {
-    /// Saved config name to edit
-    #[arg(value_name = "name")]
-    name: String,
-    /// Rename the saved config
-    #[arg(long, value_name = "name", help_heading = "Common options")]
-    new_name: Option<String>,
-    /// New server URL. OpenViking Service configs use a fixed URL.
-    #[arg(long, value_name = "url", help_heading = "Common options")]
-    url: Option<String>,
-    /// Read replacement API key from stdin
-    #[arg(
-        long,
-        conflicts_with_all = ["api_key_env", "clear_api_key", "root_api_key_stdin"],
-        help_heading = "Common options"
-    )]
-    api_key_stdin: bool,
-    /// Read replacement API key from an environment variable
-    #[arg(
-        long,
-        conflicts_with_all = ["api_key_stdin", "clear_api_key"],
-        value_name = "env",
-        help_heading = "Common options"
-    )]
-    api_key_env: Option<String>,
-    /// Remove the API key
-    #[arg(
-        long,
-        conflicts_with_all = ["api_key_stdin", "api_key_env"],
-        help_heading = "Common options"
-    )]
-    clear_api_key: bool,
-    /// Read replacement root API key from stdin
+struct TokensCountArgs {
+    /// viking:// URIs, local files, or - for standard input
+    #[arg(required = true, value_name = "uri|path|-")]
+    sources: Vec<String>,
+    /// Tokenizer to count with (default: the config's "tokenizer", else cl100k)
     #[arg(
         long,
-        conflicts_with_all = ["root_api_key_env", "clear_root_api_key", "api_key_stdin"],
+        value_parser = commands::tokens::TOKENIZERS,
+        value_name = "name",
         help_heading = "Common options"
     )]
-    root_api_key_stdin: bool,
-    /// Read replacement root API key from an environment variable
+    tokenizer: Option<String>,
+}
+
+#[derive(Args, Debug, Clone)]
+struct AskArgs {
+    /// Question to answer
+    #[arg(value_name = "question")]
+    question: String,
+    /// Only retrieve under this URI prefix
     #[arg(
         long,
-        conflicts_with_all = ["root_api_key_stdin", "clear_root_api_key"],
-        value_name = "env",
+        default_value = "",
+        value_name = "uri",
         help_heading = "Common options"
     )]
-    root_api_key_env: Option<String>,
-    /// Remove the root API key
+    target: String,
+    /// Session to search in context and to record the exchange in
+    #[arg(long = "session", value_name = "id", help_heading = "Common options")]
+    session_id: Option<String>,
+    /// Estimated token budget for the retrieved context
     #[arg(
         long,
-        conflicts_with_all = ["root_api_key_stdin", "root_api_key_env"],
-        help_heading = "Common options"
+        default_value = "8000",
+        value_name = "tokens",
+        help_heading = "Advanced options"
     )]
-    clear_root_api_key: bool,
-    /// Account identifier to send as X-OpenViking-Account
-    #[arg(long, value_name = "account", help_heading = "Advanced options")]
-    account: Option<String>,
-    /// User identifier to send as X-OpenViking-User
-    #[arg(long, value_name = "user", help_heading = "Advanced options")]
-    user: Option<String>,
-    /// Peer actor scope to send as X-OpenViking-Actor-Peer
+    budget: usize,
+    /// Maximum search results to consider
     #[arg(
-        long = "actor-peer-id",
-        hide = true,
-        conflicts_with = "clear_actor_peer_id"
+        short = 'n',
+        long = "node-limit",
+        alias = "limit",
+        default_value = "10",
+        value_name = "n",
+        help_heading = "Advanced options"
     )]
-    actor_peer_id: Option<String>,
-    /// Remove the peer actor scope
-    #[arg(
-        long = "clear-actor-peer-id",
-        hide = true,
-        conflicts_with = "actor_peer_id"
-    )]
-    clear_actor_peer_id: bool,
-    /// Make the saved config active after validation
-    #[arg(long, help_heading = "Common options")]
-    activate: bool,
-    /// Replace an existing saved config when renaming
-    #[arg(long, help_heading = "Common options")]
-    force: bool,
+    node_limit: i32,
+    /// Model to use instead of the one in the config's "llm" section
+    #[arg(long, value_name = "model", help_heading = "Advanced options")]
+    model: Option<String>,
 }
 
-#[derive(Args, Debug, Clone)]
-struct ConfigDeleteArgs {
-    /// Saved config name to delete
-    #[arg(value_name = "name")]
-    name: String,
-    /// Delete even when the saved config file cannot be parsed
-    #[arg(long, help_heading = "Common options")]
-    force: bool,
+#[derive(Args)]
+struct IndexArgs {
+    #[command(subcommand)]
+    action: IndexCommands,
 }
 
-fn find_command_index(args: &[OsString]) -> Option<usize> {
-    let root_value_options = cli_root_value_options();
-    let mut i = 1;
-    while i < args.len() {
-        let token = args[i].to_string_lossy();
-        let token_ref = token.as_ref();
-
-        if token_ref == "--compact" || token_ref == "-c" {
-            i += compact_option_width(args, i);
-            continue;
-        }
-        if token_ref.starts_with('-') {
-            i += if root_value_options.consumes_value(token_ref) && !token_ref.contains('=') {
-                2
-            } else {
-                1
-            };
-            continue;
-        }
+#[derive(Subcommand)]
+enum IndexCommands {
+    /// Report records missing from the vector index and orphaned vectors
+    Verify(IndexPrefixArgs),
+    /// Re-enqueue missing records for vectorization, optionally pruning orphans
+    Repair(IndexRepairArgs),
+    /// Re-embed everything under a prefix, e.g. after switching embedding models
+    Rebuild(IndexRebuildArgs),
+}
 
-        return Some(i);
-    }
-    None
+#[derive(Args, Debug, Clone)]
+struct IndexPrefixArgs {
+    /// Directory URI to check, including everything below it
+    #[arg(long, default_value = "viking://", value_name = "uri")]
+    prefix: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct PlainHelpMisuse {
-    help_command: String,
+#[derive(Args, Debug, Clone)]
+struct IndexRepairArgs {
+    #[command(flatten)]
+    target: IndexPrefixArgs,
+    /// Also delete vectors whose content no longer exists
+    #[arg(long)]
+    prune_orphans: bool,
+    /// Wait for each reindex to finish instead of returning task ids
+    #[arg(long)]
+    wait: bool,
+    /// Show what would be reindexed without changing anything
+    #[arg(long)]
+    dry_run: bool,
 }
 
-fn plain_help_misuse(args: &[OsString]) -> Option<PlainHelpMisuse> {
-    let tokens = command_tokens_for_plain_help(args);
-    let help_index = tokens.iter().position(|token| token == "help")?;
-    if help_index == 0 {
-        return Some(PlainHelpMisuse {
-            help_command: "ov --help".to_string(),
-        });
-    }
+#[derive(Args, Debug, Clone)]
+struct IndexRebuildArgs {
+    #[command(flatten)]
+    target: IndexPrefixArgs,
+    /// Only rebuild items changed at or after this time (e.g. 48h, 7d, 2026-03-10, ISO-8601)
+    #[arg(long, value_name = "time")]
+    changed_since: Option<String>,
+    /// Number of items to rebuild at once
+    #[arg(short = 'j', long, default_value_t = 4, value_name = "n")]
+    concurrency: usize,
+    /// List the items that would be rebuilt without changing anything
+    #[arg(long)]
+    dry_run: bool,
+}
 
-    let command = tokens
-        .first()
-        .map(|token| canonical_plain_help_token(token))?;
-    if allows_plain_help_as_user_input(command) {
-        return None;
-    }
+#[derive(Subcommand)]
+enum DaemonCommands {
+    /// Start the daemon in the background for the current server
+    Start(DaemonRunArgs),
+    /// Run the daemon in the foreground, e.g. under a service manager
+    Run(DaemonRunArgs),
+    /// Stop the daemon
+    Stop,
+    /// Show whether the daemon is running, with cache statistics
+    Status,
+}
 
-    let mut path = vec![command.to_string()];
-    if is_plain_help_group(command) {
-        if help_index == 1 {
-            return Some(PlainHelpMisuse {
-                help_command: prefixed_help_command(&path),
-            });
-        }
-        if let Some(subcommand) = tokens.get(1).map(|token| canonical_plain_help_token(token)) {
-            path.push(subcommand.to_string());
-        }
-        if command == "task"
-            && path.get(1).is_some_and(|token| token == "watch")
-            && let Some(watch_subcommand) =
-                tokens.get(2).map(|token| canonical_plain_help_token(token))
-            && watch_subcommand != "help"
-        {
-            path.push(watch_subcommand.to_string());
-        }
-        if command == "system"
-            && path.get(1).is_some_and(|token| token == "backend")
-            && let Some(backend_subcommand) =
-                tokens.get(2).map(|token| canonical_plain_help_token(token))
-            && backend_subcommand != "help"
-        {
-            path.push(backend_subcommand.to_string());
-        }
-        return Some(PlainHelpMisuse {
-            help_command: prefixed_help_command(&path),
-        });
-    }
+#[derive(Subcommand)]
+enum ScheduleCommands {
+    /// List schedules with their next run and how their last run went
+    Ls,
+    /// Schedule an ov command for the current server's daemon; a name in use is replaced
+    Add {
+        /// Name to list and remove the schedule by
+        #[arg(value_name = "name")]
+        name: String,
+        /// When to run, in local time: five cron fields (minute hour day month weekday), or @hourly, @daily, @weekly, @monthly
+        #[arg(long, value_name = "expr")]
+        cron: String,
+        /// The ov command to run, after `--`, without the leading `ov`
+        #[arg(last = true, required = true, value_name = "command")]
+        command: Vec<String>,
+    },
+    /// Remove a schedule
+    Rm {
+        /// Schedule name
+        #[arg(value_name = "name")]
+        name: String,
+    },
+}
 
-    Some(PlainHelpMisuse {
-        help_command: prefixed_help_command(&path),
-    })
+#[derive(Subcommand)]
+enum McpCommands {
+    /// Serve find, search, read, abstract, add_resource, and session tools over MCP
+    Serve(McpServeArgs),
 }
 
-fn command_tokens_for_plain_help(args: &[OsString]) -> Vec<String> {
-    let value_options = cli_value_options();
+#[derive(Args, Debug, Clone)]
+struct McpServeArgs {
+    /// stdio for hosts that launch ov themselves; sse to listen on HTTP
+    #[arg(long, default_value = "stdio", value_parser = ["stdio", "sse"], value_name = "stdio|sse")]
+    transport: String,
+    /// Address to listen on with --transport sse
+    #[arg(long, default_value = "127.0.0.1", value_name = "ip")]
+    host: String,
+    /// Port to listen on with --transport sse
+    #[arg(long, default_value_t = 8765, value_name = "port")]
+    port: u16,
+}
 
-    let mut tokens = Vec::new();
-    let mut i = 1;
-    while i < args.len() {
-        let token = args[i].to_string_lossy();
-        let token_ref = token.as_ref();
-        if value_options.consumes_value(token_ref) {
-            i += if token_ref.contains('=') { 1 } else { 2 };
-            continue;
-        }
-        if token_ref.starts_with('-') {
-            i += 1;
-            continue;
-        }
-        tokens.push(token.into_owned());
-        i += 1;
-    }
-    tokens
+#[derive(Args, Debug, Clone)]
+struct MockServerArgs {
+    /// Load files as viking://<path>, and canned find/search results from search.json
+    #[arg(long, value_name = "dir")]
+    fixtures: Option<std::path::PathBuf>,
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1", value_name = "ip")]
+    host: String,
+    /// Port to listen on
+    #[arg(long, default_value_t = 1933, value_name = "port")]
+    port: u16,
 }
 
-fn cli_value_options() -> cli_arg_scan::ValueOptions {
-    let mut command = Cli::command();
-    command.build();
-    cli_arg_scan::ValueOptions::from_command(&command)
+#[derive(Args, Debug, Clone)]
+struct DaemonRunArgs {
+    /// Seconds a cached abstract, overview, listing, or search result is reused
+    #[arg(long, default_value_t = 60, value_name = "seconds")]
+    ttl: u64,
+    /// Most responses kept in the cache
+    #[arg(long, default_value_t = 1000, value_name = "n")]
+    max_entries: usize,
+    /// Serve Prometheus metrics at http://<addr>/metrics; a bare port listens on localhost
+    #[arg(long, value_name = "[host:]port")]
+    metrics: Option<String>,
 }
 
-fn cli_root_value_options() -> cli_arg_scan::ValueOptions {
-    let mut command = Cli::command();
-    command.build();
-    cli_arg_scan::ValueOptions::from_command_arguments(&command)
+#[derive(Subcommand)]
+enum SearchCommands {
+    /// Save a search under a name; `{{name}}` placeholders are filled by `run`
+    Save {
+        /// Name to run the search by
+        #[arg(value_name = "name")]
+        name: String,
+        /// Search query; `--query "<q>"` works too
+        #[arg(value_name = "query", allow_hyphen_values = true)]
+        query: Option<String>,
+        /// `ov search` flags to save with the query, e.g. "--after {{since}} -n 20"
+        #[arg(
+            long,
+            value_name = "flags",
+            allow_hyphen_values = true,
+            help_heading = "Common options"
+        )]
+        filters: Option<String>,
+    },
+    /// Run a saved search, filling its placeholders from name=value arguments
+    Run {
+        /// Saved search name
+        #[arg(value_name = "name")]
+        name: String,
+        /// Placeholder values, e.g. since=7d
+        #[arg(value_name = "name=value")]
+        values: Vec<String>,
+    },
+    /// List saved searches
+    List,
+    /// Remove a saved search
+    Rm {
+        /// Saved search name
+        #[arg(value_name = "name")]
+        name: String,
+    },
 }
 
-fn canonical_plain_help_token(token: &str) -> &str {
-    match token {
-        "list" => "ls",
-        "del" | "delete" => "rm",
-        "rename" => "mv",
-        "lang" => "language",
-        other => other,
-    }
+#[derive(Subcommand)]
+enum ImportCommands {
+    /// Ingest documents listed (and fetched) by tools of an external MCP server
+    Mcp(McpImportArgs),
+    /// Import a Notion Markdown & CSV export, keeping its page tree and links
+    Notion(NotionImportArgs),
+    /// Import an mbox or .eml mailbox as one resource per thread
+    Mbox(MboxImportArgs),
 }
 
-fn allows_plain_help_as_user_input(command: &str) -> bool {
-    matches!(
-        command,
-        "add-resource" | "add-skill" | "find" | "search" | "grep" | "glob" | "add-memory"
-    )
+#[derive(Args, Debug, Clone)]
+struct McpImportArgs {
+    /// MCP server: an http(s) URL, or a command that speaks MCP on stdio
+    #[arg(long, value_name = "cmd|url")]
+    server: String,
+    /// List tool, then optionally a fetch tool called per document; omit to list the server's tools
+    #[arg(long = "tool", value_name = "name")]
+    tools: Vec<String>,
+    /// JSON object of arguments for the list tool
+    #[arg(long, value_name = "json")]
+    args: Option<String>,
+    /// Field holding each listed document's id; id, token, uri, ... are tried when omitted
+    #[arg(long, value_name = "field")]
+    id_field: Option<String>,
+    /// Fetch tool argument that receives the id; defaults to --id-field, else id
+    #[arg(long, value_name = "name")]
+    fetch_arg: Option<String>,
+    /// Parent URI to import under; created when missing
+    #[arg(long, default_value = "viking://resources/mcp", value_name = "uri")]
+    parent: String,
+    /// Import at most this many documents
+    #[arg(short = 'n', long, value_name = "n")]
+    limit: Option<usize>,
+    /// List the documents that would be imported without fetching them
+    #[arg(long)]
+    dry_run: bool,
+    /// Seconds to wait for each MCP reply
+    #[arg(long, default_value_t = 120, value_name = "seconds")]
+    timeout: u64,
 }
 
-fn is_plain_help_group(command: &str) -> bool {
-    matches!(
-        command,
-        "config" | "task" | "admin" | "system" | "session" | "privacy" | "observer" | "skills"
-    )
+#[derive(Args, Debug, Clone)]
+struct NotionImportArgs {
+    /// Export .zip from Notion's Export > Markdown & CSV, or its extracted folder
+    #[arg(value_name = "export.zip")]
+    export: std::path::PathBuf,
+    /// Parent URI to import the page tree under; created when missing
+    #[arg(long, default_value = "viking://resources/notion", value_name = "uri")]
+    parent: String,
+    /// List the pages and where they would go without importing them
+    #[arg(long)]
+    dry_run: bool,
 }
 
-fn prefixed_help_command(path: &[String]) -> String {
-    if path.is_empty() {
-        "ov --help".to_string()
-    } else {
-        format!("ov {} --help", path.join(" "))
-    }
+#[derive(Args, Debug, Clone)]
+struct MboxImportArgs {
+    /// Mailbox to import: an mbox file, one .eml message, or a directory of .eml files
+    #[arg(value_name = "file.mbox")]
+    path: std::path::PathBuf,
+    /// Parent URI to import the threads under; created when missing
+    #[arg(long, default_value = "viking://resources/mail", value_name = "uri")]
+    parent: String,
+    /// List the threads and where they would go without importing them
+    #[arg(long)]
+    dry_run: bool,
 }
 
-fn pre_parse_requires_cli_config_file(args: &[OsString]) -> bool {
-    let tokens = command_tokens_for_config_gate(args);
-    let Some(command) = tokens
-        .first()
-        .map(|token| canonical_plain_help_token(token))
-    else {
-        return false;
-    };
+#[derive(Args, Debug, Clone)]
+struct ClipArgs {
+    /// Directory URI to add the capture under (default: the config's "clip_inbox", else viking://resources/inbox)
+    #[arg(long, value_name = "uri")]
+    inbox: Option<String>,
+}
 
-    match command {
-        "config" => config_command_requires_cli_config_file(&tokens),
-        "language" | "version" => false,
-        "task" => known_task_command_requires_config(&tokens),
-        "admin" => tokens
-            .get(1)
-            .map(|token| is_admin_subcommand(token))
-            .unwrap_or(false),
-        "system" => known_system_command_requires_config(&tokens),
-        "session" => tokens
-            .get(1)
-            .map(|token| is_session_subcommand(token))
-            .unwrap_or(false),
-        "privacy" => tokens
-            .get(1)
-            .map(|token| is_privacy_subcommand(token))
-            .unwrap_or(false),
-        "skills" => tokens
-            .get(1)
-            .map(|token| is_skill_subcommand(token))
-            .unwrap_or(false),
-        "observer" => tokens
-            .get(1)
-            .map(|token| is_observer_subcommand(token))
-            .unwrap_or(false),
-        _ => is_top_level_server_command(command),
-    }
+#[derive(Args, Debug, Clone)]
+struct AddObjectsArgs {
+    /// Bucket and key prefix to add, as s3://bucket/prefix or tos://bucket/prefix; - to add content piped on stdin; a local path with --check-only
+    // Optional only so `ov add url` and `ov add feed` can parse without it.
+    #[arg(value_name = "s3://bucket/prefix", required = true)]
+    source: Option<String>,
+    /// Check a local file or directory for problems server-side parsing would hit, without uploading
+    #[arg(long)]
+    check_only: bool,
+    /// File name to add stdin content as, e.g. notes.md; the extension picks the parser
+    #[arg(long, value_name = "file")]
+    name: Option<String>,
+    /// Parent URI to add stdin content under; created when missing
+    #[arg(long, default_value = "viking://resources", value_name = "uri")]
+    target: String,
+    /// Parent URI to add objects under; defaults to viking://resources/<bucket>
+    #[arg(long, value_name = "uri")]
+    parent: Option<String>,
+    /// Objects to download and upload at once
+    #[arg(short = 'j', long, default_value_t = 4, value_name = "n")]
+    concurrency: usize,
+    /// Bucket region; defaults to AWS_REGION or TOS_REGION
+    #[arg(long, value_name = "region")]
+    region: Option<String>,
+    /// S3-compatible endpoint URL (MinIO, ...); defaults to AWS_ENDPOINT_URL or TOS_ENDPOINT
+    #[arg(long, value_name = "url")]
+    endpoint: Option<String>,
+    /// Add at most this many new or changed objects in this run
+    #[arg(short = 'n', long, value_name = "n")]
+    limit: Option<usize>,
+    /// List the new or changed objects without adding them or updating the state file
+    #[arg(long)]
+    dry_run: bool,
+    /// State file of objects already added; defaults to objects.json in the config directory
+    #[arg(long, value_name = "path")]
+    state: Option<std::path::PathBuf>,
+    /// Seconds to wait when connecting to the bucket
+    #[arg(long, default_value_t = 30, value_name = "seconds")]
+    timeout: u64,
 }
 
-fn config_command_requires_cli_config_file(tokens: &[String]) -> bool {
-    matches!(tokens.get(1).map(String::as_str), Some("show" | "validate"))
+#[derive(Subcommand)]
+enum AddCommands {
+    /// Crawl pages from a URL and add each as a Markdown resource
+    Url(AddUrlArgs),
+    /// Add the entries of a sitemap or RSS/Atom feed not added by earlier runs
+    Feed(AddFeedArgs),
+    /// Add a CSV, TSV, or Parquet file as a schema summary and Markdown row chunks
+    Table(AddTableArgs),
 }
 
-fn is_config_agent_command_request(args: &[OsString]) -> bool {
-    let tokens = command_tokens_for_config_gate(args);
-    if tokens.first().map(String::as_str) != Some("config") {
-        return false;
-    }
+#[derive(Args, Debug, Clone)]
+struct AddUrlArgs {
+    /// Page to start crawling from
+    #[arg(value_name = "url")]
+    url: String,
+    /// Follow links up to this many hops from the start page
+    #[arg(long, default_value_t = 0, value_name = "n")]
+    depth: usize,
+    /// Follow only links on the start URL's host
+    #[arg(long)]
+    same_domain: bool,
+    /// Parent URI to add pages under; created when missing
+    #[arg(long, default_value = "viking://resources/web", value_name = "uri")]
+    parent: String,
+    /// Add at most this many pages
+    #[arg(short = 'n', long, default_value_t = 100, value_name = "n")]
+    limit: usize,
+    /// Crawl and list the pages without uploading them
+    #[arg(long)]
+    dry_run: bool,
+    /// Seconds to wait for each page
+    #[arg(long, default_value_t = 30, value_name = "seconds")]
+    timeout: u64,
+}
 
-    match tokens.get(1).map(String::as_str) {
-        Some("add" | "edit" | "delete" | "list") => true,
-        Some("switch") => tokens.get(2).is_some(),
-        _ => false,
-    }
+#[derive(Args, Debug, Clone)]
+struct AddFeedArgs {
+    /// sitemap.xml, RSS, or Atom feed URL
+    #[arg(value_name = "url")]
+    url: String,
+    /// Parent URI to add entries under; created when missing
+    #[arg(long, default_value = "viking://resources/feeds", value_name = "uri")]
+    parent: String,
+    /// Add at most this many new entries in this run
+    #[arg(short = 'n', long, value_name = "n")]
+    limit: Option<usize>,
+    /// Record the current entries as added without adding them, so later runs only add newer ones
+    #[arg(long, conflicts_with = "dry_run")]
+    mark_seen: bool,
+    /// List the new entries without adding them or updating the state file
+    #[arg(long)]
+    dry_run: bool,
+    /// State file of entries already added; defaults to feeds.json in the config directory
+    #[arg(long, value_name = "path")]
+    state: Option<std::path::PathBuf>,
+    /// Seconds to wait for the feed and each page
+    #[arg(long, default_value_t = 30, value_name = "seconds")]
+    timeout: u64,
 }
 
-fn command_tokens_for_config_gate(args: &[OsString]) -> Vec<String> {
-    let value_options = cli_value_options();
-    let root_value_options = cli_root_value_options();
-    let mut tokens = Vec::new();
-    let mut seen_command = false;
-    let mut i = 1;
+#[derive(Args, Debug, Clone)]
+struct AddTableArgs {
+    /// Tabular file: .csv, .tsv, or .parquet
+    #[arg(value_name = "file")]
+    path: std::path::PathBuf,
+    /// Directory URI to write the schema and chunks to; defaults to viking://resources/tables/<name>
+    #[arg(long, value_name = "uri")]
+    target: Option<String>,
+    /// Rows per Markdown chunk
+    #[arg(long, default_value_t = 200, value_name = "n")]
+    chunk_rows: usize,
+    /// Field delimiter for delimited text; defaults to , for .csv and tab otherwise
+    #[arg(long, value_name = "char")]
+    delimiter: Option<char>,
+    /// Read the file and list the chunks without uploading them
+    #[arg(long)]
+    dry_run: bool,
+}
 
-    while i < args.len() {
-        let token = args[i].to_string_lossy();
-        let token_ref = token.as_ref();
+#[derive(Subcommand)]
+enum BackupCommands {
+    /// Back up every public scope, with relations, as a restore-only .ovpack
+    Create(BackupCreateArgs),
+    /// Restore a backup .ovpack to its original scope roots
+    Restore(BackupRestoreArgs),
+}
 
-        if token_ref == "--" {
-            tokens.extend(
-                args.iter()
-                    .skip(i + 1)
-                    .map(|arg| arg.to_string_lossy().to_string()),
-            );
-            break;
-        }
-
-        if !seen_command {
-            if token_ref == "--compact" || token_ref == "-c" {
-                i += compact_option_width(args, i);
-                continue;
-            }
-            if token_ref.starts_with("--") {
-                i += if root_value_options.consumes_value(token_ref) && !token_ref.contains('=') {
-                    2
-                } else {
-                    1
-                };
-                continue;
-            }
-            if token_ref.starts_with('-') {
-                i += if root_value_options.consumes_value(token_ref) && !token_ref.contains('=') {
-                    2
-                } else {
-                    1
-                };
-                continue;
-            }
-            seen_command = true;
-            tokens.push(token.into_owned());
-            i += 1;
-            continue;
-        }
-
-        if token_ref == "--compact" || token_ref == "-c" {
-            i += compact_option_width(args, i);
-            continue;
-        }
-        if value_options.consumes_value(token_ref) {
-            i += if token_ref.contains('=') { 1 } else { 2 };
-            continue;
-        }
-        if token_ref.starts_with('-') {
-            i += 1;
-            continue;
-        }
-        tokens.push(token.into_owned());
-        i += 1;
-    }
-
-    tokens
+#[derive(Subcommand)]
+enum PackCommands {
+    /// List an .ovpack's manifest, URIs, sizes, and abstracts without a server
+    Inspect {
+        /// Input .ovpack file path
+        #[arg(value_name = "file.ovpack")]
+        file_path: String,
+    },
+    /// Build an importable .ovpack from a local directory without a server
+    Create {
+        /// Local directory to pack
+        #[arg(value_name = "dir")]
+        dir: String,
+        /// Output .ovpack file path
+        #[arg(value_name = "output.ovpack")]
+        to: String,
+        /// URI the pack is rooted at (default: viking://resources/<dir name>)
+        #[arg(long = "root-uri", value_name = "uri", help_heading = "Common options")]
+        root_uri: Option<String>,
+    },
+    /// Sign an .ovpack in place so `ov import` can verify who built it
+    Sign {
+        /// .ovpack file to sign
+        #[arg(value_name = "file.ovpack")]
+        file_path: String,
+        /// Ed25519 private key in PKCS#8 PEM or DER form
+        #[arg(long, value_name = "ed25519-key", help_heading = "Common options")]
+        key: String,
+    },
+    /// Show URIs added, removed, or modified between two packs or a pack and a live subtree
+    Diff {
+        /// Older .ovpack file or viking:// URI
+        #[arg(value_name = "old")]
+        old: String,
+        /// Newer .ovpack file or viking:// URI
+        #[arg(value_name = "new")]
+        new: String,
+    },
 }
 
-fn known_task_command_requires_config(tokens: &[String]) -> bool {
-    match tokens.get(1).map(String::as_str) {
-        Some("status" | "list") => true,
-        Some("watch") => match tokens.get(2).map(String::as_str) {
-            None => true,
-            Some(token) => is_watch_subcommand(token),
-        },
-        _ => false,
-    }
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Show current configuration
+    Show,
+    /// Validate configuration file
+    Validate,
+    /// Switch between saved configs
+    Switch {
+        /// Saved config name to activate. Omit to open the interactive selector.
+        #[arg(value_name = "name")]
+        name: Option<String>,
+    },
+    /// List saved configs
+    List,
+    /// Delete a saved config
+    Delete(ConfigDeleteArgs),
+    /// Add a saved config without opening the interactive wizard
+    Add {
+        #[command(subcommand)]
+        target: ConfigAddTarget,
+    },
+    /// Edit a saved config without opening the interactive wizard
+    Edit(ConfigEditArgs),
 }
 
-fn known_system_command_requires_config(tokens: &[String]) -> bool {
-    match tokens.get(1).map(String::as_str) {
-        Some("wait" | "status" | "health" | "consistency") => true,
-        Some("backend") => matches!(
-            tokens.get(2).map(String::as_str),
-            None | Some("sync-status" | "sync-retry")
-        ),
-        Some("crypto") => matches!(tokens.get(2).map(String::as_str), None | Some("init-key")),
-        _ => false,
-    }
+#[derive(Subcommand)]
+enum ConfigAddTarget {
+    /// Add an OpenViking Service config
+    OvService(ConfigAddOvServiceArgs),
+    /// Add a custom config
+    Custom(ConfigAddCustomArgs),
 }
 
-fn is_top_level_server_command(command: &str) -> bool {
-    matches!(
-        command,
-        "add-resource"
-            | "add-skill"
-            | "ls"
-            | "tree"
-            | "mkdir"
-            | "rm"
-            | "mv"
-            | "stat"
-            | "read"
-            | "abstract"
-            | "overview"
-            | "write"
-            | "get"
-            | "find"
-            | "search"
-            | "grep"
-            | "glob"
-            | "add-memory"
-            | "relations"
-            | "link"
-            | "unlink"
-            | "export"
-            | "backup"
-            | "import"
-            | "restore"
-            | "tui"
-            | "chat"
-            | "wait"
-            | "status"
-            | "health"
-            | "reindex"
-    )
-}
+/// How to reach the server: proxy and TLS certificates.
+#[derive(Args, Debug, Clone, Default)]
+struct ConnectionArgs {
+    /// Proxy URL for server requests (http, https, socks5), or 'none' to bypass HTTP(S)_PROXY
+    #[arg(long, global = true, hide = true, value_name = "url")]
+    proxy: Option<String>,
 
-fn is_watch_subcommand(token: &str) -> bool {
-    matches!(
-        token,
-        "ls" | "show" | "rm" | "pause" | "resume" | "update" | "trigger"
-    )
-}
+    /// PEM bundle of extra CA certificates to trust for the server connection
+    #[arg(long = "ca-cert", global = true, hide = true, value_name = "path")]
+    ca_cert: Option<String>,
 
-fn is_skill_subcommand(token: &str) -> bool {
-    matches!(
-        token,
-        "add" | "list" | "ls" | "find" | "show" | "update" | "remove" | "rm" | "delete"
-    )
-}
+    /// PEM client certificate for mutual TLS
+    #[arg(long = "client-cert", global = true, hide = true, value_name = "path")]
+    client_cert: Option<String>,
 
-fn is_admin_subcommand(token: &str) -> bool {
-    matches!(
-        token,
-        "create-account"
-            | "list-accounts"
-            | "delete-account"
-            | "migrate"
-            | "register-user"
-            | "list-users"
-            | "remove-user"
-            | "set-role"
-            | "regenerate-key"
-    )
-}
+    /// PEM private key for --client-cert, unless the certificate file includes it
+    #[arg(long = "client-key", global = true, hide = true, value_name = "path")]
+    client_key: Option<String>,
 
-fn is_observer_subcommand(token: &str) -> bool {
-    matches!(
-        token,
-        "queue" | "vikingdb" | "models" | "transaction" | "retrieval" | "filesystem" | "system"
-    )
-}
+    /// Seconds to wait for each server request; goes before the command
+    #[arg(long, hide = true, value_name = "secs")]
+    timeout: Option<f64>,
 
-fn is_session_subcommand(token: &str) -> bool {
-    matches!(
-        token,
-        "new"
-            | "list"
-            | "get"
-            | "get-session-context"
-            | "get-session-archive"
-            | "delete"
-            | "add-message"
-            | "add-messages"
-            | "commit"
-    )
+    /// Seconds to wait for the connection to the server to open
+    #[arg(
+        long = "connect-timeout",
+        global = true,
+        hide = true,
+        value_name = "secs"
+    )]
+    connect_timeout: Option<f64>,
 }
 
-fn is_privacy_subcommand(token: &str) -> bool {
-    matches!(
-        token,
-        "categories" | "list" | "get" | "upsert" | "versions" | "version" | "activate"
-    )
+#[derive(Args, Debug, Clone, Default)]
+struct OfflineQueueArgs {
+    /// If the server is unreachable, queue this write for `ov queue flush` instead of failing
+    #[arg(
+        long = "queue-offline",
+        id = "queue_offline",
+        global = true,
+        hide = true
+    )]
+    queue_offline: bool,
 }
 
-fn preprocess_privacy_get_shortcut(args: Vec<OsString>) -> Vec<OsString> {
-    let Some(cmd_idx) = find_command_index(&args) else {
-        return args;
-    };
-    if args[cmd_idx].to_string_lossy() != "privacy" {
-        return args;
-    }
-    let Some(next) = args.get(cmd_idx + 1) else {
-        return args;
-    };
-    let next_token = next.to_string_lossy();
-    if next_token.starts_with('-') || is_privacy_subcommand(&next_token) {
-        return args;
-    }
-
-    let mut out = Vec::with_capacity(args.len() + 1);
-    out.extend(args[..=cmd_idx].iter().cloned());
-    out.push(OsString::from("get"));
-    out.extend(args[cmd_idx + 1..].iter().cloned());
-    out
+#[derive(Args, Debug, Clone)]
+struct BenchArgs {
+    /// Operation to repeat: find, read, or add (messages to a scratch session)
+    #[arg(value_parser = ["find", "read", "add"], value_name = "op")]
+    op: String,
+    /// Query for find, or URI for read
+    #[arg(value_name = "query|uri")]
+    target: Option<String>,
+    /// Target URI to scope find to
+    #[arg(
+        short,
+        long,
+        default_value = "",
+        value_name = "uri",
+        help_heading = "Common options"
+    )]
+    uri: String,
+    /// Number of concurrent workers
+    #[arg(
+        short = 'j',
+        long,
+        default_value = "4",
+        value_name = "n",
+        help_heading = "Common options"
+    )]
+    concurrency: usize,
+    /// Run for this many seconds (default 10 unless --requests is set)
+    #[arg(long, value_name = "secs", help_heading = "Common options")]
+    duration: Option<f64>,
+    /// Stop after this many operations in total
+    #[arg(short = 'n', long, value_name = "n", help_heading = "Common options")]
+    requests: Option<usize>,
 }
 
-fn preprocess_privacy_upsert_key_flags(args: Vec<OsString>) -> Vec<OsString> {
-    let Some(cmd_idx) = find_command_index(&args) else {
-        return args;
-    };
-    if args[cmd_idx].to_string_lossy() != "privacy" {
-        return args;
-    }
-    if args
-        .get(cmd_idx + 1)
-        .map(|s| s.to_string_lossy().to_string())
-        != Some("upsert".to_string())
-    {
-        return args;
-    }
-
-    let mut converted: Vec<OsString> = Vec::with_capacity(args.len());
-    let mut i = 0;
-
-    while i < args.len() {
-        let arg_lossy = args[i].to_string_lossy();
-
-        if i > cmd_idx + 1 && arg_lossy == "--" {
-            i += 1;
-            continue;
-        }
-
-        if i > cmd_idx + 1 && arg_lossy.starts_with("--key-") {
-            let suffix = &arg_lossy[6..];
-            if suffix.is_empty() {
-                converted.push(args[i].clone());
-                i += 1;
-                continue;
-            }
-
-            if let Some((key, value)) = suffix.split_once('=') {
-                converted.push(OsString::from("--key"));
-                converted.push(OsString::from(format!("{}={}", key, value)));
-                i += 1;
-                continue;
-            }
-
-            if i + 1 < args.len() {
-                let next_val = args[i + 1].to_string_lossy();
-                converted.push(OsString::from("--key"));
-                converted.push(OsString::from(format!("{}={}", suffix, next_val)));
-                i += 2;
-                continue;
-            }
-
-            converted.push(args[i].clone());
-            i += 1;
-            continue;
-        }
+#[derive(Args, Debug, Clone)]
+struct ConfigAddOvServiceArgs {
+    /// Saved config name. Agents should pass this for idempotent retries; generated when omitted.
+    #[arg(long, value_name = "name", help_heading = "Common options")]
+    name: Option<String>,
+    /// Read API key from stdin
+    #[arg(long, conflicts_with = "api_key_env", help_heading = "Common options")]
+    api_key_stdin: bool,
+    /// Read API key from an environment variable
+    #[arg(
+        long,
+        conflicts_with = "api_key_stdin",
+        value_name = "env",
+        help_heading = "Common options"
+    )]
+    api_key_env: Option<String>,
+    /// Account identifier to send as X-OpenViking-Account
+    #[arg(long, value_name = "account", help_heading = "Advanced options")]
+    account: Option<String>,
+    /// User identifier to send as X-OpenViking-User
+    #[arg(long, value_name = "user", help_heading = "Advanced options")]
+    user: Option<String>,
+    /// Peer actor scope to send as X-OpenViking-Actor-Peer
+    #[arg(long = "actor-peer-id", hide = true)]
+    actor_peer_id: Option<String>,
+    /// Make the saved config active after validation
+    #[arg(long, help_heading = "Common options")]
+    activate: bool,
+    /// Replace an existing saved config
+    #[arg(long, help_heading = "Common options")]
+    force: bool,
+}
 
-        converted.push(args[i].clone());
-        i += 1;
-    }
+#[derive(Args, Debug, Clone)]
+struct ConfigAddCustomArgs {
+    /// Saved config name. Agents should pass this for idempotent retries; generated when omitted.
+    #[arg(long, value_name = "name", help_heading = "Common options")]
+    name: Option<String>,
+    /// OpenViking server URL
+    #[arg(long, value_name = "url", help_heading = "Common options")]
+    url: Option<String>,
+    /// Read API key from stdin
+    #[arg(
+        long,
+        conflicts_with_all = ["api_key_env", "root_api_key_stdin"],
+        help_heading = "Common options"
+    )]
+    api_key_stdin: bool,
+    /// Read API key from an environment variable
+    #[arg(
+        long,
+        conflicts_with = "api_key_stdin",
+        value_name = "env",
+        help_heading = "Common options"
+    )]
+    api_key_env: Option<String>,
+    /// Read root API key from stdin
+    #[arg(
+        long,
+        conflicts_with_all = ["root_api_key_env", "api_key_stdin"],
+        help_heading = "Common options"
+    )]
+    root_api_key_stdin: bool,
+    /// Read root API key from an environment variable
+    #[arg(
+        long,
+        conflicts_with = "root_api_key_stdin",
+        value_name = "env",
+        help_heading = "Common options"
+    )]
+    root_api_key_env: Option<String>,
+    /// Account identifier to send as X-OpenViking-Account
+    #[arg(long, value_name = "account", help_heading = "Advanced options")]
+    account: Option<String>,
+    /// User identifier to send as X-OpenViking-User
+    #[arg(long, value_name = "user", help_heading = "Advanced options")]
+    user: Option<String>,
+    /// Peer actor scope to send as X-OpenViking-Actor-Peer
+    #[arg(long = "actor-peer-id", hide = true)]
+    actor_peer_id: Option<String>,
+    /// Make the saved config active after validation
+    #[arg(long, help_heading = "Common options")]
+    activate: bool,
+    /// Replace an existing saved config
+    #[arg(long, help_heading = "Common options")]
+    force: bool,
+}
 
-    converted
+#[derive(Args, Debug, Clone)]
+struct ConfigEditArgs {
+    /// Saved config name to edit
+    #[arg(value_name = "name")]
+    name: String,
+    /// Rename the saved config
+    #[arg(long, value_name = "name", help_heading = "Common options")]
+    new_name: Option<String>,
+    /// New server URL. OpenViking Service configs use a fixed URL.
+    #[arg(long, value_name = "url", help_heading = "Common options")]
+    url: Option<String>,
+    /// Read replacement API key from stdin
+    #[arg(
+        long,
+        conflicts_with_all = ["api_key_env", "clear_api_key", "root_api_key_stdin"],
+        help_heading = "Common options"
+    )]
+    api_key_stdin: bool,
+    /// Read replacement API key from an environment variable
+    #[arg(
+        long,
+        conflicts_with_all = ["api_key_stdin", "clear_api_key"],
+        value_name = "env",
+        help_heading = "Common options"
+    )]
+    api_key_env: Option<String>,
+    /// Remove the API key
+    #[arg(
+        long,
+        conflicts_with_all = ["api_key_stdin", "api_key_env"],
+        help_heading = "Common options"
+    )]
+    clear_api_key: bool,
+    /// Read replacement root API key from stdin
+    #[arg(
+        long,
+        conflicts_with_all = ["root_api_key_env", "clear_root_api_key", "api_key_stdin"],
+        help_heading = "Common options"
+    )]
+    root_api_key_stdin: bool,
+    /// Read replacement root API key from an environment variable
+    #[arg(
+        long,
+        conflicts_with_all = ["root_api_key_stdin", "clear_root_api_key"],
+        value_name = "env",
+        help_heading = "Common options"
+    )]
+    root_api_key_env: Option<String>,
+    /// Remove the root API key
+    #[arg(
+        long,
+        conflicts_with_all = ["root_api_key_stdin", "root_api_key_env"],
+        help_heading = "Common options"
+    )]
+    clear_root_api_key: bool,
+    /// Account identifier to send as X-OpenViking-Account
+    #[arg(long, value_name = "account", help_heading = "Advanced options")]
+    account: Option<String>,
+    /// User identifier to send as X-OpenViking-User
+    #[arg(long, value_name = "user", help_heading = "Advanced options")]
+    user: Option<String>,
+    /// Peer actor scope to send as X-OpenViking-Actor-Peer
+    #[arg(
+        long = "actor-peer-id",
+        hide = true,
+        conflicts_with = "clear_actor_peer_id"
+    )]
+    actor_peer_id: Option<String>,
+    /// Remove the peer actor scope
+    #[arg(
+        long = "clear-actor-peer-id",
+        hide = true,
+        conflicts_with = "actor_peer_id"
+    )]
+    clear_actor_peer_id: bool,
+    /// Make the saved config active after validation
+    #[arg(long, help_heading = "Common options")]
+    activate: bool,
+    /// Replace an existing saved config when renaming
+    #[arg(long, help_heading = "Common options")]
+    force: bool,
 }
 
-fn preprocess_compact_args(args: Vec<OsString>) -> Vec<OsString> {
-    if args.is_empty() {
-        return args;
-    }
+#[derive(Args, Debug, Clone)]
+struct ConfigDeleteArgs {
+    /// Saved config name to delete
+    #[arg(value_name = "name")]
+    name: String,
+    /// Delete even when the saved config file cannot be parsed
+    #[arg(long, help_heading = "Common options")]
+    force: bool,
+}
 
-    let mut converted = Vec::with_capacity(args.len());
-    converted.push(args[0].clone());
+fn find_command_index(args: &[OsString]) -> Option<usize> {
+    let root_value_options = cli_root_value_options();
     let mut i = 1;
-
     while i < args.len() {
         let token = args[i].to_string_lossy();
-        if token == "--compact" || token == "-c" {
-            if let Some(next) = args.get(i + 1) {
-                let next_value = next.to_string_lossy();
-                if is_bool_arg(&next_value) {
-                    converted.push(OsString::from(format!("--compact={next_value}")));
-                    i += 2;
-                    continue;
-                }
-            }
-            converted.push(OsString::from("--compact=true"));
-            i += 1;
+        let token_ref = token.as_ref();
+
+        if token_ref == "--compact" || token_ref == "-c" {
+            i += compact_option_width(args, i);
+            continue;
+        }
+        if token_ref.starts_with('-') {
+            i += if root_value_options.consumes_value(token_ref) && !token_ref.contains('=') {
+                2
+            } else {
+                1
+            };
             continue;
         }
 
-        converted.push(args[i].clone());
-        i += 1;
+        return Some(i);
     }
+    None
+}
 
-    converted
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PlainHelpMisuse {
+    help_command: String,
 }
 
-fn preprocess_cli_args(args: Vec<OsString>) -> Vec<OsString> {
-    let args = preprocess_compact_args(args);
-    preprocess_privacy_args(args)
-}
-
-fn pre_parse_output_options(args: &[OsString]) -> (OutputFormat, bool) {
-    let Ok(matches) = Cli::command()
-        .ignore_errors(true)
-        .try_get_matches_from(args)
-    else {
-        return (OutputFormat::Table, true);
-    };
-    (
-        matches
-            .get_one::<OutputFormat>("output")
-            .copied()
-            .unwrap_or(OutputFormat::Table),
-        matches.get_one::<bool>("compact").copied().unwrap_or(true),
-    )
-}
-
-fn preprocess_privacy_args(args: Vec<OsString>) -> Vec<OsString> {
-    let args = preprocess_privacy_get_shortcut(args);
-    preprocess_privacy_upsert_key_flags(args)
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum LanguageGateAction {
-    Continue,
-    Prompt,
-    ExitNonInteractive,
-}
+fn plain_help_misuse(args: &[OsString]) -> Option<PlainHelpMisuse> {
+    let tokens = command_tokens_for_plain_help(args);
+    let help_index = tokens.iter().position(|token| token == "help")?;
+    if help_index == 0 {
+        return Some(PlainHelpMisuse {
+            help_command: "ov --help".to_string(),
+        });
+    }
 
-fn language_gate_action(
-    args: &[OsString],
-    has_saved_language: bool,
-    is_interactive: bool,
-) -> LanguageGateAction {
-    if has_saved_language
-        || is_language_command_request(args)
-        || is_config_agent_command_request(args)
-    {
-        LanguageGateAction::Continue
-    } else if is_interactive {
-        LanguageGateAction::Prompt
-    } else {
-        LanguageGateAction::ExitNonInteractive
+    let command = tokens
+        .first()
+        .map(|token| canonical_plain_help_token(token))?;
+    if allows_plain_help_as_user_input(command) {
+        return None;
     }
-}
 
-async fn ensure_language_selected_before_command(args: &[OsString]) -> Result<bool> {
-    match language_gate_action(
-        args,
-        i18n::has_saved_language(),
-        io::stdin().is_terminal() && io::stdout().is_terminal(),
-    ) {
-        LanguageGateAction::Continue => Ok(true),
-        LanguageGateAction::Prompt => {
-            handlers::handle_language(None).await?;
-            Ok(i18n::has_saved_language())
+    let mut path = vec![command.to_string()];
+    if is_plain_help_group(command) {
+        if help_index == 1 {
+            return Some(PlainHelpMisuse {
+                help_command: prefixed_help_command(&path),
+            });
         }
-        LanguageGateAction::ExitNonInteractive => {
-            eprintln!("{}", language_required_message());
-            std::process::exit(2);
+        if let Some(subcommand) = tokens.get(1).map(|token| canonical_plain_help_token(token)) {
+            path.push(subcommand.to_string());
+        }
+        if command == "task"
+            && path.get(1).is_some_and(|token| token == "watch")
+            && let Some(watch_subcommand) =
+                tokens.get(2).map(|token| canonical_plain_help_token(token))
+            && watch_subcommand != "help"
+        {
+            path.push(watch_subcommand.to_string());
+        }
+        if command == "system"
+            && path.get(1).is_some_and(|token| token == "backend")
+            && let Some(backend_subcommand) =
+                tokens.get(2).map(|token| canonical_plain_help_token(token))
+            && backend_subcommand != "help"
+        {
+            path.push(backend_subcommand.to_string());
         }
+        return Some(PlainHelpMisuse {
+            help_command: prefixed_help_command(&path),
+        });
     }
-}
 
-fn is_language_command_request(args: &[OsString]) -> bool {
-    matches!(
-        first_command_token(args).as_deref(),
-        Some("language" | "lang")
-    )
+    Some(PlainHelpMisuse {
+        help_command: prefixed_help_command(&path),
+    })
 }
 
-fn first_command_token(args: &[OsString]) -> Option<String> {
-    let root_value_options = cli_root_value_options();
-    let mut index = 1usize;
+fn command_tokens_for_plain_help(args: &[OsString]) -> Vec<String> {
+    let value_options = cli_value_options();
 
-    while index < args.len() {
-        let token = args[index].to_string_lossy();
-        if token == "--" {
-            return args
-                .get(index + 1)
-                .map(|value| value.to_string_lossy().to_string());
-        }
-        if token == "--compact" || token == "-c" {
-            index += compact_option_width(args, index);
-            continue;
-        }
-        if token.starts_with("--") {
-            index += if root_value_options.consumes_value(&token) && !token.contains('=') {
-                2
-            } else {
-                1
-            };
+    let mut tokens = Vec::new();
+    let mut i = 1;
+    while i < args.len() {
+        let token = args[i].to_string_lossy();
+        let token_ref = token.as_ref();
+        if value_options.consumes_value(token_ref) {
+            i += if token_ref.contains('=') { 1 } else { 2 };
             continue;
         }
-        if token.starts_with('-') {
-            index += if root_value_options.consumes_value(&token) && !token.contains('=') {
-                2
-            } else {
-                1
-            };
+        if token_ref.starts_with('-') {
+            i += 1;
             continue;
         }
-        return Some(token.to_string());
+        tokens.push(token.into_owned());
+        i += 1;
     }
+    tokens
+}
 
-    None
+fn cli_value_options() -> cli_arg_scan::ValueOptions {
+    let mut command = Cli::command();
+    command.build();
+    cli_arg_scan::ValueOptions::from_command(&command)
 }
 
-fn compact_option_width(args: &[OsString], index: usize) -> usize {
-    if args
-        .get(index + 1)
-        .is_some_and(|value| is_bool_arg(&value.to_string_lossy()))
-    {
-        2
-    } else {
-        1
+fn cli_root_value_options() -> cli_arg_scan::ValueOptions {
+    let mut command = Cli::command();
+    command.build();
+    cli_arg_scan::ValueOptions::from_command_arguments(&command)
+}
+
+fn canonical_plain_help_token(token: &str) -> &str {
+    match token {
+        "list" => "ls",
+        "del" | "delete" => "rm",
+        "rename" => "mv",
+        "copy" => "cp",
+        "lang" => "language",
+        "skill" => "skills",
+        other => other,
     }
 }
 
-fn is_bool_arg(value: &str) -> bool {
+fn allows_plain_help_as_user_input(command: &str) -> bool {
     matches!(
-        value,
-        "true" | "false" | "True" | "False" | "TRUE" | "FALSE"
+        command,
+        "add-resource" | "add-skill" | "find" | "search" | "grep" | "glob" | "add-memory"
     )
 }
 
-fn language_required_message() -> String {
-    format!(
-        "{} {}\n{}:\n  {}\n  {}",
-        theme::brand_title("OpenViking").bold(),
-        theme::body("needs a display language before running commands."),
-        theme::strong("Run one of"),
-        theme::command("ov language en").bold(),
-        theme::command("ov language zh-CN").bold(),
+fn is_plain_help_group(command: &str) -> bool {
+    matches!(
+        command,
+        "config"
+            | "task"
+            | "admin"
+            | "system"
+            | "session"
+            | "memory"
+            | "privacy"
+            | "observer"
+            | "skills"
     )
 }
 
-fn language_command_can_run_picker(has_language_value: bool, is_interactive: bool) -> bool {
-    has_language_value || is_interactive
-}
-
-#[tokio::main]
-async fn main() {
-    let args = preprocess_cli_args(std::env::args_os().collect());
-    let command_display = error_ui::display_command(&args);
-    let (pre_parse_output_format, pre_parse_compact) = pre_parse_output_options(&args);
-    match ensure_language_selected_before_command(&args).await {
-        Ok(true) => {}
-        Ok(false) => return,
-        Err(e) => {
-            error_ui::print_runtime_error(
-                &command_display,
-                &e,
-                pre_parse_output_format,
-                pre_parse_compact,
-                false,
-            );
-            std::process::exit(2);
-        }
-    }
-
-    if help_ui::is_top_level_help_request(&args) {
-        print!("{}", help_ui::render_top_level_help());
-        return;
-    }
-    if let Some(help) = help_ui::render_command_help_request(&args) {
-        print!("{help}");
-        return;
-    }
-    if let Some(misuse) = plain_help_misuse(&args) {
-        let report = error_ui::report_for_plain_help_error(&command_display, misuse.help_command);
-        error_ui::print_report(&report, false);
-        std::process::exit(2);
+fn prefixed_help_command(path: &[String]) -> String {
+    if path.is_empty() {
+        "ov --help".to_string()
+    } else {
+        format!("ov {} --help", path.join(" "))
     }
+}
 
-    let mut preloaded_required_config = if pre_parse_requires_cli_config_file(&args) {
-        match Config::load_required() {
-            Ok(config) => Some(config),
-            Err(e) => {
-                error_ui::print_runtime_error(
-                    &command_display,
-                    &e,
-                    pre_parse_output_format,
-                    pre_parse_compact,
-                    false,
-                );
-                std::process::exit(2);
-            }
-        }
-    } else {
-        None
+fn pre_parse_requires_cli_config_file(args: &[OsString]) -> bool {
+    let tokens = command_tokens_for_config_gate(args);
+    let Some(command) = tokens
+        .first()
+        .map(|token| canonical_plain_help_token(token))
+    else {
+        return false;
     };
 
-    let cli = match Cli::try_parse_from(args.clone()) {
-        Ok(cli) => cli,
-        Err(error) => {
-            if error.exit_code() == 0 {
-                let _ = error.print();
-            } else {
-                let report = error_ui::report_for_clap_error(&args, &error.to_string());
-                error_ui::print_report(&report, false);
-            }
-            std::process::exit(error.exit_code());
-        }
-    };
-
-    let output_format = cli.output;
-    let compact = cli.compact;
-    let legacy_upload_options = UploadCliOptions {
-        progress: cli.progress,
-        no_progress: cli.no_progress,
-        verbose: cli.verbose,
-    };
-    if let Some(message) = legacy_upload_option_error(legacy_upload_options, &cli.command) {
-        let report = error_ui::report_for_message_error(
-            &command_display,
-            "Command Error",
-            message,
-            vec![
-                error_ui::ErrorAction::new("ov add-resource --help", "Show add-resource options"),
-                error_ui::ErrorAction::new("ov add-skill --help", "Show add-skill options"),
-            ],
-        );
-        error_ui::print_report(&report, false);
-        std::process::exit(2);
+    match command {
+        "config" => config_command_requires_cli_config_file(&tokens),
+        "language" | "version" => false,
+        "task" => known_task_command_requires_config(&tokens),
+        "admin" => tokens
+            .get(1)
+            .map(|token| is_admin_subcommand(token))
+            .unwrap_or(false),
+        "system" => known_system_command_requires_config(&tokens),
+        "add" => !args.iter().any(|arg| arg.to_str() == Some("--check-only")),
+        "search" => !matches!(
+            tokens.get(1).map(String::as_str),
+            Some("save" | "list" | "rm")
+        ),
+        "session" => tokens
+            .get(1)
+            .map(|token| is_session_subcommand(token))
+            .unwrap_or(false),
+        "memory" => tokens
+            .get(1)
+            .map(|token| is_memory_subcommand(token))
+            .unwrap_or(false),
+        "privacy" => tokens
+            .get(1)
+            .map(|token| is_privacy_subcommand(token))
+            .unwrap_or(false),
+        "skills" => tokens
+            .get(1)
+            .map(|token| is_skill_subcommand(token))
+            .unwrap_or(false),
+        "observer" => tokens
+            .get(1)
+            .map(|token| is_observer_subcommand(token))
+            .unwrap_or(false),
+        _ => is_top_level_server_command(command),
     }
+}
 
-    // Check this before loading config so misplaced --sudo reports the command rule directly.
-    if cli.sudo && !cli.command.supports_sudo() {
-        let language = i18n::Language::current();
-        let (title, message, actions) = match language {
-            i18n::Language::En => (
-                "Command Error",
-                "--sudo is only supported for admin, system, reindex, task status, and task list commands.",
-                vec![
-                    error_ui::ErrorAction::new("ov admin --help", "Show admin commands"),
-                    error_ui::ErrorAction::new("ov system --help", "Show system commands"),
-                    error_ui::ErrorAction::new("ov reindex --help", "Show reindex options"),
-                    error_ui::ErrorAction::new("ov task --help", "Show task commands"),
-                ],
-            ),
-            i18n::Language::ZhCn => (
-                "命令错误",
-                "--sudo 只支持 admin、system、reindex、task status 和 task list 命令。",
-                vec![
-                    error_ui::ErrorAction::new("ov admin --help", "查看管理命令"),
-                    error_ui::ErrorAction::new("ov system --help", "查看系统命令"),
-                    error_ui::ErrorAction::new("ov reindex --help", "查看重建索引选项"),
-                    error_ui::ErrorAction::new("ov task --help", "查看任务命令"),
-                ],
-            ),
-        };
-        let report = error_ui::report_for_message_error(&command_display, title, message, actions);
-        error_ui::print_report(&report, false);
-        std::process::exit(2);
+fn config_command_requires_cli_config_file(tokens: &[String]) -> bool {
+    matches!(tokens.get(1).map(String::as_str), Some("show" | "validate"))
+}
+
+fn is_config_agent_command_request(args: &[OsString]) -> bool {
+    let tokens = command_tokens_for_config_gate(args);
+    if tokens.first().map(String::as_str) != Some("config") {
+        return false;
     }
 
-    if let Commands::Language { language } = &cli.command {
-        if !language_command_can_run_picker(
-            language.is_some(),
-            io::stdin().is_terminal() && io::stdout().is_terminal(),
-        ) {
-            eprintln!("{}", language_required_message());
-            std::process::exit(2);
-        }
-        if let Err(e) = handlers::handle_language(language.clone()).await {
-            error_ui::print_runtime_error(
-                &command_display,
-                &e,
-                output_format,
-                compact,
-                cli.verbose,
-            );
-            std::process::exit(2);
-        }
-        return;
+    match tokens.get(1).map(String::as_str) {
+        Some("add" | "edit" | "delete" | "list") => true,
+        Some("switch") => tokens.get(2).is_some(),
+        _ => false,
     }
+}
 
-    let config_result = if cli.command.requires_cli_config_file() {
-        match preloaded_required_config.take() {
-            Some(config) => Ok(config),
-            None => Config::load_required(),
-        }
-    } else {
-        Config::load_default()
-    };
-    let config = match config_result {
-        Ok(config) => config,
-        Err(e) => {
-            error_ui::print_runtime_error(
-                &command_display,
-                &e,
-                output_format,
-                compact,
-                cli.verbose,
+fn command_tokens_for_config_gate(args: &[OsString]) -> Vec<String> {
+    let value_options = cli_value_options();
+    let root_value_options = cli_root_value_options();
+    let mut tokens = Vec::new();
+    let mut seen_command = false;
+    let mut i = 1;
+
+    while i < args.len() {
+        let token = args[i].to_string_lossy();
+        let token_ref = token.as_ref();
+
+        if token_ref == "--" {
+            tokens.extend(
+                args.iter()
+                    .skip(i + 1)
+                    .map(|arg| arg.to_string_lossy().to_string()),
             );
-            std::process::exit(2);
+            break;
         }
-    };
-    let ctx = CliContext::from_config(
-        config,
-        output_format,
-        compact,
-        cli.account.clone(),
-        cli.user.clone(),
-        cli.actor_peer_id.clone(),
-        cli.sudo,
-        None,
-        None,
-        if cli.profile { Some(true) } else { None },
-    );
-    let verbose_errors = ctx.is_verbose();
 
-    // Check if --sudo is used but root_api_key is not configured
-    if ctx.sudo && ctx.config.root_api_key.is_none() {
-        let language = i18n::Language::current();
-        let (title, message, actions) = match language {
-            i18n::Language::En => (
-                "Configuration Error",
-                "--sudo requires root_api_key in ~/.openviking/ovcli.conf.",
-                vec![
-                    error_ui::ErrorAction::new("ov config", "Edit the active config"),
-                    error_ui::ErrorAction::new("ov config show", "Show the active config"),
-                ],
-            ),
-            i18n::Language::ZhCn => (
-                "配置错误",
-                "--sudo 需要在 ~/.openviking/ovcli.conf 中配置 root_api_key。",
-                vec![
-                    error_ui::ErrorAction::new("ov config", "编辑当前配置"),
-                    error_ui::ErrorAction::new("ov config show", "显示当前配置"),
-                ],
-            ),
-        };
-        let report = error_ui::report_for_message_error(&command_display, title, message, actions);
-        error_ui::print_report(&report, false);
-        std::process::exit(2);
-    };
+        if !seen_command {
+            if token_ref == "--compact" || token_ref == "-c" {
+                i += compact_option_width(args, i);
+                continue;
+            }
+            if token_ref.starts_with("--") {
+                i += if root_value_options.consumes_value(token_ref) && !token_ref.contains('=') {
+                    2
+                } else {
+                    1
+                };
+                continue;
+            }
+            if token_ref.starts_with('-') {
+                i += if root_value_options.consumes_value(token_ref) && !token_ref.contains('=') {
+                    2
+                } else {
+                    1
+                };
+                continue;
+            }
+            seen_command = true;
+            tokens.push(token.into_owned());
+            i += 1;
+            continue;
+        }
 
-    let result = match cli.command {
-        Commands::AddResource {
-            path,
-            to,
-            parent,
-            parent_auto_create,
-            reason,
-            instruction,
-            wait,
-            timeout,
-            strict_mode,
-            ignore_dirs,
-            include,
-            exclude,
-            no_directly_upload_media,
-            watch_interval,
-            resource_args,
-            upload_options,
-        } => {
-            let ctx =
-                ctx.with_upload_options(upload_options.merged_with_legacy(legacy_upload_options));
-            handlers::handle_add_resource(
-                path,
-                to,
-                parent,
-                parent_auto_create,
-                reason,
-                instruction,
-                wait,
-                timeout,
-                strict_mode,
-                ignore_dirs,
-                include,
-                exclude,
-                no_directly_upload_media,
-                watch_interval,
-                resource_args,
-                ctx,
-            )
-            .await
+        if token_ref == "--compact" || token_ref == "-c" {
+            i += compact_option_width(args, i);
+            continue;
         }
-        Commands::AddSkill {
-            data,
-            wait,
-            timeout,
-            parent,
-            upload_options,
-        } => {
-            let ctx =
-                ctx.with_upload_options(upload_options.merged_with_legacy(legacy_upload_options));
-            handlers::handle_add_skill(data, wait, timeout, parent, ctx).await
+        if value_options.consumes_value(token_ref) {
+            i += if token_ref.contains('=') { 1 } else { 2 };
+            continue;
         }
-        Commands::Skills { action } => match action {
-            SkillCommands::Add {
-                source,
-                skills,
-                list,
-                wait,
-                yes,
-                parent,
-            } => {
-                let client = ctx.get_client();
-                commands::skills::add(
-                    &client,
-                    &source,
-                    skills,
-                    list,
-                    wait,
-                    yes,
-                    ctx.should_show_progress(),
-                    ctx.is_verbose(),
-                    ctx.output_format,
-                    ctx.compact,
-                    parent.as_deref(),
-                )
-                .await
+        if token_ref.starts_with('-') {
+            i += 1;
+            continue;
+        }
+        tokens.push(token.into_owned());
+        i += 1;
+    }
+
+    tokens
+}
+
+fn known_task_command_requires_config(tokens: &[String]) -> bool {
+    match tokens.get(1).map(String::as_str) {
+        Some("status" | "list") => true,
+        Some("watch") => match tokens.get(2).map(String::as_str) {
+            None => true,
+            Some(token) => is_watch_subcommand(token),
+        },
+        _ => false,
+    }
+}
+
+fn known_system_command_requires_config(tokens: &[String]) -> bool {
+    match tokens.get(1).map(String::as_str) {
+        Some("wait" | "status" | "health" | "consistency") => true,
+        Some("backend") => matches!(
+            tokens.get(2).map(String::as_str),
+            None | Some("sync-status" | "sync-retry")
+        ),
+        Some("crypto") => matches!(tokens.get(2).map(String::as_str), None | Some("init-key")),
+        _ => false,
+    }
+}
+
+fn is_top_level_server_command(command: &str) -> bool {
+    matches!(
+        command,
+        "add-resource"
+            | "add-skill"
+            | "add"
+            | "clip"
+            | "ls"
+            | "tree"
+            | "mkdir"
+            | "rm"
+            | "mv"
+            | "cp"
+            | "stat"
+            | "read"
+            | "abstract"
+            | "overview"
+            | "write"
+            | "append"
+            | "edit"
+            | "get"
+            | "tag"
+            | "meta"
+            | "find"
+            | "search"
+            | "grep"
+            | "glob"
+            | "context"
+            | "tokens"
+            | "ask"
+            | "add-memory"
+            | "relations"
+            | "link"
+            | "unlink"
+            | "export"
+            | "backup"
+            | "import"
+            | "restore"
+            | "watch"
+            | "tui"
+            | "pick"
+            | "workspace"
+            | "chat"
+            | "wait"
+            | "status"
+            | "health"
+            | "reindex"
+            | "mcp"
+            | "api"
+            | "bench"
+            | "audit"
+            | "stats"
+            | "top"
+            | "events"
+            | "jobs"
+            | "run"
+    )
+}
+
+fn is_watch_subcommand(token: &str) -> bool {
+    matches!(
+        token,
+        "ls" | "show" | "rm" | "pause" | "resume" | "update" | "trigger"
+    )
+}
+
+fn is_skill_subcommand(token: &str) -> bool {
+    matches!(
+        token,
+        "add" | "list" | "ls" | "find" | "show" | "update" | "remove" | "rm" | "delete" | "export"
+    )
+}
+
+fn is_admin_subcommand(token: &str) -> bool {
+    matches!(
+        token,
+        "create-account"
+            | "list-accounts"
+            | "delete-account"
+            | "migrate"
+            | "register-user"
+            | "list-users"
+            | "remove-user"
+            | "set-role"
+            | "regenerate-key"
+            | "user"
+    )
+}
+
+fn is_observer_subcommand(token: &str) -> bool {
+    matches!(
+        token,
+        "queue" | "vikingdb" | "models" | "transaction" | "retrieval" | "filesystem" | "system"
+    )
+}
+
+fn is_session_subcommand(token: &str) -> bool {
+    matches!(
+        token,
+        "new"
+            | "list"
+            | "get"
+            | "get-session-context"
+            | "get-session-archive"
+            | "delete"
+            | "add-message"
+            | "add-messages"
+            | "chat"
+            | "extract"
+            | "export"
+            | "import"
+            | "tail"
+            | "commit"
+    )
+}
+
+fn is_memory_subcommand(token: &str) -> bool {
+    matches!(token, "ls" | "list" | "show")
+}
+
+fn is_privacy_subcommand(token: &str) -> bool {
+    matches!(
+        token,
+        "categories" | "list" | "get" | "upsert" | "versions" | "version" | "activate"
+    )
+}
+
+fn preprocess_privacy_get_shortcut(args: Vec<OsString>) -> Vec<OsString> {
+    let Some(cmd_idx) = find_command_index(&args) else {
+        return args;
+    };
+    if args[cmd_idx].to_string_lossy() != "privacy" {
+        return args;
+    }
+    let Some(next) = args.get(cmd_idx + 1) else {
+        return args;
+    };
+    let next_token = next.to_string_lossy();
+    if next_token.starts_with('-') || is_privacy_subcommand(&next_token) {
+        return args;
+    }
+
+    let mut out = Vec::with_capacity(args.len() + 1);
+    out.extend(args[..=cmd_idx].iter().cloned());
+    out.push(OsString::from("get"));
+    out.extend(args[cmd_idx + 1..].iter().cloned());
+    out
+}
+
+fn preprocess_privacy_upsert_key_flags(args: Vec<OsString>) -> Vec<OsString> {
+    let Some(cmd_idx) = find_command_index(&args) else {
+        return args;
+    };
+    if args[cmd_idx].to_string_lossy() != "privacy" {
+        return args;
+    }
+    if args
+        .get(cmd_idx + 1)
+        .map(|s| s.to_string_lossy().to_string())
+        != Some("upsert".to_string())
+    {
+        return args;
+    }
+
+    let mut converted: Vec<OsString> = Vec::with_capacity(args.len());
+    let mut i = 0;
+
+    while i < args.len() {
+        let arg_lossy = args[i].to_string_lossy();
+
+        if i > cmd_idx + 1 && arg_lossy == "--" {
+            i += 1;
+            continue;
+        }
+
+        if i > cmd_idx + 1 && arg_lossy.starts_with("--key-") {
+            let suffix = &arg_lossy[6..];
+            if suffix.is_empty() {
+                converted.push(args[i].clone());
+                i += 1;
+                continue;
             }
-            SkillCommands::List { node_limit, parent } => {
-                let client = ctx.get_client();
-                commands::skills::list(
-                    &client,
-                    node_limit,
-                    ctx.output_format,
-                    ctx.compact,
-                    parent.as_deref(),
-                )
-                .await
+
+            if let Some((key, value)) = suffix.split_once('=') {
+                converted.push(OsString::from("--key"));
+                converted.push(OsString::from(format!("{}={}", key, value)));
+                i += 1;
+                continue;
             }
-            SkillCommands::Find {
-                query,
-                node_limit,
-                threshold,
-                level,
-                parent,
-            } => {
-                let client = ctx.get_client();
-                commands::skills::find(
-                    &client,
-                    &query,
-                    node_limit,
-                    threshold,
-                    level,
-                    ctx.output_format,
-                    ctx.compact,
-                    parent.as_deref(),
-                )
-                .await
+
+            if i + 1 < args.len() {
+                let next_val = args[i + 1].to_string_lossy();
+                converted.push(OsString::from("--key"));
+                converted.push(OsString::from(format!("{}={}", suffix, next_val)));
+                i += 2;
+                continue;
             }
-            SkillCommands::Show {
-                name,
-                level,
-                files,
-                source,
-                format,
-                content,
-                parent,
-            } => {
-                let client = ctx.get_client();
-                let output_format = format
-                    .as_deref()
-                    .map(OutputFormat::from)
-                    .unwrap_or(ctx.output_format);
-                let level = if content { Some(2) } else { level };
-                commands::skills::show(
-                    &client,
-                    &name,
-                    level,
-                    files,
-                    source,
-                    output_format,
-                    ctx.compact,
-                    parent.as_deref(),
-                )
-                .await
-            }
-            SkillCommands::Update {
-                skills,
-                wait,
-                yes,
-                parent,
-            } => {
-                let client = ctx.get_client();
-                commands::skills::update(
-                    &client,
-                    skills,
-                    wait,
-                    yes,
-                    ctx.output_format,
-                    ctx.compact,
-                    parent.as_deref(),
-                )
-                .await
-            }
-            SkillCommands::Remove {
-                skills,
-                all,
-                yes,
-                parent,
-            } => {
-                let client = ctx.get_client();
-                commands::skills::remove(
-                    &client,
-                    skills,
-                    all,
-                    yes,
-                    ctx.output_format,
-                    ctx.compact,
-                    parent.as_deref(),
-                )
-                .await
-            }
-            SkillCommands::Validate { path, strict } => {
-                let client = ctx.get_client();
-                commands::skills::validate(&client, &path, strict, ctx.output_format, ctx.compact)
-                    .await
-            }
-        },
-        Commands::Relations { uri } => handlers::handle_relations(uri, ctx).await,
-        Commands::Link {
-            from_uri,
-            to_uris,
-            reason,
-        } => handlers::handle_link(from_uri, to_uris, reason, ctx).await,
-        Commands::Unlink { from_uri, to_uri } => {
-            handlers::handle_unlink(from_uri, to_uri, ctx).await
-        }
-        Commands::Export {
-            uri,
-            to,
-            include_vectors,
-        } => handlers::handle_export(uri, to, include_vectors, ctx).await,
-        Commands::Backup {
-            to,
-            include_vectors,
-        } => handlers::handle_backup(to, include_vectors, ctx).await,
-        Commands::Import {
-            file_path,
-            target_uri,
-            on_conflict,
-            vector_mode,
-        } => handlers::handle_import(file_path, target_uri, on_conflict, vector_mode, ctx).await,
-        Commands::Restore {
-            file_path,
-            on_conflict,
-            vector_mode,
-        } => handlers::handle_restore(file_path, on_conflict, vector_mode, ctx).await,
-        Commands::Wait { timeout } => {
-            let client = ctx.get_client();
-            commands::system::wait(&client, timeout, ctx.output_format, ctx.compact).await
+
+            converted.push(args[i].clone());
+            i += 1;
+            continue;
         }
-        Commands::Task { action } => match action {
-            TaskCommands::Status { task_id } => {
-                let client = ctx.get_client();
-                commands::task::status(&client, &task_id, ctx.output_format, ctx.compact).await
-            }
-            TaskCommands::List { task_type, status } => {
-                let client = ctx.get_client();
-                commands::task::list(
-                    &client,
-                    task_type.as_deref(),
-                    status.as_deref(),
-                    ctx.output_format,
-                    ctx.compact,
-                )
-                .await
-            }
-            TaskCommands::Watch { action } => {
-                let client = ctx.get_client();
-                match action {
-                    WatchCommands::Ls { active_only } => {
-                        commands::watch::ls(&client, active_only, ctx.output_format, ctx.compact)
-                            .await
-                    }
-                    WatchCommands::Show { key } => {
-                        commands::watch::show(&client, &key, ctx.output_format, ctx.compact).await
-                    }
-                    WatchCommands::Rm { key } => {
-                        commands::watch::rm(&client, &key, ctx.output_format, ctx.compact).await
-                    }
-                    WatchCommands::Pause { key } => {
-                        commands::watch::pause(&client, &key, ctx.output_format, ctx.compact).await
-                    }
-                    WatchCommands::Resume { key } => {
-                        commands::watch::resume(&client, &key, ctx.output_format, ctx.compact).await
-                    }
-                    WatchCommands::Update {
-                        key,
-                        interval,
-                        active,
-                        reason,
-                        instruction,
-                    } => {
-                        commands::watch::update(
-                            &client,
-                            &key,
-                            interval,
-                            active,
-                            reason,
-                            instruction,
-                            ctx.output_format,
-                            ctx.compact,
-                        )
-                        .await
-                    }
-                    WatchCommands::Trigger { key } => {
-                        commands::watch::trigger(&client, &key, ctx.output_format, ctx.compact)
-                            .await
-                    }
+
+        converted.push(args[i].clone());
+        i += 1;
+    }
+
+    converted
+}
+
+fn preprocess_compact_args(args: Vec<OsString>) -> Vec<OsString> {
+    if args.is_empty() {
+        return args;
+    }
+
+    let mut converted = Vec::with_capacity(args.len());
+    converted.push(args[0].clone());
+    let mut i = 1;
+
+    while i < args.len() {
+        let token = args[i].to_string_lossy();
+        if token == "--compact" || token == "-c" {
+            if let Some(next) = args.get(i + 1) {
+                let next_value = next.to_string_lossy();
+                if is_bool_arg(&next_value) {
+                    converted.push(OsString::from(format!("--compact={next_value}")));
+                    i += 2;
+                    continue;
                 }
             }
-        },
-        Commands::Snapshot { cmd } => {
-            let client = ctx.get_client();
-            commands::snapshot::dispatch(&client, cmd, ctx.output_format, ctx.compact).await
-        }
-        Commands::Status { verbose } => {
-            let client = ctx.get_client();
-            commands::system::diagnostic_status(
-                &client,
-                &ctx.config,
-                ctx.output_format,
-                ctx.compact,
-                verbose,
-            )
-            .await
+            converted.push(OsString::from("--compact=true"));
+            i += 1;
+            continue;
         }
-        Commands::Health => handlers::handle_health(ctx).await,
-        Commands::System { action } => handlers::handle_system(action, ctx).await,
-        Commands::Observer { action } => handlers::handle_observer(action, ctx).await,
-        Commands::Session { action } => handlers::handle_session(action, ctx).await,
-        Commands::Admin { action } => handlers::handle_admin(action, ctx).await,
-        Commands::Privacy { action } => handlers::handle_privacy(action, ctx).await,
-        Commands::Ls {
-            uri,
-            simple,
-            recursive,
-            abs_limit,
-            all,
-            node_limit,
-        } => handlers::handle_ls(uri, simple, recursive, abs_limit, all, node_limit, ctx).await,
-        Commands::Tree {
-            uri,
-            abs_limit,
-            all,
-            node_limit,
-            level_limit,
-        } => handlers::handle_tree(uri, abs_limit, all, node_limit, level_limit, ctx).await,
-        Commands::Mkdir { uri, description } => handlers::handle_mkdir(uri, description, ctx).await,
-        Commands::Rm {
-            uri,
-            recursive,
-            wait,
-            timeout,
-        } => handlers::handle_rm(uri, recursive, wait, timeout, ctx).await,
-        Commands::Mv { from_uri, to_uri } => handlers::handle_mv(from_uri, to_uri, ctx).await,
-        Commands::Stat { uri } => handlers::handle_stat(uri, ctx).await,
-        Commands::Attrs { action } => match action {
-            AttrsCommands::Get { uri, key } => handlers::handle_attrs(uri, key, ctx).await,
-            AttrsCommands::SetTags {
-                uri,
-                tags,
-                mode,
-                recursive,
-            } => handlers::handle_set_tags(uri, tags, mode, recursive, ctx).await,
+
+        converted.push(args[i].clone());
+        i += 1;
+    }
+
+    converted
+}
+
+fn preprocess_cli_args(args: Vec<OsString>) -> Vec<OsString> {
+    let args = preprocess_compact_args(args);
+    preprocess_privacy_args(args)
+}
+
+fn pre_parse_output_options(args: &[OsString]) -> (OutputFormat, bool) {
+    let Ok(matches) = Cli::command()
+        .ignore_errors(true)
+        .try_get_matches_from(args)
+    else {
+        return (OutputFormat::Table, true);
+    };
+    (
+        matches
+            .get_one::<OutputFormat>("output")
+            .copied()
+            .unwrap_or(OutputFormat::Table),
+        matches.get_one::<bool>("compact").copied().unwrap_or(true),
+    )
+}
+
+/// `ov context assemble --query "<q>"` names the question with the flag that
+/// elsewhere filters output; it only counts as a filter once the question has
+/// been given as the argument.
+/// Commands that take a search query read a lone `--query` as that query
+/// rather than as an output filter.
+fn adopt_query_argument(cli: &mut Cli) {
+    let query = match &mut cli.command {
+        Commands::Context(ContextArgs {
+            action: ContextCommands::Assemble(args),
+        }) => &mut args.query,
+        Commands::Search(args) => match &mut args.action {
+            Some(SearchCommands::Save { query, .. }) => query,
+            _ => return,
         },
-        Commands::AddMemory { content } => handlers::handle_add_memory(content, ctx).await,
-        Commands::Tui { uri } => handlers::handle_tui(uri, ctx).await,
-        Commands::Chat {
-            message,
-            session,
-            sender,
-            stream,
-            no_format,
-            no_history,
-        } => {
-            let session_id = session.or_else(|| config::get_or_create_machine_id().ok());
-            let endpoint = if let Ok(env_endpoint) = std::env::var("VIKINGBOT_ENDPOINT") {
-                Some(env_endpoint)
-            } else if let Ok(config_url) = std::env::var("OPENVIKING_URL") {
-                Some(format!("{}/bot/v1", config_url))
-            } else {
-                None
-            };
-            let api_key = std::env::var("VIKINGBOT_API_KEY").ok();
-            let cmd = commands::chat::ChatCommand {
-                endpoint,
-                api_key,
-                account: ctx.config.account.clone(),
-                user: ctx.config.user.clone(),
-                actor_peer_id: ctx.config.effective_actor_peer_id(),
-                session: session_id,
-                sender,
-                message,
-                stream,
-                no_format,
-                no_history,
-            };
-            cmd.run().await
-        }
-        Commands::Config { action } => handlers::handle_config(action, ctx).await,
-        Commands::Language { .. } => unreachable!("language command is handled before config load"),
-        Commands::Version => {
-            println!(
-                "{}     {}",
-                theme::muted("CLI:"),
-                theme::version(env!("OPENVIKING_CLI_VERSION")).bold()
+        Commands::Pick { query, .. } => query,
+        _ => return,
+    };
+    if query.is_none() {
+        *query = cli.query.take();
+    }
+}
+
+/// `ov <name> ...` for a `<name>` that is not a built-in command: where the
+/// name is in `args`, and the `ov-<name>` plugin that runs it.
+fn plugin_request(args: &[OsString]) -> Option<(usize, std::path::PathBuf)> {
+    let index = find_command_index(args)?;
+    let name = args[index].to_str()?;
+    if name == "help" || Cli::command().find_subcommand(name).is_some() {
+        return None;
+    }
+    Some((index, commands::plugin::find(name)?))
+}
+
+/// Hand `ov <name> ...` to its plugin with the active config, credentials,
+/// and the global options given before `<name>`; see `commands::plugin`.
+fn run_plugin(args: &[OsString], index: usize, plugin: &std::path::Path) -> Result<i32> {
+    let name = args[index].to_string_lossy();
+    let matches = Cli::command()
+        .ignore_errors(true)
+        .try_get_matches_from(&args[..index])
+        .map_err(|e| Error::Client(e.to_string()))?;
+    let given = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+    let unsupported = Cli::command()
+        .get_arguments()
+        .filter(|arg| arg.is_global_set())
+        .find(|arg| {
+            let id = arg.get_id().as_str();
+            given(id)
+                && !matches!(
+                    id,
+                    "output" | "compact" | "no_color" | "workspace" | "account" | "user"
+                )
+        })
+        .map(|arg| arg.get_long().unwrap_or(arg.get_id().as_str()).to_string());
+    if let Some(long) = unsupported {
+        return Err(Error::Client(format!(
+            "--{long} is not passed to plugins; put it after `{name}` if ov-{name} takes it"
+        )));
+    }
+    let text = |id: &str| matches.get_one::<String>(id).cloned();
+    let config = Config::load_default()?;
+    let auth = config.effective_auth_with_overrides(
+        None,
+        text("workspace").or_else(|| text("account")),
+        text("user"),
+        false,
+    );
+    let config_file = config::active_config_path()
+        .ok()
+        .filter(|path| path.exists());
+    let config_name = std::env::var_os("OPENVIKING_CLI_CONFIG_FILE")
+        .is_none()
+        .then(|| config_wizard::ConfigStore::new().ok())
+        .flatten()
+        .and_then(|store| store.list_configs_report().ok())
+        .and_then(|report| report.configs.into_iter().find(|entry| entry.is_active))
+        .map(|entry| entry.name);
+    let env = commands::plugin::PluginEnv {
+        config_name,
+        config_file,
+        url: config.url.clone(),
+        api_key: auth.api_key,
+        gateway_token: config.effective_gateway_token(),
+        account: auth.account,
+        user: auth.user,
+        output_format: matches
+            .get_one::<OutputFormat>("output")
+            .copied()
+            .unwrap_or(OutputFormat::Table),
+        compact: matches.get_one::<bool>("compact").copied().unwrap_or(true),
+        no_color: matches.get_flag("no_color"),
+    };
+    commands::plugin::run(plugin, &args[index + 1..], &env)
+}
+
+/// Replace `ov search run <name>` with the `ov search` command it was saved as.
+fn expand_saved_search(cli: &mut Cli) -> Result<()> {
+    let Commands::Search(args) = &cli.command else {
+        return Ok(());
+    };
+    let Some(SearchCommands::Run { name, values }) = &args.action else {
+        return Ok(());
+    };
+    let args = commands::saved_search::expand(name, values)?;
+    let argv = ["ov", "search"]
+        .into_iter()
+        .map(String::from)
+        .chain(args.iter().cloned());
+    let saved = Cli::try_parse_from(argv).map_err(|e| {
+        let detail = e.to_string();
+        let detail = detail
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim_start_matches("error: ");
+        Error::Client(format!(
+            "Saved search '{name}' is not a valid `ov search` command: {detail}"
+        ))
+    })?;
+    cli.command = saved.command;
+    Ok(())
+}
+
+fn preprocess_privacy_args(args: Vec<OsString>) -> Vec<OsString> {
+    let args = preprocess_privacy_get_shortcut(args);
+    preprocess_privacy_upsert_key_flags(args)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LanguageGateAction {
+    Continue,
+    Prompt,
+    ExitNonInteractive,
+}
+
+fn language_gate_action(
+    args: &[OsString],
+    has_saved_language: bool,
+    is_interactive: bool,
+) -> LanguageGateAction {
+    if has_saved_language
+        || is_language_command_request(args)
+        || matches!(
+            first_command_token(args).as_deref(),
+            Some("completions" | "gen-man")
+        )
+        || is_config_agent_command_request(args)
+    {
+        LanguageGateAction::Continue
+    } else if is_interactive {
+        LanguageGateAction::Prompt
+    } else {
+        LanguageGateAction::ExitNonInteractive
+    }
+}
+
+async fn ensure_language_selected_before_command(args: &[OsString]) -> Result<bool> {
+    match language_gate_action(
+        args,
+        i18n::has_saved_language(),
+        io::stdin().is_terminal() && io::stdout().is_terminal(),
+    ) {
+        LanguageGateAction::Continue => Ok(true),
+        LanguageGateAction::Prompt => {
+            handlers::handle_language(None).await?;
+            Ok(i18n::has_saved_language())
+        }
+        LanguageGateAction::ExitNonInteractive => {
+            eprintln!("{}", language_required_message());
+            std::process::exit(2);
+        }
+    }
+}
+
+fn is_language_command_request(args: &[OsString]) -> bool {
+    matches!(
+        first_command_token(args).as_deref(),
+        Some("language" | "lang")
+    )
+}
+
+fn first_command_token(args: &[OsString]) -> Option<String> {
+    let root_value_options = cli_root_value_options();
+    let mut index = 1usize;
+
+    while index < args.len() {
+        let token = args[index].to_string_lossy();
+        if token == "--" {
+            return args
+                .get(index + 1)
+                .map(|value| value.to_string_lossy().to_string());
+        }
+        if token == "--compact" || token == "-c" {
+            index += compact_option_width(args, index);
+            continue;
+        }
+        if token.starts_with("--") {
+            index += if root_value_options.consumes_value(&token) && !token.contains('=') {
+                2
+            } else {
+                1
+            };
+            continue;
+        }
+        if token.starts_with('-') {
+            index += if root_value_options.consumes_value(&token) && !token.contains('=') {
+                2
+            } else {
+                1
+            };
+            continue;
+        }
+        return Some(token.to_string());
+    }
+
+    None
+}
+
+fn compact_option_width(args: &[OsString], index: usize) -> usize {
+    if args
+        .get(index + 1)
+        .is_some_and(|value| is_bool_arg(&value.to_string_lossy()))
+    {
+        2
+    } else {
+        1
+    }
+}
+
+fn is_bool_arg(value: &str) -> bool {
+    matches!(
+        value,
+        "true" | "false" | "True" | "False" | "TRUE" | "FALSE"
+    )
+}
+
+fn language_required_message() -> String {
+    format!(
+        "{} {}\n{}:\n  {}\n  {}",
+        theme::brand_title("OpenViking").bold(),
+        theme::body("needs a display language before running commands."),
+        theme::strong("Run one of"),
+        theme::command("ov language en").bold(),
+        theme::command("ov language zh-CN").bold(),
+    )
+}
+
+fn language_command_can_run_picker(has_language_value: bool, is_interactive: bool) -> bool {
+    has_language_value || is_interactive
+}
+
+/// Parse `--query` / `--template` and install them for `output_success`.
+fn install_script_output<'a>(
+    query: Option<&'a str>,
+    template: Option<&'a str>,
+) -> std::result::Result<(), (&'static str, &'a str, String)> {
+    if let Some(source) = query {
+        let query = query::Query::parse(source).map_err(|message| ("--query", source, message))?;
+        output::set_query(query);
+    }
+    if let Some(source) = template {
+        let template =
+            template::Template::parse(source).map_err(|message| ("--template", source, message))?;
+        output::set_template(template);
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    // Answer the completion scripts from `ov completions` before anything else runs.
+    clap_complete::CompleteEnv::with_factory(|| {
+        commands::completions::with_dynamic_values(Cli::command())
+    })
+    .var(commands::completions::COMPLETE_ENV)
+    .complete();
+
+    let args = preprocess_cli_args(std::env::args_os().collect());
+    // Honour --no-color before anything renders, including pre-parse errors and help.
+    if args.iter().any(|arg| arg == "--no-color") {
+        colored::control::set_override(false);
+    }
+    let command_display = error_ui::display_command(&args);
+    let (pre_parse_output_format, pre_parse_compact) = pre_parse_output_options(&args);
+    let typed_args = args.clone();
+    let args = match commands::alias::expand_args(args) {
+        Ok(args) => args,
+        Err(e) => {
+            error_ui::print_runtime_error(
+                &command_display,
+                &e,
+                pre_parse_output_format,
+                pre_parse_compact,
+                false,
+            );
+            std::process::exit(e.exit_code());
+        }
+    };
+    if let Some((index, plugin)) = plugin_request(&args) {
+        let code = run_plugin(&args, index, &plugin).unwrap_or_else(|e| {
+            error_ui::print_runtime_error(
+                &command_display,
+                &e,
+                pre_parse_output_format,
+                pre_parse_compact,
+                false,
+            );
+            e.exit_code()
+        });
+        std::process::exit(code);
+    }
+    match ensure_language_selected_before_command(&args).await {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(e) => {
+            error_ui::print_runtime_error(
+                &command_display,
+                &e,
+                pre_parse_output_format,
+                pre_parse_compact,
+                false,
+            );
+            std::process::exit(2);
+        }
+    }
+
+    if help_ui::is_top_level_help_request(&args) {
+        print!("{}", help_ui::render_top_level_help());
+        return;
+    }
+    if let Some(help) = help_ui::render_command_help_request(&args) {
+        print!("{help}");
+        return;
+    }
+    if help_ui::is_exit_codes_help_request(&args) {
+        // clap rejects the `help` topic itself, so read -o/-c from the options alone.
+        let options: Vec<OsString> = args
+            .iter()
+            .filter(|arg| *arg != "help" && *arg != "exit-codes")
+            .cloned()
+            .collect();
+        let (output_format, compact) = pre_parse_output_options(&options);
+        if output_format.is_json() {
+            output::output_success(help_ui::exit_codes_json(), output_format, compact);
+        } else {
+            print!("{}", help_ui::render_exit_codes_help());
+        }
+        return;
+    }
+    if let Some(misuse) = plain_help_misuse(&args) {
+        let report = error_ui::report_for_plain_help_error(&command_display, misuse.help_command);
+        error_ui::print_report(&report, false);
+        std::process::exit(2);
+    }
+
+    let mut preloaded_required_config = if pre_parse_requires_cli_config_file(&args) {
+        match Config::load_required() {
+            Ok(config) => Some(config),
+            Err(e) => {
+                error_ui::print_runtime_error(
+                    &command_display,
+                    &e,
+                    pre_parse_output_format,
+                    pre_parse_compact,
+                    false,
+                );
+                std::process::exit(e.exit_code());
+            }
+        }
+    } else {
+        None
+    };
+
+    let mut cli = match Cli::try_parse_from(args.clone()) {
+        Ok(cli) => cli,
+        Err(error) => {
+            if error.exit_code() == 0 {
+                let _ = error.print();
+            } else {
+                let report = error_ui::report_for_clap_error(&args, &error.to_string());
+                error_ui::print_report(&report, false);
+            }
+            std::process::exit(error.exit_code());
+        }
+    };
+
+    adopt_query_argument(&mut cli);
+    if let Err(e) = expand_saved_search(&mut cli) {
+        error_ui::print_runtime_error(
+            &command_display,
+            &e,
+            pre_parse_output_format,
+            pre_parse_compact,
+            false,
+        );
+        std::process::exit(e.exit_code());
+    }
+    let mut output_format = cli.output;
+    let compact = cli.compact;
+    if let Err((flag, source, message)) =
+        install_script_output(cli.query.as_deref(), cli.template.as_deref())
+    {
+        let language = i18n::Language::current();
+        let (title, message) = match language {
+            i18n::Language::En => (
+                "Command Error",
+                format!("Invalid {flag} expression '{source}': {message}"),
+            ),
+            i18n::Language::ZhCn => (
+                "命令错误",
+                format!("{flag} 表达式 '{source}' 无效：{message}"),
+            ),
+        };
+        let report = error_ui::report_for_message_error(&command_display, title, message, vec![]);
+        error_ui::print_report(&report, false);
+        std::process::exit(2);
+    }
+    let sort = match cli
+        .sort
+        .as_deref()
+        .map(|spec| (spec, output::SortKey::parse(spec)))
+    {
+        Some((_, Some(sort))) => Some(sort),
+        Some((spec, None)) => {
+            let (title, message) = match i18n::Language::current() {
+                i18n::Language::En => (
+                    "Command Error",
+                    format!(
+                        "Invalid --sort value '{spec}'. Use a column name such as 'score' or '-score'."
+                    ),
+                ),
+                i18n::Language::ZhCn => (
+                    "命令错误",
+                    format!("--sort 值 '{spec}' 无效。请使用列名，例如 'score' 或 '-score'。"),
+                ),
+            };
+            let report =
+                error_ui::report_for_message_error(&command_display, title, message, vec![]);
+            error_ui::print_report(&report, false);
+            std::process::exit(2);
+        }
+        None => None,
+    };
+    if cli.no_pager {
+        pager::disable();
+    }
+    if cli.timing {
+        timing::enable();
+    }
+    if let Err(e) = logging::init(&logging::LogOptions {
+        level: cli.log_level.as_deref(),
+        file: cli.log_file.as_deref(),
+        json: cli.log_format == "json",
+        debug_http: cli.debug_http,
+    }) {
+        error_ui::print_runtime_error(&command_display, &e, output_format, compact, false);
+        std::process::exit(e.exit_code());
+    }
+    output::set_table_options(output::TableOptions {
+        columns: cli
+            .columns
+            .iter()
+            .map(|column| column.trim().to_string())
+            .filter(|column| !column.is_empty())
+            .collect(),
+        sort,
+        bordered: io::stdout().is_terminal(),
+        terminal_width: io::stdout()
+            .is_terminal()
+            .then(|| crossterm::terminal::size().ok())
+            .flatten()
+            .map(|(columns, _)| usize::from(columns)),
+        wrap: cli.wrap,
+        no_truncate: cli.wide,
+    });
+    // Custom table renderers would hide the queried values, so route everything
+    // through the JSON path and let output_success filter it.
+    if (cli.query.is_some() || cli.template.is_some())
+        && matches!(output_format, OutputFormat::Table)
+    {
+        output_format = OutputFormat::Json;
+    }
+    let legacy_upload_options = UploadCliOptions {
+        progress: cli.progress,
+        no_progress: cli.no_progress,
+        verbose: cli.verbose,
+        resume: false,
+    };
+    if let Some(message) = legacy_upload_option_error(legacy_upload_options, &cli.command) {
+        let report = error_ui::report_for_message_error(
+            &command_display,
+            "Command Error",
+            message,
+            vec![
+                error_ui::ErrorAction::new("ov add-resource --help", "Show add-resource options"),
+                error_ui::ErrorAction::new("ov add-skill --help", "Show add-skill options"),
+            ],
+        );
+        error_ui::print_report(&report, false);
+        std::process::exit(2);
+    }
+
+    // Check this before loading config so misplaced --sudo reports the command rule directly.
+    if cli.sudo && !cli.command.supports_sudo() {
+        let language = i18n::Language::current();
+        let (title, message, actions) = match language {
+            i18n::Language::En => (
+                "Command Error",
+                "--sudo is only supported for admin, system, reindex, index, api, task status, and task list commands.",
+                vec![
+                    error_ui::ErrorAction::new("ov admin --help", "Show admin commands"),
+                    error_ui::ErrorAction::new("ov system --help", "Show system commands"),
+                    error_ui::ErrorAction::new("ov reindex --help", "Show reindex options"),
+                    error_ui::ErrorAction::new("ov task --help", "Show task commands"),
+                ],
+            ),
+            i18n::Language::ZhCn => (
+                "命令错误",
+                "--sudo 只支持 admin、system、reindex、index、api、task status 和 task list 命令。",
+                vec![
+                    error_ui::ErrorAction::new("ov admin --help", "查看管理命令"),
+                    error_ui::ErrorAction::new("ov system --help", "查看系统命令"),
+                    error_ui::ErrorAction::new("ov reindex --help", "查看重建索引选项"),
+                    error_ui::ErrorAction::new("ov task --help", "查看任务命令"),
+                ],
+            ),
+        };
+        let report = error_ui::report_for_message_error(&command_display, title, message, actions);
+        error_ui::print_report(&report, false);
+        std::process::exit(2);
+    }
+
+    if let Commands::Language { language } = &cli.command {
+        if !language_command_can_run_picker(
+            language.is_some(),
+            io::stdin().is_terminal() && io::stdout().is_terminal(),
+        ) {
+            eprintln!("{}", language_required_message());
+            std::process::exit(2);
+        }
+        if let Err(e) = handlers::handle_language(language.clone()).await {
+            error_ui::print_runtime_error(
+                &command_display,
+                &e,
+                output_format,
+                compact,
+                cli.verbose,
+            );
+            std::process::exit(2);
+        }
+        return;
+    }
+
+    let config_result = if cli.command.requires_cli_config_file() {
+        match preloaded_required_config.take() {
+            Some(config) => Ok(config),
+            None => Config::load_required(),
+        }
+    } else {
+        Config::load_default()
+    };
+    let config = match config_result.and_then(|mut config| {
+        if let Some(proxy) = cli.connection.proxy.clone() {
+            config.proxy = Some(proxy);
+            config.validate_proxy()?;
+        }
+        if cli.connection.ca_cert.is_some() {
+            config.ca_cert = cli.connection.ca_cert.clone();
+        }
+        if cli.connection.client_cert.is_some() {
+            config.client_cert = cli.connection.client_cert.clone();
+            config.client_key = cli.connection.client_key.clone();
+        } else if cli.connection.client_key.is_some() {
+            config.client_key = cli.connection.client_key.clone();
+        }
+        config.validate_tls()?;
+        if let Some(timeout) = cli.connection.timeout {
+            config.timeout = timeout;
+        }
+        if cli.connection.connect_timeout.is_some() {
+            config.connect_timeout = cli.connection.connect_timeout;
+        }
+        config.validate_timeouts()?;
+        Ok(config)
+    }) {
+        Ok(config) => config,
+        Err(e) => {
+            error_ui::print_runtime_error(
+                &command_display,
+                &e,
+                output_format,
+                compact,
+                cli.verbose,
+            );
+            std::process::exit(e.exit_code());
+        }
+    };
+    let ctx = CliContext::from_config(
+        config,
+        output_format,
+        compact,
+        cli.workspace.clone().or_else(|| cli.account.clone()),
+        cli.user.clone(),
+        cli.actor_peer_id.clone(),
+        cli.sudo,
+        None,
+        None,
+        if cli.profile { Some(true) } else { None },
+    );
+    let verbose_errors = ctx.is_verbose();
+
+    // Check if --sudo is used but root_api_key is not configured
+    if ctx.sudo && ctx.config.root_api_key.is_none() {
+        let language = i18n::Language::current();
+        let (title, message, actions) = match language {
+            i18n::Language::En => (
+                "Configuration Error",
+                "--sudo requires root_api_key in ~/.openviking/ovcli.conf.",
+                vec![
+                    error_ui::ErrorAction::new("ov config", "Edit the active config"),
+                    error_ui::ErrorAction::new("ov config show", "Show the active config"),
+                ],
+            ),
+            i18n::Language::ZhCn => (
+                "配置错误",
+                "--sudo 需要在 ~/.openviking/ovcli.conf 中配置 root_api_key。",
+                vec![
+                    error_ui::ErrorAction::new("ov config", "编辑当前配置"),
+                    error_ui::ErrorAction::new("ov config show", "显示当前配置"),
+                ],
+            ),
+        };
+        let report = error_ui::report_for_message_error(&command_display, title, message, actions);
+        error_ui::print_report(&report, false);
+        std::process::exit(error::EXIT_CONFIG);
+    };
+
+    let queue_offline = cli.offline.queue_offline;
+    if queue_offline && !cli.command.can_queue_offline() {
+        let e = Error::Client(
+            "--queue-offline applies to add-resource, add-skill, add-memory, write, append, mkdir, rm, mv, and session add-message(s), with content not read from stdin"
+                .to_string(),
+        );
+        error_ui::print_runtime_error(&command_display, &e, output_format, compact, false);
+        std::process::exit(e.exit_code());
+    }
+    if let Err(e) = cassette::install_from_env() {
+        error_ui::print_runtime_error(&command_display, &e, output_format, compact, false);
+        std::process::exit(e.exit_code());
+    }
+    // `ov config` stays usable to fix a config whose plugins do not load.
+    if !matches!(cli.command, Commands::Config { .. }) {
+        let command = first_command_token(&args).unwrap_or_default();
+        if let Err(e) = wasm_plugin::install(&ctx.config.wasm_plugin_paths(), &command) {
+            error_ui::print_runtime_error(
+                &command_display,
+                &e,
+                output_format,
+                compact,
+                verbose_errors,
+            );
+            std::process::exit(e.exit_code());
+        }
+    }
+
+    let server_url = ctx.config.url.clone();
+    let record_history = ctx.config.history && !matches!(cli.command, Commands::Recent(_));
+
+    let started = std::time::Instant::now();
+    interrupt::install();
+    let result = interrupt::run(Box::pin(dispatch(cli.command, ctx, legacy_upload_options))).await;
+    timing::print_summary(started.elapsed());
+
+    if result.is_ok()
+        && record_history
+        && let Err(e) = commands::history::record(&typed_args, &args)
+    {
+        tracing::debug!(error = %e, "could not record command history");
+    }
+    if let Err(e) = result {
+        if queue_offline && e.exit_code() == error::EXIT_UNREACHABLE {
+            match commands::queue::enqueue(&server_url, output_format, compact) {
+                Ok(()) => return,
+                Err(queue_error) => {
+                    error_ui::print_runtime_error(
+                        &command_display,
+                        &queue_error,
+                        output_format,
+                        compact,
+                        verbose_errors,
+                    );
+                }
+            }
+        }
+        if !matches!(e, Error::AlreadyReported) {
+            error_ui::print_runtime_error(
+                &command_display,
+                &e,
+                output_format,
+                compact,
+                verbose_errors,
+            );
+        }
+        std::process::exit(e.exit_code());
+    }
+}
+
+/// Run one parsed command; `main` and each line of `ov run` come through here.
+async fn dispatch(
+    command: Commands,
+    ctx: CliContext,
+    legacy_upload_options: UploadCliOptions,
+) -> Result<()> {
+    let redactor = match command.redact_options() {
+        Some(options) => options.redactor(&ctx.config)?,
+        None => None,
+    };
+    redact::set(redactor);
+
+    match command {
+        Commands::AddResource(args) => {
+            let ctx = ctx
+                .with_upload_options(
+                    args.upload_options
+                        .merged_with_legacy(legacy_upload_options),
+                )
+                .with_dedup_options(args.dedup_options);
+            let options = handlers::AddResourceOptions {
+                path: args.path,
+                to: args.to,
+                parent: args.parent,
+                parent_auto_create: args.parent_auto_create,
+                reason: args.reason,
+                instruction: args.instruction,
+                wait: args.wait,
+                timeout: args.timeout,
+                strict_mode: args.strict_mode,
+                ignore_dirs: args.ignore_dirs,
+                include: args.include,
+                exclude: args.exclude,
+                no_directly_upload_media: args.no_directly_upload_media,
+                watch_interval: args.watch_interval,
+                resource_args: args.resource_args,
+                no_extract: args.no_extract,
+                no_convert: args.no_convert,
+                force_type: args.force_type,
+            };
+            handlers::handle_add_resource(options, ctx).await
+        }
+        Commands::AddSkill(args) => {
+            let AddSkillArgs {
+                data,
+                wait,
+                timeout,
+                parent,
+                upload_options,
+            } = *args;
+            let ctx =
+                ctx.with_upload_options(upload_options.merged_with_legacy(legacy_upload_options));
+            handlers::handle_add_skill(data, wait, timeout, parent, ctx).await
+        }
+        Commands::Add {
+            action: None,
+            objects: args,
+            ..
+        } if args.check_only => commands::preflight::check(
+            args.source.as_deref().unwrap_or_default(),
+            ctx.output_format,
+            ctx.compact,
+        ),
+        Commands::Add {
+            action: None,
+            objects: args,
+            ..
+        } if args.source.as_deref() == Some("-") => {
+            let client = ctx.get_client();
+            commands::stdin_import::add_stdin(
+                &client,
+                commands::stdin_import::AddStdinOptions {
+                    name: args.name.as_deref(),
+                    target: &args.target,
+                    show_progress: ctx.should_show_progress(),
+                    verbose: ctx.is_verbose(),
+                },
+                ctx.output_format,
+                ctx.compact,
+            )
+            .await
+        }
+        Commands::Add {
+            action: None,
+            objects: args,
+            ..
+        } => {
+            let client = ctx.get_client();
+            commands::object_import::add_objects(
+                &client,
+                commands::object_import::AddObjectsOptions {
+                    source: args.source.as_deref().unwrap_or_default(),
+                    parent: args.parent.as_deref(),
+                    concurrency: args.concurrency,
+                    region: args.region.as_deref(),
+                    endpoint: args.endpoint.as_deref(),
+                    limit: args.limit,
+                    dry_run: args.dry_run,
+                    state: args.state.as_deref(),
+                    timeout: args.timeout,
+                },
+                ctx.output_format,
+                ctx.compact,
+            )
+            .await
+        }
+        Commands::Add {
+            action: Some(AddCommands::Url(args)),
+            ..
+        } => {
+            let client = ctx.get_client();
+            commands::web_import::add_url(
+                &client,
+                commands::web_import::AddUrlOptions {
+                    url: &args.url,
+                    depth: args.depth,
+                    same_domain: args.same_domain,
+                    parent: &args.parent,
+                    limit: args.limit,
+                    dry_run: args.dry_run,
+                    timeout: args.timeout,
+                },
+                ctx.output_format,
+                ctx.compact,
+            )
+            .await
+        }
+        Commands::Add {
+            action: Some(AddCommands::Feed(args)),
+            ..
+        } => {
+            let client = ctx.get_client();
+            commands::feed_import::add_feed(
+                &client,
+                commands::feed_import::AddFeedOptions {
+                    url: &args.url,
+                    parent: &args.parent,
+                    limit: args.limit,
+                    mark_seen: args.mark_seen,
+                    dry_run: args.dry_run,
+                    state: args.state.as_deref(),
+                    timeout: args.timeout,
+                },
+                ctx.output_format,
+                ctx.compact,
+            )
+            .await
+        }
+        Commands::Add {
+            action: Some(AddCommands::Table(args)),
+            ..
+        } => {
+            let client = ctx.get_client();
+            commands::table_import::add_table(
+                &client,
+                commands::table_import::AddTableOptions {
+                    path: &args.path,
+                    target: args.target.as_deref(),
+                    chunk_rows: args.chunk_rows,
+                    delimiter: args.delimiter,
+                    dry_run: args.dry_run,
+                },
+                ctx.output_format,
+                ctx.compact,
+            )
+            .await
+        }
+        Commands::Clip(args) => {
+            let client = ctx.get_client();
+            let inbox = commands::clip::inbox(args.inbox.as_deref(), &ctx.config);
+            commands::clip::clip(&client, &inbox, ctx.output_format, ctx.compact).await
+        }
+        Commands::Skills { action } => match action {
+            SkillCommands::Add {
+                source,
+                skills,
+                list,
+                wait,
+                yes,
+                no_validate,
+                parent,
+            } => {
+                let client = ctx.get_client();
+                commands::skills::add(
+                    &client,
+                    &source,
+                    skills,
+                    list,
+                    wait,
+                    yes,
+                    !no_validate,
+                    ctx.should_show_progress(),
+                    ctx.is_verbose(),
+                    ctx.output_format,
+                    ctx.compact,
+                    parent.as_deref(),
+                )
+                .await
+            }
+            SkillCommands::List { node_limit, parent } => {
+                let client = ctx.get_client();
+                commands::skills::list(
+                    &client,
+                    node_limit,
+                    ctx.output_format,
+                    ctx.compact,
+                    parent.as_deref(),
+                )
+                .await
+            }
+            SkillCommands::Find {
+                query,
+                node_limit,
+                threshold,
+                level,
+                parent,
+            } => {
+                let client = ctx.get_client();
+                commands::skills::find(
+                    &client,
+                    &query,
+                    node_limit,
+                    threshold,
+                    level,
+                    ctx.output_format,
+                    ctx.compact,
+                    parent.as_deref(),
+                )
+                .await
+            }
+            SkillCommands::Show {
+                name,
+                level,
+                files,
+                source,
+                format,
+                content,
+                parent,
+            } => {
+                let client = ctx.get_client();
+                let output_format = format
+                    .as_deref()
+                    .map(OutputFormat::from)
+                    .unwrap_or(ctx.output_format);
+                let level = if content { Some(2) } else { level };
+                commands::skills::show(
+                    &client,
+                    &name,
+                    level,
+                    files,
+                    source,
+                    output_format,
+                    ctx.compact,
+                    parent.as_deref(),
+                )
+                .await
+            }
+            SkillCommands::Update {
+                skills,
+                wait,
+                yes,
+                parent,
+            } => {
+                let client = ctx.get_client();
+                commands::skills::update(
+                    &client,
+                    skills,
+                    wait,
+                    yes,
+                    ctx.output_format,
+                    ctx.compact,
+                    parent.as_deref(),
+                )
+                .await
+            }
+            SkillCommands::Remove {
+                skills,
+                all,
+                yes,
+                parent,
+            } => {
+                let client = ctx.get_client();
+                commands::skills::remove(
+                    &client,
+                    skills,
+                    all,
+                    yes,
+                    ctx.output_format,
+                    ctx.compact,
+                    parent.as_deref(),
+                )
+                .await
+            }
+            SkillCommands::Export {
+                name,
+                dest,
+                force,
+                parent,
+            } => {
+                let client = ctx.get_client();
+                commands::skills::export(
+                    &client,
+                    &name,
+                    &dest,
+                    force,
+                    ctx.output_format,
+                    ctx.compact,
+                    parent.as_deref(),
+                )
+                .await
+            }
+            SkillCommands::Validate { path, strict } => {
+                let client = ctx.get_client();
+                commands::skills::validate(&client, &path, strict, ctx.output_format, ctx.compact)
+                    .await
+            }
+            SkillCommands::Init(args) => {
+                let client = ctx.get_client();
+                let options = commands::skills::InitOptions {
+                    name: &args.name,
+                    dest: &args.dest,
+                    description: args.description.as_deref(),
+                    tags: &args.tags,
+                    from_uri: args.from_uri.as_deref(),
+                    force: args.force,
+                };
+                commands::skills::init(&client, options, ctx.output_format, ctx.compact).await
+            }
+        },
+        Commands::Relations {
+            action: Some(action),
+            ..
+        } => {
+            let client = ctx.get_client();
+            match action {
+                RelationsCommands::Graph { uri, depth, format } => {
+                    commands::relations::graph(&client, &uri, depth, &format).await
+                }
+                RelationsCommands::Neighbors {
+                    uri,
+                    depth,
+                    reason_filter,
+                } => {
+                    commands::relations::neighbors(
+                        &client,
+                        &uri,
+                        depth,
+                        reason_filter.as_deref(),
+                        ctx.output_format,
+                        ctx.compact,
+                    )
+                    .await
+                }
+                RelationsCommands::Path {
+                    from_uri,
+                    to_uri,
+                    depth,
+                } => {
+                    commands::relations::path(
+                        &client,
+                        &from_uri,
+                        &to_uri,
+                        depth,
+                        ctx.output_format,
+                        ctx.compact,
+                    )
+                    .await
+                }
+                RelationsCommands::Import {
+                    file,
+                    format,
+                    dry_run,
+                } => {
+                    commands::relation_import::import(
+                        &client,
+                        &file,
+                        format.as_deref(),
+                        dry_run,
+                        ctx.output_format,
+                        ctx.compact,
+                    )
+                    .await
+                }
+            }
+        }
+        Commands::Relations { action: None, uri } => {
+            handlers::handle_relations(uri.unwrap_or_default(), ctx).await
+        }
+        Commands::Link {
+            from_uri,
+            to_uris,
+            reason,
+        } => handlers::handle_link(from_uri, to_uris, reason, ctx).await,
+        Commands::Unlink { from_uri, to_uri } => {
+            handlers::handle_unlink(from_uri, to_uri, ctx).await
+        }
+        Commands::Export(args) => {
+            let ExportArgs {
+                uri,
+                to,
+                include_vectors,
+                compression,
+                encryption,
+            } = *args;
+            handlers::handle_export(
+                uri,
+                to,
+                include_vectors,
+                compression.compression()?,
+                encryption.encryption()?,
+                ctx,
+            )
+            .await
+        }
+        Commands::Backup { action, create } => match action {
+            Some(BackupCommands::Restore(restore)) => run_restore(restore, ctx).await,
+            Some(BackupCommands::Create(create)) => run_backup(create, ctx).await,
+            None => run_backup(create, ctx).await,
+        },
+        Commands::Import(args) => match *args {
+            ImportArgs {
+                action: Some(ImportCommands::Mcp(args)),
+                ..
+            } => {
+                let client = ctx.get_client();
+                commands::mcp_import::import(
+                    &client,
+                    commands::mcp_import::McpImportOptions {
+                        server: &args.server,
+                        tools: &args.tools,
+                        args: args.args.as_deref(),
+                        id_field: args.id_field.as_deref(),
+                        fetch_arg: args.fetch_arg.as_deref(),
+                        parent: &args.parent,
+                        limit: args.limit,
+                        dry_run: args.dry_run,
+                        timeout: args.timeout,
+                    },
+                    ctx.output_format,
+                    ctx.compact,
+                )
+                .await
+            }
+            ImportArgs {
+                action: Some(ImportCommands::Notion(args)),
+                ..
+            } => {
+                let client = ctx.get_client();
+                commands::notion_import::import(
+                    &client,
+                    commands::notion_import::NotionImportOptions {
+                        export: &args.export,
+                        parent: &args.parent,
+                        dry_run: args.dry_run,
+                    },
+                    ctx.output_format,
+                    ctx.compact,
+                )
+                .await
+            }
+            ImportArgs {
+                action: Some(ImportCommands::Mbox(args)),
+                ..
+            } => {
+                let client = ctx.get_client();
+                commands::mbox_import::import(
+                    &client,
+                    commands::mbox_import::MboxImportOptions {
+                        path: &args.path,
+                        parent: &args.parent,
+                        dry_run: args.dry_run,
+                    },
+                    ctx.output_format,
+                    ctx.compact,
+                )
+                .await
+            }
+            ImportArgs {
+                action: None,
+                file_path,
+                target_uri,
+                on_conflict,
+                vector_mode,
+                signature,
+                decryption,
+                redact_options,
+            } => {
+                if redact::active() {
+                    return Err(Error::Client(format!(
+                        "--redact{} applies to documents imported from MCP, Notion, or mailboxes, not to .ovpack archives",
+                        if redact_options.redact {
+                            ""
+                        } else {
+                            "-pattern"
+                        }
+                    )));
+                }
+                let trust = commands::pack::PackTrust::new(
+                    signature.require_signed,
+                    &signature.trusted_keys,
+                )?;
+                handlers::handle_import(
+                    file_path.unwrap_or_default(),
+                    target_uri.unwrap_or_default(),
+                    on_conflict,
+                    vector_mode,
+                    trust,
+                    decryption.decryption()?,
+                    ctx,
+                )
+                .await
+            }
+        },
+        Commands::Pack { action } => match action {
+            PackCommands::Inspect { file_path } => {
+                commands::pack::inspect(&file_path, ctx.output_format, ctx.compact)
+            }
+            PackCommands::Create { dir, to, root_uri } => commands::pack::create(
+                &dir,
+                &to,
+                root_uri.as_deref(),
+                ctx.output_format,
+                ctx.compact,
+            ),
+            PackCommands::Sign { file_path, key } => {
+                commands::pack::sign(&file_path, &key, ctx.output_format, ctx.compact)
+            }
+            PackCommands::Diff { old, new } => {
+                let client = ctx.get_client();
+                commands::pack::diff(&client, &old, &new, ctx.output_format, ctx.compact).await
+            }
+        },
+        Commands::Restore { restore } => run_restore(restore, ctx).await,
+        Commands::Sync { sync } => {
+            handlers::handle_sync(sync.from, sync.to, sync.prefix, sync.dry_run, ctx).await
+        }
+        Commands::Run(args) => run_script(args, ctx, legacy_upload_options).await,
+        Commands::Queue { action } => match action {
+            QueueCommands::List => commands::queue::list(ctx.output_format, ctx.compact),
+            QueueCommands::Flush => {
+                let client = ctx.get_client();
+                commands::queue::flush(&client, ctx.output_format, ctx.compact).await
+            }
+            QueueCommands::Drop { id } => {
+                commands::queue::drop_command(&id, ctx.output_format, ctx.compact)
+            }
+            QueueCommands::Status { uri } => {
+                let client = ctx.get_client();
+                commands::queue::status(&client, uri.as_deref(), ctx.output_format, ctx.compact)
+                    .await
+            }
+            QueueCommands::Wait(args) => {
+                let client = ctx.get_client();
+                commands::queue::wait(
+                    &client,
+                    &args.uri,
+                    args.timeout,
+                    args.interval,
+                    ctx.output_format,
+                    ctx.compact,
+                )
+                .await
+            }
+        },
+        Commands::Index(args) => {
+            let client = ctx.get_client();
+            match args.action {
+                IndexCommands::Verify(args) => {
+                    commands::index::verify(&client, &args.prefix, ctx.output_format, ctx.compact)
+                        .await
+                }
+                IndexCommands::Repair(args) => {
+                    let options = commands::index::RepairOptions {
+                        prune_orphans: args.prune_orphans,
+                        wait: args.wait,
+                        dry_run: args.dry_run,
+                    };
+                    commands::index::repair(
+                        &client,
+                        &args.target.prefix,
+                        options,
+                        ctx.output_format,
+                        ctx.compact,
+                    )
+                    .await
+                }
+                IndexCommands::Rebuild(args) => {
+                    let options = commands::index::RebuildOptions {
+                        changed_since: args.changed_since.as_deref(),
+                        concurrency: args.concurrency,
+                        dry_run: args.dry_run,
+                    };
+                    commands::index::rebuild(
+                        &client,
+                        &args.target.prefix,
+                        options,
+                        ctx.output_format,
+                        ctx.compact,
+                    )
+                    .await
+                }
+            }
+        }
+        Commands::Watch { watch } => {
+            let client = ctx.get_client();
+            commands::mirror::watch(
+                &client,
+                &watch.dir,
+                &watch.target,
+                std::time::Duration::from_millis(watch.debounce),
+                watch.state.as_deref(),
+                watch.once,
+                ctx.output_format,
+            )
+            .await
+        }
+        Commands::Wait { timeout } => {
+            let client = ctx.get_client();
+            commands::system::wait(&client, timeout, ctx.output_format, ctx.compact).await
+        }
+        Commands::Task { action } => match action {
+            TaskCommands::Status { task_id } => {
+                let client = ctx.get_client();
+                commands::task::status(&client, &task_id, ctx.output_format, ctx.compact).await
+            }
+            TaskCommands::List { task_type, status } => {
+                let client = ctx.get_client();
+                commands::task::list(
+                    &client,
+                    task_type.as_deref(),
+                    status.as_deref(),
+                    ctx.output_format,
+                    ctx.compact,
+                )
+                .await
+            }
+            TaskCommands::Watch { action } => {
+                let client = ctx.get_client();
+                match action {
+                    WatchCommands::Ls { active_only } => {
+                        commands::watch::ls(&client, active_only, ctx.output_format, ctx.compact)
+                            .await
+                    }
+                    WatchCommands::Show { key } => {
+                        commands::watch::show(&client, &key, ctx.output_format, ctx.compact).await
+                    }
+                    WatchCommands::Rm { key } => {
+                        commands::watch::rm(&client, &key, ctx.output_format, ctx.compact).await
+                    }
+                    WatchCommands::Pause { key } => {
+                        commands::watch::pause(&client, &key, ctx.output_format, ctx.compact).await
+                    }
+                    WatchCommands::Resume { key } => {
+                        commands::watch::resume(&client, &key, ctx.output_format, ctx.compact).await
+                    }
+                    WatchCommands::Update {
+                        key,
+                        interval,
+                        active,
+                        reason,
+                        instruction,
+                    } => {
+                        commands::watch::update(
+                            &client,
+                            &key,
+                            interval,
+                            active,
+                            reason,
+                            instruction,
+                            ctx.output_format,
+                            ctx.compact,
+                        )
+                        .await
+                    }
+                    WatchCommands::Trigger { key } => {
+                        commands::watch::trigger(&client, &key, ctx.output_format, ctx.compact)
+                            .await
+                    }
+                }
+            }
+        },
+        Commands::Jobs { action } => {
+            let client = ctx.get_client();
+            match action {
+                JobsCommands::Ls {
+                    job_type,
+                    status,
+                    limit,
+                } => {
+                    commands::jobs::ls(
+                        &client,
+                        job_type.as_deref(),
+                        status.as_deref(),
+                        limit,
+                        ctx.output_format,
+                        ctx.compact,
+                    )
+                    .await
+                }
+                JobsCommands::Show { id } => {
+                    commands::jobs::show(&client, &id, ctx.output_format, ctx.compact).await
+                }
+                JobsCommands::Wait {
+                    ids,
+                    timeout,
+                    interval,
+                } => {
+                    commands::jobs::wait(
+                        &client,
+                        &ids,
+                        timeout,
+                        interval,
+                        ctx.output_format,
+                        ctx.compact,
+                    )
+                    .await
+                }
+            }
+        }
+        Commands::Events {
+            action:
+                EventsCommands::Subscribe {
+                    filter,
+                    uri,
+                    exec,
+                    interval,
+                },
+        } => {
+            let client = ctx.get_client();
+            commands::events::subscribe(
+                &client,
+                commands::events::SubscribeOptions {
+                    filter: &filter,
+                    uri: uri.as_deref(),
+                    exec: exec.as_deref(),
+                    interval,
+                },
+            )
+            .await
+        }
+        Commands::Snapshot { cmd } => {
+            let client = ctx.get_client();
+            commands::snapshot::dispatch(&client, cmd, ctx.output_format, ctx.compact).await
+        }
+        Commands::Status { verbose } => {
+            let client = ctx.get_client();
+            commands::system::diagnostic_status(
+                &client,
+                &ctx.config,
+                ctx.output_format,
+                ctx.compact,
+                verbose,
+            )
+            .await
+        }
+        Commands::Health => handlers::handle_health(ctx).await,
+        Commands::Doctor(args) => {
+            let client = ctx.get_client();
+            commands::doctor::run(&client, args.sample, ctx.output_format, ctx.compact).await
+        }
+        Commands::System { action } => handlers::handle_system(action, ctx).await,
+        Commands::Observer { action } => handlers::handle_observer(action, ctx).await,
+        Commands::Session { action } => handlers::handle_session(action, ctx).await,
+        Commands::Memory { action } => handlers::handle_memory(action, ctx).await,
+        Commands::Admin { action } => handlers::handle_admin(action, ctx).await,
+        Commands::Privacy { action } => handlers::handle_privacy(action, ctx).await,
+        Commands::Ls(args) => {
+            let LsArgs {
+                uri,
+                simple,
+                recursive,
+                abs_limit,
+                all,
+                node_limit,
+                tag,
+                token_options,
+            } = *args;
+            let ctx = ctx.with_token_options(token_options);
+            let options = commands::filesystem::LsOptions {
+                uri: &uri,
+                simple,
+                recursive,
+                abs_limit,
+                show_all_hidden: all,
+                node_limit,
+                tags: &tag,
+            };
+            handlers::handle_ls(options, ctx).await
+        }
+        Commands::Tree(args) => {
+            handlers::handle_tree(
+                args.uri,
+                args.abs_limit,
+                args.all,
+                args.node_limit,
+                args.level_limit,
+                ctx,
+            )
+            .await
+        }
+        Commands::Mkdir { uri, description } => handlers::handle_mkdir(uri, description, ctx).await,
+        Commands::Rm(args) => {
+            let RmArgs {
+                uri,
+                glob,
+                recursive,
+                dry_run,
+                yes,
+                wait,
+                timeout,
+            } = *args;
+            let options = commands::filesystem::RmOptions {
+                recursive,
+                wait,
+                timeout,
+                dry_run,
+                yes,
+            };
+            handlers::handle_rm(uri, glob, options, ctx).await
+        }
+        Commands::Mv { from_uri, to_uri } => handlers::handle_mv(from_uri, to_uri, ctx).await,
+        Commands::Cp(args) => {
+            let CpArgs {
+                from_uri,
+                to_uri,
+                recursive,
+                preserve_relations,
+                wait,
+            } = *args;
+            let options = commands::filesystem::CpOptions {
+                recursive,
+                preserve_relations,
+                wait,
+            };
+            handlers::handle_cp(from_uri, to_uri, options, ctx).await
+        }
+        Commands::Stat { uri } => handlers::handle_stat(uri, ctx).await,
+        Commands::Bench(args) => {
+            handlers::handle_bench(
+                args.op,
+                args.target,
+                args.uri,
+                args.concurrency,
+                args.duration,
+                args.requests,
+                ctx,
+            )
+            .await
+        }
+        Commands::Api(args) => {
+            handlers::handle_api(args.method, args.path, args.fields, args.raw_body, ctx).await
+        }
+        Commands::Attrs { action } => match action {
+            AttrsCommands::Get { uri, key } => handlers::handle_attrs(uri, key, ctx).await,
+            AttrsCommands::SetTags {
+                uri,
+                tags,
+                mode,
+                recursive,
+            } => handlers::handle_set_tags(uri, tags, mode, recursive, ctx).await,
+        },
+        Commands::Tag { action } => {
+            let client = ctx.get_client();
+            match action {
+                TagCommands::Add {
+                    uri,
+                    tags,
+                    recursive,
+                } => {
+                    commands::tags::add(
+                        &client,
+                        &uri,
+                        &tags,
+                        recursive,
+                        ctx.output_format,
+                        ctx.compact,
+                    )
+                    .await
+                }
+                TagCommands::Rm { uri, tags } => {
+                    commands::tags::remove(&client, &uri, &tags, ctx.output_format, ctx.compact)
+                        .await
+                }
+                TagCommands::Ls { uri } => {
+                    commands::tags::ls(&client, &uri, ctx.output_format, ctx.compact).await
+                }
+            }
+        }
+        Commands::Meta { action } => {
+            let client = ctx.get_client();
+            match action {
+                MetaCommands::Set { uri, pairs } => {
+                    commands::meta::set(&client, &uri, &pairs, ctx.output_format, ctx.compact).await
+                }
+                MetaCommands::Get { uri, key } => {
+                    commands::meta::get(
+                        &client,
+                        &uri,
+                        key.as_deref(),
+                        ctx.output_format,
+                        ctx.compact,
+                    )
+                    .await
+                }
+                MetaCommands::Rm { uri, keys } => {
+                    commands::meta::remove(&client, &uri, &keys, ctx.output_format, ctx.compact)
+                        .await
+                }
+            }
+        }
+        Commands::Alias { action } => match action {
+            AliasCommands::Set { name, uri } => {
+                commands::alias::set_alias(&name, &uri, ctx.output_format, ctx.compact)
+            }
+            AliasCommands::Ls => commands::alias::list_aliases(ctx.output_format, ctx.compact),
+            AliasCommands::Rm { name } => {
+                commands::alias::remove_alias(&name, ctx.output_format, ctx.compact)
+            }
+        },
+        Commands::Bookmark { action } => match action {
+            BookmarkCommands::Add { uri, name } => {
+                commands::alias::add_bookmark(&uri, name.as_deref(), ctx.output_format, ctx.compact)
+            }
+            BookmarkCommands::Ls => commands::alias::list_bookmarks(ctx.output_format, ctx.compact),
+            BookmarkCommands::Rm { name } => {
+                commands::alias::remove_bookmark(&name, ctx.output_format, ctx.compact)
+            }
+        },
+        Commands::Recent(args) => {
+            let RecentArgs {
+                uris,
+                commands: command_lines,
+                limit,
+                clear,
+            } = *args;
+            if clear {
+                commands::history::clear(ctx.output_format, ctx.compact)
+            } else {
+                let kind = if uris {
+                    commands::history::RecentKind::Uris
+                } else if command_lines {
+                    commands::history::RecentKind::Commands
+                } else {
+                    commands::history::RecentKind::All
+                };
+                commands::history::recent(kind, limit, ctx.output_format, ctx.compact)
+            }
+        }
+        Commands::Stats(args) => {
+            let StatsArgs {
+                uri,
+                by_prefix,
+                days,
+                node_limit,
+            } = *args;
+            let client = ctx.get_client();
+            commands::stats::stats(
+                &client,
+                commands::stats::StatsOptions {
+                    uri: &uri,
+                    by_prefix,
+                    days,
+                    node_limit,
+                },
+                ctx.output_format,
+                ctx.compact,
+            )
+            .await
+        }
+        Commands::Audit {
+            action:
+                AuditCommands::Tail {
+                    user,
+                    action,
+                    since,
+                    lines,
+                    follow,
+                    interval,
+                },
+        } => {
+            let client = ctx.get_client();
+            commands::audit::tail(
+                &client,
+                commands::audit::AuditTailOptions {
+                    user: user.as_deref(),
+                    action: action.as_deref(),
+                    since: since.as_deref(),
+                    lines,
+                    follow,
+                    interval,
+                },
+                ctx.output_format,
+                ctx.compact,
+            )
+            .await
+        }
+        Commands::Workspace { action } => match action {
+            WorkspaceCommands::List => {
+                let root_client = ctx.config.root_api_key.is_some().then(|| {
+                    CliContext {
+                        sudo: true,
+                        ..ctx.clone()
+                    }
+                    .get_client()
+                });
+                commands::workspace::list(
+                    &ctx.config,
+                    root_client.as_ref(),
+                    ctx.output_format,
+                    ctx.compact,
+                )
+                .await
+            }
+            WorkspaceCommands::Use { name } => {
+                commands::workspace::use_workspace(&name, ctx.output_format, ctx.compact)
+            }
+        },
+        Commands::AddMemory { content } => handlers::handle_add_memory(content, ctx).await,
+        Commands::Tui { uri } => handlers::handle_tui(uri, ctx).await,
+        Commands::Pick {
+            prefix,
+            query,
+            node_limit,
+        } => {
+            let client = ctx.get_client();
+            commands::pick::pick(
+                &client,
+                commands::pick::PickOptions {
+                    prefix: &prefix,
+                    query: query.as_deref(),
+                    node_limit,
+                },
+            )
+            .await
+        }
+        Commands::Top { uri, interval } => {
+            let client = ctx.get_client();
+            commands::top::top(
+                &client,
+                commands::top::TopOptions {
+                    server: &ctx.config.url,
+                    uri: uri.as_deref(),
+                    interval,
+                },
+            )
+            .await
+        }
+        Commands::Chat(args) => {
+            let ChatArgs {
+                message,
+                session,
+                sender,
+                stream,
+                no_format,
+                no_history,
+            } = *args;
+            let session_id = session.or_else(|| config::get_or_create_machine_id().ok());
+            let cmd = commands::chat::ChatCommand {
+                endpoint: commands::chat::endpoint_from_env(),
+                api_key: std::env::var("VIKINGBOT_API_KEY").ok(),
+                account: ctx.config.account.clone(),
+                user: ctx.config.user.clone(),
+                actor_peer_id: ctx.config.effective_actor_peer_id(),
+                session: session_id,
+                sender,
+                message,
+                stream,
+                no_format,
+                no_history,
+                stream_tokens: false,
+                record_session: false,
+            };
+            cmd.run().await
+        }
+        Commands::Config { action } => handlers::handle_config(action, ctx).await,
+        Commands::Daemon { action } => match action {
+            DaemonCommands::Start(args) => {
+                commands::daemon::start(
+                    &ctx.config,
+                    args.metrics.as_deref(),
+                    ctx.output_format,
+                    ctx.compact,
+                )
+                .await
+            }
+            DaemonCommands::Run(args) => {
+                commands::daemon::run(
+                    &ctx.config,
+                    args.ttl,
+                    args.max_entries,
+                    args.metrics.as_deref(),
+                )
+                .await
+            }
+            DaemonCommands::Stop => {
+                commands::daemon::stop(&ctx.config, ctx.output_format, ctx.compact).await
+            }
+            DaemonCommands::Status => {
+                commands::daemon::status(&ctx.config, ctx.output_format, ctx.compact).await
+            }
+        },
+        Commands::Schedule { action } => match action {
+            ScheduleCommands::Ls => {
+                commands::schedule::list(&ctx.config.url, ctx.output_format, ctx.compact)
+            }
+            ScheduleCommands::Add {
+                name,
+                cron,
+                command,
+            } => {
+                validate_scheduled_command(&command)?;
+                commands::schedule::add(
+                    &ctx.config.url,
+                    &name,
+                    &cron,
+                    &command,
+                    ctx.output_format,
+                    ctx.compact,
+                )
+            }
+            ScheduleCommands::Rm { name } => {
+                commands::schedule::remove(&name, ctx.output_format, ctx.compact)
+            }
+        },
+        Commands::Mcp {
+            action: McpCommands::Serve(args),
+        } => commands::mcp::serve(ctx.get_client(), &args.transport, &args.host, args.port).await,
+        Commands::MockServer(args) => {
+            commands::mock_server::run(args.fixtures.as_deref(), &args.host, args.port).await
+        }
+        Commands::Language { .. } => {
+            unreachable!("language command is handled before config load")
+        }
+        Commands::Context(args) => match args.action {
+            ContextCommands::Assemble(args) => {
+                let Some(query) = args.query.as_deref() else {
+                    return Err(Error::Client(
+                        "Give the question as an argument or with --query".to_string(),
+                    ));
+                };
+                let client = ctx.get_client();
+                let options = commands::context::AssembleOptions {
+                    query,
+                    uri: &args.uri,
+                    budget: args.budget,
+                    layers: args
+                        .levels
+                        .iter()
+                        .filter_map(|level| commands::context::Layer::parse(level))
+                        .collect(),
+                    node_limit: args.node_limit,
+                    threshold: args.threshold,
+                    session_id: None,
+                    messages: args.messages,
+                };
+                commands::context::assemble(&client, options, ctx.output_format, ctx.compact).await
+            }
+        },
+        Commands::Ask(args) => {
+            let client = ctx.get_client();
+            let options = commands::ask::AskOptions {
+                question: &args.question,
+                target: &args.target,
+                session_id: args.session_id.as_deref(),
+                budget: args.budget,
+                node_limit: args.node_limit,
+                model: args.model.as_deref(),
+            };
+            commands::ask::ask(
+                &client,
+                &ctx.config,
+                options,
+                ctx.output_format,
+                ctx.compact,
+            )
+            .await
+        }
+        Commands::Tokens(args) => match args.action {
+            TokensCommands::Count(args) => {
+                let tokenizer = match args
+                    .tokenizer
+                    .as_deref()
+                    .and_then(commands::tokens::Tokenizer::parse)
+                {
+                    Some(tokenizer) => tokenizer,
+                    None => commands::tokens::Tokenizer::from_config(&ctx.config)?,
+                };
+                let client = ctx.get_client();
+                commands::tokens::count(
+                    &client,
+                    &args.sources,
+                    tokenizer,
+                    ctx.output_format,
+                    ctx.compact,
+                )
+                .await
+            }
+        },
+        Commands::Completions(args) => commands::completions::print(&args.shell),
+        Commands::GenMan(args) => commands::gen_man::run(
+            Cli::command(),
+            args.out_dir.as_deref().map(std::path::Path::new),
+            ctx.output_format,
+            ctx.compact,
+        ),
+        Commands::Version(VersionArgs { remote: true }) => {
+            let client = ctx.get_client();
+            commands::version::remote(&client, ctx.output_format, ctx.compact).await
+        }
+        Commands::Version(VersionArgs { remote: false }) => {
+            println!(
+                "{}     {}",
+                theme::muted("CLI:"),
+                theme::version(env!("OPENVIKING_CLI_VERSION")).bold()
+            );
+
+            // Try to get server version from /health endpoint with a short timeout (3 seconds)
+            let client = ctx.get_client_with_timeout(Some(3.0));
+            match client.get::<serde_json::Value>("/health", &[]).await {
+                Ok(health) => {
+                    if let Some(version) = health.get("version").and_then(|v| v.as_str()) {
+                        println!(
+                            "{}  {}",
+                            theme::muted("Server:"),
+                            theme::sky_value(version).bold()
+                        );
+                    }
+                }
+                Err(_) => {
+                    // If can't connect to server, just don't print server version
+                }
+            }
+            Ok(())
+        }
+        Commands::Read {
+            uris,
+            render,
+            uri_list,
+        } => handlers::handle_read(uris, uri_list.from_file, render, ctx).await,
+        Commands::Abstract { uris, uri_list } => {
+            handlers::handle_abstract(uris, uri_list.from_file, ctx).await
+        }
+        Commands::Overview { uris, uri_list } => {
+            handlers::handle_overview(uris, uri_list.from_file, ctx).await
+        }
+        Commands::Write(args) => {
+            let WriteArgs {
+                uri,
+                source,
+                content,
+                from_file,
+                append,
+                mode,
+                wait,
+                timeout,
+            } = *args;
+            let effective_mode = if let Some(m) = mode {
+                m
+            } else if append {
+                "append".to_string()
+            } else {
+                "replace".to_string()
+            };
+            handlers::handle_write(
+                uri,
+                content,
+                from_file.or(source),
+                effective_mode,
+                wait,
+                timeout,
+                ctx,
+            )
+            .await
+        }
+        Commands::Append(args) => {
+            let AppendArgs {
+                uri,
+                source,
+                content,
+                wait,
+                timeout,
+            } = *args;
+            handlers::handle_write(
+                uri,
+                content,
+                source,
+                "append".to_string(),
+                wait,
+                timeout,
+                ctx,
+            )
+            .await
+        }
+        Commands::Edit(args) => {
+            let EditArgs {
+                uri,
+                force,
+                wait,
+                timeout,
+            } = *args;
+            let client = ctx.get_client();
+            commands::content::edit(
+                &client,
+                &uri,
+                force,
+                wait,
+                timeout,
+                ctx.output_format,
+                ctx.compact,
+            )
+            .await
+        }
+        Commands::SetTags(args) => {
+            handlers::handle_set_tags(args.uri, args.tags, args.mode, args.recursive, ctx).await
+        }
+        Commands::Reindex(args) => {
+            handlers::handle_reindex(args.uri, args.mode, args.wait, args.dry_run, ctx).await
+        }
+        Commands::Get { uri, local_path } => handlers::handle_get(uri, local_path, ctx).await,
+        Commands::Find(args) => {
+            let FindArgs {
+                query,
+                image,
+                uri,
+                node_limit,
+                threshold,
+                after,
+                before,
+                level,
+                context_type,
+                tags,
+                scoring_options,
+                token_options,
+                filter_options,
+                snippet_options,
+            } = *args;
+            let ctx = ctx
+                .with_scoring_options(scoring_options)
+                .with_token_options(token_options)
+                .with_snippet_options(snippet_options);
+            let filters = filter_options.merge(after, before, context_type, tags);
+            let query = query.unwrap_or_default();
+            let options = commands::search::SearchOptions {
+                query: &query,
+                uri: &uri,
+                image,
+                session_id: None,
+                node_limit,
+                threshold,
+                since: filters.after.as_deref(),
+                until: filters.before.as_deref(),
+                time_field: filters.time_field.as_deref(),
+                level,
+                context_type: filters.context_type,
+                tags: filters.tags,
+            };
+            handlers::handle_find(options, ctx).await
+        }
+        Commands::Search(args) => match *args {
+            SearchArgs {
+                action: Some(action),
+                ..
+            } => match action {
+                SearchCommands::Save {
+                    name,
+                    query,
+                    filters,
+                } => commands::saved_search::save(
+                    &name,
+                    query.as_deref().unwrap_or_default(),
+                    filters.as_deref(),
+                    ctx.output_format,
+                    ctx.compact,
+                ),
+                SearchCommands::List => {
+                    commands::saved_search::list(ctx.output_format, ctx.compact)
+                }
+                SearchCommands::Rm { name } => {
+                    commands::saved_search::remove(&name, ctx.output_format, ctx.compact)
+                }
+                SearchCommands::Run { .. } => {
+                    unreachable!("saved searches are expanded before dispatch")
+                }
+            },
+            SearchArgs {
+                action: None,
+                query,
+                image,
+                uri,
+                session_id,
+                node_limit,
+                threshold,
+                after,
+                before,
+                level,
+                context_type,
+                tags,
+                scoring_options,
+                token_options,
+                filter_options,
+                snippet_options,
+            } => {
+                let ctx = ctx
+                    .with_scoring_options(scoring_options)
+                    .with_token_options(token_options)
+                    .with_snippet_options(snippet_options);
+                let filters = filter_options.merge(after, before, context_type, tags);
+                let query = query.unwrap_or_default();
+                let options = commands::search::SearchOptions {
+                    query: &query,
+                    uri: &uri,
+                    image,
+                    session_id,
+                    node_limit,
+                    threshold,
+                    since: filters.after.as_deref(),
+                    until: filters.before.as_deref(),
+                    time_field: filters.time_field.as_deref(),
+                    level,
+                    context_type: filters.context_type,
+                    tags: filters.tags,
+                };
+                handlers::handle_search(options, ctx).await
+            }
+        },
+        Commands::Grep(args) => {
+            let GrepArgs {
+                uri,
+                exclude_uri,
+                pattern,
+                uri_prefix,
+                ignore_case,
+                fixed_strings,
+                node_limit,
+                level_limit,
+            } = *args;
+            let pattern = if fixed_strings {
+                regex::escape(&pattern)
+            } else {
+                pattern
+            };
+            handlers::handle_grep(
+                uri_prefix.unwrap_or(uri),
+                exclude_uri,
+                pattern,
+                ignore_case,
+                node_limit,
+                level_limit,
+                ctx,
+            )
+            .await
+        }
+
+        Commands::Glob {
+            pattern,
+            uri,
+            node_limit,
+        } => handlers::handle_glob(pattern, uri, node_limit, ctx).await,
+    }
+}
+
+/// `ov backup <file>` and `ov backup create <file>` take the same arguments.
+async fn run_backup(create: BackupCreateArgs, ctx: CliContext) -> Result<()> {
+    let to = create
+        .to
+        .ok_or_else(|| Error::Client("Missing backup output path".to_string()))?;
+    handlers::handle_backup(
+        to,
+        create.include_vectors,
+        create.compression.compression()?,
+        create.encryption.encryption()?,
+        !create.no_progress,
+        ctx,
+    )
+    .await
+}
+
+/// `ov restore` and `ov backup restore` take the same arguments.
+async fn run_restore(restore: BackupRestoreArgs, ctx: CliContext) -> Result<()> {
+    let on_conflict = restore.on_conflict();
+    handlers::handle_restore(
+        restore.file_path,
+        on_conflict,
+        restore.vector_mode,
+        restore.dry_run,
+        !restore.no_progress,
+        restore.decryption.decryption()?,
+        ctx,
+    )
+    .await
+}
+
+/// Check that `ov schedule add` was given an ov command that parses, so a typo
+/// fails now instead of at 2am, and one that does not manage the daemon.
+fn validate_scheduled_command(command: &[String]) -> Result<()> {
+    let cli = Cli::try_parse_from(std::iter::once("ov").chain(command.iter().map(String::as_str)))
+        .map_err(|e| {
+            let message = e.to_string();
+            let reason = message
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim_start_matches("error: ");
+            Error::Client(format!(
+                "Cannot schedule `ov {}`: {reason}",
+                command.join(" ")
+            ))
+        })?;
+    if matches!(
+        cli.command,
+        Commands::Daemon { .. } | Commands::Schedule { .. }
+    ) {
+        return Err(Error::Client(
+            "A schedule cannot run `ov daemon` or `ov schedule`".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// A line of an `ov run` script, parsed as the command it runs.
+struct ScriptCommand {
+    step: commands::script::Step,
+    command: Commands,
+    /// `-o` and `-c` when the line gives them; otherwise those of `ov run`.
+    output_format: Option<OutputFormat>,
+    compact: Option<bool>,
+}
+
+/// Parse a script line as `main` parses the command line, refusing global
+/// options other than `-o` and `-c`, which apply to the whole run.
+fn parse_script_command(script: &str, step: commands::script::Step) -> Result<ScriptCommand> {
+    let at = |message: String| Error::Client(format!("{script}:{}: {message}", step.line));
+    let args = preprocess_cli_args(
+        std::iter::once("ov")
+            .chain(step.args.iter().map(String::as_str))
+            .map(OsString::from)
+            .collect(),
+    );
+    let args = commands::alias::expand_args(args).map_err(|e| at(e.to_string()))?;
+    let matches = Cli::command().try_get_matches_from(&args).map_err(|e| {
+        let message = e.to_string();
+        at(message
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim_start_matches("error: ")
+            .to_string())
+    })?;
+    let mut cli = Cli::from_arg_matches(&matches).map_err(|e| at(e.to_string()))?;
+    let given = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+    adopt_query_argument(&mut cli);
+    let global = Cli::command()
+        .get_arguments()
+        .filter(|arg| arg.is_global_set())
+        .find(|arg| {
+            let id = arg.get_id().as_str();
+            given(id)
+                && !matches!(id, "output" | "compact")
+                && (id != "query" || cli.query.is_some())
+        })
+        .map(|arg| arg.get_long().unwrap_or(arg.get_id().as_str()).to_string());
+    if let Some(long) = global {
+        return Err(at(format!(
+            "--{long} applies to the whole script; give it to `ov run` instead"
+        )));
+    }
+    if matches!(cli.command, Commands::Run(_) | Commands::Language { .. }) {
+        return Err(at(format!(
+            "`ov {}` cannot run inside a script",
+            step.args[0]
+        )));
+    }
+    expand_saved_search(&mut cli).map_err(|e| at(e.to_string()))?;
+    Ok(ScriptCommand {
+        output_format: given("output").then_some(cli.output),
+        compact: given("compact").then_some(cli.compact),
+        command: cli.command,
+        step,
+    })
+}
+
+/// `ov run`: parse the whole script, then run its commands in order through
+/// one shared connection pool.
+async fn run_script(
+    args: RunArgs,
+    ctx: CliContext,
+    legacy_upload_options: UploadCliOptions,
+) -> Result<()> {
+    let commands = commands::script::load(&args.script, &args.vars)?
+        .into_iter()
+        .map(|step| parse_script_command(&args.script, step))
+        .collect::<Result<Vec<_>>>()?;
+    if args.dry_run {
+        let rows: Vec<serde_json::Value> = commands
+            .iter()
+            .map(|command| {
+                serde_json::json!({
+                    "line": command.step.line,
+                    "command": command.step.display(),
+                })
+            })
+            .collect();
+        output::output_success(rows, ctx.output_format, ctx.compact);
+        return Ok(());
+    }
+
+    base_client::share_connections();
+    let total = commands.len();
+    let mut failed = 0;
+    for ScriptCommand {
+        step,
+        command,
+        output_format,
+        compact,
+    } in commands
+    {
+        if interrupt::requested() {
+            return Err(Error::Interrupted);
+        }
+        let display = step.display();
+        eprintln!(
+            "{}",
+            theme::muted(format!("{}:{}: {display}", args.script, step.line))
+        );
+        let step_ctx = CliContext {
+            output_format: output_format.unwrap_or(ctx.output_format),
+            compact: compact.unwrap_or(ctx.compact),
+            ..ctx.clone()
+        };
+        let (output_format, compact) = (step_ctx.output_format, step_ctx.compact);
+        let Err(e) = Box::pin(dispatch(command, step_ctx, legacy_upload_options)).await else {
+            continue;
+        };
+        if matches!(e, Error::Interrupted) || step.on_error == commands::script::OnError::Abort {
+            return Err(e);
+        }
+        if !matches!(e, Error::AlreadyReported) {
+            error_ui::print_runtime_error(&display, &e, output_format, compact, ctx.is_verbose());
+        }
+        failed += 1;
+    }
+    if failed > 0 {
+        eprintln!(
+            "{} {failed} of {total} commands in {} failed",
+            theme::warning("warning:"),
+            args.script
+        );
+        return Err(Error::AlreadyReported);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AddCommands, AdminUserCommands, AliasCommands, ApiArgs, AuditCommands, BackupCommands,
+        BookmarkCommands, ChatArgs, Cli, CliContext, Commands, ConfigAddTarget, ConfigCommands,
+        ContextArgs, ContextCommands, CpArgs, DaemonCommands, EventsCommands, FindArgs, GrepArgs,
+        ImportCommands, IndexArgs, IndexCommands, JobsCommands, LanguageGateAction, McpCommands,
+        McpServeArgs, MetaCommands, MockServerArgs, PrivacyCommands, QueueCommands,
+        RedactCliOptions, RelationsCommands, RmArgs, RunArgs, ScheduleCommands, SearchArgs,
+        SearchCommands, SessionCommands, SkillCommands, SnapshotCmd, TagCommands, TokensArgs,
+        TokensCommands, UploadCliOptions, WorkspaceCommands, adopt_query_argument,
+        find_command_index, first_command_token, install_script_output,
+        is_language_command_request, language_command_can_run_picker, language_gate_action,
+        language_required_message, legacy_upload_option_error, parse_script_command,
+        plain_help_misuse, plugin_request, pre_parse_output_options,
+        pre_parse_requires_cli_config_file, preprocess_cli_args, preprocess_privacy_args,
+        validate_scheduled_command,
+    };
+    use crate::config::{Config, DEFAULT_CUSTOM_URL};
+    use crate::output::OutputFormat;
+    use crate::{AdminCommands, SystemBackendCommands, SystemCommands, client, commands, handlers};
+    use clap::{CommandFactory, Parser};
+    use std::ffi::OsString;
+
+    fn os_args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn cli_parses_global_identity_override_flags() {
+        let cli = Cli::try_parse_from([
+            "ov",
+            "--account",
+            "acme",
+            "--user",
+            "alice",
+            "--actor-peer-id",
+            "peer-a",
+            "ls",
+        ])
+        .expect("cli should parse");
+
+        assert_eq!(cli.account.as_deref(), Some("acme"));
+        assert_eq!(cli.user.as_deref(), Some("alice"));
+        assert_eq!(cli.actor_peer_id.as_deref(), Some("peer-a"));
+    }
+
+    #[test]
+    fn cli_parses_snapshot_diff_refs() {
+        let cli = Cli::try_parse_from([
+            "ov",
+            "snapshot",
+            "diff",
+            "viking://resources/a.md",
+            "--from",
+            "old",
+            "--to",
+            "new",
+        ])
+        .expect("snapshot diff should parse");
+
+        match cli.command {
+            Commands::Snapshot {
+                cmd:
+                    SnapshotCmd::Diff {
+                        path,
+                        from_ref,
+                        to_ref,
+                    },
+            } => {
+                assert_eq!(path, "viking://resources/a.md");
+                assert_eq!(from_ref.as_deref(), Some("old"));
+                assert_eq!(to_ref, "new");
+            }
+            _ => panic!("expected snapshot diff"),
+        }
+    }
+
+    #[test]
+    fn pre_parse_output_options_preserve_json_config_errors() {
+        for args in [
+            os_args(&["ov", "ls", "--output", "json", "--compact=false"]),
+            os_args(&["ov", "ls", "-ojson"]),
+            os_args(&["ov", "ls", "-o=json"]),
+        ] {
+            assert_eq!(pre_parse_output_options(&args).0, OutputFormat::Json);
+        }
+    }
+
+    #[test]
+    fn cli_parses_rm_glob_with_confirmation_flags() {
+        let cli = Cli::try_parse_from([
+            "ov",
+            "rm",
+            "--glob",
+            "viking://resources/contract/2023-*",
+            "-r",
+            "--dry-run",
+            "--yes",
+        ])
+        .expect("rm --glob should parse");
+
+        match cli.command {
+            Commands::Rm(args) => {
+                let RmArgs {
+                    uri,
+                    glob,
+                    recursive,
+                    dry_run,
+                    yes,
+                    ..
+                } = *args;
+                assert_eq!(uri, None);
+                assert_eq!(glob.as_deref(), Some("viking://resources/contract/2023-*"));
+                assert!(recursive && dry_run && yes);
+            }
+            _ => panic!("expected rm command"),
+        }
+
+        assert!(Cli::try_parse_from(["ov", "rm"]).is_err());
+        assert!(Cli::try_parse_from(["ov", "rm", "viking://a", "--glob", "viking://b*"]).is_err());
+    }
+
+    #[test]
+    fn cli_parses_cp_flags() {
+        let cli = Cli::try_parse_from([
+            "ov",
+            "cp",
+            "viking://resources/a",
+            "viking://resources/b",
+            "-r",
+            "--preserve-relations",
+        ])
+        .expect("cp should parse");
+
+        match cli.command {
+            Commands::Cp(args) => {
+                let CpArgs {
+                    from_uri,
+                    to_uri,
+                    recursive,
+                    preserve_relations,
+                    wait,
+                } = *args;
+                assert_eq!(from_uri, "viking://resources/a");
+                assert_eq!(to_uri, "viking://resources/b");
+                assert!(recursive && preserve_relations && !wait);
+            }
+            _ => panic!("expected cp command"),
+        }
+    }
+
+    #[test]
+    fn cli_parses_request_timeouts_apart_from_wait_timeout() {
+        let cli = Cli::try_parse_from([
+            "ov",
+            "--timeout",
+            "5",
+            "wait",
+            "--timeout",
+            "30",
+            "--connect-timeout",
+            "2",
+        ])
+        .expect("timeouts should parse");
+
+        assert_eq!(cli.connection.timeout, Some(5.0));
+        assert_eq!(cli.connection.connect_timeout, Some(2.0));
+        match cli.command {
+            Commands::Wait { timeout } => assert_eq!(timeout, Some(30.0)),
+            _ => panic!("expected wait command"),
+        }
+    }
+
+    #[test]
+    fn cli_parses_read_with_several_uris_or_from_file() {
+        let cli = Cli::try_parse_from(["ov", "read", "viking://a", "viking://b"])
+            .expect("several uris should parse");
+        match cli.command {
+            Commands::Read { uris, uri_list, .. } => {
+                assert_eq!(uris, ["viking://a", "viking://b"]);
+                assert_eq!(uri_list.from_file, None);
+            }
+            _ => panic!("expected read command"),
+        }
+
+        let cli = Cli::try_parse_from(["ov", "overview", "--from-file", "uris.txt"])
+            .expect("--from-file alone should parse");
+        match cli.command {
+            Commands::Overview { uris, uri_list } => {
+                assert!(uris.is_empty());
+                assert_eq!(uri_list.from_file.as_deref(), Some("uris.txt"));
+            }
+            _ => panic!("expected overview command"),
+        }
+        assert!(Cli::try_parse_from(["ov", "abstract"]).is_err());
+    }
+
+    #[test]
+    fn cli_parses_session_add_messages_from_file() {
+        let cli = Cli::try_parse_from([
+            "ov",
+            "session",
+            "add-messages",
+            "sess-1",
+            "--file",
+            "messages.jsonl",
+        ])
+        .expect("add-messages --file should parse");
+
+        match cli.command {
+            Commands::Session {
+                action:
+                    SessionCommands::AddMessages {
+                        session_id,
+                        messages,
+                        file,
+                    },
+            } => {
+                assert_eq!(session_id, "sess-1");
+                assert_eq!(messages, None);
+                assert_eq!(file.as_deref(), Some("messages.jsonl"));
+            }
+            _ => panic!("expected session add-messages command"),
+        }
+
+        assert!(Cli::try_parse_from(["ov", "session", "add-messages", "sess-1"]).is_err());
+    }
+
+    #[test]
+    fn cli_parses_session_chat() {
+        let cli = Cli::try_parse_from(["ov", "session", "chat", "sess-1", "-m", "hello"])
+            .expect("session chat should parse");
+
+        match cli.command {
+            Commands::Session {
+                action:
+                    SessionCommands::Chat {
+                        session_id,
+                        message,
+                        no_format,
+                        no_history,
+                    },
+            } => {
+                assert_eq!(session_id, "sess-1");
+                assert_eq!(message.as_deref(), Some("hello"));
+                assert!(!no_format && !no_history);
+            }
+            _ => panic!("expected session chat command"),
+        }
+    }
+
+    #[test]
+    fn cli_parses_grep_uri_prefix_and_fixed_strings() {
+        let cli = Cli::try_parse_from(["ov", "grep", "a.b", "viking://resources/docs", "-F"])
+            .expect("grep should parse");
+
+        match cli.command {
+            Commands::Grep(args) => {
+                let GrepArgs {
+                    uri,
+                    pattern,
+                    uri_prefix,
+                    fixed_strings,
+                    ..
+                } = *args;
+                assert_eq!(uri, "viking://");
+                assert_eq!(pattern, "a.b");
+                assert_eq!(uri_prefix.as_deref(), Some("viking://resources/docs"));
+                assert!(fixed_strings);
+            }
+            _ => panic!("expected grep command"),
+        }
+    }
+
+    #[test]
+    fn cli_parses_tree_depth_alias() {
+        for args in [
+            ["ov", "tree", "viking://resources", "--depth", "2"],
+            ["ov", "tree", "viking://resources", "-L", "2"],
+        ] {
+            let cli = Cli::try_parse_from(args).expect("tree depth should parse");
+            match cli.command {
+                Commands::Tree(args) => assert_eq!(args.level_limit, 2),
+                _ => panic!("expected tree command"),
+            }
+        }
+    }
+
+    #[test]
+    fn cli_parses_find_context_type() {
+        let cli =
+            Cli::try_parse_from(["ov", "find", "invoice", "--context-type", "memory,resource"])
+                .expect("find context type should parse");
+
+        match cli.command {
+            Commands::Find(args) => {
+                assert_eq!(
+                    args.context_type,
+                    Some(vec!["memory".to_string(), "resource".to_string()])
+                );
+            }
+            _ => panic!("expected find command"),
+        }
+    }
+
+    #[test]
+    fn cli_parses_find_image_without_query() {
+        let cli = Cli::try_parse_from(["ov", "find", "--image", "cat.png"])
+            .expect("find image should parse");
+
+        match cli.command {
+            Commands::Find(args) => {
+                assert_eq!(args.query, None);
+                assert_eq!(args.image.as_deref(), Some("cat.png"));
+            }
+            _ => panic!("expected find command"),
+        }
+    }
+
+    #[test]
+    fn cli_parses_admin_migrate_cleanup_flag() {
+        let migrate = Cli::try_parse_from(["ov", "--sudo", "admin", "migrate"])
+            .expect("admin migrate should parse");
+        match migrate.command {
+            Commands::Admin { action } => match action {
+                AdminCommands::Migrate { cleanup } => assert!(!cleanup),
+                _ => panic!("expected admin migrate command"),
+            },
+            _ => panic!("expected admin command"),
+        }
+
+        let cleanup = Cli::try_parse_from(["ov", "--sudo", "admin", "migrate", "--cleanup"])
+            .expect("admin migrate cleanup should parse");
+
+        assert!(cleanup.sudo);
+        match cleanup.command {
+            Commands::Admin { action } => match action {
+                AdminCommands::Migrate { cleanup } => assert!(cleanup),
+                _ => panic!("expected admin migrate command"),
+            },
+            _ => panic!("expected admin command"),
+        }
+    }
+
+    #[test]
+    fn cli_parses_search_context_type() {
+        let cli = Cli::try_parse_from(["ov", "search", "invoice", "--context-type", "skill"])
+            .expect("search context type should parse");
+
+        match cli.command {
+            Commands::Search(args) => {
+                assert_eq!(args.context_type, Some(vec!["skill".to_string()]));
+            }
+            _ => panic!("expected search command"),
+        }
+    }
+
+    #[test]
+    fn cli_parses_search_image_with_query() {
+        let cli = Cli::try_parse_from(["ov", "search", "poster", "--image", "viking://x.png"])
+            .expect("search image should parse");
+
+        match cli.command {
+            Commands::Search(args) => {
+                assert_eq!(args.query.as_deref(), Some("poster"));
+                assert_eq!(args.image.as_deref(), Some("viking://x.png"));
+            }
+            _ => panic!("expected search command"),
+        }
+    }
+
+    #[test]
+    fn cli_find_and_search_reject_removed_peer_id_flag() {
+        assert!(Cli::try_parse_from(["ov", "find", "invoice", "--peer-id", "peer-a"]).is_err());
+        assert!(Cli::try_parse_from(["ov", "search", "invoice", "--peer-id", "peer-a"]).is_err());
+    }
+
+    #[test]
+    fn cli_chat_sender_uses_long_flag_and_session_keeps_short_s() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from([
+            "ov",
+            "chat",
+            "-s",
+            "session-1",
+            "--sender",
+            "agent-1",
+            "--message",
+            "hello",
+        ])
+        .expect("chat flags should parse without short alias conflicts");
+
+        match cli.command {
+            Commands::Chat(args) => {
+                let ChatArgs {
+                    session, sender, ..
+                } = *args;
+                assert_eq!(session.as_deref(), Some("session-1"));
+                assert_eq!(sender, "agent-1");
+            }
+            _ => panic!("expected chat command"),
+        }
+    }
+
+    #[test]
+    fn cli_parses_system_backend_sync_status() {
+        let cli = Cli::try_parse_from(["ov", "system", "backend", "sync-status", "viking://a"])
+            .expect("system backend sync-status should parse");
+
+        match cli.command {
+            Commands::System { action } => match action {
+                SystemCommands::Backend { action } => match action {
+                    SystemBackendCommands::SyncStatus { uri } => assert_eq!(uri, "viking://a"),
+                    _ => panic!("expected backend sync-status command"),
+                },
+                _ => panic!("expected system backend command"),
+            },
+            _ => panic!("expected system command"),
+        }
+    }
+
+    #[test]
+    fn server_commands_require_existing_cli_config() {
+        let cli = Cli::try_parse_from(["ov", "ls"]).expect("ls should parse");
+        let health = Cli::try_parse_from(["ov", "health"]).expect("health should parse");
+
+        assert!(cli.command.requires_cli_config_file());
+        assert!(health.command.requires_cli_config_file());
+    }
+
+    #[test]
+    fn bare_config_switch_and_version_do_not_require_existing_cli_config() {
+        let setup = Cli::try_parse_from(["ov", "config"]).expect("bare config should parse");
+        let switch =
+            Cli::try_parse_from(["ov", "config", "switch"]).expect("config switch should parse");
+        let switch_named = Cli::try_parse_from(["ov", "config", "switch", "prod"])
+            .expect("named config switch should parse");
+        let version = Cli::try_parse_from(["ov", "version"]).expect("version should parse");
+        let remote_version =
+            Cli::try_parse_from(["ov", "version", "--remote"]).expect("version should parse");
+
+        assert!(!setup.command.requires_cli_config_file());
+        assert!(!switch.command.requires_cli_config_file());
+        assert!(!switch_named.command.requires_cli_config_file());
+        assert!(!version.command.requires_cli_config_file());
+        assert!(remote_version.command.requires_cli_config_file());
+
+        let skills_validate = Cli::try_parse_from(["ov", "skills", "validate", "./skills/foo"])
+            .expect("skills validate should parse");
+        assert!(!skills_validate.command.requires_cli_config_file());
+
+        let skills_init = Cli::try_parse_from(["ov", "skills", "init", "code-review"])
+            .expect("skills init should parse");
+        assert!(!skills_init.command.requires_cli_config_file());
+        let skills_init_from_uri = Cli::try_parse_from([
+            "ov",
+            "skills",
+            "init",
+            "code-review",
+            "--from-uri",
+            "viking://resources/guides",
+        ])
+        .expect("skills init --from-uri should parse");
+        assert!(skills_init_from_uri.command.requires_cli_config_file());
+
+        let pack_inspect = Cli::try_parse_from(["ov", "pack", "inspect", "./acme.ovpack"])
+            .expect("pack inspect should parse");
+        assert!(!pack_inspect.command.requires_cli_config_file());
+
+        let pack_create = Cli::try_parse_from([
+            "ov",
+            "pack",
+            "create",
+            "./docs",
+            "./acme.ovpack",
+            "--root-uri",
+            "viking://resources/acme",
+        ])
+        .expect("pack create should parse");
+        assert!(!pack_create.command.requires_cli_config_file());
+    }
+
+    #[test]
+    fn config_agent_commands_parse_without_secret_value_flags() {
+        let add_ov_service = Cli::try_parse_from([
+            "ov",
+            "config",
+            "add",
+            "ov-service",
+            "--name",
+            "prod",
+            "--api-key-stdin",
+            "--activate",
+        ])
+        .expect("ov-service add should parse");
+        let Commands::Config {
+            action:
+                Some(ConfigCommands::Add {
+                    target: ConfigAddTarget::OvService(ov_service),
+                }),
+        } = add_ov_service.command
+        else {
+            panic!("expected ov-service add command");
+        };
+        assert_eq!(ov_service.name.as_deref(), Some("prod"));
+        assert!(ov_service.api_key_stdin);
+        assert!(ov_service.activate);
+
+        let add_custom = Cli::try_parse_from([
+            "ov",
+            "config",
+            "add",
+            "custom",
+            "--url",
+            "https://ov.example.com",
+            "--api-key-env",
+            "OV_KEY",
+            "--actor-peer-id",
+            "peer-a",
+        ])
+        .expect("custom add should parse");
+        let Commands::Config {
+            action:
+                Some(ConfigCommands::Add {
+                    target: ConfigAddTarget::Custom(custom),
+                }),
+        } = add_custom.command
+        else {
+            panic!("expected custom add command");
+        };
+        assert_eq!(custom.url.as_deref(), Some("https://ov.example.com"));
+        assert_eq!(custom.api_key_env.as_deref(), Some("OV_KEY"));
+        assert_eq!(custom.actor_peer_id.as_deref(), Some("peer-a"));
+
+        let edit = Cli::try_parse_from([
+            "ov",
+            "config",
+            "edit",
+            "prod",
+            "--clear-api-key",
+            "--clear-actor-peer-id",
+            "--activate",
+        ])
+        .expect("config edit should parse");
+        let Commands::Config {
+            action: Some(ConfigCommands::Edit(edit)),
+        } = edit.command
+        else {
+            panic!("expected edit command");
+        };
+        assert_eq!(edit.name, "prod");
+        assert!(edit.clear_api_key);
+        assert!(edit.clear_actor_peer_id);
+        assert!(edit.activate);
+
+        assert!(
+            Cli::try_parse_from(["ov", "config", "add", "ov-service", "--api-key", "secret"])
+                .is_err(),
+            "plain API key flag must stay rejected"
+        );
+        assert!(
+            Cli::try_parse_from(["ov", "config", "add", "cloud", "--api-key-stdin"]).is_err(),
+            "old cloud provider subcommand must stay rejected"
+        );
+        assert!(
+            Cli::try_parse_from(["ov", "config", "add", "self-managed"]).is_err(),
+            "old self-managed provider subcommand must stay rejected"
+        );
+        assert!(
+            Cli::try_parse_from([
+                "ov",
+                "config",
+                "add",
+                "custom",
+                "--use-root-key-for-normal-commands",
+            ])
+            .is_err(),
+            "root-as-normal flag is obsolete and must stay rejected"
+        );
+        assert!(
+            Cli::try_parse_from([
+                "ov",
+                "config",
+                "edit",
+                "prod",
+                "--use-root-key-for-normal-commands",
+            ])
+            .is_err(),
+            "root-as-normal edit flag is obsolete and must stay rejected"
+        );
+    }
+
+    #[test]
+    fn pre_parse_config_gate_catches_incomplete_known_server_commands() {
+        for args in [
+            &["ov", "find"][..],
+            &["ov", "task", "status"],
+            &["ov", "task", "watch", "show"],
+            &["ov", "config", "validate"],
+            &["ov", "config", "show"],
+            &["ov", "system", "consistency"],
+            &["ov", "system", "backend", "sync-status"],
+            &["ov", "admin", "list-accounts"],
+        ] {
+            assert!(
+                pre_parse_requires_cli_config_file(&os_args(args)),
+                "{args:?} should require ovcli.conf before Clap positional validation"
+            );
+        }
+    }
+
+    #[test]
+    fn pre_parse_config_gate_covers_known_top_level_server_commands() {
+        for command in [
+            "add-resource",
+            "add-skill",
+            "ls",
+            "tree",
+            "mkdir",
+            "rm",
+            "mv",
+            "cp",
+            "stat",
+            "read",
+            "abstract",
+            "overview",
+            "write",
+            "get",
+            "find",
+            "search",
+            "grep",
+            "glob",
+            "add-memory",
+            "relations",
+            "link",
+            "unlink",
+            "export",
+            "backup",
+            "import",
+            "restore",
+            "watch",
+            "tui",
+            "chat",
+            "wait",
+            "status",
+            "health",
+            "reindex",
+            "api",
+            "bench",
+            "mcp",
+            "add",
+        ] {
+            assert!(
+                pre_parse_requires_cli_config_file(&os_args(&["ov", command])),
+                "{command} should require ovcli.conf before Clap validation"
+            );
+        }
+    }
+
+    #[test]
+    fn pre_parse_config_gate_covers_known_group_server_commands() {
+        let cases: &[&[&str]] = &[
+            &["ov", "config", "show"],
+            &["ov", "config", "validate"],
+            &["ov", "task", "status"],
+            &["ov", "task", "list"],
+            &["ov", "task", "watch"],
+            &["ov", "task", "watch", "ls"],
+            &["ov", "task", "watch", "show"],
+            &["ov", "task", "watch", "rm"],
+            &["ov", "task", "watch", "pause"],
+            &["ov", "task", "watch", "resume"],
+            &["ov", "task", "watch", "update"],
+            &["ov", "task", "watch", "trigger"],
+            &["ov", "admin", "create-account"],
+            &["ov", "admin", "list-accounts"],
+            &["ov", "admin", "delete-account"],
+            &["ov", "admin", "migrate"],
+            &["ov", "admin", "register-user"],
+            &["ov", "admin", "list-users"],
+            &["ov", "admin", "remove-user"],
+            &["ov", "admin", "set-role"],
+            &["ov", "admin", "regenerate-key"],
+            &["ov", "system", "wait"],
+            &["ov", "system", "status"],
+            &["ov", "system", "health"],
+            &["ov", "system", "consistency"],
+            &["ov", "system", "backend"],
+            &["ov", "system", "backend", "sync-status"],
+            &["ov", "system", "backend", "sync-retry"],
+            &["ov", "system", "crypto"],
+            &["ov", "system", "crypto", "init-key"],
+            &["ov", "session", "new"],
+            &["ov", "session", "list"],
+            &["ov", "session", "get"],
+            &["ov", "session", "get-session-context"],
+            &["ov", "session", "get-session-archive"],
+            &["ov", "session", "delete"],
+            &["ov", "session", "add-message"],
+            &["ov", "session", "add-messages"],
+            &["ov", "session", "chat"],
+            &["ov", "session", "extract"],
+            &["ov", "session", "export"],
+            &["ov", "session", "import"],
+            &["ov", "session", "tail"],
+            &["ov", "session", "commit"],
+            &["ov", "memory", "ls"],
+            &["ov", "memory", "show"],
+            &["ov", "privacy", "categories"],
+            &["ov", "privacy", "list"],
+            &["ov", "privacy", "get"],
+            &["ov", "privacy", "upsert"],
+            &["ov", "privacy", "versions"],
+            &["ov", "privacy", "version"],
+            &["ov", "privacy", "activate"],
+            &["ov", "observer", "queue"],
+            &["ov", "observer", "vikingdb"],
+            &["ov", "observer", "models"],
+            &["ov", "observer", "retrieval"],
+            &["ov", "observer", "filesystem"],
+            &["ov", "observer", "system"],
+            &["ov", "skills", "add"],
+            &["ov", "skills", "list"],
+            &["ov", "skills", "ls"],
+            &["ov", "skills", "find"],
+            &["ov", "skills", "show"],
+            &["ov", "skills", "update"],
+            &["ov", "skills", "remove"],
+            &["ov", "skills", "rm"],
+            &["ov", "skills", "delete"],
+        ];
+
+        for args in cases {
+            assert!(
+                pre_parse_requires_cli_config_file(&os_args(args)),
+                "{args:?} should require ovcli.conf before Clap validation"
             );
-
-            // Try to get server version from /health endpoint with a short timeout (3 seconds)
-            let client = ctx.get_client_with_timeout(Some(3.0));
-            match client.get::<serde_json::Value>("/health", &[]).await {
-                Ok(health) => {
-                    if let Some(version) = health.get("version").and_then(|v| v.as_str()) {
-                        println!(
-                            "{}  {}",
-                            theme::muted("Server:"),
-                            theme::sky_value(version).bold()
-                        );
-                    }
-                }
-                Err(_) => {
-                    // If can't connect to server, just don't print server version
-                }
-            }
-            Ok(())
         }
-        Commands::Read { uri } => handlers::handle_read(uri, ctx).await,
-        Commands::Abstract { uri } => handlers::handle_abstract(uri, ctx).await,
-        Commands::Overview { uri } => handlers::handle_overview(uri, ctx).await,
-        Commands::Write {
-            uri,
-            content,
-            from_file,
-            append,
-            mode,
-            wait,
-            timeout,
-        } => {
-            let effective_mode = if let Some(m) = mode {
-                m
-            } else if append {
-                "append".to_string()
-            } else {
-                "replace".to_string()
-            };
-            handlers::handle_write(uri, content, from_file, effective_mode, wait, timeout, ctx)
-                .await
+
+        assert!(
+            pre_parse_requires_cli_config_file(&preprocess_privacy_args(os_args(&[
+                "ov", "privacy", "skill", "demo",
+            ]))),
+            "privacy shortcut should require ovcli.conf after preprocessing"
+        );
+    }
+
+    #[test]
+    fn pre_parse_config_gate_respects_aliases_and_valid_help_values() {
+        for args in [
+            &["ov", "list"][..],
+            &["ov", "delete"],
+            &["ov", "rename"],
+            &["ov", "lang", "en"],
+        ] {
+            let expected = args[1] != "lang";
+            assert_eq!(
+                pre_parse_requires_cli_config_file(&os_args(args)),
+                expected,
+                "{args:?} pre-parse gate mismatch"
+            );
         }
-        Commands::SetTags {
-            uri,
-            tags,
-            mode,
-            recursive,
-        } => handlers::handle_set_tags(uri, tags, mode, recursive, ctx).await,
-        Commands::Reindex {
-            uri,
-            mode,
-            wait,
-            dry_run,
-        } => handlers::handle_reindex(uri, mode, wait, dry_run, ctx).await,
-        Commands::Get { uri, local_path } => handlers::handle_get(uri, local_path, ctx).await,
-        Commands::Find {
-            query,
-            image,
-            uri,
-            node_limit,
-            threshold,
-            after,
-            before,
-            level,
-            context_type,
-            tags,
-        } => {
-            handlers::handle_find(
-                query,
-                uri,
-                image,
-                node_limit,
-                threshold,
-                after,
-                before,
-                level,
-                context_type,
-                tags,
-                ctx,
-            )
-            .await
+    }
+
+    #[test]
+    fn pre_parse_config_gate_allows_non_server_and_unknown_commands() {
+        for args in [
+            &["ov", "con"][..],
+            &["ov", "task", "nope"],
+            &["ov", "config"],
+            &["ov", "config", "switch"],
+            &["ov", "config", "switch", "prod"],
+            &["ov", "config", "list"],
+            &["ov", "config", "delete", "prod"],
+            &["ov", "config", "add", "ov-service", "--api-key-stdin"],
+            &["ov", "config", "edit", "prod", "--activate"],
+            &["ov", "config", "setup-cli"],
+            &["ov", "skills", "validate", "./skills/foo"],
+            &["ov", "skills", "validate", "./skills/foo", "--strict"],
+            &["ov", "skills", "init", "code-review"],
+            &["ov", "search", "save", "todo", "open items"],
+            &["ov", "search", "list"],
+            &["ov", "search", "rm", "todo"],
+            &["ov", "add", "--check-only", "./contracts"],
+            &["ov", "version"],
+            &["ov", "language", "en"],
+            &["ov", "lang", "en"],
+        ] {
+            assert!(
+                !pre_parse_requires_cli_config_file(&os_args(args)),
+                "{args:?} should not be gated before Clap parsing"
+            );
         }
-        Commands::Search {
-            query,
-            image,
-            uri,
-            session_id,
-            node_limit,
-            threshold,
-            after,
-            before,
-            level,
-            context_type,
-            tags,
-        } => {
-            handlers::handle_search(
-                query,
-                uri,
-                image,
-                session_id,
-                node_limit,
-                threshold,
-                after,
-                before,
-                level,
-                context_type,
-                tags,
-                ctx,
-            )
-            .await
+    }
+
+    #[test]
+    fn cli_tree_help_hides_upload_and_admin_only_flags() {
+        let err = Cli::command()
+            .try_get_matches_from(["ov", "tree", "--help"])
+            .expect_err("help should exit through clap error");
+        let help = err.to_string();
+
+        assert!(!help.contains("--progress"));
+        assert!(!help.contains("--no-progress"));
+        assert!(!help.contains("--verbose"));
+        assert!(!help.contains("--sudo"));
+    }
+
+    #[test]
+    fn cli_add_resource_help_shows_upload_flags() {
+        let err = Cli::command()
+            .try_get_matches_from(["ov", "add-resource", "--help"])
+            .expect_err("help should exit through clap error");
+        let help = err.to_string();
+
+        assert!(help.contains("--progress"));
+        assert!(help.contains("--no-progress"));
+        assert!(help.contains("--verbose"));
+    }
+
+    #[test]
+    fn cli_add_skill_help_shows_upload_flags() {
+        let err = Cli::command()
+            .try_get_matches_from(["ov", "add-skill", "--help"])
+            .expect_err("help should exit through clap error");
+        let help = err.to_string();
+
+        assert!(help.contains("--progress"));
+        assert!(help.contains("--no-progress"));
+        assert!(help.contains("--verbose"));
+    }
+
+    #[test]
+    fn cli_tree_rejects_upload_flags_after_subcommand() {
+        assert!(Cli::try_parse_from(["ov", "tree", "viking://", "--progress"]).is_err());
+        assert!(Cli::try_parse_from(["ov", "tree", "viking://", "--no-progress"]).is_err());
+        assert!(Cli::try_parse_from(["ov", "tree", "viking://", "--verbose"]).is_err());
+    }
+
+    #[test]
+    fn cli_parses_sudo_as_hidden_global_flag_after_subcommand() {
+        let cli = Cli::try_parse_from(["ov", "tree", "viking://", "--sudo"])
+            .expect("sudo should parse anywhere so runtime can show a precise command error");
+
+        assert!(cli.sudo);
+    }
+
+    #[test]
+    fn cli_parses_upload_flags_on_upload_commands() {
+        let add_resource = Cli::try_parse_from([
+            "ov",
+            "add-resource",
+            "./README.md",
+            "--progress",
+            "--verbose",
+            "--no-extract",
+            "--no-convert",
+            "--force-type",
+            "text/markdown",
+        ])
+        .expect("add-resource upload flags should parse");
+        match add_resource.command {
+            Commands::AddResource(args) => {
+                assert!(args.no_extract);
+                assert!(args.no_convert);
+                assert_eq!(args.force_type.as_deref(), Some("text/markdown"));
+                assert!(args.upload_options.progress);
+                assert!(args.upload_options.verbose);
+            }
+            _ => panic!("expected add-resource command"),
         }
-        Commands::Grep {
-            uri,
-            exclude_uri,
-            pattern,
-            ignore_case,
-            node_limit,
-            level_limit,
-        } => {
-            handlers::handle_grep(
-                uri,
-                exclude_uri,
-                pattern,
-                ignore_case,
-                node_limit,
-                level_limit,
-                ctx,
-            )
-            .await
+
+        let add_skill = Cli::try_parse_from(["ov", "add-skill", "./skill", "--no-progress"])
+            .expect("add-skill upload flags should parse");
+        match add_skill.command {
+            Commands::AddSkill(args) => {
+                assert!(args.upload_options.no_progress);
+            }
+            _ => panic!("expected add-skill command"),
         }
 
-        Commands::Glob {
-            pattern,
-            uri,
-            node_limit,
-        } => handlers::handle_glob(pattern, uri, node_limit, ctx).await,
-    };
+        assert!(Cli::try_parse_from(["ov", "skills", "add", "./skill", "--progress"]).is_err());
+        assert!(Cli::try_parse_from(["ov", "skills", "update", "--progress"]).is_err());
+    }
 
-    if let Err(e) = result {
-        if !matches!(e, Error::AlreadyReported) {
-            error_ui::print_runtime_error(
-                &command_display,
-                &e,
-                output_format,
-                compact,
-                verbose_errors,
-            );
+    #[test]
+    fn cli_parses_skills_command_group() {
+        let list = Cli::try_parse_from(["ov", "skills", "list", "--limit", "25"])
+            .expect("skills list should parse");
+        match list.command {
+            Commands::Skills {
+                action: SkillCommands::List { node_limit, .. },
+            } => assert_eq!(node_limit, 25),
+            _ => panic!("expected skills list"),
         }
-        std::process::exit(1);
-    }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::{
-        Cli, CliContext, Commands, ConfigAddTarget, ConfigCommands, LanguageGateAction,
-        PrivacyCommands, SkillCommands, SnapshotCmd, UploadCliOptions, find_command_index,
-        first_command_token, is_language_command_request, language_command_can_run_picker,
-        language_gate_action, language_required_message, legacy_upload_option_error,
-        plain_help_misuse, pre_parse_output_options, pre_parse_requires_cli_config_file,
-        preprocess_cli_args, preprocess_privacy_args,
-    };
-    use crate::config::{Config, DEFAULT_CUSTOM_URL};
-    use crate::output::OutputFormat;
-    use crate::{AdminCommands, SystemBackendCommands, SystemCommands, handlers};
-    use clap::{CommandFactory, Parser};
-    use std::ffi::OsString;
+        let export = Cli::try_parse_from(["ov", "skill", "export", "code-review", "./out", "-f"])
+            .expect("skill export should parse");
+        match export.command {
+            Commands::Skills {
+                action:
+                    SkillCommands::Export {
+                        name, dest, force, ..
+                    },
+            } => {
+                assert_eq!(name, "code-review");
+                assert_eq!(dest, "./out");
+                assert!(force);
+            }
+            _ => panic!("expected skills export"),
+        }
 
-    fn os_args(args: &[&str]) -> Vec<OsString> {
-        args.iter().map(OsString::from).collect()
-    }
+        let init = Cli::try_parse_from([
+            "ov",
+            "skill",
+            "init",
+            "code-review",
+            "./skills",
+            "--tags",
+            "review,git",
+            "-d",
+            "Review a diff",
+        ])
+        .expect("skill init should parse");
+        match init.command {
+            Commands::Skills {
+                action: SkillCommands::Init(args),
+            } => {
+                assert_eq!(args.name, "code-review");
+                assert_eq!(args.dest, "./skills");
+                assert_eq!(args.tags, vec!["review", "git"]);
+                assert_eq!(args.description.as_deref(), Some("Review a diff"));
+                assert!(args.from_uri.is_none());
+            }
+            _ => panic!("expected skills init"),
+        }
 
-    #[test]
-    fn cli_parses_global_identity_override_flags() {
-        let cli = Cli::try_parse_from([
+        let add = Cli::try_parse_from(["ov", "skill", "add", "./skill", "--no-validate"])
+            .expect("skill add should parse");
+        assert!(matches!(
+            add.command,
+            Commands::Skills {
+                action: SkillCommands::Add {
+                    no_validate: true,
+                    ..
+                },
+            }
+        ));
+
+        let find = Cli::try_parse_from([
             "ov",
-            "--account",
-            "acme",
-            "--user",
-            "alice",
-            "--actor-peer-id",
-            "peer-a",
-            "ls",
+            "skills",
+            "find",
+            "review code",
+            "--threshold",
+            "0.4",
+            "--level",
+            "0,1",
         ])
-        .expect("cli should parse");
+        .expect("skills find should parse");
+        match find.command {
+            Commands::Skills {
+                action:
+                    SkillCommands::Find {
+                        query,
+                        threshold,
+                        level,
+                        ..
+                    },
+            } => {
+                assert_eq!(query, "review code");
+                assert_eq!(threshold, Some(0.4));
+                assert_eq!(level, Some(vec![0, 1]));
+            }
+            _ => panic!("expected skills find"),
+        }
 
-        assert_eq!(cli.account.as_deref(), Some("acme"));
-        assert_eq!(cli.user.as_deref(), Some("alice"));
-        assert_eq!(cli.actor_peer_id.as_deref(), Some("peer-a"));
-    }
+        let update =
+            Cli::try_parse_from(["ov", "skills", "update", "code-review", "--wait", "--yes"])
+                .expect("skills update should parse");
+        match update.command {
+            Commands::Skills {
+                action:
+                    SkillCommands::Update {
+                        skills, wait, yes, ..
+                    },
+            } => {
+                assert_eq!(skills, vec!["code-review"]);
+                assert!(wait);
+                assert!(yes);
+            }
+            _ => panic!("expected skills update"),
+        }
 
-    #[test]
-    fn cli_parses_snapshot_diff_refs() {
-        let cli = Cli::try_parse_from([
+        let add_selected = Cli::try_parse_from([
             "ov",
-            "snapshot",
-            "diff",
-            "viking://resources/a.md",
-            "--from",
-            "old",
-            "--to",
-            "new",
+            "skills",
+            "add",
+            "https://github.com/acme/skills.git",
+            "--skill",
+            "foo",
+            "bar",
+            "--list",
+            "--yes",
         ])
-        .expect("snapshot diff should parse");
-
-        match cli.command {
-            Commands::Snapshot {
-                cmd:
-                    SnapshotCmd::Diff {
-                        path,
-                        from_ref,
-                        to_ref,
+        .expect("skills add RFC flags should parse");
+        match add_selected.command {
+            Commands::Skills {
+                action:
+                    SkillCommands::Add {
+                        source,
+                        skills,
+                        list,
+                        yes,
+                        ..
                     },
             } => {
-                assert_eq!(path, "viking://resources/a.md");
-                assert_eq!(from_ref.as_deref(), Some("old"));
-                assert_eq!(to_ref, "new");
+                assert_eq!(source, "https://github.com/acme/skills.git");
+                assert_eq!(skills, vec!["foo", "bar"]);
+                assert!(list);
+                assert!(yes);
             }
-            _ => panic!("expected snapshot diff"),
+            _ => panic!("expected skills add"),
         }
-    }
 
-    #[test]
-    fn pre_parse_output_options_preserve_json_config_errors() {
-        for args in [
-            os_args(&["ov", "ls", "--output", "json", "--compact=false"]),
-            os_args(&["ov", "ls", "-ojson"]),
-            os_args(&["ov", "ls", "-o=json"]),
-        ] {
-            assert_eq!(pre_parse_output_options(&args).0, OutputFormat::Json);
+        let show = Cli::try_parse_from([
+            "ov",
+            "skills",
+            "show",
+            "code-review",
+            "--level",
+            "2",
+            "--files",
+            "--source",
+            "--format",
+            "json",
+        ])
+        .expect("skills show RFC flags should parse");
+        match show.command {
+            Commands::Skills {
+                action:
+                    SkillCommands::Show {
+                        level,
+                        files,
+                        source,
+                        format,
+                        ..
+                    },
+            } => {
+                assert_eq!(level, Some(2));
+                assert!(files);
+                assert!(source);
+                assert_eq!(format.as_deref(), Some("json"));
+            }
+            _ => panic!("expected skills show"),
         }
-    }
 
-    #[test]
-    fn cli_parses_find_context_type() {
-        let cli =
-            Cli::try_parse_from(["ov", "find", "invoice", "--context-type", "memory,resource"])
-                .expect("find context type should parse");
+        let show_global_output =
+            Cli::try_parse_from(["ov", "skills", "show", "code-review", "-o", "json"])
+                .expect("skills show should accept global -o after the subcommand");
+        assert_eq!(show_global_output.output, OutputFormat::Json);
 
-        match cli.command {
-            Commands::Find { context_type, .. } => {
-                assert_eq!(
-                    context_type,
-                    Some(vec!["memory".to_string(), "resource".to_string()])
-                );
+        let remove = Cli::try_parse_from(["ov", "skills", "remove", "foo", "bar", "--yes"])
+            .expect("skills remove --yes should parse");
+        match remove.command {
+            Commands::Skills {
+                action:
+                    SkillCommands::Remove {
+                        skills, yes, all, ..
+                    },
+            } => {
+                assert_eq!(skills, vec!["foo", "bar"]);
+                assert!(yes);
+                assert!(!all);
             }
-            _ => panic!("expected find command"),
+            _ => panic!("expected skills remove"),
         }
-    }
-
-    #[test]
-    fn cli_parses_find_image_without_query() {
-        let cli = Cli::try_parse_from(["ov", "find", "--image", "cat.png"])
-            .expect("find image should parse");
 
-        match cli.command {
-            Commands::Find { query, image, .. } => {
-                assert_eq!(query, None);
-                assert_eq!(image.as_deref(), Some("cat.png"));
+        let validate =
+            Cli::try_parse_from(["ov", "skills", "validate", "./skills/foo", "--strict"])
+                .expect("skills validate --strict should parse");
+        match validate.command {
+            Commands::Skills {
+                action: SkillCommands::Validate { path, strict },
+            } => {
+                assert_eq!(path, "./skills/foo");
+                assert!(strict);
             }
-            _ => panic!("expected find command"),
+            _ => panic!("expected skills validate"),
         }
     }
 
     #[test]
-    fn cli_parses_admin_migrate_cleanup_flag() {
-        let migrate = Cli::try_parse_from(["ov", "--sudo", "admin", "migrate"])
-            .expect("admin migrate should parse");
-        match migrate.command {
-            Commands::Admin { action } => match action {
-                AdminCommands::Migrate { cleanup } => assert!(!cleanup),
-                _ => panic!("expected admin migrate command"),
-            },
-            _ => panic!("expected admin command"),
-        }
-
-        let cleanup = Cli::try_parse_from(["ov", "--sudo", "admin", "migrate", "--cleanup"])
-            .expect("admin migrate cleanup should parse");
+    fn cli_keeps_legacy_pre_command_upload_flags() {
+        let cli = Cli::try_parse_from([
+            "ov",
+            "--progress",
+            "--verbose",
+            "add-resource",
+            "./README.md",
+        ])
+        .expect("legacy pre-command upload flags should still parse");
 
-        assert!(cleanup.sudo);
-        match cleanup.command {
-            Commands::Admin { action } => match action {
-                AdminCommands::Migrate { cleanup } => assert!(cleanup),
-                _ => panic!("expected admin migrate command"),
-            },
-            _ => panic!("expected admin command"),
-        }
+        assert!(cli.progress);
+        assert!(cli.verbose);
     }
 
     #[test]
-    fn cli_parses_search_context_type() {
-        let cli = Cli::try_parse_from(["ov", "search", "invoice", "--context-type", "skill"])
-            .expect("search context type should parse");
+    fn legacy_pre_command_upload_flags_only_allow_upload_commands() {
+        let upload_options = UploadCliOptions {
+            progress: true,
+            no_progress: false,
+            verbose: false,
+            resume: false,
+        };
 
-        match cli.command {
-            Commands::Search { context_type, .. } => {
-                assert_eq!(context_type, Some(vec!["skill".to_string()]));
-            }
-            _ => panic!("expected search command"),
-        }
+        let tree = Cli::try_parse_from(["ov", "--progress", "tree", "viking://"])
+            .expect("hidden legacy flag still parses before runtime validation");
+        assert_eq!(
+            legacy_upload_option_error(upload_options, &tree.command),
+            Some(
+                "--progress, --no-progress, and --verbose are only supported for add-resource and add-skill."
+            )
+        );
+
+        let add_resource = Cli::try_parse_from(["ov", "--progress", "add-resource", "./README.md"])
+            .expect("legacy pre-command upload flags should parse for add-resource");
+        assert!(legacy_upload_option_error(upload_options, &add_resource.command).is_none());
+
+        let skills_add = Cli::try_parse_from(["ov", "--progress", "skills", "add", "./skill"])
+            .expect("hidden legacy flag still parses before runtime validation");
+        assert_eq!(
+            legacy_upload_option_error(upload_options, &skills_add.command),
+            Some(
+                "--progress, --no-progress, and --verbose are only supported for add-resource and add-skill."
+            )
+        );
     }
 
     #[test]
-    fn cli_parses_search_image_with_query() {
-        let cli = Cli::try_parse_from(["ov", "search", "poster", "--image", "viking://x.png"])
-            .expect("search image should parse");
+    fn cli_parses_sudo_before_admin_command() {
+        let cli = Cli::try_parse_from(["ov", "--sudo", "admin", "list-accounts"])
+            .expect("pre-command sudo should parse");
 
-        match cli.command {
-            Commands::Search { query, image, .. } => {
-                assert_eq!(query.as_deref(), Some("poster"));
-                assert_eq!(image.as_deref(), Some("viking://x.png"));
-            }
-            _ => panic!("expected search command"),
-        }
+        assert!(cli.sudo);
     }
 
     #[test]
-    fn cli_find_and_search_reject_removed_peer_id_flag() {
-        assert!(Cli::try_parse_from(["ov", "find", "invoice", "--peer-id", "peer-a"]).is_err());
-        assert!(Cli::try_parse_from(["ov", "search", "invoice", "--peer-id", "peer-a"]).is_err());
+    fn cli_parses_sudo_after_admin_command() {
+        let cli = Cli::try_parse_from(["ov", "admin", "list-accounts", "--sudo"])
+            .expect("post-command sudo should parse");
+
+        assert!(cli.sudo);
     }
 
     #[test]
-    fn cli_chat_sender_uses_long_flag_and_session_keeps_short_s() {
-        Cli::command().debug_assert();
+    fn sudo_supports_task_status_and_list_only() {
+        let status = Cli::try_parse_from(["ov", "--sudo", "task", "status", "task-123"])
+            .expect("sudo task status should parse");
+        assert!(status.sudo);
+        assert!(status.command.supports_sudo());
 
-        let cli = Cli::try_parse_from([
+        let list = Cli::try_parse_from([
             "ov",
-            "chat",
-            "-s",
-            "session-1",
-            "--sender",
-            "agent-1",
-            "--message",
-            "hello",
+            "--sudo",
+            "task",
+            "list",
+            "--task-type",
+            "legacy_migration",
         ])
-        .expect("chat flags should parse without short alias conflicts");
+        .expect("sudo task list should parse");
+        assert!(list.sudo);
+        assert!(list.command.supports_sudo());
 
-        match cli.command {
-            Commands::Chat {
-                session, sender, ..
-            } => {
-                assert_eq!(session.as_deref(), Some("session-1"));
-                assert_eq!(sender, "agent-1");
-            }
-            _ => panic!("expected chat command"),
+        let watch = Cli::try_parse_from(["ov", "--sudo", "task", "watch", "ls"])
+            .expect("sudo task watch should still parse before runtime validation");
+        assert!(watch.sudo);
+        assert!(!watch.command.supports_sudo());
+    }
+
+    #[test]
+    fn cli_config_without_subcommand_parses_as_setup_entrypoint() {
+        Cli::try_parse_from(["ov", "config"]).expect("bare config command should parse");
+    }
+
+    #[test]
+    fn cli_config_single_purpose_subcommands_still_parse() {
+        for subcommand in ["show", "validate", "switch"] {
+            Cli::try_parse_from(["ov", "config", subcommand])
+                .unwrap_or_else(|_| panic!("config {subcommand} should parse"));
         }
     }
 
     #[test]
-    fn cli_parses_system_backend_sync_status() {
-        let cli = Cli::try_parse_from(["ov", "system", "backend", "sync-status", "viking://a"])
-            .expect("system backend sync-status should parse");
+    fn cli_config_rejects_removed_setup_cli_subcommand() {
+        assert!(Cli::try_parse_from(["ov", "config", "setup-cli"]).is_err());
+    }
+
+    #[test]
+    fn cli_language_command_and_alias_parse() {
+        let cli = Cli::try_parse_from(["ov", "language", "zh-CN"])
+            .expect("language command should parse");
+        match cli.command {
+            Commands::Language { language } => assert_eq!(language.as_deref(), Some("zh-CN")),
+            _ => panic!("expected language command"),
+        }
 
+        let cli = Cli::try_parse_from(["ov", "lang"]).expect("language alias should parse");
         match cli.command {
-            Commands::System { action } => match action {
-                SystemCommands::Backend { action } => match action {
-                    SystemBackendCommands::SyncStatus { uri } => assert_eq!(uri, "viking://a"),
-                    _ => panic!("expected backend sync-status command"),
-                },
-                _ => panic!("expected system backend command"),
-            },
-            _ => panic!("expected system command"),
+            Commands::Language { language } => assert!(language.is_none()),
+            _ => panic!("expected language command"),
         }
     }
 
     #[test]
-    fn server_commands_require_existing_cli_config() {
-        let cli = Cli::try_parse_from(["ov", "ls"]).expect("ls should parse");
-        let health = Cli::try_parse_from(["ov", "health"]).expect("health should parse");
-
-        assert!(cli.command.requires_cli_config_file());
-        assert!(health.command.requires_cli_config_file());
+    fn language_gate_detects_language_command_after_global_flags() {
+        assert!(is_language_command_request(&os_args(&["ov", "language"])));
+        assert!(is_language_command_request(&os_args(&[
+            "ov", "lang", "zh-CN"
+        ])));
+        assert!(is_language_command_request(&os_args(&[
+            "ov", "--output", "json", "language", "en",
+        ])));
+        assert!(is_language_command_request(&os_args(&[
+            "ov",
+            "--output=json",
+            "--compact",
+            "false",
+            "lang",
+        ])));
+        assert!(is_language_command_request(&os_args(&[
+            "ov",
+            "--compact",
+            "lang",
+        ])));
+        assert!(!is_language_command_request(&os_args(&["ov", "status"])));
+        assert!(!is_language_command_request(&os_args(&["ov", "--help"])));
     }
 
     #[test]
-    fn bare_config_switch_and_version_do_not_require_existing_cli_config() {
-        let setup = Cli::try_parse_from(["ov", "config"]).expect("bare config should parse");
-        let switch =
-            Cli::try_parse_from(["ov", "config", "switch"]).expect("config switch should parse");
-        let switch_named = Cli::try_parse_from(["ov", "config", "switch", "prod"])
-            .expect("named config switch should parse");
-        let version = Cli::try_parse_from(["ov", "version"]).expect("version should parse");
+    fn language_gate_action_continues_prompts_or_exits() {
+        assert_eq!(
+            language_gate_action(&os_args(&["ov", "status"]), true, false),
+            LanguageGateAction::Continue
+        );
+        assert_eq!(
+            language_gate_action(&os_args(&["ov", "language"]), false, false),
+            LanguageGateAction::Continue
+        );
+        assert_eq!(
+            language_gate_action(&os_args(&["ov", "status"]), false, true),
+            LanguageGateAction::Prompt
+        );
+        assert_eq!(
+            language_gate_action(&os_args(&["ov", "status"]), false, false),
+            LanguageGateAction::ExitNonInteractive
+        );
+    }
 
-        assert!(!setup.command.requires_cli_config_file());
-        assert!(!switch.command.requires_cli_config_file());
-        assert!(!switch_named.command.requires_cli_config_file());
-        assert!(!version.command.requires_cli_config_file());
+    #[test]
+    fn language_gate_allows_config_agent_commands_without_saved_language() {
+        for args in [
+            &["ov", "config", "add", "ov-service", "--api-key-stdin"][..],
+            &["ov", "config", "add", "custom"],
+            &["ov", "config", "edit", "prod", "--activate"],
+            &["ov", "config", "delete", "prod"],
+            &["ov", "config", "list"],
+            &["ov", "config", "switch", "prod"],
+        ] {
+            assert_eq!(
+                language_gate_action(&os_args(args), false, false),
+                LanguageGateAction::Continue,
+                "{args:?} should bypass first-run language selection"
+            );
+        }
 
-        let skills_validate = Cli::try_parse_from(["ov", "skills", "validate", "./skills/foo"])
-            .expect("skills validate should parse");
-        assert!(!skills_validate.command.requires_cli_config_file());
+        assert_eq!(
+            language_gate_action(&os_args(&["ov", "config"]), false, false),
+            LanguageGateAction::ExitNonInteractive
+        );
     }
 
     #[test]
-    fn config_agent_commands_parse_without_secret_value_flags() {
-        let add_ov_service = Cli::try_parse_from([
-            "ov",
-            "config",
-            "add",
-            "ov-service",
-            "--name",
-            "prod",
-            "--api-key-stdin",
-            "--activate",
-        ])
-        .expect("ov-service add should parse");
-        let Commands::Config {
-            action:
-                Some(ConfigCommands::Add {
-                    target: ConfigAddTarget::OvService(ov_service),
-                }),
-        } = add_ov_service.command
-        else {
-            panic!("expected ov-service add command");
-        };
-        assert_eq!(ov_service.name.as_deref(), Some("prod"));
-        assert!(ov_service.api_key_stdin);
-        assert!(ov_service.activate);
+    fn language_gate_message_points_to_explicit_language_commands() {
+        let message = language_required_message();
 
-        let add_custom = Cli::try_parse_from([
-            "ov",
-            "config",
-            "add",
-            "custom",
-            "--url",
-            "https://ov.example.com",
-            "--api-key-env",
-            "OV_KEY",
-            "--actor-peer-id",
-            "peer-a",
-        ])
-        .expect("custom add should parse");
-        let Commands::Config {
-            action:
-                Some(ConfigCommands::Add {
-                    target: ConfigAddTarget::Custom(custom),
-                }),
-        } = add_custom.command
-        else {
-            panic!("expected custom add command");
-        };
-        assert_eq!(custom.url.as_deref(), Some("https://ov.example.com"));
-        assert_eq!(custom.api_key_env.as_deref(), Some("OV_KEY"));
-        assert_eq!(custom.actor_peer_id.as_deref(), Some("peer-a"));
+        assert!(message.contains("ov language en"));
+        assert!(message.contains("ov language zh-CN"));
+    }
 
-        let edit = Cli::try_parse_from([
-            "ov",
-            "config",
-            "edit",
-            "prod",
-            "--clear-api-key",
-            "--clear-actor-peer-id",
-            "--activate",
-        ])
-        .expect("config edit should parse");
-        let Commands::Config {
-            action: Some(ConfigCommands::Edit(edit)),
-        } = edit.command
-        else {
-            panic!("expected edit command");
-        };
-        assert_eq!(edit.name, "prod");
-        assert!(edit.clear_api_key);
-        assert!(edit.clear_actor_peer_id);
-        assert!(edit.activate);
+    #[test]
+    fn language_command_without_value_requires_interactive_picker() {
+        assert!(language_command_can_run_picker(true, false));
+        assert!(language_command_can_run_picker(false, true));
+        assert!(!language_command_can_run_picker(false, false));
+    }
 
-        assert!(
-            Cli::try_parse_from(["ov", "config", "add", "ov-service", "--api-key", "secret"])
-                .is_err(),
-            "plain API key flag must stay rejected"
-        );
-        assert!(
-            Cli::try_parse_from(["ov", "config", "add", "cloud", "--api-key-stdin"]).is_err(),
-            "old cloud provider subcommand must stay rejected"
+    #[test]
+    fn first_command_token_skips_global_flags() {
+        assert_eq!(
+            first_command_token(&os_args(&[
+                "ov",
+                "--output",
+                "json",
+                "--account=acme",
+                "--actor-peer-id",
+                "peer-a",
+                "--sudo",
+                "admin",
+            ]))
+            .as_deref(),
+            Some("admin")
         );
-        assert!(
-            Cli::try_parse_from(["ov", "config", "add", "self-managed"]).is_err(),
-            "old self-managed provider subcommand must stay rejected"
+        assert_eq!(
+            first_command_token(&os_args(&["ov", "--help"])).as_deref(),
+            None
         );
-        assert!(
-            Cli::try_parse_from([
+    }
+
+    #[test]
+    fn find_command_index_skips_root_value_options() {
+        assert_eq!(
+            find_command_index(&os_args(&[
                 "ov",
-                "config",
-                "add",
-                "custom",
-                "--use-root-key-for-normal-commands",
-            ])
-            .is_err(),
-            "root-as-normal flag is obsolete and must stay rejected"
+                "--output",
+                "json",
+                "--account=acme",
+                "--actor-peer-id",
+                "peer-a",
+                "privacy",
+                "sample-policy",
+            ])),
+            Some(6)
         );
-        assert!(
-            Cli::try_parse_from([
-                "ov",
-                "config",
-                "edit",
-                "prod",
-                "--use-root-key-for-normal-commands",
-            ])
-            .is_err(),
-            "root-as-normal edit flag is obsolete and must stay rejected"
+        assert_eq!(
+            find_command_index(&os_args(&["ov", "--compact", "privacy", "sample-policy"])),
+            Some(2)
+        );
+        assert_eq!(
+            find_command_index(&os_args(&["ov", "-c", "false", "privacy", "sample-policy"])),
+            Some(3)
         );
     }
 
     #[test]
-    fn pre_parse_config_gate_catches_incomplete_known_server_commands() {
-        for args in [
-            &["ov", "find"][..],
-            &["ov", "task", "status"],
-            &["ov", "task", "watch", "show"],
-            &["ov", "config", "validate"],
-            &["ov", "config", "show"],
-            &["ov", "system", "consistency"],
-            &["ov", "system", "backend", "sync-status"],
-            &["ov", "admin", "list-accounts"],
+    fn cli_status_parses_verbose_flag() {
+        let cli =
+            Cli::try_parse_from(["ov", "status", "--verbose"]).expect("status verbose parses");
+
+        match cli.command {
+            Commands::Status { verbose } => assert!(verbose),
+            _ => panic!("expected status command"),
+        }
+    }
+
+    #[test]
+    fn plain_help_is_rejected_for_top_level_group_and_structured_leaf_commands() {
+        for (args, expected_help) in [
+            (vec!["ov", "help"], "ov --help"),
+            (vec!["ov", "config", "help"], "ov config --help"),
+            (vec!["ov", "task", "help"], "ov task --help"),
+            (vec!["ov", "admin", "help"], "ov admin --help"),
+            (vec!["ov", "ls", "help"], "ov ls --help"),
+            (vec!["ov", "tree", "help"], "ov tree --help"),
+            (vec!["ov", "read", "help"], "ov read --help"),
+            (
+                vec!["ov", "task", "status", "help"],
+                "ov task status --help",
+            ),
+            (
+                vec!["ov", "system", "consistency", "help"],
+                "ov system consistency --help",
+            ),
+            (
+                vec!["ov", "system", "backend", "sync-status", "help"],
+                "ov system backend sync-status --help",
+            ),
         ] {
-            assert!(
-                pre_parse_requires_cli_config_file(&os_args(args)),
-                "{args:?} should require ovcli.conf before Clap positional validation"
-            );
+            let misuse = plain_help_misuse(&os_args(&args))
+                .unwrap_or_else(|| panic!("{args:?} should be plain-help misuse"));
+            assert_eq!(misuse.help_command, expected_help);
         }
     }
 
     #[test]
-    fn pre_parse_config_gate_covers_known_top_level_server_commands() {
-        for command in [
-            "add-resource",
-            "add-skill",
-            "ls",
-            "tree",
-            "mkdir",
-            "rm",
-            "mv",
-            "stat",
-            "read",
-            "abstract",
-            "overview",
-            "write",
-            "get",
-            "find",
-            "search",
-            "grep",
-            "glob",
-            "add-memory",
-            "relations",
-            "link",
-            "unlink",
-            "export",
-            "backup",
-            "import",
-            "restore",
-            "tui",
-            "chat",
-            "wait",
-            "status",
-            "health",
-            "reindex",
+    fn plain_help_is_allowed_as_free_form_user_input() {
+        for args in [
+            vec!["ov", "find", "help"],
+            vec!["ov", "search", "help"],
+            vec!["ov", "grep", "help"],
+            vec!["ov", "glob", "help"],
+            vec!["ov", "add-memory", "help"],
+            vec!["ov", "add-resource", "help"],
+            vec!["ov", "add-skill", "help"],
         ] {
             assert!(
-                pre_parse_requires_cli_config_file(&os_args(&["ov", command])),
-                "{command} should require ovcli.conf before Clap validation"
+                plain_help_misuse(&os_args(&args)).is_none(),
+                "{args:?} should remain normal user input"
             );
         }
     }
 
     #[test]
-    fn pre_parse_config_gate_covers_known_group_server_commands() {
-        let cases: &[&[&str]] = &[
-            &["ov", "config", "show"],
-            &["ov", "config", "validate"],
-            &["ov", "task", "status"],
-            &["ov", "task", "list"],
-            &["ov", "task", "watch"],
-            &["ov", "task", "watch", "ls"],
-            &["ov", "task", "watch", "show"],
-            &["ov", "task", "watch", "rm"],
-            &["ov", "task", "watch", "pause"],
-            &["ov", "task", "watch", "resume"],
-            &["ov", "task", "watch", "update"],
-            &["ov", "task", "watch", "trigger"],
-            &["ov", "admin", "create-account"],
-            &["ov", "admin", "list-accounts"],
-            &["ov", "admin", "delete-account"],
-            &["ov", "admin", "migrate"],
-            &["ov", "admin", "register-user"],
-            &["ov", "admin", "list-users"],
-            &["ov", "admin", "remove-user"],
-            &["ov", "admin", "set-role"],
-            &["ov", "admin", "regenerate-key"],
-            &["ov", "system", "wait"],
-            &["ov", "system", "status"],
-            &["ov", "system", "health"],
-            &["ov", "system", "consistency"],
-            &["ov", "system", "backend"],
-            &["ov", "system", "backend", "sync-status"],
-            &["ov", "system", "backend", "sync-retry"],
-            &["ov", "system", "crypto"],
-            &["ov", "system", "crypto", "init-key"],
-            &["ov", "session", "new"],
-            &["ov", "session", "list"],
-            &["ov", "session", "get"],
-            &["ov", "session", "get-session-context"],
-            &["ov", "session", "get-session-archive"],
-            &["ov", "session", "delete"],
-            &["ov", "session", "add-message"],
-            &["ov", "session", "add-messages"],
-            &["ov", "session", "commit"],
-            &["ov", "privacy", "categories"],
-            &["ov", "privacy", "list"],
-            &["ov", "privacy", "get"],
-            &["ov", "privacy", "upsert"],
-            &["ov", "privacy", "versions"],
-            &["ov", "privacy", "version"],
-            &["ov", "privacy", "activate"],
-            &["ov", "observer", "queue"],
-            &["ov", "observer", "vikingdb"],
-            &["ov", "observer", "models"],
-            &["ov", "observer", "retrieval"],
-            &["ov", "observer", "filesystem"],
-            &["ov", "observer", "system"],
-            &["ov", "skills", "add"],
-            &["ov", "skills", "list"],
-            &["ov", "skills", "ls"],
-            &["ov", "skills", "find"],
-            &["ov", "skills", "show"],
-            &["ov", "skills", "update"],
-            &["ov", "skills", "remove"],
-            &["ov", "skills", "rm"],
-            &["ov", "skills", "delete"],
-        ];
+    fn plain_help_ignores_option_values() {
+        assert!(
+            plain_help_misuse(&os_args(&[
+                "ov",
+                "read",
+                "--account",
+                "help",
+                "viking://resource",
+            ]))
+            .is_none()
+        );
+        assert!(plain_help_misuse(&os_args(&["ov", "grep", "--uri", "help", "TODO",])).is_none());
 
-        for args in cases {
+        for args in [
+            &["ov", "mkdir", "--description", "help", "viking://notes"][..],
+            &[
+                "ov",
+                "write",
+                "viking://notes/todo.md",
+                "--from-file",
+                "help",
+            ],
+            &[
+                "ov",
+                "session",
+                "add-message",
+                "abc",
+                "--peer-id",
+                "help",
+                "--role",
+                "user",
+                "--content",
+                "hi",
+            ],
+            &[
+                "ov",
+                "privacy",
+                "upsert",
+                "cat",
+                "target",
+                "--values-json",
+                "help",
+            ],
+            &["ov", "admin", "create-account", "acct", "--admin", "help"],
+            &["ov", "config", "add", "custom", "--url", "help"],
+            &["ov", "task", "list", "--status", "help"],
+            &[
+                "ov",
+                "system",
+                "crypto",
+                "init-key",
+                "--output-file",
+                "help",
+            ],
+        ] {
             assert!(
-                pre_parse_requires_cli_config_file(&os_args(args)),
-                "{args:?} should require ovcli.conf before Clap validation"
+                plain_help_misuse(&os_args(args)).is_none(),
+                "{args:?} should treat help as an option value"
             );
         }
+    }
 
-        assert!(
-            pre_parse_requires_cli_config_file(&preprocess_privacy_args(os_args(&[
-                "ov", "privacy", "skill", "demo",
-            ]))),
-            "privacy shortcut should require ovcli.conf after preprocessing"
+    #[test]
+    fn cli_context_overrides_identity_from_cli_flags() {
+        let config = Config {
+            url: DEFAULT_CUSTOM_URL.to_string(),
+            api_key: Some("test-key".to_string()),
+            root_api_key: None,
+            account: Some("from-config-account".to_string()),
+            user: Some("from-config-user".to_string()),
+            actor_peer_id: Some("from-config-peer".to_string()),
+            agent_id: None,
+            timeout: 60.0,
+            output: "table".to_string(),
+            echo_command: true,
+            show_progress: false,
+            verbose: false,
+            upload: Default::default(),
+            extra_headers: None,
+            profile: false,
+            gateway_token: None,
+            proxy: None,
+            ca_cert: None,
+            client_cert: None,
+            client_key: None,
+            tokenizer: None,
+            clip_inbox: None,
+            history: true,
+            wasm_plugins: Vec::new(),
+            redact_patterns: Default::default(),
+            llm: None,
+            connect_timeout: None,
+        };
+
+        let ctx = CliContext::from_config(
+            config,
+            OutputFormat::Json,
+            true,
+            Some("from-cli-account".to_string()),
+            Some("from-cli-user".to_string()),
+            Some("from-cli-peer".to_string()),
+            false,
+            None,
+            None,
+            None,
         );
+
+        assert_eq!(ctx.config.account.as_deref(), Some("from-cli-account"));
+        assert_eq!(ctx.config.user.as_deref(), Some("from-cli-user"));
+        assert_eq!(ctx.config.actor_peer_id.as_deref(), Some("from-cli-peer"));
+        assert!(ctx.config.agent_id.is_none());
     }
 
     #[test]
-    fn pre_parse_config_gate_respects_aliases_and_valid_help_values() {
-        for args in [
-            &["ov", "list"][..],
-            &["ov", "delete"],
-            &["ov", "rename"],
-            &["ov", "lang", "en"],
-        ] {
-            let expected = args[1] != "lang";
-            assert_eq!(
-                pre_parse_requires_cli_config_file(&os_args(args)),
-                expected,
-                "{args:?} pre-parse gate mismatch"
-            );
-        }
+    fn cli_context_maps_agent_id_to_actor_peer_scope() {
+        let config = Config {
+            url: DEFAULT_CUSTOM_URL.to_string(),
+            api_key: Some("test-key".to_string()),
+            root_api_key: None,
+            account: None,
+            user: None,
+            actor_peer_id: None,
+            agent_id: Some("legacy-agent".to_string()),
+            timeout: 60.0,
+            output: "table".to_string(),
+            echo_command: true,
+            show_progress: false,
+            verbose: false,
+            upload: Default::default(),
+            extra_headers: None,
+            profile: false,
+            gateway_token: None,
+            proxy: None,
+            ca_cert: None,
+            client_cert: None,
+            client_key: None,
+            tokenizer: None,
+            clip_inbox: None,
+            history: true,
+            wasm_plugins: Vec::new(),
+            redact_patterns: Default::default(),
+            llm: None,
+            connect_timeout: None,
+        };
+
+        let ctx = CliContext::from_config(
+            config,
+            OutputFormat::Json,
+            true,
+            None,
+            None,
+            None,
+            false,
+            None,
+            None,
+            None,
+        );
+        let client = ctx.get_client();
+
+        assert_eq!(client.actor_peer_id(), Some("legacy-agent"));
     }
 
     #[test]
-    fn pre_parse_config_gate_allows_non_server_and_unknown_commands() {
-        for args in [
-            &["ov", "con"][..],
-            &["ov", "task", "nope"],
-            &["ov", "config"],
-            &["ov", "config", "switch"],
-            &["ov", "config", "switch", "prod"],
-            &["ov", "config", "list"],
-            &["ov", "config", "delete", "prod"],
-            &["ov", "config", "add", "ov-service", "--api-key-stdin"],
-            &["ov", "config", "edit", "prod", "--activate"],
-            &["ov", "config", "setup-cli"],
-            &["ov", "skills", "validate", "./skills/foo"],
-            &["ov", "skills", "validate", "./skills/foo", "--strict"],
-            &["ov", "version"],
-            &["ov", "language", "en"],
-            &["ov", "lang", "en"],
-        ] {
-            assert!(
-                !pre_parse_requires_cli_config_file(&os_args(args)),
-                "{args:?} should not be gated before Clap parsing"
-            );
-        }
+    fn cli_context_uses_root_api_key_with_sudo() {
+        let config = Config {
+            url: DEFAULT_CUSTOM_URL.to_string(),
+            api_key: Some("user-key".to_string()),
+            root_api_key: Some("root-key".to_string()),
+            account: None,
+            user: None,
+            actor_peer_id: Some("peer-a".to_string()),
+            agent_id: None,
+            timeout: 60.0,
+            output: "table".to_string(),
+            echo_command: true,
+            show_progress: false,
+            verbose: false,
+            profile: false,
+            upload: Default::default(),
+            extra_headers: None,
+            gateway_token: None,
+            proxy: None,
+            ca_cert: None,
+            client_cert: None,
+            client_key: None,
+            tokenizer: None,
+            clip_inbox: None,
+            history: true,
+            wasm_plugins: Vec::new(),
+            redact_patterns: Default::default(),
+            llm: None,
+            connect_timeout: None,
+        };
+
+        // Without sudo: use api_key
+        let ctx = CliContext::from_config(
+            config.clone(),
+            OutputFormat::Json,
+            true,
+            None,
+            None,
+            None,
+            false,
+            None,
+            None,
+            None,
+        );
+        let client = ctx.get_client();
+        assert_eq!(client.api_key(), Some("user-key"));
+        assert_eq!(client.actor_peer_id(), Some("peer-a"));
+
+        // With sudo: use root_api_key
+        let ctx = CliContext::from_config(
+            config,
+            OutputFormat::Json,
+            true,
+            None,
+            None,
+            None,
+            true,
+            None,
+            None,
+            None,
+        );
+        let client = ctx.get_client();
+        assert_eq!(client.api_key(), Some("root-key"));
     }
 
     #[test]
-    fn cli_tree_help_hides_upload_and_admin_only_flags() {
-        let err = Cli::command()
-            .try_get_matches_from(["ov", "tree", "--help"])
-            .expect_err("help should exit through clap error");
-        let help = err.to_string();
+    fn cli_write_rejects_removed_semantic_flags() {
+        let result = Cli::try_parse_from([
+            "ov",
+            "write",
+            "viking://resources/demo.md",
+            "--content",
+            "updated",
+            "--no-semantics",
+            "--no-vectorize",
+        ]);
 
-        assert!(!help.contains("--progress"));
-        assert!(!help.contains("--no-progress"));
-        assert!(!help.contains("--verbose"));
-        assert!(!help.contains("--sudo"));
+        assert!(result.is_err(), "removed write flags should not parse");
     }
 
     #[test]
-    fn cli_add_resource_help_shows_upload_flags() {
-        let err = Cli::command()
-            .try_get_matches_from(["ov", "add-resource", "--help"])
-            .expect_err("help should exit through clap error");
-        let help = err.to_string();
-
-        assert!(help.contains("--progress"));
-        assert!(help.contains("--no-progress"));
-        assert!(help.contains("--verbose"));
+    fn cli_parses_write_and_append_content_sources() {
+        match Cli::try_parse_from(["ov", "append", "viking://resources/logs/run.md", "-"])
+            .map(|cli| cli.command)
+        {
+            Ok(Commands::Append(args)) => {
+                assert_eq!(args.uri, "viking://resources/logs/run.md");
+                assert_eq!(args.source.as_deref(), Some("-"));
+                assert_eq!(args.content, None);
+            }
+            _ => panic!("expected append command"),
+        }
+        let cli = Cli::try_parse_from(["ov", "append", "viking://resources/logs/run.md", "-"])
+            .expect("append - should parse");
+        assert!(!cli.command.can_queue_offline());
+        let cli = Cli::try_parse_from(["ov", "write", "viking://resources/a.md", "./a.md"])
+            .expect("write with a file argument should parse");
+        assert!(cli.command.can_queue_offline());
+        assert!(
+            Cli::try_parse_from([
+                "ov",
+                "write",
+                "viking://resources/a.md",
+                "./a.md",
+                "--content",
+                "text",
+            ])
+            .is_err()
+        );
     }
 
     #[test]
-    fn cli_add_skill_help_shows_upload_flags() {
-        let err = Cli::command()
-            .try_get_matches_from(["ov", "add-skill", "--help"])
-            .expect_err("help should exit through clap error");
-        let help = err.to_string();
+    fn cli_import_rejects_removed_vectorize_flag() {
+        let result = Cli::try_parse_from([
+            "ov",
+            "import",
+            "./exports/demo.ovpack",
+            "viking://resources/imported/",
+            "--no-vectorize",
+        ]);
 
-        assert!(help.contains("--progress"));
-        assert!(help.contains("--no-progress"));
-        assert!(help.contains("--verbose"));
+        assert!(
+            result.is_err(),
+            "removed import vectorize flag should not parse"
+        );
     }
 
     #[test]
-    fn cli_tree_rejects_upload_flags_after_subcommand() {
-        assert!(Cli::try_parse_from(["ov", "tree", "viking://", "--progress"]).is_err());
-        assert!(Cli::try_parse_from(["ov", "tree", "viking://", "--no-progress"]).is_err());
-        assert!(Cli::try_parse_from(["ov", "tree", "viking://", "--verbose"]).is_err());
+    fn cli_import_rejects_removed_force_flag() {
+        let result = Cli::try_parse_from([
+            "ov",
+            "import",
+            "./exports/demo.ovpack",
+            "viking://resources/imported/",
+            "--force",
+        ]);
+
+        assert!(
+            result.is_err(),
+            "removed import force flag should not parse"
+        );
     }
 
     #[test]
-    fn cli_parses_sudo_as_hidden_global_flag_after_subcommand() {
-        let cli = Cli::try_parse_from(["ov", "tree", "viking://", "--sudo"])
-            .expect("sudo should parse anywhere so runtime can show a precise command error");
+    fn cli_parses_import_signature_flags() {
+        let cli = Cli::try_parse_from([
+            "ov",
+            "import",
+            "./exports/demo.ovpack",
+            "viking://resources/imported/",
+            "--require-signed",
+            "--trusted-key",
+            "ci.pub",
+            "--trusted-key",
+            "release.pub",
+        ])
+        .expect("import signature flags should parse");
 
-        assert!(cli.sudo);
+        match cli.command {
+            Commands::Import(args) => {
+                assert!(args.signature.require_signed);
+                assert_eq!(args.signature.trusted_keys, ["ci.pub", "release.pub"]);
+            }
+            _ => panic!("expected import command"),
+        }
     }
 
     #[test]
-    fn cli_parses_upload_flags_on_upload_commands() {
-        let add_resource = Cli::try_parse_from([
+    fn cli_parses_add_url_crawl_options() {
+        let cli = Cli::try_parse_from([
             "ov",
-            "add-resource",
-            "./README.md",
-            "--progress",
-            "--verbose",
+            "add",
+            "url",
+            "https://docs.example.com/guide/",
+            "--depth",
+            "2",
+            "--same-domain",
+            "-n",
+            "20",
         ])
-        .expect("add-resource upload flags should parse");
-        match add_resource.command {
-            Commands::AddResource { upload_options, .. } => {
-                assert!(upload_options.progress);
-                assert!(upload_options.verbose);
+        .expect("add url should parse");
+
+        match cli.command {
+            Commands::Add {
+                action: Some(AddCommands::Url(args)),
+                ..
+            } => {
+                assert_eq!(args.url, "https://docs.example.com/guide/");
+                assert_eq!(args.depth, 2);
+                assert!(args.same_domain);
+                assert_eq!(args.limit, 20);
+                assert_eq!(args.parent, "viking://resources/web");
+                assert!(!args.dry_run);
             }
-            _ => panic!("expected add-resource command"),
+            _ => panic!("expected add url command"),
         }
 
-        let add_skill = Cli::try_parse_from(["ov", "add-skill", "./skill", "--no-progress"])
-            .expect("add-skill upload flags should parse");
-        match add_skill.command {
-            Commands::AddSkill { upload_options, .. } => {
-                assert!(upload_options.no_progress);
+        assert!(Cli::try_parse_from(["ov", "add", "url"]).is_err());
+    }
+
+    #[test]
+    fn cli_parses_add_feed_state_options() {
+        let cli = Cli::try_parse_from([
+            "ov",
+            "add",
+            "feed",
+            "https://blog.example.com/feed.xml",
+            "--state",
+            "/var/lib/ov/feeds.json",
+            "-n",
+            "10",
+        ])
+        .expect("add feed should parse");
+
+        match cli.command {
+            Commands::Add {
+                action: Some(AddCommands::Feed(args)),
+                ..
+            } => {
+                assert_eq!(args.url, "https://blog.example.com/feed.xml");
+                assert_eq!(args.parent, "viking://resources/feeds");
+                assert_eq!(
+                    args.state.as_deref(),
+                    Some(std::path::Path::new("/var/lib/ov/feeds.json"))
+                );
+                assert_eq!(args.limit, Some(10));
+                assert!(!args.mark_seen);
             }
-            _ => panic!("expected add-skill command"),
+            _ => panic!("expected add feed command"),
         }
 
-        assert!(Cli::try_parse_from(["ov", "skills", "add", "./skill", "--progress"]).is_err());
-        assert!(Cli::try_parse_from(["ov", "skills", "update", "--progress"]).is_err());
+        assert!(
+            Cli::try_parse_from([
+                "ov",
+                "add",
+                "feed",
+                "https://blog.example.com/feed.xml",
+                "--mark-seen",
+                "--dry-run",
+            ])
+            .is_err()
+        );
     }
 
     #[test]
-    fn cli_parses_skills_command_group() {
-        let list = Cli::try_parse_from(["ov", "skills", "list", "--limit", "25"])
-            .expect("skills list should parse");
-        match list.command {
-            Commands::Skills {
-                action: SkillCommands::List { node_limit, .. },
-            } => assert_eq!(node_limit, 25),
-            _ => panic!("expected skills list"),
+    fn cli_parses_add_table() {
+        let cli = Cli::try_parse_from([
+            "ov",
+            "add",
+            "table",
+            "data.parquet",
+            "--target",
+            "viking://resources/datasets/sales",
+            "--chunk-rows",
+            "500",
+        ])
+        .expect("add table should parse");
+
+        match cli.command {
+            Commands::Add {
+                action: Some(AddCommands::Table(args)),
+                ..
+            } => {
+                assert_eq!(args.path, std::path::Path::new("data.parquet"));
+                assert_eq!(
+                    args.target.as_deref(),
+                    Some("viking://resources/datasets/sales")
+                );
+                assert_eq!(args.chunk_rows, 500);
+                assert_eq!(args.delimiter, None);
+                assert!(!args.dry_run);
+            }
+            _ => panic!("expected add table command"),
         }
+    }
 
-        let find = Cli::try_parse_from([
+    #[test]
+    fn cli_parses_add_object_storage_source() {
+        let cli = Cli::try_parse_from([
             "ov",
-            "skills",
-            "find",
-            "review code",
-            "--threshold",
-            "0.4",
-            "--level",
-            "0,1",
+            "add",
+            "tos://docs/handbook/",
+            "-j",
+            "8",
+            "--region",
+            "cn-shanghai",
         ])
-        .expect("skills find should parse");
-        match find.command {
-            Commands::Skills {
-                action:
-                    SkillCommands::Find {
-                        query,
-                        threshold,
-                        level,
-                        ..
-                    },
+        .expect("add tos:// should parse");
+        match cli.command {
+            Commands::Add {
+                action: None,
+                objects,
+                ..
             } => {
-                assert_eq!(query, "review code");
-                assert_eq!(threshold, Some(0.4));
-                assert_eq!(level, Some(vec![0, 1]));
+                assert_eq!(objects.source.as_deref(), Some("tos://docs/handbook/"));
+                assert_eq!(objects.concurrency, 8);
+                assert_eq!(objects.region.as_deref(), Some("cn-shanghai"));
+                assert_eq!(objects.parent, None);
+                assert!(!objects.check_only);
             }
-            _ => panic!("expected skills find"),
+            _ => panic!("expected add objects command"),
         }
 
-        let update =
-            Cli::try_parse_from(["ov", "skills", "update", "code-review", "--wait", "--yes"])
-                .expect("skills update should parse");
-        match update.command {
-            Commands::Skills {
-                action:
-                    SkillCommands::Update {
-                        skills, wait, yes, ..
-                    },
+        let cli = Cli::try_parse_from(["ov", "add", "--check-only", "./contracts"])
+            .expect("add --check-only should parse");
+        match cli.command {
+            Commands::Add {
+                action: None,
+                objects,
+                ..
             } => {
-                assert_eq!(skills, vec!["code-review"]);
-                assert!(wait);
-                assert!(yes);
+                assert_eq!(objects.source.as_deref(), Some("./contracts"));
+                assert!(objects.check_only);
             }
-            _ => panic!("expected skills update"),
+            _ => panic!("expected add --check-only command"),
         }
 
-        let add_selected = Cli::try_parse_from([
+        let cli = Cli::try_parse_from([
             "ov",
-            "skills",
             "add",
-            "https://github.com/acme/skills.git",
-            "--skill",
-            "foo",
-            "bar",
-            "--list",
-            "--yes",
+            "-",
+            "--name",
+            "notes.md",
+            "--target",
+            "viking://resources/notes",
         ])
-        .expect("skills add RFC flags should parse");
-        match add_selected.command {
-            Commands::Skills {
-                action:
-                    SkillCommands::Add {
-                        source,
-                        skills,
-                        list,
-                        yes,
-                        ..
-                    },
+        .expect("add - should parse");
+        match cli.command {
+            Commands::Add {
+                action: None,
+                objects,
+                ..
             } => {
-                assert_eq!(source, "https://github.com/acme/skills.git");
-                assert_eq!(skills, vec!["foo", "bar"]);
-                assert!(list);
-                assert!(yes);
+                assert_eq!(objects.source.as_deref(), Some("-"));
+                assert_eq!(objects.name.as_deref(), Some("notes.md"));
+                assert_eq!(objects.target, "viking://resources/notes");
             }
-            _ => panic!("expected skills add"),
+            _ => panic!("expected add - command"),
         }
 
-        let show = Cli::try_parse_from([
+        assert!(Cli::try_parse_from(["ov", "add"]).is_err());
+    }
+
+    #[test]
+    fn cli_parses_import_mcp_without_pack_arguments() {
+        let cli = Cli::try_parse_from([
             "ov",
-            "skills",
-            "show",
-            "code-review",
-            "--level",
-            "2",
-            "--files",
-            "--source",
-            "--format",
-            "json",
+            "import",
+            "mcp",
+            "--server",
+            "npx -y lark-docs-mcp",
+            "--tool",
+            "list_docs",
+            "--tool",
+            "get_doc",
+            "--args",
+            r#"{"folder":"wiki"}"#,
+            "--parent",
+            "viking://resources/lark",
+            "-n",
+            "5",
         ])
-        .expect("skills show RFC flags should parse");
-        match show.command {
-            Commands::Skills {
-                action:
-                    SkillCommands::Show {
-                        level,
-                        files,
-                        source,
-                        format,
-                        ..
-                    },
-            } => {
-                assert_eq!(level, Some(2));
-                assert!(files);
-                assert!(source);
-                assert_eq!(format.as_deref(), Some("json"));
+        .expect("import mcp should parse");
+
+        match cli.command {
+            Commands::Import(args) => {
+                assert_eq!(args.file_path, None);
+                let Some(ImportCommands::Mcp(args)) = args.action else {
+                    panic!("expected import mcp command");
+                };
+                assert_eq!(args.server, "npx -y lark-docs-mcp");
+                assert_eq!(args.tools, ["list_docs", "get_doc"]);
+                assert_eq!(args.parent, "viking://resources/lark");
+                assert_eq!(args.limit, Some(5));
+                assert_eq!(args.timeout, 120);
             }
-            _ => panic!("expected skills show"),
+            _ => panic!("expected import mcp command"),
         }
 
-        let show_global_output =
-            Cli::try_parse_from(["ov", "skills", "show", "code-review", "-o", "json"])
-                .expect("skills show should accept global -o after the subcommand");
-        assert_eq!(show_global_output.output, OutputFormat::Json);
+        assert!(Cli::try_parse_from(["ov", "import", "mcp"]).is_err());
+        assert!(Cli::try_parse_from(["ov", "import", "demo.ovpack"]).is_err());
 
-        let remove = Cli::try_parse_from(["ov", "skills", "remove", "foo", "bar", "--yes"])
-            .expect("skills remove --yes should parse");
-        match remove.command {
-            Commands::Skills {
-                action:
-                    SkillCommands::Remove {
-                        skills, yes, all, ..
-                    },
-            } => {
-                assert_eq!(skills, vec!["foo", "bar"]);
-                assert!(yes);
-                assert!(!all);
+        let cli = Cli::try_parse_from(["ov", "import", "notion", "./Export-acme.zip", "--dry-run"])
+            .expect("import notion should parse");
+        match cli.command {
+            Commands::Import(args) => {
+                let Some(ImportCommands::Notion(args)) = args.action else {
+                    panic!("expected import notion command");
+                };
+                assert_eq!(args.export, std::path::Path::new("./Export-acme.zip"));
+                assert_eq!(args.parent, "viking://resources/notion");
+                assert!(args.dry_run);
             }
-            _ => panic!("expected skills remove"),
+            _ => panic!("expected import notion command"),
         }
 
-        let validate =
-            Cli::try_parse_from(["ov", "skills", "validate", "./skills/foo", "--strict"])
-                .expect("skills validate --strict should parse");
-        match validate.command {
-            Commands::Skills {
-                action: SkillCommands::Validate { path, strict },
-            } => {
-                assert_eq!(path, "./skills/foo");
-                assert!(strict);
+        let cli = Cli::try_parse_from([
+            "ov",
+            "import",
+            "mbox",
+            "dev-list.mbox",
+            "--parent",
+            "viking://resources/lists/dev",
+        ])
+        .expect("import mbox should parse");
+        match cli.command {
+            Commands::Import(args) => {
+                let Some(ImportCommands::Mbox(args)) = args.action else {
+                    panic!("expected import mbox command");
+                };
+                assert_eq!(args.path, std::path::Path::new("dev-list.mbox"));
+                assert_eq!(args.parent, "viking://resources/lists/dev");
+                assert!(!args.dry_run);
             }
-            _ => panic!("expected skills validate"),
+            _ => panic!("expected import mbox command"),
+        }
+    }
+
+    #[test]
+    fn cli_parses_pack_encryption_flags() {
+        let cli = Cli::try_parse_from([
+            "ov",
+            "export",
+            "viking://resources/demo",
+            "./exports/demo.ovpack",
+            "--recipient",
+            "age1example",
+            "--recipient",
+            "team.txt",
+        ])
+        .expect("export encryption flags should parse");
+        match cli.command {
+            Commands::Export(args) => {
+                assert_eq!(args.encryption.recipients, ["age1example", "team.txt"]);
+            }
+            _ => panic!("expected export command"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "ov",
+            "restore",
+            "./backup.ovpack",
+            "--passphrase-env",
+            "OV_PACK_PASSPHRASE",
+        ])
+        .expect("restore decryption flags should parse");
+        match cli.command {
+            Commands::Restore { restore } => {
+                assert_eq!(
+                    restore.decryption.passphrase.passphrase_env.as_deref(),
+                    Some("OV_PACK_PASSPHRASE")
+                );
+            }
+            _ => panic!("expected restore command"),
         }
+
+        assert!(
+            Cli::try_parse_from([
+                "ov",
+                "backup",
+                "./backup.ovpack",
+                "--recipient",
+                "age1example",
+                "--passphrase-stdin",
+            ])
+            .is_err(),
+            "recipients and a passphrase are mutually exclusive"
+        );
     }
 
     #[test]
-    fn cli_keeps_legacy_pre_command_upload_flags() {
+    fn cli_parses_pack_compression_flags() {
         let cli = Cli::try_parse_from([
             "ov",
-            "--progress",
-            "--verbose",
-            "add-resource",
-            "./README.md",
+            "backup",
+            "./backup.ovpack",
+            "--compression",
+            "zstd",
+            "--level",
+            "19",
         ])
-        .expect("legacy pre-command upload flags should still parse");
+        .expect("backup compression flags should parse");
+        match cli.command {
+            Commands::Backup {
+                action: None,
+                create,
+            } => {
+                assert_eq!(create.compression.compression.as_deref(), Some("zstd"));
+                assert_eq!(create.compression.level, Some(19));
+            }
+            _ => panic!("expected backup command"),
+        }
 
-        assert!(cli.progress);
-        assert!(cli.verbose);
+        assert!(
+            Cli::try_parse_from([
+                "ov",
+                "export",
+                "viking://resources/demo",
+                "./demo.ovpack",
+                "--compression",
+                "brotli",
+            ])
+            .is_err(),
+            "unknown compression methods are rejected"
+        );
     }
 
     #[test]
-    fn legacy_pre_command_upload_flags_only_allow_upload_commands() {
-        let upload_options = UploadCliOptions {
-            progress: true,
-            no_progress: false,
-            verbose: false,
-        };
-
-        let tree = Cli::try_parse_from(["ov", "--progress", "tree", "viking://"])
-            .expect("hidden legacy flag still parses before runtime validation");
-        assert_eq!(
-            legacy_upload_option_error(upload_options, &tree.command),
-            Some(
-                "--progress, --no-progress, and --verbose are only supported for add-resource and add-skill."
-            )
-        );
+    fn cli_parses_backup_create_and_restore() {
+        let cli = Cli::try_parse_from(["ov", "backup", "create", "./backup.ovpack", "--encrypt"])
+            .expect("backup create should parse");
+        match cli.command {
+            Commands::Backup {
+                action: Some(BackupCommands::Create(create)),
+                ..
+            } => {
+                assert_eq!(create.to.as_deref(), Some("./backup.ovpack"));
+                assert!(create.encryption.encrypt);
+            }
+            _ => panic!("expected backup create command"),
+        }
 
-        let add_resource = Cli::try_parse_from(["ov", "--progress", "add-resource", "./README.md"])
-            .expect("legacy pre-command upload flags should parse for add-resource");
-        assert!(legacy_upload_option_error(upload_options, &add_resource.command).is_none());
+        let cli = Cli::try_parse_from([
+            "ov",
+            "backup",
+            "restore",
+            "./backup.ovpack",
+            "--overwrite",
+            "--dry-run",
+        ])
+        .expect("backup restore should parse");
+        match cli.command {
+            Commands::Backup {
+                action: Some(BackupCommands::Restore(restore)),
+                ..
+            } => {
+                assert_eq!(restore.file_path, "./backup.ovpack");
+                assert_eq!(restore.on_conflict().as_deref(), Some("overwrite"));
+                assert!(restore.dry_run);
+            }
+            _ => panic!("expected backup restore command"),
+        }
 
-        let skills_add = Cli::try_parse_from(["ov", "--progress", "skills", "add", "./skill"])
-            .expect("hidden legacy flag still parses before runtime validation");
-        assert_eq!(
-            legacy_upload_option_error(upload_options, &skills_add.command),
-            Some(
-                "--progress, --no-progress, and --verbose are only supported for add-resource and add-skill."
-            )
+        assert!(
+            Cli::try_parse_from([
+                "ov",
+                "backup",
+                "restore",
+                "./backup.ovpack",
+                "--overwrite",
+                "--on-conflict",
+                "skip",
+            ])
+            .is_err(),
+            "--overwrite and --on-conflict are mutually exclusive"
         );
+        assert!(Cli::try_parse_from(["ov", "backup", "create"]).is_err());
+        assert!(Cli::try_parse_from(["ov", "backup"]).is_err());
     }
 
     #[test]
-    fn cli_parses_sudo_before_admin_command() {
-        let cli = Cli::try_parse_from(["ov", "--sudo", "admin", "list-accounts"])
-            .expect("pre-command sudo should parse");
-
-        assert!(cli.sudo);
+    fn cli_parses_sync_between_saved_configs() {
+        let cli = Cli::try_parse_from([
+            "ov",
+            "sync",
+            "--from",
+            "staging",
+            "--to",
+            "prod",
+            "--prefix",
+            "viking://resources/team",
+            "--dry-run",
+        ])
+        .expect("sync should parse");
+        assert!(!cli.command.requires_cli_config_file());
+        match cli.command {
+            Commands::Sync { sync } => {
+                assert_eq!((sync.from.as_str(), sync.to.as_str()), ("staging", "prod"));
+                assert_eq!(sync.prefix, "viking://resources/team");
+                assert!(sync.dry_run);
+            }
+            _ => panic!("expected sync command"),
+        }
+        assert!(Cli::try_parse_from(["ov", "sync", "--from", "staging", "--to", "prod"]).is_err());
     }
 
     #[test]
-    fn cli_parses_sudo_after_admin_command() {
-        let cli = Cli::try_parse_from(["ov", "admin", "list-accounts", "--sudo"])
-            .expect("post-command sudo should parse");
-
-        assert!(cli.sudo);
+    fn cli_parses_mcp_serve_command() {
+        let cli = Cli::try_parse_from(["ov", "mcp", "serve"]).expect("mcp serve should parse");
+        match cli.command {
+            Commands::Mcp {
+                action: McpCommands::Serve(args),
+            } => {
+                assert_eq!(args.transport, "stdio");
+                assert_eq!((args.host.as_str(), args.port), ("127.0.0.1", 8765));
+            }
+            _ => panic!("expected mcp serve command"),
+        }
+        let cli =
+            Cli::try_parse_from(["ov", "mcp", "serve", "--transport", "sse", "--port", "9000"])
+                .expect("mcp serve --transport sse should parse");
+        assert!(matches!(
+            cli.command,
+            Commands::Mcp {
+                action: McpCommands::Serve(McpServeArgs { ref transport, port: 9000, .. }),
+            } if transport == "sse"
+        ));
+        assert!(Cli::try_parse_from(["ov", "mcp", "serve", "--transport", "ws"]).is_err());
     }
 
     #[test]
-    fn sudo_supports_task_status_and_list_only() {
-        let status = Cli::try_parse_from(["ov", "--sudo", "task", "status", "task-123"])
-            .expect("sudo task status should parse");
-        assert!(status.sudo);
-        assert!(status.command.supports_sudo());
-
-        let list = Cli::try_parse_from([
+    fn cli_parses_mock_server_command() {
+        let cli = Cli::try_parse_from(["ov", "mock-server"]).expect("mock-server should parse");
+        match cli.command {
+            Commands::MockServer(args) => {
+                assert!(args.fixtures.is_none());
+                assert_eq!((args.host.as_str(), args.port), ("127.0.0.1", 1933));
+                assert!(!Commands::MockServer(args).requires_cli_config_file());
+            }
+            _ => panic!("expected mock-server command"),
+        }
+        let cli = Cli::try_parse_from([
             "ov",
-            "--sudo",
-            "task",
-            "list",
-            "--task-type",
-            "legacy_migration",
+            "mock-server",
+            "--fixtures",
+            "tests/fixtures",
+            "--port",
+            "0",
         ])
-        .expect("sudo task list should parse");
-        assert!(list.sudo);
-        assert!(list.command.supports_sudo());
-
-        let watch = Cli::try_parse_from(["ov", "--sudo", "task", "watch", "ls"])
-            .expect("sudo task watch should still parse before runtime validation");
-        assert!(watch.sudo);
-        assert!(!watch.command.supports_sudo());
+        .expect("mock-server --fixtures should parse");
+        assert!(matches!(
+            cli.command,
+            Commands::MockServer(MockServerArgs { fixtures: Some(ref dir), port: 0, .. })
+                if dir == std::path::Path::new("tests/fixtures")
+        ));
     }
 
     #[test]
-    fn cli_config_without_subcommand_parses_as_setup_entrypoint() {
-        Cli::try_parse_from(["ov", "config"]).expect("bare config command should parse");
+    fn cli_parses_watch_command() {
+        let cli = Cli::try_parse_from([
+            "ov",
+            "watch",
+            "./vault",
+            "--target",
+            "viking://resources/notes",
+            "--debounce",
+            "250",
+        ])
+        .expect("watch should parse");
+        match cli.command {
+            Commands::Watch { watch } => {
+                assert_eq!(watch.dir, "./vault");
+                assert_eq!(watch.target, "viking://resources/notes");
+                assert_eq!(watch.debounce, 250);
+                assert!(watch.state.is_none() && !watch.once);
+            }
+            _ => panic!("expected watch command"),
+        }
+        assert!(Cli::try_parse_from(["ov", "watch", "./vault"]).is_err());
     }
 
     #[test]
-    fn cli_config_single_purpose_subcommands_still_parse() {
-        for subcommand in ["show", "validate", "switch"] {
-            Cli::try_parse_from(["ov", "config", subcommand])
-                .unwrap_or_else(|_| panic!("config {subcommand} should parse"));
+    fn cli_parses_daemon_commands() {
+        let cli = Cli::try_parse_from(["ov", "daemon", "start", "--ttl", "30"])
+            .expect("daemon start should parse");
+        match cli.command {
+            Commands::Daemon {
+                action: DaemonCommands::Start(args),
+            } => {
+                assert_eq!(args.ttl, 30);
+                assert_eq!(args.max_entries, 1000);
+                assert_eq!(args.metrics, None);
+            }
+            _ => panic!("expected daemon start"),
         }
+        match Cli::try_parse_from(["ov", "daemon", "run", "--metrics", "0.0.0.0:9464"])
+            .map(|cli| cli.command)
+        {
+            Ok(Commands::Daemon {
+                action: DaemonCommands::Run(args),
+            }) => assert_eq!(args.metrics.as_deref(), Some("0.0.0.0:9464")),
+            _ => panic!("expected daemon run --metrics"),
+        }
+        assert!(matches!(
+            Cli::try_parse_from(["ov", "daemon", "status"]).map(|cli| cli.command),
+            Ok(Commands::Daemon {
+                action: DaemonCommands::Status
+            })
+        ));
+        assert!(Cli::try_parse_from(["ov", "daemon"]).is_err());
     }
 
     #[test]
-    fn cli_config_rejects_removed_setup_cli_subcommand() {
-        assert!(Cli::try_parse_from(["ov", "config", "setup-cli"]).is_err());
+    fn cli_parses_doctor_sample() {
+        match Cli::try_parse_from(["ov", "doctor", "--sample", "0"]).map(|cli| cli.command) {
+            Ok(Commands::Doctor(args)) => assert_eq!(args.sample, 0),
+            _ => panic!("expected doctor"),
+        }
     }
 
     #[test]
-    fn cli_language_command_and_alias_parse() {
-        let cli = Cli::try_parse_from(["ov", "language", "zh-CN"])
-            .expect("language command should parse");
+    fn cli_parses_context_assemble() {
+        let cli = Cli::try_parse_from([
+            "ov",
+            "context",
+            "assemble",
+            "--query",
+            "how does auth work",
+            "--levels",
+            "l0,l2",
+            "--budget",
+            "2000",
+            "--messages",
+        ])
+        .expect("context assemble should parse");
+        let mut cli = cli;
+        adopt_query_argument(&mut cli);
+        assert_eq!(cli.query, None);
         match cli.command {
-            Commands::Language { language } => assert_eq!(language.as_deref(), Some("zh-CN")),
-            _ => panic!("expected language command"),
+            Commands::Context(ContextArgs {
+                action: ContextCommands::Assemble(args),
+            }) => {
+                assert_eq!(args.query.as_deref(), Some("how does auth work"));
+                assert_eq!(args.levels, vec!["l0", "l2"]);
+                assert_eq!(args.budget, 2000);
+                assert_eq!(args.node_limit, 10);
+                assert!(args.messages);
+            }
+            _ => panic!("expected context assemble"),
         }
+        // With the question given as the argument, --query filters the output.
+        let mut cli =
+            Cli::try_parse_from(["ov", "context", "assemble", "deploy", "--query", ".sources"])
+                .expect("context assemble should parse");
+        adopt_query_argument(&mut cli);
+        assert_eq!(cli.query.as_deref(), Some(".sources"));
+        assert!(Cli::try_parse_from(["ov", "context", "assemble", "q", "--levels", "l3"]).is_err());
+    }
 
-        let cli = Cli::try_parse_from(["ov", "lang"]).expect("language alias should parse");
+    #[test]
+    fn cli_parses_pick_with_query_from_either_place() {
+        let mut cli = Cli::try_parse_from(["ov", "pick", "--query", "guide"])
+            .expect("pick --query should parse");
+        adopt_query_argument(&mut cli);
+        assert_eq!(cli.query, None);
         match cli.command {
-            Commands::Language { language } => assert!(language.is_none()),
-            _ => panic!("expected language command"),
+            Commands::Pick {
+                prefix,
+                query,
+                node_limit,
+            } => {
+                assert_eq!(prefix, "viking://");
+                assert_eq!(query.as_deref(), Some("guide"));
+                assert_eq!(node_limit, 10000);
+            }
+            _ => panic!("expected pick command"),
         }
+        let cli = Cli::try_parse_from(["ov", "pick", "viking://resources/docs", "auth"])
+            .expect("pick with a prefix and query should parse");
+        assert!(matches!(
+            cli.command,
+            Commands::Pick { prefix, query: Some(query), .. }
+                if prefix == "viking://resources/docs" && query == "auth"
+        ));
     }
 
     #[test]
-    fn language_gate_detects_language_command_after_global_flags() {
-        assert!(is_language_command_request(&os_args(&["ov", "language"])));
-        assert!(is_language_command_request(&os_args(&[
-            "ov", "lang", "zh-CN"
-        ])));
-        assert!(is_language_command_request(&os_args(&[
-            "ov", "--output", "json", "language", "en",
-        ])));
-        assert!(is_language_command_request(&os_args(&[
-            "ov",
-            "--output=json",
-            "--compact",
-            "false",
-            "lang",
-        ])));
-        assert!(is_language_command_request(&os_args(&[
+    fn cli_parses_ask() {
+        let cli = Cli::try_parse_from([
             "ov",
-            "--compact",
-            "lang",
-        ])));
-        assert!(!is_language_command_request(&os_args(&["ov", "status"])));
-        assert!(!is_language_command_request(&os_args(&["ov", "--help"])));
+            "ask",
+            "how are tokens issued?",
+            "--target",
+            "viking://resources/docs",
+            "--session",
+            "s1",
+        ])
+        .expect("ask should parse");
+        match cli.command {
+            Commands::Ask(args) => {
+                assert_eq!(args.question, "how are tokens issued?");
+                assert_eq!(args.target, "viking://resources/docs");
+                assert_eq!(args.session_id.as_deref(), Some("s1"));
+                assert_eq!(args.budget, 8000);
+                assert_eq!(args.model, None);
+            }
+            _ => panic!("expected ask"),
+        }
     }
 
     #[test]
-    fn language_gate_action_continues_prompts_or_exits() {
-        assert_eq!(
-            language_gate_action(&os_args(&["ov", "status"]), true, false),
-            LanguageGateAction::Continue
-        );
-        assert_eq!(
-            language_gate_action(&os_args(&["ov", "language"]), false, false),
-            LanguageGateAction::Continue
-        );
-        assert_eq!(
-            language_gate_action(&os_args(&["ov", "status"]), false, true),
-            LanguageGateAction::Prompt
-        );
-        assert_eq!(
-            language_gate_action(&os_args(&["ov", "status"]), false, false),
-            LanguageGateAction::ExitNonInteractive
+    fn cli_parses_tokens_count_and_tokens_column() {
+        let cli = Cli::try_parse_from([
+            "ov",
+            "tokens",
+            "count",
+            "notes.md",
+            "-",
+            "--tokenizer",
+            "o200k",
+        ])
+        .expect("tokens count should parse");
+        assert!(!cli.command.requires_cli_config_file());
+        match cli.command {
+            Commands::Tokens(TokensArgs {
+                action: TokensCommands::Count(args),
+            }) => {
+                assert_eq!(args.sources, vec!["notes.md", "-"]);
+                assert_eq!(args.tokenizer.as_deref(), Some("o200k"));
+            }
+            _ => panic!("expected tokens count"),
+        }
+        let cli = Cli::try_parse_from(["ov", "tokens", "count", "viking://resources/a.md"])
+            .expect("tokens count should parse");
+        assert!(cli.command.requires_cli_config_file());
+        assert!(
+            Cli::try_parse_from(["ov", "tokens", "count", "-", "--tokenizer", "p50k"]).is_err()
         );
+
+        let cli =
+            Cli::try_parse_from(["ov", "find", "auth", "--tokens"]).expect("find should parse");
+        match cli.command {
+            Commands::Find(args) => assert!(args.token_options.tokens),
+            _ => panic!("expected find"),
+        }
+        let cli = Cli::try_parse_from(["ov", "ls", "--tokens"]).expect("ls should parse");
+        assert!(matches!(cli.command, Commands::Ls(args) if args.token_options.tokens));
     }
 
     #[test]
-    fn language_gate_allows_config_agent_commands_without_saved_language() {
-        for args in [
-            &["ov", "config", "add", "ov-service", "--api-key-stdin"][..],
-            &["ov", "config", "add", "custom"],
-            &["ov", "config", "edit", "prod", "--activate"],
-            &["ov", "config", "delete", "prod"],
-            &["ov", "config", "list"],
-            &["ov", "config", "switch", "prod"],
-        ] {
-            assert_eq!(
-                language_gate_action(&os_args(args), false, false),
-                LanguageGateAction::Continue,
-                "{args:?} should bypass first-run language selection"
-            );
-        }
+    fn cli_parses_search_scoring_options() {
+        let scoring = |args: &[&str]| {
+            let cli = Cli::try_parse_from(args).expect("search should parse");
+            let options = match cli.command {
+                Commands::Find(args) => args.scoring_options,
+                Commands::Search(args) => args.scoring_options,
+                _ => panic!("expected find or search"),
+            };
+            CliContext::from_config(
+                Config::default(),
+                OutputFormat::Table,
+                false,
+                None,
+                None,
+                None,
+                false,
+                None,
+                None,
+                None,
+            )
+            .with_scoring_options(options)
+            .search_scoring()
+        };
+
+        let hybrid = scoring(&[
+            "ov",
+            "find",
+            "termination clause",
+            "--mode",
+            "hybrid",
+            "--keyword-weight",
+            "0.7",
+            "--no-rerank",
+            "--scores",
+        ])
+        .expect("hybrid scoring");
+        assert_eq!(hybrid.mode.as_deref(), Some("hybrid"));
+        assert_eq!(hybrid.keyword_weight, Some(0.7));
+        assert_eq!(hybrid.vector_weight, None);
+        assert_eq!(hybrid.rerank, Some(false));
+        assert!(hybrid.include_scores);
+
+        let weighted = scoring(&["ov", "search", "q", "--vector-weight", "0.2", "--rerank"])
+            .expect("weights imply hybrid");
+        assert_eq!(weighted.mode.as_deref(), Some("hybrid"));
+        assert_eq!(weighted.rerank, Some(true));
+
+        let explained = scoring(&["ov", "search", "q", "--explain"]).expect("explain");
+        assert!(explained.explain);
+        assert!(!explained.include_scores);
 
         assert_eq!(
-            language_gate_action(&os_args(&["ov", "config"]), false, false),
-            LanguageGateAction::ExitNonInteractive
+            scoring(&["ov", "find", "q"]).expect("defaults"),
+            client::SearchScoring::default()
         );
+        assert!(
+            scoring(&[
+                "ov",
+                "find",
+                "q",
+                "--mode",
+                "keyword",
+                "--vector-weight",
+                "1"
+            ])
+            .is_err()
+        );
+        assert!(scoring(&["ov", "find", "q", "--keyword-weight=-1"]).is_err());
+        assert!(Cli::try_parse_from(["ov", "find", "q", "--mode", "bm25"]).is_err());
     }
 
     #[test]
-    fn language_gate_message_points_to_explicit_language_commands() {
-        let message = language_required_message();
+    fn cli_parses_snippets_for_find_and_search() {
+        for command in ["find", "search"] {
+            let cli = Cli::try_parse_from(["ov", command, "termination", "--snippets"])
+                .expect("snippets should parse");
+            let snippets = match cli.command {
+                Commands::Find(args) => args.snippet_options,
+                Commands::Search(args) => args.snippet_options,
+                _ => panic!("expected {command}"),
+            };
+            assert!(snippets.snippets);
+        }
+    }
 
-        assert!(message.contains("ov language en"));
-        assert!(message.contains("ov language zh-CN"));
+    #[test]
+    fn cli_folds_created_type_and_tag_filters_for_find_and_search() {
+        for command in ["find", "search"] {
+            let cli = Cli::try_parse_from([
+                "ov",
+                command,
+                "editor",
+                "--created-after",
+                "7d",
+                "--type",
+                "memory,doc",
+                "--context-type",
+                "memory",
+                "--tag",
+                "team=search",
+                "--tag",
+                "env=prod",
+            ])
+            .expect("filters should parse");
+            let filters = match cli.command {
+                Commands::Find(args) => {
+                    let FindArgs {
+                        after,
+                        before,
+                        context_type,
+                        tags,
+                        filter_options,
+                        ..
+                    } = *args;
+                    filter_options.merge(after, before, context_type, tags)
+                }
+                Commands::Search(args) => {
+                    let SearchArgs {
+                        after,
+                        before,
+                        context_type,
+                        tags,
+                        filter_options,
+                        ..
+                    } = *args;
+                    filter_options.merge(after, before, context_type, tags)
+                }
+                _ => panic!("expected {command}"),
+            };
+            assert_eq!(filters.after.as_deref(), Some("7d"));
+            assert_eq!(filters.before, None);
+            assert_eq!(filters.time_field.as_deref(), Some("created_at"));
+            assert_eq!(
+                filters.context_type,
+                Some(vec!["memory".to_string(), "resource".to_string()])
+            );
+            assert_eq!(
+                filters.tags,
+                Some(vec!["team=search".to_string(), "env=prod".to_string()])
+            );
+        }
+        assert!(
+            Cli::try_parse_from(["ov", "find", "q", "--after", "1d", "--created-after", "7d"])
+                .is_err()
+        );
+        assert!(Cli::try_parse_from(["ov", "find", "q", "--type", "resource"]).is_err());
     }
 
     #[test]
-    fn language_command_without_value_requires_interactive_picker() {
-        assert!(language_command_can_run_picker(true, false));
-        assert!(language_command_can_run_picker(false, true));
-        assert!(!language_command_can_run_picker(false, false));
+    fn cli_parses_tag_commands() {
+        match Cli::try_parse_from([
+            "ov",
+            "tag",
+            "add",
+            "viking://resources/a.md",
+            "team=search",
+            "env=prod",
+            "-r",
+        ])
+        .map(|cli| cli.command)
+        {
+            Ok(Commands::Tag {
+                action:
+                    TagCommands::Add {
+                        uri,
+                        tags,
+                        recursive,
+                    },
+            }) => {
+                assert_eq!(uri, "viking://resources/a.md");
+                assert_eq!(tags, vec!["team=search", "env=prod"]);
+                assert!(recursive);
+            }
+            _ => panic!("expected tag add"),
+        }
+        assert!(matches!(
+            Cli::try_parse_from(["ov", "tag", "rm", "viking://resources/a.md", "env"])
+                .map(|cli| cli.command),
+            Ok(Commands::Tag {
+                action: TagCommands::Rm { .. }
+            })
+        ));
+        assert!(Cli::try_parse_from(["ov", "tag", "add", "viking://resources/a.md"]).is_err());
+        match Cli::try_parse_from([
+            "ov",
+            "ls",
+            "viking://resources",
+            "--tag",
+            "team=search",
+            "--tag",
+            "env",
+        ])
+        .map(|cli| cli.command)
+        {
+            Ok(Commands::Ls(args)) => assert_eq!(args.tag, vec!["team=search", "env"]),
+            _ => panic!("expected ls"),
+        }
     }
 
     #[test]
-    fn first_command_token_skips_global_flags() {
-        assert_eq!(
-            first_command_token(&os_args(&[
-                "ov",
-                "--output",
-                "json",
-                "--account=acme",
-                "--actor-peer-id",
-                "peer-a",
-                "--sudo",
-                "admin",
-            ]))
-            .as_deref(),
-            Some("admin")
-        );
-        assert_eq!(
-            first_command_token(&os_args(&["ov", "--help"])).as_deref(),
-            None
+    fn cli_parses_relations_subcommands_and_plain_listing() {
+        match Cli::try_parse_from([
+            "ov",
+            "relations",
+            "graph",
+            "viking://resources/contract.md",
+            "--depth",
+            "2",
+            "--format",
+            "mermaid",
+        ])
+        .map(|cli| cli.command)
+        {
+            Ok(Commands::Relations {
+                action: Some(RelationsCommands::Graph { uri, depth, format }),
+                ..
+            }) => {
+                assert_eq!(uri, "viking://resources/contract.md");
+                assert_eq!(depth, 2);
+                assert_eq!(format, "mermaid");
+            }
+            _ => panic!("expected relations graph"),
+        }
+        match Cli::try_parse_from(["ov", "relations", "viking://resources/contract.md"])
+            .map(|cli| cli.command)
+        {
+            Ok(Commands::Relations { action: None, uri }) => {
+                assert_eq!(uri.as_deref(), Some("viking://resources/contract.md"))
+            }
+            _ => panic!("expected relations listing"),
+        }
+        match Cli::try_parse_from([
+            "ov",
+            "relations",
+            "neighbors",
+            "viking://resources/contract.md",
+            "--depth",
+            "3",
+            "--reason-filter",
+            "^signed",
+        ])
+        .map(|cli| cli.command)
+        {
+            Ok(Commands::Relations {
+                action:
+                    Some(RelationsCommands::Neighbors {
+                        depth,
+                        reason_filter,
+                        ..
+                    }),
+                ..
+            }) => {
+                assert_eq!(depth, 3);
+                assert_eq!(reason_filter.as_deref(), Some("^signed"));
+            }
+            _ => panic!("expected relations neighbors"),
+        }
+        match Cli::try_parse_from(["ov", "relations", "path", "viking://a.md", "viking://b.md"])
+            .map(|cli| cli.command)
+        {
+            Ok(Commands::Relations {
+                action: Some(RelationsCommands::Path { to_uri, depth, .. }),
+                ..
+            }) => {
+                assert_eq!(to_uri, "viking://b.md");
+                assert_eq!(depth, 6);
+            }
+            _ => panic!("expected relations path"),
+        }
+        match Cli::try_parse_from(["ov", "relations", "import", "edges.csv", "--dry-run"])
+            .map(|cli| cli.command)
+        {
+            Ok(Commands::Relations {
+                action:
+                    Some(RelationsCommands::Import {
+                        file,
+                        format,
+                        dry_run,
+                    }),
+                ..
+            }) => {
+                assert_eq!(file, "edges.csv");
+                assert_eq!(format, None);
+                assert!(dry_run);
+            }
+            _ => panic!("expected relations import"),
+        }
+        assert!(Cli::try_parse_from(["ov", "relations"]).is_err());
+        assert!(
+            Cli::try_parse_from(["ov", "relations", "graph", "viking://a.md", "--depth", "0"])
+                .is_err()
         );
     }
 
     #[test]
-    fn find_command_index_skips_root_value_options() {
-        assert_eq!(
-            find_command_index(&os_args(&[
-                "ov",
-                "--output",
-                "json",
-                "--account=acme",
-                "--actor-peer-id",
-                "peer-a",
-                "privacy",
-                "sample-policy",
-            ])),
-            Some(6)
-        );
-        assert_eq!(
-            find_command_index(&os_args(&["ov", "--compact", "privacy", "sample-policy"])),
-            Some(2)
-        );
-        assert_eq!(
-            find_command_index(&os_args(&["ov", "-c", "false", "privacy", "sample-policy"])),
-            Some(3)
-        );
+    fn cli_parses_meta_commands() {
+        match Cli::try_parse_from([
+            "ov",
+            "meta",
+            "set",
+            "viking://resources/a.md",
+            "source=crm",
+            "batch=42",
+        ])
+        .map(|cli| cli.command)
+        {
+            Ok(Commands::Meta {
+                action: MetaCommands::Set { uri, pairs },
+            }) => {
+                assert_eq!(uri, "viking://resources/a.md");
+                assert_eq!(pairs, vec!["source=crm", "batch=42"]);
+            }
+            _ => panic!("expected meta set"),
+        }
+        match Cli::try_parse_from(["ov", "meta", "get", "viking://resources/a.md", "source"])
+            .map(|cli| cli.command)
+        {
+            Ok(Commands::Meta {
+                action: MetaCommands::Get { key, .. },
+            }) => assert_eq!(key.as_deref(), Some("source")),
+            _ => panic!("expected meta get"),
+        }
+        assert!(Cli::try_parse_from(["ov", "meta", "set", "viking://resources/a.md"]).is_err());
+        assert!(Cli::try_parse_from(["ov", "meta", "rm", "viking://resources/a.md"]).is_err());
     }
 
     #[test]
-    fn cli_status_parses_verbose_flag() {
-        let cli =
-            Cli::try_parse_from(["ov", "status", "--verbose"]).expect("status verbose parses");
-
-        match cli.command {
-            Commands::Status { verbose } => assert!(verbose),
-            _ => panic!("expected status command"),
+    fn cli_parses_alias_and_bookmark_commands() {
+        match Cli::try_parse_from([
+            "ov",
+            "alias",
+            "set",
+            "contracts",
+            "viking://resources/contract/2024",
+        ])
+        .map(|cli| cli.command)
+        {
+            Ok(Commands::Alias {
+                action: AliasCommands::Set { name, uri },
+            }) => {
+                assert_eq!(name, "contracts");
+                assert_eq!(uri, "viking://resources/contract/2024");
+            }
+            _ => panic!("expected alias set"),
+        }
+        match Cli::try_parse_from([
+            "ov",
+            "bookmark",
+            "add",
+            "viking://resources/contract/2024/q3.md",
+            "--name",
+            "q3",
+        ])
+        .map(|cli| cli.command)
+        {
+            Ok(Commands::Bookmark {
+                action: BookmarkCommands::Add { name, .. },
+            }) => assert_eq!(name.as_deref(), Some("q3")),
+            _ => panic!("expected bookmark add"),
         }
+        let cli = Cli::try_parse_from(["ov", "bookmark", "ls"]).unwrap();
+        assert!(!cli.command.requires_cli_config_file());
     }
 
     #[test]
-    fn plain_help_is_rejected_for_top_level_group_and_structured_leaf_commands() {
-        for (args, expected_help) in [
-            (vec!["ov", "help"], "ov --help"),
-            (vec!["ov", "config", "help"], "ov config --help"),
-            (vec!["ov", "task", "help"], "ov task --help"),
-            (vec!["ov", "admin", "help"], "ov admin --help"),
-            (vec!["ov", "ls", "help"], "ov ls --help"),
-            (vec!["ov", "tree", "help"], "ov tree --help"),
-            (vec!["ov", "read", "help"], "ov read --help"),
-            (
-                vec!["ov", "task", "status", "help"],
-                "ov task status --help",
-            ),
-            (
-                vec!["ov", "system", "consistency", "help"],
-                "ov system consistency --help",
-            ),
-            (
-                vec!["ov", "system", "backend", "sync-status", "help"],
-                "ov system backend sync-status --help",
-            ),
-        ] {
-            let misuse = plain_help_misuse(&os_args(&args))
-                .unwrap_or_else(|| panic!("{args:?} should be plain-help misuse"));
-            assert_eq!(misuse.help_command, expected_help);
+    fn cli_parses_recent_flags() {
+        match Cli::try_parse_from(["ov", "recent", "--uris", "-n", "5"]).map(|cli| cli.command) {
+            Ok(Commands::Recent(args)) => {
+                assert!(args.uris && !args.commands && !args.clear);
+                assert_eq!(args.limit, 5);
+            }
+            _ => panic!("expected recent --uris"),
         }
+        assert!(Cli::try_parse_from(["ov", "recent", "--uris", "--commands"]).is_err());
+        assert!(Cli::try_parse_from(["ov", "recent", "--clear", "--uris"]).is_err());
     }
 
     #[test]
-    fn plain_help_is_allowed_as_free_form_user_input() {
-        for args in [
-            vec!["ov", "find", "help"],
-            vec!["ov", "search", "help"],
-            vec!["ov", "grep", "help"],
-            vec!["ov", "glob", "help"],
-            vec!["ov", "add-memory", "help"],
-            vec!["ov", "add-resource", "help"],
-            vec!["ov", "add-skill", "help"],
-        ] {
-            assert!(
-                plain_help_misuse(&os_args(&args)).is_none(),
-                "{args:?} should remain normal user input"
-            );
+    fn cli_parses_workspace_commands_and_override() {
+        match Cli::try_parse_from(["ov", "workspace", "use", "acme"]).map(|cli| cli.command) {
+            Ok(Commands::Workspace {
+                action: WorkspaceCommands::Use { name },
+            }) => assert_eq!(name, "acme"),
+            _ => panic!("expected workspace use"),
         }
+        let cli = Cli::try_parse_from(["ov", "ls", "viking://", "--workspace", "globex"]).unwrap();
+        assert_eq!(cli.workspace.as_deref(), Some("globex"));
+        assert!(Cli::try_parse_from(["ov", "ls", "--workspace", "a", "--account", "b"]).is_err());
     }
 
     #[test]
-    fn plain_help_ignores_option_values() {
-        assert!(
-            plain_help_misuse(&os_args(&[
-                "ov",
-                "read",
-                "--account",
-                "help",
-                "viking://resource",
-            ]))
-            .is_none()
-        );
-        assert!(plain_help_misuse(&os_args(&["ov", "grep", "--uri", "help", "TODO",])).is_none());
-
-        for args in [
-            &["ov", "mkdir", "--description", "help", "viking://notes"][..],
-            &[
-                "ov",
-                "write",
-                "viking://notes/todo.md",
-                "--from-file",
-                "help",
-            ],
-            &[
-                "ov",
-                "session",
-                "add-message",
-                "abc",
-                "--peer-id",
-                "help",
-                "--role",
-                "user",
-                "--content",
-                "hi",
-            ],
-            &[
-                "ov",
-                "privacy",
-                "upsert",
-                "cat",
-                "target",
-                "--values-json",
-                "help",
-            ],
-            &["ov", "admin", "create-account", "acct", "--admin", "help"],
-            &["ov", "config", "add", "custom", "--url", "help"],
-            &["ov", "task", "list", "--status", "help"],
-            &[
-                "ov",
-                "system",
-                "crypto",
-                "init-key",
-                "--output-file",
-                "help",
-            ],
-        ] {
-            assert!(
-                plain_help_misuse(&os_args(args)).is_none(),
-                "{args:?} should treat help as an option value"
-            );
+    fn cli_parses_top_options() {
+        match Cli::try_parse_from([
+            "ov",
+            "top",
+            "--interval",
+            "5",
+            "--uri",
+            "viking://resources",
+        ])
+        .map(|cli| cli.command)
+        {
+            Ok(Commands::Top { uri, interval }) => {
+                assert_eq!(uri.as_deref(), Some("viking://resources"));
+                assert_eq!(interval, 5.0);
+            }
+            _ => panic!("expected top"),
         }
     }
 
     #[test]
-    fn cli_context_overrides_identity_from_cli_flags() {
-        let config = Config {
-            url: DEFAULT_CUSTOM_URL.to_string(),
-            api_key: Some("test-key".to_string()),
-            root_api_key: None,
-            account: Some("from-config-account".to_string()),
-            user: Some("from-config-user".to_string()),
-            actor_peer_id: Some("from-config-peer".to_string()),
-            agent_id: None,
-            timeout: 60.0,
-            output: "table".to_string(),
-            echo_command: true,
-            show_progress: false,
-            verbose: false,
-            upload: Default::default(),
-            extra_headers: None,
-            profile: false,
-            gateway_token: None,
-        };
+    fn cli_parses_stats_options() {
+        match Cli::try_parse_from(["ov", "stats", "viking://resources/docs", "--by-prefix"])
+            .map(|cli| cli.command)
+        {
+            Ok(Commands::Stats(args)) => {
+                assert_eq!(args.uri, "viking://resources/docs");
+                assert!(args.by_prefix);
+                assert_eq!(args.days, 30);
+            }
+            _ => panic!("expected stats"),
+        }
+    }
 
-        let ctx = CliContext::from_config(
-            config,
-            OutputFormat::Json,
-            true,
-            Some("from-cli-account".to_string()),
-            Some("from-cli-user".to_string()),
-            Some("from-cli-peer".to_string()),
-            false,
-            None,
-            None,
-            None,
+    #[test]
+    fn cli_parses_schedule_add_with_its_command() {
+        match Cli::try_parse_from([
+            "ov",
+            "schedule",
+            "add",
+            "nightly",
+            "--cron",
+            "0 2 * * *",
+            "--",
+            "backup",
+            "create",
+            "/backups/nightly.ovpack",
+        ])
+        .map(|cli| cli.command)
+        {
+            Ok(Commands::Schedule {
+                action:
+                    ScheduleCommands::Add {
+                        name,
+                        cron,
+                        command,
+                    },
+            }) => {
+                assert_eq!(name, "nightly");
+                assert_eq!(cron, "0 2 * * *");
+                assert_eq!(command, vec!["backup", "create", "/backups/nightly.ovpack"]);
+                assert!(validate_scheduled_command(&command).is_ok());
+            }
+            _ => panic!("expected schedule add"),
+        }
+        assert!(
+            Cli::try_parse_from(["ov", "schedule", "add", "nightly", "--cron", "@daily"]).is_err()
         );
-
-        assert_eq!(ctx.config.account.as_deref(), Some("from-cli-account"));
-        assert_eq!(ctx.config.user.as_deref(), Some("from-cli-user"));
-        assert_eq!(ctx.config.actor_peer_id.as_deref(), Some("from-cli-peer"));
-        assert!(ctx.config.agent_id.is_none());
+        let command = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert!(validate_scheduled_command(&command(&["-o", "json", "stats"])).is_ok());
+        assert!(validate_scheduled_command(&command(&["daemon", "stop"])).is_err());
+        assert!(validate_scheduled_command(&command(&["snyc"])).is_err());
     }
 
     #[test]
-    fn cli_context_maps_agent_id_to_actor_peer_scope() {
-        let config = Config {
-            url: DEFAULT_CUSTOM_URL.to_string(),
-            api_key: Some("test-key".to_string()),
-            root_api_key: None,
-            account: None,
-            user: None,
-            actor_peer_id: None,
-            agent_id: Some("legacy-agent".to_string()),
-            timeout: 60.0,
-            output: "table".to_string(),
-            echo_command: true,
-            show_progress: false,
-            verbose: false,
-            upload: Default::default(),
-            extra_headers: None,
-            profile: false,
-            gateway_token: None,
+    fn cli_parses_redact_on_commands_that_ingest() {
+        let options = |args: &[&str]| {
+            let cli = Cli::try_parse_from(args).expect("parses");
+            cli.command.redact_options().cloned()
         };
-
-        let ctx = CliContext::from_config(
-            config,
-            OutputFormat::Json,
-            true,
-            None,
-            None,
-            None,
-            false,
-            None,
-            None,
-            None,
+        let add = options(&["ov", "add", "url", "https://example.com", "--redact"]).unwrap();
+        assert!(add.redact);
+        let mbox = options(&[
+            "ov",
+            "import",
+            "mbox",
+            "support.mbox",
+            "--redact-pattern",
+            "ticket=TCK-\\d+",
+        ])
+        .unwrap();
+        assert_eq!(mbox.redact_patterns, vec!["ticket=TCK-\\d+"]);
+        assert!(
+            options(&["ov", "session", "import", "chat.jsonl", "--redact"])
+                .unwrap()
+                .redact
         );
-        let client = ctx.get_client();
+        assert!(!options(&["ov", "add-resource", "./docs"]).unwrap().redact);
+        assert!(options(&["ov", "ls"]).is_none());
+        assert!(Cli::try_parse_from(["ov", "write", "viking://a.md", "--redact"]).is_err());
+
+        let mut config = Config::default();
+        config
+            .redact_patterns
+            .insert("ticket".to_string(), "(".to_string());
+        let error = add.redactor(&config).err().unwrap().to_string();
+        assert!(error.contains("redaction pattern 'ticket'"));
+        assert!(
+            RedactCliOptions::default()
+                .redactor(&config)
+                .unwrap()
+                .is_none()
+        );
+    }
 
-        assert_eq!(client.actor_peer_id(), Some("legacy-agent"));
+    #[test]
+    fn plugins_never_shadow_built_in_commands() {
+        assert!(plugin_request(&os_args(&["ov", "-o", "json", "ls", "viking://"])).is_none());
+        assert!(plugin_request(&os_args(&["ov", "skill", "list"])).is_none());
+        assert!(plugin_request(&os_args(&["ov", "help"])).is_none());
+        assert!(plugin_request(&os_args(&["ov", "--version"])).is_none());
+        assert!(plugin_request(&os_args(&["ov", "no-such-plugin-installed"])).is_none());
     }
 
     #[test]
-    fn cli_context_uses_root_api_key_with_sudo() {
-        let config = Config {
-            url: DEFAULT_CUSTOM_URL.to_string(),
-            api_key: Some("user-key".to_string()),
-            root_api_key: Some("root-key".to_string()),
-            account: None,
-            user: None,
-            actor_peer_id: Some("peer-a".to_string()),
-            agent_id: None,
-            timeout: 60.0,
-            output: "table".to_string(),
-            echo_command: true,
-            show_progress: false,
-            verbose: false,
-            profile: false,
-            upload: Default::default(),
-            extra_headers: None,
-            gateway_token: None,
-        };
+    fn cli_parses_run_and_script_lines() {
+        match Cli::try_parse_from(["ov", "run", "seed.ov", "--var", "env=prod", "--dry-run"])
+            .map(|cli| cli.command)
+        {
+            Ok(Commands::Run(RunArgs {
+                script,
+                vars,
+                dry_run,
+            })) => {
+                assert_eq!(script, "seed.ov");
+                assert_eq!(vars, vec!["env=prod"]);
+                assert!(dry_run);
+            }
+            _ => panic!("expected run"),
+        }
 
-        // Without sudo: use api_key
-        let ctx = CliContext::from_config(
-            config.clone(),
-            OutputFormat::Json,
-            true,
-            None,
-            None,
-            None,
-            false,
-            None,
-            None,
-            None,
+        let step = |args: &[&str]| commands::script::Step {
+            line: 3,
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            on_error: commands::script::OnError::Abort,
+        };
+        let parsed = parse_script_command("seed.ov", step(&["-o", "json", "ls", "viking://"]))
+            .expect("ls parses");
+        assert!(matches!(parsed.command, Commands::Ls { .. }));
+        assert!(matches!(parsed.output_format, Some(OutputFormat::Json)));
+        assert_eq!(parsed.compact, None);
+        assert!(
+            parse_script_command("seed.ov", step(&["ls"]))
+                .unwrap()
+                .output_format
+                .is_none()
         );
-        let client = ctx.get_client();
-        assert_eq!(client.api_key(), Some("user-key"));
-        assert_eq!(client.actor_peer_id(), Some("peer-a"));
+        let error = |args: &[&str]| {
+            parse_script_command("seed.ov", step(args))
+                .err()
+                .expect("line is refused")
+                .to_string()
+        };
+        assert!(error(&["ls", "--sudo"]).contains("seed.ov:3: --sudo applies to the whole script"));
+        assert!(error(&["run", "other.ov"]).contains("cannot run inside a script"));
+        assert!(error(&["lss"]).contains("seed.ov:3: "));
+    }
 
-        // With sudo: use root_api_key
-        let ctx = CliContext::from_config(
-            config,
-            OutputFormat::Json,
-            true,
-            None,
-            None,
-            None,
-            true,
-            None,
-            None,
-            None,
-        );
-        let client = ctx.get_client();
-        assert_eq!(client.api_key(), Some("root-key"));
+    #[test]
+    fn cli_parses_jobs_commands() {
+        match Cli::try_parse_from(["ov", "jobs", "ls", "--status", "failed", "-n", "10"])
+            .map(|cli| cli.command)
+        {
+            Ok(Commands::Jobs {
+                action:
+                    JobsCommands::Ls {
+                        job_type,
+                        status,
+                        limit,
+                    },
+            }) => {
+                assert_eq!(job_type, None);
+                assert_eq!(status.as_deref(), Some("failed"));
+                assert_eq!(limit, 10);
+            }
+            _ => panic!("expected jobs ls"),
+        }
+        match Cli::try_parse_from(["ov", "jobs", "wait", "t1", "t2", "--timeout", "600"])
+            .map(|cli| cli.command)
+        {
+            Ok(Commands::Jobs {
+                action:
+                    JobsCommands::Wait {
+                        ids,
+                        timeout,
+                        interval,
+                    },
+            }) => {
+                assert_eq!(ids, vec!["t1", "t2"]);
+                assert_eq!(timeout, Some(600.0));
+                assert_eq!(interval, 2.0);
+            }
+            _ => panic!("expected jobs wait"),
+        }
+        assert!(Cli::try_parse_from(["ov", "jobs", "wait"]).is_err());
+        assert!(Cli::try_parse_from(["ov", "jobs", "ls", "--status", "done"]).is_err());
     }
 
     #[test]
-    fn cli_write_rejects_removed_semantic_flags() {
-        let result = Cli::try_parse_from([
+    fn cli_parses_events_subscribe() {
+        match Cli::try_parse_from([
             "ov",
-            "write",
-            "viking://resources/demo.md",
-            "--content",
-            "updated",
-            "--no-semantics",
-            "--no-vectorize",
-        ]);
-
-        assert!(result.is_err(), "removed write flags should not parse");
+            "events",
+            "subscribe",
+            "--filter",
+            "resource.created,index.*",
+            "--exec",
+            "./notify.sh",
+        ])
+        .map(|cli| cli.command)
+        {
+            Ok(Commands::Events {
+                action:
+                    EventsCommands::Subscribe {
+                        filter,
+                        uri,
+                        exec,
+                        interval,
+                    },
+            }) => {
+                assert_eq!(filter, vec!["resource.created", "index.*"]);
+                assert_eq!(uri, None);
+                assert_eq!(exec.as_deref(), Some("./notify.sh"));
+                assert_eq!(interval, 2.0);
+            }
+            _ => panic!("expected events subscribe"),
+        }
     }
 
     #[test]
-    fn cli_import_rejects_removed_vectorize_flag() {
-        let result = Cli::try_parse_from([
-            "ov",
-            "import",
-            "./exports/demo.ovpack",
-            "viking://resources/imported/",
-            "--no-vectorize",
-        ]);
+    fn cli_parses_audit_tail_filters() {
+        match Cli::try_parse_from([
+            "ov", "audit", "tail", "--user", "bob", "--action", "delete", "--since", "1h", "-f",
+        ])
+        .map(|cli| cli.command)
+        {
+            Ok(Commands::Audit {
+                action:
+                    AuditCommands::Tail {
+                        user,
+                        action,
+                        since,
+                        lines,
+                        follow,
+                        ..
+                    },
+            }) => {
+                assert_eq!(user.as_deref(), Some("bob"));
+                assert_eq!(action.as_deref(), Some("delete"));
+                assert_eq!(since.as_deref(), Some("1h"));
+                assert_eq!(lines, 20);
+                assert!(follow);
+            }
+            _ => panic!("expected audit tail"),
+        }
+        assert!(Cli::try_parse_from(["ov", "audit", "tail", "--action", "rename"]).is_err());
+    }
 
+    #[test]
+    fn cli_parses_admin_user_commands() {
+        match Cli::try_parse_from(["ov", "admin", "user", "add", "alice", "--role", "admin"])
+            .map(|cli| cli.command)
+        {
+            Ok(Commands::Admin {
+                action:
+                    AdminCommands::User {
+                        action: AdminUserCommands::Add { user_id, role, .. },
+                    },
+            }) => {
+                assert_eq!(user_id, "alice");
+                assert_eq!(role, "admin");
+            }
+            _ => panic!("expected admin user add"),
+        }
         assert!(
-            result.is_err(),
-            "removed import vectorize flag should not parse"
+            Cli::try_parse_from(["ov", "admin", "grant", "alice", "viking://", "read"]).is_err()
         );
     }
 
     #[test]
-    fn cli_import_rejects_removed_force_flag() {
-        let result = Cli::try_parse_from([
+    fn cli_parses_saved_search_commands() {
+        let mut cli = Cli::try_parse_from([
             "ov",
-            "import",
-            "./exports/demo.ovpack",
-            "viking://resources/imported/",
-            "--force",
-        ]);
+            "search",
+            "save",
+            "todo",
+            "--query",
+            "unresolved action items",
+            "--filters",
+            "--after {{since}} -n 20",
+        ])
+        .expect("search save should parse");
+        adopt_query_argument(&mut cli);
+        assert_eq!(cli.query, None);
+        match cli.command {
+            Commands::Search(args) => {
+                let Some(SearchCommands::Save {
+                    name,
+                    query,
+                    filters,
+                }) = args.action
+                else {
+                    panic!("expected search save");
+                };
+                assert_eq!(name, "todo");
+                assert_eq!(query.as_deref(), Some("unresolved action items"));
+                assert_eq!(filters.as_deref(), Some("--after {{since}} -n 20"));
+            }
+            _ => panic!("expected search save"),
+        }
 
-        assert!(
-            result.is_err(),
-            "removed import force flag should not parse"
-        );
+        match Cli::try_parse_from(["ov", "search", "run", "todo", "since=7d"])
+            .map(|cli| cli.command)
+        {
+            Ok(Commands::Search(args)) => {
+                let Some(SearchCommands::Run { name, values }) = args.action else {
+                    panic!("expected search run");
+                };
+                assert_eq!(name, "todo");
+                assert_eq!(values, vec!["since=7d"]);
+            }
+            _ => panic!("expected search run"),
+        }
+        assert!(matches!(
+            Cli::try_parse_from(["ov", "search", "action items"]).map(|cli| cli.command),
+            Ok(Commands::Search(args)) if args.action.is_none() && args.query.is_some()
+        ));
+        assert!(Cli::try_parse_from(["ov", "search", "run", "todo", "-n", "5"]).is_err());
+    }
+
+    #[test]
+    fn cli_parses_index_commands() {
+        match Cli::try_parse_from(["ov", "index", "verify"]).map(|cli| cli.command) {
+            Ok(Commands::Index(IndexArgs {
+                action: IndexCommands::Verify(args),
+            })) => assert_eq!(args.prefix, "viking://"),
+            _ => panic!("expected index verify"),
+        }
+        let cli = Cli::try_parse_from([
+            "ov",
+            "--sudo",
+            "index",
+            "repair",
+            "--prefix",
+            "viking://user/memories",
+            "--prune-orphans",
+        ])
+        .expect("index repair should parse");
+        assert!(cli.command.supports_sudo());
+        match cli.command {
+            Commands::Index(IndexArgs {
+                action: IndexCommands::Repair(args),
+            }) => {
+                assert_eq!(args.target.prefix, "viking://user/memories");
+                assert!(args.prune_orphans);
+                assert!(!args.wait && !args.dry_run);
+            }
+            _ => panic!("expected index repair"),
+        }
+        match Cli::try_parse_from([
+            "ov",
+            "index",
+            "rebuild",
+            "--prefix",
+            "viking://memories",
+            "--changed-since",
+            "7d",
+            "-j",
+            "8",
+        ])
+        .map(|cli| cli.command)
+        {
+            Ok(Commands::Index(IndexArgs {
+                action: IndexCommands::Rebuild(args),
+            })) => {
+                assert_eq!(args.target.prefix, "viking://memories");
+                assert_eq!(args.changed_since.as_deref(), Some("7d"));
+                assert_eq!(args.concurrency, 8);
+                assert!(!args.dry_run);
+            }
+            _ => panic!("expected index rebuild"),
+        }
+    }
+
+    #[test]
+    fn cli_parses_queue_offline_and_queue_commands() {
+        let cli = Cli::try_parse_from(["ov", "rm", "viking://resources/a.md", "--queue-offline"])
+            .expect("--queue-offline should parse after the command");
+        assert!(cli.offline.queue_offline);
+        assert!(cli.command.can_queue_offline());
+        let cli = Cli::try_parse_from(["ov", "--queue-offline", "ls", "viking://"])
+            .expect("--queue-offline should parse before the command");
+        assert!(!cli.command.can_queue_offline());
+        assert!(matches!(
+            Cli::try_parse_from(["ov", "queue", "drop", "a1b2c3d4"]).map(|cli| cli.command),
+            Ok(Commands::Queue {
+                action: QueueCommands::Drop { id }
+            }) if id == "a1b2c3d4"
+        ));
+        match Cli::try_parse_from([
+            "ov",
+            "queue",
+            "wait",
+            "viking://resources/docs",
+            "--timeout",
+            "30",
+        ])
+        .map(|cli| cli.command)
+        {
+            Ok(Commands::Queue {
+                action: QueueCommands::Wait(args),
+            }) => {
+                assert_eq!(args.uri, "viking://resources/docs");
+                assert_eq!(args.timeout, Some(30.0));
+                assert_eq!(args.interval, 1.0);
+            }
+            _ => panic!("expected queue wait"),
+        }
     }
 
     #[test]
@@ -4609,6 +10316,70 @@ mod tests {
         assert!(result.is_ok(), "reindex command should parse");
     }
 
+    #[test]
+    fn cli_parses_api_command_with_fields() {
+        let cli = Cli::try_parse_from([
+            "ov",
+            "api",
+            "get",
+            "fs/ls",
+            "-F",
+            "uri=viking://resources",
+            "--field",
+            "recursive=true",
+        ])
+        .expect("api command should parse");
+
+        match cli.command {
+            Commands::Api(args) => {
+                let ApiArgs {
+                    method,
+                    path,
+                    fields,
+                    raw_body,
+                } = *args;
+                assert_eq!(method.to_ascii_uppercase(), "GET");
+                assert_eq!(path, "fs/ls");
+                assert_eq!(fields, vec!["uri=viking://resources", "recursive=true"]);
+                assert_eq!(raw_body, None);
+            }
+            _ => panic!("expected api command"),
+        }
+        assert!(
+            Cli::try_parse_from(["ov", "api", "TRACE", "/health"]).is_err(),
+            "unsupported methods should not parse"
+        );
+    }
+
+    #[test]
+    fn cli_parses_bench_command() {
+        let cli = Cli::try_parse_from([
+            "ov",
+            "bench",
+            "find",
+            "auth flow",
+            "-u",
+            "viking://resources",
+            "-j",
+            "8",
+            "-n",
+            "200",
+        ])
+        .expect("bench command should parse");
+
+        match cli.command {
+            Commands::Bench(args) => {
+                assert_eq!(args.op, "find");
+                assert_eq!(args.target.as_deref(), Some("auth flow"));
+                assert_eq!(args.uri, "viking://resources");
+                assert_eq!(args.concurrency, 8);
+                assert_eq!(args.duration, None);
+                assert_eq!(args.requests, Some(200));
+            }
+            _ => panic!("expected bench command"),
+        }
+    }
+
     #[test]
     fn cli_rejects_unknown_reindex_mode() {
         let result = Cli::try_parse_from([
@@ -4628,9 +10399,13 @@ mod tests {
         let after = Some("7d".to_string());
         let before = Some("2026-03-12".to_string());
 
-        handlers::append_time_filter_params(&mut params, after.as_deref(), before.as_deref());
+        handlers::append_time_filter_params(&mut params, after.as_deref(), before.as_deref(), None);
 
         assert_eq!(params, vec!["--after 7d", "--before 2026-03-12"]);
+
+        let mut params = Vec::new();
+        handlers::append_time_filter_params(&mut params, Some("7d"), None, Some("created_at"));
+        assert_eq!(params, vec!["--created-after 7d"]);
     }
 
     #[test]
@@ -4756,4 +10531,62 @@ mod tests {
         .expect("-c false should keep its explicit bool value");
         assert!(!explicit_false.compact);
     }
+
+    #[test]
+    fn global_query_flag_parses_before_and_after_subcommand() {
+        let before = Cli::try_parse_from(["ov", "--query", ".[].session_id", "session", "list"])
+            .expect("--query should parse before the subcommand");
+        assert_eq!(before.query.as_deref(), Some(".[].session_id"));
+
+        let after = Cli::try_parse_from(["ov", "ls", "viking://resources", "--query=.[].uri"])
+            .expect("--query should parse after the subcommand");
+        assert_eq!(after.query.as_deref(), Some(".[].uri"));
+
+        let find = Cli::try_parse_from(["ov", "find", "auth flow", "--query", ".resources"])
+            .expect("--query should not clash with the find query positional");
+        assert_eq!(find.query.as_deref(), Some(".resources"));
+        match find.command {
+            Commands::Find(args) => assert_eq!(args.query.as_deref(), Some("auth flow")),
+            _ => panic!("expected find"),
+        }
+    }
+
+    #[test]
+    fn global_table_layout_flags_parse_lists_and_descending_sort() {
+        let cli = Cli::try_parse_from([
+            "ov",
+            "find",
+            "auth",
+            "--columns",
+            "uri,score",
+            "--sort",
+            "-score",
+        ])
+        .expect("--columns/--sort should parse");
+        assert_eq!(cli.columns, vec!["uri", "score"]);
+        assert_eq!(cli.sort.as_deref(), Some("-score"));
+    }
+
+    #[test]
+    fn global_template_flag_installs_alongside_query() {
+        let cli = Cli::try_parse_from([
+            "ov",
+            "find",
+            "auth",
+            "--query",
+            ".resources",
+            "--template",
+            "{{.uri}}\\t{{.score}}",
+        ])
+        .expect("--template should parse with --query");
+        assert_eq!(cli.template.as_deref(), Some("{{.uri}}\\t{{.score}}"));
+
+        assert!(
+            install_script_output(None, Some("{{.uri"))
+                .is_err_and(|(flag, _, _)| flag == "--template")
+        );
+        assert!(
+            install_script_output(Some(".["), None).is_err_and(|(flag, _, _)| flag == "--query")
+        );
+    }
 }
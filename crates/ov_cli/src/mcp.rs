@@ -0,0 +1,1172 @@
+//! `ov mcp serve`: expose an OpenViking server to MCP hosts over the Model
+//! Context Protocol, and the client `ov import mcp` uses to read from other
+//! MCP servers.
+//!
+//! The protocol is JSON-RPC 2.0 over one of two transports. With stdio, the
+//! host launches `ov` itself (Claude Desktop, Cursor) and exchanges one message
+//! per line. With SSE, the host opens `GET /sse`, is told the
+//! `/messages?session_id=...` endpoint to POST requests to, and receives the
+//! replies on the event stream. Tools call the server with the CLI's own config
+//! and credentials, so a host can see and change exactly what `ov` can.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bytes::Bytes;
+use reqwest::header::{ACCEPT, CONTENT_TYPE};
+use serde_json::{Map, Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::mpsc;
+use url::Url;
+
+use crate::client::{HttpClient, SearchScoring};
+use crate::commands::session::url_encode;
+use crate::error::{Error, Result};
+
+/// Protocol revisions this server speaks, newest first. A host asking for any
+/// of them gets it back; any other request is answered with the newest.
+const PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+const SSE_PATH: &str = "/sse";
+const MESSAGES_PATH: &str = "/messages";
+
+/// Results returned by `find` and `search` when the host does not say.
+const DEFAULT_LIMIT: i64 = 10;
+
+const INSTRUCTIONS: &str = "OpenViking is a context database addressed by viking:// URIs. \
+Use find for a quick semantic lookup and search when a session's history should shape \
+the results. Read a URI's abstract before reading it in full. Sessions collect a \
+conversation; committing one extracts long-term memories from it.";
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+fn error_reply(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+fn tool(name: &str, description: &str, properties: Value, required: &[&str]) -> Value {
+    json!({
+        "name": name,
+        "description": description,
+        "inputSchema": {
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        },
+    })
+}
+
+/// The tools offered to hosts, as `tools/list` reports them.
+fn tool_definitions() -> Vec<Value> {
+    let query = json!({ "type": "string", "description": "What to look for, in natural language" });
+    let scope = json!({ "type": "string", "description": "Only search under this viking:// URI" });
+    let limit = json!({ "type": "integer", "description": "Most results to return", "default": DEFAULT_LIMIT });
+    let uri = json!({ "type": "string", "description": "viking:// URI" });
+    let session_id = json!({ "type": "string", "description": "Session id from session_create" });
+    vec![
+        tool(
+            "find",
+            "Semantic search over resources, memories and skills.",
+            json!({ "query": query, "uri": scope, "limit": limit }),
+            &["query"],
+        ),
+        tool(
+            "search",
+            "Context-aware search that can use a session's conversation to plan the query.",
+            json!({ "query": query, "uri": scope, "session_id": session_id, "limit": limit }),
+            &["query"],
+        ),
+        tool(
+            "read",
+            "Read the full content of a file.",
+            json!({ "uri": uri }),
+            &["uri"],
+        ),
+        tool(
+            "abstract",
+            "Read the short abstract of a file or directory.",
+            json!({ "uri": uri }),
+            &["uri"],
+        ),
+        tool(
+            "add_resource",
+            "Add a local file or directory, or a URL, as a resource.",
+            json!({
+                "path": { "type": "string", "description": "Local path or URL to import" },
+                "to": { "type": "string", "description": "Exact viking:// URI to import to" },
+                "parent": { "type": "string", "description": "viking:// directory to import into" },
+                "reason": { "type": "string", "description": "Why the resource is being added" },
+                "wait": { "type": "boolean", "description": "Wait until the resource is processed", "default": false },
+            }),
+            &["path"],
+        ),
+        tool(
+            "session_create",
+            "Start a session to record a conversation in.",
+            json!({}),
+            &[],
+        ),
+        tool(
+            "session_add_message",
+            "Append a message to a session.",
+            json!({
+                "session_id": session_id,
+                "role": { "type": "string", "enum": ["user", "assistant"] },
+                "content": { "type": "string", "description": "Message text" },
+            }),
+            &["session_id", "role", "content"],
+        ),
+        tool(
+            "session_commit",
+            "Commit a session, archiving its messages and extracting memories from them.",
+            json!({ "session_id": session_id }),
+            &["session_id"],
+        ),
+    ]
+}
+
+fn required_str(args: &Map<String, Value>, key: &str) -> Result<String> {
+    args.get(key)
+        .and_then(Value::as_str)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| Error::Client(format!("'{key}' is required and must be a string")))
+}
+
+fn optional_str(args: &Map<String, Value>, key: &str) -> Option<String> {
+    args.get(key)
+        .and_then(Value::as_str)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// A `tools/call` result: text content, flagged as an error when the call
+/// failed so the host can show the reason to the model.
+fn tool_result(output: Result<Value>) -> Value {
+    let (text, is_error) = match output {
+        Ok(Value::String(text)) => (text, false),
+        Ok(value) => (
+            serde_json::to_string_pretty(&value).unwrap_or_default(),
+            false,
+        ),
+        Err(e) => (e.to_string(), true),
+    };
+    json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    })
+}
+
+pub struct McpServer {
+    client: HttpClient,
+}
+
+impl McpServer {
+    pub fn new(client: HttpClient) -> Self {
+        Self { client }
+    }
+
+    /// The reply to one JSON-RPC message, or `None` when it needs none:
+    /// notifications, and responses to requests this server never sends.
+    async fn handle(&self, message: Value) -> Option<Value> {
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            if message.get("result").is_some() || message.get("error").is_some() {
+                return None;
+            }
+            return Some(error_reply(
+                id.unwrap_or(Value::Null),
+                INVALID_REQUEST,
+                "Invalid request: missing method",
+            ));
+        };
+        let params = message.get("params").cloned().unwrap_or_else(|| json!({}));
+        let result = match method {
+            "initialize" => Ok(initialize(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => self.call(&params).await,
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Method not found: {method}"),
+            )),
+        };
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => error_reply(id, e.code, &e.message),
+        })
+    }
+
+    /// The reply to one line or request body, which may hold a single message
+    /// or a batch.
+    pub async fn handle_text(&self, text: &str) -> Option<Value> {
+        match serde_json::from_str::<Value>(text) {
+            Err(e) => Some(error_reply(
+                Value::Null,
+                PARSE_ERROR,
+                &format!("Parse error: {e}"),
+            )),
+            Ok(Value::Array(messages)) => {
+                let replies: Vec<Value> =
+                    futures::future::join_all(messages.into_iter().map(|m| self.handle(m)))
+                        .await
+                        .into_iter()
+                        .flatten()
+                        .collect();
+                (!replies.is_empty()).then_some(Value::Array(replies))
+            }
+            Ok(message) => self.handle(message).await,
+        }
+    }
+
+    async fn call(&self, params: &Value) -> std::result::Result<Value, RpcError> {
+        let Some(name) = params.get("name").and_then(Value::as_str) else {
+            return Err(RpcError::new(
+                INVALID_PARAMS,
+                "tools/call needs a tool name",
+            ));
+        };
+        if !tool_definitions()
+            .iter()
+            .any(|tool| tool["name"].as_str() == Some(name))
+        {
+            return Err(RpcError::new(
+                INVALID_PARAMS,
+                format!("Unknown tool: {name}"),
+            ));
+        }
+        let args = params
+            .get("arguments")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+        Ok(tool_result(self.run_tool(name, &args).await))
+    }
+
+    async fn run_tool(&self, name: &str, args: &Map<String, Value>) -> Result<Value> {
+        let client = &self.client;
+        let limit = args
+            .get("limit")
+            .and_then(Value::as_i64)
+            .unwrap_or(DEFAULT_LIMIT)
+            .clamp(1, 100) as i32;
+        let scope = optional_str(args, "uri").unwrap_or_default();
+        let scoring = SearchScoring::default();
+        match name {
+            "find" => {
+                client
+                    .find(
+                        required_str(args, "query")?,
+                        scope,
+                        None,
+                        limit,
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        &scoring,
+                    )
+                    .await
+            }
+            "search" => {
+                client
+                    .search(
+                        required_str(args, "query")?,
+                        scope,
+                        None,
+                        optional_str(args, "session_id"),
+                        limit,
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        &scoring,
+                    )
+                    .await
+            }
+            "read" => client
+                .read(&required_str(args, "uri")?)
+                .await
+                .map(Value::String),
+            "abstract" => client
+                .abstract_content(&required_str(args, "uri")?)
+                .await
+                .map(Value::String),
+            "add_resource" => {
+                client
+                    .add_resource(
+                        &required_str(args, "path")?,
+                        optional_str(args, "to"),
+                        optional_str(args, "parent"),
+                        None,
+                        &optional_str(args, "reason").unwrap_or_default(),
+                        "",
+                        args.get("wait").and_then(Value::as_bool).unwrap_or(false),
+                        None,
+                        false,
+                        None,
+                        None,
+                        None,
+                        true,
+                        0.0,
+                        None,
+                        false,
+                        false,
+                    )
+                    .await
+            }
+            "session_create" => client.post("/api/v1/sessions", &json!({})).await,
+            "session_add_message" => {
+                let path = format!(
+                    "/api/v1/sessions/{}/messages",
+                    url_encode(&required_str(args, "session_id")?)
+                );
+                let body = json!({
+                    "role": required_str(args, "role")?,
+                    "content": required_str(args, "content")?,
+                });
+                client.post(&path, &body).await
+            }
+            "session_commit" => {
+                let path = format!(
+                    "/api/v1/sessions/{}/commit",
+                    url_encode(&required_str(args, "session_id")?)
+                );
+                client.post(&path, &json!({})).await
+            }
+            _ => Err(Error::Client(format!("Unknown tool: {name}"))),
+        }
+    }
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = PROTOCOL_VERSIONS
+        .into_iter()
+        .find(|version| Some(*version) == requested)
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": { "listChanged": false } },
+        "serverInfo": { "name": "openviking", "version": env!("OPENVIKING_CLI_VERSION") },
+        "instructions": INSTRUCTIONS,
+    })
+}
+
+/// Serve MCP on stdin and stdout until stdin closes. Requests are handled
+/// concurrently, so a slow `add_resource` does not hold up a `find`; stdout
+/// carries nothing but protocol messages.
+pub async fn serve_stdio(server: McpServer) -> Result<()> {
+    let server = Arc::new(server);
+    let (tx, mut rx) = mpsc::unbounded_channel::<Value>();
+    let writer = tokio::spawn(async move {
+        let mut stdout = tokio::io::stdout();
+        while let Some(reply) = rx.recv().await {
+            let mut line = reply.to_string();
+            line.push('\n');
+            stdout.write_all(line.as_bytes()).await?;
+            stdout.flush().await?;
+        }
+        std::io::Result::Ok(())
+    });
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let server = server.clone();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            if let Some(reply) = server.handle_text(&line).await {
+                let _ = tx.send(reply);
+            }
+        });
+    }
+    drop(tx);
+    writer
+        .await
+        .map_err(|e| Error::Client(format!("MCP stdout writer failed: {e}")))??;
+    Ok(())
+}
+
+/// One server-sent event.
+fn sse_event(event: &str, data: &str) -> Bytes {
+    Bytes::from(format!("event: {event}\ndata: {data}\n\n"))
+}
+
+/// Browsers send `Origin`; MCP hosts do not. A page on another site must not
+/// be able to drive a server bound to this machine, so only local origins are
+/// let through.
+fn is_allowed_origin(origin: Option<&str>) -> bool {
+    let Some(origin) = origin else {
+        return true;
+    };
+    url::Url::parse(origin)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .is_some_and(|host| matches!(host.as_str(), "localhost" | "127.0.0.1" | "[::1]"))
+}
+
+/// The `session_id` query parameter of a `/messages` request.
+fn session_id_of(query: Option<&str>) -> Option<String> {
+    url::form_urlencoded::parse(query?.as_bytes())
+        .find(|(key, _)| key == "session_id")
+        .map(|(_, value)| value.into_owned())
+}
+
+/// Serve MCP over HTTP with server-sent events on `addr` until Ctrl-C.
+pub async fn serve_sse(server: McpServer, addr: SocketAddr) -> Result<()> {
+    use http_body_util::combinators::UnsyncBoxBody;
+    use http_body_util::{BodyExt, Full, StreamBody};
+    use hyper::body::{Frame, Incoming};
+    use hyper::header::{CACHE_CONTROL, CONTENT_TYPE, HeaderValue, ORIGIN};
+    use hyper::{Method, Request, Response, StatusCode};
+    use hyper_util::rt::TokioIo;
+
+    type SseBody = UnsyncBoxBody<Bytes, std::io::Error>;
+    type Sessions = Mutex<HashMap<String, mpsc::UnboundedSender<Value>>>;
+
+    /// Forgets a session once its event stream is dropped.
+    struct SessionGuard {
+        id: String,
+        sessions: Arc<Sessions>,
+    }
+
+    impl Drop for SessionGuard {
+        fn drop(&mut self) {
+            self.sessions.lock().unwrap().remove(&self.id);
+        }
+    }
+
+    fn text_response(status: StatusCode, text: &str) -> Response<SseBody> {
+        let body = Full::new(Bytes::from(text.to_string())).map_err(|never| match never {});
+        let mut response = Response::new(UnsyncBoxBody::new(body));
+        *response.status_mut() = status;
+        response
+    }
+
+    async fn handle(
+        server: Arc<McpServer>,
+        sessions: Arc<Sessions>,
+        request: Request<Incoming>,
+    ) -> std::result::Result<Response<SseBody>, std::convert::Infallible> {
+        let origin = request
+            .headers()
+            .get(ORIGIN)
+            .and_then(|origin| origin.to_str().ok());
+        if !is_allowed_origin(origin) {
+            return Ok(text_response(StatusCode::FORBIDDEN, "Origin not allowed"));
+        }
+        match (request.method(), request.uri().path()) {
+            (&Method::GET, SSE_PATH) => {
+                let session_id = uuid::Uuid::new_v4().simple().to_string();
+                let (tx, rx) = mpsc::unbounded_channel::<Value>();
+                sessions.lock().unwrap().insert(session_id.clone(), tx);
+                let endpoint = format!("{MESSAGES_PATH}?session_id={session_id}");
+                let first = futures::stream::once(async move { sse_event("endpoint", &endpoint) });
+                let guard = SessionGuard {
+                    id: session_id,
+                    sessions,
+                };
+                let replies = futures::stream::unfold((rx, guard), |(mut rx, guard)| async move {
+                    let reply = rx.recv().await?;
+                    Some((sse_event("message", &reply.to_string()), (rx, guard)))
+                });
+                let frames =
+                    futures::StreamExt::map(futures::StreamExt::chain(first, replies), |bytes| {
+                        Ok::<_, std::io::Error>(Frame::data(bytes))
+                    });
+                let mut response = Response::new(UnsyncBoxBody::new(StreamBody::new(frames)));
+                let headers = response.headers_mut();
+                headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/event-stream"));
+                headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+                Ok(response)
+            }
+            (&Method::POST, MESSAGES_PATH) => {
+                let Some(session_id) = session_id_of(request.uri().query()) else {
+                    return Ok(text_response(StatusCode::BAD_REQUEST, "Missing session_id"));
+                };
+                let Some(tx) = sessions.lock().unwrap().get(&session_id).cloned() else {
+                    return Ok(text_response(StatusCode::NOT_FOUND, "Unknown session"));
+                };
+                let body = match request.into_body().collect().await {
+                    Ok(body) => body.to_bytes(),
+                    Err(e) => {
+                        return Ok(text_response(StatusCode::BAD_REQUEST, &e.to_string()));
+                    }
+                };
+                let text = String::from_utf8_lossy(&body).into_owned();
+                tokio::spawn(async move {
+                    if let Some(reply) = server.handle_text(&text).await {
+                        let _ = tx.send(reply);
+                    }
+                });
+                Ok(text_response(StatusCode::ACCEPTED, "Accepted"))
+            }
+            _ => Ok(text_response(StatusCode::NOT_FOUND, "Not found")),
+        }
+    }
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    eprintln!(
+        "ov mcp serving on http://{}{SSE_PATH}",
+        listener.local_addr()?
+    );
+    let server = Arc::new(server);
+    let sessions: Arc<Sessions> = Arc::new(Mutex::new(HashMap::new()));
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, _) = accepted?;
+                let server = server.clone();
+                let sessions = sessions.clone();
+                tokio::spawn(async move {
+                    let service = hyper::service::service_fn(move |request| {
+                        handle(server.clone(), sessions.clone(), request)
+                    });
+                    if let Err(e) = hyper::server::conn::http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await
+                        && !e.is_user()
+                        && !e.is_incomplete_message()
+                    {
+                        eprintln!("ov mcp: connection error: {e}");
+                    }
+                });
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+    Ok(())
+}
+
+/// Failures talking to another MCP server are client errors: they are not
+/// about reaching OpenViking.
+fn mcp_error(context: &str, error: reqwest::Error) -> Error {
+    Error::Client(format!("{context}: {error}"))
+}
+
+/// Split the next complete event off the front of an SSE buffer, as
+/// `(event, data)`. Events without a name are `message` events; comments and
+/// events without data are skipped.
+fn take_sse_event(buffer: &mut Vec<u8>) -> Option<(String, String)> {
+    while let Some(end) = buffer.windows(2).position(|pair| pair == b"\n\n") {
+        let block: Vec<u8> = buffer.drain(..end + 2).collect();
+        let block = String::from_utf8_lossy(&block);
+        let mut event = "message".to_string();
+        let mut data = Vec::new();
+        for line in block.lines() {
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "event" => event = value.to_string(),
+                "data" => data.push(value),
+                _ => {}
+            }
+        }
+        if !data.is_empty() {
+            return Some((event, data.join("\n")));
+        }
+    }
+    None
+}
+
+/// Server-sent events read from a streaming HTTP response.
+struct SseStream {
+    response: reqwest::Response,
+    buffer: Vec<u8>,
+}
+
+impl SseStream {
+    fn new(response: reqwest::Response) -> Self {
+        Self {
+            response,
+            buffer: Vec::new(),
+        }
+    }
+
+    async fn next(&mut self) -> Result<Option<(String, String)>> {
+        loop {
+            if let Some(event) = take_sse_event(&mut self.buffer) {
+                return Ok(Some(event));
+            }
+            let chunk = self
+                .response
+                .chunk()
+                .await
+                .map_err(|e| mcp_error("MCP event stream failed", e))?;
+            let Some(chunk) = chunk else {
+                return Ok(None);
+            };
+            self.buffer
+                .extend(chunk.iter().copied().filter(|byte| *byte != b'\r'));
+        }
+    }
+}
+
+/// The reply to request `id` in `message`, which may be a batch.
+fn matching_reply(message: Value, id: i64) -> Option<Value> {
+    match message {
+        Value::Array(messages) => messages
+            .into_iter()
+            .find_map(|message| matching_reply(message, id)),
+        message if message.get("method").is_none() && message["id"].as_i64() == Some(id) => {
+            Some(message)
+        }
+        _ => None,
+    }
+}
+
+/// What `ov` answers when a server sends it a request: pings succeed, and
+/// anything else (sampling, roots) is not supported.
+fn answer_server_request(message: &Value) -> Option<Value> {
+    let method = message.get("method").and_then(Value::as_str)?;
+    let id = message.get("id")?.clone();
+    Some(match method {
+        "ping" => json!({ "jsonrpc": "2.0", "id": id, "result": {} }),
+        _ => error_reply(id, METHOD_NOT_FOUND, &format!("Method not found: {method}")),
+    })
+}
+
+/// The text items of a tool result's content, joined.
+pub fn tool_text(result: &Value) -> String {
+    result["content"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|item| item["type"] == "text")
+        .filter_map(|item| item["text"].as_str())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+enum Transport {
+    /// A server `ov` started, spoken to over its stdin and stdout.
+    Stdio {
+        _child: Child,
+        stdin: ChildStdin,
+        stdout: Lines<BufReader<ChildStdout>>,
+    },
+    /// Streamable HTTP: every message is POSTed to one URL, and replies come
+    /// back as JSON or as an event stream.
+    Http {
+        http: reqwest::Client,
+        url: String,
+        session_id: Option<String>,
+        protocol_version: Option<String>,
+    },
+    /// The older HTTP+SSE transport that `ov mcp serve` speaks: replies
+    /// arrive on a long-lived event stream.
+    Sse {
+        http: reqwest::Client,
+        endpoint: String,
+        events: SseStream,
+    },
+}
+
+async fn write_line(stdin: &mut ChildStdin, message: &Value) -> Result<()> {
+    let mut line = message.to_string();
+    line.push('\n');
+    stdin.write_all(line.as_bytes()).await?;
+    stdin.flush().await?;
+    Ok(())
+}
+
+async fn post_message(http: &reqwest::Client, endpoint: &str, message: &Value) -> Result<()> {
+    let response = http
+        .post(endpoint)
+        .json(message)
+        .send()
+        .await
+        .map_err(|e| mcp_error("MCP request failed", e))?;
+    if !response.status().is_success() {
+        return Err(Error::Client(format!(
+            "MCP server answered HTTP {}",
+            response.status()
+        )));
+    }
+    Ok(())
+}
+
+impl Transport {
+    fn spawn(command_line: &str) -> Result<Self> {
+        let mut command = if cfg!(windows) {
+            let mut command = Command::new("cmd");
+            command.arg("/C");
+            command
+        } else {
+            let mut command = Command::new("sh");
+            command.arg("-c");
+            command
+        };
+        let mut child = command
+            .arg(command_line)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| Error::Client(format!("Cannot start MCP server `{command_line}`: {e}")))?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(Error::Client(format!(
+                "Cannot talk to MCP server `{command_line}`"
+            )));
+        };
+        Ok(Self::Stdio {
+            _child: child,
+            stdin,
+            stdout: BufReader::new(stdout).lines(),
+        })
+    }
+
+    async fn open_sse(http: reqwest::Client, url: Url) -> Result<Self> {
+        let response = http
+            .get(url.clone())
+            .header(ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .map_err(|e| mcp_error("MCP event stream failed", e))?;
+        if !response.status().is_success() {
+            return Err(Error::Client(format!(
+                "MCP server answered HTTP {} for {url}",
+                response.status()
+            )));
+        }
+        let mut events = SseStream::new(response);
+        while let Some((event, data)) = events.next().await? {
+            if event == "endpoint" {
+                let endpoint = url
+                    .join(data.trim())
+                    .map_err(|e| Error::Parse(format!("Bad MCP endpoint '{data}': {e}")))?;
+                return Ok(Self::Sse {
+                    http,
+                    endpoint: endpoint.to_string(),
+                    events,
+                });
+            }
+        }
+        Err(Error::Client(format!(
+            "MCP server at {url} closed the event stream before naming its endpoint"
+        )))
+    }
+
+    /// Send `message` and, when it is a request, wait for the reply to `id`.
+    async fn exchange(&mut self, message: &Value, id: Option<i64>) -> Result<Option<Value>> {
+        match self {
+            Self::Stdio { stdin, stdout, .. } => {
+                write_line(stdin, message).await?;
+                let Some(id) = id else {
+                    return Ok(None);
+                };
+                while let Some(line) = stdout.next_line().await? {
+                    // Servers that log to stdout are tolerated.
+                    let Ok(incoming) = serde_json::from_str::<Value>(&line) else {
+                        continue;
+                    };
+                    if let Some(answer) = answer_server_request(&incoming) {
+                        write_line(stdin, &answer).await?;
+                    } else if let Some(reply) = matching_reply(incoming, id) {
+                        return Ok(Some(reply));
+                    }
+                }
+                Err(Error::Client(
+                    "MCP server exited before answering".to_string(),
+                ))
+            }
+            Self::Http {
+                http,
+                url,
+                session_id,
+                protocol_version,
+            } => {
+                let mut request = http
+                    .post(url.as_str())
+                    .header(ACCEPT, "application/json, text/event-stream")
+                    .json(message);
+                if let Some(session_id) = session_id.as_deref() {
+                    request = request.header("Mcp-Session-Id", session_id);
+                }
+                if let Some(version) = protocol_version.as_deref() {
+                    request = request.header("MCP-Protocol-Version", version);
+                }
+                let response = request
+                    .send()
+                    .await
+                    .map_err(|e| mcp_error("MCP request failed", e))?;
+                if let Some(assigned) = response
+                    .headers()
+                    .get("mcp-session-id")
+                    .and_then(|value| value.to_str().ok())
+                {
+                    *session_id = Some(assigned.to_string());
+                }
+                if !response.status().is_success() {
+                    return Err(Error::Client(format!(
+                        "MCP server answered HTTP {}",
+                        response.status()
+                    )));
+                }
+                let Some(id) = id else {
+                    return Ok(None);
+                };
+                let is_stream = response
+                    .headers()
+                    .get(CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .is_some_and(|value| value.starts_with("text/event-stream"));
+                if !is_stream {
+                    let body: Value = response
+                        .json()
+                        .await
+                        .map_err(|e| mcp_error("MCP reply was not JSON", e))?;
+                    return matching_reply(body, id).map(Some).ok_or_else(|| {
+                        Error::Parse("MCP server replied without an answer".to_string())
+                    });
+                }
+                let mut events = SseStream::new(response);
+                while let Some((_, data)) = events.next().await? {
+                    if let Some(reply) = serde_json::from_str::<Value>(&data)
+                        .ok()
+                        .and_then(|incoming| matching_reply(incoming, id))
+                    {
+                        return Ok(Some(reply));
+                    }
+                }
+                Err(Error::Client(
+                    "MCP server closed the stream before answering".to_string(),
+                ))
+            }
+            Self::Sse {
+                http,
+                endpoint,
+                events,
+            } => {
+                post_message(http, endpoint, message).await?;
+                let Some(id) = id else {
+                    return Ok(None);
+                };
+                while let Some((event, data)) = events.next().await? {
+                    if event != "message" {
+                        continue;
+                    }
+                    let Ok(incoming) = serde_json::from_str::<Value>(&data) else {
+                        continue;
+                    };
+                    if let Some(answer) = answer_server_request(&incoming) {
+                        post_message(http, endpoint, &answer).await?;
+                    } else if let Some(reply) = matching_reply(incoming, id) {
+                        return Ok(Some(reply));
+                    }
+                }
+                Err(Error::Client(
+                    "MCP server closed the event stream before answering".to_string(),
+                ))
+            }
+        }
+    }
+}
+
+/// A connection from `ov` to another MCP server, used to pull documents out
+/// of it. Requests are sent one at a time.
+pub struct McpClient {
+    transport: Transport,
+    next_id: i64,
+    timeout: Duration,
+}
+
+impl McpClient {
+    /// Connect to `server` and complete the MCP handshake. `server` is an
+    /// `http(s)://` URL, where a path ending in `/sse` selects the HTTP+SSE
+    /// transport, or else a command line run through the shell that speaks
+    /// MCP on stdio.
+    pub async fn connect(server: &str, timeout: Duration) -> Result<Self> {
+        let transport = if server.starts_with("http://") || server.starts_with("https://") {
+            let url = Url::parse(server)
+                .map_err(|e| Error::Client(format!("Invalid MCP server URL '{server}': {e}")))?;
+            let http = reqwest::Client::builder()
+                .connect_timeout(Duration::from_secs(30))
+                .build()
+                .map_err(|e| mcp_error("Failed to build MCP client", e))?;
+            if url.path().trim_end_matches('/').ends_with(SSE_PATH) {
+                Transport::open_sse(http, url).await?
+            } else {
+                Transport::Http {
+                    http,
+                    url: url.to_string(),
+                    session_id: None,
+                    protocol_version: None,
+                }
+            }
+        } else {
+            Transport::spawn(server)?
+        };
+        let mut client = Self {
+            transport,
+            next_id: 0,
+            timeout,
+        };
+        let result = client
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": PROTOCOL_VERSIONS[0],
+                    "capabilities": {},
+                    "clientInfo": { "name": "ov", "version": env!("OPENVIKING_CLI_VERSION") },
+                }),
+            )
+            .await?;
+        if let Transport::Http {
+            protocol_version, ..
+        } = &mut client.transport
+        {
+            *protocol_version = result["protocolVersion"].as_str().map(str::to_string);
+        }
+        client
+            .transport
+            .exchange(
+                &json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+                None,
+            )
+            .await?;
+        Ok(client)
+    }
+
+    async fn request(&mut self, method: &str, params: Value) -> Result<Value> {
+        self.next_id += 1;
+        let id = self.next_id;
+        let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        let reply = tokio::time::timeout(self.timeout, self.transport.exchange(&message, Some(id)))
+            .await
+            .map_err(|_| {
+                Error::Client(format!(
+                    "MCP server did not answer {method} within {}s",
+                    self.timeout.as_secs()
+                ))
+            })??
+            .unwrap_or_default();
+        if let Some(error) = reply.get("error") {
+            let message = error["message"]
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| error.to_string());
+            return Err(Error::Client(format!("MCP {method} failed: {message}")));
+        }
+        Ok(reply.get("result").cloned().unwrap_or(Value::Null))
+    }
+
+    /// Every tool the server offers, following pagination.
+    pub async fn list_tools(&mut self) -> Result<Vec<Value>> {
+        let mut tools = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let params = match &cursor {
+                Some(cursor) => json!({ "cursor": cursor }),
+                None => json!({}),
+            };
+            let result = self.request("tools/list", params).await?;
+            tools.extend(result["tools"].as_array().cloned().unwrap_or_default());
+            match result.get("nextCursor").and_then(Value::as_str) {
+                Some(next) if Some(next) != cursor.as_deref() => cursor = Some(next.to_string()),
+                _ => return Ok(tools),
+            }
+        }
+    }
+
+    /// Call `name`; a result the server flags as an error becomes `Err`.
+    pub async fn call_tool(&mut self, name: &str, arguments: Value) -> Result<Value> {
+        let result = self
+            .request(
+                "tools/call",
+                json!({ "name": name, "arguments": arguments }),
+            )
+            .await?;
+        if result["isError"].as_bool() == Some(true) {
+            return Err(Error::Client(format!(
+                "MCP tool {name} failed: {}",
+                tool_text(&result)
+            )));
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server() -> McpServer {
+        McpServer::new(HttpClient::new(
+            "http://127.0.0.1:9",
+            None,
+            None,
+            None,
+            None,
+            1.0,
+            false,
+            None,
+        ))
+    }
+
+    #[tokio::test]
+    async fn initialize_echoes_a_supported_protocol_version() {
+        let reply = server()
+            .handle_text(
+                r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05"}}"#,
+            )
+            .await
+            .unwrap();
+        assert_eq!(reply["id"], 1);
+        assert_eq!(reply["result"]["protocolVersion"], "2024-11-05");
+        assert_eq!(reply["result"]["serverInfo"]["name"], "openviking");
+
+        let reply = server()
+            .handle(json!({"jsonrpc":"2.0","id":2,"method":"initialize","params":{"protocolVersion":"1999-01-01"}}))
+            .await
+            .unwrap();
+        assert_eq!(reply["result"]["protocolVersion"], PROTOCOL_VERSIONS[0]);
+    }
+
+    #[tokio::test]
+    async fn notifications_get_no_reply_and_unknown_methods_an_error() {
+        let server = server();
+        assert!(
+            server
+                .handle(json!({"jsonrpc":"2.0","method":"notifications/initialized"}))
+                .await
+                .is_none()
+        );
+        let reply = server
+            .handle(json!({"jsonrpc":"2.0","id":"a","method":"resources/list"}))
+            .await
+            .unwrap();
+        assert_eq!(reply["error"]["code"], METHOD_NOT_FOUND);
+
+        let reply = server.handle_text("{not json").await.unwrap();
+        assert_eq!(reply["error"]["code"], PARSE_ERROR);
+        assert_eq!(reply["id"], Value::Null);
+    }
+
+    #[tokio::test]
+    async fn tools_list_and_call_errors() {
+        let server = server();
+        let reply = server
+            .handle_text(r#"[{"jsonrpc":"2.0","id":1,"method":"tools/list"},{"jsonrpc":"2.0","method":"notifications/initialized"}]"#)
+            .await
+            .unwrap();
+        let names: Vec<&str> = reply[0]["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|tool| tool["name"].as_str())
+            .collect();
+        assert_eq!(reply.as_array().unwrap().len(), 1);
+        assert!(names.contains(&"find") && names.contains(&"session_commit"));
+
+        let reply = server
+            .handle(json!({"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"nope"}}))
+            .await
+            .unwrap();
+        assert_eq!(reply["error"]["code"], INVALID_PARAMS);
+
+        let reply = server
+            .handle(json!({"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"read","arguments":{}}}))
+            .await
+            .unwrap();
+        assert_eq!(reply["result"]["isError"], true);
+        assert!(
+            reply["result"]["content"][0]["text"]
+                .as_str()
+                .unwrap()
+                .contains("'uri' is required")
+        );
+    }
+
+    #[test]
+    fn only_local_origins_and_session_queries_are_accepted() {
+        assert!(is_allowed_origin(None));
+        assert!(is_allowed_origin(Some("http://localhost:3000")));
+        assert!(is_allowed_origin(Some("http://127.0.0.1")));
+        assert!(!is_allowed_origin(Some("https://evil.example")));
+        assert!(!is_allowed_origin(Some("null")));
+
+        assert_eq!(
+            session_id_of(Some("session_id=abc&x=1")).as_deref(),
+            Some("abc")
+        );
+        assert_eq!(session_id_of(Some("x=1")), None);
+        assert_eq!(
+            sse_event("endpoint", "/messages?session_id=abc"),
+            Bytes::from("event: endpoint\ndata: /messages?session_id=abc\n\n")
+        );
+    }
+
+    #[test]
+    fn sse_events_split_on_blank_lines() {
+        let mut buffer = b": comment\n\nevent: endpoint\ndata: /messages?session_id=a\n\ndata: {\"a\":\ndata: 1}\n\ndata: partial".to_vec();
+        assert_eq!(
+            take_sse_event(&mut buffer),
+            Some(("endpoint".to_string(), "/messages?session_id=a".to_string()))
+        );
+        assert_eq!(
+            take_sse_event(&mut buffer),
+            Some(("message".to_string(), "{\"a\":\n1}".to_string()))
+        );
+        assert_eq!(take_sse_event(&mut buffer), None);
+        assert_eq!(buffer, b"data: partial");
+    }
+
+    #[test]
+    fn client_picks_its_reply_and_answers_server_pings() {
+        let batch = json!([
+            {"jsonrpc": "2.0", "method": "notifications/progress"},
+            {"jsonrpc": "2.0", "id": 3, "result": {"tools": []}},
+        ]);
+        assert_eq!(
+            matching_reply(batch.clone(), 3).unwrap()["result"]["tools"],
+            json!([])
+        );
+        assert!(matching_reply(batch, 4).is_none());
+        assert!(matching_reply(json!({"jsonrpc": "2.0", "id": 3, "method": "ping"}), 3).is_none());
+
+        let ping = answer_server_request(&json!({"jsonrpc": "2.0", "id": 7, "method": "ping"}));
+        assert_eq!(ping.unwrap()["result"], json!({}));
+        let sampling = answer_server_request(
+            &json!({"jsonrpc": "2.0", "id": 8, "method": "sampling/createMessage"}),
+        );
+        assert_eq!(sampling.unwrap()["error"]["code"], METHOD_NOT_FOUND);
+        assert!(answer_server_request(&json!({"jsonrpc": "2.0", "id": 9, "result": {}})).is_none());
+    }
+}
@@ -0,0 +1,1395 @@
+//! `ov mock-server`: an in-memory stand-in for the OpenViking server.
+//!
+//! It answers the endpoints applications use most, so clients can be built and
+//! tested offline: the filesystem (`ls`, `tree`, `stat`, `mkdir`, `rm`, `mv`),
+//! content (`read`, `abstract`, `overview`, `write`), `add-resource` uploads,
+//! `find`, `search`, `grep`, and `glob`, and sessions with their messages,
+//! commits, and context. Anything else is answered with a 501
+//! `NOT_IMPLEMENTED` error. Nothing is persisted; every run starts over.
+//!
+//! With `--fixtures <dir>`, every file under the directory is loaded as
+//! `viking://<path>`, so `dir/resources/guide.md` reads as
+//! `viking://resources/guide.md`. A directory's `.abstract.md` and
+//! `.overview.md` give its abstract and overview; a file's abstract is its
+//! first line. A top-level `search.json` maps queries to the result `find` and
+//! `search` return for them, with `"*"` for any other query. Queries it does
+//! not answer are matched against the files, scored by the share of their words
+//! each contains.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use chrono::{SecondsFormat, Utc};
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::header::{CONTENT_TYPE, HeaderValue};
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use regex::RegexBuilder;
+use serde_json::{Map, Value, json};
+use tokio::net::TcpListener;
+
+use crate::error::{Error, Result};
+use crate::utils::{percent_decode, truncate_utf8};
+
+const ROOT: &str = "viking://";
+const SCOPES: &[&str] = &["agent", "resources", "session", "user"];
+const SEARCH_FIXTURE: &str = "search.json";
+const ABSTRACT_FILE: &str = ".abstract.md";
+const OVERVIEW_FILE: &str = ".overview.md";
+const ABSTRACT_LIMIT: usize = 256;
+const FILE_MODE: u32 = 0o644;
+const DIR_MODE: u32 = 0x8000_01ed;
+
+/// A file's content, or `None` for a directory.
+struct Node {
+    content: Option<Vec<u8>>,
+    modified: String,
+}
+
+impl Node {
+    fn dir() -> Self {
+        Self {
+            content: None,
+            modified: now(),
+        }
+    }
+
+    fn file(content: Vec<u8>) -> Self {
+        Self {
+            content: Some(content),
+            modified: now(),
+        }
+    }
+
+    fn is_dir(&self) -> bool {
+        self.content.is_none()
+    }
+
+    fn size(&self) -> usize {
+        self.content.as_ref().map_or(0, Vec::len)
+    }
+
+    fn text(&self) -> String {
+        String::from_utf8_lossy(self.content.as_deref().unwrap_or_default()).into_owned()
+    }
+}
+
+struct Session {
+    created_at: String,
+    updated_at: String,
+    messages: Vec<Value>,
+    commit_count: u64,
+}
+
+/// An error answered with `status` in the server's error envelope.
+struct Failure {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+}
+
+type Answer = std::result::Result<Value, Failure>;
+
+fn not_found(what: impl std::fmt::Display) -> Failure {
+    Failure {
+        status: StatusCode::NOT_FOUND,
+        code: "NOT_FOUND",
+        message: format!("{what} not found"),
+    }
+}
+
+fn invalid(message: impl Into<String>) -> Failure {
+    Failure {
+        status: StatusCode::BAD_REQUEST,
+        code: "INVALID_ARGUMENT",
+        message: message.into(),
+    }
+}
+
+fn conflict(uri: &str) -> Failure {
+    Failure {
+        status: StatusCode::CONFLICT,
+        code: "ALREADY_EXISTS",
+        message: format!("{uri} already exists"),
+    }
+}
+
+fn now() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn new_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// `uri` without a trailing slash, or an error if it is not a `viking://` URI.
+fn normalize(uri: &str) -> std::result::Result<String, Failure> {
+    if !uri.starts_with(ROOT) {
+        return Err(invalid(format!("{uri} is not a viking:// URI")));
+    }
+    let trimmed = uri.trim_end_matches('/');
+    Ok(if trimmed.len() < ROOT.len() {
+        ROOT.to_string()
+    } else {
+        trimmed.to_string()
+    })
+}
+
+fn parent(uri: &str) -> Option<&str> {
+    let rest = uri.strip_prefix(ROOT).filter(|rest| !rest.is_empty())?;
+    Some(match rest.rfind('/') {
+        Some(slash) => &uri[..ROOT.len() + slash],
+        None => ROOT,
+    })
+}
+
+fn name(uri: &str) -> &str {
+    uri.rsplit('/').next().unwrap_or(uri)
+}
+
+fn join(dir: &str, name: &str) -> String {
+    if dir == ROOT {
+        format!("{ROOT}{name}")
+    } else {
+        format!("{dir}/{name}")
+    }
+}
+
+/// The prefix every URI below `dir` starts with.
+fn below(dir: &str) -> String {
+    if dir == ROOT {
+        ROOT.to_string()
+    } else {
+        format!("{dir}/")
+    }
+}
+
+fn field<'a>(body: &'a Value, name: &str) -> std::result::Result<&'a str, Failure> {
+    body[name]
+        .as_str()
+        .ok_or_else(|| invalid(format!("'{name}' is required")))
+}
+
+#[derive(Default)]
+struct State {
+    nodes: BTreeMap<String, Node>,
+    sessions: BTreeMap<String, Session>,
+    /// Files sent to `temp_upload`, by id: their name and bytes.
+    uploads: HashMap<String, (String, Vec<u8>)>,
+    /// Canned `find` and `search` results, by query.
+    searches: Map<String, Value>,
+}
+
+impl State {
+    fn put(&mut self, uri: &str, node: Node) {
+        let mut ancestor = parent(uri);
+        while let Some(dir) = ancestor {
+            self.nodes.entry(dir.to_string()).or_insert_with(Node::dir);
+            ancestor = parent(dir);
+        }
+        self.nodes.insert(uri.to_string(), node);
+    }
+
+    fn node(&self, uri: &str) -> std::result::Result<&Node, Failure> {
+        self.nodes.get(uri).ok_or_else(|| not_found(uri))
+    }
+
+    /// Everything below `dir`, at any depth, with paths relative to it.
+    fn descendants<'a>(&'a self, dir: &str) -> impl Iterator<Item = (&'a str, &'a str, &'a Node)> {
+        let prefix = below(dir);
+        self.nodes
+            .range(prefix.clone()..)
+            .take_while(move |(uri, _)| uri.starts_with(&prefix))
+            .map(move |(uri, node)| (uri.as_str(), &uri[below(dir).len()..], node))
+    }
+
+    fn remove_tree(&mut self, uri: &str) -> Vec<(String, Node)> {
+        let prefix = below(uri);
+        let uris: Vec<String> = self
+            .nodes
+            .keys()
+            .filter(|key| *key == uri || key.starts_with(&prefix))
+            .cloned()
+            .collect();
+        uris.into_iter()
+            .filter_map(|key| self.nodes.remove_entry(&key))
+            .collect()
+    }
+
+    fn abstract_of(&self, uri: &str, node: &Node) -> String {
+        if node.is_dir() {
+            return self
+                .nodes
+                .get(&join(uri, ABSTRACT_FILE))
+                .map(Node::text)
+                .unwrap_or_default();
+        }
+        let text = node.text();
+        let line = text.lines().find(|line| !line.trim().is_empty());
+        truncate_utf8(line.unwrap_or_default().trim(), ABSTRACT_LIMIT).to_string()
+    }
+
+    fn overview_of(&self, uri: &str, node: &Node) -> String {
+        if node.is_dir() {
+            return self
+                .nodes
+                .get(&join(uri, OVERVIEW_FILE))
+                .map(Node::text)
+                .unwrap_or_default();
+        }
+        node.text()
+    }
+
+    fn entry(&self, uri: &str, node: &Node, rel_path: Option<&str>) -> Value {
+        let mut entry = json!({
+            "name": name(uri),
+            "uri": uri,
+            "size": node.size(),
+            "isDir": node.is_dir(),
+            "modTime": node.modified,
+            "abstract": self.abstract_of(uri, node),
+        });
+        if let Some(rel_path) = rel_path {
+            entry["rel_path"] = json!(rel_path);
+        }
+        entry
+    }
+
+    fn ls(&self, query: &Query) -> Answer {
+        let uri = normalize(query.required("uri")?)?;
+        let node = self.node(&uri)?;
+        if !node.is_dir() {
+            return Ok(json!([self.entry(&uri, node, None)]));
+        }
+        let recursive = query.flag("recursive");
+        let all = query.flag("show_all_hidden");
+        let entries = self
+            .descendants(&uri)
+            .filter(|(_, rel, _)| recursive || !rel.contains('/'))
+            .filter(|(_, rel, _)| all || !rel.split('/').any(|part| part.starts_with('.')))
+            .take(query.limit("node_limit", 1000));
+        Ok(if query.flag("simple") {
+            entries.map(|(uri, _, _)| json!(uri)).collect()
+        } else {
+            entries
+                .map(|(uri, rel, node)| self.entry(uri, node, recursive.then_some(rel)))
+                .collect()
+        })
+    }
+
+    fn tree(&self, query: &Query) -> Answer {
+        let uri = normalize(query.required("uri")?)?;
+        self.node(&uri)?;
+        let levels = query.limit("level_limit", 3);
+        let all = query.flag("show_all_hidden");
+        Ok(self
+            .descendants(&uri)
+            .filter(|(_, rel, _)| rel.split('/').count() <= levels)
+            .filter(|(_, rel, _)| all || !rel.split('/').any(|part| part.starts_with('.')))
+            .take(query.limit("node_limit", 1000))
+            .map(|(uri, rel, node)| self.entry(uri, node, Some(rel)))
+            .collect())
+    }
+
+    fn stat(&self, query: &Query) -> Answer {
+        let uri = normalize(query.required("uri")?)?;
+        let node = self.node(&uri)?;
+        Ok(json!({
+            "name": name(&uri),
+            "uri": uri,
+            "size": node.size(),
+            "mode": if node.is_dir() { DIR_MODE } else { FILE_MODE },
+            "modTime": node.modified,
+            "isDir": node.is_dir(),
+        }))
+    }
+
+    fn mkdir(&mut self, body: &Value) -> Answer {
+        let uri = normalize(field(body, "uri")?)?;
+        match self.nodes.get(&uri) {
+            Some(node) if !node.is_dir() => return Err(conflict(&uri)),
+            Some(_) => {}
+            None => self.put(&uri, Node::dir()),
+        }
+        if let Some(description) = body["description"].as_str() {
+            self.put(&join(&uri, ABSTRACT_FILE), Node::file(description.into()));
+        }
+        Ok(json!({ "uri": uri }))
+    }
+
+    fn rm(&mut self, query: &Query) -> Answer {
+        let uri = normalize(query.required("uri")?)?;
+        if uri == ROOT {
+            return Err(invalid("Cannot remove viking://"));
+        }
+        let node = self.node(&uri)?;
+        if node.is_dir() && self.descendants(&uri).next().is_some() && !query.flag("recursive") {
+            return Err(invalid(format!(
+                "{uri} is not empty; remove it recursively"
+            )));
+        }
+        let removed = self.remove_tree(&uri).len();
+        Ok(json!({ "uri": uri, "estimated_deleted_count": removed }))
+    }
+
+    fn mv(&mut self, body: &Value) -> Answer {
+        let from = normalize(field(body, "from_uri")?)?;
+        let to = normalize(field(body, "to_uri")?)?;
+        self.node(&from)?;
+        if self.nodes.contains_key(&to) {
+            return Err(conflict(&to));
+        }
+        if to.starts_with(&below(&from)) {
+            return Err(invalid(format!("Cannot move {from} into itself")));
+        }
+        for (uri, node) in self.remove_tree(&from) {
+            let moved = format!("{to}{}", &uri[from.len()..]);
+            self.put(&moved, node);
+        }
+        Ok(json!({ "from": from, "to": to }))
+    }
+
+    fn read(&self, query: &Query, part: &str) -> Answer {
+        let uri = normalize(query.required("uri")?)?;
+        let node = self.node(&uri)?;
+        Ok(json!(match part {
+            "abstract" => self.abstract_of(&uri, node),
+            "overview" => self.overview_of(&uri, node),
+            _ if node.is_dir() => return Err(invalid(format!("{uri} is a directory"))),
+            _ => node.text(),
+        }))
+    }
+
+    fn write(&mut self, body: &Value) -> Answer {
+        let uri = normalize(field(body, "uri")?)?;
+        let content = field(body, "content")?;
+        let mode = body["mode"].as_str().unwrap_or("replace");
+        let existing = self.nodes.get(&uri);
+        if existing.is_some_and(Node::is_dir) {
+            return Err(invalid(format!("{uri} is a directory")));
+        }
+        let bytes = match (mode, existing) {
+            ("create", Some(_)) => return Err(conflict(&uri)),
+            ("create", None) | ("replace", Some(_)) => content.as_bytes().to_vec(),
+            ("append", Some(node)) => [
+                node.content.as_deref().unwrap_or_default(),
+                content.as_bytes(),
+            ]
+            .concat(),
+            ("replace" | "append", None) => return Err(not_found(&uri)),
+            _ => return Err(invalid(format!("Unknown write mode '{mode}'"))),
+        };
+        self.put(&uri, Node::file(bytes));
+        Ok(json!({ "uri": uri, "mode": mode, "written_bytes": content.len() }))
+    }
+
+    fn temp_upload(&mut self, content_type: &str, body: &[u8]) -> Answer {
+        let (file_name, bytes) = multipart_file(content_type, body)
+            .ok_or_else(|| invalid("Expected a multipart upload with a file"))?;
+        let id = format!("upload_{}", new_id());
+        self.uploads.insert(id.clone(), (file_name, bytes));
+        Ok(json!({ "temp_file_id": id }))
+    }
+
+    /// Add an uploaded file or directory, or, since the mock fetches nothing,
+    /// an empty directory for a URL.
+    fn add_resource(&mut self, body: &Value) -> Answer {
+        let (name, upload) = match body["temp_file_id"].as_str() {
+            Some(id) => {
+                let (file_name, bytes) = self
+                    .uploads
+                    .remove(id)
+                    .ok_or_else(|| not_found(format!("Upload {id}")))?;
+                let name = body["source_name"]
+                    .as_str()
+                    .unwrap_or(&file_name)
+                    .to_string();
+                (name, Some((file_name, bytes)))
+            }
+            None => {
+                let path = field(body, "path")?;
+                let path = path.split(['?', '#']).next().unwrap_or(path);
+                let name = path
+                    .trim_end_matches('/')
+                    .rsplit('/')
+                    .next()
+                    .filter(|name| !name.is_empty() && !name.contains(':'))
+                    .unwrap_or("resource");
+                (name.to_string(), None)
+            }
+        };
+        let root = match body["to"].as_str() {
+            Some(to) => normalize(to)?,
+            None => join(
+                &normalize(body["parent"].as_str().unwrap_or("viking://resources"))?,
+                &name,
+            ),
+        };
+        self.remove_tree(&root);
+        match upload {
+            // Directories arrive zipped, under a name other than their own.
+            Some((file_name, bytes)) if file_name.ends_with(".zip") && file_name != name => {
+                let files = unzip(&bytes)?;
+                self.put(&root, Node::dir());
+                for (path, content) in files {
+                    self.put(&join(&root, &path), Node::file(content));
+                }
+            }
+            Some((_, bytes)) => self.put(&root, Node::file(bytes)),
+            None => self.put(&root, Node::dir()),
+        }
+        Ok(json!({ "status": "success", "root_uri": root }))
+    }
+
+    /// The canned result for the query, or the files that contain its words.
+    fn find(&self, body: &Value) -> Answer {
+        let query = body["query"].as_str().unwrap_or_default();
+        if let Some(canned) = self.searches.get(query).or_else(|| self.searches.get("*")) {
+            return Ok(canned.clone());
+        }
+        let targets = match &body["target_uri"] {
+            Value::String(uri) if !uri.is_empty() => vec![normalize(uri)?],
+            Value::Array(uris) => uris
+                .iter()
+                .filter_map(Value::as_str)
+                .map(normalize)
+                .collect::<std::result::Result<_, _>>()?,
+            _ => vec![ROOT.to_string()],
+        };
+        let limit = body["limit"].as_u64().unwrap_or(10) as usize;
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let mut hits: Vec<(f64, &str, &Node)> = self
+            .nodes
+            .iter()
+            .filter(|(uri, node)| {
+                !node.is_dir()
+                    && !name(uri).starts_with('.')
+                    && targets
+                        .iter()
+                        .any(|target| *uri == target || uri.starts_with(&below(target)))
+            })
+            .filter_map(|(uri, node)| {
+                let text = format!("{uri}\n{}", node.text()).to_lowercase();
+                let found = words.iter().filter(|word| text.contains(*word)).count();
+                (found > 0).then(|| (found as f64 / words.len() as f64, uri.as_str(), node))
+            })
+            .collect();
+        hits.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(b.1)));
+
+        let mut result = json!({ "memories": [], "resources": [], "skills": [] });
+        for (score, uri, node) in hits.into_iter().take(limit) {
+            let (context_type, bucket) = if uri.starts_with("viking://agent/skills/") {
+                ("skill", "skills")
+            } else if uri.starts_with("viking://user/") && uri.contains("/memories/") {
+                ("memory", "memories")
+            } else {
+                ("resource", "resources")
+            };
+            result[bucket].as_array_mut().unwrap().push(json!({
+                "context_type": context_type,
+                "uri": uri,
+                "level": 2,
+                "score": score,
+                "category": "",
+                "match_reason": "",
+                "relations": [],
+                "abstract": self.abstract_of(uri, node),
+                "overview": null,
+            }));
+        }
+        result["total"] = json!(
+            ["memories", "resources", "skills"]
+                .iter()
+                .map(|bucket| result[bucket].as_array().map_or(0, Vec::len))
+                .sum::<usize>()
+        );
+        Ok(result)
+    }
+
+    fn grep(&self, body: &Value) -> Answer {
+        let uri = normalize(field(body, "uri")?)?;
+        let pattern = RegexBuilder::new(field(body, "pattern")?)
+            .case_insensitive(body["case_insensitive"].as_bool().unwrap_or(false))
+            .build()
+            .map_err(|e| invalid(format!("Invalid pattern: {e}")))?;
+        let limit = node_limit(body);
+        let node = self.node(&uri)?;
+        let files = std::iter::once((uri.as_str(), node))
+            .chain(self.descendants(&uri).map(|(uri, _, node)| (uri, node)))
+            .filter(|(_, node)| !node.is_dir());
+        let matches: Vec<Value> = files
+            .flat_map(|(uri, node)| {
+                let text = node.text();
+                text.lines()
+                    .enumerate()
+                    .filter(|(_, line)| pattern.is_match(line))
+                    .map(|(index, line)| json!({ "uri": uri, "line": index + 1, "content": line }))
+                    .collect::<Vec<_>>()
+            })
+            .take(limit)
+            .collect();
+        Ok(json!({ "count": matches.len(), "matches": matches }))
+    }
+
+    fn glob(&self, body: &Value) -> Answer {
+        let uri = normalize(body["uri"].as_str().unwrap_or(ROOT))?;
+        let pattern = glob_regex(field(body, "pattern")?)?;
+        let limit = node_limit(body);
+        self.node(&uri)?;
+        let matches: Vec<&str> = self
+            .descendants(&uri)
+            .filter(|(_, rel, _)| pattern.is_match(rel))
+            .map(|(uri, _, _)| uri)
+            .take(limit)
+            .collect();
+        Ok(json!({ "count": matches.len(), "matches": matches }))
+    }
+
+    fn session(&self, id: &str) -> std::result::Result<&Session, Failure> {
+        self.sessions
+            .get(id)
+            .ok_or_else(|| not_found(format!("Session {id}")))
+    }
+
+    fn session_mut(&mut self, id: &str) -> &mut Session {
+        self.sessions
+            .entry(id.to_string())
+            .or_insert_with(|| Session {
+                created_at: now(),
+                updated_at: now(),
+                messages: Vec::new(),
+                commit_count: 0,
+            })
+    }
+
+    fn session_meta(&self, id: &str) -> Answer {
+        let session = self.session(id)?;
+        Ok(json!({
+            "session_id": id,
+            "uri": format!("viking://session/{id}"),
+            "created_at": session.created_at,
+            "updated_at": session.updated_at,
+            "message_count": session.messages.len(),
+            "commit_count": session.commit_count,
+            "pending_tokens": 0,
+        }))
+    }
+
+    /// Answer `/api/v1/sessions` and what lies below it, `rest` being the
+    /// path segments after it.
+    fn sessions(&mut self, method: &Method, rest: &[String], query: &Query, body: &[u8]) -> Answer {
+        let segments: Vec<&str> = rest.iter().map(String::as_str).collect();
+        match (method, segments.as_slice()) {
+            (&Method::POST, []) => {
+                let body = json_body(body).unwrap_or(Value::Null);
+                let id = match body["session_id"].as_str() {
+                    Some(id) if self.sessions.contains_key(id) => {
+                        return Err(conflict(&format!("Session {id}")));
+                    }
+                    Some(id) => id.to_string(),
+                    None => new_id(),
+                };
+                self.session_mut(&id);
+                Ok(json!({
+                    "session_id": id,
+                    "uri": format!("viking://session/{id}"),
+                    "user": { "account_id": "default", "user_id": "default" },
+                }))
+            }
+            (&Method::GET, []) => Ok(self
+                .sessions
+                .iter()
+                .map(|(id, session)| {
+                    json!({
+                        "session_id": id,
+                        "uri": format!("viking://session/{id}"),
+                        "is_dir": true,
+                        "mod_time": session.updated_at,
+                    })
+                })
+                .collect()),
+            (&Method::GET, [id]) => {
+                if query.flag("auto_create") {
+                    self.session_mut(id);
+                }
+                self.session_meta(id)
+            }
+            (&Method::DELETE, [id]) => {
+                self.sessions
+                    .remove(*id)
+                    .ok_or_else(|| not_found(format!("Session {id}")))?;
+                Ok(json!({ "session_id": id }))
+            }
+            (&Method::POST, [id, "messages"]) => {
+                let message = message(&json_body(body)?)?;
+                let session = self.session_mut(id);
+                session.messages.push(message);
+                session.updated_at = now();
+                Ok(json!({ "session_id": id, "message_count": session.messages.len() }))
+            }
+            (&Method::POST, [id, "messages", "batch"]) => {
+                let body = json_body(body)?;
+                let messages = body["messages"]
+                    .as_array()
+                    .ok_or_else(|| invalid("'messages' must be an array"))?
+                    .iter()
+                    .map(message)
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                let added = messages.len();
+                let session = self.session_mut(id);
+                session.messages.extend(messages);
+                session.updated_at = now();
+                Ok(json!({
+                    "session_id": id,
+                    "message_count": session.messages.len(),
+                    "added": added,
+                }))
+            }
+            (&Method::POST, [id, "commit"]) => {
+                self.session(id)?;
+                let session = self.session_mut(id);
+                session.commit_count += 1;
+                session.messages.clear();
+                session.updated_at = now();
+                Ok(json!({
+                    "session_id": id,
+                    "status": "accepted",
+                    "task_id": null,
+                    "archive_uri": format!(
+                        "viking://session/{id}/history/archive_{:03}",
+                        session.commit_count
+                    ),
+                    "archived": true,
+                }))
+            }
+            (&Method::GET, [id, "context"]) => {
+                let session = self.session(id)?;
+                Ok(json!({
+                    "latest_archive_overview": "",
+                    "pre_archive_abstracts": [],
+                    "messages": session.messages,
+                    "estimatedTokens": 0,
+                    "stats": {
+                        "totalArchives": session.commit_count,
+                        "includedArchives": 0,
+                        "droppedArchives": 0,
+                        "failedArchives": 0,
+                        "activeTokens": 0,
+                        "archiveTokens": 0,
+                    },
+                }))
+            }
+            _ => Err(unimplemented(
+                method,
+                &format!("/api/v1/sessions/{}", rest.join("/")),
+            )),
+        }
+    }
+}
+
+/// A session message as the server stores it, from a request's `role` with
+/// its `content` or `parts`.
+fn message(spec: &Value) -> Answer {
+    let role = field(spec, "role")?;
+    let parts = match &spec["parts"] {
+        Value::Array(parts) => Value::Array(parts.clone()),
+        _ => json!([{ "type": "text", "text": spec["content"].as_str().unwrap_or_default() }]),
+    };
+    let mut message = json!({
+        "id": format!("msg_{}", new_id()),
+        "role": role,
+        "parts": parts,
+        "created_at": spec["created_at"].as_str().map_or_else(now, str::to_string),
+    });
+    for key in ["peer_id", "turn_id", "message_kind"] {
+        if !spec[key].is_null() {
+            message[key] = spec[key].clone();
+        }
+    }
+    Ok(message)
+}
+
+fn unimplemented(method: &Method, path: &str) -> Failure {
+    Failure {
+        status: StatusCode::NOT_IMPLEMENTED,
+        code: "NOT_IMPLEMENTED",
+        message: format!("ov mock-server does not implement {method} {path}"),
+    }
+}
+
+fn json_body(body: &[u8]) -> Answer {
+    serde_json::from_slice(body).map_err(|e| invalid(format!("Invalid JSON body: {e}")))
+}
+
+/// A shell glob as a regex over paths: `*` and `?` stay within a segment,
+/// `**` crosses them.
+/// The request's `node_limit` the way the server reads it: 256 when absent,
+/// and no limit at all when null, zero, or negative.
+fn node_limit(body: &Value) -> usize {
+    match body.get("node_limit") {
+        None => 256,
+        Some(limit) => limit
+            .as_i64()
+            .filter(|&limit| limit > 0)
+            .map_or(usize::MAX, |limit| limit as usize),
+    }
+}
+
+fn glob_regex(pattern: &str) -> std::result::Result<regex::Regex, Failure> {
+    let mut regex = String::from("^");
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    regex::Regex::new(&regex).map_err(|e| invalid(format!("Invalid pattern: {e}")))
+}
+
+/// The files of an uploaded zip, by path.
+fn unzip(bytes: &[u8]) -> std::result::Result<Vec<(String, Vec<u8>)>, Failure> {
+    let bad = |e: zip::result::ZipError| invalid(format!("Invalid zip upload: {e}"));
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).map_err(bad)?;
+    let mut files = Vec::new();
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index).map_err(bad)?;
+        let Some(path) = file.enclosed_name() else {
+            continue;
+        };
+        if file.is_dir() {
+            continue;
+        }
+        let path = path
+            .components()
+            .map(|part| part.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let mut content = Vec::new();
+        file.read_to_end(&mut content)
+            .map_err(|e| invalid(format!("Invalid zip upload: {e}")))?;
+        files.push((path, content));
+    }
+    Ok(files)
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// The name and content of the first file in a `multipart/form-data` body.
+fn multipart_file(content_type: &str, body: &[u8]) -> Option<(String, Vec<u8>)> {
+    let boundary = content_type
+        .split(';')
+        .find_map(|param| param.trim().strip_prefix("boundary="))?
+        .trim_matches('"');
+    let delimiter = format!("--{boundary}").into_bytes();
+    let mut rest = body;
+    while let Some(start) = find_bytes(rest, &delimiter) {
+        rest = &rest[start + delimiter.len()..];
+        let part = &rest[..find_bytes(rest, &delimiter).unwrap_or(rest.len())];
+        let Some(part) = part.strip_prefix(b"\r\n") else {
+            continue;
+        };
+        let Some(header_end) = find_bytes(part, b"\r\n\r\n") else {
+            continue;
+        };
+        let headers = String::from_utf8_lossy(&part[..header_end]);
+        let Some(file_name) = headers
+            .split("filename=\"")
+            .nth(1)
+            .and_then(|rest| rest.split('"').next())
+        else {
+            continue;
+        };
+        let content = &part[header_end + 4..];
+        let content = content.strip_suffix(b"\r\n").unwrap_or(content);
+        return Some((file_name.to_string(), content.to_vec()));
+    }
+    None
+}
+
+/// The query parameters of a request.
+struct Query(HashMap<String, String>);
+
+impl Query {
+    fn parse(query: Option<&str>) -> Self {
+        Self(
+            url::form_urlencoded::parse(query.unwrap_or_default().as_bytes())
+                .into_owned()
+                .collect(),
+        )
+    }
+
+    fn required(&self, name: &str) -> std::result::Result<&str, Failure> {
+        self.0
+            .get(name)
+            .map(String::as_str)
+            .ok_or_else(|| invalid(format!("'{name}' is required")))
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.0.get(name).is_some_and(|value| value == "true")
+    }
+
+    fn limit(&self, name: &str, default: usize) -> usize {
+        self.0
+            .get(name)
+            .and_then(|value| value.parse::<i64>().ok())
+            .map_or(default, |limit| {
+                if limit < 0 {
+                    usize::MAX
+                } else {
+                    limit as usize
+                }
+            })
+    }
+}
+
+/// The in-memory server behind `ov mock-server`.
+pub struct MockServer {
+    state: Mutex<State>,
+}
+
+impl MockServer {
+    /// A server holding the scope directories, plus the files and canned
+    /// searches of `fixtures` when given.
+    pub fn new(fixtures: Option<&Path>) -> Result<Self> {
+        let mut state = State::default();
+        state.put(ROOT, Node::dir());
+        for scope in SCOPES {
+            state.put(&join(ROOT, scope), Node::dir());
+        }
+        if let Some(dir) = fixtures {
+            if !dir.is_dir() {
+                return Err(Error::Client(format!(
+                    "Fixtures directory {} not found",
+                    dir.display()
+                )));
+            }
+            for entry in walkdir::WalkDir::new(dir).min_depth(1).sort_by_file_name() {
+                let entry =
+                    entry.map_err(|e| Error::Client(format!("Failed to read fixtures: {e}")))?;
+                let rel = entry.path().strip_prefix(dir).unwrap_or(entry.path());
+                let rel = rel
+                    .components()
+                    .map(|part| part.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                if rel == SEARCH_FIXTURE {
+                    let content = std::fs::read_to_string(entry.path())?;
+                    state.searches = serde_json::from_str(&content).map_err(|e| {
+                        Error::Client(format!("Invalid {}: {e}", entry.path().display()))
+                    })?;
+                } else if entry.file_type().is_dir() {
+                    state.put(&join(ROOT, &rel), Node::dir());
+                } else {
+                    let content = std::fs::read(entry.path())?;
+                    state.put(&join(ROOT, &rel), Node::file(content));
+                }
+            }
+        }
+        Ok(Self {
+            state: Mutex::new(state),
+        })
+    }
+
+    /// The status and JSON body the server answers a request with.
+    fn answer(
+        &self,
+        method: &Method,
+        path: &str,
+        query: &Query,
+        content_type: &str,
+        body: &[u8],
+    ) -> (StatusCode, Value) {
+        if path == "/health" {
+            return (
+                StatusCode::OK,
+                json!({ "status": "ok", "healthy": true, "version": "mock" }),
+            );
+        }
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let answer = match (method, path) {
+            (&Method::GET, "/api/v1/fs/ls") => state.ls(query),
+            (&Method::GET, "/api/v1/fs/tree") => state.tree(query),
+            (&Method::GET, "/api/v1/fs/stat") => state.stat(query),
+            (&Method::POST, "/api/v1/fs/mkdir") => json_body(body).and_then(|b| state.mkdir(&b)),
+            (&Method::DELETE, "/api/v1/fs") => state.rm(query),
+            (&Method::POST, "/api/v1/fs/mv") => json_body(body).and_then(|b| state.mv(&b)),
+            (&Method::GET, "/api/v1/content/read") => state.read(query, "read"),
+            (&Method::GET, "/api/v1/content/abstract") => state.read(query, "abstract"),
+            (&Method::GET, "/api/v1/content/overview") => state.read(query, "overview"),
+            (&Method::POST, "/api/v1/content/write") => {
+                json_body(body).and_then(|b| state.write(&b))
+            }
+            (&Method::POST, "/api/v1/resources/temp_upload") => {
+                state.temp_upload(content_type, body)
+            }
+            (&Method::POST, "/api/v1/resources") => {
+                json_body(body).and_then(|b| state.add_resource(&b))
+            }
+            (&Method::POST, "/api/v1/search/find" | "/api/v1/search/search") => {
+                json_body(body).and_then(|b| state.find(&b))
+            }
+            (&Method::POST, "/api/v1/search/grep") => json_body(body).and_then(|b| state.grep(&b)),
+            (&Method::POST, "/api/v1/search/glob") => json_body(body).and_then(|b| state.glob(&b)),
+            _ => match path.strip_prefix("/api/v1/sessions") {
+                Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+                    let rest: Vec<String> = rest
+                        .split('/')
+                        .filter(|segment| !segment.is_empty())
+                        .map(percent_decode)
+                        .collect();
+                    state.sessions(method, &rest, query, body)
+                }
+                _ => Err(unimplemented(method, path)),
+            },
+        };
+        match answer {
+            Ok(result) => (StatusCode::OK, json!({ "status": "ok", "result": result })),
+            Err(failure) => (
+                failure.status,
+                json!({
+                    "status": "error",
+                    "error": { "code": failure.code, "message": failure.message },
+                }),
+            ),
+        }
+    }
+
+    async fn handle(&self, request: Request<Incoming>) -> Response<Full<Bytes>> {
+        let method = request.method().clone();
+        let path = request.uri().path().to_string();
+        let query = Query::parse(request.uri().query());
+        let content_type = request
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let body = match request.into_body().collect().await {
+            Ok(body) => body.to_bytes(),
+            Err(_) => Bytes::new(),
+        };
+        let (status, answer) = self.answer(&method, &path, &query, &content_type, &body);
+        eprintln!("{method} {path} {}", status.as_u16());
+        let mut response = Response::new(Full::new(Bytes::from(answer.to_string())));
+        *response.status_mut() = status;
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        response
+    }
+}
+
+/// Answer requests on `listener` until Ctrl-C.
+pub async fn serve(listener: TcpListener, server: Arc<MockServer>) -> Result<()> {
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, _) = accepted?;
+                let server = server.clone();
+                tokio::spawn(async move {
+                    let service = hyper::service::service_fn(move |request| {
+                        let server = server.clone();
+                        async move { Ok::<_, Error>(server.handle(request).await) }
+                    });
+                    let _ = hyper::server::conn::http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{HttpClient, SearchScoring};
+
+    fn fixtures() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let docs = dir.path().join("resources/docs");
+        std::fs::create_dir_all(&docs).unwrap();
+        std::fs::write(
+            docs.join("deploy.md"),
+            "Deploy guide\nRun the deploy script.\n",
+        )
+        .unwrap();
+        std::fs::write(docs.join("style.md"), "Style guide\nUse short lines.\n").unwrap();
+        std::fs::write(docs.join(ABSTRACT_FILE), "Team docs").unwrap();
+        std::fs::write(
+            dir.path().join(SEARCH_FIXTURE),
+            r#"{"pinned": {"memories": [], "resources": [{"uri": "viking://resources/pinned"}], "skills": [], "total": 1}}"#,
+        )
+        .unwrap();
+        dir
+    }
+
+    fn call(
+        server: &MockServer,
+        method: Method,
+        path_and_query: &str,
+        body: Value,
+    ) -> (u16, Value) {
+        let (path, query) = match path_and_query.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (path_and_query, None),
+        };
+        let body = if body.is_null() {
+            Vec::new()
+        } else {
+            body.to_string().into_bytes()
+        };
+        let (status, answer) = server.answer(
+            &method,
+            path,
+            &Query::parse(query),
+            "application/json",
+            &body,
+        );
+        (status.as_u16(), answer)
+    }
+
+    #[test]
+    fn fixtures_are_served_as_the_filesystem() {
+        let dir = fixtures();
+        let server = MockServer::new(Some(dir.path())).unwrap();
+
+        let (status, ls) = call(
+            &server,
+            Method::GET,
+            "/api/v1/fs/ls?uri=viking://resources/docs",
+            Value::Null,
+        );
+        assert_eq!(status, 200);
+        let uris: Vec<&str> = ls["result"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["uri"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            uris,
+            [
+                "viking://resources/docs/deploy.md",
+                "viking://resources/docs/style.md"
+            ]
+        );
+
+        let (_, read) = call(
+            &server,
+            Method::GET,
+            "/api/v1/content/read?uri=viking://resources/docs/deploy.md",
+            Value::Null,
+        );
+        assert_eq!(read["result"], "Deploy guide\nRun the deploy script.\n");
+        let (_, summary) = call(
+            &server,
+            Method::GET,
+            "/api/v1/content/abstract?uri=viking://resources/docs",
+            Value::Null,
+        );
+        assert_eq!(summary["result"], "Team docs");
+        assert!(
+            !server
+                .state
+                .lock()
+                .unwrap()
+                .nodes
+                .contains_key("viking://search.json")
+        );
+
+        let (status, missing) = call(
+            &server,
+            Method::GET,
+            "/api/v1/content/read?uri=viking://resources/nope.md",
+            Value::Null,
+        );
+        assert_eq!(status, 404);
+        assert_eq!(missing["error"]["code"], "NOT_FOUND");
+        let (status, unknown) = call(
+            &server,
+            Method::GET,
+            "/api/v1/relations?uri=viking://",
+            Value::Null,
+        );
+        assert_eq!(status, 501);
+        assert_eq!(unknown["error"]["code"], "NOT_IMPLEMENTED");
+    }
+
+    #[test]
+    fn search_answers_canned_queries_then_matches_files() {
+        let dir = fixtures();
+        let server = MockServer::new(Some(dir.path())).unwrap();
+
+        let (_, pinned) = call(
+            &server,
+            Method::POST,
+            "/api/v1/search/find",
+            json!({ "query": "pinned" }),
+        );
+        assert_eq!(
+            pinned["result"]["resources"][0]["uri"],
+            "viking://resources/pinned"
+        );
+
+        let (_, found) = call(
+            &server,
+            Method::POST,
+            "/api/v1/search/find",
+            json!({ "query": "deploy guide" }),
+        );
+        let resources = found["result"]["resources"].as_array().unwrap();
+        assert_eq!(resources[0]["uri"], "viking://resources/docs/deploy.md");
+        assert_eq!(resources[0]["score"], 1.0);
+        assert_eq!(resources[1]["uri"], "viking://resources/docs/style.md");
+        assert_eq!(found["result"]["total"], 2);
+
+        let (_, grep) = call(
+            &server,
+            Method::POST,
+            "/api/v1/search/grep",
+            json!({ "uri": "viking://resources", "pattern": "SCRIPT", "case_insensitive": true }),
+        );
+        assert_eq!(
+            grep["result"]["matches"],
+            json!([{ "uri": "viking://resources/docs/deploy.md", "line": 2, "content": "Run the deploy script." }])
+        );
+        let (_, glob) = call(
+            &server,
+            Method::POST,
+            "/api/v1/search/glob",
+            json!({ "uri": "viking://resources", "pattern": "**/s*.md" }),
+        );
+        assert_eq!(
+            glob["result"]["matches"],
+            json!(["viking://resources/docs/style.md"])
+        );
+        let (_, glob) = call(
+            &server,
+            Method::POST,
+            "/api/v1/search/glob",
+            json!({ "uri": "viking://resources", "pattern": "d*", "node_limit": 0 }),
+        );
+        assert_eq!(
+            glob["result"]["matches"],
+            json!(["viking://resources/docs"])
+        );
+        let (_, glob) = call(
+            &server,
+            Method::POST,
+            "/api/v1/search/glob",
+            json!({ "uri": "viking://resources", "pattern": "**/*.md", "node_limit": 1 }),
+        );
+        assert_eq!(glob["result"]["count"], 1);
+    }
+
+    #[test]
+    fn sessions_keep_messages_until_committed() {
+        let server = MockServer::new(None).unwrap();
+        let (_, created) = call(
+            &server,
+            Method::POST,
+            "/api/v1/sessions",
+            json!({ "session_id": "s1" }),
+        );
+        assert_eq!(created["result"]["session_id"], "s1");
+        let (status, _) = call(
+            &server,
+            Method::POST,
+            "/api/v1/sessions",
+            json!({ "session_id": "s1" }),
+        );
+        assert_eq!(status, 409);
+
+        call(
+            &server,
+            Method::POST,
+            "/api/v1/sessions/s1/messages",
+            json!({ "role": "user", "content": "hi" }),
+        );
+        let (_, batch) = call(
+            &server,
+            Method::POST,
+            "/api/v1/sessions/s1/messages/batch",
+            json!({ "messages": [{ "role": "assistant", "content": "hello" }] }),
+        );
+        assert_eq!(
+            (
+                batch["result"]["message_count"].as_u64(),
+                batch["result"]["added"].as_u64()
+            ),
+            (Some(2), Some(1))
+        );
+        let (_, context) = call(
+            &server,
+            Method::GET,
+            "/api/v1/sessions/s1/context",
+            Value::Null,
+        );
+        assert_eq!(
+            context["result"]["messages"][1]["parts"][0]["text"],
+            "hello"
+        );
+
+        let (_, commit) = call(
+            &server,
+            Method::POST,
+            "/api/v1/sessions/s1/commit",
+            Value::Null,
+        );
+        assert_eq!(
+            commit["result"]["archive_uri"],
+            "viking://session/s1/history/archive_001"
+        );
+        let (_, meta) = call(&server, Method::GET, "/api/v1/sessions/s1", Value::Null);
+        assert_eq!(
+            (
+                meta["result"]["message_count"].as_u64(),
+                meta["result"]["commit_count"].as_u64()
+            ),
+            (Some(0), Some(1))
+        );
+
+        let (status, _) = call(&server, Method::GET, "/api/v1/sessions/s2", Value::Null);
+        assert_eq!(status, 404);
+        let (status, _) = call(
+            &server,
+            Method::GET,
+            "/api/v1/sessions/s2?auto_create=true",
+            Value::Null,
+        );
+        assert_eq!(status, 200);
+    }
+
+    #[test]
+    fn multipart_file_finds_the_uploaded_part() {
+        let body = b"--xyz\r\nContent-Disposition: form-data; name=\"telemetry\"\r\n\r\nfalse\r\n--xyz\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.md\"\r\n\r\nline\r\n\r\n--xyz--\r\n";
+        assert_eq!(
+            multipart_file("multipart/form-data; boundary=xyz", body),
+            Some(("a.md".to_string(), b"line\r\n".to_vec()))
+        );
+        assert_eq!(
+            multipart_file("multipart/form-data; boundary=abc", body),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn the_client_works_against_the_server() {
+        let dir = fixtures();
+        let server = Arc::new(MockServer::new(Some(dir.path())).unwrap());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(serve(listener, server));
+        let client = HttpClient::new(url, None, None, None, None, 5.0, false, None);
+
+        let upload = dir.path().join("notes.md");
+        std::fs::write(&upload, "Release notes").unwrap();
+        let added = client
+            .add_resource(
+                upload.to_str().unwrap(),
+                None,
+                None,
+                None,
+                "",
+                "",
+                false,
+                None,
+                false,
+                None,
+                None,
+                None,
+                false,
+                0.0,
+                None,
+                false,
+                false,
+            )
+            .await
+            .unwrap();
+        assert_eq!(added["root_uri"], "viking://resources/notes.md");
+        client
+            .write(
+                "viking://resources/notes.md",
+                "\nMore",
+                "append",
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            client.read("viking://resources/notes.md").await.unwrap(),
+            "Release notes\nMore"
+        );
+
+        let listing = client
+            .ls(
+                "viking://resources",
+                true,
+                false,
+                "original",
+                256,
+                false,
+                256,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            listing,
+            json!(["viking://resources/docs", "viking://resources/notes.md"])
+        );
+
+        let found = client
+            .find(
+                "release".to_string(),
+                String::new(),
+                None,
+                10,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                &SearchScoring::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(found["resources"][0]["uri"], "viking://resources/notes.md");
+
+        let session: Value = client.post("/api/v1/sessions", &json!({})).await.unwrap();
+        let id = session["session_id"].as_str().unwrap();
+        let added: Value = client
+            .post(
+                &format!("/api/v1/sessions/{id}/messages"),
+                &json!({ "role": "user", "content": "hi" }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(added["message_count"], 1);
+    }
+}
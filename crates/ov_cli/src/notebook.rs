@@ -0,0 +1,240 @@
+//! Jupyter notebook conversion for `ov add-resource`.
+//!
+//! A `.ipynb` file is JSON, and the server parses it as such: cell sources end
+//! up as escaped strings next to base64 images and execution metadata. Before
+//! upload, notebooks are rendered as Markdown instead. Markdown cells are kept
+//! as they are, code cells become fenced blocks in the kernel's language, and
+//! outputs are summarized: text is kept up to [`MAX_OUTPUT_LINES`] lines,
+//! errors are reduced to their name and message, and images and other binary
+//! outputs are noted but left out.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+use tempfile::TempDir;
+use url::Url;
+
+use crate::error::{Error, Result};
+use crate::html_markdown::fragment_markdown;
+
+/// Most lines of one output kept in the Markdown.
+pub const MAX_OUTPUT_LINES: usize = 20;
+
+/// Whether `path` names a notebook by its extension.
+pub fn is_notebook(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("ipynb"))
+}
+
+/// The name a converted notebook is uploaded as: `analysis.ipynb` → `analysis.md`.
+pub fn markdown_name(name: &str) -> String {
+    let stem = name
+        .len()
+        .checked_sub(".ipynb".len())
+        .filter(|&at| name.is_char_boundary(at) && name[at..].eq_ignore_ascii_case(".ipynb"))
+        .map_or(name, |at| &name[..at]);
+    format!("{stem}.md")
+}
+
+/// A notebook converted into a temporary directory, removed on drop.
+pub struct Converted {
+    _dir: TempDir,
+    pub path: PathBuf,
+}
+
+/// Convert the notebook at `path` into a Markdown file of the same stem.
+pub fn convert_file(path: &Path) -> Result<Converted> {
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("notebook.ipynb");
+    let markdown = to_markdown(&std::fs::read_to_string(path)?).map_err(|e| {
+        Error::Client(format!(
+            "Failed to convert notebook {}: {e} (use --no-convert to upload it as is)",
+            path.display()
+        ))
+    })?;
+    let dir = tempfile::tempdir()?;
+    let converted = dir.path().join(markdown_name(name));
+    std::fs::write(&converted, markdown)?;
+    Ok(Converted {
+        _dir: dir,
+        path: converted,
+    })
+}
+
+/// Render a notebook's JSON as Markdown.
+pub fn to_markdown(json: &str) -> Result<String> {
+    let notebook: Value = serde_json::from_str(json)?;
+    let metadata = &notebook["metadata"];
+    let language = metadata["kernelspec"]["language"]
+        .as_str()
+        .or_else(|| metadata["language_info"]["name"].as_str())
+        .unwrap_or("python");
+    // nbformat 4 keeps cells at the top level; 3 nests them in worksheets.
+    let cells: Vec<&Value> = match notebook["cells"].as_array() {
+        Some(cells) => cells.iter().collect(),
+        None => notebook["worksheets"]
+            .as_array()
+            .ok_or_else(|| Error::Parse("not a Jupyter notebook: no cells".to_string()))?
+            .iter()
+            .filter_map(|sheet| sheet["cells"].as_array())
+            .flatten()
+            .collect(),
+    };
+
+    let mut blocks = Vec::new();
+    for cell in cells {
+        let source = text(cell.get("source").unwrap_or(&cell["input"]));
+        match cell["cell_type"].as_str() {
+            Some("code") => {
+                if !source.trim().is_empty() {
+                    blocks.push(fenced(language, source.trim_end()));
+                }
+                let outputs = cell["outputs"].as_array().map(Vec::as_slice);
+                blocks.extend(outputs.unwrap_or_default().iter().filter_map(output));
+            }
+            _ if source.trim().is_empty() => {}
+            _ => blocks.push(source.trim_end().to_string()),
+        }
+    }
+    Ok(blocks.join("\n\n") + "\n")
+}
+
+/// A notebook string field, stored either whole or as a list of lines.
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(lines) => lines.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+fn fenced(language: &str, code: &str) -> String {
+    // A longer fence than any backtick run in the code keeps it closed.
+    let longest = code
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    let fence = "`".repeat(longest.max(2) + 1);
+    format!("{fence}{language}\n{code}\n{fence}")
+}
+
+/// `text` without ANSI escapes, cut to [`MAX_OUTPUT_LINES`] lines.
+fn summarized(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // CSI sequences end at the first letter, such as `m` for colors.
+            chars.find(char::is_ascii_alphabetic);
+        } else {
+            plain.push(c);
+        }
+    }
+    let lines: Vec<&str> = plain.trim_end().lines().collect();
+    if lines.len() <= MAX_OUTPUT_LINES {
+        return lines.join("\n");
+    }
+    format!(
+        "{}\n... ({} more lines)",
+        lines[..MAX_OUTPUT_LINES].join("\n"),
+        lines.len() - MAX_OUTPUT_LINES
+    )
+}
+
+/// One code cell output as Markdown, or `None` when it has nothing to show.
+fn output(output: &Value) -> Option<String> {
+    let block = |text: &str| {
+        let text = summarized(text);
+        (!text.trim().is_empty()).then(|| format!("Output:\n\n{}", fenced("text", &text)))
+    };
+    match output["output_type"].as_str()? {
+        "stream" => block(&text(&output["text"])),
+        "error" | "pyerr" => Some(format!(
+            "Error: `{}: {}`",
+            output["ename"].as_str().unwrap_or("Error"),
+            summarized(output["evalue"].as_str().unwrap_or_default()).replace('`', "'")
+        )),
+        _ => {
+            let data = output.get("data").unwrap_or(output);
+            if let Some(markdown) = data.get("text/markdown") {
+                return Some(text(markdown).trim().to_string());
+            }
+            if let Some(plain) = data.get("text/plain").or_else(|| data.get("text")) {
+                return block(&text(plain));
+            }
+            if let Some(html) = data.get("text/html").or_else(|| data.get("html")) {
+                let base = Url::parse("about:blank").expect("valid base URL");
+                return Some(fragment_markdown(&text(html), &base).trim().to_string());
+            }
+            let kinds: Vec<&str> = data
+                .as_object()?
+                .keys()
+                .filter(|kind| kind.contains('/'))
+                .map(String::as_str)
+                .collect();
+            (!kinds.is_empty()).then(|| format!("_[{} output omitted]_", kinds.join(", ")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cells_become_markdown_with_summarized_outputs() {
+        let notebook = serde_json::json!({
+            "nbformat": 4,
+            "metadata": {"kernelspec": {"language": "python", "name": "python3"}},
+            "cells": [
+                {"cell_type": "markdown", "source": ["# Churn analysis\n", "Load the data."]},
+                {
+                    "cell_type": "code",
+                    "execution_count": 1,
+                    "source": "df = load()\ndf.shape",
+                    "outputs": [
+                        {"output_type": "stream", "name": "stdout", "text": ["\u{1b}[32mloaded\u{1b}[0m\n"]},
+                        {"output_type": "execute_result", "data": {"text/plain": ["(120, 4)"]}},
+                    ]
+                },
+                {
+                    "cell_type": "code",
+                    "source": "plot(df)",
+                    "outputs": [
+                        {"output_type": "display_data", "data": {"image/png": "iVBORw0KGgo="}},
+                        {"output_type": "error", "ename": "KeyError", "evalue": "'churn'", "traceback": ["..."]},
+                    ]
+                },
+                {"cell_type": "code", "source": "", "outputs": []},
+            ]
+        });
+
+        assert_eq!(
+            to_markdown(&notebook.to_string()).unwrap(),
+            "# Churn analysis\nLoad the data.\n\n\
+             ```python\ndf = load()\ndf.shape\n```\n\n\
+             Output:\n\n```text\nloaded\n```\n\n\
+             Output:\n\n```text\n(120, 4)\n```\n\n\
+             ```python\nplot(df)\n```\n\n\
+             _[image/png output omitted]_\n\n\
+             Error: `KeyError: 'churn'`\n"
+        );
+    }
+
+    #[test]
+    fn long_outputs_are_cut_and_fences_stay_closed() {
+        let long: String = (1..=25).map(|n| format!("line {n}\n")).collect();
+        let summary = summarized(&long);
+        assert!(summary.ends_with("line 20\n... (5 more lines)"));
+
+        assert_eq!(
+            fenced("markdown", "```js\nx\n```"),
+            "````markdown\n```js\nx\n```\n````"
+        );
+        assert_eq!(markdown_name("Report.IPYNB"), "Report.md");
+        assert!(to_markdown("{\"metadata\": {}}").is_err());
+    }
+}
@@ -1,42 +1,430 @@
+use futures::future::BoxFuture;
 use serde::Serialize;
 use serde_json::{Value, json};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
+use crate::error::{Error, Result};
+use crate::query::Query;
+use crate::template::Template;
 use crate::theme;
 use colored::Colorize;
 
 const MAX_COL_WIDTH: usize = 256;
+const NDJSON_COLLECTION_KEYS: &[&str] = &[
+    "memories",
+    "resources",
+    "skills",
+    "results",
+    "items",
+    "matches",
+    "entries",
+];
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum OutputFormat {
     Table,
     Json,
+    /// One JSON record per line, for streaming into `jq` and friends.
+    Ndjson,
+}
+
+impl OutputFormat {
+    /// Whether the caller should skip human table rendering entirely.
+    pub fn is_json(self) -> bool {
+        matches!(self, OutputFormat::Json | OutputFormat::Ndjson)
+    }
 }
 
 impl From<&str> for OutputFormat {
     fn from(s: &str) -> Self {
         match s {
             "json" => OutputFormat::Json,
+            "ndjson" | "jsonl" => OutputFormat::Ndjson,
             _ => OutputFormat::Table,
         }
     }
 }
 
+static QUERY: OnceLock<Query> = OnceLock::new();
+static TEMPLATE: OnceLock<Template> = OnceLock::new();
+
+/// Install the global `--query` filter. Called once from `main` after parsing.
+pub fn set_query(query: Query) {
+    let _ = QUERY.set(query);
+}
+
+/// Install the global `--template` renderer. Called once from `main` after parsing.
+pub fn set_template(template: Template) {
+    let _ = TEMPLATE.set(template);
+}
+
 pub fn output_success<T: Serialize>(result: T, format: OutputFormat, compact: bool) {
-    if matches!(format, OutputFormat::Json) {
-        if compact {
-            println!("{}", compact_success_value(result));
-        } else {
-            println!(
+    let redaction = crate::redact::take_report();
+    if redaction.is_none() && !crate::wasm_plugin::transforms_output() {
+        return print_success(result, format, compact);
+    }
+    let mut value = serde_json::to_value(result).unwrap_or(Value::Null);
+    if let (Some(redaction), Value::Object(fields)) = (redaction, &mut value) {
+        fields.insert("redaction".to_string(), redaction);
+    }
+    if !crate::wasm_plugin::transforms_output() {
+        return print_success(value, format, compact);
+    }
+    // Fail closed: a plugin that may be redacting output must not be skipped.
+    match crate::wasm_plugin::transform_output(value) {
+        Ok(value) => print_success(value, format, compact),
+        Err(message) => {
+            eprintln!("{}: {}", theme::error("Error").bold(), message);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn print_success<T: Serialize>(result: T, format: OutputFormat, compact: bool) {
+    if QUERY.get().is_some() || TEMPLATE.get().is_some() {
+        print_scripted_results(result, format);
+        return;
+    }
+
+    match format {
+        OutputFormat::Json => {
+            if compact {
+                println!("{}", compact_success_value(result));
+            } else {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&result).unwrap_or_default()
+                );
+            }
+        }
+        OutputFormat::Ndjson => print_ndjson(result),
+        OutputFormat::Table => crate::pager::page_or_print(&render_table(result, compact)),
+    }
+}
+
+/// Apply `--query` and then `--template` to the payload.
+///
+/// Without a template, every value the query produces is printed; strings are
+/// printed raw (like `jq -r`) so shell scripts can consume them directly. With
+/// a template, each value is split into records the same way NDJSON output
+/// splits them and rendered one line per record.
+fn print_scripted_results<T: Serialize>(result: T, format: OutputFormat) {
+    let value = strip_profile(serde_json::to_value(result).unwrap_or(Value::Null));
+    let values = match QUERY.get() {
+        Some(query) => query.apply(&value).unwrap_or_else(|message| {
+            exit_with_script_error(&format!("--query '{}'", query.source()), &message)
+        }),
+        None => vec![value],
+    };
+
+    if let Some(template) = TEMPLATE.get() {
+        for value in &values {
+            for record in ndjson_records(value) {
+                let line = template
+                    .render(record)
+                    .unwrap_or_else(|message| exit_with_script_error("--template", &message));
+                println!("{line}");
+            }
+        }
+        return;
+    }
+
+    for value in values {
+        match value {
+            Value::String(s) => println!("{s}"),
+            other if matches!(format, OutputFormat::Ndjson) => {
+                println!("{}", serde_json::to_string(&other).unwrap_or_default())
+            }
+            other => println!(
                 "{}",
-                serde_json::to_string_pretty(&result).unwrap_or_default()
-            );
+                serde_json::to_string_pretty(&other).unwrap_or_default()
+            ),
         }
+    }
+}
+
+fn exit_with_script_error(flag: &str, message: &str) -> ! {
+    eprintln!("{} {}: {}", theme::error("Error").bold(), flag, message);
+    std::process::exit(1);
+}
+
+/// Drop the `profile` envelope so queries address the payload itself.
+fn strip_profile(value: Value) -> Value {
+    let Value::Object(mut obj) = value else {
+        return value;
+    };
+    if obj.remove("profile").is_none() {
+        return Value::Object(obj);
+    }
+    if obj.len() == 1 && obj.contains_key("result") {
+        obj.remove("result").unwrap_or(Value::Null)
     } else {
-        print_table(result, compact);
+        Value::Object(obj)
+    }
+}
+
+/// Write each record on its own line, flushing as we go so downstream
+/// consumers can start working before the last record is printed.
+fn print_ndjson<T: Serialize>(result: T) {
+    let value = serde_json::to_value(result).unwrap_or(Value::Null);
+    for record in ndjson_records(&value) {
+        if !write_ndjson_line(record) {
+            break;
+        }
     }
 }
 
+/// Print one record on a line of its own and flush it. `false` means stdout
+/// is gone: a closed pipe (e.g. `| head`) just means nobody wants the rest.
+fn write_ndjson_line(record: &Value) -> bool {
+    use std::io::Write;
+
+    let line = serde_json::to_string(record).unwrap_or_default();
+    let stdout = std::io::stdout();
+    let mut handle = stdout.lock();
+    handle
+        .write_all(line.as_bytes())
+        .and_then(|_| handle.write_all(b"\n"))
+        .and_then(|_| handle.flush())
+        .is_ok()
+}
+
+/// Whether `-o` output shows only data: JSON, NDJSON, `--query` or
+/// `--template`, with nothing meant for a human mixed in.
+pub fn is_machine_readable(format: OutputFormat) -> bool {
+    format.is_json() || QUERY.get().is_some() || TEMPLATE.get().is_some()
+}
+
+/// Prints the records of a response while its body is still arriving, for
+/// commands whose NDJSON output is the response as the server sent it.
+///
+/// Set one on the client with `HttpClient::with_ndjson_stream`; afterwards
+/// [`NdjsonStream::printed`] tells whether the command's output is done.
+#[derive(Default)]
+pub struct NdjsonStream {
+    printed: AtomicBool,
+    decorate: Option<DecorateRecord>,
+}
+
+/// Adds to one record of an [`NdjsonStream`] before it is printed, or drops
+/// it by returning `None`.
+pub type DecorateRecord =
+    Box<dyn Fn(Value) -> BoxFuture<'static, Result<Option<Value>>> + Send + Sync>;
+
+impl NdjsonStream {
+    /// A stream when the output can go straight from the response to stdout:
+    /// NDJSON without `--query`, `--template`, or an output plugin.
+    pub fn for_output(format: OutputFormat) -> Option<Arc<Self>> {
+        Self::decorated(format, None)
+    }
+
+    /// [`Self::for_output`], passing each record through `decorate`, for
+    /// options such as `--tokens` or `--tags` that work record by record.
+    pub fn decorated(format: OutputFormat, decorate: Option<DecorateRecord>) -> Option<Arc<Self>> {
+        let direct = format == OutputFormat::Ndjson
+            && QUERY.get().is_none()
+            && TEMPLATE.get().is_none()
+            && !crate::wasm_plugin::transforms_output();
+        direct.then(|| {
+            Arc::new(Self {
+                printed: AtomicBool::new(false),
+                decorate,
+            })
+        })
+    }
+
+    /// Whether a response's records were printed, so the caller must not
+    /// print the result again.
+    pub fn printed(&self) -> bool {
+        self.printed.load(Ordering::Relaxed)
+    }
+
+    /// Read the whole body of `response`, printing each record as soon as it
+    /// is complete. A body without record collections is left for the caller
+    /// to print.
+    pub async fn read(&self, mut response: reqwest::Response) -> Result<Vec<u8>> {
+        let mut body = Vec::new();
+        let mut splitter = RecordSplitter::default();
+        let mut open = true;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| Error::from_reqwest("Failed to read response body", e))?
+        {
+            body.extend_from_slice(&chunk);
+            let mut records = Vec::new();
+            splitter.feed(&chunk, &mut |record| {
+                records.push(serde_json::from_slice::<Value>(record));
+            });
+            // A record that does not parse means the body is not JSON, which
+            // the caller reports once the body is complete.
+            for record in records.into_iter().flatten() {
+                if !open {
+                    break;
+                }
+                let record = match &self.decorate {
+                    Some(decorate) => decorate(record).await?,
+                    None => Some(record),
+                };
+                if let Some(record) = record {
+                    open = write_ndjson_line(&record);
+                }
+            }
+        }
+        if splitter.found_records {
+            self.printed.store(true, Ordering::Relaxed);
+        }
+        Ok(body)
+    }
+}
+
+/// Cuts the records out of a JSON body fed to it in pieces, the way
+/// [`ndjson_records`] finds them in a parsed one: the elements of a top-level
+/// or `result` array, or of the well-known collections beside or in `result`.
+#[derive(Default)]
+struct RecordSplitter {
+    stack: Vec<Frame>,
+    in_string: bool,
+    escaped: bool,
+    reading_key: bool,
+    /// The stack depth of the record collection being read, if any.
+    records_depth: Option<usize>,
+    record: Vec<u8>,
+    found_records: bool,
+}
+
+struct Frame {
+    array: bool,
+    expect_key: bool,
+    key: Vec<u8>,
+}
+
+impl RecordSplitter {
+    fn feed(&mut self, chunk: &[u8], emit: &mut impl FnMut(&[u8])) {
+        for &byte in chunk {
+            if let Some(depth) = self.records_depth {
+                if self.stack.len() == depth && !self.in_string && matches!(byte, b',' | b']') {
+                    let record = self.record.trim_ascii();
+                    if !record.is_empty() {
+                        emit(record);
+                    }
+                    self.record.clear();
+                    if byte == b']' {
+                        self.records_depth = None;
+                    }
+                } else {
+                    self.record.push(byte);
+                }
+            }
+            self.track(byte);
+        }
+    }
+
+    fn track(&mut self, byte: u8) {
+        if self.in_string {
+            if self.escaped {
+                self.escaped = false;
+            } else if byte == b'\\' {
+                self.escaped = true;
+            } else if byte == b'"' {
+                self.in_string = false;
+                self.reading_key = false;
+                return;
+            }
+            if self.reading_key
+                && let Some(frame) = self.stack.last_mut()
+            {
+                frame.key.push(byte);
+            }
+            return;
+        }
+        match byte {
+            b'"' => {
+                self.in_string = true;
+                if let Some(frame) = self.stack.last_mut()
+                    && frame.expect_key
+                {
+                    frame.key.clear();
+                    self.reading_key = true;
+                }
+            }
+            b':' => {
+                if let Some(frame) = self.stack.last_mut() {
+                    frame.expect_key = false;
+                }
+            }
+            b',' => {
+                if let Some(frame) = self.stack.last_mut() {
+                    frame.expect_key = !frame.array;
+                }
+            }
+            b'{' | b'[' => {
+                let records = byte == b'[' && self.records_depth.is_none() && self.at_records();
+                self.stack.push(Frame {
+                    array: byte == b'[',
+                    expect_key: byte == b'{',
+                    key: Vec::new(),
+                });
+                if records {
+                    self.records_depth = Some(self.stack.len());
+                    self.found_records = true;
+                }
+            }
+            b'}' | b']' => {
+                self.stack.pop();
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether an array starting here holds records.
+    fn at_records(&self) -> bool {
+        let key = |frame: &Frame| String::from_utf8_lossy(&frame.key).into_owned();
+        let is_collection = |frame: &Frame| NDJSON_COLLECTION_KEYS.contains(&key(frame).as_str());
+        match self.stack.as_slice() {
+            [] => true,
+            [root] => !root.array && (key(root) == "result" || is_collection(root)),
+            [root, result] => {
+                !root.array && !result.array && key(root) == "result" && is_collection(result)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Split a response into the records a line-oriented consumer cares about.
+///
+/// Arrays yield their elements. Objects holding well-known result collections
+/// (for example `memories`/`resources`/`skills` from `find`) yield the elements
+/// of every collection in order; counters and the `profile` block are dropped.
+/// Anything else is emitted as a single record.
+fn ndjson_records(value: &Value) -> Vec<&Value> {
+    if let Some(items) = value.as_array() {
+        return items.iter().collect();
+    }
+
+    let Some(obj) = value.as_object() else {
+        return vec![value];
+    };
+
+    if let Some(result) = obj.get("result")
+        && obj.keys().all(|k| k == "result" || k == "profile")
+    {
+        return ndjson_records(result);
+    }
+
+    let collections: Vec<&Vec<Value>> = NDJSON_COLLECTION_KEYS
+        .iter()
+        .filter_map(|key| obj.get(*key).and_then(Value::as_array))
+        .collect();
+    if collections.is_empty() {
+        return vec![value];
+    }
+    collections.into_iter().flatten().collect()
+}
+
 fn compact_success_value<T: Serialize>(result: T) -> Value {
     let mut obj = match serde_json::to_value(result).unwrap_or(Value::Null) {
         Value::Object(obj) => obj,
@@ -60,39 +448,28 @@ fn compact_success_value<T: Serialize>(result: T) -> Value {
     json!({ "status": "ok", "result": result, "profile": profile })
 }
 
-fn print_table<T: Serialize>(result: T, compact: bool) {
+fn render_table<T: Serialize>(result: T, compact: bool) -> String {
     // Convert to json Value for processing
     let value = match serde_json::to_value(&result) {
         Ok(v) => v,
         Err(_) => {
-            if compact {
-                println!("{}", serde_json::to_string(&result).unwrap_or_default());
-            } else {
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&result).unwrap_or_default()
-                );
-            }
-            return;
+            return render_json_fallback(&result, compact);
         }
     };
 
     // Handle string result
     if let Some(s) = value.as_str() {
-        println!("{}", theme::body(s));
-        return;
+        return theme::body(s).to_string();
     }
 
     // Handle array of objects
     if let Some(items) = value.as_array() {
         if !items.is_empty() {
             if let Some(table) = format_array_to_table(items, compact) {
-                println!("{}", table);
-                return;
+                return table;
             }
         } else {
-            println!("{}", theme::muted("(empty)"));
-            return;
+            return theme::muted("(empty)").to_string();
         }
     }
 
@@ -100,13 +477,11 @@ fn print_table<T: Serialize>(result: T, compact: bool) {
     if let Some(obj) = value.as_object() {
         if !obj.is_empty() {
             if let Some(rendered) = render_session_context(obj, compact) {
-                println!("{}", append_profile_section(rendered, obj));
-                return;
+                return append_profile_section(rendered, obj);
             }
 
             if let Some(rendered) = render_session_archive(obj, compact) {
-                println!("{}", append_profile_section(rendered, obj));
-                return;
+                return append_profile_section(rendered, obj);
             }
 
             // Rule 5: ComponentStatus (name + is_healthy + status)
@@ -121,11 +496,7 @@ fn print_table<T: Serialize>(result: T, compact: bool) {
                 };
                 let name = obj["name"].as_str().unwrap_or("");
                 let status = obj["status"].as_str().unwrap_or("");
-                println!(
-                    "{}",
-                    append_profile_section(render_component_status(name, health, status), obj)
-                );
-                return;
+                return append_profile_section(render_component_status(name, health, status), obj);
             }
 
             // Rule 6: SystemStatus (is_healthy + components)
@@ -164,13 +535,11 @@ fn print_table<T: Serialize>(result: T, compact: bool) {
                         }
                     }
                 }
-                println!("{}", append_profile_section(lines.join("\n"), obj));
-                return;
+                return append_profile_section(lines.join("\n"), obj);
             }
 
             if let Some(rendered) = value_to_table_with_profile(&value, compact) {
-                println!("{}", rendered);
-                return;
+                return rendered;
             }
 
             // Extract list fields
@@ -216,8 +585,7 @@ fn print_table<T: Serialize>(result: T, compact: bool) {
                     rows.push(serde_json::Value::Object(row));
                 }
                 if let Some(table) = format_array_to_table(&rows, compact) {
-                    println!("{}", append_profile_section(table, obj));
-                    return;
+                    return append_profile_section(table, obj);
                 }
             }
 
@@ -226,8 +594,7 @@ fn print_table<T: Serialize>(result: T, compact: bool) {
             {
                 let (_key, items) = &dict_lists[0];
                 if let Some(table) = format_array_to_table(items, compact) {
-                    println!("{}", append_profile_section(table, obj));
-                    return;
+                    return append_profile_section(table, obj);
                 }
             }
 
@@ -254,8 +621,7 @@ fn print_table<T: Serialize>(result: T, compact: bool) {
                 }
                 if !merged.is_empty() {
                     if let Some(table) = format_array_to_table(&merged, compact) {
-                        println!("{}", append_profile_section(table, obj));
-                        return;
+                        return append_profile_section(table, obj);
                     }
                 }
             }
@@ -285,20 +651,20 @@ fn print_table<T: Serialize>(result: T, compact: bool) {
                         style_table_value(&content, is_uri)
                     ));
                 }
-                println!("{}", append_profile_section(output, obj));
-                return;
+                return append_profile_section(output, obj);
             }
         }
     }
 
     // Default: JSON output
+    render_json_fallback(&result, compact)
+}
+
+fn render_json_fallback<T: Serialize>(result: &T, compact: bool) -> String {
     if compact {
-        println!("{}", serde_json::to_string(&result).unwrap_or_default());
+        serde_json::to_string(result).unwrap_or_default()
     } else {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&result).unwrap_or_default()
-        );
+        serde_json::to_string_pretty(result).unwrap_or_default()
     }
 }
 
@@ -377,6 +743,9 @@ pub fn render_table_with_optional_profile(
     value: &serde_json::Value,
     compact: bool,
 ) -> Option<String> {
+    if value.is_array() {
+        return value_to_table(value, compact);
+    }
     value_to_table_with_profile(value, compact)
 }
 
@@ -720,13 +1089,130 @@ fn summarize_message_content(parts: Option<&Vec<serde_json::Value>>) -> String {
     chunks.join(" | ")
 }
 
+static TABLE_OPTIONS: OnceLock<TableOptions> = OnceLock::new();
+
+/// Layout of list tables: column selection and ordering (`--columns` /
+/// `--sort`) plus terminal-only decoration.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TableOptions {
+    pub columns: Vec<String>,
+    pub sort: Option<SortKey>,
+    /// Draw box borders; only enabled when stdout is an interactive terminal.
+    pub bordered: bool,
+    /// Fit tables to this many columns; `None` when stdout is not a terminal.
+    pub terminal_width: Option<usize>,
+    /// Wrap long cells onto extra lines instead of truncating them (`--wrap`).
+    pub wrap: bool,
+    /// Never truncate or fit cells (`--wide` / `--no-truncate`).
+    pub no_truncate: bool,
+}
+
+impl TableOptions {
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty() && self.sort.is_none()
+    }
+
+    /// Keep the requested columns in the requested order. Tables that carry none
+    /// of them (for example a secondary table in a combined response) are left
+    /// untouched rather than rendered empty.
+    fn select_columns(
+        &self,
+        keys: Vec<String>,
+        available: &std::collections::HashSet<String>,
+    ) -> Vec<String> {
+        let selected: Vec<String> = self
+            .columns
+            .iter()
+            .filter(|column| available.contains(column.as_str()))
+            .cloned()
+            .collect();
+        if selected.is_empty() { keys } else { selected }
+    }
+}
+
+/// A `--sort` key; a leading `-` sorts descending.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SortKey {
+    pub column: String,
+    pub descending: bool,
+}
+
+impl SortKey {
+    pub fn parse(spec: &str) -> Option<Self> {
+        let spec = spec.trim();
+        let (column, descending) = match spec.strip_prefix('-') {
+            Some(column) => (column, true),
+            None => (spec.strip_prefix('+').unwrap_or(spec), false),
+        };
+        (!column.is_empty()).then(|| Self {
+            column: column.to_string(),
+            descending,
+        })
+    }
+
+    /// Stable sort; rows missing the column always sink to the bottom.
+    fn apply(&self, rows: &mut [&serde_json::Value]) {
+        rows.sort_by(|a, b| {
+            let a = a.get(&self.column).filter(|v| !v.is_null());
+            let b = b.get(&self.column).filter(|v| !v.is_null());
+            match (a, b) {
+                (None, None) => std::cmp::Ordering::Equal,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (Some(a), Some(b)) => {
+                    let ordering = compare_cell_values(a, b);
+                    if self.descending {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    }
+                }
+            }
+        });
+    }
+}
+
+fn compare_cell_values(a: &serde_json::Value, b: &serde_json::Value) -> std::cmp::Ordering {
+    let as_number = |v: &serde_json::Value| match v {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.trim().parse::<f64>().ok(),
+        _ => None,
+    };
+    match (as_number(a), as_number(b)) {
+        (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal),
+        _ => format_value(a).cmp(&format_value(b)),
+    }
+}
+
+/// Install the global `--columns` / `--sort` options. Called once from `main`.
+pub fn set_table_options(options: TableOptions) {
+    let _ = TABLE_OPTIONS.set(options);
+}
+
+/// Whether the user asked for a specific table layout. Card-style renderers
+/// step aside in that case so the generic table can honour the request.
+pub fn table_layout_requested() -> bool {
+    TABLE_OPTIONS
+        .get()
+        .is_some_and(|options| !options.is_empty())
+}
+
 struct ColumnInfo {
-    max_width: usize,          // Max width for alignment (capped at 120)
+    max_width: usize,          // Max width for alignment (capped at MAX_COL_WIDTH)
     is_numeric: bool,          // True if all values in column are numeric
     is_unbounded_column: bool, // True if column should respect server-side length
 }
 
-fn format_array_to_table(items: &Vec<serde_json::Value>, compact: bool) -> Option<String> {
+fn format_array_to_table(items: &[serde_json::Value], compact: bool) -> Option<String> {
+    let options = TABLE_OPTIONS.get().cloned().unwrap_or_default();
+    format_array_to_table_with_options(items, compact, &options)
+}
+
+fn format_array_to_table_with_options(
+    items: &[serde_json::Value],
+    compact: bool,
+    options: &TableOptions,
+) -> Option<String> {
     if items.is_empty() {
         return None;
     }
@@ -760,8 +1246,8 @@ fn format_array_to_table(items: &Vec<serde_json::Value>, compact: bool) -> Optio
         return None;
     }
 
-    // Filter out empty columns when compact is true
-    let filtered_keys: Vec<String> = if compact {
+    // Filter out empty columns when compact is true, unless the user picked columns
+    let filtered_keys: Vec<String> = if compact && options.columns.is_empty() {
         keys.iter()
             .filter(|key| {
                 items.iter().any(|item| {
@@ -785,9 +1271,19 @@ fn format_array_to_table(items: &Vec<serde_json::Value>, compact: bool) -> Optio
         return None;
     }
 
-    let keys = filtered_keys;
+    let keys = options.select_columns(filtered_keys, &key_set);
+
+    let mut items: Vec<&serde_json::Value> = items.iter().collect();
+    if let Some(sort) = &options.sort {
+        sort.apply(&mut items);
+    }
 
     // First pass: analyze columns
+    let width_cap = if options.no_truncate {
+        usize::MAX
+    } else {
+        MAX_COL_WIDTH
+    };
     let mut column_info: Vec<ColumnInfo> = Vec::new();
 
     for key in &keys {
@@ -795,13 +1291,13 @@ fn format_array_to_table(items: &Vec<serde_json::Value>, compact: bool) -> Optio
         let mut is_numeric = true;
         let mut max_width = key.width(); // Start with header width
 
-        for item in items {
+        for item in &items {
             if let Some(obj) = item.as_object() {
                 if let Some(value) = obj.get(key) {
                     let formatted = format_value(value);
                     let display_width = formatted.width();
 
-                    max_width = max_width.max(display_width.min(MAX_COL_WIDTH));
+                    max_width = max_width.max(display_width.min(width_cap));
 
                     // Check if numeric
                     if is_numeric && !is_numeric_value(value) {
@@ -818,34 +1314,64 @@ fn format_array_to_table(items: &Vec<serde_json::Value>, compact: bool) -> Optio
         });
     }
 
-    // Second pass: format rows
-    let mut output = String::new();
+    let fit_to_terminal = match options.terminal_width {
+        Some(terminal_width) if !options.no_truncate => {
+            shrink_columns_to_width(&mut column_info, &keys, terminal_width, options.bordered);
+            true
+        }
+        _ => false,
+    };
 
-    // Header row
-    let header_cells: Vec<String> = keys
+    // Second pass: format rows. Each cell is a list of (display width, styled
+    // text) lines so wrapped cells and the bordered layout can line up.
+    let header_cells: Vec<TableCell> = keys
         .iter()
         .enumerate()
         .map(|(i, k)| {
-            theme::heading(pad_cell(k, column_info[i].max_width, false))
-                .bold()
-                .to_string()
+            let padded = pad_cell(k, column_info[i].max_width, false);
+            vec![(padded.width(), theme::heading(padded).bold().to_string())]
         })
         .collect();
-    output.push_str(&header_cells.join("  "));
-    output.push('\n');
 
-    // Data rows
-    for item in items {
+    let mut rows: Vec<Vec<TableCell>> = Vec::new();
+    for item in &items {
         if let Some(obj) = item.as_object() {
-            let row_cells: Vec<String> = keys
+            let row_cells: Vec<TableCell> = keys
                 .iter()
                 .enumerate()
                 .map(|(i, k)| {
                     let info = &column_info[i];
                     let value = obj.get(k).map(|v| format_value(v)).unwrap_or_default();
+                    let style = |padded: &str| {
+                        if info.is_numeric && !info.is_unbounded_column {
+                            theme::value(padded).to_string()
+                        } else {
+                            style_table_value(padded, info.is_unbounded_column)
+                        }
+                    };
 
-                    let (content, skip_padding) =
-                        truncate_string(&value, info.is_unbounded_column, info.max_width);
+                    if fit_to_terminal && value.width() > info.max_width {
+                        // URIs and abstracts are never cut: wrap them, and wrap
+                        // every column when --wrap asks for lossless output.
+                        let lines = if options.wrap || info.is_unbounded_column {
+                            wrap_display_text(&value, info.max_width, usize::MAX)
+                        } else {
+                            vec![truncate_to_width(&value, info.max_width)]
+                        };
+                        return lines
+                            .iter()
+                            .map(|line| {
+                                let padded = pad_cell(line, info.max_width, info.is_numeric);
+                                (padded.width(), style(&padded))
+                            })
+                            .collect();
+                    }
+
+                    let (content, skip_padding) = if options.no_truncate {
+                        (value, false)
+                    } else {
+                        truncate_string(&value, info.is_unbounded_column, info.max_width)
+                    };
 
                     let padded = if skip_padding {
                         // Long URI, output as-is without padding
@@ -855,16 +1381,149 @@ fn format_array_to_table(items: &Vec<serde_json::Value>, compact: bool) -> Optio
                         pad_cell(&content, info.max_width, info.is_numeric)
                     };
 
-                    style_table_value(&padded, info.is_unbounded_column).to_string()
+                    vec![(padded.width(), style(&padded))]
                 })
                 .collect();
+            rows.push(row_cells);
+        }
+    }
+
+    if options.bordered {
+        Some(render_bordered_rows(&header_cells, &rows))
+    } else {
+        Some(render_plain_rows(&header_cells, &rows))
+    }
+}
+
+/// One table cell: a (display width, styled text) pair per rendered line.
+type TableCell = Vec<(usize, String)>;
+
+const MIN_FITTED_COL_WIDTH: usize = 8;
+
+/// Narrow the widest columns until the table fits the terminal. Numeric
+/// columns are left alone; each column keeps at least its header width (or
+/// `MIN_FITTED_COL_WIDTH`, whichever is smaller) so headers stay readable.
+fn shrink_columns_to_width(
+    columns: &mut [ColumnInfo],
+    keys: &[String],
+    terminal_width: usize,
+    bordered: bool,
+) {
+    let separators = if bordered {
+        3 * columns.len() + 1
+    } else {
+        2 * columns.len().saturating_sub(1)
+    };
+    let budget = terminal_width.saturating_sub(separators);
+    let mut total: usize = columns.iter().map(|c| c.max_width).sum();
+
+    while total > budget {
+        let widest = columns
+            .iter_mut()
+            .zip(keys)
+            .filter(|(info, key)| {
+                !info.is_numeric && info.max_width > key.width().clamp(4, MIN_FITTED_COL_WIDTH)
+            })
+            .max_by_key(|(info, _)| info.max_width);
+        let Some((info, _)) = widest else {
+            break;
+        };
+        info.max_width -= 1;
+        total -= 1;
+    }
+}
+
+fn truncate_to_width(value: &str, width: usize) -> String {
+    if value.width() <= width {
+        return value.to_string();
+    }
+    let budget = width.saturating_sub(3);
+    let mut current_width = 0;
+    let mut truncated = String::new();
+    for ch in value.chars() {
+        let ch_width = ch.width().unwrap_or(0);
+        if current_width + ch_width > budget {
+            break;
+        }
+        current_width += ch_width;
+        truncated.push(ch);
+    }
+    format!("{truncated}...")
+}
 
-            output.push_str(&row_cells.join("  "));
+fn render_plain_rows(header: &[TableCell], rows: &[Vec<TableCell>]) -> String {
+    let widths = column_widths(header, rows);
+    let mut output = String::new();
+    for row in std::iter::once(header).chain(rows.iter().map(Vec::as_slice)) {
+        for line in row_lines(row, &widths) {
+            output.push_str(&line.join("  "));
             output.push('\n');
         }
     }
+    output
+}
+
+/// Box-drawing layout used on interactive terminals.
+fn render_bordered_rows(header: &[TableCell], rows: &[Vec<TableCell>]) -> String {
+    let widths = column_widths(header, rows);
+
+    let rule = |left: &str, mid: &str, right: &str| {
+        let segments: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+        theme::border(format!("{left}{}{right}", segments.join(mid))).to_string()
+    };
+    let bar = theme::border("│").to_string();
+    let lines_for = |row: &[TableCell]| -> Vec<String> {
+        row_lines(row, &widths)
+            .into_iter()
+            .map(|cells| {
+                let cells: Vec<String> = cells.iter().map(|cell| format!(" {cell} ")).collect();
+                format!("{bar}{}{bar}", cells.join(&bar))
+            })
+            .collect()
+    };
+
+    let mut lines = vec![rule("┌", "┬", "┐")];
+    lines.extend(lines_for(header));
+    lines.push(rule("├", "┼", "┤"));
+    for row in rows {
+        lines.extend(lines_for(row));
+    }
+    lines.push(rule("└", "┴", "┘"));
+    let mut output = lines.join("\n");
+    output.push('\n');
+    output
+}
 
-    Some(output)
+fn column_widths(header: &[TableCell], rows: &[Vec<TableCell>]) -> Vec<usize> {
+    (0..header.len())
+        .map(|i| {
+            std::iter::once(header)
+                .chain(rows.iter().map(Vec::as_slice))
+                .filter_map(|row| row.get(i))
+                .flat_map(|cell| cell.iter().map(|(width, _)| *width))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+/// Expand a row of (possibly multi-line) cells into physical lines, padding
+/// short cells so every column keeps its width.
+fn row_lines(row: &[TableCell], widths: &[usize]) -> Vec<Vec<String>> {
+    let height = row.iter().map(Vec::len).max().unwrap_or(0).max(1);
+    (0..height)
+        .map(|line| {
+            row.iter()
+                .zip(widths)
+                .map(|(cell, column)| match cell.get(line) {
+                    Some((width, styled)) => {
+                        format!("{styled}{}", " ".repeat(column.saturating_sub(*width)))
+                    }
+                    None => " ".repeat(*column),
+                })
+                .collect()
+        })
+        .collect()
 }
 
 fn render_component_status(name: &str, health: &str, status: &str) -> String {
@@ -991,6 +1650,122 @@ fn truncate_string(s: &str, is_unbounded: bool, max_width: usize) -> (String, bo
     }
 }
 
+pub fn wrap_display_text(input: &str, width: usize, max_lines: usize) -> Vec<String> {
+    if width == 0 || max_lines == 0 {
+        return Vec::new();
+    }
+
+    let normalized = input.split_whitespace().collect::<Vec<_>>().join(" ");
+    if normalized.is_empty() {
+        return Vec::new();
+    }
+
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut truncated = false;
+
+    for word in normalized.split(' ') {
+        if word.is_empty() {
+            continue;
+        }
+
+        if append_word_to_line(&mut current, word, width) {
+            continue;
+        }
+
+        if !current.is_empty() {
+            lines.push(std::mem::take(&mut current));
+
+            if lines.len() == max_lines {
+                truncated = true;
+                break;
+            }
+
+            if append_word_to_line(&mut current, word, width) {
+                continue;
+            }
+        }
+
+        let mut segment = String::new();
+        let mut segment_width = 0;
+        for ch in word.chars() {
+            let char_width = UnicodeWidthChar::width(ch).unwrap_or(0);
+            if !segment.is_empty() && segment_width + char_width > width {
+                lines.push(segment);
+                segment = String::new();
+                segment_width = 0;
+
+                if lines.len() == max_lines {
+                    truncated = true;
+                    break;
+                }
+            }
+
+            segment.push(ch);
+            segment_width += char_width;
+        }
+
+        if truncated {
+            break;
+        }
+
+        current = segment;
+    }
+
+    if !truncated && !current.is_empty() {
+        lines.push(current);
+    }
+
+    if lines.len() > max_lines {
+        lines.truncate(max_lines);
+        truncated = true;
+    }
+
+    if truncated {
+        if let Some(last) = lines.last_mut() {
+            *last = with_ascii_ellipsis(last, width);
+        }
+    }
+
+    lines
+}
+
+fn append_word_to_line(line: &mut String, word: &str, width: usize) -> bool {
+    let separator_width = usize::from(!line.is_empty());
+    if line.width() + separator_width + word.width() > width {
+        return false;
+    }
+
+    if !line.is_empty() {
+        line.push(' ');
+    }
+    line.push_str(word);
+    true
+}
+
+pub fn with_ascii_ellipsis(line: &str, width: usize) -> String {
+    const ELLIPSIS: &str = "...";
+    let ellipsis_width = ELLIPSIS.width();
+    if line.width() + ellipsis_width <= width {
+        return format!("{line}{ELLIPSIS}");
+    }
+
+    let target_width = width.saturating_sub(ellipsis_width);
+    let mut output = String::new();
+    let mut output_width = 0;
+
+    for ch in line.chars() {
+        let char_width = UnicodeWidthChar::width(ch).unwrap_or(0);
+        if output_width + char_width > target_width {
+            break;
+        }
+        output.push(ch);
+        output_width += char_width;
+    }
+
+    format!("{}{ELLIPSIS}", output.trim_end())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1009,7 +1784,7 @@ mod tests {
 
         // This should not panic and should produce aligned output
         // We can't easily capture stdout, but at least verify it doesn't crash
-        print_table(obj, true);
+        render_table(obj, true);
     }
 
     #[test]
@@ -1020,13 +1795,13 @@ mod tests {
             "uri": "viking://resources/very/long/path/that/exceeds/normal/width/limits/and/should/not/be/truncated/because/it/is/a/uri"
         });
 
-        print_table(obj, true);
+        render_table(obj, true);
     }
 
     #[test]
     fn test_empty_object() {
         let obj = json!({});
-        print_table(obj, true);
+        render_table(obj, true);
     }
 
     #[test]
@@ -1074,6 +1849,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_optional_profile_render_handles_bare_row_lists() {
+        let rows = json!([{"id": "1", "name": "alpha"}]);
+
+        let rendered =
+            render_table_with_optional_profile(&rows, true).map(|value| strip_ansi(&value));
+
+        assert_eq!(rendered, Some(["id  name ", " 1  alpha", ""].join("\n")));
+    }
+
     #[test]
     fn test_profile_section_is_preserved_for_table_objects_with_list_payloads() {
         let value = json!({
@@ -1271,4 +2056,290 @@ mod tests {
             Some(["content", "", "profile", "line one", "line two", "",].join("\n"))
         );
     }
+
+    #[test]
+    fn test_output_format_parses_ndjson_aliases() {
+        assert_eq!(OutputFormat::from("ndjson"), OutputFormat::Ndjson);
+        assert_eq!(OutputFormat::from("jsonl"), OutputFormat::Ndjson);
+        assert!(OutputFormat::Ndjson.is_json());
+        assert!(!OutputFormat::Table.is_json());
+    }
+
+    #[test]
+    fn test_ndjson_records_split_arrays_and_result_envelopes() {
+        let array = json!([{"uri": "viking://a"}, {"uri": "viking://b"}]);
+        assert_eq!(ndjson_records(&array).len(), 2);
+
+        let envelope = json!({
+            "result": [{"session_id": "s1"}, {"session_id": "s2"}, {"session_id": "s3"}],
+            "profile": ["line one"]
+        });
+        let records = ndjson_records(&envelope);
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[2], &json!({"session_id": "s3"}));
+    }
+
+    #[test]
+    fn test_ndjson_records_flatten_search_collections_in_order() {
+        let value = json!({
+            "memories": [{"uri": "viking://user/m"}],
+            "resources": [{"uri": "viking://resources/r1"}, {"uri": "viking://resources/r2"}],
+            "skills": [],
+            "total": 3
+        });
+
+        let uris: Vec<&str> = ndjson_records(&value)
+            .into_iter()
+            .filter_map(|record| record["uri"].as_str())
+            .collect();
+        assert_eq!(
+            uris,
+            vec![
+                "viking://user/m",
+                "viking://resources/r1",
+                "viking://resources/r2"
+            ]
+        );
+    }
+
+    fn split_in_pieces(body: &str, piece: usize) -> (Vec<Value>, bool) {
+        let mut splitter = RecordSplitter::default();
+        let mut records = Vec::new();
+        for chunk in body.as_bytes().chunks(piece) {
+            splitter.feed(chunk, &mut |record| {
+                records.push(serde_json::from_slice(record).unwrap());
+            });
+        }
+        (records, splitter.found_records)
+    }
+
+    #[test]
+    fn test_record_splitter_matches_ndjson_records_across_chunk_boundaries() {
+        let bodies = [
+            json!({"status": "ok", "result": {
+                "memories": [{"uri": "viking://m/1", "abstract": "a, [b] \"c\""}],
+                "resources": [{"uri": "viking://r/1", "tags": ["x", "y"]}, {"uri": "viking://r/2"}],
+                "total": 3
+            }}),
+            json!({"status": "ok", "result": ["viking://a", "viking://b"], "profile": null}),
+            json!({"result": {"matches": [], "count": 0}}),
+            json!([1, {"nested": [[2]]}, "three"]),
+        ];
+        for body in bodies {
+            let payload = body.get("result").unwrap_or(&body);
+            let expected: Vec<Value> = ndjson_records(payload).into_iter().cloned().collect();
+            let text = serde_json::to_string_pretty(&body).unwrap();
+            for piece in [1, 3, 7, text.len()] {
+                assert_eq!(split_in_pieces(&text, piece), (expected.clone(), true));
+            }
+        }
+    }
+
+    #[test]
+    fn test_record_splitter_leaves_bodies_without_collections_to_the_caller() {
+        let (records, found) = split_in_pieces(
+            r#"{"status":"ok","result":{"uri":"viking://a","tags":["x"]}}"#,
+            4,
+        );
+        assert!(records.is_empty());
+        assert!(!found);
+    }
+
+    #[test]
+    fn test_ndjson_records_keep_plain_objects_whole() {
+        let value = json!({"uri": "viking://a", "size": 10, "tags": []});
+        assert_eq!(ndjson_records(&value), vec![&value]);
+    }
+
+    #[test]
+    fn test_query_input_drops_profile_envelope() {
+        let value = json!({"result": [{"uri": "viking://a"}], "profile": ["line one"]});
+        assert_eq!(strip_profile(value), json!([{"uri": "viking://a"}]));
+
+        let value = json!({"healthy": true, "profile": ["line one"]});
+        assert_eq!(strip_profile(value), json!({"healthy": true}));
+
+        let value = json!({"result": 1});
+        assert_eq!(strip_profile(value.clone()), value);
+    }
+
+    #[test]
+    fn test_sort_key_parses_direction_prefix() {
+        assert_eq!(
+            SortKey::parse("-score"),
+            Some(SortKey {
+                column: "score".to_string(),
+                descending: true
+            })
+        );
+        assert_eq!(
+            SortKey::parse("+uri"),
+            Some(SortKey {
+                column: "uri".to_string(),
+                descending: false
+            })
+        );
+        assert_eq!(SortKey::parse("-"), None);
+    }
+
+    #[test]
+    fn test_table_columns_and_sort_narrow_and_order_rows() {
+        let rows = vec![
+            json!({"uri": "viking://a", "score": 0.2, "abstract": "A", "level": 1}),
+            json!({"uri": "viking://b", "score": 0.9, "abstract": "B", "level": 2}),
+            json!({"uri": "viking://c", "abstract": "C", "level": 0}),
+            json!({"uri": "viking://d", "score": "0.5", "abstract": "D", "level": 1}),
+        ];
+        let options = TableOptions {
+            columns: vec![
+                "score".to_string(),
+                "uri".to_string(),
+                "missing".to_string(),
+            ],
+            sort: SortKey::parse("-score"),
+            ..TableOptions::default()
+        };
+
+        let rendered =
+            strip_ansi(&format_array_to_table_with_options(&rows, true, &options).expect("table"));
+        let lines: Vec<&str> = rendered.lines().collect();
+
+        assert!(lines[0].trim_start().starts_with("score"));
+        assert!(lines[0].contains("uri"));
+        assert!(!lines[0].contains("abstract"));
+        assert!(!lines[0].contains("missing"));
+        assert!(lines[1].ends_with("viking://b"));
+        assert!(lines[2].ends_with("viking://d"));
+        assert!(lines[3].ends_with("viking://a"));
+        assert!(lines[4].ends_with("viking://c"));
+    }
+
+    #[test]
+    fn test_table_columns_leave_unrelated_tables_untouched() {
+        let rows = vec![json!({"name": "queue", "pending": 0})];
+        let options = TableOptions {
+            columns: vec!["uri".to_string()],
+            sort: None,
+            ..TableOptions::default()
+        };
+
+        let rendered =
+            strip_ansi(&format_array_to_table_with_options(&rows, true, &options).expect("table"));
+
+        assert!(rendered.contains("name"));
+        assert!(rendered.contains("pending"));
+    }
+
+    #[test]
+    fn test_bordered_table_draws_box_around_aligned_cells() {
+        let rows = vec![
+            json!({"uri": "viking://resources/a.md", "size": 12}),
+            json!({"uri": "viking://resources/longer-name.md", "size": 3456}),
+        ];
+        let options = TableOptions {
+            bordered: true,
+            ..TableOptions::default()
+        };
+
+        let rendered =
+            strip_ansi(&format_array_to_table_with_options(&rows, true, &options).expect("table"));
+        let lines: Vec<&str> = rendered.lines().collect();
+
+        assert_eq!(lines.len(), 6);
+        assert!(lines[0].starts_with('┌') && lines[0].ends_with('┐'));
+        assert!(lines[1].starts_with("│ uri"));
+        assert!(lines[2].starts_with('├') && lines[2].contains('┼'));
+        assert!(lines[5].starts_with('└') && lines[5].ends_with('┘'));
+        let widths: Vec<usize> = lines.iter().map(|line| line.width()).collect();
+        assert!(widths.iter().all(|width| *width == widths[0]));
+        assert!(lines[3].contains("│   12 │"));
+    }
+
+    #[test]
+    fn test_plain_table_colors_numeric_columns_as_values() {
+        colored::control::set_override(true);
+        let rows = vec![json!({"name": "a", "count": 7})];
+        let rendered = format_array_to_table_with_options(&rows, true, &TableOptions::default())
+            .expect("table");
+        colored::control::unset_override();
+
+        assert!(rendered.contains(&theme::value("    7").to_string()));
+    }
+
+    #[test]
+    fn test_terminal_fit_truncates_plain_columns_and_wraps_uris() {
+        let rows = vec![json!({
+            "uri": "viking://resources/project/docs/architecture/overview.md",
+            "name": "a-very-long-resource-name-that-overflows",
+            "size": 1024
+        })];
+        let options = TableOptions {
+            terminal_width: Some(60),
+            bordered: true,
+            ..TableOptions::default()
+        };
+
+        let rendered =
+            strip_ansi(&format_array_to_table_with_options(&rows, true, &options).expect("table"));
+
+        for line in rendered.lines() {
+            assert!(line.width() <= 60, "line should fit the terminal: {line:?}");
+        }
+        let uri: String = rendered
+            .lines()
+            .skip(3)
+            .flat_map(|line| line.split('│').nth(1))
+            .map(str::trim)
+            .collect();
+        assert_eq!(
+            uri,
+            "viking://resources/project/docs/architecture/overview.md"
+        );
+        assert!(rendered.contains("..."));
+        assert!(rendered.contains("1024"));
+    }
+
+    #[test]
+    fn test_wrap_keeps_cjk_text_whole_at_display_width() {
+        let abstract_text = "本资源包含大量中文摘要内容，用于验证表格在窄终端中的换行效果。";
+        let rows = vec![json!({"name": "中文资源说明文档", "abstract": abstract_text})];
+        let options = TableOptions {
+            terminal_width: Some(40),
+            wrap: true,
+            bordered: true,
+            ..TableOptions::default()
+        };
+
+        let rendered =
+            strip_ansi(&format_array_to_table_with_options(&rows, true, &options).expect("table"));
+        let widths: Vec<usize> = rendered.lines().map(|line| line.width()).collect();
+
+        assert!(
+            widths
+                .iter()
+                .all(|width| *width == widths[0] && *width <= 40)
+        );
+        let body: String = rendered
+            .lines()
+            .flat_map(|line| line.split('│').nth(2))
+            .map(str::trim)
+            .collect();
+        assert!(body.contains(abstract_text));
+        assert!(!rendered.contains("..."));
+    }
+
+    #[test]
+    fn test_no_truncate_keeps_values_longer_than_column_cap() {
+        let long = "x".repeat(MAX_COL_WIDTH + 20);
+        let rows = vec![json!({"name": long})];
+        let options = TableOptions {
+            no_truncate: true,
+            terminal_width: Some(80),
+            ..TableOptions::default()
+        };
+
+        let rendered = format_array_to_table_with_options(&rows, true, &options).expect("table");
+
+        assert!(strip_ansi(&rendered).contains(&long));
+    }
 }
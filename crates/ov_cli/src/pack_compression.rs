@@ -0,0 +1,185 @@
+//! Member compression for .ovpack files.
+//!
+//! The server always writes packs with deflate. `--compression zstd` rewrites
+//! every member with zstd after download, which shrinks text-heavy packs
+//! noticeably. The server's zip reader only understands deflate, so import
+//! and restore convert such packs back before uploading them.
+
+use std::io::{Cursor, Read, Seek, Write};
+use std::path::Path;
+
+use tempfile::NamedTempFile;
+use zip::CompressionMethod;
+use zip::write::SimpleFileOptions;
+
+use crate::error::{Error, Result};
+
+pub const COMPRESSION_METHODS: [&str; 2] = ["deflate", "zstd"];
+
+#[derive(Debug, Clone, Copy)]
+pub struct PackCompression {
+    method: CompressionMethod,
+    level: Option<i64>,
+}
+
+impl PackCompression {
+    /// `None` keeps the server's deflate output as it is.
+    pub fn new(method: Option<&str>, level: Option<i64>) -> Result<Option<Self>> {
+        let (method, levels) = match method.unwrap_or("deflate") {
+            "deflate" if level.is_none() => return Ok(None),
+            "deflate" => (CompressionMethod::Deflated, 0..=9),
+            "zstd" => (CompressionMethod::Zstd, 1..=22),
+            other => {
+                return Err(Error::Client(format!(
+                    "Unknown compression '{other}'; expected one of: {}",
+                    COMPRESSION_METHODS.join(", ")
+                )));
+            }
+        };
+        if let Some(level) = level
+            && !levels.contains(&level)
+        {
+            return Err(Error::Client(format!(
+                "--level for {} must be between {} and {}",
+                method_name(method),
+                levels.start(),
+                levels.end()
+            )));
+        }
+        Ok(Some(Self { method, level }))
+    }
+
+    pub fn name(&self) -> &'static str {
+        method_name(self.method)
+    }
+
+    /// Rewrite every member of `pack` with this compression.
+    pub fn recompress(&self, pack: &[u8]) -> Result<Vec<u8>> {
+        let archive = zip::ZipArchive::new(Cursor::new(pack))?;
+        let options = SimpleFileOptions::default()
+            .compression_method(self.method)
+            .compression_level(self.level);
+        rewrite(
+            archive,
+            Cursor::new(Vec::with_capacity(pack.len())),
+            options,
+        )
+        .map(Cursor::into_inner)
+    }
+}
+
+/// Total uncompressed size of a pack's members.
+pub fn content_size(pack: &[u8]) -> Result<u64> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(pack))?;
+    let mut total = 0;
+    for index in 0..archive.len() {
+        total += archive.by_index_raw(index)?.size();
+    }
+    Ok(total)
+}
+
+/// Convert a zstd pack at `path` into a temporary deflate pack the server can
+/// read; `None` if no member uses zstd.
+pub fn deflate_to_temp(path: &Path) -> Result<Option<NamedTempFile>> {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?)?;
+    let mut zstd = false;
+    for index in 0..archive.len() {
+        zstd |= archive.by_index_raw(index)?.compression() == CompressionMethod::Zstd;
+    }
+    if !zstd {
+        return Ok(None);
+    }
+    let temp = tempfile::Builder::new()
+        .prefix("ov-deflated-")
+        .suffix(".ovpack")
+        .tempfile()?;
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    rewrite(archive, temp.as_file(), options)?;
+    Ok(Some(temp))
+}
+
+fn rewrite<R: Read + Seek, W: Write + Seek>(
+    mut archive: zip::ZipArchive<R>,
+    output: W,
+    options: SimpleFileOptions,
+) -> Result<W> {
+    let mut zip = zip::ZipWriter::new(output);
+    for index in 0..archive.len() {
+        let mut member = archive.by_index(index)?;
+        let name = member.name().to_string();
+        if member.is_dir() {
+            zip.add_directory(name, options)?;
+            continue;
+        }
+        zip.start_file(name, options.large_file(member.size() >= u32::MAX as u64))?;
+        std::io::copy(&mut member, &mut zip)?;
+    }
+    Ok(zip.finish()?)
+}
+
+fn method_name(method: CompressionMethod) -> &'static str {
+    if method == CompressionMethod::Zstd {
+        "zstd"
+    } else {
+        "deflate"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zstd_packs_shrink_and_convert_back_to_deflate() {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let deflate = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        zip.add_directory("demo/", deflate).unwrap();
+        zip.start_file("demo/files/notes.md", deflate).unwrap();
+        for line in 0..2000 {
+            writeln!(zip, "note {line}: packs of text compress well").unwrap();
+        }
+        let pack = zip.finish().unwrap().into_inner();
+
+        let zstd = PackCompression::new(Some("zstd"), Some(19))
+            .unwrap()
+            .unwrap();
+        let smaller = zstd.recompress(&pack).unwrap();
+        assert!(smaller.len() < pack.len());
+        assert_eq!(
+            content_size(&smaller).unwrap(),
+            content_size(&pack).unwrap()
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("demo.ovpack");
+        std::fs::write(&path, &smaller).unwrap();
+        let deflated = deflate_to_temp(&path).unwrap().unwrap();
+        let mut archive = zip::ZipArchive::new(deflated.reopen().unwrap()).unwrap();
+        assert!(archive.by_name("demo/").unwrap().is_dir());
+        let mut notes = archive.by_name("demo/files/notes.md").unwrap();
+        assert_eq!(notes.compression(), CompressionMethod::Deflated);
+        let mut text = String::new();
+        notes.read_to_string(&mut text).unwrap();
+        assert!(text.starts_with("note 0: "));
+
+        std::fs::write(&path, &pack).unwrap();
+        assert!(deflate_to_temp(&path).unwrap().is_none());
+    }
+
+    #[test]
+    fn compression_levels_are_checked_per_method() {
+        assert!(PackCompression::new(None, None).unwrap().is_none());
+        assert!(
+            PackCompression::new(Some("deflate"), None)
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(
+            PackCompression::new(None, Some(9)).unwrap().unwrap().name(),
+            "deflate"
+        );
+        assert!(PackCompression::new(Some("deflate"), Some(12)).is_err());
+        assert!(PackCompression::new(Some("zstd"), Some(0)).is_err());
+        assert!(PackCompression::new(Some("brotli"), None).is_err());
+    }
+}
@@ -0,0 +1,246 @@
+//! age encryption for .ovpack files.
+//!
+//! Export and backup encrypt the downloaded pack in memory, so the plaintext
+//! never reaches disk, either to age recipients (`age1...`) or to a
+//! passphrase. Import and restore recognise the age header, decrypt into a
+//! private temporary file, and upload that. Encrypted packs stay readable with
+//! the stock `age` tool.
+
+use std::io::{Read, Write};
+use std::iter;
+use std::path::Path;
+
+use age::secrecy::SecretString;
+use tempfile::NamedTempFile;
+
+use crate::error::{Error, Result};
+
+const AGE_MAGIC: &[u8] = b"age-encryption.org/";
+
+/// How `ov export`/`ov backup` encrypt a pack.
+pub enum PackEncryption {
+    Passphrase(SecretString),
+    Recipients(Vec<age::x25519::Recipient>),
+}
+
+impl PackEncryption {
+    /// `None` when no encryption was asked for. Recipients are `age1...` keys
+    /// or files holding one per line.
+    pub fn new(
+        encrypt: bool,
+        recipients: &[String],
+        passphrase: Option<SecretString>,
+    ) -> Result<Option<Self>> {
+        if !recipients.is_empty() {
+            let mut parsed = Vec::new();
+            for recipient in recipients {
+                parsed.extend(parse_recipients(recipient)?);
+            }
+            return Ok(Some(Self::Recipients(parsed)));
+        }
+        match passphrase {
+            Some(passphrase) => Ok(Some(Self::Passphrase(passphrase))),
+            None if encrypt => Err(Error::Client(
+                "--encrypt needs --recipient, --passphrase-env, or --passphrase-stdin".to_string(),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let encryptor = match self {
+            Self::Passphrase(passphrase) => {
+                age::Encryptor::with_user_passphrase(passphrase.clone())
+            }
+            Self::Recipients(recipients) => age::Encryptor::with_recipients(
+                recipients
+                    .iter()
+                    .map(|recipient| recipient as &dyn age::Recipient),
+            )
+            .map_err(|e| Error::Client(format!("Failed to encrypt pack: {e}")))?,
+        };
+        let mut ciphertext = Vec::with_capacity(plaintext.len() + 1024);
+        let mut writer = encryptor.wrap_output(&mut ciphertext)?;
+        writer.write_all(plaintext)?;
+        writer.finish()?;
+        Ok(ciphertext)
+    }
+}
+
+/// Keys `ov import`/`ov restore` may use to open an encrypted pack.
+#[derive(Default)]
+pub struct PackDecryption {
+    pub identity_files: Vec<String>,
+    pub passphrase: Option<SecretString>,
+}
+
+pub fn is_encrypted(path: &Path) -> Result<bool> {
+    let mut header = [0u8; AGE_MAGIC.len()];
+    let mut file = std::fs::File::open(path)?;
+    let read = file.read(&mut header)?;
+    Ok(read == header.len() && header == AGE_MAGIC)
+}
+
+/// Decrypt an encrypted pack into a temporary `.ovpack`; `None` if `path` is
+/// not encrypted.
+pub fn decrypt_to_temp(path: &Path, keys: &PackDecryption) -> Result<Option<NamedTempFile>> {
+    if !is_encrypted(path)? {
+        return Ok(None);
+    }
+    let decryptor = age::Decryptor::new(std::io::BufReader::new(std::fs::File::open(path)?))
+        .map_err(|e| decrypt_error(path, e))?;
+    let mut reader = if decryptor.is_scrypt() {
+        let passphrase = keys.passphrase.clone().ok_or_else(|| {
+            Error::Client(format!(
+                "Pack is passphrase-encrypted; pass --passphrase-env or --passphrase-stdin: {}",
+                path.display()
+            ))
+        })?;
+        let identity = age::scrypt::Identity::new(passphrase);
+        decryptor
+            .decrypt(iter::once(&identity as &dyn age::Identity))
+            .map_err(|e| decrypt_error(path, e))?
+    } else {
+        if keys.identity_files.is_empty() {
+            return Err(Error::Client(format!(
+                "Pack is encrypted to age recipients; pass --identity <file>: {}",
+                path.display()
+            )));
+        }
+        let mut identities = Vec::new();
+        for file in &keys.identity_files {
+            let unreadable = |e: &dyn std::fmt::Display| {
+                Error::Client(format!("Cannot read age identity file {file}: {e}"))
+            };
+            identities.extend(
+                age::IdentityFile::from_file(file.clone())
+                    .map_err(|e| unreadable(&e))?
+                    .into_identities()
+                    .map_err(|e| unreadable(&e))?,
+            );
+        }
+        decryptor
+            .decrypt(identities.iter().map(|identity| identity.as_ref()))
+            .map_err(|e| decrypt_error(path, e))?
+    };
+
+    let mut plaintext = tempfile::Builder::new()
+        .prefix("ov-decrypted-")
+        .suffix(".ovpack")
+        .tempfile()?;
+    std::io::copy(&mut reader, plaintext.as_file_mut())?;
+    Ok(Some(plaintext))
+}
+
+/// Read a passphrase the way `ov config` reads API keys: from stdin or a
+/// named environment variable, never from a terminal prompt.
+pub fn read_passphrase(stdin: bool, env_name: Option<&str>) -> Result<Option<SecretString>> {
+    let value = if stdin {
+        let mut value = String::new();
+        std::io::stdin()
+            .read_to_string(&mut value)
+            .map_err(|e| Error::Client(format!("Failed to read passphrase from stdin: {e}")))?;
+        value
+    } else if let Some(name) = env_name {
+        std::env::var(name).map_err(|_| {
+            Error::Client(format!(
+                "Environment variable '{name}' for the passphrase is unset"
+            ))
+        })?
+    } else {
+        return Ok(None);
+    };
+    let value = value.trim_end_matches(['\r', '\n']);
+    if value.is_empty() {
+        return Err(Error::Client("Passphrase cannot be empty".to_string()));
+    }
+    Ok(Some(SecretString::from(value.to_string())))
+}
+
+fn parse_recipients(value: &str) -> Result<Vec<age::x25519::Recipient>> {
+    let parse = |line: &str| {
+        line.parse::<age::x25519::Recipient>()
+            .map_err(|e| Error::Client(format!("Invalid age recipient {line}: {e}")))
+    };
+    if value.starts_with("age1") {
+        return Ok(vec![parse(value)?]);
+    }
+    let text = std::fs::read_to_string(value)
+        .map_err(|e| Error::Client(format!("Cannot read recipients file {value}: {e}")))?;
+    let recipients = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(parse)
+        .collect::<Result<Vec<_>>>()?;
+    if recipients.is_empty() {
+        return Err(Error::Client(format!("No age recipients in {value}")));
+    }
+    Ok(recipients)
+}
+
+fn decrypt_error(path: &Path, error: age::DecryptError) -> Error {
+    Error::Client(format!("Cannot decrypt {}: {error}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use age::secrecy::ExposeSecret;
+
+    use super::*;
+
+    #[test]
+    fn recipient_and_passphrase_packs_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let identity = age::x25519::Identity::generate();
+        let identity_path = dir.path().join("key.txt");
+        std::fs::write(
+            &identity_path,
+            format!("{}\n", identity.to_string().expose_secret()),
+        )
+        .unwrap();
+
+        let recipients = [identity.to_public().to_string()];
+        let encryption = PackEncryption::new(false, &recipients, None)
+            .unwrap()
+            .unwrap();
+        let sealed = dir.path().join("sealed.ovpack");
+        std::fs::write(&sealed, encryption.encrypt(b"PK\x03\x04pack").unwrap()).unwrap();
+        assert!(is_encrypted(&sealed).unwrap());
+        assert!(decrypt_to_temp(&sealed, &PackDecryption::default()).is_err());
+        let keys = PackDecryption {
+            identity_files: vec![identity_path.display().to_string()],
+            passphrase: None,
+        };
+        let opened = decrypt_to_temp(&sealed, &keys).unwrap().unwrap();
+        assert_eq!(std::fs::read(opened.path()).unwrap(), b"PK\x03\x04pack");
+
+        let passphrase = SecretString::from("correct horse".to_string());
+        let encryption = PackEncryption::new(true, &[], Some(passphrase.clone()))
+            .unwrap()
+            .unwrap();
+        std::fs::write(&sealed, encryption.encrypt(b"PK\x03\x04pack").unwrap()).unwrap();
+        let wrong = PackDecryption {
+            identity_files: Vec::new(),
+            passphrase: Some(SecretString::from("wrong".to_string())),
+        };
+        assert!(decrypt_to_temp(&sealed, &wrong).is_err());
+        let keys = PackDecryption {
+            identity_files: Vec::new(),
+            passphrase: Some(passphrase),
+        };
+        let opened = decrypt_to_temp(&sealed, &keys).unwrap().unwrap();
+        assert_eq!(std::fs::read(opened.path()).unwrap(), b"PK\x03\x04pack");
+
+        let plain = dir.path().join("plain.ovpack");
+        std::fs::write(&plain, b"PK\x03\x04pack").unwrap();
+        assert!(decrypt_to_temp(&plain, &keys).unwrap().is_none());
+    }
+
+    #[test]
+    fn encryption_needs_a_key_when_requested() {
+        assert!(PackEncryption::new(false, &[], None).unwrap().is_none());
+        assert!(PackEncryption::new(true, &[], None).is_err());
+        assert!(PackEncryption::new(false, &["age1nope".to_string()], None).is_err());
+    }
+}
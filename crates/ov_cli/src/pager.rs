@@ -0,0 +1,116 @@
+//! Page long terminal output through `$PAGER`, the way git does.
+//!
+//! Paging only kicks in when stdout is a TTY and the rendered text is taller
+//! than the terminal. Pipes, redirects, `--no-pager` and an empty or `cat`
+//! pager all print directly.
+
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const DEFAULT_PAGER: &str = "less -R";
+
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turn paging off for the rest of the process (`--no-pager`).
+pub fn disable() {
+    DISABLED.store(true, Ordering::Relaxed);
+}
+
+/// Print `text` followed by a newline, through the pager when it would not fit
+/// on one screen.
+pub fn page_or_print(text: &str) {
+    if let Some(pager) = active_pager(text)
+        && run_pager(&pager, text).is_ok()
+    {
+        return;
+    }
+    println!("{text}");
+}
+
+fn active_pager(text: &str) -> Option<Vec<String>> {
+    if DISABLED.load(Ordering::Relaxed) || !io::stdout().is_terminal() {
+        return None;
+    }
+    let (_, rows) = crossterm::terminal::size().ok()?;
+    if !exceeds_height(text, usize::from(rows)) {
+        return None;
+    }
+    pager_command(
+        env::var("OV_PAGER").ok().as_deref(),
+        env::var("PAGER").ok().as_deref(),
+    )
+}
+
+/// Leave one row for the shell prompt, like `less -F` does.
+fn exceeds_height(text: &str, rows: usize) -> bool {
+    rows > 0 && text.lines().count() >= rows
+}
+
+/// Resolve the pager program and its arguments. `OV_PAGER` wins over `PAGER`;
+/// an empty value or `cat` disables paging.
+fn pager_command(ov_pager: Option<&str>, pager: Option<&str>) -> Option<Vec<String>> {
+    let spec = ov_pager.or(pager).unwrap_or(DEFAULT_PAGER).trim();
+    let words: Vec<String> = spec.split_whitespace().map(str::to_string).collect();
+    match words.first().map(String::as_str) {
+        None | Some("cat") => None,
+        Some(_) => Some(words),
+    }
+}
+
+fn run_pager(pager: &[String], text: &str) -> io::Result<()> {
+    let mut command = Command::new(&pager[0]);
+    command.args(&pager[1..]).stdin(Stdio::piped());
+    if pager[0] == "less" && env::var_os("LESS").is_none() {
+        // Quit immediately if the output fits after all and keep colors intact.
+        command.env("LESS", "FRX");
+    }
+    let mut child = command.spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The user may quit the pager before reading everything; that is not
+        // an error worth reporting.
+        let _ = writeln!(stdin, "{text}");
+    }
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pager_command_prefers_ov_pager_and_defaults_to_less() {
+        assert_eq!(
+            pager_command(Some("most -s"), Some("more")),
+            Some(vec!["most".to_string(), "-s".to_string()])
+        );
+        assert_eq!(
+            pager_command(None, Some("more")),
+            Some(vec!["more".to_string()])
+        );
+        assert_eq!(
+            pager_command(None, None),
+            Some(vec!["less".to_string(), "-R".to_string()])
+        );
+    }
+
+    #[test]
+    fn empty_or_cat_pager_disables_paging() {
+        assert_eq!(pager_command(Some(""), Some("less")), None);
+        assert_eq!(pager_command(None, Some("cat")), None);
+        assert_eq!(pager_command(Some("  "), None), None);
+    }
+
+    #[test]
+    fn only_output_taller_than_the_terminal_is_paged() {
+        let text = (0..24)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(exceeds_height(&text, 24));
+        assert!(!exceeds_height(&text, 25));
+        assert!(!exceeds_height(&text, 0));
+    }
+}
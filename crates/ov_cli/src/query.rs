@@ -0,0 +1,810 @@
+//! A small jq-like expression engine for `--query`.
+//!
+//! Supported syntax covers what scripts usually reach for:
+//!
+//! - paths: `.`, `.foo`, `.foo.bar`, `."odd key"`, `.["key"]`, `.[0]`, `.[-1]`, `.[1:3]`, `.[]`
+//! - optional suffix `?` to swallow type errors, e.g. `.items[]?`
+//! - pipes `a | b` and multiple outputs `a, b`
+//! - comparisons `==`, `!=`, `<`, `<=`, `>`, `>=` and `and` / `or`
+//! - literals (numbers, strings, `true`, `false`, `null`)
+//! - builtins `length`, `keys`, `not`, `select(expr)`, `map(expr)`
+
+use serde_json::Value;
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    source: String,
+    expr: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Identity,
+    Literal(Value),
+    Path(Box<Expr>, Vec<Step>),
+    Pipe(Box<Expr>, Box<Expr>),
+    Comma(Box<Expr>, Box<Expr>),
+    Compare(Box<Expr>, CompareOp, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Call(Builtin),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Field(String, bool),
+    Index(i64, bool),
+    Slice(Option<i64>, Option<i64>, bool),
+    Iterate(bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Builtin {
+    Length,
+    Keys,
+    Not,
+    Select(Box<Expr>),
+    Map(Box<Expr>),
+}
+
+impl Query {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.parse_pipe()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("unexpected `{}`", token.describe()));
+        }
+        Ok(Self {
+            source: source.to_string(),
+            expr,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Evaluate the query and return every produced value in order.
+    pub fn apply(&self, input: &Value) -> Result<Vec<Value>, String> {
+        eval(&self.expr, input)
+    }
+}
+
+// ============ Tokenizer ============
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Dot,
+    Ident(String),
+    Str(String),
+    Num(f64),
+    LBracket,
+    RBracket,
+    LParen,
+    RParen,
+    Colon,
+    Question,
+    Pipe,
+    Comma,
+    Op(CompareOp),
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Dot => ".".to_string(),
+            Token::Ident(name) => name.clone(),
+            Token::Str(s) => format!("\"{s}\""),
+            Token::Num(n) => n.to_string(),
+            Token::LBracket => "[".to_string(),
+            Token::RBracket => "]".to_string(),
+            Token::LParen => "(".to_string(),
+            Token::RParen => ")".to_string(),
+            Token::Colon => ":".to_string(),
+            Token::Question => "?".to_string(),
+            Token::Pipe => "|".to_string(),
+            Token::Comma => ",".to_string(),
+            Token::Op(op) => match op {
+                CompareOp::Eq => "==",
+                CompareOp::Ne => "!=",
+                CompareOp::Lt => "<",
+                CompareOp::Le => "<=",
+                CompareOp::Gt => ">",
+                CompareOp::Ge => ">=",
+            }
+            .to_string(),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let ch = chars[i];
+        match ch {
+            c if c.is_whitespace() => i += 1,
+            '.' => {
+                tokens.push(Token::Dot);
+                i += 1;
+            }
+            '[' => {
+                tokens.push(Token::LBracket);
+                i += 1;
+            }
+            ']' => {
+                tokens.push(Token::RBracket);
+                i += 1;
+            }
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            ':' => {
+                tokens.push(Token::Colon);
+                i += 1;
+            }
+            '?' => {
+                tokens.push(Token::Question);
+                i += 1;
+            }
+            '|' => {
+                tokens.push(Token::Pipe);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            '=' | '!' | '<' | '>' => {
+                let next = chars.get(i + 1).copied();
+                let (op, width) = match (ch, next) {
+                    ('=', Some('=')) => (CompareOp::Eq, 2),
+                    ('!', Some('=')) => (CompareOp::Ne, 2),
+                    ('<', Some('=')) => (CompareOp::Le, 2),
+                    ('>', Some('=')) => (CompareOp::Ge, 2),
+                    ('<', _) => (CompareOp::Lt, 1),
+                    ('>', _) => (CompareOp::Gt, 1),
+                    _ => return Err(format!("unexpected `{ch}` at position {i}")),
+                };
+                tokens.push(Token::Op(op));
+                i += width;
+            }
+            '"' => {
+                let mut value = String::new();
+                i += 1;
+                loop {
+                    let Some(&c) = chars.get(i) else {
+                        return Err("unterminated string literal".to_string());
+                    };
+                    i += 1;
+                    match c {
+                        '"' => break,
+                        '\\' => {
+                            let escaped = chars
+                                .get(i)
+                                .copied()
+                                .ok_or_else(|| "unterminated string literal".to_string())?;
+                            i += 1;
+                            value.push(match escaped {
+                                'n' => '\n',
+                                't' => '\t',
+                                other => other,
+                            });
+                        }
+                        other => value.push(other),
+                    }
+                }
+                tokens.push(Token::Str(value));
+            }
+            c if c.is_ascii_digit() || (c == '-' && starts_number(&chars, i + 1)) => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let number = text
+                    .parse::<f64>()
+                    .map_err(|_| format!("invalid number `{text}`"))?;
+                tokens.push(Token::Num(number));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            }
+            other => return Err(format!("unexpected `{other}` at position {i}")),
+        }
+    }
+    Ok(tokens)
+}
+
+fn starts_number(chars: &[char], index: usize) -> bool {
+    chars.get(index).is_some_and(|c| c.is_ascii_digit())
+}
+
+// ============ Parser ============
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, expected: &Token) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(format!(
+                "expected `{}`, found `{}`",
+                expected.describe(),
+                token.describe()
+            )),
+            None => Err(format!(
+                "expected `{}`, found end of query",
+                expected.describe()
+            )),
+        }
+    }
+
+    fn parse_pipe(&mut self) -> Result<Expr, String> {
+        let mut left = self.parse_comma()?;
+        while self.eat(&Token::Pipe) {
+            let right = self.parse_comma()?;
+            left = Expr::Pipe(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_comma(&mut self) -> Result<Expr, String> {
+        let mut left = self.parse_or()?;
+        while self.eat(&Token::Comma) {
+            let right = self.parse_or()?;
+            left = Expr::Comma(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, String> {
+        let mut left = self.parse_and()?;
+        while self.eat(&Token::Ident("or".to_string())) {
+            let right = self.parse_and()?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, String> {
+        let mut left = self.parse_compare()?;
+        while self.eat(&Token::Ident("and".to_string())) {
+            let right = self.parse_compare()?;
+            left = Expr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_compare(&mut self) -> Result<Expr, String> {
+        let left = self.parse_term()?;
+        if let Some(Token::Op(op)) = self.peek().cloned() {
+            self.pos += 1;
+            let right = self.parse_term()?;
+            return Ok(Expr::Compare(Box::new(left), op, Box::new(right)));
+        }
+        Ok(left)
+    }
+
+    fn parse_term(&mut self) -> Result<Expr, String> {
+        let base = match self.peek().cloned() {
+            Some(Token::Dot) => {
+                self.pos += 1;
+                match self.peek().cloned() {
+                    Some(Token::Ident(name)) => {
+                        self.pos += 1;
+                        let optional = self.eat(&Token::Question);
+                        return self.parse_steps(Expr::Identity, vec![Step::Field(name, optional)]);
+                    }
+                    Some(Token::Str(name)) => {
+                        self.pos += 1;
+                        let optional = self.eat(&Token::Question);
+                        return self.parse_steps(Expr::Identity, vec![Step::Field(name, optional)]);
+                    }
+                    _ => Expr::Identity,
+                }
+            }
+            Some(Token::Str(value)) => {
+                self.pos += 1;
+                Expr::Literal(Value::String(value))
+            }
+            Some(Token::Num(value)) => {
+                self.pos += 1;
+                Expr::Literal(number_value(value))
+            }
+            Some(Token::LParen) => {
+                self.pos += 1;
+                let inner = self.parse_pipe()?;
+                self.expect(Token::RParen)?;
+                inner
+            }
+            Some(Token::Ident(name)) => {
+                self.pos += 1;
+                self.parse_ident(&name)?
+            }
+            Some(token) => return Err(format!("unexpected `{}`", token.describe())),
+            None => return Err("unexpected end of query".to_string()),
+        };
+        self.parse_steps(base, Vec::new())
+    }
+
+    fn parse_ident(&mut self, name: &str) -> Result<Expr, String> {
+        Ok(match name {
+            "true" => Expr::Literal(Value::Bool(true)),
+            "false" => Expr::Literal(Value::Bool(false)),
+            "null" => Expr::Literal(Value::Null),
+            "length" => Expr::Call(Builtin::Length),
+            "keys" => Expr::Call(Builtin::Keys),
+            "not" => Expr::Call(Builtin::Not),
+            "select" | "map" => {
+                self.expect(Token::LParen)?;
+                let inner = Box::new(self.parse_pipe()?);
+                self.expect(Token::RParen)?;
+                if name == "select" {
+                    Expr::Call(Builtin::Select(inner))
+                } else {
+                    Expr::Call(Builtin::Map(inner))
+                }
+            }
+            other => return Err(format!("unknown function `{other}`")),
+        })
+    }
+
+    fn parse_steps(&mut self, base: Expr, mut steps: Vec<Step>) -> Result<Expr, String> {
+        loop {
+            match self.peek() {
+                Some(Token::Dot) => {
+                    let name = match self.tokens.get(self.pos + 1).cloned() {
+                        Some(Token::Ident(name)) | Some(Token::Str(name)) => name,
+                        // `.[` continues the path: `.items.[0]` is the same as `.items[0]`.
+                        Some(Token::LBracket) => {
+                            self.pos += 1;
+                            continue;
+                        }
+                        _ => return Err("expected a field name after `.`".to_string()),
+                    };
+                    self.pos += 2;
+                    let optional = self.eat(&Token::Question);
+                    steps.push(Step::Field(name, optional));
+                }
+                Some(Token::LBracket) => {
+                    self.pos += 1;
+                    steps.push(self.parse_bracket()?);
+                }
+                _ => break,
+            }
+        }
+        if steps.is_empty() {
+            Ok(base)
+        } else {
+            Ok(Expr::Path(Box::new(base), steps))
+        }
+    }
+
+    fn parse_bracket(&mut self) -> Result<Step, String> {
+        let step = match self.next() {
+            Some(Token::RBracket) => {
+                return Ok(Step::Iterate(self.eat(&Token::Question)));
+            }
+            Some(Token::Str(name)) => {
+                self.expect(Token::RBracket)?;
+                Step::Field(name, false)
+            }
+            Some(Token::Num(start)) => {
+                if self.eat(&Token::Colon) {
+                    let end = self.parse_slice_bound()?;
+                    self.expect(Token::RBracket)?;
+                    Step::Slice(Some(start as i64), end, false)
+                } else {
+                    self.expect(Token::RBracket)?;
+                    Step::Index(start as i64, false)
+                }
+            }
+            Some(Token::Colon) => {
+                let end = self.parse_slice_bound()?;
+                self.expect(Token::RBracket)?;
+                Step::Slice(None, end, false)
+            }
+            Some(token) => return Err(format!("unexpected `{}` inside `[]`", token.describe())),
+            None => return Err("unterminated `[`".to_string()),
+        };
+        let optional = self.eat(&Token::Question);
+        Ok(match step {
+            Step::Field(name, _) => Step::Field(name, optional),
+            Step::Index(index, _) => Step::Index(index, optional),
+            Step::Slice(start, end, _) => Step::Slice(start, end, optional),
+            Step::Iterate(_) => Step::Iterate(optional),
+        })
+    }
+
+    fn parse_slice_bound(&mut self) -> Result<Option<i64>, String> {
+        match self.peek().cloned() {
+            Some(Token::Num(value)) => {
+                self.pos += 1;
+                Ok(Some(value as i64))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+fn number_value(value: f64) -> Value {
+    if value.fract() == 0.0 && value.abs() < i64::MAX as f64 {
+        Value::from(value as i64)
+    } else {
+        serde_json::Number::from_f64(value)
+            .map(Value::Number)
+            .unwrap_or(Value::Null)
+    }
+}
+
+// ============ Evaluation ============
+
+fn eval(expr: &Expr, input: &Value) -> Result<Vec<Value>, String> {
+    match expr {
+        Expr::Identity => Ok(vec![input.clone()]),
+        Expr::Literal(value) => Ok(vec![value.clone()]),
+        Expr::Path(base, steps) => {
+            let mut current = eval(base, input)?;
+            for step in steps {
+                let mut next = Vec::new();
+                for value in &current {
+                    apply_step(step, value, &mut next)?;
+                }
+                current = next;
+            }
+            Ok(current)
+        }
+        Expr::Pipe(left, right) => {
+            let mut out = Vec::new();
+            for value in eval(left, input)? {
+                out.extend(eval(right, &value)?);
+            }
+            Ok(out)
+        }
+        Expr::Comma(left, right) => {
+            let mut out = eval(left, input)?;
+            out.extend(eval(right, input)?);
+            Ok(out)
+        }
+        Expr::Compare(left, op, right) => {
+            let mut out = Vec::new();
+            for r in eval(right, input)? {
+                for l in eval(left, input)? {
+                    out.push(Value::Bool(compare(&l, *op, &r)));
+                }
+            }
+            Ok(out)
+        }
+        Expr::And(left, right) => {
+            let mut out = Vec::new();
+            for l in eval(left, input)? {
+                if !truthy(&l) {
+                    out.push(Value::Bool(false));
+                    continue;
+                }
+                for r in eval(right, input)? {
+                    out.push(Value::Bool(truthy(&r)));
+                }
+            }
+            Ok(out)
+        }
+        Expr::Or(left, right) => {
+            let mut out = Vec::new();
+            for l in eval(left, input)? {
+                if truthy(&l) {
+                    out.push(Value::Bool(true));
+                    continue;
+                }
+                for r in eval(right, input)? {
+                    out.push(Value::Bool(truthy(&r)));
+                }
+            }
+            Ok(out)
+        }
+        Expr::Call(builtin) => call_builtin(builtin, input),
+    }
+}
+
+fn apply_step(step: &Step, value: &Value, out: &mut Vec<Value>) -> Result<(), String> {
+    let (result, optional) = match step {
+        Step::Field(name, optional) => (
+            match value {
+                Value::Object(obj) => {
+                    out.push(obj.get(name).cloned().unwrap_or(Value::Null));
+                    Ok(())
+                }
+                Value::Null => {
+                    out.push(Value::Null);
+                    Ok(())
+                }
+                other => Err(format!("cannot index {} with \"{name}\"", type_name(other))),
+            },
+            *optional,
+        ),
+        Step::Index(index, optional) => (
+            match value {
+                Value::Array(items) => {
+                    let resolved = resolve_index(*index, items.len());
+                    out.push(
+                        resolved
+                            .and_then(|i| items.get(i))
+                            .cloned()
+                            .unwrap_or(Value::Null),
+                    );
+                    Ok(())
+                }
+                Value::Null => {
+                    out.push(Value::Null);
+                    Ok(())
+                }
+                other => Err(format!("cannot index {} with number", type_name(other))),
+            },
+            *optional,
+        ),
+        Step::Slice(start, end, optional) => (
+            match value {
+                Value::Array(items) => {
+                    let (from, to) = slice_bounds(*start, *end, items.len());
+                    out.push(Value::Array(items[from..to].to_vec()));
+                    Ok(())
+                }
+                Value::String(s) => {
+                    let chars: Vec<char> = s.chars().collect();
+                    let (from, to) = slice_bounds(*start, *end, chars.len());
+                    out.push(Value::String(chars[from..to].iter().collect()));
+                    Ok(())
+                }
+                Value::Null => {
+                    out.push(Value::Null);
+                    Ok(())
+                }
+                other => Err(format!("cannot slice {}", type_name(other))),
+            },
+            *optional,
+        ),
+        Step::Iterate(optional) => (
+            match value {
+                Value::Array(items) => {
+                    out.extend(items.iter().cloned());
+                    Ok(())
+                }
+                Value::Object(obj) => {
+                    out.extend(obj.values().cloned());
+                    Ok(())
+                }
+                other => Err(format!("cannot iterate over {}", type_name(other))),
+            },
+            *optional,
+        ),
+    };
+    match result {
+        Err(_) if optional => Ok(()),
+        other => other,
+    }
+}
+
+fn resolve_index(index: i64, len: usize) -> Option<usize> {
+    if index < 0 {
+        len.checked_sub(index.unsigned_abs() as usize)
+    } else {
+        Some(index as usize)
+    }
+}
+
+fn slice_bounds(start: Option<i64>, end: Option<i64>, len: usize) -> (usize, usize) {
+    let clamp = |bound: i64| -> usize {
+        if bound < 0 {
+            len.saturating_sub(bound.unsigned_abs() as usize)
+        } else {
+            (bound as usize).min(len)
+        }
+    };
+    let from = start.map(clamp).unwrap_or(0);
+    let to = end.map(clamp).unwrap_or(len);
+    (from, to.max(from))
+}
+
+fn call_builtin(builtin: &Builtin, input: &Value) -> Result<Vec<Value>, String> {
+    match builtin {
+        Builtin::Length => Ok(vec![match input {
+            Value::Array(items) => Value::from(items.len()),
+            Value::Object(obj) => Value::from(obj.len()),
+            Value::String(s) => Value::from(s.chars().count()),
+            Value::Null => Value::from(0),
+            Value::Number(n) => Value::from(n.as_f64().unwrap_or(0.0).abs()),
+            Value::Bool(_) => return Err("boolean has no length".to_string()),
+        }]),
+        Builtin::Keys => match input {
+            Value::Object(obj) => {
+                let mut keys: Vec<&String> = obj.keys().collect();
+                keys.sort();
+                Ok(vec![Value::from(
+                    keys.into_iter().cloned().collect::<Vec<_>>(),
+                )])
+            }
+            Value::Array(items) => Ok(vec![Value::from((0..items.len()).collect::<Vec<_>>())]),
+            other => Err(format!("{} has no keys", type_name(other))),
+        },
+        Builtin::Not => Ok(vec![Value::Bool(!truthy(input))]),
+        Builtin::Select(condition) => {
+            let keep = eval(condition, input)?.iter().any(truthy);
+            Ok(if keep {
+                vec![input.clone()]
+            } else {
+                Vec::new()
+            })
+        }
+        Builtin::Map(inner) => {
+            let Value::Array(items) = input else {
+                return Err(format!("cannot map over {}", type_name(input)));
+            };
+            let mut out = Vec::new();
+            for item in items {
+                out.extend(eval(inner, item)?);
+            }
+            Ok(vec![Value::Array(out)])
+        }
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    !matches!(value, Value::Null | Value::Bool(false))
+}
+
+fn compare(left: &Value, op: CompareOp, right: &Value) -> bool {
+    let ordering = match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64().partial_cmp(&b.as_f64()),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        (Value::Null, Value::Null) => Some(Ordering::Equal),
+        _ => None,
+    };
+    match op {
+        CompareOp::Eq => left == right || ordering == Some(Ordering::Equal),
+        CompareOp::Ne => !(left == right || ordering == Some(Ordering::Equal)),
+        CompareOp::Lt => ordering == Some(Ordering::Less),
+        CompareOp::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        CompareOp::Gt => ordering == Some(Ordering::Greater),
+        CompareOp::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn run(query: &str, input: Value) -> Vec<Value> {
+        Query::parse(query)
+            .expect("query should parse")
+            .apply(&input)
+            .expect("query should evaluate")
+    }
+
+    #[test]
+    fn field_paths_and_iteration() {
+        let input = json!([
+            {"session_id": "s1", "meta": {"turns": 3}},
+            {"session_id": "s2", "meta": {"turns": 5}}
+        ]);
+
+        assert_eq!(
+            run(".[].session_id", input.clone()),
+            vec![json!("s1"), json!("s2")]
+        );
+        assert_eq!(run(".[1].meta.turns", input.clone()), vec![json!(5)]);
+        assert_eq!(run(".[-1].session_id", input.clone()), vec![json!("s2")]);
+        assert_eq!(run(".[0:1] | length", input), vec![json!(1)]);
+    }
+
+    #[test]
+    fn quoted_keys_and_missing_fields() {
+        let input = json!({"odd key": 1, "nested": null});
+
+        assert_eq!(run(".\"odd key\"", input.clone()), vec![json!(1)]);
+        assert_eq!(run(".[\"odd key\"]", input.clone()), vec![json!(1)]);
+        assert_eq!(run(".missing", input.clone()), vec![Value::Null]);
+        assert_eq!(run(".nested.deeper", input), vec![Value::Null]);
+    }
+
+    #[test]
+    fn select_filters_by_comparison() {
+        let input = json!({"resources": [
+            {"uri": "viking://a", "score": 0.9},
+            {"uri": "viking://b", "score": 0.2},
+            {"uri": "viking://c", "score": 0.7}
+        ]});
+
+        assert_eq!(
+            run(".resources[] | select(.score >= 0.7) | .uri", input.clone()),
+            vec![json!("viking://a"), json!("viking://c")]
+        );
+        assert_eq!(
+            run(".resources | map(.uri) | length", input),
+            vec![json!(3)]
+        );
+    }
+
+    #[test]
+    fn comma_and_boolean_operators() {
+        let input = json!({"a": 1, "b": "x", "ok": true});
+
+        assert_eq!(run(".a, .b", input.clone()), vec![json!(1), json!("x")]);
+        assert_eq!(run(".ok and .a == 1", input.clone()), vec![json!(true)]);
+        assert_eq!(run(".ok | not", input.clone()), vec![json!(false)]);
+        assert_eq!(run("keys", input), vec![json!(["a", "b", "ok"])]);
+    }
+
+    #[test]
+    fn optional_suffix_swallows_type_errors() {
+        let input = json!({"items": "not a list"});
+
+        assert!(Query::parse(".items[]").unwrap().apply(&input).is_err());
+        assert!(run(".items[]?", input).is_empty());
+    }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        assert!(Query::parse(".[").is_err());
+        assert!(Query::parse(".foo |").is_err());
+        assert!(Query::parse("frobnicate").is_err());
+        assert!(Query::parse(".a ) ").is_err());
+    }
+}
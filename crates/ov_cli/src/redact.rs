@@ -0,0 +1,310 @@
+//! `--redact`: mask personal data in text before it is sent to the server.
+//!
+//! `ov add-resource`, `ov add`, `ov import`, and `ov session import` accept
+//! `--redact`. Every text file uploaded, document written, and message
+//! imported then has its emails, phone numbers, and ID numbers (Chinese
+//! resident IDs with a valid check digit and US social security numbers)
+//! replaced with `[REDACTED:<rule>]`, followed by the `redact_patterns` of the
+//! config and any `--redact-pattern name=regex`, each masked under its name.
+//! Files that are not UTF-8 text are sent as they are and reported as
+//! unscanned, after any ingest plugins have had the chance to convert them.
+//!
+//! The command's result gains a `redaction` report: how many matches each
+//! rule masked, per file or document and in total.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+use regex::{Captures, Regex};
+use serde_json::{Value, json};
+
+use crate::error::{Error, Result};
+
+// `\b` is Unicode-aware by default and would count the CJK characters that
+// usually sit right next to these values as part of the word, so the patterns
+// use ASCII word boundaries instead.
+const EMAIL: &str =
+    r"(?i)(?-u:\b)[a-z0-9][a-z0-9._%+-]*@[a-z0-9-]+(?:\.[a-z0-9-]+)*\.[a-z]{2,}(?-u:\b)";
+const ID: &str = r"(?-u:\b)(?:\d{17}[\dXx]|\d{3}-\d{2}-\d{4})(?-u:\b)";
+const PHONE: &str = r"\+\d(?:[ .-]?\d){6,14}(?-u:\b)|(?:\(\d{3}\)|(?-u:\b)\d{3})[ .-]\d{3}[ .-]\d{4}(?-u:\b)|(?-u:\b)1[3-9]\d{9}(?-u:\b)";
+
+struct Rule {
+    name: String,
+    pattern: Regex,
+    /// Whether a match is really this kind of data, for rules whose pattern
+    /// alone would mask too much.
+    check: Option<fn(&str) -> bool>,
+}
+
+/// The rules to mask text with, in the order they apply.
+pub struct Redactor {
+    rules: Vec<Rule>,
+}
+
+impl Redactor {
+    /// The built-in rules followed by `patterns`, each given as `name=regex`.
+    pub fn new(patterns: &[String]) -> Result<Self> {
+        let built_in = |name: &str, pattern: &str, check| Rule {
+            name: name.to_string(),
+            pattern: Regex::new(pattern).expect("built-in redaction patterns are valid"),
+            check,
+        };
+        let mut rules = vec![
+            built_in("email", EMAIL, None),
+            built_in("id", ID, Some(valid_id as fn(&str) -> bool)),
+            built_in("phone", PHONE, None),
+        ];
+        for pattern in patterns {
+            let (name, regex) = pattern
+                .split_once('=')
+                .filter(|(name, _)| {
+                    !name.is_empty()
+                        && name
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+                })
+                .ok_or_else(|| {
+                    Error::Client(format!(
+                        "Invalid redaction pattern '{pattern}'; use name=regex, with a name of letters, digits, '_', and '-'"
+                    ))
+                })?;
+            let pattern = Regex::new(regex).map_err(|e| {
+                Error::Client(format!("Invalid regex for redaction pattern '{name}': {e}"))
+            })?;
+            rules.push(Rule {
+                name: name.to_string(),
+                pattern,
+                check: None,
+            });
+        }
+        Ok(Self { rules })
+    }
+
+    /// `text` with every match masked, and how many matches each rule masked.
+    pub fn redact<'a>(&self, text: &'a str) -> (Cow<'a, str>, BTreeMap<String, usize>) {
+        let mut text = Cow::Borrowed(text);
+        let mut counts = BTreeMap::new();
+        for rule in &self.rules {
+            let mut count = 0;
+            let masked = rule.pattern.replace_all(&text, |caps: &Captures| {
+                let found = &caps[0];
+                if rule.check.is_some_and(|check| !check(found)) {
+                    return found.to_string();
+                }
+                count += 1;
+                format!("[REDACTED:{}]", rule.name)
+            });
+            if count > 0 {
+                let masked = masked.into_owned();
+                text = Cow::Owned(masked);
+                counts.insert(rule.name.clone(), count);
+            }
+        }
+        (text, counts)
+    }
+}
+
+/// A Chinese resident ID whose check digit matches, or a US social security
+/// number with an area, group, and serial that are issued.
+fn valid_id(id: &str) -> bool {
+    if let Some((area, rest)) = id.split_once('-') {
+        let (group, serial) = rest.split_once('-').unwrap_or_default();
+        return area != "000"
+            && area != "666"
+            && !area.starts_with('9')
+            && group != "00"
+            && serial != "0000";
+    }
+    const WEIGHTS: [u32; 17] = [7, 9, 10, 5, 8, 4, 2, 1, 6, 3, 7, 9, 10, 5, 8, 4, 2];
+    let digits = id.as_bytes();
+    let sum: u32 = WEIGHTS
+        .iter()
+        .zip(digits)
+        .map(|(weight, digit)| weight * u32::from(digit - b'0'))
+        .sum();
+    b"10X98765432"[(sum % 11) as usize] == digits[17].to_ascii_uppercase()
+}
+
+/// What was masked in the files and documents sent so far.
+struct Report {
+    sources: Vec<(String, BTreeMap<String, usize>)>,
+    unscanned: Vec<String>,
+}
+
+static ACTIVE: RwLock<Option<Arc<Redactor>>> = RwLock::new(None);
+static REPORT: Mutex<Report> = Mutex::new(Report {
+    sources: Vec::new(),
+    unscanned: Vec::new(),
+});
+
+/// Redact what the current command sends with `redactor`, or stop
+/// redacting; either way the report starts over.
+pub fn set(redactor: Option<Redactor>) {
+    *ACTIVE.write().unwrap_or_else(|e| e.into_inner()) = redactor.map(Arc::new);
+    let mut report = REPORT.lock().unwrap_or_else(|e| e.into_inner());
+    report.sources.clear();
+    report.unscanned.clear();
+}
+
+fn active_redactor() -> Option<Arc<Redactor>> {
+    ACTIVE.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Whether the current command redacts what it sends.
+pub fn active() -> bool {
+    active_redactor().is_some()
+}
+
+fn record(source: &str, counts: BTreeMap<String, usize>) {
+    if !counts.is_empty() {
+        let mut report = REPORT.lock().unwrap_or_else(|e| e.into_inner());
+        report.sources.push((source.to_string(), counts));
+    }
+}
+
+/// Mask `text`, sent as `source`.
+pub fn text<'a>(source: &str, text: &'a str) -> Cow<'a, str> {
+    let Some(redactor) = active_redactor() else {
+        return Cow::Borrowed(text);
+    };
+    let (masked, counts) = redactor.redact(text);
+    record(source, counts);
+    masked
+}
+
+/// Mask the file `name` about to be uploaded, if it is text.
+pub fn file(name: &str, bytes: Vec<u8>) -> Vec<u8> {
+    if !active() {
+        return bytes;
+    }
+    match String::from_utf8(bytes) {
+        Ok(content) => match text(name, &content) {
+            Cow::Borrowed(_) => content.into_bytes(),
+            Cow::Owned(masked) => masked.into_bytes(),
+        },
+        Err(e) => {
+            let mut report = REPORT.lock().unwrap_or_else(|e| e.into_inner());
+            report.unscanned.push(name.to_string());
+            e.into_bytes()
+        }
+    }
+}
+
+/// Mask every string in `value`, sent as `source`.
+pub fn value(source: &str, value: &mut Value) {
+    let Some(redactor) = active_redactor() else {
+        return;
+    };
+    let mut counts = BTreeMap::new();
+    redact_strings(&redactor, value, &mut counts);
+    record(source, counts);
+}
+
+fn redact_strings(redactor: &Redactor, value: &mut Value, counts: &mut BTreeMap<String, usize>) {
+    match value {
+        Value::String(text) => {
+            let (masked, found) = redactor.redact(text);
+            if let Cow::Owned(masked) = masked {
+                *text = masked;
+            }
+            for (rule, count) in found {
+                *counts.entry(rule).or_default() += count;
+            }
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| redact_strings(redactor, item, counts)),
+        Value::Object(fields) => fields
+            .values_mut()
+            .for_each(|field| redact_strings(redactor, field, counts)),
+        _ => {}
+    }
+}
+
+/// The report of what the current command masked since the last report,
+/// when it redacts.
+pub fn take_report() -> Option<Value> {
+    if !active() {
+        return None;
+    }
+    let mut report = REPORT.lock().unwrap_or_else(|e| e.into_inner());
+    let mut total: BTreeMap<String, usize> = BTreeMap::new();
+    let sources: Vec<Value> = report
+        .sources
+        .drain(..)
+        .map(|(source, counts)| {
+            for (rule, count) in &counts {
+                *total.entry(rule.clone()).or_default() += count;
+            }
+            json!({"source": source, "masked": counts})
+        })
+        .collect();
+    Some(json!({
+        "masked": total.values().sum::<usize>(),
+        "by_rule": total,
+        "sources": sources,
+        "unscanned": std::mem::take(&mut report.unscanned),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_rules_mask_contact_details_and_ids() {
+        let redactor = Redactor::new(&[]).unwrap();
+        let text = "Mail li.wei+vip@example.com.cn or call +86 138 0013 8000, (555) 123-4567 \
+                    or 13912345678. ID 11010519491231002X, SSN 123-45-6789. \
+                    Order 11010519491231002Y shipped 2026-04-12 to 000-12-3456.";
+        let (masked, counts) = redactor.redact(text);
+        assert_eq!(
+            masked,
+            "Mail [REDACTED:email] or call [REDACTED:phone], [REDACTED:phone] \
+             or [REDACTED:phone]. ID [REDACTED:id], SSN [REDACTED:id]. \
+             Order 11010519491231002Y shipped 2026-04-12 to 000-12-3456."
+        );
+        assert_eq!(counts["email"], 1);
+        assert_eq!(counts["id"], 2);
+        assert_eq!(counts["phone"], 3);
+
+        let (masked, counts) = redactor.redact("nothing to hide");
+        assert!(matches!(masked, Cow::Borrowed(_)));
+        assert!(counts.is_empty());
+    }
+
+    #[test]
+    fn built_in_rules_mask_values_next_to_cjk_text() {
+        let redactor = Redactor::new(&[]).unwrap();
+        let (masked, counts) =
+            redactor.redact("手机13812345678，邮箱zhang@example.com，身份证11010519491231002X");
+        assert_eq!(
+            masked,
+            "手机[REDACTED:phone]，邮箱[REDACTED:email]，身份证[REDACTED:id]"
+        );
+        assert_eq!(counts["email"], 1);
+        assert_eq!(counts["id"], 1);
+        assert_eq!(counts["phone"], 1);
+
+        let (masked, _) = redactor.redact("电话+86 138 0013 8000转人工，SSN是123-45-6789。");
+        assert_eq!(masked, "电话[REDACTED:phone]转人工，SSN是[REDACTED:id]。");
+    }
+
+    #[test]
+    fn custom_patterns_are_masked_under_their_names() {
+        let redactor = Redactor::new(&[
+            "ticket=TCK-\\d+".to_string(),
+            "card=\\b4\\d{15}\\b".to_string(),
+        ])
+        .unwrap();
+        let (masked, counts) = redactor.redact("TCK-42 paid with 4111111111111111");
+        assert_eq!(masked, "[REDACTED:ticket] paid with [REDACTED:card]");
+        assert_eq!(counts["ticket"], 1);
+
+        assert!(Redactor::new(&["no-equals".to_string()]).is_err());
+        assert!(Redactor::new(&["bad name=x".to_string()]).is_err());
+        let error = Redactor::new(&["open=(".to_string()]).err().unwrap();
+        assert!(error.to_string().contains("'open'"));
+    }
+}
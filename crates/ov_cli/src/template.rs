@@ -0,0 +1,130 @@
+//! `--template` rendering: literal text with `{{ expr }}` placeholders.
+//!
+//! Placeholders are `--query` expressions evaluated against each record, so
+//! `{{.uri}}`, `{{.meta.size}}` and `{{.tags | length}}` all work. The escapes
+//! `\t`, `\n` and `\\` are expanded in the literal text, which lets shell users
+//! write `--template '{{.uri}}\t{{.abstract}}'` without `$'...'` quoting.
+
+use crate::query::Query;
+use serde_json::Value;
+
+#[derive(Debug, Clone)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Literal(String),
+    Placeholder(Query),
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut rest = source;
+        while let Some(start) = rest.find("{{") {
+            if start > 0 {
+                segments.push(Segment::Literal(unescape(&rest[..start])));
+            }
+            let after_open = &rest[start + 2..];
+            let end = after_open
+                .find("}}")
+                .ok_or_else(|| "unclosed `{{` in template".to_string())?;
+            let expr = after_open[..end].trim();
+            if expr.is_empty() {
+                return Err("empty `{{}}` placeholder in template".to_string());
+            }
+            let query =
+                Query::parse(expr).map_err(|message| format!("in `{{{{{expr}}}}}`: {message}"))?;
+            segments.push(Segment::Placeholder(query));
+            rest = &after_open[end + 2..];
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Literal(unescape(rest)));
+        }
+        Ok(Self { segments })
+    }
+
+    /// Render one record. Multiple values from a placeholder are joined by `,`.
+    pub fn render(&self, record: &Value) -> Result<String, String> {
+        let mut out = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => out.push_str(text),
+                Segment::Placeholder(query) => {
+                    let values = query.apply(record)?;
+                    let rendered: Vec<String> = values.iter().map(render_value).collect();
+                    out.push_str(&rendered.join(","));
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn render_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('\\') => out.push('\\'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn renders_fields_with_escaped_separators() {
+        let template = Template::parse("{{.uri}}\\t{{.abstract}}").expect("template");
+        let record = json!({"uri": "viking://a", "abstract": "Alpha"});
+
+        assert_eq!(template.render(&record).unwrap(), "viking://a\tAlpha");
+    }
+
+    #[test]
+    fn renders_missing_fields_as_empty_and_numbers_as_json() {
+        let template = Template::parse("{{ .uri }} score={{.score}} tags={{.tags | length}}")
+            .expect("template");
+        let record = json!({"score": 0.5, "tags": ["a", "b"]});
+
+        assert_eq!(template.render(&record).unwrap(), " score=0.5 tags=2");
+    }
+
+    #[test]
+    fn rejects_unclosed_and_invalid_placeholders() {
+        assert!(Template::parse("{{.uri").is_err());
+        assert!(Template::parse("{{}}").is_err());
+        assert!(Template::parse("{{.[}}").is_err());
+    }
+
+    #[test]
+    fn keeps_unknown_escapes_verbatim() {
+        let template = Template::parse("a\\qb\\\\c").expect("template");
+        assert_eq!(template.render(&json!({})).unwrap(), "a\\qb\\c");
+    }
+}
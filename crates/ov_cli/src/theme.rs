@@ -3,15 +3,15 @@ use std::env;
 use colored::{ColoredString, Colorize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) struct Rgb(pub(crate) u8, pub(crate) u8, pub(crate) u8);
+pub struct Rgb(pub u8, pub u8, pub u8);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum ThemeColor {
+pub enum ThemeColor {
     TrueColor(Rgb),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum ColorLevel {
+pub enum ColorLevel {
     NoColor,
     Ansi16,
     Ansi256,
@@ -19,7 +19,7 @@ pub(crate) enum ColorLevel {
 }
 
 impl ThemeColor {
-    pub(crate) fn rgb_fallback(&self) -> Rgb {
+    pub fn rgb_fallback(&self) -> Rgb {
         match self {
             ThemeColor::TrueColor(rgb) => *rgb,
         }
@@ -27,37 +27,37 @@ impl ThemeColor {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) struct CliTheme {
-    pub(crate) wordmark_start: Rgb,
-    pub(crate) wordmark_mid: Rgb,
-    pub(crate) wordmark_end: Rgb,
-    pub(crate) logo_end: Rgb,
-    pub(crate) tagline_start: Rgb,
-    pub(crate) tagline_mid: Rgb,
-    pub(crate) tagline_end: Rgb,
-    pub(crate) border: ThemeColor,
-    pub(crate) version: ThemeColor,
-    pub(crate) brand_title: ThemeColor,
-    pub(crate) body: ThemeColor,
-    pub(crate) muted: ThemeColor,
-    pub(crate) command: ThemeColor,
-    pub(crate) heading: ThemeColor,
-    pub(crate) value: ThemeColor,
-    pub(crate) sky_value: ThemeColor,
-    pub(crate) success: ThemeColor,
-    pub(crate) warning: ThemeColor,
-    pub(crate) error: ThemeColor,
-    pub(crate) config_name: ThemeColor,
-    pub(crate) section_marker: ThemeColor,
-    pub(crate) prompt: ThemeColor,
-    pub(crate) selection: ThemeColor,
-}
-
-pub(crate) fn active_theme() -> CliTheme {
+pub struct CliTheme {
+    pub wordmark_start: Rgb,
+    pub wordmark_mid: Rgb,
+    pub wordmark_end: Rgb,
+    pub logo_end: Rgb,
+    pub tagline_start: Rgb,
+    pub tagline_mid: Rgb,
+    pub tagline_end: Rgb,
+    pub border: ThemeColor,
+    pub version: ThemeColor,
+    pub brand_title: ThemeColor,
+    pub body: ThemeColor,
+    pub muted: ThemeColor,
+    pub command: ThemeColor,
+    pub heading: ThemeColor,
+    pub value: ThemeColor,
+    pub sky_value: ThemeColor,
+    pub success: ThemeColor,
+    pub warning: ThemeColor,
+    pub error: ThemeColor,
+    pub config_name: ThemeColor,
+    pub section_marker: ThemeColor,
+    pub prompt: ThemeColor,
+    pub selection: ThemeColor,
+}
+
+pub fn active_theme() -> CliTheme {
     palette()
 }
 
-pub(crate) fn palette() -> CliTheme {
+pub fn palette() -> CliTheme {
     CliTheme {
         wordmark_start: Rgb(22, 181, 166),
         wordmark_mid: Rgb(0, 140, 132),
@@ -85,14 +85,14 @@ pub(crate) fn palette() -> CliTheme {
     }
 }
 
-pub(crate) fn colorize(text: impl Into<String>, color: ThemeColor) -> ColoredString {
+pub fn colorize(text: impl Into<String>, color: ThemeColor) -> ColoredString {
     let text = text.into();
     match color {
         ThemeColor::TrueColor(Rgb(red, green, blue)) => text.truecolor(red, green, blue),
     }
 }
 
-pub(crate) fn terminal_color_level() -> ColorLevel {
+pub fn terminal_color_level() -> ColorLevel {
     if !colored::control::SHOULD_COLORIZE.should_colorize() {
         return ColorLevel::NoColor;
     }
@@ -104,7 +104,7 @@ pub(crate) fn terminal_color_level() -> ColorLevel {
     )
 }
 
-pub(crate) fn terminal_color_level_from_env(
+pub fn terminal_color_level_from_env(
     colorterm: Option<&str>,
     term: Option<&str>,
     term_program: Option<&str>,
@@ -143,11 +143,11 @@ pub(crate) fn terminal_color_level_from_env(
     ColorLevel::Ansi16
 }
 
-pub(crate) fn style_rgb(text: impl AsRef<str>, rgb: Rgb, bold: bool) -> String {
+pub fn style_rgb(text: impl AsRef<str>, rgb: Rgb, bold: bool) -> String {
     style_rgb_for_level(text, rgb, bold, terminal_color_level())
 }
 
-pub(crate) fn style_rgb_for_level(
+pub fn style_rgb_for_level(
     text: impl AsRef<str>,
     rgb: Rgb,
     bold: bool,
@@ -175,7 +175,7 @@ fn ansi_style(text: &str, fg_code: &str, bold: bool) -> String {
     }
 }
 
-pub(crate) fn ansi256_index_for_rgb(rgb: Rgb) -> u8 {
+pub fn ansi256_index_for_rgb(rgb: Rgb) -> u8 {
     let Rgb(red, green, blue) = rgb;
 
     if red.abs_diff(green) <= 10 && green.abs_diff(blue) <= 10 {
@@ -241,85 +241,85 @@ fn rgb_distance_squared(left: Rgb, right: Rgb) -> u32 {
     (red * red + green * green + blue * blue) as u32
 }
 
-pub(crate) fn brand_title(text: impl Into<String>) -> ColoredString {
+pub fn brand_title(text: impl Into<String>) -> ColoredString {
     let theme = active_theme();
     colorize(text, theme.brand_title)
 }
 
-pub(crate) fn border(text: impl Into<String>) -> ColoredString {
+pub fn border(text: impl Into<String>) -> ColoredString {
     colorize(text, active_theme().border)
 }
 
-pub(crate) fn version(text: impl Into<String>) -> ColoredString {
+pub fn version(text: impl Into<String>) -> ColoredString {
     colorize(text, active_theme().version)
 }
 
-pub(crate) fn command(text: impl Into<String>) -> ColoredString {
+pub fn command(text: impl Into<String>) -> ColoredString {
     let theme = active_theme();
     colorize(text, theme.command)
 }
 
-pub(crate) fn body(text: impl Into<String>) -> ColoredString {
+pub fn body(text: impl Into<String>) -> ColoredString {
     let theme = active_theme();
     colorize(text, theme.body)
 }
 
-pub(crate) fn muted(text: impl Into<String>) -> ColoredString {
+pub fn muted(text: impl Into<String>) -> ColoredString {
     let theme = active_theme();
     colorize(text, theme.muted)
 }
 
-pub(crate) fn heading(text: impl Into<String>) -> ColoredString {
+pub fn heading(text: impl Into<String>) -> ColoredString {
     let theme = active_theme();
     colorize(text, theme.heading)
 }
 
-pub(crate) fn value(text: impl Into<String>) -> ColoredString {
+pub fn value(text: impl Into<String>) -> ColoredString {
     let theme = active_theme();
     colorize(text, theme.value)
 }
 
-pub(crate) fn sky_value(text: impl Into<String>) -> ColoredString {
+pub fn sky_value(text: impl Into<String>) -> ColoredString {
     let theme = active_theme();
     colorize(text, theme.sky_value)
 }
 
-pub(crate) fn success(text: impl Into<String>) -> ColoredString {
+pub fn success(text: impl Into<String>) -> ColoredString {
     let theme = active_theme();
     colorize(text, theme.success)
 }
 
-pub(crate) fn warning(text: impl Into<String>) -> ColoredString {
+pub fn warning(text: impl Into<String>) -> ColoredString {
     let theme = active_theme();
     colorize(text, theme.warning)
 }
 
-pub(crate) fn error(text: impl Into<String>) -> ColoredString {
+pub fn error(text: impl Into<String>) -> ColoredString {
     let theme = active_theme();
     colorize(text, theme.error)
 }
 
-pub(crate) fn config_name(text: impl Into<String>) -> ColoredString {
+pub fn config_name(text: impl Into<String>) -> ColoredString {
     let theme = active_theme();
     colorize(text, theme.config_name)
 }
 
-pub(crate) fn section_marker(text: impl Into<String>) -> ColoredString {
+pub fn section_marker(text: impl Into<String>) -> ColoredString {
     let theme = active_theme();
     colorize(text, theme.section_marker)
 }
 
-pub(crate) fn prompt(text: impl Into<String>) -> ColoredString {
+pub fn prompt(text: impl Into<String>) -> ColoredString {
     let theme = active_theme();
     colorize(text, theme.prompt)
 }
 
-pub(crate) fn selection(text: impl Into<String>) -> ColoredString {
+pub fn selection(text: impl Into<String>) -> ColoredString {
     let theme = active_theme();
     colorize(text, theme.selection)
 }
 
-pub(crate) fn strong(text: impl Into<String>) -> ColoredString {
+pub fn strong(text: impl Into<String>) -> ColoredString {
     body(text).bold()
 }
 
@@ -0,0 +1,148 @@
+//! `--timing`: print the latency of every API call and a summary at exit.
+//!
+//! Each response is reported on stderr as it arrives, measured up to the
+//! response headers. The summary adds request count, p50/p95 latency and the
+//! bytes sent and received, so slow bulk jobs can be attributed to the server
+//! or the network.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::theme;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static SAMPLES: Mutex<Vec<Duration>> = Mutex::new(Vec::new());
+static BYTES_SENT: AtomicU64 = AtomicU64::new(0);
+static BYTES_RECEIVED: AtomicU64 = AtomicU64::new(0);
+
+/// Turn timing on for the rest of the process (`--timing`).
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Record one completed call and print its line.
+pub fn record(method: &str, path: &str, status: Option<u16>, elapsed: Duration, sent: u64) {
+    if !enabled() {
+        return;
+    }
+    BYTES_SENT.fetch_add(sent, Ordering::Relaxed);
+    if let Ok(mut samples) = SAMPLES.lock() {
+        samples.push(elapsed);
+    }
+    let status = status.map_or_else(|| "ERR".to_string(), |status| status.to_string());
+    eprintln!(
+        "{} {method} {path} {status} {}",
+        theme::muted("timing:"),
+        format_duration(elapsed)
+    );
+}
+
+pub fn record_received(bytes: u64) {
+    if enabled() {
+        BYTES_RECEIVED.fetch_add(bytes, Ordering::Relaxed);
+    }
+}
+
+/// Print the end-of-command summary; nothing when timing is off or no call was made.
+pub fn print_summary(total: Duration) {
+    if !enabled() {
+        return;
+    }
+    let samples = SAMPLES
+        .lock()
+        .map(|samples| samples.clone())
+        .unwrap_or_default();
+    if let Some(summary) = summary_line(
+        &samples,
+        BYTES_SENT.load(Ordering::Relaxed),
+        BYTES_RECEIVED.load(Ordering::Relaxed),
+        total,
+    ) {
+        eprintln!("{} {summary}", theme::muted("timing:"));
+    }
+}
+
+fn summary_line(samples: &[Duration], sent: u64, received: u64, total: Duration) -> Option<String> {
+    if samples.is_empty() {
+        return None;
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort();
+    Some(format!(
+        "{} request(s), p50 {}, p95 {}, max {}, sent {}, received {}, total {}",
+        sorted.len(),
+        format_duration(percentile(&sorted, 50)),
+        format_duration(percentile(&sorted, 95)),
+        format_duration(sorted[sorted.len() - 1]),
+        format_bytes(sent),
+        format_bytes(received),
+        format_duration(total),
+    ))
+}
+
+/// Nearest-rank percentile of an ascending, non-empty slice.
+pub fn percentile(sorted: &[Duration], percent: usize) -> Duration {
+    let rank = (sorted.len() * percent).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let millis = duration.as_secs_f64() * 1000.0;
+    if millis >= 1000.0 {
+        format!("{:.2} s", millis / 1000.0)
+    } else {
+        format!("{millis:.0} ms")
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const KIB: f64 = 1024.0;
+    let value = bytes as f64;
+    if value >= KIB * KIB {
+        format!("{:.1} MiB", value / KIB / KIB)
+    } else if value >= KIB {
+        format!("{:.1} KiB", value / KIB)
+    } else {
+        format!("{bytes} B")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().copied().map(Duration::from_millis).collect()
+    }
+
+    #[test]
+    fn percentile_uses_nearest_rank() {
+        let sorted = millis(&[10, 20, 30, 40, 50, 60, 70, 80, 90, 100]);
+
+        assert_eq!(percentile(&sorted, 50), Duration::from_millis(50));
+        assert_eq!(percentile(&sorted, 95), Duration::from_millis(100));
+        assert_eq!(percentile(&millis(&[7]), 95), Duration::from_millis(7));
+    }
+
+    #[test]
+    fn summary_line_reports_latency_and_bytes() {
+        let summary = summary_line(
+            &millis(&[120, 15, 30]),
+            2048,
+            3 * 1024 * 1024,
+            Duration::from_millis(1500),
+        )
+        .expect("samples produce a summary");
+
+        assert_eq!(
+            summary,
+            "3 request(s), p50 30 ms, p95 120 ms, max 120 ms, sent 2.0 KiB, received 3.0 MiB, total 1.50 s"
+        );
+        assert_eq!(summary_line(&[], 0, 0, Duration::ZERO), None);
+    }
+}
@@ -0,0 +1,347 @@
+//! WebAssembly plugins that post-process command output and filter files
+//! before upload, listed per config under `wasm_plugins`.
+//!
+//! A plugin is a core WebAssembly module that imports nothing and exports
+//! `memory`, `alloc(len: i32) -> i32`, and one or both hooks:
+//!
+//! - `transform_output(ptr: i32, len: i32) -> i64` is passed
+//!   `{"command": "<name>", "result": <value>}` as JSON and returns, as JSON,
+//!   the result to print instead. It runs before `--query` and `--template`.
+//! - `filter_ingest(ptr: i32, len: i32) -> i64` is passed a file's path within
+//!   the upload, a NUL byte, and the file's bytes. It returns the same layout
+//!   for what to upload in their place, or -1 to leave the file out; a new
+//!   path renames the file, e.g. after converting a proprietary format to
+//!   Markdown.
+//!
+//! Hooks return their output as `ptr << 32 | len` in their own memory. The
+//! plugins of a config run in the order listed, each call in a fresh instance.
+//!
+//! Plugins are interpreted with no host functions at all, so they cannot read
+//! files, open connections, or see the environment; credentials never reach
+//! them, only the bytes they are passed. Each call is limited to [`FUEL`]
+//! units of work and [`MAX_MEMORY`] bytes of memory, and may return at most
+//! [`MAX_OUTPUT`] bytes. A plugin that fails
+//! stops the command instead of letting unfiltered data through.
+
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde_json::{Value, json};
+use wasmi::{Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use crate::error::{Error, Result};
+
+/// Work one hook call may do, roughly one unit per instruction.
+pub const FUEL: u64 = 2_000_000_000;
+/// Linear memory one hook call may grow to.
+pub const MAX_MEMORY: usize = 512 * 1024 * 1024;
+/// Output one hook call may return.
+pub const MAX_OUTPUT: usize = 64 * 1024 * 1024;
+
+const OUTPUT_HOOK: &str = "transform_output";
+const INGEST_HOOK: &str = "filter_ingest";
+
+/// One compiled plugin module.
+pub struct Plugin {
+    path: PathBuf,
+    engine: Engine,
+    module: Module,
+    fuel: u64,
+}
+
+impl Plugin {
+    /// Compile the module at `path`, refusing one that imports anything or
+    /// exports no hook.
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path).map_err(|e| {
+            Error::Config(format!(
+                "Failed to read WASM plugin '{}': {e}",
+                path.display()
+            ))
+        })?;
+        let mut config = wasmi::Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, &bytes)
+            .map_err(|e| Error::Config(format!("Invalid WASM plugin '{}': {e}", path.display())))?;
+        if let Some(import) = module.imports().next() {
+            return Err(Error::Config(format!(
+                "WASM plugin '{}' imports {}::{}; plugins may not import anything",
+                path.display(),
+                import.module(),
+                import.name()
+            )));
+        }
+        let plugin = Self {
+            path: path.to_path_buf(),
+            engine,
+            module,
+            fuel: FUEL,
+        };
+        if !plugin.has(OUTPUT_HOOK) && !plugin.has(INGEST_HOOK) {
+            return Err(Error::Config(format!(
+                "WASM plugin '{}' exports neither {OUTPUT_HOOK} nor {INGEST_HOOK}",
+                path.display()
+            )));
+        }
+        Ok(plugin)
+    }
+
+    fn has(&self, hook: &str) -> bool {
+        self.module.exports().any(|export| export.name() == hook)
+    }
+
+    /// Call `hook` on `input` in a fresh instance; `None` when it returns -1.
+    fn call(&self, hook: &str, input: &[u8]) -> std::result::Result<Option<Vec<u8>>, String> {
+        let limits = StoreLimitsBuilder::new().memory_size(MAX_MEMORY).build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits: &mut StoreLimits| limits);
+        store.set_fuel(self.fuel).map_err(|e| e.to_string())?;
+        let instance = Linker::<StoreLimits>::new(&self.engine)
+            .instantiate(&mut store, &self.module)
+            .and_then(|instance| instance.start(&mut store))
+            .map_err(|e| e.to_string())?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or("it exports no memory")?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&store, "alloc")
+            .map_err(|e| format!("alloc: {e}"))?;
+        let hook = instance
+            .get_typed_func::<(i32, i32), i64>(&store, hook)
+            .map_err(|e| format!("{hook}: {e}"))?;
+
+        let len = i32::try_from(input.len()).map_err(|_| "the input is over 2 GiB")?;
+        let ptr = alloc.call(&mut store, len).map_err(|e| e.to_string())?;
+        memory
+            .write(&mut store, ptr as u32 as usize, input)
+            .map_err(|_| "alloc returned memory it does not have")?;
+        let packed = hook
+            .call(&mut store, (ptr, len))
+            .map_err(|e| e.to_string())?;
+        if packed == -1 {
+            return Ok(None);
+        }
+        let packed = packed as u64;
+        let (ptr, len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+        if len > MAX_OUTPUT {
+            return Err(format!("its output is over {} MiB", MAX_OUTPUT >> 20));
+        }
+        let output = ptr
+            .checked_add(len)
+            .and_then(|end| memory.data(&store).get(ptr..end))
+            .ok_or("it returned a range outside its memory")?;
+        Ok(Some(output.to_vec()))
+    }
+
+    fn failed(&self, on: &str, message: impl std::fmt::Display) -> String {
+        format!(
+            "WASM plugin '{}' failed on {on}: {message}",
+            self.path.display()
+        )
+    }
+}
+
+struct Installed {
+    command: String,
+    plugins: Vec<Plugin>,
+}
+
+static INSTALLED: OnceLock<Installed> = OnceLock::new();
+
+/// The installed plugins that export `hook`, in order.
+fn with_hook(hook: &str) -> impl Iterator<Item = &'static Plugin> {
+    INSTALLED
+        .get()
+        .into_iter()
+        .flat_map(|installed| &installed.plugins)
+        .filter(move |plugin| plugin.has(hook))
+}
+
+/// Load the plugins at `paths` for the rest of the process, which runs
+/// `command`. Called once from `main` after the config is read.
+pub fn install(paths: &[PathBuf], command: &str) -> Result<()> {
+    let plugins = paths
+        .iter()
+        .map(|path| Plugin::load(path))
+        .collect::<Result<_>>()?;
+    let _ = INSTALLED.set(Installed {
+        command: command.to_string(),
+        plugins,
+    });
+    Ok(())
+}
+
+/// Whether any installed plugin transforms output.
+pub fn transforms_output() -> bool {
+    with_hook(OUTPUT_HOOK).next().is_some()
+}
+
+/// Pass a command's result through every output plugin in turn.
+pub fn transform_output(result: Value) -> std::result::Result<Value, String> {
+    let command = INSTALLED.get().map_or("", |installed| &installed.command);
+    with_hook(OUTPUT_HOOK).try_fold(result, |result, plugin| {
+        let input = json!({"command": command, "result": result}).to_string();
+        let output = plugin
+            .call(OUTPUT_HOOK, input.as_bytes())
+            .map_err(|e| plugin.failed("output", e))?
+            .ok_or_else(|| plugin.failed("output", "it returned -1"))?;
+        serde_json::from_slice(&output)
+            .map_err(|e| plugin.failed("output", format!("it returned invalid JSON: {e}")))
+    })
+}
+
+/// Whether any installed plugin filters uploaded files.
+pub fn filters_ingest() -> bool {
+    with_hook(INGEST_HOOK).next().is_some()
+}
+
+/// Pass a file about to be uploaded as `name` through every ingest plugin in
+/// turn: what to upload in its place, or `None` when a plugin left it out.
+pub fn filter_ingest(name: &str, bytes: Vec<u8>) -> Result<Option<(String, Vec<u8>)>> {
+    let mut file = (name.to_string(), bytes);
+    for plugin in with_hook(INGEST_HOOK) {
+        let mut input = Vec::with_capacity(file.0.len() + 1 + file.1.len());
+        input.extend_from_slice(file.0.as_bytes());
+        input.push(0);
+        input.extend_from_slice(&file.1);
+        let output = plugin
+            .call(INGEST_HOOK, &input)
+            .map_err(|e| Error::Client(plugin.failed(&file.0, e)))?;
+        let Some(mut output) = output else {
+            return Ok(None);
+        };
+        let split = output.iter().position(|&byte| byte == 0).ok_or_else(|| {
+            Error::Client(plugin.failed(&file.0, "its output has no NUL after the path"))
+        })?;
+        let renamed = std::str::from_utf8(&output[..split])
+            .ok()
+            .filter(|renamed| is_relative_path(renamed))
+            .map(str::to_string)
+            .ok_or_else(|| Error::Client(plugin.failed(&file.0, "it returned an invalid path")))?;
+        file = (renamed, output.split_off(split + 1));
+    }
+    Ok(Some(file))
+}
+
+/// A non-empty relative path that stays inside the upload.
+fn is_relative_path(path: &str) -> bool {
+    !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A plugin whose hooks upper-case ASCII letters in place, and whose
+    /// ingest hook leaves out files named `skip`.
+    const UPPERCASE: &str = r#"
+        (module
+          (memory (export "memory") 2)
+          (global $next (mut i32) (i32.const 0))
+          (func (export "alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $next))
+            (global.set $next (i32.add (global.get $next) (local.get $len)))
+            (local.get $ptr))
+          (func $upper (param $ptr i32) (param $len i32) (result i64)
+            (local $i i32) (local $c i32)
+            (block $done
+              (loop $next
+                (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+                (local.set $c (i32.load8_u (i32.add (local.get $ptr) (local.get $i))))
+                (if (i32.and (i32.ge_u (local.get $c) (i32.const 97))
+                             (i32.le_u (local.get $c) (i32.const 122)))
+                  (then (i32.store8 (i32.add (local.get $ptr) (local.get $i))
+                                    (i32.sub (local.get $c) (i32.const 32)))))
+                (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                (br $next)))
+            (i64.or (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+                    (i64.extend_i32_u (local.get $len))))
+          (func (export "transform_output") (param i32 i32) (result i64)
+            (call $upper (local.get 0) (local.get 1)))
+          (func (export "filter_ingest") (param $ptr i32) (param $len i32) (result i64)
+            (if (i32.and (i32.eq (i32.load (local.get $ptr)) (i32.const 0x70696b73))
+                         (i32.eqz (i32.load8_u (i32.add (local.get $ptr) (i32.const 4)))))
+              (then (return (i64.const -1))))
+            (call $upper (local.get $ptr) (local.get $len))))
+    "#;
+
+    fn plugin(dir: &tempfile::TempDir, name: &str, wat: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        std::fs::write(&path, wat::parse_str(wat).unwrap()).unwrap();
+        path
+    }
+
+    #[test]
+    fn plugins_transform_output_and_filter_ingested_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugin = Plugin::load(&plugin(&dir, "upper.wasm", UPPERCASE)).unwrap();
+
+        let output = plugin.call(OUTPUT_HOOK, b"\"abc\"").unwrap().unwrap();
+        assert_eq!(output, b"\"ABC\"");
+        let output = plugin
+            .call(INGEST_HOOK, b"notes.md\0secret")
+            .unwrap()
+            .unwrap();
+        assert_eq!(output, b"NOTES.MD\0SECRET");
+        assert_eq!(plugin.call(INGEST_HOOK, b"skip\0anything").unwrap(), None);
+    }
+
+    #[test]
+    fn plugins_that_import_or_misbehave_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let importing = plugin(
+            &dir,
+            "env.wasm",
+            r#"(module (import "wasi_snapshot_preview1" "environ_get" (func (param i32 i32) (result i32))))"#,
+        );
+        let error = Plugin::load(&importing).err().unwrap().to_string();
+        assert!(error.contains("imports wasi_snapshot_preview1::environ_get"));
+
+        let no_hooks = plugin(&dir, "empty.wasm", "(module)");
+        assert!(Plugin::load(&no_hooks).is_err());
+
+        let spinning = plugin(
+            &dir,
+            "spin.wasm",
+            r#"(module
+                 (memory (export "memory") 1)
+                 (func (export "alloc") (param i32) (result i32) (i32.const 0))
+                 (func (export "transform_output") (param i32 i32) (result i64)
+                   (loop $forever (br $forever))
+                   (i64.const 0)))"#,
+        );
+        let mut spinning = Plugin::load(&spinning).unwrap();
+        spinning.fuel = 100_000;
+        let error = spinning.call(OUTPUT_HOOK, b"{}").unwrap_err();
+        assert!(error.contains("fuel"), "{error}");
+
+        let returning = |name: &str, packed: &str| {
+            let wat = format!(
+                r#"(module
+                     (memory (export "memory") 1)
+                     (func (export "alloc") (param i32) (result i32) (i32.const 0))
+                     (func (export "transform_output") (param i32 i32) (result i64)
+                       (i64.const {packed})))"#
+            );
+            Plugin::load(&plugin(&dir, name, &wat)).unwrap()
+        };
+        let error = returning("huge.wasm", "0xffffffff")
+            .call(OUTPUT_HOOK, b"{}")
+            .unwrap_err();
+        assert!(error.contains("over 64 MiB"), "{error}");
+        let error = returning("outside.wasm", "0xfff000002000")
+            .call(OUTPUT_HOOK, b"{}")
+            .unwrap_err();
+        assert!(error.contains("outside its memory"), "{error}");
+
+        assert!(is_relative_path("docs/a.md"));
+        assert!(!is_relative_path("../a.md"));
+        assert!(!is_relative_path("/etc/passwd"));
+        assert!(!is_relative_path(""));
+    }
+}
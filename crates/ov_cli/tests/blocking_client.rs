@@ -0,0 +1,104 @@
+#![cfg(feature = "blocking")]
+
+mod common;
+
+use common::MockServer;
+use ov_cli::client::SearchScoring;
+use ov_cli::{blocking, client};
+use serde_json::{Value, json};
+
+#[test]
+fn blocking_client_talks_to_a_server_without_a_runtime() {
+    let server = MockServer::start(&[("resources/guide.md", "Guide\nHow to start.\n")]);
+    let client = blocking::HttpClient::new(client::HttpClient::new(
+        server.url.as_str(),
+        None,
+        None,
+        None,
+        None,
+        5.0,
+        false,
+        None,
+    ))
+    .unwrap();
+
+    assert_eq!(
+        client.read("viking://resources/guide.md").unwrap(),
+        "Guide\nHow to start.\n"
+    );
+    let listing: Value = client
+        .get(
+            "/api/v1/fs/ls",
+            &[("uri".to_string(), "viking://resources".to_string())],
+        )
+        .unwrap();
+    assert_eq!(listing[0]["uri"], "viking://resources/guide.md");
+
+    client
+        .write(
+            "viking://resources/notes.md",
+            "alpha beta",
+            "create",
+            false,
+            None,
+        )
+        .unwrap();
+    assert_eq!(
+        client.read("viking://resources/notes.md").unwrap(),
+        "alpha beta"
+    );
+    let found = client
+        .find(
+            "beta".to_string(),
+            String::new(),
+            None,
+            10,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            &SearchScoring::default(),
+        )
+        .unwrap();
+    assert_eq!(found["resources"][0]["uri"], "viking://resources/notes.md");
+
+    let session: Value = client.post("/api/v1/sessions", &json!({})).unwrap();
+    assert!(session["session_id"].is_string());
+    assert!(client.read("viking://resources/missing.md").is_err());
+}
+
+#[test]
+fn blocking_client_refuses_to_run_inside_a_runtime() {
+    let server = MockServer::start(&[("resources/guide.md", "Guide\n")]);
+    let inner = || {
+        client::HttpClient::new(
+            server.url.as_str(),
+            None,
+            None,
+            None,
+            None,
+            5.0,
+            false,
+            None,
+        )
+    };
+    let client = blocking::HttpClient::new(inner()).unwrap();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    let error = runtime
+        .block_on(async { client.read("viking://resources/guide.md") })
+        .unwrap_err();
+    assert!(
+        error.to_string().contains("inside an async runtime"),
+        "{error}"
+    );
+    let created = runtime.block_on(async { blocking::HttpClient::new(inner()).is_err() });
+    assert!(created);
+    assert_eq!(
+        client.read("viking://resources/guide.md").unwrap(),
+        "Guide\n"
+    );
+}
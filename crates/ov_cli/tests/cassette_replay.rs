@@ -0,0 +1,80 @@
+mod common;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use common::{Home, MockServer};
+use ov_cli::cassette::{CASSETTE_ENV, Cassette, RECORD_ENV};
+use ov_cli::client::HttpClient;
+use serde_json::{Value, json};
+
+/// Nothing listens here, so any request that reaches the network fails.
+const NO_SERVER: &str = "http://127.0.0.1:9";
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+#[test]
+fn find_answers_from_a_cassette_without_a_server() {
+    let home = Home::new(NO_SERVER);
+    let output = Home::run(
+        home.command()
+            .env(CASSETTE_ENV, fixture("find.cassette.json"))
+            .args(["-o", "ndjson", "find", "hello"]),
+    );
+    let hits: Vec<Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0]["uri"], "viking://resources/hello.md");
+}
+
+#[test]
+fn recorded_commands_replay_the_same_output_once_the_server_is_gone() {
+    let mut server = MockServer::start(&[
+        ("resources/guide.md", "Guide\nHow to start.\n"),
+        ("resources/notes/a.md", "A note\n"),
+    ]);
+    let cassette = server.home.path().join("ls.cassette.json");
+    let ls = ["-o", "json", "ls", "viking://resources"];
+
+    let recorded = Home::run(
+        server
+            .home
+            .command()
+            .env(CASSETTE_ENV, &cassette)
+            .env(RECORD_ENV, "1")
+            .args(ls),
+    );
+    server.stop();
+    let replayed = Home::run(server.home.command().env(CASSETTE_ENV, &cassette).args(ls));
+
+    assert_eq!(replayed.stdout, recorded.stdout);
+    let listing: Value = serde_json::from_slice(&replayed.stdout).unwrap();
+    assert_eq!(listing["result"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn library_clients_replay_cassettes() {
+    let cassette = Cassette::replay(fixture("find.cassette.json")).unwrap();
+    let client = HttpClient::new(NO_SERVER, None, None, None, None, 5.0, false, None)
+        .with_cassette(Some(Arc::new(cassette)));
+
+    let found: Value = client
+        .post("/api/v1/search/find", &json!({ "query": "hello" }))
+        .await
+        .unwrap();
+    assert_eq!(found["total"], 1);
+
+    let error = client
+        .post::<_, Value>("/api/v1/search/find", &json!({ "query": "hello" }))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("no response left"));
+}
@@ -0,0 +1,155 @@
+//! Runs the `ov` binary from a home directory of its own, against an
+//! `ov mock-server` loaded with fixtures or any other server.
+
+#![allow(dead_code)]
+
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+
+use tempfile::TempDir;
+
+const OV: &str = env!("CARGO_BIN_EXE_ov");
+
+/// A home directory with an English display language and a config naming
+/// the server to talk to.
+pub struct Home {
+    dir: TempDir,
+}
+
+impl Home {
+    pub fn new(url: &str) -> Self {
+        let home = Self {
+            dir: tempfile::tempdir().unwrap(),
+        };
+        std::fs::create_dir_all(home.config_dir()).unwrap();
+        std::fs::write(
+            home.config_dir().join("ovcli.settings.conf"),
+            r#"{"language": "en"}"#,
+        )
+        .unwrap();
+        home.set_url(url);
+        home
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    fn config_dir(&self) -> PathBuf {
+        self.path().join(".openviking")
+    }
+
+    pub fn set_url(&self, url: &str) {
+        std::fs::write(
+            self.config_dir().join("ovcli.conf"),
+            serde_json::json!({ "url": url }).to_string(),
+        )
+        .unwrap();
+    }
+
+    /// `ov` with this home and none of the caller's `OPENVIKING_*` or `OV_*`
+    /// settings.
+    pub fn command(&self) -> Command {
+        let mut command = Command::new(OV);
+        for (name, _) in std::env::vars() {
+            if name.starts_with("OPENVIKING_") || name.starts_with("OV_") {
+                command.env_remove(name);
+            }
+        }
+        command
+            .env("HOME", self.path())
+            .env(
+                "OPENVIKING_CLI_CONFIG_FILE",
+                self.config_dir().join("ovcli.conf"),
+            )
+            .env("OPENVIKING_NO_DAEMON", "1")
+            .env("NO_COLOR", "1")
+            .stdin(Stdio::null());
+        command
+    }
+
+    /// Run `command`, which must succeed.
+    pub fn run(command: &mut Command) -> Output {
+        let output = command.output().unwrap();
+        assert!(
+            output.status.success(),
+            "`{command:?}` failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        output
+    }
+
+    /// Run `ov args`, which must succeed.
+    pub fn ov(&self, args: &[&str]) -> Output {
+        Self::run(self.command().args(args))
+    }
+
+    /// The standard output of `ov args`.
+    pub fn stdout(&self, args: &[&str]) -> String {
+        String::from_utf8(self.ov(args).stdout).unwrap()
+    }
+}
+
+pub struct MockServer {
+    child: Child,
+    pub home: Home,
+    pub url: String,
+}
+
+impl MockServer {
+    /// Start a mock server serving `files`, each given as
+    /// `(path under viking://, content)`, with a home configured for it.
+    pub fn start(files: &[(&str, &str)]) -> Self {
+        let home = Home::new("http://127.0.0.1:9");
+        let fixtures = home.path().join("fixtures");
+        std::fs::create_dir_all(&fixtures).unwrap();
+        for (path, content) in files {
+            let path = fixtures.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        let mut child = home
+            .command()
+            .args(["mock-server", "--port", "0", "--fixtures"])
+            .arg(&fixtures)
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut stderr = BufReader::new(child.stderr.take().unwrap());
+        let mut line = String::new();
+        stderr.read_line(&mut line).unwrap();
+        let url = line
+            .trim()
+            .strip_prefix("ov mock-server listening on ")
+            .unwrap_or_else(|| panic!("unexpected mock-server output: {line}"))
+            .to_string();
+        // The server logs every request; keep reading so it never blocks.
+        std::thread::spawn(move || std::io::copy(&mut stderr, &mut std::io::sink()));
+
+        home.set_url(&url);
+        Self { child, home, url }
+    }
+
+    /// Shut the server down, keeping the home directory.
+    pub fn stop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+
+    pub fn ov(&self, args: &[&str]) -> Output {
+        self.home.ov(args)
+    }
+
+    pub fn stdout(&self, args: &[&str]) -> String {
+        self.home.stdout(args)
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
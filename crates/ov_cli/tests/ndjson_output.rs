@@ -0,0 +1,68 @@
+mod common;
+
+use common::MockServer;
+use serde_json::Value;
+
+const FILES: &[(&str, &str)] = &[
+    ("resources/hello.md", "Hello world\nA first note.\n"),
+    (
+        "resources/notes/hello-again.md",
+        "Hello again\nA second note.\n",
+    ),
+    ("resources/notes/other.md", "Nothing to see\n"),
+];
+
+fn json_lines(stdout: &str) -> Vec<Value> {
+    stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap_or_else(|e| panic!("not JSON ({e}): {line}")))
+        .collect()
+}
+
+#[test]
+fn ndjson_output_is_one_json_record_per_line() {
+    let server = MockServer::start(FILES);
+
+    let hits = json_lines(&server.stdout(&["-o", "ndjson", "find", "hello"]));
+    assert_eq!(hits.len(), 2);
+    assert!(hits.iter().all(|hit| hit["uri"].is_string()));
+
+    let entries = json_lines(&server.stdout(&["-o", "ndjson", "ls", "viking://resources/notes"]));
+    let uris: Vec<&str> = entries.iter().filter_map(|e| e["uri"].as_str()).collect();
+    assert_eq!(
+        uris,
+        [
+            "viking://resources/notes/hello-again.md",
+            "viking://resources/notes/other.md"
+        ]
+    );
+
+    let matches = json_lines(&server.stdout(&["-o", "jsonl", "glob", "**/hello*.md"]));
+    assert_eq!(matches.len(), 2);
+}
+
+#[test]
+fn streamed_records_carry_per_record_options() {
+    let server = MockServer::start(FILES);
+
+    let hits = json_lines(&server.stdout(&["-o", "ndjson", "find", "hello", "--snippets"]));
+    assert_eq!(hits.len(), 2);
+    assert!(hits.iter().all(|hit| hit["snippets"].is_array()));
+
+    let entries =
+        json_lines(&server.stdout(&["-o", "ndjson", "ls", "viking://resources/notes", "--tokens"]));
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().all(|entry| entry["tokens"].is_u64()));
+}
+
+#[test]
+fn command_echo_stays_off_stdout() {
+    let server = MockServer::start(FILES);
+
+    let stdout = server.stdout(&["-o", "json", "find", "hello"]);
+    serde_json::from_str::<Value>(&stdout).unwrap();
+
+    let output = server.ov(&["find", "hello"]);
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("cmd: ov find"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cmd: ov find"));
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use common::MockServer;
+use serde_json::Value;
+
+#[test]
+fn rm_glob_previews_and_removes_files_and_directories() {
+    let server = MockServer::start(&[
+        ("resources/notes/a.md", "A note\n"),
+        ("resources/news.md", "News\n"),
+        ("resources/other.md", "Other\n"),
+    ]);
+
+    let preview: Value = serde_json::from_str(&server.stdout(&[
+        "-o",
+        "json",
+        "rm",
+        "--glob",
+        "viking://resources/n*",
+        "--dry-run",
+    ]))
+    .unwrap();
+    assert_eq!(
+        preview["result"]["uris"],
+        serde_json::json!(["viking://resources/news.md", "viking://resources/notes"])
+    );
+
+    server.ov(&["rm", "--glob", "viking://resources/n*", "-r", "--yes"]);
+    let left: Value = serde_json::from_str(&server.stdout(&[
+        "-o",
+        "json",
+        "glob",
+        "**",
+        "--uri",
+        "viking://resources",
+    ]))
+    .unwrap();
+    assert_eq!(
+        left["result"]["matches"],
+        serde_json::json!(["viking://resources/other.md"])
+    );
+}
@@ -18,6 +18,7 @@ from typing import Any, Dict, List, Optional, Tuple
 from openviking.core.retrieval_targets import default_target_directories
 from openviking.models.embedder.base import EmbedResult, embed_compat
 from openviking.models.rerank import RerankClient
+from openviking.retrieve.keyword_scoring import keyword_scores, tokenize
 from openviking.retrieve.memory_lifecycle import hotness_score
 from openviking.retrieve.retrieval_stats import get_stats_collector
 from openviking.server.identity import RequestContext
@@ -34,6 +35,7 @@ from openviking_cli.retrieve.types import (
     ContextType,
     MatchedContext,
     QueryResult,
+    ScoringOptions,
     TypedQuery,
 )
 from openviking_cli.utils.config import RerankConfig, RetrievalConfig
@@ -55,6 +57,7 @@ class HierarchicalRetriever:
     DIRECTORY_DOMINANCE_RATIO = 1.2  # Directory score must exceed max child score
     GLOBAL_SEARCH_TOPK = 10  # Global retrieval count (more candidates = better rerank precision)
     MAX_PARALLEL_CHILD_SEARCHES = 4  # Limit per-request fan-out against remote vector stores
+    KEYWORD_SCAN_LIMIT = 10000  # Records scanned for keyword hits without backend keyword search
     LEVEL_URI_SUFFIX = {0: ".abstract.md", 1: ".overview.md"}
 
     def __init__(
@@ -106,6 +109,7 @@ class HierarchicalRetriever:
         score_gte: bool = False,
         scope_dsl: Optional[FilterExpr | Dict[str, Any]] = None,
         level: Optional[List[int]] = None,
+        scoring: Optional[ScoringOptions] = None,
     ) -> QueryResult:
         """
         Execute hierarchical retrieval.
@@ -116,17 +120,26 @@ class HierarchicalRetriever:
             score_gte: True uses >=, False uses >
             scope_dsl: Additional scope constraints passed from public find/search filter
             level: Optional result level filter (0=L0, 1=L1, 2=L2)
+            scoring: Ranking mode, rerank override and hybrid weights
         """
         t0 = time.monotonic()
         telemetry = get_current_telemetry()
         effective_threshold = self._resolve_threshold(score_threshold)
         image_query = bool(getattr(query, "image_query", False))
+        scoring = scoring or ScoringOptions()
+        if scoring.rerank and not self._rerank_client:
+            raise InvalidArgumentError("Rerank was requested but no rerank model is configured.")
         if mode is None:
             mode = RetrieverMode.QUICK if not self._rerank_client else RetrieverMode.THINKING
         if image_query:
             mode = RetrieverMode.QUICK
             if level is None:
                 level = [2]
+        use_rerank = self._uses_rerank(mode, scoring.rerank)
+        # Keyword scores re-rank vector candidates joined by the scope's keyword
+        # hits, so draw a wider pool first.
+        keyword_ranking = scoring.mode in ("keyword", "hybrid") and not image_query
+        pool_limit = max(limit * 5, 50) if keyword_ranking else limit
 
         # 创建 proxy 包装器，绑定当前 ctx
         vector_proxy = VikingDBManagerProxy(self.vector_store, ctx)
@@ -173,7 +186,9 @@ class HierarchicalRetriever:
             context_type = ContextType.RESOURCE.value
 
         if mode == RetrieverMode.QUICK:
-            search_limit = max(limit * 5, 50) if image_query else max(limit, self.GLOBAL_SEARCH_TOPK)
+            search_limit = (
+                max(limit * 5, 50) if image_query else max(pool_limit, self.GLOBAL_SEARCH_TOPK)
+            )
             with telemetry.measure("search.vector_retrieval"):
                 quick_results = await vector_proxy.search_in_tenant(
                     query_vector=query_vector,
@@ -201,6 +216,7 @@ class HierarchicalRetriever:
                 candidate = dict(result)
                 candidate["_score"] = score
                 candidate["_final_score"] = score
+                candidate["_vector_score"] = score
 
                 previous = collected_by_uri.get(uri)
                 if previous is None or score > previous.get("_final_score", 0.0):
@@ -247,12 +263,13 @@ class HierarchicalRetriever:
                     )
 
             # Step 3: Pick recursive entry points from directory hits and explicit roots.
-            directory_scores = [self._finite_score(r.get("_score", 0.0)) for r in global_results]
-            if self._rerank_client and mode == RetrieverMode.THINKING:
+            vector_scores = [self._finite_score(r.get("_score", 0.0)) for r in global_results]
+            directory_scores = vector_scores
+            if use_rerank:
                 directory_scores = await self._rerank_scores(
                     query.query,
                     [str(r.get("abstract", "")) for r in global_results],
-                    directory_scores,
+                    vector_scores,
                 )
 
             starting_points = []
@@ -272,11 +289,16 @@ class HierarchicalRetriever:
             # Add directory hits to the result pool only when explicitly requested.
             initial_candidates = []
             if level is not None:
-                for result, score in zip(global_results, directory_scores, strict=True):
+                for result, vector_score, score in zip(
+                    global_results, vector_scores, directory_scores, strict=True
+                ):
                     if result.get("level", 2) not in level:
                         continue
                     candidate = dict(result)
                     candidate["_score"] = score
+                    candidate["_vector_score"] = vector_score
+                    if use_rerank:
+                        candidate["_rerank_score"] = score
                     initial_candidates.append(candidate)
 
             # Step 4: Recursive search
@@ -287,8 +309,9 @@ class HierarchicalRetriever:
                     query_vector=query_vector,
                     sparse_query_vector=sparse_query_vector,
                     starting_points=starting_points,
-                    limit=limit,
+                    limit=pool_limit,
                     mode=mode,
+                    rerank=scoring.rerank,
                     threshold=effective_threshold,
                     score_gte=score_gte,
                     context_type=context_type,
@@ -298,13 +321,31 @@ class HierarchicalRetriever:
                     level=level,
                 )
             apply_hotness = True
-            rerank_used = self._rerank_client is not None and mode == RetrieverMode.THINKING
+            rerank_used = use_rerank
+
+        # Step 5: Keyword scoring for keyword and hybrid modes
+        if keyword_ranking:
+            pooled_uris = {c.get("uri") for c in candidates}
+            for hit in await self._keyword_recall(
+                vector_proxy,
+                query.query,
+                context_type=context_type,
+                target_dirs=target_dirs,
+                scope_dsl=scope_dsl,
+                level=level,
+                limit=pool_limit,
+            ):
+                if hit.get("uri") and hit["uri"] not in pooled_uris:
+                    pooled_uris.add(hit["uri"])
+                    candidates.append(hit)
+            candidates = self._apply_keyword_scores(query.query, candidates, scoring)
 
         # Step 6: Convert results
         matched = await self._convert_to_matched_contexts(
             candidates,
             ctx=ctx,
             apply_hotness=apply_hotness,
+            include_scores=scoring.include_scores,
         )
         final = matched[:limit]
 
@@ -323,6 +364,92 @@ class HierarchicalRetriever:
             searched_directories=root_uris,
         )
 
+    def _uses_rerank(self, mode: str, rerank: Optional[bool] = None) -> bool:
+        """Whether to rerank: by request when given, otherwise in thinking mode."""
+        if not self._rerank_client or rerank is False:
+            return False
+        return bool(rerank) or mode == RetrieverMode.THINKING
+
+    async def _keyword_recall(
+        self,
+        vector_proxy: VikingDBManagerProxy,
+        query: str,
+        context_type: Optional[str],
+        target_dirs: List[str],
+        scope_dsl: Optional[FilterExpr | Dict[str, Any]],
+        level: Optional[List[int]],
+        limit: int,
+    ) -> List[Dict[str, Any]]:
+        """Records in scope that contain the query's terms, whatever their vector score.
+
+        Uses the backend's keyword search, or scans up to ``KEYWORD_SCAN_LIMIT``
+        records of the scope where it has none. Hits carry no semantic score.
+        """
+        scope = {
+            "context_type": context_type,
+            "target_directories": target_dirs,
+            "extra_filter": scope_dsl,
+            "level": level,
+        }
+        try:
+            hits = await vector_proxy.keyword_search_in_tenant(query=query, limit=limit, **scope)
+        except Exception as e:
+            if not isinstance(e, NotImplementedError):
+                logger.warning("Keyword search failed, scanning the scope instead: %s", e)
+            terms = set(tokenize(query))
+            hits = []
+            cursor = None
+            scanned = 0
+            while terms and scanned < self.KEYWORD_SCAN_LIMIT:
+                page, cursor = await vector_proxy.scroll_in_tenant(
+                    limit=min(1000, self.KEYWORD_SCAN_LIMIT - scanned), cursor=cursor, **scope
+                )
+                scanned += len(page)
+                hits.extend(
+                    record
+                    for record in page
+                    if terms & set(tokenize(self._keyword_document(record)))
+                )
+                if not cursor:
+                    break
+        return [{**hit, "_score": 0.0, "_final_score": 0.0} for hit in hits]
+
+    @staticmethod
+    def _keyword_document(candidate: Dict[str, Any]) -> str:
+        """The text keyword scores match against: the URI's last segment and the abstract."""
+        name = candidate.get("uri", "").rstrip("/").rsplit("/", 1)[-1]
+        return f"{name} {candidate.get('abstract', '')}"
+
+    @classmethod
+    def _apply_keyword_scores(
+        cls,
+        query: str,
+        candidates: List[Dict[str, Any]],
+        scoring: ScoringOptions,
+    ) -> List[Dict[str, Any]]:
+        """Rank candidates by keyword score alone, or blended with their semantic score.
+
+        Keyword mode drops candidates that contain none of the query's terms.
+        """
+        documents = [cls._keyword_document(c) for c in candidates]
+        scores = keyword_scores(query, documents)
+        weight_sum = scoring.vector_weight + scoring.keyword_weight
+        ranked = []
+        for candidate, keyword_score in zip(candidates, scores, strict=True):
+            candidate["_keyword_score"] = keyword_score
+            semantic_score = candidate.get("_final_score", candidate.get("_score", 0.0))
+            if scoring.mode == "keyword":
+                if keyword_score <= 0:
+                    continue
+                candidate["_final_score"] = keyword_score
+            else:
+                candidate["_final_score"] = (
+                    scoring.vector_weight * semantic_score + scoring.keyword_weight * keyword_score
+                ) / weight_sum
+            ranked.append(candidate)
+        ranked.sort(key=lambda c: c["_final_score"], reverse=True)
+        return ranked
+
     def _resolve_threshold(self, threshold: Optional[float]) -> float:
         resolved = threshold if threshold is not None else self.threshold
         return resolved if resolved is not None else 0.0
@@ -400,6 +527,7 @@ class HierarchicalRetriever:
         starting_points: List[Tuple[str, float]],
         limit: int,
         mode: str,
+        rerank: Optional[bool] = None,
         threshold: Optional[float] = None,
         score_gte: bool = False,
         context_type: Optional[str] = None,
@@ -494,13 +622,20 @@ class HierarchicalRetriever:
                 if not results:
                     continue
 
-                query_scores = [self._finite_score(r.get("_score", 0.0)) for r in results]
-                if self._rerank_client and mode == RetrieverMode.THINKING:
+                vector_scores = [self._finite_score(r.get("_score", 0.0)) for r in results]
+                query_scores = vector_scores
+                reranked = self._uses_rerank(mode, rerank)
+                if reranked:
                     documents = [str(r.get("abstract", "")) for r in results]
-                    query_scores = await self._rerank_scores(query, documents, query_scores)
+                    query_scores = await self._rerank_scores(query, documents, vector_scores)
 
-                for r, score in zip(results, query_scores, strict=True):
+                for r, vector_score, score in zip(
+                    results, vector_scores, query_scores, strict=True
+                ):
                     uri = r.get("uri", "")
+                    r["_vector_score"] = vector_score
+                    if reranked:
+                        r["_rerank_score"] = score
                     final_score = (
                         alpha * score + (1 - alpha) * current_score if current_score else score
                     )
@@ -565,6 +700,7 @@ class HierarchicalRetriever:
         candidates: List[Dict[str, Any]],
         ctx: RequestContext,
         apply_hotness: bool = True,
+        include_scores: bool = False,
     ) -> List[MatchedContext]:
         """Convert candidate results to MatchedContext list.
 
@@ -615,6 +751,7 @@ class HierarchicalRetriever:
                     category=c.get("category", ""),
                     score=final_score,
                     relations=relations,
+                    scores=self._strategy_scores(c) if include_scores else None,
                 )
             )
 
@@ -622,6 +759,19 @@ class HierarchicalRetriever:
         results.sort(key=lambda x: x.score, reverse=True)
         return results
 
+    @classmethod
+    def _strategy_scores(cls, candidate: Dict[str, Any]) -> Dict[str, float]:
+        """The score each ranking strategy gave ``candidate``."""
+        return {
+            name: cls._finite_score(candidate[key])
+            for name, key in (
+                ("vector", "_vector_score"),
+                ("keyword", "_keyword_score"),
+                ("rerank", "_rerank_score"),
+            )
+            if key in candidate
+        }
+
     @classmethod
     def _append_level_suffix(cls, uri: str, level: int) -> str:
         """Return user-facing URI with L0/L1 suffix reconstructed by level."""
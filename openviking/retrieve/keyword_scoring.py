@@ -0,0 +1,45 @@
+# Copyright (c) 2026 Beijing Volcano Engine Technology Co., Ltd.
+# SPDX-License-Identifier: AGPL-3.0
+"""
+Keyword scoring for retrieval candidates.
+
+Scores how much of a query's vocabulary a candidate contains, weighting each
+term by how rare it is among the candidates, so exact terms such as clause
+numbers or defined names outrank words every candidate shares.
+"""
+
+import math
+import re
+from typing import List
+
+_CJK = "\u4e00-\u9fff"
+# One token per CJK character, otherwise runs of letters and digits.
+_TOKEN_PATTERN = re.compile(rf"[{_CJK}]|[^\W_{_CJK}]+")
+
+
+def tokenize(text: str) -> List[str]:
+    """Lowercased terms of ``text``."""
+    return _TOKEN_PATTERN.findall(text.lower())
+
+
+def keyword_scores(query: str, documents: List[str]) -> List[float]:
+    """Score each document in [0, 1] by the query terms it contains.
+
+    A term's weight is its inverse document frequency across ``documents``;
+    a document's score is the weight of the query terms it contains over the
+    weight of all of them.
+    """
+    terms = set(tokenize(query))
+    if not terms or not documents:
+        return [0.0] * len(documents)
+    document_terms = [set(tokenize(document)) for document in documents]
+    total = len(documents)
+    weights = {
+        term: math.log(1 + (total + 1) / (sum(term in doc for doc in document_terms) + 0.5))
+        for term in terms
+    }
+    weight_sum = sum(weights.values())
+    return [
+        sum(weight for term, weight in weights.items() if term in doc) / weight_sum
+        for doc in document_terms
+    ]
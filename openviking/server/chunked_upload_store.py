@@ -0,0 +1,216 @@
+# Copyright (c) 2026 Beijing Volcano Engine Technology Co., Ltd.
+# SPDX-License-Identifier: AGPL-3.0
+"""Resumable chunked uploads that finish as regular temporary uploads.
+
+A client declares the final size and SHA-256, appends chunks at the offset the
+server reports, then completes the upload. Completion verifies the assembled
+file against the declared size and checksum and hands it to
+:class:`TempUploadStore`, so ``add_resource`` consumes the returned
+``temp_file_id`` like any other upload. Partial uploads live under
+``chunked/`` in the upload temp directory and survive client restarts.
+"""
+
+from __future__ import annotations
+
+import asyncio
+import json
+import re
+import time
+import uuid
+from contextlib import suppress
+from pathlib import Path
+from typing import Any, AsyncIterator, BinaryIO, Optional
+
+from openviking.server.config import ServerConfig
+from openviking.server.content_hash_index import file_sha256
+from openviking.server.identity import RequestContext
+from openviking.server.temp_upload_store import TempUploadStore
+from openviking_cli.exceptions import (
+    ConflictError,
+    FailedPreconditionError,
+    InvalidArgumentError,
+    NotFoundError,
+)
+from openviking_cli.utils.config.open_viking_config import get_openviking_config
+
+_STATE_DIR = "chunked"
+_MAX_AGE_SECONDS = 24 * 3600
+_UPLOAD_ID_RE = re.compile(r"^[0-9a-f]{32}$")
+_SHA256_RE = re.compile(r"^[0-9a-f]{64}$")
+
+# Serializes appends and completion of one upload, so concurrent requests for the
+# same upload id never interleave writes or hash a file that is still growing.
+_upload_locks: dict[str, asyncio.Lock] = {}
+
+
+def _upload_lock(upload_id: str) -> asyncio.Lock:
+    return _upload_locks.setdefault(upload_id, asyncio.Lock())
+
+
+class _AssembledFile:
+    """Minimal ``UploadFile`` stand-in so the assembled file reuses ``save_upload``."""
+
+    def __init__(self, file: BinaryIO, filename: str):
+        self._file = file
+        self.filename = filename
+        self.content_type: Optional[str] = None
+
+    async def read(self, size: int = -1) -> bytes:
+        return self._file.read(size)
+
+
+class ChunkedUploadStore:
+    def __init__(self, server_config: ServerConfig):
+        self.server_config = server_config
+        self.temp_cfg = server_config.temp_upload
+
+    @staticmethod
+    def build(server_config: ServerConfig) -> "ChunkedUploadStore":
+        return ChunkedUploadStore(server_config)
+
+    def start(self, filename: str, size: int, sha256: str, ctx: RequestContext) -> dict[str, Any]:
+        if size < 0:
+            raise InvalidArgumentError("size must not be negative.")
+        if size > self.temp_cfg.shared_max_size_bytes:
+            raise InvalidArgumentError(
+                f"Upload exceeds size limit ({self.temp_cfg.shared_max_size_bytes} bytes)."
+            )
+        sha256 = sha256.lower()
+        if not _SHA256_RE.match(sha256):
+            raise InvalidArgumentError("sha256 must be a 64-character hex digest.")
+
+        state_dir = self._state_dir()
+        self._cleanup_expired(state_dir)
+        upload_id = uuid.uuid4().hex
+        meta = {
+            "upload_id": upload_id,
+            "filename": Path(filename).name or "upload.tmp",
+            "size": size,
+            "sha256": sha256,
+            "account": ctx.account_id,
+            "user": ctx.user.user_id,
+            "created_at": int(time.time()),
+        }
+        (state_dir / f"{upload_id}.part").touch()
+        (state_dir / f"{upload_id}.json").write_text(json.dumps(meta), encoding="utf-8")
+        return self._status(meta, 0)
+
+    def status(self, upload_id: str, ctx: RequestContext) -> dict[str, Any]:
+        meta = self._load_meta(upload_id, ctx)
+        return self._status(meta, self._part_path(upload_id).stat().st_size)
+
+    async def append(
+        self,
+        upload_id: str,
+        offset: int,
+        chunks: AsyncIterator[bytes],
+        ctx: RequestContext,
+    ) -> dict[str, Any]:
+        async with _upload_lock(upload_id):
+            meta = self._load_meta(upload_id, ctx)
+            part_path = self._part_path(upload_id)
+            received = part_path.stat().st_size
+            if offset != received:
+                raise ConflictError(
+                    f"Chunk offset {offset} does not match the {received} bytes already received.",
+                    resource=upload_id,
+                )
+
+            with open(part_path, "ab") as f:
+                async for chunk in chunks:
+                    if received + len(chunk) > meta["size"]:
+                        f.truncate(offset)
+                        raise InvalidArgumentError(
+                            f"Chunk runs past the declared upload size ({meta['size']} bytes)."
+                        )
+                    f.write(chunk)
+                    received += len(chunk)
+            return self._status(meta, received)
+
+    async def complete(self, upload_id: str, upload_mode: str, ctx: RequestContext) -> str:
+        async with _upload_lock(upload_id):
+            meta = self._load_meta(upload_id, ctx)
+            part_path = self._part_path(upload_id)
+            received = part_path.stat().st_size
+            if received != meta["size"]:
+                raise FailedPreconditionError(
+                    f"Upload is incomplete: {received} of {meta['size']} bytes received.",
+                    details={"upload_id": upload_id, "received": received},
+                )
+
+            actual = await asyncio.to_thread(file_sha256, str(part_path))
+            if actual != meta["sha256"]:
+                self._remove(upload_id)
+                raise InvalidArgumentError(
+                    "Checksum mismatch: the assembled upload does not match the declared sha256. "
+                    "The partial upload was discarded; upload the file again.",
+                    details={"expected": meta["sha256"], "actual": actual},
+                )
+
+            try:
+                with open(part_path, "rb") as f:
+                    store = TempUploadStore.build(self.server_config)
+                    return await store.save_upload(
+                        _AssembledFile(f, meta["filename"]), upload_mode, ctx
+                    )
+            finally:
+                self._remove(upload_id)
+
+    def abort(self, upload_id: str, ctx: RequestContext) -> None:
+        self._load_meta(upload_id, ctx)
+        self._remove(upload_id)
+
+    @staticmethod
+    def _status(meta: dict[str, Any], received: int) -> dict[str, Any]:
+        return {
+            "upload_id": meta["upload_id"],
+            "filename": meta["filename"],
+            "size": meta["size"],
+            "sha256": meta["sha256"],
+            "received": received,
+        }
+
+    @staticmethod
+    def _state_dir() -> Path:
+        state_dir = get_openviking_config().storage.get_upload_temp_dir() / _STATE_DIR
+        state_dir.mkdir(parents=True, exist_ok=True)
+        return state_dir
+
+    def _part_path(self, upload_id: str) -> Path:
+        return self._state_dir() / f"{upload_id}.part"
+
+    def _load_meta(self, upload_id: str, ctx: RequestContext) -> dict[str, Any]:
+        if not _UPLOAD_ID_RE.match(upload_id):
+            raise NotFoundError(upload_id, "upload")
+        state_dir = self._state_dir()
+        meta_path = state_dir / f"{upload_id}.json"
+        try:
+            meta = json.loads(meta_path.read_text(encoding="utf-8"))
+        except (FileNotFoundError, ValueError) as exc:
+            raise NotFoundError(upload_id, "upload") from exc
+        # Another tenant's upload is reported as missing rather than forbidden.
+        if meta.get("account") != ctx.account_id or meta.get("user") != ctx.user.user_id:
+            raise NotFoundError(upload_id, "upload")
+        if not (state_dir / f"{upload_id}.part").is_file():
+            raise NotFoundError(upload_id, "upload")
+        return meta
+
+    def _remove(self, upload_id: str) -> None:
+        _upload_locks.pop(upload_id, None)
+        state_dir = self._state_dir()
+        for suffix in (".part", ".json"):
+            with suppress(FileNotFoundError):
+                (state_dir / f"{upload_id}{suffix}").unlink()
+
+    @staticmethod
+    def _cleanup_expired(state_dir: Path) -> None:
+        """Drop uploads whose data has not grown for a day."""
+        now = time.time()
+        for meta_path in state_dir.glob("*.json"):
+            part_path = meta_path.with_suffix(".part")
+            with suppress(FileNotFoundError):
+                touched = (part_path if part_path.exists() else meta_path).stat().st_mtime
+                if now - touched > _MAX_AGE_SECONDS:
+                    part_path.unlink(missing_ok=True)
+                    meta_path.unlink()
+                    _upload_locks.pop(meta_path.stem, None)
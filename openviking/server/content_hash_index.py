@@ -0,0 +1,92 @@
+# Copyright (c) 2026 Beijing Volcano Engine Technology Co., Ltd.
+# SPDX-License-Identifier: AGPL-3.0
+"""Per-account index from uploaded file content to the resources it became.
+
+When a client declares the SHA-256 of an uploaded file, ``add_resource`` checks
+it against the uploaded bytes and records it against the returned
+``root_uri``. Before the next upload of the same bytes the client asks
+:meth:`ContentHashIndex.lookup` and can skip the upload. Entries live next to
+shared temp uploads under the internal ``viking://upload`` scope, so they are
+account-scoped and hidden from normal browsing. A hit is only reported while
+the recorded resource is still visible to the caller.
+"""
+
+from __future__ import annotations
+
+import hashlib
+import json
+import re
+import time
+from typing import Any, Optional
+
+from openviking.server.identity import RequestContext, Role
+from openviking.storage.viking_fs import get_viking_fs
+from openviking_cli.exceptions import InvalidArgumentError
+from openviking_cli.utils import get_logger
+
+logger = get_logger(__name__)
+
+_INDEX_PREFIX = "viking://upload/content_hashes"
+_SHA256_RE = re.compile(r"^[0-9a-f]{64}$")
+
+
+def normalize_sha256(sha256: str) -> str:
+    digest = sha256.strip().lower()
+    if not _SHA256_RE.match(digest):
+        raise InvalidArgumentError("sha256 must be a 64-character hex digest.")
+    return digest
+
+
+def file_sha256(path: str) -> str:
+    digest = hashlib.sha256()
+    with open(path, "rb") as f:
+        for chunk in iter(lambda: f.read(1024 * 1024), b""):
+            digest.update(chunk)
+    return digest.hexdigest()
+
+
+class ContentHashIndex:
+    @staticmethod
+    def _internal_ctx(ctx: RequestContext) -> RequestContext:
+        return RequestContext(user=ctx.user, role=Role.ROOT)
+
+    @staticmethod
+    def _entry_uri(sha256: str) -> str:
+        return f"{_INDEX_PREFIX}/{sha256}.json"
+
+    async def lookup(self, sha256: str, ctx: RequestContext) -> Optional[dict[str, Any]]:
+        """Return the recorded entry for ``sha256`` if its resource still exists."""
+        sha256 = normalize_sha256(sha256)
+        vfs = get_viking_fs()
+        try:
+            raw = await vfs.read_file(self._entry_uri(sha256), ctx=self._internal_ctx(ctx))
+            entry = json.loads(raw)
+        except (FileNotFoundError, ValueError):
+            return None
+        root_uri = entry.get("root_uri")
+        if not root_uri or not await vfs.exists(root_uri, ctx=ctx):
+            return None
+        return entry
+
+    async def record(
+        self,
+        sha256: str,
+        root_uri: str,
+        source_name: Optional[str],
+        ctx: RequestContext,
+    ) -> None:
+        """Remember that ``sha256`` was imported as ``root_uri``; failures only log."""
+        entry = {
+            "sha256": sha256,
+            "root_uri": root_uri,
+            "source_name": source_name,
+            "recorded_at": int(time.time()),
+        }
+        try:
+            await get_viking_fs().write_file(
+                self._entry_uri(sha256),
+                json.dumps(entry, ensure_ascii=False),
+                ctx=self._internal_ctx(ctx),
+            )
+        except Exception as exc:
+            logger.warning("Failed to record content hash for %s: %s", root_uri, exc)
@@ -5,7 +5,7 @@
 from typing import Any, Literal, Optional
 
 from fastapi import APIRouter, Body, Depends, Query
-from pydantic import BaseModel
+from pydantic import BaseModel, ConfigDict, Field
 
 from openviking.core.namespace import NamespaceShapeError, canonicalize_uri, context_type_for_uri
 from openviking.core.path_variables import resolve_path_variables
@@ -182,6 +182,7 @@ async def attrs(
             "context_type": context_type_for_uri(canonical_uri),
             "attrs": {
                 "tags": await _tags_attr(service, canonical_uri, _ctx),
+                "metadata": await service.fs.get_metadata(canonical_uri, ctx=_ctx),
             },
         }
         if result["context_type"] == "memory" and not stat_result.get("isDir", False):
@@ -212,6 +213,38 @@ async def attrs_set_tags(
     return await content_set_tags(request, _ctx)
 
 
+class SetMetadataRequest(BaseModel):
+    """Request to merge or remove free-form user metadata keys on a URI."""
+
+    model_config = ConfigDict(extra="forbid")
+
+    uri: str
+    values: dict[str, Any] = Field(default_factory=dict)
+    remove: list[str] = Field(default_factory=list)
+
+
+@router.post("/attrs/set_metadata")
+async def attrs_set_metadata(
+    request: SetMetadataRequest = Body(...),
+    _ctx: RequestContext = Depends(get_request_context),
+):
+    """Set free-form key/value user metadata for a file or directory."""
+    service = get_service()
+    uri = resolve_path_variables(request.uri)
+    if not request.values and not request.remove:
+        raise InvalidArgumentError("set_metadata needs values to set or keys to remove")
+    try:
+        metadata = await service.fs.set_metadata(
+            uri, values=request.values, remove=request.remove, ctx=_ctx
+        )
+    except AGFSClientError as e:
+        mapped = map_exception(e, resource=uri, resource_type="file")
+        if mapped is not None:
+            raise mapped from e
+        raise
+    return Response(status="ok", result={"uri": uri, "metadata": metadata})
+
+
 class MkdirRequest(BaseModel):
     """Request model for mkdir."""
 
@@ -5,12 +5,15 @@
 Provides observability API for monitoring component status.
 Mirrors SDK's client.observer API:
 - /api/v1/observer/queue - Queue status
+- /api/v1/observer/queue/items - Queue counts and per-URI progress
 - /api/v1/observer/vikingdb - VikingDB status
 - /api/v1/observer/models - Models status (VLM, Embedding, Rerank)
 - /api/v1/observer/system - System overall status
 """
 
-from fastapi import APIRouter, Depends
+from typing import Optional
+
+from fastapi import APIRouter, Depends, Query
 
 from openviking.server.auth import get_request_context
 from openviking.server.dependencies import get_service
@@ -52,6 +55,16 @@ async def observer_queue(
     return Response(status="ok", result=_component_to_dict(component))
 
 
+@router.get("/queue/items")
+async def observer_queue_items(
+    uri: Optional[str] = Query(None, description="Only report messages for this URI subtree"),
+    _ctx: RequestContext = Depends(get_request_context),
+):
+    """Get queue counts, oldest pending age, and per-URI progress."""
+    service = get_service()
+    return Response(status="ok", result=service.debug.observer.queue_detail(uri))
+
+
 @router.get("/vikingdb")
 async def observer_vikingdb(
     ctx: RequestContext = Depends(get_request_context),
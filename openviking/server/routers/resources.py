@@ -2,12 +2,19 @@
 # SPDX-License-Identifier: AGPL-3.0
 """Resource endpoints for OpenViking HTTP Server."""
 
+import asyncio
 from typing import Any, Dict, Optional
 
-from fastapi import APIRouter, Depends, File, Form, HTTPException, Request, UploadFile
+from fastapi import APIRouter, Depends, File, Form, HTTPException, Query, Request, UploadFile
 from pydantic import BaseModel, ConfigDict, Field, model_validator
 
 from openviking.server.auth import get_request_context, get_upload_request_context
+from openviking.server.chunked_upload_store import ChunkedUploadStore
+from openviking.server.content_hash_index import (
+    ContentHashIndex,
+    file_sha256,
+    normalize_sha256,
+)
 from openviking.server.dependencies import get_service
 from openviking.server.identity import RequestContext
 from openviking.server.local_input_guard import require_remote_resource_source
@@ -30,6 +37,8 @@ class AddResourceRequest(BaseModel):
             Either path or temp_file_id must be provided.
         temp_file_id: Temporary upload id returned by /api/v1/resources/temp_upload.
             Either path or temp_file_id must be provided.
+        sha256: Hex SHA-256 of the uploaded file. Only valid with temp_file_id; the
+            resulting root_uri is recorded so /resources/by-hash can find it later.
         to: Target URI for the resource (e.g., "viking://resources/my_resource").
             If not specified, an auto-generated URI will be used.
         parent: Parent URI under which the resource will be stored.
@@ -68,6 +77,7 @@ class AddResourceRequest(BaseModel):
 
     path: Optional[str] = None
     temp_file_id: Optional[str] = None
+    sha256: Optional[str] = None
     to: Optional[str] = None
     parent: Optional[str] = None
     create_parent: bool = False
@@ -168,6 +178,111 @@ async def temp_upload(
     return response_from_result(execution.result, telemetry=execution.telemetry)
 
 
+class StartChunkedUploadRequest(BaseModel):
+    """Request model for starting a resumable chunked upload.
+
+    Attributes:
+        filename: Original file name, kept for the resulting temp upload.
+        size: Total size of the file in bytes.
+        sha256: Hex SHA-256 of the whole file, verified when the upload completes.
+    """
+
+    model_config = ConfigDict(extra="forbid")
+
+    filename: str
+    size: int = Field(ge=0)
+    sha256: str
+
+
+class CompleteChunkedUploadRequest(BaseModel):
+    """Request model for completing a chunked upload."""
+
+    model_config = ConfigDict(extra="forbid")
+
+    upload_mode: str = "local"
+
+
+@router.post("/resources/uploads")
+async def start_chunked_upload(
+    request: Request,
+    body: StartChunkedUploadRequest,
+    _ctx: RequestContext = Depends(get_request_context),
+):
+    """Start a resumable upload; chunks are then appended with ``PUT``."""
+    store = ChunkedUploadStore.build(request.app.state.config)
+    return response_from_result(store.start(body.filename, body.size, body.sha256, _ctx))
+
+
+@router.get("/resources/uploads/{upload_id}")
+async def chunked_upload_status(
+    request: Request,
+    upload_id: str,
+    _ctx: RequestContext = Depends(get_request_context),
+):
+    """Report how many bytes of an upload the server holds, to resume from there."""
+    store = ChunkedUploadStore.build(request.app.state.config)
+    return response_from_result(store.status(upload_id, _ctx))
+
+
+@router.put("/resources/uploads/{upload_id}")
+async def append_chunked_upload(
+    request: Request,
+    upload_id: str,
+    offset: int = Query(..., ge=0),
+    _ctx: RequestContext = Depends(get_request_context),
+):
+    """Append the raw request body at ``offset``, which must equal the bytes received."""
+    store = ChunkedUploadStore.build(request.app.state.config)
+    result = await store.append(upload_id, offset, request.stream(), _ctx)
+    return response_from_result(result)
+
+
+@router.post("/resources/uploads/{upload_id}/complete")
+async def complete_chunked_upload(
+    request: Request,
+    upload_id: str,
+    body: CompleteChunkedUploadRequest,
+    _ctx: RequestContext = Depends(get_request_context),
+):
+    """Verify the assembled file's size and sha256 and turn it into a temp upload."""
+    store = ChunkedUploadStore.build(request.app.state.config)
+    temp_file_id = await store.complete(upload_id, body.upload_mode, _ctx)
+    return response_from_result({"temp_file_id": temp_file_id})
+
+
+@router.delete("/resources/uploads/{upload_id}")
+async def abort_chunked_upload(
+    request: Request,
+    upload_id: str,
+    _ctx: RequestContext = Depends(get_request_context),
+):
+    """Discard a partial upload."""
+    store = ChunkedUploadStore.build(request.app.state.config)
+    store.abort(upload_id, _ctx)
+    return response_from_result({"upload_id": upload_id, "aborted": True})
+
+
+@router.get("/resources/by-hash/{sha256}")
+async def find_resource_by_hash(
+    sha256: str,
+    _ctx: RequestContext = Depends(get_request_context),
+):
+    """Look up a resource previously imported from a file with this SHA-256.
+
+    ``root_uri`` is null when no such import is recorded or the resource has since been
+    removed, so clients can tell a miss from a server without this endpoint.
+    """
+    sha256 = normalize_sha256(sha256)
+    entry = await ContentHashIndex().lookup(sha256, _ctx)
+    return response_from_result(
+        {
+            "sha256": sha256,
+            "root_uri": entry["root_uri"] if entry else None,
+            "source_name": entry.get("source_name") if entry else None,
+        }
+    )
+
+
 @router.post("/resources")
 async def add_resource(
     http_request: Request,
@@ -192,6 +307,11 @@ async def add_resource(
         path = require_remote_resource_source(path)
     if path is None:
         raise InvalidArgumentError("Either 'path' or 'temp_file_id' must be provided.")
+    sha256 = None
+    if request.sha256 is not None:
+        if not request.temp_file_id:
+            raise InvalidArgumentError("'sha256' can only be given with 'temp_file_id'.")
+        sha256 = normalize_sha256(request.sha256)
 
     # Use original_filename from upload if source_name not explicitly provided
     source_name = request.source_name
@@ -219,6 +339,10 @@ async def add_resource(
 
     async def _add() -> dict[str, Any]:
         try:
+            # The index is trusted by later uploads, so record only a hash of
+            # the bytes actually received.
+            if sha256 and await asyncio.to_thread(file_sha256, path) != sha256:
+                raise InvalidArgumentError("'sha256' does not match the uploaded file.")
             result = await service.resources.add_resource(
                 path=path,
                 ctx=_ctx,
@@ -240,6 +364,13 @@ async def add_resource(
         else:
             if resolved and store:
                 await store.mark_consumed(resolved, _ctx)
+            if (
+                sha256
+                and isinstance(result, dict)
+                and result.get("status") != "error"
+                and result.get("root_uri")
+            ):
+                await ContentHashIndex().record(sha256, result["root_uri"], source_name, _ctx)
             return result
         finally:
             if resolved:
@@ -31,6 +31,9 @@ from openviking.utils.search_filters import (
 )
 from openviking.utils.tags import build_search_tags_filter
 from openviking_cli.exceptions import InvalidArgumentError, NotFoundError
+from openviking_cli.retrieve import ScoringOptions
+
+SearchMode = Literal["vector", "keyword", "hybrid"]
 
 
 def _sanitize_floats(obj: Any) -> Any:
@@ -82,6 +85,31 @@ def _resolve_search_filter(
         raise InvalidArgumentError(str(exc)) from exc
 
 
+def _resolve_scoring(
+    mode: Optional[SearchMode],
+    rerank: Optional[bool],
+    vector_weight: Optional[float],
+    keyword_weight: Optional[float],
+    include_scores: bool,
+) -> Optional[ScoringOptions]:
+    """Build ranking options from request fields; None keeps the defaults."""
+    weighted = vector_weight is not None or keyword_weight is not None
+    if weighted and mode != "hybrid":
+        raise InvalidArgumentError("vector_weight and keyword_weight require mode 'hybrid'.")
+    if mode is None and rerank is None and not include_scores:
+        return None
+    scoring = ScoringOptions(mode=mode or "vector", rerank=rerank, include_scores=include_scores)
+    if vector_weight is not None:
+        scoring.vector_weight = vector_weight
+    if keyword_weight is not None:
+        scoring.keyword_weight = keyword_weight
+    if scoring.vector_weight < 0 or scoring.keyword_weight < 0:
+        raise InvalidArgumentError("Hybrid weights must not be negative.")
+    if scoring.vector_weight + scoring.keyword_weight <= 0:
+        raise InvalidArgumentError("At least one hybrid weight must be positive.")
+    return scoring
+
+
 def _resolve_uri_or_uris(uri: Union[str, List[str]]) -> Union[str, List[str]]:
     """Resolve path variables in a single URI or list of URIs."""
     if isinstance(uri, list):
@@ -110,6 +138,11 @@ class FindRequest(BaseModel):
     until: Optional[str] = None
     time_field: Optional[TimeField] = None
     level: Optional[Union[int, str, List[int]]] = None
+    mode: Optional[SearchMode] = None
+    rerank: Optional[bool] = None
+    vector_weight: Optional[float] = None
+    keyword_weight: Optional[float] = None
+    include_scores: bool = False
     telemetry: TelemetryRequest = False
 
 
@@ -136,6 +169,11 @@ class SearchRequest(BaseModel):
     until: Optional[str] = None
     time_field: Optional[TimeField] = None
     level: Optional[Union[int, str, List[int]]] = None
+    mode: Optional[SearchMode] = None
+    rerank: Optional[bool] = None
+    vector_weight: Optional[float] = None
+    keyword_weight: Optional[float] = None
+    include_scores: bool = False
     telemetry: TelemetryRequest = False
 
 
@@ -190,6 +228,13 @@ async def find(
         request.tags,
     )
     resolved_target_uri = _resolve_uri_or_uris(request.target_uri)
+    scoring = _resolve_scoring(
+        request.mode,
+        request.rerank,
+        request.vector_weight,
+        request.keyword_weight,
+        request.include_scores,
+    )
     execution = await run_operation(
         operation="search.find",
         telemetry=request.telemetry,
@@ -202,6 +247,7 @@ async def find(
             filter=effective_filter,
             level=_resolve_levels(request.level) or None,
             image_url=request.image_url,
+            scoring=scoring,
         ),
     )
     result = execution.result
@@ -232,6 +278,13 @@ async def search(
         request.tags,
     )
     resolved_target_uri = _resolve_uri_or_uris(request.target_uri)
+    scoring = _resolve_scoring(
+        request.mode,
+        request.rerank,
+        request.vector_weight,
+        request.keyword_weight,
+        request.include_scores,
+    )
 
     async def _search():
         session = None
@@ -248,6 +301,7 @@ async def search(
             filter=effective_filter,
             level=_resolve_levels(request.level) or None,
             image_url=request.image_url,
+            scoring=scoring,
         )
 
     execution = await run_operation(
@@ -109,6 +109,14 @@ class ObserverService:
             status=observer.get_status_table(),
         )
 
+    def queue_detail(self, uri: Optional[str] = None) -> Dict[str, Any]:
+        """Get queue counts and per-URI progress as structured data."""
+        try:
+            qm = get_queue_manager()
+        except Exception:
+            return {"queues": [], "items": [], "complete": True}
+        return QueueObserver(qm).get_status_dict(uri)
+
     def vikingdb(self, ctx: Optional[RequestContext] = None) -> ComponentStatus:
         """Get VikingDB status."""
         if self._vikingdb is None:
@@ -457,6 +457,24 @@ class FSService:
         uri = validate_viking_uri(uri)
         return await viking_fs.stat(uri, ctx=ctx)
 
+    async def get_metadata(self, uri: str, ctx: RequestContext) -> Dict[str, Any]:
+        """Get the user metadata attached to a file or directory."""
+        viking_fs = self._ensure_initialized()
+        uri = validate_viking_uri(uri)
+        return await viking_fs.get_metadata(uri, ctx=ctx)
+
+    async def set_metadata(
+        self,
+        uri: str,
+        values: Dict[str, Any],
+        remove: List[str],
+        ctx: RequestContext,
+    ) -> Dict[str, Any]:
+        """Merge user metadata keys into a URI and drop the ``remove`` keys."""
+        viking_fs = self._ensure_initialized()
+        uri = validate_viking_uri(uri)
+        return await viking_fs.set_metadata(uri, values=values, remove=remove, ctx=ctx)
+
     async def system_sync_status(self, uri: str, ctx: RequestContext) -> Dict[str, Any]:
         """Return multi-write sync status for one Viking URI subtree."""
         viking_fs = self._ensure_initialized()
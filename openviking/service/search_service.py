@@ -19,6 +19,7 @@ from openviking.utils.image_search import (
     is_viking_uri,
 )
 from openviking_cli.exceptions import InvalidArgumentError, NotInitializedError
+from openviking_cli.retrieve import ScoringOptions
 from openviking_cli.utils import get_logger
 
 if TYPE_CHECKING:
@@ -77,6 +78,7 @@ class SearchService:
         filter: Optional[Dict] = None,
         level: Optional[List[int]] = None,
         image_url: Optional[str] = None,
+        scoring: Optional[ScoringOptions] = None,
     ) -> Any:
         """Complex search with session context.
 
@@ -88,6 +90,7 @@ class SearchService:
             score_threshold: Score threshold
             filter: Metadata filters
             level: Filter by level (0=abstract, 1=overview, 2=file)
+            scoring: Ranking mode, rerank override and hybrid weights
 
         Returns:
             FindResult
@@ -111,6 +114,7 @@ class SearchService:
             filter=filter,
             level=level,
             image_url=resolved_image_url,
+            scoring=scoring,
         )
         return result
 
@@ -124,6 +128,7 @@ class SearchService:
         filter: Optional[Dict] = None,
         level: Optional[List[int]] = None,
         image_url: Optional[str] = None,
+        scoring: Optional[ScoringOptions] = None,
     ) -> Any:
         """Semantic search without session context.
 
@@ -134,6 +139,7 @@ class SearchService:
             score_threshold: Score threshold
             filter: Metadata filters
             level: Filter by level (0=abstract, 1=overview, 2=file)
+            scoring: Ranking mode, rerank override and hybrid weights
 
         Returns:
             FindResult
@@ -151,5 +157,6 @@ class SearchService:
             filter=filter,
             level=level,
             image_url=resolved_image_url,
+            scoring=scoring,
         )
         return result
@@ -6,6 +6,7 @@ MULTIWRITE_PATH_LOCK_FILE = ".path.ovlock"
 MULTIWRITE_EXACT_LOCK_FILE_PREFIX = ".exact.ovlock."
 MULTIWRITE_REDIRECT_FILE = ".redirect.json"
 MULTIWRITE_SYNC_LOG_FILE = ".sync_log.json"
+METADATA_FILE = ".meta.json"
 
 MULTIWRITE_INTERNAL_FILE_NAMES = frozenset(
     {
@@ -28,6 +29,7 @@ WEBDAV_RESERVED_FILENAMES = frozenset(
         ".abstract.md",
         ".overview.md",
         ".relations.json",
+        METADATA_FILE,
         *MULTIWRITE_INTERNAL_FILE_NAMES,
     }
 )
@@ -6,10 +6,11 @@ QueueObserver: Queue system observability tool.
 Provides methods to observe and report queue status in various formats.
 """
 
-from typing import Dict, Optional
+import time
+from typing import Any, Dict, Optional
 
 from openviking.storage.observers.base_observer import BaseObserver
-from openviking.storage.queuefs.named_queue import QueueStatus
+from openviking.storage.queuefs.named_queue import QueueStatus, UriProgress
 from openviking.storage.queuefs.queue_manager import QueueManager
 from openviking_cli.utils import run_async
 from openviking_cli.utils.logger import get_logger
@@ -38,6 +39,87 @@ class QueueObserver(BaseObserver):
     def __str__(self) -> str:
         return self.get_status_table()
 
+    async def get_status_dict_async(self, uri_prefix: Optional[str] = None) -> Dict[str, Any]:
+        statuses = await self._queue_manager.check_status()
+        dag_stats = self._get_semantic_dag_stats()
+        progress = self._queue_manager.uri_progress(uri_prefix)
+        return self._format_status_as_dict(statuses, dag_stats, progress, uri_prefix)
+
+    def get_status_dict(self, uri_prefix: Optional[str] = None) -> Dict[str, Any]:
+        return run_async(self.get_status_dict_async(uri_prefix))
+
+    def _format_status_as_dict(
+        self,
+        statuses: Dict[str, QueueStatus],
+        dag_stats: Optional[object],
+        progress: Dict[str, Dict[str, UriProgress]],
+        uri_prefix: Optional[str],
+    ) -> Dict[str, Any]:
+        """
+        Format queue statuses and tracked per-URI progress as plain data.
+
+        ``complete`` covers the whole server, or only ``uri_prefix`` when given.
+        """
+        now = time.time()
+
+        def age(since: Optional[float]) -> Optional[float]:
+            return round(now - since, 1) if since is not None else None
+
+        queues = [
+            {
+                "name": name,
+                "pending": status.pending,
+                "in_progress": status.in_progress,
+                "processed": status.processed,
+                "requeued": status.requeue_count,
+                "errors": status.error_count,
+                "oldest_pending_seconds": age(status.oldest_enqueued_at),
+            }
+            for name, status in statuses.items()
+        ]
+        if dag_stats:
+            queues.append(
+                {
+                    "name": "Semantic-Nodes",
+                    "pending": getattr(dag_stats, "pending_nodes", 0),
+                    "in_progress": getattr(dag_stats, "in_progress_nodes", 0),
+                    "processed": getattr(dag_stats, "done_nodes", 0),
+                    "requeued": 0,
+                    "errors": 0,
+                    "oldest_pending_seconds": None,
+                }
+            )
+
+        items = []
+        for queue_name, uris in progress.items():
+            for uri, item in uris.items():
+                if item.in_progress:
+                    state = "processing"
+                elif item.pending:
+                    state = "pending"
+                else:
+                    state = "failed"
+                items.append(
+                    {
+                        "uri": uri,
+                        "queue": queue_name,
+                        "state": state,
+                        "pending": item.pending,
+                        "in_progress": item.in_progress,
+                        "oldest_pending_seconds": age(
+                            item.enqueued_at[0] if item.enqueued_at else None
+                        ),
+                        "error": item.last_error,
+                    }
+                )
+        items.sort(key=lambda item: (item["uri"], item["queue"]))
+
+        if uri_prefix:
+            complete = all(item["state"] == "failed" for item in items)
+        else:
+            complete = all(queue["pending"] == 0 and queue["in_progress"] == 0 for queue in queues)
+        return {"queues": queues, "items": items, "complete": complete}
+
     def _format_status_as_table(
         self, statuses: Dict[str, QueueStatus], dag_stats: Optional[object]
     ) -> str:
@@ -29,6 +29,8 @@ OVPACK_INDEX_RECORDS_FILENAME = "index_records.jsonl"
 OVPACK_INDEX_RECORDS_PATH = f"{OVPACK_INTERNAL_DIR}/{OVPACK_INDEX_RECORDS_FILENAME}"
 OVPACK_DENSE_FILENAME = "dense.f32"
 OVPACK_DENSE_PATH = f"{OVPACK_INTERNAL_DIR}/{OVPACK_DENSE_FILENAME}"
+OVPACK_SIGNATURE_FILENAME = "signature.json"
+OVPACK_SIGNATURE_PATH = f"{OVPACK_INTERNAL_DIR}/{OVPACK_SIGNATURE_FILENAME}"
 OVPACK_ON_CONFLICT_VALUES = frozenset({"fail", "overwrite", "skip"})
 OVPACK_VECTOR_MODE_VALUES = frozenset({"auto", "recompute", "require"})
 OVPACK_BACKUP_NAME = "openviking-backup"
@@ -139,12 +139,15 @@ async def _existing_scope_roots(
     return existing
 
 
-def _exportable_entries(entries: list[dict[str, Any]]) -> list[dict[str, Any]]:
+def _exportable_entries(
+    entries: list[dict[str, Any]], *, keep_relations: bool = False
+) -> list[dict[str, Any]]:
+    """Drop internal files; backups keep relation tables since they restore in place."""
     exportable: list[dict[str, Any]] = []
     for entry in entries:
         rel_path = str(entry.get("rel_path") or "")
         validate_ovpack_user_rel_path(rel_path, operation="export")
-        if not is_excluded_rel_path(rel_path):
+        if keep_relations or not is_excluded_rel_path(rel_path):
             exportable.append(entry)
     return exportable
 
@@ -392,7 +395,7 @@ async def _backup_entries(viking_fs, ctx: RequestContext) -> list[dict[str, Any]
         except (NotFoundError, FileNotFoundError):
             continue
 
-        for entry in _exportable_entries(scope_entries):
+        for entry in _exportable_entries(scope_entries, keep_relations=True):
             rel_path = entry.get("rel_path", "")
             if not rel_path:
                 continue
@@ -561,7 +564,7 @@ async def backup_ovpack(
     vector_store=None,
     include_vectors: bool = False,
 ) -> str:
-    """Export all public OpenViking scopes as a restore-only backup package."""
+    """Export all public OpenViking scopes, relations included, as a restore-only backup."""
     base_name = OVPACK_BACKUP_NAME
     if os.path.isdir(to):
         to = os.path.join(to, f"{base_name}.ovpack")
@@ -646,7 +649,9 @@ async def restore_ovpack(
 
         manifest_entries = manifest_entries_by_path(manifest)
         backup_scopes = backup_scopes_from_manifest(manifest, manifest_entries)
-        members = validated_import_members(infolist, base_name, root_uri)
+        members = validated_import_members(
+            infolist, base_name, root_uri, allow_relations=True
+        )
         existing_roots = await _existing_scope_roots(viking_fs, backup_scopes, ctx)
 
         if existing_roots:
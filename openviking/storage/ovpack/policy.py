@@ -55,12 +55,15 @@ def validate_public_scope(uri: str, *, operation: str, allow_root: bool = False)
         raise InvalidArgumentError(f"ovpack {operation} is not supported for scope: {parsed.scope}")
 
 
-def validate_import_target_uri(uri: str) -> None:
-    """Enforce the same target-policy boundary as direct content writes."""
+def validate_import_target_uri(uri: str, *, allow_relations: bool = False) -> None:
+    """Enforce the same target-policy boundary as direct content writes.
+
+    Backup restores pass ``allow_relations`` to bring relation tables back.
+    """
     validate_public_scope(uri, operation="import")
     validate_ovpack_user_rel_path(_scope_relative_path(uri), operation="import")
     name = leaf_name(uri)
-    if name in EXCLUDED_FILENAMES:
+    if name in EXCLUDED_FILENAMES and not allow_relations:
         raise InvalidArgumentError(f"cannot import internal ovpack file: {uri}")
     if is_watch_task_control_uri(uri):
         raise InvalidArgumentError(f"cannot import watch task control file: {uri}")
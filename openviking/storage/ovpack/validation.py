@@ -12,6 +12,7 @@ from openviking.storage.ovpack.format import (
     OVPACK_DENSE_PATH,
     OVPACK_INDEX_RECORDS_PATH,
     OVPACK_MANIFEST_ZIP_LEAF,
+    OVPACK_SIGNATURE_PATH,
     get_viking_rel_path_from_zip,
     internal_zip_path,
     is_content_zip_path,
@@ -348,11 +349,16 @@ def _validate_internal_members(
     if manifest_dense_info(manifest) is not None:
         expected_files.add(internal_zip_path(base_name, OVPACK_DENSE_PATH))
 
+    # Signatures are added by `ov pack sign` and checked by the client before upload.
+    optional_files = {internal_zip_path(base_name, OVPACK_SIGNATURE_PATH)}
+
     actual_files: set[str] = set()
     for info in infolist:
         safe_zip_path = validate_ovpack_member_path(info.filename, base_name)
         if not is_internal_zip_path(safe_zip_path, base_name) or safe_zip_path.endswith("/"):
             continue
+        if safe_zip_path in optional_files:
+            continue
         actual_files.add(safe_zip_path)
 
     missing = sorted(expected_files - actual_files)
@@ -474,7 +480,11 @@ def validate_manifest_content(
 
 
 def validated_import_members(
-    infolist: list[zipfile.ZipInfo], base_name: str, root_uri: str
+    infolist: list[zipfile.ZipInfo],
+    base_name: str,
+    root_uri: str,
+    *,
+    allow_relations: bool = False,
 ) -> list[tuple[zipfile.ZipInfo, str, str, str]]:
     members: list[tuple[zipfile.ZipInfo, str, str, str]] = []
     for info in infolist:
@@ -506,7 +516,7 @@ def validated_import_members(
             members.append((info, safe_zip_path, kind, rel_path))
             continue
         target_uri = join_uri(root_uri, rel_path)
-        validate_import_target_uri(target_uri)
+        validate_import_target_uri(target_uri, allow_relations=allow_relations)
         members.append((info, safe_zip_path, kind, rel_path))
 
     return members
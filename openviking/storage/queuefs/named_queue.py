@@ -3,9 +3,11 @@
 import abc
 import json
 import threading
+import time
+from collections import deque
 from dataclasses import dataclass, field
 from datetime import datetime
-from typing import Any, Callable, Dict, List, Optional, Union
+from typing import Any, Callable, Deque, Dict, List, Optional, Union
 
 from openviking.pyagfs import AGFSSyncClientProtocol, AsyncAGFSClient
 from openviking.pyagfs.exceptions import AGFSAlreadyExistsError, AGFSNotFoundError
@@ -33,6 +35,7 @@ class QueueStatus:
     requeue_count: int = 0
     error_count: int = 0
     errors: List[QueueError] = field(default_factory=list)
+    oldest_enqueued_at: Optional[float] = None
 
     @property
     def has_errors(self) -> bool:
@@ -43,6 +46,47 @@ class QueueStatus:
         return self.pending == 0 and self.in_progress == 0
 
 
+@dataclass
+class UriProgress:
+    """Messages of one queue that concern a single URI.
+
+    Only messages enqueued by this process are tracked; messages recovered
+    from a previous run count towards ``QueueStatus.pending`` only.
+    """
+
+    enqueued_at: Deque[float] = field(default_factory=deque)
+    in_progress: int = 0
+    last_error: Optional[str] = None
+
+    @property
+    def pending(self) -> int:
+        return len(self.enqueued_at)
+
+    @property
+    def is_idle(self) -> bool:
+        return not self.enqueued_at and self.in_progress == 0
+
+
+def message_uri(data: Any) -> Optional[str]:
+    """Best-effort URI of a queue message, raw (``{"id", "data"}``) or not."""
+    if isinstance(data, dict) and isinstance(data.get("data"), (str, dict)):
+        data = data["data"]
+    if isinstance(data, str):
+        try:
+            data = json.loads(data)
+        except ValueError:
+            return None
+    if not isinstance(data, dict):
+        return None
+    context_data = data.get("context_data")
+    if isinstance(context_data, dict) and context_data.get("uri"):
+        return str(context_data["uri"])
+    for key in ("uri", "root_uri", "session_uri"):
+        if data.get(key):
+            return str(data[key])
+    return None
+
+
 class EnqueueHookBase(abc.ABC):
     """Enqueue hook base class.
 
@@ -125,6 +169,7 @@ class NamedQueue:
         self._requeue_count = 0
         self._error_count = 0
         self._errors: List[QueueError] = []
+        self._uris: Dict[str, UriProgress] = {}
 
         # Inject callbacks to handler
         if self._dequeue_handler:
@@ -134,10 +179,58 @@ class NamedQueue:
                 on_error=self._on_process_error,
             )
 
-    def _on_dequeue_start(self) -> None:
+    def _on_dequeue_start(self, data: Any = None) -> None:
         """Called on dequeue."""
+        uri = message_uri(data)
         with self._lock:
             self._in_progress += 1
+            progress = self._uris.get(uri) if uri else None
+            if progress is not None:
+                if progress.enqueued_at:
+                    progress.enqueued_at.popleft()
+                progress.in_progress += 1
+
+    def _on_enqueued(self, data: Any) -> None:
+        """Start tracking a message that was just written to the queue."""
+        uri = message_uri(data)
+        if not uri:
+            return
+        with self._lock:
+            progress = self._uris.setdefault(uri, UriProgress())
+            progress.enqueued_at.append(time.time())
+            progress.last_error = None
+
+    def _on_item_done(self, data: Any, error: Optional[str] = None) -> None:
+        """Stop tracking a dequeued message once its handler has returned."""
+        uri = message_uri(data)
+        with self._lock:
+            progress = self._uris.get(uri) if uri else None
+            if progress is None:
+                return
+            progress.in_progress = max(progress.in_progress - 1, 0)
+            if error:
+                progress.last_error = error
+            if progress.is_idle and progress.last_error is None:
+                del self._uris[uri]
+            elif len(self._uris) > self.MAX_ERRORS:
+                for stale in [key for key, item in self._uris.items() if item.is_idle]:
+                    if len(self._uris) <= self.MAX_ERRORS:
+                        break
+                    del self._uris[stale]
+
+    def uri_progress(self, uri_prefix: Optional[str] = None) -> Dict[str, UriProgress]:
+        """Snapshot of tracked messages, optionally limited to a URI subtree."""
+        prefix = uri_prefix.rstrip("/") if uri_prefix else None
+        with self._lock:
+            return {
+                uri: UriProgress(
+                    enqueued_at=deque(progress.enqueued_at),
+                    in_progress=progress.in_progress,
+                    last_error=progress.last_error,
+                )
+                for uri, progress in self._uris.items()
+                if prefix is None or uri == prefix or uri.startswith(prefix + "/")
+            }
 
     def _on_process_success(self) -> None:
         """Called on processing success."""
@@ -164,11 +257,18 @@ class NamedQueue:
             )
             if len(self._errors) > self.MAX_ERRORS:
                 self._errors = self._errors[-self.MAX_ERRORS :]
+            uri = message_uri(data)
+            if uri in self._uris:
+                self._uris[uri].last_error = error_msg
 
     async def get_status(self) -> QueueStatus:
         """Get queue status."""
         pending = await self.size()
         with self._lock:
+            oldest = min(
+                (progress.enqueued_at[0] for progress in self._uris.values() if progress.pending),
+                default=None,
+            )
             return QueueStatus(
                 pending=pending,
                 in_progress=self._in_progress,
@@ -176,6 +276,7 @@ class NamedQueue:
                 requeue_count=self._requeue_count,
                 error_count=self._error_count,
                 errors=list(self._errors),
+                oldest_enqueued_at=oldest,
             )
 
     def reset_status(self) -> None:
@@ -186,6 +287,7 @@ class NamedQueue:
             self._requeue_count = 0
             self._error_count = 0
             self._errors = []
+            self._uris = {}
 
     def has_dequeue_handler(self) -> bool:
         """Check if dequeue handler exists."""
@@ -213,6 +315,7 @@ class NamedQueue:
             data = json.dumps(data)
 
         msg_id = await self._async_agfs.write(enqueue_file, data.encode("utf-8"))
+        self._on_enqueued(data)
         return msg_id if isinstance(msg_id, str) else str(msg_id)
 
     async def ack(self, msg_id: str) -> None:
@@ -267,8 +370,14 @@ class NamedQueue:
             # Capture message ID before passing data to handler (handler may modify it)
             msg_id = data.get("id", "") if isinstance(data, dict) else ""
             if self._dequeue_handler:
-                self._on_dequeue_start()
-                data = await self._dequeue_handler.on_dequeue(data)
+                raw = data
+                self._on_dequeue_start(raw)
+                try:
+                    data = await self._dequeue_handler.on_dequeue(data)
+                except Exception as e:
+                    self._on_item_done(raw, str(e))
+                    raise
+                self._on_item_done(raw)
             # Ack unconditionally after handler returns (success or handled error).
             # If on_dequeue raises, the exception propagates and ack is skipped —
             # the message will be recovered on next startup.
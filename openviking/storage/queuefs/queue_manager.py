@@ -15,7 +15,13 @@ from typing import Any, Dict, Optional, Set, Union
 from openviking_cli.utils.logger import get_logger
 
 from .embedding_queue import EmbeddingQueue
-from .named_queue import DequeueHandlerBase, EnqueueHookBase, NamedQueue, QueueStatus
+from .named_queue import (
+    DequeueHandlerBase,
+    EnqueueHookBase,
+    NamedQueue,
+    QueueStatus,
+    UriProgress,
+)
 from .semantic_queue import SemanticQueue
 
 logger = get_logger(__name__)
@@ -222,12 +228,14 @@ class QueueManager:
                 msg_id = data.get("id", "") if isinstance(data, dict) else ""
                 try:
                     await queue.process_dequeued(data)
+                    queue._on_item_done(data)
                     # Ack after successful processing (delete from persistent storage).
                     await queue.ack(msg_id)
                 except Exception as e:
                     # Handler did not call report_error; decrement in_progress manually.
                     # Do NOT ack — let RecoverStale re-queue on next startup.
                     queue._on_process_error(str(e), data)
+                    queue._on_item_done(data, str(e))
                     logger.error(f"[QueueManager] Concurrent worker error for {queue.name}: {e}")
 
         while not stop_event.is_set():
@@ -247,7 +255,7 @@ class QueueManager:
                     break
                 # Increment before task creation to close the race window where
                 # size=0 and in_progress=0 between dequeue_raw() and task execution.
-                queue._on_dequeue_start()
+                queue._on_dequeue_start(data)
                 task = asyncio.create_task(process_one(data))
                 active_tasks.add(task)
                 logger.debug(
@@ -376,6 +384,10 @@ class QueueManager:
             return {queue_name: await self._queues[queue_name].get_status()}
         return {name: await q.get_status() for name, q in self._queues.items()}
 
+    def uri_progress(self, uri_prefix: Optional[str] = None) -> Dict[str, Dict[str, UriProgress]]:
+        """Tracked per-URI progress of every queue, keyed by queue name."""
+        return {name: q.uri_progress(uri_prefix) for name, q in self._queues.items()}
+
     def has_errors(self, queue_name: Optional[str] = None) -> bool:
         """Check if there are errors."""
         if queue_name:
@@ -8,6 +8,7 @@ Responsibilities:
 - URI conversion (viking:// <-> /local/)
 - L0/L1 reading (.abstract.md, .overview.md)
 - Relation management (.relations.json)
+- User metadata (.meta.json)
 - Semantic search (vector retrieval + rerank)
 - Vector sync (sync vector store on rm/mv)
 """
@@ -50,6 +51,7 @@ from openviking.server.error_mapping import is_not_found_error, map_exception
 from openviking.server.identity import RequestContext, Role
 from openviking.storage.expr import And, PathScope, RawDSL
 from openviking.storage.internal_names import (
+    METADATA_FILE,
     MULTIWRITE_PATH_LOCK_FILE,
     STORAGE_INTERNAL_ENTRY_NAMES,
 )
@@ -71,6 +73,7 @@ from openviking_cli.utils.uri import VikingURI
 if TYPE_CHECKING:
     from openviking.storage.transaction.lock_handle import LockHandle
     from openviking.storage.viking_vector_index_backend import VikingVectorIndexBackend
+    from openviking_cli.retrieve import ScoringOptions
     from openviking_cli.utils.config import GrepConfig, RerankConfig, RetrievalConfig
 
 logger = get_logger(__name__)
@@ -713,6 +716,8 @@ class VikingFS:
                             f"Directory not empty: {uri}. Use recursive=True to delete non-empty directories."
                         )
                     raise
+                if not is_dir:
+                    await self._move_file_metadata(path, None)
                 # Add estimated_deleted_count to the result
                 if isinstance(result, dict):
                     result["estimated_deleted_count"] = estimated_count
@@ -851,6 +856,8 @@ class VikingFS:
 
             # Delete source
             await self._async_agfs.rm(old_path, recursive=is_dir)
+            if not is_dir:
+                await self._move_file_metadata(old_path, new_path)
             return {}
 
     async def system_sync_status(
@@ -2051,6 +2058,7 @@ class VikingFS:
         ctx: Optional[RequestContext] = None,
         level: Optional[List[int]] = None,
         image_url: Optional[str] = None,
+        scoring: Optional["ScoringOptions"] = None,
     ):
         """Semantic search.
 
@@ -2060,6 +2068,7 @@ class VikingFS:
             limit: Return count
             score_threshold: Score threshold
             filter: Metadata filter
+            scoring: Ranking mode, rerank override and hybrid weights
 
         Returns:
             FindResult
@@ -2117,6 +2126,7 @@ class VikingFS:
             score_threshold=score_threshold,
             scope_dsl=filter,
             level=level,
+            scoring=scoring,
         )
 
         # Convert QueryResult to FindResult
@@ -2133,6 +2143,7 @@ class VikingFS:
             memories=memories,
             resources=resources,
             skills=skills,
+            query_results=[result],
         )
         telemetry.set("vector.returned", find_result.total)
         return find_result
@@ -2148,6 +2159,7 @@ class VikingFS:
         ctx: Optional[RequestContext] = None,
         level: Optional[List[int]] = None,
         image_url: Optional[str] = None,
+        scoring: Optional["ScoringOptions"] = None,
     ):
         """Complex search with session context.
 
@@ -2157,6 +2169,7 @@ class VikingFS:
             session_info: Session information
             limit: Return count
             filter: Metadata filter
+            scoring: Ranking mode, rerank override and hybrid weights
 
         Returns:
             FindResult
@@ -2255,6 +2268,7 @@ class VikingFS:
                 score_threshold=score_threshold,
                 scope_dsl=filter,
                 level=level,
+                scoring=scoring,
             )
 
         query_results = await asyncio.gather(*[_execute(tq) for tq in typed_queries])
@@ -3102,6 +3116,102 @@ class VikingFS:
 
         await self._async_agfs.write(table_path, content)
 
+    # ========== User Metadata ==========
+    #
+    # Free-form key/value metadata lives in a ``.meta.json`` table per
+    # directory: a directory's own entry under ``"."`` and each file's under
+    # its name, so a directory carries its files' metadata when it moves.
+
+    async def _metadata_location(
+        self, uri: str, ctx: Optional[RequestContext] = None
+    ) -> tuple[str, str]:
+        """Return the ``.meta.json`` path and the key holding ``uri``'s entry."""
+        path = self._uri_to_path(uri, ctx=ctx)
+        try:
+            stat = await self._async_agfs.stat(path)
+        except Exception as exc:
+            if is_not_found_error(exc):
+                raise NotFoundError(uri, "file") from exc
+            raise
+        if isinstance(stat, dict) and stat.get("isDir", False):
+            return f"{path.rstrip('/')}/{METADATA_FILE}", "."
+        parent, _, name = path.rstrip("/").rpartition("/")
+        return f"{parent}/{METADATA_FILE}", name
+
+    async def _read_metadata_table(self, table_path: str) -> Dict[str, Dict[str, Any]]:
+        """Read a ``.meta.json`` table; a missing or unreadable one is empty."""
+        try:
+            content = self._handle_agfs_read(await self._async_agfs.read(table_path))
+            data = json.loads(content.decode("utf-8"))
+        except Exception:
+            return {}
+        return data if isinstance(data, dict) else {}
+
+    async def _write_metadata_table(
+        self, table_path: str, table: Dict[str, Dict[str, Any]]
+    ) -> None:
+        """Write a ``.meta.json`` table, removing it once it is empty."""
+        if not table:
+            try:
+                await self._async_agfs.rm(table_path)
+            except Exception:
+                pass
+            return
+        content = json.dumps(table, ensure_ascii=False, indent=2).encode("utf-8")
+        await self._async_agfs.write(table_path, content)
+
+    async def get_metadata(
+        self, uri: str, ctx: Optional[RequestContext] = None
+    ) -> Dict[str, Any]:
+        """Get the user metadata attached to a file or directory."""
+        self._ensure_access(uri, ctx)
+        table_path, key = await self._metadata_location(uri, ctx=ctx)
+        table = await self._read_metadata_table(table_path)
+        entry = table.get(key)
+        return dict(entry) if isinstance(entry, dict) else {}
+
+    async def set_metadata(
+        self,
+        uri: str,
+        values: Optional[Dict[str, Any]] = None,
+        remove: Optional[List[str]] = None,
+        ctx: Optional[RequestContext] = None,
+    ) -> Dict[str, Any]:
+        """Merge ``values`` into a URI's user metadata and drop the ``remove`` keys."""
+        self._ensure_mutable_access(uri, ctx)
+        table_path, key = await self._metadata_location(uri, ctx=ctx)
+        table = await self._read_metadata_table(table_path)
+        entry = dict(table.get(key) or {})
+        entry.update(values or {})
+        for name in remove or []:
+            entry.pop(name, None)
+        if entry:
+            table[key] = entry
+        else:
+            table.pop(key, None)
+        await self._write_metadata_table(table_path, table)
+        return entry
+
+    async def _move_file_metadata(self, old_path: str, new_path: Optional[str]) -> None:
+        """Carry a file's metadata entry to ``new_path``, or drop it when None."""
+        old_parent, _, old_name = old_path.rstrip("/").rpartition("/")
+        old_table_path = f"{old_parent}/{METADATA_FILE}"
+        old_table = await self._read_metadata_table(old_table_path)
+        entry = old_table.pop(old_name, None)
+        if entry is None:
+            return
+        try:
+            await self._write_metadata_table(old_table_path, old_table)
+            if new_path is None:
+                return
+            new_parent, _, new_name = new_path.rstrip("/").rpartition("/")
+            new_table_path = f"{new_parent}/{METADATA_FILE}"
+            new_table = await self._read_metadata_table(new_table_path)
+            new_table[new_name] = entry
+            await self._write_metadata_table(new_table_path, new_table)
+        except Exception as e:
+            logger.warning(f"[VikingFS] Failed to update metadata for {old_path}: {e}")
+
     # ========== Batch Read (backward compatible) ==========
 
     async def read_batch(
@@ -3710,7 +3820,7 @@ class VikingFS:
         ".abstract.md": ContextLevel.ABSTRACT,
         ".overview.md": ContextLevel.OVERVIEW,
     }
-    _NO_VECTOR_DERIVED = frozenset({".relations.json", ".ovgitignore"})
+    _NO_VECTOR_DERIVED = frozenset({".relations.json", METADATA_FILE, ".ovgitignore"})
 
     def _classify_restore_path(self, tree_path: str, *, deleted: bool) -> Optional[tuple]:
         """Classify a restore-affected tree path into a vector maintenance task.
@@ -677,6 +677,8 @@ class _SingleAccountBackend:
                 filter=filter,
                 output_fields=output_fields,
             )
+        except NotImplementedError:
+            raise
         except Exception as e:
             logger.error("Error searching by keywords: %s", e)
             raise
@@ -1303,6 +1305,61 @@ class VikingVectorIndexBackend:
             ctx=ctx,
         )
 
+    async def keyword_search_in_tenant(
+        self,
+        ctx: RequestContext,
+        query: str,
+        context_type: Optional[str] = None,
+        target_directories: Optional[List[str]] = None,
+        extra_filter: Optional[FilterExpr | Dict[str, Any]] = None,
+        level: Optional[List[int]] = None,
+        limit: int = 10,
+    ) -> List[Dict[str, Any]]:
+        """BM25 keyword search over the same scope as ``search_in_tenant``.
+
+        Raises ``NotImplementedError`` on backends without keyword search.
+        """
+        scope_filter = self._build_scope_filter(
+            ctx=ctx,
+            context_type=context_type,
+            target_directories=target_directories,
+            extra_filter=extra_filter,
+            level=level,
+        )
+        return await self.search_by_keywords(
+            query=query,
+            limit=limit,
+            filter=scope_filter,
+            output_fields=RETRIEVAL_OUTPUT_FIELDS,
+            ctx=ctx,
+        )
+
+    async def scroll_in_tenant(
+        self,
+        ctx: RequestContext,
+        context_type: Optional[str] = None,
+        target_directories: Optional[List[str]] = None,
+        extra_filter: Optional[FilterExpr | Dict[str, Any]] = None,
+        level: Optional[List[int]] = None,
+        limit: int = 100,
+        cursor: Optional[str] = None,
+    ) -> tuple[List[Dict[str, Any]], Optional[str]]:
+        """One page of the records ``search_in_tenant`` would search."""
+        scope_filter = self._build_scope_filter(
+            ctx=ctx,
+            context_type=context_type,
+            target_directories=target_directories,
+            extra_filter=extra_filter,
+            level=level,
+        )
+        return await self.scroll(
+            filter=scope_filter,
+            limit=limit,
+            cursor=cursor,
+            output_fields=RETRIEVAL_OUTPUT_FIELDS,
+            ctx=ctx,
+        )
+
     async def get_context_by_uri(
         self,
         uri: str,
@@ -465,6 +465,44 @@ class VikingDBManagerProxy:
             limit=limit,
         )
 
+    async def keyword_search_in_tenant(
+        self,
+        query: str,
+        context_type: Optional[str] = None,
+        target_directories: Optional[List[str]] = None,
+        extra_filter: Optional[FilterExpr | Dict[str, Any]] = None,
+        level: Optional[List[int]] = None,
+        limit: int = 10,
+    ) -> List[Dict[str, Any]]:
+        return await self._manager.keyword_search_in_tenant(
+            self._ctx,
+            query=query,
+            context_type=context_type,
+            target_directories=target_directories,
+            extra_filter=extra_filter,
+            level=level,
+            limit=limit,
+        )
+
+    async def scroll_in_tenant(
+        self,
+        context_type: Optional[str] = None,
+        target_directories: Optional[List[str]] = None,
+        extra_filter: Optional[FilterExpr | Dict[str, Any]] = None,
+        level: Optional[List[int]] = None,
+        limit: int = 100,
+        cursor: Optional[str] = None,
+    ) -> Tuple[List[Dict[str, Any]], Optional[str]]:
+        return await self._manager.scroll_in_tenant(
+            self._ctx,
+            context_type=context_type,
+            target_directories=target_directories,
+            extra_filter=extra_filter,
+            level=level,
+            limit=limit,
+            cursor=cursor,
+        )
+
     async def get_context_by_uri(
         self,
         uri: str,
@@ -5,6 +5,7 @@ from openviking_cli.retrieve.types import (
     QueryPlan,
     QueryResult,
     RelatedContext,
+    ScoringOptions,
     TypedQuery,
 )
 
@@ -12,6 +13,7 @@ __all__ = [
     # Types
     "ContextType",
     "TypedQuery",
+    "ScoringOptions",
     "QueryPlan",
     "RelatedContext",
     "MatchedContext",
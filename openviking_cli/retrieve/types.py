@@ -255,6 +255,28 @@ class TypedQuery:
     image_query: bool = False
 
 
+@dataclass
+class ScoringOptions:
+    """
+    How retrieval ranks candidates.
+
+    Attributes:
+        mode: "vector" ranks by embedding similarity, "keyword" by matches of
+            the query's terms, "hybrid" by a weighted blend of the two
+        rerank: Force the configured rerank model on or off; None keeps the
+            server default
+        vector_weight: Weight of the vector score in hybrid mode
+        keyword_weight: Weight of the keyword score in hybrid mode
+        include_scores: Return each strategy's score with every match
+    """
+
+    mode: str = "vector"
+    rerank: Optional[bool] = None
+    vector_weight: float = 0.5
+    keyword_weight: float = 0.5
+    include_scores: bool = False
+
+
 @dataclass
 class QueryPlan:
     """
@@ -293,6 +315,8 @@ class MatchedContext:
     match_reason: str = ""
 
     relations: List[RelatedContext] = field(default_factory=list)
+    # Per-strategy scores (vector, keyword, rerank), when requested
+    scores: Optional[Dict[str, float]] = None
 
 
 @dataclass
@@ -374,7 +398,7 @@ class FindResult:
 
     def _context_to_dict(self, ctx: MatchedContext) -> Dict[str, Any]:
         """Convert MatchedContext to dict."""
-        return {
+        result = {
             "context_type": ctx.context_type.value,
             "uri": ctx.uri,
             "level": ctx.level,
@@ -385,6 +409,9 @@ class FindResult:
             "abstract": ctx.abstract,
             "overview": ctx.overview,
         }
+        if ctx.scores is not None:
+            result["scores"] = ctx.scores
+        return result
 
     def _query_to_dict(self, q: TypedQuery) -> Dict[str, Any]:
         """Convert TypedQuery to dict."""
@@ -431,6 +458,7 @@ class FindResult:
                     RelatedContext(uri=r.get("uri", ""), abstract=r.get("abstract", ""))
                     for r in d.get("relations", [])
                 ],
+                scores=d.get("scores"),
             )
 
         return cls(
@@ -258,6 +258,38 @@ class MissingSidecarBackupVikingFS(FakeBackupVikingFS):
         )
 
 
+class RelationsBackupVikingFS(FakeBackupVikingFS):
+    def __init__(self) -> None:
+        super().__init__()
+        self.binary_files["viking://resources/.relations.json"] = b'{"links": []}'
+
+    async def tree(
+        self,
+        uri: str,
+        show_all_hidden: bool = False,
+        node_limit=None,
+        level_limit=None,
+        ctx=None,
+    ):
+        entries = await super().tree(
+            uri,
+            show_all_hidden=show_all_hidden,
+            node_limit=node_limit,
+            level_limit=level_limit,
+            ctx=ctx,
+        )
+        if uri == "viking://resources":
+            entries.append(
+                {
+                    "rel_path": ".relations.json",
+                    "uri": "viking://resources/.relations.json",
+                    "isDir": False,
+                    "size": 13,
+                }
+            )
+        return entries
+
+
 class FakeRestoreVectorVikingFS(FakeVikingFS):
     async def tree(self, uri: str, node_limit=None, level_limit=None, ctx=None):
         self.tree_calls.append(uri)
@@ -621,6 +653,22 @@ async def test_backup_restore_contract(temp_ovpack_path: Path, request_ctx: Requ
     assert fake_fs.tree_calls == ["viking://resources", "viking://user"]
 
 
+@pytest.mark.asyncio
+async def test_backup_keeps_relation_tables(temp_ovpack_path: Path, request_ctx: RequestContext):
+    await backup_ovpack(
+        RelationsBackupVikingFS(),
+        str(temp_ovpack_path),
+        ctx=request_ctx,
+    )
+
+    with zipfile.ZipFile(temp_ovpack_path, "r") as zf:
+        assert zf.read("openviking-backup/files/resources/.relations.json") == b'{"links": []}'
+
+    fake_fs = FakeVikingFS()
+    await restore_ovpack(fake_fs, str(temp_ovpack_path), request_ctx)
+    assert "viking://resources/.relations.json" in fake_fs.written_files
+
+
 @pytest.mark.asyncio
 async def test_backup_skips_missing_semantic_sidecars(
     temp_ovpack_path: Path,
@@ -894,6 +942,29 @@ async def test_import_ovpack_rejects_manifest_file_hash_mismatch(
     assert fake_fs.written_files == []
 
 
+@pytest.mark.asyncio
+async def test_import_ovpack_accepts_signature_member(
+    temp_ovpack_path: Path,
+    request_ctx: RequestContext,
+    monkeypatch: pytest.MonkeyPatch,
+):
+    async def noop_vectorization(*args, **kwargs):
+        return None
+
+    monkeypatch.setattr(
+        "openviking.storage.ovpack.operations._enqueue_direct_vectorization",
+        noop_vectorization,
+    )
+    _write_ovpack_with_manifest(temp_ovpack_path, "demo", {"notes.txt": "hello"})
+    with zipfile.ZipFile(temp_ovpack_path, "a") as zf:
+        zf.writestr("demo/_ovpack/signature.json", json.dumps({"algorithm": "ed25519"}))
+    fake_fs = FakeVikingFS()
+
+    await import_ovpack(fake_fs, str(temp_ovpack_path), "viking://resources", request_ctx)
+
+    assert fake_fs.written_files == ["viking://resources/demo/notes.txt"]
+
+
 @pytest.mark.asyncio
 async def test_import_ovpack_rejects_previous_manifest_version(
     temp_ovpack_path: Path, request_ctx: RequestContext
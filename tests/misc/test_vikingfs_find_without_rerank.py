@@ -53,6 +53,7 @@ async def test_find_works_without_rerank_config(monkeypatch) -> None:
             score_threshold,
             scope_dsl,
             level,
+            scoring=None,
         ):
             captured["typed_query"] = typed_query
             captured["ctx"] = ctx
@@ -88,6 +89,7 @@ async def test_find_works_without_rerank_config(monkeypatch) -> None:
 
     assert result.total == 1
     assert [ctx.uri for ctx in result.resources] == ["viking://resources/docs/guide.md"]
+    assert result.query_results[0].searched_directories == ["viking://resources/docs"]
     assert captured["storage"] is fs.vector_store
     assert captured["embedder"] is fs.query_embedder
     assert captured["rerank_config"] is None
@@ -120,6 +122,7 @@ async def test_find_accepts_image_url_without_text_query(monkeypatch) -> None:
             score_threshold,
             scope_dsl,
             level,
+            scoring=None,
         ):
             captured["typed_query"] = typed_query
             return QueryResult(
@@ -12,7 +12,8 @@ import pytest
 from openviking.retrieve.hierarchical_retriever import HierarchicalRetriever, RetrieverMode
 from openviking.server.identity import RequestContext, Role
 from openviking.utils.token_estimation import estimate_text_tokens
-from openviking_cli.retrieve.types import ContextType, TypedQuery
+from openviking_cli.exceptions import InvalidArgumentError
+from openviking_cli.retrieve.types import ContextType, ScoringOptions, TypedQuery
 from openviking_cli.session.user_id import UserIdentifier
 from openviking_cli.utils.config import RerankConfig, RetrievalConfig
 
@@ -117,9 +118,34 @@ class DummyStorage:
 
 
 class QuickSearchStorage(DummyStorage):
-    def __init__(self, results):
+    def __init__(self, results, scope=None):
         super().__init__()
         self.results = list(results)
+        self.scope = list(scope if scope is not None else results)
+        self.scroll_calls = []
+
+    async def keyword_search_in_tenant(self, ctx, query, **kwargs):
+        raise NotImplementedError("no keyword search")
+
+    async def scroll_in_tenant(
+        self,
+        ctx,
+        context_type=None,
+        target_directories=None,
+        extra_filter=None,
+        level=None,
+        limit: int = 100,
+        cursor=None,
+    ):
+        self.scroll_calls.append({"level": level, "limit": limit, "cursor": cursor})
+        start = int(cursor or 0)
+        page = [
+            dict(record)
+            for record in self.scope[start : start + limit]
+            if level is None or record.get("level", 2) in level
+        ]
+        next_cursor = str(start + limit) if start + limit < len(self.scope) else None
+        return page, next_cursor
 
     async def search_in_tenant(
         self,
@@ -559,6 +585,123 @@ async def test_quick_mode_keeps_scores_pure_when_hotness_and_propagation_configu
     assert storage.child_search_calls == []
 
 
+@pytest.mark.asyncio
+async def test_keyword_mode_ranks_exact_terms_and_reports_strategy_scores():
+    storage = QuickSearchStorage(
+        [
+            _result("viking://resources/payment.md", 0.9, abstract="payment schedule"),
+            _result("viking://resources/termination.md", 0.4, abstract="termination clause 4.2"),
+            _result("viking://resources/notice.md", 0.6, abstract="notice of termination"),
+        ]
+    )
+    retriever = HierarchicalRetriever(storage=storage, embedder=DummyEmbedder(), rerank_config=None)
+    query = TypedQuery(query="termination clause", context_type=ContextType.RESOURCE, intent="")
+
+    result = await retriever.retrieve(
+        query,
+        ctx=_ctx(),
+        limit=3,
+        mode=RetrieverMode.QUICK,
+        scoring=ScoringOptions(mode="keyword", include_scores=True),
+    )
+
+    assert [ctx.uri for ctx in result.matched_contexts] == [
+        "viking://resources/termination.md",
+        "viking://resources/notice.md",
+    ]
+    assert result.matched_contexts[0].scores["vector"] == pytest.approx(0.4)
+    assert result.matched_contexts[0].scores["keyword"] == pytest.approx(1.0)
+    assert "rerank" not in result.matched_contexts[0].scores
+    assert storage.search_calls[0]["limit"] == 50
+
+
+@pytest.mark.asyncio
+async def test_keyword_mode_finds_scope_hits_outside_the_vector_pool():
+    in_pool = [
+        _result("viking://resources/payment.md", 0.9, abstract="payment schedule"),
+        _result("viking://resources/notice.md", 0.6, abstract="notice period"),
+    ]
+    outside = _result("viking://resources/termination.md", 0.0, abstract="termination clause 4.2")
+    storage = QuickSearchStorage(in_pool, scope=[*in_pool, outside])
+    retriever = HierarchicalRetriever(storage=storage, embedder=DummyEmbedder(), rerank_config=None)
+    query = TypedQuery(query="termination clause", context_type=ContextType.RESOURCE, intent="")
+
+    result = await retriever.retrieve(
+        query,
+        ctx=_ctx(),
+        limit=3,
+        mode=RetrieverMode.QUICK,
+        scoring=ScoringOptions(mode="keyword", include_scores=True),
+    )
+
+    assert [ctx.uri for ctx in result.matched_contexts] == ["viking://resources/termination.md"]
+    assert result.matched_contexts[0].scores["keyword"] == pytest.approx(1.0)
+    assert "vector" not in result.matched_contexts[0].scores
+
+    hybrid = await retriever.retrieve(
+        query,
+        ctx=_ctx(),
+        limit=3,
+        mode=RetrieverMode.QUICK,
+        scoring=ScoringOptions(mode="hybrid", vector_weight=1.0, keyword_weight=1.0),
+    )
+    assert "viking://resources/termination.md" in [ctx.uri for ctx in hybrid.matched_contexts]
+
+
+def test_hybrid_mode_blends_vector_and_keyword_scores_by_weight():
+    candidates = [
+        _result("viking://resources/a.md", 0.9, abstract="payment schedule", _final_score=0.9),
+        _result("viking://resources/b.md", 0.5, abstract="termination clause", _final_score=0.5),
+    ]
+
+    ranked = HierarchicalRetriever._apply_keyword_scores(
+        "termination clause",
+        [dict(c) for c in candidates],
+        ScoringOptions(mode="hybrid", vector_weight=1.0, keyword_weight=1.0),
+    )
+    assert [c["uri"] for c in ranked] == ["viking://resources/b.md", "viking://resources/a.md"]
+    assert ranked[0]["_final_score"] == pytest.approx(0.75)
+
+    ranked = HierarchicalRetriever._apply_keyword_scores(
+        "termination clause",
+        [dict(c) for c in candidates],
+        ScoringOptions(mode="hybrid", vector_weight=1.0, keyword_weight=0.0),
+    )
+    assert [c["uri"] for c in ranked] == ["viking://resources/a.md", "viking://resources/b.md"]
+
+
+@pytest.mark.asyncio
+async def test_rerank_can_be_disabled_per_request(monkeypatch):
+    fake_client = FakeRerankClient([0.95, 0.05, 0.11, 0.95])
+    monkeypatch.setattr(
+        "openviking.retrieve.hierarchical_retriever.RerankClient.from_config",
+        lambda config: fake_client,
+    )
+    retriever = HierarchicalRetriever(
+        storage=DummyStorage(), embedder=DummyEmbedder(), rerank_config=_config()
+    )
+
+    await retriever.retrieve(
+        _query(),
+        ctx=_ctx(),
+        limit=2,
+        mode=RetrieverMode.THINKING,
+        scoring=ScoringOptions(rerank=False),
+    )
+
+    assert fake_client.calls == []
+
+
+@pytest.mark.asyncio
+async def test_rerank_request_without_rerank_model_is_rejected():
+    retriever = HierarchicalRetriever(
+        storage=DummyStorage(), embedder=DummyEmbedder(), rerank_config=None
+    )
+
+    with pytest.raises(InvalidArgumentError, match="rerank"):
+        await retriever.retrieve(_query(), ctx=_ctx(), scoring=ScoringOptions(rerank=True))
+
+
 @pytest.mark.asyncio
 async def test_score_propagation_alpha_uses_configured_weight():
     retriever = HierarchicalRetriever(
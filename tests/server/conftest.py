@@ -162,6 +162,10 @@ def upload_temp_dir(temp_dir: Path, monkeypatch) -> Path:
         "openviking.server.temp_upload_store.get_openviking_config",
         lambda: config,
     )
+    monkeypatch.setattr(
+        "openviking.server.chunked_upload_store.get_openviking_config",
+        lambda: config,
+    )
     return temp_dir
 
 
@@ -4,6 +4,7 @@
 """Tests for resource management endpoints."""
 
 import asyncio
+import hashlib
 import zipfile
 
 import httpx
@@ -645,6 +646,121 @@ async def test_temp_upload_with_telemetry_returns_summary(
     assert body["telemetry"]["summary"]["operation"] == "resources.temp_upload"
 
 
+async def _start_chunked_upload(client: httpx.AsyncClient, content: bytes) -> str:
+    resp = await client.post(
+        "/api/v1/resources/uploads",
+        json={
+            "filename": "big.md",
+            "size": len(content),
+            "sha256": hashlib.sha256(content).hexdigest(),
+        },
+    )
+    assert resp.status_code == 200
+    assert resp.json()["result"]["received"] == 0
+    return resp.json()["result"]["upload_id"]
+
+
+async def test_chunked_upload_resumes_and_completes(
+    client: httpx.AsyncClient,
+    upload_temp_dir,
+):
+    content = b"# chunked upload\n" * 64
+    upload_id = await _start_chunked_upload(client, content)
+
+    resp = await client.put(
+        f"/api/v1/resources/uploads/{upload_id}", params={"offset": 0}, content=content[:300]
+    )
+    assert resp.status_code == 200
+    assert resp.json()["result"]["received"] == 300
+
+    resp = await client.put(
+        f"/api/v1/resources/uploads/{upload_id}", params={"offset": 0}, content=content[:300]
+    )
+    assert resp.status_code == 409
+    assert resp.json()["error"]["code"] == "CONFLICT"
+
+    status = await client.get(f"/api/v1/resources/uploads/{upload_id}")
+    offset = status.json()["result"]["received"]
+    assert offset == 300
+    resp = await client.put(
+        f"/api/v1/resources/uploads/{upload_id}",
+        params={"offset": offset},
+        content=content[offset:],
+    )
+    assert resp.json()["result"]["received"] == len(content)
+
+    resp = await client.post(f"/api/v1/resources/uploads/{upload_id}/complete", json={})
+    assert resp.status_code == 200
+    temp_file_id = resp.json()["result"]["temp_file_id"]
+    assert temp_file_id.endswith(".md")
+    assert (upload_temp_dir / temp_file_id).read_bytes() == content
+
+    status = await client.get(f"/api/v1/resources/uploads/{upload_id}")
+    assert status.status_code == 404
+
+
+async def test_chunked_upload_serializes_concurrent_appends(
+    client: httpx.AsyncClient,
+    upload_temp_dir,
+):
+    content = b"# concurrent\n" * 64
+    upload_id = await _start_chunked_upload(client, content)
+
+    async def body():
+        for start in range(0, len(content), 64):
+            await asyncio.sleep(0)
+            yield content[start : start + 64]
+
+    responses = await asyncio.gather(
+        *(
+            client.put(
+                f"/api/v1/resources/uploads/{upload_id}", params={"offset": 0}, content=body()
+            )
+            for _ in range(2)
+        )
+    )
+    assert sorted(resp.status_code for resp in responses) == [200, 409]
+
+    status = await client.get(f"/api/v1/resources/uploads/{upload_id}")
+    assert status.json()["result"]["received"] == len(content)
+    resp = await client.post(f"/api/v1/resources/uploads/{upload_id}/complete", json={})
+    temp_file_id = resp.json()["result"]["temp_file_id"]
+    assert (upload_temp_dir / temp_file_id).read_bytes() == content
+
+
+async def test_chunked_upload_rejects_checksum_mismatch(
+    client: httpx.AsyncClient,
+    upload_temp_dir,
+):
+    content = b"# expected\n"
+    upload_id = await _start_chunked_upload(client, content)
+    await client.put(
+        f"/api/v1/resources/uploads/{upload_id}", params={"offset": 0}, content=b"# corrupted"
+    )
+
+    resp = await client.post(f"/api/v1/resources/uploads/{upload_id}/complete", json={})
+    assert resp.status_code == 400
+    body = resp.json()
+    assert body["error"]["code"] == "INVALID_ARGUMENT"
+    assert "Checksum mismatch" in body["error"]["message"]
+
+
+async def test_chunked_upload_complete_requires_all_bytes(
+    client: httpx.AsyncClient,
+    upload_temp_dir,
+):
+    content = b"# partial\n" * 4
+    upload_id = await _start_chunked_upload(client, content)
+    await client.put(
+        f"/api/v1/resources/uploads/{upload_id}", params={"offset": 0}, content=content[:5]
+    )
+
+    resp = await client.post(f"/api/v1/resources/uploads/{upload_id}/complete", json={})
+    assert resp.json()["error"]["code"] == "FAILED_PRECONDITION"
+    status = await client.get(f"/api/v1/resources/uploads/{upload_id}")
+    assert status.json()["result"]["received"] == 5
+
+
 async def test_add_resource_rejects_direct_local_path(client: httpx.AsyncClient):
     resp = await client.post(
         "/api/v1/resources",
@@ -676,6 +792,68 @@ async def test_add_resource_accepts_temp_uploaded_file(
     assert body["result"]["root_uri"].startswith("viking://")
 
 
+async def test_add_resource_records_content_hash_for_dedup(
+    client: httpx.AsyncClient,
+    upload_temp_dir,
+):
+    content = b"# dedup\n"
+    sha256 = hashlib.sha256(content).hexdigest()
+    miss = await client.get(f"/api/v1/resources/by-hash/{sha256}")
+    assert miss.status_code == 200
+    assert miss.json()["result"]["root_uri"] is None
+
+    upload_resp = await client.post(
+        "/api/v1/resources/temp_upload",
+        files={"file": ("dedup.md", content, "text/markdown")},
+    )
+    temp_file_id = upload_resp.json()["result"]["temp_file_id"]
+    resp = await client.post(
+        "/api/v1/resources",
+        json={"temp_file_id": temp_file_id, "sha256": sha256.upper(), "wait": True},
+    )
+    assert resp.status_code == 200
+    root_uri = resp.json()["result"]["root_uri"]
+
+    hit = await client.get(f"/api/v1/resources/by-hash/{sha256}")
+    assert hit.status_code == 200
+    assert hit.json()["result"]["root_uri"] == root_uri
+    assert hit.json()["result"]["source_name"] == "dedup.md"
+
+    await client.request("DELETE", "/api/v1/fs", params={"uri": root_uri, "recursive": True})
+    gone = await client.get(f"/api/v1/resources/by-hash/{sha256}")
+    assert gone.json()["result"]["root_uri"] is None
+
+
+async def test_add_resource_rejects_sha256_that_does_not_match_upload(
+    client: httpx.AsyncClient,
+    upload_temp_dir,
+):
+    claimed = hashlib.sha256(b"# someone else's file\n").hexdigest()
+    upload_resp = await client.post(
+        "/api/v1/resources/temp_upload",
+        files={"file": ("mismatch.md", b"# mismatch\n", "text/markdown")},
+    )
+    temp_file_id = upload_resp.json()["result"]["temp_file_id"]
+    resp = await client.post(
+        "/api/v1/resources",
+        json={"temp_file_id": temp_file_id, "sha256": claimed, "wait": True},
+    )
+    assert resp.status_code == 400
+    assert resp.json()["error"]["code"] == "INVALID_ARGUMENT"
+
+    miss = await client.get(f"/api/v1/resources/by-hash/{claimed}")
+    assert miss.json()["result"]["root_uri"] is None
+
+
+async def test_add_resource_rejects_sha256_without_temp_upload(client: httpx.AsyncClient):
+    resp = await client.post(
+        "/api/v1/resources",
+        json={"path": "https://example.com/a.md", "sha256": "0" * 64},
+    )
+    assert resp.status_code == 400
+    assert resp.json()["error"]["code"] == "INVALID_ARGUMENT"
+
+
 async def test_shared_temp_upload_and_add_resource_deletes_upload_dir(
     client: httpx.AsyncClient,
     service,
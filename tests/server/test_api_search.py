@@ -150,6 +150,46 @@ async def test_find_without_level_passes_none(client: httpx.AsyncClient, service
     assert captured["level"] is None
 
 
+async def test_find_passes_scoring_options_to_service(
+    client: httpx.AsyncClient, service, monkeypatch
+):
+    captured = {}
+
+    async def fake_find(*, scoring=None, **kwargs):
+        captured["scoring"] = scoring
+        return {"items": []}
+
+    monkeypatch.setattr(service.search, "find", fake_find)
+
+    resp = await client.post(
+        "/api/v1/search/find",
+        json={
+            "query": "termination clause",
+            "mode": "hybrid",
+            "rerank": False,
+            "keyword_weight": 0.8,
+            "include_scores": True,
+        },
+    )
+
+    assert resp.status_code == 200
+    scoring = captured["scoring"]
+    assert scoring.mode == "hybrid"
+    assert scoring.rerank is False
+    assert scoring.vector_weight == 0.5
+    assert scoring.keyword_weight == 0.8
+    assert scoring.include_scores is True
+
+
+async def test_find_rejects_weights_outside_hybrid_mode(client: httpx.AsyncClient):
+    resp = await client.post(
+        "/api/v1/search/find",
+        json={"query": "sample", "mode": "keyword", "keyword_weight": 1.0},
+    )
+
+    assert resp.status_code == 400
+
+
 async def test_find_level_filters_l2_only(client_with_resource):
     client, uri = client_with_resource
     resp = await client.post(
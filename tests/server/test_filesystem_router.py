@@ -51,6 +51,9 @@ async def test_attrs_returns_memory_fields_and_tags(monkeypatch):
     async def fake_read(uri, ctx=None):
         return raw_memory
 
+    async def fake_get_metadata(uri, ctx=None):
+        return {"source": "crm"}
+
     class FakeVectorManager:
         async def filter(self, **kwargs):
             return [
@@ -65,7 +68,9 @@ async def test_attrs_returns_memory_fields_and_tags(monkeypatch):
         filesystem,
         "get_service",
         lambda: SimpleNamespace(
-            fs=SimpleNamespace(stat=fake_stat, read=fake_read),
+            fs=SimpleNamespace(
+                stat=fake_stat, read=fake_read, get_metadata=fake_get_metadata
+            ),
             vikingdb_manager=FakeVectorManager(),
         ),
     )
@@ -83,3 +88,32 @@ async def test_attrs_returns_memory_fields_and_tags(monkeypatch):
         "memory_type": "preferences",
     }
     assert attrs["tags"] == ["team=search"]
+    assert attrs["metadata"] == {"source": "crm"}
+
+
+@pytest.mark.asyncio
+async def test_set_metadata_merges_values_and_removes_keys(monkeypatch):
+    calls = []
+
+    async def fake_set_metadata(uri, values, remove, ctx=None):
+        calls.append((uri, values, remove))
+        return {"source": "crm", "batch": 7}
+
+    monkeypatch.setattr(
+        filesystem,
+        "get_service",
+        lambda: SimpleNamespace(fs=SimpleNamespace(set_metadata=fake_set_metadata)),
+    )
+
+    response = await filesystem.attrs_set_metadata(
+        request=filesystem.SetMetadataRequest(
+            uri="viking://resources/a.md", values={"batch": 7}, remove=["owner"]
+        ),
+        _ctx=RequestContext(user=UserIdentifier("acct", "alice"), role=Role.USER),
+    )
+
+    assert calls == [("viking://resources/a.md", {"batch": 7}, ["owner"])]
+    assert response.result == {
+        "uri": "viking://resources/a.md",
+        "metadata": {"source": "crm", "batch": 7},
+    }